@@ -0,0 +1,139 @@
+//! Benchmarks for code that runs on every message
+//!
+//! Run with `cargo bench --bench hot_paths`. Compare against a saved baseline with
+//! `cargo bench --bench hot_paths -- --save-baseline main` and `--baseline main`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::collections::HashMap;
+
+use persona::commands::CommandHandler;
+use persona::features::conflict::ConflictDetector;
+use persona::features::personas::{PersonaManager, PromptBuilder};
+use persona::features::plugins::substitute_params;
+
+/// Conversation window sizes passed to the conflict detector
+const WINDOW_SIZES: [usize; 3] = [10, 100, 1000];
+
+fn bench_prompt_builder(c: &mut Criterion) {
+    let persona_manager = PersonaManager::new();
+    let mut group = c.benchmark_group("prompt_builder");
+
+    group.bench_function("persona_only", |b| {
+        b.iter(|| PromptBuilder::new(&persona_manager, black_box("obi")).build())
+    });
+    group.bench_function("modifier_verbosity_paragraphs", |b| {
+        b.iter(|| {
+            PromptBuilder::new(&persona_manager, black_box("obi"))
+                .with_modifier(Some("explain"))
+                .with_verbosity("detailed")
+                .with_max_paragraphs(Some(3))
+                .build()
+        })
+    });
+    group.bench_function("unknown_persona_fallback", |b| {
+        b.iter(|| PromptBuilder::new(&persona_manager, black_box("does-not-exist")).build())
+    });
+
+    group.finish();
+}
+
+/// Alternating two-user exchange mixing calm and hostile messages
+fn conversation(len: usize) -> Vec<(String, String, String)> {
+    const LINES: [&str; 6] = [
+        "I think we should try the other approach first",
+        "That is COMPLETELY WRONG and you know it!!!",
+        "Can you share the link to the docs?",
+        "you're an idiot, nobody asked you",
+        "Sure, here it is, let me know what you think",
+        "shut up, this is stupid and so are you",
+    ];
+    let start = chrono::Utc::now().timestamp() - len as i64 * 5;
+
+    (0..len)
+        .map(|i| {
+            (
+                format!("user{}", i % 2),
+                LINES[i % LINES.len()].to_string(),
+                (start + i as i64 * 5).to_string(),
+            )
+        })
+        .collect()
+}
+
+fn bench_conflict_detector(c: &mut Criterion) {
+    let detector = ConflictDetector::new();
+    let mut group = c.benchmark_group("detect_heated_argument");
+
+    for size in WINDOW_SIZES {
+        let messages = conversation(size);
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(size),
+            &messages,
+            |b, messages| b.iter(|| detector.detect_heated_argument(black_box(messages), 120)),
+        );
+    }
+
+    group.finish();
+}
+
+fn bench_substitute_params(c: &mut Criterion) {
+    let mut group = c.benchmark_group("substitute_params");
+
+    let few: HashMap<String, String> = [
+        ("url", "https://www.youtube.com/watch?v=dQw4w9WgXcQ"),
+        ("language", "en"),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v.to_string()))
+    .collect();
+    group.bench_function("two_params", |b| {
+        b.iter(|| substitute_params(black_box("yt-dlp --lang ${language} ${url}"), &few))
+    });
+
+    let many: HashMap<String, String> = (0..20)
+        .map(|i| (format!("param{i}"), format!("value-{i}")))
+        .collect();
+    let template: String = (0..20)
+        .map(|i| format!("--flag{i} ${{param{i}}} "))
+        .collect();
+    group.bench_function("twenty_params", |b| {
+        b.iter(|| substitute_params(black_box(&template), &many))
+    });
+
+    group.finish();
+}
+
+fn bench_attachment_formatting(c: &mut Criterion) {
+    let mut group = c.benchmark_group("format_attachments_for_context");
+
+    let small = vec![("notes.txt".to_string(), "line of notes\n".repeat(20))];
+    group.bench_function("one_small_file", |b| {
+        b.iter(|| CommandHandler::format_attachments_for_context(black_box(&small)))
+    });
+
+    let large: Vec<(String, String)> = (0..5)
+        .map(|i| {
+            (
+                format!("log{i}.txt"),
+                "2024-01-01 INFO request ok\n".repeat(2_000),
+            )
+        })
+        .collect();
+    let bytes: usize = large.iter().map(|(_, content)| content.len()).sum();
+    group.throughput(Throughput::Bytes(bytes as u64));
+    group.bench_function("five_large_files", |b| {
+        b.iter(|| CommandHandler::format_attachments_for_context(black_box(&large)))
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_prompt_builder,
+    bench_conflict_detector,
+    bench_substitute_params,
+    bench_attachment_formatting
+);
+criterion_main!(benches);
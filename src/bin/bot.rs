@@ -1,62 +1,120 @@
 use anyhow::Result;
 use dotenvy::dotenv;
-use log::{error, info};
+use log::{error, info, warn};
 use serenity::async_trait;
 use serenity::model::application::interaction::Interaction;
-use serenity::model::channel::Message;
+use serenity::model::channel::{Channel, GuildChannel, Message, PartialGuildChannel};
 use serenity::model::gateway::Ready;
 use serenity::prelude::*;
+use std::io::Read;
 use std::sync::Arc;
 
 use persona::commands::{
     register_global_commands_with_plugins, register_guild_commands_with_plugins, CommandHandler,
+    ComponentRegistry, PaginationHandler,
 };
-use persona::core::Config;
+use persona::core::oneshot::{parse_oneshot_args, run_oneshot, OneshotArgs};
+use persona::core::{openai_chat_client, Config, DEFAULT_OPENAI_MODEL};
 use persona::database::Database;
+use persona::features::analytics::runtime::{spawn_tracked, TaskKind};
 use persona::features::analytics::{metrics_collection_loop, InteractionTracker, UsageTracker};
-use persona::features::personas::PersonaManager;
+use persona::features::birthdays::BirthdayScheduler;
+use persona::features::channel_topics::{TopicScheduler, TopicWriter};
+use persona::features::countdown::CountdownScheduler;
+use persona::features::discussion::{restore_all_discussion_states, DiscussionType};
+use persona::features::dm_history::{HistoryMenu, SessionWriter};
+use persona::features::guardrails::nsfw_channels;
+use persona::features::language::LANGUAGES;
+#[cfg(feature = "matrix")]
+use persona::features::matrix::{MatrixBridge, MatrixConfig};
+use persona::features::meetings::MeetingButtons;
+use persona::features::personas::verbosity::autocomplete_label;
+use persona::features::personas::{PersonaManager, ThemeScheduler, VERBOSITY_PRESETS};
 use persona::features::plugins::{
-    JobManager, OutputHandler, Plugin, PluginConfig, PluginExecutor, PluginManager,
+    JobButtons, JobManager, OutputHandler, PluginConfig, PluginExecutor, PluginManager,
+    SubscriptionPoller,
 };
+use persona::features::presence::PresenceRotator;
+use persona::features::qotd::{QotdScheduler, QuestionWriter};
 use persona::features::reminders::ReminderScheduler;
-use persona::features::startup::StartupNotifier;
+use persona::features::resilience::{
+    init_chaos, install_crash_reporter, record_fatal_error, BreadcrumbLogger,
+};
+use persona::features::send_later::{MessageRewriter, SendLaterScheduler};
+use persona::features::server_report::{ReportScheduler, ServerReporter};
+use persona::features::standup::StandupScheduler;
+use persona::features::startup::{PluginLoadStatus, StartupNotifier, StartupReport};
+use persona::features::story::restore_all_story_states;
+use persona::features::suggestions::SuggestionButtons;
+#[cfg(feature = "telegram")]
+use persona::features::telegram::{TelegramBridge, TelegramConfig};
+use persona::features::trivia::TriviaButtons;
+use persona::features::updater::restart_requested;
+use persona::features::verification::{
+    VerificationButtons, VerificationGate, VerificationScheduler,
+};
+use persona::features::webhooks::BudgetMonitor;
 use persona::ipc::{
     AttachmentInfo, BotEvent, ChannelInfo, ChannelType, DisplayMessage, GuildInfo, IpcServer,
 };
 use persona::message_components::MessageComponentHandler;
-use serenity::model::guild::Guild;
+use persona::message_writer::MessageWriter;
+use persona::testing::{replay_file, InteractionRecorder};
+use serenity::model::guild::{Guild, Member};
 use serenity::model::id::GuildId;
+use serenity::model::user::User;
 
 struct Handler {
     command_handler: Arc<CommandHandler>,
-    component_handler: Arc<MessageComponentHandler>,
+    components: Arc<ComponentRegistry>,
     guild_id: Option<GuildId>,
     startup_notifier: StartupNotifier,
-    plugins: Vec<Plugin>,
     ipc_server: Option<Arc<IpcServer>>,
     start_time: std::time::Instant,
+    recorder: Option<Arc<InteractionRecorder>>,
+    presence: Option<Arc<PresenceRotator>>,
+    verification: Option<Arc<VerificationGate>>,
 }
 
 impl Handler {
     fn new(
         command_handler: CommandHandler,
-        component_handler: MessageComponentHandler,
+        components: ComponentRegistry,
         guild_id: Option<GuildId>,
         startup_notifier: StartupNotifier,
-        plugins: Vec<Plugin>,
         ipc_server: Option<Arc<IpcServer>>,
     ) -> Self {
         Handler {
             command_handler: Arc::new(command_handler),
-            component_handler: Arc::new(component_handler),
+            components: Arc::new(components),
             guild_id,
             startup_notifier,
-            plugins,
             ipc_server,
             start_time: std::time::Instant::now(),
+            recorder: None,
+            presence: None,
+            verification: None,
         }
     }
 
+    /// Record every slash command payload (anonymized) for later `--replay`
+    fn with_recorder(mut self, recorder: Option<InteractionRecorder>) -> Self {
+        self.recorder = recorder.map(Arc::new);
+        self
+    }
+
+    /// Rotate the bot's activity status once connected
+    fn with_presence(mut self, presence: PresenceRotator) -> Self {
+        self.presence = Some(Arc::new(presence));
+        self
+    }
+
+    /// Gate new members behind verification (needs the Server Members intent)
+    fn with_verification(mut self, gate: VerificationGate) -> Self {
+        self.verification = Some(Arc::new(gate));
+        self
+    }
+
     /// Convert a Serenity message to a DisplayMessage for IPC
     fn to_display_message(msg: &Message) -> DisplayMessage {
         // Convert serenity timestamp to chrono DateTime
@@ -238,6 +296,59 @@ impl EventHandler for Handler {
         }
     }
 
+    async fn guild_member_addition(&self, ctx: Context, new_member: Member) {
+        if let Some(gate) = &self.verification {
+            if let Err(e) = gate.on_member_join(&ctx.http, &new_member).await {
+                error!(
+                    "Error starting verification for {} in guild {}: {e}",
+                    new_member.user.id, new_member.guild_id
+                );
+            }
+        }
+    }
+
+    async fn guild_member_removal(
+        &self,
+        ctx: Context,
+        guild_id: GuildId,
+        user: User,
+        _member_data_if_available: Option<Member>,
+    ) {
+        if let Some(gate) = &self.verification {
+            if let Err(e) = gate
+                .on_member_leave(&ctx.http, &guild_id.to_string(), &user.id.to_string())
+                .await
+            {
+                error!(
+                    "Error clearing verification for {} in guild {guild_id}: {e}",
+                    user.id
+                );
+            }
+        }
+    }
+
+    async fn channel_create(&self, _ctx: Context, channel: &GuildChannel) {
+        nsfw_channels().record_channel(channel);
+    }
+
+    async fn channel_update(&self, _ctx: Context, _old: Option<Channel>, new: Channel) {
+        if let Channel::Guild(channel) = new {
+            nsfw_channels().record_channel(&channel);
+        }
+    }
+
+    async fn channel_delete(&self, _ctx: Context, channel: &GuildChannel) {
+        nsfw_channels().forget(channel.id.0);
+    }
+
+    async fn thread_create(&self, _ctx: Context, thread: GuildChannel) {
+        nsfw_channels().record_channel(&thread);
+    }
+
+    async fn thread_delete(&self, _ctx: Context, thread: PartialGuildChannel) {
+        nsfw_channels().forget(thread.id.0);
+    }
+
     async fn ready(&self, ctx: Context, ready: Ready) {
         info!("🎉 {} is connected and ready!", ready.user.name);
         info!("📡 Connected to {} guilds", ready.guilds.len());
@@ -250,12 +361,13 @@ impl EventHandler for Handler {
             info!("⚡ Shard: {}/{}", shard[0] + 1, shard[1]);
         }
 
-        // Log plugin information
-        let enabled_plugins: Vec<_> = self.plugins.iter().filter(|p| p.enabled).collect();
+        // Log plugin information (read live, so reconnects after a reload register the new set)
+        let plugins = self.command_handler.get_plugins();
+        let enabled_plugins: Vec<_> = plugins.iter().filter(|p| p.enabled).collect();
         if !enabled_plugins.is_empty() {
             info!(
                 "🔌 {} plugins loaded ({} enabled)",
-                self.plugins.len(),
+                plugins.len(),
                 enabled_plugins.len()
             );
             for plugin in &enabled_plugins {
@@ -352,7 +464,7 @@ impl EventHandler for Handler {
         if let Some(guild_id) = self.guild_id {
             info!("🔧 Development mode: Registering commands for guild {guild_id}");
             if let Err(e) =
-                register_guild_commands_with_plugins(&ctx, guild_id, &self.plugins).await
+                register_guild_commands_with_plugins(&ctx.http, guild_id, &plugins).await
             {
                 error!("❌ Failed to register guild slash commands: {e}");
             } else {
@@ -360,16 +472,20 @@ impl EventHandler for Handler {
             }
         } else {
             info!("🌍 Production mode: Registering commands globally");
-            if let Err(e) = register_global_commands_with_plugins(&ctx, &self.plugins).await {
+            if let Err(e) = register_global_commands_with_plugins(&ctx.http, &plugins).await {
                 error!("❌ Failed to register global slash commands: {e}");
             } else {
                 info!("✅ Successfully registered slash commands globally (may take up to 1 hour to propagate)");
             }
         }
 
+        if let Some(presence) = &self.presence {
+            presence.start(ctx.clone());
+        }
+
         // Send startup notification if enabled (includes plugin versions and commit details)
         self.startup_notifier
-            .send_if_enabled(&ctx.http, &ready, &self.plugins)
+            .send_if_enabled(&ctx.http, &ready, &plugins)
             .await;
     }
 
@@ -410,6 +526,7 @@ impl EventHandler for Handler {
                 guild.channels.len()
             );
         }
+        nsfw_channels().record_guild(&guild);
 
         // Update IPC with this guild's info
         if let Some(ipc) = &self.ipc_server {
@@ -441,6 +558,10 @@ impl EventHandler for Handler {
     async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
         match interaction {
             Interaction::ApplicationCommand(command) => {
+                if let Some(recorder) = &self.recorder {
+                    recorder.record_command(&command);
+                }
+
                 if let Err(e) = self
                     .command_handler
                     .handle_slash_command(&ctx, &command)
@@ -479,11 +600,7 @@ impl EventHandler for Handler {
                 }
             }
             Interaction::MessageComponent(component) => {
-                if let Err(e) = self
-                    .component_handler
-                    .handle_component_interaction(&ctx, &component)
-                    .await
-                {
+                if let Err(e) = self.components.dispatch_component(&ctx, &component).await {
                     error!(
                         "Error handling component interaction '{}': {}",
                         component.data.custom_id, e
@@ -511,11 +628,7 @@ impl EventHandler for Handler {
                 }
             }
             Interaction::ModalSubmit(modal) => {
-                if let Err(e) = self
-                    .component_handler
-                    .handle_modal_submit(&ctx, &modal)
-                    .await
-                {
+                if let Err(e) = self.components.dispatch_modal(&ctx, &modal).await {
                     error!(
                         "Error handling modal submit '{}': {}",
                         modal.data.custom_id, e
@@ -610,16 +723,15 @@ impl EventHandler for Handler {
 
                         autocomplete
                             .create_autocomplete_response(&ctx.http, |response| match setting {
-                                "verbosity" => response
-                                    .add_string_choice(
-                                        "concise - Brief responses (2-3 sentences)",
-                                        "concise",
-                                    )
-                                    .add_string_choice("normal - Balanced responses", "normal")
-                                    .add_string_choice(
-                                        "detailed - Comprehensive responses",
-                                        "detailed",
-                                    ),
+                                "verbosity" => {
+                                    for preset in &VERBOSITY_PRESETS {
+                                        response.add_string_choice(
+                                            autocomplete_label(preset),
+                                            preset.id,
+                                        );
+                                    }
+                                    response
+                                }
                                 "persona" => response
                                     .add_string_choice("obi - Obi-Wan Kenobi (wise mentor)", "obi")
                                     .add_string_choice(
@@ -658,6 +770,71 @@ impl EventHandler for Handler {
                                         "disabled - Disable conflict mediation in this channel",
                                         "disabled",
                                     ),
+                                "birthdays" => response
+                                    .add_string_choice(
+                                        "enabled - Post birthday wishes in this channel",
+                                        "enabled",
+                                    )
+                                    .add_string_choice(
+                                        "disabled - Stop posting birthday wishes",
+                                        "disabled",
+                                    ),
+                                "achievements" => response
+                                    .add_string_choice(
+                                        "enabled - Announce new badges in this channel",
+                                        "enabled",
+                                    )
+                                    .add_string_choice(
+                                        "disabled - Stop announcing badges",
+                                        "disabled",
+                                    ),
+                                "link_previews" => response
+                                    .add_string_choice(
+                                        "reply - Summarize posted links in a short reply",
+                                        "reply",
+                                    )
+                                    .add_string_choice(
+                                        "thread - Summarize posted links in a thread",
+                                        "thread",
+                                    )
+                                    .add_string_choice("disabled - No link previews", "disabled"),
+                                "sentiment_tracking" => response
+                                    .add_string_choice(
+                                        "enabled - Track this channel's mood for /mood",
+                                        "enabled",
+                                    )
+                                    .add_string_choice(
+                                        "disabled - Stop tracking sentiment",
+                                        "disabled",
+                                    ),
+                                "time_detection" => response
+                                    .add_string_choice(
+                                        "enabled - Show times in messages in everyone's local time",
+                                        "enabled",
+                                    )
+                                    .add_string_choice(
+                                        "disabled - Leave times in messages alone",
+                                        "disabled",
+                                    ),
+                                "language" => {
+                                    for language in LANGUAGES {
+                                        response.add_string_choice(
+                                            format!(
+                                                "{} - Nudge messages not in {}",
+                                                language.code, language.name
+                                            ),
+                                            language.code,
+                                        );
+                                    }
+                                    response.add_string_choice(
+                                        "disabled - Any language is fine here",
+                                        "disabled",
+                                    )
+                                }
+                                "scene" => response.add_string_choice(
+                                    "clear - Remove this channel's scene (or type a scene)",
+                                    "clear",
+                                ),
                                 _ => response,
                             })
                             .await
@@ -676,50 +853,91 @@ impl EventHandler for Handler {
                         autocomplete
                             .create_autocomplete_response(&ctx.http, |response| {
                                 match setting {
-                                    "default_verbosity" => response
+                                    "default_verbosity" => {
+                                        for preset in &VERBOSITY_PRESETS {
+                                            response.add_string_choice(
+                                                autocomplete_label(preset),
+                                                preset.id,
+                                            );
+                                        }
+                                        response
+                                    }
+                                    "default_persona" | "category_persona" => {
+                                        let response = response
+                                            .add_string_choice(
+                                                "obi - Obi-Wan Kenobi (wise mentor)",
+                                                "obi",
+                                            )
+                                            .add_string_choice(
+                                                "muppet - Enthusiastic Muppet friend",
+                                                "muppet",
+                                            )
+                                            .add_string_choice(
+                                                "chef - Passionate cooking expert",
+                                                "chef",
+                                            )
+                                            .add_string_choice(
+                                                "teacher - Patient educator",
+                                                "teacher",
+                                            )
+                                            .add_string_choice(
+                                                "analyst - Step-by-step analyst",
+                                                "analyst",
+                                            )
+                                            .add_string_choice(
+                                                "visionary - Future-focused big thinker",
+                                                "visionary",
+                                            )
+                                            .add_string_choice(
+                                                "noir - Hard-boiled detective",
+                                                "noir",
+                                            )
+                                            .add_string_choice("zen - Contemplative sage", "zen")
+                                            .add_string_choice(
+                                                "bard - Charismatic storyteller",
+                                                "bard",
+                                            )
+                                            .add_string_choice(
+                                                "coach - Motivational coach",
+                                                "coach",
+                                            )
+                                            .add_string_choice(
+                                                "scientist - Curious researcher",
+                                                "scientist",
+                                            )
+                                            .add_string_choice(
+                                                "gamer - Friendly gaming enthusiast",
+                                                "gamer",
+                                            );
+                                        if setting == "category_persona" {
+                                            response.add_string_choice(
+                                                "clear - Remove category persona",
+                                                "clear",
+                                            )
+                                        } else {
+                                            response
+                                        }
+                                    }
+                                    // Free-form lists; these are examples to edit
+                                    "persona_rotation" => response
                                         .add_string_choice(
-                                            "concise - Brief responses (2-3 sentences)",
-                                            "concise",
+                                            "obi, chef, bard - Rotate daily",
+                                            "obi, chef, bard",
                                         )
-                                        .add_string_choice("normal - Balanced responses", "normal")
                                         .add_string_choice(
-                                            "detailed - Comprehensive responses",
-                                            "detailed",
+                                            "disabled - Restore the usual default persona",
+                                            "disabled",
                                         ),
-                                    "default_persona" => response
-                                        .add_string_choice(
-                                            "obi - Obi-Wan Kenobi (wise mentor)",
-                                            "obi",
-                                        )
+                                    "seasonal_themes" => response
                                         .add_string_choice(
-                                            "muppet - Enthusiastic Muppet friend",
-                                            "muppet",
-                                        )
-                                        .add_string_choice(
-                                            "chef - Passionate cooking expert",
-                                            "chef",
-                                        )
-                                        .add_string_choice("teacher - Patient educator", "teacher")
-                                        .add_string_choice(
-                                            "analyst - Step-by-step analyst",
-                                            "analyst",
-                                        )
-                                        .add_string_choice(
-                                            "visionary - Future-focused big thinker",
-                                            "visionary",
+                                            "enabled - All themes on their default dates",
+                                            "enabled",
                                         )
-                                        .add_string_choice("noir - Hard-boiled detective", "noir")
-                                        .add_string_choice("zen - Contemplative sage", "zen")
-                                        .add_string_choice("bard - Charismatic storyteller", "bard")
-                                        .add_string_choice("coach - Motivational coach", "coach")
                                         .add_string_choice(
-                                            "scientist - Curious researcher",
-                                            "scientist",
+                                            "halloween@10-15..10-31, new_year - Custom dates",
+                                            "halloween@10-15..10-31, new_year",
                                         )
-                                        .add_string_choice(
-                                            "gamer - Friendly gaming enthusiast",
-                                            "gamer",
-                                        ),
+                                        .add_string_choice("disabled - No seasonal themes", "disabled"),
                                     "conflict_mediation" => response
                                         .add_string_choice(
                                             "enabled - Bot will mediate conflicts",
@@ -803,6 +1021,42 @@ impl EventHandler for Handler {
                                             "disabled - Plain text responses",
                                             "disabled",
                                         ),
+                                    "custom_emojis" => response
+                                        .add_string_choice(
+                                            "enabled - Personas may use this server's emojis",
+                                            "enabled",
+                                        )
+                                        .add_string_choice(
+                                            "disabled - Standard emoji only (default)",
+                                            "disabled",
+                                        ),
+                                    "privacy_analytics" => response
+                                        .add_string_choice(
+                                            "enabled - Track members unless they opt out (default)",
+                                            "enabled",
+                                        )
+                                        .add_string_choice(
+                                            "disabled - Skip analytics unless members opt in",
+                                            "disabled",
+                                        ),
+                                    "privacy_conflict_analysis" => response
+                                        .add_string_choice(
+                                            "enabled - Analyse messages unless members opt out (default)",
+                                            "enabled",
+                                        )
+                                        .add_string_choice(
+                                            "disabled - Skip members unless they opt in",
+                                            "disabled",
+                                        ),
+                                    "answer_disclosure" => response
+                                        .add_string_choice(
+                                            "enabled - Answers show confidence and sources",
+                                            "enabled",
+                                        )
+                                        .add_string_choice(
+                                            "disabled - Plain answers (default)",
+                                            "disabled",
+                                        ),
                                     // Startup notification settings (global)
                                     "startup_notification" => response
                                         .add_string_choice(
@@ -849,9 +1103,32 @@ impl EventHandler for Handler {
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let result = run().await;
+    if let Err(e) = &result {
+        // Left for the next start to DM to the owner
+        record_fatal_error(e);
+    }
+    result
+}
+
+async fn run() -> Result<()> {
     // Load environment variables from .env file
     dotenv().ok();
 
+    // `--replay <file>` re-runs recorded interactions against mocks and exits
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(pos) = args.iter().position(|a| a == "--replay") {
+        let path = args
+            .get(pos + 1)
+            .ok_or_else(|| anyhow::anyhow!("--replay requires a recording file path"))?;
+        return run_replay(path).await;
+    }
+
+    // `--oneshot "prompt" [--persona name]` prints one persona answer and exits
+    if args.iter().any(|a| a == "--oneshot") {
+        run_oneshot_cli(&args).await;
+    }
+
     let config = Config::from_env()?;
 
     // Ensure OPENAI_API_KEY is set in environment for the openai crate
@@ -860,13 +1137,35 @@ async fn main() -> Result<()> {
     std::env::set_var("OPENAI_API_KEY", &config.openai_api_key);
     std::env::set_var("OPENAI_KEY", &config.openai_api_key);
 
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(&config.log_level))
-        .init();
+    // Log lines double as breadcrumbs for crash reports
+    BreadcrumbLogger::init(env_logger::Builder::from_env(
+        env_logger::Env::default().default_filter_or(&config.log_level),
+    ));
+    install_crash_reporter(&config.crash_report_path);
 
     info!("Starting Persona Discord Bot...");
 
+    // Fault injection must be configured before the database and chat clients exist
+    init_chaos(config.chaos());
+
     // Create database first so IPC server can use it for stats queries
-    let database = Database::new(&config.database_path).await?;
+    let database =
+        Database::with_pool_size(&config.database_path, config.database_pool_size)
+            .await?
+            .with_settings_cache_ttl(std::time::Duration::from_secs(
+                config.settings_cache_ttl_secs,
+            ));
+
+    // Optional subsystems that fail below are reported in the startup notification
+    let migrations = database.migration_report();
+    if !migrations.is_empty() {
+        info!(
+            "🗄️ Schema migrations applied: {} table(s) created, {} column(s) added",
+            migrations.created_tables.len(),
+            migrations.added_columns.len()
+        );
+    }
+    let mut startup_report = StartupReport::new(migrations.clone());
 
     // Start IPC server for TUI communication with database access
     let ipc_server =
@@ -875,17 +1174,21 @@ async fn main() -> Result<()> {
         error!(
             "Failed to start IPC server: {e}. TUI control will be unavailable."
         );
+        startup_report.degraded("IPC server", &e);
     } else {
         info!("📡 IPC server started for TUI communication");
     }
 
-    // Spawn IPC heartbeat task
+    // Spawn IPC heartbeat task, streaming system metrics to connected TUIs
     let heartbeat_ipc = ipc_server.clone();
-    tokio::spawn(async move {
+    spawn_tracked(TaskKind::Schedulers, async move {
         let mut interval = tokio::time::interval(std::time::Duration::from_secs(30));
         loop {
             interval.tick().await;
             heartbeat_ipc.send_heartbeat();
+            if heartbeat_ipc.client_count().await > 0 {
+                heartbeat_ipc.broadcast_system_metrics().await;
+            }
         }
     });
 
@@ -893,6 +1196,37 @@ async fn main() -> Result<()> {
     ipc_server.clone().start_command_processor();
     let usage_tracker = UsageTracker::new(database.clone());
     let interaction_tracker = InteractionTracker::new(database.clone());
+    if let Err(e) = interaction_tracker.reconcile_open_sessions().await {
+        warn!("Failed to reconcile open DM sessions: {e}");
+        startup_report.degraded("DM session reconciliation", &e);
+    }
+    match database.purge_expired_component_state().await {
+        Ok(0) => {}
+        Ok(purged) => info!("Purged {purged} expired component state rows"),
+        Err(e) => {
+            warn!("Failed to purge expired component state: {e}");
+            startup_report.degraded("Component state cleanup", &e);
+        }
+    }
+    // Threads with a live council, debate or story are recognised from memory per message
+    for kind in [DiscussionType::Council, DiscussionType::Debate] {
+        match restore_all_discussion_states(&database, kind).await {
+            Ok(0) => {}
+            Ok(restored) => info!("Restored {restored} {kind} sessions"),
+            Err(e) => {
+                warn!("Failed to restore {kind} sessions: {e}");
+                startup_report.degraded("Discussion state restore", &e);
+            }
+        }
+    }
+    match restore_all_story_states(&database).await {
+        Ok(0) => {}
+        Ok(restored) => info!("Restored {restored} stories"),
+        Err(e) => {
+            warn!("Failed to restore stories: {e}");
+            startup_report.degraded("Story state restore", &e);
+        }
+    }
     let persona_manager = PersonaManager::new();
 
     // Load plugins: check for plugins/ directory first, fall back to plugins.yaml
@@ -903,7 +1237,7 @@ async fn main() -> Result<()> {
             "plugins.yaml".to_string()
         }
     });
-    let (plugins, plugin_manager): (Vec<Plugin>, Option<Arc<PluginManager>>) =
+    let plugin_manager: Option<Arc<PluginManager>> =
         match PluginConfig::load_auto(&plugins_path) {
             Ok(plugin_config) => {
                 let source = if std::path::Path::new(&plugins_path).is_dir() {
@@ -915,7 +1249,10 @@ async fn main() -> Result<()> {
                     "📄 Loaded {} plugin(s) from {plugins_path} ({source})",
                     plugin_config.plugins.len()
                 );
-                let plugins = plugin_config.plugins.clone();
+                startup_report.plugins = PluginLoadStatus::Loaded {
+                    count: plugin_config.plugins.len(),
+                    source: format!("{plugins_path} ({source})"),
+                };
 
                 // Create allowed commands list
                 let allowed_commands = vec!["docker".to_string(), "sh".to_string()];
@@ -927,26 +1264,40 @@ async fn main() -> Result<()> {
                     .with_usage_tracker(usage_tracker.clone());
 
                 let pm = Arc::new(PluginManager {
-                    config: plugin_config,
+                    config: Arc::new(std::sync::RwLock::new(plugin_config)),
                     executor,
                     job_manager,
                     output_handler,
                 });
 
-                (plugins, Some(pm))
+                Some(pm)
             }
             Err(e) => {
                 if std::path::Path::new(&plugins_path).exists() {
                     error!("❌ Failed to load plugins from {plugins_path}: {e}");
+                    startup_report.plugins = PluginLoadStatus::Failed(e.to_string());
+                    startup_report.degraded("Plugins", &e);
                 } else {
                     info!(
                         "📄 No plugins.yaml found at {plugins_path} - plugin system disabled"
                     );
                 }
-                (vec![], None)
+                None
             }
         };
 
+    // Buffer passive guild message storage off the message hot path
+    let message_writer = MessageWriter::new(
+        database.clone(),
+        config.message_batch_size,
+        std::time::Duration::from_millis(config.message_flush_interval_ms),
+    );
+
+    let presence = PresenceRotator::new(
+        database.clone(),
+        plugin_manager.as_ref().map(|pm| pm.job_manager.clone()),
+    );
+
     let command_handler = CommandHandler::new(
         database.clone(),
         config.openai_api_key.clone(),
@@ -956,11 +1307,32 @@ async fn main() -> Result<()> {
         config.mediation_cooldown_minutes,
         usage_tracker.clone(),
         interaction_tracker,
-        plugin_manager,
-    );
-
-    let component_handler =
-        MessageComponentHandler::new(command_handler.clone(), persona_manager, database.clone());
+        plugin_manager.clone(),
+    )
+    .with_message_writer(message_writer.clone());
+
+    let mut components = ComponentRegistry::new().with_access_rules(database.clone());
+    components.register(Arc::new(MessageComponentHandler::new(
+        command_handler.clone(),
+        persona_manager,
+        database.clone(),
+    )));
+    components.register(Arc::new(PaginationHandler::new(database.clone())));
+    components.register(Arc::new(TriviaButtons));
+    components.register(Arc::new(MeetingButtons::new(database.clone())));
+    components.register(Arc::new(SuggestionButtons::new(database.clone())));
+    components.register(Arc::new(VerificationButtons::new(database.clone())));
+    components.register(Arc::new(HistoryMenu::new(
+        database.clone(),
+        SessionWriter::new(
+            openai_chat_client(),
+            config.openai_model.clone(),
+            usage_tracker.clone(),
+        ),
+    )));
+    if let Some(pm) = &plugin_manager {
+        components.register(Arc::new(JobButtons::new(pm.job_manager.clone())));
+    }
 
     // Parse guild ID if provided for development mode
     let guild_id = config
@@ -969,22 +1341,82 @@ async fn main() -> Result<()> {
         .and_then(|id| id.parse::<u64>().ok())
         .map(GuildId);
 
+    // Let obi-ctl reload plugins and cancel jobs over IPC
+    if let Some(pm) = plugin_manager.clone() {
+        ipc_server
+            .set_plugin_manager(pm, plugins_path.clone(), guild_id)
+            .await;
+    }
+
+    let recorder = match &config.interaction_record_path {
+        Some(path) => match InteractionRecorder::open(path) {
+            Ok(recorder) => {
+                info!("Recording anonymized interactions to {}", path);
+                Some(recorder)
+            }
+            Err(e) => {
+                warn!("Interaction recording disabled: {}", e);
+                startup_report.degraded("Interaction recording", &e);
+                None
+            }
+        },
+        None => None,
+    };
+
+    // Answer in Matrix rooms with the same persona chat pipeline
+    #[cfg(feature = "matrix")]
+    if let Some(matrix_config) = MatrixConfig::from_env() {
+        match MatrixBridge::new(&matrix_config, command_handler.clone(), database.clone()) {
+            Ok(bridge) => {
+                spawn_tracked(TaskKind::Schedulers, bridge.run());
+            }
+            Err(e) => {
+                warn!("Matrix bridge disabled: {}", e);
+                startup_report.degraded("Matrix bridge", &e);
+            }
+        }
+    }
+
+    // Telegram companion bot sharing personas, history and reminders
+    #[cfg(feature = "telegram")]
+    if let Some(telegram_config) = TelegramConfig::from_env() {
+        let bridge =
+            TelegramBridge::new(&telegram_config, command_handler.clone(), database.clone());
+        spawn_tracked(TaskKind::Schedulers, bridge.run());
+    }
+
     // Create startup notifier (reads config from database)
-    let startup_notifier = StartupNotifier::new(Arc::new(database.clone()));
+    let startup_notifier =
+        StartupNotifier::new(Arc::new(database.clone())).with_report(startup_report);
 
     let handler = Handler::new(
         command_handler,
-        component_handler,
+        components,
         guild_id,
         startup_notifier,
-        plugins,
         Some(ipc_server),
-    );
+    )
+    .with_recorder(recorder)
+    .with_presence(presence);
+    let handler = if config.members_intent {
+        handler.with_verification(VerificationGate::new(
+            database.clone(),
+            openai_chat_client(),
+            config.openai_model.clone(),
+            usage_tracker.clone(),
+        ))
+    } else {
+        handler
+    };
 
-    let intents = GatewayIntents::GUILDS
+    let mut intents = GatewayIntents::GUILDS
         | GatewayIntents::GUILD_MESSAGES
         | GatewayIntents::DIRECT_MESSAGES
         | GatewayIntents::MESSAGE_CONTENT;
+    // Member joins for the verification gate; privileged, so only when asked for
+    if config.members_intent {
+        intents |= GatewayIntents::GUILD_MEMBERS;
+    }
 
     // Build the Discord client with proper gateway configuration
     let mut client = Client::builder(&config.discord_token, intents)
@@ -1002,25 +1434,165 @@ async fn main() -> Result<()> {
     info!("Bot configured successfully. Connecting to Discord gateway...");
 
     // Start the reminder scheduler
-    let scheduler =
-        ReminderScheduler::new(database.clone(), config.openai_model.clone(), usage_tracker);
+    let scheduler = ReminderScheduler::new(
+        database.clone(),
+        config.openai_model.clone(),
+        usage_tracker.clone(),
+    );
     let http = client.cache_and_http.http.clone();
-    tokio::spawn(async move {
+    spawn_tracked(TaskKind::Schedulers, async move {
         scheduler.run(http).await;
     });
 
+    // Start the birthday and anniversary scheduler
+    let birthday_scheduler = BirthdayScheduler::new(
+        database.clone(),
+        openai_chat_client(),
+        config.openai_model.clone(),
+        usage_tracker.clone(),
+    );
+    let http = client.cache_and_http.http.clone();
+    spawn_tracked(TaskKind::Schedulers, async move {
+        birthday_scheduler.run(http).await;
+    });
+
+    // Start the daily standup scheduler
+    let standup_scheduler = StandupScheduler::new(database.clone());
+    let http = client.cache_and_http.http.clone();
+    spawn_tracked(TaskKind::Schedulers, async move {
+        standup_scheduler.run(http).await;
+    });
+
+    // Start the scheduled channel topic updates
+    let topic_scheduler = TopicScheduler::new(
+        database.clone(),
+        TopicWriter::new(
+            openai_chat_client(),
+            config.openai_model.clone(),
+            usage_tracker.clone(),
+        ),
+    );
+    let http = client.cache_and_http.http.clone();
+    spawn_tracked(TaskKind::Schedulers, async move {
+        topic_scheduler.run(http).await;
+    });
+
+    // Start the question of the day scheduler
+    let qotd_scheduler = QotdScheduler::new(
+        database.clone(),
+        QuestionWriter::new(
+            openai_chat_client(),
+            config.openai_model.clone(),
+            usage_tracker.clone(),
+        ),
+    );
+    let http = client.cache_and_http.http.clone();
+    spawn_tracked(TaskKind::Schedulers, async move {
+        qotd_scheduler.run(http).await;
+    });
+
+    // Start the verification timeout scheduler
+    let verification_scheduler = VerificationScheduler::new(database.clone());
+    let http = client.cache_and_http.http.clone();
+    spawn_tracked(TaskKind::Schedulers, async move {
+        verification_scheduler.run(http).await;
+    });
+
+    // Start posting /send_later messages
+    let send_later_scheduler = SendLaterScheduler::new(
+        database.clone(),
+        MessageRewriter::new(
+            openai_chat_client(),
+            config.openai_model.clone(),
+            usage_tracker.clone(),
+        ),
+    );
+    let http = client.cache_and_http.http.clone();
+    spawn_tracked(TaskKind::Schedulers, async move {
+        send_later_scheduler.run(http).await;
+    });
+
+    // Start updating /countdown embeds
+    let countdown_scheduler = CountdownScheduler::new(database.clone());
+    let http = client.cache_and_http.http.clone();
+    spawn_tracked(TaskKind::Schedulers, async move {
+        countdown_scheduler.run(http).await;
+    });
+
+    // Start the weekly server report scheduler
+    let report_scheduler = ReportScheduler::new(
+        database.clone(),
+        ServerReporter::new(
+            database.clone(),
+            openai_chat_client(),
+            config.openai_model.clone(),
+            usage_tracker,
+        ),
+    );
+    let http = client.cache_and_http.http.clone();
+    spawn_tracked(TaskKind::Schedulers, async move {
+        report_scheduler.run(http).await;
+    });
+
+    // Start polling YouTube channel subscriptions
+    if let Some(pm) = plugin_manager {
+        let subscription_poller = SubscriptionPoller::new(pm);
+        let http = client.cache_and_http.http.clone();
+        spawn_tracked(TaskKind::Schedulers, async move {
+            subscription_poller.run(http).await;
+        });
+    }
+
+    // Start the webhook daily budget monitor
+    let budget_monitor = BudgetMonitor::new(database.clone());
+    spawn_tracked(TaskKind::Schedulers, async move {
+        budget_monitor.run().await;
+    });
+
+    // Start the persona rotation / seasonal theme scheduler
+    let theme_scheduler = ThemeScheduler::new(database.clone());
+    spawn_tracked(TaskKind::Schedulers, async move {
+        theme_scheduler.run().await;
+    });
+
     // Start the system metrics collection task
     let metrics_db = Arc::new(database);
     let db_path = config.database_path.clone();
-    tokio::spawn(async move {
-        metrics_collection_loop(metrics_db, db_path).await;
+    let raw_retention_days = config.raw_usage_retention_days;
+    let rollup_retention_days = config.rollup_retention_days;
+    let metrics_http = client.cache_and_http.http.clone();
+    spawn_tracked(TaskKind::Schedulers, async move {
+        metrics_collection_loop(
+            metrics_http,
+            metrics_db,
+            db_path,
+            raw_retention_days,
+            rollup_retention_days,
+        )
+        .await;
     });
 
     // Log gateway connection attempt
     info!("Establishing WebSocket connection to Discord gateway...");
     info!("Gateway intents: {intents:?}");
 
-    if let Err(why) = client.start().await {
+    // Stop the gateway on SIGINT/SIGTERM (or after /admin update) so buffered
+    // writes can be flushed; systemd restarts the bot after a clean exit
+    let shard_manager = client.shard_manager.clone();
+    tokio::spawn(async move {
+        tokio::select! {
+            _ = shutdown_signal() => info!("Shutdown signal received, disconnecting from Discord..."),
+            _ = restart_requested() => info!("Restarting for update, disconnecting from Discord..."),
+        }
+        shard_manager.lock().await.shutdown_all().await;
+    });
+
+    let result = client.start().await;
+
+    info!("Flushing buffered messages before exit...");
+    message_writer.shutdown().await;
+
+    if let Err(why) = result {
         error!("Gateway connection failed: {why:?}");
         error!("This could be due to:");
         error!("  - Invalid bot token");
@@ -1035,3 +1607,82 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// Resolve when the process receives Ctrl+C or (on Unix) SIGTERM from systemd
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = sigterm.recv() => {}
+                }
+            }
+            Err(e) => {
+                error!("Failed to install SIGTERM handler: {e}");
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
+
+/// Replay a recording against the dry-run harness and print what the bot would have sent
+async fn run_oneshot_cli(args: &[String]) -> ! {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+
+    // Errors go to stderr with a failing exit code, not into the crash report
+    match oneshot_answer(args).await {
+        Ok(answer) => {
+            println!("{answer}");
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("❌ {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn oneshot_answer(args: &[String]) -> Result<String> {
+    let OneshotArgs { prompt, persona } = parse_oneshot_args(args)?
+        .ok_or_else(|| anyhow::anyhow!("--oneshot requires a prompt (or - for stdin)"))?;
+    let api_key = std::env::var("OPENAI_API_KEY")
+        .map_err(|_| anyhow::anyhow!("OPENAI_API_KEY environment variable not set"))?;
+    std::env::set_var("OPENAI_KEY", &api_key);
+    let model = std::env::var("OPENAI_MODEL").unwrap_or_else(|_| DEFAULT_OPENAI_MODEL.to_string());
+
+    let prompt = if prompt == "-" {
+        let mut prompt = String::new();
+        std::io::stdin().read_to_string(&mut prompt)?;
+        prompt
+    } else {
+        prompt
+    };
+    run_oneshot(
+        openai_chat_client().as_ref(),
+        &PersonaManager::new(),
+        &model,
+        &persona,
+        &prompt,
+    )
+    .await
+}
+
+async fn run_replay(path: &str) -> Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+
+    let report = replay_file(path).await?;
+    print!("{}", report.render());
+
+    if report.failures() > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
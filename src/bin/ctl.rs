@@ -0,0 +1,105 @@
+//! # Obi Admin Console
+//!
+//! Scriptable admin operations over the bot's IPC socket.
+//!
+//! Usage: `cargo run --bin obi-ctl -- reload-plugins` runs one command;
+//! without arguments it reads commands from stdin, one per line.
+
+use anyhow::{anyhow, Result};
+use std::io::IsTerminal;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+use persona::ipc::ctl::{match_reply, parse_admin_command, AdminCommand, HELP};
+use persona::ipc::{IpcClient, TuiCommand};
+
+/// How long to wait for the bot to answer a command
+const REPLY_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[tokio::main]
+async fn main() {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = if args.is_empty() {
+        repl().await
+    } else {
+        one_command(&args.join(" ")).await
+    };
+    if let Err(e) = result {
+        eprintln!("❌ {e}");
+        std::process::exit(1);
+    }
+}
+
+/// Run a single command from the command line; failure sets the exit code
+async fn one_command(line: &str) -> Result<()> {
+    let request_id = uuid::Uuid::new_v4().to_string();
+    match parse_admin_command(line, &request_id).map_err(|e| anyhow!(e))? {
+        None | Some(AdminCommand::Help) => println!("{HELP}"),
+        Some(AdminCommand::Quit) => {}
+        Some(AdminCommand::Send(command)) => {
+            let mut client = IpcClient::connect().await?;
+            client.disable_reconnect().await;
+            let (success, text) = execute(&mut client, command, &request_id).await?;
+            println!("{text}");
+            if !success {
+                std::process::exit(1);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Read commands from stdin until EOF or `quit`
+async fn repl() -> Result<()> {
+    let interactive = std::io::stdin().is_terminal();
+    let mut client = IpcClient::connect().await?;
+    client.disable_reconnect().await;
+    if interactive {
+        println!("Connected to the bot. Type 'help' for commands.");
+    }
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    loop {
+        if interactive {
+            eprint!("obi> ");
+        }
+        let Some(line) = lines.next_line().await? else {
+            break;
+        };
+        let request_id = uuid::Uuid::new_v4().to_string();
+        match parse_admin_command(&line, &request_id) {
+            Ok(None) => {}
+            Ok(Some(AdminCommand::Help)) => println!("{HELP}"),
+            Ok(Some(AdminCommand::Quit)) => break,
+            Ok(Some(AdminCommand::Send(command))) => {
+                match execute(&mut client, command, &request_id).await? {
+                    (true, text) => println!("{text}"),
+                    (false, text) => println!("❌ {text}"),
+                }
+            }
+            Err(e) => println!("❌ {e}"),
+        }
+    }
+    Ok(())
+}
+
+/// Send a command and wait for its reply, skipping unrelated events
+async fn execute(
+    client: &mut IpcClient,
+    command: TuiCommand,
+    request_id: &str,
+) -> Result<(bool, String)> {
+    client.send(command.clone()).await?;
+    tokio::time::timeout(REPLY_TIMEOUT, async {
+        while let Some(event) = client.recv().await {
+            if let Some(reply) = match_reply(&command, request_id, &event) {
+                return Ok(reply);
+            }
+        }
+        Err(anyhow!("Connection to the bot closed"))
+    })
+    .await
+    .map_err(|_| anyhow!("No reply from the bot within {}s", REPLY_TIMEOUT.as_secs()))?
+}
@@ -188,10 +188,19 @@ async fn handle_action(
                     Screen::Stats => {
                         // Request historical metrics for charts
                         let _ = client
-                            .request_historical_metrics("cpu".to_string(), 24)
+                            .request_historical_metrics(
+                                "cpu".to_string(),
+                                app.stats_cache.time_period.history_hours(),
+                            )
                             .await;
                         let _ = client
-                            .request_historical_metrics("memory".to_string(), 24)
+                            .request_historical_metrics(
+                                "memory".to_string(),
+                                app.stats_cache.time_period.history_hours(),
+                            )
+                            .await;
+                        let _ = client
+                            .request_sentiment_timeline(app.stats_cache.time_period.mood_hours())
                             .await;
                     }
                     Screen::Channels => {
@@ -234,6 +243,9 @@ async fn handle_action(
                 Screen::Errors => {
                     app.errors_state.select_previous();
                 }
+                Screen::Search => {
+                    app.search_state.select_previous();
+                }
                 Screen::Settings => {
                     let index = app.settings_current_index_mut();
                     if *index > 0 {
@@ -275,6 +287,9 @@ async fn handle_action(
                 Screen::Errors => {
                     app.errors_state.select_next();
                 }
+                Screen::Search => {
+                    app.search_state.select_next();
+                }
                 _ => {}
             }
         }
@@ -339,6 +354,25 @@ async fn handle_action(
                         app.errors_state.enter_details();
                     }
                 }
+                Screen::Search => {
+                    // Jump to the channel watcher for the selected result
+                    if let Some(result) = app.search_state.selected_result() {
+                        let channel_id = result.channel_id;
+                        app.switch_screen(Screen::Channels);
+                        if !app.channel_state.is_watching(channel_id) {
+                            app.channel_state.watch(channel_id);
+                        }
+                        app.channel_state.select(channel_id);
+                        if let Some(client) = ipc_client {
+                            let _ = client.watch_channel(channel_id).await;
+                            if app.channel_state.needs_history(channel_id) {
+                                app.channel_state.start_fetching_history();
+                                let _ = client.get_channel_history(channel_id, 50).await;
+                            }
+                            let _ = client.request_channel_info(channel_id).await;
+                        }
+                    }
+                }
                 _ => {}
             }
         }
@@ -366,13 +400,17 @@ async fn handle_action(
                     app.switch_screen(Screen::Dashboard);
                 }
             }
-            Screen::Help => {
+            Screen::Help | Screen::Search => {
                 app.switch_screen(Screen::Dashboard);
             }
             _ => {}
         },
         KeyAction::StartInput => {
-            app.start_editing();
+            if app.current_screen == Screen::Search {
+                app.start_search_input();
+            } else {
+                app.start_editing();
+            }
         }
         KeyAction::StartMessageInput => {
             // Only allow message input when a channel is selected
@@ -415,6 +453,15 @@ async fn handle_action(
                                         Some("Invalid channel ID - must be a number".to_string());
                                 }
                             }
+                            InputPurpose::Search => {}
+                        }
+                    }
+                    Screen::Search => {
+                        if let Some(client) = ipc_client {
+                            app.search_state.start_search(input.clone());
+                            let _ = client.request_search(input, None, 50).await;
+                        } else {
+                            app.error_message = Some("Not connected to bot".to_string());
                         }
                     }
                     _ => {}
@@ -451,10 +498,19 @@ async fn handle_action(
                             .await;
                         let _ = client.request_system_metrics().await;
                         let _ = client
-                            .request_historical_metrics("cpu".to_string(), 24)
+                            .request_historical_metrics(
+                                "cpu".to_string(),
+                                app.stats_cache.time_period.history_hours(),
+                            )
+                            .await;
+                        let _ = client
+                            .request_historical_metrics(
+                                "memory".to_string(),
+                                app.stats_cache.time_period.history_hours(),
+                            )
                             .await;
                         let _ = client
-                            .request_historical_metrics("memory".to_string(), 24)
+                            .request_sentiment_timeline(app.stats_cache.time_period.mood_hours())
                             .await;
                         app.stats_cache.start_refresh();
                         app.status_message = Some("Refreshing...".to_string());
@@ -497,6 +553,16 @@ async fn handle_action(
                         let _ = client
                             .request_usage_stats(app.stats_cache.time_period.days())
                             .await;
+                        let hours = app.stats_cache.time_period.history_hours();
+                        let _ = client
+                            .request_historical_metrics("cpu".to_string(), hours)
+                            .await;
+                        let _ = client
+                            .request_historical_metrics("memory".to_string(), hours)
+                            .await;
+                        let _ = client
+                            .request_sentiment_timeline(app.stats_cache.time_period.mood_hours())
+                            .await;
                     }
                 }
                 _ => {}
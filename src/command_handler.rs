@@ -1,23 +1,65 @@
-use crate::commands::context::CommandContext;
+use crate::commands::context::{channel_category_id, channel_scope_ids, CommandContext};
 use crate::commands::handlers::create_all_handlers;
-use crate::commands::registry::CommandRegistry;
+use crate::commands::middleware::{AccessMiddleware, RateLimitMiddleware, SimilarityMiddleware};
+use crate::commands::registry::{CommandRegistry, Dispatch};
 use crate::core::{
-    chunk_for_embed, chunk_for_message, continuation_embed, persona_embed,
+    chunk_for_embed, chunk_for_message, continuation_embed, describe_stickers, emoji_prompt,
+    fetch_guild_emojis, openai_chat_client, persona_embed, sanitize_emojis, ChatClient,
+    ModelParams, Source, SourceList,
 };
-use crate::database::Database;
+use crate::database::{Database, PrivacySettings};
+use crate::features::achievements::{self, Progress};
+use crate::features::analytics::runtime::{spawn_tracked, TaskKind};
 use crate::features::analytics::{CostBucket, InteractionTracker, UsageTracker};
-use crate::features::audio::transcriber::AudioTranscriber;
-use crate::features::conflict::{ConflictDetector, ConflictMediator};
-use crate::features::council::get_active_councils;
+use crate::features::anon_questions::{self, loggable_user};
+use crate::features::audio::transcriber::{AudioTranscriber, NoAudioTrackError};
+use crate::features::autoresponse::{
+    self, AutoResponder, AutoResponseGate, ResponseKind, TriggerDecision,
+};
+use crate::features::conflict::window::WINDOW_SIZE;
+use crate::features::conflict::{ConflictDetector, ConflictMediator, MessageWindow};
+use crate::features::council::{get_active_councils, TurnUsage};
+use crate::features::debate::get_active_debates;
+use crate::features::discussion::{save_discussion_state_logged, DiscussionType};
+use crate::features::dm_history::{SessionWriter, DM_HISTORY_FEATURE};
+use crate::features::glossary::apply_glossary;
+use crate::features::guardrails::{apply_guardrails, ChannelGrounding, GuardrailProfile};
 use crate::features::image_gen::generator::ImageGenerator;
-use crate::features::personas::PersonaManager;
-use crate::features::plugins::PluginManager;
-use crate::features::rate_limiting::RateLimiter;
+use crate::features::language::{self, LanguageNudger, NudgeDecision, NudgeGate};
+use crate::features::leveling::{self, XpSource};
+use crate::features::link_preview::{
+    self, post_link_previews, LinkPreviewMode, LinkPreviewer, PreviewDecision, PreviewGate,
+};
+use crate::features::memories::apply_memories;
+use crate::features::ocr;
+use crate::features::personas::themes::ACTIVE_THEME_SETTING;
+use crate::features::personas::{apply_theme, verbosity, PersonaManager};
+use crate::features::plugins::{transcript_qa, PluginManager};
+use crate::features::rate_limiting::{
+    throttle_warning_embed, RateLimiter, SimilarityThrottle, ThrottleDecision,
+};
+use crate::features::resilience::{chaos, circuit_breakers, CircuitOpenError, Dependency};
+use crate::features::scenes::{apply_scene, channel_scene};
+use crate::features::sentiment;
+use crate::features::standup;
+use crate::features::story::{
+    get_active_stories, save_story_state_logged, StoryTurn, StoryWriter, MAX_CONTRIBUTION_CHARS,
+    STORY_FEATURE,
+};
+use crate::features::timezones;
+use crate::features::trivia;
+use crate::features::verification;
+use crate::features::voice_commands::{self, VoiceCommandContext};
+use crate::features::webhooks::{self, WebhookEvent};
+use crate::message_components::{MessageComponentHandler, DM_CONSENT_DECLINED, DM_CONSENT_PROMPT};
+use crate::message_writer::MessageWriter;
 use anyhow::Result;
 use log::{debug, error, info, warn};
-use openai::chat::{ChatCompletion, ChatCompletionMessage, ChatCompletionMessageRole};
+use openai::chat::{ChatCompletionMessage, ChatCompletionMessageRole};
+use serenity::http::Http;
 use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
 use serenity::model::channel::Message;
+use serenity::model::id::{ChannelId, MessageId};
 use serenity::prelude::Context;
 use std::sync::Arc;
 use std::time::Duration;
@@ -28,11 +70,16 @@ use uuid::Uuid;
 pub struct CommandHandler {
     persona_manager: PersonaManager,
     database: Database,
-    rate_limiter: RateLimiter,
+    rate_limiter: Arc<RateLimiter>,
+    prompt_throttle: Arc<SimilarityThrottle>,
+    link_preview_gate: Arc<PreviewGate>,
+    language_gate: Arc<NudgeGate>,
+    autoresponse_gate: Arc<AutoResponseGate>,
     audio_transcriber: AudioTranscriber,
     openai_model: String,
     conflict_detector: ConflictDetector,
     conflict_mediator: ConflictMediator,
+    conflict_window: MessageWindow,
     conflict_enabled: bool,
     conflict_sensitivity_threshold: f32,
     usage_tracker: UsageTracker,
@@ -40,6 +87,8 @@ pub struct CommandHandler {
     plugin_manager: Option<Arc<PluginManager>>,
     command_registry: CommandRegistry,
     command_context: Arc<CommandContext>,
+    message_writer: Option<MessageWriter>,
+    chat_client: Arc<dyn ChatClient>,
 }
 
 impl CommandHandler {
@@ -67,8 +116,24 @@ impl CommandHandler {
         let image_generator = ImageGenerator::new(openai_api_key.clone());
         let start_time = std::time::Instant::now();
 
+        // Build registry from all handler modules; commands share the message rate limiter
+        let rate_limiter = Arc::new(RateLimiter::new(10, Duration::from_secs(60)));
+        let prompt_throttle = Arc::new(SimilarityThrottle::new());
+        let mut command_registry = CommandRegistry::new();
+        for handler in create_all_handlers() {
+            command_registry.register(handler);
+        }
+        // Blocked users are turned away before they count against the rate limit
+        command_registry.add_middleware(Arc::new(AccessMiddleware));
+        command_registry.add_middleware(Arc::new(RateLimitMiddleware::new(Arc::clone(
+            &rate_limiter,
+        ))));
+        command_registry.add_middleware(Arc::new(SimilarityMiddleware::new(Arc::clone(
+            &prompt_throttle,
+        ))));
+
         // Build shared context for modular handlers
-        let command_context = Arc::new(CommandContext::with_start_time(
+        let mut command_context = CommandContext::with_start_time(
             persona_manager.clone(),
             database.clone(),
             usage_tracker.clone(),
@@ -77,22 +142,23 @@ impl CommandHandler {
             plugin_manager.clone(),
             openai_model.clone(),
             start_time,
-        ));
-
-        // Build registry from all handler modules
-        let mut command_registry = CommandRegistry::new();
-        for handler in create_all_handlers() {
-            command_registry.register(handler);
-        }
+        );
+        command_context.command_metrics = command_registry.metrics();
+        let command_context = Arc::new(command_context);
 
         CommandHandler {
             persona_manager,
             database,
-            rate_limiter: RateLimiter::new(10, Duration::from_secs(60)),
+            rate_limiter,
+            prompt_throttle,
+            link_preview_gate: Arc::new(PreviewGate::new()),
+            language_gate: Arc::new(NudgeGate::new()),
+            autoresponse_gate: Arc::new(AutoResponseGate::new()),
             audio_transcriber: AudioTranscriber::new(openai_api_key),
             openai_model,
             conflict_detector: ConflictDetector::new(),
             conflict_mediator: ConflictMediator::new(999, mediation_cooldown_minutes), // High limit for testing
+            conflict_window: MessageWindow::new(),
             conflict_enabled,
             conflict_sensitivity_threshold: sensitivity_threshold,
             usage_tracker,
@@ -100,14 +166,33 @@ impl CommandHandler {
             plugin_manager,
             command_registry,
             command_context,
+            message_writer: None,
+            chat_client: openai_chat_client(),
         }
     }
 
+    /// Route all chat completions through the given client (mock or live)
+    pub fn with_chat_client(mut self, chat_client: Arc<dyn ChatClient>) -> Self {
+        self.command_context = Arc::new(
+            (*self.command_context)
+                .clone()
+                .with_chat_client(chat_client.clone()),
+        );
+        self.chat_client = chat_client;
+        self
+    }
+
+    /// Buffer passive guild message storage through a write-behind writer
+    pub fn with_message_writer(mut self, writer: MessageWriter) -> Self {
+        self.message_writer = Some(writer);
+        self
+    }
+
     /// Get a reference to the loaded plugins (if any)
     pub fn get_plugins(&self) -> Vec<crate::features::plugins::Plugin> {
         self.plugin_manager
             .as_ref()
-            .map(|pm| pm.config.plugins.clone())
+            .map(|pm| pm.plugins())
             .unwrap_or_default()
     }
 
@@ -130,13 +215,62 @@ impl CommandHandler {
             None
         };
 
+        // Guild block and allow lists (/admin block, /admin allow) come before anything else
+        if let Some(gid) = guild_id_opt {
+            let rules = self.database.get_access_rules(gid).await?;
+            if !rules.is_empty() {
+                let channels = if rules.has_channel_rules() {
+                    channel_scope_ids(ctx, msg.channel_id).await
+                } else {
+                    Vec::new()
+                };
+                if !rules.permits(&user_id, &channels) {
+                    debug!("[{request_id}] 🚫 Ignoring message from {user_id} in {channel_id} (access rules)");
+                    return Ok(());
+                }
+            }
+
+            // A new member's reply in the gated channel is a verification answer
+            if verification::handle_answer(&ctx.http, &self.database, msg).await? {
+                debug!("[{request_id}] 🚪 Verification answer from {user_id}");
+                return Ok(());
+            }
+
+            // A typed A-D during an open trivia question is an answer, not a chat message
+            if let Some(outcome) =
+                trivia::record_message_answer(msg.channel_id.0, &user_id, &msg.content)
+            {
+                debug!("[{request_id}] 🎲 Trivia answer from {user_id}: {outcome:?}");
+                if outcome == trivia::AnswerOutcome::Recorded {
+                    let _ = msg.react(&ctx.http, '✅').await;
+                }
+                return Ok(());
+            }
+        } else if let Some(reply) =
+            standup::handle_dm_reply(&self.database, &user_id, &msg.content).await?
+        {
+            // Members who joined a standup answer its questions by DM; only the
+            // answer is kept, in the standup entry
+            debug!("[{request_id}] 🗒️ Standup reply from {user_id}");
+            msg.channel_id.say(&ctx.http, reply).await?;
+            return Ok(());
+        }
+
+        // DMs are neither read nor stored until the user accepts the consent prompt
+        let dm_consent = if guild_id_opt.is_none() {
+            self.database.get_dm_consent(&user_id).await?
+        } else {
+            Some(true)
+        };
+        let logged_content = if dm_consent == Some(true) {
+            msg.content.chars().take(100).collect::<String>()
+        } else {
+            "<awaiting DM consent>".to_string()
+        };
+
         info!(
             "[{}] 📥 Message received | User: {} | Channel: {} | Guild: {} | Content: '{}'",
-            request_id,
-            user_id,
-            channel_id,
-            guild_id,
-            msg.content.chars().take(100).collect::<String>()
+            request_id, user_id, channel_id, guild_id, logged_content
         );
 
         debug!("[{request_id}] 🔍 Checking rate limit for user: {user_id}");
@@ -154,6 +288,28 @@ impl CommandHandler {
         }
         debug!("[{request_id}] ✅ Rate limit check passed");
 
+        if dm_consent != Some(true) {
+            info!("[{request_id}] 🔒 No DM consent from {user_id}, sending consent prompt");
+            let declined = dm_consent == Some(false);
+            msg.channel_id
+                .send_message(&ctx.http, |m| {
+                    m.content(if declined {
+                        DM_CONSENT_DECLINED
+                    } else {
+                        DM_CONSENT_PROMPT
+                    })
+                    .set_components(MessageComponentHandler::create_dm_consent_buttons(declined))
+                })
+                .await?;
+            return Ok(());
+        }
+
+        // /privacy choices decide what is kept about this message and who may analyse it
+        let privacy = self
+            .database
+            .get_privacy_settings(&user_id, guild_id_opt)
+            .await?;
+
         // Get audio transcription mode for this guild
         let is_dm = msg.guild_id.is_none();
         let audio_mode = if let Some(gid) = guild_id_opt {
@@ -187,7 +343,7 @@ impl CommandHandler {
                 msg.attachments.len(),
                 audio_mode
             );
-            self.handle_audio_attachments(ctx, msg, guild_id_opt)
+            self.handle_audio_attachments(ctx, msg, guild_id_opt, privacy)
                 .await?
         } else {
             false
@@ -205,9 +361,44 @@ impl CommandHandler {
         // Store guild messages FIRST (needed for conflict detection to have data)
         if !is_dm && !content.is_empty() && !content.starts_with('/') {
             debug!("[{request_id}] 💾 Storing guild message for analysis");
-            self.database
-                .store_message(&user_id, &channel_id, "user", content, None)
-                .await?;
+            if let Some(writer) = &self.message_writer {
+                writer.store(guild_id_opt, &user_id, &channel_id, "user", content, None);
+            } else {
+                self.database
+                    .store_guild_message(guild_id_opt, &user_id, &channel_id, "user", content, None)
+                    .await?;
+            }
+            self.conflict_window.record(
+                &channel_id,
+                &user_id,
+                content,
+                chrono::Utc::now().timestamp(),
+            );
+        }
+
+        // Channel mood only counts messages from users who allow analytics
+        if !is_dm && privacy.analytics && !content.is_empty() && !content.starts_with('/') {
+            if let Err(e) = self.record_sentiment(guild_id_opt, &channel_id, content).await {
+                warn!("[{request_id}] ⚠️ Sentiment tracking error: {e}");
+            }
+        }
+
+        // XP for chatting; short messages don't count
+        if let Some(gid) = guild_id_opt {
+            if content.chars().count() >= leveling::MIN_MESSAGE_CHARS && !content.starts_with('/') {
+                if let Err(e) = leveling::grant(
+                    &self.database,
+                    &ctx.http,
+                    gid,
+                    &user_id,
+                    &channel_id,
+                    XpSource::Message,
+                )
+                .await
+                {
+                    warn!("[{request_id}] ⚠️ Leveling error: {e}");
+                }
+            }
         }
 
         // Conflict detection - check both env var AND feature flag
@@ -222,12 +413,13 @@ impl CommandHandler {
         if !is_dm
             && self.conflict_enabled
             && guild_conflict_enabled
+            && privacy.conflict_analysis
             && !content.is_empty()
             && !content.starts_with('/')
         {
             debug!("[{request_id}] 🔍 Running conflict detection analysis");
             if let Err(e) = self
-                .check_and_mediate_conflicts(ctx, msg, &channel_id, guild_id_opt)
+                .check_and_mediate_conflicts(&ctx.http, msg.channel_id, msg.id, guild_id_opt)
                 .await
             {
                 warn!("[{request_id}] ⚠️ Conflict detection error: {e}");
@@ -245,11 +437,18 @@ impl CommandHandler {
                 .await?;
         } else if is_dm && !content.is_empty() && !audio_handled {
             info!("[{request_id}] 💬 Processing DM message (auto-response mode)");
-            self.handle_dm_message_with_id(ctx, msg, request_id).await?;
+            self.handle_dm_message_with_id(ctx, msg, request_id, privacy)
+                .await?;
+        } else if !is_dm
+            && !audio_handled
+            && !content.is_empty()
+            && self.is_in_active_story_thread(msg.channel_id)
+        {
+            self.handle_story_contribution(ctx, msg, request_id).await?;
         } else if !is_dm
             && !audio_handled
             && !content.is_empty()
-            && self.is_in_active_debate_thread(msg.channel_id).await
+            && self.is_in_active_debate_thread(msg.channel_id)
         {
             // Check if debate_auto_response is enabled for this guild
             let auto_response_enabled = if let Some(gid) = guild_id_opt {
@@ -264,7 +463,7 @@ impl CommandHandler {
 
             if auto_response_enabled {
                 info!("[{request_id}] 🎭 Auto-responding in active debate thread");
-                self.handle_mention_message_with_id(ctx, msg, request_id)
+                self.handle_mention_message_with_id(ctx, msg, request_id, privacy)
                     .await?;
             } else {
                 debug!("[{request_id}] ℹ️ Message in debate thread but auto-response disabled");
@@ -272,7 +471,7 @@ impl CommandHandler {
         } else if !is_dm
             && !audio_handled
             && !content.is_empty()
-            && self.is_in_active_council_thread(msg.channel_id).await
+            && self.is_in_active_council_thread(msg.channel_id)
         {
             // Council threads respond to mentions by default
             if self.is_bot_mentioned(ctx, msg).await? {
@@ -281,6 +480,18 @@ impl CommandHandler {
             } else {
                 debug!("[{request_id}] ℹ️ Message in council thread but bot not mentioned");
             }
+        } else if !is_dm
+            && !audio_handled
+            && !content.is_empty()
+            && self
+                .auto_respond(ctx, msg, request_id)
+                .await
+                .unwrap_or_else(|e| {
+                    warn!("[{request_id}] ⚠️ Auto-response error: {e}");
+                    false
+                })
+        {
+            debug!("[{request_id}] 🤖 Answered by an auto-response trigger");
         } else if !is_dm
             && !audio_handled
             && self.is_bot_mentioned(ctx, msg).await?
@@ -299,13 +510,22 @@ impl CommandHandler {
 
             if mention_enabled {
                 info!("[{request_id}] 🏷️ Bot mentioned in channel - responding");
-                self.handle_mention_message_with_id(ctx, msg, request_id)
+                self.handle_mention_message_with_id(ctx, msg, request_id, privacy)
                     .await?;
             } else {
                 debug!("[{request_id}] ℹ️ Bot mentioned but mention_responses disabled for guild");
             }
         } else if !is_dm && !content.is_empty() {
             debug!("[{request_id}] ℹ️ Guild message stored (no bot response needed)");
+            if let Err(e) = self.preview_links(ctx, msg, request_id).await {
+                warn!("[{request_id}] ⚠️ Link preview error: {e}");
+            }
+            if let Err(e) = self.enforce_language(ctx, msg, request_id).await {
+                warn!("[{request_id}] ⚠️ Language nudge error: {e}");
+            }
+            if let Err(e) = self.convert_times(ctx, msg, request_id).await {
+                warn!("[{request_id}] ⚠️ Time conversion error: {e}");
+            }
         } else {
             debug!("[{request_id}] ℹ️ Message ignored (empty or DM)");
         }
@@ -314,25 +534,443 @@ impl CommandHandler {
         Ok(())
     }
 
+    /// Add a message's sentiment to its channel's hourly bucket when the channel opted in
+    async fn record_sentiment(
+        &self,
+        guild_id: Option<&str>,
+        channel_id: &str,
+        content: &str,
+    ) -> Result<()> {
+        let Some(guild_id) = guild_id else {
+            return Ok(());
+        };
+        if !self
+            .database
+            .get_channel_sentiment_tracking(guild_id, channel_id)
+            .await?
+            || !self
+                .database
+                .is_feature_enabled(sentiment::SENTIMENT_FEATURE, None, Some(guild_id))
+                .await?
+        {
+            return Ok(());
+        }
+        let score = self.conflict_detector.get_sentiment_score(content);
+        let hour = sentiment::hour_start(chrono::Utc::now().timestamp());
+        self.database
+            .record_channel_sentiment(guild_id, channel_id, hour, score as f64)
+            .await
+    }
+
+    /// Summarize bare URLs in a plain guild message when the channel opted in
+    ///
+    /// Fetching and summarizing runs in the background; the gate decides up
+    /// front which URLs count against the channel's preview budget.
+    async fn preview_links(&self, ctx: &Context, msg: &Message, request_id: Uuid) -> Result<()> {
+        let Some(guild_id) = msg.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        let urls = link_preview::extract_urls(&msg.content);
+        if urls.is_empty() {
+            return Ok(());
+        }
+
+        let channel_id = msg.channel_id.to_string();
+        let setting = self
+            .database
+            .get_channel_link_previews(&guild_id, &channel_id)
+            .await?;
+        let Some(mode) = LinkPreviewMode::from_setting(&setting) else {
+            return Ok(());
+        };
+        if !self
+            .database
+            .is_feature_enabled(link_preview::LINK_PREVIEW_FEATURE, None, Some(&guild_id))
+            .await?
+        {
+            return Ok(());
+        }
+
+        let urls: Vec<String> = urls
+            .into_iter()
+            .take(link_preview::MAX_URLS_PER_MESSAGE)
+            .filter(
+                |url| match self.link_preview_gate.check(msg.channel_id.0, url) {
+                    PreviewDecision::Allow => true,
+                    decision => {
+                        debug!("[{request_id}] 🔗 Skipping preview of {url}: {decision:?}");
+                        false
+                    }
+                },
+            )
+            .collect();
+        if urls.is_empty() {
+            return Ok(());
+        }
+
+        info!(
+            "[{request_id}] 🔗 Previewing {} link(s) in {channel_id}",
+            urls.len()
+        );
+        let previewer = LinkPreviewer::new(
+            self.chat_client.clone(),
+            self.openai_model.clone(),
+            self.usage_tracker.clone(),
+        );
+        let grounding =
+            ChannelGrounding::for_channel(&self.database, Some(&guild_id), Some(&channel_id))
+                .await?;
+        let http = ctx.http.clone();
+        let msg = msg.clone();
+        spawn_tracked(TaskKind::Discussions, async move {
+            let user_id = msg.author.id.to_string();
+            let mut previews = Vec::new();
+            for url in &urls {
+                match previewer
+                    .summarize(
+                        url,
+                        &grounding,
+                        &user_id,
+                        Some(&guild_id),
+                        &channel_id,
+                        request_id,
+                    )
+                    .await
+                {
+                    Ok(Some(preview)) => previews.push(preview),
+                    Ok(None) => debug!("[{request_id}] 🔗 Nothing to preview at {url}"),
+                    Err(e) => warn!("[{request_id}] ⚠️ Failed to preview {url}: {e}"),
+                }
+            }
+            if let Err(e) = post_link_previews(&http, &msg, &previews, mode).await {
+                warn!("[{request_id}] ⚠️ Failed to post link previews: {e}");
+            }
+        });
+        Ok(())
+    }
+
+    /// Answer a plain guild message with the first auto-response trigger it matches
+    ///
+    /// Triggers are checked highest priority first and the first match
+    /// decides: when it's cooling down, nothing is sent and the message
+    /// carries on down the normal path. Returns whether a reply was sent (or,
+    /// for persona replies, started in the background).
+    async fn auto_respond(&self, ctx: &Context, msg: &Message, request_id: Uuid) -> Result<bool> {
+        let Some(guild_id) = msg.guild_id.map(|id| id.to_string()) else {
+            return Ok(false);
+        };
+        if !self
+            .database
+            .is_feature_enabled(autoresponse::AUTORESPONSE_FEATURE, None, Some(&guild_id))
+            .await?
+        {
+            return Ok(false);
+        }
+        let triggers = self.database.get_autoresponses(&guild_id).await?;
+        let Some((trigger, decision)) = triggers.iter().find_map(|trigger| {
+            match self.autoresponse_gate.check(trigger, &msg.content) {
+                TriggerDecision::NoMatch => None,
+                decision => Some((trigger, decision)),
+            }
+        }) else {
+            return Ok(false);
+        };
+        if decision == TriggerDecision::Cooldown {
+            debug!(
+                "[{request_id}] 🤖 Trigger #{} matched but is cooling down",
+                trigger.id
+            );
+            return Ok(false);
+        }
+        info!(
+            "[{request_id}] 🤖 Auto-response #{} triggered by {} in {}",
+            trigger.id, msg.author.id, msg.channel_id
+        );
+
+        match ResponseKind::from_setting(Some(&trigger.response_kind)) {
+            ResponseKind::Reply => {
+                let reply = autoresponse::render_reply(&trigger.response, msg.author.id.0);
+                msg.channel_id
+                    .send_message(&ctx.http, |m| {
+                        m.content(reply)
+                            .reference_message(msg)
+                            .allowed_mentions(|a| a.users([msg.author.id]).replied_user(false))
+                    })
+                    .await?;
+            }
+            ResponseKind::Persona => {
+                let user_id = msg.author.id.to_string();
+                let channel_id = msg.channel_id.to_string();
+                let category_id = channel_category_id(ctx, msg.channel_id).await;
+                let persona = self
+                    .database
+                    .get_persona_with_channel(
+                        &user_id,
+                        &guild_id,
+                        &channel_id,
+                        category_id.as_deref(),
+                    )
+                    .await?;
+                let theme = self
+                    .database
+                    .get_guild_setting(&guild_id, ACTIVE_THEME_SETTING)
+                    .await?;
+                let persona_prompt = apply_theme(
+                    self.persona_manager.get_system_prompt(&persona, None),
+                    theme.as_deref(),
+                );
+                let grounding = ChannelGrounding::for_channel(
+                    &self.database,
+                    Some(&guild_id),
+                    Some(&channel_id),
+                )
+                .await?;
+                let responder = AutoResponder::new(
+                    self.chat_client.clone(),
+                    self.openai_model.clone(),
+                    self.usage_tracker.clone(),
+                );
+                let instructions = trigger.response.clone();
+                let http = ctx.http.clone();
+                let msg = msg.clone();
+                spawn_tracked(TaskKind::Discussions, async move {
+                    let reply = match responder
+                        .respond(&persona_prompt, &grounding, &instructions, &msg, request_id)
+                        .await
+                    {
+                        Ok(Some(reply)) => reply,
+                        Ok(None) => return,
+                        Err(e) => {
+                            warn!("[{request_id}] ⚠️ Failed to write auto-response: {e}");
+                            return;
+                        }
+                    };
+                    if let Err(e) = msg
+                        .channel_id
+                        .send_message(&http, |m| {
+                            m.content(reply)
+                                .reference_message(&msg)
+                                .allowed_mentions(|a| a.replied_user(false))
+                        })
+                        .await
+                    {
+                        warn!("[{request_id}] ⚠️ Failed to post auto-response: {e}");
+                    }
+                });
+            }
+        }
+        Ok(true)
+    }
+
+    /// Nudge the author of a plain guild message written outside the channel's language
+    ///
+    /// Detection and the gate run inline; the persona-voiced reply is written
+    /// and posted in the background.
+    async fn enforce_language(&self, ctx: &Context, msg: &Message, request_id: Uuid) -> Result<()> {
+        let Some(guild_id) = msg.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        let channel_id = msg.channel_id.to_string();
+        let Some(designated) = self
+            .database
+            .get_channel_language(&guild_id, &channel_id)
+            .await?
+            .as_deref()
+            .and_then(language::find_language)
+        else {
+            return Ok(());
+        };
+        let Some(detected) = language::off_language(&msg.content, designated) else {
+            return Ok(());
+        };
+        if !self
+            .database
+            .is_feature_enabled(language::LANGUAGE_FEATURE, None, Some(&guild_id))
+            .await?
+        {
+            return Ok(());
+        }
+        if let Some(role_id) = self
+            .database
+            .get_guild_setting(&guild_id, language::EXEMPT_ROLE_SETTING)
+            .await?
+        {
+            let exempt = msg
+                .member
+                .as_ref()
+                .is_some_and(|m| m.roles.iter().any(|r| r.to_string() == role_id));
+            if exempt {
+                return Ok(());
+            }
+        }
+        match self.language_gate.check(msg.channel_id.0, msg.author.id.0) {
+            NudgeDecision::Allow => {}
+            decision => {
+                debug!(
+                    "[{request_id}] 🌐 Not nudging {} about {}: {decision:?}",
+                    msg.author.id, detected.code
+                );
+                return Ok(());
+            }
+        }
+
+        let user_id = msg.author.id.to_string();
+        let category_id = channel_category_id(ctx, msg.channel_id).await;
+        let persona = self
+            .database
+            .get_persona_with_channel(&user_id, &guild_id, &channel_id, category_id.as_deref())
+            .await?;
+        let persona_prompt = self.persona_manager.get_system_prompt(&persona, None);
+        let grounding =
+            ChannelGrounding::for_channel(&self.database, Some(&guild_id), Some(&channel_id))
+                .await?;
+        let nudger = LanguageNudger::new(
+            self.chat_client.clone(),
+            self.openai_model.clone(),
+            self.usage_tracker.clone(),
+        );
+        let http = ctx.http.clone();
+        let msg = msg.clone();
+        spawn_tracked(TaskKind::Discussions, async move {
+            let nudge = match nudger
+                .nudge(
+                    &persona_prompt,
+                    &grounding,
+                    designated,
+                    detected,
+                    &msg,
+                    request_id,
+                )
+                .await
+            {
+                Ok(Some(nudge)) => nudge,
+                Ok(None) => return,
+                Err(e) => {
+                    warn!("[{request_id}] ⚠️ Failed to write language nudge: {e}");
+                    return;
+                }
+            };
+            if let Err(e) = msg
+                .channel_id
+                .send_message(&http, |m| {
+                    m.content(nudge)
+                        .reference_message(&msg)
+                        .allowed_mentions(|a| a.replied_user(false))
+                })
+                .await
+            {
+                warn!("[{request_id}] ⚠️ Failed to post language nudge: {e}");
+            }
+        });
+        Ok(())
+    }
+
+    /// Reply with timestamp markup for clock times in a plain guild message
+    ///
+    /// Only in channels that opted in, and only for authors who stored a
+    /// time zone with `/time zone`, since a bare time means nothing without one.
+    async fn convert_times(&self, ctx: &Context, msg: &Message, request_id: Uuid) -> Result<()> {
+        let Some(guild_id) = msg.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        let times = timezones::detect_times(&msg.content);
+        if times.is_empty()
+            || !self
+                .database
+                .get_channel_time_detection(&guild_id, &msg.channel_id.to_string())
+                .await?
+            || !self
+                .database
+                .is_feature_enabled(timezones::TIMEZONES_FEATURE, None, Some(&guild_id))
+                .await?
+        {
+            return Ok(());
+        }
+        let Some(zone) = timezones::member_zone(&self.database, &msg.author.id.to_string()).await?
+        else {
+            return Ok(());
+        };
+
+        let now = chrono::Utc::now();
+        let lines: Vec<String> = times
+            .iter()
+            .map(|time| {
+                let unix = timezones::next_occurrence(*time, &zone, now);
+                format!(
+                    "🕒 {} {zone} → {}",
+                    time.format("%H:%M"),
+                    timezones::timestamp_markup(unix)
+                )
+            })
+            .collect();
+        debug!(
+            "[{request_id}] 🕒 Converting {} time(s) for {}",
+            lines.len(),
+            msg.author.id
+        );
+        msg.channel_id
+            .send_message(&ctx.http, |m| {
+                m.content(lines.join("\n"))
+                    .reference_message(msg)
+                    .allowed_mentions(|a| a.replied_user(false))
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Near-duplicate prompt throttling for messages that reach the model
+    ///
+    /// Warns once when a strike is earned; prompts during the cooldown are
+    /// dropped without a reply so the warning itself can't be farmed.
+    async fn prompt_allowed(&self, ctx: &Context, msg: &Message, request_id: Uuid) -> Result<bool> {
+        let user_id = msg.author.id.to_string();
+        match self.prompt_throttle.check(&user_id, msg.content.trim()) {
+            ThrottleDecision::Allow => Ok(true),
+            ThrottleDecision::Warn { cooldown, strikes } => {
+                warn!(
+                    "[{request_id}] 🔁 Repeated prompts from {user_id}, strike {strikes} ({}s cooldown)",
+                    cooldown.as_secs()
+                );
+                msg.channel_id
+                    .send_message(&ctx.http, |m| {
+                        m.set_embed(throttle_warning_embed(cooldown, strikes))
+                            .reference_message(msg)
+                    })
+                    .await?;
+                Ok(false)
+            }
+            ThrottleDecision::Cooldown { remaining } => {
+                debug!(
+                    "[{request_id}] 🔁 Dropping prompt from {user_id}, {}s of cooldown left",
+                    remaining.as_secs()
+                );
+                Ok(false)
+            }
+        }
+    }
+
     async fn is_bot_mentioned(&self, ctx: &Context, msg: &Message) -> Result<bool> {
         let current_user = ctx.http.get_current_user().await?;
         Ok(msg.mentions.iter().any(|user| user.id == current_user.id))
     }
 
     /// Check if the channel has an active debate (for auto-response feature)
-    async fn is_in_active_debate_thread(&self, channel_id: serenity::model::id::ChannelId) -> bool {
-        use crate::features::debate::get_active_debates;
+    ///
+    /// Persisted sessions are restored into memory at startup, so this never reads the database.
+    fn is_in_active_debate_thread(&self, channel_id: serenity::model::id::ChannelId) -> bool {
         get_active_debates().contains_key(&channel_id.0)
     }
 
     /// Check if the channel has an active council (for follow-up questions)
-    async fn is_in_active_council_thread(
-        &self,
-        channel_id: serenity::model::id::ChannelId,
-    ) -> bool {
+    fn is_in_active_council_thread(&self, channel_id: serenity::model::id::ChannelId) -> bool {
         get_active_councils().contains_key(&channel_id.0)
     }
 
+    /// Check if the channel is a /story thread
+    fn is_in_active_story_thread(&self, channel_id: serenity::model::id::ChannelId) -> bool {
+        get_active_stories().contains_key(&channel_id.0)
+    }
+
     async fn is_in_thread(&self, ctx: &Context, msg: &Message) -> Result<bool> {
         use serenity::model::channel::{Channel, ChannelType};
 
@@ -406,6 +1044,7 @@ impl CommandHandler {
         ctx: &Context,
         msg: &Message,
         request_id: Uuid,
+        privacy: PrivacySettings,
     ) -> Result<()> {
         let start_time = Instant::now();
         let user_id = msg.author.id.to_string();
@@ -419,6 +1058,10 @@ impl CommandHandler {
             user_message.chars().take(100).collect::<String>()
         );
 
+        if !self.prompt_allowed(ctx, msg, request_id).await? {
+            return Ok(());
+        }
+
         // Get or create DM session
         let session_id = self
             .interaction_tracker
@@ -436,8 +1079,11 @@ impl CommandHandler {
         );
 
         // Read any text attachments from the message
-        let text_attachments = self.get_text_attachments_context(msg, request_id).await;
-        let attachment_context = self.format_attachments_for_context(&text_attachments);
+        let mut sources = SourceList::new();
+        let text_attachments = self
+            .get_text_attachments_context(msg, request_id, &mut sources)
+            .await;
+        let attachment_context = Self::format_attachments_for_context(&text_attachments);
 
         // Enhance user message with attachment content if present
         let enhanced_message = if attachment_context.is_empty() {
@@ -447,7 +1093,10 @@ impl CommandHandler {
                 "[{request_id}] 📎 Including {} text attachment(s) in context",
                 text_attachments.len()
             );
-            format!("{attachment_context}{user_message}")
+            format!(
+                "{attachment_context}{}{user_message}",
+                sources.citation_instructions()
+            )
         };
 
         // Get user's persona
@@ -456,17 +1105,27 @@ impl CommandHandler {
         debug!("[{request_id}] 🎭 User persona: {user_persona}");
 
         // Store user message in conversation history (store original message, not enhanced)
-        debug!("[{request_id}] 💾 Storing user message to conversation history");
-        self.database
-            .store_message(
-                &user_id,
+        if privacy.dm_history {
+            debug!("[{request_id}] 💾 Storing user message to conversation history");
+            self.database
+                .store_message(
+                    &user_id,
+                    &channel_id,
+                    "user",
+                    user_message,
+                    Some(&user_persona),
+                )
+                .await?;
+            self.conflict_window.record(
                 &channel_id,
-                "user",
+                &user_id,
                 user_message,
-                Some(&user_persona),
-            )
-            .await?;
-        debug!("[{request_id}] ✅ User message stored successfully");
+                chrono::Utc::now().timestamp(),
+            );
+            debug!("[{request_id}] ✅ User message stored successfully");
+        } else {
+            debug!("[{request_id}] 🔒 DM history opted out, not storing message");
+        }
 
         // Retrieve conversation history (last 40 messages = ~20 exchanges)
         debug!("[{request_id}] 📚 Retrieving conversation history");
@@ -494,11 +1153,13 @@ impl CommandHandler {
         );
 
         // Log usage
-        debug!("[{request_id}] 📊 Logging usage to database");
-        self.database
-            .log_usage(&user_id, "dm_chat", Some(&user_persona))
-            .await?;
-        debug!("[{request_id}] ✅ Usage logged successfully");
+        if privacy.analytics {
+            debug!("[{request_id}] 📊 Logging usage to database");
+            self.database
+                .log_usage(&user_id, None, "dm_chat", Some(&user_persona))
+                .await?;
+            debug!("[{request_id}] ✅ Usage logged successfully");
+        }
 
         // Get AI response with conversation history (use enhanced message with attachments)
         info!("[{request_id}] 🚀 Calling OpenAI API for DM response");
@@ -511,6 +1172,12 @@ impl CommandHandler {
                 Some(&user_id),
                 None,
                 Some(&channel_id),
+                verbosity::budgeted(
+                    self.persona_manager
+                        .model_params(&user_persona, Some("chat")),
+                    "normal",
+                    0,
+                ),
             )
             .await;
 
@@ -549,11 +1216,15 @@ impl CommandHandler {
 
                             if let Some(p) = persona {
                                 // First chunk gets full embed with author, rest are continuation
-                                let embed = if i == 0 {
+                                let mut embed = if i == 0 {
                                     persona_embed(p, chunk)
                                 } else {
                                     continuation_embed(p, chunk)
                                 };
+                                // Source footnotes go under the last chunk
+                                if i == chunks.len() - 1 {
+                                    sources.apply_footer(&mut embed);
+                                }
                                 msg.channel_id
                                     .send_message(&ctx.http, |m| m.set_embed(embed))
                                     .await?;
@@ -576,29 +1247,42 @@ impl CommandHandler {
                         ai_response.len()
                     );
                     if let Some(p) = persona {
-                        let embed = persona_embed(p, &ai_response);
+                        let mut embed = persona_embed(p, &ai_response);
+                        sources.apply_footer(&mut embed);
                         msg.channel_id
                             .send_message(&ctx.http, |m| m.set_embed(embed))
                             .await?;
                     } else {
                         // Fallback to plain text if persona not found
-                        msg.channel_id.say(&ctx.http, &ai_response).await?;
+                        msg.channel_id
+                            .say(&ctx.http, sources.append_to(&ai_response))
+                            .await?;
                     }
                     info!("[{request_id}] ✅ DM embed response sent successfully");
                 }
 
                 // Store assistant response in conversation history
-                debug!("[{request_id}] 💾 Storing assistant response to conversation history");
-                self.database
-                    .store_message(
-                        &user_id,
+                if privacy.dm_history {
+                    debug!("[{request_id}] 💾 Storing assistant response to conversation history");
+                    self.database
+                        .store_message(
+                            &user_id,
+                            &channel_id,
+                            "assistant",
+                            &ai_response,
+                            Some(&user_persona),
+                        )
+                        .await?;
+                    self.conflict_window.record(
                         &channel_id,
-                        "assistant",
+                        &user_id,
                         &ai_response,
-                        Some(&user_persona),
-                    )
-                    .await?;
-                debug!("[{request_id}] ✅ Assistant response stored successfully");
+                        chrono::Utc::now().timestamp(),
+                    );
+                    debug!("[{request_id}] ✅ Assistant response stored successfully");
+                    self.title_dm_session(&session_id, &user_id, user_message, &ai_response)
+                        .await;
+                }
 
                 // Track message sent with response time
                 let response_time_ms = start_time.elapsed().as_millis() as u64;
@@ -619,11 +1303,7 @@ impl CommandHandler {
                 debug!("[{request_id}] ⌨️ Stopped typing indicator");
                 error!("[{request_id}] ❌ AI response error in DM: {e}");
 
-                let error_message = if e.to_string().contains("timed out") {
-                    "⏱️ Sorry, I'm taking too long to think. Please try again with a shorter message."
-                } else {
-                    "❌ Sorry, I encountered an error. Please try again later."
-                };
+                let error_message = Self::chat_error_message(&e);
 
                 debug!("[{request_id}] 📤 Sending error message to user");
                 msg.channel_id.say(&ctx.http, error_message).await?;
@@ -635,11 +1315,59 @@ impl CommandHandler {
         Ok(())
     }
 
+    /// Title a DM session in the background after its first stored reply
+    async fn title_dm_session(
+        &self,
+        session_id: &str,
+        user_id: &str,
+        user_message: &str,
+        reply: &str,
+    ) {
+        let enabled = self
+            .database
+            .is_feature_enabled(DM_HISTORY_FEATURE, None, None)
+            .await
+            .unwrap_or(false);
+        if !enabled
+            || self
+                .database
+                .has_dm_session_title(session_id)
+                .await
+                .unwrap_or(true)
+        {
+            return;
+        }
+
+        let database = self.database.clone();
+        let writer = SessionWriter::new(
+            self.chat_client.clone(),
+            self.openai_model.clone(),
+            self.usage_tracker.clone(),
+        );
+        let (session_id, user_id, user_message, reply) = (
+            session_id.to_string(),
+            user_id.to_string(),
+            user_message.to_string(),
+            reply.to_string(),
+        );
+        spawn_tracked(TaskKind::Discussions, async move {
+            match writer.title(&user_id, &user_message, &reply).await {
+                Ok(title) => {
+                    if let Err(e) = database.set_dm_session_title(&session_id, &title).await {
+                        warn!("⚠️ Failed to store title for DM session {session_id}: {e}");
+                    }
+                }
+                Err(e) => warn!("⚠️ Failed to title DM session {session_id}: {e}"),
+            }
+        });
+    }
+
     async fn handle_mention_message_with_id(
         &self,
         ctx: &Context,
         msg: &Message,
         request_id: Uuid,
+        privacy: PrivacySettings,
     ) -> Result<()> {
         let user_id = msg.author.id.to_string();
         let channel_id = msg.channel_id.to_string();
@@ -654,11 +1382,16 @@ impl CommandHandler {
             user_message.chars().take(100).collect::<String>()
         );
 
-        // Get user's persona with channel override -> user -> guild default cascade
+        if !self.prompt_allowed(ctx, msg, request_id).await? {
+            return Ok(());
+        }
+
+        // Get user's persona with channel override -> category -> user -> guild default cascade
         debug!("[{request_id}] 🎭 Fetching user persona from database");
         let user_persona = if let Some(gid) = guild_id_opt {
+            let category_id = channel_category_id(ctx, msg.channel_id).await;
             self.database
-                .get_persona_with_channel(&user_id, gid, &channel_id)
+                .get_persona_with_channel(&user_id, gid, &channel_id, category_id.as_deref())
                 .await?
         } else {
             self.database
@@ -683,21 +1416,44 @@ impl CommandHandler {
         debug!("[{request_id}] 🧵 Is thread: {is_thread} | Max context: {max_context}");
 
         // Read text attachments from current message
-        let mut all_attachments = self.get_text_attachments_context(msg, request_id).await;
+        let mut sources = SourceList::new();
+        let mut all_attachments = self
+            .get_text_attachments_context(msg, request_id, &mut sources)
+            .await;
+
+        // Threads a transcription job posted into answer from the stored transcript
+        let transcript_context = if is_thread {
+            self.database
+                .get_thread_transcript(&channel_id)
+                .await?
+                .map(|transcript| {
+                    info!(
+                        "[{request_id}] 📜 Answering from the transcript of job {}",
+                        transcript.job_id
+                    );
+                    transcript_qa::question_context(&transcript, user_message)
+                })
+        } else {
+            None
+        };
 
         // If in a thread and user seems to be asking about files, fetch thread attachments
-        if is_thread && self.seems_like_file_question(user_message) && all_attachments.is_empty() {
+        if is_thread
+            && transcript_context.is_none()
+            && self.seems_like_file_question(user_message)
+            && all_attachments.is_empty()
+        {
             info!(
                 "[{request_id}] 📎 User asking about files in thread, fetching thread attachments"
             );
             let thread_attachments = self
-                .fetch_thread_attachments(ctx, msg.channel_id, 20, request_id)
+                .fetch_thread_attachments(ctx, msg.channel_id, 20, request_id, &mut sources)
                 .await?;
             all_attachments.extend(thread_attachments);
         }
 
         // Format attachment context
-        let attachment_context = self.format_attachments_for_context(&all_attachments);
+        let attachment_context = Self::format_attachments_for_context(&all_attachments);
 
         // Enhance user message with attachment content if present
         let enhanced_message = if attachment_context.is_empty() {
@@ -707,15 +1463,37 @@ impl CommandHandler {
                 "[{request_id}] 📎 Including {} text attachment(s) in context",
                 all_attachments.len()
             );
-            format!("{attachment_context}{user_message}")
+            format!(
+                "{attachment_context}{}{user_message}",
+                sources.citation_instructions()
+            )
+        };
+
+        let enhanced_message = match &transcript_context {
+            Some(context) => format!("{context}{enhanced_message}"),
+            None => enhanced_message,
+        };
+
+        // Stickers aren't part of the text, so tell the model they were sent
+        let sticker_names: Vec<String> = msg.sticker_items.iter().map(|s| s.name.clone()).collect();
+        let enhanced_message = match describe_stickers(&sticker_names) {
+            Some(stickers) => format!("{enhanced_message}\n{stickers}"),
+            None => enhanced_message,
         };
 
         // Retrieve conversation history based on context type
         let conversation_history = if is_thread {
-            // Thread context: Fetch messages from Discord
+            // Thread context: Fetch messages from Discord, after any context forked into it
             info!("[{request_id}] 🧵 Fetching thread context from Discord");
-            self.fetch_thread_messages(ctx, msg, max_context as u8, request_id)
-                .await?
+            let mut history = self
+                .database
+                .get_fork_context(&channel_id, max_context)
+                .await?;
+            history.extend(
+                self.fetch_thread_messages(ctx, msg, max_context as u8, request_id)
+                    .await?,
+            );
+            history
         } else {
             // Channel context: Use database history
             info!("[{request_id}] 📚 Fetching channel context from database");
@@ -723,7 +1501,8 @@ impl CommandHandler {
             // Store user message in conversation history for channels (store original, not enhanced)
             debug!("[{request_id}] 💾 Storing user message to conversation history");
             self.database
-                .store_message(
+                .store_guild_message(
+                    guild_id_opt,
                     &user_id,
                     &channel_id,
                     "user",
@@ -762,6 +1541,34 @@ impl CommandHandler {
         let system_prompt =
             self.persona_manager
                 .get_system_prompt_with_verbosity(&user_persona, None, &verbosity);
+        let system_prompt = match guild_id_opt {
+            Some(gid) => {
+                let theme = self
+                    .database
+                    .get_guild_setting(gid, ACTIVE_THEME_SETTING)
+                    .await?;
+                apply_theme(system_prompt, theme.as_deref())
+            }
+            None => system_prompt,
+        };
+
+        // Guild custom emojis the persona may use (custom_emojis guild setting)
+        let guild_emojis = match (msg.guild_id, guild_id_opt) {
+            (Some(guild_id), Some(gid))
+                if self
+                    .database
+                    .get_guild_setting(gid, "custom_emojis")
+                    .await?
+                    .is_some_and(|v| v == "enabled") =>
+            {
+                Some(fetch_guild_emojis(ctx, guild_id).await)
+            }
+            _ => None,
+        };
+        let system_prompt = match guild_emojis.as_deref().and_then(emoji_prompt) {
+            Some(section) => format!("{system_prompt}{section}"),
+            None => system_prompt,
+        };
         debug!(
             "[{}] ✅ System prompt generated | Length: {} chars",
             request_id,
@@ -769,11 +1576,13 @@ impl CommandHandler {
         );
 
         // Log usage
-        debug!("[{request_id}] 📊 Logging usage to database");
-        self.database
-            .log_usage(&user_id, "mention_chat", Some(&user_persona))
-            .await?;
-        debug!("[{request_id}] ✅ Usage logged successfully");
+        if privacy.analytics {
+            debug!("[{request_id}] 📊 Logging usage to database");
+            self.database
+                .log_usage(&user_id, guild_id_opt, "mention_chat", Some(&user_persona))
+                .await?;
+            debug!("[{request_id}] ✅ Usage logged successfully");
+        }
 
         // Get AI response with conversation history (use enhanced message with attachments)
         info!("[{request_id}] 🚀 Calling OpenAI API for mention response");
@@ -786,6 +1595,12 @@ impl CommandHandler {
                 Some(&user_id),
                 guild_id_opt,
                 Some(&channel_id),
+                verbosity::budgeted(
+                    self.persona_manager
+                        .model_params(&user_persona, Some("chat")),
+                    &verbosity,
+                    0,
+                ),
             )
             .await
         {
@@ -795,6 +1610,10 @@ impl CommandHandler {
                     request_id,
                     ai_response.len()
                 );
+                let ai_response = match &guild_emojis {
+                    Some(emojis) => sanitize_emojis(&ai_response, emojis),
+                    None => ai_response,
+                };
 
                 // Stop typing
                 typing.stop();
@@ -835,11 +1654,15 @@ impl CommandHandler {
                                 );
 
                                 // First chunk gets full embed with author, rest are continuation
-                                let embed = if i == 0 {
+                                let mut embed = if i == 0 {
                                     persona_embed(p, chunk)
                                 } else {
                                     continuation_embed(p, chunk)
                                 };
+                                // Source footnotes go under the last chunk
+                                if i == chunks.len() - 1 {
+                                    sources.apply_footer(&mut embed);
+                                }
                                 msg.channel_id
                                     .send_message(&ctx.http, |m| m.set_embed(embed))
                                     .await?;
@@ -859,7 +1682,8 @@ impl CommandHandler {
                             request_id,
                             ai_response.len()
                         );
-                        let embed = persona_embed(p, &ai_response);
+                        let mut embed = persona_embed(p, &ai_response);
+                        sources.apply_footer(&mut embed);
                         msg.channel_id
                             .send_message(&ctx.http, |m| m.set_embed(embed))
                             .await?;
@@ -867,7 +1691,8 @@ impl CommandHandler {
                     }
                 } else {
                     // Plain text fallback (legacy behavior or embeds disabled)
-                    let chunks = chunk_for_message(&ai_response);
+                    let plain_response = sources.append_to(&ai_response);
+                    let chunks = chunk_for_message(&plain_response);
                     if chunks.len() > 1 {
                         debug!("[{request_id}] 📄 Response too long, splitting into {} chunks", chunks.len());
 
@@ -903,9 +1728,9 @@ impl CommandHandler {
                         debug!(
                             "[{}] 📤 Sending mention response as reply ({} chars)",
                             request_id,
-                            ai_response.len()
+                            plain_response.len()
                         );
-                        msg.reply(&ctx.http, &ai_response).await?;
+                        msg.reply(&ctx.http, &plain_response).await?;
                         info!("[{request_id}] ✅ Mention response sent successfully");
                     }
                 }
@@ -914,7 +1739,8 @@ impl CommandHandler {
                 if !is_thread {
                     debug!("[{request_id}] 💾 Storing assistant response to conversation history");
                     self.database
-                        .store_message(
+                        .store_guild_message(
+                            guild_id_opt,
                             &user_id,
                             &channel_id,
                             "assistant",
@@ -932,11 +1758,7 @@ impl CommandHandler {
                 debug!("[{request_id}] ⌨️ Stopped typing indicator");
                 error!("[{request_id}] ❌ AI response error in mention: {e}");
 
-                let error_message = if e.to_string().contains("timed out") {
-                    "⏱️ Sorry, I'm taking too long to think. Please try again with a shorter message."
-                } else {
-                    "❌ Sorry, I encountered an error. Please try again later."
-                };
+                let error_message = Self::chat_error_message(&e);
 
                 debug!("[{request_id}] 📤 Sending error message to user as reply");
                 msg.reply(&ctx.http, error_message).await?;
@@ -961,44 +1783,54 @@ impl CommandHandler {
             .map(|id| id.to_string())
             .unwrap_or_else(|| "DM".to_string());
 
+        let asked_anonymously = command.data.name == anon_questions::ASK_COMMAND;
+        let logged_user = loggable_user(&command.data.name, &user_id);
+
         info!(
             "[{}] 📥 Slash command received | Command: {} | User: {} | Channel: {} | Guild: {}",
-            request_id, command.data.name, user_id, channel_id, guild_id
+            request_id, command.data.name, logged_user, channel_id, guild_id
         );
 
-        debug!("[{request_id}] 🔍 Checking rate limit for user: {user_id}");
-        if !self.rate_limiter.wait_for_rate_limit(&user_id).await {
-            warn!("[{request_id}] 🚫 Rate limit exceeded for user: {user_id} in slash command");
-            debug!("[{request_id}] 📤 Sending rate limit response to Discord");
-            command
-                .create_interaction_response(&ctx.http, |response| {
-                    response
-                        .kind(serenity::model::application::interaction::InteractionResponseType::ChannelMessageWithSource)
-                        .interaction_response_data(|message| {
-                            message.content("You're sending commands too quickly! Please slow down.")
-                        })
-                })
-                .await?;
-            info!("[{request_id}] ✅ Rate limit response sent successfully");
-            return Ok(());
-        }
-        debug!("[{request_id}] ✅ Rate limit check passed");
+        chaos().discord_fault(&format!("interactions/{}/callback", command.id))?;
 
         info!(
             "[{}] 🎯 Processing slash command: {} from user: {}",
-            request_id, command.data.name, user_id
+            request_id, command.data.name, logged_user
         );
 
-        let cmd_name = command.data.name.as_str();
+        // Every command (including /plugins and its virtual plugins) goes through the
+        // registry, which applies middleware such as rate limiting and records metrics
+        let dispatch = self
+            .command_registry
+            .dispatch(Arc::clone(&self.command_context), ctx, command)
+            .await?;
 
-        // Look up handler in the registry (includes PluginsHandler for /plugins)
-        if let Some(handler) = self.command_registry.get(cmd_name) {
-            debug!("[{request_id}] Dispatching to registered handler: {cmd_name}");
-            handler
-                .handle(Arc::clone(&self.command_context), ctx, command)
-                .await?;
-        } else {
-            warn!("[{request_id}] Unknown slash command: {cmd_name}");
+        if let Dispatch::Stopped(middleware) = dispatch {
+            info!(
+                "[{request_id}] ⛔ Slash command {} stopped by {middleware} middleware",
+                command.data.name
+            );
+        } else if dispatch == Dispatch::Handled && !asked_anonymously {
+            // A level-up announced next to an anonymous question would name its author
+            if let Some(gid) = command.guild_id.map(|id| id.to_string()) {
+                if let Err(e) = leveling::grant(
+                    &self.database,
+                    &ctx.http,
+                    &gid,
+                    &user_id,
+                    &channel_id,
+                    XpSource::Interaction,
+                )
+                .await
+                {
+                    warn!("[{request_id}] ⚠️ Leveling error: {e}");
+                }
+            }
+        } else if dispatch == Dispatch::Unknown {
+            warn!(
+                "[{request_id}] Unknown slash command: {}",
+                command.data.name
+            );
             command
                 .create_interaction_response(&ctx.http, |response| {
                     response
@@ -1042,11 +1874,15 @@ impl CommandHandler {
             None,
             None,
             None,
+            ModelParams::default(),
         )
         .await
     }
 
     /// Get AI response with full context for usage tracking
+    ///
+    /// `params` are the persona's temperature, top_p and max_tokens overrides
+    /// for the command (see `PersonaManager::model_params`).
     #[allow(clippy::too_many_arguments)]
     pub async fn get_ai_response_with_context(
         &self,
@@ -1057,14 +1893,16 @@ impl CommandHandler {
         user_id: Option<&str>,
         guild_id: Option<&str>,
         channel_id: Option<&str>,
+        params: ModelParams,
     ) -> Result<String> {
         let start_time = Instant::now();
 
         info!(
-            "[{}] 🤖 Starting OpenAI API request | Model: {} | History messages: {}",
+            "[{}] 🤖 Starting OpenAI API request | Model: {} | History messages: {} | Params: {:?}",
             request_id,
             self.openai_model,
-            conversation_history.len()
+            conversation_history.len(),
+            params
         );
         debug!(
             "[{}] 📝 System prompt length: {} chars | User message length: {} chars",
@@ -1079,9 +1917,17 @@ impl CommandHandler {
         );
 
         debug!("[{request_id}] 🔨 Building OpenAI message objects");
+        let system_prompt =
+            apply_guardrails(&self.database, guild_id, channel_id, system_prompt).await?;
+        let system_prompt =
+            apply_memories(&self.database, user_id, guild_id, channel_id, system_prompt).await?;
+        let system_prompt =
+            apply_scene(&self.database, guild_id, channel_id, system_prompt).await?;
+        let system_prompt =
+            apply_glossary(&self.database, guild_id, user_message, system_prompt).await?;
         let mut messages = vec![ChatCompletionMessage {
             role: ChatCompletionMessageRole::System,
-            content: Some(system_prompt.to_string()),
+            content: Some(system_prompt),
             name: None,
             function_call: None,
             tool_call_id: None,
@@ -1121,9 +1967,15 @@ impl CommandHandler {
             messages.len()
         );
 
+        circuit_breakers().check(Dependency::OpenAiChat)?;
+
         // Add timeout to the OpenAI API call (45 seconds)
         debug!("[{request_id}] 🚀 Initiating OpenAI API call with 45-second timeout");
-        let chat_completion_future = ChatCompletion::builder(&self.openai_model, messages).create();
+        let chat_completion_future = self.chat_client.create_chat_completion_with_params(
+            &self.openai_model,
+            messages,
+            params,
+        );
 
         info!("[{request_id}] ⏰ Waiting for OpenAI API response (timeout: 45s)");
         let chat_completion = timeout(TokioDuration::from_secs(45), chat_completion_future)
@@ -1132,12 +1984,16 @@ impl CommandHandler {
                 let elapsed = start_time.elapsed();
                 error!("[{request_id}] ⏱️ OpenAI API request timed out after {elapsed:?}");
                 anyhow::anyhow!("OpenAI API request timed out after 45 seconds")
-            })?
-            .map_err(|e| {
-                let elapsed = start_time.elapsed();
-                error!("[{request_id}] ❌ OpenAI API error after {elapsed:?}: {e}");
-                anyhow::anyhow!("OpenAI API error: {}", e)
-            })?;
+            })
+            .and_then(|result| {
+                result.map_err(|e| {
+                    let elapsed = start_time.elapsed();
+                    error!("[{request_id}] ❌ OpenAI API error after {elapsed:?}: {e}");
+                    anyhow::anyhow!("OpenAI API error: {}", e)
+                })
+            });
+        circuit_breakers().record(Dependency::OpenAiChat, &chat_completion);
+        let chat_completion = chat_completion?;
 
         let elapsed = start_time.elapsed();
         info!("[{request_id}] ✅ OpenAI API response received after {elapsed:?}");
@@ -1188,12 +2044,129 @@ impl CommandHandler {
         Ok(trimmed_response)
     }
 
+    /// One persona chat turn for a non-Discord front end (Matrix, Telegram)
+    ///
+    /// Works like a DM: the user's persona and conversation history are used,
+    /// and both sides are stored unless the user opted out of DM history.
+    /// `user_id` and `channel_id` are the front end's own identifiers. Returns
+    /// the persona id and reply, or `None` when the user is rate limited.
+    pub async fn bridge_chat(
+        &self,
+        user_id: &str,
+        channel_id: &str,
+        message: &str,
+        request_id: Uuid,
+    ) -> Result<Option<(String, String)>> {
+        if !self.rate_limiter.check_rate_limit(user_id).await {
+            info!("[{request_id}] ⏳ Bridge message rate limited | User: {user_id}");
+            return Ok(None);
+        }
+
+        let privacy = self.database.get_privacy_settings(user_id, None).await?;
+        let user_persona = self.database.get_user_persona(user_id).await?;
+        if privacy.dm_history {
+            self.database
+                .store_message(user_id, channel_id, "user", message, Some(&user_persona))
+                .await?;
+        }
+        let conversation_history = self
+            .database
+            .get_conversation_history(user_id, channel_id, 40)
+            .await?;
+        if privacy.analytics {
+            self.database
+                .log_usage(user_id, None, "bridge_chat", Some(&user_persona))
+                .await?;
+        }
+
+        let system_prompt = self.persona_manager.get_system_prompt(&user_persona, None);
+        let reply = self
+            .get_ai_response_with_context(
+                &system_prompt,
+                message,
+                conversation_history,
+                request_id,
+                Some(user_id),
+                None,
+                Some(channel_id),
+                self.persona_manager
+                    .model_params(&user_persona, Some("chat")),
+            )
+            .await?;
+
+        if privacy.dm_history {
+            self.database
+                .store_message(
+                    user_id,
+                    channel_id,
+                    "assistant",
+                    &reply,
+                    Some(&user_persona),
+                )
+                .await?;
+        }
+        Ok(Some((user_persona, reply)))
+    }
+
+    /// One-shot persona answer for a non-Discord front end, like `/ask`
+    ///
+    /// No conversation history is read or stored. Returns `None` when the
+    /// user is rate limited.
+    pub async fn bridge_ask(
+        &self,
+        user_id: &str,
+        channel_id: &str,
+        persona_id: &str,
+        prompt: &str,
+        request_id: Uuid,
+    ) -> Result<Option<String>> {
+        if !self.rate_limiter.check_rate_limit(user_id).await {
+            info!("[{request_id}] ⏳ Bridge ask rate limited | User: {user_id}");
+            return Ok(None);
+        }
+
+        let privacy = self.database.get_privacy_settings(user_id, None).await?;
+        if privacy.analytics {
+            self.database
+                .log_usage(user_id, None, "bridge_ask", Some(persona_id))
+                .await?;
+        }
+
+        let system_prompt = self.persona_manager.get_system_prompt(persona_id, None);
+        let reply = self
+            .get_ai_response_with_context(
+                &system_prompt,
+                prompt,
+                Vec::new(),
+                request_id,
+                Some(user_id),
+                None,
+                Some(channel_id),
+                self.persona_manager.model_params(persona_id, Some("ask")),
+            )
+            .await?;
+        Ok(Some(reply))
+    }
+
+    /// What to tell the user when a chat turn fails
+    pub fn chat_error_message(e: &anyhow::Error) -> String {
+        if let Some(open) = e.downcast_ref::<CircuitOpenError>() {
+            format!("🔌 {open}")
+        } else if e.to_string().contains("timed out") {
+            "⏱️ Sorry, I'm taking too long to think. Please try again with a shorter message."
+                .to_string()
+        } else {
+            "❌ Sorry, I encountered an error. Please try again later.".to_string()
+        }
+    }
+
     /// Handle audio attachments, returns true if any audio was processed
     async fn handle_audio_attachments(
         &self,
         ctx: &Context,
         msg: &Message,
         guild_id_opt: Option<&str>,
+        privacy: PrivacySettings,
     ) -> Result<bool> {
         let user_id = msg.author.id.to_string();
         let mut audio_processed = false;
@@ -1213,9 +2186,17 @@ impl CommandHandler {
                 info!("Processing audio attachment: {}", attachment.filename);
                 audio_processed = true;
 
-                msg.channel_id
-                    .say(&ctx.http, "🎵 Transcribing your audio... please wait!")
-                    .await?;
+                if let Err(open) = circuit_breakers().peek(Dependency::Whisper) {
+                    msg.channel_id.say(&ctx.http, format!("🔌 {open}")).await?;
+                    continue;
+                }
+
+                let wait_message = if AudioTranscriber::is_video_file(&attachment.filename) {
+                    "🎬 Extracting and transcribing the audio from your video... please wait!"
+                } else {
+                    "🎵 Transcribing your audio... please wait!"
+                };
+                msg.channel_id.say(&ctx.http, wait_message).await?;
 
                 match self
                     .audio_transcriber
@@ -1248,9 +2229,36 @@ impl CommandHandler {
                                 }
                             }
 
+                            // Voice messages starting with a wake phrase are commands
+                            let bot_name = ctx.cache.current_user().name.clone();
+                            let voice_ctx = VoiceCommandContext {
+                                user_id: &user_id,
+                                channel_id: &msg.channel_id.to_string(),
+                                guild_id: guild_id_opt,
+                            };
+                            if let Some(reply) = voice_commands::handle_transcript(
+                                &self.database,
+                                &bot_name,
+                                transcription,
+                                &voice_ctx,
+                            )
+                            .await?
+                            {
+                                msg.channel_id.say(&ctx.http, reply).await?;
+                                if privacy.analytics {
+                                    self.database
+                                        .log_usage(&user_id, guild_id_opt, "voice_command", None)
+                                        .await?;
+                                }
+                            }
+
                             // Only generate AI commentary if output mode is "with_commentary"
                             if output_mode == "with_commentary" && !msg.content.trim().is_empty() {
-                                let user_persona = self.database.get_user_persona(&user_id).await?;
+                                let guild_id = msg.guild_id.map(|id| id.to_string());
+                                let user_persona = self
+                                    .database
+                                    .get_user_persona_with_guild(&user_id, guild_id.as_deref())
+                                    .await?;
                                 let system_prompt =
                                     self.persona_manager.get_system_prompt(&user_persona, None);
                                 let combined_message = format!(
@@ -1272,15 +2280,38 @@ impl CommandHandler {
                             }
                         }
 
-                        self.database
-                            .log_usage(&user_id, "audio_transcription", None)
-                            .await?;
+                        if privacy.analytics {
+                            self.database
+                                .log_usage(&user_id, guild_id_opt, "audio_transcription", None)
+                                .await?;
+                        }
+                        if let Some(guild_id) = guild_id_opt {
+                            // The Whisper usage above is written in the background
+                            let pending = Progress {
+                                transcription_seconds: result.duration_seconds,
+                                ..Progress::default()
+                            };
+                            if let Err(e) = achievements::check(
+                                &self.database,
+                                &ctx.http,
+                                guild_id,
+                                &user_id,
+                                pending,
+                            )
+                            .await
+                            {
+                                warn!("Failed to check achievements for {user_id}: {e}");
+                            }
+                        }
                     }
                     Err(e) => {
                         error!("Transcription error: {e}");
-                        msg.channel_id
-                            .say(&ctx.http, "Sorry, I couldn't transcribe that audio file. Please make sure it's a valid audio format.")
-                            .await?;
+                        let reply = match e.downcast_ref::<CircuitOpenError>() {
+                            Some(open) => format!("🔌 {open}"),
+                            None if e.is::<NoAudioTrackError>() => format!("🔇 {e}"),
+                            None => "Sorry, I couldn't transcribe that audio file. Please make sure it's a valid audio or video format.".to_string(),
+                        };
+                        msg.channel_id.say(&ctx.http, reply).await?;
                     }
                 }
             }
@@ -1294,7 +2325,8 @@ impl CommandHandler {
             // Whisper native formats
             ".mp3", ".mp4", ".m4a", ".wav", ".webm", ".mpeg", ".mpga",
             // Converted via ffmpeg
-            ".flac", ".ogg", ".aac", ".wma", ".mov", ".avi", ".mkv", ".opus", ".m4v",
+            ".flac", ".ogg", ".aac", ".wma", ".mov", ".avi", ".mkv", ".opus", ".m4v", ".wmv",
+            ".flv", ".3gp",
         ];
 
         let filename_lower = filename.to_lowercase();
@@ -1304,7 +2336,7 @@ impl CommandHandler {
     }
 
     /// Check if an attachment is a text-based file that can be read
-    fn is_text_attachment(&self, filename: &str) -> bool {
+    pub(crate) fn is_text_attachment(filename: &str) -> bool {
         let text_extensions = [
             // Plain text
             ".txt",
@@ -1426,6 +2458,7 @@ impl CommandHandler {
         channel_id: serenity::model::id::ChannelId,
         limit: u8,
         request_id: Uuid,
+        sources: &mut SourceList,
     ) -> Result<Vec<(String, String)>> {
         use serenity::builder::GetMessages;
 
@@ -1442,7 +2475,7 @@ impl CommandHandler {
 
         for message in messages.iter() {
             for attachment in &message.attachments {
-                if self.is_text_attachment(&attachment.filename) {
+                if Self::is_text_attachment(&attachment.filename) {
                     debug!(
                         "[{request_id}] 📄 Found text attachment: {}",
                         attachment.filename
@@ -1450,6 +2483,7 @@ impl CommandHandler {
                     if let Ok(Some((filename, content))) =
                         self.read_text_attachment(attachment).await
                     {
+                        sources.add(Source::attachment(&filename, &attachment.url));
                         attachments.push((filename, content));
                     }
                 }
@@ -1494,17 +2528,20 @@ impl CommandHandler {
         &self,
         msg: &Message,
         request_id: Uuid,
+        sources: &mut SourceList,
     ) -> Vec<(String, String)> {
         let mut attachments = Vec::new();
+        let mut images_read = 0;
 
         for attachment in &msg.attachments {
-            if self.is_text_attachment(&attachment.filename) {
+            if Self::is_text_attachment(&attachment.filename) {
                 debug!(
                     "[{request_id}] 📄 Reading text attachment from message: {}",
                     attachment.filename
                 );
                 match self.read_text_attachment(attachment).await {
                     Ok(Some((filename, content))) => {
+                        sources.add(Source::attachment(&filename, &attachment.url));
                         attachments.push((filename, content));
                     }
                     Ok(None) => {
@@ -1520,14 +2557,62 @@ impl CommandHandler {
                         );
                     }
                 }
+            } else if ocr::is_image_file(&attachment.filename) {
+                if images_read >= ocr::MAX_IMAGES_PER_MESSAGE || !self.ocr_enabled(msg).await {
+                    continue;
+                }
+                images_read += 1;
+                debug!(
+                    "[{request_id}] 🖼️ Reading text from image attachment: {}",
+                    attachment.filename
+                );
+                match ocr::read_image_attachment(
+                    &attachment.url,
+                    &attachment.filename,
+                    attachment.size,
+                )
+                .await
+                {
+                    Ok(Some(text)) => {
+                        sources.add(Source::attachment(&attachment.filename, &attachment.url));
+                        attachments.push((
+                            format!("{} (text read from image)", attachment.filename),
+                            text,
+                        ));
+                    }
+                    Ok(None) => {
+                        debug!(
+                            "[{request_id}] 🖼️ No readable text in image: {}",
+                            attachment.filename
+                        );
+                    }
+                    Err(e) => {
+                        warn!(
+                            "[{request_id}] ❌ OCR failed for {}: {e}",
+                            attachment.filename
+                        );
+                    }
+                }
             }
         }
 
         attachments
     }
 
+    /// Whether images in this message should be read with OCR
+    async fn ocr_enabled(&self, msg: &Message) -> bool {
+        if !ocr::is_tesseract_available() {
+            return false;
+        }
+        let guild_id = msg.guild_id.map(|id| id.to_string());
+        self.database
+            .is_feature_enabled(ocr::OCR_FEATURE, None, guild_id.as_deref())
+            .await
+            .unwrap_or(true)
+    }
+
     /// Format text attachments into a context string to prepend to user message
-    fn format_attachments_for_context(&self, attachments: &[(String, String)]) -> String {
+    pub fn format_attachments_for_context(attachments: &[(String, String)]) -> String {
         if attachments.is_empty() {
             return String::new();
         }
@@ -1541,25 +2626,34 @@ impl CommandHandler {
         context
     }
 
-    async fn check_and_mediate_conflicts(
+    /// Analyze recent channel history and post a mediation message if a conflict is found
+    ///
+    /// Takes the HTTP client rather than a full Context so the dry-run harness can drive it.
+    pub(crate) async fn check_and_mediate_conflicts(
         &self,
-        ctx: &Context,
-        msg: &Message,
-        channel_id: &str,
+        http: &Http,
+        channel: ChannelId,
+        trigger_message_id: MessageId,
         guild_id: Option<&str>,
     ) -> Result<()> {
-        // Get guild-specific conflict sensitivity
+        let channel_id = channel.to_string();
+        let channel_id = channel_id.as_str();
+
+        // Get guild-specific conflict sensitivity, then the guardrail profile's
         let sensitivity_threshold = if let Some(gid) = guild_id {
             let sensitivity = self
                 .database
                 .get_guild_setting(gid, "conflict_sensitivity")
-                .await?
-                .unwrap_or_else(|| "medium".to_string());
-            match sensitivity.as_str() {
-                "low" => 0.7,
-                "high" => 0.35,
-                "ultra" => 0.3,
-                _ => self.conflict_sensitivity_threshold, // Use env var default
+                .await?;
+            match sensitivity.as_deref() {
+                Some("low") => 0.7,
+                Some("high") => 0.35,
+                Some("ultra") => 0.3,
+                Some(_) => self.conflict_sensitivity_threshold, // Use env var default
+                None => GuardrailProfile::for_channel(&self.database, guild_id, Some(channel_id))
+                    .await?
+                    .conflict_threshold()
+                    .unwrap_or(self.conflict_sensitivity_threshold),
             }
         } else {
             self.conflict_sensitivity_threshold
@@ -1582,19 +2676,40 @@ impl CommandHandler {
             .get_last_mediation_timestamp(channel_id)
             .await?;
 
-        // Get recent messages, optionally filtering to only new messages since last mediation
-        let recent_messages = if let Some(last_ts) = last_mediation_ts {
-            info!("🔍 Getting messages since last mediation at timestamp {last_ts}");
-            self.database
-                .get_recent_channel_messages_since(channel_id, last_ts, 10)
-                .await?
-        } else {
-            info!("🔍 No previous mediation found, getting all recent messages");
-            self.database
-                .get_recent_channel_messages(channel_id, 10)
-                .await?
+        // Recent messages come from the in-memory window once the channel is seeded;
+        // the first check in a channel flushes buffered writes and seeds it from history
+        let mut recent_messages = match self.conflict_window.since(channel_id, last_mediation_ts) {
+            Some(messages) => messages,
+            None => {
+                if let Some(writer) = &self.message_writer {
+                    writer.flush().await;
+                }
+                let history = self
+                    .database
+                    .get_recent_channel_messages(channel_id, WINDOW_SIZE)
+                    .await?;
+                self.conflict_window.seed(channel_id, &history);
+                self.conflict_window
+                    .since(channel_id, last_mediation_ts)
+                    .unwrap_or_default()
+            }
         };
 
+        // Members who opted out of conflict analysis are never read
+        let mut opted_out = std::collections::HashSet::new();
+        for (user_id, _, _) in &recent_messages {
+            if !opted_out.contains(user_id)
+                && !self
+                    .database
+                    .get_privacy_settings(user_id, guild_id)
+                    .await?
+                    .conflict_analysis
+            {
+                opted_out.insert(user_id.clone());
+            }
+        }
+        recent_messages.retain(|(user_id, _, _)| !opted_out.contains(user_id));
+
         info!(
             "🔍 Conflict check: Found {} recent messages in channel {} (after last mediation)",
             recent_messages.len(),
@@ -1675,10 +2790,30 @@ impl CommandHandler {
                     &participants_json,
                     &conflict_type,
                     confidence,
-                    &msg.id.to_string(),
+                    &trigger_message_id.to_string(),
                 )
                 .await?;
 
+            if let Some(gid) = guild_id {
+                webhooks::notify(
+                    &self.database,
+                    gid,
+                    WebhookEvent::ConflictDetected,
+                    vec![
+                        ("channel_id", channel_id.to_string()),
+                        ("conflict_type", conflict_type.clone()),
+                        ("confidence", format!("{:.0}%", confidence * 100.0)),
+                        ("participants", participants.len().to_string()),
+                        (
+                            "link",
+                            format!(
+                                "https://discord.com/channels/{gid}/{channel_id}/{trigger_message_id}"
+                            ),
+                        ),
+                    ],
+                );
+            }
+
             // Generate context-aware mediation response using OpenAI
             info!("🤖 Generating context-aware mediation response with OpenAI...");
             let mediation_text = match self
@@ -1703,7 +2838,7 @@ impl CommandHandler {
             };
 
             // Send mediation message as Obi-Wan with proper error handling
-            match msg.channel_id.say(&ctx.http, &mediation_text).await {
+            match channel.say(http, &mediation_text).await {
                 Ok(mediation_msg) => {
                     info!("☮️ Mediation sent successfully in channel {channel_id} | Message: {mediation_text}");
 
@@ -1782,23 +2917,24 @@ impl CommandHandler {
         );
 
         // Call OpenAI (API key set at startup)
-        let chat_completion = ChatCompletion::builder(
-            &self.openai_model,
-            vec![ChatCompletionMessage {
-                role: ChatCompletionMessageRole::System,
-                content: Some(mediation_prompt),
-                name: None,
-                function_call: None,
-                tool_call_id: None,
-                tool_calls: None,
-            }],
-        )
-        .create()
-        .await
-        .map_err(|e| {
-            error!("Conflict mediation OpenAI API error: {e}");
-            anyhow::anyhow!("OpenAI API error: {e}")
-        })?;
+        let chat_completion = self
+            .chat_client
+            .create_chat_completion(
+                &self.openai_model,
+                vec![ChatCompletionMessage {
+                    role: ChatCompletionMessageRole::System,
+                    content: Some(mediation_prompt),
+                    name: None,
+                    function_call: None,
+                    tool_call_id: None,
+                    tool_calls: None,
+                }],
+            )
+            .await
+            .map_err(|e| {
+                error!("Conflict mediation OpenAI API error: {e}");
+                anyhow::anyhow!("OpenAI API error: {e}")
+            })?;
 
         // Log usage for mediation (system-initiated, no specific user)
         if let Some(usage) = &chat_completion.usage {
@@ -1827,6 +2963,102 @@ impl CommandHandler {
         Ok(response)
     }
 
+    /// Add a member's message to the story in this thread and let the persona
+    /// write the next paragraph
+    ///
+    /// Messages posted while the persona is still writing are treated as chat
+    /// and left out of the story.
+    async fn handle_story_contribution(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        request_id: Uuid,
+    ) -> Result<()> {
+        let thread_id = msg.channel_id;
+        let user_id = msg.author.id.to_string();
+        let text = msg.content.trim();
+
+        if let Some(gid) = msg.guild_id {
+            if !self
+                .database
+                .is_feature_enabled(STORY_FEATURE, None, Some(&gid.to_string()))
+                .await?
+            {
+                return Ok(());
+            }
+        }
+        let author_name = msg
+            .author_nick(&ctx.http)
+            .await
+            .unwrap_or_else(|| msg.author.name.clone());
+
+        let rejection = {
+            let Some(mut story) = get_active_stories().get_mut(&thread_id.0) else {
+                return Ok(());
+            };
+            if story.turn == StoryTurn::Persona {
+                debug!(
+                    "[{request_id}] 📖 Ignoring message in {thread_id} while the persona writes"
+                );
+                return Ok(());
+            }
+            if story.is_full() {
+                Some("📖 This story is full. Run `/story conclude` for the ending.".to_string())
+            } else if text.chars().count() > MAX_CONTRIBUTION_CHARS {
+                Some(format!(
+                    "📖 Paragraphs are limited to {MAX_CONTRIBUTION_CHARS} characters. Try splitting it up!"
+                ))
+            } else {
+                story.add_member_part(&user_id, &author_name, text.to_string());
+                None
+            }
+        };
+        if let Some(rejection) = rejection {
+            msg.reply(&ctx.http, rejection).await?;
+            return Ok(());
+        }
+
+        info!("[{request_id}] 📖 Story paragraph from {user_id} in {thread_id}");
+        save_story_state_logged(&self.database, thread_id.0).await;
+
+        let guild_id = msg.guild_id.map(|g| g.to_string());
+        let writer = StoryWriter::new(
+            self.chat_client.clone(),
+            self.openai_model.clone(),
+            self.usage_tracker.clone(),
+            self.persona_manager.clone(),
+        )
+        .with_guardrails(
+            GuardrailProfile::for_channel(
+                &self.database,
+                guild_id.as_deref(),
+                Some(&msg.channel_id.to_string()),
+            )
+            .await?,
+        )
+        .with_scene(
+            channel_scene(
+                &self.database,
+                guild_id.as_deref(),
+                Some(&msg.channel_id.to_string()),
+            )
+            .await?,
+        );
+        let usage = TurnUsage {
+            user_id,
+            guild_id,
+            channel_id: thread_id.to_string(),
+            request_id,
+            cost_bucket: CostBucket::Story,
+        };
+        let http = ctx.http.clone();
+        let database = self.database.clone();
+        spawn_tracked(TaskKind::Discussions, async move {
+            writer.take_turn(&http, &database, thread_id, &usage).await;
+        });
+        Ok(())
+    }
+
     /// Handle a follow-up question in an active council thread
     ///
     /// All council personas respond to the follow-up with context from the discussion.
@@ -1875,6 +3107,7 @@ impl CommandHandler {
 
         // Clone values for async task
         let openai_model = self.openai_model.clone();
+        let chat_client = self.chat_client.clone();
         let usage_tracker = self.usage_tracker.clone();
         let persona_manager = self.persona_manager.clone();
         let persona_ids = council_state.persona_ids.clone();
@@ -1893,6 +3126,8 @@ impl CommandHandler {
             .send_message(&ctx.http, |m| m.set_embed(reconvene_embed))
             .await;
 
+        let database = self.database.clone();
+
         // Spawn task to get responses from each persona
         tokio::spawn(async move {
             for (i, persona_id) in persona_ids.iter().enumerate() {
@@ -1935,8 +3170,8 @@ impl CommandHandler {
                 ];
 
                 // Call OpenAI
-                let response = match openai::chat::ChatCompletion::builder(&openai_model, messages)
-                    .create()
+                let response = match chat_client
+                    .create_chat_completion(&openai_model, messages)
                     .await
                 {
                     Ok(completion) => {
@@ -1995,6 +3230,7 @@ impl CommandHandler {
                 }
             }
 
+            save_discussion_state_logged(&database, DiscussionType::Council, channel_id.0).await;
             info!("[{request_id}] Council follow-up completed");
         });
 
@@ -0,0 +1,536 @@
+//! Component and modal registry
+//!
+//! Buttons, select menus and modals carry a namespaced custom_id of the form
+//! `feature:action:payload`, optionally followed by `:expires_at` (unix
+//! seconds). The registry routes interactions to the handler that owns the
+//! feature and answers expired or unknown components itself. Custom IDs that
+//! predate the namespaced format are translated so buttons already posted in
+//! Discord keep working. With [`ComponentRegistry::with_access_rules`] the
+//! guild's /admin block and allow lists apply here as they do to commands.
+//!
+//! - **Version**: 1.1.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.1.0: Components and modals follow the guild's /admin block and allow lists
+//! - 1.0.0: Initial release with namespaced routing, expiry and legacy ID translation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::{debug, info};
+use serenity::model::application::interaction::message_component::MessageComponentInteraction;
+use serenity::model::application::interaction::modal::ModalSubmitInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::model::id::{ChannelId, GuildId, UserId};
+use serenity::prelude::Context;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::middleware::{access_permitted, ACCESS_REFUSED_MESSAGE};
+use crate::database::Database;
+use crate::features::discussion::{
+    parse_council_speaker_id, parse_debate_hear_id, CONTINUE_COUNCIL_PREFIX,
+    CONTINUE_DEBATE_PREFIX, DISMISS_COUNCIL_PREFIX, END_DEBATE_PREFIX, HEAR_DEBATE_PREFIX,
+    SPEAKER_COUNCIL_PREFIX,
+};
+
+/// Separator between custom_id segments
+const SEGMENT_SEPARATOR: char = ':';
+
+/// Separator between values inside a payload (`thread_id/persona_id`)
+pub const PAYLOAD_SEPARATOR: char = '/';
+
+/// Parsed `feature:action:payload[:expires_at]` custom_id
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentId {
+    pub feature: String,
+    pub action: String,
+    pub payload: String,
+    /// Unix timestamp after which the component is rejected as expired
+    pub expires_at: Option<i64>,
+}
+
+impl ComponentId {
+    pub fn new(
+        feature: impl Into<String>,
+        action: impl Into<String>,
+        payload: impl Into<String>,
+    ) -> Self {
+        Self {
+            feature: feature.into(),
+            action: action.into(),
+            payload: payload.into(),
+            expires_at: None,
+        }
+    }
+
+    /// Expire the component `ttl` from now
+    pub fn expires_in(mut self, ttl: Duration) -> Self {
+        self.expires_at = Some(chrono::Utc::now().timestamp() + ttl.as_secs() as i64);
+        self
+    }
+
+    /// Parse a namespaced custom_id; returns None for legacy or malformed IDs
+    pub fn parse(custom_id: &str) -> Option<Self> {
+        let mut segments = custom_id.split(SEGMENT_SEPARATOR);
+        let feature = segments.next().filter(|s| !s.is_empty())?;
+        let action = segments.next().filter(|s| !s.is_empty())?;
+        let payload = segments.next().unwrap_or("");
+        let expires_at = match segments.next() {
+            Some(raw) => Some(raw.parse().ok()?),
+            None => None,
+        };
+        if segments.next().is_some() {
+            return None;
+        }
+
+        Some(Self {
+            feature: feature.to_string(),
+            action: action.to_string(),
+            payload: payload.to_string(),
+            expires_at,
+        })
+    }
+
+    /// Translate a custom_id from before namespacing (e.g. `debate_continue_123`)
+    pub fn from_legacy(custom_id: &str) -> Option<Self> {
+        let exact = match custom_id {
+            "show_help_modal" => Some(("help", "show")),
+            "show_persona_modal" => Some(("prompt", "show")),
+            "help_feedback_modal" => Some(("help", "feedback")),
+            "persona_creation_modal" => Some(("persona", "create")),
+            "ai_prompt_modal" => Some(("prompt", "submit")),
+            _ => None,
+        };
+        if let Some((feature, action)) = exact {
+            return Some(Self::new(feature, action, ""));
+        }
+        if let Some(persona) = custom_id.strip_prefix("persona_") {
+            return Some(Self::new("persona", "select", persona));
+        }
+        if let Some((thread_id, persona_id)) = parse_debate_hear_id(custom_id) {
+            return Some(Self::new(
+                "debate",
+                "hear",
+                format!("{thread_id}{PAYLOAD_SEPARATOR}{persona_id}"),
+            ));
+        }
+        if let Some((thread_id, persona_id)) = parse_council_speaker_id(custom_id) {
+            return Some(Self::new(
+                "council",
+                "speaker",
+                format!("{thread_id}{PAYLOAD_SEPARATOR}{persona_id}"),
+            ));
+        }
+
+        if custom_id.starts_with(HEAR_DEBATE_PREFIX)
+            || custom_id.starts_with(SPEAKER_COUNCIL_PREFIX)
+        {
+            return None;
+        }
+
+        const PREFIXES: [(&str, &str, &str); 7] = [
+            (CONTINUE_DEBATE_PREFIX, "debate", "continue"),
+            (END_DEBATE_PREFIX, "debate", "end"),
+            (CONTINUE_COUNCIL_PREFIX, "council", "continue"),
+            (DISMISS_COUNCIL_PREFIX, "council", "dismiss"),
+            ("confirm_", "confirm", "yes"),
+            ("cancel_", "confirm", "no"),
+            ("page_", "page", ""),
+        ];
+        for (prefix, feature, action) in PREFIXES {
+            if let Some(rest) = custom_id.strip_prefix(prefix) {
+                // Pagination encoded the action itself after the prefix
+                return Some(if action.is_empty() {
+                    Self::new(feature, rest, "")
+                } else {
+                    Self::new(feature, action, rest)
+                });
+            }
+        }
+
+        None
+    }
+
+    /// Parse either format
+    pub fn resolve(custom_id: &str) -> Option<Self> {
+        Self::parse(custom_id).or_else(|| Self::from_legacy(custom_id))
+    }
+
+    pub fn is_expired_at(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|at| now >= at)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(chrono::Utc::now().timestamp())
+    }
+
+    /// Payload values split on `/`
+    pub fn payload_parts(&self) -> Vec<&str> {
+        self.payload.split(PAYLOAD_SEPARATOR).collect()
+    }
+
+    /// Leading payload value as a thread/channel ID
+    pub fn thread_id(&self) -> Option<u64> {
+        self.payload_parts().first()?.parse().ok()
+    }
+}
+
+impl fmt::Display for ComponentId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.feature, self.action, self.payload)?;
+        if let Some(at) = self.expires_at {
+            write!(f, ":{at}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Handler for the components and modals of one or more features
+#[async_trait]
+pub trait ComponentHandler: Send + Sync {
+    /// Feature namespaces this handler owns (the first custom_id segment)
+    fn features(&self) -> &'static [&'static str];
+
+    /// Handle a button or select menu interaction
+    async fn handle_component(
+        &self,
+        ctx: &Context,
+        interaction: &MessageComponentInteraction,
+        id: &ComponentId,
+    ) -> Result<()>;
+
+    /// Handle a modal submission
+    async fn handle_modal(
+        &self,
+        _ctx: &Context,
+        _interaction: &ModalSubmitInteraction,
+        id: &ComponentId,
+    ) -> Result<()> {
+        anyhow::bail!("Feature '{}' does not accept modals", id.feature)
+    }
+}
+
+/// Where a custom_id routes to
+pub enum Route {
+    Handler(ComponentId, Arc<dyn ComponentHandler>),
+    Expired(ComponentId),
+    Unknown,
+}
+
+/// Outcome of dispatching a component or modal through the registry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComponentDispatch {
+    Handled,
+    /// The component expired; the user was told so
+    Expired,
+    /// No handler owns the custom_id; the user was told so
+    Unknown,
+    /// The guild's access rules refuse the user; the user was told so
+    Refused,
+}
+
+/// Registry mapping feature namespaces to component handlers
+#[derive(Clone, Default)]
+pub struct ComponentRegistry {
+    handlers: HashMap<&'static str, Arc<dyn ComponentHandler>>,
+    access: Option<Database>,
+}
+
+impl ComponentRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check the guild's access rules before handing an interaction to its handler
+    pub fn with_access_rules(mut self, database: Database) -> Self {
+        self.access = Some(database);
+        self
+    }
+
+    /// Whether the access rules, if any, let the user through
+    async fn permits(
+        &self,
+        ctx: &Context,
+        guild_id: Option<GuildId>,
+        channel_id: ChannelId,
+        user_id: UserId,
+    ) -> Result<bool> {
+        match &self.access {
+            Some(database) => access_permitted(database, ctx, guild_id, channel_id, user_id).await,
+            None => Ok(true),
+        }
+    }
+
+    /// Register a handler for all of its feature namespaces
+    pub fn register(&mut self, handler: Arc<dyn ComponentHandler>) {
+        for feature in handler.features() {
+            self.handlers.insert(feature, Arc::clone(&handler));
+        }
+    }
+
+    pub fn get(&self, feature: &str) -> Option<Arc<dyn ComponentHandler>> {
+        self.handlers.get(feature).cloned()
+    }
+
+    pub fn contains(&self, feature: &str) -> bool {
+        self.handlers.contains_key(feature)
+    }
+
+    /// Resolve a custom_id against the registered features at time `now`
+    pub fn route(&self, custom_id: &str, now: i64) -> Route {
+        let Some(id) = ComponentId::resolve(custom_id) else {
+            return Route::Unknown;
+        };
+        let Some(handler) = self.get(&id.feature) else {
+            return Route::Unknown;
+        };
+        if id.is_expired_at(now) {
+            return Route::Expired(id);
+        }
+        Route::Handler(id, handler)
+    }
+
+    /// Route a button or select menu interaction
+    pub async fn dispatch_component(
+        &self,
+        ctx: &Context,
+        interaction: &MessageComponentInteraction,
+    ) -> Result<ComponentDispatch> {
+        let custom_id = &interaction.data.custom_id;
+        info!(
+            "Processing component interaction: {custom_id} from user: {}",
+            interaction.user.id
+        );
+
+        match self.route(custom_id, chrono::Utc::now().timestamp()) {
+            Route::Handler(id, handler) => {
+                let permitted = self
+                    .permits(
+                        ctx,
+                        interaction.guild_id,
+                        interaction.channel_id,
+                        interaction.user.id,
+                    )
+                    .await?;
+                if !permitted {
+                    info!(
+                        "🚫 Component {custom_id} from {} refused by access rules",
+                        interaction.user.id
+                    );
+                    interaction
+                        .create_interaction_response(&ctx.http, |response| {
+                            response
+                                .kind(InteractionResponseType::ChannelMessageWithSource)
+                                .interaction_response_data(|message| {
+                                    message.content(ACCESS_REFUSED_MESSAGE).ephemeral(true)
+                                })
+                        })
+                        .await?;
+                    return Ok(ComponentDispatch::Refused);
+                }
+                debug!(
+                    "Dispatching component {custom_id} to feature {}",
+                    id.feature
+                );
+                handler.handle_component(ctx, interaction, &id).await?;
+                Ok(ComponentDispatch::Handled)
+            }
+            Route::Expired(_) => {
+                interaction
+                    .create_interaction_response(&ctx.http, |response| {
+                        response
+                            .kind(InteractionResponseType::ChannelMessageWithSource)
+                            .interaction_response_data(|message| {
+                                message
+                                    .content("⌛ This button has expired. Run the command again to get a fresh one.")
+                                    .ephemeral(true)
+                            })
+                    })
+                    .await?;
+                Ok(ComponentDispatch::Expired)
+            }
+            Route::Unknown => {
+                interaction
+                    .create_interaction_response(&ctx.http, |response| {
+                        response
+                            .kind(InteractionResponseType::ChannelMessageWithSource)
+                            .interaction_response_data(|message| {
+                                message
+                                    .content("Unknown component interaction.")
+                                    .ephemeral(true)
+                            })
+                    })
+                    .await?;
+                Ok(ComponentDispatch::Unknown)
+            }
+        }
+    }
+
+    /// Route a modal submission
+    pub async fn dispatch_modal(
+        &self,
+        ctx: &Context,
+        interaction: &ModalSubmitInteraction,
+    ) -> Result<ComponentDispatch> {
+        let custom_id = &interaction.data.custom_id;
+        info!(
+            "Processing modal submit: {custom_id} from user: {}",
+            interaction.user.id
+        );
+
+        let (dispatch, message) = match self.route(custom_id, chrono::Utc::now().timestamp()) {
+            Route::Handler(id, handler) => {
+                let permitted = self
+                    .permits(
+                        ctx,
+                        interaction.guild_id,
+                        interaction.channel_id,
+                        interaction.user.id,
+                    )
+                    .await?;
+                if permitted {
+                    handler.handle_modal(ctx, interaction, &id).await?;
+                    return Ok(ComponentDispatch::Handled);
+                }
+                info!(
+                    "🚫 Modal {custom_id} from {} refused by access rules",
+                    interaction.user.id
+                );
+                (ComponentDispatch::Refused, ACCESS_REFUSED_MESSAGE)
+            }
+            Route::Expired(_) => (
+                ComponentDispatch::Expired,
+                "⌛ This form has expired. Please open it again.",
+            ),
+            Route::Unknown => (ComponentDispatch::Unknown, "Unknown modal submission."),
+        };
+
+        interaction
+            .create_interaction_response(&ctx.http, |response| {
+                response
+                    .kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|m| m.content(message).ephemeral(true))
+            })
+            .await?;
+        Ok(dispatch)
+    }
+
+    /// Number of registered feature namespaces
+    pub fn len(&self) -> usize {
+        self.handlers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct NoopHandler;
+
+    #[async_trait]
+    impl ComponentHandler for NoopHandler {
+        fn features(&self) -> &'static [&'static str] {
+            &["debate", "council"]
+        }
+
+        async fn handle_component(
+            &self,
+            _ctx: &Context,
+            _interaction: &MessageComponentInteraction,
+            _id: &ComponentId,
+        ) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_component_id_round_trip() {
+        let id = ComponentId::new("debate", "hear", "123/obi");
+        assert_eq!(id.to_string(), "debate:hear:123/obi");
+        assert_eq!(ComponentId::parse(&id.to_string()), Some(id.clone()));
+        assert_eq!(id.thread_id(), Some(123));
+        assert_eq!(id.payload_parts(), vec!["123", "obi"]);
+
+        let expiring = ComponentId {
+            expires_at: Some(1_700_000_000),
+            ..ComponentId::new("confirm", "yes", "reset")
+        };
+        assert_eq!(expiring.to_string(), "confirm:yes:reset:1700000000");
+        assert_eq!(
+            ComponentId::parse("confirm:yes:reset:1700000000"),
+            Some(expiring)
+        );
+
+        assert_eq!(
+            ComponentId::parse("help:show"),
+            Some(ComponentId::new("help", "show", ""))
+        );
+        assert!(ComponentId::parse("debate_continue_123").is_none());
+        assert!(ComponentId::parse(":hear:1").is_none());
+        assert!(ComponentId::parse("a:b:c:not-a-time").is_none());
+        assert!(ComponentId::parse("a:b:c:1:extra").is_none());
+    }
+
+    #[test]
+    fn test_legacy_ids_translate() {
+        let cases = [
+            ("debate_continue_123", "debate:continue:123"),
+            ("debate_end_123", "debate:end:123"),
+            ("hear_debate_123_obi", "debate:hear:123/obi"),
+            ("speaker_council_456_chef_2", "council:speaker:456/chef"),
+            ("continue_council_456", "council:continue:456"),
+            ("dismiss_council_456", "council:dismiss:456"),
+            ("persona_muppet", "persona:select:muppet"),
+            ("confirm_reset", "confirm:yes:reset"),
+            ("cancel_reset", "confirm:no:reset"),
+            ("page_next", "page:next:"),
+            ("show_help_modal", "help:show:"),
+            ("ai_prompt_modal", "prompt:submit:"),
+            ("persona_creation_modal", "persona:create:"),
+        ];
+        for (legacy, expected) in cases {
+            assert_eq!(
+                ComponentId::from_legacy(legacy).map(|id| id.to_string()),
+                Some(expected.to_string()),
+                "{legacy}"
+            );
+        }
+        assert!(ComponentId::from_legacy("something_else").is_none());
+    }
+
+    #[test]
+    fn test_route_expiry_and_unknown_features() {
+        let mut registry = ComponentRegistry::new();
+        registry.register(Arc::new(NoopHandler));
+        assert_eq!(registry.len(), 2);
+
+        assert!(matches!(
+            registry.route("debate:continue:1", 100),
+            Route::Handler(ref id, _) if id.action == "continue"
+        ));
+        assert!(matches!(
+            registry.route("debate_continue_1", 100),
+            Route::Handler(..)
+        ));
+        assert!(matches!(
+            registry.route("council:continue:1:99", 100),
+            Route::Expired(_)
+        ));
+        assert!(matches!(
+            registry.route("council:continue:1:101", 100),
+            Route::Handler(..)
+        ));
+        assert!(matches!(
+            registry.route("trivia:answer:1", 100),
+            Route::Unknown
+        ));
+        assert!(matches!(registry.route("garbage", 100), Route::Unknown));
+    }
+}
@@ -1,21 +1,46 @@
 //! Shared context for command handlers
 //!
-//! - **Version**: 1.2.0
+//! - **Version**: 1.17.0
 //! - **Since**: 3.38.0
 //!
 //! ## Changelog
+//! - 1.17.0: get_ai_response_with_params adds glossary definitions for terms in the user message
+//! - 1.16.0: AI responses, council_generator() and story_writer() carry the channel's scene
+//! - 1.15.0: get_ai_response_with_params adds the member's pinned memories for the channel
+//! - 1.14.0: get_ai_response_with_params passes persona temperature, top_p and max_tokens
+//! - 1.13.0: Guardrail profiles are looked up per channel, so NSFW channels can differ
+//! - 1.12.0: get_ai_response, council_generator() and story_writer() apply the guild's guardrail profile
+//! - 1.11.0: active_persona for commands that ask on a member's behalf
+//! - 1.10.0: story_writer() for /story paragraphs
+//! - 1.9.0: channel_scope_ids for block and allow lists
+//! - 1.8.0: is_bot_owner for owner-only commands
+//! - 1.7.0: council_generator() for shared council turns
+//! - 1.6.0: channel_category_id for category persona resolution
+//! - 1.5.0: Expose the registry's per-command metrics to handlers
+//! - 1.4.0: OpenAI calls go through an injectable ChatClient
+//! - 1.3.0: get_ai_response is guarded by the OpenAI chat circuit breaker
 //! - 1.2.0: Add PluginManager for plugin command handling
 //! - 1.1.0: Add ImageGenerator for imagine command
 //! - 1.0.0: Initial implementation with core shared state
 
+use super::middleware::CommandMetrics;
+use crate::core::{openai_chat_client, ChatClient, ModelParams};
 use crate::database::Database;
 use crate::features::analytics::{CostBucket, InteractionTracker, UsageTracker};
+use crate::features::council::CouncilGenerator;
+use crate::features::glossary::apply_glossary;
+use crate::features::guardrails::{apply_guardrails, GuardrailProfile};
 use crate::features::image_gen::generator::ImageGenerator;
+use crate::features::memories::apply_memories;
 use crate::features::personas::PersonaManager;
 use crate::features::plugins::PluginManager;
+use crate::features::resilience::{circuit_breakers, Dependency};
+use crate::features::scenes::{apply_scene, channel_scene};
+use crate::features::story::StoryWriter;
 use anyhow::Result;
 use log::{debug, error};
-use openai::chat::{ChatCompletion, ChatCompletionMessage, ChatCompletionMessageRole};
+use openai::chat::{ChatCompletionMessage, ChatCompletionMessageRole};
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::timeout;
@@ -30,7 +55,8 @@ use uuid::Uuid;
 /// - InteractionTracker for analytics
 /// - ImageGenerator for DALL-E image generation
 /// - PluginManager for plugin command execution
-/// - OpenAI configuration
+/// - ChatClient and model for OpenAI chat completions
+/// - Per-command dispatch metrics
 /// - Bot start time for uptime tracking
 #[derive(Clone)]
 pub struct CommandContext {
@@ -41,6 +67,8 @@ pub struct CommandContext {
     pub image_generator: ImageGenerator,
     pub plugin_manager: Option<Arc<PluginManager>>,
     pub openai_model: String,
+    pub chat_client: Arc<dyn ChatClient>,
+    pub command_metrics: Arc<CommandMetrics>,
     pub start_time: std::time::Instant,
 }
 
@@ -63,6 +91,8 @@ impl CommandContext {
             image_generator,
             plugin_manager,
             openai_model,
+            chat_client: openai_chat_client(),
+            command_metrics: Arc::new(CommandMetrics::new()),
             start_time: std::time::Instant::now(),
         }
     }
@@ -87,13 +117,60 @@ impl CommandContext {
             image_generator,
             plugin_manager,
             openai_model,
+            chat_client: openai_chat_client(),
+            command_metrics: Arc::new(CommandMetrics::new()),
             start_time,
         }
     }
 
+    /// Council member generator backed by this context's chat client, with
+    /// the channel's guardrail profile and scene
+    pub async fn council_generator(
+        &self,
+        guild_id: Option<&str>,
+        channel_id: &str,
+    ) -> Result<CouncilGenerator> {
+        Ok(CouncilGenerator::new(
+            self.chat_client.clone(),
+            self.openai_model.clone(),
+            self.usage_tracker.clone(),
+            self.persona_manager.clone(),
+        )
+        .with_guardrails(
+            GuardrailProfile::for_channel(&self.database, guild_id, Some(channel_id)).await?,
+        )
+        .with_scene(channel_scene(&self.database, guild_id, Some(channel_id)).await?))
+    }
+
+    /// Story writer backed by this context's chat client, with the channel's
+    /// guardrail profile and scene
+    pub async fn story_writer(
+        &self,
+        guild_id: Option<&str>,
+        channel_id: &str,
+    ) -> Result<StoryWriter> {
+        Ok(StoryWriter::new(
+            self.chat_client.clone(),
+            self.openai_model.clone(),
+            self.usage_tracker.clone(),
+            self.persona_manager.clone(),
+        )
+        .with_guardrails(
+            GuardrailProfile::for_channel(&self.database, guild_id, Some(channel_id)).await?,
+        )
+        .with_scene(channel_scene(&self.database, guild_id, Some(channel_id)).await?))
+    }
+
+    /// Replace the chat client (used by the dry-run harness to inject a mock)
+    pub fn with_chat_client(mut self, chat_client: Arc<dyn ChatClient>) -> Self {
+        self.chat_client = chat_client;
+        self
+    }
+
     /// Get AI response with conversation context
     ///
-    /// This is the core OpenAI integration for command handlers.
+    /// This is the core OpenAI integration for command handlers. The
+    /// channel's guardrail profile is applied to the system prompt here.
     ///
     /// # Arguments
     ///
@@ -116,13 +193,52 @@ impl CommandContext {
         guild_id: Option<&str>,
         channel_id: Option<&str>,
         cost_bucket: CostBucket,
+    ) -> Result<String> {
+        self.get_ai_response_with_params(
+            system_prompt,
+            user_message,
+            history,
+            request_id,
+            user_id,
+            guild_id,
+            channel_id,
+            cost_bucket,
+            ModelParams::default(),
+        )
+        .await
+    }
+
+    /// Same as `get_ai_response`, with the persona's model parameters
+    ///
+    /// `params` usually comes from `PersonaManager::model_params` for the
+    /// persona and command.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_ai_response_with_params(
+        &self,
+        system_prompt: &str,
+        user_message: &str,
+        history: Vec<(String, String)>,
+        request_id: Uuid,
+        user_id: Option<&str>,
+        guild_id: Option<&str>,
+        channel_id: Option<&str>,
+        cost_bucket: CostBucket,
+        params: ModelParams,
     ) -> Result<String> {
         debug!("[{request_id}] Building AI request with {} history messages", history.len());
+        let system_prompt =
+            apply_guardrails(&self.database, guild_id, channel_id, system_prompt).await?;
+        let system_prompt =
+            apply_memories(&self.database, user_id, guild_id, channel_id, system_prompt).await?;
+        let system_prompt =
+            apply_scene(&self.database, guild_id, channel_id, system_prompt).await?;
+        let system_prompt =
+            apply_glossary(&self.database, guild_id, user_message, system_prompt).await?;
 
         // Build messages array
         let mut messages = vec![ChatCompletionMessage {
             role: ChatCompletionMessageRole::System,
-            content: Some(system_prompt.to_string()),
+            content: Some(system_prompt),
             name: None,
             function_call: None,
             tool_call_id: None,
@@ -158,17 +274,27 @@ impl CommandContext {
 
         debug!("[{request_id}] Sending {} messages to OpenAI", messages.len());
 
+        circuit_breakers().check(Dependency::OpenAiChat)?;
+
         // Call OpenAI API with timeout
         let completion = timeout(
             Duration::from_secs(45),
-            ChatCompletion::builder(&self.openai_model, messages).create(),
+            self.chat_client.create_chat_completion_with_params(
+                &self.openai_model,
+                messages,
+                params,
+            ),
         )
         .await
-        .map_err(|_| anyhow::anyhow!("OpenAI request timed out after 45 seconds"))?
-        .map_err(|e| {
-            error!("[{request_id}] OpenAI API error: {e}");
-            anyhow::anyhow!("OpenAI API error: {e}")
-        })?;
+        .map_err(|_| anyhow::anyhow!("OpenAI request timed out after 45 seconds"))
+        .and_then(|result| {
+            result.map_err(|e| {
+                error!("[{request_id}] OpenAI API error: {e}");
+                anyhow::anyhow!("OpenAI API error: {e}")
+            })
+        });
+        circuit_breakers().record(Dependency::OpenAiChat, &completion);
+        let completion = completion?;
 
         let response = completion
             .choices
@@ -198,6 +324,32 @@ impl CommandContext {
         Ok(response)
     }
 
+    /// The persona a member gets in the channel a command was used in
+    ///
+    /// Channel and category overrides apply in guilds; DMs use the member's
+    /// own default.
+    pub async fn active_persona(
+        &self,
+        serenity_ctx: &serenity::prelude::Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<String> {
+        let user_id = command.user.id.to_string();
+        match command.guild_id {
+            Some(guild_id) => {
+                let category_id = channel_category_id(serenity_ctx, command.channel_id).await;
+                self.database
+                    .get_persona_with_channel(
+                        &user_id,
+                        &guild_id.to_string(),
+                        &command.channel_id.to_string(),
+                        category_id.as_deref(),
+                    )
+                    .await
+            }
+            None => self.database.get_user_persona(&user_id).await,
+        }
+    }
+
     /// Get AI response without history (simple single-turn)
     pub async fn get_simple_ai_response(
         &self,
@@ -236,6 +388,82 @@ pub async fn is_in_thread_channel(
     }
 }
 
+/// Category a channel sits in (threads resolve through their parent channel)
+pub async fn channel_category_id(
+    serenity_ctx: &serenity::prelude::Context,
+    channel_id: serenity::model::id::ChannelId,
+) -> Option<String> {
+    use serenity::model::channel::{Channel, ChannelType};
+
+    let mut current = channel_id;
+    for _ in 0..2 {
+        let channel = match serenity_ctx.cache.guild_channel(current) {
+            Some(channel) => channel,
+            None => match serenity_ctx.http.get_channel(current.0).await {
+                Ok(Channel::Guild(channel)) => channel,
+                _ => return None,
+            },
+        };
+        let parent = channel.parent_id?;
+        match channel.kind {
+            ChannelType::PublicThread | ChannelType::PrivateThread | ChannelType::NewsThread => {
+                current = parent
+            }
+            _ => return Some(parent.to_string()),
+        }
+    }
+    None
+}
+
+/// A channel followed by its parents (a thread's channel, then the category),
+/// so block and allow lists can name any of them
+pub async fn channel_scope_ids(
+    serenity_ctx: &serenity::prelude::Context,
+    channel_id: serenity::model::id::ChannelId,
+) -> Vec<String> {
+    use serenity::model::channel::Channel;
+
+    let mut ids = vec![channel_id.to_string()];
+    let mut current = channel_id;
+    for _ in 0..2 {
+        let channel = match serenity_ctx.cache.guild_channel(current) {
+            Some(channel) => channel,
+            None => match serenity_ctx.http.get_channel(current.0).await {
+                Ok(Channel::Guild(channel)) => channel,
+                _ => break,
+            },
+        };
+        let Some(parent) = channel.parent_id else {
+            break;
+        };
+        ids.push(parent.to_string());
+        current = parent;
+    }
+    ids
+}
+
+/// Whether a user owns the bot: the application owner or the configured
+/// startup notification owner
+pub async fn is_bot_owner(
+    serenity_ctx: &serenity::prelude::Context,
+    database: &Database,
+    user_id: serenity::model::id::UserId,
+) -> bool {
+    let configured = database
+        .get_bot_setting("startup_notify_owner_id")
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| v.parse::<u64>().ok());
+    if configured == Some(user_id.0) {
+        return true;
+    }
+    match serenity_ctx.http.get_current_application_info().await {
+        Ok(app) => app.owner.id == user_id,
+        Err(_) => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -246,4 +474,42 @@ mod tests {
         fn assert_clone<T: Clone>() {}
         assert_clone::<CommandContext>();
     }
+
+    #[tokio::test]
+    async fn test_get_ai_response_with_params_sends_persona_params() {
+        let database = Database::new(":memory:").await.unwrap();
+        let mock = Arc::new(crate::testing::MockChatClient::new());
+        let ctx = CommandContext::new(
+            PersonaManager::new(),
+            database.clone(),
+            UsageTracker::new(database.clone()),
+            InteractionTracker::new(database),
+            ImageGenerator::new("test-key".to_string()),
+            None,
+            "gpt-mock".to_string(),
+        )
+        .with_chat_client(mock.clone());
+
+        let params = ctx.persona_manager.model_params("analyst", Some("ask"));
+        ctx.get_ai_response_with_params(
+            "You are an analyst.",
+            "Plan my week",
+            Vec::new(),
+            Uuid::new_v4(),
+            None,
+            None,
+            None,
+            CostBucket::Ask,
+            params,
+        )
+        .await
+        .unwrap();
+        ctx.get_simple_ai_response("You are a chef.", "Hi", Uuid::new_v4(), CostBucket::Ask)
+            .await
+            .unwrap();
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].temperature, Some(0.0));
+        assert_eq!(requests[1].temperature, None);
+    }
 }
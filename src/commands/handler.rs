@@ -1,9 +1,10 @@
 //! Slash command handler trait and infrastructure
 //!
-//! - **Version**: 1.0.0
+//! - **Version**: 1.1.0
 //! - **Since**: 3.38.0
 //!
 //! ## Changelog
+//! - 1.1.0: Handlers can declare ephemeral replies so slow commands are deferred privately
 //! - 1.0.0: Initial implementation for modular command handling
 
 use anyhow::Result;
@@ -48,6 +49,14 @@ pub trait SlashCommandHandler: Send + Sync {
     /// A handler can process multiple commands if they share logic.
     fn command_names(&self) -> &'static [&'static str];
 
+    /// Whether this handler replies to `command` ephemerally
+    ///
+    /// The registry defers slow commands automatically; for these it defers
+    /// privately, since the deferred placeholder decides who sees the reply.
+    fn ephemeral(&self, _command: &ApplicationCommandInteraction) -> bool {
+        false
+    }
+
     /// Handle the slash command
     ///
     /// # Arguments
@@ -1,27 +1,57 @@
 //! Admin command handlers
 //!
-//! Handles: set_channel, set_guild, settings, admin_role, set_user
+//! Handles: set_channel, set_guild, settings, admin_role, set_user, admin
 //!
-//! - **Version**: 1.0.0
+//! - **Version**: 1.15.0
 //! - **Since**: 3.38.0
 //!
 //! ## Changelog
+//! - 1.15.0: /set_channel scene attaches a roleplay scene to a channel's persona prompts; /settings shows it
+//! - 1.14.0: /settings shows the guardrail profile
+//! - 1.13.0: /set_channel time_detection converts clock times in a channel's messages to timestamps
+//! - 1.12.0: /set_channel language and /set_guild language_exempt_role configure language nudges; /settings shows them
+//! - 1.11.0: /set_channel achievements picks where new badges are announced; /settings shows it
+//! - 1.10.0: /set_channel sentiment_tracking opts a channel into the /mood timeline
+//! - 1.9.0: /set_channel birthdays picks the channel for birthday wishes; /settings shows it
+//! - 1.8.0: Owner-only /admin presence configures the rotating activity status
+//! - 1.7.0: /settings shows the custom_emojis setting
+//! - 1.6.0: /set_guild persona_rotation and seasonal_themes apply immediately
+//! - 1.5.0: /admin block|allow|unblock user|channel and /admin access manage server access lists
+//! - 1.4.0: /set_user persona is stored per guild (DMs keep the global preference)
+//! - 1.3.0: Owner-only /admin update installs GitHub releases and restarts
+//! - 1.2.0: /set_guild category_persona maps channel categories to default personas
+//! - 1.1.0: Initial responses go through InteractionResponder (auto-defer safe)
 //! - 1.0.0: Extracted from command_handler.rs
 
 use anyhow::Result;
 use async_trait::async_trait;
-use log::info;
+use log::{error, info, warn};
 use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
 use serenity::model::application::interaction::InteractionResponseType;
 use serenity::prelude::Context;
 use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::commands::context::CommandContext;
+use crate::commands::context::{is_bot_owner, CommandContext};
 use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
 use crate::commands::slash::{
     admin::{validate_channel_setting, validate_guild_setting, validate_user_setting},
-    get_channel_option, get_role_option, get_string_option,
+    get_bool_option, get_channel_option, get_integer_option, get_role_option, get_string_option,
+    get_user_option,
+};
+use crate::database::{AccessRule, AccessRules, AccessTarget};
+use crate::features::achievements::CHANNEL_SETTING as ACHIEVEMENT_CHANNEL_SETTING;
+use crate::features::birthdays::CHANNEL_SETTING as BIRTHDAY_CHANNEL_SETTING;
+use crate::features::get_bot_version;
+use crate::features::guardrails::GuardrailProfile;
+use crate::features::language::{find_language, EXEMPT_ROLE_SETTING};
+use crate::features::personas::themes::{
+    apply_guild_themes, find_theme, update_rotation_base, ACTIVE_THEME_SETTING,
+};
+use crate::features::presence;
+use crate::features::updater::{
+    drain_jobs, install_release, latest_release, request_restart, UpdateConfig,
 };
 
 /// Handler for admin/settings commands
@@ -30,7 +60,14 @@ pub struct AdminHandler;
 #[async_trait]
 impl SlashCommandHandler for AdminHandler {
     fn command_names(&self) -> &'static [&'static str] {
-        &["set_channel", "set_guild", "settings", "admin_role", "set_user"]
+        &[
+            "set_channel",
+            "set_guild",
+            "settings",
+            "admin_role",
+            "set_user",
+            "admin",
+        ]
     }
 
     async fn handle(
@@ -46,6 +83,10 @@ impl SlashCommandHandler for AdminHandler {
             "settings" => self.handle_settings(&ctx, serenity_ctx, command, request_id).await,
             "admin_role" => self.handle_admin_role(&ctx, serenity_ctx, command, request_id).await,
             "set_user" => self.handle_set_user(&ctx, serenity_ctx, command, request_id).await,
+            "admin" => {
+                self.handle_admin(&ctx, serenity_ctx, command, request_id)
+                    .await
+            }
             _ => Ok(()),
         }
     }
@@ -57,10 +98,11 @@ impl AdminHandler {
         serenity_ctx: &Context,
         command: &ApplicationCommandInteraction,
     ) -> Result<Option<String>> {
+        let responder = InteractionResponder::for_command(command);
         match command.guild_id {
             Some(id) => Ok(Some(id.to_string())),
             None => {
-                command
+                responder
                     .create_interaction_response(&serenity_ctx.http, |response| {
                         response
                             .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -82,6 +124,7 @@ impl AdminHandler {
         command: &ApplicationCommandInteraction,
         request_id: Uuid,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let guild_id = match Self::require_guild(serenity_ctx, command).await? {
             Some(id) => id,
             None => return Ok(()),
@@ -95,7 +138,7 @@ impl AdminHandler {
         // Validate setting and value
         let (is_valid, error_msg) = validate_channel_setting(&setting, &value);
         if !is_valid {
-            command
+            responder
                 .create_interaction_response(&serenity_ctx.http, |response| {
                     response
                         .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -147,6 +190,138 @@ impl AdminHandler {
                 let status = if enabled { "Enabled" } else { "Disabled" };
                 format!("Conflict mediation for <#{target_channel_id}> is now **{status}**")
             }
+            "birthdays" => {
+                let current = ctx
+                    .database
+                    .get_guild_setting(&guild_id, BIRTHDAY_CHANNEL_SETTING)
+                    .await?;
+                if value == "enabled" {
+                    ctx.database
+                        .set_guild_setting(&guild_id, BIRTHDAY_CHANNEL_SETTING, &target_channel_id)
+                        .await?;
+                    info!("[{request_id}] Birthday wishes now go to channel {target_channel_id}");
+                    format!("🎂 Birthday and anniversary wishes will be posted in <#{target_channel_id}>")
+                } else if current.as_deref() == Some(target_channel_id.as_str()) {
+                    ctx.database
+                        .delete_guild_setting(&guild_id, BIRTHDAY_CHANNEL_SETTING)
+                        .await?;
+                    info!(
+                        "[{request_id}] Birthday wishes disabled (was channel {target_channel_id})"
+                    );
+                    "Birthday wishes are now **Disabled** for this server".to_string()
+                } else {
+                    format!("<#{target_channel_id}> isn't the birthday channel, nothing changed")
+                }
+            }
+            "achievements" => {
+                let current = ctx
+                    .database
+                    .get_guild_setting(&guild_id, ACHIEVEMENT_CHANNEL_SETTING)
+                    .await?;
+                if value == "enabled" {
+                    ctx.database
+                        .set_guild_setting(
+                            &guild_id,
+                            ACHIEVEMENT_CHANNEL_SETTING,
+                            &target_channel_id,
+                        )
+                        .await?;
+                    info!("[{request_id}] Achievement announcements now go to channel {target_channel_id}");
+                    format!("🏅 New badges will be announced in <#{target_channel_id}>")
+                } else if current.as_deref() == Some(target_channel_id.as_str()) {
+                    ctx.database
+                        .delete_guild_setting(&guild_id, ACHIEVEMENT_CHANNEL_SETTING)
+                        .await?;
+                    info!(
+                        "[{request_id}] Achievement announcements disabled (was channel {target_channel_id})"
+                    );
+                    "Achievement announcements are now **Disabled** for this server".to_string()
+                } else {
+                    format!(
+                        "<#{target_channel_id}> isn't the achievements channel, nothing changed"
+                    )
+                }
+            }
+            "link_previews" => {
+                ctx.database
+                    .set_channel_link_previews(&guild_id, &target_channel_id, &value)
+                    .await?;
+                info!(
+                    "[{request_id}] Set link_previews for channel {target_channel_id} to {value}"
+                );
+                match value.as_str() {
+                    "reply" => format!(
+                        "🔗 Links posted in <#{target_channel_id}> will get a short summary reply"
+                    ),
+                    "thread" => format!(
+                        "🔗 Links posted in <#{target_channel_id}> will get a summary in a thread"
+                    ),
+                    _ => format!("Link previews for <#{target_channel_id}> are now **Disabled**"),
+                }
+            }
+            "sentiment_tracking" => {
+                let enabled = value == "enabled";
+                ctx.database
+                    .set_channel_sentiment_tracking(&guild_id, &target_channel_id, enabled)
+                    .await?;
+                info!(
+                    "[{request_id}] Set sentiment_tracking for channel {target_channel_id} to {value}"
+                );
+                if enabled {
+                    format!("📈 Tracking the mood of <#{target_channel_id}>, see `/mood`")
+                } else {
+                    format!("Sentiment tracking for <#{target_channel_id}> is now **Disabled**")
+                }
+            }
+            "time_detection" => {
+                let enabled = value == "enabled";
+                ctx.database
+                    .set_channel_time_detection(&guild_id, &target_channel_id, enabled)
+                    .await?;
+                info!(
+                    "[{request_id}] Set time_detection for channel {target_channel_id} to {value}"
+                );
+                if enabled {
+                    format!(
+                        "🕒 Times written in <#{target_channel_id}> will be shown in everyone's local time for members who set `/time zone`"
+                    )
+                } else {
+                    format!("Time detection for <#{target_channel_id}> is now **Disabled**")
+                }
+            }
+            "language" => {
+                let language = find_language(&value);
+                ctx.database
+                    .set_channel_language(&guild_id, &target_channel_id, language.map(|l| l.code))
+                    .await?;
+                info!("[{request_id}] Set language for channel {target_channel_id} to {value}");
+                match language {
+                    Some(language) => format!(
+                        "🌐 Members posting in another language in <#{target_channel_id}> will get a gentle reminder to use **{}**",
+                        language.name
+                    ),
+                    None => format!("Language nudges for <#{target_channel_id}> are now **Disabled**"),
+                }
+            }
+            "scene" => {
+                if value == "clear" {
+                    ctx.database
+                        .set_channel_scene(&guild_id, &target_channel_id, None)
+                        .await?;
+                    info!("[{request_id}] Cleared scene for channel {target_channel_id}");
+                    format!("🎭 Scene cleared for <#{target_channel_id}>")
+                } else {
+                    let scene = value.trim();
+                    ctx.database
+                        .set_channel_scene(&guild_id, &target_channel_id, Some(scene))
+                        .await?;
+                    info!(
+                        "[{request_id}] Set scene for channel {target_channel_id} ({} chars)",
+                        scene.chars().count()
+                    );
+                    format!("🎭 Personas in <#{target_channel_id}> will stay in this scene:\n>>> {scene}")
+                }
+            }
             "max_paragraphs" => {
                 let max_paragraphs: i64 = value.parse().unwrap_or(0);
                 ctx.database
@@ -166,7 +341,7 @@ impl AdminHandler {
             }
         };
 
-        command
+        responder
             .create_interaction_response(&serenity_ctx.http, |response| {
                 response
                     .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -185,6 +360,7 @@ impl AdminHandler {
         command: &ApplicationCommandInteraction,
         request_id: Uuid,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let guild_id = match Self::require_guild(serenity_ctx, command).await? {
             Some(id) => id,
             None => return Ok(()),
@@ -198,7 +374,7 @@ impl AdminHandler {
         // Validate setting and value using shared validation
         let (is_valid, error_msg) = validate_guild_setting(&setting, &value);
         if !is_valid {
-            command
+            responder
                 .create_interaction_response(&serenity_ctx.http, |response| {
                     response
                         .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -210,6 +386,36 @@ impl AdminHandler {
             return Ok(());
         }
 
+        if setting == "category_persona" {
+            let response_message = match get_channel_option(&command.data.options, "category") {
+                Some(category_id) => {
+                    let category_id = category_id.to_string();
+                    if value == "clear" {
+                        ctx.database
+                            .set_category_persona(&guild_id, &category_id, None)
+                            .await?;
+                        info!("[{request_id}] Cleared persona for category {category_id}");
+                        format!("Category persona cleared for <#{category_id}>.")
+                    } else {
+                        ctx.database
+                            .set_category_persona(&guild_id, &category_id, Some(&value))
+                            .await?;
+                        info!("[{request_id}] Set persona for category {category_id} to {value}");
+                        format!("Channels in <#{category_id}> now default to **{value}** unless they have their own persona override.")
+                    }
+                }
+                None => "Pick a channel category with the `category` option.".to_string(),
+            };
+            responder
+                .create_interaction_response(&serenity_ctx.http, |response| {
+                    response
+                        .kind(InteractionResponseType::ChannelMessageWithSource)
+                        .interaction_response_data(|message| message.content(response_message))
+                })
+                .await?;
+            return Ok(());
+        }
+
         // Check if this is a global bot setting or a guild setting
         let is_global_setting = matches!(
             setting.as_str(),
@@ -223,15 +429,29 @@ impl AdminHandler {
         if is_global_setting {
             info!("[{request_id}] Setting global bot setting '{setting}' to '{value}'");
             ctx.database.set_bot_setting(&setting, &value).await?;
+        } else if setting == EXEMPT_ROLE_SETTING && value == "none" {
+            info!("[{request_id}] Clearing guild {guild_id} setting '{setting}'");
+            ctx.database
+                .delete_guild_setting(&guild_id, &setting)
+                .await?;
         } else {
             info!("[{request_id}] Setting guild {guild_id} setting '{setting}' to '{value}'");
             ctx.database
                 .set_guild_setting(&guild_id, &setting, &value)
                 .await?;
+            match setting.as_str() {
+                "default_persona" => update_rotation_base(&ctx.database, &guild_id, &value).await?,
+                // Apply now rather than at the scheduler's next pass
+                "persona_rotation" | "seasonal_themes" => {
+                    let today = chrono::Utc::now().date_naive();
+                    apply_guild_themes(&ctx.database, &guild_id, today).await?
+                }
+                _ => {}
+            }
         }
 
         let scope = if is_global_setting { "Global" } else { "Guild" };
-        command
+        responder
             .create_interaction_response(&serenity_ctx.http, |response| {
                 response
                     .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -253,6 +473,7 @@ impl AdminHandler {
         command: &ApplicationCommandInteraction,
         request_id: Uuid,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let guild_id = match Self::require_guild(serenity_ctx, command).await? {
             Some(id) => id,
             None => return Ok(()),
@@ -268,6 +489,25 @@ impl AdminHandler {
         let channel_persona_display = channel_persona
             .map(|p| format!("`{p}` (override)"))
             .unwrap_or_else(|| "Not set (uses user/guild default)".to_string());
+        let channel_language_display = match ctx
+            .database
+            .get_channel_language(&guild_id, &channel_id)
+            .await?
+            .as_deref()
+            .and_then(find_language)
+        {
+            Some(language) => format!("`{}` ({})", language.code, language.name),
+            None => "Any".to_string(),
+        };
+
+        let channel_scene_display = match ctx
+            .database
+            .get_channel_scene(&guild_id, &channel_id)
+            .await?
+        {
+            Some(scene) => format!("Set ({} chars)", scene.chars().count()),
+            None => "None".to_string(),
+        };
 
         // Get guild settings with defaults
         let guild_default_verbosity = ctx
@@ -280,6 +520,23 @@ impl AdminHandler {
             .get_guild_setting(&guild_id, "default_persona")
             .await?
             .unwrap_or_else(|| "obi".to_string());
+        let guild_persona_rotation = ctx
+            .database
+            .get_guild_setting(&guild_id, "persona_rotation")
+            .await?
+            .unwrap_or_else(|| "disabled".to_string());
+        let guild_seasonal_themes = ctx
+            .database
+            .get_guild_setting(&guild_id, "seasonal_themes")
+            .await?
+            .unwrap_or_else(|| "disabled".to_string());
+        let active_theme_display = ctx
+            .database
+            .get_guild_setting(&guild_id, ACTIVE_THEME_SETTING)
+            .await?
+            .and_then(|key| find_theme(&key))
+            .map(|theme| format!(" (now: {})", theme.name))
+            .unwrap_or_default();
         let guild_conflict_mediation = ctx
             .database
             .get_guild_setting(&guild_id, "conflict_mediation")
@@ -290,6 +547,9 @@ impl AdminHandler {
             .get_guild_setting(&guild_id, "conflict_sensitivity")
             .await?
             .unwrap_or_else(|| "medium".to_string());
+        let guild_guardrails = GuardrailProfile::for_guild(&ctx.database, Some(&guild_id))
+            .await?
+            .as_str();
         let guild_mediation_cooldown = ctx
             .database
             .get_guild_setting(&guild_id, "mediation_cooldown")
@@ -320,6 +580,11 @@ impl AdminHandler {
             .get_guild_setting(&guild_id, "mention_responses")
             .await?
             .unwrap_or_else(|| "enabled".to_string());
+        let guild_custom_emojis = ctx
+            .database
+            .get_guild_setting(&guild_id, "custom_emojis")
+            .await?
+            .unwrap_or_else(|| "disabled".to_string());
         let guild_debate_auto_response = ctx
             .database
             .get_guild_setting(&guild_id, "debate_auto_response")
@@ -331,11 +596,46 @@ impl AdminHandler {
             .database
             .get_guild_setting(&guild_id, "bot_admin_role")
             .await?;
+        let birthday_channel_display = match ctx
+            .database
+            .get_guild_setting(&guild_id, BIRTHDAY_CHANNEL_SETTING)
+            .await?
+        {
+            Some(channel_id) => format!("<#{channel_id}>"),
+            None => "Not set".to_string(),
+        };
+        let language_exempt_role_display = match ctx
+            .database
+            .get_guild_setting(&guild_id, EXEMPT_ROLE_SETTING)
+            .await?
+        {
+            Some(role_id) => format!("<@&{role_id}>"),
+            None => "Not set".to_string(),
+        };
+        let achievement_channel_display = match ctx
+            .database
+            .get_guild_setting(&guild_id, ACHIEVEMENT_CHANNEL_SETTING)
+            .await?
+        {
+            Some(channel_id) => format!("<#{channel_id}>"),
+            None => "Not set".to_string(),
+        };
         let admin_role_display = match admin_role {
             Some(role_id) => format!("<@&{role_id}>"),
             None => "Not set (Discord admins only)".to_string(),
         };
 
+        let category_personas = ctx.database.get_category_personas(&guild_id).await?;
+        let category_personas_display = if category_personas.is_empty() {
+            "None".to_string()
+        } else {
+            category_personas
+                .iter()
+                .map(|(category_id, persona)| format!("<#{category_id}> → `{persona}`"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
         let conflict_status = if conflict_enabled {
             "Enabled"
         } else {
@@ -347,25 +647,35 @@ impl AdminHandler {
             **Channel Settings** (<#{channel_id}>):\n\
             - Verbosity: `{channel_verbosity}`\n\
             - Persona: {channel_persona_display}\n\
-            - Conflict Mediation: {conflict_status}\n\n\
+            - Conflict Mediation: {conflict_status}\n\
+            - Language: {channel_language_display}\n\
+            - Scene: {channel_scene_display}\n\n\
             **Guild Settings**:\n\
             - Default Verbosity: `{guild_default_verbosity}`\n\
             - Default Persona: `{guild_default_persona}`\n\
+            - Category Personas: {category_personas_display}\n\
+            - Persona Rotation: `{guild_persona_rotation}`\n\
+            - Seasonal Themes: `{guild_seasonal_themes}`{active_theme_display}\n\
             - Conflict Mediation: `{guild_conflict_mediation}`\n\
             - Conflict Sensitivity: `{guild_conflict_sensitivity}`\n\
+            - Guardrail Profile: `{guild_guardrails}`\n\
             - Mediation Cooldown: `{guild_mediation_cooldown}` minutes\n\
             - Max Context Messages: `{guild_max_context}`\n\
             - Audio Transcription: `{guild_audio_transcription}`\n\
             - Audio Transcription Mode: `{guild_audio_mode}`\n\
             - Audio Transcription Output: `{guild_audio_output}`\n\
             - Mention Responses: `{guild_mention_responses}`\n\
+            - Custom Emojis: `{guild_custom_emojis}`\n\
             - Debate Auto-Response: `{guild_debate_auto_response}`\n\
+            - Birthday Channel: {birthday_channel_display}\n\
+            - Achievement Channel: {achievement_channel_display}\n\
+            - Language Exempt Role: {language_exempt_role_display}\n\
             - Bot Admin Role: {admin_role_display}\n"
         );
 
         info!("[{request_id}] Displaying settings for guild {guild_id} channel {channel_id}");
 
-        command
+        responder
             .create_interaction_response(&serenity_ctx.http, |response| {
                 response
                     .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -384,6 +694,7 @@ impl AdminHandler {
         command: &ApplicationCommandInteraction,
         request_id: Uuid,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let guild_id = match Self::require_guild(serenity_ctx, command).await? {
             Some(id) => id,
             None => return Ok(()),
@@ -398,7 +709,7 @@ impl AdminHandler {
             .set_guild_setting(&guild_id, "bot_admin_role", &role_id.to_string())
             .await?;
 
-        command
+        responder
             .create_interaction_response(&serenity_ctx.http, |response| {
                 response
                     .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -421,6 +732,7 @@ impl AdminHandler {
         command: &ApplicationCommandInteraction,
         request_id: Uuid,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let setting = get_string_option(&command.data.options, "setting")
             .ok_or_else(|| anyhow::anyhow!("Missing setting parameter"))?;
         let value = get_string_option(&command.data.options, "value")
@@ -429,7 +741,7 @@ impl AdminHandler {
         // Validate setting and value
         let (is_valid, error_msg) = validate_user_setting(&setting, &value);
         if !is_valid {
-            command
+            responder
                 .create_interaction_response(&serenity_ctx.http, |response| {
                     response
                         .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -446,7 +758,10 @@ impl AdminHandler {
         // Apply the setting
         let response_message = match setting.as_str() {
             "persona" => {
-                ctx.database.set_user_persona(&user_id, &value).await?;
+                let guild_id = command.guild_id.map(|id| id.to_string());
+                ctx.database
+                    .set_user_persona_in_guild(&user_id, guild_id.as_deref(), &value)
+                    .await?;
                 info!("[{request_id}] Set persona for user {user_id} to {value}");
                 format!("Your persona has been set to **{value}**")
             }
@@ -455,7 +770,7 @@ impl AdminHandler {
             }
         };
 
-        command
+        responder
             .create_interaction_response(&serenity_ctx.http, |response| {
                 response
                     .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -464,6 +779,335 @@ impl AdminHandler {
             .await?;
         Ok(())
     }
+
+    /// Handle /admin - owner-only maintenance and server access lists
+    async fn handle_admin(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        request_id: Uuid,
+    ) -> Result<()> {
+        let Some(subcommand) = command.data.options.first() else {
+            return Ok(());
+        };
+        match subcommand.name.as_str() {
+            "update" | "presence" => {
+                let responder = InteractionResponder::for_command(command);
+                if !is_bot_owner(serenity_ctx, &ctx.database, command.user.id).await {
+                    responder
+                        .create_interaction_response(&serenity_ctx.http, |response| {
+                            response
+                                .kind(InteractionResponseType::ChannelMessageWithSource)
+                                .interaction_response_data(|message| {
+                                    message
+                                        .content(format!(
+                                            "Only the bot owner can use `/admin {}`.",
+                                            subcommand.name
+                                        ))
+                                        .ephemeral(true)
+                                })
+                        })
+                        .await?;
+                    return Ok(());
+                }
+                if subcommand.name == "presence" {
+                    return self
+                        .handle_presence(ctx, serenity_ctx, command, request_id)
+                        .await;
+                }
+                let check_only =
+                    get_bool_option(&subcommand.options, "check_only").unwrap_or(false);
+                self.handle_update(ctx, serenity_ctx, command, check_only, request_id)
+                    .await
+            }
+            "block" | "allow" | "unblock" | "access" => {
+                self.handle_access(ctx, serenity_ctx, command, request_id)
+                    .await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Handle /admin presence - show or change the rotating activity status
+    async fn handle_presence(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        request_id: Uuid,
+    ) -> Result<()> {
+        let options = command
+            .data
+            .options
+            .first()
+            .map(|o| o.options.as_slice())
+            .unwrap_or_default();
+        if let Some(messages) = get_string_option(options, "messages") {
+            ctx.database
+                .set_bot_setting(presence::MESSAGES_SETTING, messages.trim())
+                .await?;
+            info!("[{request_id}] Presence messages set to '{messages}'");
+        }
+        if let Some(interval) = get_integer_option(options, "interval") {
+            ctx.database
+                .set_bot_setting(presence::INTERVAL_SETTING, &interval.to_string())
+                .await?;
+            info!("[{request_id}] Presence interval set to {interval} minutes");
+        }
+
+        let templates = presence::configured_messages(&ctx.database).await?;
+        let interval = presence::configured_interval(&ctx.database).await?;
+
+        let stats = presence::PresenceStats {
+            guilds: serenity_ctx.cache.guild_count(),
+            jobs: ctx
+                .plugin_manager
+                .as_ref()
+                .map(|pm| pm.job_manager.active_job_count())
+                .unwrap_or(0),
+        };
+        let content = match templates {
+            None => {
+                "🟢 Activity status rotation is **disabled**. Set `messages` to turn it back on."
+                    .to_string()
+            }
+            Some(templates) => {
+                let mut content =
+                    format!("🟢 **Activity status** (changes every {interval} min)\n");
+                for template in &templates {
+                    let preview = match presence::render_template(template, &stats) {
+                        Some(line) => format!("{:?} {}", line.kind, line.text),
+                        None => "skipped while zero".to_string(),
+                    };
+                    content.push_str(&format!("- `{template}` → {preview}\n"));
+                }
+                content.push_str("\nPlaceholders: `{guilds}`, `{jobs}`, `{version}`. Use `disabled` to turn rotation off.");
+                content
+            }
+        };
+
+        let responder = InteractionResponder::for_command(command);
+        responder
+            .create_interaction_response(&serenity_ctx.http, |response| {
+                response
+                    .kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|message| message.content(content).ephemeral(true))
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Handle /admin block|allow|unblock user|channel and /admin access
+    async fn handle_access(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        request_id: Uuid,
+    ) -> Result<()> {
+        let Some(guild_id) = Self::require_guild(serenity_ctx, command).await? else {
+            return Ok(());
+        };
+        let Some(group) = command.data.options.first() else {
+            return Ok(());
+        };
+
+        let content = if group.name == "access" {
+            let rules = ctx.database.get_access_rules(&guild_id).await?;
+            Self::format_access_rules(&rules)
+        } else {
+            let sub = group
+                .options
+                .first()
+                .ok_or_else(|| anyhow::anyhow!("Missing user or channel subcommand"))?;
+            let (target, target_id, mention) = match sub.name.as_str() {
+                "user" => {
+                    let id = get_user_option(&sub.options, "target")
+                        .ok_or_else(|| anyhow::anyhow!("Missing target user"))?;
+                    (AccessTarget::User, id.to_string(), format!("<@{id}>"))
+                }
+                _ => {
+                    let id = get_channel_option(&sub.options, "target")
+                        .ok_or_else(|| anyhow::anyhow!("Missing target channel"))?;
+                    (AccessTarget::Channel, id.to_string(), format!("<#{id}>"))
+                }
+            };
+            let rule = match group.name.as_str() {
+                "block" => Some(AccessRule::Block),
+                "allow" => Some(AccessRule::Allow),
+                _ => None,
+            };
+
+            ctx.database
+                .set_access_rule(
+                    &guild_id,
+                    target,
+                    &target_id,
+                    rule,
+                    &command.user.id.to_string(),
+                )
+                .await?;
+            info!(
+                "[{request_id}] /admin {} {} {target_id} | Guild: {guild_id} | By: {}",
+                group.name, sub.name, command.user.id
+            );
+
+            match rule {
+                Some(AccessRule::Block) => format!("🚫 I'll ignore {mention} in this server."),
+                Some(AccessRule::Allow) => format!(
+                    "✅ Added {mention} to the allowlist. Only allowlisted {}s get responses now.",
+                    sub.name
+                ),
+                None => format!("↩️ Removed {mention} from the block and allow lists."),
+            }
+        };
+
+        let responder = InteractionResponder::for_command(command);
+        responder
+            .create_interaction_response(&serenity_ctx.http, |response| {
+                response
+                    .kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|message| message.content(content).ephemeral(true))
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Summarize a guild's block and allow lists
+    fn format_access_rules(rules: &AccessRules) -> String {
+        if rules.is_empty() {
+            return "🛡️ No access rules: I respond to everyone in every channel.".to_string();
+        }
+
+        let list = |ids: &[String], prefix: &str| {
+            if ids.is_empty() {
+                "none".to_string()
+            } else {
+                ids.iter()
+                    .map(|id| format!("<{prefix}{id}>"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            }
+        };
+        let mut output = "🛡️ **Access rules**\n".to_string();
+        output.push_str(&format!(
+            "**Blocked users**: {}\n",
+            list(&rules.blocked_users, "@")
+        ));
+        output.push_str(&format!(
+            "**Blocked channels**: {}\n",
+            list(&rules.blocked_channels, "#")
+        ));
+        output.push_str(&format!(
+            "**Allowed users**: {}\n",
+            list(&rules.allowed_users, "@")
+        ));
+        output.push_str(&format!(
+            "**Allowed channels**: {}\n",
+            list(&rules.allowed_channels, "#")
+        ));
+        output.push_str("_An empty allowlist means everyone is allowed._");
+        output
+    }
+
+    /// Handle /admin update - check for, install and restart into a newer release
+    async fn handle_update(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        check_only: bool,
+        request_id: Uuid,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        responder
+            .create_interaction_response(&serenity_ctx.http, |response| {
+                response
+                    .kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                    .interaction_response_data(|message| message.ephemeral(true))
+            })
+            .await?;
+
+        let progress = |text: String| async move {
+            if let Err(e) = command
+                .edit_original_interaction_response(&serenity_ctx.http, |r| r.content(text))
+                .await
+            {
+                warn!("[{request_id}] Failed to update /admin update progress: {e}");
+            }
+        };
+
+        let config = UpdateConfig::from_env();
+        let current = get_bot_version();
+        let release = match latest_release(&config).await {
+            Ok(release) => release,
+            Err(e) => {
+                progress(format!("❌ Couldn't check for updates: {e}")).await;
+                return Ok(());
+            }
+        };
+
+        if !release.is_newer() {
+            progress(format!(
+                "✅ Already up to date: running `v{current}`, latest release is `{}`.",
+                release.tag_name
+            ))
+            .await;
+            return Ok(());
+        }
+        if check_only {
+            progress(format!(
+                "⬆️ Update available: `v{current}` → `{}`\n{}",
+                release.tag_name, release.html_url
+            ))
+            .await;
+            return Ok(());
+        }
+
+        info!(
+            "[{request_id}] Installing {} (running v{current})",
+            release.tag_name
+        );
+        progress(format!("⬇️ Installing `{}`...", release.tag_name)).await;
+        let installed = match install_release(&config, &release).await {
+            Ok(summary) => summary,
+            Err(e) => {
+                error!("[{request_id}] Update to {} failed: {e}", release.tag_name);
+                progress(format!("❌ Update to `{}` failed: {e}", release.tag_name)).await;
+                return Ok(());
+            }
+        };
+
+        // Let running plugin jobs finish before the process exits
+        let jobs = ctx.plugin_manager.as_ref().map(|pm| pm.job_manager.clone());
+        let active = jobs.as_ref().map_or(0, |j| j.active_job_count());
+        if active > 0 {
+            progress(format!(
+                "{installed}\n⏳ Waiting for {active} running job(s) to finish (up to {}s)...",
+                config.drain_timeout.as_secs()
+            ))
+            .await;
+        }
+        let remaining = drain_jobs(
+            || jobs.as_ref().map_or(0, |j| j.active_job_count()),
+            config.drain_timeout,
+        )
+        .await;
+        let note = if remaining > 0 {
+            format!(" ({remaining} job(s) still running will be recovered after the restart)")
+        } else {
+            String::new()
+        };
+
+        progress(format!(
+            "{installed}\n🔄 Restarting into `{}`{note}...",
+            release.tag_name
+        ))
+        .await;
+        request_restart();
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -480,6 +1124,23 @@ mod tests {
         assert!(names.contains(&"settings"));
         assert!(names.contains(&"admin_role"));
         assert!(names.contains(&"set_user"));
-        assert_eq!(names.len(), 5);
+        assert!(names.contains(&"admin"));
+        assert_eq!(names.len(), 6);
+    }
+
+    #[test]
+    fn test_format_access_rules() {
+        let empty = AdminHandler::format_access_rules(&AccessRules::default());
+        assert!(empty.contains("No access rules"));
+
+        let rules = AccessRules {
+            blocked_users: vec!["1".to_string(), "2".to_string()],
+            allowed_channels: vec!["9".to_string()],
+            ..Default::default()
+        };
+        let output = AdminHandler::format_access_rules(&rules);
+        assert!(output.contains("**Blocked users**: <@1>, <@2>"));
+        assert!(output.contains("**Blocked channels**: none"));
+        assert!(output.contains("**Allowed channels**: <#9>"));
     }
 }
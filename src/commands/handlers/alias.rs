@@ -0,0 +1,316 @@
+//! Alias command handler
+//!
+//! Handles: alias
+//!
+//! Members save prompt templates for themselves, or with Manage Server for
+//! the whole server, and run them with arguments. Running an alias expands
+//! it into a prompt for the /ask pipeline; a personal alias wins over a
+//! shared one with the same name.
+//!
+//! - **Version**: 1.1.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.1.0: Default persona comes from CommandContext::active_persona
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use serenity::model::application::interaction::application_command::{
+    ApplicationCommandInteraction, CommandDataOption,
+};
+use serenity::prelude::Context;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::handlers::ask::AskHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::{get_bool_option, get_string_option};
+use crate::database::PromptAlias;
+use crate::features::aliases::{
+    expand, list_line, validate_name, validate_template, ALIASES_FEATURE, GUILD_SCOPE,
+    MAX_GUILD_ALIASES, MAX_USER_ALIASES, USER_SCOPE,
+};
+
+/// Handler for /alias
+pub struct AliasHandler;
+
+#[async_trait]
+impl SlashCommandHandler for AliasHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["alias"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let guild_id = command.guild_id.map(|id| id.to_string());
+        if let Some(guild_id) = guild_id.as_deref() {
+            if !ctx
+                .database
+                .is_feature_enabled(ALIASES_FEATURE, None, Some(guild_id))
+                .await?
+            {
+                return responder
+                    .reply_ephemeral(serenity_ctx, "❌ Aliases are disabled on this server.")
+                    .await;
+            }
+        }
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+        let options = &subcommand.options;
+
+        let content = match subcommand.name.as_str() {
+            "run" => return Self::handle_run(&ctx, serenity_ctx, command, options).await,
+            "create" => Self::handle_create(&ctx, command, options).await?,
+            "list" => Self::handle_list(&ctx, command).await?,
+            _ => Self::handle_delete(&ctx, command, options).await?,
+        };
+        responder.reply_ephemeral(serenity_ctx, &content).await
+    }
+}
+
+impl AliasHandler {
+    /// The scope an alias command works in, or an error reply
+    ///
+    /// Shared aliases belong to the guild and need Manage Server to change.
+    fn scope(
+        command: &ApplicationCommandInteraction,
+        options: &[CommandDataOption],
+    ) -> Result<(&'static str, String), &'static str> {
+        if !get_bool_option(options, "shared").unwrap_or(false) {
+            return Ok((USER_SCOPE, command.user.id.to_string()));
+        }
+        let Some(guild_id) = command.guild_id else {
+            return Err("❌ Shared aliases only exist on servers.");
+        };
+        let can_manage = command
+            .member
+            .as_ref()
+            .and_then(|m| m.permissions)
+            .is_some_and(|p| p.manage_guild());
+        if !can_manage {
+            return Err("❌ You need the Manage Server permission to change shared aliases.");
+        }
+        Ok((GUILD_SCOPE, guild_id.to_string()))
+    }
+
+    /// Handle /alias create, returning the reply
+    async fn handle_create(
+        ctx: &CommandContext,
+        command: &ApplicationCommandInteraction,
+        options: &[CommandDataOption],
+    ) -> Result<String> {
+        let (scope, scope_id) = match Self::scope(command, options) {
+            Ok(scope) => scope,
+            Err(reply) => return Ok(reply.to_string()),
+        };
+        let name = match validate_name(&get_string_option(options, "name").unwrap_or_default()) {
+            Ok(name) => name,
+            Err(e) => return Ok(format!("❌ {e}")),
+        };
+        let template =
+            match validate_template(&get_string_option(options, "prompt").unwrap_or_default()) {
+                Ok(template) => template,
+                Err(e) => return Ok(format!("❌ {e}")),
+            };
+
+        let db = &ctx.database;
+        let replacing = db
+            .get_prompt_alias(scope, &scope_id, &name)
+            .await?
+            .is_some();
+        let limit = if scope == GUILD_SCOPE {
+            MAX_GUILD_ALIASES
+        } else {
+            MAX_USER_ALIASES
+        };
+        if !replacing && db.count_prompt_aliases(scope, &scope_id).await? >= limit {
+            return Ok(format!(
+                "❌ That's already {limit} aliases. Delete one with `/alias delete` first."
+            ));
+        }
+
+        let alias = PromptAlias {
+            scope: scope.to_string(),
+            scope_id,
+            name,
+            template,
+            persona: get_string_option(options, "persona"),
+            created_by: command.user.id.to_string(),
+        };
+        db.upsert_prompt_alias(&alias).await?;
+        info!(
+            "/alias create | User: {} | {scope} alias `{}`",
+            command.user.id, alias.name
+        );
+        Ok(format!(
+            "✅ {} {} alias:\n{}\nRun it with `/alias run name:{}`.",
+            if replacing { "Updated" } else { "Saved" },
+            if scope == GUILD_SCOPE {
+                "the shared"
+            } else {
+                "your"
+            },
+            list_line(&alias),
+            alias.name
+        ))
+    }
+
+    /// Handle /alias run: expand the alias and hand the prompt to /ask
+    async fn handle_run(
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        options: &[CommandDataOption],
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let user_id = command.user.id.to_string();
+        let guild_id = command.guild_id.map(|id| id.to_string());
+        let name = get_string_option(options, "name")
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase();
+
+        let db = &ctx.database;
+        let mut alias = db.get_prompt_alias(USER_SCOPE, &user_id, &name).await?;
+        if alias.is_none() {
+            if let Some(guild_id) = guild_id.as_deref() {
+                alias = db.get_prompt_alias(GUILD_SCOPE, guild_id, &name).await?;
+            }
+        }
+        let Some(alias) = alias else {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    &format!("❌ There's no alias `{name}`. See yours with `/alias list`."),
+                )
+                .await;
+        };
+        let prompt = match expand(
+            &alias.template,
+            &get_string_option(options, "args").unwrap_or_default(),
+        ) {
+            Ok(prompt) => prompt,
+            Err(e) => {
+                return responder
+                    .reply_ephemeral(serenity_ctx, &format!("❌ {e}"))
+                    .await
+            }
+        };
+
+        let persona_id = match get_string_option(options, "persona").or(alias.persona) {
+            Some(persona_id) => persona_id,
+            None => ctx.active_persona(serenity_ctx, command).await?,
+        };
+        let request_id = Uuid::new_v4();
+        info!(
+            "[{request_id}] /alias run | User: {user_id} | Alias: {name} | Persona: {persona_id}"
+        );
+        AskHandler::ask(
+            ctx,
+            serenity_ctx,
+            command,
+            request_id,
+            &persona_id,
+            &prompt,
+            get_bool_option(options, "ignore_context").unwrap_or(false),
+            None,
+            None,
+            None,
+        )
+        .await
+    }
+
+    /// Handle /alias list, returning the reply
+    async fn handle_list(
+        ctx: &CommandContext,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<String> {
+        let db = &ctx.database;
+        let personal = db
+            .get_prompt_aliases(USER_SCOPE, &command.user.id.to_string())
+            .await?;
+        let shared = match command.guild_id {
+            Some(guild_id) => {
+                db.get_prompt_aliases(GUILD_SCOPE, &guild_id.to_string())
+                    .await?
+            }
+            None => Vec::new(),
+        };
+        if personal.is_empty() && shared.is_empty() {
+            return Ok(
+                "No aliases yet. Save one with `/alias create`, e.g. a prompt like \
+                `Explain ${topic} to a beginner`."
+                    .to_string(),
+            );
+        }
+
+        let mut sections = Vec::new();
+        if !personal.is_empty() {
+            let lines: Vec<String> = personal.iter().map(list_line).collect();
+            sections.push(format!("**Your aliases**\n{}", lines.join("\n")));
+        }
+        if !shared.is_empty() {
+            let lines: Vec<String> = shared.iter().map(list_line).collect();
+            sections.push(format!("**Shared on this server**\n{}", lines.join("\n")));
+        }
+        let content = sections.join("\n\n");
+        // 75 aliases at most, but long previews could still pass Discord's limit
+        Ok(if content.chars().count() > 2000 {
+            content.chars().take(1999).collect::<String>() + "…"
+        } else {
+            content
+        })
+    }
+
+    /// Handle /alias delete, returning the reply
+    async fn handle_delete(
+        ctx: &CommandContext,
+        command: &ApplicationCommandInteraction,
+        options: &[CommandDataOption],
+    ) -> Result<String> {
+        let (scope, scope_id) = match Self::scope(command, options) {
+            Ok(scope) => scope,
+            Err(reply) => return Ok(reply.to_string()),
+        };
+        let name = get_string_option(options, "name")
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase();
+        if ctx
+            .database
+            .delete_prompt_alias(scope, &scope_id, &name)
+            .await?
+        {
+            info!(
+                "/alias delete | User: {} | {scope} alias `{name}`",
+                command.user.id
+            );
+            Ok(format!("🗑️ Deleted alias `{name}`."))
+        } else {
+            Ok(format!("❌ There's no {scope} alias `{name}`."))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alias_handler_commands() {
+        let handler = AliasHandler;
+        assert_eq!(handler.command_names(), &["alias"]);
+    }
+}
@@ -0,0 +1,430 @@
+//! Anonymous question command handler
+//!
+//! Handles: ask_anon, anon
+//!
+//! `/ask_anon` stores the question with its author and either posts it
+//! straight away or, with moderation on, queues it and announces it in the
+//! review channel. Members with Manage Server configure the feature and
+//! approve, reject or reveal queued questions with `/anon`. Posted questions
+//! never carry the author, and the channel's persona may answer them.
+//!
+//! - **Version**: 1.0.1
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.1: Replies are deferred privately by the registry instead of by hand
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use log::{info, warn};
+use serenity::model::application::interaction::application_command::{
+    ApplicationCommandInteraction, CommandDataOption,
+};
+use serenity::model::id::ChannelId;
+use serenity::prelude::Context;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::{
+    get_bool_option, get_channel_option, get_integer_option, get_string_option,
+};
+use crate::database::AnonQuestion;
+use crate::features::analytics::CostBucket;
+use crate::features::anon_questions::{
+    answer_prompt, question_embed, queue_line, validate_question, ANON_FEATURE, ANON_USER_ID,
+    ASK_COMMAND, CHANNEL_SETTING, MODERATION_SETTING, PERSONA_SETTING, QUEUE_LIMIT,
+    REVIEW_CHANNEL_SETTING,
+};
+use crate::features::personas::themes::{apply_theme, ACTIVE_THEME_SETTING};
+
+/// Handler for /ask_anon and /anon
+pub struct AnonQuestionsHandler;
+
+#[async_trait]
+impl SlashCommandHandler for AnonQuestionsHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &[ASK_COMMAND, "anon"]
+    }
+
+    fn ephemeral(&self, _command: &ApplicationCommandInteraction) -> bool {
+        true
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let request_id = Uuid::new_v4();
+        let responder = InteractionResponder::for_command(command);
+        let Some(guild_id) = command.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        if !ctx
+            .database
+            .is_feature_enabled(ANON_FEATURE, None, Some(&guild_id))
+            .await?
+        {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "❌ Anonymous questions are disabled on this server.",
+                )
+                .await;
+        }
+
+        let content = match command.data.name.as_str() {
+            ASK_COMMAND => {
+                self.handle_ask_anon(&ctx, serenity_ctx, command, &guild_id, request_id)
+                    .await?
+            }
+            _ => {
+                let can_manage = command
+                    .member
+                    .as_ref()
+                    .and_then(|m| m.permissions)
+                    .is_some_and(|p| p.manage_guild());
+                if can_manage {
+                    self.handle_anon(&ctx, serenity_ctx, command, &guild_id, request_id)
+                        .await?
+                } else {
+                    "❌ You need the Manage Server permission to manage anonymous questions."
+                        .to_string()
+                }
+            }
+        };
+        responder.reply_ephemeral(serenity_ctx, &content).await
+    }
+}
+
+impl AnonQuestionsHandler {
+    /// Handle /ask_anon, returning the reply for the author
+    async fn handle_ask_anon(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        guild_id: &str,
+        request_id: Uuid,
+    ) -> Result<String> {
+        let db = &ctx.database;
+        let Some(channel_id) = db.get_guild_setting(guild_id, CHANNEL_SETTING).await? else {
+            return Ok("❌ Anonymous questions aren't set up yet. An admin can pick a channel with `/anon setup`.".to_string());
+        };
+        let text = match get_string_option(&command.data.options, "question")
+            .as_deref()
+            .map(validate_question)
+            .unwrap_or(Err("Missing question"))
+        {
+            Ok(text) => text,
+            Err(message) => return Ok(format!("❌ {message}")),
+        };
+        let moderated = db
+            .get_guild_setting(guild_id, MODERATION_SETTING)
+            .await?
+            .is_some_and(|v| v == "enabled");
+        let status = if moderated { "pending" } else { "posted" };
+        let author_id = command.user.id.to_string();
+        let id = db
+            .create_anon_question(guild_id, &author_id, &text, status, Utc::now().timestamp())
+            .await?;
+        // The author is deliberately left out of the logs
+        info!("[{request_id}] /ask_anon | Guild: {guild_id} | Question {id} | {status}");
+        let Some(question) = db.get_anon_question(guild_id, id).await? else {
+            return Ok(format!("❌ Question #{id} went missing."));
+        };
+
+        if moderated {
+            if let Some(review_channel) = db
+                .get_guild_setting(guild_id, REVIEW_CHANNEL_SETTING)
+                .await?
+            {
+                let embed = question_embed(&question);
+                let notice = format!(
+                    "📥 New anonymous question waiting for review. Post it with `/anon approve id:{id}` or drop it with `/anon reject id:{id}`."
+                );
+                if let Err(e) = ChannelId(review_channel.parse()?)
+                    .send_message(&serenity_ctx.http, |m| m.content(notice).set_embed(embed))
+                    .await
+                {
+                    warn!("[{request_id}] Failed to announce anonymous question {id} in {review_channel}: {e}");
+                }
+            }
+            return Ok(format!(
+                "✅ Your question was sent to the moderators. If they approve it, it'll be posted anonymously in <#{channel_id}>."
+            ));
+        }
+
+        match self
+            .post_question(ctx, serenity_ctx, &question, &channel_id, request_id)
+            .await
+        {
+            Ok(()) => Ok(format!(
+                "✅ Your question was posted anonymously in <#{channel_id}>."
+            )),
+            Err(e) => {
+                warn!("[{request_id}] Failed to post anonymous question {id}: {e}");
+                Ok(format!(
+                    "❌ I couldn't post in <#{channel_id}>. Ask an admin to check my permissions there."
+                ))
+            }
+        }
+    }
+
+    /// Handle /anon setup, queue, approve, reject and reveal, returning the reply
+    async fn handle_anon(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        guild_id: &str,
+        request_id: Uuid,
+    ) -> Result<String> {
+        let user_id = command.user.id.to_string();
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+        let db = &ctx.database;
+        let id = get_integer_option(&subcommand.options, "id").unwrap_or(0);
+
+        match subcommand.name.as_str() {
+            "setup" => Self::handle_setup(ctx, guild_id, &subcommand.options).await,
+            "queue" => {
+                let pending = db.get_pending_anon_questions(guild_id, QUEUE_LIMIT).await?;
+                Ok(Self::format_queue(&pending))
+            }
+            "approve" => {
+                if !db
+                    .review_anon_question(guild_id, id, "posted", &user_id)
+                    .await?
+                {
+                    return Ok(format!("❌ Question #{id} isn't waiting for review."));
+                }
+                info!("[{request_id}] /anon approve | User: {user_id} | Guild: {guild_id} | Question {id}");
+                let (Some(question), Some(channel_id)) = (
+                    db.get_anon_question(guild_id, id).await?,
+                    db.get_guild_setting(guild_id, CHANNEL_SETTING).await?,
+                ) else {
+                    return Ok("❌ Anonymous questions aren't set up yet. Pick a channel with `/anon setup`.".to_string());
+                };
+                match self
+                    .post_question(ctx, serenity_ctx, &question, &channel_id, request_id)
+                    .await
+                {
+                    Ok(()) => Ok(format!("✅ Posted question #{id} in <#{channel_id}>.")),
+                    Err(e) => {
+                        warn!("[{request_id}] Failed to post anonymous question {id}: {e}");
+                        Ok(format!(
+                            "❌ Question #{id} was approved, but I couldn't post in <#{channel_id}>. Check my permissions there."
+                        ))
+                    }
+                }
+            }
+            "reject" => {
+                if db
+                    .review_anon_question(guild_id, id, "rejected", &user_id)
+                    .await?
+                {
+                    info!("[{request_id}] /anon reject | User: {user_id} | Guild: {guild_id} | Question {id}");
+                    Ok(format!(
+                        "🗑️ Rejected question #{id}. The author isn't notified."
+                    ))
+                } else {
+                    Ok(format!("❌ Question #{id} isn't waiting for review."))
+                }
+            }
+            _ => match db.get_anon_question(guild_id, id).await? {
+                Some(question) => {
+                    warn!(
+                        "[{request_id}] /anon reveal | User: {user_id} | Guild: {guild_id} | Question {id}"
+                    );
+                    Ok(format!(
+                        "🔎 Question #{id} was asked by <@{author}> (`{author}`). Please only use this to handle abuse.",
+                        author = question.author_id
+                    ))
+                }
+                None => Ok(format!("❌ There's no question #{id} on this server.")),
+            },
+        }
+    }
+
+    /// Handle /anon setup, returning the reply
+    async fn handle_setup(
+        ctx: &CommandContext,
+        guild_id: &str,
+        options: &[CommandDataOption],
+    ) -> Result<String> {
+        let Some(channel_id) = get_channel_option(options, "channel") else {
+            return Ok("❌ Pick a channel.".to_string());
+        };
+        let db = &ctx.database;
+        db.set_guild_setting(guild_id, CHANNEL_SETTING, &channel_id.to_string())
+            .await?;
+        match get_channel_option(options, "review_channel") {
+            Some(review) => {
+                db.set_guild_setting(guild_id, REVIEW_CHANNEL_SETTING, &review.to_string())
+                    .await?
+            }
+            None => {
+                db.delete_guild_setting(guild_id, REVIEW_CHANNEL_SETTING)
+                    .await?
+            }
+        }
+        for (option, setting) in [
+            ("moderation", MODERATION_SETTING),
+            ("persona_answers", PERSONA_SETTING),
+        ] {
+            if let Some(on) = get_bool_option(options, option) {
+                let value = if on { "enabled" } else { "disabled" };
+                db.set_guild_setting(guild_id, setting, value).await?;
+            }
+        }
+        let moderated = db
+            .get_guild_setting(guild_id, MODERATION_SETTING)
+            .await?
+            .is_some_and(|v| v == "enabled");
+        let persona = db
+            .get_guild_setting(guild_id, PERSONA_SETTING)
+            .await?
+            .is_some_and(|v| v == "enabled");
+        info!("/anon setup | Guild: {guild_id} | Channel: {channel_id} | Moderated: {moderated}");
+
+        let mut reply = format!("✅ Anonymous questions go to <#{channel_id}>");
+        reply.push_str(if moderated {
+            " after a moderator approves them with `/anon approve`."
+        } else {
+            " right away."
+        });
+        if moderated {
+            if let Some(review) = get_channel_option(options, "review_channel") {
+                reply.push_str(&format!(" New questions are announced in <#{review}>."));
+            }
+        }
+        if persona {
+            reply.push_str(" The channel's persona answers each one.");
+        }
+        Ok(reply)
+    }
+
+    /// Post a question without its author and let the persona answer when enabled
+    async fn post_question(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        question: &AnonQuestion,
+        channel_id: &str,
+        request_id: Uuid,
+    ) -> Result<()> {
+        let embed = question_embed(question);
+        let channel = ChannelId(channel_id.parse()?);
+        let message = channel
+            .send_message(&serenity_ctx.http, |m| m.set_embed(embed))
+            .await?;
+        let db = &ctx.database;
+        db.set_anon_question_message(question.id, &message.id.to_string())
+            .await?;
+
+        let persona_answers = db
+            .get_guild_setting(&question.guild_id, PERSONA_SETTING)
+            .await?
+            .is_some_and(|v| v == "enabled");
+        if !persona_answers {
+            return Ok(());
+        }
+        let persona_name = db
+            .get_persona_with_channel(ANON_USER_ID, &question.guild_id, channel_id, None)
+            .await
+            .unwrap_or_else(|_| "obi".to_string());
+        let theme = db
+            .get_guild_setting(&question.guild_id, ACTIVE_THEME_SETTING)
+            .await?;
+        let persona_prompt = apply_theme(
+            ctx.persona_manager.get_system_prompt(&persona_name, None),
+            theme.as_deref(),
+        );
+        // No user ID, so the usage log can't tie the answer back to the author
+        let answer = match ctx
+            .get_ai_response(
+                &answer_prompt(&persona_prompt),
+                &question.content,
+                Vec::new(),
+                request_id,
+                None,
+                Some(&question.guild_id),
+                Some(channel_id),
+                CostBucket::AnonQuestions,
+            )
+            .await
+        {
+            Ok(answer) => answer,
+            Err(e) => {
+                warn!(
+                    "[{request_id}] Persona answer to anonymous question {} failed: {e}",
+                    question.id
+                );
+                return Ok(());
+            }
+        };
+        channel
+            .send_message(&serenity_ctx.http, |m| {
+                m.content(answer.trim())
+                    .reference_message(&message)
+                    .allowed_mentions(|a| a.empty_parse())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Reply for /anon queue
+    fn format_queue(pending: &[AnonQuestion]) -> String {
+        if pending.is_empty() {
+            return "No anonymous questions are waiting for review.".to_string();
+        }
+        let mut lines = vec![format!("📥 **Awaiting review ({})**", pending.len())];
+        lines.extend(pending.iter().map(queue_line));
+        lines.push("Use `/anon approve` or `/anon reject` with the question number.".to_string());
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anon_questions_handler_commands() {
+        let handler = AnonQuestionsHandler;
+        assert_eq!(handler.command_names(), &["ask_anon", "anon"]);
+    }
+
+    #[test]
+    fn test_format_queue() {
+        assert_eq!(
+            AnonQuestionsHandler::format_queue(&[]),
+            "No anonymous questions are waiting for review."
+        );
+        let question = AnonQuestion {
+            id: 3,
+            guild_id: "1".to_string(),
+            author_id: "42".to_string(),
+            content: "Is the raid still on?".to_string(),
+            status: "pending".to_string(),
+            created_at: 1_700_000_000,
+            reviewed_by: None,
+            message_id: None,
+        };
+        let text = AnonQuestionsHandler::format_queue(&[question]);
+        assert!(text.starts_with(
+            "📥 **Awaiting review (1)**\n`#3` <t:1700000000:R> Is the raid still on?\n"
+        ));
+        assert!(!text.contains("42"));
+    }
+}
@@ -1,18 +1,30 @@
-//! Ask command handler
+//! Ask command handlers
 //!
-//! Handles: ask
+//! Handles: ask, ask_all
 //!
-//! - **Version**: 1.1.0
+//! - **Version**: 1.13.0
 //! - **Since**: 3.38.0
 //!
 //! ## Changelog
+//! - 1.13.0: Replies are capped at the token budget for max_paragraphs
+//! - 1.12.0: Answers use the persona's temperature, top_p and max_tokens for ask
+//! - 1.11.0: Personas the guild's guardrail profile doesn't allow are refused
+//! - 1.10.0: Disclosure mode puts the answer's confidence, sources and assumptions in the footer
+//! - 1.9.0: devils_advocate adds a labeled rebuttal from a second persona, written with the debate prompt
+//! - 1.8.0: The /ask pipeline is shared as AskHandler::ask for /alias run
+//! - 1.7.0: Answered /ask calls count toward achievements
+//! - 1.6.0: /ask picks up the guild's active seasonal theme
+//! - 1.5.0: Forked threads include the context copied by /fork
+//! - 1.4.0: /ask_all gathers 2-3 personas' answers concurrently into one reply
+//! - 1.3.0: Initial responses go through InteractionResponder (auto-defer safe)
+//! - 1.2.0: Friendly message when the OpenAI chat circuit breaker is open
 //! - 1.1.0: Use shared persona embed builders from core::embeds
 //! - 1.0.0: Extracted from command_handler.rs
 
 use anyhow::Result;
 use async_trait::async_trait;
-use log::{debug, error, info};
-use serenity::builder::GetMessages;
+use log::{debug, error, info, warn};
+use serenity::builder::{CreateEmbed, GetMessages};
 use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
 use serenity::model::application::interaction::InteractionResponseType;
 use serenity::prelude::Context;
@@ -22,18 +34,37 @@ use uuid::Uuid;
 
 use crate::commands::context::{is_in_thread_channel, CommandContext};
 use crate::commands::handler::SlashCommandHandler;
-use crate::commands::slash::{get_bool_option, get_integer_option, get_string_option};
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::{
+    ask::ASK_ALL_MAX_PERSONAS, get_bool_option, get_integer_option, get_string_option,
+};
 use crate::core::{chunk_for_embed, continuation_embed, persona_embed};
+use crate::features::achievements::{self, Progress};
 use crate::features::analytics::CostBucket;
-use crate::features::personas::apply_paragraph_limit;
+use crate::features::council::TurnUsage;
+use crate::features::debate::{rebuttal_message, DebateOrchestrator};
+use crate::features::disclosure::{self, split_disclosure, with_disclosure_prompt};
+use crate::features::guardrails::GuardrailProfile;
+use crate::features::personas::themes::ACTIVE_THEME_SETTING;
+use crate::features::personas::{apply_paragraph_limit, apply_theme, verbosity, Persona};
+use crate::features::resilience::CircuitOpenError;
 
-/// Handler for /ask command - ask any persona a question
+/// Discord's limit on the combined text of all embeds in one message
+const MESSAGE_EMBED_CHARS: usize = 6000;
+
+/// Discord's limit on embeds per message
+const MESSAGE_EMBEDS: usize = 10;
+
+/// Label on the first embed of a devil's advocate rebuttal
+const DEVILS_ADVOCATE_TITLE: &str = "😈 Devil's advocate";
+
+/// Handler for /ask and /ask_all - ask one or several personas a question
 pub struct AskHandler;
 
 #[async_trait]
 impl SlashCommandHandler for AskHandler {
     fn command_names(&self) -> &'static [&'static str] {
-        &["ask"]
+        &["ask", "ask_all"]
     }
 
     async fn handle(
@@ -43,8 +74,17 @@ impl SlashCommandHandler for AskHandler {
         command: &ApplicationCommandInteraction,
     ) -> Result<()> {
         let request_id = Uuid::new_v4();
-        self.handle_ask(&ctx, serenity_ctx, command, request_id)
-            .await
+        match command.data.name.as_str() {
+            "ask" => {
+                self.handle_ask(&ctx, serenity_ctx, command, request_id)
+                    .await
+            }
+            "ask_all" => {
+                self.handle_ask_all(&ctx, serenity_ctx, command, request_id)
+                    .await
+            }
+            _ => Ok(()),
+        }
     }
 }
 
@@ -57,8 +97,6 @@ impl AskHandler {
         command: &ApplicationCommandInteraction,
         request_id: Uuid,
     ) -> Result<()> {
-        let start_time = Instant::now();
-
         // Extract command options
         let persona_id = get_string_option(&command.data.options, "persona")
             .ok_or_else(|| anyhow::anyhow!("Missing persona argument"))?;
@@ -66,6 +104,47 @@ impl AskHandler {
             .ok_or_else(|| anyhow::anyhow!("Missing prompt argument"))?;
         let ignore_context =
             get_bool_option(&command.data.options, "ignore_context").unwrap_or(false);
+        let paragraphs = get_integer_option(&command.data.options, "paragraphs");
+        let devils_advocate = get_string_option(&command.data.options, "devils_advocate");
+        let disclosure = get_bool_option(&command.data.options, "disclosure");
+
+        Self::ask(
+            ctx,
+            serenity_ctx,
+            command,
+            request_id,
+            &persona_id,
+            &prompt,
+            ignore_context,
+            paragraphs,
+            devils_advocate.as_deref(),
+            disclosure,
+        )
+        .await
+    }
+
+    /// Answer `prompt` as `persona_id`, replying to `command`
+    ///
+    /// The /ask pipeline: context fetch, themed persona prompt, embeds and
+    /// achievements. Shared with commands that expand into a prompt, like
+    /// /alias run. `paragraphs` overrides the channel's paragraph limit.
+    /// `devils_advocate` names a second persona who rebuts the answer, and
+    /// `disclosure` overrides the guild's answer disclosure setting.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn ask(
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        request_id: Uuid,
+        persona_id: &str,
+        prompt: &str,
+        ignore_context: bool,
+        paragraphs: Option<i64>,
+        devils_advocate: Option<&str>,
+        disclosure: Option<bool>,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let start_time = Instant::now();
 
         let user_id = command.user.id.to_string();
         let channel_id = command.channel_id;
@@ -76,9 +155,9 @@ impl AskHandler {
         );
 
         // Validate persona exists
-        let persona = ctx.persona_manager.get_persona_with_portrait(&persona_id);
+        let persona = ctx.persona_manager.get_persona_with_portrait(persona_id);
         if persona.is_none() {
-            command
+            responder
                 .create_interaction_response(&serenity_ctx.http, |r| {
                     r.kind(InteractionResponseType::ChannelMessageWithSource)
                         .interaction_response_data(|m| {
@@ -91,36 +170,91 @@ impl AskHandler {
         }
         let persona = persona.unwrap();
 
-        // Get max_paragraphs: per-request overrides channel default; 0 = no limit
-        let max_paragraphs = get_integer_option(&command.data.options, "paragraphs")
-            .unwrap_or_else(|| {
-                if let Some(gid) = command.guild_id {
-                    tokio::task::block_in_place(|| {
-                        tokio::runtime::Handle::current().block_on(async {
-                            ctx.database
-                                .get_channel_max_paragraphs(
-                                    &gid.to_string(),
-                                    &channel_id.to_string(),
-                                )
-                                .await
-                                .unwrap_or(0)
+        let advocate = match devils_advocate {
+            Some(advocate_id) => {
+                let advocate = (advocate_id != persona_id)
+                    .then(|| ctx.persona_manager.get_persona_with_portrait(advocate_id))
+                    .flatten();
+                if advocate.is_none() {
+                    responder
+                        .create_interaction_response(&serenity_ctx.http, |r| {
+                            r.kind(InteractionResponseType::ChannelMessageWithSource)
+                                .interaction_response_data(|m| {
+                                    m.content("Pick a different persona as the devil's advocate.")
+                                        .ephemeral(true)
+                                })
                         })
-                    })
-                } else {
-                    0
+                        .await?;
+                    return Ok(());
                 }
-            });
+                advocate.map(|advocate| (advocate_id, advocate))
+            }
+            None => None,
+        };
+
+        let guardrails = GuardrailProfile::for_channel(
+            &ctx.database,
+            guild_id.as_deref(),
+            Some(&channel_id.to_string()),
+        )
+        .await?;
+        let blocked = std::iter::once((persona_id, &persona))
+            .chain(advocate.as_ref().map(|(id, advocate)| (*id, advocate)))
+            .find(|(id, _)| !guardrails.allows_persona(id));
+        if let Some((_, blocked)) = blocked {
+            let message = guardrails.blocked_persona_message(&blocked.name);
+            responder
+                .create_interaction_response(&serenity_ctx.http, |r| {
+                    r.kind(InteractionResponseType::ChannelMessageWithSource)
+                        .interaction_response_data(|m| m.content(message).ephemeral(true))
+                })
+                .await?;
+            return Ok(());
+        }
+
+        // Get max_paragraphs: per-request overrides channel default; 0 = no limit
+        let max_paragraphs = paragraphs.unwrap_or_else(|| {
+            if let Some(gid) = command.guild_id {
+                tokio::task::block_in_place(|| {
+                    tokio::runtime::Handle::current().block_on(async {
+                        ctx.database
+                            .get_channel_max_paragraphs(&gid.to_string(), &channel_id.to_string())
+                            .await
+                            .unwrap_or(0)
+                    })
+                })
+            } else {
+                0
+            }
+        });
 
         // Get system prompt for the persona with paragraph limit applied
-        let system_prompt = ctx.persona_manager.get_system_prompt(&persona_id, None);
+        let system_prompt = ctx.persona_manager.get_system_prompt(persona_id, None);
         let system_prompt = apply_paragraph_limit(&system_prompt, max_paragraphs);
+        let system_prompt = match command.guild_id {
+            Some(gid) => {
+                let theme = ctx
+                    .database
+                    .get_guild_setting(&gid.to_string(), ACTIVE_THEME_SETTING)
+                    .await?;
+                apply_theme(system_prompt, theme.as_deref())
+            }
+            None => system_prompt,
+        };
+        let disclosure =
+            disclosure::is_enabled(&ctx.database, guild_id.as_deref(), disclosure).await?;
+        let system_prompt = if disclosure {
+            with_disclosure_prompt(&system_prompt)
+        } else {
+            system_prompt
+        };
         debug!(
-            "[{request_id}] System prompt with paragraph limit | MaxParagraphs: {max_paragraphs}"
+            "[{request_id}] System prompt with paragraph limit | MaxParagraphs: {max_paragraphs} | Disclosure: {disclosure}"
         );
 
         // Defer the interaction (required for AI calls that may take time)
         info!("[{request_id}] Deferring interaction response");
-        command
+        responder
             .create_interaction_response(&serenity_ctx.http, |r| {
                 r.kind(InteractionResponseType::DeferredChannelMessageWithSource)
             })
@@ -149,19 +283,25 @@ impl AskHandler {
                     .unwrap_or_default();
 
                 let bot_id = serenity_ctx.http.get_current_user().await?.id;
-                messages
-                    .iter()
-                    .rev() // Oldest first
-                    .filter(|m| !m.content.is_empty())
-                    .map(|m| {
-                        let role = if m.author.id == bot_id {
-                            "assistant".to_string()
-                        } else {
-                            "user".to_string()
-                        };
-                        (role, m.content.clone())
-                    })
-                    .collect()
+                let mut history = ctx
+                    .database
+                    .get_fork_context(&channel_id.to_string(), 20)
+                    .await?;
+                history.extend(
+                    messages
+                        .iter()
+                        .rev() // Oldest first
+                        .filter(|m| !m.content.is_empty())
+                        .map(|m| {
+                            let role = if m.author.id == bot_id {
+                                "assistant".to_string()
+                            } else {
+                                "user".to_string()
+                            };
+                            (role, m.content.clone())
+                        }),
+                );
+                history
             } else {
                 debug!("[{request_id}] Fetching channel context from database");
                 ctx.database
@@ -179,21 +319,26 @@ impl AskHandler {
 
         // Log usage
         ctx.database
-            .log_usage(&user_id, "ask", Some(&persona_id))
+            .log_usage(&user_id, guild_id.as_deref(), "ask", Some(persona_id))
             .await?;
 
         // Get AI response
         info!("[{request_id}] Calling OpenAI API");
         let ai_response = ctx
-            .get_ai_response(
+            .get_ai_response_with_params(
                 &system_prompt,
-                &prompt,
+                prompt,
                 conversation_history,
                 request_id,
                 Some(&user_id),
                 guild_id.as_deref(),
                 Some(&channel_id.to_string()),
                 CostBucket::Ask,
+                verbosity::budgeted(
+                    ctx.persona_manager.model_params(persona_id, Some("ask")),
+                    "normal",
+                    max_paragraphs,
+                ),
             )
             .await;
 
@@ -206,51 +351,105 @@ impl AskHandler {
                     response.len()
                 );
 
-                // Build and send embed response
-                let chunks = chunk_for_embed(&response);
-                if chunks.len() > 1 {
-                    debug!("[{request_id}] Response split into {} chunks", chunks.len());
+                let (response, disclosed) = if disclosure {
+                    split_disclosure(&response)
+                } else {
+                    (response, None)
+                };
+                let mut embeds = answer_embeds(&persona, &response);
+                if let (Some(disclosed), Some((last, chars))) = (&disclosed, embeds.last_mut()) {
+                    let footer = disclosed.footer_text();
+                    *chars += footer.len();
+                    last.footer(|f| f.text(footer));
+                }
+
+                // Devil's advocate: the debate's rebuttal turn against the answer
+                if let Some((advocate_id, advocate)) = &advocate {
+                    let rebuttal_prompt = DebateOrchestrator::new()
+                        .rebuttal_prompt(advocate_id, &persona.name, prompt)
+                        .map(|p| apply_paragraph_limit(&p, max_paragraphs));
+                    let rebuttal = match rebuttal_prompt {
+                        Some(rebuttal_prompt) => {
+                            ctx.get_ai_response_with_params(
+                                &rebuttal_prompt,
+                                &rebuttal_message(&persona.name, prompt, &response),
+                                Vec::new(),
+                                request_id,
+                                Some(&user_id),
+                                guild_id.as_deref(),
+                                Some(&channel_id.to_string()),
+                                CostBucket::Ask,
+                                verbosity::budgeted(
+                                    ctx.persona_manager.model_params(advocate_id, Some("ask")),
+                                    "normal",
+                                    max_paragraphs,
+                                ),
+                            )
+                            .await
+                        }
+                        None => Err(anyhow::anyhow!("Unknown persona: {advocate_id}")),
+                    };
+                    match rebuttal {
+                        Ok(rebuttal) => {
+                            let mut rebuttal_embeds = answer_embeds(advocate, &rebuttal);
+                            if let Some((first, chars)) = rebuttal_embeds.first_mut() {
+                                first.title(DEVILS_ADVOCATE_TITLE);
+                                *chars += DEVILS_ADVOCATE_TITLE.len();
+                            }
+                            embeds.extend(rebuttal_embeds);
+                        }
+                        Err(e) => {
+                            warn!("[{request_id}] Devil's advocate rebuttal failed: {e}")
+                        }
+                    }
+                }
 
-                    if let Some(first_chunk) = chunks.first() {
-                        let embed = persona_embed(&persona, first_chunk);
+                let groups = pack_embeds(embeds);
+                debug!(
+                    "[{request_id}] Response sent in {} message(s)",
+                    groups.len()
+                );
+                for (i, group) in groups.into_iter().enumerate() {
+                    if i == 0 {
                         command
                             .edit_original_interaction_response(&serenity_ctx.http, |r| {
-                                r.set_embed(embed)
+                                r.set_embeds(group)
                             })
                             .await?;
+                    } else {
+                        command
+                            .create_followup_message(&serenity_ctx.http, |m| m.set_embeds(group))
+                            .await?;
                     }
-
-                    // Send remaining chunks as follow-ups
-                    for chunk in chunks.iter().skip(1) {
-                        if !chunk.trim().is_empty() {
-                            let embed = continuation_embed(&persona, chunk);
-                            command
-                                .create_followup_message(&serenity_ctx.http, |m| {
-                                    m.set_embed(embed)
-                                })
-                                .await?;
-                        }
-                    }
-                } else {
-                    let embed = persona_embed(&persona, &response);
-                    command
-                        .edit_original_interaction_response(&serenity_ctx.http, |r| {
-                            r.set_embed(embed)
-                        })
-                        .await?;
                 }
 
                 info!("[{request_id}] /ask response sent successfully");
+
+                if let Some(guild_id) = guild_id.as_deref() {
+                    if let Err(e) = achievements::check(
+                        &ctx.database,
+                        &serenity_ctx.http,
+                        guild_id,
+                        &user_id,
+                        Progress::default(),
+                    )
+                    .await
+                    {
+                        warn!("[{request_id}] Failed to check achievements: {e}");
+                    }
+                }
             }
             Err(e) => {
                 error!("[{request_id}] AI response failed: {e}");
+                let message = match e.downcast_ref::<CircuitOpenError>() {
+                    Some(open) => format!("🔌 {open}"),
+                    None => format!(
+                        "Sorry, I couldn't get a response from {}. Please try again.",
+                        persona.name
+                    ),
+                };
                 command
-                    .edit_original_interaction_response(&serenity_ctx.http, |r| {
-                        r.content(format!(
-                            "Sorry, I couldn't get a response from {}. Please try again.",
-                            persona.name
-                        ))
-                    })
+                    .edit_original_interaction_response(&serenity_ctx.http, |r| r.content(message))
                     .await?;
             }
         }
@@ -258,6 +457,138 @@ impl AskHandler {
         Ok(())
     }
 
+    /// Handle /ask_all command - the council's opening round, answered in place
+    async fn handle_ask_all(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        request_id: Uuid,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let prompt = get_string_option(&command.data.options, "prompt")
+            .ok_or_else(|| anyhow::anyhow!("Missing prompt argument"))?;
+
+        let mut persona_ids: Vec<String> = Vec::new();
+        for i in 1..=ASK_ALL_MAX_PERSONAS {
+            if let Some(persona_id) =
+                get_string_option(&command.data.options, &format!("persona{i}"))
+            {
+                if !persona_ids.contains(&persona_id) {
+                    persona_ids.push(persona_id);
+                }
+            }
+        }
+
+        let user_id = command.user.id.to_string();
+        info!("[{request_id}] /ask_all command | Personas: {persona_ids:?} | User: {user_id}");
+
+        let guild_id = command.guild_id.map(|id| id.to_string());
+        let channel_id = command.channel_id.to_string();
+        let guardrails =
+            GuardrailProfile::for_channel(&ctx.database, guild_id.as_deref(), Some(&channel_id))
+                .await?;
+        let error = if persona_ids.len() < 2 {
+            Some("Pick at least 2 different personas.".to_string())
+        } else {
+            persona_ids
+                .iter()
+                .find_map(|id| match ctx.persona_manager.get_persona(id) {
+                    None => Some(format!("Unknown persona: `{id}`")),
+                    Some(persona) if !guardrails.allows_persona(id) => {
+                        Some(guardrails.blocked_persona_message(&persona.name))
+                    }
+                    Some(_) => None,
+                })
+        };
+        if let Some(error) = error {
+            responder
+                .create_interaction_response(&serenity_ctx.http, |r| {
+                    r.kind(InteractionResponseType::ChannelMessageWithSource)
+                        .interaction_response_data(|m| m.content(error).ephemeral(true))
+                })
+                .await?;
+            return Ok(());
+        }
+
+        responder.defer(&serenity_ctx.http).await?;
+        ctx.database
+            .log_usage(&user_id, guild_id.as_deref(), "ask_all", None)
+            .await?;
+
+        let usage = TurnUsage {
+            user_id,
+            guild_id: guild_id.clone(),
+            channel_id: command.channel_id.to_string(),
+            request_id,
+            cost_bucket: CostBucket::Ask,
+        };
+
+        let responses = ctx
+            .council_generator(guild_id.as_deref(), &channel_id)
+            .await?
+            .opening_statements(&persona_ids, &prompt, &usage)
+            .await;
+
+        let mut embeds = Vec::new();
+        for (persona_id, response) in &responses {
+            let Some(persona) = ctx.persona_manager.get_persona_with_portrait(persona_id) else {
+                continue;
+            };
+            embeds.extend(answer_embeds(&persona, response));
+        }
+
+        for group in pack_embeds(embeds) {
+            responder
+                .create_interaction_response(&serenity_ctx.http, |r| {
+                    r.interaction_response_data(|m| m.set_embeds(group))
+                })
+                .await?;
+        }
+
+        info!(
+            "[{request_id}] /ask_all response sent for {} personas",
+            responses.len()
+        );
+        Ok(())
+    }
+}
+
+/// A persona's answer as embeds (with their text length), continuing long answers
+fn answer_embeds(persona: &Persona, response: &str) -> Vec<(CreateEmbed, usize)> {
+    let mut embeds = Vec::new();
+    for (i, chunk) in chunk_for_embed(response).iter().enumerate() {
+        if i == 0 {
+            embeds.push((
+                persona_embed(persona, chunk),
+                chunk.len() + persona.name.len(),
+            ));
+        } else if !chunk.trim().is_empty() {
+            embeds.push((continuation_embed(persona, chunk), chunk.len()));
+        }
+    }
+    embeds
+}
+
+/// Group embeds (with their text length) into messages within Discord's per-message limits
+fn pack_embeds(embeds: Vec<(CreateEmbed, usize)>) -> Vec<Vec<CreateEmbed>> {
+    let mut groups: Vec<Vec<CreateEmbed>> = Vec::new();
+    let mut current = Vec::new();
+    let mut current_chars = 0;
+    for (embed, chars) in embeds {
+        if !current.is_empty()
+            && (current_chars + chars > MESSAGE_EMBED_CHARS || current.len() == MESSAGE_EMBEDS)
+        {
+            groups.push(std::mem::take(&mut current));
+            current_chars = 0;
+        }
+        current.push(embed);
+        current_chars += chars;
+    }
+    if !current.is_empty() {
+        groups.push(current);
+    }
+    groups
 }
 
 #[cfg(test)]
@@ -270,6 +601,54 @@ mod tests {
         let names = handler.command_names();
 
         assert!(names.contains(&"ask"));
-        assert_eq!(names.len(), 1);
+        assert!(names.contains(&"ask_all"));
+        assert_eq!(names.len(), 2);
+    }
+
+    #[test]
+    fn test_answer_embeds_continue_long_answers() {
+        let persona = crate::features::personas::PersonaManager::new()
+            .get_persona("obi")
+            .unwrap()
+            .clone();
+
+        let description =
+            |embed: &CreateEmbed| embed.0["description"].as_str().unwrap().to_string();
+
+        let short = answer_embeds(&persona, "Hello there.");
+        assert_eq!(short.len(), 1);
+        assert_eq!(short[0].0 .0["author"]["name"], "Obi-Wan");
+        assert_eq!(description(&short[0].0), "Hello there.");
+        // The persona name in the author line counts toward the message limit
+        assert_eq!(short[0].1, 19);
+
+        let answer = "The Force. ".repeat(600);
+        let long = answer_embeds(&persona, &answer);
+        assert_eq!(long.len(), 2);
+        assert_eq!(long[0].0 .0["author"]["name"], "Obi-Wan");
+        assert!(!long[1].0 .0.contains_key("author"));
+        assert_eq!(long[1].1, description(&long[1].0).len());
+        // Nothing is lost between the first embed and its continuation
+        let rejoined: Vec<String> = long.iter().map(|(embed, _)| description(embed)).collect();
+        assert_eq!(rejoined.join(" "), answer.trim_end());
+    }
+
+    #[test]
+    fn test_pack_embeds_respects_message_limits() {
+        let sized = |sizes: &[usize]| -> Vec<usize> {
+            let embeds = sizes
+                .iter()
+                .map(|&chars| (CreateEmbed::default(), chars))
+                .collect();
+            pack_embeds(embeds).iter().map(Vec::len).collect()
+        };
+
+        // Three short answers fit in one reply
+        assert_eq!(sized(&[800, 900, 1200]), vec![3]);
+        // Long answers spill into followups
+        assert_eq!(sized(&[4000, 1500, 4000, 300]), vec![2, 2]);
+        // An oversized embed still gets its own message
+        assert_eq!(sized(&[7000, 10]), vec![1, 1]);
+        assert_eq!(sized(&[1; 12]), vec![10, 2]);
     }
 }
@@ -0,0 +1,220 @@
+//! Auto-response command handler
+//!
+//! Handles: autoresponse
+//!
+//! Members with Manage Server add, list, remove, toggle and reorder the
+//! guild's triggers. Matching messages are answered by the message handler.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use serenity::model::application::interaction::application_command::{
+    ApplicationCommandInteraction, CommandDataOption,
+};
+use serenity::prelude::Context;
+use std::sync::Arc;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::{get_integer_option, get_string_option};
+use crate::database::AutoResponse;
+use crate::features::autoresponse::{
+    list_line, validate_pattern, validate_response, MatchKind, ResponseKind, AUTORESPONSE_FEATURE,
+    DEFAULT_COOLDOWN_SECS, MAX_COOLDOWN_SECS, MAX_TRIGGERS_PER_GUILD,
+};
+
+/// Handler for /autoresponse
+pub struct AutoResponseHandler;
+
+#[async_trait]
+impl SlashCommandHandler for AutoResponseHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["autoresponse"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let Some(guild_id) = command.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        let can_manage = command
+            .member
+            .as_ref()
+            .and_then(|m| m.permissions)
+            .is_some_and(|p| p.manage_guild());
+        if !can_manage {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "❌ You need the Manage Server permission to manage auto-responses.",
+                )
+                .await;
+        }
+        if !ctx
+            .database
+            .is_feature_enabled(AUTORESPONSE_FEATURE, None, Some(&guild_id))
+            .await?
+        {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "❌ Auto-responses are disabled on this server.",
+                )
+                .await;
+        }
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+        let user_id = command.user.id.to_string();
+        let options = &subcommand.options;
+
+        let content = match subcommand.name.as_str() {
+            "add" => Self::handle_add(&ctx, &guild_id, &user_id, options).await?,
+            "list" => Self::handle_list(&ctx, &guild_id).await?,
+            "remove" => {
+                let id = get_integer_option(options, "id").unwrap_or(0);
+                if ctx.database.remove_autoresponse(&guild_id, id).await? {
+                    info!("/autoresponse remove | User: {user_id} | Guild: {guild_id} | #{id}");
+                    format!("🗑️ Removed trigger `#{id}`.")
+                } else {
+                    Self::not_found(id)
+                }
+            }
+            "enable" | "disable" => {
+                let id = get_integer_option(options, "id").unwrap_or(0);
+                let enabled = subcommand.name == "enable";
+                if ctx
+                    .database
+                    .set_autoresponse_enabled(&guild_id, id, enabled)
+                    .await?
+                {
+                    info!(
+                        "/autoresponse {} | User: {user_id} | Guild: {guild_id} | #{id}",
+                        subcommand.name
+                    );
+                    format!(
+                        "✅ Trigger `#{id}` is {}.",
+                        if enabled { "on" } else { "off" }
+                    )
+                } else {
+                    Self::not_found(id)
+                }
+            }
+            _ => {
+                let id = get_integer_option(options, "id").unwrap_or(0);
+                let priority = get_integer_option(options, "value").unwrap_or(0);
+                if ctx
+                    .database
+                    .set_autoresponse_priority(&guild_id, id, priority)
+                    .await?
+                {
+                    format!("✅ Trigger `#{id}` now has priority **{priority}**.")
+                } else {
+                    Self::not_found(id)
+                }
+            }
+        };
+        responder.reply_ephemeral(serenity_ctx, &content).await
+    }
+}
+
+impl AutoResponseHandler {
+    /// Handle /autoresponse add, returning the reply
+    async fn handle_add(
+        ctx: &CommandContext,
+        guild_id: &str,
+        user_id: &str,
+        options: &[CommandDataOption],
+    ) -> Result<String> {
+        let match_kind = MatchKind::from_setting(get_string_option(options, "match").as_deref());
+        let response_kind =
+            ResponseKind::from_setting(get_string_option(options, "mode").as_deref());
+        let pattern = match validate_pattern(
+            &get_string_option(options, "pattern").unwrap_or_default(),
+            match_kind,
+        ) {
+            Ok(pattern) => pattern,
+            Err(e) => return Ok(format!("❌ {e}")),
+        };
+        let response =
+            match validate_response(&get_string_option(options, "response").unwrap_or_default()) {
+                Ok(response) => response,
+                Err(e) => return Ok(format!("❌ {e}")),
+            };
+        if ctx.database.count_autoresponses(guild_id).await? >= MAX_TRIGGERS_PER_GUILD {
+            return Ok(format!(
+                "❌ This server already has {MAX_TRIGGERS_PER_GUILD} triggers. Remove one first."
+            ));
+        }
+
+        let mut trigger = AutoResponse {
+            id: 0,
+            guild_id: guild_id.to_string(),
+            pattern,
+            match_kind: match_kind.as_str().to_string(),
+            response,
+            response_kind: response_kind.as_str().to_string(),
+            cooldown_secs: get_integer_option(options, "cooldown_seconds")
+                .unwrap_or(DEFAULT_COOLDOWN_SECS)
+                .clamp(0, MAX_COOLDOWN_SECS),
+            enabled: true,
+            priority: get_integer_option(options, "priority").unwrap_or(0),
+            created_by: user_id.to_string(),
+        };
+        trigger.id = ctx.database.add_autoresponse(&trigger).await?;
+        info!(
+            "/autoresponse add | User: {user_id} | Guild: {guild_id} | #{} {} {}",
+            trigger.id, trigger.match_kind, trigger.response_kind
+        );
+        Ok(format!("✅ Added trigger:\n{}", list_line(&trigger)))
+    }
+
+    /// Handle /autoresponse list, returning the reply
+    async fn handle_list(ctx: &CommandContext, guild_id: &str) -> Result<String> {
+        let triggers = ctx.database.get_autoresponses(guild_id).await?;
+        if triggers.is_empty() {
+            return Ok("No auto-responses yet. Add one with `/autoresponse add`.".to_string());
+        }
+        let mut content = "🤖 **Auto-responses** (checked top to bottom)".to_string();
+        for (shown, trigger) in triggers.iter().enumerate() {
+            let line = list_line(trigger);
+            // Leave room for the overflow note within Discord's 2000 characters
+            if content.chars().count() + line.chars().count() > 1950 {
+                content.push_str(&format!("\n…and {} more", triggers.len() - shown));
+                break;
+            }
+            content.push('\n');
+            content.push_str(&line);
+        }
+        Ok(content)
+    }
+
+    fn not_found(id: i64) -> String {
+        format!("❌ There's no trigger `#{id}` on this server.")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_autoresponse_handler_commands() {
+        let handler = AutoResponseHandler;
+        assert_eq!(handler.command_names(), &["autoresponse"]);
+    }
+}
@@ -0,0 +1,359 @@
+//! Birthday command handler
+//!
+//! Handles: birthday
+//!
+//! Members save their own birthday and anniversary with a time zone, list
+//! what's coming up in the server, and opt out of public wishes. Wishes are
+//! posted by the birthday scheduler in the channel set with
+//! `/set_channel birthdays`.
+//!
+//! - **Version**: 1.1.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.1.0: Dates are saved with an IANA zone, defaulting to the member's /time zone
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{NaiveDate, Utc};
+use log::info;
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::prelude::Context;
+use std::sync::Arc;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::{get_bool_option, get_string_option};
+use crate::core::Zone;
+use crate::database::{Celebration, CelebrationKind};
+use crate::features::birthdays::{days_until, format_date, parse_date, CHANNEL_SETTING};
+use crate::features::timezones::member_zone;
+
+/// Dates shown by /birthday list
+const MAX_LISTED: usize = 15;
+
+/// Handler for /birthday command
+pub struct BirthdayHandler;
+
+#[async_trait]
+impl SlashCommandHandler for BirthdayHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["birthday"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        self.handle_birthday(&ctx, serenity_ctx, command).await
+    }
+}
+
+impl BirthdayHandler {
+    /// Handle /birthday set, remove, list and wishes
+    async fn handle_birthday(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let user_id = command.user.id.to_string();
+        let Some(guild_id) = command.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+
+        let content = if !ctx
+            .database
+            .is_feature_enabled("birthdays", None, Some(&guild_id))
+            .await?
+        {
+            "❌ Birthday wishes are disabled on this server.".to_string()
+        } else {
+            match subcommand.name.as_str() {
+                "set" => {
+                    let kind = get_string_option(&subcommand.options, "kind")
+                        .and_then(|k| CelebrationKind::from_key(&k))
+                        .unwrap_or(CelebrationKind::Birthday);
+                    let date = get_string_option(&subcommand.options, "date")
+                        .ok_or_else(|| anyhow::anyhow!("Missing date parameter"))?;
+                    let timezone = get_string_option(&subcommand.options, "timezone");
+                    let member_zone = member_zone(&ctx.database, &user_id).await?;
+                    match Self::parse_celebration(
+                        &guild_id,
+                        &user_id,
+                        kind,
+                        &date,
+                        timezone,
+                        member_zone,
+                    ) {
+                        Ok(celebration) => {
+                            ctx.database.set_celebration(&celebration).await?;
+                            info!(
+                                "/birthday set | User: {user_id} | Guild: {guild_id} | {} {date}",
+                                kind.key()
+                            );
+                            Self::format_saved(&celebration)
+                        }
+                        Err(message) => format!("❌ {message}"),
+                    }
+                }
+                "remove" => {
+                    let kind = get_string_option(&subcommand.options, "kind")
+                        .and_then(|k| CelebrationKind::from_key(&k));
+                    if ctx
+                        .database
+                        .remove_celebration(&guild_id, &user_id, kind)
+                        .await?
+                    {
+                        "🗑️ Removed. I won't celebrate that date here anymore.".to_string()
+                    } else {
+                        "You don't have that date saved in this server.".to_string()
+                    }
+                }
+                "wishes" => {
+                    let enabled = get_bool_option(&subcommand.options, "enabled").unwrap_or(true);
+                    ctx.database
+                        .set_celebration_opt_out(&guild_id, &user_id, !enabled)
+                        .await?;
+                    if enabled {
+                        "🎉 You'll be wished on your saved dates in this server.".to_string()
+                    } else {
+                        "🔕 Opted out. Your dates are kept but I won't post wishes for you here."
+                            .to_string()
+                    }
+                }
+                _ => {
+                    let celebrations = ctx.database.get_guild_celebrations(&guild_id).await?;
+                    let channel = ctx
+                        .database
+                        .get_guild_setting(&guild_id, CHANNEL_SETTING)
+                        .await?;
+                    Self::format_upcoming(&celebrations, Utc::now().date_naive(), channel)
+                }
+            }
+        };
+
+        responder
+            .create_interaction_response(&serenity_ctx.http, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|m| m.content(content).ephemeral(true))
+            })
+            .await?;
+        Ok(())
+    }
+
+    fn parse_celebration(
+        guild_id: &str,
+        user_id: &str,
+        kind: CelebrationKind,
+        date: &str,
+        timezone: Option<String>,
+        member_zone: Option<Zone>,
+    ) -> Result<Celebration, &'static str> {
+        let (month, day, year) = parse_date(date)?;
+        let timezone = timezone
+            .as_deref()
+            .map(Zone::parse)
+            .transpose()?
+            .or(member_zone)
+            .unwrap_or(Zone::Fixed(0));
+        Ok(Celebration {
+            guild_id: guild_id.to_string(),
+            user_id: user_id.to_string(),
+            kind,
+            month,
+            day,
+            // Birth years aren't needed and aren't kept
+            year: year.filter(|_| kind == CelebrationKind::Anniversary),
+            timezone,
+            last_wished_year: None,
+        })
+    }
+
+    fn format_saved(celebration: &Celebration) -> String {
+        let local_today = celebration.timezone.local_time(Utc::now()).date();
+        let days = days_until(celebration.month, celebration.day, local_today);
+        format!(
+            "✅ Saved your {} as **{}** ({}). {}",
+            celebration.kind.key(),
+            format_date(celebration.month, celebration.day),
+            celebration.timezone,
+            match days {
+                0 => "That's today! 🎉".to_string(),
+                1 => "That's tomorrow!".to_string(),
+                n => format!("That's in {n} days."),
+            }
+        )
+    }
+
+    /// Dates ordered by how soon they come up, nearest first
+    fn format_upcoming(
+        celebrations: &[Celebration],
+        today: NaiveDate,
+        channel: Option<String>,
+    ) -> String {
+        let mut output = "🎂 **Upcoming birthdays & anniversaries**\n".to_string();
+        if celebrations.is_empty() {
+            output.push_str("Nobody has saved a date yet. Use `/birthday set` to add yours!\n");
+        }
+
+        let mut upcoming: Vec<(i64, &Celebration)> = celebrations
+            .iter()
+            .map(|c| (days_until(c.month, c.day, today), c))
+            .collect();
+        upcoming.sort_by_key(|(days, _)| *days);
+        for (days, celebration) in upcoming.iter().take(MAX_LISTED) {
+            let icon = match celebration.kind {
+                CelebrationKind::Birthday => "🎂",
+                CelebrationKind::Anniversary => "🎉",
+            };
+            let when = match days {
+                0 => "today".to_string(),
+                1 => "tomorrow".to_string(),
+                n => format!("in {n} days"),
+            };
+            output.push_str(&format!(
+                "{icon} **{}** ({when}) <@{}>\n",
+                format_date(celebration.month, celebration.day),
+                celebration.user_id
+            ));
+        }
+        if upcoming.len() > MAX_LISTED {
+            output.push_str(&format!("…and {} more\n", upcoming.len() - MAX_LISTED));
+        }
+
+        match channel {
+            Some(channel_id) => {
+                output.push_str(&format!("\nWishes are posted in <#{channel_id}>."))
+            }
+            None => output.push_str(
+                "\nNo birthday channel is set yet. An admin can pick one with \
+                 `/set_channel setting:birthdays value:enabled`.",
+            ),
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_birthday_handler_commands() {
+        let handler = BirthdayHandler;
+        assert_eq!(handler.command_names(), &["birthday"]);
+    }
+
+    #[test]
+    fn test_parse_celebration_drops_birth_year() {
+        let birthday = BirthdayHandler::parse_celebration(
+            "g1",
+            "u1",
+            CelebrationKind::Birthday,
+            "1990-03-14",
+            Some("UTC+2".to_string()),
+            None,
+        )
+        .unwrap();
+        assert_eq!((birthday.month, birthday.day), (3, 14));
+        assert_eq!(birthday.year, None);
+        assert_eq!(birthday.timezone, Zone::Fixed(120));
+
+        let anniversary = BirthdayHandler::parse_celebration(
+            "g1",
+            "u1",
+            CelebrationKind::Anniversary,
+            "2019-06-01",
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(anniversary.year, Some(2019));
+        assert_eq!(anniversary.timezone, Zone::Fixed(0));
+
+        assert!(BirthdayHandler::parse_celebration(
+            "g1",
+            "u1",
+            CelebrationKind::Birthday,
+            "03-14",
+            Some("Mars/Olympus".to_string()),
+            None,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_celebration_zone_defaults_to_time_zone() {
+        let berlin = Zone::Named(chrono_tz::Tz::Europe__Berlin);
+        let saved = BirthdayHandler::parse_celebration(
+            "g1",
+            "u1",
+            CelebrationKind::Birthday,
+            "03-14",
+            None,
+            Some(berlin),
+        )
+        .unwrap();
+        assert_eq!(saved.timezone, berlin);
+
+        let given = BirthdayHandler::parse_celebration(
+            "g1",
+            "u1",
+            CelebrationKind::Birthday,
+            "03-14",
+            Some("america/new_york".to_string()),
+            Some(berlin),
+        )
+        .unwrap();
+        assert_eq!(
+            given.timezone,
+            Zone::Named(chrono_tz::Tz::America__New_York)
+        );
+    }
+
+    #[test]
+    fn test_format_upcoming_orders_by_next_date() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+        let entry = |user: &str, month, day| Celebration {
+            guild_id: "g1".to_string(),
+            user_id: user.to_string(),
+            kind: CelebrationKind::Birthday,
+            month,
+            day,
+            year: None,
+            timezone: Zone::Fixed(0),
+            last_wished_year: None,
+        };
+        let output = BirthdayHandler::format_upcoming(
+            &[
+                entry("jan", 1, 2),
+                entry("oct", 10, 16),
+                entry("today", 10, 15),
+            ],
+            today,
+            Some("42".to_string()),
+        );
+        let today_at = output.find("<@today>").unwrap();
+        let oct_at = output.find("<@oct>").unwrap();
+        let jan_at = output.find("<@jan>").unwrap();
+        assert!(today_at < oct_at && oct_at < jan_at);
+        assert!(output.contains("**October 16** (tomorrow)"));
+        assert!(output.contains("Wishes are posted in <#42>."));
+
+        let empty = BirthdayHandler::format_upcoming(&[], today, None);
+        assert!(empty.contains("Nobody has saved a date yet"));
+        assert!(empty.contains("No birthday channel is set yet"));
+    }
+}
@@ -0,0 +1,291 @@
+//! Channel topic command handler
+//!
+//! Handles: channel_topic
+//!
+//! Members with Manage Channels schedule a channel's topic to rotate through
+//! their own list or to show an AI-written question of the day, stop it, or
+//! list the server's schedules. Updates are applied by the channel topic
+//! scheduler (see `features::channel_topics`).
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use serenity::model::application::interaction::application_command::{
+    ApplicationCommandInteraction, CommandDataOption,
+};
+use serenity::model::id::ChannelId;
+use serenity::prelude::Context;
+use std::sync::Arc;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::{get_channel_option, get_integer_option, get_string_option};
+use crate::core::parse_utc_offset;
+use crate::database::ChannelTopicSchedule;
+use crate::features::channel_topics::{
+    first_run_at, parse_topics, TopicMode, CHANNEL_TOPICS_FEATURE, DEFAULT_INTERVAL_HOURS,
+    MAX_INTERVAL_HOURS,
+};
+
+/// Handler for /channel_topic
+pub struct ChannelTopicHandler;
+
+#[async_trait]
+impl SlashCommandHandler for ChannelTopicHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["channel_topic"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        self.handle_channel_topic(&ctx, serenity_ctx, command).await
+    }
+}
+
+impl ChannelTopicHandler {
+    /// Handle /channel_topic schedule, stop and list
+    async fn handle_channel_topic(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let user_id = command.user.id.to_string();
+        let Some(guild_id) = command.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        let can_manage = command
+            .member
+            .as_ref()
+            .and_then(|m| m.permissions)
+            .is_some_and(|p| p.manage_channels());
+        if !can_manage {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "❌ You need the Manage Channels permission to schedule channel topics.",
+                )
+                .await;
+        }
+        if !ctx
+            .database
+            .is_feature_enabled(CHANNEL_TOPICS_FEATURE, None, Some(&guild_id))
+            .await?
+        {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "❌ Scheduled channel topics are disabled on this server.",
+                )
+                .await;
+        }
+
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+        let channel_id = get_channel_option(&subcommand.options, "channel")
+            .map(ChannelId)
+            .unwrap_or(command.channel_id);
+
+        let content = match subcommand.name.as_str() {
+            "schedule" => {
+                if !Self::bot_can_manage(serenity_ctx, channel_id) {
+                    format!(
+                        "❌ I need the Manage Channels permission in <#{channel_id}> to change its topic."
+                    )
+                } else {
+                    let now = chrono::Utc::now();
+                    match Self::parse_schedule(
+                        &subcommand.options,
+                        &guild_id,
+                        channel_id,
+                        &user_id,
+                        now,
+                    ) {
+                        Ok(schedule) => {
+                            ctx.database.set_channel_topic_schedule(&schedule).await?;
+                            info!(
+                                "/channel_topic schedule | User: {user_id} | Guild: {guild_id} | Channel: {channel_id} | {} every {}h",
+                                schedule.mode, schedule.interval_hours
+                            );
+                            format!("✅ {}", Self::describe(&schedule))
+                        }
+                        Err(message) => format!("❌ {message}"),
+                    }
+                }
+            }
+            "stop" => {
+                if ctx
+                    .database
+                    .delete_channel_topic_schedule(&guild_id, &channel_id.to_string())
+                    .await?
+                {
+                    info!("/channel_topic stop | User: {user_id} | Guild: {guild_id} | Channel: {channel_id}");
+                    format!("✅ Stopped scheduled topic updates in <#{channel_id}>. Its current topic stays.")
+                } else {
+                    format!("<#{channel_id}> has no scheduled topic updates.")
+                }
+            }
+            _ => {
+                let schedules = ctx.database.get_channel_topic_schedules(&guild_id).await?;
+                if schedules.is_empty() {
+                    "No channel topics are scheduled here. Start one with `/channel_topic schedule`."
+                        .to_string()
+                } else {
+                    schedules
+                        .iter()
+                        .map(|s| format!("- {}", Self::describe(s)))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                }
+            }
+        };
+
+        responder.reply_ephemeral(serenity_ctx, &content).await
+    }
+
+    /// Whether the bot may edit `channel_id`; assumed yes when the channel isn't cached
+    fn bot_can_manage(serenity_ctx: &Context, channel_id: ChannelId) -> bool {
+        let Some(channel) = serenity_ctx.cache.guild_channel(channel_id) else {
+            return true;
+        };
+        channel
+            .permissions_for_user(&serenity_ctx.cache, serenity_ctx.cache.current_user_id())
+            .map_or(true, |p| p.manage_channels())
+    }
+
+    /// Validate `/channel_topic schedule` options into a new schedule for `channel_id`
+    fn parse_schedule(
+        options: &[CommandDataOption],
+        guild_id: &str,
+        channel_id: ChannelId,
+        user_id: &str,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<ChannelTopicSchedule, &'static str> {
+        let mode = get_string_option(options, "mode")
+            .as_deref()
+            .and_then(TopicMode::from_setting)
+            .ok_or("Pick a mode")?;
+        let topics = match mode {
+            TopicMode::Rotate => parse_topics(
+                get_string_option(options, "topics")
+                    .as_deref()
+                    .ok_or("Rotate mode needs `topics`, separated by `|`.")?,
+            )?,
+            TopicMode::Question => Vec::new(),
+        };
+        let theme = match mode {
+            TopicMode::Question => get_string_option(options, "theme")
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty()),
+            TopicMode::Rotate => None,
+        };
+        let interval_hours =
+            get_integer_option(options, "every_hours").unwrap_or(DEFAULT_INTERVAL_HOURS);
+        if !(1..=MAX_INTERVAL_HOURS).contains(&interval_hours) {
+            return Err("Updates must be between 1 and 168 hours apart.");
+        }
+        let hour = get_integer_option(options, "hour");
+        if hour.is_some_and(|h| !(0..=23).contains(&h)) {
+            return Err("The hour must be between 0 and 23");
+        }
+        let utc_offset_minutes = get_string_option(options, "timezone")
+            .as_deref()
+            .map(parse_utc_offset)
+            .transpose()?
+            .unwrap_or(0);
+
+        Ok(ChannelTopicSchedule {
+            guild_id: guild_id.to_string(),
+            channel_id: channel_id.to_string(),
+            mode: mode.as_str().to_string(),
+            topics,
+            theme,
+            interval_hours,
+            next_run_at: first_run_at(now, hour.map(|h| h as u32), utc_offset_minutes),
+            next_index: 0,
+            last_topic: None,
+            created_by: user_id.to_string(),
+        })
+    }
+
+    /// One-line summary of a schedule
+    fn describe(schedule: &ChannelTopicSchedule) -> String {
+        let every = match schedule.interval_hours {
+            1 => "every hour".to_string(),
+            24 => "every day".to_string(),
+            168 => "every week".to_string(),
+            hours => format!("every {hours} hours"),
+        };
+        let what = match TopicMode::from_setting(&schedule.mode) {
+            Some(TopicMode::Rotate) => {
+                format!("rotates through {} topics", schedule.topics.len())
+            }
+            _ => match &schedule.theme {
+                Some(theme) => format!("gets a new question of the day about {theme}"),
+                None => "gets a new question of the day".to_string(),
+            },
+        };
+        format!(
+            "<#{}> {what} {every}, next <t:{}:R>.",
+            schedule.channel_id, schedule.next_run_at
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(
+        mode: &str,
+        topics: usize,
+        theme: Option<&str>,
+        hours: i64,
+    ) -> ChannelTopicSchedule {
+        ChannelTopicSchedule {
+            guild_id: "1".to_string(),
+            channel_id: "42".to_string(),
+            mode: mode.to_string(),
+            topics: vec!["t".to_string(); topics],
+            theme: theme.map(str::to_string),
+            interval_hours: hours,
+            next_run_at: 1_700_000_000,
+            next_index: 0,
+            last_topic: None,
+            created_by: "2".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_channel_topic_handler_commands() {
+        let handler = ChannelTopicHandler;
+        assert_eq!(handler.command_names(), &["channel_topic"]);
+    }
+
+    #[test]
+    fn test_describe_schedule() {
+        assert_eq!(
+            ChannelTopicHandler::describe(&schedule("rotate", 3, None, 24)),
+            "<#42> rotates through 3 topics every day, next <t:1700000000:R>."
+        );
+        assert_eq!(
+            ChannelTopicHandler::describe(&schedule("question", 0, Some("books"), 12)),
+            "<#42> gets a new question of the day about books every 12 hours, next <t:1700000000:R>."
+        );
+    }
+}
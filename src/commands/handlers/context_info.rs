@@ -2,10 +2,17 @@
 //!
 //! Handles: context
 //!
-//! - **Version**: 1.0.0
+//! Shows what the next prompt would contain for this channel: the system
+//! prompt size, each history message, any council summary and the text
+//! attachments a file question would pull in.
+//!
+//! - **Version**: 1.3.0
 //! - **Since**: 4.4.0
 //!
 //! ## Changelog
+//! - 1.3.0: Lists every history message, council summary and thread attachment with token estimates
+//! - 1.2.0: Active persona honours category defaults
+//! - 1.1.0: Initial responses go through InteractionResponder (auto-defer safe)
 //! - 1.0.0: Initial implementation
 
 use anyhow::Result;
@@ -13,12 +20,16 @@ use async_trait::async_trait;
 use log::{debug, info};
 use serenity::builder::GetMessages;
 use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
-use serenity::model::application::interaction::InteractionResponseType;
 use serenity::prelude::Context;
 use std::sync::Arc;
 
-use crate::commands::context::{is_in_thread_channel, CommandContext};
+use crate::commands::context::{channel_category_id, is_in_thread_channel, CommandContext};
 use crate::commands::handler::SlashCommandHandler;
+use crate::commands::pagination::{PaginatedEmbed, PAGE_CHARS};
+use crate::commands::responder::InteractionResponder;
+use crate::commands::CommandHandler;
+use crate::core::chunk_text;
+use crate::features::council::get_active_councils;
 
 /// Approximate characters per token (standard GPT heuristic)
 const CHARS_PER_TOKEN: usize = 4;
@@ -29,6 +40,15 @@ const OVERHEAD_TOKENS: usize = 50;
 /// Default context limit for mentions and DMs
 const DEFAULT_MENTION_CONTEXT_LIMIT: i64 = 40;
 
+/// Recent thread messages scanned for text attachments (matches the mention path)
+const THREAD_ATTACHMENT_SCAN: usize = 20;
+
+/// Characters of each history message shown in the listing
+const MESSAGE_PREVIEW_CHARS: usize = 600;
+
+/// History messages per page
+const HISTORY_PER_PAGE: usize = 5;
+
 pub struct ContextInfoHandler;
 
 #[async_trait]
@@ -54,6 +74,7 @@ impl ContextInfoHandler {
         serenity_ctx: &Context,
         command: &ApplicationCommandInteraction,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let user_id = command.user.id.to_string();
         let channel_id = command.channel_id;
         let guild_id = command.guild_id.map(|id| id.to_string());
@@ -74,8 +95,14 @@ impl ContextInfoHandler {
 
         // Resolve active persona
         let persona_id = if let Some(ref gid) = guild_id {
+            let category_id = channel_category_id(serenity_ctx, channel_id).await;
             ctx.database
-                .get_persona_with_channel(&user_id, gid, &channel_id.to_string())
+                .get_persona_with_channel(
+                    &user_id,
+                    gid,
+                    &channel_id.to_string(),
+                    category_id.as_deref(),
+                )
                 .await?
         } else {
             ctx.database
@@ -83,10 +110,19 @@ impl ContextInfoHandler {
                 .await?
         };
 
-        // Get system prompt
-        let system_prompt = ctx.persona_manager.get_system_prompt(&persona_id, None);
+        // Get system prompt the same way the chat paths do (verbosity applies in guilds)
+        let system_prompt = if let Some(ref gid) = guild_id {
+            let verbosity = ctx
+                .database
+                .get_channel_verbosity(gid, &channel_id.to_string())
+                .await?;
+            ctx.persona_manager
+                .get_system_prompt_with_verbosity(&persona_id, None, &verbosity)
+        } else {
+            ctx.persona_manager.get_system_prompt(&persona_id, None)
+        };
         let system_prompt_chars = system_prompt.len();
-        let system_prompt_tokens = system_prompt_chars / CHARS_PER_TOKEN;
+        let system_prompt_tokens = estimate_tokens(&system_prompt);
 
         // Get context limit setting
         let context_limit = if let Some(ref gid) = guild_id {
@@ -99,31 +135,64 @@ impl ContextInfoHandler {
             DEFAULT_MENTION_CONTEXT_LIMIT
         };
 
-        // Fetch conversation history
-        let (history, context_source) = if in_thread {
+        // Council threads answer follow-ups from the council summary instead of history
+        let council_summary = if in_thread {
+            get_active_councils()
+                .get(&channel_id.0)
+                .map(|state| state.get_context_summary())
+        } else {
+            None
+        };
+
+        // Fetch conversation history and any text attachments in the thread
+        let mut attachments: Vec<(String, u64)> = Vec::new();
+        let (history, context_source) = if council_summary.is_some() {
+            (Vec::new(), "Council Summary")
+        } else if in_thread {
+            let mut history = ctx
+                .database
+                .get_fork_context(&channel_id.to_string(), context_limit)
+                .await
+                .unwrap_or_default();
+            let forked = !history.is_empty();
+
             let messages = channel_id
                 .messages(&serenity_ctx.http, |builder: &mut GetMessages| {
-                    builder.limit(context_limit.min(100) as u64)
+                    builder.limit(context_limit.clamp(1, 100) as u64)
                 })
                 .await
                 .unwrap_or_default();
 
             let bot_id = serenity_ctx.http.get_current_user().await?.id;
-            let history: Vec<(String, String)> = messages
+            history.extend(
+                messages
+                    .iter()
+                    .rev()
+                    .filter(|m| !m.content.is_empty())
+                    .map(|m| {
+                        let role = if m.author.id == bot_id {
+                            "assistant".to_string()
+                        } else {
+                            "user".to_string()
+                        };
+                        (role, m.content.clone())
+                    }),
+            );
+
+            attachments = messages
                 .iter()
-                .rev()
-                .filter(|m| !m.content.is_empty())
-                .map(|m| {
-                    let role = if m.author.id == bot_id {
-                        "assistant".to_string()
-                    } else {
-                        "user".to_string()
-                    };
-                    (role, m.content.clone())
-                })
+                .take(THREAD_ATTACHMENT_SCAN)
+                .flat_map(|m| m.attachments.iter())
+                .filter(|a| CommandHandler::is_text_attachment(&a.filename))
+                .map(|a| (a.filename.clone(), a.size))
                 .collect();
 
-            (history, "Discord Thread")
+            let source = if forked {
+                "Forked Context + Discord Thread"
+            } else {
+                "Discord Thread"
+            };
+            (history, source)
         } else {
             let history = ctx
                 .database
@@ -141,7 +210,8 @@ impl ContextInfoHandler {
         let message_count = history.len();
         let history_chars: usize = history.iter().map(|(_, content)| content.len()).sum();
         let history_tokens = history_chars / CHARS_PER_TOKEN;
-        let total_tokens = system_prompt_tokens + history_tokens + OVERHEAD_TOKENS;
+        let summary_tokens = council_summary.as_deref().map(estimate_tokens).unwrap_or(0);
+        let total_tokens = system_prompt_tokens + history_tokens + summary_tokens + OVERHEAD_TOKENS;
 
         debug!(
             "/context | Persona: {persona_id} | Messages: {message_count} | Total tokens: ~{total_tokens}"
@@ -156,19 +226,35 @@ impl ContextInfoHandler {
 
         // Get persona info for embed styling
         let persona = ctx.persona_manager.get_persona_with_portrait(&persona_id);
-        let (persona_name, persona_color, portrait_url) = match &persona {
-            Some(p) => (p.name.as_str(), p.color, p.portrait_url.as_deref()),
-            None => (persona_id.as_str(), 0x95a5a6, None),
+        let (persona_name, persona_color) = match &persona {
+            Some(p) => (p.name.as_str(), p.color),
+            None => (persona_id.as_str(), 0x95a5a6),
+        };
+
+        let summary_line = if council_summary.is_some() {
+            format!("**Summary:** ~{} tokens\n", format_number(summary_tokens))
+        } else {
+            String::new()
+        };
+        let attachment_line = if attachments.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "**Attachments:** {} text file(s) in this thread, included when you ask about files\n",
+                attachments.len()
+            )
         };
 
-        // Build embed description
-        let description = format!(
+        // Overview page
+        let overview = format!(
             "**Location:** {channel_display} ({location_type})\n\
              **Active Persona:** {persona_name}\n\
              **Model:** {model}\n\
              \n\
              **System Prompt:** ~{sys_tokens} tokens ({sys_chars} chars)\n\
              **History:** {msg_count} messages (~{hist_tokens} tokens, {hist_chars} chars)\n\
+             {summary_line}\
+             {attachment_line}\
              **Overhead:** ~{overhead} tokens (formatting)\n\
              \n\
              **Total Estimate:** ~{total} tokens\n\
@@ -186,22 +272,12 @@ impl ContextInfoHandler {
             source = context_source,
         );
 
-        // Send ephemeral response
-        command
-            .create_interaction_response(&serenity_ctx.http, |r| {
-                r.kind(InteractionResponseType::ChannelMessageWithSource)
-                    .interaction_response_data(|m| {
-                        m.ephemeral(true).embed(|e| {
-                            e.title("Context Window");
-                            e.description(&description);
-                            e.color(persona_color);
-                            if let Some(url) = portrait_url {
-                                e.thumbnail(url);
-                            }
-                            e
-                        })
-                    })
-            })
+        let pages = context_pages(overview, &history, council_summary.as_deref(), &attachments);
+        PaginatedEmbed::new("Context Window", pages)
+            .with_color(persona_color)
+            .with_owner(command.user.id.0)
+            .with_footer("Token counts are estimates (~4 chars per token)")
+            .respond(&ctx.database, &serenity_ctx.http, &responder, true)
             .await?;
 
         info!("/context response sent | User: {user_id} | {message_count} messages, ~{total_tokens} tokens");
@@ -209,6 +285,86 @@ impl ContextInfoHandler {
     }
 }
 
+/// Rough token count for a piece of prompt text
+fn estimate_tokens(text: &str) -> usize {
+    text.len() / CHARS_PER_TOKEN
+}
+
+/// One history message as it appears in the listing
+fn format_history_entry(index: usize, role: &str, content: &str) -> String {
+    let speaker = if role == "assistant" { "Bot" } else { "User" };
+    let preview = if content.chars().count() > MESSAGE_PREVIEW_CHARS {
+        let cut: String = content.chars().take(MESSAGE_PREVIEW_CHARS).collect();
+        format!("{cut}…")
+    } else {
+        content.to_string()
+    };
+    let quoted = preview
+        .lines()
+        .map(|line| format!("> {line}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "**{index}. {speaker}** (~{} tokens)\n{quoted}",
+        format_number(estimate_tokens(content))
+    )
+}
+
+/// One thread attachment with its size and estimated token cost
+fn format_attachment_entry(filename: &str, size: u64) -> String {
+    format!(
+        "📎 `{filename}` ({:.1} KB, ~{} tokens)",
+        size as f64 / 1024.0,
+        format_number(size as usize / CHARS_PER_TOKEN)
+    )
+}
+
+/// Overview first, then the history in prompt order, the summary and attachments
+fn context_pages(
+    overview: String,
+    history: &[(String, String)],
+    summary: Option<&str>,
+    attachments: &[(String, u64)],
+) -> Vec<String> {
+    let mut pages = vec![overview];
+
+    let entries: Vec<String> = history
+        .iter()
+        .enumerate()
+        .map(|(i, (role, content))| format_history_entry(i + 1, role, content))
+        .collect();
+    if !entries.is_empty() {
+        let listing = PaginatedEmbed::from_lines("", entries, HISTORY_PER_PAGE);
+        pages.extend(
+            listing
+                .pages
+                .into_iter()
+                .map(|page| format!("**History** (oldest first)\n\n{page}")),
+        );
+    }
+
+    if let Some(summary) = summary {
+        pages.extend(
+            chunk_text(summary, PAGE_CHARS)
+                .into_iter()
+                .map(|page| format!("**Summary**\n\n{page}")),
+        );
+    }
+
+    if !attachments.is_empty() {
+        let lines: Vec<String> = attachments
+            .iter()
+            .map(|(name, size)| format_attachment_entry(name, *size))
+            .collect();
+        pages.push(format!(
+            "**Attachments** (included when you ask about files)\n\n{}",
+            lines.join("\n")
+        ));
+    }
+
+    pages
+}
+
 /// Format a number with comma separators for readability
 fn format_number(n: usize) -> String {
     let s = n.to_string();
@@ -253,4 +409,53 @@ mod tests {
         // ~4 chars/token is the standard GPT heuristic
         assert_eq!(CHARS_PER_TOKEN, 4);
     }
+
+    #[test]
+    fn test_history_entry_quotes_and_truncates() {
+        let entry = format_history_entry(3, "assistant", "line one\nline two");
+        assert!(entry.starts_with("**3. Bot** (~4 tokens)"));
+        assert!(entry.contains("> line one\n> line two"));
+
+        let long = "x".repeat(MESSAGE_PREVIEW_CHARS + 50);
+        let entry = format_history_entry(1, "user", &long);
+        assert!(entry.starts_with("**1. User**"));
+        assert!(entry.ends_with('…'));
+        assert!(entry.contains(&format!("~{} tokens", long.len() / CHARS_PER_TOKEN)));
+    }
+
+    #[test]
+    fn test_attachment_entry() {
+        assert_eq!(
+            format_attachment_entry("notes.md", 4096),
+            "📎 `notes.md` (4.0 KB, ~1,024 tokens)"
+        );
+    }
+
+    #[test]
+    fn test_context_pages_order() {
+        let history: Vec<(String, String)> = (0..7)
+            .map(|i| ("user".to_string(), format!("message {i}")))
+            .collect();
+        let attachments = vec![("log.txt".to_string(), 800)];
+        let pages = context_pages(
+            "overview".to_string(),
+            &history,
+            Some("Original topic: tabs"),
+            &attachments,
+        );
+
+        assert_eq!(pages.len(), 5);
+        assert_eq!(pages[0], "overview");
+        assert!(pages[1].starts_with("**History**"));
+        assert!(pages[1].contains("**1. User**"));
+        assert!(pages[2].contains("**7. User**"));
+        assert!(pages[3].contains("Original topic: tabs"));
+        assert!(pages[4].contains("`log.txt`"));
+    }
+
+    #[test]
+    fn test_context_pages_overview_only() {
+        let pages = context_pages("overview".to_string(), &[], None, &[]);
+        assert_eq!(pages, vec!["overview".to_string()]);
+    }
 }
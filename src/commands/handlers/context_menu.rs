@@ -2,10 +2,12 @@
 //!
 //! Handles: Analyze Message, Explain Message, Analyze User
 //!
-//! - **Version**: 1.0.0
+//! - **Version**: 1.2.0
 //! - **Since**: 3.38.0
 //!
 //! ## Changelog
+//! - 1.2.0: Persona lookup is scoped to the invoking guild
+//! - 1.1.0: Initial responses go through InteractionResponder (auto-defer safe)
 //! - 1.0.0: Extracted from command_handler.rs
 
 use anyhow::Result;
@@ -19,6 +21,7 @@ use uuid::Uuid;
 
 use crate::commands::context::CommandContext;
 use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
 use crate::features::analytics::CostBucket;
 
 /// Handler for context menu commands: Analyze Message, Explain Message, Analyze User
@@ -60,9 +63,14 @@ impl ContextMenuHandler {
         command: &ApplicationCommandInteraction,
         request_id: Uuid,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let user_id = command.user.id.to_string();
         let message_content = "Message content will be analyzed".to_string();
-        let user_persona = ctx.database.get_user_persona(&user_id).await?;
+        let guild_id = command.guild_id.map(|id| id.to_string());
+        let user_persona = ctx
+            .database
+            .get_user_persona_with_guild(&user_id, guild_id.as_deref())
+            .await?;
 
         info!(
             "[{request_id}] Context menu: {} | User: {user_id}",
@@ -84,11 +92,16 @@ impl ContextMenuHandler {
         let prompt = format!("Please analyze this message: \"{message_content}\"");
 
         ctx.database
-            .log_usage(&user_id, &command.data.name, Some(&user_persona))
+            .log_usage(
+                &user_id,
+                guild_id.as_deref(),
+                &command.data.name,
+                Some(&user_persona),
+            )
             .await?;
 
         // Defer interaction (AI calls may take time)
-        command
+        responder
             .create_interaction_response(&serenity_ctx.http, |response| {
                 response.kind(InteractionResponseType::DeferredChannelMessageWithSource)
             })
@@ -132,9 +145,14 @@ impl ContextMenuHandler {
         command: &ApplicationCommandInteraction,
         request_id: Uuid,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let user_id = command.user.id.to_string();
         let target_user = "Discord User".to_string();
-        let user_persona = ctx.database.get_user_persona(&user_id).await?;
+        let guild_id = command.guild_id.map(|id| id.to_string());
+        let user_persona = ctx
+            .database
+            .get_user_persona_with_guild(&user_id, guild_id.as_deref())
+            .await?;
 
         info!("[{request_id}] Context menu: Analyze User | User: {user_id}");
 
@@ -147,11 +165,16 @@ impl ContextMenuHandler {
         );
 
         ctx.database
-            .log_usage(&user_id, "analyze_user", Some(&user_persona))
+            .log_usage(
+                &user_id,
+                guild_id.as_deref(),
+                "analyze_user",
+                Some(&user_persona),
+            )
             .await?;
 
         // Defer interaction (AI calls may take time)
-        command
+        responder
             .create_interaction_response(&serenity_ctx.http, |response| {
                 response.kind(InteractionResponseType::DeferredChannelMessageWithSource)
             })
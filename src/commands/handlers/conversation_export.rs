@@ -0,0 +1,227 @@
+//! Conversation export handler
+//!
+//! Handles: Export Conversation
+//!
+//! Packages a recent exchange with the bot as a Markdown attachment, sent
+//! only to the member who asked. Exporting another member's stored
+//! conversation needs Manage Messages; thread exports cover messages the
+//! member can already read.
+//!
+//! - **Version**: 1.0.1
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.1: Declares its replies ephemeral to the registry
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use log::info;
+use serenity::builder::GetMessages;
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::model::channel::{AttachmentType, Message};
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use crate::commands::context::{is_in_thread_channel, CommandContext};
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::features::conversation_export::{
+    parse_stored_timestamp, render_markdown, ExportEntry, EXPORT_COMMAND, EXPORT_FEATURE,
+    EXPORT_LIMIT,
+};
+
+/// Handler for the Export Conversation context menu
+pub struct ConversationExportHandler;
+
+#[async_trait]
+impl SlashCommandHandler for ConversationExportHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &[EXPORT_COMMAND]
+    }
+
+    fn ephemeral(&self, _command: &ApplicationCommandInteraction) -> bool {
+        true
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        if let Some(guild_id) = command.guild_id {
+            if !ctx
+                .database
+                .is_feature_enabled(EXPORT_FEATURE, None, Some(&guild_id.to_string()))
+                .await?
+            {
+                return responder
+                    .reply_ephemeral(
+                        serenity_ctx,
+                        "❌ Conversation export is disabled on this server.",
+                    )
+                    .await;
+            }
+        }
+        let Some(target) = command.data.resolved.messages.values().next() else {
+            return Ok(());
+        };
+
+        responder
+            .create_interaction_response(&serenity_ctx.http, |r| {
+                r.kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                    .interaction_response_data(|d| d.ephemeral(true))
+            })
+            .await?;
+
+        let bot_id = serenity_ctx.cache.current_user_id();
+        let channel_id = command.channel_id;
+        let entries = if is_in_thread_channel(serenity_ctx, channel_id).await? {
+            let messages = channel_id
+                .messages(&serenity_ctx.http, |builder: &mut GetMessages| {
+                    builder.limit(EXPORT_LIMIT as u64)
+                })
+                .await?;
+            messages
+                .iter()
+                .rev() // Oldest first
+                .filter_map(|m| Self::thread_entry(m, bot_id))
+                .collect::<Vec<_>>()
+        } else {
+            // Picking one of the bot's replies exports the invoker's own exchange
+            let member = if target.author.id == bot_id {
+                &command.user
+            } else {
+                &target.author
+            };
+            let can_export_others = command
+                .member
+                .as_ref()
+                .and_then(|m| m.permissions)
+                .is_some_and(|p| p.manage_messages());
+            if member.id != command.user.id && command.guild_id.is_some() && !can_export_others {
+                return responder
+                    .reply_ephemeral(
+                        serenity_ctx,
+                        "❌ You need the Manage Messages permission to export someone else's conversation.",
+                    )
+                    .await;
+            }
+            ctx.database
+                .get_user_channel_messages(
+                    &member.id.to_string(),
+                    &channel_id.to_string(),
+                    EXPORT_LIMIT,
+                )
+                .await?
+                .into_iter()
+                .filter_map(|m| {
+                    let from_bot = m.role == "assistant";
+                    let speaker = if from_bot {
+                        m.persona
+                            .as_deref()
+                            .filter(|p| !p.is_empty())
+                            .and_then(|p| ctx.persona_manager.get_persona(p))
+                            .map(|p| p.name.clone())
+                            .unwrap_or_else(|| "Bot".to_string())
+                    } else {
+                        member.name.clone()
+                    };
+                    Some(ExportEntry {
+                        speaker,
+                        from_bot,
+                        timestamp: parse_stored_timestamp(&m.timestamp)?,
+                        content: m.content,
+                    })
+                })
+                .collect()
+        };
+        if entries.is_empty() {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "There's no conversation with me here to export.",
+                )
+                .await;
+        }
+
+        let source = match channel_id.name(&serenity_ctx.cache).await {
+            Some(name) => format!("#{name}"),
+            None => format!("channel {channel_id}"),
+        };
+        let markdown = render_markdown(&source, &entries, Utc::now());
+        info!(
+            "Export Conversation | User: {} | Channel: {channel_id} | {} messages",
+            command.user.id,
+            entries.len()
+        );
+        responder
+            .reply_ephemeral(
+                serenity_ctx,
+                &format!("📄 Exported {} messages from {source}.", entries.len()),
+            )
+            .await?;
+        command
+            .create_followup_message(&serenity_ctx.http, |m| {
+                m.add_file(AttachmentType::Bytes {
+                    data: Cow::Owned(markdown.into_bytes()),
+                    filename: format!("conversation-{channel_id}.md"),
+                })
+                .ephemeral(true)
+            })
+            .await?;
+        Ok(())
+    }
+}
+
+impl ConversationExportHandler {
+    /// A thread message as an export entry, attributing bot embeds to their persona
+    fn thread_entry(message: &Message, bot_id: UserId) -> Option<ExportEntry> {
+        let from_bot = message.author.id == bot_id;
+        let embed_text: Vec<&str> = message
+            .embeds
+            .iter()
+            .filter_map(|e| e.description.as_deref())
+            .collect();
+        let content = if message.content.trim().is_empty() {
+            embed_text.join("\n\n")
+        } else {
+            message.content.clone()
+        };
+        if content.trim().is_empty() {
+            return None;
+        }
+        let speaker = if from_bot {
+            message
+                .embeds
+                .iter()
+                .find_map(|e| e.author.as_ref().map(|a| a.name.clone()))
+                .unwrap_or_else(|| message.author.name.clone())
+        } else {
+            message.author.name.clone()
+        };
+        Some(ExportEntry {
+            speaker,
+            from_bot,
+            timestamp: DateTime::from_timestamp(message.timestamp.unix_timestamp(), 0)?,
+            content,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversation_export_handler_commands() {
+        let handler = ConversationExportHandler;
+        assert_eq!(handler.command_names(), &["Export Conversation"]);
+    }
+}
@@ -0,0 +1,159 @@
+//! Convert command handler
+//!
+//! Handles: convert
+//!
+//! Units convert from the built-in table; currencies use the cached exchange
+//! rates, which fall back to older or bundled rates when the rates service
+//! is down. With `in_character` the member's persona phrases the result.
+//!
+//! - **Version**: 1.1.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.1.0: Resolution and result text moved to the conversions feature
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::{info, warn};
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::prelude::Context;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::{get_bool_option, get_number_option, get_string_option};
+use crate::features::analytics::CostBucket;
+use crate::features::conversions::{Conversion, CONVERT_FEATURE, PHRASING_PROMPT};
+
+/// Handler for /convert
+pub struct ConvertHandler;
+
+#[async_trait]
+impl SlashCommandHandler for ConvertHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["convert"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let guild_id = command.guild_id.map(|id| id.to_string());
+        if let Some(guild_id) = &guild_id {
+            if !ctx
+                .database
+                .is_feature_enabled(CONVERT_FEATURE, None, Some(guild_id))
+                .await?
+            {
+                return responder
+                    .reply_ephemeral(serenity_ctx, "❌ Conversions are disabled on this server.")
+                    .await;
+            }
+        }
+        let options = &command.data.options;
+        let amount = get_number_option(options, "amount").unwrap_or_default();
+        let from = get_string_option(options, "from").unwrap_or_default();
+        let to = get_string_option(options, "to").unwrap_or_default();
+        let conversion = match Conversion::resolve(&from, &to) {
+            Ok(conversion) => conversion,
+            Err(e) => {
+                return responder
+                    .reply_ephemeral(serenity_ctx, &format!("❌ {e}"))
+                    .await
+            }
+        };
+        let user_id = command.user.id.to_string();
+        info!("/convert | User: {user_id} | {amount} {from} -> {to}");
+
+        // Rate fetches and persona phrasing can both take a moment
+        responder
+            .create_interaction_response(&serenity_ctx.http, |response| {
+                response.kind(InteractionResponseType::DeferredChannelMessageWithSource)
+            })
+            .await?;
+
+        let (headline, note) = match conversion.run(amount).await {
+            Ok(result) => result,
+            Err(e) => (format!("❌ {e}"), None),
+        };
+
+        let failed = headline.starts_with('❌');
+        let headline = if get_bool_option(options, "in_character").unwrap_or(false) && !failed {
+            Self::phrase_in_character(&ctx, command, guild_id.as_deref(), &headline)
+                .await
+                .unwrap_or_else(|e| {
+                    warn!("⚠️ Persona phrasing for /convert failed: {e}");
+                    headline
+                })
+        } else {
+            headline
+        };
+        let content = match note {
+            Some(note) => format!("{headline}\n{note}"),
+            None => headline,
+        };
+        command
+            .edit_original_interaction_response(&serenity_ctx.http, |response| {
+                response.content(content)
+            })
+            .await?;
+        Ok(())
+    }
+}
+
+impl ConvertHandler {
+    /// Have the member's persona restate the result
+    async fn phrase_in_character(
+        ctx: &CommandContext,
+        command: &ApplicationCommandInteraction,
+        guild_id: Option<&str>,
+        result: &str,
+    ) -> Result<String> {
+        let user_id = command.user.id.to_string();
+        let channel_id = command.channel_id.to_string();
+        let persona = match guild_id {
+            Some(guild_id) => {
+                ctx.database
+                    .get_persona_with_channel(&user_id, guild_id, &channel_id, None)
+                    .await?
+            }
+            None => ctx.database.get_user_persona(&user_id).await?,
+        };
+        let persona_prompt = ctx.persona_manager.get_system_prompt(&persona, None);
+        let phrased = ctx
+            .get_ai_response(
+                &format!("{persona_prompt}\n\n{PHRASING_PROMPT}"),
+                &result.replace("**", ""),
+                Vec::new(),
+                Uuid::new_v4(),
+                Some(&user_id),
+                guild_id,
+                Some(&channel_id),
+                CostBucket::Convert,
+            )
+            .await?;
+        Ok(format!(
+            "{}\n-# {}",
+            phrased.trim(),
+            result.replace("**", "")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_handler_commands() {
+        let handler = ConvertHandler;
+        assert_eq!(handler.command_names(), &["convert"]);
+    }
+}
@@ -2,10 +2,19 @@
 //!
 //! Handles: council, conclude
 //!
-//! - **Version**: 1.1.0
+//! - **Version**: 1.9.0
 //! - **Since**: 3.38.0
 //!
 //! ## Changelog
+//! - 1.9.0: Council turns follow the guild's guardrail profile, which can refuse members
+//! - 1.8.0: /conclude on a council has the members vote on a conclusion, then posts a synthesis
+//! - 1.7.0: /conclude on a debate records the result and adds audience vote buttons
+//! - 1.6.0: /conclude on a debate notifies the guild's debate_concluded webhooks
+//! - 1.5.1: Council turn task is counted in /sysinfo runtime metrics
+//! - 1.5.0: Opening statements come from CouncilGenerator
+//! - 1.4.0: Initial responses go through InteractionResponder (auto-defer safe)
+//! - 1.3.0: Persist council state so its buttons survive restarts; /conclude forgets it
+//! - 1.2.0: Council turns use the context's ChatClient
 //! - 1.1.0: Use shared persona embed builders from core::embeds
 //! - 1.0.0: Extracted from command_handler.rs
 
@@ -20,11 +29,18 @@ use uuid::Uuid;
 
 use crate::commands::context::{is_in_thread_channel, CommandContext};
 use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
 use crate::commands::slash::get_string_option;
-use crate::core::persona_embed;
+use crate::core::{persona_embed, truncate_for_embed};
+use crate::features::analytics::runtime::{spawn_tracked, TaskKind};
 use crate::features::analytics::CostBucket;
-use crate::features::council::{get_active_councils, CouncilState};
-use crate::features::debate::get_active_debates;
+use crate::features::council::{get_active_councils, tally_embed, CouncilState, TurnUsage};
+use crate::features::debate::{get_active_debates, notify_concluded, record_result};
+use crate::features::discussion::{
+    create_debate_vote_buttons, forget_discussion_state, save_discussion_state_logged,
+    DiscussionType,
+};
+use crate::features::guardrails::GuardrailProfile;
 
 /// Handler for /council and /conclude commands
 pub struct CouncilHandler;
@@ -65,6 +81,7 @@ impl CouncilHandler {
         command: &ApplicationCommandInteraction,
         request_id: Uuid,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         // Extract prompt
         let prompt = get_string_option(&command.data.options, "prompt")
             .ok_or_else(|| anyhow::anyhow!("Missing prompt argument"))?;
@@ -94,7 +111,7 @@ impl CouncilHandler {
 
         // Validate we have at least 2 personas
         if persona_ids.len() < 2 {
-            command
+            responder
                 .create_interaction_response(&serenity_ctx.http, |r| {
                     r.kind(InteractionResponseType::ChannelMessageWithSource)
                         .interaction_response_data(|m| {
@@ -106,13 +123,29 @@ impl CouncilHandler {
             return Ok(());
         }
 
-        // Validate all personas exist and collect their data
+        // Validate all personas exist and are allowed, and collect their data
+        let guardrails = GuardrailProfile::for_channel(
+            &ctx.database,
+            guild_id.as_deref(),
+            Some(&channel_id.to_string()),
+        )
+        .await?;
         let mut personas: Vec<crate::features::personas::Persona> = Vec::new();
         for persona_id in &persona_ids {
             if let Some(persona) = ctx.persona_manager.get_persona_with_portrait(persona_id) {
+                if !guardrails.allows_persona(persona_id) {
+                    let message = guardrails.blocked_persona_message(&persona.name);
+                    responder
+                        .create_interaction_response(&serenity_ctx.http, |r| {
+                            r.kind(InteractionResponseType::ChannelMessageWithSource)
+                                .interaction_response_data(|m| m.content(message).ephemeral(true))
+                        })
+                        .await?;
+                    return Ok(());
+                }
                 personas.push(persona);
             } else {
-                command
+                responder
                     .create_interaction_response(&serenity_ctx.http, |r| {
                         r.kind(InteractionResponseType::ChannelMessageWithSource)
                             .interaction_response_data(|m| {
@@ -146,7 +179,7 @@ impl CouncilHandler {
         // Determine the thread_id to use
         let thread_id = if in_thread {
             if existing_council {
-                command
+                responder
                     .create_interaction_response(&serenity_ctx.http, |r| {
                         r.kind(InteractionResponseType::ChannelMessageWithSource)
                             .interaction_response_data(|m| {
@@ -161,7 +194,7 @@ impl CouncilHandler {
             // Run council directly in existing thread
             info!("[{request_id}] Running council in existing thread");
 
-            command
+            responder
                 .create_interaction_response(&serenity_ctx.http, |r| {
                     r.kind(InteractionResponseType::ChannelMessageWithSource)
                         .interaction_response_data(|m| {
@@ -191,7 +224,7 @@ impl CouncilHandler {
             channel_id
         } else {
             // Not in a thread - create one
-            command
+            responder
                 .create_interaction_response(&serenity_ctx.http, |r| {
                     r.kind(InteractionResponseType::ChannelMessageWithSource)
                         .interaction_response_data(|m| {
@@ -270,7 +303,9 @@ impl CouncilHandler {
         };
 
         // Log usage
-        ctx.database.log_usage(&user_id, "council", None).await?;
+        ctx.database
+            .log_usage(&user_id, guild_id.as_deref(), "council", None)
+            .await?;
 
         // Check for prior discussion context in this thread
         let prior_context = crate::features::detect_thread_context(thread_id.0);
@@ -289,18 +324,25 @@ impl CouncilHandler {
         get_active_councils().insert(thread_id.0, council_state);
 
         // Clone values needed for the async task
-        let openai_model = ctx.openai_model.clone();
-        let usage_tracker = ctx.usage_tracker.clone();
+        let generator = ctx
+            .council_generator(guild_id.as_deref(), &thread_id.to_string())
+            .await?;
+        let usage = TurnUsage {
+            user_id: user_id.clone(),
+            guild_id: guild_id.clone(),
+            channel_id: channel_id.to_string(),
+            request_id,
+            cost_bucket: CostBucket::Council,
+        };
         let persona_manager = ctx.persona_manager.clone();
         let ctx_clone = serenity_ctx.clone();
         let prompt_clone = prompt.clone();
-        let guild_id_clone = guild_id.clone();
-        let channel_id_str = channel_id.to_string();
         let rules_clone = rules.clone();
         let prior_context_clone = prior_context_text.clone();
+        let database = ctx.database.clone();
 
         // Spawn a task to get responses from each persona
-        tokio::spawn(async move {
+        spawn_tracked(TaskKind::Discussions, async move {
             // Add the initial user message to history
             if let Some(mut state) = get_active_councils().get_mut(&thread_id.0) {
                 state.add_user_message(prompt_clone.clone());
@@ -312,84 +354,15 @@ impl CouncilHandler {
                     None => continue,
                 };
 
-                let system_prompt = persona_manager.get_system_prompt(persona_id, None);
-
-                // Build rules section if provided
-                let rules_section = rules_clone
-                    .as_ref()
-                    .map(|r| {
-                        format!(
-                            "\n\n## Ground Rules\nThe following rules and definitions apply to this discussion:\n{r}\n"
-                        )
-                    })
-                    .unwrap_or_default();
-
-                // Build prior context section if available
-                let prior_section = prior_context_clone
-                    .as_ref()
-                    .map(|c| format!("\n\n{c}\n"))
-                    .unwrap_or_default();
-
-                let council_context = format!(
-                    "{}{}{}\n\nYou are participating in a council discussion with other personas. \
-                    Share your unique perspective on the topic. Be concise but thoughtful. \
-                    You are speaking as {} - stay true to your character.",
-                    system_prompt, rules_section, prior_section, persona.name
-                );
-
-                let messages = vec![
-                    openai::chat::ChatCompletionMessage {
-                        role: openai::chat::ChatCompletionMessageRole::System,
-                        content: Some(council_context),
-                        name: None,
-                        function_call: None,
-                        tool_call_id: None,
-                        tool_calls: None,
-                    },
-                    openai::chat::ChatCompletionMessage {
-                        role: openai::chat::ChatCompletionMessageRole::User,
-                        content: Some(prompt_clone.clone()),
-                        name: None,
-                        function_call: None,
-                        tool_call_id: None,
-                        tool_calls: None,
-                    },
-                ];
-
-                let response =
-                    match openai::chat::ChatCompletion::builder(&openai_model, messages)
-                        .create()
-                        .await
-                    {
-                        Ok(completion) => {
-                            if let Some(usage) = &completion.usage {
-                                usage_tracker.log_chat(
-                                    &openai_model,
-                                    usage.prompt_tokens,
-                                    usage.completion_tokens,
-                                    usage.total_tokens,
-                                    &user_id,
-                                    guild_id_clone.as_deref(),
-                                    Some(&channel_id_str),
-                                    Some(&request_id.to_string()),
-                                    CostBucket::Council,
-                                );
-                            }
-
-                            completion
-                                .choices
-                                .first()
-                                .and_then(|c| c.message.content.clone())
-                                .unwrap_or_else(|| "I have no words at this time.".to_string())
-                        }
-                        Err(e) => {
-                            error!(
-                                "[{request_id}] Council: Failed to get response from {}: {}",
-                                persona.name, e
-                            );
-                            format!("*{} is momentarily lost in thought...*", persona.name)
-                        }
-                    };
+                let response = generator
+                    .opening_statement(
+                        persona_id,
+                        &prompt_clone,
+                        rules_clone.as_deref(),
+                        prior_context_clone.as_deref(),
+                        &usage,
+                    )
+                    .await;
 
                 // Add response to council history
                 if let Some(mut state) = get_active_councils().get_mut(&thread_id.0) {
@@ -419,6 +392,7 @@ impl CouncilHandler {
             if let Some(mut state) = get_active_councils().get_mut(&thread_id.0) {
                 state.mark_opening_complete();
             }
+            save_discussion_state_logged(&database, DiscussionType::Council, thread_id.0).await;
 
             // Post interactive control buttons
             let control_embed = serenity::builder::CreateEmbed::default()
@@ -459,18 +433,24 @@ impl CouncilHandler {
         command: &ApplicationCommandInteraction,
         request_id: Uuid,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let channel_id = command.channel_id.0;
 
         info!("[{request_id}] /conclude command in channel {channel_id}");
 
         // Check for active council
         if let Some((_, council_state)) = get_active_councils().remove(&channel_id) {
+            forget_discussion_state(&ctx.database, DiscussionType::Council, channel_id).await?;
             info!(
                 "[{request_id}] Concluding council session on topic: {}",
                 council_state.topic
             );
+            let vote = council_state
+                .history
+                .iter()
+                .any(|msg| msg.role == "assistant");
 
-            command
+            responder
                 .create_interaction_response(&serenity_ctx.http, |r| {
                     r.kind(InteractionResponseType::ChannelMessageWithSource)
                         .interaction_response_data(|m| {
@@ -479,7 +459,7 @@ impl CouncilHandler {
                                     .description(format!(
                                         "**Topic:** {}\n\n\
                                         **Participants:** {}\n\n\
-                                        This council session has been formally concluded.",
+                                        This council session has been formally concluded.{}",
                                         council_state.topic,
                                         council_state
                                             .persona_ids
@@ -489,7 +469,12 @@ impl CouncilHandler {
                                                 .get_persona(id)
                                                 .map(|p| p.name.clone()))
                                             .collect::<Vec<_>>()
-                                            .join(", ")
+                                            .join(", "),
+                                        if vote {
+                                            " The members will now vote on a conclusion."
+                                        } else {
+                                            ""
+                                        }
                                     ))
                                     .color(0x9B59B6)
                             })
@@ -497,16 +482,47 @@ impl CouncilHandler {
                 })
                 .await?;
 
+            if vote {
+                let conclusion = get_string_option(&command.data.options, "conclusion");
+                self.spawn_conclusion_vote(
+                    ctx,
+                    serenity_ctx,
+                    command,
+                    council_state,
+                    conclusion,
+                    request_id,
+                )
+                .await?;
+            }
             return Ok(());
         }
 
         // Check for active debate
         if let Some((_, debate_state)) = get_active_debates().remove(&channel_id) {
+            forget_discussion_state(&ctx.database, DiscussionType::Debate, channel_id).await?;
             info!(
                 "[{request_id}] Concluding debate session on topic: {}",
                 debate_state.config.topic
             );
 
+            let guild_id = command.guild_id.map(|id| id.to_string());
+            record_result(&ctx.database, guild_id.as_deref(), channel_id, &debate_state).await;
+            if let Some(guild_id) = &guild_id {
+                notify_concluded(
+                    &ctx.database,
+                    &ctx.persona_manager,
+                    guild_id,
+                    channel_id,
+                    &debate_state,
+                );
+            }
+            let vote_buttons = create_debate_vote_buttons(
+                channel_id,
+                &debate_state.config.persona1_id,
+                &debate_state.config.persona2_id,
+                &ctx.persona_manager,
+            );
+
             let p1_name = ctx
                 .persona_manager
                 .get_persona(&debate_state.config.persona1_id)
@@ -518,7 +534,7 @@ impl CouncilHandler {
                 .map(|p| p.name.clone())
                 .unwrap_or_else(|| debate_state.config.persona2_id.clone());
 
-            command
+            responder
                 .create_interaction_response(&serenity_ctx.http, |r| {
                     r.kind(InteractionResponseType::ChannelMessageWithSource)
                         .interaction_response_data(|m| {
@@ -528,7 +544,7 @@ impl CouncilHandler {
                                         "**Topic:** {}\n\n\
                                         **Debaters:** {} vs {}\n\
                                         **Total Rounds:** {}\n\n\
-                                        This debate has been formally concluded.",
+                                        This debate has been formally concluded. Who won?",
                                         debate_state.config.topic,
                                         p1_name,
                                         p2_name,
@@ -536,6 +552,7 @@ impl CouncilHandler {
                                     ))
                                     .color(0x7289DA)
                             })
+                            .set_components(vote_buttons)
                         })
                 })
                 .await?;
@@ -544,7 +561,7 @@ impl CouncilHandler {
         }
 
         // No active session found
-        command
+        responder
             .create_interaction_response(&serenity_ctx.http, |r| {
                 r.kind(InteractionResponseType::ChannelMessageWithSource)
                     .interaction_response_data(|m| {
@@ -556,6 +573,79 @@ impl CouncilHandler {
 
         Ok(())
     }
+
+    /// Have the council members vote on a conclusion, then post the synthesis
+    async fn spawn_conclusion_vote(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        council_state: CouncilState,
+        conclusion: Option<String>,
+        request_id: Uuid,
+    ) -> Result<()> {
+        let guild_id = command.guild_id.map(|id| id.to_string());
+        let generator = ctx
+            .council_generator(guild_id.as_deref(), &command.channel_id.to_string())
+            .await?;
+        let usage = TurnUsage {
+            user_id: command.user.id.to_string(),
+            guild_id,
+            channel_id: command.channel_id.to_string(),
+            request_id,
+            cost_bucket: CostBucket::Council,
+        };
+        let persona_manager = ctx.persona_manager.clone();
+        let http = serenity_ctx.http.clone();
+        let thread_id = command.channel_id;
+
+        spawn_tracked(TaskKind::Discussions, async move {
+            let discussion = council_state.get_context_summary();
+            let _ = thread_id.broadcast_typing(&http).await;
+
+            let conclusion = match conclusion {
+                Some(conclusion) => conclusion,
+                None => match generator.draft_conclusion(&discussion, &usage).await {
+                    Some(drafted) => drafted,
+                    None => {
+                        let _ = thread_id
+                            .send_message(&http, |m| {
+                                m.content("The council couldn't agree on what to vote on.")
+                            })
+                            .await;
+                        return;
+                    }
+                },
+            };
+
+            let votes = generator
+                .cast_votes(&council_state.persona_ids, &discussion, &conclusion, &usage)
+                .await;
+            let tally = tally_embed(&conclusion, &votes, &persona_manager);
+            if let Err(e) = thread_id.send_message(&http, |m| m.set_embed(tally)).await {
+                error!("[{request_id}] Council: Failed to post the vote tally: {e}");
+                return;
+            }
+
+            let _ = thread_id.broadcast_typing(&http).await;
+            if let Some(synthesis) = generator
+                .final_synthesis(&discussion, &conclusion, &votes, &usage)
+                .await
+            {
+                let _ = thread_id
+                    .send_message(&http, |m| {
+                        m.embed(|e| {
+                            e.title("Council Synthesis")
+                                .description(truncate_for_embed(&synthesis))
+                                .color(0x9B59B6)
+                        })
+                    })
+                    .await;
+            }
+            info!("[{request_id}] Council conclusion vote completed");
+        });
+        Ok(())
+    }
 }
 
 #[cfg(test)]
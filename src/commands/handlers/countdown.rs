@@ -0,0 +1,237 @@
+//! Countdown command handler
+//!
+//! Handles: countdown
+//!
+//! Members with Manage Messages post countdown embeds, list the running
+//! ones and cancel them. Pinging a role at zero needs the role to be
+//! mentionable or the member to have Mention Everyone. The embeds are kept
+//! up to date by the countdown scheduler.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::{info, warn};
+use serenity::model::application::interaction::application_command::{
+    ApplicationCommandInteraction, CommandDataOption,
+};
+use serenity::model::id::{ChannelId, MessageId, RoleId};
+use serenity::prelude::Context;
+use std::sync::Arc;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::handlers::remind::RemindHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::{get_integer_option, get_role_option, get_string_option};
+use crate::database::Countdown;
+use crate::features::countdown::{
+    countdown_embed, list_line, validate_title, COUNTDOWN_FEATURE, MAX_COUNTDOWNS_PER_GUILD,
+    MAX_DURATION_SECS,
+};
+
+/// Handler for /countdown
+pub struct CountdownHandler;
+
+#[async_trait]
+impl SlashCommandHandler for CountdownHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["countdown"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let Some(guild_id) = command.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        let can_manage = command
+            .member
+            .as_ref()
+            .and_then(|m| m.permissions)
+            .is_some_and(|p| p.manage_messages());
+        if !can_manage {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "❌ You need the Manage Messages permission to run countdowns.",
+                )
+                .await;
+        }
+        if !ctx
+            .database
+            .is_feature_enabled(COUNTDOWN_FEATURE, None, Some(&guild_id))
+            .await?
+        {
+            return responder
+                .reply_ephemeral(serenity_ctx, "❌ Countdowns are disabled on this server.")
+                .await;
+        }
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+
+        let content = match subcommand.name.as_str() {
+            "start" => {
+                Self::handle_start(&ctx, serenity_ctx, command, &guild_id, &subcommand.options)
+                    .await?
+            }
+            "list" => {
+                let countdowns = ctx.database.get_guild_countdowns(&guild_id).await?;
+                if countdowns.is_empty() {
+                    "No countdowns are running. Start one with `/countdown start`.".to_string()
+                } else {
+                    format!(
+                        "⏳ **Running countdowns**\n{}",
+                        countdowns
+                            .iter()
+                            .map(|c| format!("- {}", list_line(c)))
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    )
+                }
+            }
+            _ => {
+                let id = get_integer_option(&subcommand.options, "id").unwrap_or_default();
+                match ctx.database.get_countdown(id).await? {
+                    Some(countdown) if countdown.guild_id == guild_id => {
+                        ctx.database.delete_countdown(id).await?;
+                        Self::delete_embed(serenity_ctx, &countdown).await;
+                        info!(
+                            "/countdown cancel | User: {} | Guild: {guild_id} | #{id}",
+                            command.user.id
+                        );
+                        format!("✅ Cancelled countdown #{id}.")
+                    }
+                    _ => format!("❌ Countdown #{id} not found."),
+                }
+            }
+        };
+        responder.reply_ephemeral(serenity_ctx, &content).await
+    }
+}
+
+impl CountdownHandler {
+    /// Handle /countdown start, returning the reply
+    async fn handle_start(
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        guild_id: &str,
+        options: &[CommandDataOption],
+    ) -> Result<String> {
+        let title = match validate_title(&get_string_option(options, "title").unwrap_or_default()) {
+            Ok(title) => title,
+            Err(e) => return Ok(format!("❌ {e}")),
+        };
+        let Some(duration) = get_string_option(options, "in")
+            .as_deref()
+            .and_then(RemindHandler::parse_duration)
+        else {
+            return Ok(
+                "❌ Invalid time format. Use formats like `2h`, `3d`, or `1w2d`.".to_string(),
+            );
+        };
+        if duration > MAX_DURATION_SECS {
+            return Ok("❌ Countdowns can run for at most a year.".to_string());
+        }
+        let role_id = get_role_option(options, "role").map(RoleId);
+        if let Some(role_id) = role_id {
+            let mentionable = command
+                .data
+                .resolved
+                .roles
+                .get(&role_id)
+                .is_some_and(|r| r.mentionable);
+            let can_mention_everyone = command
+                .member
+                .as_ref()
+                .and_then(|m| m.permissions)
+                .is_some_and(|p| p.mention_everyone());
+            if !mentionable && !can_mention_everyone {
+                return Ok(format!(
+                    "❌ <@&{role_id}> can't be mentioned by everyone, so you need the Mention Everyone permission to ping it."
+                ));
+            }
+        }
+        if ctx.database.count_countdowns(guild_id).await? >= MAX_COUNTDOWNS_PER_GUILD {
+            return Ok(format!(
+                "❌ This server already has {MAX_COUNTDOWNS_PER_GUILD} countdowns running. Cancel one first."
+            ));
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        let mut countdown = Countdown {
+            id: 0,
+            guild_id: guild_id.to_string(),
+            channel_id: command.channel_id.to_string(),
+            message_id: None,
+            title,
+            ends_at: now + duration,
+            role_id: role_id.map(|r| r.to_string()),
+            created_by: command.user.id.to_string(),
+        };
+        countdown.id = ctx.database.add_countdown(&countdown).await?;
+        let embed = countdown_embed(&countdown, now);
+        let message = match command
+            .channel_id
+            .send_message(&serenity_ctx.http, |m| m.set_embed(embed))
+            .await
+        {
+            Ok(message) => message,
+            Err(e) => {
+                ctx.database.delete_countdown(countdown.id).await?;
+                warn!("⚠️ Failed to post countdown in {}: {e}", command.channel_id);
+                return Ok("❌ I couldn't post the countdown in this channel.".to_string());
+            }
+        };
+        ctx.database
+            .set_countdown_message(countdown.id, &message.id.to_string())
+            .await?;
+        info!(
+            "/countdown start | User: {} | Guild: {guild_id} | #{} ends {}",
+            command.user.id, countdown.id, countdown.ends_at
+        );
+        Ok(format!(
+            "✅ Countdown #{} to **{}** started. It ends <t:{}:R>.",
+            countdown.id, countdown.title, countdown.ends_at
+        ))
+    }
+
+    /// Remove a cancelled countdown's embed; it may already be gone
+    async fn delete_embed(serenity_ctx: &Context, countdown: &Countdown) {
+        let (Ok(channel_id), Some(Ok(message_id))) = (
+            countdown.channel_id.parse::<u64>(),
+            countdown.message_id.as_deref().map(str::parse::<u64>),
+        ) else {
+            return;
+        };
+        if let Err(e) = ChannelId(channel_id)
+            .delete_message(&serenity_ctx.http, MessageId(message_id))
+            .await
+        {
+            warn!("⚠️ Failed to delete countdown #{} embed: {e}", countdown.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_countdown_handler_commands() {
+        let handler = CountdownHandler;
+        assert_eq!(handler.command_names(), &["countdown"]);
+    }
+}
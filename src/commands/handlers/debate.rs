@@ -2,17 +2,26 @@
 //!
 //! Handles: debate
 //!
-//! - **Version**: 1.0.0
+//! - **Version**: 1.6.0
 //! - **Since**: 3.38.0
 //!
 //! ## Changelog
+//! - 1.6.0: Debaters see the channel's scene
+//! - 1.5.0: Debates follow the guild's guardrail profile
+//! - 1.4.0: /debate start and /debate import, which seeds the debate from a pasted or attached transcript
+//! - 1.3.1: Debate task is counted in /sysinfo runtime metrics
+//! - 1.3.0: Initial responses go through InteractionResponder (auto-defer safe)
+//! - 1.2.0: Persist debate state so its buttons survive restarts
+//! - 1.1.0: Debate turns use the context's ChatClient
 //! - 1.0.0: Extracted from command_handler.rs
 
 use anyhow::Result;
 use async_trait::async_trait;
 use log::{debug, error, info, warn};
 use serenity::builder::GetMessages;
-use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::application::interaction::application_command::{
+    ApplicationCommandInteraction, CommandDataOption,
+};
 use serenity::model::application::interaction::InteractionResponseType;
 use serenity::model::id::ChannelId;
 use serenity::prelude::Context;
@@ -21,14 +30,22 @@ use uuid::Uuid;
 
 use crate::commands::context::{is_in_thread_channel, CommandContext};
 use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
 use crate::commands::slash::{
     debate::{DEFAULT_RESPONSES, MAX_RESPONSES, MIN_RESPONSES},
-    get_integer_option, get_string_option,
+    get_attachment_option, get_integer_option, get_string_option,
 };
+use crate::features::analytics::runtime::{spawn_tracked, TaskKind};
 use crate::features::analytics::CostBucket;
 use crate::features::debate::{
     get_active_debates, orchestrator::DebateConfig, DebateOrchestrator,
 };
+use crate::features::discussion::{
+    parse_transcript, save_discussion_state_logged, DiscussionType, ImportedTranscript,
+    MAX_TRANSCRIPT_BYTES,
+};
+use crate::features::guardrails::GuardrailProfile;
+use crate::features::scenes::{self, channel_scene};
 
 /// Handler for /debate command - multi-persona debates
 pub struct DebateHandler;
@@ -52,7 +69,7 @@ impl SlashCommandHandler for DebateHandler {
 }
 
 impl DebateHandler {
-    /// Handle /debate command
+    /// Handle /debate start and /debate import
     async fn handle_debate(
         &self,
         ctx: &CommandContext,
@@ -60,26 +77,34 @@ impl DebateHandler {
         command: &ApplicationCommandInteraction,
         request_id: Uuid,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+        let options = &subcommand.options;
+
         // Extract command options
-        let persona1_id = get_string_option(&command.data.options, "persona1")
+        let persona1_id = get_string_option(options, "persona1")
             .ok_or_else(|| anyhow::anyhow!("Missing persona1 argument"))?;
-        let persona2_id = get_string_option(&command.data.options, "persona2")
+        let persona2_id = get_string_option(options, "persona2")
             .ok_or_else(|| anyhow::anyhow!("Missing persona2 argument"))?;
-        let topic = get_string_option(&command.data.options, "topic")
+        let topic = get_string_option(options, "topic")
             .ok_or_else(|| anyhow::anyhow!("Missing topic argument"))?;
-        let rounds = get_integer_option(&command.data.options, "rounds")
+        let rounds = get_integer_option(options, "rounds")
             .unwrap_or(DEFAULT_RESPONSES)
             .clamp(MIN_RESPONSES, MAX_RESPONSES);
 
         // Extract optional rules parameter
-        let rules = get_string_option(&command.data.options, "rules");
+        let rules = get_string_option(options, "rules");
 
         // Determine if this is opening-only mode (default: 2 rounds = opening statements)
         let opening_only = rounds <= 2;
 
         // Validate personas are different
         if persona1_id == persona2_id {
-            command
+            responder
                 .create_interaction_response(&serenity_ctx.http, |r| {
                     r.kind(InteractionResponseType::ChannelMessageWithSource)
                         .interaction_response_data(|message| {
@@ -98,7 +123,7 @@ impl DebateHandler {
         let persona2 = ctx.persona_manager.get_persona(&persona2_id);
 
         if persona1.is_none() || persona2.is_none() {
-            command
+            responder
                 .create_interaction_response(&serenity_ctx.http, |r| {
                     r.kind(InteractionResponseType::ChannelMessageWithSource)
                         .interaction_response_data(|message| {
@@ -114,10 +139,67 @@ impl DebateHandler {
         let persona1_name = persona1.unwrap().name.clone();
         let persona2_name = persona2.unwrap().name.clone();
 
+        let guild_id = command.guild_id.map(|g| g.to_string());
+        let guardrails = GuardrailProfile::for_channel(
+            &ctx.database,
+            guild_id.as_deref(),
+            Some(&command.channel_id.to_string()),
+        )
+        .await?;
+        let scene = channel_scene(
+            &ctx.database,
+            guild_id.as_deref(),
+            Some(&command.channel_id.to_string()),
+        )
+        .await?;
+        for (persona_id, name) in [
+            (&persona1_id, &persona1_name),
+            (&persona2_id, &persona2_name),
+        ] {
+            if !guardrails.allows_persona(persona_id) {
+                let message = guardrails.blocked_persona_message(name);
+                return responder.reply_ephemeral(serenity_ctx, &message).await;
+            }
+        }
+
         // Check if we're already in a thread with an existing debate
         let channel_id = command.channel_id;
         let existing_debate = get_active_debates().get(&channel_id.0).map(|d| d.clone());
 
+        // /debate import continues a transcript instead of taking over a debate
+        let imported = if subcommand.name == "import" {
+            if existing_debate.is_some() {
+                return responder
+                    .reply_ephemeral(
+                        serenity_ctx,
+                        "This thread already has a debate. Import the transcript from a channel or a thread without one.",
+                    )
+                    .await;
+            }
+            match Self::load_transcript(options, &topic, rules.clone()).await? {
+                Ok(imported) => Some(imported),
+                Err(message) => {
+                    return responder.reply_ephemeral(serenity_ctx, &message).await;
+                }
+            }
+        } else {
+            None
+        };
+        let import_note = imported
+            .as_ref()
+            .map(|t| {
+                let mut note = format!(
+                    "\n**Continuing:** {} imported messages from {}",
+                    t.context.message_count(),
+                    t.context.participants.join(", ")
+                );
+                if t.dropped > 0 {
+                    note.push_str(&format!(" (the oldest {} were left out)", t.dropped));
+                }
+                note
+            })
+            .unwrap_or_default();
+
         // Determine if this is a tag-team debate (joining an existing one)
         let (thread_id, initial_history, previous_debaters) = if let Some(prev_state) =
             existing_debate
@@ -150,7 +232,7 @@ impl DebateHandler {
                     });
 
             // Send response in the thread
-            command
+            responder
                 .create_interaction_response(&serenity_ctx.http, |r| {
                     r.kind(InteractionResponseType::ChannelMessageWithSource)
                         .interaction_response_data(|m| {
@@ -182,7 +264,7 @@ impl DebateHandler {
                 );
 
                 // Send response message (no thread creation)
-                command
+                responder
                     .create_interaction_response(&serenity_ctx.http, |r| {
                         r.kind(InteractionResponseType::ChannelMessageWithSource)
                             .interaction_response_data(|m| {
@@ -190,7 +272,7 @@ impl DebateHandler {
                                     "**Debate Starting!**\n\n\
                                     **Topic:** {topic}\n\
                                     **Debaters:** {persona1_name} vs {persona2_name}\n\
-                                    **Rounds:** {rounds}"
+                                    **Rounds:** {rounds}{import_note}"
                                 ))
                             })
                     })
@@ -219,7 +301,7 @@ impl DebateHandler {
                 );
 
                 // Send initial response message
-                command
+                responder
                     .create_interaction_response(&serenity_ctx.http, |r| {
                         r.kind(InteractionResponseType::ChannelMessageWithSource)
                             .interaction_response_data(|m| {
@@ -227,7 +309,7 @@ impl DebateHandler {
                                     "**Debate Starting!**\n\n\
                                     **Topic:** {topic}\n\
                                     **Debaters:** {persona1_name} vs {persona2_name}\n\
-                                    **Rounds:** {rounds}"
+                                    **Rounds:** {rounds}{import_note}"
                                 ))
                             })
                     })
@@ -277,7 +359,7 @@ impl DebateHandler {
                             "**Debate Started!**\n\n\
                             **Topic:** {topic}\n\
                             **Debaters:** {persona1_name} vs {persona2_name}\n\
-                            **Rounds:** {rounds}\n\n\
+                            **Rounds:** {rounds}{import_note}\n\n\
                             The debate is happening in the thread below!"
                         ))
                     })
@@ -309,6 +391,19 @@ impl DebateHandler {
             .as_ref()
             .map(|tc| crate::features::format_prior_context(tc, &ctx.persona_manager));
 
+        // An imported transcript stands in for the previous debaters
+        let (initial_history, previous_debaters, imported_context) = match &imported {
+            Some(t) => (
+                Some(t.history()),
+                t.previous_speakers(),
+                Some(crate::features::format_prior_context(
+                    &t.context,
+                    &ctx.persona_manager,
+                )),
+            ),
+            None => (initial_history, previous_debaters, None),
+        };
+
         // Create debate config with optional initial history and rules
         let config = DebateConfig {
             persona1_id: persona1_id.clone(),
@@ -321,24 +416,29 @@ impl DebateHandler {
             previous_debaters,
             rules: rules.clone(),
             opening_only,
+            prior_context: imported_context,
         };
 
         // Clone what we need for the async closure
         let openai_model = ctx.openai_model.clone();
+        let chat_client = ctx.chat_client.clone();
         let usage_tracker = ctx.usage_tracker.clone();
         let user_id = command.user.id.to_string();
-        let guild_id = command.guild_id.map(|g| g.to_string());
         let channel_id_str = thread_id.to_string();
 
         // Run the debate (this spawns the orchestrator)
         let ctx_clone = serenity_ctx.clone();
+        let database = ctx.database.clone();
 
-        tokio::spawn(async move {
+        spawn_tracked(TaskKind::Discussions, async move {
             // Create a closure for getting AI responses
             let get_response = |system_prompt: String,
                                 user_message: String,
                                 history: Vec<(String, String)>| {
+                let system_prompt =
+                    scenes::with_scene(guardrails.apply(&system_prompt), scene.as_deref());
                 let model = openai_model.clone();
+                let client = chat_client.clone();
                 let tracker = usage_tracker.clone();
                 let uid = user_id.clone();
                 let gid = guild_id.clone();
@@ -380,8 +480,8 @@ impl DebateHandler {
                         tool_calls: None,
                     });
 
-                    let chat_completion = openai::chat::ChatCompletion::builder(&model, messages)
-                        .create()
+                    let chat_completion = client
+                        .create_chat_completion(&model, messages)
                         .await
                         .map_err(|e| anyhow::anyhow!("OpenAI API error: {}", e))?;
 
@@ -418,11 +518,45 @@ impl DebateHandler {
                     })
                     .await;
             }
+            save_discussion_state_logged(&database, DiscussionType::Debate, thread_id.0).await;
         });
 
         Ok(())
     }
 
+    /// Read the transcript for /debate import from the file or the pasted text
+    ///
+    /// The inner error is a message for the user.
+    async fn load_transcript(
+        options: &[CommandDataOption],
+        topic: &str,
+        rules: Option<String>,
+    ) -> Result<Result<ImportedTranscript, String>> {
+        let text = if let Some(file) = get_attachment_option(options, "file") {
+            if file.size > MAX_TRANSCRIPT_BYTES {
+                return Ok(Err(format!(
+                    "That transcript is too large. Files can be up to {} KB.",
+                    MAX_TRANSCRIPT_BYTES / 1024
+                )));
+            }
+            match String::from_utf8(file.download().await?) {
+                Ok(text) => text,
+                Err(_) => {
+                    return Ok(Err(
+                        "That file isn't plain text. Attach a .txt or .md transcript.".to_string(),
+                    ))
+                }
+            }
+        } else if let Some(text) = get_string_option(options, "transcript") {
+            text
+        } else {
+            return Ok(Err(
+                "Paste the transcript or attach it as a file to import it.".to_string(),
+            ));
+        };
+        Ok(parse_transcript(&text, topic, rules))
+    }
+
     /// Fetch thread history for tag-team debate context
     async fn fetch_thread_history(
         serenity_ctx: &Context,
@@ -0,0 +1,385 @@
+//! Dice command handler
+//!
+//! Handles: roll, initiative
+//!
+//! `/roll` works anywhere and can have the member's persona narrate the
+//! outcome. `/initiative` only works in threads, one encounter per thread,
+//! and anyone in the thread can run it, as a table's game master would.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::{info, warn};
+use rand::Rng;
+use serenity::model::application::interaction::application_command::{
+    ApplicationCommandInteraction, CommandDataOption,
+};
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::prelude::Context;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::commands::context::{is_in_thread_channel, CommandContext};
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::{get_bool_option, get_integer_option, get_string_option};
+use crate::database::InitiativeEntry;
+use crate::features::analytics::CostBucket;
+use crate::features::dice::{
+    initiative_lines, turn_after_removal, validate_combatant, Advantage, DiceExpr, DiceRoll,
+    DICE_FEATURE, MAX_INITIATIVE_ENTRIES, NARRATION_PROMPT,
+};
+
+/// Handler for /roll and /initiative
+pub struct DiceHandler;
+
+#[async_trait]
+impl SlashCommandHandler for DiceHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["roll", "initiative"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let guild_id = command.guild_id.map(|id| id.to_string());
+        if let Some(guild_id) = &guild_id {
+            if !ctx
+                .database
+                .is_feature_enabled(DICE_FEATURE, None, Some(guild_id))
+                .await?
+            {
+                return Self::reply(
+                    &responder,
+                    serenity_ctx,
+                    "❌ Dice are disabled on this server.",
+                    true,
+                )
+                .await;
+            }
+        }
+        match command.data.name.as_str() {
+            "roll" => Self::handle_roll(&ctx, serenity_ctx, command, guild_id.as_deref()).await,
+            _ => {
+                let Some(guild_id) = guild_id else {
+                    return Ok(());
+                };
+                if !is_in_thread_channel(serenity_ctx, command.channel_id).await? {
+                    return Self::reply(
+                        &responder,
+                        serenity_ctx,
+                        "❌ Initiative is tracked per thread. Start a thread for the encounter and run it there.",
+                        true,
+                    )
+                    .await;
+                }
+                let subcommand = command
+                    .data
+                    .options
+                    .first()
+                    .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+                match Self::handle_initiative(&ctx, command, &guild_id, subcommand).await? {
+                    Ok(content) => Self::reply(&responder, serenity_ctx, &content, false).await,
+                    Err(e) => Self::reply(&responder, serenity_ctx, &format!("❌ {e}"), true).await,
+                }
+            }
+        }
+    }
+}
+
+impl DiceHandler {
+    /// Handle /roll
+    async fn handle_roll(
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        guild_id: Option<&str>,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let options = &command.data.options;
+        let notation = get_string_option(options, "dice").unwrap_or_default();
+        let advantage = Advantage::from_option(get_string_option(options, "mode").as_deref());
+        let expr = match DiceExpr::parse(&notation, advantage) {
+            Ok(expr) => expr,
+            Err(e) => {
+                return Self::reply(&responder, serenity_ctx, &format!("❌ {e}"), true).await;
+            }
+        };
+        let roll = expr.roll();
+        let reason = get_string_option(options, "reason");
+        let name = Self::display_name(command);
+        let result = Self::roll_line(&name, notation.trim(), reason.as_deref(), &roll);
+        info!(
+            "/roll | User: {} | {} = {}",
+            command.user.id,
+            notation.trim(),
+            roll.total
+        );
+
+        if !get_bool_option(options, "narrate").unwrap_or(false) {
+            return Self::reply(&responder, serenity_ctx, &result, false).await;
+        }
+        responder
+            .create_interaction_response(&serenity_ctx.http, |response| {
+                response.kind(InteractionResponseType::DeferredChannelMessageWithSource)
+            })
+            .await?;
+        let content =
+            match Self::narrate(ctx, command, guild_id, &name, reason.as_deref(), &roll).await {
+                Ok(narration) => format!("{result}\n\n{narration}"),
+                Err(e) => {
+                    warn!("⚠️ Persona narration for /roll failed: {e}");
+                    result
+                }
+            };
+        command
+            .edit_original_interaction_response(&serenity_ctx.http, |response| {
+                response.content(content)
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// The public roll result
+    fn roll_line(name: &str, notation: &str, reason: Option<&str>, roll: &DiceRoll) -> String {
+        let reason = reason
+            .map(|r| format!(" for *{}*", r.trim()))
+            .unwrap_or_default();
+        let natural = match roll.natural() {
+            Some(20) => " 🌟 **Natural 20!**",
+            Some(1) => " 💀 **Natural 1!**",
+            _ => "",
+        };
+        format!(
+            "🎲 **{name}** rolls `{notation}`{reason}\n{} = **{}**{natural}",
+            roll.breakdown(),
+            roll.total
+        )
+    }
+
+    /// Have the member's persona narrate the roll
+    async fn narrate(
+        ctx: &CommandContext,
+        command: &ApplicationCommandInteraction,
+        guild_id: Option<&str>,
+        name: &str,
+        reason: Option<&str>,
+        roll: &DiceRoll,
+    ) -> Result<String> {
+        let user_id = command.user.id.to_string();
+        let channel_id = command.channel_id.to_string();
+        let persona = match guild_id {
+            Some(guild_id) => {
+                ctx.database
+                    .get_persona_with_channel(&user_id, guild_id, &channel_id, None)
+                    .await?
+            }
+            None => ctx.database.get_user_persona(&user_id).await?,
+        };
+        let persona_prompt = ctx.persona_manager.get_system_prompt(&persona, None);
+        let natural = match roll.natural() {
+            Some(n) => format!("\nNatural {n}"),
+            None => String::new(),
+        };
+        let narration = ctx
+            .get_ai_response(
+                &format!("{persona_prompt}\n\n{NARRATION_PROMPT}"),
+                &format!(
+                    "Player: {name}\nRolling for: {}\nTotal: {}{natural}",
+                    reason.unwrap_or("an unspecified action"),
+                    roll.total
+                ),
+                Vec::new(),
+                Uuid::new_v4(),
+                Some(&user_id),
+                guild_id,
+                Some(&channel_id),
+                CostBucket::Dice,
+            )
+            .await?;
+        Ok(narration.trim().to_string())
+    }
+
+    /// Handle an /initiative subcommand, returning the public reply or the
+    /// error shown to the member
+    async fn handle_initiative(
+        ctx: &CommandContext,
+        command: &ApplicationCommandInteraction,
+        guild_id: &str,
+        subcommand: &CommandDataOption,
+    ) -> Result<Result<String, String>> {
+        let db = &ctx.database;
+        let thread_id = command.channel_id.to_string();
+        let options = &subcommand.options;
+        let order = db.get_initiative_order(&thread_id).await?;
+        let (turn, round) = db.get_initiative_turn(&thread_id).await?;
+
+        let content = match subcommand.name.as_str() {
+            "add" => {
+                let name = match get_string_option(options, "name") {
+                    Some(name) => name,
+                    None => Self::display_name(command),
+                };
+                let name = match validate_combatant(&name) {
+                    Ok(name) => name,
+                    Err(e) => return Ok(Err(e)),
+                };
+                let replacing = order.iter().any(|e| e.name.eq_ignore_ascii_case(&name));
+                if !replacing && order.len() >= MAX_INITIATIVE_ENTRIES {
+                    return Ok(Err(format!(
+                        "This encounter already has {MAX_INITIATIVE_ENTRIES} combatants."
+                    )));
+                }
+                let modifier = get_integer_option(options, "modifier").unwrap_or(0);
+                let (initiative, detail) = match get_integer_option(options, "value") {
+                    Some(value) => (value, "set".to_string()),
+                    None => {
+                        let rolled = rand::rng().random_range(1..=20);
+                        (rolled + modifier, format!("d20 ({rolled}) {modifier:+}"))
+                    }
+                };
+                db.upsert_initiative_entry(&InitiativeEntry {
+                    thread_id: thread_id.clone(),
+                    guild_id: guild_id.to_string(),
+                    name: name.clone(),
+                    initiative,
+                    modifier,
+                    added_by: command.user.id.to_string(),
+                })
+                .await?;
+                info!("/initiative add | Thread: {thread_id} | {name} = {initiative}");
+                // Whoever's turn it was keeps it as the order shifts around them
+                let current = order.get(turn).map(|e| e.name.clone());
+                let order = db.get_initiative_order(&thread_id).await?;
+                let turn = current
+                    .and_then(|current| order.iter().position(|e| e.name == current))
+                    .unwrap_or(turn);
+                db.set_initiative_turn(&thread_id, turn, round).await?;
+                format!(
+                    "⚔️ **{name}** {} initiative **{initiative}** ({detail}).\n\n{}",
+                    if replacing { "now has" } else { "joins with" },
+                    Self::order_text(&order, turn, round)
+                )
+            }
+            "list" => {
+                if order.is_empty() {
+                    return Ok(Err(
+                        "No encounter in this thread yet. Add combatants with `/initiative add`."
+                            .to_string(),
+                    ));
+                }
+                Self::order_text(&order, turn, round)
+            }
+            "next" => {
+                if order.is_empty() {
+                    return Ok(Err(
+                        "No encounter in this thread yet. Add combatants with `/initiative add`."
+                            .to_string(),
+                    ));
+                }
+                let (turn, round) = if turn + 1 >= order.len() {
+                    (0, round + 1)
+                } else {
+                    (turn + 1, round)
+                };
+                db.set_initiative_turn(&thread_id, turn, round).await?;
+                format!(
+                    "➡️ Round {round}: it's **{}**'s turn.\n\n{}",
+                    order[turn].name,
+                    Self::order_text(&order, turn, round)
+                )
+            }
+            "remove" => {
+                let name = get_string_option(options, "name").unwrap_or_default();
+                let Some(index) = order
+                    .iter()
+                    .position(|e| e.name.eq_ignore_ascii_case(name.trim()))
+                else {
+                    return Ok(Err(format!("**{}** isn't in the order.", name.trim())));
+                };
+                db.delete_initiative_entry(&thread_id, &order[index].name)
+                    .await?;
+                let order = db.get_initiative_order(&thread_id).await?;
+                let (turn, round) = turn_after_removal(turn, round, index, order.len());
+                db.set_initiative_turn(&thread_id, turn, round).await?;
+                let removed = format!("🚪 **{}** leaves the encounter.", name.trim());
+                if order.is_empty() {
+                    removed
+                } else {
+                    format!("{removed}\n\n{}", Self::order_text(&order, turn, round))
+                }
+            }
+            _ => {
+                let removed = db.clear_initiative(&thread_id).await?;
+                info!("/initiative clear | Thread: {thread_id} | {removed} combatants");
+                format!(
+                    "🏁 Encounter over after {} round{}. The order is cleared.",
+                    round,
+                    if round == 1 { "" } else { "s" }
+                )
+            }
+        };
+        Ok(Ok(content))
+    }
+
+    fn order_text(order: &[InitiativeEntry], turn: usize, round: i64) -> String {
+        format!(
+            "**Initiative: round {round}**\n{}",
+            initiative_lines(order, turn).join("\n")
+        )
+    }
+
+    fn display_name(command: &ApplicationCommandInteraction) -> String {
+        command
+            .member
+            .as_ref()
+            .and_then(|m| m.nick.clone())
+            .unwrap_or_else(|| command.user.name.clone())
+    }
+
+    async fn reply(
+        responder: &InteractionResponder,
+        serenity_ctx: &Context,
+        content: &str,
+        ephemeral: bool,
+    ) -> Result<()> {
+        responder
+            .create_interaction_response(&serenity_ctx.http, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|m| m.content(content).ephemeral(ephemeral))
+            })
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dice_handler_commands() {
+        let handler = DiceHandler;
+        assert_eq!(handler.command_names(), &["roll", "initiative"]);
+    }
+
+    #[test]
+    fn test_roll_line() {
+        let roll = DiceExpr::parse("d20+5", Advantage::None)
+            .unwrap()
+            .roll_with(|_| 20);
+        assert_eq!(
+            DiceHandler::roll_line("Aria", "d20+5", Some("stealth"), &roll),
+            "🎲 **Aria** rolls `d20+5` for *stealth*\n[20] + 5 = **25** 🌟 **Natural 20!**"
+        );
+    }
+}
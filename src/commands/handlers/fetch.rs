@@ -2,10 +2,14 @@
 //!
 //! Handles: fetch
 //!
-//! - **Version**: 1.2.0
+//! - **Version**: 1.5.1
 //! - **Since**: 4.2.0
 //!
 //! ## Changelog
+//! - 1.5.1: HTML text extraction shared with link previews
+//! - 1.5.0: Active persona honours category defaults
+//! - 1.4.0: Numbered source footnotes in the response footer
+//! - 1.3.0: Initial responses go through InteractionResponder (auto-defer safe)
 //! - 1.2.0: Use shared persona embed builders from core::embeds
 //! - 1.1.0: Add file download and upload support for non-HTML content
 //! - 1.0.0: Initial implementation
@@ -23,12 +27,14 @@ use std::sync::Arc;
 use std::time::Instant;
 use uuid::Uuid;
 
-use crate::commands::context::{is_in_thread_channel, CommandContext};
+use crate::commands::context::{channel_category_id, is_in_thread_channel, CommandContext};
 use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
 use crate::commands::slash::get_string_option;
 use crate::core::{
     chunk_for_embed, continuation_embed, detect_content_kind, download_file, format_file_size,
-    is_within_upload_limit, max_upload_size, persona_embed, ContentKind, DownloadedFile,
+    is_within_upload_limit, max_upload_size, persona_embed, ContentKind, DownloadedFile, Source,
+    SourceList,
 };
 use crate::features::analytics::CostBucket;
 use serenity::builder::CreateEmbed;
@@ -70,6 +76,7 @@ impl FetchHandler {
         command: &ApplicationCommandInteraction,
         request_id: Uuid,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let start_time = Instant::now();
 
         // Extract options
@@ -90,7 +97,7 @@ impl FetchHandler {
 
         // Basic URL validation
         if !url.starts_with("http://") && !url.starts_with("https://") {
-            command
+            responder
                 .create_interaction_response(&serenity_ctx.http, |r| {
                     r.kind(InteractionResponseType::ChannelMessageWithSource)
                         .interaction_response_data(|m| {
@@ -104,7 +111,7 @@ impl FetchHandler {
 
         // Defer response (fetching + AI call will take time)
         info!("[{request_id}] Deferring interaction response");
-        command
+        responder
             .create_interaction_response(&serenity_ctx.http, |r| {
                 r.kind(InteractionResponseType::DeferredChannelMessageWithSource)
             })
@@ -242,10 +249,11 @@ impl FetchHandler {
             extracted_text.len()
         );
 
-        // Resolve user's active persona (channel -> user -> guild -> env -> fallback)
+        // Resolve user's active persona (channel -> category -> user -> guild -> env -> fallback)
         let persona_id = if let Some(gid) = guild_id {
+            let category_id = channel_category_id(serenity_ctx, command.channel_id).await;
             ctx.database
-                .get_persona_with_channel(user_id, gid, channel_id)
+                .get_persona_with_channel(user_id, gid, channel_id, category_id.as_deref())
                 .await?
         } else {
             ctx.database
@@ -259,11 +267,17 @@ impl FetchHandler {
         debug!("[{request_id}] Using persona: {persona_id}");
 
         // Build user message with page content
-        let user_message = Self::build_user_message(url, &extracted_text, question);
+        let mut sources = SourceList::new();
+        sources.add(Source::url(url));
+        let user_message = format!(
+            "{}{}",
+            sources.citation_instructions(),
+            Self::build_user_message(url, &extracted_text, question)
+        );
 
         // Log usage
         ctx.database
-            .log_usage(user_id, "fetch", Some(&persona_id))
+            .log_usage(user_id, guild_id, "fetch", Some(&persona_id))
             .await?;
 
         // Get AI response
@@ -296,8 +310,7 @@ impl FetchHandler {
                         debug!("[{request_id}] Response split into {} chunks", chunks.len());
 
                         if let Some(first_chunk) = chunks.first() {
-                            let mut embed = persona_embed(p, first_chunk);
-                            Self::add_fetch_footer(&mut embed, url, question);
+                            let embed = persona_embed(p, first_chunk);
                             command
                                 .edit_original_interaction_response(&serenity_ctx.http, |r| {
                                     r.set_embed(embed)
@@ -305,9 +318,12 @@ impl FetchHandler {
                                 .await?;
                         }
 
-                        for chunk in chunks.iter().skip(1) {
+                        for (i, chunk) in chunks.iter().enumerate().skip(1) {
                             if !chunk.trim().is_empty() {
-                                let embed = continuation_embed(p, chunk);
+                                let mut embed = continuation_embed(p, chunk);
+                                if i == chunks.len() - 1 {
+                                    Self::add_fetch_footer(&mut embed, &sources, question);
+                                }
                                 command
                                     .create_followup_message(&serenity_ctx.http, |m| {
                                         m.set_embed(embed)
@@ -317,7 +333,7 @@ impl FetchHandler {
                         }
                     } else {
                         let mut embed = persona_embed(p, &response);
-                        Self::add_fetch_footer(&mut embed, url, question);
+                        Self::add_fetch_footer(&mut embed, &sources, question);
                         command
                             .edit_original_interaction_response(&serenity_ctx.http, |r| {
                                 r.set_embed(embed)
@@ -500,7 +516,7 @@ impl FetchHandler {
     }
 
     /// Extract meaningful text content from HTML
-    fn extract_text(html: &str, request_id: Uuid) -> String {
+    pub(crate) fn extract_text(html: &str, request_id: Uuid) -> String {
         let document = Html::parse_document(html);
 
         // Try to find main content areas first
@@ -618,20 +634,14 @@ impl FetchHandler {
         )
     }
 
-    /// Add fetch-specific footer showing URL and optional question
-    fn add_fetch_footer(embed: &mut CreateEmbed, url: &str, question: Option<&str>) {
-        // Truncate URL for footer if needed (Discord footer limit is 2048 chars)
-        let display_url: String = if url.len() > 200 {
-            format!("{}...", &url[..197])
-        } else {
-            url.to_string()
-        };
-
+    /// Add fetch-specific footer showing the optional question and source footnotes
+    fn add_fetch_footer(embed: &mut CreateEmbed, sources: &SourceList, question: Option<&str>) {
+        let footnotes = sources.footnotes();
         if let Some(q) = question {
             let truncated_q: String = q.chars().take(100).collect();
-            embed.footer(|f| f.text(format!("Q: {truncated_q} | {display_url}")));
+            embed.footer(|f| f.text(format!("Q: {truncated_q}\n{footnotes}")));
         } else {
-            embed.footer(|f| f.text(format!("Summary of {display_url}")));
+            embed.footer(|f| f.text(footnotes));
         }
     }
 }
@@ -0,0 +1,297 @@
+//! Fork command handler
+//!
+//! Handles: fork
+//!
+//! Copies the current conversation (a channel's stored history, a thread's
+//! messages, or the user's DM history) into a new thread. Replies in the fork
+//! see the copied context first; the original conversation is not touched.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::{error, info};
+use serenity::builder::{CreateEmbed, GetMessages};
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::model::channel::{Channel, ChannelType, Message};
+use serenity::model::id::{ChannelId, UserId};
+use serenity::prelude::Context;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::{get_bool_option, get_string_option};
+
+/// Most recent messages carried into a fork
+pub const FORK_MESSAGES: u8 = 50;
+
+/// Discord's limit on thread names
+const THREAD_NAME_LIMIT: usize = 100;
+
+/// Handler for /fork command
+pub struct ForkHandler;
+
+#[async_trait]
+impl SlashCommandHandler for ForkHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["fork"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let request_id = Uuid::new_v4();
+        self.handle_fork(&ctx, serenity_ctx, command, request_id)
+            .await
+    }
+}
+
+impl ForkHandler {
+    /// Handle /fork command
+    async fn handle_fork(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        request_id: Uuid,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let user_id = command.user.id.to_string();
+        let from_dm = get_bool_option(&command.data.options, "from_dm").unwrap_or(false);
+        let name = get_string_option(&command.data.options, "name");
+
+        info!("[{request_id}] /fork command | User: {user_id} | From DM: {from_dm}");
+
+        let channel = match serenity_ctx.http.get_channel(command.channel_id.0).await {
+            Ok(Channel::Guild(channel)) => channel,
+            _ => {
+                responder
+                    .create_interaction_response(&serenity_ctx.http, |r| {
+                        r.kind(InteractionResponseType::ChannelMessageWithSource)
+                            .interaction_response_data(|m| {
+                                m.content(
+                                    "Discord doesn't allow threads in DMs. Run `/fork from_dm:True` \
+                                     in a server channel to continue your DM conversation there.",
+                                )
+                                .ephemeral(true)
+                            })
+                    })
+                    .await?;
+                return Ok(());
+            }
+        };
+        let in_thread = matches!(
+            channel.kind,
+            ChannelType::PublicThread | ChannelType::PrivateThread
+        );
+        // Forks of a thread sit next to it in the parent channel
+        let parent_id = if in_thread {
+            channel.parent_id.unwrap_or(channel.id)
+        } else {
+            channel.id
+        };
+
+        responder.defer(&serenity_ctx.http).await?;
+
+        // Export the conversation being forked
+        let (source_channel_id, source_label, messages) = if from_dm {
+            let dm = command.user.create_dm_channel(&serenity_ctx.http).await?;
+            let history = ctx
+                .database
+                .get_conversation_history(&user_id, &dm.id.to_string(), FORK_MESSAGES as i64)
+                .await?;
+            (dm.id, "your DMs".to_string(), history)
+        } else if in_thread {
+            let mut history = ctx
+                .database
+                .get_fork_context(&channel.id.to_string(), FORK_MESSAGES as i64)
+                .await?;
+            history.extend(thread_history(serenity_ctx, channel.id).await?);
+            let skip = history.len().saturating_sub(FORK_MESSAGES as usize);
+            (
+                channel.id,
+                format!("<#{}>", channel.id),
+                history.split_off(skip),
+            )
+        } else {
+            let history = ctx
+                .database
+                .get_conversation_history(&user_id, &channel.id.to_string(), FORK_MESSAGES as i64)
+                .await?;
+            (channel.id, format!("<#{}>", channel.id), history)
+        };
+
+        if messages.is_empty() {
+            responder
+                .create_interaction_response(&serenity_ctx.http, |r| {
+                    r.interaction_response_data(|m| {
+                        m.content(format!(
+                            "There's no conversation with me in {source_label} to fork yet."
+                        ))
+                    })
+                })
+                .await?;
+            return Ok(());
+        }
+
+        let thread_name = fork_thread_name(name.as_deref(), &channel.name);
+        // DM history stays out of public view
+        let kind = if from_dm {
+            ChannelType::PrivateThread
+        } else {
+            ChannelType::PublicThread
+        };
+        let thread = match parent_id
+            .create_private_thread(&serenity_ctx.http, |t| {
+                t.name(&thread_name).kind(kind).auto_archive_duration(1440)
+            })
+            .await
+        {
+            Ok(thread) => thread,
+            Err(e) => {
+                error!("[{request_id}] Failed to create fork thread: {e}");
+                responder
+                    .create_interaction_response(&serenity_ctx.http, |r| {
+                        r.interaction_response_data(|m| {
+                            m.content(
+                                "Could not create the fork thread. Make sure I have permission to create threads here.",
+                            )
+                        })
+                    })
+                    .await?;
+                return Ok(());
+            }
+        };
+        if from_dm {
+            thread
+                .id
+                .add_thread_member(&serenity_ctx.http, command.user.id)
+                .await?;
+        }
+
+        ctx.database
+            .fork_conversation(
+                &thread.id.to_string(),
+                &source_channel_id.to_string(),
+                &user_id,
+                &messages,
+            )
+            .await?;
+        let guild_id = command.guild_id.map(|id| id.to_string());
+        ctx.database
+            .log_usage(&user_id, guild_id.as_deref(), "fork", None)
+            .await?;
+
+        let intro = fork_intro_embed(&source_label, messages.len(), command.user.id);
+        let _ = thread
+            .id
+            .send_message(&serenity_ctx.http, |m| m.set_embed(intro))
+            .await;
+
+        responder
+            .create_interaction_response(&serenity_ctx.http, |r| {
+                r.interaction_response_data(|m| {
+                    m.content(format!(
+                        "🍴 Forked {} messages from {source_label} into <#{}>.",
+                        messages.len(),
+                        thread.id
+                    ))
+                })
+            })
+            .await?;
+
+        info!(
+            "[{request_id}] Forked {} messages from {source_channel_id} into thread {}",
+            messages.len(),
+            thread.id
+        );
+        Ok(())
+    }
+}
+
+/// Recent thread messages as (role, content), oldest first
+async fn thread_history(
+    serenity_ctx: &Context,
+    thread_id: ChannelId,
+) -> Result<Vec<(String, String)>> {
+    let messages = thread_id
+        .messages(&serenity_ctx.http, |builder: &mut GetMessages| {
+            builder.limit(FORK_MESSAGES as u64)
+        })
+        .await?;
+    let bot_id = serenity_ctx.cache.current_user_id();
+
+    Ok(messages
+        .iter()
+        .rev()
+        .filter_map(|m| message_turn(m, bot_id))
+        .collect())
+}
+
+/// A message as a conversation turn (persona replies are embeds, so fall back to their text)
+fn message_turn(message: &Message, bot_id: UserId) -> Option<(String, String)> {
+    let content = if message.content.is_empty() {
+        message.embeds.first()?.description.clone()?
+    } else {
+        message.content.clone()
+    };
+    let role = if message.author.id == bot_id {
+        "assistant"
+    } else {
+        "user"
+    };
+    Some((role.to_string(), content))
+}
+
+/// Thread name from the user's choice or the forked channel
+fn fork_thread_name(requested: Option<&str>, channel_name: &str) -> String {
+    let name = match requested.map(str::trim).filter(|n| !n.is_empty()) {
+        Some(name) => name.to_string(),
+        None => format!("Fork: {channel_name}"),
+    };
+    name.chars().take(THREAD_NAME_LIMIT).collect()
+}
+
+fn fork_intro_embed(source_label: &str, message_count: usize, user_id: UserId) -> CreateEmbed {
+    CreateEmbed::default()
+        .title("🍴 Conversation Fork")
+        .description(format!(
+            "<@{user_id}> forked {message_count} messages from {source_label}.\n\n\
+            Mention me here to take the conversation in a new direction. \
+            The original conversation is unchanged."
+        ))
+        .color(0x5865F2)
+        .to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fork_handler_commands() {
+        let handler = ForkHandler;
+        assert_eq!(handler.command_names(), &["fork"]);
+    }
+
+    #[test]
+    fn test_fork_thread_name() {
+        assert_eq!(fork_thread_name(None, "general"), "Fork: general");
+        assert_eq!(fork_thread_name(Some("  "), "general"), "Fork: general");
+        assert_eq!(fork_thread_name(Some("Plan B"), "general"), "Plan B");
+        assert_eq!(
+            fork_thread_name(Some(&"x".repeat(150)), "general").len(),
+            THREAD_NAME_LIMIT
+        );
+    }
+}
@@ -0,0 +1,182 @@
+//! Glossary command handler
+//!
+//! Handles: glossary
+//!
+//! Anyone can list the server's glossary; adding and removing terms need
+//! Manage Server. Generation paths add the definitions of terms a message
+//! mentions to the system prompt (see `features::glossary`).
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use serenity::model::application::interaction::application_command::{
+    ApplicationCommandInteraction, CommandDataOption,
+};
+use serenity::prelude::Context;
+use std::sync::Arc;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::get_string_option;
+use crate::database::GlossaryEntry;
+use crate::features::glossary::{
+    list_line, validate_definition, validate_term, GLOSSARY_FEATURE, MAX_TERMS_PER_GUILD,
+};
+
+/// Handler for /glossary
+pub struct GlossaryHandler;
+
+#[async_trait]
+impl SlashCommandHandler for GlossaryHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["glossary"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let Some(guild_id) = command.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        if !ctx
+            .database
+            .is_feature_enabled(GLOSSARY_FEATURE, None, Some(&guild_id))
+            .await?
+        {
+            return responder
+                .reply_ephemeral(serenity_ctx, "❌ The glossary is disabled on this server.")
+                .await;
+        }
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+        let options = &subcommand.options;
+
+        let manages = matches!(subcommand.name.as_str(), "add" | "remove");
+        let can_manage = command
+            .member
+            .as_ref()
+            .and_then(|m| m.permissions)
+            .is_some_and(|p| p.manage_guild());
+        if manages && !can_manage {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "❌ You need the Manage Server permission to change the glossary.",
+                )
+                .await;
+        }
+
+        let content = match subcommand.name.as_str() {
+            "add" => Self::handle_add(&ctx, command, &guild_id, options).await?,
+            "remove" => {
+                let term = get_string_option(options, "term").unwrap_or_default();
+                let term = term.trim();
+                if ctx.database.delete_glossary_term(&guild_id, term).await? {
+                    info!(
+                        "/glossary remove | User: {} | Guild: {guild_id} | {term}",
+                        command.user.id
+                    );
+                    format!("🗑️ Removed **{term}** from the glossary.")
+                } else {
+                    format!("❌ There's no **{term}** in the glossary.")
+                }
+            }
+            _ => Self::handle_list(&ctx, &guild_id).await?,
+        };
+        responder.reply_ephemeral(serenity_ctx, &content).await
+    }
+}
+
+impl GlossaryHandler {
+    /// Handle /glossary add, returning the reply
+    async fn handle_add(
+        ctx: &CommandContext,
+        command: &ApplicationCommandInteraction,
+        guild_id: &str,
+        options: &[CommandDataOption],
+    ) -> Result<String> {
+        let validated = validate_term(&get_string_option(options, "term").unwrap_or_default())
+            .and_then(|term| {
+                Ok(GlossaryEntry {
+                    guild_id: guild_id.to_string(),
+                    term,
+                    definition: validate_definition(
+                        &get_string_option(options, "definition").unwrap_or_default(),
+                    )?,
+                    created_by: command.user.id.to_string(),
+                })
+            });
+        let entry = match validated {
+            Ok(entry) => entry,
+            Err(e) => return Ok(format!("❌ {e}")),
+        };
+
+        let db = &ctx.database;
+        if db.count_glossary_terms(guild_id).await? >= MAX_TERMS_PER_GUILD
+            && !db
+                .get_glossary_terms(guild_id)
+                .await?
+                .iter()
+                .any(|existing| existing.term.eq_ignore_ascii_case(&entry.term))
+        {
+            return Ok(format!(
+                "❌ The glossary already holds {MAX_TERMS_PER_GUILD} terms. Remove one first."
+            ));
+        }
+        let added = db.upsert_glossary_term(&entry).await?;
+        info!(
+            "/glossary add | User: {} | Guild: {guild_id} | {} | New: {added}",
+            command.user.id, entry.term
+        );
+        Ok(format!(
+            "✅ {} in the glossary:\n{}\nPersonas will use this meaning when someone mentions it.",
+            if added { "Added" } else { "Updated" },
+            list_line(&entry)
+        ))
+    }
+
+    /// Handle /glossary list, returning the reply
+    async fn handle_list(ctx: &CommandContext, guild_id: &str) -> Result<String> {
+        let entries = ctx.database.get_glossary_terms(guild_id).await?;
+        if entries.is_empty() {
+            return Ok("The glossary is empty. Admins add terms with `/glossary add`.".to_string());
+        }
+
+        let mut content = format!("📖 **Server glossary** ({} terms)", entries.len());
+        for (shown, entry) in entries.iter().enumerate() {
+            let line = format!("\n{}", list_line(entry));
+            // Leave room for the overflow note within Discord's 2000 characters
+            if content.chars().count() + line.chars().count() > 1950 {
+                content.push_str(&format!("\n…and {} more", entries.len() - shown));
+                break;
+            }
+            content.push_str(&line);
+        }
+        Ok(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glossary_handler_commands() {
+        let handler = GlossaryHandler;
+        assert_eq!(handler.command_names(), &["glossary"]);
+    }
+}
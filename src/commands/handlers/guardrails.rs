@@ -0,0 +1,256 @@
+//! Guardrails command handler
+//!
+//! Handles: guardrails
+//!
+//! Members with Manage Server show or set the guild's guardrail profile and
+//! the NSFW channel policy. The profile itself is applied by the generation
+//! paths (see `features::guardrails`).
+//!
+//! - **Version**: 1.1.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.1.0: /guardrails nsfw; show includes the NSFW channel policy
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::prelude::Context;
+use std::sync::Arc;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::{get_bool_option, get_string_option};
+use crate::database::Database;
+use crate::features::guardrails::{
+    GuardrailProfile, FALLBACK_PERSONA, GUARDRAILS_FEATURE, GUARDRAIL_SETTING,
+    NSFW_IMAGINE_SETTING, NSFW_PROFILE_SETTING, STRICT_BLOCKED_PERSONAS,
+};
+use crate::features::personas::PersonaManager;
+
+/// Handler for /guardrails
+pub struct GuardrailsHandler;
+
+#[async_trait]
+impl SlashCommandHandler for GuardrailsHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["guardrails"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        self.handle_guardrails(&ctx, serenity_ctx, command).await
+    }
+}
+
+impl GuardrailsHandler {
+    /// Handle /guardrails show and set
+    async fn handle_guardrails(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let user_id = command.user.id.to_string();
+        let Some(guild_id) = command.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        let can_manage = command
+            .member
+            .as_ref()
+            .and_then(|m| m.permissions)
+            .is_some_and(|p| p.manage_guild());
+        if !can_manage {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "❌ You need the Manage Server permission to change the guardrail profile.",
+                )
+                .await;
+        }
+        if !ctx
+            .database
+            .is_feature_enabled(GUARDRAILS_FEATURE, None, Some(&guild_id))
+            .await?
+        {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "❌ Guardrail profiles are disabled on this server.",
+                )
+                .await;
+        }
+
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+
+        let content = match subcommand.name.as_str() {
+            "set" => {
+                match get_string_option(&subcommand.options, "profile")
+                    .as_deref()
+                    .and_then(GuardrailProfile::parse)
+                {
+                    Some(profile) => {
+                        ctx.database
+                            .set_guild_setting(&guild_id, GUARDRAIL_SETTING, profile.as_str())
+                            .await?;
+                        info!(
+                            "/guardrails set | User: {user_id} | Guild: {guild_id} | Profile: {}",
+                            profile.as_str()
+                        );
+                        format!(
+                            "✅ Guardrail profile set.\n\n{}",
+                            Self::describe(profile, &ctx.persona_manager)
+                        )
+                    }
+                    None => "❌ Pick `strict`, `standard` or `relaxed`.".to_string(),
+                }
+            }
+            "nsfw" => {
+                let profile = get_string_option(&subcommand.options, "profile");
+                match profile.as_deref() {
+                    Some("same") => {
+                        ctx.database
+                            .delete_guild_setting(&guild_id, NSFW_PROFILE_SETTING)
+                            .await?
+                    }
+                    Some(name) if GuardrailProfile::parse(name).is_some() => {
+                        ctx.database
+                            .set_guild_setting(&guild_id, NSFW_PROFILE_SETTING, name)
+                            .await?
+                    }
+                    _ => {}
+                }
+                if let Some(imagine) = get_bool_option(&subcommand.options, "imagine") {
+                    let value = if imagine { "enabled" } else { "disabled" };
+                    ctx.database
+                        .set_guild_setting(&guild_id, NSFW_IMAGINE_SETTING, value)
+                        .await?;
+                }
+                info!(
+                    "/guardrails nsfw | User: {user_id} | Guild: {guild_id} | Profile: {profile:?}"
+                );
+                format!(
+                    "✅ NSFW channel policy updated.\n\n{}",
+                    Self::describe_nsfw(&ctx.database, &guild_id).await?
+                )
+            }
+            _ => {
+                let profile = GuardrailProfile::for_guild(&ctx.database, Some(&guild_id)).await?;
+                format!(
+                    "{}\n\n{}",
+                    Self::describe(profile, &ctx.persona_manager),
+                    Self::describe_nsfw(&ctx.database, &guild_id).await?
+                )
+            }
+        };
+
+        responder.reply_ephemeral(serenity_ctx, &content).await
+    }
+
+    /// What a profile changes, for /guardrails show and set
+    fn describe(profile: GuardrailProfile, persona_manager: &PersonaManager) -> String {
+        let mut lines = vec![format!(
+            "**Guardrail profile:** `{}` — {}",
+            profile.as_str(),
+            profile.description()
+        )];
+        let blocked: Vec<String> = STRICT_BLOCKED_PERSONAS
+            .iter()
+            .filter(|id| !profile.allows_persona(id))
+            .map(|id| {
+                persona_manager
+                    .get_persona(id)
+                    .map(|p| p.name.clone())
+                    .unwrap_or_else(|| id.to_string())
+            })
+            .collect();
+        if !blocked.is_empty() {
+            lines.push(format!(
+                "Unavailable personas: {} (members using them get `{FALLBACK_PERSONA}` instead)",
+                blocked.join(", ")
+            ));
+        }
+        match profile.conflict_threshold() {
+            Some(threshold) => lines.push(format!(
+                "Conflict mediation threshold: {threshold} unless `conflict_sensitivity` is set"
+            )),
+            None => lines.push("Conflict mediation uses `conflict_sensitivity`.".to_string()),
+        }
+        lines.join("\n")
+    }
+
+    /// The NSFW channel policy, for /guardrails show and nsfw
+    async fn describe_nsfw(database: &Database, guild_id: &str) -> Result<String> {
+        let profile = database
+            .get_guild_setting(guild_id, NSFW_PROFILE_SETTING)
+            .await?
+            .as_deref()
+            .and_then(GuardrailProfile::parse);
+        let imagine = database
+            .get_guild_setting(guild_id, NSFW_IMAGINE_SETTING)
+            .await?
+            .as_deref()
+            == Some("enabled");
+        Ok(format!(
+            "**NSFW channels:** {} profile, /imagine {}",
+            profile.map_or("the server's", |p| p.as_str()),
+            if imagine { "allowed" } else { "refused" }
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guardrails_handler_commands() {
+        let handler = GuardrailsHandler;
+        assert_eq!(handler.command_names(), &["guardrails"]);
+    }
+
+    #[test]
+    fn test_describe_profile() {
+        let personas = PersonaManager::new();
+        let strict = GuardrailsHandler::describe(GuardrailProfile::Strict, &personas);
+        assert!(strict.starts_with("**Guardrail profile:** `strict`"));
+        assert!(strict.contains("Unavailable personas: "));
+        assert!(strict.contains("threshold: 0.35"));
+
+        let standard = GuardrailsHandler::describe(GuardrailProfile::Standard, &personas);
+        assert!(!standard.contains("Unavailable personas"));
+        assert!(standard.ends_with("Conflict mediation uses `conflict_sensitivity`."));
+    }
+
+    #[tokio::test]
+    async fn test_describe_nsfw() {
+        let db = Database::new(":memory:").await.unwrap();
+        assert_eq!(
+            GuardrailsHandler::describe_nsfw(&db, "g1").await.unwrap(),
+            "**NSFW channels:** the server's profile, /imagine refused"
+        );
+
+        db.set_guild_setting("g1", NSFW_PROFILE_SETTING, "relaxed")
+            .await
+            .unwrap();
+        db.set_guild_setting("g1", NSFW_IMAGINE_SETTING, "enabled")
+            .await
+            .unwrap();
+        assert_eq!(
+            GuardrailsHandler::describe_nsfw(&db, "g1").await.unwrap(),
+            "**NSFW channels:** relaxed profile, /imagine allowed"
+        );
+    }
+}
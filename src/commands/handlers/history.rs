@@ -0,0 +1,158 @@
+//! History command handler
+//!
+//! Handles: history
+//!
+//! Lists the user's recent DM sessions with their titles and dates, with a
+//! select menu whose recaps and resume button are handled by
+//! `features::dm_history::HistoryMenu`.
+//!
+//! - **Version**: 1.0.1
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.1: Slow replies are deferred privately
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::prelude::Context;
+use std::sync::Arc;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::database::DmSessionSummary;
+use crate::features::dm_history::{history_menu, session_line, DM_HISTORY_FEATURE, HISTORY_LIMIT};
+
+/// Handler for /history
+pub struct HistoryHandler;
+
+#[async_trait]
+impl SlashCommandHandler for HistoryHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["history"]
+    }
+
+    fn ephemeral(&self, _command: &ApplicationCommandInteraction) -> bool {
+        true
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        self.handle_history(&ctx, serenity_ctx, command).await
+    }
+}
+
+impl HistoryHandler {
+    /// Handle /history
+    async fn handle_history(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let user_id = command.user.id.to_string();
+        let guild_id = command.guild_id.map(|id| id.to_string());
+
+        if !ctx
+            .database
+            .is_feature_enabled(DM_HISTORY_FEATURE, None, guild_id.as_deref())
+            .await?
+        {
+            return Self::reply(
+                &responder,
+                serenity_ctx,
+                "❌ Conversation history is disabled.",
+                &[],
+            )
+            .await;
+        }
+
+        let sessions = ctx
+            .database
+            .get_dm_session_summaries(&user_id, HISTORY_LIMIT)
+            .await?;
+        info!("/history | User: {user_id} | Sessions: {}", sessions.len());
+        Self::reply(
+            &responder,
+            serenity_ctx,
+            &Self::describe(&sessions),
+            &sessions,
+        )
+        .await
+    }
+
+    /// The session list shown above the select menu
+    fn describe(sessions: &[DmSessionSummary]) -> String {
+        if sessions.is_empty() {
+            return "You don't have any recorded DM conversations yet. \
+                    Sessions are recorded while DM analytics is on in `/privacy`."
+                .to_string();
+        }
+        let mut lines = vec!["📜 **Your recent DM conversations**".to_string()];
+        lines.extend(sessions.iter().map(session_line));
+        lines.push(String::new());
+        lines.push("Pick one below for a recap.".to_string());
+        lines.join("\n")
+    }
+
+    async fn reply(
+        responder: &InteractionResponder,
+        serenity_ctx: &Context,
+        content: &str,
+        sessions: &[DmSessionSummary],
+    ) -> Result<()> {
+        responder
+            .create_interaction_response(&serenity_ctx.http, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|m| {
+                        m.content(content).ephemeral(true);
+                        if !sessions.is_empty() {
+                            m.set_components(history_menu(sessions));
+                        }
+                        m
+                    })
+            })
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_handler_commands() {
+        let handler = HistoryHandler;
+        assert_eq!(handler.command_names(), &["history"]);
+    }
+
+    #[test]
+    fn test_describe_sessions() {
+        assert!(HistoryHandler::describe(&[]).starts_with("You don't have any"));
+
+        let sessions = vec![DmSessionSummary {
+            session_id: "s1".to_string(),
+            channel_id: "dm1".to_string(),
+            title: Some("Brining a turkey".to_string()),
+            started_at: "2025-10-09 08:53:20".to_string(),
+            ended_at: None,
+            message_count: 6,
+        }];
+        assert_eq!(
+            HistoryHandler::describe(&sessions),
+            "📜 **Your recent DM conversations**\n\
+             **Brining a turkey** — <t:1760000000:f> · 6 messages\n\n\
+             Pick one below for a recap."
+        );
+    }
+}
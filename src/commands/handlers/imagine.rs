@@ -2,10 +2,14 @@
 //!
 //! Handles: imagine
 //!
-//! - **Version**: 1.0.0
+//! - **Version**: 1.4.0
 //! - **Since**: 3.38.0
 //!
 //! ## Changelog
+//! - 1.4.0: Refused in NSFW channels unless the server allows it with /guardrails nsfw
+//! - 1.3.0: The /imagine pipeline is shared as ImagineHandler::imagine for /prompts use
+//! - 1.2.0: Initial responses go through InteractionResponder (auto-defer safe)
+//! - 1.1.0: Friendly message when the DALL-E circuit breaker is open
 //! - 1.0.0: Extracted from command_handler.rs
 
 use anyhow::Result;
@@ -21,9 +25,12 @@ use std::time::Instant;
 
 use crate::commands::context::CommandContext;
 use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
 use crate::commands::slash::get_string_option;
 use crate::features::analytics::CostBucket;
+use crate::features::guardrails::imagine_allowed;
 use crate::features::image_gen::generator::{ImageSize, ImageStyle};
+use crate::features::resilience::CircuitOpenError;
 
 /// Handler for DALL-E image generation command
 pub struct ImagineHandler;
@@ -55,6 +62,35 @@ impl ImagineHandler {
         serenity_ctx: &Context,
         command: &ApplicationCommandInteraction,
     ) -> Result<()> {
+        // Get the prompt (required)
+        let prompt = get_string_option(&command.data.options, "prompt")
+            .ok_or_else(|| anyhow::anyhow!("Missing prompt parameter"))?;
+
+        // Get optional size (default: square)
+        let size = get_string_option(&command.data.options, "size")
+            .and_then(|s| ImageSize::parse(&s))
+            .unwrap_or(ImageSize::Square);
+
+        // Get optional style (default: vivid)
+        let style = get_string_option(&command.data.options, "style")
+            .and_then(|s| ImageStyle::parse(&s))
+            .unwrap_or(ImageStyle::Vivid);
+
+        Self::imagine(ctx, serenity_ctx, command, &prompt, size, style).await
+    }
+
+    /// Generate an image for `prompt` and reply to `command` with it
+    ///
+    /// The /imagine pipeline, shared with /prompts use.
+    pub(crate) async fn imagine(
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        prompt: &str,
+        size: ImageSize,
+        style: ImageStyle,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let start_time = Instant::now();
         let user_id = command.user.id.to_string();
 
@@ -70,7 +106,7 @@ impl ImagineHandler {
         };
 
         if !image_gen_enabled {
-            command
+            responder
                 .create_interaction_response(&serenity_ctx.http, |response| {
                     response
                         .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -82,21 +118,21 @@ impl ImagineHandler {
             return Ok(());
         }
 
-        debug!("Starting image generation | Command: imagine");
-
-        // Get the prompt (required)
-        let prompt = get_string_option(&command.data.options, "prompt")
-            .ok_or_else(|| anyhow::anyhow!("Missing prompt parameter"))?;
-
-        // Get optional size (default: square)
-        let size = get_string_option(&command.data.options, "size")
-            .and_then(|s| ImageSize::parse(&s))
-            .unwrap_or(ImageSize::Square);
+        if !imagine_allowed(&ctx.database, guild_id_opt, &command.channel_id.to_string()).await? {
+            responder
+                .create_interaction_response(&serenity_ctx.http, |response| {
+                    response
+                        .kind(InteractionResponseType::ChannelMessageWithSource)
+                        .interaction_response_data(|msg| {
+                            msg.content("Image generation is turned off in age-restricted channels on this server.")
+                                .ephemeral(true)
+                        })
+                })
+                .await?;
+            return Ok(());
+        }
 
-        // Get optional style (default: vivid)
-        let style = get_string_option(&command.data.options, "style")
-            .and_then(|s| ImageStyle::parse(&s))
-            .unwrap_or(ImageStyle::Vivid);
+        debug!("Starting image generation | Command: {}", command.data.name);
 
         info!(
             "Generating image | User: {} | Size: {} | Style: {} | Prompt: '{}'",
@@ -107,11 +143,13 @@ impl ImagineHandler {
         );
 
         // Log usage
-        ctx.database.log_usage(&user_id, "imagine", None).await?;
+        ctx.database
+            .log_usage(&user_id, guild_id_opt, "imagine", None)
+            .await?;
 
         // Defer the response immediately (DALL-E can take 10-30 seconds)
         info!("Deferring Discord interaction response (DALL-E generation)");
-        command
+        responder
             .create_interaction_response(&serenity_ctx.http, |response| {
                 response.kind(InteractionResponseType::DeferredChannelMessageWithSource)
             })
@@ -125,7 +163,7 @@ impl ImagineHandler {
         let channel_id_str = command.channel_id.to_string();
         match ctx
             .image_generator
-            .generate_image(&prompt, size, style)
+            .generate_image(prompt, size, style)
             .await
         {
             Ok(generated_image) => {
@@ -137,6 +175,7 @@ impl ImagineHandler {
                     size.as_str(),
                     "standard", // DALL-E 3 via this bot uses standard quality
                     1,          // One image per request
+                    Some(prompt),
                     &user_id,
                     guild_id_opt,
                     Some(&channel_id_str),
@@ -151,7 +190,7 @@ impl ImagineHandler {
                         // Build the response message
                         let mut response_text = format!("**Generated Image**\n> {prompt}");
                         if let Some(revised) = &generated_image.revised_prompt {
-                            if revised != &prompt {
+                            if revised != prompt {
                                 response_text
                                     .push_str(&format!("\n\n*DALL-E revised prompt:* _{revised}_"));
                             }
@@ -201,16 +240,18 @@ impl ImagineHandler {
                 let processing_time = start_time.elapsed();
                 error!("DALL-E error after {processing_time:?}: {e}");
 
-                let error_message = if e.to_string().contains("content_policy")
+                let error_message = if let Some(open) = e.downcast_ref::<CircuitOpenError>() {
+                    format!("**Unavailable** - {open}")
+                } else if e.to_string().contains("content_policy")
                     || e.to_string().contains("safety")
                 {
-                    "**Content Policy Violation** - Your prompt was rejected by DALL-E's safety system. Please try a different prompt."
+                    "**Content Policy Violation** - Your prompt was rejected by DALL-E's safety system. Please try a different prompt.".to_string()
                 } else if e.to_string().contains("rate") || e.to_string().contains("limit") {
-                    "**Rate Limited** - Too many image requests. Please wait a moment and try again."
+                    "**Rate Limited** - Too many image requests. Please wait a moment and try again.".to_string()
                 } else if e.to_string().contains("billing") || e.to_string().contains("quota") {
-                    "**Quota Exceeded** - The image generation quota has been reached. Please try again later."
+                    "**Quota Exceeded** - The image generation quota has been reached. Please try again later.".to_string()
                 } else {
-                    "**Error** - Failed to generate image. Please try again with a different prompt."
+                    "**Error** - Failed to generate image. Please try again with a different prompt.".to_string()
                 };
 
                 command
@@ -1,11 +1,23 @@
 //! Info/analytics command handler
 //!
-//! Handles: introspect, commits, features, toggle, sysinfo, usage, dm_stats, session_history
+//! Handles: introspect, commits, features, toggle, sysinfo, alerts, usage, dm_stats, session_history
 //!
-//! - **Version**: 1.0.0
+//! - **Version**: 1.12.0
 //! - **Since**: 3.38.0
 //!
 //! ## Changelog
+//! - 1.12.0: /usage lists embedding requests and tokens
+//! - 1.11.0: Add plugin_costs scope to /usage (AI summary spend per plugin)
+//! - 1.10.0: Personal /usage in a guild only counts that guild's usage; server scopes exclude DM usage
+//! - 1.9.0: /alerts lists metric alert rules with their current value and cooldown
+//! - 1.8.0: /sysinfo History (30d) view with a daily cost row, read from downsampled metrics tiers
+//! - 1.7.0: /sysinfo shows storage, network throughput, per-feature tasks and OpenAI in-flight requests
+//! - 1.6.0: /introspect file explains allowlisted repository source files
+//! - 1.5.0: /introspect overview adds a live architecture map (embed + Mermaid attachment)
+//! - 1.4.0: Active persona honours category defaults
+//! - 1.3.0: Paginated embed output via PaginatedEmbed
+//! - 1.2.0: Initial responses go through InteractionResponder (auto-defer safe)
+//! - 1.1.0: Add image_costs scope to /usage (DALL-E spend by channel, user, and prompt)
 //! - 1.0.0: Extracted from command_handler.rs
 
 use anyhow::Result;
@@ -14,18 +26,28 @@ use log::{debug, error, info, warn};
 use openai::chat::{ChatCompletion, ChatCompletionMessage, ChatCompletionMessageRole};
 use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
 use serenity::model::application::interaction::InteractionResponseType;
+use serenity::model::channel::AttachmentType;
 use serenity::prelude::Context;
+use std::borrow::Cow;
 use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::commands::context::CommandContext;
+use crate::commands::context::{channel_category_id, CommandContext};
 use crate::commands::handler::SlashCommandHandler;
+use crate::commands::pagination::PaginatedEmbed;
+use crate::commands::responder::InteractionResponder;
 use crate::commands::slash::{get_integer_option, get_string_option};
-use crate::features::analytics::CostBucket;
-use crate::features::introspection::get_component_snippet;
+use crate::database::ImageCostBreakdown;
+use crate::features::analytics::runtime::runtime_counters;
+use crate::features::analytics::{alert_engine, CostBucket};
+use crate::features::introspection::{
+    architecture_fields, architecture_mermaid, architecture_text, get_component_snippet,
+    SourceAllowlist, MERMAID_FILENAME,
+};
+use crate::features::{get_bot_version, get_features};
 
 /// Handler for info/analytics commands: introspect, commits, features, toggle,
-/// sysinfo, usage, dm_stats, session_history
+/// sysinfo, alerts, usage, dm_stats, session_history
 pub struct InfoHandler;
 
 #[async_trait]
@@ -37,6 +59,7 @@ impl SlashCommandHandler for InfoHandler {
             "features",
             "toggle",
             "sysinfo",
+            "alerts",
             "usage",
             "dm_stats",
             "session_history",
@@ -56,6 +79,7 @@ impl SlashCommandHandler for InfoHandler {
             "features" => self.handle_features(&ctx, serenity_ctx, command, request_id).await,
             "toggle" => self.handle_toggle(&ctx, serenity_ctx, command, request_id).await,
             "sysinfo" => self.handle_sysinfo(&ctx, serenity_ctx, command, request_id).await,
+            "alerts" => self.handle_alerts(&ctx, serenity_ctx, command, request_id).await,
             "usage" => self.handle_usage(&ctx, serenity_ctx, command, request_id).await,
             "dm_stats" => self.handle_dm_stats(serenity_ctx, command, request_id, &ctx).await,
             "session_history" => {
@@ -78,30 +102,77 @@ impl InfoHandler {
         command: &ApplicationCommandInteraction,
         request_id: Uuid,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let user_id = command.user.id.to_string();
         let channel_id = command.channel_id.to_string();
         let guild_id = command.guild_id.map(|id| id.to_string());
 
-        let component = get_string_option(&command.data.options, "component")
-            .ok_or_else(|| anyhow::anyhow!("Missing component parameter"))?;
-
-        info!("[{request_id}] Introspect requested for component: {component} by user: {user_id}");
+        let file = get_string_option(&command.data.options, "file");
+        let component = get_string_option(&command.data.options, "component");
+        let subject = file
+            .clone()
+            .or_else(|| component.clone())
+            .unwrap_or_default();
+
+        info!("[{request_id}] Introspect requested for {subject} by user: {user_id}");
+
+        // Resolve the code before deferring so path errors stay private
+        let (component_title, code_snippet, language) = match (&file, component.as_deref()) {
+            (Some(path), _) => match SourceAllowlist::from_env().read(path).await {
+                Ok(source) => {
+                    let note = if source.truncated {
+                        format!("\n\n[truncated, {} lines total]", source.total_lines)
+                    } else {
+                        String::new()
+                    };
+                    (
+                        format!("`{}`", source.path),
+                        format!("{}{note}", source.content),
+                        source.language(),
+                    )
+                }
+                Err(e) => {
+                    warn!("[{request_id}] Introspect file rejected: {e}");
+                    return responder
+                        .reply_ephemeral(serenity_ctx, &e.to_string())
+                        .await;
+                }
+            },
+            (None, Some(component)) => {
+                let (title, snippet) = get_component_snippet(component);
+                let code = if component == "overview" {
+                    // Ground the explanation in what is actually compiled in
+                    format!(
+                        "{snippet}\n\n/* Live feature registry:\n{}*/",
+                        architecture_text()
+                    )
+                } else {
+                    snippet.to_string()
+                };
+                (title.to_string(), code, "rust")
+            }
+            (None, None) => {
+                let text = "Pick a `component` or a source `file` to introspect.";
+                return responder.reply_ephemeral(serenity_ctx, text).await;
+            }
+        };
+        let show_architecture_map = file.is_none() && component.as_deref() == Some("overview");
 
-        command
+        responder
             .create_interaction_response(&serenity_ctx.http, |response| {
                 response.kind(InteractionResponseType::DeferredChannelMessageWithSource)
             })
             .await?;
 
         let persona_name = if let Some(gid) = &guild_id {
+            let category_id = channel_category_id(serenity_ctx, command.channel_id).await;
             ctx.database
-                .get_persona_with_channel(&user_id, gid, &channel_id)
+                .get_persona_with_channel(&user_id, gid, &channel_id, category_id.as_deref())
                 .await?
         } else {
             ctx.database.get_user_persona(&user_id).await?
         };
 
-        let (component_title, code_snippet) = get_component_snippet(&component);
         let persona = ctx.persona_manager.get_persona(&persona_name);
         let persona_prompt = persona.map(|p| p.system_prompt.as_str()).unwrap_or("");
 
@@ -110,7 +181,7 @@ impl InfoHandler {
             You are now being asked to explain your own implementation. \
             The user wants to understand how you work internally.\n\n\
             Here is actual code from your implementation - {component_title}:\n\n\
-            ```rust\n{code_snippet}\n```\n\n\
+            ```{language}\n{code_snippet}\n```\n\n\
             Explain this code in your characteristic style and personality. \
             Use metaphors and analogies that fit your character. \
             Make it entertaining and educational. \
@@ -118,6 +189,7 @@ impl InfoHandler {
             Aim for 2-3 paragraphs."
         );
 
+        let in_flight = runtime_counters().openai_request();
         let chat_completion = ChatCompletion::builder(
             &ctx.openai_model,
             vec![
@@ -131,9 +203,13 @@ impl InfoHandler {
                 },
                 ChatCompletionMessage {
                     role: ChatCompletionMessageRole::User,
-                    content: Some(format!(
-                        "Explain how your {component_title} system works, in your own words."
-                    )),
+                    content: Some(if file.is_some() {
+                        format!("Explain what {component_title} does, in your own words.")
+                    } else {
+                        format!(
+                            "Explain how your {component_title} system works, in your own words."
+                        )
+                    }),
                     name: None,
                     function_call: None,
                     tool_call_id: None,
@@ -143,6 +219,7 @@ impl InfoHandler {
         )
         .create()
         .await;
+        drop(in_flight);
 
         let channel_id_str = command.channel_id.to_string();
 
@@ -171,9 +248,7 @@ impl InfoHandler {
             }
             Err(e) => {
                 warn!("[{request_id}] OpenAI error during introspection: {e}");
-                format!(
-                    "I encountered an error while attempting to explain my {component} system: {e}"
-                )
+                format!("I encountered an error while attempting to explain {subject}: {e}")
             }
         };
 
@@ -185,10 +260,41 @@ impl InfoHandler {
             })
             .await?;
 
+        if show_architecture_map {
+            let fields = architecture_fields();
+            let feature_count = get_features().len();
+            command
+                .create_followup_message(&serenity_ctx.http, |message| {
+                    message
+                        .embed(|e| {
+                            e.title(format!("🗺️ Architecture Map (v{})", get_bot_version()))
+                                .description(format!(
+                                    "{feature_count} features from the live registry. \
+                                    🔀 = toggleable, ← = depends on."
+                                ))
+                                .color(0x5865F2);
+                            for (name, value) in &fields {
+                                e.field(name, value, false);
+                            }
+                            e.footer(|f| f.text(format!("Mermaid diagram: {MERMAID_FILENAME}")))
+                        })
+                        .add_file(AttachmentType::Bytes {
+                            data: Cow::Owned(architecture_mermaid().into_bytes()),
+                            filename: MERMAID_FILENAME.to_string(),
+                        })
+                })
+                .await?;
+        }
+
         ctx.database
-            .log_usage(&user_id, "introspect", Some(&persona_name))
+            .log_usage(
+                &user_id,
+                guild_id.as_deref(),
+                "introspect",
+                Some(&persona_name),
+            )
             .await?;
-        info!("[{request_id}] Introspection complete for component: {component}");
+        info!("[{request_id}] Introspection complete for {subject}");
         Ok(())
     }
 
@@ -202,6 +308,7 @@ impl InfoHandler {
         command: &ApplicationCommandInteraction,
         request_id: Uuid,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         use crate::features::startup::notification::{
             format_commit_for_thread, get_detailed_commits, get_github_repo_url,
         };
@@ -217,7 +324,7 @@ impl InfoHandler {
         let repo_url = get_github_repo_url().await;
 
         if commits.is_empty() {
-            command
+            responder
                 .create_interaction_response(&serenity_ctx.http, |response| {
                     response
                         .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -240,7 +347,7 @@ impl InfoHandler {
             ));
         }
 
-        command
+        responder
             .create_interaction_response(&serenity_ctx.http, |response| {
                 response
                     .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -290,7 +397,10 @@ impl InfoHandler {
             }
         }
 
-        ctx.database.log_usage(&user_id, "commits", None).await?;
+        let guild_id = command.guild_id.map(|id| id.to_string());
+        ctx.database
+            .log_usage(&user_id, guild_id.as_deref(), "commits", None)
+            .await?;
         info!("[{request_id}] Commits command completed");
         Ok(())
     }
@@ -305,6 +415,7 @@ impl InfoHandler {
         command: &ApplicationCommandInteraction,
         request_id: Uuid,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let user_id = command.user.id.to_string();
         let guild_id = command.guild_id.map(|id| id.to_string());
 
@@ -338,14 +449,16 @@ impl InfoHandler {
         output.push_str("```\n");
         output.push_str("Use `/toggle <feature>` to enable/disable toggleable features.");
 
-        command
+        responder
             .create_interaction_response(&serenity_ctx.http, |r| {
                 r.kind(InteractionResponseType::ChannelMessageWithSource)
                     .interaction_response_data(|m| m.content(output))
             })
             .await?;
 
-        ctx.database.log_usage(&user_id, "features", None).await?;
+        ctx.database
+            .log_usage(&user_id, guild_id.as_deref(), "features", None)
+            .await?;
         info!("[{request_id}] Features command completed");
         Ok(())
     }
@@ -360,6 +473,7 @@ impl InfoHandler {
         command: &ApplicationCommandInteraction,
         request_id: Uuid,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let user_id = command.user.id.to_string();
         let guild_id = command.guild_id.map(|id| id.to_string());
 
@@ -370,7 +484,7 @@ impl InfoHandler {
             .ok_or_else(|| anyhow::anyhow!("Unknown feature: {}", feature_id))?;
 
         if !feature.toggleable {
-            command
+            responder
                 .create_interaction_response(&serenity_ctx.http, |r| {
                     r.kind(InteractionResponseType::ChannelMessageWithSource)
                         .interaction_response_data(|m| {
@@ -410,14 +524,16 @@ impl InfoHandler {
             feature.name, status, feature.id, feature.version
         );
 
-        command
+        responder
             .create_interaction_response(&serenity_ctx.http, |r| {
                 r.kind(InteractionResponseType::ChannelMessageWithSource)
                     .interaction_response_data(|m| m.content(response))
             })
             .await?;
 
-        ctx.database.log_usage(&user_id, "toggle", None).await?;
+        ctx.database
+            .log_usage(&user_id, guild_id.as_deref(), "toggle", None)
+            .await?;
         info!("[{request_id}] Toggle command completed: {feature_id} -> {new_enabled}");
         Ok(())
     }
@@ -432,6 +548,7 @@ impl InfoHandler {
         command: &ApplicationCommandInteraction,
         request_id: Uuid,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         use crate::features::analytics::system_info::{
             format_history, CurrentMetrics, HistoricalSummary,
         };
@@ -442,16 +559,19 @@ impl InfoHandler {
 
         info!("[{request_id}] Sysinfo requested: view={view}");
 
-        command
+        responder
             .create_interaction_response(&serenity_ctx.http, |response| {
                 response.kind(InteractionResponseType::DeferredChannelMessageWithSource)
             })
             .await?;
 
         let response = match view.as_str() {
-            "history_24h" | "history_7d" => {
-                let hours = if view == "history_24h" { 24 } else { 168 };
-                let period_label = if view == "history_24h" { "24h" } else { "7d" };
+            "history_24h" | "history_7d" | "history_30d" => {
+                let (hours, period_label) = match view.as_str() {
+                    "history_24h" => (24, "24h"),
+                    "history_7d" => (168, "7d"),
+                    _ => (720, "30d"),
+                };
 
                 let db_size_data = ctx
                     .database
@@ -474,27 +594,29 @@ impl InfoHandler {
                 let bot_memory = HistoricalSummary::from_data(&bot_memory_data);
                 let system_memory = HistoricalSummary::from_data(&system_memory_data);
                 let system_cpu = HistoricalSummary::from_data(&system_cpu_data);
-
-                format_history(db_size, bot_memory, system_memory, system_cpu, period_label)
+                let cost_data: Vec<(i64, f64)> = ctx
+                    .database
+                    .get_daily_cost_trend((hours / 24) as u32)
+                    .await?
+                    .into_iter()
+                    .enumerate()
+                    .map(|(day, (_, cost))| (day as i64, cost))
+                    .collect();
+                let daily_cost = HistoricalSummary::from_data(&cost_data);
+
+                format_history(
+                    db_size,
+                    bot_memory,
+                    system_memory,
+                    system_cpu,
+                    daily_cost,
+                    period_label,
+                )
             }
             _ => {
-                let mut sys = sysinfo::System::new();
-                sys.refresh_cpu_usage();
-                tokio::time::sleep(std::time::Duration::from_millis(200)).await;
-                sys.refresh_cpu_usage();
-                sys.refresh_memory();
-
-                if let Ok(pid) = sysinfo::get_current_pid() {
-                    sys.refresh_processes_specifics(
-                        sysinfo::ProcessesToUpdate::Some(&[pid]),
-                        true,
-                        sysinfo::ProcessRefreshKind::new().with_memory(),
-                    );
-                }
-
                 let db_path = std::env::var("DATABASE_PATH")
                     .unwrap_or_else(|_| "persona.db".to_string());
-                let metrics = CurrentMetrics::gather(&sys, &db_path);
+                let metrics = CurrentMetrics::sample(&db_path).await;
                 let bot_uptime_secs = ctx.start_time.elapsed().as_secs();
                 metrics.format(bot_uptime_secs)
             }
@@ -504,11 +626,43 @@ impl InfoHandler {
             .edit_original_interaction_response(&serenity_ctx.http, |msg| msg.content(response))
             .await?;
 
-        ctx.database.log_usage(&user_id, "sysinfo", None).await?;
+        let guild_id = command.guild_id.map(|id| id.to_string());
+        ctx.database
+            .log_usage(&user_id, guild_id.as_deref(), "sysinfo", None)
+            .await?;
         info!("[{request_id}] Sysinfo command completed");
         Ok(())
     }
 
+    // ── alerts ──────────────────────────────────────────────────────────
+
+    /// Handle /alerts command - list alert rules and their current state
+    async fn handle_alerts(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        request_id: Uuid,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let user_id = command.user.id.to_string();
+        let output = alert_engine().format_statuses(chrono::Utc::now());
+
+        responder
+            .create_interaction_response(&serenity_ctx.http, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|m| m.content(output))
+            })
+            .await?;
+
+        let guild_id = command.guild_id.map(|id| id.to_string());
+        ctx.database
+            .log_usage(&user_id, guild_id.as_deref(), "alerts", None)
+            .await?;
+        info!("[{request_id}] Alerts command completed");
+        Ok(())
+    }
+
     // ── usage ───────────────────────────────────────────────────────────
 
     /// Handle /usage command - display usage statistics
@@ -519,6 +673,7 @@ impl InfoHandler {
         command: &ApplicationCommandInteraction,
         request_id: Uuid,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let user_id = command.user.id.to_string();
         let guild_id = command.guild_id.map(|id| id.to_string());
 
@@ -527,7 +682,7 @@ impl InfoHandler {
 
         info!("[{request_id}] Usage requested: scope={scope}");
 
-        command
+        responder
             .create_interaction_response(&serenity_ctx.http, |response| {
                 response.kind(InteractionResponseType::DeferredChannelMessageWithSource)
             })
@@ -535,11 +690,11 @@ impl InfoHandler {
 
         let response = match scope.as_str() {
             "personal_today" => {
-                let stats = ctx.database.get_user_usage_stats(&user_id, 1).await?;
+                let stats = Self::personal_usage(ctx, &user_id, guild_id.as_deref(), 1).await?;
                 Self::format_usage_stats("Your Usage Today", &stats, None)
             }
             "personal_7d" => {
-                let stats = ctx.database.get_user_usage_stats(&user_id, 7).await?;
+                let stats = Self::personal_usage(ctx, &user_id, guild_id.as_deref(), 7).await?;
                 Self::format_usage_stats("Your Usage (7 days)", &stats, None)
             }
             "server_today" => {
@@ -569,18 +724,58 @@ impl InfoHandler {
                     "Top users is only available in guild channels.".to_string()
                 }
             }
+            "image_costs" => {
+                if let Some(gid) = &guild_id {
+                    let breakdown = ctx
+                        .database
+                        .get_guild_image_cost_breakdown(gid, 7, 5)
+                        .await?;
+                    Self::format_image_costs("Image Generation Costs (7 days)", &breakdown)
+                } else {
+                    "Image costs are only available in guild channels.".to_string()
+                }
+            }
+            "plugin_costs" => {
+                if let Some(gid) = &guild_id {
+                    let costs = ctx.database.get_guild_plugin_summary_costs(gid, 7).await?;
+                    Self::format_plugin_costs("Plugin AI Summary Costs (7 days)", &costs)
+                } else {
+                    "Plugin costs are only available in guild channels.".to_string()
+                }
+            }
             _ => "Invalid scope. Please select a valid option.".to_string(),
         };
 
-        command
-            .edit_original_interaction_response(&serenity_ctx.http, |msg| msg.content(response))
+        PaginatedEmbed::from_text("📊 Usage", &response)
+            .with_owner(command.user.id.0)
+            .respond(&ctx.database, &serenity_ctx.http, &responder, false)
             .await?;
 
-        ctx.database.log_usage(&user_id, "usage", None).await?;
+        ctx.database
+            .log_usage(&user_id, guild_id.as_deref(), "usage", None)
+            .await?;
         info!("[{request_id}] Usage command completed");
         Ok(())
     }
 
+    /// A user's own usage, limited to the current guild so other servers
+    /// (and their DMs) stay private; falls back to all usage in DMs
+    async fn personal_usage(
+        ctx: &CommandContext,
+        user_id: &str,
+        guild_id: Option<&str>,
+        days: i64,
+    ) -> Result<Vec<(String, i64, i64, f64, i64, f64)>> {
+        match guild_id {
+            Some(gid) => {
+                ctx.database
+                    .get_user_guild_usage_stats(user_id, gid, days)
+                    .await
+            }
+            None => ctx.database.get_user_usage_stats(user_id, days).await,
+        }
+    }
+
     /// Format usage statistics into a Discord-friendly string
     fn format_usage_stats(
         title: &str,
@@ -623,6 +818,10 @@ impl InfoHandler {
                         "**Images (DALL-E)**: {requests} requests, {images} images, ${cost:.4}"
                     )
                 }
+                "embedding" => {
+                    total_tokens += tokens;
+                    format!("**Embeddings**: {requests} requests, {tokens} tokens, ${cost:.4}")
+                }
                 _ => format!("**{service_type}**: {requests} requests, ${cost:.4}"),
             };
             lines.push(details);
@@ -669,6 +868,70 @@ impl InfoHandler {
         lines.join("\n")
     }
 
+    /// Format per-plugin summary spend into a Discord-friendly string
+    fn format_plugin_costs(title: &str, costs: &[(String, i64, f64)]) -> String {
+        if costs.is_empty() {
+            return format!("**{title}**\n\nNo plugin summaries generated for this period.");
+        }
+
+        let mut lines = vec![format!("**{title}**\n")];
+        for (plugin, requests, cost) in costs {
+            lines.push(format!("`{plugin}`: {requests} summaries, ${cost:.4}"));
+        }
+        lines.join("\n")
+    }
+
+    /// Format a DALL-E cost breakdown into a Discord-friendly string
+    fn format_image_costs(title: &str, breakdown: &ImageCostBreakdown) -> String {
+        if breakdown.total_images == 0 {
+            return format!("**{title}**\n\nNo images generated for this period.");
+        }
+
+        let mut lines = vec![format!("**{title}**\n")];
+        lines.push(format!(
+            "**Total**: {} images, ${:.4} ({:.1} images/day)",
+            breakdown.total_images,
+            breakdown.total_cost,
+            breakdown.avg_images_per_day()
+        ));
+
+        lines.push(String::new());
+        lines.push("**By Channel**".to_string());
+        for entry in &breakdown.by_channel {
+            let share = if breakdown.total_cost > 0.0 {
+                entry.cost / breakdown.total_cost * 100.0
+            } else {
+                0.0
+            };
+            lines.push(format!(
+                "<#{}>: {} images, ${:.4} ({share:.0}%)",
+                entry.key, entry.images, entry.cost
+            ));
+        }
+
+        lines.push(String::new());
+        lines.push("**By User**".to_string());
+        for entry in &breakdown.by_user {
+            lines.push(format!(
+                "<@{}>: {} images, ${:.4}",
+                entry.key, entry.images, entry.cost
+            ));
+        }
+
+        if !breakdown.top_prompts.is_empty() {
+            lines.push(String::new());
+            lines.push("**Most Expensive Prompts**".to_string());
+            for entry in &breakdown.top_prompts {
+                lines.push(format!(
+                    "`{}`: {} requests, ${:.4}",
+                    entry.key, entry.requests, entry.cost
+                ));
+            }
+        }
+
+        lines.join("\n")
+    }
+
     // ── dm_stats ────────────────────────────────────────────────────────
 
     /// Handle /dm_stats command - show DM session statistics
@@ -679,6 +942,7 @@ impl InfoHandler {
         request_id: Uuid,
         ctx: &CommandContext,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let user_id = command.user.id.to_string();
 
         let period = get_string_option(&command.data.options, "period")
@@ -750,7 +1014,7 @@ impl InfoHandler {
                     )
                 };
 
-                command
+                responder
                     .create_interaction_response(&serenity_ctx.http, |r| {
                         r.kind(InteractionResponseType::ChannelMessageWithSource)
                             .interaction_response_data(|message| {
@@ -761,7 +1025,7 @@ impl InfoHandler {
             }
             Err(e) => {
                 error!("[{request_id}] Error fetching DM stats: {e}");
-                command
+                responder
                     .create_interaction_response(&serenity_ctx.http, |r| {
                         r.kind(InteractionResponseType::ChannelMessageWithSource)
                             .interaction_response_data(|message| {
@@ -789,6 +1053,7 @@ impl InfoHandler {
         request_id: Uuid,
         ctx: &CommandContext,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let user_id = command.user.id.to_string();
         let limit = get_integer_option(&command.data.options, "limit").unwrap_or(5);
 
@@ -839,7 +1104,7 @@ impl InfoHandler {
                     output
                 };
 
-                command
+                responder
                     .create_interaction_response(&serenity_ctx.http, |r| {
                         r.kind(InteractionResponseType::ChannelMessageWithSource)
                             .interaction_response_data(|message| {
@@ -850,7 +1115,7 @@ impl InfoHandler {
             }
             Err(e) => {
                 error!("[{request_id}] Error fetching session history: {e}");
-                command
+                responder
                     .create_interaction_response(&serenity_ctx.http, |r| {
                         r.kind(InteractionResponseType::ChannelMessageWithSource)
                             .interaction_response_data(|message| {
@@ -883,9 +1148,55 @@ mod tests {
         assert!(names.contains(&"features"));
         assert!(names.contains(&"toggle"));
         assert!(names.contains(&"sysinfo"));
+        assert!(names.contains(&"alerts"));
         assert!(names.contains(&"usage"));
         assert!(names.contains(&"dm_stats"));
         assert!(names.contains(&"session_history"));
-        assert_eq!(names.len(), 8);
+        assert_eq!(names.len(), 9);
+    }
+
+    #[test]
+    fn test_format_image_costs_empty() {
+        let breakdown = ImageCostBreakdown {
+            days: 7,
+            ..Default::default()
+        };
+        let output = InfoHandler::format_image_costs("Image Costs", &breakdown);
+        assert!(output.contains("No images generated"));
+    }
+
+    #[test]
+    fn test_format_image_costs_breakdown() {
+        use crate::database::ImageCostEntry;
+
+        let breakdown = ImageCostBreakdown {
+            days: 7,
+            total_images: 14,
+            total_cost: 0.56,
+            by_channel: vec![ImageCostEntry {
+                key: "123".to_string(),
+                requests: 14,
+                images: 14,
+                cost: 0.56,
+            }],
+            by_user: vec![ImageCostEntry {
+                key: "456".to_string(),
+                requests: 14,
+                images: 14,
+                cost: 0.56,
+            }],
+            top_prompts: vec![ImageCostEntry {
+                key: "deadbeefdeadbeef".to_string(),
+                requests: 3,
+                images: 3,
+                cost: 0.12,
+            }],
+        };
+        let output = InfoHandler::format_image_costs("Image Costs", &breakdown);
+        assert!(output.contains("2.0 images/day"));
+        assert!(output.contains("<#123>"));
+        assert!(output.contains("(100%)"));
+        assert!(output.contains("<@456>"));
+        assert!(output.contains("`deadbeefdeadbeef`"));
     }
 }
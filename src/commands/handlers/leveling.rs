@@ -0,0 +1,346 @@
+//! Leveling command handler
+//!
+//! Handles: rank, leaderboard, leveling
+//!
+//! `/rank` and `/leaderboard` show members' levels and XP (see
+//! `features::leveling`); `/leveling` lets admins turn XP on or off, pick the
+//! level-up channel and set role rewards.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use serenity::builder::CreateEmbed;
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+use std::sync::Arc;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::{
+    get_channel_option, get_integer_option, get_role_option, get_user_option,
+};
+use crate::database::MemberXp;
+use crate::features::leveling::{
+    is_enabled, level_progress, progress_bar, CHANNEL_SETTING, ENABLED_SETTING, LEVELING_FEATURE,
+};
+
+/// Members listed by /leaderboard
+const LEADERBOARD_SIZE: usize = 10;
+
+/// Blocks in the /rank progress bar
+const BAR_WIDTH: usize = 12;
+
+/// Handler for /rank, /leaderboard and /leveling
+pub struct LevelingHandler;
+
+#[async_trait]
+impl SlashCommandHandler for LevelingHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["rank", "leaderboard", "leveling"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let Some(guild_id) = command.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        if command.data.name == "leveling" {
+            return self
+                .handle_leveling(&ctx, serenity_ctx, command, &responder, &guild_id)
+                .await;
+        }
+
+        if !is_enabled(&ctx.database, &guild_id).await? {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "XP isn't on in this server. An admin can start it with `/leveling on`.",
+                )
+                .await;
+        }
+        let embed = if command.data.name == "rank" {
+            self.rank_embed(&ctx, serenity_ctx, command, &guild_id)
+                .await?
+        } else {
+            let members = ctx
+                .database
+                .get_xp_leaderboard(&guild_id, LEADERBOARD_SIZE)
+                .await?;
+            Self::leaderboard_embed(&members)
+        };
+        responder
+            .create_interaction_response(&serenity_ctx.http, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|m| m.add_embed(embed))
+            })
+            .await?;
+        Ok(())
+    }
+}
+
+impl LevelingHandler {
+    /// Build the /rank [user] card
+    async fn rank_embed(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        guild_id: &str,
+    ) -> Result<CreateEmbed> {
+        let target_id = get_user_option(&command.data.options, "user")
+            .map(UserId)
+            .unwrap_or(command.user.id);
+        let user = if target_id == command.user.id {
+            command.user.clone()
+        } else {
+            match command.data.resolved.users.get(&target_id) {
+                Some(user) => user.clone(),
+                None => target_id.to_user(&serenity_ctx.http).await?,
+            }
+        };
+        let user_id = target_id.to_string();
+        info!(
+            "/rank | User: {} | Guild: {guild_id} | Target: {user_id}",
+            command.user.id
+        );
+
+        let rank = ctx.database.get_member_rank(guild_id, &user_id).await?;
+        let rewards = ctx.database.get_level_rewards(guild_id).await?;
+        let mut embed = CreateEmbed::default();
+        embed
+            .author(|a| a.name(user.tag()).icon_url(user.face()))
+            .title("📈 Rank")
+            .color(0x5865F2);
+        let Some((member, position)) = rank else {
+            embed.description("No XP yet. Chat or use the bot to start earning.");
+            return Ok(embed);
+        };
+
+        let progress = level_progress(member.xp);
+        embed
+            .field("Level", progress.level.to_string(), true)
+            .field("Rank", format!("#{position}"), true)
+            .field("Total XP", member.xp.to_string(), true)
+            .field(
+                format!("Progress to level {}", progress.level + 1),
+                format!(
+                    "`{}` {}/{} XP",
+                    progress_bar(progress.current, progress.needed, BAR_WIDTH),
+                    progress.current,
+                    progress.needed
+                ),
+                false,
+            );
+        if let Some((level, role_id)) = rewards.iter().find(|(level, _)| *level > progress.level) {
+            embed.field(
+                "Next reward",
+                format!("<@&{role_id}> at level {level}"),
+                false,
+            );
+        }
+        Ok(embed)
+    }
+
+    fn leaderboard_embed(members: &[MemberXp]) -> CreateEmbed {
+        let mut embed = CreateEmbed::default();
+        embed.title("🏆 XP Leaderboard").color(0xF1C40F);
+        if members.is_empty() {
+            embed.description("Nobody has earned XP here yet.");
+        } else {
+            embed.description(Self::format_leaderboard(members));
+        }
+        embed
+    }
+
+    fn format_leaderboard(members: &[MemberXp]) -> String {
+        members
+            .iter()
+            .enumerate()
+            .map(|(i, member)| {
+                format!(
+                    "{}. <@{}>: level **{}** · {} XP",
+                    i + 1,
+                    member.user_id,
+                    level_progress(member.xp).level,
+                    member.xp
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Handle /leveling on, off, reward, unreward and status
+    async fn handle_leveling(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        responder: &InteractionResponder,
+        guild_id: &str,
+    ) -> Result<()> {
+        let user_id = command.user.id.to_string();
+        let can_manage = command
+            .member
+            .as_ref()
+            .and_then(|m| m.permissions)
+            .is_some_and(|p| p.manage_guild());
+        if !can_manage {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "❌ You need the Manage Server permission to configure leveling.",
+                )
+                .await;
+        }
+        if !ctx
+            .database
+            .is_feature_enabled(LEVELING_FEATURE, None, Some(guild_id))
+            .await?
+        {
+            return responder
+                .reply_ephemeral(serenity_ctx, "❌ Leveling is disabled on this server.")
+                .await;
+        }
+
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+        let options = &subcommand.options;
+        let db = &ctx.database;
+        let content = match subcommand.name.as_str() {
+            "on" => {
+                db.set_guild_setting(guild_id, ENABLED_SETTING, "enabled")
+                    .await?;
+                let channel = get_channel_option(options, "channel").map(|id| id.to_string());
+                match &channel {
+                    Some(channel_id) => {
+                        db.set_guild_setting(guild_id, CHANNEL_SETTING, channel_id)
+                            .await?
+                    }
+                    None => db.delete_guild_setting(guild_id, CHANNEL_SETTING).await?,
+                }
+                info!("/leveling on | User: {user_id} | Guild: {guild_id} | Channel: {channel:?}");
+                match channel {
+                    Some(channel_id) => {
+                        format!(
+                            "✅ Members now earn XP. Level-ups are announced in <#{channel_id}>."
+                        )
+                    }
+                    None => "✅ Members now earn XP. Level-ups are announced where they happen."
+                        .to_string(),
+                }
+            }
+            "off" => {
+                db.delete_guild_setting(guild_id, ENABLED_SETTING).await?;
+                info!("/leveling off | User: {user_id} | Guild: {guild_id}");
+                "XP is off. Earned XP and rewards are kept for if you turn it back on.".to_string()
+            }
+            "reward" => {
+                let level = get_integer_option(options, "level")
+                    .ok_or_else(|| anyhow::anyhow!("Missing level"))?;
+                let role_id = get_role_option(options, "role")
+                    .ok_or_else(|| anyhow::anyhow!("Missing role"))?
+                    .to_string();
+                db.set_level_reward(guild_id, level, &role_id).await?;
+                info!(
+                    "/leveling reward | User: {user_id} | Guild: {guild_id} | {level} -> {role_id}"
+                );
+                format!(
+                    "🎁 Members reaching level {level} get <@&{role_id}>. \
+                     My role must be above it to hand it out."
+                )
+            }
+            "unreward" => {
+                let level = get_integer_option(options, "level")
+                    .ok_or_else(|| anyhow::anyhow!("Missing level"))?;
+                if db.remove_level_reward(guild_id, level).await? {
+                    format!("🗑️ Removed the level {level} reward.")
+                } else {
+                    format!("There's no reward for level {level}.")
+                }
+            }
+            _ => {
+                let enabled = is_enabled(db, guild_id).await?;
+                let channel = db.get_guild_setting(guild_id, CHANNEL_SETTING).await?;
+                let rewards = db.get_level_rewards(guild_id).await?;
+                Self::format_status(enabled, channel.as_deref(), &rewards)
+            }
+        };
+        responder.reply_ephemeral(serenity_ctx, &content).await
+    }
+
+    fn format_status(enabled: bool, channel: Option<&str>, rewards: &[(i64, String)]) -> String {
+        let mut status = if enabled {
+            match channel {
+                Some(channel_id) => format!("XP is **on**, level-ups go to <#{channel_id}>."),
+                None => "XP is **on**, level-ups are announced where they happen.".to_string(),
+            }
+        } else {
+            "XP is **off**.".to_string()
+        };
+        if rewards.is_empty() {
+            status.push_str("\nNo role rewards yet. Add one with `/leveling reward`.");
+        } else {
+            status.push_str("\n**Role rewards**");
+            for (level, role_id) in rewards {
+                status.push_str(&format!("\n- Level {level}: <@&{role_id}>"));
+            }
+        }
+        status
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leveling_handler_commands() {
+        let handler = LevelingHandler;
+        assert_eq!(
+            handler.command_names(),
+            &["rank", "leaderboard", "leveling"]
+        );
+    }
+
+    #[test]
+    fn test_format_leaderboard_and_status() {
+        let members = vec![
+            MemberXp {
+                user_id: "1".to_string(),
+                xp: 300,
+                grants: 12,
+            },
+            MemberXp {
+                user_id: "2".to_string(),
+                xp: 40,
+                grants: 2,
+            },
+        ];
+        assert_eq!(
+            LevelingHandler::format_leaderboard(&members),
+            "1. <@1>: level **2** · 300 XP\n2. <@2>: level **0** · 40 XP"
+        );
+
+        let status = LevelingHandler::format_status(true, Some("5"), &[(5, "9".to_string())]);
+        assert!(status.starts_with("XP is **on**, level-ups go to <#5>."));
+        assert!(status.ends_with("- Level 5: <@&9>"));
+        assert!(LevelingHandler::format_status(false, None, &[]).contains("No role rewards"));
+    }
+}
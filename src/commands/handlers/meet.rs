@@ -0,0 +1,175 @@
+//! Meet command handler
+//!
+//! Handles: meet
+//!
+//! `/meet propose` saves the poll and posts it with one button per slot.
+//! Toggling availability and picking the winning slot are handled by
+//! `MeetingButtons`.
+//!
+//! - **Version**: 1.1.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.1.0: Accept IANA zone names and default to the organizer's /time zone
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use log::info;
+use serenity::model::application::interaction::application_command::{
+    ApplicationCommandInteraction, CommandDataOption,
+};
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::prelude::Context;
+use std::sync::Arc;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::{get_bool_option, get_integer_option, get_string_option};
+use crate::core::Zone;
+use crate::database::MeetingPoll;
+use crate::features::meetings::{
+    parse_slots, poll_buttons, poll_embed, DEFAULT_DURATION_MINUTES, DEFAULT_REMIND_MINUTES,
+    MEETINGS_FEATURE,
+};
+use crate::features::timezones::member_zone;
+
+/// Handler for /meet command
+pub struct MeetHandler;
+
+#[async_trait]
+impl SlashCommandHandler for MeetHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["meet"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        self.handle_propose(&ctx, serenity_ctx, command).await
+    }
+}
+
+impl MeetHandler {
+    /// Handle /meet propose
+    async fn handle_propose(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let Some(guild_id) = command.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+
+        let user_id = command.user.id.to_string();
+        let error = if !ctx
+            .database
+            .is_feature_enabled(MEETINGS_FEATURE, None, Some(&guild_id))
+            .await?
+        {
+            "❌ Meeting polls are disabled on this server.".to_string()
+        } else {
+            match Self::parse_poll(
+                &guild_id,
+                &command.channel_id.to_string(),
+                &user_id,
+                &subcommand.options,
+                member_zone(&ctx.database, &user_id).await?,
+                Utc::now().timestamp(),
+            ) {
+                Ok(mut poll) => {
+                    poll.id = ctx.database.create_meeting_poll(&poll).await?;
+                    info!(
+                        "/meet propose | User: {} | Guild: {guild_id} | Poll {} | {} slots",
+                        poll.organizer_id,
+                        poll.id,
+                        poll.slots.len()
+                    );
+                    let embed = poll_embed(&poll, &vec![Vec::new(); poll.slots.len()]);
+                    let components = poll_buttons(poll.id, poll.slots.len(), false);
+                    responder
+                        .create_interaction_response(&serenity_ctx.http, |r| {
+                            r.kind(InteractionResponseType::ChannelMessageWithSource)
+                                .interaction_response_data(|m| {
+                                    m.set_embed(embed).set_components(components)
+                                })
+                        })
+                        .await?;
+                    return Ok(());
+                }
+                Err(message) => format!("❌ {message}"),
+            }
+        };
+
+        responder
+            .create_interaction_response(&serenity_ctx.http, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|m| m.content(error).ephemeral(true))
+            })
+            .await?;
+        Ok(())
+    }
+
+    fn parse_poll(
+        guild_id: &str,
+        channel_id: &str,
+        organizer_id: &str,
+        options: &[CommandDataOption],
+        organizer_zone: Option<Zone>,
+        now: i64,
+    ) -> Result<MeetingPoll, String> {
+        let title = get_string_option(options, "title")
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .ok_or("Give the meeting a title")?;
+        let slots = get_string_option(options, "slots").unwrap_or_default();
+        let timezone = get_string_option(options, "timezone")
+            .as_deref()
+            .map(Zone::parse)
+            .transpose()?
+            .or(organizer_zone)
+            .unwrap_or(Zone::Fixed(0));
+        let slots = parse_slots(&slots, &timezone, now)?;
+
+        Ok(MeetingPoll {
+            id: 0,
+            guild_id: guild_id.to_string(),
+            channel_id: channel_id.to_string(),
+            organizer_id: organizer_id.to_string(),
+            title,
+            slots,
+            timezone,
+            duration_minutes: get_integer_option(options, "duration")
+                .map(|d| d.clamp(5, 720) as u32)
+                .unwrap_or(DEFAULT_DURATION_MINUTES),
+            create_event: get_bool_option(options, "event").unwrap_or(false),
+            remind_minutes: get_integer_option(options, "reminder")
+                .map(|m| m.clamp(0, 1440) as u32)
+                .unwrap_or(DEFAULT_REMIND_MINUTES),
+            chosen_slot: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meet_handler_commands() {
+        let handler = MeetHandler;
+        assert_eq!(handler.command_names(), &["meet"]);
+    }
+}
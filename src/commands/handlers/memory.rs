@@ -0,0 +1,240 @@
+//! Memory command handler
+//!
+//! Handles: memory, Remember this
+//!
+//! The Remember this context menu pins a message as a memory for the member
+//! in that channel; /memory lists and forgets them. Generation paths add the
+//! memories to the system prompt (see `features::memories`).
+//!
+//! - **Version**: 1.0.1
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.1: Slow replies are deferred privately
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::prelude::Context;
+use std::sync::Arc;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::{get_bool_option, get_integer_option};
+use crate::database::PinnedMemory;
+use crate::features::memories::{
+    author_label, memory_text, BOT_AUTHOR, MAX_MEMORIES_PER_CHANNEL, MEMORIES_FEATURE,
+    REMEMBER_COMMAND,
+};
+
+/// Characters of each memory shown by /memory list
+const LIST_PREVIEW_CHARS: usize = 120;
+
+/// Handler for /memory and the Remember this context menu
+pub struct MemoryHandler;
+
+#[async_trait]
+impl SlashCommandHandler for MemoryHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["memory", REMEMBER_COMMAND]
+    }
+
+    fn ephemeral(&self, _command: &ApplicationCommandInteraction) -> bool {
+        true
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let guild_id = command.guild_id.map(|id| id.to_string());
+        if !ctx
+            .database
+            .is_feature_enabled(MEMORIES_FEATURE, None, guild_id.as_deref())
+            .await?
+        {
+            return responder
+                .reply_ephemeral(serenity_ctx, "❌ Pinned memories are disabled here.")
+                .await;
+        }
+
+        let content = if command.data.name == REMEMBER_COMMAND {
+            self.handle_remember(&ctx, serenity_ctx, command).await?
+        } else {
+            self.handle_memory(&ctx, command).await?
+        };
+        responder.reply_ephemeral(serenity_ctx, &content).await
+    }
+}
+
+impl MemoryHandler {
+    /// Handle the Remember this context menu
+    async fn handle_remember(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<String> {
+        let Some(target) = command.data.resolved.messages.values().next() else {
+            return Ok("❌ I couldn't read that message.".to_string());
+        };
+        let Some(text) = memory_text(
+            &target.content,
+            target.embeds.first().and_then(|e| e.description.as_deref()),
+        ) else {
+            return Ok("❌ That message has no text to remember.".to_string());
+        };
+
+        let user_id = command.user.id.to_string();
+        let channel_id = command.channel_id.to_string();
+        let pinned = ctx
+            .database
+            .get_pinned_memories(&user_id, Some(&channel_id))
+            .await?;
+        if pinned.len() >= MAX_MEMORIES_PER_CHANNEL {
+            return Ok(format!(
+                "❌ You've pinned {MAX_MEMORIES_PER_CHANNEL} memories in this channel. \
+                 Forget one with `/memory forget` first."
+            ));
+        }
+
+        let author = if target.author.id == serenity_ctx.cache.current_user_id() {
+            BOT_AUTHOR.to_string()
+        } else {
+            target.author.name.clone()
+        };
+        let added = ctx
+            .database
+            .add_pinned_memory(
+                &user_id,
+                &channel_id,
+                &target.id.to_string(),
+                &text,
+                &author,
+            )
+            .await?;
+        info!(
+            "📌 Remember this | User: {user_id} | Channel: {channel_id} | Message: {} | Added: {added}",
+            target.id
+        );
+        Ok(if added {
+            "📌 Got it. I'll keep that in mind whenever we talk here. See `/memory list`."
+                .to_string()
+        } else {
+            "📌 I'm already remembering that message.".to_string()
+        })
+    }
+
+    /// Handle /memory list, forget and clear
+    async fn handle_memory(
+        &self,
+        ctx: &CommandContext,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<String> {
+        let user_id = command.user.id.to_string();
+        let channel_id = command.channel_id.to_string();
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+        let everywhere = get_bool_option(&subcommand.options, "everywhere").unwrap_or(false);
+        let scope = (!everywhere).then_some(channel_id.as_str());
+
+        match subcommand.name.as_str() {
+            "forget" => {
+                let id = get_integer_option(&subcommand.options, "id").unwrap_or_default();
+                Ok(if ctx.database.delete_pinned_memory(&user_id, id).await? {
+                    info!("/memory forget | User: {user_id} | Memory: {id}");
+                    format!("🗑️ Forgot memory #{id}.")
+                } else {
+                    format!("❌ You don't have a memory #{id}.")
+                })
+            }
+            "clear" => {
+                let cleared = ctx.database.clear_pinned_memories(&user_id, scope).await?;
+                info!("/memory clear | User: {user_id} | Everywhere: {everywhere} | Cleared: {cleared}");
+                Ok(format!("🗑️ Forgot {cleared} memories."))
+            }
+            _ => {
+                let memories = ctx.database.get_pinned_memories(&user_id, scope).await?;
+                Ok(Self::describe(&memories, everywhere))
+            }
+        }
+    }
+
+    /// The /memory list reply
+    fn describe(memories: &[PinnedMemory], everywhere: bool) -> String {
+        if memories.is_empty() {
+            return "You haven't pinned any memories yet. Right-click a message and pick \
+                    **Apps → Remember this**."
+                .to_string();
+        }
+        let mut lines = vec![format!(
+            "📌 **Your pinned memories{}**",
+            if everywhere { "" } else { " in this channel" }
+        )];
+        for memory in memories {
+            let mut preview: String = memory.content.chars().take(LIST_PREVIEW_CHARS).collect();
+            if memory.content.chars().count() > LIST_PREVIEW_CHARS {
+                preview.push('…');
+            }
+            let place = if everywhere {
+                format!(" in <#{}>", memory.channel_id)
+            } else {
+                String::new()
+            };
+            lines.push(format!(
+                "`#{}` {}{place}: {preview}",
+                memory.id,
+                author_label(memory)
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory(id: i64, author: &str, content: &str) -> PinnedMemory {
+        PinnedMemory {
+            id,
+            user_id: "u1".to_string(),
+            channel_id: "c1".to_string(),
+            content: content.to_string(),
+            author_name: author.to_string(),
+            created_at: "2025-10-09 08:53:20".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_memory_handler_commands() {
+        let handler = MemoryHandler;
+        assert_eq!(handler.command_names(), &["memory", "Remember this"]);
+    }
+
+    #[test]
+    fn test_describe_memories() {
+        assert!(MemoryHandler::describe(&[], false).starts_with("You haven't pinned"));
+
+        let memories = vec![
+            memory(3, "Ada", "My cat is called Miso"),
+            memory(7, BOT_AUTHOR, &"x".repeat(130)),
+        ];
+        let list = MemoryHandler::describe(&memories, false);
+        assert!(list.starts_with("📌 **Your pinned memories in this channel**\n"));
+        assert!(list.contains("`#3` Ada: My cat is called Miso"));
+        assert!(list.ends_with(&format!("`#7` You: {}…", "x".repeat(120))));
+
+        let everywhere = MemoryHandler::describe(&memories[..1], true);
+        assert!(everywhere.contains("`#3` Ada in <#c1>: My cat"));
+    }
+}
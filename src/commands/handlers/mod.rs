@@ -1,9 +1,44 @@
 //! Per-command handler implementations
 //!
-//! - **Version**: 5.0.0
+//! - **Version**: 40.0.0
 //! - **Since**: 3.38.0
 //!
 //! ## Changelog
+//! - 40.0.0: Add GlossaryHandler for /glossary
+//! - 39.0.0: Add MemoryHandler for /memory and the Remember this context menu
+//! - 38.0.0: Add HistoryHandler for /history
+//! - 37.0.0: Add GuardrailsHandler for /guardrails
+//! - 36.0.0: Add DiceHandler for /roll and /initiative
+//! - 35.0.0: Add ConvertHandler for /convert
+//! - 34.0.0: Add TimeHandler for /time
+//! - 33.0.0: Add CountdownHandler for /countdown
+//! - 32.0.0: Add SendLaterHandler for /send_later
+//! - 31.0.0: Add ConversationExportHandler for the Export Conversation context menu
+//! - 30.0.0: Add PromptsHandler for the /prompts library
+//! - 29.0.0: Add AliasHandler for /alias prompt shortcuts
+//! - 28.0.0: Add AutoResponseHandler for /autoresponse
+//! - 27.0.0: Add VerificationHandler for /verification
+//! - 26.0.0: Add AnonQuestionsHandler for /ask_anon and /anon
+//! - 25.0.0: Add SupportHandler for /support tickets
+//! - 24.0.0: Add SuggestionsHandler for /suggest and /suggestions
+//! - 23.0.0: Add QotdHandler for /qotd questions of the day
+//! - 22.0.0: Add ChannelTopicHandler for /channel_topic scheduled topics
+//! - 21.0.0: Add LevelingHandler for /rank, /leaderboard and /leveling
+//! - 20.0.0: Add ProfileHandler for /profile member cards
+//! - 19.0.0: Add MoodHandler for /mood channel sentiment timelines
+//! - 18.0.0: Add TopicsHandler for /topics trending topics
+//! - 17.0.0: Add ReportHandler for /report weekly server reports
+//! - 16.0.0: Add TelegramHandler for /telegram account linking (telegram feature)
+//! - 15.0.0: Add WebhooksHandler for /webhooks outbound notifications
+//! - 14.0.0: Add TicketHandler for /ticket and the Create Ticket context menu
+//! - 13.0.0: Add MeetHandler for /meet availability polls
+//! - 12.0.0: Add StandupHandler for /standup schedule and participation
+//! - 11.0.0: Add StoryHandler for /story collaborative writing
+//! - 10.0.0: Add TriviaHandler for /trivia games and leaderboard
+//! - 9.0.0: Add BirthdayHandler for /birthday dates and opt-outs
+//! - 8.0.0: Add PrivacyHandler for /privacy opt-outs
+//! - 7.0.0: Add ForkHandler for /fork conversation branching
+//! - 6.0.0: Add SearchHandler for /search full-text message search
 //! - 5.0.0: Add ContextInfoHandler for /context window inspection
 //! - 4.0.0: Add FetchHandler for /fetch webpage summaries
 //! - 3.0.0: Add PluginsHandler for /plugins subcommand dispatch
@@ -17,18 +52,54 @@
 //! - 1.0.0: Initial extraction from monolithic command_handler.rs
 
 pub mod admin;
+pub mod alias;
+pub mod anon_questions;
 pub mod ask;
+pub mod autoresponse;
+pub mod birthday;
+pub mod channel_topic;
 pub mod context_info;
 pub mod context_menu;
+pub mod conversation_export;
+pub mod convert;
 pub mod council;
+pub mod countdown;
 pub mod debate;
+pub mod dice;
 pub mod fetch;
+pub mod fork;
+pub mod glossary;
+pub mod guardrails;
+pub mod history;
 pub mod imagine;
 pub mod info;
+pub mod leveling;
+pub mod meet;
+pub mod memory;
+pub mod mood;
 pub mod persona;
 pub mod plugins;
+pub mod privacy;
+pub mod profile;
+pub mod prompts;
+pub mod qotd;
 pub mod remind;
+pub mod report;
+pub mod search;
+pub mod send_later;
+pub mod standup;
+pub mod story;
+pub mod suggestions;
+pub mod support;
+#[cfg(feature = "telegram")]
+pub mod telegram;
+pub mod ticket;
+pub mod time;
+pub mod topics;
+pub mod trivia;
 pub mod utility;
+pub mod verification;
+pub mod webhooks;
 
 use std::sync::Arc;
 
@@ -47,10 +118,46 @@ pub fn create_all_handlers() -> Vec<Arc<dyn SlashCommandHandler>> {
         Arc::new(ask::AskHandler),
         Arc::new(debate::DebateHandler),
         Arc::new(fetch::FetchHandler),
+        Arc::new(fork::ForkHandler),
         Arc::new(context_info::ContextInfoHandler),
         Arc::new(council::CouncilHandler),
         Arc::new(info::InfoHandler),
         Arc::new(context_menu::ContextMenuHandler),
         Arc::new(plugins::PluginsHandler),
+        Arc::new(search::SearchHandler),
+        Arc::new(privacy::PrivacyHandler),
+        Arc::new(birthday::BirthdayHandler),
+        Arc::new(trivia::TriviaHandler),
+        Arc::new(story::StoryHandler),
+        Arc::new(standup::StandupHandler),
+        Arc::new(meet::MeetHandler),
+        Arc::new(ticket::TicketHandler),
+        Arc::new(webhooks::WebhooksHandler),
+        Arc::new(report::ReportHandler),
+        Arc::new(topics::TopicsHandler),
+        Arc::new(mood::MoodHandler),
+        Arc::new(profile::ProfileHandler),
+        Arc::new(leveling::LevelingHandler),
+        Arc::new(channel_topic::ChannelTopicHandler),
+        Arc::new(qotd::QotdHandler),
+        Arc::new(suggestions::SuggestionsHandler),
+        Arc::new(support::SupportHandler),
+        Arc::new(anon_questions::AnonQuestionsHandler),
+        Arc::new(verification::VerificationHandler),
+        Arc::new(autoresponse::AutoResponseHandler),
+        Arc::new(alias::AliasHandler),
+        Arc::new(prompts::PromptsHandler),
+        Arc::new(conversation_export::ConversationExportHandler),
+        Arc::new(send_later::SendLaterHandler),
+        Arc::new(countdown::CountdownHandler),
+        Arc::new(time::TimeHandler),
+        Arc::new(convert::ConvertHandler),
+        Arc::new(dice::DiceHandler),
+        Arc::new(guardrails::GuardrailsHandler),
+        Arc::new(history::HistoryHandler),
+        Arc::new(memory::MemoryHandler),
+        Arc::new(glossary::GlossaryHandler),
+        #[cfg(feature = "telegram")]
+        Arc::new(telegram::TelegramHandler),
     ]
 }
@@ -0,0 +1,197 @@
+//! Mood command handler
+//!
+//! Handles: mood
+//!
+//! Shows a channel's hourly sentiment as a sparkline with the current, rolling
+//! and overall mood.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use serenity::builder::CreateEmbed;
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::prelude::Context;
+use std::sync::Arc;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::{get_channel_option, get_integer_option};
+use crate::database::SentimentPoint;
+use crate::features::sentiment::{
+    hour_start, hourly_series, mood_label, rolling_window, sparkline, weighted_average,
+    MAX_MOOD_HOURS, ROLLING_WINDOW_HOURS, SENTIMENT_FEATURE,
+};
+
+/// Window used when no `hours` option is given
+const DEFAULT_HOURS: i64 = 24;
+
+/// Handler for /mood
+pub struct MoodHandler;
+
+#[async_trait]
+impl SlashCommandHandler for MoodHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["mood"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        self.handle_mood(&ctx, serenity_ctx, command).await
+    }
+}
+
+impl MoodHandler {
+    /// Handle /mood [hours] [channel]
+    async fn handle_mood(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let user_id = command.user.id.to_string();
+        let Some(guild_id) = command.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+
+        let options = &command.data.options;
+        let hours = get_integer_option(options, "hours")
+            .unwrap_or(DEFAULT_HOURS)
+            .clamp(1, MAX_MOOD_HOURS);
+        let channel_id = get_channel_option(options, "channel")
+            .unwrap_or(command.channel_id.0)
+            .to_string();
+        info!("/mood | User: {user_id} | Guild: {guild_id} | <#{channel_id}> {hours}h");
+
+        let problem = if !ctx
+            .database
+            .is_feature_enabled(SENTIMENT_FEATURE, None, Some(&guild_id))
+            .await?
+        {
+            Some("❌ Channel sentiment is disabled on this server.".to_string())
+        } else if !ctx
+            .database
+            .get_channel_sentiment_tracking(&guild_id, &channel_id)
+            .await?
+        {
+            Some(format!(
+                "Sentiment isn't tracked in <#{channel_id}>. An admin can turn it on with \
+                 `/set_channel sentiment_tracking enabled`."
+            ))
+        } else {
+            None
+        };
+        if let Some(problem) = problem {
+            responder
+                .create_interaction_response(&serenity_ctx.http, |r| {
+                    r.kind(InteractionResponseType::ChannelMessageWithSource)
+                        .interaction_response_data(|m| m.content(problem).ephemeral(true))
+                })
+                .await?;
+            return Ok(());
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        let since = hour_start(now) - (hours - 1) * 3600;
+        let points = ctx
+            .database
+            .get_channel_sentiment(&channel_id, since)
+            .await?;
+        let embed = Self::mood_embed(&channel_id, hours, &points, since, now);
+        responder
+            .create_interaction_response(&serenity_ctx.http, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|m| m.add_embed(embed))
+            })
+            .await?;
+        Ok(())
+    }
+
+    fn mood_embed(
+        channel_id: &str,
+        hours: i64,
+        points: &[SentimentPoint],
+        since: i64,
+        now: i64,
+    ) -> CreateEmbed {
+        let mut embed = CreateEmbed::default();
+        embed.title("🌡️ Channel Mood").color(0x5865F2).footer(|f| {
+            f.text(format!(
+                "Last {hours}h · one block per hour, ▁ hostile → █ friendly"
+            ))
+        });
+
+        let Some(average) = weighted_average(points) else {
+            embed.description(format!(
+                "No messages scored in <#{channel_id}> over the last {hours}h yet."
+            ));
+            return embed;
+        };
+        let messages: i64 = points.iter().map(|p| p.messages).sum();
+        embed.description(format!(
+            "<#{channel_id}>\n```\n{}\n```",
+            sparkline(&hourly_series(points, since, now))
+        ));
+        if let Some(latest) = points.last() {
+            embed.field(
+                "Latest hour",
+                Self::format_mood(latest.score, latest.messages),
+                true,
+            );
+        }
+        let recent = rolling_window(points, now, ROLLING_WINDOW_HOURS);
+        if let Some(rolling) = weighted_average(&recent) {
+            embed.field(
+                format!("Last {ROLLING_WINDOW_HOURS}h"),
+                Self::format_mood(rolling, recent.iter().map(|p| p.messages).sum()),
+                true,
+            );
+        }
+        embed.field(
+            format!("Last {hours}h"),
+            Self::format_mood(average, messages),
+            true,
+        );
+        embed
+    }
+
+    fn format_mood(score: f64, messages: i64) -> String {
+        let (emoji, label) = mood_label(score);
+        format!("{emoji} {label} ({score:+.2})\n{messages} messages")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mood_handler_commands() {
+        let handler = MoodHandler;
+        assert_eq!(handler.command_names(), &["mood"]);
+    }
+
+    #[test]
+    fn test_format_mood() {
+        assert_eq!(
+            MoodHandler::format_mood(0.25, 12),
+            "🙂 Positive (+0.25)\n12 messages"
+        );
+        assert_eq!(
+            MoodHandler::format_mood(-0.5, 3),
+            "😠 Heated (-0.50)\n3 messages"
+        );
+    }
+}
@@ -2,10 +2,12 @@
 //!
 //! Handles: personas
 //!
-//! - **Version**: 1.0.0
+//! - **Version**: 1.2.0
 //! - **Since**: 3.38.0
 //!
 //! ## Changelog
+//! - 1.2.0: Current persona is read for the guild the command runs in
+//! - 1.1.0: Initial responses go through InteractionResponder (auto-defer safe)
 //! - 1.0.0: Extracted from command_handler.rs
 
 use anyhow::Result;
@@ -18,6 +20,7 @@ use std::sync::Arc;
 
 use crate::commands::context::CommandContext;
 use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
 use crate::message_components::MessageComponentHandler;
 
 /// Handler for persona listing command
@@ -53,6 +56,7 @@ impl PersonaHandler {
         serenity_ctx: &Context,
         command: &ApplicationCommandInteraction,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let personas = ctx.persona_manager.list_personas();
         let mut response = "**Available Personas:**\n".to_string();
 
@@ -61,11 +65,15 @@ impl PersonaHandler {
         }
 
         let user_id = command.user.id.to_string();
-        let current_persona = ctx.database.get_user_persona(&user_id).await?;
+        let guild_id = command.guild_id.map(|id| id.to_string());
+        let current_persona = ctx
+            .database
+            .get_user_persona_with_guild(&user_id, guild_id.as_deref())
+            .await?;
         response.push_str(&format!("\nYour current persona: `{current_persona}`"));
         response.push_str("\n\n**Quick Switch:**\nUse the dropdown below to change your persona!");
 
-        command
+        responder
             .create_interaction_response(&serenity_ctx.http, |response_builder| {
                 response_builder
                     .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -2,10 +2,17 @@
 //!
 //! Handles: plugins (with subcommands for each plugin)
 //!
-//! - **Version**: 1.0.0
+//! - **Version**: 1.6.0
 //! - **Since**: 4.0.0
 //!
 //! ## Changelog
+//! - 1.6.0: Added transcribe_subscribe, transcribe_unsubscribe and transcribe_subscriptions for YouTube channel subscriptions
+//! - 1.5.0: YouTube playlists go through PluginManager::execute_playlist_url with filter options
+//! - 1.4.0: Requests for a URL already being processed link to the running job with a notify button
+//! - 1.3.1: Look up plugins through PluginManager so reloads apply
+//! - 1.3.0: Paginated embed output via PaginatedEmbed
+//! - 1.2.0: Initial responses go through InteractionResponder (auto-defer safe)
+//! - 1.1.0: Reject YouTube plugin runs up front while the yt-dlp circuit breaker is open
 //! - 1.0.0: Initial implementation - migrated from command_handler.rs plugin dispatch
 
 use anyhow::Result;
@@ -22,7 +29,19 @@ use uuid::Uuid;
 
 use crate::commands::context::CommandContext;
 use crate::commands::handler::SlashCommandHandler;
-use crate::features::plugins::{short_job_id, PluginManager};
+use crate::commands::pagination::PaginatedEmbed;
+use crate::commands::responder::InteractionResponder;
+use crate::database::TranscribeSubscription;
+use crate::features::plugins::subscriptions::{
+    DEFAULT_INTERVAL_HOURS, DEFAULT_MAX_PER_CHECK, FEED_WINDOW, MAX_SUBSCRIPTIONS_PER_GUILD,
+};
+use crate::features::plugins::{
+    channel_uploads_url, enumerate_channel_uploads, short_job_id, watch_button, PluginManager,
+};
+use crate::features::resilience::{circuit_breakers, Dependency};
+
+/// Job lines shown per page of /plugins transcribe_status
+const JOBS_PER_PAGE: usize = 10;
 
 /// Handler for all plugin commands via /plugins <subcommand>
 pub struct PluginsHandler;
@@ -39,12 +58,13 @@ impl SlashCommandHandler for PluginsHandler {
         serenity_ctx: &Context,
         command: &ApplicationCommandInteraction,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let request_id = Uuid::new_v4();
 
         let plugin_manager = match ctx.plugin_manager {
             Some(ref pm) => pm.clone(),
             None => {
-                command
+                responder
                     .create_interaction_response(&serenity_ctx.http, |response| {
                         response
                             .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -63,7 +83,7 @@ impl SlashCommandHandler for PluginsHandler {
         let (subcommand_name, sub_options) = match extract_subcommand(&command.data.options) {
             Some(result) => result,
             None => {
-                command
+                responder
                     .create_interaction_response(&serenity_ctx.http, |response| {
                         response
                             .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -81,16 +101,11 @@ impl SlashCommandHandler for PluginsHandler {
         };
 
         // Find the plugin matching the subcommand
-        let plugin = match plugin_manager
-            .config
-            .plugins
-            .iter()
-            .find(|p| p.enabled && p.command.name == subcommand_name)
-        {
-            Some(p) => p.clone(),
+        let plugin = match plugin_manager.get_plugin_by_command(&subcommand_name) {
+            Some(p) => p,
             None => {
                 warn!("[{request_id}] Unknown plugin subcommand: {subcommand_name}");
-                command
+                responder
                     .create_interaction_response(&serenity_ctx.http, |response| {
                         response
                             .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -131,6 +146,7 @@ impl PluginsHandler {
         sub_options: Vec<CommandDataOption>,
         request_id: Uuid,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let user_id = command.user.id.to_string();
         let guild_id = command.guild_id.map(|id| id.to_string());
 
@@ -141,7 +157,7 @@ impl PluginsHandler {
 
         // Check guild_only restriction
         if plugin.security.guild_only && guild_id.is_none() {
-            command
+            responder
                 .create_interaction_response(&serenity_ctx.http, |response| {
                     response
                         .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -155,6 +171,38 @@ impl PluginsHandler {
             return Ok(());
         }
 
+        // Join a job already processing this URL instead of starting a second one
+        let existing_job = match (&guild_id, extract_params(&sub_options).remove("url")) {
+            (Some(gid), Some(url)) if !plugin.is_virtual() => plugin_manager
+                .job_manager
+                .find_active_job(gid, &plugin.name, &url),
+            _ => None,
+        };
+        if let Some(job) = existing_job {
+            info!(
+                "[{}] 🔁 Joining existing job {} for plugin {}",
+                request_id, job.id, plugin.name
+            );
+            let location = format!("<#{}>", job.thread_id.as_ref().unwrap_or(&job.channel_id));
+            responder
+                .create_interaction_response(&serenity_ctx.http, |response| {
+                    response
+                        .kind(InteractionResponseType::ChannelMessageWithSource)
+                        .interaction_response_data(|message| {
+                            message
+                                .content(format!(
+                                    "⏳ This is already being processed (job `{}`, started by <@{}>). Follow along in {location}.",
+                                    short_job_id(&job.id),
+                                    job.user_id
+                                ))
+                                .set_components(watch_button(&job.id))
+                                .ephemeral(true)
+                        })
+                })
+                .await?;
+            return Ok(());
+        }
+
         // Check cooldown
         if plugin.security.cooldown_seconds > 0
             && !plugin_manager.job_manager.check_cooldown(
@@ -162,7 +210,7 @@ impl PluginsHandler {
                 &plugin.name,
                 plugin.security.cooldown_seconds,
             ) {
-                command
+                responder
                     .create_interaction_response(&serenity_ctx.http, |response| {
                         response
                             .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -199,7 +247,7 @@ impl PluginsHandler {
                     if let Some(ref pattern) = validation.pattern {
                         let re = regex::Regex::new(pattern)?;
                         if !re.is_match(value) {
-                            command
+                            responder
                                 .create_interaction_response(&serenity_ctx.http, |response| {
                                     response
                                         .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -218,7 +266,7 @@ impl PluginsHandler {
                     // Check length constraints
                     if let Some(min_len) = validation.min_length {
                         if value.len() < min_len {
-                            command
+                            responder
                                 .create_interaction_response(&serenity_ctx.http, |response| {
                                     response
                                         .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -236,7 +284,7 @@ impl PluginsHandler {
                     }
                     if let Some(max_len) = validation.max_length {
                         if value.len() > max_len {
-                            command
+                            responder
                                 .create_interaction_response(&serenity_ctx.http, |response| {
                                     response
                                         .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -254,7 +302,7 @@ impl PluginsHandler {
                     }
                 }
             } else if opt_def.required {
-                command
+                responder
                     .create_interaction_response(&serenity_ctx.http, |response| {
                         response
                             .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -279,7 +327,7 @@ impl PluginsHandler {
                 .is_feature_enabled("plugins", None, Some(gid))
                 .await?;
             if !enabled {
-                command
+                responder
                     .create_interaction_response(&serenity_ctx.http, |response| {
                         response
                             .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -294,6 +342,25 @@ impl PluginsHandler {
             }
         }
 
+        // Don't start a long-running job that yt-dlp can't serve right now
+        let is_youtube_url = params
+            .get("url")
+            .is_some_and(|u| u.contains("youtube.com") || u.contains("youtu.be"));
+        if is_youtube_url {
+            if let Err(open) = circuit_breakers().peek(Dependency::YtDlp) {
+                responder
+                    .create_interaction_response(&serenity_ctx.http, |response| {
+                        response
+                            .kind(InteractionResponseType::ChannelMessageWithSource)
+                            .interaction_response_data(|message| {
+                                message.content(format!("🔌 {open}")).ephemeral(true)
+                            })
+                    })
+                    .await?;
+                return Ok(());
+            }
+        }
+
         // Handle virtual plugins (no CLI execution, handled internally)
         if plugin.is_virtual() {
             info!(
@@ -315,7 +382,7 @@ impl PluginsHandler {
 
         // Defer the response (command will take a while)
         // Use ephemeral response so only the thread appears in the channel
-        command
+        responder
             .create_interaction_response(&serenity_ctx.http, |response| {
                 response
                     .kind(InteractionResponseType::DeferredChannelMessageWithSource)
@@ -363,7 +430,26 @@ impl PluginsHandler {
                 .map(|u| u.contains("playlist?list=") || u.contains("&list="))
                 .unwrap_or(false);
 
-            let result = if use_chunking && is_youtube && !is_playlist {
+            let playlists_enabled = plugin.playlist.as_ref().is_some_and(|p| p.enabled);
+
+            let result = if is_youtube && is_playlist && playlists_enabled {
+                // Playlists are listed and filtered up front, then transcribed video by video
+                let url = params.get("url").cloned().unwrap_or_default();
+                info!("[{request_id}] 📋 Using playlist transcription for: {url}");
+
+                plugin_manager
+                    .execute_playlist_url(
+                        http,
+                        plugin.clone(),
+                        &url,
+                        &params,
+                        user_id_owned,
+                        guild_id_owned,
+                        discord_channel_id,
+                        interaction_info,
+                    )
+                    .await
+            } else if use_chunking && is_youtube && !is_playlist {
                 // Use chunked transcription for YouTube videos (not playlists)
                 let url = params.get("url").cloned().unwrap_or_default();
                 let video_title = crate::features::plugins::fetch_youtube_title(&url)
@@ -453,6 +539,7 @@ impl PluginsHandler {
         user_id: &str,
         request_id: Uuid,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         match plugin.command.name.as_str() {
             "transcribe_cancel" => {
                 self.handle_transcribe_cancel(
@@ -469,13 +556,32 @@ impl PluginsHandler {
                 self.handle_transcribe_status(ctx, command, plugin_manager, user_id, request_id)
                     .await
             }
+            "transcribe_subscribe" => {
+                self.handle_transcribe_subscribe(
+                    ctx,
+                    command,
+                    plugin_manager,
+                    params,
+                    user_id,
+                    request_id,
+                )
+                .await
+            }
+            "transcribe_unsubscribe" => {
+                self.handle_transcribe_unsubscribe(ctx, command, plugin_manager, params, user_id)
+                    .await
+            }
+            "transcribe_subscriptions" => {
+                self.handle_transcribe_subscriptions(ctx, command, plugin_manager)
+                    .await
+            }
             _ => {
                 // Unknown virtual plugin
                 warn!(
                     "[{}] ❓ Unknown virtual plugin: {}",
                     request_id, plugin.command.name
                 );
-                command
+                responder
                     .create_interaction_response(&ctx.http, |response| {
                         response
                             .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -501,6 +607,7 @@ impl PluginsHandler {
         user_id: &str,
         request_id: Uuid,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         info!(
             "[{request_id}] 🛑 Processing transcribe_cancel for user {user_id}"
         );
@@ -544,7 +651,7 @@ impl PluginsHandler {
                         info!(
                             "[{request_id}] ✅ Cancelled playlist job {job_id} for user {user_id}"
                         );
-                        command
+                        responder
                             .create_interaction_response(&ctx.http, |response| {
                                 response
                                     .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -565,7 +672,7 @@ impl PluginsHandler {
                     }
                     Ok(false) => {
                         // Job wasn't active (already completed or cancelled)
-                        command
+                        responder
                             .create_interaction_response(&ctx.http, |response| {
                                 response
                                     .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -583,7 +690,7 @@ impl PluginsHandler {
                     }
                     Err(e) => {
                         error!("[{request_id}] ❌ Failed to cancel job {job_id}: {e}");
-                        command
+                        responder
                             .create_interaction_response(&ctx.http, |response| {
                                 response
                                     .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -599,7 +706,7 @@ impl PluginsHandler {
             }
             None => {
                 // No active job found
-                command
+                responder
                     .create_interaction_response(&ctx.http, |response| {
                         response
                             .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -628,6 +735,7 @@ impl PluginsHandler {
         user_id: &str,
         request_id: Uuid,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         info!(
             "[{request_id}] 📊 Processing transcribe_status for user {user_id}"
         );
@@ -651,7 +759,7 @@ impl PluginsHandler {
             .collect();
 
         if active_jobs.is_empty() && active_video_jobs.is_empty() {
-            command
+            responder
                 .create_interaction_response(&ctx.http, |response| {
                     response
                         .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -668,11 +776,11 @@ impl PluginsHandler {
             return Ok(());
         }
 
-        // Build status message
-        let mut status_lines = vec!["**Your Transcription Jobs:**".to_string()];
+        // Build status lines
+        let mut status_lines = Vec::new();
 
         if !active_jobs.is_empty() {
-            status_lines.push("\n**Playlist Jobs:**".to_string());
+            status_lines.push("**Playlist Jobs:**".to_string());
             for job in &active_jobs {
                 let progress_pct = job.progress_percent();
                 let title = job.playlist_title.as_deref().unwrap_or("Untitled playlist");
@@ -720,17 +828,273 @@ impl PluginsHandler {
             }
         }
 
-        status_lines
-            .push("\n*To cancel a job, use `/plugins transcribe_cancel [job_id]`*".to_string());
+        PaginatedEmbed::from_lines("Your Transcription Jobs", &status_lines, JOBS_PER_PAGE)
+            .with_footer("To cancel a job, use /plugins transcribe_cancel [job_id]")
+            .with_owner(command.user.id.0)
+            .respond(
+                plugin_manager.job_manager.database(),
+                &ctx.http,
+                &responder,
+                true,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Handle /plugins transcribe_subscribe - transcribe a YouTube channel's new uploads
+    async fn handle_transcribe_subscribe(
+        &self,
+        ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        plugin_manager: &Arc<PluginManager>,
+        params: &HashMap<String, String>,
+        user_id: &str,
+        request_id: Uuid,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let Some(guild_id) = command.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        let can_manage = command
+            .member
+            .as_ref()
+            .and_then(|m| m.permissions)
+            .is_some_and(|p| p.manage_guild());
+        if !can_manage {
+            return responder
+                .reply_ephemeral(
+                    ctx,
+                    "❌ You need the Manage Server permission to add channel subscriptions.",
+                )
+                .await;
+        }
+        let Some(source_url) = params
+            .get("channel_url")
+            .and_then(|url| channel_uploads_url(url))
+        else {
+            return responder
+                .reply_ephemeral(
+                    ctx,
+                    "❌ That doesn't look like a YouTube channel URL (e.g. `https://www.youtube.com/@handle`).",
+                )
+                .await;
+        };
+        let channel_id = params
+            .get("channel")
+            .and_then(|c| c.parse::<u64>().ok())
+            .unwrap_or(command.channel_id.0)
+            .to_string();
+
+        let database = plugin_manager.job_manager.database();
+        let existing = database
+            .get_guild_transcribe_subscriptions(&guild_id)
+            .await?;
+        if let Some(sub) = existing
+            .iter()
+            .find(|s| s.source_url == source_url && s.channel_id == channel_id)
+        {
+            return responder
+                .reply_ephemeral(
+                    ctx,
+                    &format!(
+                        "This channel is already subscribed in <#{channel_id}> (subscription #{}).",
+                        sub.id
+                    ),
+                )
+                .await;
+        }
+        if existing.len() >= MAX_SUBSCRIPTIONS_PER_GUILD {
+            return responder
+                .reply_ephemeral(
+                    ctx,
+                    &format!(
+                        "❌ This server already has {MAX_SUBSCRIPTIONS_PER_GUILD} channel subscriptions. Remove one first."
+                    ),
+                )
+                .await;
+        }
 
-        command
+        responder
             .create_interaction_response(&ctx.http, |response| {
                 response
-                    .kind(InteractionResponseType::ChannelMessageWithSource)
-                    .interaction_response_data(|message| {
-                        message.content(status_lines.join("\n")).ephemeral(true)
-                    })
+                    .kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                    .interaction_response_data(|data| data.ephemeral(true))
+            })
+            .await?;
+
+        // Current uploads are the baseline; only videos published after this get transcribed
+        let feed = match enumerate_channel_uploads(&source_url, Some(FEED_WINDOW)).await {
+            Ok(feed) => feed,
+            Err(e) => {
+                warn!("[{request_id}] Failed to read channel {source_url}: {e}");
+                return responder
+                    .reply_ephemeral(
+                        ctx,
+                        &format!("❌ Couldn't read that channel's uploads: {e}"),
+                    )
+                    .await;
+            }
+        };
+        let interval_hours = params
+            .get("interval_hours")
+            .and_then(|h| h.parse::<u32>().ok())
+            .unwrap_or(DEFAULT_INTERVAL_HOURS)
+            .clamp(1, 168);
+        let max_per_check = params
+            .get("max_videos")
+            .and_then(|m| m.parse::<u32>().ok())
+            .unwrap_or(DEFAULT_MAX_PER_CHECK)
+            .clamp(1, 10);
+        let title = feed.uploader.clone().unwrap_or_else(|| feed.title.clone());
+
+        let mut subscription = TranscribeSubscription {
+            id: 0,
+            guild_id,
+            channel_id,
+            source_url,
+            title: Some(title.clone()),
+            user_id: user_id.to_string(),
+            interval_minutes: interval_hours * 60,
+            max_per_check,
+            last_checked_at: Some(chrono::Utc::now().timestamp()),
+        };
+        subscription.id = database
+            .create_transcribe_subscription(&subscription)
+            .await?;
+        let seen: Vec<String> = feed.items.iter().map(|i| i.video_id.clone()).collect();
+        database
+            .mark_subscription_videos_seen(subscription.id, &seen)
+            .await?;
+
+        info!(
+            "[{request_id}] 📡 Subscription #{} added for {} in channel {}",
+            subscription.id, subscription.source_url, subscription.channel_id
+        );
+        responder
+            .reply_ephemeral(
+                ctx,
+                &format!(
+                    "📡 Subscribed to **{title}** (#{}). New uploads will be transcribed into threads in <#{}>, \
+                    checking every {interval_hours}h (up to {max_per_check} per check).\n\
+                    Remove it with `/plugins transcribe_unsubscribe {}`.",
+                    subscription.id, subscription.channel_id, subscription.id
+                ),
+            )
+            .await
+    }
+
+    /// Handle /plugins transcribe_unsubscribe - remove a channel subscription
+    async fn handle_transcribe_unsubscribe(
+        &self,
+        ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        plugin_manager: &Arc<PluginManager>,
+        params: &HashMap<String, String>,
+        user_id: &str,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let Some(guild_id) = command.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        let Some(id) = params
+            .get("subscription_id")
+            .and_then(|id| id.parse::<i64>().ok())
+        else {
+            return responder
+                .reply_ephemeral(ctx, "❌ Invalid subscription ID.")
+                .await;
+        };
+
+        let database = plugin_manager.job_manager.database();
+        let subscriptions = database
+            .get_guild_transcribe_subscriptions(&guild_id)
+            .await?;
+        let Some(subscription) = subscriptions.iter().find(|s| s.id == id) else {
+            return responder
+                .reply_ephemeral(ctx, &format!("❌ No subscription #{id} in this server."))
+                .await;
+        };
+        let can_manage = command
+            .member
+            .as_ref()
+            .and_then(|m| m.permissions)
+            .is_some_and(|p| p.manage_guild());
+        if subscription.user_id != user_id && !can_manage {
+            return responder
+                .reply_ephemeral(
+                    ctx,
+                    "❌ Only the member who subscribed or someone with Manage Server can remove this.",
+                )
+                .await;
+        }
+
+        database
+            .delete_transcribe_subscription(&guild_id, id)
+            .await?;
+        let title = subscription
+            .title
+            .as_deref()
+            .unwrap_or(&subscription.source_url);
+        responder
+            .reply_ephemeral(ctx, &format!("✅ Unsubscribed from **{title}** (#{id})."))
+            .await
+    }
+
+    /// Handle /plugins transcribe_subscriptions - list the server's channel subscriptions
+    async fn handle_transcribe_subscriptions(
+        &self,
+        ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        plugin_manager: &Arc<PluginManager>,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let Some(guild_id) = command.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        let subscriptions = plugin_manager
+            .job_manager
+            .database()
+            .get_guild_transcribe_subscriptions(&guild_id)
+            .await?;
+        if subscriptions.is_empty() {
+            return responder
+                .reply_ephemeral(
+                    ctx,
+                    "📭 No channel subscriptions yet. Add one with `/plugins transcribe_subscribe`.",
+                )
+                .await;
+        }
+
+        let lines: Vec<String> = subscriptions
+            .iter()
+            .map(|s| {
+                let last_checked = s
+                    .last_checked_at
+                    .map(|at| format!("<t:{at}:R>"))
+                    .unwrap_or_else(|| "never".to_string());
+                format!(
+                    "• `#{}` [{}]({}) → <#{}> every {}h, up to {} per check (last checked {last_checked}, by <@{}>)",
+                    s.id,
+                    s.title.as_deref().unwrap_or("YouTube channel"),
+                    s.source_url,
+                    s.channel_id,
+                    s.interval_minutes / 60,
+                    s.max_per_check,
+                    s.user_id
+                )
             })
+            .collect();
+
+        PaginatedEmbed::from_lines("Channel Subscriptions", &lines, JOBS_PER_PAGE)
+            .with_footer("To remove one, use /plugins transcribe_unsubscribe <id>")
+            .with_owner(command.user.id.0)
+            .respond(
+                plugin_manager.job_manager.database(),
+                &ctx.http,
+                &responder,
+                true,
+            )
             .await?;
 
         Ok(())
@@ -0,0 +1,174 @@
+//! Privacy command handler
+//!
+//! Handles: privacy
+//!
+//! Users choose whether their DMs are stored, whether their activity feeds
+//! analytics, and whether conflict detection may read their messages. Options
+//! left at "default" follow the server's `privacy_*` guild settings.
+//!
+//! - **Version**: 1.0.1
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.1: Slow replies are deferred privately
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::prelude::Context;
+use std::sync::Arc;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::get_string_option;
+use crate::database::{PrivacyOption, PrivacySettings};
+
+/// Handler for /privacy command
+pub struct PrivacyHandler;
+
+#[async_trait]
+impl SlashCommandHandler for PrivacyHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["privacy"]
+    }
+
+    fn ephemeral(&self, _command: &ApplicationCommandInteraction) -> bool {
+        true
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        self.handle_privacy(&ctx, serenity_ctx, command).await
+    }
+}
+
+impl PrivacyHandler {
+    /// Handle /privacy settings and /privacy set
+    async fn handle_privacy(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let user_id = command.user.id.to_string();
+        let guild_id = command.guild_id.map(|id| id.to_string());
+
+        let mut content = String::new();
+        if let Some(set) = command.data.options.iter().find(|o| o.name == "set") {
+            let option = get_string_option(&set.options, "setting")
+                .and_then(|key| PrivacyOption::from_key(&key))
+                .ok_or_else(|| anyhow::anyhow!("Missing or unknown privacy setting"))?;
+            let value = get_string_option(&set.options, "value")
+                .ok_or_else(|| anyhow::anyhow!("Missing value parameter"))?;
+            let allowed = match value.as_str() {
+                "allow" => Some(true),
+                "opt_out" => Some(false),
+                _ => None,
+            };
+
+            ctx.database
+                .set_privacy_choice(&user_id, option, allowed)
+                .await?;
+            info!(
+                "/privacy set | User: {user_id} | {} = {value}",
+                option.key()
+            );
+
+            content.push_str(&format!(
+                "Updated **{}**: {}\n",
+                option.label(),
+                match allowed {
+                    Some(true) => "allowed",
+                    Some(false) => "opted out",
+                    None => "back to the default",
+                }
+            ));
+            if option == PrivacyOption::DmHistory && allowed == Some(false) {
+                content.push_str("Use `/forget` in our DM to delete what's already stored.\n");
+            }
+            content.push('\n');
+        }
+
+        let choices = ctx.database.get_privacy_choices(&user_id).await?;
+        let settings = ctx
+            .database
+            .get_privacy_settings(&user_id, guild_id.as_deref())
+            .await?;
+        content.push_str(&Self::format_settings(
+            settings,
+            &choices,
+            guild_id.is_some(),
+        ));
+
+        responder
+            .create_interaction_response(&serenity_ctx.http, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|m| m.content(content).ephemeral(true))
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// One line per option with its effective value and where it comes from
+    fn format_settings(
+        settings: PrivacySettings,
+        choices: &[(PrivacyOption, bool)],
+        in_guild: bool,
+    ) -> String {
+        let mut output = "🔒 **Your privacy settings**\n".to_string();
+        for option in PrivacyOption::ALL {
+            let allowed = settings.allows(option);
+            let source = if choices.iter().any(|(o, _)| *o == option) {
+                "your choice"
+            } else if in_guild && option.guild_setting().is_some() {
+                "server default"
+            } else {
+                "default"
+            };
+            output.push_str(&format!(
+                "{} **{}**: {} ({source})\n",
+                if allowed { "✅" } else { "🚫" },
+                option.label(),
+                if allowed { "allowed" } else { "opted out" },
+            ));
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_privacy_handler_commands() {
+        let handler = PrivacyHandler;
+        assert_eq!(handler.command_names(), &["privacy"]);
+    }
+
+    #[test]
+    fn test_format_settings_sources() {
+        let settings = PrivacySettings {
+            dm_history: false,
+            analytics: true,
+            conflict_analysis: false,
+        };
+        let output =
+            PrivacyHandler::format_settings(settings, &[(PrivacyOption::DmHistory, false)], true);
+        assert!(output.contains("🚫 **DM history storage**: opted out (your choice)"));
+        assert!(output.contains("✅ **Analytics tracking**: allowed (server default)"));
+        assert!(output.contains("🚫 **Conflict analysis**: opted out (server default)"));
+
+        let output = PrivacyHandler::format_settings(PrivacySettings::default(), &[], false);
+        assert!(output.contains("✅ **Conflict analysis**: allowed (default)"));
+    }
+}
@@ -0,0 +1,305 @@
+//! Profile command handler
+//!
+//! Handles: profile
+//!
+//! Shows a member's profile card: preferred and most used persona, message
+//! counts, top commands, transcription minutes, debate and trivia records,
+//! badges and when they joined. Members who opted out of analytics only show
+//! the basics.
+//!
+//! - **Version**: 1.1.1
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.1.1: Top commands only count commands used in the server the card is shown in
+//! - 1.1.0: Cards list the member's achievement badges
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use serenity::builder::CreateEmbed;
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::model::id::UserId;
+use serenity::model::user::User;
+use serenity::prelude::Context;
+use std::sync::Arc;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::get_user_option;
+use crate::database::UserProfile;
+use crate::features::achievements::get_achievement;
+use crate::features::personas::PersonaManager;
+
+/// Feature id for toggles
+pub const PROFILE_FEATURE: &str = "profiles";
+
+/// Commands listed on the card
+const TOP_COMMANDS: usize = 3;
+
+/// Embed colour when the preferred persona is unknown
+const DEFAULT_COLOR: u32 = 0x5865F2;
+
+/// Handler for /profile
+pub struct ProfileHandler;
+
+#[async_trait]
+impl SlashCommandHandler for ProfileHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["profile"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        self.handle_profile(&ctx, serenity_ctx, command).await
+    }
+}
+
+impl ProfileHandler {
+    /// Handle /profile [user]
+    async fn handle_profile(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let Some(guild_id) = command.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        if !ctx
+            .database
+            .is_feature_enabled(PROFILE_FEATURE, None, Some(&guild_id))
+            .await?
+        {
+            responder
+                .create_interaction_response(&serenity_ctx.http, |r| {
+                    r.kind(InteractionResponseType::ChannelMessageWithSource)
+                        .interaction_response_data(|m| {
+                            m.content("❌ Profile cards are disabled on this server.")
+                                .ephemeral(true)
+                        })
+                })
+                .await?;
+            return Ok(());
+        }
+
+        let target_id = get_user_option(&command.data.options, "user")
+            .map(UserId)
+            .unwrap_or(command.user.id);
+        let user: User = if target_id == command.user.id {
+            command.user.clone()
+        } else {
+            match command.data.resolved.users.get(&target_id) {
+                Some(user) => user.clone(),
+                None => target_id.to_user(&serenity_ctx.http).await?,
+            }
+        };
+        let joined_at = if target_id == command.user.id {
+            command.member.as_ref().and_then(|m| m.joined_at)
+        } else {
+            command
+                .data
+                .resolved
+                .members
+                .get(&target_id)
+                .and_then(|m| m.joined_at)
+        };
+        let user_id = target_id.to_string();
+        info!(
+            "/profile | User: {} | Guild: {guild_id} | Target: {user_id}",
+            command.user.id
+        );
+
+        let preferred = ctx
+            .database
+            .get_user_persona_with_guild(&user_id, Some(&guild_id))
+            .await?;
+        let privacy = ctx
+            .database
+            .get_privacy_settings(&user_id, Some(&guild_id))
+            .await?;
+        let (profile, badges) = if privacy.analytics {
+            (
+                Some(
+                    ctx.database
+                        .get_user_profile(&user_id, &guild_id, TOP_COMMANDS)
+                        .await?,
+                ),
+                ctx.database
+                    .get_user_achievements(&guild_id, &user_id)
+                    .await?,
+            )
+        } else {
+            (None, Vec::new())
+        };
+
+        let mut embed =
+            Self::profile_embed(&ctx.persona_manager, &preferred, profile.as_ref(), &badges);
+        embed.author(|a| a.name(user.tag()).icon_url(user.face()));
+        if let Some(joined_at) = joined_at {
+            embed.field(
+                "Joined",
+                format!("<t:{}:D>", joined_at.unix_timestamp()),
+                true,
+            );
+        }
+        embed.field(
+            "Account created",
+            format!("<t:{}:D>", user.created_at().unix_timestamp()),
+            true,
+        );
+
+        responder
+            .create_interaction_response(&serenity_ctx.http, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|m| m.add_embed(embed))
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// The card's persona and stats fields; `profile` is `None` when analytics are opted out
+    fn profile_embed(
+        persona_manager: &PersonaManager,
+        preferred: &str,
+        profile: Option<&UserProfile>,
+        badges: &[String],
+    ) -> CreateEmbed {
+        let persona_name = |id: &str| {
+            persona_manager
+                .get_persona(id)
+                .map(|p| p.name.clone())
+                .unwrap_or_else(|| id.to_string())
+        };
+        let mut embed = CreateEmbed::default();
+        embed.title("🪪 Profile").color(
+            persona_manager
+                .get_persona(preferred)
+                .map(|p| p.color)
+                .unwrap_or(DEFAULT_COLOR),
+        );
+
+        let Some(profile) = profile else {
+            embed
+                .field("Persona", persona_name(preferred), true)
+                .footer(|f| f.text("This member has opted out of analytics"));
+            return embed;
+        };
+
+        let persona = match &profile.favorite_persona {
+            Some(favorite) if favorite != preferred => format!(
+                "{} (talks to {} most)",
+                persona_name(preferred),
+                persona_name(favorite)
+            ),
+            _ => persona_name(preferred),
+        };
+        embed
+            .field("Persona", persona, true)
+            .field(
+                "Messages",
+                format!(
+                    "{} sent · {} bot replies",
+                    profile.message_count, profile.bot_replies
+                ),
+                true,
+            )
+            .field(
+                "Transcribed",
+                format_minutes(profile.transcription_seconds),
+                true,
+            )
+            .field("Top commands", format_commands(&profile.top_commands), true)
+            .field("Debates", format_debates(profile), true)
+            .field(
+                "Trivia",
+                match &profile.trivia {
+                    Some(t) => format!(
+                        "{} pts · {} correct\n{} won of {} games",
+                        t.points, t.correct_answers, t.games_won, t.games_played
+                    ),
+                    None => "No games yet".to_string(),
+                },
+                true,
+            )
+            .field("Badges", format_badges(badges), false);
+        embed
+    }
+}
+
+fn format_minutes(seconds: f64) -> String {
+    format!("{:.1} min", seconds / 60.0)
+}
+
+fn format_commands(commands: &[(String, i64)]) -> String {
+    if commands.is_empty() {
+        return "None yet".to_string();
+    }
+    commands
+        .iter()
+        .map(|(command, count)| format!("`/{command}` ×{count}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Badge emoji and names by stored key; keys of retired badges are skipped
+fn format_badges(keys: &[String]) -> String {
+    let badges: Vec<String> = keys
+        .iter()
+        .filter_map(|key| get_achievement(key))
+        .map(|a| format!("{} {}", a.emoji, a.name))
+        .collect();
+    if badges.is_empty() {
+        "None yet".to_string()
+    } else {
+        badges.join(" · ")
+    }
+}
+
+fn format_debates(profile: &UserProfile) -> String {
+    format!(
+        "{} started\n{} wins from {} votes",
+        profile.debates_started, profile.debate_wins, profile.debate_votes
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_handler_commands() {
+        let handler = ProfileHandler;
+        assert_eq!(handler.command_names(), &["profile"]);
+    }
+
+    #[test]
+    fn test_format_helpers() {
+        assert_eq!(format_minutes(90.0), "1.5 min");
+        assert_eq!(format_commands(&[]), "None yet");
+        assert_eq!(
+            format_commands(&[("ask".to_string(), 12), ("trivia".to_string(), 3)]),
+            "`/ask` ×12\n`/trivia` ×3"
+        );
+        let profile = UserProfile {
+            debates_started: 2,
+            debate_votes: 5,
+            debate_wins: 3,
+            ..UserProfile::default()
+        };
+        assert_eq!(format_debates(&profile), "2 started\n3 wins from 5 votes");
+        assert_eq!(format_badges(&[]), "None yet");
+        assert_eq!(
+            format_badges(&["trivia_champion".to_string(), "retired".to_string()]),
+            "🧠 Trivia Champion"
+        );
+    }
+}
@@ -0,0 +1,442 @@
+//! Prompts command handler
+//!
+//! Handles: prompts
+//!
+//! Anyone can browse, use and export the guild's prompt library; saving,
+//! deleting and importing need Manage Server. Using a prompt fills its
+//! variables and hands it to the /ask or /imagine pipeline.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use serenity::model::application::interaction::application_command::{
+    ApplicationCommandInteraction, CommandDataOption,
+};
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::model::channel::AttachmentType;
+use serenity::prelude::Context;
+use std::borrow::Cow;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::handlers::ask::AskHandler;
+use crate::commands::handlers::imagine::ImagineHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::{get_attachment_option, get_bool_option, get_string_option};
+use crate::database::GuildPrompt;
+use crate::features::aliases::{expand, validate_name, validate_template};
+use crate::features::image_gen::generator::{ImageSize, ImageStyle};
+use crate::features::prompt_library::{
+    export_json, list_line, parse_import, validate_category, validate_description, PromptTarget,
+    MAX_IMPORT_BYTES, MAX_PROMPTS_PER_GUILD, PROMPTS_FEATURE,
+};
+
+/// Rejected import entries listed in the reply
+const MAX_REJECTED_SHOWN: usize = 10;
+
+/// Handler for /prompts
+pub struct PromptsHandler;
+
+#[async_trait]
+impl SlashCommandHandler for PromptsHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["prompts"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let Some(guild_id) = command.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        if !ctx
+            .database
+            .is_feature_enabled(PROMPTS_FEATURE, None, Some(&guild_id))
+            .await?
+        {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "❌ The prompt library is disabled on this server.",
+                )
+                .await;
+        }
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+        let options = &subcommand.options;
+
+        let manages = matches!(subcommand.name.as_str(), "save" | "delete" | "import");
+        let can_manage = command
+            .member
+            .as_ref()
+            .and_then(|m| m.permissions)
+            .is_some_and(|p| p.manage_guild());
+        if manages && !can_manage {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "❌ You need the Manage Server permission to change the prompt library.",
+                )
+                .await;
+        }
+
+        let content = match subcommand.name.as_str() {
+            "use" => {
+                return Self::handle_use(&ctx, serenity_ctx, command, &guild_id, options).await
+            }
+            "export" => {
+                return Self::handle_export(&ctx, serenity_ctx, &responder, &guild_id, options)
+                    .await
+            }
+            "save" => Self::handle_save(&ctx, command, &guild_id, options).await?,
+            "list" => Self::handle_list(&ctx, &guild_id, options).await?,
+            "delete" => {
+                let name = get_string_option(options, "name")
+                    .unwrap_or_default()
+                    .trim()
+                    .to_lowercase();
+                if ctx.database.delete_guild_prompt(&guild_id, &name).await? {
+                    info!(
+                        "/prompts delete | User: {} | Guild: {guild_id} | {name}",
+                        command.user.id
+                    );
+                    format!("🗑️ Removed `{name}` from the library.")
+                } else {
+                    format!("❌ There's no prompt `{name}` in the library.")
+                }
+            }
+            _ => Self::handle_import(&ctx, command, &guild_id, options).await?,
+        };
+        responder.reply_ephemeral(serenity_ctx, &content).await
+    }
+}
+
+impl PromptsHandler {
+    /// Handle /prompts save, returning the reply
+    async fn handle_save(
+        ctx: &CommandContext,
+        command: &ApplicationCommandInteraction,
+        guild_id: &str,
+        options: &[CommandDataOption],
+    ) -> Result<String> {
+        let validated = validate_name(&get_string_option(options, "name").unwrap_or_default())
+            .and_then(|name| {
+                Ok(GuildPrompt {
+                    guild_id: guild_id.to_string(),
+                    name,
+                    category: validate_category(get_string_option(options, "category").as_deref())?,
+                    description: validate_description(
+                        get_string_option(options, "description").as_deref(),
+                    )?,
+                    template: validate_template(
+                        &get_string_option(options, "prompt").unwrap_or_default(),
+                    )?,
+                    created_by: command.user.id.to_string(),
+                    uses: 0,
+                })
+            });
+        let prompt = match validated {
+            Ok(prompt) => prompt,
+            Err(e) => return Ok(format!("❌ {e}")),
+        };
+
+        let db = &ctx.database;
+        let replacing = db.get_guild_prompt(guild_id, &prompt.name).await?.is_some();
+        if !replacing && db.count_guild_prompts(guild_id).await? >= MAX_PROMPTS_PER_GUILD {
+            return Ok(format!(
+                "❌ The library already holds {MAX_PROMPTS_PER_GUILD} prompts. Delete one first."
+            ));
+        }
+        db.upsert_guild_prompt(&prompt).await?;
+        info!(
+            "/prompts save | User: {} | Guild: {guild_id} | {} in {}",
+            command.user.id, prompt.name, prompt.category
+        );
+        Ok(format!(
+            "✅ {} `{}` in **{}**:\n{}\nRun it with `/prompts use name:{}`.",
+            if replacing { "Updated" } else { "Saved" },
+            prompt.name,
+            prompt.category,
+            list_line(&prompt),
+            prompt.name
+        ))
+    }
+
+    /// Handle /prompts list, returning the reply
+    async fn handle_list(
+        ctx: &CommandContext,
+        guild_id: &str,
+        options: &[CommandDataOption],
+    ) -> Result<String> {
+        let category = match get_string_option(options, "category") {
+            Some(category) => match validate_category(Some(&category)) {
+                Ok(category) => Some(category),
+                Err(e) => return Ok(format!("❌ {e}")),
+            },
+            None => None,
+        };
+        let prompts = ctx
+            .database
+            .get_guild_prompts(guild_id, category.as_deref())
+            .await?;
+        if prompts.is_empty() {
+            return Ok(match category {
+                Some(category) => format!("No prompts in **{category}** yet."),
+                None => {
+                    "The library is empty. Admins add prompts with `/prompts save`.".to_string()
+                }
+            });
+        }
+
+        let mut content = "📚 **Prompt library**".to_string();
+        let mut current_category = None;
+        for (shown, prompt) in prompts.iter().enumerate() {
+            let mut entry = String::new();
+            if current_category != Some(&prompt.category) {
+                entry.push_str(&format!("\n**{}**", prompt.category));
+            }
+            entry.push('\n');
+            entry.push_str(&list_line(prompt));
+            // Leave room for the overflow note within Discord's 2000 characters
+            if content.chars().count() + entry.chars().count() > 1950 {
+                content.push_str(&format!(
+                    "\n…and {} more; narrow it down with `category`",
+                    prompts.len() - shown
+                ));
+                break;
+            }
+            content.push_str(&entry);
+            current_category = Some(&prompt.category);
+        }
+        Ok(content)
+    }
+
+    /// Handle /prompts use: fill the prompt's variables and run it
+    async fn handle_use(
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        guild_id: &str,
+        options: &[CommandDataOption],
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let name = get_string_option(options, "name")
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase();
+        let Some(library_prompt) = ctx.database.get_guild_prompt(guild_id, &name).await? else {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    &format!("❌ There's no prompt `{name}`. Browse them with `/prompts list`."),
+                )
+                .await;
+        };
+        let prompt = match expand(
+            &library_prompt.template,
+            &get_string_option(options, "variables").unwrap_or_default(),
+        ) {
+            Ok(prompt) => prompt,
+            Err(e) => {
+                return responder
+                    .reply_ephemeral(serenity_ctx, &format!("❌ {e}"))
+                    .await
+            }
+        };
+        ctx.database
+            .record_guild_prompt_use(guild_id, &library_prompt.name)
+            .await?;
+
+        let target = PromptTarget::from_setting(get_string_option(options, "with").as_deref());
+        info!(
+            "/prompts use | User: {} | Guild: {guild_id} | {name} | {target:?}",
+            command.user.id
+        );
+        match target {
+            PromptTarget::Ask => {
+                let persona_id = match get_string_option(options, "persona") {
+                    Some(persona_id) => persona_id,
+                    None => ctx.active_persona(serenity_ctx, command).await?,
+                };
+                AskHandler::ask(
+                    ctx,
+                    serenity_ctx,
+                    command,
+                    Uuid::new_v4(),
+                    &persona_id,
+                    &prompt,
+                    false,
+                    None,
+                    None,
+                    None,
+                )
+                .await
+            }
+            PromptTarget::Imagine => {
+                ImagineHandler::imagine(
+                    ctx,
+                    serenity_ctx,
+                    command,
+                    &prompt,
+                    ImageSize::Square,
+                    ImageStyle::Vivid,
+                )
+                .await
+            }
+        }
+    }
+
+    /// Handle /prompts export: reply with the library as a JSON file
+    async fn handle_export(
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        responder: &InteractionResponder,
+        guild_id: &str,
+        options: &[CommandDataOption],
+    ) -> Result<()> {
+        let category = get_string_option(options, "category")
+            .and_then(|category| validate_category(Some(&category)).ok());
+        let prompts = ctx
+            .database
+            .get_guild_prompts(guild_id, category.as_deref())
+            .await?;
+        if prompts.is_empty() {
+            return responder
+                .reply_ephemeral(serenity_ctx, "There's nothing to export yet.")
+                .await;
+        }
+        let json = export_json(&prompts);
+        let filename = match &category {
+            Some(category) => format!("prompts-{category}.json"),
+            None => "prompts.json".to_string(),
+        };
+        responder
+            .create_interaction_response(&serenity_ctx.http, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|m| {
+                        m.content(format!(
+                            "📦 {} prompt{}. Load them on another server with `/prompts import`.",
+                            prompts.len(),
+                            if prompts.len() == 1 { "" } else { "s" }
+                        ))
+                        .add_file(AttachmentType::Bytes {
+                            data: Cow::Owned(json.into_bytes()),
+                            filename,
+                        })
+                        .ephemeral(true)
+                    })
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Handle /prompts import, returning the reply
+    async fn handle_import(
+        ctx: &CommandContext,
+        command: &ApplicationCommandInteraction,
+        guild_id: &str,
+        options: &[CommandDataOption],
+    ) -> Result<String> {
+        let Some(file) = get_attachment_option(options, "file") else {
+            return Ok("❌ Attach a file from `/prompts export`.".to_string());
+        };
+        if file.size > MAX_IMPORT_BYTES {
+            return Ok("❌ That file is too large for a prompt library export.".to_string());
+        }
+        let parsed = match parse_import(&file.download().await?) {
+            Ok(parsed) => parsed,
+            Err(e) => return Ok(format!("❌ {e}")),
+        };
+        let overwrite = get_bool_option(options, "overwrite").unwrap_or(false);
+
+        let db = &ctx.database;
+        let mut count = db.count_guild_prompts(guild_id).await?;
+        let (mut added, mut replaced, mut skipped) = (0, 0, 0);
+        let mut rejected = parsed.rejected;
+        for imported in parsed.prompts {
+            let exists = db
+                .get_guild_prompt(guild_id, &imported.name)
+                .await?
+                .is_some();
+            if exists && !overwrite {
+                skipped += 1;
+                continue;
+            }
+            if !exists && count >= MAX_PROMPTS_PER_GUILD {
+                rejected.push(format!("`{}`: the library is full", imported.name));
+                continue;
+            }
+            db.upsert_guild_prompt(&GuildPrompt {
+                guild_id: guild_id.to_string(),
+                name: imported.name,
+                category: imported.category.unwrap_or_default(),
+                description: imported.description,
+                template: imported.template,
+                created_by: command.user.id.to_string(),
+                uses: 0,
+            })
+            .await?;
+            if exists {
+                replaced += 1;
+            } else {
+                added += 1;
+                count += 1;
+            }
+        }
+        info!(
+            "/prompts import | User: {} | Guild: {guild_id} | +{added} ~{replaced} ={skipped} x{}",
+            command.user.id,
+            rejected.len()
+        );
+
+        let mut content = format!("📥 Imported **{added}** new prompt(s)");
+        if replaced > 0 {
+            content.push_str(&format!(", replaced **{replaced}**"));
+        }
+        if skipped > 0 {
+            content.push_str(&format!(
+                ", skipped **{skipped}** that already exist (use `overwrite` to replace them)"
+            ));
+        }
+        content.push('.');
+        if !rejected.is_empty() {
+            content.push_str(&format!("\n⚠️ {} couldn't be imported:", rejected.len()));
+            for reason in rejected.iter().take(MAX_REJECTED_SHOWN) {
+                content.push_str(&format!("\n• {reason}"));
+            }
+            if rejected.len() > MAX_REJECTED_SHOWN {
+                content.push_str(&format!(
+                    "\n…and {} more",
+                    rejected.len() - MAX_REJECTED_SHOWN
+                ));
+            }
+        }
+        Ok(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompts_handler_commands() {
+        let handler = PromptsHandler;
+        assert_eq!(handler.command_names(), &["prompts"]);
+    }
+}
@@ -0,0 +1,291 @@
+//! Question of the day command handler
+//!
+//! Handles: qotd
+//!
+//! Members with Manage Server choose the channel, local hour and answer threads
+//! for the daily question, stop it, and manage the queue of questions posted
+//! before the persona writes its own. Posting and digests are done by the
+//! question of the day scheduler (see `features::qotd`).
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use serenity::model::application::interaction::application_command::{
+    ApplicationCommandInteraction, CommandDataOption,
+};
+use serenity::prelude::Context;
+use std::sync::Arc;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::{
+    get_bool_option, get_channel_option, get_integer_option, get_string_option,
+};
+use crate::core::{format_utc_offset, parse_utc_offset};
+use crate::database::{QotdConfig, QotdQuestion};
+use crate::features::channel_topics::first_run_at;
+use crate::features::qotd::{validate_question, DEFAULT_HOUR, MAX_QUEUE_LEN, QOTD_FEATURE};
+
+/// Handler for /qotd
+pub struct QotdHandler;
+
+#[async_trait]
+impl SlashCommandHandler for QotdHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["qotd"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        self.handle_qotd(&ctx, serenity_ctx, command).await
+    }
+}
+
+impl QotdHandler {
+    /// Handle /qotd setup, stop, add, list and skip
+    async fn handle_qotd(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let user_id = command.user.id.to_string();
+        let Some(guild_id) = command.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        let can_manage = command
+            .member
+            .as_ref()
+            .and_then(|m| m.permissions)
+            .is_some_and(|p| p.manage_guild());
+        if !can_manage {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "❌ You need the Manage Server permission to manage the question of the day.",
+                )
+                .await;
+        }
+        if !ctx
+            .database
+            .is_feature_enabled(QOTD_FEATURE, None, Some(&guild_id))
+            .await?
+        {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "❌ The question of the day is disabled on this server.",
+                )
+                .await;
+        }
+
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+
+        let content = match subcommand.name.as_str() {
+            "setup" => {
+                match Self::parse_config(
+                    &subcommand.options,
+                    &guild_id,
+                    &user_id,
+                    chrono::Utc::now(),
+                ) {
+                    Ok(config) => {
+                        ctx.database.set_qotd_config(&config).await?;
+                        info!(
+                            "/qotd setup | User: {user_id} | Guild: {guild_id} | Channel: {} | Hour: {}",
+                            config.channel_id, config.hour
+                        );
+                        format!("✅ {}", Self::describe(&config))
+                    }
+                    Err(message) => format!("❌ {message}"),
+                }
+            }
+            "stop" => {
+                if ctx.database.delete_qotd_config(&guild_id).await? {
+                    info!("/qotd stop | User: {user_id} | Guild: {guild_id}");
+                    "✅ Stopped the question of the day. Queued questions are kept.".to_string()
+                } else {
+                    "The question of the day isn't set up here.".to_string()
+                }
+            }
+            "add" => {
+                let queue_len = ctx.database.get_qotd_queue(&guild_id).await?.len();
+                match get_string_option(&subcommand.options, "question")
+                    .as_deref()
+                    .map(validate_question)
+                    .unwrap_or(Err("Missing question"))
+                {
+                    Ok(_) if queue_len >= MAX_QUEUE_LEN => {
+                        "❌ The queue is full (50 questions). Skip some before adding more."
+                            .to_string()
+                    }
+                    Ok(question) => {
+                        let id = ctx
+                            .database
+                            .add_qotd_question(&guild_id, &question, &user_id)
+                            .await?;
+                        info!("/qotd add | User: {user_id} | Guild: {guild_id} | Id: {id}");
+                        format!("✅ Queued question #{id} ({} in the queue).", queue_len + 1)
+                    }
+                    Err(message) => format!("❌ {message}"),
+                }
+            }
+            "skip" => {
+                let id = get_integer_option(&subcommand.options, "id");
+                match ctx.database.remove_qotd_question(&guild_id, id).await? {
+                    Some(skipped) => {
+                        info!(
+                            "/qotd skip | User: {user_id} | Guild: {guild_id} | Id: {}",
+                            skipped.id
+                        );
+                        format!("✅ Dropped #{}: {}", skipped.id, skipped.question)
+                    }
+                    None if id.is_some() => "❌ No queued question has that number.".to_string(),
+                    None => "The queue is empty.".to_string(),
+                }
+            }
+            _ => {
+                let config = ctx.database.get_qotd_config(&guild_id).await?;
+                let queue = ctx.database.get_qotd_queue(&guild_id).await?;
+                Self::format_list(config.as_ref(), &queue)
+            }
+        };
+
+        responder.reply_ephemeral(serenity_ctx, &content).await
+    }
+
+    /// Validate `/qotd setup` options into a schedule starting at the next local `hour`
+    fn parse_config(
+        options: &[CommandDataOption],
+        guild_id: &str,
+        user_id: &str,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<QotdConfig, &'static str> {
+        let channel_id = get_channel_option(options, "channel").ok_or("Pick a channel")?;
+        let hour = get_integer_option(options, "hour").unwrap_or(DEFAULT_HOUR);
+        if !(0..=23).contains(&hour) {
+            return Err("The hour must be between 0 and 23");
+        }
+        let utc_offset_minutes = get_string_option(options, "timezone")
+            .as_deref()
+            .map(parse_utc_offset)
+            .transpose()?
+            .unwrap_or(0);
+
+        Ok(QotdConfig {
+            guild_id: guild_id.to_string(),
+            channel_id: channel_id.to_string(),
+            hour,
+            utc_offset_minutes,
+            threads: get_bool_option(options, "threads").unwrap_or(false),
+            digest_channel_id: get_channel_option(options, "digest_channel")
+                .map(|id| id.to_string()),
+            next_run_at: first_run_at(now, Some(hour as u32), utc_offset_minutes),
+            created_by: user_id.to_string(),
+        })
+    }
+
+    /// One-line summary of a schedule
+    fn describe(config: &QotdConfig) -> String {
+        let mut summary = format!(
+            "Questions of the day go to <#{}> at {:02}:00 {}",
+            config.channel_id,
+            config.hour,
+            format_utc_offset(config.utc_offset_minutes)
+        );
+        if config.threads {
+            summary.push_str(" with a thread for answers");
+        }
+        if let Some(digest) = &config.digest_channel_id {
+            summary.push_str(&format!(", best answers in <#{digest}>"));
+        }
+        format!("{summary}. Next one <t:{}:R>.", config.next_run_at)
+    }
+
+    /// The schedule and the queue, next question first
+    fn format_list(config: Option<&QotdConfig>, queue: &[QotdQuestion]) -> String {
+        let mut lines = vec![match config {
+            Some(config) => Self::describe(config),
+            None => {
+                "The question of the day isn't set up yet. Start it with `/qotd setup`.".to_string()
+            }
+        }];
+        if queue.is_empty() {
+            lines.push("The queue is empty, so the persona writes each question.".to_string());
+        } else {
+            lines.push(format!("**Queue ({}):**", queue.len()));
+            lines.extend(
+                queue
+                    .iter()
+                    .map(|q| format!("`#{}` {} (<@{}>)", q.id, q.question, q.added_by)),
+            );
+        }
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(threads: bool, digest: Option<&str>) -> QotdConfig {
+        QotdConfig {
+            guild_id: "1".to_string(),
+            channel_id: "42".to_string(),
+            hour: 9,
+            utc_offset_minutes: 120,
+            threads,
+            digest_channel_id: digest.map(str::to_string),
+            next_run_at: 1_700_000_000,
+            created_by: "2".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_qotd_handler_commands() {
+        let handler = QotdHandler;
+        assert_eq!(handler.command_names(), &["qotd"]);
+    }
+
+    #[test]
+    fn test_describe_config() {
+        assert_eq!(
+            QotdHandler::describe(&config(false, None)),
+            "Questions of the day go to <#42> at 09:00 UTC+02:00. Next one <t:1700000000:R>."
+        );
+        assert_eq!(
+            QotdHandler::describe(&config(true, Some("7"))),
+            "Questions of the day go to <#42> at 09:00 UTC+02:00 with a thread for answers, best answers in <#7>. Next one <t:1700000000:R>."
+        );
+    }
+
+    #[test]
+    fn test_format_list() {
+        let queue = vec![QotdQuestion {
+            id: 3,
+            question: "Tabs or spaces?".to_string(),
+            added_by: "5".to_string(),
+        }];
+        let list = QotdHandler::format_list(None, &queue);
+        assert!(list.starts_with("The question of the day isn't set up yet."));
+        assert!(list.ends_with("**Queue (1):**\n`#3` Tabs or spaces? (<@5>)"));
+        assert!(QotdHandler::format_list(Some(&config(false, None)), &[])
+            .ends_with("the persona writes each question."));
+    }
+}
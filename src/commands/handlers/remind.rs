@@ -1,11 +1,15 @@
 //! Reminder command handlers
 //!
-//! Handles: remind, reminders, forget
+//! Handles: remind, reminders, forget, undo
 //!
-//! - **Version**: 1.0.0
+//! - **Version**: 1.3.1
 //! - **Since**: 3.38.0
 //!
 //! ## Changelog
+//! - 1.3.1: Duration parsing and formatting are public for the Telegram front end
+//! - 1.3.0: /undo removes the last exchange from conversation history
+//! - 1.2.0: Paginated embed output via PaginatedEmbed
+//! - 1.1.0: Initial responses go through InteractionResponder (auto-defer safe)
 //! - 1.0.0: Extracted from command_handler.rs
 
 use anyhow::Result;
@@ -18,15 +22,23 @@ use std::sync::Arc;
 
 use crate::commands::context::CommandContext;
 use crate::commands::handler::SlashCommandHandler;
+use crate::commands::pagination::PaginatedEmbed;
+use crate::commands::responder::InteractionResponder;
 use crate::commands::slash::{get_integer_option, get_string_option};
 
+/// Reminders shown per page of /reminders
+const REMINDERS_PER_PAGE: usize = 5;
+
+/// Characters of each removed message quoted back by /undo
+const UNDO_PREVIEW_CHARS: usize = 120;
+
 /// Handler for reminder-related commands
 pub struct RemindHandler;
 
 #[async_trait]
 impl SlashCommandHandler for RemindHandler {
     fn command_names(&self) -> &'static [&'static str] {
-        &["remind", "reminders", "forget"]
+        &["remind", "reminders", "forget", "undo"]
     }
 
     async fn handle(
@@ -39,6 +51,7 @@ impl SlashCommandHandler for RemindHandler {
             "remind" => self.handle_remind(&ctx, serenity_ctx, command).await,
             "reminders" => self.handle_reminders(&ctx, serenity_ctx, command).await,
             "forget" => self.handle_forget(&ctx, serenity_ctx, command).await,
+            "undo" => self.handle_undo(&ctx, serenity_ctx, command).await,
             _ => Ok(()),
         }
     }
@@ -52,6 +65,7 @@ impl RemindHandler {
         serenity_ctx: &Context,
         command: &ApplicationCommandInteraction,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let user_id = command.user.id.to_string();
         let channel_id = command.channel_id.to_string();
 
@@ -67,7 +81,7 @@ impl RemindHandler {
         };
 
         if !reminders_enabled {
-            command
+            responder
                 .create_interaction_response(&serenity_ctx.http, |response| {
                     response
                         .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -88,7 +102,7 @@ impl RemindHandler {
         let duration_seconds = match Self::parse_duration(&time_str) {
             Some(secs) => secs,
             None => {
-                command
+                responder
                     .create_interaction_response(&serenity_ctx.http, |response| {
                         response
                             .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -122,10 +136,12 @@ impl RemindHandler {
         );
 
         // Log usage
-        ctx.database.log_usage(&user_id, "remind", None).await?;
+        ctx.database
+            .log_usage(&user_id, guild_id_opt, "remind", None)
+            .await?;
 
         let duration_display = Self::format_duration(duration_seconds);
-        command
+        responder
             .create_interaction_response(&serenity_ctx.http, |response| {
                 response
                     .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -147,6 +163,7 @@ impl RemindHandler {
         serenity_ctx: &Context,
         command: &ApplicationCommandInteraction,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let user_id = command.user.id.to_string();
 
         // Check if reminders feature is enabled for this guild
@@ -161,7 +178,7 @@ impl RemindHandler {
         };
 
         if !reminders_enabled {
-            command
+            responder
                 .create_interaction_response(&serenity_ctx.http, |response| {
                     response
                         .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -196,6 +213,7 @@ impl RemindHandler {
         command: &ApplicationCommandInteraction,
         user_id: &str,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let reminder_id = get_integer_option(&command.data.options, "id");
 
         if let Some(id) = reminder_id {
@@ -203,7 +221,7 @@ impl RemindHandler {
 
             if deleted {
                 info!("Deleted reminder {id} for user {user_id}");
-                command
+                responder
                     .create_interaction_response(&serenity_ctx.http, |response| {
                         response
                             .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -213,7 +231,7 @@ impl RemindHandler {
                     })
                     .await?;
             } else {
-                command
+                responder
                     .create_interaction_response(&serenity_ctx.http, |response| {
                         response
                             .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -226,7 +244,7 @@ impl RemindHandler {
                     .await?;
             }
         } else {
-            command
+            responder
                 .create_interaction_response(&serenity_ctx.http, |response| {
                     response
                         .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -250,10 +268,11 @@ impl RemindHandler {
         command: &ApplicationCommandInteraction,
         user_id: &str,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let reminders = ctx.database.get_user_reminders(user_id).await?;
 
         if reminders.is_empty() {
-            command
+            responder
                 .create_interaction_response(&serenity_ctx.http, |response| {
                     response
                         .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -265,7 +284,7 @@ impl RemindHandler {
                 })
                 .await?;
         } else {
-            let mut reminder_list = String::from("📋 **Your Pending Reminders:**\n\n");
+            let mut entries = Vec::with_capacity(reminders.len());
 
             for (id, _channel_id, text, remind_at) in &reminders {
                 let remind_time =
@@ -290,18 +309,13 @@ impl RemindHandler {
                     remind_at.clone()
                 };
 
-                reminder_list
-                    .push_str(&format!("**#{id}** - {time_display} ({remind_at})\n> {text}\n\n"));
+                entries.push(format!("**#{id}** - {time_display} ({remind_at})\n> {text}\n"));
             }
 
-            reminder_list.push_str("*Use `/reminders cancel <id>` to cancel a reminder.*");
-
-            command
-                .create_interaction_response(&serenity_ctx.http, |response| {
-                    response
-                        .kind(InteractionResponseType::ChannelMessageWithSource)
-                        .interaction_response_data(|msg| msg.content(&reminder_list))
-                })
+            PaginatedEmbed::from_lines("📋 Your Pending Reminders", &entries, REMINDERS_PER_PAGE)
+                .with_footer("Use /reminders cancel <id> to cancel a reminder")
+                .with_owner(command.user.id.0)
+                .respond(&ctx.database, &serenity_ctx.http, &responder, false)
                 .await?;
         }
 
@@ -315,6 +329,7 @@ impl RemindHandler {
         serenity_ctx: &Context,
         command: &ApplicationCommandInteraction,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let user_id = command.user.id.to_string();
         let channel_id = command.channel_id.to_string();
 
@@ -331,7 +346,7 @@ impl RemindHandler {
             "Cleared conversation history for user {user_id} in channel {channel_id}"
         );
 
-        command
+        responder
             .create_interaction_response(&serenity_ctx.http, |response| {
                 response
                     .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -346,8 +361,66 @@ impl RemindHandler {
         Ok(())
     }
 
+    /// Handle /undo command - drop the last user+assistant exchange
+    async fn handle_undo(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let user_id = command.user.id.to_string();
+        let channel_id = command.channel_id.to_string();
+
+        debug!("Processing undo command for user: {user_id} in channel: {channel_id}");
+
+        let removed = ctx
+            .database
+            .undo_last_exchange(&user_id, &channel_id)
+            .await?;
+        let content = if removed.is_empty() {
+            "There's nothing to undo in this channel.".to_string()
+        } else {
+            info!(
+                "Removed {} messages from history for user {user_id} in channel {channel_id}",
+                removed.len()
+            );
+            Self::format_undo_summary(&removed)
+        };
+
+        responder
+            .create_interaction_response(&serenity_ctx.http, |response| {
+                response
+                    .kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|message| message.content(content).ephemeral(true))
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    /// Describe what /undo removed, one short preview per message
+    fn format_undo_summary(removed: &[(String, String)]) -> String {
+        let mut summary = String::from("↩️ Removed from our conversation history:\n");
+        for (role, content) in removed {
+            let who = if role == "user" { "You" } else { "Me" };
+            let preview: String = content.chars().take(UNDO_PREVIEW_CHARS).collect();
+            let ellipsis = if content.chars().count() > UNDO_PREVIEW_CHARS {
+                "..."
+            } else {
+                ""
+            };
+            summary.push_str(&format!(
+                "> **{who}:** {}{ellipsis}\n",
+                preview.replace('\n', " ")
+            ));
+        }
+        summary.push_str("\nI won't use this exchange as context anymore.");
+        summary
+    }
+
     /// Parse a time duration string like "30m", "2h", "1d", "1h30m" into seconds
-    fn parse_duration(time_str: &str) -> Option<i64> {
+    pub fn parse_duration(time_str: &str) -> Option<i64> {
         let time_str = time_str.trim().to_lowercase();
         let mut total_seconds: i64 = 0;
         let mut current_number = String::new();
@@ -379,7 +452,7 @@ impl RemindHandler {
     }
 
     /// Format a duration in seconds into a human-readable string
-    fn format_duration(seconds: i64) -> String {
+    pub fn format_duration(seconds: i64) -> String {
         if seconds < 60 {
             format!("{} second{}", seconds, if seconds == 1 { "" } else { "s" })
         } else if seconds < 3600 {
@@ -429,7 +502,19 @@ mod tests {
         assert!(names.contains(&"remind"));
         assert!(names.contains(&"reminders"));
         assert!(names.contains(&"forget"));
-        assert_eq!(names.len(), 3);
+        assert!(names.contains(&"undo"));
+        assert_eq!(names.len(), 4);
+    }
+
+    #[test]
+    fn test_format_undo_summary() {
+        let removed = vec![
+            ("user".to_string(), "bad\nprompt".to_string()),
+            ("assistant".to_string(), "x".repeat(200)),
+        ];
+        let summary = RemindHandler::format_undo_summary(&removed);
+        assert!(summary.contains("> **You:** bad prompt\n"));
+        assert!(summary.contains(&format!("> **Me:** {}...", "x".repeat(UNDO_PREVIEW_CHARS))));
     }
 
     #[test]
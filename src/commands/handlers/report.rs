@@ -0,0 +1,257 @@
+//! Report command handler
+//!
+//! Handles: report
+//!
+//! Admins schedule the weekly "state of the server" report with `/report
+//! schedule`, stop it with `/report off`, or post one on demand with `/report
+//! now`. Scheduled reports are sent by the report scheduler.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::{error, info};
+use serenity::model::application::interaction::application_command::{
+    ApplicationCommandInteraction, CommandDataOption,
+};
+use serenity::prelude::Context;
+use std::sync::Arc;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::{get_channel_option, get_integer_option, get_string_option};
+use crate::core::{format_utc_offset, parse_utc_offset};
+use crate::features::server_report::{
+    parse_weekday, weekday_name, ServerReporter, CHANNEL_SETTING, DAY_SETTING, DEFAULT_HOUR,
+    HOUR_SETTING, OFFSET_SETTING, REPORT_FEATURE,
+};
+
+/// Handler for /report
+pub struct ReportHandler;
+
+/// A validated `/report schedule`
+#[derive(Debug, PartialEq)]
+struct ReportSchedule {
+    channel_id: String,
+    day: String,
+    hour: u32,
+    utc_offset_minutes: i32,
+}
+
+#[async_trait]
+impl SlashCommandHandler for ReportHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["report"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        self.handle_report(&ctx, serenity_ctx, command).await
+    }
+}
+
+impl ReportHandler {
+    /// Handle /report schedule, off and now
+    async fn handle_report(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let user_id = command.user.id.to_string();
+        let Some(guild_id) = command.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        let can_manage = command
+            .member
+            .as_ref()
+            .and_then(|m| m.permissions)
+            .is_some_and(|p| p.manage_guild());
+        if !can_manage {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "❌ You need the Manage Server permission to use server reports.",
+                )
+                .await;
+        }
+        if !ctx
+            .database
+            .is_feature_enabled(REPORT_FEATURE, None, Some(&guild_id))
+            .await?
+        {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "❌ Server reports are disabled on this server.",
+                )
+                .await;
+        }
+
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+
+        let content = match subcommand.name.as_str() {
+            "schedule" => match Self::parse_schedule(&subcommand.options) {
+                Ok(schedule) => {
+                    let db = &ctx.database;
+                    db.set_guild_setting(&guild_id, CHANNEL_SETTING, &schedule.channel_id)
+                        .await?;
+                    db.set_guild_setting(&guild_id, DAY_SETTING, &schedule.day)
+                        .await?;
+                    db.set_guild_setting(&guild_id, HOUR_SETTING, &schedule.hour.to_string())
+                        .await?;
+                    db.set_guild_setting(
+                        &guild_id,
+                        OFFSET_SETTING,
+                        &schedule.utc_offset_minutes.to_string(),
+                    )
+                    .await?;
+                    info!(
+                        "/report schedule | User: {user_id} | Guild: {guild_id} | {} {:02}:00 {} in <#{}>",
+                        schedule.day,
+                        schedule.hour,
+                        format_utc_offset(schedule.utc_offset_minutes),
+                        schedule.channel_id
+                    );
+                    Self::format_schedule(&schedule)
+                }
+                Err(message) => format!("❌ {message}"),
+            },
+            "off" => {
+                if ctx
+                    .database
+                    .get_guild_setting(&guild_id, CHANNEL_SETTING)
+                    .await?
+                    .is_some()
+                {
+                    ctx.database
+                        .delete_guild_setting(&guild_id, CHANNEL_SETTING)
+                        .await?;
+                    info!("/report off | User: {user_id} | Guild: {guild_id}");
+                    "✅ Weekly server reports are off.".to_string()
+                } else {
+                    "No weekly report is scheduled here.".to_string()
+                }
+            }
+            _ => return Self::post_now(ctx, serenity_ctx, command, &responder, &guild_id).await,
+        };
+
+        responder.reply_ephemeral(serenity_ctx, &content).await
+    }
+
+    /// Build a report for the last week and post it as the command response
+    async fn post_now(
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        responder: &InteractionResponder,
+        guild_id: &str,
+    ) -> Result<()> {
+        responder.defer(&serenity_ctx.http).await?;
+        info!(
+            "/report now | User: {} | Guild: {guild_id}",
+            command.user.id
+        );
+
+        let reporter = ServerReporter::new(
+            ctx.database.clone(),
+            ctx.chat_client.clone(),
+            ctx.openai_model.clone(),
+            ctx.usage_tracker.clone(),
+        );
+        match reporter
+            .build(guild_id, &command.channel_id.to_string())
+            .await
+        {
+            Ok(embed) => {
+                command
+                    .edit_original_interaction_response(&serenity_ctx.http, |r| r.set_embed(embed))
+                    .await?;
+            }
+            Err(e) => {
+                error!("Failed to build server report for {guild_id}: {e}");
+                command
+                    .edit_original_interaction_response(&serenity_ctx.http, |r| {
+                        r.content("❌ Couldn't build the report right now. Try again later.")
+                    })
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_schedule(options: &[CommandDataOption]) -> Result<ReportSchedule, &'static str> {
+        let channel_id = get_channel_option(options, "channel").ok_or("Pick a channel")?;
+        let day = get_string_option(options, "day")
+            .as_deref()
+            .and_then(parse_weekday)
+            .ok_or("Pick a day of the week")?;
+        let hour = get_integer_option(options, "hour").unwrap_or(DEFAULT_HOUR.into());
+        if !(0..=23).contains(&hour) {
+            return Err("The hour must be between 0 and 23");
+        }
+        let utc_offset_minutes = get_string_option(options, "timezone")
+            .as_deref()
+            .map(parse_utc_offset)
+            .transpose()?
+            .unwrap_or(0);
+
+        Ok(ReportSchedule {
+            channel_id: channel_id.to_string(),
+            day: weekday_name(day)[..3].to_lowercase(),
+            hour: hour as u32,
+            utc_offset_minutes,
+        })
+    }
+
+    fn format_schedule(schedule: &ReportSchedule) -> String {
+        let day = parse_weekday(&schedule.day).map_or("", weekday_name);
+        format!(
+            "✅ The state of the server report will be posted in <#{}> every **{day}** at \
+             **{:02}:00** ({}).",
+            schedule.channel_id,
+            schedule.hour,
+            format_utc_offset(schedule.utc_offset_minutes)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_handler_commands() {
+        let handler = ReportHandler;
+        assert_eq!(handler.command_names(), &["report"]);
+    }
+
+    #[test]
+    fn test_format_schedule() {
+        let schedule = ReportSchedule {
+            channel_id: "42".to_string(),
+            day: "fri".to_string(),
+            hour: 17,
+            utc_offset_minutes: 120,
+        };
+        assert_eq!(
+            ReportHandler::format_schedule(&schedule),
+            "✅ The state of the server report will be posted in <#42> every **Friday** at \
+             **17:00** (UTC+02:00)."
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! Message search command handler
+//!
+//! Handles: search
+//!
+//! - **Version**: 1.2.1
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.2.1: Slow searches are deferred privately
+//! - 1.2.0: Paginated embed output via PaginatedEmbed
+//! - 1.1.0: Initial responses go through InteractionResponder (auto-defer safe)
+//! - 1.0.0: Initial implementation (FTS5 search over stored guild messages)
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use log::{info, warn};
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::model::channel::Channel;
+use serenity::model::guild::{Guild, Member};
+use serenity::model::id::ChannelId;
+use serenity::prelude::Context;
+use std::sync::Arc;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::pagination::PaginatedEmbed;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::{get_channel_option, get_integer_option, get_string_option};
+use crate::database::MessageSearchResult;
+
+/// Default number of results shown
+const DEFAULT_LIMIT: i64 = 10;
+
+/// Results shown per page
+const RESULTS_PER_PAGE: usize = 5;
+
+/// Maximum characters of the query echoed in the page title
+const MAX_TITLE_QUERY_LEN: usize = 100;
+
+/// Maximum characters of each snippet
+const MAX_SNIPPET_LEN: usize = 160;
+
+pub struct SearchHandler;
+
+#[async_trait]
+impl SlashCommandHandler for SearchHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["search"]
+    }
+
+    fn ephemeral(&self, _command: &ApplicationCommandInteraction) -> bool {
+        true
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        self.handle_search(&ctx, serenity_ctx, command).await
+    }
+}
+
+impl SearchHandler {
+    /// Handle /search command - full-text search scoped to the invoking guild
+    async fn handle_search(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let user_id = command.user.id.to_string();
+
+        let outcome = match command.guild_id {
+            None => Err("This command can only be used in a server.".to_string()),
+            Some(guild_id) => {
+                let query = get_string_option(&command.data.options, "query")
+                    .ok_or_else(|| anyhow::anyhow!("Missing query parameter"))?;
+                let channel_id =
+                    get_channel_option(&command.data.options, "channel").map(|id| id.to_string());
+                let limit = get_integer_option(&command.data.options, "limit")
+                    .unwrap_or(DEFAULT_LIMIT)
+                    .clamp(1, 25);
+
+                info!("/search | User: {user_id} | Guild: {guild_id} | Query: '{query}'");
+
+                // Over-fetch so results hidden by channel permissions don't shrink the page
+                match ctx
+                    .database
+                    .search_messages(
+                        &query,
+                        Some(&guild_id.to_string()),
+                        channel_id.as_deref(),
+                        limit * 2,
+                    )
+                    .await
+                {
+                    Ok(mut results) => {
+                        let guild = serenity_ctx.cache.guild(guild_id);
+                        if let (Some(guild), Some(member)) = (&guild, &command.member) {
+                            results.retain(|r| Self::can_view(guild, member, &r.channel_id));
+                        }
+                        results.truncate(limit as usize);
+                        Self::format_results(&query, &results)
+                            .map(|pages| pages.with_owner(command.user.id.0))
+                    }
+                    Err(e) => {
+                        warn!("/search failed: {e}");
+                        Err(
+                            "Search is unavailable right now. Check the bot logs for details."
+                                .to_string(),
+                        )
+                    }
+                }
+            }
+        };
+
+        match outcome {
+            Ok(pages) => {
+                pages
+                    .respond(&ctx.database, &serenity_ctx.http, &responder, true)
+                    .await?
+            }
+            Err(message) => {
+                responder
+                    .create_interaction_response(&serenity_ctx.http, |r| {
+                        r.kind(InteractionResponseType::ChannelMessageWithSource)
+                            .interaction_response_data(|m| m.content(message).ephemeral(true))
+                    })
+                    .await?
+            }
+        }
+
+        let guild_id = command.guild_id.map(|id| id.to_string());
+        ctx.database
+            .log_usage(&user_id, guild_id.as_deref(), "search", None)
+            .await?;
+        Ok(())
+    }
+
+    /// Whether the member can read the channel (threads inherit from their parent)
+    fn can_view(guild: &Guild, member: &Member, channel_id: &str) -> bool {
+        let Ok(id) = channel_id.parse::<u64>() else {
+            return false;
+        };
+        let id = ChannelId(id);
+
+        let channel = match guild.channels.get(&id) {
+            Some(Channel::Guild(channel)) => Some(channel),
+            _ => guild
+                .threads
+                .iter()
+                .find(|t| t.id == id)
+                .and_then(|t| t.parent_id)
+                .and_then(|parent| match guild.channels.get(&parent) {
+                    Some(Channel::Guild(channel)) => Some(channel),
+                    _ => None,
+                }),
+        };
+
+        match channel {
+            Some(channel) => guild
+                .user_permissions_in(channel, member)
+                .map(|p| p.view_channel())
+                .unwrap_or(false),
+            // Deleted or uncached channel: only server administrators see it
+            None => {
+                guild.owner_id == member.user.id
+                    || member.permissions.is_some_and(|p| p.administrator())
+            }
+        }
+    }
+
+    /// Page search results for Discord, or explain that nothing matched
+    fn format_results(
+        query: &str,
+        results: &[MessageSearchResult],
+    ) -> Result<PaginatedEmbed, String> {
+        if results.is_empty() {
+            return Err(format!("No messages found for `{query}`."));
+        }
+
+        let shown_query: String = query.chars().take(MAX_TITLE_QUERY_LEN).collect();
+        Ok(PaginatedEmbed::from_lines(
+            format!(
+                "🔎 Search results for \"{shown_query}\" ({})",
+                results.len()
+            ),
+            results.iter().map(Self::format_result_line),
+            RESULTS_PER_PAGE,
+        ))
+    }
+
+    fn format_result_line(result: &MessageSearchResult) -> String {
+        let when = NaiveDateTime::parse_from_str(&result.timestamp, "%Y-%m-%d %H:%M:%S")
+            .map(|dt| format!("<t:{}:f>", dt.and_utc().timestamp()))
+            .unwrap_or_else(|_| result.timestamp.clone());
+
+        let author = if result.role == "assistant" {
+            "bot".to_string()
+        } else {
+            format!("<@{}>", result.user_id)
+        };
+
+        let snippet: String = result.snippet.replace('\n', " ");
+        let snippet = if snippet.chars().count() > MAX_SNIPPET_LEN {
+            format!(
+                "{}...",
+                snippet.chars().take(MAX_SNIPPET_LEN).collect::<String>()
+            )
+        } else {
+            snippet
+        };
+
+        format!("{when} <#{}> {author}: {snippet}", result.channel_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::pagination::PAGE_CHARS;
+
+    fn result(role: &str, snippet: &str) -> MessageSearchResult {
+        MessageSearchResult {
+            id: 1,
+            guild_id: Some("g1".to_string()),
+            channel_id: "42".to_string(),
+            user_id: "7".to_string(),
+            username: None,
+            role: role.to_string(),
+            snippet: snippet.to_string(),
+            timestamp: "2024-01-02 03:04:05".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_search_handler_commands() {
+        let handler = SearchHandler;
+        assert_eq!(handler.command_names(), &["search"]);
+    }
+
+    #[test]
+    fn test_format_results_empty() {
+        let output = SearchHandler::format_results("deploy", &[]).unwrap_err();
+        assert_eq!(output, "No messages found for `deploy`.");
+    }
+
+    #[test]
+    fn test_format_results_lines() {
+        let pages = SearchHandler::format_results(
+            "deploy",
+            &[
+                result("user", "the **deploy** failed"),
+                result("assistant", "try a **deploy**"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(pages.title, "🔎 Search results for \"deploy\" (2)");
+        assert_eq!(pages.page_count(), 1);
+        let output = &pages.pages[0];
+        assert!(output.contains("<t:1704164645:f> <#42> <@7>: the **deploy** failed"));
+        assert!(output.contains("<#42> bot: try a **deploy**"));
+    }
+
+    #[test]
+    fn test_format_results_pages_every_result() {
+        let long = "x".repeat(500);
+        let results: Vec<_> = (0..25).map(|_| result("user", &long)).collect();
+        let pages = SearchHandler::format_results("x", &results).unwrap();
+        assert_eq!(pages.page_count(), 5);
+        assert!(pages.pages.iter().all(|p| p.len() <= PAGE_CHARS));
+        let lines: usize = pages.pages.iter().map(|p| p.lines().count()).sum();
+        assert_eq!(lines, 25);
+    }
+}
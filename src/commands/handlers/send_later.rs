@@ -0,0 +1,227 @@
+//! Send later command handler
+//!
+//! Handles: send_later
+//!
+//! Members queue messages for channels they can post in, optionally to be
+//! rewritten by their persona when sent. Like reminders, members list and
+//! cancel their own; members with Manage Messages manage everyone's. Posting
+//! is done by the send later scheduler.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use serenity::model::application::interaction::application_command::{
+    ApplicationCommandInteraction, CommandDataOption,
+};
+use serenity::model::id::ChannelId;
+use serenity::prelude::Context;
+use std::sync::Arc;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::handlers::remind::RemindHandler;
+use crate::commands::pagination::PaginatedEmbed;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::{
+    get_bool_option, get_channel_option, get_integer_option, get_string_option,
+};
+use crate::database::ScheduledMessage;
+use crate::features::send_later::{
+    list_line, validate_message, MAX_DELAY_SECS, MAX_PENDING_PER_USER, SEND_LATER_FEATURE,
+};
+
+/// Queued messages shown per page of /send_later list
+const MESSAGES_PER_PAGE: usize = 5;
+
+/// Handler for /send_later
+pub struct SendLaterHandler;
+
+#[async_trait]
+impl SlashCommandHandler for SendLaterHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["send_later"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let Some(guild_id) = command.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        if !ctx
+            .database
+            .is_feature_enabled(SEND_LATER_FEATURE, None, Some(&guild_id))
+            .await?
+        {
+            return responder
+                .reply_ephemeral(serenity_ctx, "❌ Send later is disabled on this server.")
+                .await;
+        }
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+        let user_id = command.user.id.to_string();
+        let can_manage = command
+            .member
+            .as_ref()
+            .and_then(|m| m.permissions)
+            .is_some_and(|p| p.manage_messages());
+
+        let content = match subcommand.name.as_str() {
+            "schedule" => {
+                Self::handle_schedule(&ctx, serenity_ctx, command, &guild_id, &subcommand.options)
+                    .await?
+            }
+            "list" => {
+                let messages = ctx
+                    .database
+                    .get_scheduled_messages(&guild_id, (!can_manage).then_some(user_id.as_str()))
+                    .await?;
+                if messages.is_empty() {
+                    "📭 No messages are queued. Schedule one with `/send_later schedule`."
+                        .to_string()
+                } else {
+                    let title = if can_manage {
+                        "📨 Queued messages on this server"
+                    } else {
+                        "📨 Your queued messages"
+                    };
+                    let lines: Vec<String> =
+                        messages.iter().map(|m| list_line(m, can_manage)).collect();
+                    return PaginatedEmbed::from_lines(title, &lines, MESSAGES_PER_PAGE)
+                        .with_footer("Use /send_later cancel <id> to cancel a message")
+                        .with_owner(command.user.id.0)
+                        .respond(&ctx.database, &serenity_ctx.http, &responder, true)
+                        .await;
+                }
+            }
+            _ => {
+                let id = get_integer_option(&subcommand.options, "id").unwrap_or_default();
+                let owner = (!can_manage).then_some(user_id.as_str());
+                if ctx
+                    .database
+                    .delete_scheduled_message(id, &guild_id, owner)
+                    .await?
+                {
+                    info!("/send_later cancel | User: {user_id} | Guild: {guild_id} | #{id}");
+                    format!("✅ Cancelled message #{id}.")
+                } else {
+                    format!("❌ Message #{id} not found or doesn't belong to you.")
+                }
+            }
+        };
+        responder.reply_ephemeral(serenity_ctx, &content).await
+    }
+}
+
+impl SendLaterHandler {
+    /// Handle /send_later schedule, returning the reply
+    async fn handle_schedule(
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        guild_id: &str,
+        options: &[CommandDataOption],
+    ) -> Result<String> {
+        let user_id = command.user.id.to_string();
+        let Some(channel_id) = get_channel_option(options, "channel").map(ChannelId) else {
+            return Ok("❌ Pick a channel to post in.".to_string());
+        };
+        // Discord resolves the channel with the invoker's permissions in it
+        let can_send = command
+            .data
+            .resolved
+            .channels
+            .get(&channel_id)
+            .and_then(|c| c.permissions)
+            .is_some_and(|p| p.view_channel() && p.send_messages());
+        if !can_send {
+            return Ok(format!(
+                "❌ You can only schedule messages for channels you can post in, and <#{channel_id}> isn't one."
+            ));
+        }
+        let Some(delay) = get_string_option(options, "in")
+            .as_deref()
+            .and_then(RemindHandler::parse_duration)
+        else {
+            return Ok(
+                "❌ Invalid time format. Use formats like `30m`, `2h`, `1d`, or `1h30m`."
+                    .to_string(),
+            );
+        };
+        if delay > MAX_DELAY_SECS {
+            return Ok("❌ Messages can be scheduled at most 30 days ahead.".to_string());
+        }
+        let content =
+            match validate_message(&get_string_option(options, "message").unwrap_or_default()) {
+                Ok(content) => content,
+                Err(e) => return Ok(format!("❌ {e}")),
+            };
+        if ctx
+            .database
+            .count_scheduled_messages(guild_id, &user_id)
+            .await?
+            >= MAX_PENDING_PER_USER
+        {
+            return Ok(format!(
+                "❌ You already have {MAX_PENDING_PER_USER} messages queued here. Cancel one first."
+            ));
+        }
+        let persona = if get_bool_option(options, "persona_rewrite").unwrap_or(false) {
+            Some(ctx.active_persona(serenity_ctx, command).await?)
+        } else {
+            None
+        };
+
+        let send_at = chrono::Utc::now().timestamp() + delay;
+        let id = ctx
+            .database
+            .add_scheduled_message(&ScheduledMessage {
+                id: 0,
+                guild_id: guild_id.to_string(),
+                channel_id: channel_id.to_string(),
+                user_id: user_id.clone(),
+                content,
+                persona: persona.clone(),
+                send_at,
+            })
+            .await?;
+        info!(
+            "/send_later schedule | User: {user_id} | Guild: {guild_id} | #{id} to {channel_id} in {}",
+            RemindHandler::format_duration(delay)
+        );
+        ctx.database
+            .log_usage(&user_id, Some(guild_id), "send_later", None)
+            .await?;
+
+        let rewrite = persona
+            .map(|p| format!(", rewritten by **{p}**"))
+            .unwrap_or_default();
+        Ok(format!(
+            "📨 Scheduled for <#{channel_id}> <t:{send_at}:R>{rewrite}.\n*Message ID: #{id}*"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_send_later_handler_commands() {
+        let handler = SendLaterHandler;
+        assert_eq!(handler.command_names(), &["send_later"]);
+    }
+}
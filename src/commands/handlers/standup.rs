@@ -0,0 +1,403 @@
+//! Standup command handler
+//!
+//! Handles: standup
+//!
+//! Admins set the schedule and summary channel with `/standup config`;
+//! members opt in with `/standup join` and can skip days. Prompts and
+//! summaries are sent by the standup scheduler, and DM replies are collected
+//! in `CommandHandler::handle_message`.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use log::info;
+use serenity::model::application::interaction::application_command::{
+    ApplicationCommandInteraction, CommandDataOption,
+};
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::prelude::Context;
+use std::sync::Arc;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::{
+    get_bool_option, get_channel_option, get_integer_option, get_string_option,
+};
+use crate::core::{format_utc_offset, parse_utc_offset};
+use crate::database::{Database, StandupConfig, StandupEntry, StandupMember};
+use crate::features::standup::{
+    date_key, format_time, is_skipping, local_date, parse_time, skip_until,
+    DEFAULT_COLLECT_MINUTES, MAX_COLLECT_MINUTES, MIN_COLLECT_MINUTES, QUESTIONS, STANDUP_FEATURE,
+};
+
+/// Handler for /standup command
+pub struct StandupHandler;
+
+#[async_trait]
+impl SlashCommandHandler for StandupHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["standup"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        self.handle_standup(&ctx, serenity_ctx, command).await
+    }
+}
+
+impl StandupHandler {
+    /// Handle /standup config, join, leave, skip and status
+    async fn handle_standup(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let user_id = command.user.id.to_string();
+        let Some(guild_id) = command.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+
+        let mut ephemeral = true;
+        let content = if !ctx
+            .database
+            .is_feature_enabled(STANDUP_FEATURE, None, Some(&guild_id))
+            .await?
+        {
+            "❌ Standups are disabled on this server.".to_string()
+        } else {
+            match subcommand.name.as_str() {
+                "config" => {
+                    let can_manage = command
+                        .member
+                        .as_ref()
+                        .and_then(|m| m.permissions)
+                        .is_some_and(|p| p.manage_guild());
+                    if !can_manage {
+                        "❌ You need the Manage Server permission to configure the standup."
+                            .to_string()
+                    } else {
+                        let existing = ctx.database.get_standup_config(&guild_id).await?;
+                        match Self::parse_config(&guild_id, &subcommand.options, existing) {
+                            Ok(config) => {
+                                ctx.database.set_standup_config(&config).await?;
+                                info!(
+                                    "/standup config | User: {user_id} | Guild: {guild_id} | {} {} in <#{}>",
+                                    format_time(config.prompt_minute),
+                                    format_utc_offset(config.utc_offset_minutes),
+                                    config.channel_id
+                                );
+                                Self::format_config(&config)
+                            }
+                            Err(message) => format!("❌ {message}"),
+                        }
+                    }
+                }
+                "join" => {
+                    if ctx
+                        .database
+                        .set_standup_member(&guild_id, &user_id, true)
+                        .await?
+                    {
+                        let schedule = ctx.database.get_standup_config(&guild_id).await?;
+                        match schedule {
+                            Some(config) => format!(
+                                "✅ You're in! I'll DM you the standup questions at **{}** ({}). \
+                                 Make sure DMs from server members are allowed.",
+                                format_time(config.prompt_minute),
+                                format_utc_offset(config.utc_offset_minutes)
+                            ),
+                            None => "✅ You're in! An admin still needs to set the schedule \
+                                     with `/standup config`."
+                                .to_string(),
+                        }
+                    } else {
+                        "You're already part of the standup.".to_string()
+                    }
+                }
+                "leave" => {
+                    if ctx
+                        .database
+                        .set_standup_member(&guild_id, &user_id, false)
+                        .await?
+                    {
+                        "👋 You've left the standup. Your past answers are kept in earlier summaries."
+                            .to_string()
+                    } else {
+                        "You're not part of the standup here.".to_string()
+                    }
+                }
+                "skip" => {
+                    let days = get_integer_option(&subcommand.options, "days")
+                        .unwrap_or(1)
+                        .clamp(1, 30) as u32;
+                    Self::handle_skip(&ctx.database, &guild_id, &user_id, days).await?
+                }
+                _ => {
+                    ephemeral = false;
+                    let config = ctx.database.get_standup_config(&guild_id).await?;
+                    let members = ctx.database.get_standup_members(&guild_id).await?;
+                    let entries = match &config {
+                        Some(config) => {
+                            let today = date_key(local_date(Utc::now(), config.utc_offset_minutes));
+                            ctx.database.get_standup_entries(&guild_id, &today).await?
+                        }
+                        None => Vec::new(),
+                    };
+                    Self::format_status(config.as_ref(), &members, &entries)
+                }
+            }
+        };
+
+        responder
+            .create_interaction_response(&serenity_ctx.http, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|m| m.content(content).ephemeral(ephemeral))
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Build a config from the options, keeping unset values from `existing`
+    fn parse_config(
+        guild_id: &str,
+        options: &[CommandDataOption],
+        existing: Option<StandupConfig>,
+    ) -> Result<StandupConfig, &'static str> {
+        let channel_id = get_channel_option(options, "channel").ok_or("Pick a channel")?;
+        let time = get_string_option(options, "time").ok_or("Give a time like 09:30")?;
+        let prompt_minute = parse_time(&time)?;
+        let timezone = get_string_option(options, "timezone");
+        let utc_offset_minutes = timezone.as_deref().map(parse_utc_offset).transpose()?;
+        let collect_minutes = get_integer_option(options, "window")
+            .map(|m| m.clamp(MIN_COLLECT_MINUTES.into(), MAX_COLLECT_MINUTES.into()) as u32);
+
+        Ok(StandupConfig {
+            guild_id: guild_id.to_string(),
+            channel_id: channel_id.to_string(),
+            prompt_minute,
+            utc_offset_minutes: utc_offset_minutes
+                .or(existing.as_ref().map(|c| c.utc_offset_minutes))
+                .unwrap_or(0),
+            collect_minutes: collect_minutes
+                .or(existing.as_ref().map(|c| c.collect_minutes))
+                .unwrap_or(DEFAULT_COLLECT_MINUTES),
+            weekdays_only: get_bool_option(options, "weekdays_only")
+                .or(existing.as_ref().map(|c| c.weekdays_only))
+                .unwrap_or(true),
+            enabled: get_bool_option(options, "enabled").unwrap_or(true),
+            // Bookkeeping columns are kept by the upsert
+            last_prompt_date: None,
+            last_prompt_at: None,
+            last_summary_date: None,
+        })
+    }
+
+    fn format_config(config: &StandupConfig) -> String {
+        let mut output = format!(
+            "✅ Standup questions go out at **{}** ({}){}, and the summary is posted in <#{}> \
+             {} minutes later.",
+            format_time(config.prompt_minute),
+            format_utc_offset(config.utc_offset_minutes),
+            if config.weekdays_only {
+                " on weekdays"
+            } else {
+                " every day"
+            },
+            config.channel_id,
+            config.collect_minutes
+        );
+        if !config.enabled {
+            output.push_str("\n⏸️ The standup is paused until it's enabled again.");
+        }
+        output
+    }
+
+    /// Skip from today; also closes today's round if it's already open
+    async fn handle_skip(
+        database: &Database,
+        guild_id: &str,
+        user_id: &str,
+        days: u32,
+    ) -> Result<String> {
+        let offset = database
+            .get_standup_config(guild_id)
+            .await?
+            .map(|c| c.utc_offset_minutes)
+            .unwrap_or(0);
+        let today = local_date(Utc::now(), offset);
+        let until = skip_until(today, days);
+        if !database
+            .set_standup_skip(guild_id, user_id, Some(&until))
+            .await?
+        {
+            return Ok(
+                "You're not part of the standup here. Use `/standup join` first.".to_string(),
+            );
+        }
+
+        let today = date_key(today);
+        let open = database
+            .get_standup_entries(guild_id, &today)
+            .await?
+            .into_iter()
+            .find(|e| e.user_id == user_id && !e.skipped);
+        if let Some(mut entry) = open {
+            entry.skipped = true;
+            database.save_standup_entry(&entry).await?;
+        }
+
+        info!("/standup skip | User: {user_id} | Guild: {guild_id} | Until: {until}");
+        Ok(if days == 1 {
+            "⏭️ Skipping today's standup.".to_string()
+        } else {
+            format!("⏭️ Skipping standups through **{until}**.")
+        })
+    }
+
+    fn format_status(
+        config: Option<&StandupConfig>,
+        members: &[StandupMember],
+        entries: &[StandupEntry],
+    ) -> String {
+        let mut output = "🗒️ **Daily standup**\n".to_string();
+        match config {
+            Some(config) => {
+                output.push_str(&format!(
+                    "Questions at **{}** ({}){}, summary in <#{}> after {} minutes.{}\n",
+                    format_time(config.prompt_minute),
+                    format_utc_offset(config.utc_offset_minutes),
+                    if config.weekdays_only {
+                        " on weekdays"
+                    } else {
+                        " every day"
+                    },
+                    config.channel_id,
+                    config.collect_minutes,
+                    if config.enabled {
+                        ""
+                    } else {
+                        " ⏸️ Paused."
+                    }
+                ));
+            }
+            None => output
+                .push_str("Not scheduled yet. An admin can set it up with `/standup config`.\n"),
+        }
+
+        if members.is_empty() {
+            output.push_str("\nNobody has joined yet. Use `/standup join` to take part!");
+            return output;
+        }
+
+        let today = config
+            .map(|c| date_key(local_date(Utc::now(), c.utc_offset_minutes)))
+            .unwrap_or_default();
+        output.push_str(&format!("\n**Members ({})**\n", members.len()));
+        for member in members {
+            let progress = match entries.iter().find(|e| e.user_id == member.user_id) {
+                Some(entry) if entry.skipped => "⏭️ skipped today".to_string(),
+                Some(entry) if entry.step as usize == QUESTIONS.len() => "✅ done".to_string(),
+                Some(entry) => format!("💬 {}/{} answered", entry.step, QUESTIONS.len()),
+                None if is_skipping(member, &today) => format!(
+                    "⏭️ skipping through {}",
+                    member.skip_until.as_deref().unwrap_or_default()
+                ),
+                None => "⏳ not asked yet today".to_string(),
+            };
+            output.push_str(&format!("<@{}> {progress}\n", member.user_id));
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> StandupConfig {
+        StandupConfig {
+            guild_id: "g1".to_string(),
+            channel_id: "42".to_string(),
+            prompt_minute: 570,
+            utc_offset_minutes: -300,
+            collect_minutes: 90,
+            weekdays_only: true,
+            enabled: true,
+            last_prompt_date: None,
+            last_prompt_at: None,
+            last_summary_date: None,
+        }
+    }
+
+    #[test]
+    fn test_standup_handler_commands() {
+        let handler = StandupHandler;
+        assert_eq!(handler.command_names(), &["standup"]);
+    }
+
+    #[test]
+    fn test_format_config() {
+        let output = StandupHandler::format_config(&config());
+        assert!(output.contains("**09:30** (UTC-05:00) on weekdays"));
+        assert!(output.contains("<#42> 90 minutes later"));
+        assert!(!output.contains("paused"));
+    }
+
+    #[test]
+    fn test_format_status_shows_progress() {
+        let members = vec![
+            StandupMember {
+                user_id: "1".to_string(),
+                skip_until: None,
+            },
+            StandupMember {
+                user_id: "2".to_string(),
+                skip_until: None,
+            },
+            StandupMember {
+                user_id: "3".to_string(),
+                skip_until: None,
+            },
+        ];
+        let entries = vec![
+            StandupEntry {
+                user_id: "1".to_string(),
+                step: 3,
+                ..Default::default()
+            },
+            StandupEntry {
+                user_id: "2".to_string(),
+                step: 1,
+                ..Default::default()
+            },
+        ];
+
+        let output = StandupHandler::format_status(Some(&config()), &members, &entries);
+        assert!(output.contains("**Members (3)**"));
+        assert!(output.contains("<@1> ✅ done"));
+        assert!(output.contains("<@2> 💬 1/3 answered"));
+        assert!(output.contains("<@3> ⏳ not asked yet today"));
+
+        let empty = StandupHandler::format_status(None, &[], &[]);
+        assert!(empty.contains("Not scheduled yet"));
+        assert!(empty.contains("Nobody has joined yet"));
+    }
+}
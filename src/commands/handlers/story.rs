@@ -0,0 +1,369 @@
+//! Story command handler
+//!
+//! Handles: story
+//!
+//! `/story start` opens a thread where the persona writes the first paragraph;
+//! members then add paragraphs by posting in the thread and the persona
+//! answers each one (see `CommandHandler::handle_story_contribution`).
+//! `/story conclude` writes an ending and exports the story as a file.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::{error, info, warn};
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::model::channel::AttachmentType;
+use serenity::prelude::Context;
+use std::borrow::Cow;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::commands::context::{is_in_thread_channel, CommandContext};
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::get_string_option;
+use crate::core::persona_embed;
+use crate::features::analytics::runtime::{spawn_tracked, TaskKind};
+use crate::features::analytics::CostBucket;
+use crate::features::council::TurnUsage;
+use crate::features::story::{
+    forget_story_state, get_active_stories, restore_story_state, save_story_state_logged,
+    story_filename, StoryState, StoryTurn, STORY_FEATURE,
+};
+
+/// Longest thread name Discord accepts is 100; leave room for the prefix
+const MAX_THREAD_TITLE_CHARS: usize = 90;
+
+/// Handler for /story command
+pub struct StoryHandler;
+
+#[async_trait]
+impl SlashCommandHandler for StoryHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["story"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let request_id = Uuid::new_v4();
+        self.handle_story(&ctx, serenity_ctx, command, request_id)
+            .await
+    }
+}
+
+impl StoryHandler {
+    /// Handle /story start and conclude
+    async fn handle_story(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        request_id: Uuid,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let Some(guild_id) = command.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+
+        if !ctx
+            .database
+            .is_feature_enabled(STORY_FEATURE, None, Some(&guild_id))
+            .await?
+        {
+            return responder
+                .reply_ephemeral(serenity_ctx, "❌ Story mode is disabled on this server.")
+                .await;
+        }
+
+        match subcommand.name.as_str() {
+            "start" => {
+                self.handle_start(ctx, serenity_ctx, command, &guild_id, request_id)
+                    .await
+            }
+            _ => {
+                self.handle_conclude(ctx, serenity_ctx, command, request_id)
+                    .await
+            }
+        }
+    }
+
+    /// Open the story thread and have the persona write the first paragraph
+    async fn handle_start(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        guild_id: &str,
+        request_id: Uuid,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let channel_id = command.channel_id;
+        let user_id = command.user.id.to_string();
+        let options = &command.data.options[0].options;
+        let premise = get_string_option(options, "premise")
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Missing premise argument"))?;
+
+        let persona_id = match get_string_option(options, "persona") {
+            Some(id) => id,
+            None => {
+                ctx.database
+                    .get_persona_with_channel(&user_id, guild_id, &channel_id.to_string(), None)
+                    .await?
+            }
+        };
+        let Some(persona) = ctx.persona_manager.get_persona(&persona_id) else {
+            return responder
+                .reply_ephemeral(serenity_ctx, &format!("Unknown persona: `{persona_id}`"))
+                .await;
+        };
+        let persona_name = persona.name.clone();
+
+        if restore_story_state(&ctx.database, channel_id.0).await? {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "A story is already being written here. Run `/story conclude` to finish it first.",
+                )
+                .await;
+        }
+
+        info!(
+            "[{request_id}] /story start | User: {user_id} | Channel: {channel_id} | Persona: {persona_id} | Premise: {premise}"
+        );
+        let announcement = format!(
+            "📖 **A new story begins!**\n\n\
+            **Premise:** {premise}\n\
+            **Co-writer:** {persona_name}\n\n\
+            {persona_name} writes the first paragraph. After each of theirs, anyone can \
+            post the next one in the thread. Finish with `/story conclude`."
+        );
+
+        let in_thread = is_in_thread_channel(serenity_ctx, channel_id)
+            .await
+            .unwrap_or(false);
+        responder
+            .create_interaction_response(&serenity_ctx.http, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|m| m.content(&announcement))
+            })
+            .await?;
+
+        // Already in a thread - write the story right here
+        let thread_id = if in_thread {
+            channel_id
+        } else {
+            let message = command.get_interaction_response(&serenity_ctx.http).await?;
+            let title: String = premise.chars().take(MAX_THREAD_TITLE_CHARS).collect();
+            match channel_id
+                .create_public_thread(&serenity_ctx.http, message.id, |t| {
+                    t.name(format!("📖 {title}")).auto_archive_duration(1440)
+                })
+                .await
+            {
+                Ok(thread) => thread.id,
+                Err(e) => {
+                    error!("[{request_id}] Failed to create story thread: {e}");
+                    command
+                        .edit_original_interaction_response(&serenity_ctx.http, |r| {
+                            r.content(
+                                "**Story Failed**\n\n\
+                                Could not create a thread. Make sure I have permission to create threads.",
+                            )
+                        })
+                        .await?;
+                    return Ok(());
+                }
+            }
+        };
+
+        get_active_stories().insert(
+            thread_id.0,
+            StoryState::new(
+                premise,
+                persona_id,
+                user_id.clone(),
+                Some(guild_id.to_string()),
+            ),
+        );
+        save_story_state_logged(&ctx.database, thread_id.0).await;
+
+        let writer = ctx
+            .story_writer(Some(guild_id), &thread_id.to_string())
+            .await?;
+        let http = serenity_ctx.http.clone();
+        let database = ctx.database.clone();
+        let usage = TurnUsage {
+            user_id,
+            guild_id: Some(guild_id.to_string()),
+            channel_id: thread_id.to_string(),
+            request_id,
+            cost_bucket: CostBucket::Story,
+        };
+        spawn_tracked(TaskKind::Discussions, async move {
+            writer.take_turn(&http, &database, thread_id, &usage).await;
+        });
+        Ok(())
+    }
+
+    /// Write the ending, post it, and attach the full story as Markdown
+    async fn handle_conclude(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        request_id: Uuid,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let channel_id = command.channel_id;
+        let user_id = command.user.id.to_string();
+
+        if !restore_story_state(&ctx.database, channel_id.0).await? {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "There's no story being written in this thread.",
+                )
+                .await;
+        }
+        let Some(state) = get_active_stories().get(&channel_id.0).map(|s| s.clone()) else {
+            return Ok(());
+        };
+
+        let can_manage = command
+            .member
+            .as_ref()
+            .and_then(|m| m.permissions)
+            .is_some_and(|p| p.manage_messages());
+        if state.initiator_id != user_id && !can_manage {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "Only the member who started the story (or a moderator) can conclude it.",
+                )
+                .await;
+        }
+        if state.turn == StoryTurn::Persona {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "The next paragraph is still being written. Try again in a moment.",
+                )
+                .await;
+        }
+
+        get_active_stories().remove(&channel_id.0);
+        forget_story_state(&ctx.database, channel_id.0).await?;
+        info!(
+            "[{request_id}] /story conclude | User: {user_id} | Thread: {channel_id} | {} paragraphs",
+            state.parts.len()
+        );
+        responder.defer(&serenity_ctx.http).await?;
+
+        let persona = ctx
+            .persona_manager
+            .get_persona_with_portrait(&state.persona_id);
+        let persona_name = persona
+            .as_ref()
+            .map(|p| p.name.clone())
+            .unwrap_or_else(|| state.persona_id.clone());
+        let usage = TurnUsage {
+            user_id: user_id.clone(),
+            guild_id: state.guild_id.clone(),
+            channel_id: channel_id.to_string(),
+            request_id,
+            cost_bucket: CostBucket::Story,
+        };
+
+        let mut state = state;
+        let writer = ctx
+            .story_writer(state.guild_id.as_deref(), &channel_id.to_string())
+            .await?;
+        let (title, ending) = match writer.ending(&state, &usage).await {
+            Ok((title, ending)) => (title, Some(ending)),
+            Err(e) => {
+                warn!("[{request_id}] Story ending failed, exporting without one: {e}");
+                (state.premise.chars().take(80).collect(), None)
+            }
+        };
+
+        match (&ending, &persona) {
+            (Some(ending), Some(persona)) => {
+                let mut embed = persona_embed(persona, ending);
+                embed.title(format!("📖 {title}: The End"));
+                responder
+                    .create_interaction_response(&serenity_ctx.http, |r| {
+                        r.kind(InteractionResponseType::ChannelMessageWithSource)
+                            .interaction_response_data(|m| m.set_embed(embed))
+                    })
+                    .await?;
+            }
+            (ending, _) => {
+                let content = match ending {
+                    Some(ending) => format!("📖 **{title}: The End**\n\n{ending}"),
+                    None => format!(
+                        "📖 **{title}**\n\n*{persona_name} couldn't find the right ending, \
+                         but here's the story so far.*"
+                    ),
+                };
+                responder
+                    .create_interaction_response(&serenity_ctx.http, |r| {
+                        r.kind(InteractionResponseType::ChannelMessageWithSource)
+                            .interaction_response_data(|m| m.content(content))
+                    })
+                    .await?;
+            }
+        }
+
+        if let Some(ending) = ending {
+            state.add_persona_part(&persona_name, ending);
+        }
+        let document = state.to_markdown(&title, &persona_name);
+        let contributors = state.contributors().len();
+        if let Err(e) = channel_id
+            .send_message(&serenity_ctx.http, |m| {
+                m.content(format!(
+                    "The complete story: {} paragraphs by {persona_name} and {contributors} \
+                     member{}.",
+                    state.parts.len(),
+                    if contributors == 1 { "" } else { "s" }
+                ))
+                .add_file(AttachmentType::Bytes {
+                    data: Cow::Owned(document.into_bytes()),
+                    filename: story_filename(&title),
+                })
+            })
+            .await
+        {
+            error!("[{request_id}] Failed to export story: {e}");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_story_handler_commands() {
+        let handler = StoryHandler;
+        assert_eq!(handler.command_names(), &["story"]);
+    }
+}
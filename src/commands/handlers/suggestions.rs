@@ -0,0 +1,297 @@
+//! Suggestion command handler
+//!
+//! Handles: suggest, suggestions
+//!
+//! `/suggest` saves the suggestion and posts it in the suggestion channel with
+//! vote buttons (handled by `SuggestionButtons`). `/suggestions list` shows
+//! recent ones by status; members with Manage Server pick the channel and mark
+//! suggestions Accepted, Declined or Implemented, which re-renders the post.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::{info, warn};
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::id::ChannelId;
+use serenity::prelude::Context;
+use std::sync::Arc;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::{get_channel_option, get_integer_option, get_string_option};
+use crate::database::Suggestion;
+use crate::features::suggestions::{
+    list_line, refresh_message, suggestion_buttons, suggestion_embed, validate_suggestion,
+    SuggestionStatus, CHANNEL_SETTING, LIST_LIMIT, SUGGESTIONS_FEATURE,
+};
+
+/// Handler for /suggest and /suggestions
+pub struct SuggestionsHandler;
+
+#[async_trait]
+impl SlashCommandHandler for SuggestionsHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["suggest", "suggestions"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let Some(guild_id) = command.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        if !ctx
+            .database
+            .is_feature_enabled(SUGGESTIONS_FEATURE, None, Some(&guild_id))
+            .await?
+        {
+            return responder
+                .reply_ephemeral(serenity_ctx, "❌ Suggestions are disabled on this server.")
+                .await;
+        }
+
+        let content = match command.data.name.as_str() {
+            "suggest" => {
+                self.handle_suggest(&ctx, serenity_ctx, command, &guild_id)
+                    .await?
+            }
+            _ => {
+                self.handle_suggestions(&ctx, serenity_ctx, command, &guild_id)
+                    .await?
+            }
+        };
+        responder.reply_ephemeral(serenity_ctx, &content).await
+    }
+}
+
+impl SuggestionsHandler {
+    /// Handle /suggest, returning the reply for the author
+    async fn handle_suggest(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        guild_id: &str,
+    ) -> Result<String> {
+        let user_id = command.user.id.to_string();
+        let Some(channel_id) = ctx
+            .database
+            .get_guild_setting(guild_id, CHANNEL_SETTING)
+            .await?
+        else {
+            return Ok("❌ Suggestions aren't set up yet. An admin can pick a channel with `/suggestions channel`.".to_string());
+        };
+        let text = match get_string_option(&command.data.options, "suggestion")
+            .as_deref()
+            .map(validate_suggestion)
+            .unwrap_or(Err("Missing suggestion"))
+        {
+            Ok(text) => text,
+            Err(message) => return Ok(format!("❌ {message}")),
+        };
+
+        let id = ctx
+            .database
+            .create_suggestion(guild_id, &channel_id, &user_id, &text)
+            .await?;
+        let suggestion = Suggestion {
+            id,
+            guild_id: guild_id.to_string(),
+            channel_id: channel_id.clone(),
+            message_id: None,
+            author_id: user_id.clone(),
+            content: text,
+            status: SuggestionStatus::Open.as_str().to_string(),
+            status_note: None,
+            reviewed_by: None,
+            upvotes: 0,
+            downvotes: 0,
+        };
+        let embed = suggestion_embed(&suggestion);
+        let components = suggestion_buttons(&suggestion);
+        let posted = ChannelId(channel_id.parse()?)
+            .send_message(&serenity_ctx.http, |m| {
+                m.set_embed(embed).set_components(components)
+            })
+            .await;
+        match posted {
+            Ok(message) => {
+                ctx.database
+                    .set_suggestion_message(id, &message.id.to_string())
+                    .await?;
+                info!("/suggest | User: {user_id} | Guild: {guild_id} | Suggestion {id}");
+                Ok(format!(
+                    "✅ Posted suggestion #{id} in <#{channel_id}>. Thanks for the idea!"
+                ))
+            }
+            Err(e) => {
+                warn!("⚠️ Failed to post suggestion {id} in {channel_id}: {e}");
+                ctx.database.delete_suggestion(id).await?;
+                Ok(format!(
+                    "❌ I couldn't post in <#{channel_id}>. Ask an admin to check my permissions there."
+                ))
+            }
+        }
+    }
+
+    /// Handle /suggestions list, mark and channel, returning the reply
+    async fn handle_suggestions(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        guild_id: &str,
+    ) -> Result<String> {
+        let user_id = command.user.id.to_string();
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+        let can_manage = command
+            .member
+            .as_ref()
+            .and_then(|m| m.permissions)
+            .is_some_and(|p| p.manage_guild());
+
+        match subcommand.name.as_str() {
+            "list" => {
+                let status = get_string_option(&subcommand.options, "status")
+                    .as_deref()
+                    .and_then(SuggestionStatus::from_setting);
+                let suggestions = ctx
+                    .database
+                    .get_suggestions(guild_id, status.map(SuggestionStatus::as_str), LIST_LIMIT)
+                    .await?;
+                Ok(Self::format_list(status, &suggestions))
+            }
+            _ if !can_manage => {
+                Ok("❌ You need the Manage Server permission to review suggestions.".to_string())
+            }
+            "mark" => {
+                let id = get_integer_option(&subcommand.options, "id").unwrap_or(0);
+                let Some(status) = get_string_option(&subcommand.options, "status")
+                    .as_deref()
+                    .and_then(SuggestionStatus::from_setting)
+                else {
+                    return Ok("❌ Pick a status.".to_string());
+                };
+                let reason = get_string_option(&subcommand.options, "reason")
+                    .map(|r| r.trim().to_string())
+                    .filter(|r| !r.is_empty());
+                if !ctx
+                    .database
+                    .set_suggestion_status(
+                        guild_id,
+                        id,
+                        status.as_str(),
+                        reason.as_deref(),
+                        &user_id,
+                    )
+                    .await?
+                {
+                    return Ok(format!("❌ There's no suggestion #{id} on this server."));
+                }
+                info!(
+                    "/suggestions mark | User: {user_id} | Guild: {guild_id} | Suggestion {id} | {}",
+                    status.as_str()
+                );
+
+                let Some(suggestion) = ctx.database.get_suggestion(id).await? else {
+                    return Ok(format!("❌ There's no suggestion #{id} on this server."));
+                };
+                match refresh_message(&serenity_ctx.http, &suggestion).await {
+                    Ok(()) => Ok(format!("✅ Suggestion #{id} is now {}.", status.label())),
+                    Err(e) => {
+                        warn!("⚠️ Failed to update suggestion {id} message: {e}");
+                        Ok(format!(
+                            "✅ Suggestion #{id} is now {}, but I couldn't update its post.",
+                            status.label()
+                        ))
+                    }
+                }
+            }
+            _ => {
+                let Some(channel_id) = get_channel_option(&subcommand.options, "channel") else {
+                    return Ok("❌ Pick a channel.".to_string());
+                };
+                ctx.database
+                    .set_guild_setting(guild_id, CHANNEL_SETTING, &channel_id.to_string())
+                    .await?;
+                info!("/suggestions channel | User: {user_id} | Guild: {guild_id} | Channel: {channel_id}");
+                Ok(format!(
+                    "✅ Suggestions from `/suggest` will be posted in <#{channel_id}>."
+                ))
+            }
+        }
+    }
+
+    /// Reply for /suggestions list
+    fn format_list(status: Option<SuggestionStatus>, suggestions: &[Suggestion]) -> String {
+        if suggestions.is_empty() {
+            return match status {
+                Some(status) => format!("No suggestions are {}.", status.label()),
+                None => "No suggestions yet. Post one with `/suggest`.".to_string(),
+            };
+        }
+        let heading = match status {
+            Some(status) => format!("**{} suggestions**", status.label()),
+            None => "**Recent suggestions**".to_string(),
+        };
+        std::iter::once(heading)
+            .chain(suggestions.iter().map(list_line))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suggestions_handler_commands() {
+        let handler = SuggestionsHandler;
+        assert_eq!(handler.command_names(), &["suggest", "suggestions"]);
+    }
+
+    #[test]
+    fn test_format_list() {
+        assert_eq!(
+            SuggestionsHandler::format_list(None, &[]),
+            "No suggestions yet. Post one with `/suggest`."
+        );
+        assert_eq!(
+            SuggestionsHandler::format_list(Some(SuggestionStatus::Declined), &[]),
+            "No suggestions are ❌ Declined."
+        );
+
+        let suggestion = Suggestion {
+            id: 1,
+            guild_id: "1".to_string(),
+            channel_id: "2".to_string(),
+            message_id: None,
+            author_id: "3".to_string(),
+            content: "More emotes".to_string(),
+            status: "open".to_string(),
+            status_note: None,
+            reviewed_by: None,
+            upvotes: 1,
+            downvotes: 0,
+        };
+        assert_eq!(
+            SuggestionsHandler::format_list(None, &[suggestion]),
+            "**Recent suggestions**\n`#1` 🗳️ Open for votes · 👍 1 👎 0 · More emotes"
+        );
+    }
+}
@@ -0,0 +1,591 @@
+//! Support command handler
+//!
+//! Handles: support
+//!
+//! `/support open` creates a private ticket thread with the requester and the
+//! support role, where the persona may post a first-line answer from the
+//! knowledge base. `/support close` summarizes the thread, records the ticket
+//! as closed and archives it. Members with Manage Server configure the role,
+//! channel and first-line answers, manage the knowledge base and read metrics.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use log::{info, warn};
+use serenity::model::application::interaction::application_command::{
+    ApplicationCommandInteraction, CommandDataOption,
+};
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::model::channel::ChannelType;
+use serenity::model::id::{ChannelId, RoleId};
+use serenity::prelude::Context;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::{
+    get_bool_option, get_channel_option, get_integer_option, get_role_option, get_string_option,
+};
+use crate::database::SupportKbEntry;
+use crate::features::analytics::CostBucket;
+use crate::features::support::{
+    first_line_prompt, format_duration, format_stats, parse_first_line, relevant_entries,
+    thread_name, transcript, CHANNEL_SETTING, FIRST_LINE_SETTING, KB_CONTEXT_ENTRIES,
+    MAX_KB_ENTRIES, ROLE_SETTING, SUMMARY_PROMPT, SUPPORT_FEATURE,
+};
+
+/// Messages read from a ticket thread for its closing summary
+const SUMMARY_MESSAGES: u64 = 100;
+
+/// Handler for /support
+pub struct SupportHandler;
+
+#[async_trait]
+impl SlashCommandHandler for SupportHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["support"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let request_id = Uuid::new_v4();
+        self.handle_support(&ctx, serenity_ctx, command, request_id)
+            .await
+    }
+}
+
+impl SupportHandler {
+    /// Route /support subcommands
+    async fn handle_support(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        request_id: Uuid,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let Some(guild_id) = command.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        if !ctx
+            .database
+            .is_feature_enabled(SUPPORT_FEATURE, None, Some(&guild_id))
+            .await?
+        {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "❌ Support tickets are disabled on this server.",
+                )
+                .await;
+        }
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+
+        match subcommand.name.as_str() {
+            "open" => {
+                self.handle_open(
+                    ctx,
+                    serenity_ctx,
+                    command,
+                    &responder,
+                    &guild_id,
+                    &subcommand.options,
+                    request_id,
+                )
+                .await
+            }
+            "close" => {
+                self.handle_close(ctx, serenity_ctx, command, &responder, request_id)
+                    .await
+            }
+            admin => {
+                let can_manage = command
+                    .member
+                    .as_ref()
+                    .and_then(|m| m.permissions)
+                    .is_some_and(|p| p.manage_guild());
+                let content = if !can_manage {
+                    "❌ You need the Manage Server permission to manage support.".to_string()
+                } else {
+                    match admin {
+                        "setup" => Self::handle_setup(ctx, &guild_id, &subcommand.options).await?,
+                        "stats" => {
+                            let stats = ctx
+                                .database
+                                .get_support_stats(&guild_id, Utc::now().timestamp())
+                                .await?;
+                            format_stats(&stats)
+                        }
+                        _ => {
+                            Self::handle_kb(
+                                ctx,
+                                &guild_id,
+                                &command.user.id.to_string(),
+                                subcommand,
+                            )
+                            .await?
+                        }
+                    }
+                };
+                responder.reply_ephemeral(serenity_ctx, &content).await
+            }
+        }
+    }
+
+    /// Handle /support open: create the private thread and try a first-line answer
+    #[allow(clippy::too_many_arguments)]
+    async fn handle_open(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        responder: &InteractionResponder,
+        guild_id: &str,
+        options: &[CommandDataOption],
+        request_id: Uuid,
+    ) -> Result<()> {
+        let user_id = command.user.id.to_string();
+        let Some(role_id) = ctx
+            .database
+            .get_guild_setting(guild_id, ROLE_SETTING)
+            .await?
+        else {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "❌ Support tickets aren't set up yet. An admin can pick a support role with `/support setup`.",
+                )
+                .await;
+        };
+        let Some(subject) = get_string_option(options, "subject")
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+        else {
+            return responder
+                .reply_ephemeral(serenity_ctx, "❌ Give your ticket a subject.")
+                .await;
+        };
+        let details = get_string_option(options, "details")
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty());
+
+        responder
+            .create_interaction_response(&serenity_ctx.http, |r| {
+                r.kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                    .interaction_response_data(|m| m.ephemeral(true))
+            })
+            .await?;
+
+        let parent = match ctx
+            .database
+            .get_guild_setting(guild_id, CHANNEL_SETTING)
+            .await?
+            .and_then(|id| id.parse::<u64>().ok())
+        {
+            Some(id) => ChannelId(id),
+            None => command.channel_id,
+        };
+        let thread = match parent
+            .create_private_thread(&serenity_ctx.http, |t| {
+                t.name(thread_name(&command.user.name, &subject))
+                    .kind(ChannelType::PrivateThread)
+                    .auto_archive_duration(1440)
+            })
+            .await
+        {
+            Ok(thread) => thread,
+            Err(e) => {
+                warn!("[{request_id}] Failed to create support thread in {parent}: {e}");
+                return responder
+                    .reply_ephemeral(
+                        serenity_ctx,
+                        &format!("❌ I couldn't open a private thread in <#{parent}>. I need the Create Private Threads permission there."),
+                    )
+                    .await;
+            }
+        };
+        thread
+            .id
+            .add_thread_member(&serenity_ctx.http, command.user.id)
+            .await?;
+
+        let mut opening = format!("🎫 **{subject}**\nOpened by <@{user_id}> · <@&{role_id}>",);
+        if let Some(details) = &details {
+            opening.push_str(&format!("\n\n{details}"));
+        }
+        opening.push_str(
+            "\n\nSomeone from the support team will be with you soon. Use `/support close` here once it's sorted.",
+        );
+        let role = role_id.parse::<u64>().map(RoleId).ok();
+        thread
+            .id
+            .send_message(&serenity_ctx.http, |m| {
+                m.content(opening).allowed_mentions(|a| {
+                    a.users([command.user.id]);
+                    if let Some(role) = role {
+                        a.roles([role]);
+                    }
+                    a
+                })
+            })
+            .await?;
+
+        let thread_id = thread.id.to_string();
+        let ticket_id = ctx
+            .database
+            .create_support_ticket(
+                guild_id,
+                &thread_id,
+                &user_id,
+                &subject,
+                Utc::now().timestamp(),
+            )
+            .await?;
+        info!("[{request_id}] /support open | User: {user_id} | Guild: {guild_id} | Ticket {ticket_id}");
+
+        let first_line = ctx
+            .database
+            .get_guild_setting(guild_id, FIRST_LINE_SETTING)
+            .await?
+            .is_some_and(|v| v == "enabled");
+        if first_line {
+            let query = match &details {
+                Some(details) => format!("{subject}\n{details}"),
+                None => subject.clone(),
+            };
+            match Self::first_line_answer(
+                ctx,
+                guild_id,
+                &parent.to_string(),
+                &user_id,
+                &query,
+                request_id,
+            )
+            .await
+            {
+                Ok(Some(answer)) => {
+                    thread
+                        .id
+                        .say(&serenity_ctx.http, format!("🤖 {answer}"))
+                        .await?;
+                    ctx.database.mark_support_first_line(ticket_id).await?;
+                }
+                Ok(None) => {}
+                Err(e) => warn!("[{request_id}] First-line support answer failed: {e}"),
+            }
+        }
+
+        responder
+            .reply_ephemeral(
+                serenity_ctx,
+                &format!("✅ Opened ticket #{ticket_id}: <#{thread_id}>"),
+            )
+            .await
+    }
+
+    /// Ask the channel's persona to answer from the most relevant knowledge base entries
+    async fn first_line_answer(
+        ctx: &CommandContext,
+        guild_id: &str,
+        channel_id: &str,
+        user_id: &str,
+        query: &str,
+        request_id: Uuid,
+    ) -> Result<Option<String>> {
+        let kb = ctx.database.get_support_kb(guild_id).await?;
+        let entries = relevant_entries(query, &kb, KB_CONTEXT_ENTRIES);
+        if entries.is_empty() {
+            return Ok(None);
+        }
+        let persona = ctx
+            .database
+            .get_persona_with_channel(user_id, guild_id, channel_id, None)
+            .await?;
+        let persona_prompt = ctx.persona_manager.get_system_prompt(&persona, None);
+        let reply = ctx
+            .get_ai_response(
+                &first_line_prompt(&persona_prompt, &entries),
+                query,
+                Vec::new(),
+                request_id,
+                Some(user_id),
+                Some(guild_id),
+                Some(channel_id),
+                CostBucket::Support,
+            )
+            .await?;
+        Ok(parse_first_line(&reply))
+    }
+
+    /// Handle /support close inside a ticket thread
+    async fn handle_close(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        responder: &InteractionResponder,
+        request_id: Uuid,
+    ) -> Result<()> {
+        let user_id = command.user.id.to_string();
+        let Some(ticket) = ctx
+            .database
+            .get_support_ticket_by_thread(&command.channel_id.to_string())
+            .await?
+        else {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "❌ Use `/support close` inside a ticket thread.",
+                )
+                .await;
+        };
+        if ticket.status != "open" {
+            return responder
+                .reply_ephemeral(serenity_ctx, "This ticket is already closed.")
+                .await;
+        }
+        let role_id = ctx
+            .database
+            .get_guild_setting(&ticket.guild_id, ROLE_SETTING)
+            .await?;
+        let is_support = command.member.as_ref().is_some_and(|m| {
+            m.roles
+                .iter()
+                .any(|r| role_id.as_deref() == Some(r.to_string().as_str()))
+                || m.permissions.is_some_and(|p| p.manage_threads())
+        });
+        if ticket.requester_id != user_id && !is_support {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "❌ Only the requester or the support team can close this ticket.",
+                )
+                .await;
+        }
+
+        responder.defer(&serenity_ctx.http).await?;
+
+        let messages: Vec<(String, String)> = command
+            .channel_id
+            .messages(&serenity_ctx.http, |r| r.limit(SUMMARY_MESSAGES))
+            .await?
+            .into_iter()
+            .rev()
+            .filter(|m| !m.content.trim().is_empty())
+            .map(|m| (m.author.name, m.content))
+            .collect();
+        let summary = if messages.is_empty() {
+            None
+        } else {
+            match ctx
+                .get_ai_response(
+                    SUMMARY_PROMPT,
+                    &transcript(&messages),
+                    Vec::new(),
+                    request_id,
+                    Some(&user_id),
+                    Some(&ticket.guild_id),
+                    Some(&ticket.thread_id),
+                    CostBucket::Support,
+                )
+                .await
+            {
+                Ok(summary) => Some(summary.trim().to_string()).filter(|s| !s.is_empty()),
+                Err(e) => {
+                    warn!("[{request_id}] Support ticket summary failed: {e}");
+                    None
+                }
+            }
+        };
+
+        let now = Utc::now().timestamp();
+        ctx.database
+            .close_support_ticket(ticket.id, &user_id, now, summary.as_deref())
+            .await?;
+        info!(
+            "[{request_id}] /support close | User: {user_id} | Guild: {} | Ticket {}",
+            ticket.guild_id, ticket.id
+        );
+
+        let mut content = format!(
+            "🔒 Ticket #{} closed by <@{user_id}> after {}.",
+            ticket.id,
+            format_duration(now - ticket.opened_at)
+        );
+        if let Some(summary) = &summary {
+            content.push_str(&format!("\n\n**Summary**\n{summary}"));
+        }
+        responder
+            .create_interaction_response(&serenity_ctx.http, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|m| m.content(content))
+            })
+            .await?;
+
+        if let Err(e) = command
+            .channel_id
+            .edit_thread(&serenity_ctx.http, |t| t.archived(true).locked(true))
+            .await
+        {
+            warn!("[{request_id}] Failed to archive support thread: {e}");
+        }
+        Ok(())
+    }
+
+    /// Handle /support setup, returning the reply
+    async fn handle_setup(
+        ctx: &CommandContext,
+        guild_id: &str,
+        options: &[CommandDataOption],
+    ) -> Result<String> {
+        let Some(role_id) = get_role_option(options, "role") else {
+            return Ok("❌ Pick a support role.".to_string());
+        };
+        let db = &ctx.database;
+        db.set_guild_setting(guild_id, ROLE_SETTING, &role_id.to_string())
+            .await?;
+        let channel = match get_channel_option(options, "channel") {
+            Some(channel_id) => {
+                db.set_guild_setting(guild_id, CHANNEL_SETTING, &channel_id.to_string())
+                    .await?;
+                format!("in <#{channel_id}>")
+            }
+            None => {
+                db.delete_guild_setting(guild_id, CHANNEL_SETTING).await?;
+                "wherever `/support open` is used".to_string()
+            }
+        };
+        if let Some(first_line) = get_bool_option(options, "first_line") {
+            let value = if first_line { "enabled" } else { "disabled" };
+            db.set_guild_setting(guild_id, FIRST_LINE_SETTING, value)
+                .await?;
+        }
+        let first_line = db
+            .get_guild_setting(guild_id, FIRST_LINE_SETTING)
+            .await?
+            .is_some_and(|v| v == "enabled");
+        info!("/support setup | Guild: {guild_id} | Role: {role_id}");
+
+        Ok(format!(
+            "✅ Tickets open as private threads {channel} with <@&{role_id}>. First-line answers from the knowledge base are {}.",
+            if first_line { "on" } else { "off" }
+        ))
+    }
+
+    /// Handle /support kb add, remove and list, returning the reply
+    async fn handle_kb(
+        ctx: &CommandContext,
+        guild_id: &str,
+        user_id: &str,
+        group: &CommandDataOption,
+    ) -> Result<String> {
+        let sub = group
+            .options
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Missing kb subcommand"))?;
+        match sub.name.as_str() {
+            "add" => {
+                let title = get_string_option(&sub.options, "title")
+                    .map(|t| t.trim().to_string())
+                    .filter(|t| !t.is_empty());
+                let content = get_string_option(&sub.options, "content")
+                    .map(|c| c.trim().to_string())
+                    .filter(|c| !c.is_empty());
+                let (Some(title), Some(content)) = (title, content) else {
+                    return Ok("❌ An article needs a title and content.".to_string());
+                };
+                if ctx.database.get_support_kb(guild_id).await?.len() >= MAX_KB_ENTRIES {
+                    return Ok(
+                        "❌ The knowledge base is full (50 articles). Remove some first."
+                            .to_string(),
+                    );
+                }
+                let id = ctx
+                    .database
+                    .add_support_kb_entry(guild_id, &title, &content, user_id)
+                    .await?;
+                info!("/support kb add | User: {user_id} | Guild: {guild_id} | Article {id}");
+                Ok(format!("✅ Added article #{id}: **{title}**"))
+            }
+            "remove" => {
+                let id = get_integer_option(&sub.options, "id").unwrap_or(0);
+                if ctx.database.delete_support_kb_entry(guild_id, id).await? {
+                    info!(
+                        "/support kb remove | User: {user_id} | Guild: {guild_id} | Article {id}"
+                    );
+                    Ok(format!("✅ Removed article #{id}."))
+                } else {
+                    Ok(format!("❌ There's no article #{id}."))
+                }
+            }
+            _ => Ok(Self::format_kb(
+                &ctx.database.get_support_kb(guild_id).await?,
+            )),
+        }
+    }
+
+    /// Reply for /support kb list
+    fn format_kb(entries: &[SupportKbEntry]) -> String {
+        if entries.is_empty() {
+            return "The knowledge base is empty. Add articles with `/support kb add`.".to_string();
+        }
+        let mut lines = vec![format!("📚 **Knowledge base ({})**", entries.len())];
+        lines.extend(entries.iter().map(|e| {
+            let mut preview: String = e.content.split_whitespace().collect::<Vec<_>>().join(" ");
+            if preview.chars().count() > 80 {
+                preview = preview.chars().take(80).collect::<String>() + "…";
+            }
+            format!("`#{}` **{}**: {preview}", e.id, e.title)
+        }));
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_support_handler_commands() {
+        let handler = SupportHandler;
+        assert_eq!(handler.command_names(), &["support"]);
+    }
+
+    #[test]
+    fn test_format_kb() {
+        assert!(SupportHandler::format_kb(&[]).starts_with("The knowledge base is empty."));
+        let entries = vec![
+            SupportKbEntry {
+                id: 1,
+                title: "Password reset".to_string(),
+                content: "Use the\nreset link.".to_string(),
+            },
+            SupportKbEntry {
+                id: 2,
+                title: "Long".to_string(),
+                content: "y".repeat(120),
+            },
+        ];
+        let text = SupportHandler::format_kb(&entries);
+        assert!(text.starts_with(
+            "📚 **Knowledge base (2)**\n`#1` **Password reset**: Use the reset link.\n"
+        ));
+        assert!(text.ends_with('…'));
+    }
+}
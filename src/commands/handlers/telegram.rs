@@ -0,0 +1,113 @@
+//! Telegram command handler
+//!
+//! Handles: telegram
+//!
+//! Issues the one-time codes that link a Telegram account to the Discord
+//! user, and removes links. Codes are only ever shown ephemerally.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::prelude::Context;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::features::telegram::{TelegramConfig, LINK_CODE_MINUTES};
+
+/// Handler for /telegram
+pub struct TelegramHandler;
+
+#[async_trait]
+impl SlashCommandHandler for TelegramHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["telegram"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let request_id = Uuid::new_v4();
+        let responder = InteractionResponder::for_command(command);
+        let user_id = command.user.id.to_string();
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .map(|o| o.name.as_str())
+            .unwrap_or_default();
+
+        let content = if TelegramConfig::from_env().is_none() {
+            "❌ The Telegram bot isn't set up on this instance.".to_string()
+        } else {
+            match subcommand {
+                "link" => {
+                    let code = link_code();
+                    let expires_at = (chrono::Utc::now()
+                        + chrono::Duration::minutes(LINK_CODE_MINUTES))
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string();
+                    ctx.database
+                        .create_telegram_link_code(&user_id, &code, &expires_at)
+                        .await?;
+                    info!("[{request_id}] /telegram link | User: {user_id}");
+                    format!(
+                        "Send this to the Telegram bot within {LINK_CODE_MINUTES} minutes:\n`/link {code}`\n\
+                         Your persona, history and reminders are then shared, and `/continue` \
+                         there picks up your latest conversation here."
+                    )
+                }
+                "unlink" => match ctx.database.delete_telegram_links(&user_id).await? {
+                    0 => "You don't have a linked Telegram account.".to_string(),
+                    _ => "✅ Telegram account unlinked.".to_string(),
+                },
+                _ => "Unknown subcommand.".to_string(),
+            }
+        };
+
+        responder
+            .create_interaction_response(&serenity_ctx.http, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|m| m.content(content).ephemeral(true))
+            })
+            .await?;
+        Ok(())
+    }
+}
+
+/// Eight uppercase hex characters, easy to type on a phone
+fn link_code() -> String {
+    Uuid::new_v4().simple().to_string()[..8].to_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_telegram_handler_commands() {
+        assert_eq!(TelegramHandler.command_names(), &["telegram"]);
+    }
+
+    #[test]
+    fn test_link_code_shape() {
+        let code = link_code();
+        assert_eq!(code.len(), 8);
+        assert!(code
+            .chars()
+            .all(|c| c.is_ascii_hexdigit() && !c.is_ascii_lowercase()));
+    }
+}
@@ -0,0 +1,462 @@
+//! Ticket command handler
+//!
+//! Handles: ticket, Create Ticket
+//!
+//! Files a message in the guild's Jira or Linear workspace, optionally
+//! summarizing it into a title and description first, and posts the created
+//! issue's link back in the channel. `/ticket config` stores the workspace
+//! and API token per guild.
+//!
+//! - **Version**: 1.0.1
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.1: Config, status and remove replies stay private even when auto-deferred; short tokens are fully masked
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::{info, warn};
+use serenity::model::application::interaction::application_command::{
+    ApplicationCommandInteraction, CommandDataOption,
+};
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::model::channel::Message;
+use serenity::model::id::{ChannelId, MessageId};
+use serenity::prelude::Context;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::{get_bool_option, get_string_option};
+use crate::database::{IssueProvider, IssueTrackerConfig};
+use crate::features::analytics::CostBucket;
+use crate::features::issues::{
+    draft_from_message, mask_token, message_link, parse_message_reference, parse_summary,
+    summary_prompt, validate_config, IssueClient, IssueDraft, DEFAULT_JIRA_ISSUE_TYPE,
+    ISSUES_FEATURE, TOKEN_OPTION,
+};
+
+/// Handler for /ticket and the Create Ticket context menu
+pub struct TicketHandler;
+
+#[async_trait]
+impl SlashCommandHandler for TicketHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["ticket", "Create Ticket"]
+    }
+
+    /// Only filed issues are announced; config, status and remove stay private
+    fn ephemeral(&self, command: &ApplicationCommandInteraction) -> bool {
+        command.data.name == "ticket"
+            && command
+                .data
+                .options
+                .first()
+                .is_some_and(|subcommand| subcommand.name != "create")
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let request_id = Uuid::new_v4();
+        self.handle_ticket(&ctx, serenity_ctx, command, request_id)
+            .await
+    }
+}
+
+impl TicketHandler {
+    /// Route /ticket subcommands and the context menu
+    async fn handle_ticket(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        request_id: Uuid,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let Some(guild_id) = command.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        if !ctx
+            .database
+            .is_feature_enabled(ISSUES_FEATURE, None, Some(&guild_id))
+            .await?
+        {
+            return responder
+                .reply_ephemeral(serenity_ctx, "❌ Issue filing is disabled on this server.")
+                .await;
+        }
+
+        // The context menu always files the message it was opened on
+        if command.data.name == "Create Ticket" {
+            let message = command.data.resolved.messages.values().next().cloned();
+            return self
+                .handle_create(
+                    ctx,
+                    serenity_ctx,
+                    command,
+                    &guild_id,
+                    message,
+                    None,
+                    None,
+                    request_id,
+                )
+                .await;
+        }
+
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+        let options = &subcommand.options;
+        let can_manage = command
+            .member
+            .as_ref()
+            .and_then(|m| m.permissions)
+            .is_some_and(|p| p.manage_guild());
+
+        let content = match subcommand.name.as_str() {
+            "create" => {
+                let message = match get_string_option(options, "message") {
+                    Some(reference) => {
+                        match parse_message_reference(&reference) {
+                            Some((channel, message_id)) => {
+                                let channel = channel.map(ChannelId).unwrap_or(command.channel_id);
+                                match channel
+                                    .message(&serenity_ctx.http, MessageId(message_id))
+                                    .await
+                                {
+                                    Ok(message) => Some(message),
+                                    Err(e) => {
+                                        warn!("[{request_id}] Couldn't fetch message {message_id}: {e}");
+                                        return responder
+                                            .reply_ephemeral(
+                                                serenity_ctx,
+                                                "❌ I couldn't find that message. Use a message link or an ID from this channel.",
+                                            )
+                                            .await;
+                                    }
+                                }
+                            }
+                            None => {
+                                return responder
+                                    .reply_ephemeral(
+                                        serenity_ctx,
+                                        "❌ That isn't a message link or ID.",
+                                    )
+                                    .await;
+                            }
+                        }
+                    }
+                    None => None,
+                };
+                let title = get_string_option(options, "title")
+                    .map(|t| t.trim().to_string())
+                    .filter(|t| !t.is_empty());
+                if message.is_none() && title.is_none() {
+                    "❌ Give a message link or a title to file.".to_string()
+                } else {
+                    let summarize = get_bool_option(options, "summarize");
+                    return self
+                        .handle_create(
+                            ctx,
+                            serenity_ctx,
+                            command,
+                            &guild_id,
+                            message,
+                            title,
+                            summarize,
+                            request_id,
+                        )
+                        .await;
+                }
+            }
+            "config" if can_manage => match Self::parse_config(&guild_id, options) {
+                Ok(config) => {
+                    ctx.database.set_issue_tracker_config(&config).await?;
+                    info!(
+                        "[{request_id}] /ticket config | User: {} | Guild: {guild_id} | {} {}",
+                        command.user.id,
+                        config.provider.key(),
+                        config.project
+                    );
+                    format!(
+                        "✅ Connected. Issues will be filed in {}.\n{}",
+                        Self::describe_target(&config),
+                        "The token is stored for this server only and won't be shown again."
+                    )
+                }
+                Err(message) => format!("❌ {message}"),
+            },
+            "remove" if can_manage => {
+                if ctx.database.delete_issue_tracker_config(&guild_id).await? {
+                    "🗑️ Disconnected. The API token has been deleted.".to_string()
+                } else {
+                    "No issue tracker is connected.".to_string()
+                }
+            }
+            "config" | "remove" => {
+                "❌ You need the Manage Server permission to change the issue tracker.".to_string()
+            }
+            _ => match ctx.database.get_issue_tracker_config(&guild_id).await? {
+                Some(config) => Self::format_status(&config),
+                None => "No issue tracker is connected. An admin can set one up with \
+                         `/ticket config`."
+                    .to_string(),
+            },
+        };
+        responder.reply_ephemeral(serenity_ctx, &content).await
+    }
+
+    /// Draft, file and announce an issue
+    #[allow(clippy::too_many_arguments)]
+    async fn handle_create(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        guild_id: &str,
+        message: Option<Message>,
+        title: Option<String>,
+        summarize: Option<bool>,
+        request_id: Uuid,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let Some(config) = ctx.database.get_issue_tracker_config(guild_id).await? else {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "No issue tracker is connected. An admin can set one up with `/ticket config`.",
+                )
+                .await;
+        };
+        let user_id = command.user.id.to_string();
+        info!(
+            "[{request_id}] Ticket | User: {user_id} | Guild: {guild_id} | Message: {:?}",
+            message.as_ref().map(|m| m.id)
+        );
+        responder.defer(&serenity_ctx.http).await?;
+
+        let mut source = None;
+        let mut draft = match &message {
+            Some(message) => {
+                let link = message_link(guild_id, message.channel_id.0, message.id.0);
+                let text = Self::message_text(message);
+                let author = message.author.name.clone();
+                let fallback = draft_from_message(&text, &author, &link);
+                let draft = if summarize.unwrap_or(config.summarize) {
+                    let user_message = format!("Message from {author}:\n{text}");
+                    match ctx
+                        .get_ai_response(
+                            &summary_prompt(),
+                            &user_message,
+                            Vec::new(),
+                            request_id,
+                            Some(&user_id),
+                            Some(guild_id),
+                            Some(&command.channel_id.to_string()),
+                            CostBucket::Ticket,
+                        )
+                        .await
+                    {
+                        Ok(reply) => parse_summary(&reply, &author, &link, fallback),
+                        Err(e) => {
+                            warn!("[{request_id}] Ticket summary failed, filing as-is: {e}");
+                            fallback
+                        }
+                    }
+                } else {
+                    fallback
+                };
+                source = Some(link);
+                draft
+            }
+            None => IssueDraft {
+                title: String::new(),
+                description: format!(
+                    "Filed from Discord by {} in <#{}>",
+                    command.user.name, command.channel_id
+                ),
+            },
+        };
+        if let Some(title) = title {
+            draft.title = title;
+        }
+
+        let content = match IssueClient::new().create_issue(&config, &draft).await {
+            Ok(issue) => {
+                let mut content = format!(
+                    "🎫 Filed **[{}]({})**: {}",
+                    issue.key, issue.url, draft.title
+                );
+                if let Some(link) = source {
+                    content.push_str(&format!("\nFrom {link}"));
+                }
+                content
+            }
+            Err(e) => format!(
+                "❌ {} didn't accept the issue: {e}",
+                Self::provider_name(config.provider)
+            ),
+        };
+        responder
+            .create_interaction_response(&serenity_ctx.http, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|m| m.content(content))
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Message text with attachment links, since tickets can't carry the files
+    fn message_text(message: &Message) -> String {
+        let mut text = message.content.trim().to_string();
+        for attachment in &message.attachments {
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            text.push_str(&attachment.url);
+        }
+        if text.is_empty() {
+            text = "(message had no text)".to_string();
+        }
+        text
+    }
+
+    fn parse_config(
+        guild_id: &str,
+        options: &[CommandDataOption],
+    ) -> Result<IssueTrackerConfig, &'static str> {
+        let provider = get_string_option(options, "provider")
+            .and_then(|p| IssueProvider::from_key(&p))
+            .ok_or("Pick Jira or Linear.")?;
+        let text = |name| {
+            get_string_option(options, name)
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let config = IssueTrackerConfig {
+            guild_id: guild_id.to_string(),
+            provider,
+            project: text("project").unwrap_or_default(),
+            api_token: text(TOKEN_OPTION).unwrap_or_default(),
+            base_url: text("site_url").map(|u| u.trim_end_matches('/').to_string()),
+            email: text("email"),
+            issue_type: text("issue_type"),
+            summarize: get_bool_option(options, "summarize").unwrap_or(true),
+        };
+        validate_config(&config)?;
+        Ok(config)
+    }
+
+    fn provider_name(provider: IssueProvider) -> &'static str {
+        match provider {
+            IssueProvider::Jira => "Jira",
+            IssueProvider::Linear => "Linear",
+        }
+    }
+
+    fn describe_target(config: &IssueTrackerConfig) -> String {
+        match config.provider {
+            IssueProvider::Jira => format!(
+                "Jira project **{}** at {} as {} issues",
+                config.project,
+                config.base_url.as_deref().unwrap_or_default(),
+                config
+                    .issue_type
+                    .as_deref()
+                    .unwrap_or(DEFAULT_JIRA_ISSUE_TYPE)
+            ),
+            IssueProvider::Linear => format!("Linear team `{}`", config.project),
+        }
+    }
+
+    fn format_status(config: &IssueTrackerConfig) -> String {
+        format!(
+            "🎫 Issues are filed in {}.\nToken: `{}`{}\nSummarize by default: {}",
+            Self::describe_target(config),
+            mask_token(&config.api_token),
+            config
+                .email
+                .as_deref()
+                .map(|email| format!(" ({email})"))
+                .unwrap_or_default(),
+            if config.summarize { "yes" } else { "no" }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{slash_command, subcommand};
+
+    fn linear() -> IssueTrackerConfig {
+        IssueTrackerConfig {
+            guild_id: "g1".to_string(),
+            provider: IssueProvider::Linear,
+            project: "team-123".to_string(),
+            api_token: "lin_api_secret0123ABCD".to_string(),
+            base_url: None,
+            email: None,
+            issue_type: None,
+            summarize: true,
+        }
+    }
+
+    #[test]
+    fn test_ticket_handler_commands() {
+        let handler = TicketHandler;
+        assert_eq!(handler.command_names(), &["ticket", "Create Ticket"]);
+    }
+
+    #[test]
+    fn test_status_masks_token() {
+        let status = TicketHandler::format_status(&linear());
+        assert!(status.contains("Linear team `team-123`"));
+        assert!(status.contains("••••ABCD"));
+        assert!(!status.contains("secret"));
+
+        let jira = IssueTrackerConfig {
+            provider: IssueProvider::Jira,
+            project: "OPS".to_string(),
+            base_url: Some("https://acme.atlassian.net".to_string()),
+            email: Some("bot@acme.test".to_string()),
+            ..linear()
+        };
+        let status = TicketHandler::format_status(&jira);
+        assert!(
+            status.contains("Jira project **OPS** at https://acme.atlassian.net as Task issues")
+        );
+        assert!(status.contains("(bot@acme.test)"));
+
+        let short = IssueTrackerConfig {
+            api_token: "abcd1234".to_string(),
+            ..linear()
+        };
+        let status = TicketHandler::format_status(&short);
+        assert!(status.contains("Token: `••••`"));
+        assert!(!status.contains("1234"));
+    }
+
+    #[test]
+    fn test_only_create_is_announced() {
+        let handler = TicketHandler;
+        for (name, ephemeral) in [
+            ("create", false),
+            ("config", true),
+            ("status", true),
+            ("remove", true),
+        ] {
+            let command = slash_command("ticket", vec![subcommand(name, vec![])]).unwrap();
+            assert_eq!(handler.ephemeral(&command), ephemeral, "{name}");
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! Time command handler
+//!
+//! Handles: time
+//!
+//! Members store a time zone (an IANA name or a UTC offset), used by `/time convert` and by time
+//! detection in opted-in channels, and convert times to Discord timestamp
+//! markup.
+//!
+//! - **Version**: 1.1.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.1.0: Accept IANA zone names through `core::time` instead of birthday helpers
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use log::info;
+use serenity::model::application::interaction::application_command::{
+    ApplicationCommandInteraction, CommandDataOption,
+};
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::prelude::Context;
+use std::sync::Arc;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::get_string_option;
+use crate::core::Zone;
+use crate::features::timezones::{member_zone, parse_when, TIMEZONES_FEATURE, TIMEZONE_PREFERENCE};
+
+/// Handler for /time
+pub struct TimeHandler;
+
+#[async_trait]
+impl SlashCommandHandler for TimeHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["time"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        if let Some(guild_id) = command.guild_id {
+            if !ctx
+                .database
+                .is_feature_enabled(TIMEZONES_FEATURE, None, Some(&guild_id.to_string()))
+                .await?
+            {
+                return Self::reply(
+                    &responder,
+                    serenity_ctx,
+                    "❌ Time zone tools are disabled on this server.",
+                    true,
+                )
+                .await;
+            }
+        }
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+        let user_id = command.user.id.to_string();
+        let stored = member_zone(&ctx.database, &user_id).await?;
+
+        match subcommand.name.as_str() {
+            "zone" => {
+                let content = match get_string_option(&subcommand.options, "timezone") {
+                    Some(timezone) => match Zone::parse(&timezone) {
+                        Ok(zone) => {
+                            ctx.database
+                                .set_user_preference(
+                                    &user_id,
+                                    TIMEZONE_PREFERENCE,
+                                    &zone.to_setting(),
+                                )
+                                .await?;
+                            info!("/time zone | User: {user_id} | {zone}");
+                            format!(
+                                "🕒 Your time zone is now **{zone}**. Times you write in channels with time detection will be shown in everyone's local time."
+                            )
+                        }
+                        Err(e) => format!("❌ {e}"),
+                    },
+                    None => match stored {
+                        Some(zone) => format!("🕒 Your time zone is **{zone}**."),
+                        None => "You haven't set a time zone. Set one with `/time zone timezone:Europe/Berlin`."
+                            .to_string(),
+                    },
+                };
+                Self::reply(&responder, serenity_ctx, &content, true).await
+            }
+            _ => match Self::convert(&subcommand.options, stored) {
+                Ok(content) => Self::reply(&responder, serenity_ctx, &content, false).await,
+                Err(e) => Self::reply(&responder, serenity_ctx, &format!("❌ {e}"), true).await,
+            },
+        }
+    }
+}
+
+impl TimeHandler {
+    /// Handle /time convert, returning the public reply
+    fn convert(options: &[CommandDataOption], stored: Option<Zone>) -> Result<String, String> {
+        let input = get_string_option(options, "time").unwrap_or_default();
+        let zone = match get_string_option(options, "from") {
+            Some(from) => Zone::parse(&from)?,
+            None => stored.ok_or(
+                "Tell me which zone the time is in with `from`, or set yours with `/time zone`.",
+            )?,
+        };
+        let unix = parse_when(&input, &zone, Utc::now())?;
+        Ok(format!(
+            "🕒 **{}** ({zone}) is <t:{unix}:F> (<t:{unix}:R>) in your local time.\nCopy it: `<t:{unix}:F>`",
+            input.trim()
+        ))
+    }
+
+    async fn reply(
+        responder: &InteractionResponder,
+        serenity_ctx: &Context,
+        content: &str,
+        ephemeral: bool,
+    ) -> Result<()> {
+        responder
+            .create_interaction_response(&serenity_ctx.http, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|m| m.content(content).ephemeral(ephemeral))
+            })
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_handler_commands() {
+        let handler = TimeHandler;
+        assert_eq!(handler.command_names(), &["time"]);
+    }
+}
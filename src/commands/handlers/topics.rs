@@ -0,0 +1,214 @@
+//! Topics command handler
+//!
+//! Handles: topics
+//!
+//! Clusters a channel's recent stored messages by embedding and posts the
+//! trending topics with example messages.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::{error, info};
+use serenity::builder::CreateEmbed;
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::prelude::Context;
+use std::sync::Arc;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::topics::MAX_TOPIC_HOURS;
+use crate::commands::slash::{get_channel_option, get_integer_option};
+use crate::features::analytics::topics::{MAX_MESSAGES, MIN_MESSAGES};
+use crate::features::analytics::{
+    openai_embedding_client, TopicFinder, TopicMessage, TrendingTopic, TOPICS_FEATURE,
+};
+
+/// Window used when no `hours` option is given
+const DEFAULT_HOURS: i64 = 24;
+
+/// Handler for /topics
+pub struct TopicsHandler;
+
+#[async_trait]
+impl SlashCommandHandler for TopicsHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["topics"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        self.handle_topics(&ctx, serenity_ctx, command).await
+    }
+}
+
+impl TopicsHandler {
+    /// Handle /topics [hours] [channel]
+    async fn handle_topics(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let user_id = command.user.id.to_string();
+        let Some(guild_id) = command.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        if !ctx
+            .database
+            .is_feature_enabled(TOPICS_FEATURE, None, Some(&guild_id))
+            .await?
+        {
+            responder
+                .create_interaction_response(&serenity_ctx.http, |r| {
+                    r.kind(InteractionResponseType::ChannelMessageWithSource)
+                        .interaction_response_data(|m| {
+                            m.content("❌ Trending topics are disabled on this server.")
+                                .ephemeral(true)
+                        })
+                })
+                .await?;
+            return Ok(());
+        }
+
+        let options = &command.data.options;
+        let hours = get_integer_option(options, "hours")
+            .unwrap_or(DEFAULT_HOURS)
+            .clamp(1, MAX_TOPIC_HOURS);
+        let channel_id = get_channel_option(options, "channel")
+            .unwrap_or(command.channel_id.0)
+            .to_string();
+
+        responder.defer(&serenity_ctx.http).await?;
+        info!("/topics | User: {user_id} | Guild: {guild_id} | <#{channel_id}> {hours}h");
+
+        let since = chrono::Utc::now().timestamp() - hours * 3600;
+        let messages: Vec<TopicMessage> = ctx
+            .database
+            .get_channel_user_messages_since(&channel_id, since, MAX_MESSAGES)
+            .await?
+            .into_iter()
+            .map(|(content, timestamp)| TopicMessage { content, timestamp })
+            .collect();
+
+        let finder = TopicFinder::new(
+            openai_embedding_client(),
+            ctx.chat_client.clone(),
+            ctx.openai_model.clone(),
+            ctx.usage_tracker.clone(),
+        );
+        let topics = match finder
+            .find(&messages, since, &user_id, Some(&guild_id), &channel_id)
+            .await
+        {
+            Ok(topics) => topics,
+            Err(e) => {
+                error!("Failed to find topics in {channel_id}: {e}");
+                command
+                    .edit_original_interaction_response(&serenity_ctx.http, |r| {
+                        r.content("❌ Couldn't work out the topics right now. Try again later.")
+                    })
+                    .await?;
+                return Ok(());
+            }
+        };
+
+        if topics.is_empty() {
+            command
+                .edit_original_interaction_response(&serenity_ctx.http, |r| {
+                    r.content(format!(
+                        "Not enough discussion in <#{channel_id}> over the last {hours}h to find \
+                         topics (needs at least {MIN_MESSAGES} stored messages)."
+                    ))
+                })
+                .await?;
+            return Ok(());
+        }
+
+        let embed = Self::topics_embed(&channel_id, hours, messages.len(), &topics);
+        command
+            .edit_original_interaction_response(&serenity_ctx.http, |r| r.set_embed(embed))
+            .await?;
+        Ok(())
+    }
+
+    fn topics_embed(
+        channel_id: &str,
+        hours: i64,
+        message_count: usize,
+        topics: &[TrendingTopic],
+    ) -> CreateEmbed {
+        let mut embed = CreateEmbed::default();
+        embed
+            .title("🔥 Trending Topics")
+            .description(format!("What <#{channel_id}> has been talking about"))
+            .color(0xF4900C);
+        for (i, topic) in topics.iter().enumerate() {
+            embed.field(
+                format!(
+                    "{}. {}{}",
+                    i + 1,
+                    topic.label,
+                    if topic.rising { " 📈" } else { "" }
+                ),
+                Self::format_topic(topic),
+                false,
+            );
+        }
+        embed.footer(|f| {
+            f.text(format!(
+                "Last {hours}h · {message_count} messages · 📈 = picking up recently"
+            ))
+        });
+        embed
+    }
+
+    fn format_topic(topic: &TrendingTopic) -> String {
+        let mut lines = vec![format!(
+            "{} messages, {} recently",
+            topic.messages, topic.recent
+        )];
+        lines.extend(topic.examples.iter().map(|e| format!("> {e}")));
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_topics_handler_commands() {
+        let handler = TopicsHandler;
+        assert_eq!(handler.command_names(), &["topics"]);
+    }
+
+    #[test]
+    fn test_format_topic() {
+        let topic = TrendingTopic {
+            label: "Release build".to_string(),
+            messages: 7,
+            recent: 4,
+            rising: true,
+            examples: vec![
+                "the build is red again".to_string(),
+                "who broke main".to_string(),
+            ],
+        };
+        assert_eq!(
+            TopicsHandler::format_topic(&topic),
+            "7 messages, 4 recently\n> the build is red again\n> who broke main"
+        );
+    }
+}
@@ -0,0 +1,344 @@
+//! Trivia command handler
+//!
+//! Handles: trivia
+//!
+//! `/trivia start` has a persona write a themed quiz and hosts it in the
+//! channel; answers come in through buttons or typed letters (see
+//! `features::trivia`). Results feed the per-guild leaderboard shown by
+//! `/trivia leaderboard`.
+//!
+//! - **Version**: 1.1.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.1.0: Leaderboard shows games won
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::{info, warn};
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::prelude::Context;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::{get_integer_option, get_string_option};
+use crate::database::TriviaStanding;
+use crate::features::analytics::runtime::{spawn_tracked, TaskKind};
+use crate::features::analytics::CostBucket;
+use crate::features::personas::apply_theme;
+use crate::features::personas::themes::ACTIVE_THEME_SETTING;
+use crate::features::trivia::{
+    cancel_session, end_session, parse_quiz, quiz_prompt, run_game, session_host, start_session,
+    TriviaGame, DEFAULT_ANSWER_SECONDS, DEFAULT_QUESTIONS, MAX_ANSWER_SECONDS, MAX_QUESTIONS,
+    MIN_ANSWER_SECONDS, TRIVIA_FEATURE,
+};
+
+/// Topic used when /trivia start is given none
+const DEFAULT_TOPIC: &str = "general knowledge";
+
+/// Players shown by /trivia leaderboard
+const LEADERBOARD_SIZE: usize = 10;
+
+/// Handler for /trivia command
+pub struct TriviaHandler;
+
+#[async_trait]
+impl SlashCommandHandler for TriviaHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["trivia"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        self.handle_trivia(&ctx, serenity_ctx, command).await
+    }
+}
+
+impl TriviaHandler {
+    /// Handle /trivia start, stop and leaderboard
+    async fn handle_trivia(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let Some(guild_id) = command.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+
+        if !ctx
+            .database
+            .is_feature_enabled(TRIVIA_FEATURE, None, Some(&guild_id))
+            .await?
+        {
+            return Self::reply(
+                &responder,
+                serenity_ctx,
+                "❌ Trivia is disabled on this server.".to_string(),
+                true,
+            )
+            .await;
+        }
+
+        match subcommand.name.as_str() {
+            "start" => {
+                self.handle_start(ctx, serenity_ctx, command, &responder, &guild_id)
+                    .await
+            }
+            "stop" => {
+                let channel_id = command.channel_id.0;
+                let user_id = command.user.id.to_string();
+                let can_manage = command
+                    .member
+                    .as_ref()
+                    .and_then(|m| m.permissions)
+                    .is_some_and(|p| p.manage_messages());
+                let content = match session_host(channel_id) {
+                    None => "There's no trivia game running in this channel.".to_string(),
+                    Some(host) if host != user_id && !can_manage => {
+                        "Only the member who started the game (or a moderator) can stop it."
+                            .to_string()
+                    }
+                    Some(_) => {
+                        cancel_session(channel_id);
+                        info!("/trivia stop | User: {user_id} | Channel: {channel_id}");
+                        "🛑 Stopping after the current question.".to_string()
+                    }
+                };
+                Self::reply(&responder, serenity_ctx, content, true).await
+            }
+            _ => {
+                let standings = ctx
+                    .database
+                    .get_trivia_leaderboard(&guild_id, LEADERBOARD_SIZE)
+                    .await?;
+                Self::reply(
+                    &responder,
+                    serenity_ctx,
+                    Self::format_leaderboard(&standings),
+                    false,
+                )
+                .await
+            }
+        }
+    }
+
+    /// Generate the quiz and start the game task
+    async fn handle_start(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        responder: &InteractionResponder,
+        guild_id: &str,
+    ) -> Result<()> {
+        let request_id = Uuid::new_v4();
+        let channel_id = command.channel_id;
+        let user_id = command.user.id.to_string();
+        let options = &command.data.options[0].options;
+        let topic = get_string_option(options, "topic")
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .unwrap_or_else(|| DEFAULT_TOPIC.to_string());
+        let count = get_integer_option(options, "questions")
+            .map(|n| n.clamp(1, MAX_QUESTIONS as i64) as usize)
+            .unwrap_or(DEFAULT_QUESTIONS);
+        let seconds = get_integer_option(options, "seconds")
+            .map(|s| s.clamp(MIN_ANSWER_SECONDS as i64, MAX_ANSWER_SECONDS as i64) as u64)
+            .unwrap_or(DEFAULT_ANSWER_SECONDS);
+
+        let persona_id = match get_string_option(options, "host") {
+            Some(id) => id,
+            None => {
+                ctx.database
+                    .get_persona_with_channel(&user_id, guild_id, &channel_id.to_string(), None)
+                    .await?
+            }
+        };
+        let Some(persona) = ctx.persona_manager.get_persona(&persona_id) else {
+            return Self::reply(
+                responder,
+                serenity_ctx,
+                format!("Unknown persona: `{persona_id}`"),
+                true,
+            )
+            .await;
+        };
+        let (host_name, host_color) = (persona.name.clone(), persona.color);
+
+        if !start_session(channel_id.0, guild_id, &user_id) {
+            return Self::reply(
+                responder,
+                serenity_ctx,
+                "A trivia game is already running in this channel.".to_string(),
+                true,
+            )
+            .await;
+        }
+        info!(
+            "[{request_id}] /trivia start | User: {user_id} | Channel: {channel_id} | Host: {persona_id} | Topic: {topic} | {count}x{seconds}s"
+        );
+        // The channel is claimed from here on, so release it on any early exit
+        if let Err(e) = responder.defer(&serenity_ctx.http).await {
+            end_session(channel_id.0);
+            return Err(e);
+        }
+
+        let theme = ctx
+            .database
+            .get_guild_setting(guild_id, ACTIVE_THEME_SETTING)
+            .await
+            .ok()
+            .flatten();
+        let persona_prompt = apply_theme(
+            ctx.persona_manager.get_system_prompt(&persona_id, None),
+            theme.as_deref(),
+        );
+        let mut quiz = match ctx
+            .get_ai_response(
+                &quiz_prompt(&persona_prompt, &host_name, &topic, count),
+                &format!("Write the trivia questions about {topic}."),
+                vec![],
+                request_id,
+                Some(&user_id),
+                Some(guild_id),
+                Some(&channel_id.to_string()),
+                CostBucket::Trivia,
+            )
+            .await
+        {
+            Ok(raw) => parse_quiz(&raw),
+            Err(e) => {
+                warn!("[{request_id}] Trivia question generation failed: {e}");
+                Default::default()
+            }
+        };
+
+        if quiz.questions.is_empty() {
+            end_session(channel_id.0);
+            return Self::reply(
+                responder,
+                serenity_ctx,
+                format!(
+                    "❌ {host_name} couldn't come up with questions about that. Try another topic?"
+                ),
+                true,
+            )
+            .await;
+        }
+
+        quiz.questions.truncate(count);
+        let total = quiz.questions.len();
+        Self::reply(
+            responder,
+            serenity_ctx,
+            format!("🎲 **{host_name}** is hosting {total} questions on **{topic}**. Get ready!"),
+            false,
+        )
+        .await?;
+
+        let game = TriviaGame {
+            channel_id,
+            guild_id: guild_id.to_string(),
+            topic,
+            host_name,
+            host_color,
+            quiz,
+            window: Duration::from_secs(seconds),
+            database: ctx.database.clone(),
+        };
+        spawn_tracked(
+            TaskKind::Discussions,
+            run_game(game, serenity_ctx.http.clone()),
+        );
+        Ok(())
+    }
+
+    async fn reply(
+        responder: &InteractionResponder,
+        serenity_ctx: &Context,
+        content: String,
+        ephemeral: bool,
+    ) -> Result<()> {
+        responder
+            .create_interaction_response(&serenity_ctx.http, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|m| m.content(content).ephemeral(ephemeral))
+            })
+            .await?;
+        Ok(())
+    }
+
+    fn format_leaderboard(standings: &[TriviaStanding]) -> String {
+        let mut output = "🏆 **Trivia leaderboard**\n".to_string();
+        if standings.is_empty() {
+            output.push_str("No games have been played here yet. Start one with `/trivia start`!");
+            return output;
+        }
+        for (i, standing) in standings.iter().enumerate() {
+            output.push_str(&format!(
+                "{}. <@{}>: **{}** pts ({} correct, {} won of {} games)\n",
+                i + 1,
+                standing.user_id,
+                standing.points,
+                standing.correct_answers,
+                standing.games_won,
+                standing.games_played
+            ));
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trivia_handler_commands() {
+        let handler = TriviaHandler;
+        assert_eq!(handler.command_names(), &["trivia"]);
+    }
+
+    #[test]
+    fn test_format_leaderboard() {
+        let standings = vec![
+            TriviaStanding {
+                user_id: "1".to_string(),
+                points: 42,
+                correct_answers: 3,
+                games_played: 2,
+                games_won: 1,
+            },
+            TriviaStanding {
+                user_id: "2".to_string(),
+                points: 10,
+                correct_answers: 1,
+                games_played: 1,
+                games_won: 0,
+            },
+        ];
+        let output = TriviaHandler::format_leaderboard(&standings);
+        assert!(output.contains("1. <@1>: **42** pts (3 correct, 1 won of 2 games)"));
+        assert!(output.find("<@1>").unwrap() < output.find("<@2>").unwrap());
+
+        let empty = TriviaHandler::format_leaderboard(&[]);
+        assert!(empty.contains("No games have been played here yet"));
+    }
+}
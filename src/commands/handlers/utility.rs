@@ -2,10 +2,13 @@
 //!
 //! Handles: ping, help, status, version, uptime
 //!
-//! - **Version**: 1.0.0
+//! - **Version**: 1.3.0
 //! - **Since**: 3.38.0
 //!
 //! ## Changelog
+//! - 1.3.0: Initial responses go through InteractionResponder (auto-defer safe)
+//! - 1.2.0: /status lists the most-used commands with latency and error counts
+//! - 1.1.0: /status reports circuit breaker state per external dependency
 //! - 1.0.0: Extracted from command_handler.rs
 
 use anyhow::Result;
@@ -18,6 +21,9 @@ use std::sync::Arc;
 
 use crate::commands::context::CommandContext;
 use crate::commands::handler::SlashCommandHandler;
+use crate::commands::middleware::format_command_metrics;
+use crate::commands::responder::InteractionResponder;
+use crate::features::resilience::{circuit_breakers, format_breaker_status};
 use crate::message_components::MessageComponentHandler;
 
 /// Handler for utility commands: ping, help, status, version, uptime
@@ -54,10 +60,14 @@ impl UtilityHandler {
         serenity_ctx: &Context,
         command: &ApplicationCommandInteraction,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let user_id = command.user.id.to_string();
-        ctx.database.log_usage(&user_id, "ping", None).await?;
+        let guild_id = command.guild_id.map(|id| id.to_string());
+        ctx.database
+            .log_usage(&user_id, guild_id.as_deref(), "ping", None)
+            .await?;
 
-        command
+        responder
             .create_interaction_response(&serenity_ctx.http, |response| {
                 response
                     .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -75,6 +85,7 @@ impl UtilityHandler {
         serenity_ctx: &Context,
         command: &ApplicationCommandInteraction,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let help_text = r#"**Available Slash Commands:**
 `/ping` - Test bot responsiveness
 `/help` - Show this help message
@@ -95,7 +106,7 @@ impl UtilityHandler {
 **Interactive Features:**
 Use the buttons below for more help or to try custom prompts!"#;
 
-        command
+        responder
             .create_interaction_response(&serenity_ctx.http, |response| {
                 response
                     .kind(InteractionResponseType::ChannelMessageWithSource)
@@ -117,6 +128,7 @@ Use the buttons below for more help or to try custom prompts!"#;
         serenity_ctx: &Context,
         command: &ApplicationCommandInteraction,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let user_id = command.user.id.to_string();
 
         let uptime = ctx.start_time.elapsed();
@@ -128,21 +140,28 @@ Use the buttons below for more help or to try custom prompts!"#;
             "**Bot Status**\n\
             ✅ Online and operational\n\
             ⏱️ Uptime: {}h {}m {}s\n\
-            📦 Version: {}",
+            📦 Version: {}\n\n\
+            **Dependencies**\n{}\n\n\
+            **Top Commands**\n{}",
             hours,
             minutes,
             seconds,
-            crate::features::get_bot_version()
+            crate::features::get_bot_version(),
+            format_breaker_status(&circuit_breakers().snapshot()),
+            format_command_metrics(&ctx.command_metrics.snapshot(), 5)
         );
 
-        command
+        responder
             .create_interaction_response(&serenity_ctx.http, |r| {
                 r.kind(InteractionResponseType::ChannelMessageWithSource)
                     .interaction_response_data(|m| m.content(response))
             })
             .await?;
 
-        ctx.database.log_usage(&user_id, "status", None).await?;
+        let guild_id = command.guild_id.map(|id| id.to_string());
+        ctx.database
+            .log_usage(&user_id, guild_id.as_deref(), "status", None)
+            .await?;
         info!("Status command completed for user {user_id}");
         Ok(())
     }
@@ -154,6 +173,7 @@ Use the buttons below for more help or to try custom prompts!"#;
         serenity_ctx: &Context,
         command: &ApplicationCommandInteraction,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let user_id = command.user.id.to_string();
 
         let mut output = format!(
@@ -166,14 +186,17 @@ Use the buttons below for more help or to try custom prompts!"#;
             output.push_str(&format!("• {} v{}\n", feature.name, feature.version));
         }
 
-        command
+        responder
             .create_interaction_response(&serenity_ctx.http, |r| {
                 r.kind(InteractionResponseType::ChannelMessageWithSource)
                     .interaction_response_data(|m| m.content(output))
             })
             .await?;
 
-        ctx.database.log_usage(&user_id, "version", None).await?;
+        let guild_id = command.guild_id.map(|id| id.to_string());
+        ctx.database
+            .log_usage(&user_id, guild_id.as_deref(), "version", None)
+            .await?;
         info!("Version command completed for user {user_id}");
         Ok(())
     }
@@ -185,6 +208,7 @@ Use the buttons below for more help or to try custom prompts!"#;
         serenity_ctx: &Context,
         command: &ApplicationCommandInteraction,
     ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
         let user_id = command.user.id.to_string();
 
         let uptime = ctx.start_time.elapsed();
@@ -203,14 +227,17 @@ Use the buttons below for more help or to try custom prompts!"#;
             format!("⏱️ Uptime: {seconds}s")
         };
 
-        command
+        responder
             .create_interaction_response(&serenity_ctx.http, |r| {
                 r.kind(InteractionResponseType::ChannelMessageWithSource)
                     .interaction_response_data(|m| m.content(response))
             })
             .await?;
 
-        ctx.database.log_usage(&user_id, "uptime", None).await?;
+        let guild_id = command.guild_id.map(|id| id.to_string());
+        ctx.database
+            .log_usage(&user_id, guild_id.as_deref(), "uptime", None)
+            .await?;
         info!("Uptime command completed for user {user_id}");
         Ok(())
     }
@@ -0,0 +1,207 @@
+//! Verification command handler
+//!
+//! Handles: verification
+//!
+//! Members with Manage Server pick the gated channel, the member role, the
+//! verification mode and the kick timeout. Prompts, answers and kicks are
+//! handled by the verification gate, buttons and scheduler.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use serenity::model::application::interaction::application_command::{
+    ApplicationCommandInteraction, CommandDataOption,
+};
+use serenity::prelude::Context;
+use std::sync::Arc;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::{
+    get_channel_option, get_integer_option, get_role_option, get_string_option,
+};
+use crate::features::verification::{
+    VerificationMode, CHANNEL_SETTING, MAX_TIMEOUT_MINUTES, MODE_SETTING, ROLE_SETTING,
+    TIMEOUT_SETTING, VERIFICATION_FEATURE,
+};
+
+/// Handler for /verification
+pub struct VerificationHandler;
+
+#[async_trait]
+impl SlashCommandHandler for VerificationHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["verification"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let Some(guild_id) = command.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        let can_manage = command
+            .member
+            .as_ref()
+            .and_then(|m| m.permissions)
+            .is_some_and(|p| p.manage_guild());
+        if !can_manage {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "❌ You need the Manage Server permission to configure verification.",
+                )
+                .await;
+        }
+        if !ctx
+            .database
+            .is_feature_enabled(VERIFICATION_FEATURE, None, Some(&guild_id))
+            .await?
+        {
+            return responder
+                .reply_ephemeral(serenity_ctx, "❌ Verification is disabled on this server.")
+                .await;
+        }
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+        let user_id = command.user.id.to_string();
+
+        let content = match subcommand.name.as_str() {
+            "setup" => Self::handle_setup(&ctx, &guild_id, &subcommand.options).await?,
+            "status" => Self::handle_status(&ctx, &guild_id).await?,
+            _ => {
+                for setting in [CHANNEL_SETTING, ROLE_SETTING, MODE_SETTING, TIMEOUT_SETTING] {
+                    ctx.database
+                        .delete_guild_setting(&guild_id, setting)
+                        .await?;
+                }
+                let cleared = ctx.database.clear_pending_verifications(&guild_id).await?;
+                info!("/verification disable | User: {user_id} | Guild: {guild_id}");
+                format!(
+                    "✅ New members are no longer gated. {cleared} pending verification{} cleared.",
+                    if cleared == 1 { " was" } else { "s were" }
+                )
+            }
+        };
+        responder.reply_ephemeral(serenity_ctx, &content).await
+    }
+}
+
+impl VerificationHandler {
+    /// Handle /verification setup, returning the reply
+    async fn handle_setup(
+        ctx: &CommandContext,
+        guild_id: &str,
+        options: &[CommandDataOption],
+    ) -> Result<String> {
+        let (Some(channel_id), Some(role_id)) = (
+            get_channel_option(options, "channel"),
+            get_role_option(options, "role"),
+        ) else {
+            return Ok("❌ Pick a gated channel and a member role.".to_string());
+        };
+        let mode = VerificationMode::from_setting(get_string_option(options, "mode").as_deref());
+        let timeout = get_integer_option(options, "timeout_minutes")
+            .unwrap_or(0)
+            .clamp(0, MAX_TIMEOUT_MINUTES);
+
+        let db = &ctx.database;
+        db.set_guild_setting(guild_id, CHANNEL_SETTING, &channel_id.to_string())
+            .await?;
+        db.set_guild_setting(guild_id, ROLE_SETTING, &role_id.to_string())
+            .await?;
+        db.set_guild_setting(guild_id, MODE_SETTING, mode.as_str())
+            .await?;
+        db.set_guild_setting(guild_id, TIMEOUT_SETTING, &timeout.to_string())
+            .await?;
+        info!(
+            "/verification setup | Guild: {guild_id} | Channel: {channel_id} | Role: {role_id} | {}",
+            mode.as_str()
+        );
+
+        Ok(format!(
+            "✅ New members will {} in <#{channel_id}> to get <@&{role_id}>. {}\n\
+            Make sure <#{channel_id}> is the only channel unverified members can see, that my role is above <@&{role_id}>, \
+            and that the bot runs with the Server Members intent so it sees new members join.",
+            mode.label(),
+            Self::timeout_text(timeout)
+        ))
+    }
+
+    /// Handle /verification status, returning the reply
+    async fn handle_status(ctx: &CommandContext, guild_id: &str) -> Result<String> {
+        let db = &ctx.database;
+        let (Some(channel_id), Some(role_id)) = (
+            db.get_guild_setting(guild_id, CHANNEL_SETTING).await?,
+            db.get_guild_setting(guild_id, ROLE_SETTING).await?,
+        ) else {
+            return Ok(
+                "New members aren't gated. Turn it on with `/verification setup`.".to_string(),
+            );
+        };
+        let mode = VerificationMode::from_setting(
+            db.get_guild_setting(guild_id, MODE_SETTING)
+                .await?
+                .as_deref(),
+        );
+        let timeout = db
+            .get_guild_setting(guild_id, TIMEOUT_SETTING)
+            .await?
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0);
+        let pending = db.count_pending_verifications(guild_id).await?;
+        Ok(format!(
+            "🚪 **Verification gate**\n\
+            New members {} in <#{channel_id}> to get <@&{role_id}>.\n\
+            {}\n\
+            Waiting to verify: **{pending}**",
+            mode.label(),
+            Self::timeout_text(timeout)
+        ))
+    }
+
+    fn timeout_text(minutes: i64) -> String {
+        if minutes > 0 {
+            format!("Members who haven't verified after {minutes} minutes are kicked.")
+        } else {
+            "Unverified members are never kicked.".to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verification_handler_commands() {
+        let handler = VerificationHandler;
+        assert_eq!(handler.command_names(), &["verification"]);
+    }
+
+    #[test]
+    fn test_timeout_text() {
+        assert_eq!(
+            VerificationHandler::timeout_text(30),
+            "Members who haven't verified after 30 minutes are kicked."
+        );
+        assert_eq!(
+            VerificationHandler::timeout_text(0),
+            "Unverified members are never kicked."
+        );
+    }
+}
@@ -0,0 +1,338 @@
+//! Webhooks command handler
+//!
+//! Handles: webhooks
+//!
+//! Manages the outbound webhooks a guild sends bot events to, and the daily
+//! OpenAI budget behind the `budget_threshold` event. URLs often carry a
+//! secret, so only their host is ever shown back.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use serenity::model::application::interaction::application_command::{
+    ApplicationCommandInteraction, CommandDataOption,
+};
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::prelude::Context;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::commands::context::CommandContext;
+use crate::commands::handler::SlashCommandHandler;
+use crate::commands::responder::InteractionResponder;
+use crate::commands::slash::{get_integer_option, get_number_option, get_string_option};
+use crate::database::{GuildWebhook, WebhookFormat};
+use crate::features::webhooks::budget::{BUDGET_NOTIFIED_SETTING, BUDGET_SETTING};
+use crate::features::webhooks::{
+    deliver, parse_events, validate_url, WebhookEvent, MAX_TEMPLATE_CHARS, MAX_WEBHOOKS_PER_GUILD,
+    WEBHOOKS_FEATURE,
+};
+
+/// Handler for /webhooks
+pub struct WebhooksHandler;
+
+#[async_trait]
+impl SlashCommandHandler for WebhooksHandler {
+    fn command_names(&self) -> &'static [&'static str] {
+        &["webhooks"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let request_id = Uuid::new_v4();
+        self.handle_webhooks(&ctx, serenity_ctx, command, request_id)
+            .await
+    }
+}
+
+impl WebhooksHandler {
+    /// Route /webhooks subcommands
+    async fn handle_webhooks(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        request_id: Uuid,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let Some(guild_id) = command.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        let can_manage = command
+            .member
+            .as_ref()
+            .and_then(|m| m.permissions)
+            .is_some_and(|p| p.manage_guild());
+        if !can_manage {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    "❌ You need the Manage Server permission to manage webhooks.",
+                )
+                .await;
+        }
+        if !ctx
+            .database
+            .is_feature_enabled(WEBHOOKS_FEATURE, None, Some(&guild_id))
+            .await?
+        {
+            return responder
+                .reply_ephemeral(serenity_ctx, "❌ Webhooks are disabled on this server.")
+                .await;
+        }
+
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Missing subcommand"))?;
+        let options = &subcommand.options;
+
+        let content = match subcommand.name.as_str() {
+            "add" => {
+                let webhooks = ctx.database.get_guild_webhooks(&guild_id).await?;
+                if webhooks.len() >= MAX_WEBHOOKS_PER_GUILD {
+                    format!(
+                        "❌ This server already has {MAX_WEBHOOKS_PER_GUILD} webhooks. Remove one first."
+                    )
+                } else {
+                    match Self::parse_add(options) {
+                        Ok((url, format, events, template)) => {
+                            let keys: Vec<&str> = events.iter().map(|e| e.key()).collect();
+                            let id = ctx
+                                .database
+                                .add_guild_webhook(
+                                    &guild_id,
+                                    &url,
+                                    format,
+                                    &keys,
+                                    template.as_deref(),
+                                    &command.user.id.to_string(),
+                                )
+                                .await?;
+                            info!(
+                                "[{request_id}] /webhooks add | User: {} | Guild: {guild_id} | #{id} {} {}",
+                                command.user.id,
+                                format.key(),
+                                keys.join(",")
+                            );
+                            format!(
+                                "✅ Webhook `#{id}` ({}) will receive {}.\nTry it with `/webhooks test id:{id}`.",
+                                url_host(&url),
+                                Self::format_events(&keys)
+                            )
+                        }
+                        Err(message) => format!("❌ {message}"),
+                    }
+                }
+            }
+            "list" => {
+                let webhooks = ctx.database.get_guild_webhooks(&guild_id).await?;
+                let budget = ctx
+                    .database
+                    .get_guild_setting(&guild_id, BUDGET_SETTING)
+                    .await?
+                    .and_then(|v| v.parse::<f64>().ok());
+                Self::format_list(&webhooks, budget)
+            }
+            "remove" => {
+                let id = get_integer_option(options, "id").unwrap_or_default();
+                if ctx.database.delete_guild_webhook(&guild_id, id).await? {
+                    format!("🗑️ Webhook `#{id}` removed.")
+                } else {
+                    format!("❌ There's no webhook `#{id}` on this server.")
+                }
+            }
+            "test" => {
+                let id = get_integer_option(options, "id").unwrap_or_default();
+                return self
+                    .handle_test(ctx, serenity_ctx, command, &guild_id, id)
+                    .await;
+            }
+            "budget" => {
+                let usd = get_number_option(options, "usd").unwrap_or_default();
+                if usd <= 0.0 {
+                    ctx.database
+                        .delete_guild_setting(&guild_id, BUDGET_SETTING)
+                        .await?;
+                    "✅ Daily budget alerts are off.".to_string()
+                } else {
+                    ctx.database
+                        .set_guild_setting(&guild_id, BUDGET_SETTING, &format!("{usd:.2}"))
+                        .await?;
+                    // A new budget may be crossed again today
+                    ctx.database
+                        .delete_guild_setting(&guild_id, BUDGET_NOTIFIED_SETTING)
+                        .await?;
+                    format!(
+                        "✅ `budget_threshold` webhooks fire once a day when this server's OpenAI spend passes ${usd:.2}."
+                    )
+                }
+            }
+            _ => "Unknown subcommand.".to_string(),
+        };
+        responder.reply_ephemeral(serenity_ctx, &content).await
+    }
+
+    /// Send a sample of the webhook's first event and report the outcome
+    async fn handle_test(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        guild_id: &str,
+        id: i64,
+    ) -> Result<()> {
+        let responder = InteractionResponder::for_command(command);
+        let webhooks = ctx.database.get_guild_webhooks(guild_id).await?;
+        let Some(webhook) = webhooks.into_iter().find(|w| w.id == id) else {
+            return responder
+                .reply_ephemeral(
+                    serenity_ctx,
+                    &format!("❌ There's no webhook `#{id}` on this server."),
+                )
+                .await;
+        };
+        let event = webhook
+            .events
+            .iter()
+            .find_map(|key| WebhookEvent::from_key(key))
+            .unwrap_or(WebhookEvent::JobCompleted);
+
+        // Retries can take longer than Discord waits for a reply
+        responder
+            .create_interaction_response(&serenity_ctx.http, |response| {
+                response
+                    .kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                    .interaction_response_data(|message| message.ephemeral(true))
+            })
+            .await?;
+
+        let outcome = deliver(&webhook, event, &event.sample_fields()).await;
+        ctx.database
+            .set_webhook_last_error(webhook.id, outcome.as_ref().err().map(String::as_str))
+            .await?;
+        let content = match outcome {
+            Ok(()) => format!(
+                "✅ Sent a sample `{}` event to webhook `#{id}`.",
+                event.key()
+            ),
+            Err(e) => format!("❌ Webhook `#{id}` didn't accept the sample: {e}"),
+        };
+        responder.reply_ephemeral(serenity_ctx, &content).await
+    }
+
+    fn parse_add(
+        options: &[CommandDataOption],
+    ) -> Result<(String, WebhookFormat, Vec<WebhookEvent>, Option<String>), String> {
+        let url = get_string_option(options, "url")
+            .map(|u| u.trim().to_string())
+            .unwrap_or_default();
+        validate_url(&url)?;
+        let events = parse_events(&get_string_option(options, "events").unwrap_or_default())?;
+        let format = get_string_option(options, "format")
+            .and_then(|f| WebhookFormat::from_key(&f))
+            .unwrap_or(WebhookFormat::Generic);
+        let template = get_string_option(options, "template")
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty());
+        if template
+            .as_ref()
+            .is_some_and(|t| t.chars().count() > MAX_TEMPLATE_CHARS)
+        {
+            return Err(format!(
+                "Templates can be at most {MAX_TEMPLATE_CHARS} characters."
+            ));
+        }
+        Ok((url, format, events, template))
+    }
+
+    fn format_events(keys: &[impl AsRef<str>]) -> String {
+        keys.iter()
+            .map(|k| format!("`{}`", k.as_ref()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn format_list(webhooks: &[GuildWebhook], budget: Option<f64>) -> String {
+        let mut lines = Vec::new();
+        if webhooks.is_empty() {
+            lines.push("No webhooks yet. Add one with `/webhooks add`.".to_string());
+        }
+        for webhook in webhooks {
+            let mut line = format!(
+                "`#{}` **{}** ({}): {}",
+                webhook.id,
+                url_host(&webhook.url),
+                webhook.format.key(),
+                Self::format_events(&webhook.events)
+            );
+            if webhook.template.is_some() {
+                line.push_str(" · custom template");
+            }
+            if let Some(error) = &webhook.last_error {
+                line.push_str(&format!("\n  ⚠️ Last delivery failed: {error}"));
+            }
+            lines.push(line);
+        }
+        lines.push(match budget {
+            Some(budget) => format!("Daily budget: ${budget:.2}"),
+            None => "Daily budget: not set".to_string(),
+        });
+        lines.join("\n")
+    }
+}
+
+/// Host part of a webhook URL, so tokens in the path stay hidden
+fn url_host(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_else(|| "invalid URL".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn webhook() -> GuildWebhook {
+        GuildWebhook {
+            id: 3,
+            guild_id: "g1".to_string(),
+            url: "https://hooks.slack.com/services/T000/B000/secret".to_string(),
+            format: WebhookFormat::Slack,
+            events: vec!["job_completed".to_string()],
+            template: None,
+            last_error: Some("HTTP 404 Not Found".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_webhooks_handler_commands() {
+        let handler = WebhooksHandler;
+        assert_eq!(handler.command_names(), &["webhooks"]);
+    }
+
+    #[test]
+    fn test_list_hides_url_path() {
+        let list = WebhooksHandler::format_list(&[webhook()], Some(5.0));
+        assert!(list.contains("`#3` **hooks.slack.com** (slack): `job_completed`"));
+        assert!(list.contains("Last delivery failed: HTTP 404 Not Found"));
+        assert!(list.contains("Daily budget: $5.00"));
+        assert!(!list.contains("secret"));
+
+        let empty = WebhooksHandler::format_list(&[], None);
+        assert!(empty.contains("/webhooks add"));
+        assert!(empty.contains("not set"));
+    }
+}
@@ -0,0 +1,449 @@
+//! Command middleware and per-handler metrics
+//!
+//! Middleware runs around every registry dispatch: `before` can short-circuit a
+//! command (after responding to the user itself, through
+//! `InteractionResponder::for_command` since the dispatch may already have
+//! been auto-deferred), `after` sees the outcome and latency. Metrics are
+//! recorded by the registry for every dispatched command.
+//!
+//! - **Version**: 1.3.2
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.3.2: Refusals are sent through the dispatch's InteractionResponder, so they edit an auto-deferred reply
+//! - 1.3.1: SimilarityMiddleware finds prompts inside subcommands, such as /alias run and /prompts use
+//! - 1.3.0: access_permitted shares the /admin access check with component dispatch
+//! - 1.2.0: SimilarityMiddleware cools down users repeating near-identical prompts
+//! - 1.1.0: AccessMiddleware enforces /admin block and allow lists
+//! - 1.0.0: Initial release with RateLimitMiddleware and CommandMetrics
+
+use anyhow::Result;
+use async_trait::async_trait;
+use dashmap::DashMap;
+use log::{info, warn};
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::application::interaction::application_command::{
+    ApplicationCommandInteraction, CommandDataOption,
+};
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::model::id::{ChannelId, GuildId, UserId};
+use serenity::prelude::Context;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::context::{channel_scope_ids, CommandContext};
+use super::responder::InteractionResponder;
+use super::slash::get_string_option;
+use crate::database::Database;
+use crate::features::anon_questions::loggable_user;
+use crate::features::rate_limiting::{
+    throttle_warning_embed, RateLimiter, SimilarityThrottle, ThrottleDecision,
+};
+
+/// Whether dispatch should continue after a middleware's `before` hook
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    Continue,
+    /// Stop dispatch; the middleware has already responded to the interaction
+    Stop,
+}
+
+/// Hook run around every slash command dispatched through the registry
+#[async_trait]
+pub trait CommandMiddleware: Send + Sync {
+    /// Name used in logs
+    fn name(&self) -> &'static str;
+
+    /// Runs before the handler, in registration order
+    async fn before(
+        &self,
+        _ctx: &CommandContext,
+        _serenity_ctx: &Context,
+        _command: &ApplicationCommandInteraction,
+    ) -> Result<Flow> {
+        Ok(Flow::Continue)
+    }
+
+    /// Runs after the handler (not called when dispatch was stopped)
+    async fn after(
+        &self,
+        _command: &ApplicationCommandInteraction,
+        _elapsed: Duration,
+        _result: &Result<()>,
+    ) {
+    }
+}
+
+/// Per-user slash command throttling (shares its limiter with message handling)
+pub struct RateLimitMiddleware {
+    limiter: Arc<RateLimiter>,
+}
+
+impl RateLimitMiddleware {
+    pub fn new(limiter: Arc<RateLimiter>) -> Self {
+        Self { limiter }
+    }
+}
+
+#[async_trait]
+impl CommandMiddleware for RateLimitMiddleware {
+    fn name(&self) -> &'static str {
+        "rate_limit"
+    }
+
+    async fn before(
+        &self,
+        _ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<Flow> {
+        let user_id = command.user.id.to_string();
+        if self.limiter.wait_for_rate_limit(&user_id).await {
+            return Ok(Flow::Continue);
+        }
+
+        warn!("🚫 Rate limit exceeded for user: {user_id} in slash command");
+        InteractionResponder::for_command(command)
+            .create_interaction_response(&serenity_ctx.http, |response| {
+                response
+                    .kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|message| {
+                        message.content("You're sending commands too quickly! Please slow down.")
+                    })
+            })
+            .await?;
+        info!("✅ Rate limit response sent successfully");
+        Ok(Flow::Stop)
+    }
+}
+
+/// Near-duplicate prompt throttling for commands that send a prompt to the model
+/// (shares its throttle with message handling)
+pub struct SimilarityMiddleware {
+    throttle: Arc<SimilarityThrottle>,
+}
+
+impl SimilarityMiddleware {
+    pub fn new(throttle: Arc<SimilarityThrottle>) -> Self {
+        Self { throttle }
+    }
+}
+
+#[async_trait]
+impl CommandMiddleware for SimilarityMiddleware {
+    fn name(&self) -> &'static str {
+        "similarity"
+    }
+
+    async fn before(
+        &self,
+        _ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<Flow> {
+        let Some(prompt) = prompt_text(&command.data.options) else {
+            return Ok(Flow::Continue);
+        };
+
+        let user_id = command.user.id.to_string();
+        let content = match self.throttle.check(&user_id, &prompt) {
+            ThrottleDecision::Allow => return Ok(Flow::Continue),
+            ThrottleDecision::Warn { cooldown, strikes } => {
+                warn!(
+                    "🔁 Repeated prompts from {} in /{}, strike {strikes}",
+                    loggable_user(&command.data.name, &user_id),
+                    command.data.name
+                );
+                InteractionResponder::for_command(command)
+                    .create_interaction_response(&serenity_ctx.http, |response| {
+                        response
+                            .kind(InteractionResponseType::ChannelMessageWithSource)
+                            .interaction_response_data(|message| {
+                                message
+                                    .add_embed(throttle_warning_embed(cooldown, strikes))
+                                    .ephemeral(true)
+                            })
+                    })
+                    .await?;
+                return Ok(Flow::Stop);
+            }
+            ThrottleDecision::Cooldown { remaining } => format!(
+                "You're cooling down after repeating the same prompt. Try again in {}s.",
+                remaining.as_secs().max(1)
+            ),
+        };
+        InteractionResponder::for_command(command)
+            .create_interaction_response(&serenity_ctx.http, |response| {
+                response
+                    .kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|message| message.content(content).ephemeral(true))
+            })
+            .await?;
+        Ok(Flow::Stop)
+    }
+}
+
+/// Options whose text reaches the model, directly or filling in a saved prompt
+const PROMPT_OPTIONS: [&str; 4] = ["prompt", "question", "args", "variables"];
+
+/// The prompt a command sends, looking inside subcommands and subcommand groups
+///
+/// A saved alias or template `name` next to the prompt is part of the text,
+/// so running the same one with the same input counts as a repeat.
+fn prompt_text(options: &[CommandDataOption]) -> Option<String> {
+    if let Some(subcommand) = options.iter().find(|option| {
+        matches!(
+            option.kind,
+            CommandOptionType::SubCommand | CommandOptionType::SubCommandGroup
+        )
+    }) {
+        return prompt_text(&subcommand.options);
+    }
+    let prompt = PROMPT_OPTIONS
+        .iter()
+        .find_map(|name| get_string_option(options, name))?;
+    Some(match get_string_option(options, "name") {
+        Some(name) => format!("{name} {prompt}"),
+        None => prompt,
+    })
+}
+
+/// Applies the guild's /admin block and allow lists to slash commands
+///
+/// `/admin` itself is exempt so server admins can always edit the lists.
+pub struct AccessMiddleware;
+
+#[async_trait]
+impl CommandMiddleware for AccessMiddleware {
+    fn name(&self) -> &'static str {
+        "access"
+    }
+
+    async fn before(
+        &self,
+        ctx: &CommandContext,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<Flow> {
+        if command.data.name == "admin" {
+            return Ok(Flow::Continue);
+        }
+        let permitted = access_permitted(
+            &ctx.database,
+            serenity_ctx,
+            command.guild_id,
+            command.channel_id,
+            command.user.id,
+        )
+        .await?;
+        if permitted {
+            return Ok(Flow::Continue);
+        }
+
+        info!(
+            "🚫 /{} from {} refused by access rules",
+            command.data.name,
+            loggable_user(&command.data.name, &command.user.id.to_string())
+        );
+        InteractionResponder::for_command(command)
+            .create_interaction_response(&serenity_ctx.http, |response| {
+                response
+                    .kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|message| {
+                        message.content(ACCESS_REFUSED_MESSAGE).ephemeral(true)
+                    })
+            })
+            .await?;
+        Ok(Flow::Stop)
+    }
+}
+
+/// Reply to interactions refused by the access rules
+pub const ACCESS_REFUSED_MESSAGE: &str = "I'm not available to you in this channel.";
+
+/// Whether the guild's /admin block and allow lists let `user_id` use the bot
+/// in `channel_id` (always true outside guilds)
+pub async fn access_permitted(
+    database: &Database,
+    serenity_ctx: &Context,
+    guild_id: Option<GuildId>,
+    channel_id: ChannelId,
+    user_id: UserId,
+) -> Result<bool> {
+    let Some(guild_id) = guild_id else {
+        return Ok(true);
+    };
+    let rules = database.get_access_rules(&guild_id.to_string()).await?;
+    if rules.is_empty() {
+        return Ok(true);
+    }
+    let channels = if rules.has_channel_rules() {
+        channel_scope_ids(serenity_ctx, channel_id).await
+    } else {
+        Vec::new()
+    };
+    Ok(rules.permits(&user_id.to_string(), &channels))
+}
+
+/// Aggregated statistics for one command name
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommandStats {
+    pub name: String,
+    pub invocations: u64,
+    pub errors: u64,
+    pub total_time: Duration,
+    pub max_time: Duration,
+}
+
+impl CommandStats {
+    pub fn average_time(&self) -> Duration {
+        if self.invocations == 0 {
+            Duration::ZERO
+        } else {
+            self.total_time / self.invocations as u32
+        }
+    }
+}
+
+/// Invocation counts, error counts and latency per command
+#[derive(Default)]
+pub struct CommandMetrics {
+    stats: DashMap<String, CommandStats>,
+}
+
+impl CommandMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one dispatch of `name`
+    pub fn record(&self, name: &str, elapsed: Duration, success: bool) {
+        let mut entry = self
+            .stats
+            .entry(name.to_string())
+            .or_insert_with(|| CommandStats {
+                name: name.to_string(),
+                ..Default::default()
+            });
+        entry.invocations += 1;
+        if !success {
+            entry.errors += 1;
+        }
+        entry.total_time += elapsed;
+        entry.max_time = entry.max_time.max(elapsed);
+    }
+
+    /// Stats for a single command, if it has been dispatched
+    pub fn get(&self, name: &str) -> Option<CommandStats> {
+        self.stats.get(name).map(|s| s.clone())
+    }
+
+    /// All stats, most-used first
+    pub fn snapshot(&self) -> Vec<CommandStats> {
+        let mut stats: Vec<CommandStats> = self.stats.iter().map(|s| s.clone()).collect();
+        stats.sort_by(|a, b| b.invocations.cmp(&a.invocations).then(a.name.cmp(&b.name)));
+        stats
+    }
+}
+
+/// Format the most-used commands for /status
+pub fn format_command_metrics(stats: &[CommandStats], limit: usize) -> String {
+    if stats.is_empty() {
+        return "No commands handled yet".to_string();
+    }
+
+    stats
+        .iter()
+        .take(limit)
+        .map(|s| {
+            let errors = match s.errors {
+                0 => String::new(),
+                1 => ", 1 error".to_string(),
+                n => format!(", {n} errors"),
+            };
+            format!(
+                "`/{}` {}× (avg {}ms, max {}ms{})",
+                s.name,
+                s.invocations,
+                s.average_time().as_millis(),
+                s.max_time.as_millis(),
+                errors
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{option, slash_command, subcommand};
+    use serde_json::json;
+
+    fn options(options: Vec<serde_json::Value>) -> Vec<CommandDataOption> {
+        slash_command("test", options).unwrap().data.options
+    }
+
+    #[test]
+    fn test_prompt_text_reads_top_level_and_nested_prompts() {
+        assert_eq!(
+            prompt_text(&options(vec![option("prompt", "write me an essay")])),
+            Some("write me an essay".to_string())
+        );
+        assert_eq!(
+            prompt_text(&options(vec![subcommand(
+                "run",
+                vec![
+                    option("name", "summarize"),
+                    option("args", "the meeting notes")
+                ]
+            )])),
+            Some("summarize the meeting notes".to_string())
+        );
+        let group = json!({
+            "name": "library",
+            "type": 2,
+            "options": [subcommand("use", vec![option("variables", "topic=cats")])],
+        });
+        assert_eq!(
+            prompt_text(&options(vec![group])),
+            Some("topic=cats".to_string())
+        );
+        assert_eq!(
+            prompt_text(&options(vec![subcommand(
+                "delete",
+                vec![option("name", "summarize")]
+            )])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_metrics_record_counts_errors_and_latency() {
+        let metrics = CommandMetrics::new();
+        metrics.record("ask", Duration::from_millis(100), true);
+        metrics.record("ask", Duration::from_millis(300), false);
+        metrics.record("ping", Duration::from_millis(5), true);
+
+        let ask = metrics.get("ask").unwrap();
+        assert_eq!(ask.invocations, 2);
+        assert_eq!(ask.errors, 1);
+        assert_eq!(ask.average_time(), Duration::from_millis(200));
+        assert_eq!(ask.max_time, Duration::from_millis(300));
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot[0].name, "ask");
+        assert_eq!(snapshot[1].name, "ping");
+    }
+
+    #[test]
+    fn test_format_command_metrics() {
+        assert_eq!(format_command_metrics(&[], 5), "No commands handled yet");
+
+        let metrics = CommandMetrics::new();
+        metrics.record("ask", Duration::from_millis(100), false);
+        metrics.record("ping", Duration::from_millis(4), true);
+        let output = format_command_metrics(&metrics.snapshot(), 1);
+        assert_eq!(output, "`/ask` 1× (avg 100ms, max 100ms, 1 error)");
+    }
+}
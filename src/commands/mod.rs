@@ -2,28 +2,43 @@
 //!
 //! Slash command (/) handling for Discord interactions.
 //!
-//! - **Version**: 2.1.0
+//! - **Version**: 2.5.0
 //! - **Since**: 0.2.0
 //! - **Toggleable**: false
 //!
 //! ## Changelog
+//! - 2.5.0: PaginatedEmbed with stored pages and previous/next buttons
+//! - 2.4.0: InteractionResponder auto-defers slow slash command handlers
+//! - 2.3.0: ComponentRegistry routes namespaced button, select and modal custom IDs
+//! - 2.2.0: All slash commands dispatch through CommandRegistry with middleware and metrics
 //! - 2.1.0: Add modular handler infrastructure (handler trait, context, registry)
 //! - 2.0.0: Remove bang commands, slash-only command system
 //! - 1.0.0: Initial reorganization with modular command structure
 
+pub mod components;
 pub mod context;
 pub mod handler;
 pub mod handlers;
+pub mod middleware;
+pub mod pagination;
 pub mod registry;
+pub mod responder;
 pub mod slash;
 
 // Re-export the CommandHandler from the handler module
 pub use crate::command_handler::CommandHandler;
 
 // Re-export handler infrastructure
+pub use components::{ComponentDispatch, ComponentHandler, ComponentId, ComponentRegistry};
 pub use context::CommandContext;
 pub use handler::SlashCommandHandler;
-pub use registry::CommandRegistry;
+pub use middleware::{
+    format_command_metrics, CommandMetrics, CommandMiddleware, CommandStats, Flow,
+    RateLimitMiddleware,
+};
+pub use pagination::{PaginatedEmbed, PaginationHandler, PAGINATION_TTL};
+pub use registry::{CommandRegistry, Dispatch};
+pub use responder::{InteractionResponder, ResponseState, AUTO_DEFER_AFTER};
 
 // Re-export commonly used items from submodules
 pub use slash::{
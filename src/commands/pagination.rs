@@ -0,0 +1,403 @@
+//! Paginated embeds with previous/next buttons
+//!
+//! Long listings are split into pages and stored in the `component_state`
+//! table under a random key. The buttons carry that key and the page they
+//! lead to, so `PaginationHandler` can redraw any page (even after a restart)
+//! until the pages expire after `PAGINATION_TTL`.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with PaginatedEmbed and PaginationHandler
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serenity::builder::{CreateComponents, CreateEmbed};
+use serenity::http::Http;
+use serenity::model::application::component::ButtonStyle;
+use serenity::model::application::interaction::message_component::MessageComponentInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::model::channel::Message;
+use serenity::model::id::ChannelId;
+use serenity::prelude::Context;
+use std::time::Duration;
+use uuid::Uuid;
+
+use super::components::{ComponentHandler, ComponentId};
+use super::responder::InteractionResponder;
+use crate::core::chunk_text;
+use crate::database::Database;
+
+/// Component feature namespace for page buttons
+pub const PAGINATION_FEATURE: &str = "page";
+
+/// How long page buttons (and the stored pages) stay usable
+pub const PAGINATION_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Maximum characters per page (embeds allow 4096, but shorter pages read better)
+pub const PAGE_CHARS: usize = 1800;
+
+/// Embed color used when the caller doesn't pick one
+const DEFAULT_COLOR: u32 = 0x5865F2;
+
+/// An embed whose description is split across pages
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaginatedEmbed {
+    pub title: String,
+    pub pages: Vec<String>,
+    pub color: u32,
+    pub footer: Option<String>,
+    /// Only this user may turn the pages (None: anyone)
+    pub owner_id: Option<u64>,
+}
+
+impl PaginatedEmbed {
+    pub fn new(title: impl Into<String>, pages: Vec<String>) -> Self {
+        Self {
+            title: title.into(),
+            pages,
+            color: DEFAULT_COLOR,
+            footer: None,
+            owner_id: None,
+        }
+    }
+
+    /// Split free text into pages on paragraph/sentence boundaries
+    pub fn from_text(title: impl Into<String>, text: &str) -> Self {
+        Self::new(title, chunk_text(text, PAGE_CHARS))
+    }
+
+    /// Group lines into pages of at most `per_page` lines (and `PAGE_CHARS` characters)
+    pub fn from_lines<I, S>(title: impl Into<String>, lines: I, per_page: usize) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let per_page = per_page.max(1);
+        let mut pages = Vec::new();
+        let mut current: Vec<String> = Vec::new();
+        let mut current_len = 0;
+
+        for line in lines {
+            let line = line.as_ref();
+            if !current.is_empty()
+                && (current.len() >= per_page || current_len + line.len() + 1 > PAGE_CHARS)
+            {
+                pages.push(current.join("\n"));
+                current.clear();
+                current_len = 0;
+            }
+            current_len += line.len() + 1;
+            current.push(line.to_string());
+        }
+        if !current.is_empty() {
+            pages.push(current.join("\n"));
+        }
+
+        Self::new(title, pages)
+    }
+
+    pub fn with_color(mut self, color: u32) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Text shown after the page indicator in the footer
+    pub fn with_footer(mut self, footer: impl Into<String>) -> Self {
+        self.footer = Some(footer.into());
+        self
+    }
+
+    pub fn with_owner(mut self, user_id: u64) -> Self {
+        self.owner_id = Some(user_id);
+        self
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len().max(1)
+    }
+
+    /// Clamp a requested page to the valid range
+    fn clamp_page(&self, page: usize) -> usize {
+        page.min(self.page_count() - 1)
+    }
+
+    /// Render one page (0-based) as an embed
+    pub fn embed(&self, page: usize) -> CreateEmbed {
+        let page = self.clamp_page(page);
+        let mut embed = CreateEmbed::default();
+        embed.title(&self.title);
+        embed.color(self.color);
+        embed.description(self.pages.get(page).map(String::as_str).unwrap_or(""));
+
+        let indicator = format!("Page {}/{}", page + 1, self.page_count());
+        let footer = match &self.footer {
+            Some(footer) => format!("{indicator} • {footer}"),
+            None => indicator,
+        };
+        embed.footer(|f| f.text(footer));
+        embed
+    }
+
+    /// Previous/indicator/next buttons for a stored embed (empty for a single page)
+    pub fn components(&self, key: &str, page: usize) -> CreateComponents {
+        let mut components = CreateComponents::default();
+        if self.page_count() <= 1 {
+            return components;
+        }
+
+        let page = self.clamp_page(page);
+        let last = self.page_count() - 1;
+        let button_id = |action: &str, target: usize| {
+            ComponentId::new(PAGINATION_FEATURE, action, format!("{key}/{target}"))
+                .expires_in(PAGINATION_TTL)
+                .to_string()
+        };
+
+        components.create_action_row(|row| {
+            row.create_button(|b| {
+                b.custom_id(button_id("prev", page.saturating_sub(1)))
+                    .label("◀ Prev")
+                    .style(ButtonStyle::Secondary)
+                    .disabled(page == 0)
+            })
+            .create_button(|b| {
+                b.custom_id(button_id("info", page))
+                    .label(format!("{}/{}", page + 1, last + 1))
+                    .style(ButtonStyle::Secondary)
+                    .disabled(true)
+            })
+            .create_button(|b| {
+                b.custom_id(button_id("next", (page + 1).min(last)))
+                    .label("Next ▶")
+                    .style(ButtonStyle::Secondary)
+                    .disabled(page == last)
+            })
+        });
+        components
+    }
+
+    /// Store the pages so the buttons can find them; returns the page key
+    pub async fn save(&self, database: &Database) -> Result<String> {
+        let key = Uuid::new_v4().simple().to_string();
+        let expires_at = chrono::Utc::now().timestamp() + PAGINATION_TTL.as_secs() as i64;
+        database
+            .save_component_state(
+                PAGINATION_FEATURE,
+                &key,
+                &serde_json::to_string(self)?,
+                Some(expires_at),
+            )
+            .await?;
+        Ok(key)
+    }
+
+    pub async fn load(database: &Database, key: &str) -> Result<Option<Self>> {
+        database
+            .get_component_state(PAGINATION_FEATURE, key)
+            .await?
+            .map(|payload| serde_json::from_str(&payload).map_err(Into::into))
+            .transpose()
+    }
+
+    /// Key for the buttons, storing the pages only when there is more than one
+    async fn key(&self, database: &Database) -> Result<String> {
+        if self.page_count() > 1 {
+            self.save(database).await
+        } else {
+            Ok(String::new())
+        }
+    }
+
+    /// Answer a slash command with the first page
+    pub async fn respond(
+        &self,
+        database: &Database,
+        http: impl AsRef<Http>,
+        responder: &InteractionResponder,
+        ephemeral: bool,
+    ) -> Result<()> {
+        let key = self.key(database).await?;
+        let embed = self.embed(0);
+        let components = self.components(&key, 0);
+        responder
+            .create_interaction_response(http, |response| {
+                response
+                    .kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|message| {
+                        message
+                            .set_embed(embed)
+                            .set_components(components)
+                            .ephemeral(ephemeral)
+                    })
+            })
+            .await
+    }
+
+    /// Post the first page as a regular channel message
+    pub async fn send(
+        &self,
+        database: &Database,
+        http: impl AsRef<Http>,
+        channel_id: ChannelId,
+    ) -> Result<Message> {
+        let key = self.key(database).await?;
+        let embed = self.embed(0);
+        let components = self.components(&key, 0);
+        let message = channel_id
+            .send_message(http.as_ref(), |m| {
+                m.set_embed(embed).set_components(components)
+            })
+            .await?;
+        Ok(message)
+    }
+}
+
+/// Parse a page button payload (`key/page`)
+pub fn parse_page_payload(id: &ComponentId) -> Option<(&str, usize)> {
+    match id.payload_parts().as_slice() {
+        [key, page] if !key.is_empty() => Some((key, page.parse().ok()?)),
+        _ => None,
+    }
+}
+
+/// Turns the pages of any stored `PaginatedEmbed`
+pub struct PaginationHandler {
+    database: Database,
+}
+
+impl PaginationHandler {
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+}
+
+#[async_trait]
+impl ComponentHandler for PaginationHandler {
+    fn features(&self) -> &'static [&'static str] {
+        &[PAGINATION_FEATURE]
+    }
+
+    async fn handle_component(
+        &self,
+        ctx: &Context,
+        interaction: &MessageComponentInteraction,
+        id: &ComponentId,
+    ) -> Result<()> {
+        // Buttons posted before pages were stored have no key to look up
+        let stored = match parse_page_payload(id) {
+            Some((key, page)) => PaginatedEmbed::load(&self.database, key)
+                .await?
+                .map(|pages| (key, page, pages)),
+            None => None,
+        };
+        let Some((key, page, pages)) = stored else {
+            return InteractionResponder::for_component(interaction)
+                .reply_ephemeral(
+                    ctx,
+                    "⌛ These pages have expired. Run the command again to see them.",
+                )
+                .await;
+        };
+
+        if pages
+            .owner_id
+            .is_some_and(|owner| owner != interaction.user.id.0)
+        {
+            return InteractionResponder::for_component(interaction)
+                .reply_ephemeral(
+                    ctx,
+                    "Only the person who ran the command can turn these pages.",
+                )
+                .await;
+        }
+
+        let embed = pages.embed(page);
+        let components = pages.components(key, page);
+        interaction
+            .create_interaction_response(&ctx.http, |response| {
+                response
+                    .kind(InteractionResponseType::UpdateMessage)
+                    .interaction_response_data(|message| {
+                        message.set_embed(embed).set_components(components)
+                    })
+            })
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn button_ids(components: &CreateComponents) -> Vec<(String, bool)> {
+        components.0[0]["components"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|b| {
+                (
+                    b["custom_id"].as_str().unwrap().to_string(),
+                    b["disabled"].as_bool().unwrap_or(false),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_from_lines_respects_page_size_and_char_limit() {
+        let lines: Vec<String> = (1..=12).map(|i| format!("line {i}")).collect();
+        let paged = PaginatedEmbed::from_lines("Title", &lines, 5);
+        assert_eq!(paged.page_count(), 3);
+        assert_eq!(paged.pages[2], "line 11\nline 12");
+
+        let long = vec!["x".repeat(1000); 3];
+        let paged = PaginatedEmbed::from_lines("Title", &long, 10);
+        assert_eq!(paged.page_count(), 3);
+        assert!(paged.pages.iter().all(|p| p.len() <= PAGE_CHARS));
+    }
+
+    #[test]
+    fn test_components_point_at_neighbouring_pages() {
+        let paged = PaginatedEmbed::new("T", vec!["a".into(), "b".into(), "c".into()]);
+        assert!(PaginatedEmbed::new("T", vec!["only".into()])
+            .components("k", 0)
+            .0
+            .is_empty());
+
+        let buttons = button_ids(&paged.components("abc", 1));
+        let ids: Vec<ComponentId> = buttons
+            .iter()
+            .map(|(id, _)| ComponentId::parse(id).unwrap())
+            .collect();
+        assert_eq!(parse_page_payload(&ids[0]), Some(("abc", 0)));
+        assert_eq!(parse_page_payload(&ids[2]), Some(("abc", 2)));
+        assert!(ids.iter().all(|id| id.expires_at.is_some()));
+        assert!(buttons[1].1, "page indicator is not clickable");
+
+        let first = button_ids(&paged.components("abc", 0));
+        assert!(first[0].1 && !first[2].1);
+        let last = button_ids(&paged.components("abc", 7));
+        assert!(!last[0].1 && last[2].1);
+    }
+
+    #[tokio::test]
+    async fn test_pages_round_trip_through_database() {
+        let database = Database::new(":memory:").await.unwrap();
+        let paged = PaginatedEmbed::from_text("Transcript", &"Word. ".repeat(1000))
+            .with_owner(42)
+            .with_footer("transcript.txt");
+        assert!(paged.page_count() > 1);
+
+        let key = paged.save(&database).await.unwrap();
+        let loaded = PaginatedEmbed::load(&database, &key).await.unwrap();
+        assert_eq!(loaded, Some(paged));
+        assert_eq!(
+            PaginatedEmbed::load(&database, "missing").await.unwrap(),
+            None
+        );
+    }
+}
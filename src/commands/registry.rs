@@ -1,15 +1,38 @@
 //! Command handler registry
 //!
-//! - **Version**: 1.0.0
+//! - **Version**: 1.4.0
 //! - **Since**: 3.38.0
 //!
 //! ## Changelog
+//! - 1.4.0: Tracking starts before middleware, so a slow middleware check is auto-deferred too
+//! - 1.3.0: The auto-defer is ephemeral for handlers that declare ephemeral replies
+//! - 1.2.0: dispatch() tracks an InteractionResponder that auto-defers slow handlers
+//! - 1.1.0: dispatch() runs middleware and records per-command metrics
 //! - 1.0.0: Initial implementation for handler dispatch
 
+use anyhow::Result;
+use log::debug;
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::prelude::Context;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 
+use super::context::CommandContext;
 use super::handler::SlashCommandHandler;
+use super::middleware::{CommandMetrics, CommandMiddleware, Flow};
+use super::responder::{InteractionResponder, AUTO_DEFER_AFTER};
+
+/// Outcome of dispatching a slash command through the registry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dispatch {
+    /// A handler ran (successfully; errors are returned as `Err`)
+    Handled,
+    /// A middleware stopped the command before it reached the handler
+    Stopped(&'static str),
+    /// No handler is registered for the command name
+    Unknown,
+}
 
 /// Registry mapping command names to handlers
 ///
@@ -30,6 +53,8 @@ use super::handler::SlashCommandHandler;
 #[derive(Clone)]
 pub struct CommandRegistry {
     handlers: HashMap<&'static str, Arc<dyn SlashCommandHandler>>,
+    middleware: Vec<Arc<dyn CommandMiddleware>>,
+    metrics: Arc<CommandMetrics>,
 }
 
 impl CommandRegistry {
@@ -37,9 +62,75 @@ impl CommandRegistry {
     pub fn new() -> Self {
         Self {
             handlers: HashMap::new(),
+            middleware: Vec::new(),
+            metrics: Arc::new(CommandMetrics::new()),
         }
     }
 
+    /// Add middleware that runs around every dispatched command (in registration order)
+    pub fn add_middleware(&mut self, middleware: Arc<dyn CommandMiddleware>) {
+        self.middleware.push(middleware);
+    }
+
+    /// Shared per-command metrics recorded by `dispatch`
+    pub fn metrics(&self) -> Arc<CommandMetrics> {
+        Arc::clone(&self.metrics)
+    }
+
+    /// Run middleware, the matching handler and metrics for a slash command
+    ///
+    /// While middleware and the handler run, an `InteractionResponder` (see
+    /// `InteractionResponder::for_command`) defers the interaction if nothing
+    /// has been sent after `AUTO_DEFER_AFTER`. Unknown commands are reported as
+    /// `Dispatch::Unknown` without running middleware so the caller can respond
+    /// with its own help hint.
+    pub async fn dispatch(
+        &self,
+        ctx: Arc<CommandContext>,
+        serenity_ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Result<Dispatch> {
+        let name = command.data.name.as_str();
+        let Some(handler) = self.get(name) else {
+            return Ok(Dispatch::Unknown);
+        };
+
+        let responder = InteractionResponder::track(
+            command,
+            Arc::clone(&serenity_ctx.http),
+            AUTO_DEFER_AFTER,
+            handler.ephemeral(command),
+        );
+        for middleware in &self.middleware {
+            let flow = middleware.before(&ctx, serenity_ctx, command).await;
+            if !matches!(flow, Ok(Flow::Continue)) {
+                responder.finish();
+            }
+            if flow? == Flow::Stop {
+                debug!(
+                    "Command /{name} stopped by {} middleware",
+                    middleware.name()
+                );
+                return Ok(Dispatch::Stopped(middleware.name()));
+            }
+        }
+
+        debug!("Dispatching to registered handler: {name}");
+        let started = Instant::now();
+        let result = handler
+            .handle(Arc::clone(&ctx), serenity_ctx, command)
+            .await;
+        let elapsed = started.elapsed();
+        responder.finish();
+
+        self.metrics.record(name, elapsed, result.is_ok());
+        for middleware in &self.middleware {
+            middleware.after(command, elapsed, &result).await;
+        }
+
+        result.map(|_| Dispatch::Handled)
+    }
+
     /// Register a handler for its declared command names
     ///
     /// The handler is registered for all names returned by `command_names()`.
@@ -90,10 +181,13 @@ impl Default for CommandRegistry {
 mod tests {
     use super::*;
     use crate::commands::context::CommandContext;
-    use anyhow::Result;
+    use crate::database::Database;
+    use crate::features::analytics::{InteractionTracker, UsageTracker};
+    use crate::features::image_gen::generator::ImageGenerator;
+    use crate::features::personas::PersonaManager;
+    use crate::testing::{slash_command, MockDiscord};
     use async_trait::async_trait;
-    use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
-    use serenity::prelude::Context;
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
     // Mock handler for testing
     struct MockHandler {
@@ -164,4 +258,187 @@ mod tests {
         let registry = CommandRegistry::default();
         assert!(registry.is_empty());
     }
+
+    /// Handler that counts calls and optionally fails
+    struct CountingHandler {
+        names: &'static [&'static str],
+        calls: AtomicUsize,
+        fail: bool,
+    }
+
+    impl CountingHandler {
+        fn new(names: &'static [&'static str], fail: bool) -> Arc<Self> {
+            Arc::new(Self {
+                names,
+                calls: AtomicUsize::new(0),
+                fail,
+            })
+        }
+    }
+
+    #[async_trait]
+    impl SlashCommandHandler for CountingHandler {
+        fn command_names(&self) -> &'static [&'static str] {
+            self.names
+        }
+
+        async fn handle(
+            &self,
+            _ctx: Arc<CommandContext>,
+            _serenity_ctx: &Context,
+            _command: &ApplicationCommandInteraction,
+        ) -> Result<()> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.fail {
+                anyhow::bail!("handler failed");
+            }
+            Ok(())
+        }
+    }
+
+    /// Middleware that stops /blocked and counts `after` calls
+    #[derive(Default)]
+    struct BlockingMiddleware {
+        after_calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl CommandMiddleware for BlockingMiddleware {
+        fn name(&self) -> &'static str {
+            "blocking"
+        }
+
+        async fn before(
+            &self,
+            _ctx: &CommandContext,
+            _serenity_ctx: &Context,
+            command: &ApplicationCommandInteraction,
+        ) -> Result<Flow> {
+            Ok(match command.data.name.as_str() {
+                "blocked" => Flow::Stop,
+                _ => Flow::Continue,
+            })
+        }
+
+        async fn after(
+            &self,
+            _command: &ApplicationCommandInteraction,
+            _elapsed: std::time::Duration,
+            _result: &Result<()>,
+        ) {
+            self.after_calls.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    async fn test_context() -> Arc<CommandContext> {
+        let database = Database::new(":memory:").await.unwrap();
+        Arc::new(CommandContext::new(
+            PersonaManager::new(),
+            database.clone(),
+            UsageTracker::new(database.clone()),
+            InteractionTracker::new(database.clone()),
+            ImageGenerator::new("test-key".to_string()),
+            None,
+            "gpt-mock".to_string(),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_runs_middleware_and_records_metrics() {
+        let discord = MockDiscord::start().await.unwrap();
+        let serenity_ctx = discord.context();
+        let ctx = test_context().await;
+
+        let ping = CountingHandler::new(&["ping"], false);
+        let broken = CountingHandler::new(&["broken"], true);
+        let blocked = CountingHandler::new(&["blocked"], false);
+        let middleware = Arc::new(BlockingMiddleware::default());
+
+        let mut registry = CommandRegistry::new();
+        registry.register(ping.clone());
+        registry.register(broken.clone());
+        registry.register(blocked.clone());
+        registry.add_middleware(middleware.clone());
+
+        let dispatch = |name: &str| {
+            let command = slash_command(name, vec![]).unwrap();
+            let registry = registry.clone();
+            let ctx = ctx.clone();
+            let serenity_ctx = serenity_ctx.clone();
+            async move { registry.dispatch(ctx, &serenity_ctx, &command).await }
+        };
+
+        assert_eq!(dispatch("ping").await.unwrap(), Dispatch::Handled);
+        assert!(dispatch("broken").await.is_err());
+        assert_eq!(
+            dispatch("blocked").await.unwrap(),
+            Dispatch::Stopped("blocking")
+        );
+        assert_eq!(dispatch("missing").await.unwrap(), Dispatch::Unknown);
+
+        assert_eq!(ping.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(broken.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(blocked.calls.load(Ordering::SeqCst), 0);
+        assert_eq!(middleware.after_calls.load(Ordering::SeqCst), 2);
+
+        let metrics = registry.metrics();
+        assert_eq!(metrics.get("ping").unwrap().invocations, 1);
+        let broken_stats = metrics.get("broken").unwrap();
+        assert_eq!((broken_stats.invocations, broken_stats.errors), (1, 1));
+        assert!(metrics.get("blocked").is_none());
+        assert!(metrics.get("missing").is_none());
+    }
+
+    /// Middleware that takes longer than the auto-defer window, then refuses
+    struct SlowRefusalMiddleware;
+
+    #[async_trait]
+    impl CommandMiddleware for SlowRefusalMiddleware {
+        fn name(&self) -> &'static str {
+            "slow_refusal"
+        }
+
+        async fn before(
+            &self,
+            _ctx: &CommandContext,
+            serenity_ctx: &Context,
+            command: &ApplicationCommandInteraction,
+        ) -> Result<Flow> {
+            tokio::time::sleep(AUTO_DEFER_AFTER + std::time::Duration::from_millis(300)).await;
+            InteractionResponder::for_command(command)
+                .create_interaction_response(&serenity_ctx.http, |r| {
+                    r.interaction_response_data(|m| m.content("refused"))
+                })
+                .await?;
+            Ok(Flow::Stop)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_auto_defers_slow_middleware() {
+        let discord = MockDiscord::start().await.unwrap();
+        let serenity_ctx = discord.context();
+        let handler = CountingHandler::new(&["slow"], false);
+
+        let mut registry = CommandRegistry::new();
+        registry.register(handler.clone());
+        registry.add_middleware(Arc::new(SlowRefusalMiddleware));
+
+        let command = slash_command("slow", vec![]).unwrap();
+        let dispatch = registry
+            .dispatch(test_context().await, &serenity_ctx, &command)
+            .await
+            .unwrap();
+        assert_eq!(dispatch, Dispatch::Stopped("slow_refusal"));
+        assert_eq!(handler.calls.load(Ordering::SeqCst), 0);
+
+        // The refusal edits the placeholder instead of failing as a second response
+        let requests = discord.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].path.ends_with("/callback"));
+        assert_eq!(requests[0].body["type"], 5);
+        assert_eq!(requests[1].method, "PATCH");
+        assert!(requests[1].path.ends_with("/messages/@original"));
+        assert_eq!(requests[1].content(), Some("refused"));
+    }
 }
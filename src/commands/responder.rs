@@ -0,0 +1,391 @@
+//! Interaction responder with an automatic defer guard
+//!
+//! Discord drops an interaction that isn't acknowledged within 3 seconds and
+//! every later response fails with "Unknown interaction". The registry wraps
+//! each dispatched slash command in an `InteractionResponder` that defers the
+//! interaction if the handler hasn't responded after `AUTO_DEFER_AFTER`,
+//! privately when the handler declares its replies ephemeral.
+//! Handlers send their first response through the responder, which turns it
+//! into an edit of the deferred message (or a followup) when needed.
+//! Button and select menu handlers use an untracked responder for their
+//! private replies.
+//!
+//! - **Version**: 1.2.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.2.0: reply_ephemeral for private replies, and untracked responders for component interactions
+//! - 1.1.0: Auto-defer is ephemeral for handlers that reply privately
+//! - 1.0.0: Initial release with auto-defer guard and state tracking
+
+use anyhow::Result;
+use dashmap::DashMap;
+use log::{debug, warn};
+use serde_json::{json, Value};
+use serenity::builder::CreateInteractionResponse;
+use serenity::http::{Http, HttpError};
+use serenity::json::hashmap_to_json_map;
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::application::interaction::message_component::MessageComponentInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use std::sync::{Arc, Mutex as StdMutex, OnceLock};
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+/// How long a handler may run before the interaction is deferred for it
+pub const AUTO_DEFER_AFTER: Duration = Duration::from_secs(2);
+
+/// Discord error code for responding to an interaction twice
+const ALREADY_ACKNOWLEDGED: isize = 40060;
+
+/// Message flag that shows a response only to the invoking user
+const EPHEMERAL_FLAG: u64 = 64;
+
+/// Where an interaction is in its response lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseState {
+    /// Nothing has been sent yet
+    Pending,
+    /// Acknowledged with a "thinking..." placeholder
+    Deferred { automatic: bool },
+    /// The initial response has been sent
+    Responded,
+}
+
+/// Tracks and sends the response to one slash command interaction
+pub struct InteractionResponder {
+    interaction_id: u64,
+    token: String,
+    command_name: String,
+    /// Auto-defer privately, so a later edit isn't shown to the channel
+    ephemeral: bool,
+    state: Mutex<ResponseState>,
+    guard: StdMutex<Option<JoinHandle<()>>>,
+}
+
+/// Responders for interactions currently being dispatched (keyed by interaction ID)
+static IN_FLIGHT: OnceLock<DashMap<u64, Arc<InteractionResponder>>> = OnceLock::new();
+
+fn in_flight() -> &'static DashMap<u64, Arc<InteractionResponder>> {
+    IN_FLIGHT.get_or_init(DashMap::new)
+}
+
+impl InteractionResponder {
+    pub fn new(command: &ApplicationCommandInteraction) -> Self {
+        Self::untracked(command.id.0, &command.token, &command.data.name)
+    }
+
+    /// A responder for a button or select menu interaction
+    ///
+    /// Component interactions aren't auto-deferred; the responder only
+    /// tracks whether a response has been sent.
+    pub fn for_component(component: &MessageComponentInteraction) -> Self {
+        Self::untracked(component.id.0, &component.token, &component.data.custom_id)
+    }
+
+    fn untracked(interaction_id: u64, token: &str, command_name: &str) -> Self {
+        Self {
+            interaction_id,
+            token: token.to_string(),
+            command_name: command_name.to_string(),
+            ephemeral: false,
+            state: Mutex::new(ResponseState::Pending),
+            guard: StdMutex::new(None),
+        }
+    }
+
+    /// The responder the registry is tracking for `command`, or a fresh untracked one
+    pub fn for_command(command: &ApplicationCommandInteraction) -> Arc<Self> {
+        in_flight()
+            .get(&command.id.0)
+            .map(|r| Arc::clone(&r))
+            .unwrap_or_else(|| Arc::new(Self::new(command)))
+    }
+
+    /// Start tracking `command` and defer it automatically after `after`
+    ///
+    /// With `ephemeral` the automatic defer (and so the response that edits
+    /// it) is only shown to the user who ran the command.
+    pub fn track(
+        command: &ApplicationCommandInteraction,
+        http: Arc<Http>,
+        after: Duration,
+        ephemeral: bool,
+    ) -> Arc<Self> {
+        let mut responder = Self::new(command);
+        responder.ephemeral = ephemeral;
+        let responder = Arc::new(responder);
+        in_flight().insert(responder.interaction_id, Arc::clone(&responder));
+
+        let guarded = Arc::clone(&responder);
+        let handle = tokio::spawn(async move {
+            tokio::time::sleep(after).await;
+            guarded.auto_defer(&http, after).await;
+        });
+        *responder.guard.lock().unwrap() = Some(handle);
+        responder
+    }
+
+    /// Stop the guard and forget the interaction
+    pub fn finish(&self) {
+        if let Some(handle) = self.guard.lock().unwrap().take() {
+            handle.abort();
+        }
+        in_flight().remove(&self.interaction_id);
+    }
+
+    pub async fn state(&self) -> ResponseState {
+        *self.state.lock().await
+    }
+
+    async fn auto_defer(&self, http: &Http, after: Duration) {
+        let mut state = self.state.lock().await;
+        if *state != ResponseState::Pending {
+            return;
+        }
+
+        let mut body =
+            json!({ "type": InteractionResponseType::DeferredChannelMessageWithSource as u8 });
+        if self.ephemeral {
+            body["data"] = json!({ "flags": EPHEMERAL_FLAG });
+        }
+        match http
+            .create_interaction_response(self.interaction_id, &self.token, &body)
+            .await
+        {
+            Ok(()) => {
+                warn!(
+                    "⏳ Auto-deferred /{} after {}ms without a response",
+                    self.command_name,
+                    after.as_millis()
+                );
+                *state = ResponseState::Deferred { automatic: true };
+            }
+            // The handler answered directly instead of through the responder
+            Err(e) if discord_error_code(&e) == Some(ALREADY_ACKNOWLEDGED) => {
+                *state = ResponseState::Responded;
+            }
+            Err(e) => warn!("Failed to auto-defer /{}: {e}", self.command_name),
+        }
+    }
+
+    /// Defer the interaction if nothing has been sent yet
+    pub async fn defer(&self, http: impl AsRef<Http>) -> Result<()> {
+        self.create_interaction_response(http, |r| {
+            r.kind(InteractionResponseType::DeferredChannelMessageWithSource)
+        })
+        .await
+    }
+
+    /// Respond with `content`, shown only to the user who invoked the interaction
+    pub async fn reply_ephemeral(&self, http: impl AsRef<Http>, content: &str) -> Result<()> {
+        self.create_interaction_response(http, |r| {
+            r.kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|m| m.content(content).ephemeral(true))
+        })
+        .await
+    }
+
+    /// Drop-in replacement for `ApplicationCommandInteraction::create_interaction_response`
+    ///
+    /// Sent as-is while the interaction is pending. Once it has been deferred
+    /// (by the handler or the guard) a message response edits the deferred
+    /// placeholder, and after a full response it becomes a followup. A defer
+    /// request on an already acknowledged interaction is a no-op.
+    pub async fn create_interaction_response<'a, F>(
+        &self,
+        http: impl AsRef<Http>,
+        f: F,
+    ) -> Result<()>
+    where
+        for<'b> F:
+            FnOnce(&'b mut CreateInteractionResponse<'a>) -> &'b mut CreateInteractionResponse<'a>,
+    {
+        let mut response = CreateInteractionResponse::default();
+        f(&mut response);
+        let files = std::mem::take(&mut response.1);
+        let map = hashmap_to_json_map(response.0);
+        let is_defer = map.get("type").and_then(Value::as_u64)
+            == Some(InteractionResponseType::DeferredChannelMessageWithSource as u64);
+        let data = map.get("data").cloned().unwrap_or_else(|| json!({}));
+
+        let http = http.as_ref();
+        let mut state = self.state.lock().await;
+        match *state {
+            ResponseState::Pending => {
+                let body = Value::from(map);
+                if files.is_empty() {
+                    http.create_interaction_response(self.interaction_id, &self.token, &body)
+                        .await?;
+                } else {
+                    http.create_interaction_response_with_files(
+                        self.interaction_id,
+                        &self.token,
+                        &body,
+                        files,
+                    )
+                    .await?;
+                }
+                *state = if is_defer {
+                    ResponseState::Deferred { automatic: false }
+                } else {
+                    ResponseState::Responded
+                };
+            }
+            _ if is_defer => {
+                debug!(
+                    "/{} already acknowledged; skipping defer",
+                    self.command_name
+                );
+            }
+            ResponseState::Deferred { .. } if files.is_empty() => {
+                http.edit_original_interaction_response(&self.token, &data)
+                    .await?;
+                *state = ResponseState::Responded;
+            }
+            ResponseState::Deferred { .. } | ResponseState::Responded => {
+                if files.is_empty() {
+                    http.create_followup_message(&self.token, &data).await?;
+                } else {
+                    http.create_followup_message_with_files(&self.token, &data, files)
+                        .await?;
+                }
+                *state = ResponseState::Responded;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for InteractionResponder {
+    fn drop(&mut self) {
+        if let Some(handle) = self.guard.get_mut().ok().and_then(Option::take) {
+            handle.abort();
+        }
+    }
+}
+
+fn discord_error_code(error: &serenity::Error) -> Option<isize> {
+    match error {
+        serenity::Error::Http(http) => match http.as_ref() {
+            HttpError::UnsuccessfulRequest(response) => Some(response.error.code),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{slash_command, MockDiscord};
+
+    #[tokio::test]
+    async fn test_auto_defer_turns_late_response_into_edit() {
+        let discord = MockDiscord::start().await.unwrap();
+        let command = slash_command("slow", vec![]).unwrap();
+
+        let responder =
+            InteractionResponder::track(&command, discord.http(), Duration::from_millis(20), false);
+        assert!(Arc::ptr_eq(
+            &responder,
+            &InteractionResponder::for_command(&command)
+        ));
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(
+            responder.state().await,
+            ResponseState::Deferred { automatic: true }
+        );
+
+        responder
+            .create_interaction_response(discord.http(), |r| {
+                r.interaction_response_data(|m| m.content("finally"))
+            })
+            .await
+            .unwrap();
+        responder.finish();
+        assert_eq!(responder.state().await, ResponseState::Responded);
+
+        let requests = discord.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].path.ends_with("/callback"));
+        assert_eq!(requests[0].body["type"], 5);
+        assert_eq!(requests[1].method, "PATCH");
+        assert!(requests[1].path.ends_with("/messages/@original"));
+        assert_eq!(requests[1].content(), Some("finally"));
+        assert!(!Arc::ptr_eq(
+            &responder,
+            &InteractionResponder::for_command(&command)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_fast_response_is_not_deferred() {
+        let discord = MockDiscord::start().await.unwrap();
+        let command = slash_command("fast", vec![]).unwrap();
+
+        let responder =
+            InteractionResponder::track(&command, discord.http(), Duration::from_millis(50), false);
+        responder
+            .create_interaction_response(discord.http(), |r| {
+                r.interaction_response_data(|m| m.content("quick"))
+            })
+            .await
+            .unwrap();
+        // A second message becomes a followup, and a late defer is ignored
+        responder
+            .create_interaction_response(discord.http(), |r| {
+                r.interaction_response_data(|m| m.content("more"))
+            })
+            .await
+            .unwrap();
+        responder.defer(discord.http()).await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        responder.finish();
+
+        let requests = discord.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].body["type"], 4);
+        assert_eq!(requests[1].method, "POST");
+        assert!(requests[1].path.starts_with("/webhooks/"));
+        assert_eq!(requests[1].content(), Some("more"));
+    }
+
+    #[tokio::test]
+    async fn test_reply_ephemeral_sets_the_ephemeral_flag() {
+        let discord = MockDiscord::start().await.unwrap();
+        let command = slash_command("private", vec![]).unwrap();
+
+        let responder = InteractionResponder::new(&command);
+        responder
+            .reply_ephemeral(discord.http(), "only you")
+            .await
+            .unwrap();
+
+        let requests = discord.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].body["type"], 4);
+        assert_eq!(requests[0].body["data"]["flags"], EPHEMERAL_FLAG);
+        assert_eq!(requests[0].content(), Some("only you"));
+        assert_eq!(responder.state().await, ResponseState::Responded);
+    }
+
+    #[tokio::test]
+    async fn test_auto_defer_can_be_ephemeral() {
+        let discord = MockDiscord::start().await.unwrap();
+        let command = slash_command("private", vec![]).unwrap();
+
+        let responder =
+            InteractionResponder::track(&command, discord.http(), Duration::from_millis(20), true);
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        responder.finish();
+
+        let requests = discord.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].body["type"], 5);
+        assert_eq!(requests[0].body["data"]["flags"], EPHEMERAL_FLAG);
+    }
+}
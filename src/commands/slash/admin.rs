@@ -1,9 +1,15 @@
-//! Admin slash commands: /introspect, /settings, /set_channel, /set_guild, /admin_role, /features, /toggle, /sysinfo, /usage
+//! Admin slash commands: /introspect, /settings, /set_channel, /set_guild, /admin_role, /features, /toggle, /sysinfo, /alerts, /usage, /search, /admin
 
 use serenity::builder::CreateApplicationCommand;
 use serenity::model::application::command::CommandOptionType;
+use serenity::model::channel::ChannelType;
 use serenity::model::permissions::Permissions;
 
+use crate::features::language::find_language;
+use crate::features::link_preview::LINK_PREVIEW_MODES;
+use crate::features::personas::themes::{parse_rotation, parse_theme_schedule};
+use crate::features::scenes::MAX_SCENE_CHARS;
+
 /// Creates admin commands
 pub fn create_commands() -> Vec<CreateApplicationCommand> {
     vec![
@@ -15,7 +21,10 @@ pub fn create_commands() -> Vec<CreateApplicationCommand> {
         create_features_command(),
         create_toggle_command(),
         create_sysinfo_command(),
+        create_alerts_command(),
         create_usage_command(),
+        create_search_command(),
+        create_admin_command(),
     ]
 }
 
@@ -30,14 +39,21 @@ fn create_introspect_command() -> CreateApplicationCommand {
                 .name("component")
                 .description("Which part of the bot to explain")
                 .kind(CommandOptionType::String)
-                .required(true)
-                .add_string_choice("Overview - Bot architecture", "overview")
+                .required(false)
+                .add_string_choice("Overview - Live architecture map", "overview")
                 .add_string_choice("Personas - Personality system", "personas")
                 .add_string_choice("Reminders - Scheduling system", "reminders")
                 .add_string_choice("Conflict - Mediation system", "conflict")
                 .add_string_choice("Commands - How I process commands", "commands")
                 .add_string_choice("Database - How I remember things", "database")
         })
+        .create_option(|option| {
+            option
+                .name("file")
+                .description("Or a repository source file to explain, e.g. src/features/mod.rs")
+                .kind(CommandOptionType::String)
+                .required(false)
+        })
         .to_owned()
 }
 
@@ -56,6 +72,13 @@ fn create_set_channel_command() -> CreateApplicationCommand {
                 .add_string_choice("verbosity", "verbosity")
                 .add_string_choice("persona", "persona")
                 .add_string_choice("conflict_mediation", "conflict_mediation")
+                .add_string_choice("birthdays", "birthdays")
+                .add_string_choice("achievements", "achievements")
+                .add_string_choice("link_previews", "link_previews")
+                .add_string_choice("sentiment_tracking", "sentiment_tracking")
+                .add_string_choice("language", "language")
+                .add_string_choice("time_detection", "time_detection")
+                .add_string_choice("scene", "scene")
         })
         .create_option(|option| {
             option
@@ -90,7 +113,11 @@ fn create_set_guild_command() -> CreateApplicationCommand {
                 // High priority settings
                 .add_string_choice("default_verbosity", "default_verbosity")
                 .add_string_choice("default_persona", "default_persona")
+                .add_string_choice("category_persona", "category_persona")
+                .add_string_choice("persona_rotation", "persona_rotation")
+                .add_string_choice("seasonal_themes", "seasonal_themes")
                 .add_string_choice("response_embeds", "response_embeds")
+                .add_string_choice("custom_emojis", "custom_emojis")
                 .add_string_choice("conflict_mediation", "conflict_mediation")
                 .add_string_choice("conflict_sensitivity", "conflict_sensitivity")
                 .add_string_choice("mediation_cooldown", "mediation_cooldown")
@@ -101,6 +128,10 @@ fn create_set_guild_command() -> CreateApplicationCommand {
                 .add_string_choice("audio_transcription_output", "audio_transcription_output")
                 .add_string_choice("mention_responses", "mention_responses")
                 .add_string_choice("debate_auto_response", "debate_auto_response")
+                .add_string_choice("privacy_analytics", "privacy_analytics")
+                .add_string_choice("privacy_conflict_analysis", "privacy_conflict_analysis")
+                .add_string_choice("language_exempt_role", "language_exempt_role")
+                .add_string_choice("answer_disclosure", "answer_disclosure")
                 // Global bot settings (stored in bot_settings table)
                 .add_string_choice("startup_notification", "startup_notification")
                 .add_string_choice("startup_notify_owner_id", "startup_notify_owner_id")
@@ -119,6 +150,14 @@ fn create_set_guild_command() -> CreateApplicationCommand {
                 .required(true)
                 .set_autocomplete(true)
         })
+        .create_option(|option| {
+            option
+                .name("category")
+                .description("Channel category (required for category_persona)")
+                .kind(CommandOptionType::Channel)
+                .channel_types(&[ChannelType::Category])
+                .required(false)
+        })
         .to_owned()
 }
 
@@ -193,10 +232,20 @@ fn create_sysinfo_command() -> CreateApplicationCommand {
                 .add_string_choice("Current Status", "current")
                 .add_string_choice("History (24h)", "history_24h")
                 .add_string_choice("History (7d)", "history_7d")
+                .add_string_choice("History (30d)", "history_30d")
         })
         .to_owned()
 }
 
+/// Creates the alerts command (admin) - lists metric alert rules and their state
+fn create_alerts_command() -> CreateApplicationCommand {
+    CreateApplicationCommand::default()
+        .name("alerts")
+        .description("List metric alert rules and whether they are firing (Admin)")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .to_owned()
+}
+
 /// Creates the usage command - displays OpenAI API usage and cost metrics
 fn create_usage_command() -> CreateApplicationCommand {
     CreateApplicationCommand::default()
@@ -213,23 +262,162 @@ fn create_usage_command() -> CreateApplicationCommand {
                 .add_string_choice("Server Usage (Today) - Admin", "server_today")
                 .add_string_choice("Server Usage (7 days) - Admin", "server_7d")
                 .add_string_choice("Top Users (7 days) - Admin", "top_users")
+                .add_string_choice("Image Costs by Channel (7 days) - Admin", "image_costs")
+                .add_string_choice("Plugin AI Costs (7 days) - Admin", "plugin_costs")
+        })
+        .to_owned()
+}
+
+/// Creates the search command (admin) - full-text search over stored messages
+fn create_search_command() -> CreateApplicationCommand {
+    CreateApplicationCommand::default()
+        .name("search")
+        .description("Search stored messages in this server (Admin)")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .create_option(|option| {
+            option
+                .name("query")
+                .description("Words to search for (append * for prefix match)")
+                .kind(CommandOptionType::String)
+                .required(true)
+        })
+        .create_option(|option| {
+            option
+                .name("channel")
+                .description("Only search this channel")
+                .kind(CommandOptionType::Channel)
+                .required(false)
+        })
+        .create_option(|option| {
+            option
+                .name("limit")
+                .description("Number of results to show (1-25)")
+                .kind(CommandOptionType::Integer)
+                .required(false)
+                .min_int_value(1)
+                .max_int_value(25)
         })
         .to_owned()
 }
 
+/// Creates the admin command - owner maintenance plus server block/allow lists
+fn create_admin_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("admin")
+        .description("Bot maintenance and access control")
+        .default_member_permissions(Permissions::ADMINISTRATOR)
+        .create_option(|option| {
+            option
+                .name("update")
+                .description("Check GitHub releases and install a newer version")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("check_only")
+                        .description("Only report whether an update is available")
+                        .kind(CommandOptionType::Boolean)
+                        .required(false)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("presence")
+                .description("Show or set the rotating activity status")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("messages")
+                        .description("Templates split by |, e.g. Watching {guilds} servers | Transcribing {jobs} videos")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("interval")
+                        .description("Minutes between status changes")
+                        .kind(CommandOptionType::Integer)
+                        .min_int_value(1)
+                        .max_int_value(60)
+                        .required(false)
+                })
+        });
+
+    for (group, description) in [
+        ("block", "Make the bot ignore a user or channel"),
+        (
+            "allow",
+            "Allowlist a user or channel; once set, only listed ones get responses",
+        ),
+        (
+            "unblock",
+            "Remove a user or channel from the block and allow lists",
+        ),
+    ] {
+        command.create_option(|option| {
+            option
+                .name(group)
+                .description(description)
+                .kind(CommandOptionType::SubCommandGroup)
+                .create_sub_option(|sub| {
+                    sub.name("user")
+                        .description("Apply to a user")
+                        .kind(CommandOptionType::SubCommand)
+                        .create_sub_option(|target| {
+                            target
+                                .name("target")
+                                .description("The user")
+                                .kind(CommandOptionType::User)
+                                .required(true)
+                        })
+                })
+                .create_sub_option(|sub| {
+                    sub.name("channel")
+                        .description("Apply to a channel or category")
+                        .kind(CommandOptionType::SubCommand)
+                        .create_sub_option(|target| {
+                            target
+                                .name("target")
+                                .description("The channel or category")
+                                .kind(CommandOptionType::Channel)
+                                .required(true)
+                        })
+                })
+        });
+    }
+
+    command.create_option(|option| {
+        option
+            .name("access")
+            .description("Show this server's block and allow lists")
+            .kind(CommandOptionType::SubCommand)
+    });
+    command
+}
+
 // ==================== Validation Functions ====================
 
 /// Valid user settings
 pub const USER_SETTINGS: &[&str] = &["persona"];
 
 /// Valid channel settings
-pub const CHANNEL_SETTINGS: &[&str] = &["verbosity", "persona", "conflict_mediation", "max_paragraphs"];
+pub const CHANNEL_SETTINGS: &[&str] = &[
+    "verbosity",
+    "persona",
+    "conflict_mediation",
+    "max_paragraphs",
+    "link_previews",
+    "sentiment_tracking",
+    "language",
+    "time_detection",
+    "scene",
+];
 
 /// Valid guild settings
 pub const GUILD_SETTINGS: &[&str] = &[
     "default_verbosity",
     "default_persona",
+    "persona_rotation",
+    "seasonal_themes",
     "response_embeds",
+    "custom_emojis",
     "conflict_mediation",
     "conflict_sensitivity",
     "mediation_cooldown",
@@ -239,6 +427,10 @@ pub const GUILD_SETTINGS: &[&str] = &[
     "audio_transcription_output",
     "mention_responses",
     "debate_auto_response",
+    "privacy_analytics",
+    "privacy_conflict_analysis",
+    "language_exempt_role",
+    "answer_disclosure",
     "startup_notification",
     "startup_notify_owner_id",
     "startup_notify_channel_id",
@@ -327,6 +519,58 @@ pub fn validate_channel_setting(setting: &str, value: &str) -> (bool, &'static s
                 (false, "Invalid value. Use: `enabled` or `disabled`.")
             }
         }
+        "birthdays" => {
+            if ENABLED_DISABLED_VALUES.contains(&value) {
+                (true, "")
+            } else {
+                (false, "Invalid value. Use: `enabled` or `disabled`.")
+            }
+        }
+        "achievements" => {
+            if ENABLED_DISABLED_VALUES.contains(&value) {
+                (true, "")
+            } else {
+                (false, "Invalid value. Use: `enabled` or `disabled`.")
+            }
+        }
+        "sentiment_tracking" | "time_detection" => {
+            if ENABLED_DISABLED_VALUES.contains(&value) {
+                (true, "")
+            } else {
+                (false, "Invalid value. Use: `enabled` or `disabled`.")
+            }
+        }
+        "language" => {
+            if value == "disabled" || find_language(value).is_some() {
+                (true, "")
+            } else {
+                (
+                    false,
+                    "Invalid language. Use one of: `en`, `es`, `fr`, `de`, `pt`, `it`, `nl`, or `disabled`.",
+                )
+            }
+        }
+        "link_previews" => {
+            if LINK_PREVIEW_MODES.contains(&value) {
+                (true, "")
+            } else {
+                (
+                    false,
+                    "Invalid value. Use: `reply`, `thread` or `disabled`.",
+                )
+            }
+        }
+        "scene" => {
+            let scene = value.trim();
+            if scene.is_empty() || scene.chars().count() > MAX_SCENE_CHARS {
+                (
+                    false,
+                    "Invalid scene. Describe the scene in up to 2000 characters, or use `clear`.",
+                )
+            } else {
+                (true, "")
+            }
+        }
         "max_paragraphs" => {
             if let Ok(num) = value.parse::<i64>() {
                 if num == 0 || (1..=10).contains(&num) {
@@ -362,11 +606,30 @@ pub fn validate_guild_setting(setting: &str, value: &str) -> (bool, &'static str
                 (false, "Invalid persona. Use one of: `obi`, `muppet`, `chef`, `teacher`, `analyst`, `visionary`, `noir`, `zen`, `bard`, `coach`, `scientist`, `gamer`.")
             }
         }
+        "category_persona" => {
+            if value == "clear" || PERSONA_VALUES.contains(&value) {
+                (true, "")
+            } else {
+                (false, "Invalid persona. Use one of: `obi`, `muppet`, `chef`, `teacher`, `analyst`, `visionary`, `noir`, `zen`, `bard`, `coach`, `scientist`, `gamer`, or `clear`.")
+            }
+        }
+        "persona_rotation" => match parse_rotation(value) {
+            Ok(_) => (true, ""),
+            Err(e) => (false, e),
+        },
+        "seasonal_themes" => match parse_theme_schedule(value) {
+            Ok(_) => (true, ""),
+            Err(e) => (false, e),
+        },
         "conflict_mediation"
         | "audio_transcription"
         | "mention_responses"
         | "response_embeds"
-        | "debate_auto_response" => {
+        | "custom_emojis"
+        | "debate_auto_response"
+        | "privacy_analytics"
+        | "privacy_conflict_analysis"
+        | "answer_disclosure" => {
             if ENABLED_DISABLED_VALUES.contains(&value) {
                 (true, "")
             } else {
@@ -427,6 +690,17 @@ pub fn validate_guild_setting(setting: &str, value: &str) -> (bool, &'static str
                 (false, "Invalid value. Use: `enabled` or `disabled`.")
             }
         }
+        "language_exempt_role" => {
+            // A Discord role ID (numeric), or `none` to clear it
+            if value == "none" || (!value.is_empty() && value.parse::<u64>().is_ok()) {
+                (true, "")
+            } else {
+                (
+                    false,
+                    "Invalid role ID. Enter a valid Discord role ID (numeric) or `none`.",
+                )
+            }
+        }
         "startup_notify_owner_id" => {
             // Must be a valid Discord user ID (numeric)
             if !value.is_empty() && value.parse::<u64>().is_ok() {
@@ -472,7 +746,22 @@ mod tests {
     #[test]
     fn test_create_admin_commands() {
         let commands = create_commands();
-        assert_eq!(commands.len(), 9, "Should have 9 admin commands");
+        assert_eq!(commands.len(), 12, "Should have 12 admin commands");
+    }
+
+    #[test]
+    fn test_admin_command_subcommands() {
+        let admin = create_admin_command();
+        let names: Vec<&str> = admin.0["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            ["update", "presence", "block", "allow", "unblock", "access"]
+        );
     }
 
     // ==================== User Setting Validation Tests ====================
@@ -566,6 +855,56 @@ mod tests {
         assert!(msg.contains("enabled"));
     }
 
+    #[test]
+    fn test_validate_channel_birthdays() {
+        assert!(validate_channel_setting("birthdays", "enabled").0);
+        assert!(validate_channel_setting("birthdays", "disabled").0);
+        assert!(!validate_channel_setting("birthdays", "#general").0);
+    }
+
+    #[test]
+    fn test_validate_channel_achievements() {
+        assert!(validate_channel_setting("achievements", "enabled").0);
+        assert!(!validate_channel_setting("achievements", "reply").0);
+    }
+
+    #[test]
+    fn test_validate_channel_link_previews() {
+        assert!(validate_channel_setting("link_previews", "reply").0);
+        assert!(validate_channel_setting("link_previews", "thread").0);
+        assert!(validate_channel_setting("link_previews", "disabled").0);
+        assert!(!validate_channel_setting("link_previews", "enabled").0);
+    }
+
+    #[test]
+    fn test_validate_channel_sentiment_tracking() {
+        assert!(validate_channel_setting("sentiment_tracking", "enabled").0);
+        assert!(validate_channel_setting("sentiment_tracking", "disabled").0);
+        assert!(!validate_channel_setting("sentiment_tracking", "reply").0);
+    }
+
+    #[test]
+    fn test_validate_channel_time_detection() {
+        assert!(validate_channel_setting("time_detection", "enabled").0);
+        assert!(validate_channel_setting("time_detection", "disabled").0);
+        assert!(!validate_channel_setting("time_detection", "3pm").0);
+    }
+
+    #[test]
+    fn test_validate_channel_scene() {
+        assert!(validate_channel_setting("scene", "A rainy port city in 1920s Shanghai").0);
+        assert!(validate_channel_setting("scene", "clear").0);
+        assert!(!validate_channel_setting("scene", "   ").0);
+        assert!(!validate_channel_setting("scene", &"a".repeat(MAX_SCENE_CHARS + 1)).0);
+    }
+
+    #[test]
+    fn test_validate_channel_language() {
+        assert!(validate_channel_setting("language", "fr").0);
+        assert!(validate_channel_setting("language", "disabled").0);
+        assert!(!validate_channel_setting("language", "french").0);
+    }
+
     #[test]
     fn test_validate_channel_unknown_setting() {
         let (valid, msg) = validate_channel_setting("unknown_setting", "value");
@@ -603,6 +942,15 @@ mod tests {
         assert!(validate_guild_setting("default_verbosity", "detailed").0);
     }
 
+    #[test]
+    fn test_validate_guild_persona_rotation_and_themes() {
+        assert!(validate_guild_setting("persona_rotation", "obi, chef").0);
+        assert!(validate_guild_setting("persona_rotation", "disabled").0);
+        assert!(!validate_guild_setting("persona_rotation", "obi").0);
+        assert!(validate_guild_setting("seasonal_themes", "halloween@10-20..10-31").0);
+        assert!(!validate_guild_setting("seasonal_themes", "halloween@10-20").0);
+    }
+
     #[test]
     fn test_validate_guild_default_verbosity_invalid() {
         let (valid, msg) = validate_guild_setting("default_verbosity", "brief");
@@ -633,6 +981,14 @@ mod tests {
         assert!(msg.contains("obi"));
     }
 
+    #[test]
+    fn test_validate_guild_category_persona() {
+        assert!(validate_guild_setting("category_persona", "chef").0);
+        assert!(validate_guild_setting("category_persona", "clear").0);
+        assert!(!validate_guild_setting("category_persona", "unknown").0);
+        assert!(!validate_guild_setting("default_persona", "clear").0);
+    }
+
     #[test]
     fn test_validate_guild_conflict_sensitivity_valid() {
         assert!(validate_guild_setting("conflict_sensitivity", "low").0);
@@ -701,6 +1057,13 @@ mod tests {
         assert!(validate_guild_setting("startup_notify_owner_id", "123456789").0);
     }
 
+    #[test]
+    fn test_validate_guild_language_exempt_role() {
+        assert!(validate_guild_setting("language_exempt_role", "123456789").0);
+        assert!(validate_guild_setting("language_exempt_role", "none").0);
+        assert!(!validate_guild_setting("language_exempt_role", "@mods").0);
+    }
+
     #[test]
     fn test_validate_guild_unknown_setting() {
         let (valid, msg) = validate_guild_setting("unknown_setting", "value");
@@ -710,11 +1073,16 @@ mod tests {
 
     #[test]
     fn test_channel_settings_list() {
-        assert_eq!(CHANNEL_SETTINGS.len(), 4);
+        assert_eq!(CHANNEL_SETTINGS.len(), 9);
         assert!(CHANNEL_SETTINGS.contains(&"verbosity"));
         assert!(CHANNEL_SETTINGS.contains(&"persona"));
         assert!(CHANNEL_SETTINGS.contains(&"conflict_mediation"));
         assert!(CHANNEL_SETTINGS.contains(&"max_paragraphs"));
+        assert!(CHANNEL_SETTINGS.contains(&"link_previews"));
+        assert!(CHANNEL_SETTINGS.contains(&"sentiment_tracking"));
+        assert!(CHANNEL_SETTINGS.contains(&"language"));
+        assert!(CHANNEL_SETTINGS.contains(&"time_detection"));
+        assert!(CHANNEL_SETTINGS.contains(&"scene"));
     }
 
     #[test]
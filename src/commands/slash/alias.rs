@@ -0,0 +1,136 @@
+//! # Alias Command
+//!
+//! Save prompt templates under short names and run them through /ask.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::{CreateApplicationCommand, CreateApplicationCommandOption};
+use serenity::model::application::command::CommandOptionType;
+
+use crate::features::aliases::{MAX_NAME_CHARS, MAX_TEMPLATE_CHARS};
+use crate::features::personas::add_persona_choices;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_alias_command()]
+}
+
+fn name_option(sub: &mut CreateApplicationCommandOption) -> &mut CreateApplicationCommandOption {
+    sub.name("name")
+        .description("Alias name")
+        .kind(CommandOptionType::String)
+        .required(true)
+        .min_length(1)
+        .max_length(MAX_NAME_CHARS as u16)
+}
+
+fn persona_option(sub: &mut CreateApplicationCommandOption) -> &mut CreateApplicationCommandOption {
+    sub.name("persona")
+        .description("Persona to ask (defaults to your current persona)")
+        .kind(CommandOptionType::String)
+        .required(false);
+    add_persona_choices(sub);
+    sub
+}
+
+fn shared_option(sub: &mut CreateApplicationCommandOption) -> &mut CreateApplicationCommandOption {
+    sub.name("shared")
+        .description("The server's shared alias instead of your own (needs Manage Server)")
+        .kind(CommandOptionType::Boolean)
+        .required(false)
+}
+
+fn create_alias_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("alias")
+        .description("Shortcuts for prompts you use often")
+        .create_option(|option| {
+            option
+                .name("create")
+                .description("Save a prompt template; ${name} placeholders are filled when it runs")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(name_option)
+                .create_sub_option(|sub| {
+                    sub.name("prompt")
+                        .description("Prompt template, e.g. Explain ${topic} to a beginner")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .min_length(1)
+                        .max_length(MAX_TEMPLATE_CHARS as u16)
+                })
+                .create_sub_option(persona_option)
+                .create_sub_option(shared_option)
+        })
+        .create_option(|option| {
+            option
+                .name("run")
+                .description("Fill in an alias and ask it")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(name_option)
+                .create_sub_option(|sub| {
+                    sub.name("args")
+                        .description(
+                            "Values for the placeholders, in order; quote multi-word values",
+                        )
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .max_length(2000)
+                })
+                .create_sub_option(persona_option)
+                .create_sub_option(|sub| {
+                    sub.name("ignore_context")
+                        .description("Skip fetching channel/thread history (default: false)")
+                        .kind(CommandOptionType::Boolean)
+                        .required(false)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("list")
+                .description("Show your aliases and the server's shared ones")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("delete")
+                .description("Delete an alias")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(name_option)
+                .create_sub_option(shared_option)
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_alias_command() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 1);
+
+        let command = &commands[0];
+        assert_eq!(command.0["name"], "alias");
+
+        let subcommands: Vec<&str> = command.0["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(subcommands, ["create", "run", "list", "delete"]);
+
+        let run_options: Vec<&str> = command.0["options"][1]["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(run_options, ["name", "args", "persona", "ignore_context"]);
+    }
+}
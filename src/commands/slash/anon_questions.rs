@@ -0,0 +1,139 @@
+//! # Anonymous Question Commands
+//!
+//! Ask a question anonymously and moderate the anonymous question queue.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::{CreateApplicationCommand, CreateApplicationCommandOption};
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::channel::ChannelType;
+use serenity::model::permissions::Permissions;
+
+use crate::features::anon_questions::ASK_COMMAND;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_ask_anon_command(), create_anon_command()]
+}
+
+fn create_ask_anon_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name(ASK_COMMAND)
+        .description("Ask the server a question without your name on it")
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("question")
+                .description("Your question")
+                .kind(CommandOptionType::String)
+                .required(true)
+                .max_length(1000)
+        });
+    command
+}
+
+fn id_option(sub: &mut CreateApplicationCommandOption) -> &mut CreateApplicationCommandOption {
+    sub.name("id")
+        .description("Question number from /anon queue")
+        .kind(CommandOptionType::Integer)
+        .required(true)
+        .min_int_value(1)
+}
+
+fn create_anon_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("anon")
+        .description("Manage anonymous questions")
+        .dm_permission(false)
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .create_option(|option| {
+            option
+                .name("setup")
+                .description("Choose where anonymous questions go and how they're handled")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("channel")
+                        .description("Channel questions are posted in")
+                        .kind(CommandOptionType::Channel)
+                        .channel_types(&[ChannelType::Text])
+                        .required(true)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("moderation")
+                        .description("Hold questions for approval before posting")
+                        .kind(CommandOptionType::Boolean)
+                        .required(false)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("review_channel")
+                        .description("Channel where new questions are announced for review")
+                        .kind(CommandOptionType::Channel)
+                        .channel_types(&[ChannelType::Text])
+                        .required(false)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("persona_answers")
+                        .description("Have the channel's persona answer posted questions")
+                        .kind(CommandOptionType::Boolean)
+                        .required(false)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("queue")
+                .description("Show questions waiting for review")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("approve")
+                .description("Post a queued question")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(id_option)
+        })
+        .create_option(|option| {
+            option
+                .name("reject")
+                .description("Drop a queued question")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(id_option)
+        })
+        .create_option(|option| {
+            option
+                .name("reveal")
+                .description("Show who asked a question, for handling abuse")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(id_option)
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_anon_commands() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[0].0["name"], "ask_anon");
+        assert_eq!(commands[1].0["name"], "anon");
+
+        let subcommands: Vec<&str> = commands[1].0["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            subcommands,
+            ["setup", "queue", "approve", "reject", "reveal"]
+        );
+        assert_eq!(commands[1].0["options"][2]["options"][0]["name"], "id");
+    }
+}
@@ -1,11 +1,14 @@
-//! # Ask Command
+//! # Ask Commands
 //!
-//! Request a response from any persona with a custom prompt.
+//! Request a response from any persona (or a few at once) with a custom prompt.
 //!
-//! - **Version**: 1.1.0
+//! - **Version**: 1.4.0
 //! - **Since**: 3.30.0
 //!
 //! ## Changelog
+//! - 1.4.0: /ask disclosure option for a confidence and sources footer
+//! - 1.3.0: /ask devils_advocate option for a rebuttal from a second persona
+//! - 1.2.0: Add /ask_all for side-by-side answers from 2-3 personas
 //! - 1.1.0: Use shared PERSONA_CHOICES from personas::choices
 //! - 1.0.0: Initial implementation
 
@@ -14,9 +17,12 @@ use serenity::builder::CreateApplicationCommand;
 use serenity::model::application::command::CommandOptionType;
 
 pub fn create_commands() -> Vec<CreateApplicationCommand> {
-    vec![create_ask_command()]
+    vec![create_ask_command(), create_ask_all_command()]
 }
 
+/// Most personas /ask_all will gather in one reply
+pub const ASK_ALL_MAX_PERSONAS: usize = 3;
+
 fn create_ask_command() -> CreateApplicationCommand {
     let mut command = CreateApplicationCommand::default();
     command
@@ -57,7 +63,55 @@ fn create_ask_command() -> CreateApplicationCommand {
                 .required(false)
                 .min_int_value(0)
                 .max_int_value(10)
+        })
+        .create_option(|option| {
+            option
+                .name("devils_advocate")
+                .description("A second persona who argues against the answer")
+                .kind(CommandOptionType::String)
+                .required(false);
+            for (name, value) in PERSONA_CHOICES {
+                option.add_string_choice(name, value);
+            }
+            option
+        })
+        .create_option(|option| {
+            option
+                .name("disclosure")
+                .description("Show confidence, sources and assumptions (default: server setting)")
+                .kind(CommandOptionType::Boolean)
+                .required(false)
+        });
+    command
+}
+
+fn create_ask_all_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("ask_all")
+        .description("Ask 2-3 personas the same question and compare their answers");
+    for i in 1..=ASK_ALL_MAX_PERSONAS {
+        command.create_option(|option| {
+            option
+                .name(format!("persona{i}"))
+                .description(format!("Persona #{i}"))
+                .kind(CommandOptionType::String)
+                .required(i <= 2);
+            for (name, value) in PERSONA_CHOICES {
+                option.add_string_choice(name, value);
+            }
+            option
         });
+    }
+    command.create_option(|option| {
+        option
+            .name("prompt")
+            .description("Your question or prompt for the personas")
+            .kind(CommandOptionType::String)
+            .required(true)
+            .min_length(1)
+            .max_length(2000)
+    });
     command
 }
 
@@ -68,13 +122,28 @@ mod tests {
     #[test]
     fn test_create_ask_command() {
         let commands = create_commands();
-        assert_eq!(commands.len(), 1);
+        assert_eq!(commands.len(), 2);
 
         let ask = &commands[0];
         let name = ask.0.get("name").unwrap().as_str().unwrap();
         assert_eq!(name, "ask");
     }
 
+    #[test]
+    fn test_ask_all_requires_two_personas() {
+        let commands = create_commands();
+        let ask_all = &commands[1];
+        assert_eq!(ask_all.0.get("name").unwrap().as_str().unwrap(), "ask_all");
+
+        let options = ask_all.0.get("options").unwrap().as_array().unwrap();
+        let required: Vec<&str> = options
+            .iter()
+            .filter(|o| o["required"] == true)
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(required, vec!["persona1", "persona2", "prompt"]);
+    }
+
     #[test]
     fn test_persona_choices_complete() {
         // Ensure all 17 personas are available
@@ -0,0 +1,160 @@
+//! # Auto-Response Command
+//!
+//! Manage keyword and regex triggers answered with canned or persona replies.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::{CreateApplicationCommand, CreateApplicationCommandOption};
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::permissions::Permissions;
+
+use crate::features::autoresponse::{MAX_COOLDOWN_SECS, MAX_PATTERN_CHARS, MAX_RESPONSE_CHARS};
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_autoresponse_command()]
+}
+
+fn id_option(sub: &mut CreateApplicationCommandOption) -> &mut CreateApplicationCommandOption {
+    sub.name("id")
+        .description("Trigger number from /autoresponse list")
+        .kind(CommandOptionType::Integer)
+        .required(true)
+        .min_int_value(1)
+}
+
+fn create_autoresponse_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("autoresponse")
+        .description("Answer messages matching a keyword or regex automatically")
+        .dm_permission(false)
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .create_option(|option| {
+            option
+                .name("add")
+                .description("Add a trigger")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("pattern")
+                        .description("Keyword or regex to look for")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .max_length(MAX_PATTERN_CHARS as u16)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("response")
+                        .description("Reply text ({user} mentions the author), or instructions for the persona")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .max_length(MAX_RESPONSE_CHARS as u16)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("mode")
+                        .description("How to answer (defaults to the reply as written)")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .add_string_choice("Post the reply as written", "reply")
+                        .add_string_choice("Persona writes a reply from the instructions", "persona")
+                })
+                .create_sub_option(|sub| {
+                    sub.name("match")
+                        .description("How to match the pattern (defaults to a whole-word keyword)")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .add_string_choice("Keyword or phrase", "keyword")
+                        .add_string_choice("Regular expression", "regex")
+                })
+                .create_sub_option(|sub| {
+                    sub.name("cooldown_seconds")
+                        .description("Wait this long before the trigger fires again (default 60)")
+                        .kind(CommandOptionType::Integer)
+                        .required(false)
+                        .min_int_value(0)
+                        .max_int_value(MAX_COOLDOWN_SECS)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("priority")
+                        .description("Higher priorities are checked first (default 0)")
+                        .kind(CommandOptionType::Integer)
+                        .required(false)
+                        .min_int_value(-100)
+                        .max_int_value(100)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("list")
+                .description("Show this server's triggers in the order they're checked")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("remove")
+                .description("Delete a trigger")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(id_option)
+        })
+        .create_option(|option| {
+            option
+                .name("enable")
+                .description("Turn a trigger back on")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(id_option)
+        })
+        .create_option(|option| {
+            option
+                .name("disable")
+                .description("Turn a trigger off without deleting it")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(id_option)
+        })
+        .create_option(|option| {
+            option
+                .name("priority")
+                .description("Change the order a trigger is checked in")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(id_option)
+                .create_sub_option(|sub| {
+                    sub.name("value")
+                        .description("Higher priorities are checked first")
+                        .kind(CommandOptionType::Integer)
+                        .required(true)
+                        .min_int_value(-100)
+                        .max_int_value(100)
+                })
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_autoresponse_command() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 1);
+
+        let command = &commands[0];
+        assert_eq!(command.0["name"], "autoresponse");
+
+        let subcommands: Vec<&str> = command.0["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            subcommands,
+            ["add", "list", "remove", "enable", "disable", "priority"]
+        );
+        assert_eq!(
+            command.0["options"][0]["options"][4]["max_value"],
+            MAX_COOLDOWN_SECS
+        );
+    }
+}
@@ -0,0 +1,109 @@
+//! # Birthday Command
+//!
+//! Save birthdays and anniversaries for the server's daily wishes.
+//!
+//! - **Version**: 1.1.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.1.0: Timezone option takes IANA zone names
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::{CreateApplicationCommand, CreateApplicationCommandOption};
+use serenity::model::application::command::CommandOptionType;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_birthday_command()]
+}
+
+fn kind_option(sub: &mut CreateApplicationCommandOption) -> &mut CreateApplicationCommandOption {
+    sub.name("kind")
+        .description("Birthday or anniversary (defaults to birthday)")
+        .kind(CommandOptionType::String)
+        .required(false)
+        .add_string_choice("Birthday", "birthday")
+        .add_string_choice("Anniversary", "anniversary")
+}
+
+fn create_birthday_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("birthday")
+        .description("Save your birthday or anniversary so your persona can celebrate it here")
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("set")
+                .description("Save or change a date")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("date")
+                        .description("MM-DD, or YYYY-MM-DD for an anniversary to count the years")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .max_length(10)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("timezone")
+                        .description(
+                            "Your zone, e.g. Europe/Berlin or UTC+2 (defaults to your /time zone)",
+                        )
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .max_length(40)
+                })
+                .create_sub_option(kind_option)
+        })
+        .create_option(|option| {
+            option
+                .name("remove")
+                .description("Forget a saved date (both when no kind is given)")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(kind_option)
+        })
+        .create_option(|option| {
+            option
+                .name("list")
+                .description("Show upcoming birthdays and anniversaries in this server")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("wishes")
+                .description("Opt out of (or back into) public wishes without deleting your dates")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("enabled")
+                        .description("Whether the bot may wish you in this server")
+                        .kind(CommandOptionType::Boolean)
+                        .required(true)
+                })
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_birthday_command() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 1);
+
+        let birthday = &commands[0];
+        let name = birthday.0.get("name").unwrap().as_str().unwrap();
+        assert_eq!(name, "birthday");
+
+        let subcommands: Vec<&str> = birthday
+            .0
+            .get("options")
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(subcommands, ["set", "remove", "list", "wishes"]);
+    }
+}
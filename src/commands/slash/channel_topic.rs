@@ -0,0 +1,134 @@
+//! # Channel Topic Command
+//!
+//! Schedule a channel's topic to rotate through a list or to show an
+//! AI-written question of the day.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::channel::ChannelType;
+use serenity::model::Permissions;
+
+use crate::features::channel_topics::MAX_INTERVAL_HOURS;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_channel_topic_command()]
+}
+
+fn create_channel_topic_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("channel_topic")
+        .description("Change a channel's topic on a schedule")
+        .default_member_permissions(Permissions::MANAGE_CHANNELS)
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("schedule")
+                .description("Rotate a channel's topic or set a daily question")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("mode")
+                        .description("Where new topics come from")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .add_string_choice("Rotate through my topics", "rotate")
+                        .add_string_choice("AI question of the day", "question")
+                })
+                .create_sub_option(|sub| {
+                    sub.name("channel")
+                        .description("Channel to update (defaults to this one)")
+                        .kind(CommandOptionType::Channel)
+                        .channel_types(&[ChannelType::Text, ChannelType::News])
+                        .required(false)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("topics")
+                        .description("Rotate mode: topics separated by |")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .max_length(4000)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("theme")
+                        .description("Question mode: what the questions are about")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .max_length(100)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("every_hours")
+                        .description("Hours between updates (defaults to 24)")
+                        .kind(CommandOptionType::Integer)
+                        .required(false)
+                        .min_int_value(1)
+                        .max_int_value(MAX_INTERVAL_HOURS)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("hour")
+                        .description("Local hour of the first update, 0-23 (defaults to now)")
+                        .kind(CommandOptionType::Integer)
+                        .required(false)
+                        .min_int_value(0)
+                        .max_int_value(23)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("timezone")
+                        .description("UTC offset for the hour, e.g. UTC+2 (defaults to UTC)")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .max_length(12)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("stop")
+                .description("Stop a channel's scheduled topic updates")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("channel")
+                        .description("Channel to stop (defaults to this one)")
+                        .kind(CommandOptionType::Channel)
+                        .channel_types(&[ChannelType::Text, ChannelType::News])
+                        .required(false)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("list")
+                .description("Show this server's scheduled topic updates")
+                .kind(CommandOptionType::SubCommand)
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_channel_topic_command() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 1);
+
+        let command = &commands[0];
+        assert_eq!(command.0["name"], "channel_topic");
+
+        let subcommands: Vec<&str> = command.0["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(subcommands, ["schedule", "stop", "list"]);
+
+        let schedule = &command.0["options"][0]["options"];
+        assert_eq!(schedule[0]["name"], "mode");
+        assert_eq!(schedule[0]["choices"].as_array().unwrap().len(), 2);
+    }
+}
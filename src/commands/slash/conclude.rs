@@ -2,13 +2,15 @@
 //!
 //! End the current council or debate session in a thread.
 //!
-//! - **Version**: 1.0.0
+//! - **Version**: 1.1.0
 //! - **Since**: 3.33.0
 //!
 //! ## Changelog
+//! - 1.1.0: Optional conclusion for the council to vote on
 //! - 1.0.0: Initial implementation
 
 use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
 
 pub fn create_commands() -> Vec<CreateApplicationCommand> {
     vec![create_conclude_command()]
@@ -18,7 +20,15 @@ fn create_conclude_command() -> CreateApplicationCommand {
     let mut command = CreateApplicationCommand::default();
     command
         .name("conclude")
-        .description("End the current council or debate session in this thread");
+        .description("End the current council or debate session in this thread")
+        .create_option(|option| {
+            option
+                .name("conclusion")
+                .description("Conclusion for the council to vote on (drafted for you if left out)")
+                .kind(CommandOptionType::String)
+                .required(false)
+                .max_length(500)
+        });
     command
 }
 
@@ -1,11 +1,12 @@
 //! # Context Info Command
 //!
-//! Shows users their current context window stats.
+//! Shows users what their next prompt would include.
 //!
-//! - **Version**: 1.0.0
+//! - **Version**: 1.1.0
 //! - **Since**: 4.4.0
 //!
 //! ## Changelog
+//! - 1.1.0: Description covers the per-message listing
 //! - 1.0.0: Initial implementation
 
 use serenity::builder::CreateApplicationCommand;
@@ -18,7 +19,7 @@ fn create_context_command() -> CreateApplicationCommand {
     let mut command = CreateApplicationCommand::default();
     command
         .name("context")
-        .description("Show your current context window — message count, token estimate, and active persona");
+        .description("Show what the next prompt includes — history, summary, attachments and token estimates");
     command
 }
 
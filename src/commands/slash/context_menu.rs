@@ -3,12 +3,18 @@
 use serenity::builder::CreateApplicationCommand;
 use serenity::model::application::command::CommandType;
 
+use crate::features::conversation_export::EXPORT_COMMAND;
+use crate::features::memories::REMEMBER_COMMAND;
+
 /// Creates context menu commands
 pub fn create_commands() -> Vec<CreateApplicationCommand> {
     vec![
         create_analyze_message_context_command(),
         create_explain_message_context_command(),
         create_analyze_user_context_command(),
+        create_ticket_context_command(),
+        create_export_conversation_context_command(),
+        create_remember_context_command(),
     ]
 }
 
@@ -35,3 +41,28 @@ fn create_analyze_user_context_command() -> CreateApplicationCommand {
         .kind(CommandType::User)
         .to_owned()
 }
+
+/// Creates the create ticket context menu command
+fn create_ticket_context_command() -> CreateApplicationCommand {
+    CreateApplicationCommand::default()
+        .name("Create Ticket")
+        .kind(CommandType::Message)
+        .dm_permission(false)
+        .to_owned()
+}
+
+/// Creates the export conversation context menu command
+fn create_export_conversation_context_command() -> CreateApplicationCommand {
+    CreateApplicationCommand::default()
+        .name(EXPORT_COMMAND)
+        .kind(CommandType::Message)
+        .to_owned()
+}
+
+/// Creates the remember this context menu command
+fn create_remember_context_command() -> CreateApplicationCommand {
+    CreateApplicationCommand::default()
+        .name(REMEMBER_COMMAND)
+        .kind(CommandType::Message)
+        .to_owned()
+}
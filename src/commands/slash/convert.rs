@@ -0,0 +1,76 @@
+//! # Convert Command
+//!
+//! Convert amounts between units and currencies.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_convert_command()]
+}
+
+fn create_convert_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("convert")
+        .description("Convert between units or currencies")
+        .create_option(|option| {
+            option
+                .name("amount")
+                .description("How much to convert")
+                .kind(CommandOptionType::Number)
+                .required(true)
+        })
+        .create_option(|option| {
+            option
+                .name("from")
+                .description("Unit or currency code to convert from, e.g. mi, °F, kg or USD")
+                .kind(CommandOptionType::String)
+                .required(true)
+                .max_length(32)
+        })
+        .create_option(|option| {
+            option
+                .name("to")
+                .description("Unit or currency code to convert to, e.g. km, °C, lb or EUR")
+                .kind(CommandOptionType::String)
+                .required(true)
+                .max_length(32)
+        })
+        .create_option(|option| {
+            option
+                .name("in_character")
+                .description("Have your persona phrase the result")
+                .kind(CommandOptionType::Boolean)
+                .required(false)
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_convert_command() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 1);
+
+        let command = &commands[0];
+        assert_eq!(command.0["name"], "convert");
+
+        let options: Vec<&str> = command.0["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(options, ["amount", "from", "to", "in_character"]);
+    }
+}
@@ -0,0 +1,94 @@
+//! # Countdown Command
+//!
+//! Post embeds counting down to an event.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::permissions::Permissions;
+
+use crate::features::countdown::MAX_TITLE_CHARS;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_countdown_command()]
+}
+
+fn create_countdown_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("countdown")
+        .description("Count down to an event with a live embed")
+        .dm_permission(false)
+        .default_member_permissions(Permissions::MANAGE_MESSAGES)
+        .create_option(|option| {
+            option
+                .name("start")
+                .description("Post a countdown in this channel")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("title")
+                        .description("What you're counting down to")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .max_length(MAX_TITLE_CHARS as u16)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("in")
+                        .description("How long from now (e.g., 2h, 3d, 1w2d)")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("role")
+                        .description("Role to ping when it reaches zero")
+                        .kind(CommandOptionType::Role)
+                        .required(false)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("list")
+                .description("Show this server's running countdowns")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("cancel")
+                .description("Stop a countdown and remove its embed")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("id")
+                        .description("Countdown ID from /countdown list")
+                        .kind(CommandOptionType::Integer)
+                        .required(true)
+                })
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_countdown_command() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 1);
+
+        let command = &commands[0];
+        assert_eq!(command.0["name"], "countdown");
+
+        let subcommands: Vec<&str> = command.0["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(subcommands, ["start", "list", "cancel"]);
+    }
+}
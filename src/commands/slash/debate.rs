@@ -2,16 +2,17 @@
 //!
 //! Creates a threaded debate between two personas on a given topic.
 //!
-//! - **Version**: 2.1.0
+//! - **Version**: 3.0.0
 //! - **Since**: 3.27.0
 //!
 //! ## Changelog
+//! - 3.0.0: Split into start and import subcommands; import continues a pasted or attached transcript
 //! - 2.1.0: Use shared PERSONA_CHOICES from personas::choices
 //! - 2.0.0: Added rules parameter, opening-only default, interactive controls
 //! - 1.0.0: Initial implementation
 
 use crate::features::personas::PERSONA_CHOICES;
-use serenity::builder::CreateApplicationCommand;
+use serenity::builder::{CreateApplicationCommand, CreateApplicationCommandOption};
 use serenity::model::application::command::CommandOptionType;
 
 /// Default number of responses in a debate (opening statements only)
@@ -34,53 +35,82 @@ fn create_debate_command() -> CreateApplicationCommand {
         .description("Start a threaded debate between two personas on a topic")
         .create_option(|option| {
             option
-                .name("persona1")
+                .name("start")
+                .description("Start a new debate between two personas")
+                .kind(CommandOptionType::SubCommand);
+            add_debate_options(option);
+            option
+        })
+        .create_option(|option| {
+            option
+                .name("import")
+                .description("Continue an argument started elsewhere from its transcript")
+                .kind(CommandOptionType::SubCommand);
+            add_debate_options(option);
+            option
+                .create_sub_option(|sub| {
+                    sub.name("transcript")
+                        .description("Pasted transcript, e.g. Alice: Yes! | Bob: No way.")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .max_length(6000)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("file")
+                        .description("Transcript as a text file")
+                        .kind(CommandOptionType::Attachment)
+                        .required(false)
+                })
+        });
+    command
+}
+
+/// Options shared by /debate start and /debate import
+fn add_debate_options(option: &mut CreateApplicationCommandOption) {
+    option
+        .create_sub_option(|sub| {
+            sub.name("persona1")
                 .description("First debater")
                 .kind(CommandOptionType::String)
                 .required(true);
             for (name, value) in PERSONA_CHOICES {
-                option.add_string_choice(name, value);
+                sub.add_string_choice(name, value);
             }
-            option
+            sub
         })
-        .create_option(|option| {
-            option
-                .name("persona2")
+        .create_sub_option(|sub| {
+            sub.name("persona2")
                 .description("Second debater")
                 .kind(CommandOptionType::String)
                 .required(true);
             for (name, value) in PERSONA_CHOICES {
-                option.add_string_choice(name, value);
+                sub.add_string_choice(name, value);
             }
-            option
+            sub
         })
-        .create_option(|option| {
-            option
-                .name("topic")
+        .create_sub_option(|sub| {
+            sub.name("topic")
                 .description("The topic or question to debate")
                 .kind(CommandOptionType::String)
                 .required(true)
                 .min_length(5)
                 .max_length(500)
         })
-        .create_option(|option| {
-            option
-                .name("rounds")
+        .create_sub_option(|sub| {
+            sub.name("rounds")
                 .description("Number of responses (default: 2 opening, 0 for interactive only)")
                 .kind(CommandOptionType::Integer)
                 .required(false)
                 .min_int_value(MIN_RESPONSES as u64)
                 .max_int_value(MAX_RESPONSES as u64)
         })
-        .create_option(|option| {
-            option
-                .name("rules")
+        .create_sub_option(|sub| {
+            sub.name("rules")
                 .description("Ground rules and term definitions for the debate")
                 .kind(CommandOptionType::String)
                 .required(false)
                 .max_length(1000)
         });
-    command
 }
 
 #[cfg(test)]
@@ -95,6 +125,14 @@ mod tests {
         let debate = &commands[0];
         let name = debate.0.get("name").unwrap().as_str().unwrap();
         assert_eq!(name, "debate");
+
+        let subcommands: Vec<&str> = debate.0["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(subcommands, ["start", "import"]);
     }
 
     #[test]
@@ -0,0 +1,148 @@
+//! # Dice Commands
+//!
+//! Roll dice notation and track initiative in a thread.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+
+use crate::features::dice::MAX_COMBATANT_CHARS;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_roll_command(), create_initiative_command()]
+}
+
+fn create_roll_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("roll")
+        .description("Roll dice, like 3d6+2, 4d6kh3 or d20 adv")
+        .create_option(|option| {
+            option
+                .name("dice")
+                .description("Dice notation, e.g. d20+5, 2d6+1d4, 4d6kh3 or d20 adv")
+                .kind(CommandOptionType::String)
+                .required(true)
+                .max_length(100)
+        })
+        .create_option(|option| {
+            option
+                .name("mode")
+                .description("Roll a single die twice and keep the better or worse")
+                .kind(CommandOptionType::String)
+                .required(false)
+                .add_string_choice("Advantage", "advantage")
+                .add_string_choice("Disadvantage", "disadvantage")
+        })
+        .create_option(|option| {
+            option
+                .name("reason")
+                .description("What the roll is for, e.g. stealth check")
+                .kind(CommandOptionType::String)
+                .required(false)
+                .max_length(100)
+        })
+        .create_option(|option| {
+            option
+                .name("narrate")
+                .description("Have your persona narrate the outcome")
+                .kind(CommandOptionType::Boolean)
+                .required(false)
+        });
+    command
+}
+
+fn create_initiative_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("initiative")
+        .description("Track turn order for an encounter in this thread")
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("add")
+                .description("Add a combatant, rolling d20 plus their modifier")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("name")
+                        .description("Combatant name (defaults to your name)")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .max_length(MAX_COMBATANT_CHARS as u16)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("modifier")
+                        .description("Initiative modifier added to the roll")
+                        .kind(CommandOptionType::Integer)
+                        .required(false)
+                        .min_int_value(-20)
+                        .max_int_value(20)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("value")
+                        .description("Use this initiative instead of rolling")
+                        .kind(CommandOptionType::Integer)
+                        .required(false)
+                        .min_int_value(-50)
+                        .max_int_value(100)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("list")
+                .description("Show the turn order")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("next")
+                .description("Move to the next combatant's turn")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("remove")
+                .description("Take a combatant out of the order")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("name")
+                        .description("Combatant name")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .max_length(MAX_COMBATANT_CHARS as u16)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("clear")
+                .description("End the encounter and clear the order")
+                .kind(CommandOptionType::SubCommand)
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_dice_commands() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[0].0["name"], "roll");
+        assert_eq!(commands[1].0["name"], "initiative");
+
+        let subcommands: Vec<&str> = commands[1].0["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(subcommands, ["add", "list", "next", "remove", "clear"]);
+    }
+}
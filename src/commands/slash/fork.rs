@@ -0,0 +1,55 @@
+//! # Fork Command
+//!
+//! Branch the current conversation into a new thread.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_fork_command()]
+}
+
+fn create_fork_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("fork")
+        .description("Continue this conversation in a new thread without changing the original")
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("name")
+                .description("Name for the new thread")
+                .kind(CommandOptionType::String)
+                .required(false)
+                .max_length(100)
+        })
+        .create_option(|option| {
+            option
+                .name("from_dm")
+                .description("Fork your DM conversation with me instead (opens a private thread)")
+                .kind(CommandOptionType::Boolean)
+                .required(false)
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_fork_command() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 1);
+
+        let fork = &commands[0];
+        let name = fork.0.get("name").unwrap().as_str().unwrap();
+        assert_eq!(name, "fork");
+    }
+}
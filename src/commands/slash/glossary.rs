@@ -0,0 +1,64 @@
+//! # Glossary Command
+//!
+//! The server's glossary of its own terms and acronyms: add, list and remove.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::{CreateApplicationCommand, CreateApplicationCommandOption};
+use serenity::model::application::command::CommandOptionType;
+
+use crate::features::glossary::{MAX_DEFINITION_CHARS, MAX_TERM_CHARS};
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_glossary_command()]
+}
+
+fn term_option(sub: &mut CreateApplicationCommandOption) -> &mut CreateApplicationCommandOption {
+    sub.name("term")
+        .description("Term or acronym, e.g. PvP")
+        .kind(CommandOptionType::String)
+        .required(true)
+        .min_length(1)
+        .max_length(MAX_TERM_CHARS as u16)
+}
+
+fn create_glossary_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("glossary")
+        .description("Terms with a special meaning on this server")
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("add")
+                .description("Define or redefine a term (needs Manage Server)")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(term_option)
+                .create_sub_option(|sub| {
+                    sub.name("definition")
+                        .description("What the term means here")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .min_length(1)
+                        .max_length(MAX_DEFINITION_CHARS as u16)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("list")
+                .description("Show the server's glossary")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("remove")
+                .description("Remove a term (needs Manage Server)")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(term_option)
+        });
+    command
+}
@@ -0,0 +1,76 @@
+//! # Guardrails Command
+//!
+//! Show or choose the server's guardrail profile, and how NSFW channels are
+//! treated.
+//!
+//! - **Version**: 1.1.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.1.0: /guardrails nsfw sets the NSFW channel profile and /imagine policy
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::Permissions;
+
+use crate::features::guardrails::GuardrailProfile;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_guardrails_command()]
+}
+
+fn create_guardrails_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("guardrails")
+        .description("Safety profile for this server's AI replies")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("show")
+                .description("Show the current profile and what it changes")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("set")
+                .description("Choose the profile every reply on this server follows")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("profile")
+                        .description("Strict, standard or relaxed")
+                        .kind(CommandOptionType::String)
+                        .required(true);
+                    for profile in GuardrailProfile::ALL {
+                        sub.add_string_choice(profile.as_str(), profile.as_str());
+                    }
+                    sub
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("nsfw")
+                .description("How channels marked NSFW are treated")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("profile")
+                        .description("Profile used in NSFW channels (same = the server's profile)")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .add_string_choice("same", "same");
+                    for profile in GuardrailProfile::ALL {
+                        sub.add_string_choice(profile.as_str(), profile.as_str());
+                    }
+                    sub
+                })
+                .create_sub_option(|sub| {
+                    sub.name("imagine")
+                        .description("Allow /imagine in NSFW channels (default: refused)")
+                        .kind(CommandOptionType::Boolean)
+                        .required(false)
+                })
+        });
+    command
+}
@@ -0,0 +1,24 @@
+//! # History Command
+//!
+//! Browse past DM conversations by title, recap one and resume it.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::CreateApplicationCommand;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_history_command()]
+}
+
+fn create_history_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("history")
+        .description("Browse your past DM conversations, recap one or pick it back up")
+        .dm_permission(true);
+    command
+}
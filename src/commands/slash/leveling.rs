@@ -0,0 +1,142 @@
+//! # Leveling Commands
+//!
+//! /rank and /leaderboard for members, /leveling for admins to turn XP on and
+//! set role rewards.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::channel::ChannelType;
+use serenity::model::Permissions;
+
+use crate::features::leveling::MAX_REWARD_LEVEL;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![
+        create_rank_command(),
+        create_leaderboard_command(),
+        create_leveling_command(),
+    ]
+}
+
+fn create_rank_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("rank")
+        .description("Show a member's level and XP in this server")
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("user")
+                .description("Member to look up (defaults to you)")
+                .kind(CommandOptionType::User)
+                .required(false)
+        });
+    command
+}
+
+fn create_leaderboard_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("leaderboard")
+        .description("Show this server's members with the most XP")
+        .dm_permission(false);
+    command
+}
+
+fn create_leveling_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("leveling")
+        .description("Configure XP and level rewards for this server")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("on")
+                .description("Start giving members XP for activity")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("channel")
+                        .description("Where to announce level-ups (defaults to where they happen)")
+                        .kind(CommandOptionType::Channel)
+                        .channel_types(&[ChannelType::Text])
+                        .required(false)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("off")
+                .description("Stop giving XP; earned XP is kept")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("reward")
+                .description("Give a role to members reaching a level")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("level")
+                        .description("Level that earns the role")
+                        .kind(CommandOptionType::Integer)
+                        .required(true)
+                        .min_int_value(1)
+                        .max_int_value(MAX_REWARD_LEVEL)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("role")
+                        .description("Role to give")
+                        .kind(CommandOptionType::Role)
+                        .required(true)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("unreward")
+                .description("Remove the role reward for a level")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("level")
+                        .description("Level whose reward to remove")
+                        .kind(CommandOptionType::Integer)
+                        .required(true)
+                        .min_int_value(1)
+                        .max_int_value(MAX_REWARD_LEVEL)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("status")
+                .description("Show whether XP is on and the role rewards")
+                .kind(CommandOptionType::SubCommand)
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_leveling_commands() {
+        let commands = create_commands();
+        let names: Vec<&str> = commands
+            .iter()
+            .map(|c| c.0["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["rank", "leaderboard", "leveling"]);
+
+        let subcommands: Vec<&str> = commands[2].0["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(subcommands, ["on", "off", "reward", "unreward", "status"]);
+    }
+}
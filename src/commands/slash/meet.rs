@@ -0,0 +1,106 @@
+//! # Meet Command
+//!
+//! Poll members for the time slot that suits the most people.
+//!
+//! - **Version**: 1.1.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.1.0: Timezone option takes IANA zone names
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_meet_command()]
+}
+
+fn create_meet_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("meet")
+        .description("Find a meeting time that works for everyone")
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("propose")
+                .description("Post time options as buttons so members can mark when they're free")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("title")
+                        .description("What the meeting is about")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .max_length(100)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("slots")
+                        .description("Up to 5 times as YYYY-MM-DD HH:MM, separated by commas")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .max_length(200)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("timezone")
+                        .description("Zone the times are in, e.g. Europe/Berlin (defaults to your /time zone)")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .max_length(40)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("duration")
+                        .description("Meeting length in minutes (defaults to 60)")
+                        .kind(CommandOptionType::Integer)
+                        .required(false)
+                        .min_int_value(5)
+                        .max_int_value(720)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("event")
+                        .description(
+                            "Create a server event for the chosen time (defaults to false)",
+                        )
+                        .kind(CommandOptionType::Boolean)
+                        .required(false)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("reminder")
+                        .description(
+                            "Minutes before to remind participants, 0 for none (defaults to 15)",
+                        )
+                        .kind(CommandOptionType::Integer)
+                        .required(false)
+                        .min_int_value(0)
+                        .max_int_value(1440)
+                })
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_meet_command() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 1);
+
+        let meet = &commands[0];
+        assert_eq!(meet.0.get("name").unwrap().as_str().unwrap(), "meet");
+
+        let propose = &meet.0["options"][0];
+        assert_eq!(propose["name"], "propose");
+        let options: Vec<&str> = propose["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            options,
+            ["title", "slots", "timezone", "duration", "event", "reminder"]
+        );
+    }
+}
@@ -0,0 +1,62 @@
+//! # Memory Command
+//!
+//! List and forget the messages pinned with the Remember this context menu.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_memory_command()]
+}
+
+fn create_memory_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("memory")
+        .description("Messages you pinned for me to remember")
+        .dm_permission(true)
+        .create_option(|option| {
+            option
+                .name("list")
+                .description("Show your pinned memories")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("everywhere")
+                        .description("Include every channel, not just this one")
+                        .kind(CommandOptionType::Boolean)
+                        .required(false)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("forget")
+                .description("Forget one memory")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("id")
+                        .description("Memory number from /memory list")
+                        .kind(CommandOptionType::Integer)
+                        .required(true)
+                        .min_int_value(1)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("clear")
+                .description("Forget all your memories in this channel")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("everywhere")
+                        .description("Forget them in every channel")
+                        .kind(CommandOptionType::Boolean)
+                        .required(false)
+                })
+        });
+    command
+}
@@ -2,34 +2,107 @@
 //!
 //! Discord native slash commands with autocomplete and validation.
 //!
-//! - **Version**: 2.0.0
+//! - **Version**: 2.35.0
 //! - **Since**: 0.2.0
 //! - **Toggleable**: false
 //!
 //! ## Changelog
+//! - 2.35.0: Add /glossary
+//! - 2.34.0: Add /memory and the Remember this context menu
+//! - 2.33.0: Add /history
+//! - 2.32.0: Add /guardrails
+//! - 2.31.0: Add /roll and /initiative
+//! - 2.30.0: Add /convert
+//! - 2.29.0: Add /time
+//! - 2.28.0: Add /countdown
+//! - 2.27.0: Add /send_later
+//! - 2.26.0: Add the Export Conversation context menu
+//! - 2.25.0: Add /prompts and get_attachment_option
+//! - 2.24.0: Add /alias
+//! - 2.23.0: Add /autoresponse
+//! - 2.22.0: Add /verification
+//! - 2.21.0: Add /ask_anon and /anon
+//! - 2.20.0: Add /support
+//! - 2.19.0: Add /suggest and /suggestions
+//! - 2.18.0: Add /qotd
+//! - 2.17.0: Add /channel_topic
+//! - 2.16.0: Add /rank, /leaderboard and /leveling
+//! - 2.15.0: Add /profile
+//! - 2.14.0: Add /mood
+//! - 2.13.0: Add /topics
+//! - 2.12.0: Add /report
+//! - 2.11.0: Registration with plugins takes an Http client so IPC reloads can re-register
+//! - 2.10.0: Add /telegram when built with the telegram feature
+//! - 2.9.0: Add /webhooks and get_number_option
+//! - 2.8.0: Add /ticket and the Create Ticket context menu
+//! - 2.7.0: Add /meet
+//! - 2.6.0: Add /standup
+//! - 2.5.0: Add /story
+//! - 2.4.0: Add /trivia
+//! - 2.3.0: Add /birthday
+//! - 2.2.0: Add /privacy
+//! - 2.1.0: Add /fork
 //! - 2.0.0: Consolidate plugins into single /plugins command with subcommands
 //! - 1.0.0: Reorganized from monolithic slash_commands.rs
 
 pub mod admin;
-mod ask;
+mod alias;
+mod anon_questions;
+pub mod ask;
+mod autoresponse;
+mod birthday;
+mod channel_topic;
 pub mod conclude;
 mod context_menu;
+mod convert;
 pub mod council;
+mod countdown;
 pub mod debate;
+mod dice;
 mod dm_stats;
 mod context_info;
 mod fetch;
+mod fork;
+mod glossary;
+mod guardrails;
+mod history;
 mod imagine;
+mod leveling;
+mod meet;
+mod memory;
+mod mood;
 mod persona;
+mod privacy;
+mod profile;
+mod prompts;
+mod qotd;
 mod remind;
+mod report;
+mod send_later;
+mod standup;
+mod story;
+mod suggestions;
+mod support;
+#[cfg(feature = "telegram")]
+mod telegram;
+mod ticket;
+mod time;
+pub mod topics;
+mod trivia;
 mod utility;
+mod verification;
+mod webhooks;
 
 use crate::features::plugins::{create_plugins_command, Plugin};
 use anyhow::Result;
 use log::info;
 use serenity::builder::CreateApplicationCommand;
+use serenity::http::Http;
 use serenity::model::application::command::Command;
-use serenity::model::application::interaction::application_command::CommandDataOption;
+use serenity::model::application::interaction::application_command::{
+    CommandDataOption, CommandDataOptionValue,
+};
+use serenity::model::channel::Attachment;
 use serenity::model::id::GuildId;
 use serenity::prelude::Context;
 
@@ -78,6 +151,72 @@ pub fn create_slash_commands_with_plugins(plugins: &[Plugin]) -> Vec<CreateAppli
     // Context info command
     commands.extend(context_info::create_commands());
 
+    // Fork command
+    commands.extend(fork::create_commands());
+
+    // Privacy command
+    commands.extend(privacy::create_commands());
+
+    // Birthday command
+    commands.extend(birthday::create_commands());
+
+    // Trivia command
+    commands.extend(trivia::create_commands());
+
+    // Story command
+    commands.extend(story::create_commands());
+
+    // Standup command
+    commands.extend(standup::create_commands());
+
+    // Meeting poll command
+    commands.extend(meet::create_commands());
+
+    // Issue tracker command
+    commands.extend(ticket::create_commands());
+
+    // Outbound webhook command
+    commands.extend(webhooks::create_commands());
+
+    // Weekly server report command
+    commands.extend(report::create_commands());
+
+    // Trending topics command
+    commands.extend(topics::create_commands());
+
+    // Channel mood timeline command
+    commands.extend(mood::create_commands());
+
+    // Member profile card command
+    commands.extend(profile::create_commands());
+
+    // XP and level commands
+    commands.extend(leveling::create_commands());
+
+    // Scheduled channel topic command
+    commands.extend(channel_topic::create_commands());
+    commands.extend(qotd::create_commands());
+    commands.extend(suggestions::create_commands());
+    commands.extend(support::create_commands());
+    commands.extend(anon_questions::create_commands());
+    commands.extend(verification::create_commands());
+    commands.extend(autoresponse::create_commands());
+    commands.extend(alias::create_commands());
+    commands.extend(prompts::create_commands());
+    commands.extend(send_later::create_commands());
+    commands.extend(countdown::create_commands());
+    commands.extend(time::create_commands());
+    commands.extend(convert::create_commands());
+    commands.extend(dice::create_commands());
+    commands.extend(guardrails::create_commands());
+    commands.extend(history::create_commands());
+    commands.extend(memory::create_commands());
+    commands.extend(glossary::create_commands());
+
+    // Telegram account linking, only when the Telegram front end is built
+    #[cfg(feature = "telegram")]
+    commands.extend(telegram::create_commands());
+
     // Plugin commands (single /plugins command with subcommands)
     if !plugins.is_empty() {
         commands.push(create_plugins_command(plugins));
@@ -93,18 +232,15 @@ pub fn create_context_menu_commands() -> Vec<CreateApplicationCommand> {
 
 /// Registers all slash commands globally (without plugins)
 pub async fn register_global_commands(ctx: &Context) -> Result<()> {
-    register_global_commands_with_plugins(ctx, &[]).await
+    register_global_commands_with_plugins(&ctx.http, &[]).await
 }
 
 /// Registers all slash commands globally with plugin commands
-pub async fn register_global_commands_with_plugins(
-    ctx: &Context,
-    plugins: &[Plugin],
-) -> Result<()> {
+pub async fn register_global_commands_with_plugins(http: &Http, plugins: &[Plugin]) -> Result<()> {
     let slash_commands = create_slash_commands_with_plugins(plugins);
     let context_commands = create_context_menu_commands();
 
-    Command::set_global_application_commands(&ctx.http, |commands| {
+    Command::set_global_application_commands(http, |commands| {
         for command in slash_commands {
             commands.add_application_command(command);
         }
@@ -130,12 +266,12 @@ pub async fn register_global_commands_with_plugins(
 
 /// Registers all slash commands for a specific guild (faster for testing)
 pub async fn register_guild_commands(ctx: &Context, guild_id: GuildId) -> Result<()> {
-    register_guild_commands_with_plugins(ctx, guild_id, &[]).await
+    register_guild_commands_with_plugins(&ctx.http, guild_id, &[]).await
 }
 
 /// Registers all slash commands for a specific guild with plugin commands
 pub async fn register_guild_commands_with_plugins(
-    ctx: &Context,
+    http: &Http,
     guild_id: GuildId,
     plugins: &[Plugin],
 ) -> Result<()> {
@@ -143,7 +279,7 @@ pub async fn register_guild_commands_with_plugins(
     let context_commands = create_context_menu_commands();
 
     guild_id
-        .set_application_commands(&ctx.http, |commands| {
+        .set_application_commands(http, |commands| {
             for command in slash_commands {
                 commands.add_application_command(command);
             }
@@ -188,6 +324,16 @@ pub fn get_channel_option(options: &[CommandDataOption], name: &str) -> Option<u
         .and_then(|s| s.parse().ok())
 }
 
+/// Utility function to get user option from slash command
+pub fn get_user_option(options: &[CommandDataOption], name: &str) -> Option<u64> {
+    options
+        .iter()
+        .find(|opt| opt.name == name)
+        .and_then(|opt| opt.value.as_ref())
+        .and_then(|val| val.as_str())
+        .and_then(|s| s.parse().ok())
+}
+
 /// Utility function to get role option from slash command
 pub fn get_role_option(options: &[CommandDataOption], name: &str) -> Option<u64> {
     options
@@ -207,6 +353,15 @@ pub fn get_integer_option(options: &[CommandDataOption], name: &str) -> Option<i
         .and_then(|val| val.as_i64())
 }
 
+/// Utility function to get number option from slash command
+pub fn get_number_option(options: &[CommandDataOption], name: &str) -> Option<f64> {
+    options
+        .iter()
+        .find(|opt| opt.name == name)
+        .and_then(|opt| opt.value.as_ref())
+        .and_then(|val| val.as_f64())
+}
+
 /// Utility function to get boolean option from slash command
 pub fn get_bool_option(options: &[CommandDataOption], name: &str) -> Option<bool> {
     options
@@ -216,6 +371,17 @@ pub fn get_bool_option(options: &[CommandDataOption], name: &str) -> Option<bool
         .and_then(|val| val.as_bool())
 }
 
+/// Utility function to get attachment option from slash command
+pub fn get_attachment_option(options: &[CommandDataOption], name: &str) -> Option<Attachment> {
+    options
+        .iter()
+        .find(|opt| opt.name == name)
+        .and_then(|opt| match &opt.resolved {
+            Some(CommandDataOptionValue::Attachment(attachment)) => Some(attachment.clone()),
+            _ => None,
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -237,6 +403,7 @@ mod tests {
             "set_user",
             "imagine",
             "forget",
+            "undo",
             "remind",
             "reminders",
             "introspect",
@@ -252,10 +419,12 @@ mod tests {
             "features",
             "toggle",
             "sysinfo",
+            "alerts",
             // Commits command
             "commits",
-            // Ask command
+            // Ask commands
             "ask",
+            "ask_all",
             // Council command
             "council",
             // Conclude command
@@ -264,6 +433,78 @@ mod tests {
             "fetch",
             // Context info command
             "context",
+            // Fork command
+            "fork",
+            // Privacy settings
+            "privacy",
+            // Message search
+            "search",
+            // Owner maintenance
+            "admin",
+            // Birthdays and anniversaries
+            "birthday",
+            // Trivia games
+            "trivia",
+            // Collaborative stories
+            "story",
+            // Daily standups
+            "standup",
+            // Meeting polls
+            "meet",
+            // Jira / Linear issues
+            "ticket",
+            // Outbound webhooks
+            "webhooks",
+            // Weekly server reports
+            "report",
+            // Trending topics
+            "topics",
+            // Channel mood timelines
+            "mood",
+            // Member profile cards
+            "profile",
+            // XP and levels
+            "rank",
+            "leaderboard",
+            "leveling",
+            // Scheduled channel topics
+            "channel_topic",
+            // Question of the day
+            "qotd",
+            // Suggestion box
+            "suggest",
+            "suggestions",
+            // Support tickets
+            "support",
+            // Anonymous questions
+            "ask_anon",
+            "anon",
+            // Verification gate
+            "verification",
+            // Keyword auto-responses
+            "autoresponse",
+            // Prompt aliases
+            "alias",
+            // Guild prompt library
+            "prompts",
+            // Scheduled messages
+            "send_later",
+            // Live countdown embeds
+            "countdown",
+            // Time zone conversion
+            "time",
+            // Unit and currency conversion
+            "convert",
+            // Dice and initiative
+            "roll",
+            "initiative",
+            "guardrails",
+            // DM conversation browser
+            "history",
+            // Pinned memories
+            "memory",
+            // Guild glossary
+            "glossary",
         ];
 
         for expected in expected_commands {
@@ -277,6 +518,6 @@ mod tests {
     #[test]
     fn test_create_context_menu_commands() {
         let commands = create_context_menu_commands();
-        assert_eq!(commands.len(), 3, "Should have 3 context menu commands");
+        assert_eq!(commands.len(), 6, "Should have 6 context menu commands");
     }
 }
@@ -0,0 +1,63 @@
+//! # Mood Command
+//!
+//! Show a channel's sentiment timeline.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::channel::ChannelType;
+
+use crate::features::sentiment::MAX_MOOD_HOURS;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_mood_command()]
+}
+
+fn create_mood_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("mood")
+        .description("Hourly sentiment timeline for a channel with sentiment tracking on")
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("hours")
+                .description("How far back to look (defaults to 24)")
+                .kind(CommandOptionType::Integer)
+                .required(false)
+                .min_int_value(1)
+                .max_int_value(MAX_MOOD_HOURS)
+        })
+        .create_option(|option| {
+            option
+                .name("channel")
+                .description("Channel to look at (defaults to this one)")
+                .kind(CommandOptionType::Channel)
+                .channel_types(&[ChannelType::Text, ChannelType::PublicThread])
+                .required(false)
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_mood_command() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 1);
+
+        let mood = &commands[0];
+        assert_eq!(mood.0.get("name").unwrap().as_str().unwrap(), "mood");
+        let options = mood.0["options"].as_array().unwrap();
+        assert_eq!(options.len(), 2);
+        assert_eq!(options[0]["name"], "hours");
+        assert_eq!(options[0]["max_value"], MAX_MOOD_HOURS);
+    }
+}
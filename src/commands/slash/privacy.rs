@@ -0,0 +1,75 @@
+//! # Privacy Command
+//!
+//! Let users see and change what the bot keeps about their messages.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+
+use crate::database::PrivacyOption;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_privacy_command()]
+}
+
+fn create_privacy_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("privacy")
+        .description("View or change how your messages are stored and analysed")
+        .create_option(|option| {
+            option
+                .name("settings")
+                .description("Show your current privacy settings")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("set")
+                .description("Opt in or out of one kind of data use")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("setting")
+                        .description("What to change")
+                        .kind(CommandOptionType::String)
+                        .required(true);
+                    for option in PrivacyOption::ALL {
+                        sub.add_string_choice(option.label(), option.key());
+                    }
+                    sub
+                })
+                .create_sub_option(|sub| {
+                    sub.name("value")
+                        .description("Allow it, opt out, or follow the server default")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .add_string_choice("Allow", "allow")
+                        .add_string_choice("Opt out", "opt_out")
+                        .add_string_choice("Server default", "default")
+                })
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_privacy_command() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 1);
+
+        let privacy = &commands[0];
+        let name = privacy.0.get("name").unwrap().as_str().unwrap();
+        assert_eq!(name, "privacy");
+
+        let subcommands = privacy.0.get("options").unwrap().as_array().unwrap();
+        assert_eq!(subcommands.len(), 2);
+    }
+}
@@ -0,0 +1,49 @@
+//! # Profile Command
+//!
+//! Show a member's profile card.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_profile_command()]
+}
+
+fn create_profile_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("profile")
+        .description("Profile card with persona, activity, commands, debates and trivia")
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("user")
+                .description("Member to show (defaults to you)")
+                .kind(CommandOptionType::User)
+                .required(false)
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_profile_command() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 1);
+
+        let profile = &commands[0];
+        assert_eq!(profile.0.get("name").unwrap().as_str().unwrap(), "profile");
+        let options = profile.0["options"].as_array().unwrap();
+        assert_eq!(options.len(), 1);
+        assert_eq!(options[0]["name"], "user");
+    }
+}
@@ -0,0 +1,168 @@
+//! # Prompts Command
+//!
+//! The guild's shared prompt library: save, list, use, delete, export and
+//! import prompts.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::{CreateApplicationCommand, CreateApplicationCommandOption};
+use serenity::model::application::command::CommandOptionType;
+
+use crate::features::aliases::{MAX_NAME_CHARS, MAX_TEMPLATE_CHARS};
+use crate::features::personas::add_persona_choices;
+use crate::features::prompt_library::MAX_DESCRIPTION_CHARS;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_prompts_command()]
+}
+
+fn name_option(sub: &mut CreateApplicationCommandOption) -> &mut CreateApplicationCommandOption {
+    sub.name("name")
+        .description("Prompt name")
+        .kind(CommandOptionType::String)
+        .required(true)
+        .min_length(1)
+        .max_length(MAX_NAME_CHARS as u16)
+}
+
+fn category_option(
+    sub: &mut CreateApplicationCommandOption,
+) -> &mut CreateApplicationCommandOption {
+    sub.name("category")
+        .description("Category, e.g. writing or art")
+        .kind(CommandOptionType::String)
+        .required(false)
+        .max_length(MAX_NAME_CHARS as u16)
+}
+
+fn create_prompts_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("prompts")
+        .description("The server's shared prompt library")
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("save")
+                .description("Add or update a prompt (needs Manage Server)")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(name_option)
+                .create_sub_option(|sub| {
+                    sub.name("prompt")
+                        .description(
+                            "Prompt text; ${variable} placeholders are filled when it's used",
+                        )
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .min_length(1)
+                        .max_length(MAX_TEMPLATE_CHARS as u16)
+                })
+                .create_sub_option(category_option)
+                .create_sub_option(|sub| {
+                    sub.name("description")
+                        .description("What the prompt is for")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .max_length(MAX_DESCRIPTION_CHARS as u16)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("list")
+                .description("Browse the library")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(category_option)
+        })
+        .create_option(|option| {
+            option
+                .name("use")
+                .description("Fill in a prompt and run it")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(name_option)
+                .create_sub_option(|sub| {
+                    sub.name("variables")
+                        .description("Values for the variables, in order; quote multi-word values")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .max_length(2000)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("with")
+                        .description("Run it through /ask or /imagine (default: ask)")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .add_string_choice("Ask a persona", "ask")
+                        .add_string_choice("Generate an image", "imagine")
+                })
+                .create_sub_option(|sub| {
+                    sub.name("persona")
+                        .description("Persona to ask (defaults to your current persona)")
+                        .kind(CommandOptionType::String)
+                        .required(false);
+                    add_persona_choices(sub);
+                    sub
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("delete")
+                .description("Remove a prompt (needs Manage Server)")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(name_option)
+        })
+        .create_option(|option| {
+            option
+                .name("export")
+                .description("Download the library as a JSON file")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(category_option)
+        })
+        .create_option(|option| {
+            option
+                .name("import")
+                .description("Add prompts from an exported JSON file (needs Manage Server)")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("file")
+                        .description("File from /prompts export")
+                        .kind(CommandOptionType::Attachment)
+                        .required(true)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("overwrite")
+                        .description("Replace prompts that already exist (default: skip them)")
+                        .kind(CommandOptionType::Boolean)
+                        .required(false)
+                })
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_prompts_command() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 1);
+
+        let command = &commands[0];
+        assert_eq!(command.0["name"], "prompts");
+
+        let subcommands: Vec<&str> = command.0["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            subcommands,
+            ["save", "list", "use", "delete", "export", "import"]
+        );
+    }
+}
@@ -0,0 +1,133 @@
+//! # Question of the Day Command
+//!
+//! Schedule a daily question and manage the queue of questions it draws from.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::channel::ChannelType;
+use serenity::model::Permissions;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_qotd_command()]
+}
+
+fn create_qotd_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("qotd")
+        .description("Post a question of the day")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("setup")
+                .description("Choose where and when the daily question is posted")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("channel")
+                        .description("Channel questions are posted in")
+                        .kind(CommandOptionType::Channel)
+                        .channel_types(&[ChannelType::Text, ChannelType::News])
+                        .required(true)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("hour")
+                        .description("Local hour to post at, 0-23 (defaults to 9)")
+                        .kind(CommandOptionType::Integer)
+                        .required(false)
+                        .min_int_value(0)
+                        .max_int_value(23)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("timezone")
+                        .description("UTC offset for the hour, e.g. UTC+2 (defaults to UTC)")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .max_length(12)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("threads")
+                        .description("Open a thread for answers to each question")
+                        .kind(CommandOptionType::Boolean)
+                        .required(false)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("digest_channel")
+                        .description("Where the best answers are archived (defaults to the question channel)")
+                        .kind(CommandOptionType::Channel)
+                        .channel_types(&[ChannelType::Text, ChannelType::News])
+                        .required(false)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("stop")
+                .description("Stop posting questions of the day")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("add")
+                .description("Queue a question; queued questions go out before written ones")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("question")
+                        .description("The question to ask")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .max_length(300)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("list")
+                .description("Show the schedule and the queued questions")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("skip")
+                .description("Drop a queued question")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("id")
+                        .description("Question number from /qotd list (defaults to the next one)")
+                        .kind(CommandOptionType::Integer)
+                        .required(false)
+                        .min_int_value(1)
+                })
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_qotd_command() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 1);
+
+        let command = &commands[0];
+        assert_eq!(command.0["name"], "qotd");
+
+        let subcommands: Vec<&str> = command.0["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(subcommands, ["setup", "stop", "add", "list", "skip"]);
+
+        let setup = &command.0["options"][0]["options"];
+        assert_eq!(setup[0]["name"], "channel");
+        assert_eq!(setup[0]["required"], true);
+    }
+}
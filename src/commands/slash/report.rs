@@ -0,0 +1,107 @@
+//! # Report Command
+//!
+//! Schedule the weekly "state of the server" report or post one right away.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::channel::ChannelType;
+use serenity::model::Permissions;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_report_command()]
+}
+
+fn create_report_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("report")
+        .description("Weekly state of the server report for admins")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("schedule")
+                .description("Post the report every week")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("channel")
+                        .description("Channel the report is posted in")
+                        .kind(CommandOptionType::Channel)
+                        .channel_types(&[ChannelType::Text])
+                        .required(true)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("day")
+                        .description("Day of the week to post on")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .add_string_choice("Monday", "mon")
+                        .add_string_choice("Tuesday", "tue")
+                        .add_string_choice("Wednesday", "wed")
+                        .add_string_choice("Thursday", "thu")
+                        .add_string_choice("Friday", "fri")
+                        .add_string_choice("Saturday", "sat")
+                        .add_string_choice("Sunday", "sun")
+                })
+                .create_sub_option(|sub| {
+                    sub.name("hour")
+                        .description("Local hour to post at, 0-23 (defaults to 9)")
+                        .kind(CommandOptionType::Integer)
+                        .required(false)
+                        .min_int_value(0)
+                        .max_int_value(23)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("timezone")
+                        .description("UTC offset, e.g. UTC+2 or UTC-5 (defaults to UTC)")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .max_length(12)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("off")
+                .description("Stop the weekly report")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("now")
+                .description("Post a report for the last 7 days in this channel")
+                .kind(CommandOptionType::SubCommand)
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_report_command() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 1);
+
+        let report = &commands[0];
+        assert_eq!(report.0.get("name").unwrap().as_str().unwrap(), "report");
+
+        let subcommands: Vec<&str> = report.0["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(subcommands, ["schedule", "off", "now"]);
+
+        let schedule = &report.0["options"][0]["options"];
+        assert_eq!(schedule[1]["name"], "day");
+        assert_eq!(schedule[1]["choices"].as_array().unwrap().len(), 7);
+    }
+}
@@ -0,0 +1,101 @@
+//! # Send Later Command
+//!
+//! Queue a message for a channel at a future time.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::channel::ChannelType;
+
+use crate::features::send_later::MAX_MESSAGE_CHARS;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_send_later_command()]
+}
+
+fn create_send_later_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("send_later")
+        .description("Schedule a message to be posted later")
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("schedule")
+                .description("Queue a message for a channel")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("channel")
+                        .description("Channel to post the message in")
+                        .kind(CommandOptionType::Channel)
+                        .channel_types(&[ChannelType::Text, ChannelType::News])
+                        .required(true)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("in")
+                        .description("How long from now (e.g., 30m, 2h, 1d, 1h30m)")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("message")
+                        .description("What to post")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .max_length(MAX_MESSAGE_CHARS as u16)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("persona_rewrite")
+                        .description("Have your persona rewrite it in its voice when it's sent")
+                        .kind(CommandOptionType::Boolean)
+                        .required(false)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("list")
+                .description("Show queued messages")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("cancel")
+                .description("Cancel a queued message")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("id")
+                        .description("Message ID from /send_later list")
+                        .kind(CommandOptionType::Integer)
+                        .required(true)
+                })
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_send_later_command() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 1);
+
+        let command = &commands[0];
+        assert_eq!(command.0["name"], "send_later");
+
+        let subcommands: Vec<&str> = command.0["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(subcommands, ["schedule", "list", "cancel"]);
+        assert_eq!(command.0["options"][0]["options"][2]["max_length"], 2000);
+    }
+}
@@ -0,0 +1,133 @@
+//! # Standup Command
+//!
+//! Configure the daily standup and manage your own participation.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use crate::features::standup::{MAX_COLLECT_MINUTES, MIN_COLLECT_MINUTES};
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::channel::ChannelType;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_standup_command()]
+}
+
+fn create_standup_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("standup")
+        .description("Daily standups collected by DM and summarized in a team channel")
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("config")
+                .description("Set when and where the standup runs (Manage Server)")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("channel")
+                        .description("Channel the summary is posted in")
+                        .kind(CommandOptionType::Channel)
+                        .channel_types(&[ChannelType::Text])
+                        .required(true)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("time")
+                        .description("When to ask, as 24-hour HH:MM, e.g. 09:30")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .max_length(5)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("timezone")
+                        .description("Team UTC offset, e.g. UTC+2 or UTC-5 (defaults to UTC)")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .max_length(12)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("window")
+                        .description("Minutes to collect replies before posting the summary")
+                        .kind(CommandOptionType::Integer)
+                        .required(false)
+                        .min_int_value(MIN_COLLECT_MINUTES)
+                        .max_int_value(MAX_COLLECT_MINUTES)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("weekdays_only")
+                        .description("Skip Saturdays and Sundays (defaults to true)")
+                        .kind(CommandOptionType::Boolean)
+                        .required(false)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("enabled")
+                        .description("Pause or resume the standup (defaults to true)")
+                        .kind(CommandOptionType::Boolean)
+                        .required(false)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("join")
+                .description("Get the daily standup questions by DM")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("leave")
+                .description("Stop getting standup questions")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("skip")
+                .description("Sit out the standup, starting today")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("days")
+                        .description("How many days to skip (defaults to 1)")
+                        .kind(CommandOptionType::Integer)
+                        .required(false)
+                        .min_int_value(1)
+                        .max_int_value(30)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("status")
+                .description("Show the schedule, members and today's replies")
+                .kind(CommandOptionType::SubCommand)
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_standup_command() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 1);
+
+        let standup = &commands[0];
+        assert_eq!(standup.0.get("name").unwrap().as_str().unwrap(), "standup");
+
+        let subcommands: Vec<&str> = standup.0["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(subcommands, ["config", "join", "leave", "skip", "status"]);
+
+        let config = &standup.0["options"][0]["options"];
+        assert_eq!(config[0]["name"], "channel");
+        assert_eq!(config[1]["name"], "time");
+        assert_eq!(config[1]["required"], true);
+    }
+}
@@ -0,0 +1,81 @@
+//! # Story Command
+//!
+//! Write a story in turns with a persona in a thread.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use crate::features::personas::PERSONA_CHOICES;
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_story_command()]
+}
+
+fn create_story_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("story")
+        .description("Write a story together with a persona, one paragraph at a time")
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("start")
+                .description("Open a story thread; the persona writes first, then anyone adds the next paragraph")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("premise")
+                        .description("What the story is about")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .max_length(300)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("persona")
+                        .description("Persona co-writing the story (defaults to yours)")
+                        .kind(CommandOptionType::String)
+                        .required(false);
+                    for (name, value) in PERSONA_CHOICES {
+                        sub.add_string_choice(name, value);
+                    }
+                    sub
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("conclude")
+                .description("Have the persona write an ending and export the story as a file")
+                .kind(CommandOptionType::SubCommand)
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_story_command() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 1);
+
+        let story = &commands[0];
+        assert_eq!(story.0.get("name").unwrap().as_str().unwrap(), "story");
+
+        let subcommands: Vec<&str> = story.0["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(subcommands, ["start", "conclude"]);
+
+        let premise = &story.0["options"][0]["options"][0];
+        assert_eq!(premise["name"], "premise");
+        assert_eq!(premise["required"], true);
+    }
+}
@@ -0,0 +1,130 @@
+//! # Suggestion Commands
+//!
+//! Post suggestions for the server to vote on, and review them.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::channel::ChannelType;
+
+use crate::features::suggestions::SuggestionStatus;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_suggest_command(), create_suggestions_command()]
+}
+
+fn create_suggest_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("suggest")
+        .description("Post a suggestion for the server to vote on")
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("suggestion")
+                .description("Your idea")
+                .kind(CommandOptionType::String)
+                .required(true)
+                .max_length(1000)
+        });
+    command
+}
+
+fn create_suggestions_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("suggestions")
+        .description("Browse and review suggestions")
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("list")
+                .description("Show recent suggestions")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    let sub = sub
+                        .name("status")
+                        .description("Only suggestions with this status")
+                        .kind(CommandOptionType::String)
+                        .required(false);
+                    for status in SuggestionStatus::ALL {
+                        sub.add_string_choice(status.label(), status.as_str());
+                    }
+                    sub
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("mark")
+                .description("Accept, decline or mark a suggestion implemented (Manage Server)")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("id")
+                        .description("Suggestion number")
+                        .kind(CommandOptionType::Integer)
+                        .required(true)
+                        .min_int_value(1)
+                })
+                .create_sub_option(|sub| {
+                    let sub = sub
+                        .name("status")
+                        .description("New status")
+                        .kind(CommandOptionType::String)
+                        .required(true);
+                    for status in SuggestionStatus::ALL {
+                        sub.add_string_choice(status.label(), status.as_str());
+                    }
+                    sub
+                })
+                .create_sub_option(|sub| {
+                    sub.name("reason")
+                        .description("Note shown on the suggestion")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .max_length(500)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("channel")
+                .description("Set where suggestions are posted (Manage Server)")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("channel")
+                        .description("Suggestion channel")
+                        .kind(CommandOptionType::Channel)
+                        .channel_types(&[ChannelType::Text, ChannelType::News])
+                        .required(true)
+                })
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_suggestion_commands() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[0].0["name"], "suggest");
+        assert_eq!(commands[1].0["name"], "suggestions");
+
+        let subcommands: Vec<&str> = commands[1].0["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(subcommands, ["list", "mark", "channel"]);
+
+        let mark = &commands[1].0["options"][1]["options"];
+        assert_eq!(mark[1]["choices"].as_array().unwrap().len(), 4);
+    }
+}
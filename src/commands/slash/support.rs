@@ -0,0 +1,156 @@
+//! # Support Command
+//!
+//! Open and close private support tickets, and manage the knowledge base the
+//! persona answers from.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::channel::ChannelType;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_support_command()]
+}
+
+fn create_support_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("support")
+        .description("Private support tickets")
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("open")
+                .description("Open a private ticket with the support team")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("subject")
+                        .description("What you need help with")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .max_length(100)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("details")
+                        .description("Anything else the team should know")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .max_length(1000)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("close")
+                .description("Close this ticket with a summary and archive it")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("setup")
+                .description("Choose the support role and ticket channel (Manage Server)")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("role")
+                        .description("Role added to every ticket")
+                        .kind(CommandOptionType::Role)
+                        .required(true)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("channel")
+                        .description("Channel ticket threads are created in (defaults to where /support open is used)")
+                        .kind(CommandOptionType::Channel)
+                        .channel_types(&[ChannelType::Text])
+                        .required(false)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("first_line")
+                        .description("Let the persona answer from the knowledge base first")
+                        .kind(CommandOptionType::Boolean)
+                        .required(false)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("stats")
+                .description("Ticket metrics (Manage Server)")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("kb")
+                .description("Manage the support knowledge base (Manage Server)")
+                .kind(CommandOptionType::SubCommandGroup)
+                .create_sub_option(|sub| {
+                    sub.name("add")
+                        .description("Add an article")
+                        .kind(CommandOptionType::SubCommand)
+                        .create_sub_option(|opt| {
+                            opt.name("title")
+                                .description("What the article is about")
+                                .kind(CommandOptionType::String)
+                                .required(true)
+                                .max_length(100)
+                        })
+                        .create_sub_option(|opt| {
+                            opt.name("content")
+                                .description("The answer")
+                                .kind(CommandOptionType::String)
+                                .required(true)
+                                .max_length(2000)
+                        })
+                })
+                .create_sub_option(|sub| {
+                    sub.name("remove")
+                        .description("Remove an article")
+                        .kind(CommandOptionType::SubCommand)
+                        .create_sub_option(|opt| {
+                            opt.name("id")
+                                .description("Article number from /support kb list")
+                                .kind(CommandOptionType::Integer)
+                                .required(true)
+                                .min_int_value(1)
+                        })
+                })
+                .create_sub_option(|sub| {
+                    sub.name("list")
+                        .description("Show the articles")
+                        .kind(CommandOptionType::SubCommand)
+                })
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_support_command() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 1);
+
+        let command = &commands[0];
+        assert_eq!(command.0["name"], "support");
+
+        let subcommands: Vec<&str> = command.0["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(subcommands, ["open", "close", "setup", "stats", "kb"]);
+
+        let kb: Vec<&str> = command.0["options"][4]["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(kb, ["add", "remove", "list"]);
+    }
+}
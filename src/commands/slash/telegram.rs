@@ -0,0 +1,49 @@
+//! # Telegram Command
+//!
+//! Link a Telegram account to the Discord user running the command.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_telegram_command()]
+}
+
+fn create_telegram_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("telegram")
+        .description("Use your personas from the Telegram bot")
+        .create_option(|option| {
+            option
+                .name("link")
+                .description("Get a one-time code to link your Telegram account")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("unlink")
+                .description("Unlink every Telegram account linked to you")
+                .kind(CommandOptionType::SubCommand)
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_telegram_command_has_subcommands() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 1);
+        let options = commands[0].0.get("options").unwrap().as_array().unwrap();
+        assert_eq!(options.len(), 2);
+    }
+}
@@ -0,0 +1,146 @@
+//! # Ticket Command
+//!
+//! File messages as Jira or Linear issues and configure the workspace.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+
+use crate::features::issues::TOKEN_OPTION;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_ticket_command()]
+}
+
+fn create_ticket_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("ticket")
+        .description("File messages as Jira or Linear issues")
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("create")
+                .description("File a message (or just a title) as an issue")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("message")
+                        .description("Link or ID of the message to file")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .max_length(200)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("title")
+                        .description("Issue title (required without a message)")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .max_length(120)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("summarize")
+                        .description("Summarize the message into a title and description")
+                        .kind(CommandOptionType::Boolean)
+                        .required(false)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("config")
+                .description("Connect this server to Jira or Linear (Manage Server)")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("provider")
+                        .description("Issue tracker")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .add_string_choice("Jira", "jira")
+                        .add_string_choice("Linear", "linear")
+                })
+                .create_sub_option(|sub| {
+                    sub.name("project")
+                        .description("Jira project key or Linear team ID")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .max_length(100)
+                })
+                .create_sub_option(|sub| {
+                    sub.name(TOKEN_OPTION)
+                        .description("API token (stored for this server, never shown again)")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .max_length(500)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("site_url")
+                        .description("Jira only: your site, e.g. https://acme.atlassian.net")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .max_length(200)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("email")
+                        .description("Jira only: email of the account the token belongs to")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .max_length(200)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("issue_type")
+                        .description("Jira only: issue type to create (defaults to Task)")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .max_length(50)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("summarize")
+                        .description("Summarize messages by default (defaults to true)")
+                        .kind(CommandOptionType::Boolean)
+                        .required(false)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("status")
+                .description("Show which tracker this server files issues in")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("remove")
+                .description("Disconnect the tracker and delete its token (Manage Server)")
+                .kind(CommandOptionType::SubCommand)
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_ticket_command() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 1);
+
+        let ticket = &commands[0];
+        assert_eq!(ticket.0.get("name").unwrap().as_str().unwrap(), "ticket");
+
+        let subcommands: Vec<&str> = ticket.0["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(subcommands, ["create", "config", "status", "remove"]);
+
+        let provider = &ticket.0["options"][1]["options"][0];
+        assert_eq!(provider["name"], "provider");
+        assert_eq!(provider["choices"].as_array().unwrap().len(), 2);
+    }
+}
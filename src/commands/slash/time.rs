@@ -0,0 +1,77 @@
+//! # Time Command
+//!
+//! Store a time zone and convert times to Discord timestamps.
+//!
+//! - **Version**: 1.1.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.1.0: Zones are given by IANA name or UTC offset
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_time_command()]
+}
+
+fn create_time_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("time")
+        .description("Share times that show up in everyone's local time")
+        .create_option(|option| {
+            option
+                .name("zone")
+                .description("Set or show your time zone")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("timezone")
+                        .description("Zone name like Europe/Berlin, or a UTC offset like UTC+5:30")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("convert")
+                .description("Turn a time into a timestamp everyone sees in their own zone")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("time")
+                        .description("e.g. 15:30, 3pm, tomorrow 9am or 2026-10-20 18:00")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("from")
+                        .description("Zone the time is in, e.g. America/New_York (defaults to your /time zone)")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                })
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_time_command() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 1);
+
+        let command = &commands[0];
+        assert_eq!(command.0["name"], "time");
+
+        let subcommands: Vec<&str> = command.0["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(subcommands, ["zone", "convert"]);
+    }
+}
@@ -0,0 +1,64 @@
+//! # Topics Command
+//!
+//! Show what a channel has been talking about lately.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::channel::ChannelType;
+
+/// Longest window /topics looks back over, in hours
+pub const MAX_TOPIC_HOURS: i64 = 168;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_topics_command()]
+}
+
+fn create_topics_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("topics")
+        .description("Trending discussion topics in a channel, with example messages")
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("hours")
+                .description("How far back to look (defaults to 24)")
+                .kind(CommandOptionType::Integer)
+                .required(false)
+                .min_int_value(1)
+                .max_int_value(MAX_TOPIC_HOURS)
+        })
+        .create_option(|option| {
+            option
+                .name("channel")
+                .description("Channel to look at (defaults to this one)")
+                .kind(CommandOptionType::Channel)
+                .channel_types(&[ChannelType::Text, ChannelType::PublicThread])
+                .required(false)
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_topics_command() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 1);
+
+        let topics = &commands[0];
+        assert_eq!(topics.0.get("name").unwrap().as_str().unwrap(), "topics");
+        let options = topics.0["options"].as_array().unwrap();
+        assert_eq!(options.len(), 2);
+        assert_eq!(options[0]["name"], "hours");
+        assert_eq!(options[0]["max_value"], MAX_TOPIC_HOURS);
+    }
+}
@@ -0,0 +1,106 @@
+//! # Trivia Command
+//!
+//! Start persona-hosted trivia games and view the server leaderboard.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+
+use crate::features::personas::PERSONA_CHOICES;
+use crate::features::trivia::{
+    DEFAULT_ANSWER_SECONDS, DEFAULT_QUESTIONS, MAX_ANSWER_SECONDS, MAX_QUESTIONS,
+    MIN_ANSWER_SECONDS,
+};
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_trivia_command()]
+}
+
+fn create_trivia_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("trivia")
+        .description("Play a trivia game hosted by a persona")
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("start")
+                .description("Start a trivia game in this channel")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("topic")
+                        .description("What the questions are about (defaults to general knowledge)")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .max_length(100)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("host")
+                        .description("Persona hosting the game (defaults to yours)")
+                        .kind(CommandOptionType::String)
+                        .required(false);
+                    for (name, value) in PERSONA_CHOICES {
+                        sub.add_string_choice(name, value);
+                    }
+                    sub
+                })
+                .create_sub_option(|sub| {
+                    sub.name("questions")
+                        .description(format!("Number of questions (default {DEFAULT_QUESTIONS})"))
+                        .kind(CommandOptionType::Integer)
+                        .required(false)
+                        .min_int_value(1)
+                        .max_int_value(MAX_QUESTIONS as u64)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("seconds")
+                        .description(format!(
+                            "Seconds to answer each question (default {DEFAULT_ANSWER_SECONDS})"
+                        ))
+                        .kind(CommandOptionType::Integer)
+                        .required(false)
+                        .min_int_value(MIN_ANSWER_SECONDS)
+                        .max_int_value(MAX_ANSWER_SECONDS)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("stop")
+                .description("Stop the game in this channel after the current question")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("leaderboard")
+                .description("Show this server's top trivia players")
+                .kind(CommandOptionType::SubCommand)
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_trivia_command() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 1);
+
+        let trivia = &commands[0];
+        assert_eq!(trivia.0.get("name").unwrap().as_str().unwrap(), "trivia");
+
+        let subcommands: Vec<&str> = trivia.0["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(subcommands, ["start", "stop", "leaderboard"]);
+    }
+}
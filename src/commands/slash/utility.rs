@@ -1,4 +1,4 @@
-//! Utility slash commands: /ping, /help, /forget, /status, /version, /uptime, /commits
+//! Utility slash commands: /ping, /help, /forget, /undo, /status, /version, /uptime, /commits
 
 use serenity::builder::CreateApplicationCommand;
 use serenity::model::application::command::CommandOptionType;
@@ -9,6 +9,7 @@ pub fn create_commands() -> Vec<CreateApplicationCommand> {
         create_ping_command(),
         create_help_command(),
         create_forget_command(),
+        create_undo_command(),
         create_status_command(),
         create_version_command(),
         create_uptime_command(),
@@ -40,6 +41,14 @@ fn create_forget_command() -> CreateApplicationCommand {
         .to_owned()
 }
 
+/// Creates the undo command
+fn create_undo_command() -> CreateApplicationCommand {
+    CreateApplicationCommand::default()
+        .name("undo")
+        .description("Remove your last message and my reply from the conversation history")
+        .to_owned()
+}
+
 /// Creates the status command
 fn create_status_command() -> CreateApplicationCommand {
     CreateApplicationCommand::default()
@@ -0,0 +1,101 @@
+//! # Verification Command
+//!
+//! Configure the verification gate new members pass before getting the member
+//! role.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::channel::ChannelType;
+use serenity::model::permissions::Permissions;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_verification_command()]
+}
+
+fn create_verification_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("verification")
+        .description("Gate new members behind a quick verification")
+        .dm_permission(false)
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .create_option(|option| {
+            option
+                .name("setup")
+                .description("Turn on the gate for new members")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("channel")
+                        .description("Gated channel new members verify in")
+                        .kind(CommandOptionType::Channel)
+                        .channel_types(&[ChannelType::Text])
+                        .required(true)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("role")
+                        .description("Member role granted after verifying")
+                        .kind(CommandOptionType::Role)
+                        .required(true)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("mode")
+                        .description("How members verify (defaults to a button)")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .add_string_choice("Click a button", "button")
+                        .add_string_choice("Answer the persona's question", "question")
+                })
+                .create_sub_option(|sub| {
+                    sub.name("timeout_minutes")
+                        .description(
+                            "Kick members who haven't verified after this long (0 = never)",
+                        )
+                        .kind(CommandOptionType::Integer)
+                        .required(false)
+                        .min_int_value(0)
+                        .max_int_value(1440)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("status")
+                .description("Show the gate's settings and pending members")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("disable")
+                .description("Stop gating new members")
+                .kind(CommandOptionType::SubCommand)
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_verification_command() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 1);
+
+        let command = &commands[0];
+        assert_eq!(command.0["name"], "verification");
+
+        let subcommands: Vec<&str> = command.0["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(subcommands, ["setup", "status", "disable"]);
+        assert_eq!(command.0["options"][0]["options"][3]["max_value"], 1440);
+    }
+}
@@ -0,0 +1,136 @@
+//! # Webhooks Command
+//!
+//! Send bot events to Slack, Matrix or any JSON endpoint.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::Permissions;
+
+pub fn create_commands() -> Vec<CreateApplicationCommand> {
+    vec![create_webhooks_command()]
+}
+
+fn create_webhooks_command() -> CreateApplicationCommand {
+    let mut command = CreateApplicationCommand::default();
+    command
+        .name("webhooks")
+        .description("Send bot events to outside webhooks")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("add")
+                .description("Subscribe a webhook URL to bot events")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("url")
+                        .description("HTTPS webhook URL")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .max_length(500)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("events")
+                        .description(
+                            "all, or any of job_completed, debate_concluded, conflict_detected, budget_threshold",
+                        )
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .max_length(200)
+                })
+                .create_sub_option(|sub| {
+                    sub.name("format")
+                        .description("Payload shape (defaults to generic JSON)")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .add_string_choice("Slack", "slack")
+                        .add_string_choice("Matrix (hookshot)", "matrix")
+                        .add_string_choice("Generic JSON", "generic")
+                })
+                .create_sub_option(|sub| {
+                    sub.name("template")
+                        .description("Message text with {field} placeholders, e.g. {topic}")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .max_length(500)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("list")
+                .description("Show this server's webhooks")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("remove")
+                .description("Delete a webhook")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("id")
+                        .description("Webhook ID from /webhooks list")
+                        .kind(CommandOptionType::Integer)
+                        .required(true)
+                        .min_int_value(1)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("test")
+                .description("Send a sample event to a webhook")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("id")
+                        .description("Webhook ID from /webhooks list")
+                        .kind(CommandOptionType::Integer)
+                        .required(true)
+                        .min_int_value(1)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("budget")
+                .description("Daily OpenAI spend that fires budget_threshold")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub| {
+                    sub.name("usd")
+                        .description("Budget in USD, 0 to turn off")
+                        .kind(CommandOptionType::Number)
+                        .required(true)
+                        .min_number_value(0.0)
+                        .max_number_value(10000.0)
+                })
+        });
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_webhooks_command() {
+        let commands = create_commands();
+        assert_eq!(commands.len(), 1);
+
+        let webhooks = &commands[0];
+        assert_eq!(
+            webhooks.0.get("name").unwrap().as_str().unwrap(),
+            "webhooks"
+        );
+
+        let subcommands: Vec<&str> = webhooks.0["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(subcommands, ["add", "list", "remove", "test", "budget"]);
+    }
+}
@@ -0,0 +1,194 @@
+//! # Chat Client
+//!
+//! Abstraction over the OpenAI chat completion endpoint so that command handlers,
+//! debates and conflict mediation can be driven by a mock in tests and replays.
+//!
+//! - **Version**: 1.6.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.6.0: Shared chat_message constructor for plain text messages
+//! - 1.5.0: ModelParams::with_token_cap for response length budgets
+//! - 1.4.0: Added ModelParams and create_chat_completion_with_params for sampling overrides
+//! - 1.3.0: Added create_chat_completion_limited for capped completions
+//! - 1.2.0: Live requests are counted as OpenAI in-flight in /sysinfo
+//! - 1.1.0: Default client is wrapped with chaos fault injection when enabled
+//! - 1.0.0: Initial release with the live OpenAI implementation
+
+use anyhow::Result;
+use async_trait::async_trait;
+use openai::chat::{ChatCompletion, ChatCompletionMessage, ChatCompletionMessageRole};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::features::analytics::runtime::runtime_counters;
+use crate::features::resilience::{chaos, ChaosChatClient};
+
+/// Sampling overrides for one request; `None` keeps the API default
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelParams {
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub top_p: Option<f32>,
+    #[serde(default)]
+    pub max_tokens: Option<u64>,
+}
+
+impl ModelParams {
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    pub fn with_top_p(mut self, top_p: f32) -> Self {
+        self.top_p = Some(top_p);
+        self
+    }
+
+    pub fn with_max_tokens(mut self, max_tokens: u64) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Lower `max_tokens` to `cap` unless it's already lower
+    pub fn with_token_cap(mut self, cap: u64) -> Self {
+        self.max_tokens = Some(self.max_tokens.map_or(cap, |limit| limit.min(cap)));
+        self
+    }
+
+    /// These parameters with every value set in `overrides` replacing ours
+    pub fn merge(self, overrides: ModelParams) -> Self {
+        Self {
+            temperature: overrides.temperature.or(self.temperature),
+            top_p: overrides.top_p.or(self.top_p),
+            max_tokens: overrides.max_tokens.or(self.max_tokens),
+        }
+    }
+}
+
+/// Creates chat completions for a model and message list
+#[async_trait]
+pub trait ChatClient: Send + Sync {
+    async fn create_chat_completion(
+        &self,
+        model: &str,
+        messages: Vec<ChatCompletionMessage>,
+    ) -> Result<ChatCompletion>;
+
+    /// Same as `create_chat_completion`, capping generated tokens when `max_tokens` is set
+    async fn create_chat_completion_limited(
+        &self,
+        model: &str,
+        messages: Vec<ChatCompletionMessage>,
+        max_tokens: Option<u64>,
+    ) -> Result<ChatCompletion> {
+        let _ = max_tokens;
+        self.create_chat_completion(model, messages).await
+    }
+
+    /// Same as `create_chat_completion`, with temperature, top_p and token cap overrides
+    async fn create_chat_completion_with_params(
+        &self,
+        model: &str,
+        messages: Vec<ChatCompletionMessage>,
+        params: ModelParams,
+    ) -> Result<ChatCompletion> {
+        self.create_chat_completion_limited(model, messages, params.max_tokens)
+            .await
+    }
+}
+
+/// A plain text message with no name, function or tool calls
+pub fn chat_message(role: ChatCompletionMessageRole, content: String) -> ChatCompletionMessage {
+    ChatCompletionMessage {
+        role,
+        content: Some(content),
+        name: None,
+        function_call: None,
+        tool_call_id: None,
+        tool_calls: None,
+    }
+}
+
+/// Live client backed by the OpenAI API (key is set globally at startup)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OpenAiChatClient;
+
+#[async_trait]
+impl ChatClient for OpenAiChatClient {
+    async fn create_chat_completion(
+        &self,
+        model: &str,
+        messages: Vec<ChatCompletionMessage>,
+    ) -> Result<ChatCompletion> {
+        let _in_flight = runtime_counters().openai_request();
+        Ok(ChatCompletion::builder(model, messages).create().await?)
+    }
+
+    async fn create_chat_completion_limited(
+        &self,
+        model: &str,
+        messages: Vec<ChatCompletionMessage>,
+        max_tokens: Option<u64>,
+    ) -> Result<ChatCompletion> {
+        let _in_flight = runtime_counters().openai_request();
+        let builder = ChatCompletion::builder(model, messages);
+        let builder = match max_tokens {
+            Some(limit) => builder.max_completion_tokens(limit),
+            None => builder,
+        };
+        Ok(builder.create().await?)
+    }
+
+    async fn create_chat_completion_with_params(
+        &self,
+        model: &str,
+        messages: Vec<ChatCompletionMessage>,
+        params: ModelParams,
+    ) -> Result<ChatCompletion> {
+        let _in_flight = runtime_counters().openai_request();
+        let mut builder = ChatCompletion::builder(model, messages);
+        if let Some(temperature) = params.temperature {
+            builder = builder.temperature(temperature);
+        }
+        if let Some(top_p) = params.top_p {
+            builder = builder.top_p(top_p);
+        }
+        if let Some(limit) = params.max_tokens {
+            builder = builder.max_completion_tokens(limit);
+        }
+        Ok(builder.create().await?)
+    }
+}
+
+/// Default client used when none is injected
+pub fn openai_chat_client() -> Arc<dyn ChatClient> {
+    if chaos().is_enabled() {
+        Arc::new(ChaosChatClient::new(Arc::new(OpenAiChatClient)))
+    } else {
+        Arc::new(OpenAiChatClient)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_params_merge() {
+        let persona = ModelParams::default().with_temperature(1.1).with_top_p(0.9);
+        let command = ModelParams::default()
+            .with_temperature(0.2)
+            .with_max_tokens(400);
+        assert_eq!(
+            persona.merge(command),
+            ModelParams {
+                temperature: Some(0.2),
+                top_p: Some(0.9),
+                max_tokens: Some(400),
+            }
+        );
+        assert_eq!(persona.merge(ModelParams::default()), persona);
+    }
+}
@@ -2,17 +2,35 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::env;
 
+use crate::features::resilience::ChaosConfig;
+
+/// Chat model used when OPENAI_MODEL isn't set
+pub const DEFAULT_OPENAI_MODEL: &str = "gpt-5.1";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub discord_token: String,
     pub openai_api_key: String,
     pub database_path: String,
+    pub database_pool_size: usize,
+    pub message_batch_size: usize,
+    pub message_flush_interval_ms: u64,
+    pub settings_cache_ttl_secs: u64,
+    pub raw_usage_retention_days: i64,
+    pub rollup_retention_days: i64,
     pub log_level: String,
     pub discord_guild_id: Option<String>,
     pub openai_model: String,
     pub conflict_mediation_enabled: bool,
     pub conflict_sensitivity: String,
     pub mediation_cooldown_minutes: u64,
+    pub interaction_record_path: Option<String>,
+    /// Request the privileged Server Members intent, needed for member join events
+    pub members_intent: bool,
+    pub crash_report_path: String,
+    pub chaos_openai_timeout_rate: f64,
+    pub chaos_discord_429_rate: f64,
+    pub chaos_database_error_rate: f64,
 }
 
 impl Config {
@@ -24,9 +42,34 @@ impl Config {
             openai_api_key: env::var("OPENAI_API_KEY")
                 .map_err(|_| anyhow::anyhow!("OPENAI_API_KEY environment variable not set"))?,
             database_path: env::var("DATABASE_PATH").unwrap_or_else(|_| "persona.db".to_string()),
+            database_pool_size: env::var("DATABASE_POOL_SIZE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(crate::database::DEFAULT_POOL_SIZE),
+            message_batch_size: env::var("MESSAGE_BATCH_SIZE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(crate::message_writer::DEFAULT_BATCH_SIZE),
+            message_flush_interval_ms: env::var("MESSAGE_FLUSH_INTERVAL_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(crate::message_writer::DEFAULT_FLUSH_INTERVAL_MS),
+            settings_cache_ttl_secs: env::var("SETTINGS_CACHE_TTL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(crate::database::DEFAULT_SETTINGS_CACHE_TTL.as_secs()),
+            raw_usage_retention_days: env::var("RAW_USAGE_RETENTION_DAYS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(crate::database::DEFAULT_RAW_USAGE_RETENTION_DAYS),
+            rollup_retention_days: env::var("ROLLUP_RETENTION_DAYS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(crate::database::DEFAULT_ROLLUP_RETENTION_DAYS),
             log_level: env::var("LOG_LEVEL").unwrap_or_else(|_| "info".to_string()),
             discord_guild_id: env::var("DISCORD_GUILD_ID").ok(),
-            openai_model: env::var("OPENAI_MODEL").unwrap_or_else(|_| "gpt-5.1".to_string()),
+            openai_model: env::var("OPENAI_MODEL")
+                .unwrap_or_else(|_| DEFAULT_OPENAI_MODEL.to_string()),
             conflict_mediation_enabled: env::var("CONFLICT_MEDIATION_ENABLED")
                 .unwrap_or_else(|_| "true".to_string())
                 .to_lowercase()
@@ -37,8 +80,34 @@ impl Config {
                 .unwrap_or_else(|_| "5".to_string())
                 .parse()
                 .unwrap_or(5),
+            interaction_record_path: env::var("INTERACTION_RECORD_PATH").ok(),
+            members_intent: env::var("DISCORD_MEMBERS_INTENT")
+                .map(|v| v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+            crash_report_path: env::var("CRASH_REPORT_PATH").unwrap_or_else(|_| {
+                crate::features::resilience::crash::DEFAULT_CRASH_REPORT_PATH.to_string()
+            }),
+            chaos_openai_timeout_rate: chaos_rate("CHAOS_OPENAI_TIMEOUT_RATE"),
+            chaos_discord_429_rate: chaos_rate("CHAOS_DISCORD_429_RATE"),
+            chaos_database_error_rate: chaos_rate("CHAOS_DATABASE_ERROR_RATE"),
         })
     }
+
+    /// Fault injection rates (all zero unless the hidden CHAOS_* flags are set)
+    pub fn chaos(&self) -> ChaosConfig {
+        ChaosConfig {
+            openai_timeout_rate: self.chaos_openai_timeout_rate,
+            discord_429_rate: self.chaos_discord_429_rate,
+            database_error_rate: self.chaos_database_error_rate,
+        }
+    }
+}
+
+fn chaos_rate(name: &str) -> f64 {
+    env::var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0.0)
 }
 
 #[cfg(test)]
@@ -41,6 +41,7 @@ pub fn continuation_embed(persona: &Persona, text: &str) -> CreateEmbed {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::features::personas::PersonaSampling;
 
     fn test_persona() -> Persona {
         Persona {
@@ -49,6 +50,7 @@ mod tests {
             description: String::new(),
             portrait_url: Some("https://example.com/portrait.png".to_string()),
             color: 0xFF5733,
+            sampling: PersonaSampling::default(),
         }
     }
 
@@ -59,6 +61,7 @@ mod tests {
             description: String::new(),
             portrait_url: None,
             color: 0x00FF00,
+            sampling: PersonaSampling::default(),
         }
     }
 
@@ -0,0 +1,187 @@
+//! Guild custom emoji support for persona replies
+//!
+//! Lists a guild's custom emojis for the system prompt and checks the emoji
+//! markup in generated replies before they are sent: markup for emojis the
+//! guild has is normalised, `:name:` shortcodes are expanded, and anything
+//! else is stripped.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use regex::{Captures, Regex};
+use serenity::model::id::GuildId;
+use serenity::prelude::Context;
+use std::sync::OnceLock;
+
+/// At most this many emojis are listed in the prompt
+pub const MAX_PROMPT_EMOJIS: usize = 50;
+
+/// A custom emoji the bot can use in a guild
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuildEmoji {
+    pub id: u64,
+    pub name: String,
+    pub animated: bool,
+}
+
+impl GuildEmoji {
+    /// Discord message markup, e.g. `<:wave:123>` or `<a:dance:456>`
+    pub fn markup(&self) -> String {
+        let prefix = if self.animated { "a" } else { "" };
+        format!("<{prefix}:{}:{}>", self.name, self.id)
+    }
+}
+
+/// Available custom emojis of a guild, from the cache or the API
+pub async fn fetch_guild_emojis(ctx: &Context, guild_id: GuildId) -> Vec<GuildEmoji> {
+    let emojis = match ctx
+        .cache
+        .guild_field(guild_id, |g| g.emojis.values().cloned().collect::<Vec<_>>())
+    {
+        Some(emojis) => emojis,
+        None => guild_id.emojis(&ctx.http).await.unwrap_or_default(),
+    };
+    emojis
+        .into_iter()
+        .filter(|e| e.available)
+        .map(|e| GuildEmoji {
+            id: e.id.0,
+            name: e.name,
+            animated: e.animated,
+        })
+        .collect()
+}
+
+/// System prompt section listing the emojis a persona may use
+pub fn emoji_prompt(emojis: &[GuildEmoji]) -> Option<String> {
+    if emojis.is_empty() {
+        return None;
+    }
+    let list = emojis
+        .iter()
+        .take(MAX_PROMPT_EMOJIS)
+        .map(GuildEmoji::markup)
+        .collect::<Vec<_>>()
+        .join(" ");
+    Some(format!(
+        "\n\n## Custom Emojis\nThis server has custom emojis. Use one now and then where it \
+         fits your personality, written exactly as listed. Don't invent others.\n{list}"
+    ))
+}
+
+fn emoji_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    // Full markup (with a possibly bogus ID) and the space before it, or a bare :shortcode:
+    PATTERN.get_or_init(|| {
+        Regex::new(r"( ?)<(a?):(\w{1,32}):([^<>\s]*)>|:(\w{2,32}):").expect("valid emoji regex")
+    })
+}
+
+/// Keep only emoji markup that resolves to one of `emojis`
+///
+/// Markup is matched by ID first and then by name, and rewritten with the
+/// emoji's real name and animation flag. `:name:` shortcodes naming a guild
+/// emoji are expanded; other shortcodes are left as text.
+pub fn sanitize_emojis(text: &str, emojis: &[GuildEmoji]) -> String {
+    let by_name = |name: &str| emojis.iter().find(|e| e.name.eq_ignore_ascii_case(name));
+    emoji_pattern()
+        .replace_all(text, |caps: &Captures| {
+            if let Some(shortcode) = caps.get(5) {
+                return by_name(shortcode.as_str())
+                    .map(GuildEmoji::markup)
+                    .unwrap_or_else(|| caps[0].to_string());
+            }
+            // Stripped markup takes its leading space with it
+            caps[4]
+                .parse::<u64>()
+                .ok()
+                .and_then(|id| emojis.iter().find(|e| e.id == id))
+                .or_else(|| by_name(&caps[3]))
+                .map(|emoji| format!("{}{}", &caps[1], emoji.markup()))
+                .unwrap_or_default()
+        })
+        .into_owned()
+}
+
+/// Describe stickers attached to a user's message for the model
+pub fn describe_stickers(names: &[String]) -> Option<String> {
+    if names.is_empty() {
+        return None;
+    }
+    Some(format!("[Sent with sticker: {}]", names.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn emojis() -> Vec<GuildEmoji> {
+        vec![
+            GuildEmoji {
+                id: 111111111111111111,
+                name: "wave".to_string(),
+                animated: false,
+            },
+            GuildEmoji {
+                id: 222222222222222222,
+                name: "party".to_string(),
+                animated: true,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_emoji_prompt() {
+        assert!(emoji_prompt(&[]).is_none());
+        let prompt = emoji_prompt(&emojis()).unwrap();
+        assert!(prompt.contains("<:wave:111111111111111111> <a:party:222222222222222222>"));
+    }
+
+    #[test]
+    fn test_sanitize_keeps_and_fixes_guild_emojis() {
+        let emojis = emojis();
+        assert_eq!(
+            sanitize_emojis("hi <:wave:111111111111111111>", &emojis),
+            "hi <:wave:111111111111111111>"
+        );
+        // Wrong animation flag and wrong ID are corrected from the guild list
+        assert_eq!(
+            sanitize_emojis("<:party:222222222222222222> <:wave:999>", &emojis),
+            "<a:party:222222222222222222> <:wave:111111111111111111>"
+        );
+        assert_eq!(
+            sanitize_emojis("yay :Party: :smile:", &emojis),
+            "yay <a:party:222222222222222222> :smile:"
+        );
+    }
+
+    #[test]
+    fn test_sanitize_strips_unknown_markup() {
+        let emojis = emojis();
+        assert_eq!(
+            sanitize_emojis("nice <:pepe:333333333333333333> work", &emojis),
+            "nice work"
+        );
+        assert_eq!(
+            sanitize_emojis("broken <:wave_hi:> markup", &emojis),
+            "broken markup"
+        );
+        assert_eq!(sanitize_emojis("a <b> c", &emojis), "a <b> c");
+        assert_eq!(
+            sanitize_emojis("```\n    indented\n```", &emojis),
+            "```\n    indented\n```"
+        );
+    }
+
+    #[test]
+    fn test_describe_stickers() {
+        assert!(describe_stickers(&[]).is_none());
+        assert_eq!(
+            describe_stickers(&["Thumbs Up".to_string()]).unwrap(),
+            "[Sent with sticker: Thumbs Up]"
+        );
+    }
+}
@@ -2,24 +2,38 @@
 //!
 //! Core domain types, configuration, and error handling for the persona bot.
 //!
-//! - **Version**: 1.3.0
+//! - **Version**: 1.8.0
 //! - **Since**: 0.7.0
 //! - **Toggleable**: false
 //!
 //! ## Changelog
+//! - 1.8.0: Add time module with IANA zones and the shared UTC offset helpers
+//! - 1.7.0: Add oneshot module for the --oneshot CLI mode
+//! - 1.6.0: Add emoji module for guild custom emojis in persona replies
+//! - 1.5.0: Add sources module carrying response provenance for footnotes
+//! - 1.4.0: Add chat_client module with the mockable ChatClient trait
 //! - 1.3.0: Add embeds module with shared persona embed builders
 //! - 1.2.0: Add file_utils module with download, content detection, and file utilities
 //! - 1.1.0: Add response module with Discord message chunking utilities
 //! - 1.0.0: Initial creation with config module
 
+pub mod chat_client;
 pub mod config;
 pub mod embeds;
+pub mod emoji;
 pub mod file_utils;
+pub mod oneshot;
 pub mod response;
+pub mod sources;
+pub mod time;
 
 // Re-export commonly used items
-pub use config::Config;
+pub use chat_client::{
+    chat_message, openai_chat_client, ChatClient, ModelParams, OpenAiChatClient,
+};
+pub use config::{Config, DEFAULT_OPENAI_MODEL};
 pub use embeds::{continuation_embed, persona_embed};
+pub use emoji::{describe_stickers, emoji_prompt, fetch_guild_emojis, sanitize_emojis, GuildEmoji};
 pub use file_utils::{
     detect_content_kind, download_file, extract_filename, format_file_size, is_within_upload_limit,
     max_upload_size, sanitize_filename, ContentKind, DownloadedFile,
@@ -28,3 +42,5 @@ pub use response::{
     chunk_for_embed, chunk_for_message, chunk_text, truncate_for_embed, truncate_for_message,
     EMBED_LIMIT, MESSAGE_LIMIT,
 };
+pub use self::time::{format_utc_offset, local_time, parse_utc_offset, Zone};
+pub use sources::{Source, SourceKind, SourceList};
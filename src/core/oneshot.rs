@@ -0,0 +1,158 @@
+//! # One-Shot Mode
+//!
+//! `bot --oneshot "prompt" [--persona name]` sends a single prompt through a
+//! persona's system prompt and prints the answer, without connecting to
+//! Discord. Handy for trying prompt changes and for shell scripts. A prompt
+//! of `-` is read from stdin.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use openai::chat::{ChatCompletionMessage, ChatCompletionMessageRole};
+
+use crate::core::ChatClient;
+use crate::features::personas::PersonaManager;
+
+/// Persona used when `--persona` isn't given
+pub const DEFAULT_ONESHOT_PERSONA: &str = "obi";
+
+/// Arguments of a `--oneshot` run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OneshotArgs {
+    /// The prompt, or `-` to read it from stdin
+    pub prompt: String,
+    pub persona: String,
+}
+
+/// Pick `--oneshot` and `--persona` out of the command line
+///
+/// Returns `None` when `--oneshot` isn't present.
+pub fn parse_oneshot_args(args: &[String]) -> Result<Option<OneshotArgs>> {
+    let Some(pos) = args.iter().position(|a| a == "--oneshot") else {
+        return Ok(None);
+    };
+    let prompt = args
+        .get(pos + 1)
+        .filter(|p| !p.trim().is_empty())
+        .ok_or_else(|| anyhow::anyhow!("--oneshot requires a prompt (or - for stdin)"))?;
+    let persona = match args.iter().position(|a| a == "--persona") {
+        Some(pos) => args
+            .get(pos + 1)
+            .ok_or_else(|| anyhow::anyhow!("--persona requires a persona name"))?
+            .to_lowercase(),
+        None => DEFAULT_ONESHOT_PERSONA.to_string(),
+    };
+    Ok(Some(OneshotArgs {
+        prompt: prompt.clone(),
+        persona,
+    }))
+}
+
+/// Answer one prompt as a persona
+pub async fn run_oneshot(
+    chat_client: &dyn ChatClient,
+    persona_manager: &PersonaManager,
+    model: &str,
+    persona_id: &str,
+    prompt: &str,
+) -> Result<String> {
+    if persona_manager.get_persona(persona_id).is_none() {
+        let mut names: Vec<&String> = persona_manager
+            .list_personas()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        names.sort();
+        let names: Vec<&str> = names.into_iter().map(String::as_str).collect();
+        anyhow::bail!(
+            "Unknown persona '{persona_id}'. Available: {}",
+            names.join(", ")
+        );
+    }
+
+    let messages = vec![
+        ChatCompletionMessage {
+            role: ChatCompletionMessageRole::System,
+            content: Some(persona_manager.get_system_prompt(persona_id, None)),
+            name: None,
+            function_call: None,
+            tool_call_id: None,
+            tool_calls: None,
+        },
+        ChatCompletionMessage {
+            role: ChatCompletionMessageRole::User,
+            content: Some(prompt.to_string()),
+            name: None,
+            function_call: None,
+            tool_call_id: None,
+            tool_calls: None,
+        },
+    ];
+    let completion = chat_client.create_chat_completion(model, messages).await?;
+    completion
+        .choices
+        .first()
+        .and_then(|choice| choice.message.content.as_ref())
+        .map(|content| content.trim().to_string())
+        .ok_or_else(|| anyhow::anyhow!("No response from OpenAI"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::mock_openai::MockChatClient;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_oneshot_args() {
+        assert_eq!(parse_oneshot_args(&args(&["bot"])).unwrap(), None);
+        assert_eq!(
+            parse_oneshot_args(&args(&["bot", "--oneshot", "hi there"])).unwrap(),
+            Some(OneshotArgs {
+                prompt: "hi there".to_string(),
+                persona: DEFAULT_ONESHOT_PERSONA.to_string(),
+            })
+        );
+        assert_eq!(
+            parse_oneshot_args(&args(&["bot", "--persona", "Chef", "--oneshot", "-"]))
+                .unwrap()
+                .map(|a| (a.prompt, a.persona)),
+            Some(("-".to_string(), "chef".to_string()))
+        );
+        assert!(parse_oneshot_args(&args(&["bot", "--oneshot"])).is_err());
+        assert!(parse_oneshot_args(&args(&["bot", "--oneshot", "hi", "--persona"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_oneshot_uses_persona_prompt() {
+        let client = MockChatClient::new();
+        client.push_reply("  Hello there.  ");
+        let personas = PersonaManager::new();
+
+        let reply = run_oneshot(&client, &personas, "gpt-test", "obi", "greet me")
+            .await
+            .unwrap();
+        assert_eq!(reply, "Hello there.");
+
+        let request = &client.requests()[0];
+        assert_eq!(request.model, "gpt-test");
+        assert_eq!(
+            request.system_prompt(),
+            Some(personas.get_system_prompt("obi", None).as_str())
+        );
+        assert_eq!(request.last_user_message(), Some("greet me"));
+
+        let err = run_oneshot(&client, &personas, "gpt-test", "nobody", "hi")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Unknown persona 'nobody'"));
+        assert_eq!(client.request_count(), 1);
+    }
+}
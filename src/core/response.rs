@@ -1,9 +1,10 @@
 //! Response chunking and Discord message utilities
 //!
-//! - **Version**: 1.0.0
+//! - **Version**: 2.0.0
 //! - **Since**: 3.38.0
 //!
 //! ## Changelog
+//! - 2.0.0: Split on paragraph/sentence boundaries and keep code blocks balanced across chunks
 //! - 1.0.0: Extracted from 8 duplicate implementations in command_handler.rs
 
 /// Discord embed description limit
@@ -11,63 +12,107 @@ pub const EMBED_LIMIT: usize = 4096;
 /// Discord message content limit
 pub const MESSAGE_LIMIT: usize = 2000;
 
-/// Chunk text into pieces that fit Discord limits (UTF-8 safe, line-aware)
+/// Opening and closing marker of a fenced code block
+const CODE_FENCE: &str = "```";
+
+/// Split points in order of preference: paragraph, line, sentence, word
+const SPLIT_POINTS: [&str; 6] = ["\n\n", "\n", ". ", "! ", "? ", " "];
+
+/// Chunk text into pieces that fit Discord limits (UTF-8 and markdown aware)
 ///
 /// This function splits text respecting:
 /// - UTF-8 character boundaries (never splits mid-character)
-/// - Line boundaries when possible (prefers splitting at newlines)
-/// - Falls back to byte-aware character splitting for very long lines
+/// - Paragraph, line, sentence and word boundaries, in that order of preference
+/// - Fenced code blocks: a block cut across chunks is closed at the end of one
+///   chunk and reopened (with its language tag) at the start of the next
 pub fn chunk_text(text: &str, max_size: usize) -> Vec<String> {
     if text.len() <= max_size {
         return vec![text.to_string()];
     }
 
+    let has_fences = text.contains(CODE_FENCE);
     let mut chunks = Vec::new();
-    let mut current = String::new();
-
-    for line in text.lines() {
-        let line_with_newline = format!("{line}\n");
-        if current.len() + line_with_newline.len() > max_size {
-            if !current.is_empty() {
-                chunks.push(current.trim_end().to_string());
-                current = String::new();
-            }
-            // Handle lines longer than max_size (byte-aware)
-            if line_with_newline.len() > max_size {
-                chunks.extend(chunk_long_line(line, max_size));
-            } else {
-                current = line_with_newline;
-            }
+    let mut open_fence: Option<String> = None;
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        let prefix = open_fence
+            .as_ref()
+            .map(|fence| format!("{fence}\n"))
+            .unwrap_or_default();
+        if prefix.len() + rest.len() <= max_size {
+            chunks.push(format!("{prefix}{}", rest.trim_end()));
+            break;
+        }
+
+        // Leave room to reopen and close a code block around this chunk
+        let reserve = if has_fences {
+            prefix.len() + CODE_FENCE.len() + 1
         } else {
-            current.push_str(&line_with_newline);
+            0
+        };
+        let cut = find_split(rest, max_size.saturating_sub(reserve).max(1));
+        let piece = rest[..cut].trim_end();
+        rest = rest[cut..].trim_start_matches('\n');
+
+        let reopened = open_fence.is_some();
+        open_fence = track_fence(open_fence, piece);
+        // Nothing but the end of a block that the previous chunk already closed
+        if piece.is_empty() || (reopened && matches!(piece.trim(), "" | CODE_FENCE)) {
+            continue;
         }
+
+        let mut chunk = format!("{prefix}{piece}");
+        if open_fence.is_some() {
+            chunk.push('\n');
+            chunk.push_str(CODE_FENCE);
+        }
+        chunks.push(chunk);
     }
-    if !current.is_empty() {
-        chunks.push(current.trim_end().to_string());
+
+    if chunks.is_empty() {
+        chunks.push(String::new());
     }
     chunks
 }
 
-/// Split a single long line into chunks respecting UTF-8 boundaries
-fn chunk_long_line(line: &str, max_size: usize) -> Vec<String> {
-    let mut result = Vec::new();
-    let mut current = String::new();
-
-    for ch in line.chars() {
-        let ch_len = ch.len_utf8();
-        if current.len() + ch_len > max_size
-            && !current.is_empty() {
-                result.push(current);
-                current = String::new();
-            }
-        current.push(ch);
+/// Byte index to cut `text` at so the first part is at most `budget` bytes
+fn find_split(text: &str, budget: usize) -> usize {
+    let mut limit = budget.min(text.len());
+    while !text.is_char_boundary(limit) {
+        limit -= 1;
     }
-
-    if !current.is_empty() {
-        result.push(current);
+    if limit == 0 {
+        // A single character wider than the budget still has to go somewhere
+        return text.chars().next().map_or(text.len(), char::len_utf8);
     }
 
-    result
+    // Don't accept a boundary that would leave a tiny chunk behind
+    let window = &text[..limit];
+    let min_cut = limit / 2;
+    SPLIT_POINTS
+        .iter()
+        .find_map(|sep| {
+            window
+                .rfind(sep)
+                .map(|idx| idx + sep.len())
+                .filter(|&cut| cut > min_cut)
+        })
+        .unwrap_or(limit)
+}
+
+/// Fence state after `piece`: the opening line of a still-open code block, if any
+fn track_fence(mut open_fence: Option<String>, piece: &str) -> Option<String> {
+    for line in piece.lines() {
+        let line = line.trim();
+        if line.starts_with(CODE_FENCE) {
+            open_fence = match open_fence {
+                Some(_) => None,
+                None => Some(line.to_string()),
+            };
+        }
+    }
+    open_fence
 }
 
 /// Chunk text for embed descriptions (4096 character limit)
@@ -193,4 +238,45 @@ mod tests {
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].len(), 100);
     }
+
+    #[test]
+    fn test_prefers_paragraph_and_sentence_boundaries() {
+        let text = format!(
+            "{}\n\n{}",
+            "First paragraph.".repeat(3),
+            "Second one. ".repeat(4)
+        );
+        let result = chunk_text(&text, 60);
+        assert_eq!(result[0], "First paragraph.".repeat(3));
+        for chunk in &result[1..] {
+            assert!(chunk.ends_with('.'), "split mid-sentence: {chunk:?}");
+        }
+    }
+
+    #[test]
+    fn test_multibyte_without_whitespace() {
+        let text = "世界".repeat(100);
+        let chunks = chunk_text(&text, 100);
+        assert!(chunks.iter().all(|c| c.len() <= 100));
+        assert_eq!(chunks.concat(), text);
+    }
+
+    #[test]
+    fn test_code_blocks_stay_balanced() {
+        let code: String = (0..30).map(|i| format!("let x{i} = {i};\n")).collect();
+        let text = format!("Here you go:\n```rust\n{code}```\nDone.");
+        let chunks = chunk_text(&text, 200);
+        assert!(chunks.len() > 2);
+
+        for (i, chunk) in chunks.iter().enumerate() {
+            assert!(chunk.len() <= 200);
+            let fences = chunk.lines().filter(|l| l.starts_with(CODE_FENCE)).count();
+            assert_eq!(fences % 2, 0, "unbalanced chunk {i}: {chunk:?}");
+            if i > 0 && i < chunks.len() - 1 {
+                assert!(chunk.starts_with("```rust\n"));
+            }
+        }
+        assert!(chunks.last().unwrap().ends_with("Done."));
+        assert!(chunks.iter().all(|c| !c.contains("```rust\n```")));
+    }
 }
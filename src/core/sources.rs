@@ -0,0 +1,205 @@
+//! Source provenance for AI responses
+//!
+//! Context builders record where injected content came from (fetched pages,
+//! text attachments) in a `SourceList`. The list numbers each source, tells
+//! the model how to cite them, and renders the numbered footnotes that the
+//! response formatter puts in the embed footer.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with URL and attachment sources
+
+use serenity::builder::CreateEmbed;
+
+/// Discord embed footer text limit
+pub const FOOTER_LIMIT: usize = 2048;
+
+/// Longest URL shown in a footnote before it is shortened
+const MAX_FOOTNOTE_URL_LEN: usize = 200;
+
+/// What kind of content a source contributed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceKind {
+    /// A web page fetched for the request
+    Url,
+    /// A text file attached to the conversation
+    Attachment,
+}
+
+/// One piece of context the model was given
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Source {
+    pub kind: SourceKind,
+    /// Human readable name (the URL for pages, the filename for attachments)
+    pub label: String,
+    pub url: Option<String>,
+}
+
+impl Source {
+    pub fn url(url: impl Into<String>) -> Self {
+        let url = url.into();
+        Self {
+            kind: SourceKind::Url,
+            label: url.clone(),
+            url: Some(url),
+        }
+    }
+
+    pub fn attachment(filename: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            kind: SourceKind::Attachment,
+            label: filename.into(),
+            url: Some(url.into()),
+        }
+    }
+
+    /// How the source is introduced to the model
+    fn prompt_label(&self) -> String {
+        match self.kind {
+            SourceKind::Url => format!("Webpage: {}", self.label),
+            SourceKind::Attachment => format!("Attached file: {}", self.label),
+        }
+    }
+
+    /// How the source is shown to the user (embed footers don't render links,
+    /// so pages show their URL and attachments their filename)
+    fn footnote_label(&self) -> String {
+        match (self.kind, &self.url) {
+            (SourceKind::Url, Some(url)) if url.chars().count() > MAX_FOOTNOTE_URL_LEN => {
+                let short: String = url.chars().take(MAX_FOOTNOTE_URL_LEN - 3).collect();
+                format!("{short}...")
+            }
+            (SourceKind::Url, Some(url)) => url.clone(),
+            _ => self.label.clone(),
+        }
+    }
+}
+
+/// Numbered, de-duplicated sources in the order they entered the context
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceList {
+    sources: Vec<Source>,
+}
+
+impl SourceList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a source and return its footnote number (existing sources keep theirs)
+    pub fn add(&mut self, source: Source) -> usize {
+        if let Some(index) = self.sources.iter().position(|s| *s == source) {
+            return index + 1;
+        }
+        self.sources.push(source);
+        self.sources.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.sources.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Source> {
+        self.sources.iter()
+    }
+
+    /// Source list and citation instructions to prepend to the user message
+    pub fn citation_instructions(&self) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+
+        let mut text = String::from("[Sources]\n");
+        for (i, source) in self.sources.iter().enumerate() {
+            text.push_str(&format!("[{}] {}\n", i + 1, source.prompt_label()));
+        }
+        text.push_str(
+            "When your answer uses information from one of these sources, cite it inline \
+             with its number in square brackets, e.g. [1].\n\n",
+        );
+        text
+    }
+
+    /// Numbered footnotes, one per line, within the embed footer limit
+    pub fn footnotes(&self) -> String {
+        let mut text = String::from("Sources:");
+        for (i, source) in self.sources.iter().enumerate() {
+            let line = format!("\n[{}] {}", i + 1, source.footnote_label());
+            if text.len() + line.len() > FOOTER_LIMIT {
+                break;
+            }
+            text.push_str(&line);
+        }
+        text
+    }
+
+    /// Put the footnotes in an embed's footer (no-op without sources)
+    pub fn apply_footer(&self, embed: &mut CreateEmbed) {
+        if !self.is_empty() {
+            let footnotes = self.footnotes();
+            embed.footer(|f| f.text(footnotes));
+        }
+    }
+
+    /// Append the footnotes to a plain-text response
+    pub fn append_to(&self, text: &str) -> String {
+        if self.is_empty() {
+            text.to_string()
+        } else {
+            format!("{text}\n\n{}", self.footnotes())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sources_are_numbered_and_deduplicated() {
+        let mut sources = SourceList::new();
+        assert_eq!(sources.add(Source::url("https://example.com/a")), 1);
+        assert_eq!(
+            sources.add(Source::attachment("notes.txt", "https://cdn/notes.txt")),
+            2
+        );
+        assert_eq!(sources.add(Source::url("https://example.com/a")), 1);
+        assert_eq!(sources.len(), 2);
+
+        let instructions = sources.citation_instructions();
+        assert!(instructions.contains("[1] Webpage: https://example.com/a"));
+        assert!(instructions.contains("[2] Attached file: notes.txt"));
+
+        assert_eq!(
+            sources.footnotes(),
+            "Sources:\n[1] https://example.com/a\n[2] notes.txt"
+        );
+    }
+
+    #[test]
+    fn test_empty_list_adds_nothing() {
+        let sources = SourceList::new();
+        assert_eq!(sources.citation_instructions(), "");
+        assert_eq!(sources.append_to("answer"), "answer");
+    }
+
+    #[test]
+    fn test_footnotes_stay_within_footer_limit() {
+        let mut sources = SourceList::new();
+        for i in 0..50 {
+            sources.add(Source::url(format!(
+                "https://example.com/{i}/{}",
+                "x".repeat(300)
+            )));
+        }
+        let footnotes = sources.footnotes();
+        assert!(footnotes.len() <= FOOTER_LIMIT);
+        assert!(footnotes.lines().nth(1).unwrap().ends_with("..."));
+    }
+}
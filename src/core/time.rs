@@ -0,0 +1,236 @@
+//! # Time Zones
+//!
+//! Shared helpers for reading times in a member's or guild's zone. A [`Zone`]
+//! is either an IANA name such as `Europe/Berlin`, converted through the tz
+//! database so daylight saving is followed, or a fixed UTC offset for people
+//! who'd rather give one.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.8
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with IANA zones, moved the offset helpers out of birthdays
+
+use chrono::{DateTime, Duration, NaiveDateTime, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::fmt;
+
+/// Offsets outside UTC-12:00..UTC+14:00 don't exist
+const MIN_OFFSET_MINUTES: i32 = -12 * 60;
+const MAX_OFFSET_MINUTES: i32 = 14 * 60;
+
+/// Parse a UTC offset such as `UTC+2`, `-05:00`, `GMT+5:30` or `UTC` into minutes
+pub fn parse_utc_offset(value: &str) -> Result<i32, &'static str> {
+    const INVALID: &str =
+        "Invalid timezone. Use a UTC offset such as `UTC+2`, `UTC-5` or `UTC+5:30`.";
+    let value = value.trim().to_ascii_uppercase();
+    let rest = value
+        .strip_prefix("UTC")
+        .or_else(|| value.strip_prefix("GMT"))
+        .unwrap_or(&value)
+        .trim();
+    if rest.is_empty() || rest == "Z" {
+        return Ok(0);
+    }
+
+    let (sign, rest) = match (rest.strip_prefix('+'), rest.strip_prefix('-')) {
+        (Some(rest), _) => (1, rest),
+        (_, Some(rest)) => (-1, rest),
+        _ => return Err(INVALID),
+    };
+    let (hours, minutes) = match rest.split_once(':') {
+        Some((h, m)) => (h, m),
+        None if rest.len() == 4 => rest.split_at(2),
+        None => (rest, "0"),
+    };
+    let hours = hours.parse::<i32>().map_err(|_| INVALID)?;
+    let minutes = minutes.parse::<i32>().map_err(|_| INVALID)?;
+    if !(0..60).contains(&minutes) {
+        return Err(INVALID);
+    }
+
+    let offset = sign * (hours * 60 + minutes);
+    if !(MIN_OFFSET_MINUTES..=MAX_OFFSET_MINUTES).contains(&offset) {
+        return Err("UTC offsets range from `UTC-12` to `UTC+14`.");
+    }
+    Ok(offset)
+}
+
+/// Display an offset in minutes as `UTC+05:30`
+pub fn format_utc_offset(minutes: i32) -> String {
+    let sign = if minutes < 0 { '-' } else { '+' };
+    let minutes = minutes.abs();
+    format!("UTC{sign}{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+/// Wall-clock time at a fixed UTC offset
+pub fn local_time(now: DateTime<Utc>, utc_offset_minutes: i32) -> NaiveDateTime {
+    now.naive_utc() + Duration::minutes(utc_offset_minutes as i64)
+}
+
+/// A time zone a member or organizer gave
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Zone {
+    /// IANA zone, following its daylight saving rules
+    Named(Tz),
+    /// Fixed offset in minutes
+    Fixed(i32),
+}
+
+impl Zone {
+    /// Parse an IANA name (any case) or a UTC offset
+    pub fn parse(value: &str) -> Result<Self, &'static str> {
+        let value = value.trim();
+        if let Ok(tz) = Tz::from_str_insensitive(value) {
+            return Ok(Self::Named(tz));
+        }
+        parse_utc_offset(value).map(Self::Fixed).map_err(|_| {
+            "Invalid timezone. Use a zone name such as `Europe/Berlin` or `America/New_York`, \
+             or a UTC offset such as `UTC+5:30`."
+        })
+    }
+
+    /// Read a zone saved with [`Zone::to_setting`]; bare numbers are offsets
+    /// in minutes, as stored before zone names were supported
+    pub fn from_setting(value: &str) -> Option<Self> {
+        match value.parse::<i32>() {
+            Ok(minutes) => Some(Self::Fixed(minutes)),
+            Err(_) => value.parse::<Tz>().ok().map(Self::Named),
+        }
+    }
+
+    /// The form stored in settings and tables
+    pub fn to_setting(&self) -> String {
+        match self {
+            Self::Named(tz) => tz.name().to_string(),
+            Self::Fixed(minutes) => minutes.to_string(),
+        }
+    }
+
+    /// Offset from UTC in minutes at `at`
+    pub fn offset_minutes(&self, at: DateTime<Utc>) -> i32 {
+        match self {
+            Self::Named(tz) => {
+                tz.offset_from_utc_datetime(&at.naive_utc())
+                    .fix()
+                    .local_minus_utc()
+                    / 60
+            }
+            Self::Fixed(minutes) => *minutes,
+        }
+    }
+
+    /// Wall-clock time in this zone
+    pub fn local_time(&self, now: DateTime<Utc>) -> NaiveDateTime {
+        local_time(now, self.offset_minutes(now))
+    }
+
+    /// The unix time of a wall-clock time in this zone
+    ///
+    /// A time repeated when clocks go back is read as the first one; a time
+    /// skipped when they go forward is read an hour later, as a wall clock
+    /// set before the change would show it.
+    pub fn to_unix(&self, local: NaiveDateTime) -> i64 {
+        match self {
+            Self::Named(tz) => tz
+                .from_local_datetime(&local)
+                .earliest()
+                .or_else(|| {
+                    tz.from_local_datetime(&(local + Duration::hours(1)))
+                        .earliest()
+                })
+                .map(|at| at.timestamp())
+                .unwrap_or_else(|| local.and_utc().timestamp()),
+            Self::Fixed(minutes) => (local - Duration::minutes(i64::from(*minutes)))
+                .and_utc()
+                .timestamp(),
+        }
+    }
+}
+
+impl fmt::Display for Zone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Named(tz) => f.write_str(tz.name()),
+            Self::Fixed(minutes) => f.write_str(&format_utc_offset(*minutes)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn utc(value: &str) -> i64 {
+        local(value).and_utc().timestamp()
+    }
+
+    #[test]
+    fn test_parse_utc_offset() {
+        assert_eq!(parse_utc_offset("UTC"), Ok(0));
+        assert_eq!(parse_utc_offset("utc+2"), Ok(120));
+        assert_eq!(parse_utc_offset("GMT-5"), Ok(-300));
+        assert_eq!(parse_utc_offset("+05:30"), Ok(330));
+        assert_eq!(parse_utc_offset("-0930"), Ok(-570));
+        assert!(parse_utc_offset("UTC+15").is_err());
+        assert!(parse_utc_offset("UTC+2:75").is_err());
+        assert!(parse_utc_offset("Europe/Berlin").is_err());
+        assert!(parse_utc_offset("ü+2").is_err());
+        assert_eq!(format_utc_offset(330), "UTC+05:30");
+        assert_eq!(format_utc_offset(-300), "UTC-05:00");
+    }
+
+    #[test]
+    fn test_zone_parse_and_setting() {
+        let berlin = Zone::parse(" europe/berlin ").unwrap();
+        assert_eq!(berlin, Zone::Named(Tz::Europe__Berlin));
+        assert_eq!(berlin.to_string(), "Europe/Berlin");
+        assert_eq!(Zone::parse("UTC+5:30"), Ok(Zone::Fixed(330)));
+        assert_eq!(Zone::Fixed(330).to_string(), "UTC+05:30");
+        assert!(Zone::parse("Mars/Olympus").is_err());
+
+        for zone in [berlin, Zone::Fixed(-300)] {
+            assert_eq!(Zone::from_setting(&zone.to_setting()), Some(zone));
+        }
+        // Offsets stored before zone names were supported still read
+        assert_eq!(Zone::from_setting("120"), Some(Zone::Fixed(120)));
+        assert_eq!(Zone::from_setting("nowhere"), None);
+    }
+
+    #[test]
+    fn test_named_zone_follows_daylight_saving() {
+        let berlin = Zone::Named(Tz::Europe__Berlin);
+        // Summer time (UTC+2) ends on 2026-10-25
+        assert_eq!(
+            berlin.to_unix(local("2026-10-24 15:00")),
+            utc("2026-10-24 13:00")
+        );
+        assert_eq!(
+            berlin.to_unix(local("2026-10-26 15:00")),
+            utc("2026-10-26 14:00")
+        );
+        let summer = DateTime::from_timestamp(utc("2026-07-01 12:00"), 0).unwrap();
+        let winter = DateTime::from_timestamp(utc("2026-12-01 12:00"), 0).unwrap();
+        assert_eq!(berlin.offset_minutes(summer), 120);
+        assert_eq!(berlin.local_time(winter), local("2026-12-01 13:00"));
+
+        // 02:30 happens twice when clocks go back, and not at all when they go forward
+        assert_eq!(
+            berlin.to_unix(local("2026-10-25 02:30")),
+            utc("2026-10-25 00:30")
+        );
+        assert_eq!(
+            berlin.to_unix(local("2026-03-29 02:30")),
+            utc("2026-03-29 01:30")
+        );
+
+        assert_eq!(
+            Zone::Fixed(120).to_unix(local("2026-10-26 15:00")),
+            utc("2026-10-26 13:00")
+        );
+    }
+}
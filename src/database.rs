@@ -1,3718 +1,13763 @@
 use anyhow::Result;
-use log::info;
+use dashmap::DashMap;
+use log::{info, warn};
 use sqlite::{Connection, State};
+use std::collections::{BTreeMap, HashSet};
+use std::hash::Hash;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use tokio::sync::Mutex;
-
-#[derive(Clone)]
-pub struct Database {
-    connection: Arc<Mutex<Connection>>,
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::core::Zone;
+use crate::features::guardrails::GuardrailProfile;
+use crate::features::resilience::chaos;
+
+/// Default number of pooled SQLite connections
+pub const DEFAULT_POOL_SIZE: usize = 4;
+
+/// How long a connection waits on a locked database before returning SQLITE_BUSY
+const BUSY_TIMEOUT_MS: usize = 5_000;
+
+/// Fixed-size pool of SQLite connections
+///
+/// `lock()` hands out the first idle connection and otherwise waits on a
+/// round-robin slot. Every connection runs in WAL mode with a busy timeout,
+/// so readers never block the writer and short write contention between
+/// connections is retried inside SQLite instead of surfacing as SQLITE_BUSY.
+struct ConnectionPool {
+    connections: Vec<Mutex<Connection>>,
+    next: AtomicUsize,
 }
 
-impl Database {
-    pub async fn new(database_path: &str) -> Result<Self> {
-        let connection = sqlite::open(database_path)?;
-        let db = Database {
-            connection: Arc::new(Mutex::new(connection)),
+impl ConnectionPool {
+    fn open(database_path: &str, size: usize) -> Result<Self> {
+        // In-memory databases are private to a connection, so they can't be pooled
+        let size = if database_path == ":memory:" {
+            1
+        } else {
+            size.max(1)
         };
 
-        db.init_tables().await?;
-        info!("Database initialized at: {database_path}");
-        Ok(db)
+        let mut connections = Vec::with_capacity(size);
+        for _ in 0..size {
+            let mut connection = sqlite::open(database_path)?;
+            connection.set_busy_timeout(BUSY_TIMEOUT_MS)?;
+            connection.execute(
+                "PRAGMA journal_mode = WAL;
+                 PRAGMA synchronous = NORMAL;
+                 PRAGMA temp_store = MEMORY;",
+            )?;
+            connections.push(Mutex::new(connection));
+        }
+
+        Ok(Self {
+            connections,
+            next: AtomicUsize::new(0),
+        })
     }
 
-    async fn init_tables(&self) -> Result<()> {
-        let conn = self.connection.lock().await;
+    /// Acquire a connection, preferring one that is currently idle
+    ///
+    /// This is the single entry point for every query, so it is also where chaos
+    /// fault injection simulates database errors.
+    async fn lock(&self) -> Result<MutexGuard<'_, Connection>> {
+        chaos().database_fault("database")?;
+        for connection in &self.connections {
+            if let Ok(guard) = connection.try_lock() {
+                return Ok(guard);
+            }
+        }
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.connections.len();
+        Ok(self.connections[index].lock().await)
+    }
 
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS user_preferences (
-                user_id TEXT PRIMARY KEY,
-                default_persona TEXT DEFAULT 'obi',
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
-            )",
-        )?;
+    fn size(&self) -> usize {
+        self.connections.len()
+    }
+}
 
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS usage_stats (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                user_id TEXT NOT NULL,
-                command TEXT NOT NULL,
-                persona TEXT,
-                timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
-            )",
-        )?;
+/// Default lifetime of cached guild/channel settings
+pub const DEFAULT_SETTINGS_CACHE_TTL: Duration = Duration::from_secs(60);
 
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS conversation_history (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                user_id TEXT NOT NULL,
-                channel_id TEXT NOT NULL,
-                role TEXT NOT NULL,
-                content TEXT NOT NULL,
-                persona TEXT,
-                timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
-            )",
-        )?;
+/// Default number of days raw usage rows are kept after being rolled up
+pub const DEFAULT_RAW_USAGE_RETENTION_DAYS: i64 = 7;
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_user_channel
-             ON conversation_history(user_id, channel_id)",
-        )?;
+/// Default number of days daily analytics rollups are kept
+pub const DEFAULT_ROLLUP_RETENTION_DAYS: i64 = 400;
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_timestamp
-             ON conversation_history(timestamp)",
-        )?;
+/// Hourly rollups only need to outlive the raw rows they're rebuilt from
+const HOURLY_ROLLUP_RETENTION_DAYS: i64 = 35;
 
-        // Enhanced Interaction Tracking
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS message_metadata (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                message_id TEXT NOT NULL,
-                user_id TEXT NOT NULL,
-                channel_id TEXT NOT NULL,
-                attachment_urls TEXT,
-                embed_data TEXT,
-                reactions TEXT,
-                edited_at DATETIME,
-                deleted_at DATETIME,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
-            )",
-        )?;
+/// bot_settings key holding the start of the first hour not yet rolled up
+const ROLLUP_WATERMARK_KEY: &str = "analytics_rollup_watermark";
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_message_id
-             ON message_metadata(message_id)",
-        )?;
+/// bot_settings key holding the start of the first hour of system metrics not yet downsampled
+const METRICS_WATERMARK_KEY: &str = "system_metrics_rollup_watermark";
 
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS interaction_sessions (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                user_id TEXT NOT NULL,
-                guild_id TEXT,
-                session_start DATETIME DEFAULT CURRENT_TIMESTAMP,
-                session_end DATETIME,
-                message_count INTEGER DEFAULT 0,
-                last_activity DATETIME DEFAULT CURRENT_TIMESTAMP
-            )",
-        )?;
+/// Days of raw 1-minute system metrics kept
+pub const MINUTE_METRICS_RETENTION_DAYS: i64 = 2;
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_session_user
-             ON interaction_sessions(user_id, session_start)",
-        )?;
+/// Days of hourly system metrics kept, enough to graph a month at hourly resolution
+pub const HOURLY_METRICS_RETENTION_DAYS: i64 = 35;
 
-        // Feature-Specific Data
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS user_bookmarks (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                user_id TEXT NOT NULL,
-                channel_id TEXT NOT NULL,
-                message_id TEXT NOT NULL,
-                bookmark_name TEXT,
-                bookmark_note TEXT,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
-            )",
-        )?;
+/// Suggestion columns, with vote tallies, selected from `suggestions s`
+const SUGGESTION_COLUMNS: &str = "SELECT s.id, s.guild_id, s.channel_id, s.message_id, s.author_id,
+    s.content, s.status, s.status_note, s.reviewed_by,
+    (SELECT COUNT(*) FROM suggestion_votes v WHERE v.suggestion_id = s.id AND v.vote > 0),
+    (SELECT COUNT(*) FROM suggestion_votes v WHERE v.suggestion_id = s.id AND v.vote < 0)";
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_bookmark_user
-             ON user_bookmarks(user_id)",
-        )?;
+/// Resolution a system metrics series is read at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricsTier {
+    /// Raw samples from the metrics loop
+    Minute,
+    Hour,
+    Day,
+}
 
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS reminders (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                user_id TEXT NOT NULL,
-                channel_id TEXT NOT NULL,
-                reminder_text TEXT NOT NULL,
-                remind_at DATETIME NOT NULL,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                completed BOOLEAN DEFAULT 0,
-                completed_at DATETIME
-            )",
-        )?;
+impl MetricsTier {
+    /// Finest tier still retained for the whole span
+    pub fn for_span(hours: i64) -> Self {
+        if hours <= MINUTE_METRICS_RETENTION_DAYS * 24 {
+            MetricsTier::Minute
+        } else if hours <= HOURLY_METRICS_RETENTION_DAYS * 24 {
+            MetricsTier::Hour
+        } else {
+            MetricsTier::Day
+        }
+    }
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_reminder_time
-             ON reminders(remind_at, completed)",
-        )?;
+    /// Value of `system_metrics_rollup.tier`
+    pub fn id(&self) -> &'static str {
+        match self {
+            MetricsTier::Minute => "minute",
+            MetricsTier::Hour => "hour",
+            MetricsTier::Day => "day",
+        }
+    }
+}
 
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS custom_commands (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                command_name TEXT NOT NULL,
-                response_text TEXT NOT NULL,
-                created_by_user_id TEXT NOT NULL,
-                guild_id TEXT,
-                is_global BOOLEAN DEFAULT 0,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                UNIQUE(command_name, guild_id)
-            )",
-        )?;
+/// A use of a user's messages they can opt out of with /privacy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrivacyOption {
+    /// Storing DM conversation history
+    DmHistory,
+    /// DM session tracking and usage statistics
+    Analytics,
+    /// Reading guild messages for conflict detection
+    ConflictAnalysis,
+}
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_custom_command
-             ON custom_commands(command_name, guild_id)",
-        )?;
+impl PrivacyOption {
+    pub const ALL: [PrivacyOption; 3] = [
+        PrivacyOption::DmHistory,
+        PrivacyOption::Analytics,
+        PrivacyOption::ConflictAnalysis,
+    ];
+
+    /// Value of `user_privacy.option`
+    pub fn key(&self) -> &'static str {
+        match self {
+            PrivacyOption::DmHistory => "dm_history",
+            PrivacyOption::Analytics => "analytics",
+            PrivacyOption::ConflictAnalysis => "conflict_analysis",
+        }
+    }
 
-        // Analytics & Metrics
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS daily_analytics (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                date DATE UNIQUE NOT NULL,
-                total_messages INTEGER DEFAULT 0,
-                unique_users INTEGER DEFAULT 0,
-                total_commands INTEGER DEFAULT 0,
-                total_errors INTEGER DEFAULT 0,
-                persona_usage TEXT,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
-            )",
-        )?;
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|option| option.key() == key)
+    }
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_analytics_date
-             ON daily_analytics(date)",
-        )?;
+    pub fn label(&self) -> &'static str {
+        match self {
+            PrivacyOption::DmHistory => "DM history storage",
+            PrivacyOption::Analytics => "Analytics tracking",
+            PrivacyOption::ConflictAnalysis => "Conflict analysis",
+        }
+    }
 
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS performance_metrics (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                metric_type TEXT NOT NULL,
-                value REAL NOT NULL,
-                unit TEXT,
-                metadata TEXT,
-                timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
-            )",
-        )?;
+    /// Guild setting holding the server default, for options that apply inside guilds
+    pub fn guild_setting(&self) -> Option<&'static str> {
+        match self {
+            PrivacyOption::DmHistory => None,
+            PrivacyOption::Analytics => Some("privacy_analytics"),
+            PrivacyOption::ConflictAnalysis => Some("privacy_conflict_analysis"),
+        }
+    }
+}
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_metrics_type
-             ON performance_metrics(metric_type, timestamp)",
-        )?;
+/// Effective privacy settings for one user in one place (a guild or DMs)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrivacySettings {
+    pub dm_history: bool,
+    pub analytics: bool,
+    pub conflict_analysis: bool,
+}
 
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS error_logs (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                error_type TEXT NOT NULL,
-                error_message TEXT NOT NULL,
-                stack_trace TEXT,
-                user_id TEXT,
-                channel_id TEXT,
-                command TEXT,
-                metadata TEXT,
-                timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
-            )",
-        )?;
+impl Default for PrivacySettings {
+    /// Everything is allowed until the user or guild says otherwise
+    fn default() -> Self {
+        Self {
+            dm_history: true,
+            analytics: true,
+            conflict_analysis: true,
+        }
+    }
+}
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_error_type
-             ON error_logs(error_type, timestamp)",
-        )?;
+impl PrivacySettings {
+    pub fn allows(&self, option: PrivacyOption) -> bool {
+        match option {
+            PrivacyOption::DmHistory => self.dm_history,
+            PrivacyOption::Analytics => self.analytics,
+            PrivacyOption::ConflictAnalysis => self.conflict_analysis,
+        }
+    }
 
-        // Extended Configuration
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS feature_flags (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                feature_name TEXT NOT NULL,
-                enabled BOOLEAN DEFAULT 0,
-                user_id TEXT,
-                guild_id TEXT,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                UNIQUE(feature_name, user_id, guild_id)
-            )",
-        )?;
+    fn set(&mut self, option: PrivacyOption, allowed: bool) {
+        match option {
+            PrivacyOption::DmHistory => self.dm_history = allowed,
+            PrivacyOption::Analytics => self.analytics = allowed,
+            PrivacyOption::ConflictAnalysis => self.conflict_analysis = allowed,
+        }
+    }
+}
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_feature_flag
-             ON feature_flags(feature_name, user_id, guild_id)",
-        )?;
+/// What an /admin block or allow entry applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessTarget {
+    User,
+    Channel,
+}
 
-        // Feature versions tracking for audit trail
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS feature_versions (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                feature_name TEXT NOT NULL,
-                version TEXT NOT NULL,
-                guild_id TEXT,
-                toggled_by TEXT,
-                enabled BOOLEAN NOT NULL,
-                changed_at DATETIME DEFAULT CURRENT_TIMESTAMP
-            )",
-        )?;
+impl AccessTarget {
+    /// Value of `access_rules.target_type`
+    pub fn key(&self) -> &'static str {
+        match self {
+            AccessTarget::User => "user",
+            AccessTarget::Channel => "channel",
+        }
+    }
+}
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_feature_versions
-             ON feature_versions(feature_name, guild_id, changed_at)",
-        )?;
+/// Whether a listed user or channel is ignored or allowlisted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessRule {
+    Block,
+    Allow,
+}
 
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS guild_settings (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                guild_id TEXT NOT NULL,
-                setting_key TEXT NOT NULL,
-                setting_value TEXT,
-                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                UNIQUE(guild_id, setting_key)
-            )",
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_guild_setting
-             ON guild_settings(guild_id, setting_key)",
-        )?;
+impl AccessRule {
+    /// Value of `access_rules.rule`
+    pub fn key(&self) -> &'static str {
+        match self {
+            AccessRule::Block => "block",
+            AccessRule::Allow => "allow",
+        }
+    }
+}
 
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS extended_user_preferences (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                user_id TEXT NOT NULL,
-                preference_key TEXT NOT NULL,
-                preference_value TEXT,
-                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                UNIQUE(user_id, preference_key)
-            )",
-        )?;
+/// A guild's block and allow lists
+///
+/// An empty allowlist imposes nothing; once it has entries the bot only
+/// responds to those users (or in those channels).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AccessRules {
+    pub blocked_users: Vec<String>,
+    pub blocked_channels: Vec<String>,
+    pub allowed_users: Vec<String>,
+    pub allowed_channels: Vec<String>,
+}
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_user_pref
-             ON extended_user_preferences(user_id, preference_key)",
-        )?;
+impl AccessRules {
+    pub fn is_empty(&self) -> bool {
+        self.blocked_users.is_empty()
+            && self.blocked_channels.is_empty()
+            && self.allowed_users.is_empty()
+            && self.allowed_channels.is_empty()
+    }
 
-        // Conflict Detection & Mediation
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS conflict_detection (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                channel_id TEXT NOT NULL,
-                guild_id TEXT,
-                participants TEXT NOT NULL,
-                detection_type TEXT NOT NULL,
-                confidence_score REAL,
-                last_message_id TEXT,
-                mediation_triggered BOOLEAN DEFAULT 0,
-                mediation_message_id TEXT,
-                first_detected DATETIME DEFAULT CURRENT_TIMESTAMP,
-                last_detected DATETIME DEFAULT CURRENT_TIMESTAMP,
-                resolved_at DATETIME
-            )",
-        )?;
+    /// Whether any rule depends on which channel a message is in
+    pub fn has_channel_rules(&self) -> bool {
+        !self.blocked_channels.is_empty() || !self.allowed_channels.is_empty()
+    }
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_conflict_channel
-             ON conflict_detection(channel_id, guild_id)",
-        )?;
+    /// Whether the bot may respond to `user_id` in a channel, given the channel
+    /// and its parents (thread parent, category) as `channel_ids`
+    pub fn permits(&self, user_id: &str, channel_ids: &[String]) -> bool {
+        let user_id = user_id.to_string();
+        if self.blocked_users.contains(&user_id)
+            || channel_ids
+                .iter()
+                .any(|c| self.blocked_channels.contains(c))
+        {
+            return false;
+        }
+        if !self.allowed_users.is_empty() && !self.allowed_users.contains(&user_id) {
+            return false;
+        }
+        self.allowed_channels.is_empty()
+            || channel_ids
+                .iter()
+                .any(|c| self.allowed_channels.contains(c))
+    }
+}
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_conflict_timestamp
-             ON conflict_detection(first_detected)",
-        )?;
+/// Whether a saved /birthday date is a birthday or an anniversary
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CelebrationKind {
+    Birthday,
+    Anniversary,
+}
 
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS mediation_history (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                conflict_id INTEGER NOT NULL,
-                channel_id TEXT NOT NULL,
-                mediation_message TEXT,
-                effectiveness_rating INTEGER,
-                follow_up_messages INTEGER DEFAULT 0,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY(conflict_id) REFERENCES conflict_detection(id)
-            )",
-        )?;
+impl CelebrationKind {
+    /// Value of `celebrations.kind`
+    pub fn key(&self) -> &'static str {
+        match self {
+            CelebrationKind::Birthday => "birthday",
+            CelebrationKind::Anniversary => "anniversary",
+        }
+    }
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_mediation_conflict
-             ON mediation_history(conflict_id)",
-        )?;
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "birthday" => Some(CelebrationKind::Birthday),
+            "anniversary" => Some(CelebrationKind::Anniversary),
+            _ => None,
+        }
+    }
+}
 
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS user_interaction_patterns (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                user_id_a TEXT NOT NULL,
-                user_id_b TEXT NOT NULL,
-                channel_id TEXT,
-                guild_id TEXT,
-                interaction_count INTEGER DEFAULT 0,
-                last_interaction DATETIME,
-                conflict_incidents INTEGER DEFAULT 0,
-                avg_response_time_ms INTEGER,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                UNIQUE(user_id_a, user_id_b, channel_id)
-            )",
-        )?;
+/// A member's saved birthday or anniversary in one guild
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Celebration {
+    pub guild_id: String,
+    pub user_id: String,
+    pub kind: CelebrationKind,
+    pub month: u32,
+    pub day: u32,
+    /// Only kept for anniversaries, to count the years
+    pub year: Option<i32>,
+    /// The member's zone, so wishes arrive on their local date
+    pub timezone: Zone,
+    /// Local year of the last wish, so each date is celebrated once a year
+    pub last_wished_year: Option<i32>,
+}
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_interaction_users
-             ON user_interaction_patterns(user_id_a, user_id_b)",
-        )?;
+/// A member's all-time trivia record in one guild
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TriviaStanding {
+    pub user_id: String,
+    pub points: i64,
+    pub correct_answers: i64,
+    pub games_played: i64,
+    pub games_won: i64,
+}
 
-        // Channel Settings (for per-channel verbosity and other settings)
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS channel_settings (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                guild_id TEXT NOT NULL,
-                channel_id TEXT NOT NULL,
-                verbosity TEXT DEFAULT 'concise',
-                conflict_enabled BOOLEAN DEFAULT 1,
-                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                UNIQUE(guild_id, channel_id)
-            )",
-        )?;
+/// A member's XP in one guild
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemberXp {
+    pub user_id: String,
+    pub xp: i64,
+    /// Messages and interactions that earned XP
+    pub grants: i64,
+}
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_channel_settings_guild
-             ON channel_settings(guild_id)",
-        )?;
+/// A channel's scheduled topic updates (`/channel_topic schedule`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelTopicSchedule {
+    pub guild_id: String,
+    pub channel_id: String,
+    /// `rotate` or `question`
+    pub mode: String,
+    /// Topics cycled through in rotate mode
+    pub topics: Vec<String>,
+    /// Subject steering questions of the day
+    pub theme: Option<String>,
+    pub interval_hours: i64,
+    /// Unix time of the next update
+    pub next_run_at: i64,
+    /// Rotation topic used at the next update
+    pub next_index: i64,
+    /// Topic set by the latest update
+    pub last_topic: Option<String>,
+    pub created_by: String,
+}
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_channel_settings_channel
-             ON channel_settings(channel_id)",
-        )?;
+/// A guild's question of the day schedule (`/qotd setup`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QotdConfig {
+    pub guild_id: String,
+    pub channel_id: String,
+    /// Local hour questions are posted at
+    pub hour: i64,
+    pub utc_offset_minutes: i32,
+    /// Whether each question gets a thread for answers
+    pub threads: bool,
+    /// Where the best answers are archived; the question channel when unset
+    pub digest_channel_id: Option<String>,
+    /// Unix time of the next question
+    pub next_run_at: i64,
+    pub created_by: String,
+}
 
-        // Add default_persona column to channel_settings if it doesn't exist
-        // SQLite doesn't support ADD COLUMN IF NOT EXISTS, so we check pragmatically
-        let has_channel_persona: bool = {
-            let mut stmt = conn.prepare("PRAGMA table_info(channel_settings)")?;
-            let mut found = false;
-            while let Ok(State::Row) = stmt.next() {
-                let col_name: String = stmt.read(1)?;
-                if col_name == "default_persona" {
-                    found = true;
-                    break;
-                }
-            }
-            found
-        };
+/// A question waiting in a guild's QOTD queue
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QotdQuestion {
+    pub id: i64,
+    pub question: String,
+    pub added_by: String,
+}
 
-        if !has_channel_persona {
-            conn.execute("ALTER TABLE channel_settings ADD COLUMN default_persona TEXT")?;
-        }
+/// A posted question of the day
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QotdPost {
+    pub guild_id: String,
+    pub channel_id: String,
+    pub message_id: String,
+    /// Answer thread, when threads are on
+    pub thread_id: Option<String>,
+    pub question: String,
+    pub posted_at: i64,
+}
 
-        // Add max_paragraphs column to channel_settings if it doesn't exist
-        let has_max_paragraphs: bool = {
-            let mut stmt = conn.prepare("PRAGMA table_info(channel_settings)")?;
-            let mut found = false;
-            while let Ok(State::Row) = stmt.next() {
-                let col_name: String = stmt.read(1)?;
-                if col_name == "max_paragraphs" {
-                    found = true;
-                    break;
-                }
-            }
-            found
-        };
+/// The best-reacted answer to a question of the day
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QotdAnswer {
+    pub user_id: String,
+    pub content: String,
+    pub reactions: i64,
+}
 
-        if !has_max_paragraphs {
-            conn.execute("ALTER TABLE channel_settings ADD COLUMN max_paragraphs INTEGER DEFAULT 0")?;
-        }
+/// A member's `/suggest` entry with its vote tally
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    pub id: i64,
+    pub guild_id: String,
+    /// Suggestion channel it was posted in
+    pub channel_id: String,
+    /// Message showing it, once posted
+    pub message_id: Option<String>,
+    pub author_id: String,
+    pub content: String,
+    /// `open`, `accepted`, `declined` or `implemented`
+    pub status: String,
+    /// Reason given by the admin who last changed the status
+    pub status_note: Option<String>,
+    pub reviewed_by: Option<String>,
+    pub upvotes: i64,
+    pub downvotes: i64,
+}
 
-        // Bot Settings (for global bot configuration, not per-guild)
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS bot_settings (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                setting_key TEXT NOT NULL UNIQUE,
-                setting_value TEXT,
-                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
-            )",
-        )?;
+/// A `/support` ticket and the private thread it lives in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SupportTicket {
+    pub id: i64,
+    pub guild_id: String,
+    pub thread_id: String,
+    pub requester_id: String,
+    pub subject: String,
+    /// `open` or `closed`
+    pub status: String,
+    pub opened_at: i64,
+    pub closed_at: Option<i64>,
+    pub closed_by: Option<String>,
+    /// Whether the persona posted a first-line answer from the knowledge base
+    pub first_line_answered: bool,
+    /// AI summary written when the ticket was closed
+    pub summary: Option<String>,
+}
 
-        // OpenAI Usage Tracking Tables
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS openai_usage (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                request_id TEXT,
-                user_id TEXT NOT NULL,
-                guild_id TEXT,
-                channel_id TEXT,
-                service_type TEXT NOT NULL,
-                model TEXT NOT NULL,
-                input_tokens INTEGER DEFAULT 0,
-                output_tokens INTEGER DEFAULT 0,
-                total_tokens INTEGER DEFAULT 0,
-                audio_duration_seconds REAL DEFAULT 0,
-                image_count INTEGER DEFAULT 0,
-                image_size TEXT,
-                estimated_cost_usd REAL NOT NULL,
-                timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
-            )",
-        )?;
+/// A knowledge base entry the persona draws first-line support answers from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SupportKbEntry {
+    pub id: i64,
+    pub title: String,
+    pub content: String,
+}
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_openai_usage_user_ts
-             ON openai_usage(user_id, timestamp)",
-        )?;
+/// Ticket metrics for `/support stats`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SupportStats {
+    pub open: i64,
+    pub closed: i64,
+    /// Average seconds from opening to closing, over closed tickets
+    pub avg_resolution_secs: Option<i64>,
+    /// Tickets that got a first-line answer from the knowledge base
+    pub first_line_answered: i64,
+    /// Tickets opened in the last seven days
+    pub opened_last_week: i64,
+}
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_openai_usage_guild_ts
-             ON openai_usage(guild_id, timestamp)",
-        )?;
+/// A question relayed anonymously by `/ask_anon`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnonQuestion {
+    pub id: i64,
+    pub guild_id: String,
+    /// Kept private; only shown to admins through `/anon reveal`
+    pub author_id: String,
+    pub content: String,
+    /// `pending`, `posted` or `rejected`
+    pub status: String,
+    pub created_at: i64,
+    pub reviewed_by: Option<String>,
+    pub message_id: Option<String>,
+}
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_openai_usage_timestamp
-             ON openai_usage(timestamp)",
-        )?;
+/// A new member who hasn't passed the verification gate yet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingVerification {
+    pub guild_id: String,
+    pub user_id: String,
+    /// Gated channel the prompt was posted in
+    pub channel_id: String,
+    pub message_id: Option<String>,
+    /// Expected reply in question mode; None for the button
+    pub answer: Option<String>,
+    /// When the member is kicked if still unverified; None when kicks are off
+    pub deadline: Option<i64>,
+}
 
-        // Daily aggregates for fast queries (90-day retention)
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS openai_usage_daily (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                date DATE NOT NULL,
-                guild_id TEXT,
-                user_id TEXT,
-                service_type TEXT NOT NULL,
-                request_count INTEGER DEFAULT 0,
-                total_tokens INTEGER DEFAULT 0,
-                total_audio_seconds REAL DEFAULT 0,
-                total_images INTEGER DEFAULT 0,
-                total_cost_usd REAL DEFAULT 0,
-                UNIQUE(date, guild_id, user_id, service_type)
-            )",
-        )?;
+/// A guild's keyword or regex trigger from `/autoresponse`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutoResponse {
+    pub id: i64,
+    pub guild_id: String,
+    pub pattern: String,
+    /// `keyword` or `regex`
+    pub match_kind: String,
+    /// Canned reply text, or instructions for the persona
+    pub response: String,
+    /// `reply` or `persona`
+    pub response_kind: String,
+    pub cooldown_secs: i64,
+    pub enabled: bool,
+    /// Higher priorities are checked first
+    pub priority: i64,
+    pub created_by: String,
+}
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_openai_daily_guild_date
-             ON openai_usage_daily(guild_id, date)",
-        )?;
+/// A saved prompt template from `/alias create`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptAlias {
+    /// `user` for personal aliases, `guild` for shared ones
+    pub scope: String,
+    /// The owning user's ID, or the guild's ID for shared aliases
+    pub scope_id: String,
+    pub name: String,
+    /// Prompt with `${arg}` placeholders
+    pub template: String,
+    /// Persona the alias asks, when it names one
+    pub persona: Option<String>,
+    pub created_by: String,
+}
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_openai_daily_user_date
-             ON openai_usage_daily(user_id, date)",
-        )?;
+/// A prompt in a guild's shared `/prompts` library
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuildPrompt {
+    pub guild_id: String,
+    pub name: String,
+    pub category: String,
+    pub description: Option<String>,
+    /// Prompt with `${variable}` placeholders
+    pub template: String,
+    pub created_by: String,
+    /// Times `/prompts use` ran it
+    pub uses: i64,
+}
 
-        // Migration: add cost_bucket column for tracking usage by feature
-        // Uses ALTER TABLE which silently fails if column already exists
-        let _ = conn.execute(
-            "ALTER TABLE openai_usage ADD COLUMN cost_bucket TEXT NOT NULL DEFAULT 'unknown'",
-        );
-        let _ = conn.execute(
-            "ALTER TABLE openai_usage_daily ADD COLUMN cost_bucket TEXT NOT NULL DEFAULT 'unknown'",
+/// A message queued with `/send_later`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledMessage {
+    pub id: i64,
+    pub guild_id: String,
+    pub channel_id: String,
+    pub user_id: String,
+    pub content: String,
+    /// Persona that rewrites the message when it's sent; None sends it as written
+    pub persona: Option<String>,
+    /// Unix time the message goes out
+    pub send_at: i64,
+}
+
+/// A live `/countdown` embed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Countdown {
+    pub id: i64,
+    pub guild_id: String,
+    pub channel_id: String,
+    /// The embed being updated; None until it's posted
+    pub message_id: Option<String>,
+    pub title: String,
+    /// Unix time the countdown reaches zero
+    pub ends_at: i64,
+    /// Role pinged at zero
+    pub role_id: Option<String>,
+    pub created_by: String,
+}
+
+/// A combatant in a thread's `/initiative` order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InitiativeEntry {
+    pub thread_id: String,
+    pub guild_id: String,
+    pub name: String,
+    /// Rolled or set initiative; higher goes first
+    pub initiative: i64,
+    /// Initiative modifier, which breaks ties
+    pub modifier: i64,
+    pub added_by: String,
+}
+
+/// A DM session listed by `/history`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DmSessionSummary {
+    pub session_id: String,
+    pub channel_id: String,
+    /// Generated after the session's first reply; None until then
+    pub title: Option<String>,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    pub message_count: i64,
+}
+
+/// A message a user pinned with "Remember this"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PinnedMemory {
+    pub id: i64,
+    pub user_id: String,
+    pub channel_id: String,
+    pub content: String,
+    /// Who wrote the pinned message (the bot for its own replies)
+    pub author_name: String,
+    pub created_at: String,
+}
+
+/// A server-specific term defined with `/glossary add`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlossaryEntry {
+    pub guild_id: String,
+    /// As the admin wrote it; lookups ignore case
+    pub term: String,
+    pub definition: String,
+    pub created_by: String,
+}
+
+/// A concluded debate that the audience can vote on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebateResult {
+    pub thread_id: String,
+    pub guild_id: Option<String>,
+    pub initiator_id: String,
+    pub persona1_id: String,
+    pub persona2_id: String,
+    pub rounds: i64,
+}
+
+/// Everything /profile shows about a member, scoped to one guild
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UserProfile {
+    /// Persona the member talks to most here
+    pub favorite_persona: Option<String>,
+    /// Messages the member sent in the guild
+    pub message_count: i64,
+    /// Bot replies the member received in the guild
+    pub bot_replies: i64,
+    /// Most used commands with invocation counts, busiest first
+    pub top_commands: Vec<(String, i64)>,
+    /// Seconds of audio transcribed for the member in the guild
+    pub transcription_seconds: f64,
+    pub debates_started: i64,
+    pub debate_votes: i64,
+    /// Debates where the member's pick won the audience vote
+    pub debate_wins: i64,
+    pub trivia: Option<TriviaStanding>,
+}
+
+/// A guild's daily /standup schedule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StandupConfig {
+    pub guild_id: String,
+    /// Channel the compiled summary is posted in
+    pub channel_id: String,
+    /// Local time the prompts go out, in minutes after midnight
+    pub prompt_minute: u32,
+    pub utc_offset_minutes: i32,
+    /// How long members have to reply before the summary is posted
+    pub collect_minutes: u32,
+    pub weekdays_only: bool,
+    pub enabled: bool,
+    /// Local date (YYYY-MM-DD) of the latest round of prompts
+    pub last_prompt_date: Option<String>,
+    /// Unix time the latest round of prompts went out
+    pub last_prompt_at: Option<i64>,
+    /// Local date of the latest posted summary
+    pub last_summary_date: Option<String>,
+}
+
+/// A member who joined a guild's standup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StandupMember {
+    pub user_id: String,
+    /// Standups up to and including this local date are skipped
+    pub skip_until: Option<String>,
+}
+
+/// One member's answers for one day's standup
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StandupEntry {
+    pub guild_id: String,
+    pub user_id: String,
+    pub standup_date: String,
+    /// Questions answered so far (3 when complete)
+    pub step: u32,
+    pub yesterday: Option<String>,
+    pub today: Option<String>,
+    pub blockers: Option<String>,
+    pub skipped: bool,
+}
+
+/// A /meet availability poll
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MeetingPoll {
+    pub id: i64,
+    pub guild_id: String,
+    pub channel_id: String,
+    pub organizer_id: String,
+    pub title: String,
+    /// Proposed start times as unix seconds, in the order they were given
+    pub slots: Vec<i64>,
+    /// Zone the organizer proposed the times in
+    pub timezone: Zone,
+    pub duration_minutes: u32,
+    /// Create a Discord Scheduled Event for the chosen slot
+    pub create_event: bool,
+    /// Minutes before the meeting to remind participants (0 for none)
+    pub remind_minutes: u32,
+    /// Index into `slots` once the poll is closed
+    pub chosen_slot: Option<usize>,
+}
+
+/// Issue tracker a guild files /ticket issues in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueProvider {
+    Jira,
+    Linear,
+}
+
+impl IssueProvider {
+    /// Value of `issue_tracker_configs.provider`
+    pub fn key(&self) -> &'static str {
+        match self {
+            IssueProvider::Jira => "jira",
+            IssueProvider::Linear => "linear",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "jira" => Some(IssueProvider::Jira),
+            "linear" => Some(IssueProvider::Linear),
+            _ => None,
+        }
+    }
+}
+
+/// A guild's issue tracker workspace and credentials
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssueTrackerConfig {
+    pub guild_id: String,
+    pub provider: IssueProvider,
+    /// Jira project key or Linear team ID
+    pub project: String,
+    pub api_token: String,
+    /// Jira site URL, e.g. `https://acme.atlassian.net` (unused for Linear)
+    pub base_url: Option<String>,
+    /// Jira account email the token belongs to (unused for Linear)
+    pub email: Option<String>,
+    /// Jira issue type name (unused for Linear)
+    pub issue_type: Option<String>,
+    /// Summarize messages into a title and description by default
+    pub summarize: bool,
+}
+
+/// Payload shape a guild webhook expects
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookFormat {
+    /// Slack incoming webhook (`{"text": ...}`)
+    Slack,
+    /// Matrix hookshot generic webhook (`{"text": ..., "username": ...}`)
+    Matrix,
+    /// Event name, rendered text and the raw fields
+    Generic,
+}
+
+impl WebhookFormat {
+    /// Value of `guild_webhooks.format`
+    pub fn key(&self) -> &'static str {
+        match self {
+            WebhookFormat::Slack => "slack",
+            WebhookFormat::Matrix => "matrix",
+            WebhookFormat::Generic => "generic",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "slack" => Some(WebhookFormat::Slack),
+            "matrix" => Some(WebhookFormat::Matrix),
+            "generic" => Some(WebhookFormat::Generic),
+            _ => None,
+        }
+    }
+}
+
+/// An outbound webhook a guild subscribed to bot events
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuildWebhook {
+    pub id: i64,
+    pub guild_id: String,
+    pub url: String,
+    pub format: WebhookFormat,
+    /// Event keys this webhook receives
+    pub events: Vec<String>,
+    /// Message template overriding each event's default text
+    pub template: Option<String>,
+    /// Error from the last delivery that gave up, cleared on success
+    pub last_error: Option<String>,
+}
+
+/// Concurrent map whose entries expire after a fixed TTL
+struct TtlMap<K: Eq + Hash, V: Clone> {
+    entries: DashMap<K, (V, Instant)>,
+}
+
+impl<K: Eq + Hash, V: Clone> TtlMap<K, V> {
+    fn new() -> Self {
+        Self {
+            entries: DashMap::new(),
+        }
+    }
+
+    fn get(&self, key: &K, ttl: Duration) -> Option<V> {
+        let entry = self.entries.get(key)?;
+        if entry.1.elapsed() < ttl {
+            Some(entry.0.clone())
+        } else {
+            drop(entry);
+            self.entries.remove(key);
+            None
+        }
+    }
+
+    fn insert(&self, key: K, value: V) {
+        self.entries.insert(key, (value, Instant::now()));
+    }
+
+    fn remove(&self, key: &K) {
+        self.entries.remove(key);
+    }
+
+    fn retain(&self, keep: impl Fn(&K) -> bool) {
+        self.entries.retain(|k, _| keep(k));
+    }
+
+    fn clear(&self) {
+        self.entries.clear();
+    }
+}
+
+/// Read-through cache for the settings consulted on every message
+///
+/// Entries are invalidated by the matching `set_*` methods, so writes from
+/// slash commands and IPC are visible immediately; the TTL only bounds how
+/// long an out-of-band edit to the database file can go unnoticed.
+struct SettingsCache {
+    ttl: Duration,
+    /// (guild_id, setting_key) -> setting_value
+    guild_settings: TtlMap<(String, String), Option<String>>,
+    /// (feature_name, user_id, guild_id) -> enabled
+    feature_flags: TtlMap<(String, String, String), bool>,
+    /// (guild_id, channel_id) -> effective verbosity
+    channel_verbosity: TtlMap<(String, String), String>,
+    /// (guild_id, channel_id, column) -> raw value of a per-message channel_settings column
+    channel_columns: TtlMap<(String, String, &'static str), Option<String>>,
+    /// user_id -> explicit /privacy choices as (option, allowed)
+    user_privacy: TtlMap<String, Vec<(PrivacyOption, bool)>>,
+    /// guild_id -> /admin block and allow lists
+    access_rules: TtlMap<String, AccessRules>,
+    /// guild_id -> /autoresponse triggers in evaluation order
+    autoresponses: TtlMap<String, Vec<AutoResponse>>,
+}
+
+impl SettingsCache {
+    fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            guild_settings: TtlMap::new(),
+            feature_flags: TtlMap::new(),
+            channel_verbosity: TtlMap::new(),
+            channel_columns: TtlMap::new(),
+            user_privacy: TtlMap::new(),
+            access_rules: TtlMap::new(),
+            autoresponses: TtlMap::new(),
+        }
+    }
+}
+
+/// Schema changes `init_tables` applied when the database was opened
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MigrationReport {
+    pub created_tables: Vec<String>,
+    /// `table.column` for columns added to tables that already existed
+    pub added_columns: Vec<String>,
+    pub table_count: usize,
+}
+
+impl MigrationReport {
+    fn between(
+        before: &BTreeMap<String, Vec<String>>,
+        after: &BTreeMap<String, Vec<String>>,
+    ) -> Self {
+        let mut report = MigrationReport {
+            table_count: after.len(),
+            ..Default::default()
+        };
+        for (table, columns) in after {
+            match before.get(table) {
+                None => report.created_tables.push(table.clone()),
+                Some(old) => report.added_columns.extend(
+                    columns
+                        .iter()
+                        .filter(|c| !old.contains(c))
+                        .map(|c| format!("{table}.{c}")),
+                ),
+            }
+        }
+        report
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.created_tables.is_empty() && self.added_columns.is_empty()
+    }
+}
+
+#[derive(Clone)]
+pub struct Database {
+    connection: Arc<ConnectionPool>,
+    settings_cache: Arc<SettingsCache>,
+    migrations: Arc<MigrationReport>,
+}
+
+impl Database {
+    pub async fn new(database_path: &str) -> Result<Self> {
+        Self::with_pool_size(database_path, DEFAULT_POOL_SIZE).await
+    }
+
+    /// Open the database with a specific number of pooled connections
+    pub async fn with_pool_size(database_path: &str, pool_size: usize) -> Result<Self> {
+        let pool = ConnectionPool::open(database_path, pool_size)?;
+        let mut db = Database {
+            connection: Arc::new(pool),
+            settings_cache: Arc::new(SettingsCache::new(DEFAULT_SETTINGS_CACHE_TTL)),
+            migrations: Arc::new(MigrationReport::default()),
+        };
+
+        let before = db.schema().await?;
+        db.init_tables().await?;
+        let after = db.schema().await?;
+        db.migrations = Arc::new(MigrationReport::between(&before, &after));
+        info!(
+            "Database initialized at: {database_path} (WAL, {} pooled connections)",
+            db.connection.size()
         );
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_openai_usage_bucket
-             ON openai_usage(cost_bucket, timestamp)",
+        Ok(db)
+    }
+
+    /// Set how long guild/channel settings stay cached (zero disables caching)
+    pub fn with_settings_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.settings_cache = Arc::new(SettingsCache::new(ttl));
+        self
+    }
+
+    /// Drop all cached settings so the next reads go to SQLite
+    pub fn clear_settings_cache(&self) {
+        self.settings_cache.guild_settings.clear();
+        self.settings_cache.feature_flags.clear();
+        self.settings_cache.channel_verbosity.clear();
+        self.settings_cache.channel_columns.clear();
+        self.settings_cache.user_privacy.clear();
+        self.settings_cache.access_rules.clear();
+        self.settings_cache.autoresponses.clear();
+        info!("Settings cache cleared");
+    }
+
+    /// Schema changes applied when this database was opened
+    pub fn migration_report(&self) -> &MigrationReport {
+        &self.migrations
+    }
+
+    /// Every table with its column names
+    async fn schema(&self) -> Result<BTreeMap<String, Vec<String>>> {
+        let conn = self.connection.lock().await?;
+        let mut tables = Vec::new();
+        let mut stmt = conn.prepare(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
         )?;
+        while let Ok(State::Row) = stmt.next() {
+            tables.push(stmt.read::<String, _>(0)?);
+        }
+
+        let mut schema = BTreeMap::new();
+        for table in tables {
+            let mut stmt = conn.prepare(format!("PRAGMA table_info({table})"))?;
+            let mut columns = Vec::new();
+            while let Ok(State::Row) = stmt.next() {
+                columns.push(stmt.read::<String, _>(1)?);
+            }
+            schema.insert(table, columns);
+        }
+        Ok(schema)
+    }
+
+    async fn init_tables(&self) -> Result<()> {
+        let conn = self.connection.lock().await?;
+
         conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_openai_daily_bucket
-             ON openai_usage_daily(cost_bucket, date)",
+            "CREATE TABLE IF NOT EXISTS user_preferences (
+                user_id TEXT PRIMARY KEY,
+                default_persona TEXT DEFAULT 'obi',
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
         )?;
 
-        // DM Interaction Tracking Tables
         conn.execute(
-            "CREATE TABLE IF NOT EXISTS dm_sessions (
+            "CREATE TABLE IF NOT EXISTS usage_stats (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
-                session_id TEXT UNIQUE NOT NULL,
                 user_id TEXT NOT NULL,
-                channel_id TEXT NOT NULL,
-                started_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                last_activity_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                ended_at DATETIME,
-                end_reason TEXT,
-                message_count INTEGER DEFAULT 0,
-                user_message_count INTEGER DEFAULT 0,
-                bot_message_count INTEGER DEFAULT 0,
-                total_user_chars INTEGER DEFAULT 0,
-                total_bot_chars INTEGER DEFAULT 0,
-                avg_response_time_ms INTEGER
+                command TEXT NOT NULL,
+                persona TEXT,
+                timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
             )",
         )?;
 
+        // Guild a command was used in (NULL in DMs and for rows logged before it was recorded)
+        let _ = conn.execute("ALTER TABLE usage_stats ADD COLUMN guild_id TEXT");
+
         conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_dm_sessions_user
-             ON dm_sessions(user_id, started_at DESC)",
+            "CREATE TABLE IF NOT EXISTS conversation_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id TEXT NOT NULL,
+                channel_id TEXT NOT NULL,
+                role TEXT NOT NULL,
+                content TEXT NOT NULL,
+                persona TEXT,
+                timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
         )?;
 
         conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_dm_sessions_active
-             ON dm_sessions(session_id) WHERE ended_at IS NULL",
+            "CREATE INDEX IF NOT EXISTS idx_user_channel
+             ON conversation_history(user_id, channel_id)",
         )?;
 
         conn.execute(
-            "CREATE TABLE IF NOT EXISTS dm_session_metrics (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                session_id TEXT UNIQUE NOT NULL,
-                total_api_calls INTEGER DEFAULT 0,
-                total_tokens INTEGER DEFAULT 0,
-                total_api_cost_usd REAL DEFAULT 0,
-                chat_calls INTEGER DEFAULT 0,
-                whisper_calls INTEGER DEFAULT 0,
-                dalle_calls INTEGER DEFAULT 0,
-                audio_transcriptions INTEGER DEFAULT 0,
-                slash_commands_used INTEGER DEFAULT 0,
-                conversation_depth INTEGER DEFAULT 0,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY(session_id) REFERENCES dm_sessions(session_id)
-            )",
+            "CREATE INDEX IF NOT EXISTS idx_timestamp
+             ON conversation_history(timestamp)",
         )?;
 
+        // Enhanced Interaction Tracking
         conn.execute(
-            "CREATE TABLE IF NOT EXISTS dm_events (
+            "CREATE TABLE IF NOT EXISTS message_metadata (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
-                session_id TEXT NOT NULL,
-                event_type TEXT NOT NULL,
+                message_id TEXT NOT NULL,
                 user_id TEXT NOT NULL,
                 channel_id TEXT NOT NULL,
-                event_data TEXT,
-                timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY(session_id) REFERENCES dm_sessions(session_id)
+                attachment_urls TEXT,
+                embed_data TEXT,
+                reactions TEXT,
+                edited_at DATETIME,
+                deleted_at DATETIME,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
             )",
         )?;
 
         conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_dm_events_session
-             ON dm_events(session_id, timestamp)",
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_dm_events_type
-             ON dm_events(event_type, timestamp)",
+            "CREATE INDEX IF NOT EXISTS idx_message_id
+             ON message_metadata(message_id)",
         )?;
 
-        // Plugin Jobs Table
         conn.execute(
-            "CREATE TABLE IF NOT EXISTS plugin_jobs (
-                id TEXT PRIMARY KEY,
-                plugin_name TEXT NOT NULL,
+            "CREATE TABLE IF NOT EXISTS interaction_sessions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
                 user_id TEXT NOT NULL,
                 guild_id TEXT,
-                channel_id TEXT NOT NULL,
-                thread_id TEXT,
-                status TEXT NOT NULL DEFAULT 'pending',
-                params TEXT,
-                result TEXT,
-                error TEXT,
-                started_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                completed_at DATETIME
+                session_start DATETIME DEFAULT CURRENT_TIMESTAMP,
+                session_end DATETIME,
+                message_count INTEGER DEFAULT 0,
+                last_activity DATETIME DEFAULT CURRENT_TIMESTAMP
             )",
         )?;
 
         conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_plugin_jobs_status
-             ON plugin_jobs(status, started_at)",
+            "CREATE INDEX IF NOT EXISTS idx_session_user
+             ON interaction_sessions(user_id, session_start)",
         )?;
 
+        // Feature-Specific Data
         conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_plugin_jobs_user
-             ON plugin_jobs(user_id, plugin_name, started_at)",
-        )?;
-
-        // Add parent_playlist_id column to plugin_jobs if it doesn't exist
-        // SQLite doesn't support ADD COLUMN IF NOT EXISTS, so we check pragmatically
-        let has_parent_playlist_id: bool = {
-            let mut stmt = conn.prepare("PRAGMA table_info(plugin_jobs)")?;
-            let mut found = false;
-            while let Ok(State::Row) = stmt.next() {
-                let col_name: String = stmt.read(1)?;
-                if col_name == "parent_playlist_id" {
-                    found = true;
-                    break;
-                }
-            }
-            found
-        };
+            "CREATE TABLE IF NOT EXISTS user_bookmarks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id TEXT NOT NULL,
+                channel_id TEXT NOT NULL,
+                message_id TEXT NOT NULL,
+                bookmark_name TEXT,
+                bookmark_note TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+        )?;
 
-        if !has_parent_playlist_id {
-            conn.execute("ALTER TABLE plugin_jobs ADD COLUMN parent_playlist_id TEXT")?;
-            conn.execute(
-                "CREATE INDEX IF NOT EXISTS idx_plugin_jobs_parent
-                 ON plugin_jobs(parent_playlist_id)",
-            )?;
-        }
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_bookmark_user
+             ON user_bookmarks(user_id)",
+        )?;
 
-        // Playlist Jobs Table (for multi-video transcription)
         conn.execute(
-            "CREATE TABLE IF NOT EXISTS playlist_jobs (
-                id TEXT PRIMARY KEY,
+            "CREATE TABLE IF NOT EXISTS reminders (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
                 user_id TEXT NOT NULL,
-                guild_id TEXT,
                 channel_id TEXT NOT NULL,
-                thread_id TEXT,
-                playlist_url TEXT NOT NULL,
-                playlist_id TEXT NOT NULL,
-                playlist_title TEXT,
-                total_videos INTEGER NOT NULL,
-                completed_videos INTEGER DEFAULT 0,
-                failed_videos INTEGER DEFAULT 0,
-                skipped_videos INTEGER DEFAULT 0,
-                status TEXT NOT NULL DEFAULT 'pending',
-                max_videos INTEGER,
-                current_video_job_id TEXT,
-                error TEXT,
-                started_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                completed_at DATETIME,
-                cancelled_at DATETIME,
-                cancelled_by TEXT
+                reminder_text TEXT NOT NULL,
+                remind_at DATETIME NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                completed BOOLEAN DEFAULT 0,
+                completed_at DATETIME
             )",
         )?;
 
         conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_playlist_jobs_status
-             ON playlist_jobs(status, started_at)",
+            "CREATE INDEX IF NOT EXISTS idx_reminder_time
+             ON reminders(remind_at, completed)",
         )?;
 
         conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_playlist_jobs_user
-             ON playlist_jobs(user_id, started_at)",
+            "CREATE TABLE IF NOT EXISTS custom_commands (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                command_name TEXT NOT NULL,
+                response_text TEXT NOT NULL,
+                created_by_user_id TEXT NOT NULL,
+                guild_id TEXT,
+                is_global BOOLEAN DEFAULT 0,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                UNIQUE(command_name, guild_id)
+            )",
         )?;
 
-        // User Cache Table - caches Discord usernames for TUI display
         conn.execute(
-            "CREATE TABLE IF NOT EXISTS user_cache (
-                user_id TEXT PRIMARY KEY,
-                username TEXT NOT NULL,
-                discriminator TEXT,
-                display_name TEXT,
-                is_bot INTEGER DEFAULT 0,
-                last_seen INTEGER,
-                updated_at INTEGER DEFAULT (strftime('%s', 'now'))
+            "CREATE INDEX IF NOT EXISTS idx_custom_command
+             ON custom_commands(command_name, guild_id)",
+        )?;
+
+        // Analytics & Metrics
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS daily_analytics (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                date DATE UNIQUE NOT NULL,
+                total_messages INTEGER DEFAULT 0,
+                unique_users INTEGER DEFAULT 0,
+                total_commands INTEGER DEFAULT 0,
+                total_errors INTEGER DEFAULT 0,
+                persona_usage TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
             )",
         )?;
 
         conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_user_cache_username
-             ON user_cache(username)",
+            "CREATE INDEX IF NOT EXISTS idx_analytics_date
+             ON daily_analytics(date)",
         )?;
 
-        Ok(())
-    }
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS performance_metrics (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                metric_type TEXT NOT NULL,
+                value REAL NOT NULL,
+                unit TEXT,
+                metadata TEXT,
+                timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+        )?;
 
-    pub async fn get_user_persona(&self, user_id: &str) -> Result<String> {
-        let conn = self.connection.lock().await;
-        let mut statement =
-            conn.prepare("SELECT default_persona FROM user_preferences WHERE user_id = ?")?;
-        statement.bind((1, user_id))?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_metrics_type
+             ON performance_metrics(metric_type, timestamp)",
+        )?;
 
-        if let Ok(State::Row) = statement.next() {
-            Ok(statement.read::<String, _>("default_persona")?)
-        } else {
-            // Check for PERSONA environment variable, fallback to 'obi'
-            Ok(std::env::var("PERSONA").unwrap_or_else(|_| "obi".to_string()))
-        }
-    }
+        // Downsampled system metrics (see downsample_system_metrics): raw
+        // performance_metrics rows are the 1-minute tier
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS system_metrics_rollup (
+                tier TEXT NOT NULL,
+                bucket TEXT NOT NULL,
+                metric_type TEXT NOT NULL,
+                avg_value REAL NOT NULL,
+                min_value REAL NOT NULL,
+                max_value REAL NOT NULL,
+                samples INTEGER NOT NULL,
+                UNIQUE(tier, metric_type, bucket)
+            )",
+        )?;
 
-    /// Get user persona with guild default fallback
-    /// Cascade: user preference -> guild default -> env var -> "obi"
-    pub async fn get_user_persona_with_guild(
-        &self,
-        user_id: &str,
-        guild_id: Option<&str>,
-    ) -> Result<String> {
-        let conn = self.connection.lock().await;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS error_logs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                error_type TEXT NOT NULL,
+                error_message TEXT NOT NULL,
+                stack_trace TEXT,
+                user_id TEXT,
+                channel_id TEXT,
+                command TEXT,
+                metadata TEXT,
+                timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+        )?;
 
-        // First check user preference
-        let mut statement =
-            conn.prepare("SELECT default_persona FROM user_preferences WHERE user_id = ?")?;
-        statement.bind((1, user_id))?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_error_type
+             ON error_logs(error_type, timestamp)",
+        )?;
 
-        if let Ok(State::Row) = statement.next() {
-            return Ok(statement.read::<String, _>("default_persona")?);
-        }
+        // Extended Configuration
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS feature_flags (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                feature_name TEXT NOT NULL,
+                enabled BOOLEAN DEFAULT 0,
+                user_id TEXT,
+                guild_id TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                UNIQUE(feature_name, user_id, guild_id)
+            )",
+        )?;
 
-        // Check guild default if guild_id is provided
-        if let Some(gid) = guild_id {
-            drop(statement);
-            let mut guild_stmt = conn.prepare(
-                "SELECT setting_value FROM guild_settings WHERE guild_id = ? AND setting_key = 'default_persona'"
-            )?;
-            guild_stmt.bind((1, gid))?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_feature_flag
+             ON feature_flags(feature_name, user_id, guild_id)",
+        )?;
 
-            if let Ok(State::Row) = guild_stmt.next() {
-                return Ok(guild_stmt.read::<String, _>(0)?);
-            }
-        }
+        // Feature versions tracking for audit trail
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS feature_versions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                feature_name TEXT NOT NULL,
+                version TEXT NOT NULL,
+                guild_id TEXT,
+                toggled_by TEXT,
+                enabled BOOLEAN NOT NULL,
+                changed_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+        )?;
 
-        // Fall back to PERSONA environment variable, then 'obi'
-        Ok(std::env::var("PERSONA").unwrap_or_else(|_| "obi".to_string()))
-    }
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_feature_versions
+             ON feature_versions(feature_name, guild_id, changed_at)",
+        )?;
 
-    /// Get the channel-level persona override if set
-    pub async fn get_channel_persona(
-        &self,
-        guild_id: &str,
-        channel_id: &str,
-    ) -> Result<Option<String>> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "SELECT default_persona FROM channel_settings WHERE guild_id = ? AND channel_id = ?",
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS guild_settings (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                guild_id TEXT NOT NULL,
+                setting_key TEXT NOT NULL,
+                setting_value TEXT,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                UNIQUE(guild_id, setting_key)
+            )",
         )?;
-        statement.bind((1, guild_id))?;
-        statement.bind((2, channel_id))?;
 
-        if let Ok(State::Row) = statement.next() {
-            let persona: Option<String> = statement.read::<Option<String>, _>(0)?;
-            Ok(persona)
-        } else {
-            Ok(None)
-        }
-    }
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_guild_setting
+             ON guild_settings(guild_id, setting_key)",
+        )?;
 
-    /// Set or clear the channel-level persona override
-    /// Pass None to clear the override
-    pub async fn set_channel_persona(
-        &self,
-        guild_id: &str,
-        channel_id: &str,
-        persona: Option<&str>,
-    ) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "INSERT INTO channel_settings (guild_id, channel_id, default_persona, updated_at)
-             VALUES (?, ?, ?, CURRENT_TIMESTAMP)
-             ON CONFLICT(guild_id, channel_id) DO UPDATE SET
-             default_persona = excluded.default_persona,
-             updated_at = CURRENT_TIMESTAMP",
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS extended_user_preferences (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id TEXT NOT NULL,
+                preference_key TEXT NOT NULL,
+                preference_value TEXT,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                UNIQUE(user_id, preference_key)
+            )",
         )?;
-        statement.bind((1, guild_id))?;
-        statement.bind((2, channel_id))?;
-        statement.bind((3, persona))?;
-        statement.next()?;
-        match persona {
-            Some(p) => info!("Set persona for channel {channel_id} to {p}"),
-            None => info!("Cleared persona override for channel {channel_id}"),
-        }
-        Ok(())
-    }
 
-    /// Get persona with full cascade: channel override -> user preference -> guild default -> env -> "obi"
-    pub async fn get_persona_with_channel(
-        &self,
-        user_id: &str,
-        guild_id: &str,
-        channel_id: &str,
-    ) -> Result<String> {
-        let conn = self.connection.lock().await;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_user_pref
+             ON extended_user_preferences(user_id, preference_key)",
+        )?;
 
-        // First check channel override (highest priority)
-        let mut channel_stmt = conn.prepare(
-            "SELECT default_persona FROM channel_settings WHERE guild_id = ? AND channel_id = ?",
+        // Conflict Detection & Mediation
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS conflict_detection (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                channel_id TEXT NOT NULL,
+                guild_id TEXT,
+                participants TEXT NOT NULL,
+                detection_type TEXT NOT NULL,
+                confidence_score REAL,
+                last_message_id TEXT,
+                mediation_triggered BOOLEAN DEFAULT 0,
+                mediation_message_id TEXT,
+                first_detected DATETIME DEFAULT CURRENT_TIMESTAMP,
+                last_detected DATETIME DEFAULT CURRENT_TIMESTAMP,
+                resolved_at DATETIME
+            )",
         )?;
-        channel_stmt.bind((1, guild_id))?;
-        channel_stmt.bind((2, channel_id))?;
 
-        if let Ok(State::Row) = channel_stmt.next() {
-            if let Ok(Some(persona)) = channel_stmt.read::<Option<String>, _>(0) {
-                return Ok(persona);
-            }
-        }
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_conflict_channel
+             ON conflict_detection(channel_id, guild_id)",
+        )?;
 
-        // Check user preference
-        drop(channel_stmt);
-        let mut user_stmt =
-            conn.prepare("SELECT default_persona FROM user_preferences WHERE user_id = ?")?;
-        user_stmt.bind((1, user_id))?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_conflict_timestamp
+             ON conflict_detection(first_detected)",
+        )?;
 
-        if let Ok(State::Row) = user_stmt.next() {
-            return Ok(user_stmt.read::<String, _>("default_persona")?);
-        }
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS mediation_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                conflict_id INTEGER NOT NULL,
+                channel_id TEXT NOT NULL,
+                mediation_message TEXT,
+                effectiveness_rating INTEGER,
+                follow_up_messages INTEGER DEFAULT 0,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY(conflict_id) REFERENCES conflict_detection(id)
+            )",
+        )?;
 
-        // Check guild default
-        drop(user_stmt);
-        let mut guild_stmt = conn.prepare(
-            "SELECT setting_value FROM guild_settings WHERE guild_id = ? AND setting_key = 'default_persona'"
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_mediation_conflict
+             ON mediation_history(conflict_id)",
         )?;
-        guild_stmt.bind((1, guild_id))?;
 
-        if let Ok(State::Row) = guild_stmt.next() {
-            return Ok(guild_stmt.read::<String, _>(0)?);
-        }
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS user_interaction_patterns (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id_a TEXT NOT NULL,
+                user_id_b TEXT NOT NULL,
+                channel_id TEXT,
+                guild_id TEXT,
+                interaction_count INTEGER DEFAULT 0,
+                last_interaction DATETIME,
+                conflict_incidents INTEGER DEFAULT 0,
+                avg_response_time_ms INTEGER,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                UNIQUE(user_id_a, user_id_b, channel_id)
+            )",
+        )?;
 
-        // Fall back to PERSONA environment variable, then 'obi'
-        Ok(std::env::var("PERSONA").unwrap_or_else(|_| "obi".to_string()))
-    }
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_interaction_users
+             ON user_interaction_patterns(user_id_a, user_id_b)",
+        )?;
 
-    pub async fn set_user_persona(&self, user_id: &str, persona: &str) -> Result<()> {
-        let conn = self.connection.lock().await;
+        // Channel Settings (for per-channel verbosity and other settings)
         conn.execute(
-            "INSERT OR REPLACE INTO user_preferences (user_id, default_persona, updated_at) 
-             VALUES (?, ?, CURRENT_TIMESTAMP)",
+            "CREATE TABLE IF NOT EXISTS channel_settings (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                guild_id TEXT NOT NULL,
+                channel_id TEXT NOT NULL,
+                verbosity TEXT DEFAULT 'concise',
+                conflict_enabled BOOLEAN DEFAULT 1,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                UNIQUE(guild_id, channel_id)
+            )",
         )?;
 
-        let mut statement = conn.prepare(
-            "INSERT OR REPLACE INTO user_preferences (user_id, default_persona, updated_at) 
-             VALUES (?, ?, CURRENT_TIMESTAMP)",
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_channel_settings_guild
+             ON channel_settings(guild_id)",
         )?;
-        statement.bind((1, user_id))?;
-        statement.bind((2, persona))?;
-        statement.next()?;
 
-        info!("Updated persona for user {user_id} to {persona}");
-        Ok(())
-    }
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_channel_settings_channel
+             ON channel_settings(channel_id)",
+        )?;
 
-    // User Cache Methods - for TUI username display
+        // Add default_persona column to channel_settings if it doesn't exist
+        // SQLite doesn't support ADD COLUMN IF NOT EXISTS, so we check pragmatically
+        let has_channel_persona: bool = {
+            let mut stmt = conn.prepare("PRAGMA table_info(channel_settings)")?;
+            let mut found = false;
+            while let Ok(State::Row) = stmt.next() {
+                let col_name: String = stmt.read(1)?;
+                if col_name == "default_persona" {
+                    found = true;
+                    break;
+                }
+            }
+            found
+        };
 
-    /// Cache a Discord user's information for TUI display
-    pub async fn cache_user(
-        &self,
-        user_id: u64,
-        username: &str,
-        discriminator: u16,
-        is_bot: bool,
-    ) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "INSERT INTO user_cache (user_id, username, discriminator, is_bot, last_seen, updated_at)
-             VALUES (?, ?, ?, ?, strftime('%s', 'now'), strftime('%s', 'now'))
-             ON CONFLICT(user_id) DO UPDATE SET
-             username = excluded.username,
-             discriminator = excluded.discriminator,
-             is_bot = excluded.is_bot,
-             last_seen = strftime('%s', 'now'),
-             updated_at = strftime('%s', 'now')"
-        )?;
-        statement.bind((1, user_id.to_string().as_str()))?;
-        statement.bind((2, username))?;
-        statement.bind((3, discriminator.to_string().as_str()))?;
-        statement.bind((4, if is_bot { 1i64 } else { 0i64 }))?;
-        statement.next()?;
-        Ok(())
-    }
+        if !has_channel_persona {
+            conn.execute("ALTER TABLE channel_settings ADD COLUMN default_persona TEXT")?;
+        }
 
-    /// Get a cached username by user ID
-    pub async fn get_cached_username(&self, user_id: &str) -> Result<Option<String>> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare("SELECT username FROM user_cache WHERE user_id = ?")?;
-        statement.bind((1, user_id))?;
+        // Add max_paragraphs column to channel_settings if it doesn't exist
+        let has_max_paragraphs: bool = {
+            let mut stmt = conn.prepare("PRAGMA table_info(channel_settings)")?;
+            let mut found = false;
+            while let Ok(State::Row) = stmt.next() {
+                let col_name: String = stmt.read(1)?;
+                if col_name == "max_paragraphs" {
+                    found = true;
+                    break;
+                }
+            }
+            found
+        };
 
-        if let Ok(State::Row) = statement.next() {
-            Ok(Some(statement.read::<String, _>(0)?))
-        } else {
-            Ok(None)
+        if !has_max_paragraphs {
+            conn.execute("ALTER TABLE channel_settings ADD COLUMN max_paragraphs INTEGER DEFAULT 0")?;
         }
-    }
 
-    pub async fn log_usage(
-        &self,
-        user_id: &str,
-        command: &str,
-        persona: Option<&str>,
-    ) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement =
-            conn.prepare("INSERT INTO usage_stats (user_id, command, persona) VALUES (?, ?, ?)")?;
-        statement.bind((1, user_id))?;
-        statement.bind((2, command))?;
-        statement.bind((3, persona.unwrap_or("")))?;
-        statement.next()?;
-        Ok(())
-    }
+        // Link preview mode per channel (reply, thread or disabled)
+        let _ = conn.execute(
+            "ALTER TABLE channel_settings ADD COLUMN link_previews TEXT DEFAULT 'disabled'",
+        );
 
-    pub async fn store_message(
-        &self,
-        user_id: &str,
-        channel_id: &str,
-        role: &str,
-        content: &str,
-        persona: Option<&str>,
-    ) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "INSERT INTO conversation_history (user_id, channel_id, role, content, persona) VALUES (?, ?, ?, ?, ?)"
+        // Hourly sentiment tracking per channel (opt-in)
+        let _ = conn.execute(
+            "ALTER TABLE channel_settings ADD COLUMN sentiment_tracking BOOLEAN DEFAULT 0",
+        );
+
+        // Designated language per channel for language nudges (NULL = any)
+        let _ = conn.execute("ALTER TABLE channel_settings ADD COLUMN language TEXT");
+
+        // Clock times in messages converted to timestamps per channel (opt-in)
+        let _ = conn
+            .execute("ALTER TABLE channel_settings ADD COLUMN time_detection BOOLEAN DEFAULT 0");
+
+        // Roleplay scene injected into persona prompts per channel (NULL = none)
+        let _ = conn.execute("ALTER TABLE channel_settings ADD COLUMN scene TEXT");
+
+        // Guild scoping for conversation history (used by /search)
+        if conn
+            .execute("ALTER TABLE conversation_history ADD COLUMN guild_id TEXT")
+            .is_ok()
+        {
+            // Backfill existing rows for channels whose guild is known
+            conn.execute(
+                "UPDATE conversation_history SET guild_id = (
+                    SELECT cs.guild_id FROM channel_settings cs
+                    WHERE cs.channel_id = conversation_history.channel_id
+                 )
+                 WHERE guild_id IS NULL",
+            )?;
+        }
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_conversation_guild
+             ON conversation_history(guild_id, timestamp)",
         )?;
-        statement.bind((1, user_id))?;
-        statement.bind((2, channel_id))?;
-        statement.bind((3, role))?;
-        statement.bind((4, content))?;
-        statement.bind((5, persona.unwrap_or("")))?;
-        statement.next()?;
-        Ok(())
-    }
 
-    pub async fn get_conversation_history(
-        &self,
-        user_id: &str,
-        channel_id: &str,
-        limit: i64,
-    ) -> Result<Vec<(String, String)>> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "SELECT role, content FROM conversation_history
-             WHERE user_id = ? AND channel_id = ?
-             ORDER BY timestamp DESC
-             LIMIT ?",
-        )?;
-        statement.bind((1, user_id))?;
-        statement.bind((2, channel_id))?;
-        statement.bind((3, limit))?;
+        // Full-text index over conversation history (FTS5, external content)
+        let has_fts = {
+            let mut stmt = conn.prepare(
+                "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'conversation_fts'",
+            )?;
+            matches!(stmt.next(), Ok(State::Row))
+        };
 
-        let mut history = Vec::new();
-        while let Ok(State::Row) = statement.next() {
-            let role = statement.read::<String, _>("role")?;
-            let content = statement.read::<String, _>("content")?;
-            history.push((role, content));
+        match conn.execute(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS conversation_fts USING fts5(
+                content,
+                content='conversation_history',
+                content_rowid='id'
+            )",
+        ) {
+            Ok(()) => {
+                conn.execute(
+                    "CREATE TRIGGER IF NOT EXISTS conversation_fts_insert
+                     AFTER INSERT ON conversation_history BEGIN
+                        INSERT INTO conversation_fts(rowid, content) VALUES (new.id, new.content);
+                     END",
+                )?;
+                conn.execute(
+                    "CREATE TRIGGER IF NOT EXISTS conversation_fts_delete
+                     AFTER DELETE ON conversation_history BEGIN
+                        INSERT INTO conversation_fts(conversation_fts, rowid, content)
+                        VALUES ('delete', old.id, old.content);
+                     END",
+                )?;
+                conn.execute(
+                    "CREATE TRIGGER IF NOT EXISTS conversation_fts_update
+                     AFTER UPDATE OF content ON conversation_history BEGIN
+                        INSERT INTO conversation_fts(conversation_fts, rowid, content)
+                        VALUES ('delete', old.id, old.content);
+                        INSERT INTO conversation_fts(rowid, content) VALUES (new.id, new.content);
+                     END",
+                )?;
+
+                if !has_fts {
+                    info!("Building full-text index for conversation history");
+                    conn.execute(
+                        "INSERT INTO conversation_fts(conversation_fts) VALUES ('rebuild')",
+                    )?;
+                }
+            }
+            Err(e) => warn!("FTS5 unavailable, message search disabled: {e}"),
         }
 
-        // Reverse to get chronological order (oldest first)
-        history.reverse();
-        Ok(history)
-    }
+        // Bot Settings (for global bot configuration, not per-guild)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS bot_settings (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                setting_key TEXT NOT NULL UNIQUE,
+                setting_value TEXT,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+        )?;
 
-    pub async fn clear_conversation_history(&self, user_id: &str, channel_id: &str) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement =
-            conn.prepare("DELETE FROM conversation_history WHERE user_id = ? AND channel_id = ?")?;
-        statement.bind((1, user_id))?;
-        statement.bind((2, channel_id))?;
-        statement.next()?;
-        info!("Cleared conversation history for user {user_id} in channel {channel_id}");
-        Ok(())
-    }
+        // OpenAI Usage Tracking Tables
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS openai_usage (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                request_id TEXT,
+                user_id TEXT NOT NULL,
+                guild_id TEXT,
+                channel_id TEXT,
+                service_type TEXT NOT NULL,
+                model TEXT NOT NULL,
+                input_tokens INTEGER DEFAULT 0,
+                output_tokens INTEGER DEFAULT 0,
+                total_tokens INTEGER DEFAULT 0,
+                audio_duration_seconds REAL DEFAULT 0,
+                image_count INTEGER DEFAULT 0,
+                image_size TEXT,
+                estimated_cost_usd REAL NOT NULL,
+                timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+        )?;
 
-    pub async fn cleanup_old_messages(&self, days: i64) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "DELETE FROM conversation_history WHERE timestamp < datetime('now', ? || ' days')",
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_openai_usage_user_ts
+             ON openai_usage(user_id, timestamp)",
         )?;
-        statement.bind((1, format!("-{days}").as_str()))?;
-        statement.next()?;
-        info!("Cleaned up conversation history older than {days} days");
-        Ok(())
-    }
 
-    // Message Metadata Methods
-    pub async fn store_message_metadata(
-        &self,
-        message_id: &str,
-        user_id: &str,
-        channel_id: &str,
-        attachment_urls: Option<&str>,
-        embed_data: Option<&str>,
-        reactions: Option<&str>,
-    ) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "INSERT INTO message_metadata (message_id, user_id, channel_id, attachment_urls, embed_data, reactions)
-             VALUES (?, ?, ?, ?, ?, ?)"
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_openai_usage_guild_ts
+             ON openai_usage(guild_id, timestamp)",
         )?;
-        statement.bind((1, message_id))?;
-        statement.bind((2, user_id))?;
-        statement.bind((3, channel_id))?;
-        statement.bind((4, attachment_urls.unwrap_or("")))?;
-        statement.bind((5, embed_data.unwrap_or("")))?;
-        statement.bind((6, reactions.unwrap_or("")))?;
-        statement.next()?;
-        Ok(())
-    }
 
-    pub async fn update_message_metadata_reactions(
-        &self,
-        message_id: &str,
-        reactions: &str,
-    ) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement =
-            conn.prepare("UPDATE message_metadata SET reactions = ? WHERE message_id = ?")?;
-        statement.bind((1, reactions))?;
-        statement.bind((2, message_id))?;
-        statement.next()?;
-        Ok(())
-    }
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_openai_usage_timestamp
+             ON openai_usage(timestamp)",
+        )?;
 
-    pub async fn mark_message_deleted(&self, message_id: &str) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "UPDATE message_metadata SET deleted_at = CURRENT_TIMESTAMP WHERE message_id = ?",
+        // Daily aggregates for fast queries (90-day retention)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS openai_usage_daily (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                date DATE NOT NULL,
+                guild_id TEXT,
+                user_id TEXT,
+                service_type TEXT NOT NULL,
+                request_count INTEGER DEFAULT 0,
+                total_tokens INTEGER DEFAULT 0,
+                total_audio_seconds REAL DEFAULT 0,
+                total_images INTEGER DEFAULT 0,
+                total_cost_usd REAL DEFAULT 0,
+                UNIQUE(date, guild_id, user_id, service_type)
+            )",
         )?;
-        statement.bind((1, message_id))?;
-        statement.next()?;
-        Ok(())
-    }
 
-    pub async fn mark_message_edited(&self, message_id: &str) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "UPDATE message_metadata SET edited_at = CURRENT_TIMESTAMP WHERE message_id = ?",
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_openai_daily_guild_date
+             ON openai_usage_daily(guild_id, date)",
         )?;
-        statement.bind((1, message_id))?;
-        statement.next()?;
-        Ok(())
-    }
 
-    // Interaction Session Methods
-    pub async fn start_session(&self, user_id: &str, guild_id: Option<&str>) -> Result<i64> {
-        let conn = self.connection.lock().await;
-        let mut statement =
-            conn.prepare("INSERT INTO interaction_sessions (user_id, guild_id) VALUES (?, ?)")?;
-        statement.bind((1, user_id))?;
-        statement.bind((2, guild_id.unwrap_or("")))?;
-        statement.next()?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_openai_daily_user_date
+             ON openai_usage_daily(user_id, date)",
+        )?;
 
-        // Get the last inserted row id
-        let mut stmt = conn.prepare("SELECT last_insert_rowid()")?;
-        stmt.next()?;
-        let session_id = stmt.read::<i64, _>(0)?;
-        Ok(session_id)
-    }
+        // Migration: add cost_bucket column for tracking usage by feature
+        // Uses ALTER TABLE which silently fails if column already exists
+        let _ = conn.execute(
+            "ALTER TABLE openai_usage ADD COLUMN cost_bucket TEXT NOT NULL DEFAULT 'unknown'",
+        );
+        let _ = conn.execute(
+            "ALTER TABLE openai_usage_daily ADD COLUMN cost_bucket TEXT NOT NULL DEFAULT 'unknown'",
+        );
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_openai_usage_bucket
+             ON openai_usage(cost_bucket, timestamp)",
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_openai_daily_bucket
+             ON openai_usage_daily(cost_bucket, date)",
+        )?;
 
-    pub async fn update_session_activity(&self, session_id: i64) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "UPDATE interaction_sessions
-             SET message_count = message_count + 1, last_activity = CURRENT_TIMESTAMP
-             WHERE id = ?",
+        // Migration: add prompt_hash column so image spend can be grouped by prompt
+        let _ = conn.execute("ALTER TABLE openai_usage ADD COLUMN prompt_hash TEXT");
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_openai_usage_service_guild
+             ON openai_usage(service_type, guild_id, timestamp)",
         )?;
-        statement.bind((1, session_id))?;
-        statement.next()?;
-        Ok(())
-    }
 
-    pub async fn end_session(&self, session_id: i64) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "UPDATE interaction_sessions SET session_end = CURRENT_TIMESTAMP WHERE id = ?",
+        // Analytics rollups, maintained by the metrics loop (see rollup_analytics)
+        // so long-range reports never scan raw request rows
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS openai_usage_rollup_hourly (
+                hour TEXT NOT NULL,
+                guild_id TEXT NOT NULL DEFAULT '',
+                channel_id TEXT NOT NULL DEFAULT '',
+                user_id TEXT NOT NULL,
+                service_type TEXT NOT NULL,
+                cost_bucket TEXT NOT NULL DEFAULT 'unknown',
+                prompt_hash TEXT NOT NULL DEFAULT '',
+                request_count INTEGER DEFAULT 0,
+                total_tokens INTEGER DEFAULT 0,
+                total_audio_seconds REAL DEFAULT 0,
+                total_images INTEGER DEFAULT 0,
+                total_cost_usd REAL DEFAULT 0,
+                UNIQUE(hour, guild_id, channel_id, user_id, service_type, cost_bucket, prompt_hash)
+            )",
         )?;
-        statement.bind((1, session_id))?;
-        statement.next()?;
-        Ok(())
-    }
 
-    // User Bookmark Methods
-    pub async fn add_bookmark(
-        &self,
-        user_id: &str,
-        channel_id: &str,
-        message_id: &str,
-        bookmark_name: Option<&str>,
-        bookmark_note: Option<&str>,
-    ) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "INSERT INTO user_bookmarks (user_id, channel_id, message_id, bookmark_name, bookmark_note)
-             VALUES (?, ?, ?, ?, ?)"
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS openai_usage_rollup_daily (
+                date TEXT NOT NULL,
+                guild_id TEXT NOT NULL DEFAULT '',
+                channel_id TEXT NOT NULL DEFAULT '',
+                user_id TEXT NOT NULL,
+                service_type TEXT NOT NULL,
+                cost_bucket TEXT NOT NULL DEFAULT 'unknown',
+                prompt_hash TEXT NOT NULL DEFAULT '',
+                request_count INTEGER DEFAULT 0,
+                total_tokens INTEGER DEFAULT 0,
+                total_audio_seconds REAL DEFAULT 0,
+                total_images INTEGER DEFAULT 0,
+                total_cost_usd REAL DEFAULT 0,
+                UNIQUE(date, guild_id, channel_id, user_id, service_type, cost_bucket, prompt_hash)
+            )",
         )?;
-        statement.bind((1, user_id))?;
-        statement.bind((2, channel_id))?;
-        statement.bind((3, message_id))?;
-        statement.bind((4, bookmark_name.unwrap_or("")))?;
-        statement.bind((5, bookmark_note.unwrap_or("")))?;
-        statement.next()?;
-        info!("Added bookmark for user {user_id}");
-        Ok(())
-    }
 
-    pub async fn get_user_bookmarks(
-        &self,
-        user_id: &str,
-    ) -> Result<Vec<(String, String, String, String)>> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "SELECT message_id, channel_id, bookmark_name, bookmark_note
-             FROM user_bookmarks WHERE user_id = ?
-             ORDER BY created_at DESC",
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_usage_rollup_daily_service_guild
+             ON openai_usage_rollup_daily(service_type, guild_id, date)",
         )?;
-        statement.bind((1, user_id))?;
 
-        let mut bookmarks = Vec::new();
-        while let Ok(State::Row) = statement.next() {
-            let message_id = statement.read::<String, _>(0)?;
-            let channel_id = statement.read::<String, _>(1)?;
-            let bookmark_name = statement.read::<String, _>(2)?;
-            let bookmark_note = statement.read::<String, _>(3)?;
-            bookmarks.push((message_id, channel_id, bookmark_name, bookmark_note));
+        // Daily command counts per guild ('' for DMs). Tables from before the
+        // guild was part of the key are rebuilt, keeping their days under ''.
+        let command_usage_columns: Vec<String> = {
+            let mut stmt = conn.prepare("PRAGMA table_info(command_usage_daily)")?;
+            let mut columns = Vec::new();
+            while let Ok(State::Row) = stmt.next() {
+                columns.push(stmt.read::<String, _>(1)?);
+            }
+            columns
+        };
+        let rebuild_command_usage = !command_usage_columns.is_empty()
+            && !command_usage_columns.iter().any(|c| c == "guild_id");
+        if rebuild_command_usage {
+            conn.execute("ALTER TABLE command_usage_daily RENAME TO command_usage_daily_old")?;
         }
-        Ok(bookmarks)
-    }
-
-    pub async fn delete_bookmark(&self, user_id: &str, message_id: &str) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement =
-            conn.prepare("DELETE FROM user_bookmarks WHERE user_id = ? AND message_id = ?")?;
-        statement.bind((1, user_id))?;
-        statement.bind((2, message_id))?;
-        statement.next()?;
-        Ok(())
-    }
-
-    // Reminder Methods
-    pub async fn add_reminder(
-        &self,
-        user_id: &str,
-        channel_id: &str,
-        reminder_text: &str,
-        remind_at: &str,
-    ) -> Result<i64> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "INSERT INTO reminders (user_id, channel_id, reminder_text, remind_at)
-             VALUES (?, ?, ?, ?)",
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS command_usage_daily (
+                date TEXT NOT NULL,
+                guild_id TEXT NOT NULL DEFAULT '',
+                user_id TEXT NOT NULL,
+                command TEXT NOT NULL,
+                persona TEXT NOT NULL DEFAULT '',
+                invocations INTEGER DEFAULT 0,
+                UNIQUE(date, guild_id, user_id, command, persona)
+            )",
         )?;
-        statement.bind((1, user_id))?;
-        statement.bind((2, channel_id))?;
-        statement.bind((3, reminder_text))?;
-        statement.bind((4, remind_at))?;
-        statement.next()?;
+        if rebuild_command_usage {
+            conn.execute(
+                "INSERT INTO command_usage_daily (date, user_id, command, persona, invocations)
+                 SELECT date, user_id, command, persona, invocations FROM command_usage_daily_old",
+            )?;
+            conn.execute("DROP TABLE command_usage_daily_old")?;
+        }
 
-        let mut stmt = conn.prepare("SELECT last_insert_rowid()")?;
-        stmt.next()?;
-        let reminder_id = stmt.read::<i64, _>(0)?;
-        info!("Added reminder {reminder_id} for user {user_id}");
-        Ok(reminder_id)
-    }
+        // Daily rollups plus the raw rows the metrics loop hasn't folded in yet,
+        // so reports stay exact between rollup runs
+        conn.execute(
+            "CREATE VIEW IF NOT EXISTS openai_usage_rollup_live AS
+             SELECT date, guild_id, channel_id, user_id, service_type, cost_bucket, prompt_hash,
+                    request_count, total_tokens, total_audio_seconds, total_images, total_cost_usd
+             FROM openai_usage_rollup_daily
+             UNION ALL
+             SELECT date(timestamp), COALESCE(guild_id, ''), COALESCE(channel_id, ''), user_id,
+                    service_type, cost_bucket, COALESCE(prompt_hash, ''),
+                    1, total_tokens, audio_duration_seconds, image_count, estimated_cost_usd
+             FROM openai_usage
+             WHERE timestamp >= COALESCE(
+                 (SELECT setting_value FROM bot_settings WHERE setting_key = 'analytics_rollup_watermark'),
+                 ''
+             )",
+        )?;
 
-    pub async fn get_pending_reminders(&self) -> Result<Vec<(i64, String, String, String)>> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "SELECT id, user_id, channel_id, reminder_text
-             FROM reminders
-             WHERE completed = 0 AND remind_at <= datetime('now')
-             ORDER BY remind_at ASC",
+        // DM Interaction Tracking Tables
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS dm_sessions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_id TEXT UNIQUE NOT NULL,
+                user_id TEXT NOT NULL,
+                channel_id TEXT NOT NULL,
+                started_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                last_activity_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                ended_at DATETIME,
+                end_reason TEXT,
+                message_count INTEGER DEFAULT 0,
+                user_message_count INTEGER DEFAULT 0,
+                bot_message_count INTEGER DEFAULT 0,
+                total_user_chars INTEGER DEFAULT 0,
+                total_bot_chars INTEGER DEFAULT 0,
+                avg_response_time_ms INTEGER
+            )",
         )?;
 
-        let mut reminders = Vec::new();
-        while let Ok(State::Row) = statement.next() {
-            let id = statement.read::<i64, _>(0)?;
-            let user_id = statement.read::<String, _>(1)?;
-            let channel_id = statement.read::<String, _>(2)?;
-            let reminder_text = statement.read::<String, _>(3)?;
-            reminders.push((id, user_id, channel_id, reminder_text));
-        }
-        Ok(reminders)
-    }
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_dm_sessions_user
+             ON dm_sessions(user_id, started_at DESC)",
+        )?;
 
-    pub async fn complete_reminder(&self, reminder_id: i64) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "UPDATE reminders SET completed = 1, completed_at = CURRENT_TIMESTAMP WHERE id = ?",
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_dm_sessions_active
+             ON dm_sessions(session_id) WHERE ended_at IS NULL",
         )?;
-        statement.bind((1, reminder_id))?;
-        statement.next()?;
-        Ok(())
-    }
 
-    pub async fn get_user_reminders(
-        &self,
-        user_id: &str,
-    ) -> Result<Vec<(i64, String, String, String)>> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "SELECT id, channel_id, reminder_text, remind_at
-             FROM reminders
-             WHERE user_id = ? AND completed = 0
-             ORDER BY remind_at ASC",
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS dm_session_metrics (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_id TEXT UNIQUE NOT NULL,
+                total_api_calls INTEGER DEFAULT 0,
+                total_tokens INTEGER DEFAULT 0,
+                total_api_cost_usd REAL DEFAULT 0,
+                chat_calls INTEGER DEFAULT 0,
+                whisper_calls INTEGER DEFAULT 0,
+                dalle_calls INTEGER DEFAULT 0,
+                audio_transcriptions INTEGER DEFAULT 0,
+                slash_commands_used INTEGER DEFAULT 0,
+                conversation_depth INTEGER DEFAULT 0,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY(session_id) REFERENCES dm_sessions(session_id)
+            )",
         )?;
-        statement.bind((1, user_id))?;
 
-        let mut reminders = Vec::new();
-        while let Ok(State::Row) = statement.next() {
-            let id = statement.read::<i64, _>(0)?;
-            let channel_id = statement.read::<String, _>(1)?;
-            let reminder_text = statement.read::<String, _>(2)?;
-            let remind_at = statement.read::<String, _>(3)?;
-            reminders.push((id, channel_id, reminder_text, remind_at));
-        }
-        Ok(reminders)
-    }
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS dm_events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_id TEXT NOT NULL,
+                event_type TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                channel_id TEXT NOT NULL,
+                event_data TEXT,
+                timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY(session_id) REFERENCES dm_sessions(session_id)
+            )",
+        )?;
 
-    pub async fn delete_reminder(&self, reminder_id: i64, user_id: &str) -> Result<bool> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare("DELETE FROM reminders WHERE id = ? AND user_id = ?")?;
-        statement.bind((1, reminder_id))?;
-        statement.bind((2, user_id))?;
-        statement.next()?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_dm_events_session
+             ON dm_events(session_id, timestamp)",
+        )?;
 
-        // Check if a row was actually deleted
-        let mut check = conn.prepare("SELECT changes()")?;
-        check.next()?;
-        let changes = check.read::<i64, _>(0)?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_dm_events_type
+             ON dm_events(event_type, timestamp)",
+        )?;
 
-        if changes > 0 {
-            info!("Deleted reminder {reminder_id} for user {user_id}");
-            Ok(true)
-        } else {
-            Ok(false)
-        }
-    }
+        // Plugin Jobs Table
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS plugin_jobs (
+                id TEXT PRIMARY KEY,
+                plugin_name TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                guild_id TEXT,
+                channel_id TEXT NOT NULL,
+                thread_id TEXT,
+                status TEXT NOT NULL DEFAULT 'pending',
+                params TEXT,
+                result TEXT,
+                error TEXT,
+                started_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                completed_at DATETIME
+            )",
+        )?;
 
-    // Custom Command Methods
-    pub async fn add_custom_command(
-        &self,
-        command_name: &str,
-        response_text: &str,
-        created_by_user_id: &str,
-        guild_id: Option<&str>,
-    ) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let is_global = guild_id.is_none();
-        let mut statement = conn.prepare(
-            "INSERT OR REPLACE INTO custom_commands (command_name, response_text, created_by_user_id, guild_id, is_global, updated_at)
-             VALUES (?, ?, ?, ?, ?, CURRENT_TIMESTAMP)"
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_plugin_jobs_status
+             ON plugin_jobs(status, started_at)",
         )?;
-        statement.bind((1, command_name))?;
-        statement.bind((2, response_text))?;
-        statement.bind((3, created_by_user_id))?;
-        statement.bind((4, guild_id.unwrap_or("")))?;
-        statement.bind((5, if is_global { 1i64 } else { 0i64 }))?;
-        statement.next()?;
-        info!("Added custom command: {command_name}");
-        Ok(())
-    }
 
-    pub async fn get_custom_command(
-        &self,
-        command_name: &str,
-        guild_id: Option<&str>,
-    ) -> Result<Option<String>> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "SELECT response_text FROM custom_commands
-             WHERE command_name = ? AND (guild_id = ? OR is_global = 1)
-             ORDER BY is_global ASC
-             LIMIT 1",
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_plugin_jobs_user
+             ON plugin_jobs(user_id, plugin_name, started_at)",
         )?;
-        statement.bind((1, command_name))?;
-        statement.bind((2, guild_id.unwrap_or("")))?;
 
-        if let Ok(State::Row) = statement.next() {
-            Ok(Some(statement.read::<String, _>(0)?))
-        } else {
-            Ok(None)
+        // Add parent_playlist_id column to plugin_jobs if it doesn't exist
+        // SQLite doesn't support ADD COLUMN IF NOT EXISTS, so we check pragmatically
+        let has_parent_playlist_id: bool = {
+            let mut stmt = conn.prepare("PRAGMA table_info(plugin_jobs)")?;
+            let mut found = false;
+            while let Ok(State::Row) = stmt.next() {
+                let col_name: String = stmt.read(1)?;
+                if col_name == "parent_playlist_id" {
+                    found = true;
+                    break;
+                }
+            }
+            found
+        };
+
+        if !has_parent_playlist_id {
+            conn.execute("ALTER TABLE plugin_jobs ADD COLUMN parent_playlist_id TEXT")?;
+            conn.execute(
+                "CREATE INDEX IF NOT EXISTS idx_plugin_jobs_parent
+                 ON plugin_jobs(parent_playlist_id)",
+            )?;
         }
-    }
 
-    pub async fn delete_custom_command(
-        &self,
-        command_name: &str,
-        guild_id: Option<&str>,
-    ) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement =
-            conn.prepare("DELETE FROM custom_commands WHERE command_name = ? AND guild_id = ?")?;
-        statement.bind((1, command_name))?;
-        statement.bind((2, guild_id.unwrap_or("")))?;
-        statement.next()?;
-        Ok(())
-    }
-
-    // Analytics Methods
-    pub async fn increment_daily_stat(&self, stat_type: &str) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
+        // Playlist Jobs Table (for multi-video transcription)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS playlist_jobs (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                guild_id TEXT,
+                channel_id TEXT NOT NULL,
+                thread_id TEXT,
+                playlist_url TEXT NOT NULL,
+                playlist_id TEXT NOT NULL,
+                playlist_title TEXT,
+                total_videos INTEGER NOT NULL,
+                completed_videos INTEGER DEFAULT 0,
+                failed_videos INTEGER DEFAULT 0,
+                skipped_videos INTEGER DEFAULT 0,
+                status TEXT NOT NULL DEFAULT 'pending',
+                max_videos INTEGER,
+                current_video_job_id TEXT,
+                error TEXT,
+                started_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                completed_at DATETIME,
+                cancelled_at DATETIME,
+                cancelled_by TEXT
+            )",
+        )?;
 
-        match stat_type {
-            "message" => {
-                conn.execute(
-                    "INSERT INTO daily_analytics (date, total_messages) VALUES (?, 1)
-                     ON CONFLICT(date) DO UPDATE SET total_messages = total_messages + 1",
-                )?;
-            }
-            "command" => {
-                conn.execute(
-                    "INSERT INTO daily_analytics (date, total_commands) VALUES (?, 1)
-                     ON CONFLICT(date) DO UPDATE SET total_commands = total_commands + 1",
-                )?;
-            }
-            "error" => {
-                conn.execute(
-                    "INSERT INTO daily_analytics (date, total_errors) VALUES (?, 1)
-                     ON CONFLICT(date) DO UPDATE SET total_errors = total_errors + 1",
-                )?;
-            }
-            _ => {}
-        }
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_playlist_jobs_status
+             ON playlist_jobs(status, started_at)",
+        )?;
 
-        let mut statement = conn.prepare(
-            "INSERT INTO daily_analytics (date, total_messages) VALUES (?, 0)
-             ON CONFLICT(date) DO NOTHING",
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_playlist_jobs_user
+             ON playlist_jobs(user_id, started_at)",
         )?;
-        statement.bind((1, date.as_str()))?;
-        statement.next()?;
-        Ok(())
-    }
 
-    pub async fn add_performance_metric(
-        &self,
-        metric_type: &str,
-        value: f64,
-        unit: Option<&str>,
-        metadata: Option<&str>,
-    ) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "INSERT INTO performance_metrics (metric_type, value, unit, metadata) VALUES (?, ?, ?, ?)"
+        // User Cache Table - caches Discord usernames for TUI display
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS user_cache (
+                user_id TEXT PRIMARY KEY,
+                username TEXT NOT NULL,
+                discriminator TEXT,
+                display_name TEXT,
+                is_bot INTEGER DEFAULT 0,
+                last_seen INTEGER,
+                updated_at INTEGER DEFAULT (strftime('%s', 'now'))
+            )",
         )?;
-        statement.bind((1, metric_type))?;
-        statement.bind((2, value))?;
-        statement.bind((3, unit.unwrap_or("")))?;
-        statement.bind((4, metadata.unwrap_or("")))?;
-        statement.next()?;
-        Ok(())
-    }
 
-    // System Metrics Methods (for /sysinfo command)
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_user_cache_username
+             ON user_cache(username)",
+        )?;
 
-    /// Store a system metric snapshot (uses performance_metrics table)
-    pub async fn store_system_metric(&self, metric_type: &str, value: f64) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "INSERT INTO performance_metrics (metric_type, value, unit, metadata) VALUES (?, ?, 'system', '')"
+        // Component State Table - state behind buttons that must survive restarts
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS component_state (
+                feature TEXT NOT NULL,
+                state_key TEXT NOT NULL,
+                payload TEXT NOT NULL,
+                expires_at INTEGER,
+                updated_at INTEGER DEFAULT (strftime('%s', 'now')),
+                PRIMARY KEY (feature, state_key)
+            )",
         )?;
-        statement.bind((1, metric_type))?;
-        statement.bind((2, value))?;
-        statement.next()?;
-        Ok(())
-    }
 
-    /// Get historical metrics data for a specific metric type
-    /// Returns (unix_timestamp, value) pairs ordered by time ascending
-    pub async fn get_metrics_history(
-        &self,
-        metric_type: &str,
-        hours: i64,
-    ) -> Result<Vec<(i64, f64)>> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "SELECT strftime('%s', timestamp) as unix_time, value
-             FROM performance_metrics
-             WHERE metric_type = ? AND timestamp >= datetime('now', ? || ' hours')
-             ORDER BY timestamp ASC",
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_component_state_expiry
+             ON component_state(expires_at)",
         )?;
-        statement.bind((1, metric_type))?;
-        statement.bind((2, format!("-{hours}").as_str()))?;
 
-        let mut results = Vec::new();
-        while let Ok(State::Row) = statement.next() {
-            let timestamp_str = statement.read::<String, _>(0)?;
-            let timestamp = timestamp_str.parse::<i64>().unwrap_or(0);
-            let value = statement.read::<f64, _>(1)?;
-            results.push((timestamp, value));
-        }
-        Ok(results)
-    }
+        // Conversation Forks - threads seeded with another conversation's context
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS conversation_forks (
+                thread_id TEXT PRIMARY KEY,
+                source_channel_id TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                message_count INTEGER NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+        )?;
 
-    /// Cleanup old metrics data (keep last N days)
-    pub async fn cleanup_old_metrics(&self, days: i64) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "DELETE FROM performance_metrics WHERE unit = 'system' AND timestamp < datetime('now', ? || ' days')"
+        // Category Settings - default persona for every channel in a category
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS category_settings (
+                guild_id TEXT NOT NULL,
+                category_id TEXT NOT NULL,
+                default_persona TEXT NOT NULL,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (guild_id, category_id)
+            )",
         )?;
-        statement.bind((1, format!("-{days}").as_str()))?;
-        statement.next()?;
-        info!("Cleaned up system metrics older than {days} days");
-        Ok(())
-    }
 
-    #[allow(clippy::too_many_arguments)]
-    pub async fn log_error(
-        &self,
-        error_type: &str,
-        error_message: &str,
-        stack_trace: Option<&str>,
-        user_id: Option<&str>,
-        channel_id: Option<&str>,
-        command: Option<&str>,
-        metadata: Option<&str>,
-    ) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "INSERT INTO error_logs (error_type, error_message, stack_trace, user_id, channel_id, command, metadata)
-             VALUES (?, ?, ?, ?, ?, ?, ?)"
+        // Per-guild persona preferences; user_preferences only applies in DMs
+        let has_guild_personas = {
+            let mut stmt = conn.prepare(
+                "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'guild_user_personas'",
+            )?;
+            matches!(stmt.next(), Ok(State::Row))
+        };
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS guild_user_personas (
+                guild_id TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                default_persona TEXT NOT NULL,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (guild_id, user_id)
+            )",
         )?;
-        statement.bind((1, error_type))?;
-        statement.bind((2, error_message))?;
-        statement.bind((3, stack_trace.unwrap_or("")))?;
-        statement.bind((4, user_id.unwrap_or("")))?;
-        statement.bind((5, channel_id.unwrap_or("")))?;
-        statement.bind((6, command.unwrap_or("")))?;
-        statement.bind((7, metadata.unwrap_or("")))?;
-        statement.next()?;
 
-        // Also increment daily error count
-        self.increment_daily_stat("error").await?;
-        Ok(())
-    }
+        if !has_guild_personas {
+            Self::backfill_guild_ids(&conn)?;
+        }
 
-    // Feature Flag Methods
-    pub async fn set_feature_flag(
-        &self,
-        feature_name: &str,
-        enabled: bool,
-        user_id: Option<&str>,
-        guild_id: Option<&str>,
-    ) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "INSERT OR REPLACE INTO feature_flags (feature_name, enabled, user_id, guild_id, updated_at)
-             VALUES (?, ?, ?, ?, CURRENT_TIMESTAMP)"
+        // Explicit /privacy choices; options without a row use the guild default
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS user_privacy (
+                user_id TEXT NOT NULL,
+                option TEXT NOT NULL,
+                allowed BOOLEAN NOT NULL,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (user_id, option)
+            )",
         )?;
-        statement.bind((1, feature_name))?;
-        statement.bind((2, if enabled { 1i64 } else { 0i64 }))?;
-        statement.bind((3, user_id.unwrap_or("")))?;
-        statement.bind((4, guild_id.unwrap_or("")))?;
-        statement.next()?;
-        Ok(())
-    }
 
-    /// Check if a feature is enabled for a guild
-    /// Returns true by default if no record exists (features are enabled unless explicitly disabled)
-    pub async fn is_feature_enabled(
-        &self,
-        feature_name: &str,
-        user_id: Option<&str>,
-        guild_id: Option<&str>,
-    ) -> Result<bool> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "SELECT enabled FROM feature_flags
-             WHERE feature_name = ? AND user_id = ? AND guild_id = ?
-             LIMIT 1",
+        // First-contact consent before DM content is read or stored
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS dm_consent (
+                user_id TEXT PRIMARY KEY,
+                accepted BOOLEAN NOT NULL,
+                decided_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
         )?;
-        statement.bind((1, feature_name))?;
-        statement.bind((2, user_id.unwrap_or("")))?;
-        statement.bind((3, guild_id.unwrap_or("")))?;
 
-        if let Ok(State::Row) = statement.next() {
-            let enabled = statement.read::<i64, _>(0)?;
-            Ok(enabled == 1)
-        } else {
-            // Default to enabled if no explicit setting exists
-            Ok(true)
-        }
-    }
+        // /admin block and allow lists; a target is either blocked or allowed
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS access_rules (
+                guild_id TEXT NOT NULL,
+                target_type TEXT NOT NULL,
+                target_id TEXT NOT NULL,
+                rule TEXT NOT NULL,
+                created_by TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (guild_id, target_type, target_id)
+            )",
+        )?;
 
-    /// Get all feature flags for a guild
-    /// Returns a map of feature_name -> enabled status
-    pub async fn get_guild_feature_flags(
-        &self,
-        guild_id: &str,
-    ) -> Result<std::collections::HashMap<String, bool>> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "SELECT feature_name, enabled FROM feature_flags
-             WHERE guild_id = ? AND user_id = ''",
+        // /birthday dates; one birthday and one anniversary per member and guild
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS celebrations (
+                guild_id TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                month INTEGER NOT NULL,
+                day INTEGER NOT NULL,
+                year INTEGER,
+                utc_offset_minutes INTEGER NOT NULL DEFAULT 0,
+                last_wished_year INTEGER,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (guild_id, user_id, kind)
+            )",
         )?;
-        statement.bind((1, guild_id))?;
 
-        let mut flags = std::collections::HashMap::new();
-        while let Ok(State::Row) = statement.next() {
-            let feature_name = statement.read::<String, _>(0)?;
-            let enabled = statement.read::<i64, _>(1)? == 1;
-            flags.insert(feature_name, enabled);
-        }
-        Ok(flags)
-    }
-
-    /// Record a feature toggle action in the audit trail
-    pub async fn record_feature_toggle(
-        &self,
-        feature_name: &str,
-        version: &str,
-        guild_id: Option<&str>,
-        toggled_by: &str,
-        enabled: bool,
-    ) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "INSERT INTO feature_versions (feature_name, version, guild_id, toggled_by, enabled)
-             VALUES (?, ?, ?, ?, ?)",
-        )?;
-        statement.bind((1, feature_name))?;
-        statement.bind((2, version))?;
-        statement.bind((3, guild_id.unwrap_or("")))?;
-        statement.bind((4, toggled_by))?;
-        statement.bind((5, if enabled { 1i64 } else { 0i64 }))?;
-        statement.next()?;
-        info!("Recorded feature toggle: {feature_name} -> {enabled} by {toggled_by}");
-        Ok(())
-    }
+        // Member's zone by IANA name; dates saved before it fall back to utc_offset_minutes
+        let _ = conn.execute("ALTER TABLE celebrations ADD COLUMN timezone TEXT");
 
-    // Guild Settings Methods
-    pub async fn set_guild_setting(
-        &self,
-        guild_id: &str,
-        setting_key: &str,
-        setting_value: &str,
-    ) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "INSERT OR REPLACE INTO guild_settings (guild_id, setting_key, setting_value, updated_at)
-             VALUES (?, ?, ?, CURRENT_TIMESTAMP)"
+        // Members who asked not to be wished publicly in a guild
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS celebration_opt_outs (
+                guild_id TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (guild_id, user_id)
+            )",
         )?;
-        statement.bind((1, guild_id))?;
-        statement.bind((2, setting_key))?;
-        statement.bind((3, setting_value))?;
-        statement.next()?;
-        Ok(())
-    }
 
-    pub async fn get_guild_setting(
-        &self,
-        guild_id: &str,
-        setting_key: &str,
-    ) -> Result<Option<String>> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "SELECT setting_value FROM guild_settings WHERE guild_id = ? AND setting_key = ?",
+        // Per-guild /trivia leaderboard
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS trivia_scores (
+                guild_id TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                points INTEGER NOT NULL DEFAULT 0,
+                correct_answers INTEGER NOT NULL DEFAULT 0,
+                games_played INTEGER NOT NULL DEFAULT 0,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (guild_id, user_id)
+            )",
         )?;
-        statement.bind((1, guild_id))?;
-        statement.bind((2, setting_key))?;
 
-        if let Ok(State::Row) = statement.next() {
-            Ok(Some(statement.read::<String, _>(0)?))
-        } else {
-            Ok(None)
-        }
-    }
+        // Games a member finished on top of the scoreboard
+        let _ = conn.execute(
+            "ALTER TABLE trivia_scores ADD COLUMN games_won INTEGER NOT NULL DEFAULT 0",
+        );
 
-    // Bot Settings Methods (global, not per-guild)
-    pub async fn set_bot_setting(&self, setting_key: &str, setting_value: &str) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "INSERT OR REPLACE INTO bot_settings (setting_key, setting_value, updated_at)
-             VALUES (?, ?, CURRENT_TIMESTAMP)",
+        // Concluded debates and the audience's pick of who won each one
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS debate_results (
+                thread_id TEXT PRIMARY KEY,
+                guild_id TEXT,
+                initiator_id TEXT NOT NULL,
+                persona1_id TEXT NOT NULL,
+                persona2_id TEXT NOT NULL,
+                rounds INTEGER NOT NULL DEFAULT 0,
+                concluded_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
         )?;
-        statement.bind((1, setting_key))?;
-        statement.bind((2, setting_value))?;
-        statement.next()?;
-        Ok(())
-    }
 
-    pub async fn get_bot_setting(&self, setting_key: &str) -> Result<Option<String>> {
-        let conn = self.connection.lock().await;
-        let mut statement =
-            conn.prepare("SELECT setting_value FROM bot_settings WHERE setting_key = ?")?;
-        statement.bind((1, setting_key))?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS debate_votes (
+                thread_id TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                persona_id TEXT NOT NULL,
+                voted_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (thread_id, user_id)
+            )",
+        )?;
 
-        if let Ok(State::Row) = statement.next() {
-            Ok(Some(statement.read::<String, _>(0)?))
-        } else {
-            Ok(None)
-        }
-    }
+        // Badges members earned, once per guild
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS achievements (
+                guild_id TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                achievement TEXT NOT NULL,
+                earned_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (guild_id, user_id, achievement)
+            )",
+        )?;
 
-    // Extended User Preferences Methods
-    pub async fn set_user_preference(
-        &self,
-        user_id: &str,
-        preference_key: &str,
-        preference_value: &str,
-    ) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "INSERT OR REPLACE INTO extended_user_preferences (user_id, preference_key, preference_value, updated_at)
-             VALUES (?, ?, ?, CURRENT_TIMESTAMP)"
+        // Per-guild XP for /rank and /leaderboard
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS member_xp (
+                guild_id TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                xp INTEGER NOT NULL DEFAULT 0,
+                grants INTEGER NOT NULL DEFAULT 0,
+                last_grant_at INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (guild_id, user_id)
+            )",
         )?;
-        statement.bind((1, user_id))?;
-        statement.bind((2, preference_key))?;
-        statement.bind((3, preference_value))?;
-        statement.next()?;
-        Ok(())
-    }
 
-    pub async fn get_user_preference(
-        &self,
-        user_id: &str,
-        preference_key: &str,
-    ) -> Result<Option<String>> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "SELECT preference_value FROM extended_user_preferences WHERE user_id = ? AND preference_key = ?"
+        // Roles handed out when members reach a level
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS level_rewards (
+                guild_id TEXT NOT NULL,
+                level INTEGER NOT NULL,
+                role_id TEXT NOT NULL,
+                PRIMARY KEY (guild_id, level)
+            )",
         )?;
-        statement.bind((1, user_id))?;
-        statement.bind((2, preference_key))?;
 
-        if let Ok(State::Row) = statement.next() {
-            Ok(Some(statement.read::<String, _>(0)?))
-        } else {
-            Ok(None)
-        }
-    }
+        // Scheduled channel topic updates, one schedule per channel
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS channel_topic_schedules (
+                channel_id TEXT PRIMARY KEY,
+                guild_id TEXT NOT NULL,
+                mode TEXT NOT NULL,
+                topics TEXT NOT NULL DEFAULT '',
+                theme TEXT,
+                interval_hours INTEGER NOT NULL,
+                next_run_at INTEGER NOT NULL,
+                next_index INTEGER NOT NULL DEFAULT 0,
+                last_topic TEXT,
+                created_by TEXT NOT NULL,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+        )?;
 
-    // Conflict Detection & Mediation Methods
+        // Question of the day schedule per guild
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS qotd_configs (
+                guild_id TEXT PRIMARY KEY,
+                channel_id TEXT NOT NULL,
+                hour INTEGER NOT NULL,
+                utc_offset_minutes INTEGER NOT NULL DEFAULT 0,
+                threads INTEGER NOT NULL DEFAULT 0,
+                digest_channel_id TEXT,
+                next_run_at INTEGER NOT NULL,
+                created_by TEXT NOT NULL,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+        )?;
 
-    pub async fn record_conflict_detection(
-        &self,
-        channel_id: &str,
-        guild_id: Option<&str>,
-        participants: &str, // JSON array of user IDs
-        detection_type: &str,
-        confidence: f32,
-        last_message_id: &str,
-    ) -> Result<i64> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "INSERT INTO conflict_detection
-             (channel_id, guild_id, participants, detection_type, confidence_score, last_message_id)
-             VALUES (?, ?, ?, ?, ?, ?)",
+        // Questions queued by admins, posted oldest first
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS qotd_queue (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                guild_id TEXT NOT NULL,
+                question TEXT NOT NULL,
+                added_by TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
         )?;
-        statement.bind((1, channel_id))?;
-        statement.bind((2, guild_id.unwrap_or("")))?;
-        statement.bind((3, participants))?;
-        statement.bind((4, detection_type))?;
-        statement.bind((5, confidence as f64))?;
-        statement.bind((6, last_message_id))?;
-        statement.next()?;
 
-        // Get the ID of the inserted row
-        let mut id_statement = conn.prepare("SELECT last_insert_rowid()")?;
-        id_statement.next()?;
-        let conflict_id = id_statement.read::<i64, _>(0)?;
+        // Posted questions and, once digested, their best answer
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS qotd_posts (
+                message_id TEXT PRIMARY KEY,
+                guild_id TEXT NOT NULL,
+                channel_id TEXT NOT NULL,
+                thread_id TEXT,
+                question TEXT NOT NULL,
+                posted_at INTEGER NOT NULL,
+                digested INTEGER NOT NULL DEFAULT 0,
+                best_user_id TEXT,
+                best_answer TEXT,
+                best_reactions INTEGER
+            )",
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_qotd_posts_guild ON qotd_posts(guild_id, posted_at)",
+        )?;
 
-        info!("Recorded conflict detection in channel {channel_id} with confidence {confidence}");
-        Ok(conflict_id)
-    }
+        // /suggest entries and the votes cast on them
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS suggestions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                guild_id TEXT NOT NULL,
+                channel_id TEXT NOT NULL,
+                message_id TEXT,
+                author_id TEXT NOT NULL,
+                content TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'open',
+                status_note TEXT,
+                reviewed_by TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_suggestions_guild ON suggestions(guild_id, status)",
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS suggestion_votes (
+                suggestion_id INTEGER NOT NULL,
+                user_id TEXT NOT NULL,
+                vote INTEGER NOT NULL,
+                PRIMARY KEY (suggestion_id, user_id)
+            )",
+        )?;
 
-    pub async fn mark_conflict_resolved(&self, conflict_id: i64) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "UPDATE conflict_detection SET resolved_at = CURRENT_TIMESTAMP WHERE id = ?",
+        // /support tickets, one private thread each
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS support_tickets (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                guild_id TEXT NOT NULL,
+                thread_id TEXT NOT NULL UNIQUE,
+                requester_id TEXT NOT NULL,
+                subject TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'open',
+                opened_at INTEGER NOT NULL,
+                closed_at INTEGER,
+                closed_by TEXT,
+                first_line_answered INTEGER NOT NULL DEFAULT 0,
+                summary TEXT
+            )",
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_support_tickets_guild ON support_tickets(guild_id, status)",
         )?;
-        statement.bind((1, conflict_id))?;
-        statement.next()?;
-        info!("Marked conflict {conflict_id} as resolved");
-        Ok(())
-    }
 
-    pub async fn mark_mediation_triggered(&self, conflict_id: i64, message_id: &str) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "UPDATE conflict_detection
-             SET mediation_triggered = 1, mediation_message_id = ?
-             WHERE id = ?",
+        // Knowledge base for first-line /support answers
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS support_kb (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                guild_id TEXT NOT NULL,
+                title TEXT NOT NULL,
+                content TEXT NOT NULL,
+                created_by TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
         )?;
-        statement.bind((1, message_id))?;
-        statement.bind((2, conflict_id))?;
-        statement.next()?;
-        Ok(())
-    }
 
-    pub async fn get_channel_active_conflict(&self, channel_id: &str) -> Result<Option<i64>> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "SELECT id FROM conflict_detection
-             WHERE channel_id = ? AND resolved_at IS NULL
-             ORDER BY last_detected DESC LIMIT 1",
+        // Anonymous /ask_anon questions and their moderation state
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS anon_questions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                guild_id TEXT NOT NULL,
+                author_id TEXT NOT NULL,
+                content TEXT NOT NULL,
+                status TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                reviewed_by TEXT,
+                message_id TEXT
+            )",
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_anon_questions_guild ON anon_questions(guild_id, status)",
         )?;
-        statement.bind((1, channel_id))?;
 
-        if let Ok(State::Row) = statement.next() {
-            Ok(Some(statement.read::<i64, _>(0)?))
-        } else {
-            Ok(None)
-        }
-    }
+        // Members waiting to pass the verification gate
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS verification_pending (
+                guild_id TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                channel_id TEXT NOT NULL,
+                message_id TEXT,
+                answer TEXT,
+                deadline INTEGER,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (guild_id, user_id)
+            )",
+        )?;
 
-    pub async fn record_mediation(
-        &self,
-        conflict_id: i64,
-        channel_id: &str,
-        message_text: &str,
-    ) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "INSERT INTO mediation_history (conflict_id, channel_id, mediation_message)
-             VALUES (?, ?, ?)",
+        // /autoresponse triggers per guild
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS autoresponses (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                guild_id TEXT NOT NULL,
+                pattern TEXT NOT NULL,
+                match_kind TEXT NOT NULL,
+                response TEXT NOT NULL,
+                response_kind TEXT NOT NULL,
+                cooldown_secs INTEGER NOT NULL DEFAULT 0,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                priority INTEGER NOT NULL DEFAULT 0,
+                created_by TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_autoresponses_guild ON autoresponses(guild_id, priority)",
         )?;
-        statement.bind((1, conflict_id))?;
-        statement.bind((2, channel_id))?;
-        statement.bind((3, message_text))?;
-        statement.next()?;
-        info!("Recorded mediation for conflict {conflict_id}");
-        Ok(())
-    }
 
-    /// Get the timestamp of the last mediation in a channel
-    pub async fn get_last_mediation_timestamp(&self, channel_id: &str) -> Result<Option<i64>> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "SELECT strftime('%s', mh.created_at) as unix_time
-             FROM mediation_history mh
-             WHERE mh.channel_id = ?
-             ORDER BY mh.created_at DESC
-             LIMIT 1",
+        // /alias prompt templates, personal or shared with a guild
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS prompt_aliases (
+                scope TEXT NOT NULL,
+                scope_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                template TEXT NOT NULL,
+                persona TEXT,
+                created_by TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (scope, scope_id, name)
+            )",
         )?;
-        statement.bind((1, channel_id))?;
 
-        if let Ok(State::Row) = statement.next() {
-            let timestamp_str = statement.read::<String, _>(0)?;
-            Ok(Some(timestamp_str.parse::<i64>()?))
-        } else {
-            Ok(None)
-        }
-    }
+        // /prompts library shared per guild
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS guild_prompts (
+                guild_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                category TEXT NOT NULL,
+                description TEXT,
+                template TEXT NOT NULL,
+                created_by TEXT NOT NULL,
+                uses INTEGER NOT NULL DEFAULT 0,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (guild_id, name)
+            )",
+        )?;
 
-    pub async fn get_recent_channel_messages(
-        &self,
-        channel_id: &str,
-        limit: usize,
-    ) -> Result<Vec<(String, String, String)>> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "SELECT user_id, content, strftime('%s', timestamp) as unix_time
-             FROM conversation_history
-             WHERE channel_id = ?
-             ORDER BY timestamp DESC
-             LIMIT ?",
+        // /send_later messages waiting to be posted
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS scheduled_messages (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                guild_id TEXT NOT NULL,
+                channel_id TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                content TEXT NOT NULL,
+                persona TEXT,
+                send_at INTEGER NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_scheduled_messages_send_at
+             ON scheduled_messages(send_at)",
         )?;
-        statement.bind((1, channel_id))?;
-        statement.bind((2, limit as i64))?;
 
-        let mut messages = Vec::new();
-        while let Ok(State::Row) = statement.next() {
-            let user_id = statement.read::<String, _>(0)?;
-            let content = statement.read::<String, _>(1)?;
-            let timestamp = statement.read::<String, _>(2)?;
-            messages.push((user_id, content, timestamp));
-        }
+        // /countdown embeds still counting
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS countdowns (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                guild_id TEXT NOT NULL,
+                channel_id TEXT NOT NULL,
+                message_id TEXT,
+                title TEXT NOT NULL,
+                ends_at INTEGER NOT NULL,
+                role_id TEXT,
+                created_by TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+        )?;
 
-        // Reverse to get chronological order
-        messages.reverse();
-        Ok(messages)
-    }
+        // /initiative order per thread
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS initiative_entries (
+                thread_id TEXT NOT NULL,
+                guild_id TEXT NOT NULL,
+                name TEXT NOT NULL COLLATE NOCASE,
+                initiative INTEGER NOT NULL,
+                modifier INTEGER NOT NULL DEFAULT 0,
+                added_by TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (thread_id, name)
+            )",
+        )?;
 
-    /// Get recent channel messages that occurred after a specific timestamp
-    /// This is used to avoid re-analyzing messages that have already been mediated
-    pub async fn get_recent_channel_messages_since(
-        &self,
-        channel_id: &str,
-        since_timestamp: i64,
-        limit: usize,
-    ) -> Result<Vec<(String, String, String)>> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "SELECT user_id, content, strftime('%s', timestamp) as unix_time
-             FROM conversation_history
-             WHERE channel_id = ?
-               AND CAST(strftime('%s', timestamp) AS INTEGER) > ?
-             ORDER BY timestamp DESC
-             LIMIT ?",
+        // Whose turn it is in each thread's initiative order
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS initiative_turns (
+                thread_id TEXT PRIMARY KEY,
+                turn INTEGER NOT NULL DEFAULT 0,
+                round INTEGER NOT NULL DEFAULT 1
+            )",
         )?;
-        statement.bind((1, channel_id))?;
-        statement.bind((2, since_timestamp))?;
-        statement.bind((3, limit as i64))?;
 
-        let mut messages = Vec::new();
-        while let Ok(State::Row) = statement.next() {
-            let user_id = statement.read::<String, _>(0)?;
-            let content = statement.read::<String, _>(1)?;
-            let timestamp = statement.read::<String, _>(2)?;
-            messages.push((user_id, content, timestamp));
-        }
+        // Generated titles of DM sessions for /history
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS dm_session_titles (
+                session_id TEXT PRIMARY KEY,
+                title TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+        )?;
 
-        // Reverse to get chronological order
-        messages.reverse();
-        Ok(messages)
-    }
+        // Messages pinned as memories, per user and channel
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS pinned_memories (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id TEXT NOT NULL,
+                channel_id TEXT NOT NULL,
+                message_id TEXT NOT NULL,
+                content TEXT NOT NULL,
+                author_name TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                UNIQUE(user_id, channel_id, message_id)
+            )",
+        )?;
 
-    pub async fn update_user_interaction_pattern(
-        &self,
-        user_id_a: &str,
-        user_id_b: &str,
-        channel_id: &str,
-        is_conflict: bool,
-    ) -> Result<()> {
-        let conn = self.connection.lock().await;
+        // /glossary terms per guild
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS glossary_terms (
+                guild_id TEXT NOT NULL,
+                term TEXT NOT NULL COLLATE NOCASE,
+                definition TEXT NOT NULL,
+                created_by TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (guild_id, term)
+            )",
+        )?;
 
-        // Ensure user_id_a is always lexicographically smaller (for consistent lookups)
-        let (user_a, user_b) = if user_id_a < user_id_b {
-            (user_id_a, user_id_b)
-        } else {
-            (user_id_b, user_id_a)
-        };
+        // /standup schedule per guild
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS standup_configs (
+                guild_id TEXT PRIMARY KEY,
+                channel_id TEXT NOT NULL,
+                prompt_minute INTEGER NOT NULL,
+                utc_offset_minutes INTEGER NOT NULL DEFAULT 0,
+                collect_minutes INTEGER NOT NULL,
+                weekdays_only INTEGER NOT NULL DEFAULT 1,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                last_prompt_date TEXT,
+                last_prompt_at INTEGER,
+                last_summary_date TEXT,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+        )?;
 
-        let conflict_increment = if is_conflict { 1 } else { 0 };
+        // Members who opted into a guild's standup
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS standup_members (
+                guild_id TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                skip_until TEXT,
+                joined_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (guild_id, user_id)
+            )",
+        )?;
 
-        let mut statement = conn.prepare(
-            "INSERT INTO user_interaction_patterns
-             (user_id_a, user_id_b, channel_id, interaction_count, conflict_incidents, last_interaction)
-             VALUES (?, ?, ?, 1, ?, CURRENT_TIMESTAMP)
-             ON CONFLICT(user_id_a, user_id_b, channel_id) DO UPDATE SET
-             interaction_count = interaction_count + 1,
-             conflict_incidents = conflict_incidents + ?,
-             last_interaction = CURRENT_TIMESTAMP"
+        // Daily standup answers collected by DM
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS standup_entries (
+                guild_id TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                standup_date TEXT NOT NULL,
+                step INTEGER NOT NULL DEFAULT 0,
+                yesterday TEXT,
+                today TEXT,
+                blockers TEXT,
+                skipped INTEGER NOT NULL DEFAULT 0,
+                prompted_at INTEGER NOT NULL,
+                PRIMARY KEY (guild_id, user_id, standup_date)
+            )",
         )?;
-        statement.bind((1, user_a))?;
-        statement.bind((2, user_b))?;
-        statement.bind((3, channel_id))?;
-        statement.bind((4, conflict_increment))?;
-        statement.bind((5, conflict_increment))?;
-        statement.next()?;
-        Ok(())
-    }
 
-    // Channel Settings Methods
+        // /meet availability polls; slots are comma-separated unix start times
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS meeting_polls (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                guild_id TEXT NOT NULL,
+                channel_id TEXT NOT NULL,
+                organizer_id TEXT NOT NULL,
+                title TEXT NOT NULL,
+                slots TEXT NOT NULL,
+                utc_offset_minutes INTEGER NOT NULL DEFAULT 0,
+                duration_minutes INTEGER NOT NULL,
+                create_event INTEGER NOT NULL DEFAULT 0,
+                remind_minutes INTEGER NOT NULL DEFAULT 0,
+                chosen_slot INTEGER,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+        )?;
 
-    /// Get verbosity for a channel, falling back to guild default, then "concise"
-    pub async fn get_channel_verbosity(&self, guild_id: &str, channel_id: &str) -> Result<String> {
-        let conn = self.connection.lock().await;
+        // Organizer's zone by IANA name; polls from before it fall back to utc_offset_minutes
+        let _ = conn.execute("ALTER TABLE meeting_polls ADD COLUMN timezone TEXT");
 
-        // First try channel-specific setting
-        let mut statement = conn.prepare(
-            "SELECT verbosity FROM channel_settings WHERE guild_id = ? AND channel_id = ?",
+        // Which members can make which slot
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS meeting_availability (
+                poll_id INTEGER NOT NULL,
+                slot_index INTEGER NOT NULL,
+                user_id TEXT NOT NULL,
+                PRIMARY KEY (poll_id, slot_index, user_id)
+            )",
         )?;
-        statement.bind((1, guild_id))?;
-        statement.bind((2, channel_id))?;
 
-        if let Ok(State::Row) = statement.next() {
-            return Ok(statement.read::<String, _>(0)?);
-        }
+        // /ticket Jira or Linear workspace per guild
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS issue_tracker_configs (
+                guild_id TEXT PRIMARY KEY,
+                provider TEXT NOT NULL,
+                project TEXT NOT NULL,
+                api_token TEXT NOT NULL,
+                base_url TEXT,
+                email TEXT,
+                issue_type TEXT,
+                summarize INTEGER NOT NULL DEFAULT 1,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+        )?;
 
-        // Fall back to guild default
-        drop(statement);
-        let mut guild_stmt = conn.prepare(
-            "SELECT setting_value FROM guild_settings WHERE guild_id = ? AND setting_key = 'default_verbosity'"
+        // /webhooks outbound event notifications
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS guild_webhooks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                guild_id TEXT NOT NULL,
+                url TEXT NOT NULL,
+                format TEXT NOT NULL,
+                events TEXT NOT NULL,
+                template TEXT,
+                created_by TEXT NOT NULL,
+                last_error TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_guild_webhooks_guild
+             ON guild_webhooks(guild_id)",
         )?;
-        guild_stmt.bind((1, guild_id))?;
 
-        if let Ok(State::Row) = guild_stmt.next() {
-            return Ok(guild_stmt.read::<String, _>(0)?);
-        }
+        // Telegram accounts linked to Discord users, and pending link codes
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS telegram_links (
+                telegram_user_id TEXT PRIMARY KEY,
+                discord_user_id TEXT NOT NULL,
+                continue_channel_id TEXT,
+                linked_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS telegram_link_codes (
+                code TEXT PRIMARY KEY,
+                discord_user_id TEXT NOT NULL,
+                expires_at DATETIME NOT NULL
+            )",
+        )?;
 
-        // Default to concise
-        Ok("concise".to_string())
-    }
+        // Transcripts by YouTube video ID, reused instead of re-transcribing
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS transcript_cache (
+                video_id TEXT PRIMARY KEY,
+                transcript TEXT NOT NULL,
+                title TEXT,
+                job_id TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                hit_count INTEGER NOT NULL DEFAULT 0,
+                last_hit_at DATETIME
+            )",
+        )?;
 
-    /// Set verbosity for a specific channel
-    pub async fn set_channel_verbosity(
-        &self,
-        guild_id: &str,
-        channel_id: &str,
-        verbosity: &str,
-    ) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "INSERT INTO channel_settings (guild_id, channel_id, verbosity, updated_at)
-             VALUES (?, ?, ?, CURRENT_TIMESTAMP)
-             ON CONFLICT(guild_id, channel_id) DO UPDATE SET
-             verbosity = excluded.verbosity,
-             updated_at = CURRENT_TIMESTAMP",
+        // Transcripts posted into a thread, for answering questions asked there
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS thread_transcripts (
+                thread_id TEXT PRIMARY KEY,
+                job_id TEXT NOT NULL,
+                title TEXT,
+                transcript TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
         )?;
-        statement.bind((1, guild_id))?;
-        statement.bind((2, channel_id))?;
-        statement.bind((3, verbosity))?;
-        statement.next()?;
-        info!("Set verbosity for channel {channel_id} to {verbosity}");
+
+        // YouTube channels polled for new uploads to transcribe
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS transcribe_subscriptions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                guild_id TEXT NOT NULL,
+                channel_id TEXT NOT NULL,
+                source_url TEXT NOT NULL,
+                title TEXT,
+                user_id TEXT NOT NULL,
+                interval_minutes INTEGER NOT NULL,
+                max_per_check INTEGER NOT NULL,
+                last_checked_at INTEGER,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                UNIQUE(guild_id, channel_id, source_url)
+            )",
+        )?;
+
+        // Uploads a subscription has already handled
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS transcribe_subscription_videos (
+                subscription_id INTEGER NOT NULL,
+                video_id TEXT NOT NULL,
+                seen_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (subscription_id, video_id)
+            )",
+        )?;
+
+        // Per-channel sentiment, one row per hour (hour = unix start of the hour)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS channel_sentiment_hourly (
+                channel_id TEXT NOT NULL,
+                hour INTEGER NOT NULL,
+                guild_id TEXT NOT NULL,
+                messages INTEGER NOT NULL DEFAULT 0,
+                score_sum REAL NOT NULL DEFAULT 0,
+                PRIMARY KEY (channel_id, hour)
+            )",
+        )?;
+
         Ok(())
     }
 
-    /// Get all settings for a channel (verbosity, conflict_enabled, persona)
-    pub async fn get_channel_settings(
+    /// One-time migration to per-guild isolation
+    ///
+    /// Fills in `conversation_history.guild_id` from every table that pairs a
+    /// channel with its guild, and copies each global persona preference into
+    /// the guilds the user was active in so existing choices keep working there.
+    fn backfill_guild_ids(conn: &Connection) -> Result<()> {
+        conn.execute(
+            "UPDATE conversation_history SET guild_id = (
+                SELECT guild_id FROM (
+                    SELECT channel_id, guild_id FROM channel_settings
+                    UNION ALL
+                    SELECT channel_id, guild_id FROM openai_usage
+                    WHERE guild_id IS NOT NULL AND guild_id != '' AND channel_id IS NOT NULL
+                    UNION ALL
+                    SELECT channel_id, guild_id FROM conflict_detection WHERE guild_id IS NOT NULL
+                ) known
+                WHERE known.channel_id = conversation_history.channel_id
+                LIMIT 1
+             )
+             WHERE guild_id IS NULL",
+        )?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO guild_user_personas (guild_id, user_id, default_persona)
+             SELECT active.guild_id, up.user_id, up.default_persona
+             FROM user_preferences up
+             JOIN (
+                 SELECT DISTINCT user_id, guild_id FROM conversation_history
+                 WHERE guild_id IS NOT NULL AND guild_id != ''
+                 UNION
+                 SELECT DISTINCT user_id, guild_id FROM openai_usage
+                 WHERE guild_id IS NOT NULL AND guild_id != ''
+             ) active ON active.user_id = up.user_id
+             WHERE up.default_persona IS NOT NULL",
+        )?;
+        info!("Backfilled guild IDs for per-guild data isolation");
+        Ok(())
+    }
+
+    pub async fn get_user_persona(&self, user_id: &str) -> Result<String> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("SELECT default_persona FROM user_preferences WHERE user_id = ?")?;
+        statement.bind((1, user_id))?;
+
+        if let Ok(State::Row) = statement.next() {
+            Ok(statement.read::<String, _>("default_persona")?)
+        } else {
+            // Check for PERSONA environment variable, fallback to 'obi'
+            Ok(std::env::var("PERSONA").unwrap_or_else(|_| "obi".to_string()))
+        }
+    }
+
+    /// Get user persona with guild default fallback
+    /// Cascade: user preference -> guild default -> env var -> "obi"
+    ///
+    /// Inside a guild only that guild's preference counts; the global
+    /// `user_preferences` row is the DM preference and never leaks in.
+    /// Personas the guild's guardrail profile doesn't allow fall back.
+    pub async fn get_user_persona_with_guild(
+        &self,
+        user_id: &str,
+        guild_id: Option<&str>,
+    ) -> Result<String> {
+        let persona = self
+            .lookup_user_persona_with_guild(user_id, guild_id)
+            .await?;
+        Ok(GuardrailProfile::for_guild(self, guild_id)
+            .await?
+            .resolve_persona(persona))
+    }
+
+    async fn lookup_user_persona_with_guild(
+        &self,
+        user_id: &str,
+        guild_id: Option<&str>,
+    ) -> Result<String> {
+        let conn = self.connection.lock().await?;
+
+        // First check user preference for this scope
+        let mut statement = match guild_id {
+            Some(gid) => {
+                let mut stmt = conn.prepare(
+                    "SELECT default_persona FROM guild_user_personas WHERE guild_id = ? AND user_id = ?",
+                )?;
+                stmt.bind((1, gid))?;
+                stmt.bind((2, user_id))?;
+                stmt
+            }
+            None => {
+                let mut stmt =
+                    conn.prepare("SELECT default_persona FROM user_preferences WHERE user_id = ?")?;
+                stmt.bind((1, user_id))?;
+                stmt
+            }
+        };
+
+        if let Ok(State::Row) = statement.next() {
+            return Ok(statement.read::<String, _>("default_persona")?);
+        }
+
+        // Check guild default if guild_id is provided
+        if let Some(gid) = guild_id {
+            drop(statement);
+            let mut guild_stmt = conn.prepare(
+                "SELECT setting_value FROM guild_settings WHERE guild_id = ? AND setting_key = 'default_persona'"
+            )?;
+            guild_stmt.bind((1, gid))?;
+
+            if let Ok(State::Row) = guild_stmt.next() {
+                return Ok(guild_stmt.read::<String, _>(0)?);
+            }
+        }
+
+        // Fall back to PERSONA environment variable, then 'obi'
+        Ok(std::env::var("PERSONA").unwrap_or_else(|_| "obi".to_string()))
+    }
+
+    /// Get the channel-level persona override if set
+    pub async fn get_channel_persona(
         &self,
         guild_id: &str,
         channel_id: &str,
-    ) -> Result<(String, bool, Option<String>)> {
-        let conn = self.connection.lock().await;
+    ) -> Result<Option<String>> {
+        let conn = self.connection.lock().await?;
         let mut statement = conn.prepare(
-            "SELECT verbosity, conflict_enabled, default_persona FROM channel_settings WHERE guild_id = ? AND channel_id = ?"
+            "SELECT default_persona FROM channel_settings WHERE guild_id = ? AND channel_id = ?",
         )?;
         statement.bind((1, guild_id))?;
         statement.bind((2, channel_id))?;
 
         if let Ok(State::Row) = statement.next() {
-            let verbosity = statement.read::<String, _>(0)?;
-            let conflict_enabled = statement.read::<i64, _>(1)? == 1;
-            let persona = statement.read::<Option<String>, _>(2)?;
-            Ok((verbosity, conflict_enabled, persona))
+            let persona: Option<String> = statement.read::<Option<String>, _>(0)?;
+            Ok(persona)
         } else {
-            // Return defaults
-            Ok(("concise".to_string(), true, None))
+            Ok(None)
         }
     }
 
-    /// Set whether conflict detection is enabled for a channel
-    pub async fn set_channel_conflict_enabled(
+    /// Set or clear the channel-level persona override
+    /// Pass None to clear the override
+    pub async fn set_channel_persona(
         &self,
         guild_id: &str,
         channel_id: &str,
-        enabled: bool,
+        persona: Option<&str>,
     ) -> Result<()> {
-        let conn = self.connection.lock().await;
+        let conn = self.connection.lock().await?;
         let mut statement = conn.prepare(
-            "INSERT INTO channel_settings (guild_id, channel_id, conflict_enabled, updated_at)
+            "INSERT INTO channel_settings (guild_id, channel_id, default_persona, updated_at)
              VALUES (?, ?, ?, CURRENT_TIMESTAMP)
              ON CONFLICT(guild_id, channel_id) DO UPDATE SET
-             conflict_enabled = excluded.conflict_enabled,
+             default_persona = excluded.default_persona,
              updated_at = CURRENT_TIMESTAMP",
         )?;
         statement.bind((1, guild_id))?;
         statement.bind((2, channel_id))?;
-        statement.bind((3, if enabled { 1i64 } else { 0i64 }))?;
+        statement.bind((3, persona))?;
         statement.next()?;
-        info!("Set conflict_enabled for channel {channel_id} to {enabled}");
+        match persona {
+            Some(p) => info!("Set persona for channel {channel_id} to {p}"),
+            None => info!("Cleared persona override for channel {channel_id}"),
+        }
         Ok(())
     }
 
-    /// Get max_paragraphs for a channel (0 = no limit)
-    pub async fn get_channel_max_paragraphs(&self, guild_id: &str, channel_id: &str) -> Result<i64> {
-        let conn = self.connection.lock().await;
+    /// Set or clear the default persona for a channel category
+    /// Pass None to clear the mapping
+    pub async fn set_category_persona(
+        &self,
+        guild_id: &str,
+        category_id: &str,
+        persona: Option<&str>,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        match persona {
+            Some(p) => {
+                let mut statement = conn.prepare(
+                    "INSERT INTO category_settings (guild_id, category_id, default_persona, updated_at)
+                     VALUES (?, ?, ?, CURRENT_TIMESTAMP)
+                     ON CONFLICT(guild_id, category_id) DO UPDATE SET
+                     default_persona = excluded.default_persona,
+                     updated_at = CURRENT_TIMESTAMP",
+                )?;
+                statement.bind((1, guild_id))?;
+                statement.bind((2, category_id))?;
+                statement.bind((3, p))?;
+                statement.next()?;
+                info!("Set persona for category {category_id} to {p}");
+            }
+            None => {
+                let mut statement = conn.prepare(
+                    "DELETE FROM category_settings WHERE guild_id = ? AND category_id = ?",
+                )?;
+                statement.bind((1, guild_id))?;
+                statement.bind((2, category_id))?;
+                statement.next()?;
+                info!("Cleared persona for category {category_id}");
+            }
+        }
+        Ok(())
+    }
+
+    /// All category persona mappings for a guild as (category_id, persona)
+    pub async fn get_category_personas(&self, guild_id: &str) -> Result<Vec<(String, String)>> {
+        let conn = self.connection.lock().await?;
         let mut statement = conn.prepare(
-            "SELECT max_paragraphs FROM channel_settings WHERE guild_id = ? AND channel_id = ?",
+            "SELECT category_id, default_persona FROM category_settings
+             WHERE guild_id = ? ORDER BY updated_at",
         )?;
         statement.bind((1, guild_id))?;
-        statement.bind((2, channel_id))?;
 
-        if let Ok(State::Row) = statement.next() {
-            let value: Option<i64> = statement.read(0)?;
-            Ok(value.unwrap_or(0))
-        } else {
-            Ok(0) // Default: no limit
+        let mut mappings = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            mappings.push((
+                statement.read::<String, _>(0)?,
+                statement.read::<String, _>(1)?,
+            ));
         }
+        Ok(mappings)
     }
 
-    /// Set max_paragraphs for a channel (0 = no limit, 1-10 = enforced limit)
-    pub async fn set_channel_max_paragraphs(
+    /// Get persona with full cascade:
+    /// channel override -> category default -> user preference -> guild default -> env -> "obi"
+    ///
+    /// Personas the guild's guardrail profile doesn't allow fall back.
+    pub async fn get_persona_with_channel(
         &self,
+        user_id: &str,
         guild_id: &str,
         channel_id: &str,
-        max_paragraphs: i64,
-    ) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "INSERT INTO channel_settings (guild_id, channel_id, max_paragraphs, updated_at)
-             VALUES (?, ?, ?, CURRENT_TIMESTAMP)
-             ON CONFLICT(guild_id, channel_id) DO UPDATE SET
-             max_paragraphs = excluded.max_paragraphs,
-             updated_at = CURRENT_TIMESTAMP",
-        )?;
-        statement.bind((1, guild_id))?;
-        statement.bind((2, channel_id))?;
-        statement.bind((3, max_paragraphs))?;
-        statement.next()?;
-        info!("Set max_paragraphs for channel {channel_id} to {max_paragraphs}");
-        Ok(())
+        category_id: Option<&str>,
+    ) -> Result<String> {
+        let persona = self
+            .lookup_persona_with_channel(user_id, guild_id, channel_id, category_id)
+            .await?;
+        Ok(GuardrailProfile::for_channel(self, Some(guild_id), Some(channel_id))
+            .await?
+            .resolve_persona(persona))
     }
 
-    /// Check if a user has the bot admin role for a guild
-    pub async fn has_bot_admin_role(&self, guild_id: &str, user_roles: &[String]) -> Result<bool> {
-        // Get the bot admin role ID from guild settings
-        let admin_role = self.get_guild_setting(guild_id, "bot_admin_role").await?;
+    async fn lookup_persona_with_channel(
+        &self,
+        user_id: &str,
+        guild_id: &str,
+        channel_id: &str,
+        category_id: Option<&str>,
+    ) -> Result<String> {
+        let conn = self.connection.lock().await?;
 
-        if let Some(role_id) = admin_role {
-            Ok(user_roles.iter().any(|r| r == &role_id))
-        } else {
-            // No bot admin role set - only Discord admins can manage
-            Ok(false)
+        // First check channel override (highest priority)
+        let mut channel_stmt = conn.prepare(
+            "SELECT default_persona FROM channel_settings WHERE guild_id = ? AND channel_id = ?",
+        )?;
+        channel_stmt.bind((1, guild_id))?;
+        channel_stmt.bind((2, channel_id))?;
+
+        if let Ok(State::Row) = channel_stmt.next() {
+            if let Ok(Some(persona)) = channel_stmt.read::<Option<String>, _>(0) {
+                return Ok(persona);
+            }
+        }
+
+        // Then the default for the channel's category
+        drop(channel_stmt);
+        if let Some(category_id) = category_id {
+            let mut category_stmt = conn.prepare(
+                "SELECT default_persona FROM category_settings WHERE guild_id = ? AND category_id = ?",
+            )?;
+            category_stmt.bind((1, guild_id))?;
+            category_stmt.bind((2, category_id))?;
+
+            if let Ok(State::Row) = category_stmt.next() {
+                return Ok(category_stmt.read::<String, _>(0)?);
+            }
+        }
+
+        // Check the user's preference in this guild
+        let mut user_stmt = conn.prepare(
+            "SELECT default_persona FROM guild_user_personas WHERE guild_id = ? AND user_id = ?",
+        )?;
+        user_stmt.bind((1, guild_id))?;
+        user_stmt.bind((2, user_id))?;
+
+        if let Ok(State::Row) = user_stmt.next() {
+            return Ok(user_stmt.read::<String, _>("default_persona")?);
+        }
+
+        // Check guild default
+        drop(user_stmt);
+        let mut guild_stmt = conn.prepare(
+            "SELECT setting_value FROM guild_settings WHERE guild_id = ? AND setting_key = 'default_persona'"
+        )?;
+        guild_stmt.bind((1, guild_id))?;
+
+        if let Ok(State::Row) = guild_stmt.next() {
+            return Ok(guild_stmt.read::<String, _>(0)?);
         }
+
+        // Fall back to PERSONA environment variable, then 'obi'
+        Ok(std::env::var("PERSONA").unwrap_or_else(|_| "obi".to_string()))
     }
 
-    // OpenAI Usage Tracking Methods
+    pub async fn set_user_persona(&self, user_id: &str, persona: &str) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        conn.execute(
+            "INSERT OR REPLACE INTO user_preferences (user_id, default_persona, updated_at) 
+             VALUES (?, ?, CURRENT_TIMESTAMP)",
+        )?;
 
-    /// Log a ChatCompletion (GPT) usage event
-    #[allow(clippy::too_many_arguments)]
-    pub async fn log_openai_chat_usage(
+        let mut statement = conn.prepare(
+            "INSERT OR REPLACE INTO user_preferences (user_id, default_persona, updated_at) 
+             VALUES (?, ?, CURRENT_TIMESTAMP)",
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, persona))?;
+        statement.next()?;
+
+        info!("Updated persona for user {user_id} to {persona}");
+        Ok(())
+    }
+
+    /// Set a user's persona preference scoped to one guild
+    /// Pass None for the guild to set the DM (global) preference instead
+    pub async fn set_user_persona_in_guild(
         &self,
-        model: &str,
-        input_tokens: u32,
-        output_tokens: u32,
-        total_tokens: u32,
-        estimated_cost: f64,
         user_id: &str,
         guild_id: Option<&str>,
-        channel_id: Option<&str>,
-        request_id: Option<&str>,
-        cost_bucket: &str,
+        persona: &str,
     ) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
+        let Some(guild_id) = guild_id else {
+            return self.set_user_persona(user_id, persona).await;
+        };
 
-        // Insert into raw usage table
+        let conn = self.connection.lock().await?;
         let mut statement = conn.prepare(
-            "INSERT INTO openai_usage
-             (request_id, user_id, guild_id, channel_id, service_type, model,
-              input_tokens, output_tokens, total_tokens, estimated_cost_usd, cost_bucket)
-             VALUES (?, ?, ?, ?, 'chat', ?, ?, ?, ?, ?, ?)",
+            "INSERT INTO guild_user_personas (guild_id, user_id, default_persona, updated_at)
+             VALUES (?, ?, ?, CURRENT_TIMESTAMP)
+             ON CONFLICT(guild_id, user_id) DO UPDATE SET
+             default_persona = excluded.default_persona,
+             updated_at = CURRENT_TIMESTAMP",
         )?;
-        statement.bind((1, request_id.unwrap_or("")))?;
+        statement.bind((1, guild_id))?;
         statement.bind((2, user_id))?;
-        statement.bind((3, guild_id.unwrap_or("")))?;
-        statement.bind((4, channel_id.unwrap_or("")))?;
-        statement.bind((5, model))?;
-        statement.bind((6, input_tokens as i64))?;
-        statement.bind((7, output_tokens as i64))?;
-        statement.bind((8, total_tokens as i64))?;
-        statement.bind((9, estimated_cost))?;
-        statement.bind((10, cost_bucket))?;
+        statement.bind((3, persona))?;
         statement.next()?;
 
-        // Update daily aggregate
-        drop(statement);
-        let mut agg_stmt = conn.prepare(
-            "INSERT INTO openai_usage_daily
-             (date, guild_id, user_id, service_type, request_count, total_tokens, total_cost_usd, cost_bucket)
-             VALUES (?, ?, ?, 'chat', 1, ?, ?, ?)
-             ON CONFLICT(date, guild_id, user_id, service_type) DO UPDATE SET
-             request_count = request_count + 1,
-             total_tokens = total_tokens + excluded.total_tokens,
-             total_cost_usd = total_cost_usd + excluded.total_cost_usd",
-        )?;
-        agg_stmt.bind((1, date.as_str()))?;
-        agg_stmt.bind((2, guild_id.unwrap_or("")))?;
-        agg_stmt.bind((3, user_id))?;
-        agg_stmt.bind((4, total_tokens as i64))?;
-        agg_stmt.bind((5, estimated_cost))?;
-        agg_stmt.bind((6, cost_bucket))?;
-        agg_stmt.next()?;
+        info!("Updated persona for user {user_id} in guild {guild_id} to {persona}");
+        Ok(())
+    }
 
+    // User Cache Methods - for TUI username display
+
+    /// Cache a Discord user's information for TUI display
+    pub async fn cache_user(
+        &self,
+        user_id: u64,
+        username: &str,
+        discriminator: u16,
+        is_bot: bool,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO user_cache (user_id, username, discriminator, is_bot, last_seen, updated_at)
+             VALUES (?, ?, ?, ?, strftime('%s', 'now'), strftime('%s', 'now'))
+             ON CONFLICT(user_id) DO UPDATE SET
+             username = excluded.username,
+             discriminator = excluded.discriminator,
+             is_bot = excluded.is_bot,
+             last_seen = strftime('%s', 'now'),
+             updated_at = strftime('%s', 'now')"
+        )?;
+        statement.bind((1, user_id.to_string().as_str()))?;
+        statement.bind((2, username))?;
+        statement.bind((3, discriminator.to_string().as_str()))?;
+        statement.bind((4, if is_bot { 1i64 } else { 0i64 }))?;
+        statement.next()?;
         Ok(())
     }
 
-    /// Log a Whisper (audio transcription) usage event
-    pub async fn log_openai_whisper_usage(
+    /// Get a cached username by user ID
+    pub async fn get_cached_username(&self, user_id: &str) -> Result<Option<String>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare("SELECT username FROM user_cache WHERE user_id = ?")?;
+        statement.bind((1, user_id))?;
+
+        if let Ok(State::Row) = statement.next() {
+            Ok(Some(statement.read::<String, _>(0)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Record a command use; `guild_id` is None in DMs
+    pub async fn log_usage(
         &self,
-        audio_duration_seconds: f64,
-        estimated_cost: f64,
         user_id: &str,
         guild_id: Option<&str>,
-        channel_id: Option<&str>,
-        cost_bucket: &str,
+        command: &str,
+        persona: Option<&str>,
     ) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
-
-        // Insert into raw usage table
+        let conn = self.connection.lock().await?;
         let mut statement = conn.prepare(
-            "INSERT INTO openai_usage
-             (user_id, guild_id, channel_id, service_type, model,
-              audio_duration_seconds, estimated_cost_usd, cost_bucket)
-             VALUES (?, ?, ?, 'whisper', 'whisper-1', ?, ?, ?)",
+            "INSERT INTO usage_stats (user_id, guild_id, command, persona) VALUES (?, ?, ?, ?)",
         )?;
         statement.bind((1, user_id))?;
-        statement.bind((2, guild_id.unwrap_or("")))?;
-        statement.bind((3, channel_id.unwrap_or("")))?;
-        statement.bind((4, audio_duration_seconds))?;
-        statement.bind((5, estimated_cost))?;
-        statement.bind((6, cost_bucket))?;
+        statement.bind((2, guild_id))?;
+        statement.bind((3, command))?;
+        statement.bind((4, persona.unwrap_or("")))?;
         statement.next()?;
-
-        // Update daily aggregate
-        drop(statement);
-        let mut agg_stmt = conn.prepare(
-            "INSERT INTO openai_usage_daily
-             (date, guild_id, user_id, service_type, request_count, total_audio_seconds, total_cost_usd, cost_bucket)
-             VALUES (?, ?, ?, 'whisper', 1, ?, ?, ?)
-             ON CONFLICT(date, guild_id, user_id, service_type) DO UPDATE SET
-             request_count = request_count + 1,
-             total_audio_seconds = total_audio_seconds + excluded.total_audio_seconds,
-             total_cost_usd = total_cost_usd + excluded.total_cost_usd",
-        )?;
-        agg_stmt.bind((1, date.as_str()))?;
-        agg_stmt.bind((2, guild_id.unwrap_or("")))?;
-        agg_stmt.bind((3, user_id))?;
-        agg_stmt.bind((4, audio_duration_seconds))?;
-        agg_stmt.bind((5, estimated_cost))?;
-        agg_stmt.bind((6, cost_bucket))?;
-        agg_stmt.next()?;
-
         Ok(())
     }
 
-    /// Log a DALL-E (image generation) usage event
-    pub async fn log_openai_dalle_usage(
+    pub async fn store_message(
         &self,
-        image_size: &str,
-        image_count: u32,
-        estimated_cost: f64,
         user_id: &str,
-        guild_id: Option<&str>,
-        channel_id: Option<&str>,
-        cost_bucket: &str,
+        channel_id: &str,
+        role: &str,
+        content: &str,
+        persona: Option<&str>,
     ) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
+        self.store_guild_message(None, user_id, channel_id, role, content, persona)
+            .await
+    }
 
-        // Insert into raw usage table
+    /// Store a message tagged with its guild so it can be found by guild-scoped search
+    pub async fn store_guild_message(
+        &self,
+        guild_id: Option<&str>,
+        user_id: &str,
+        channel_id: &str,
+        role: &str,
+        content: &str,
+        persona: Option<&str>,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
         let mut statement = conn.prepare(
-            "INSERT INTO openai_usage
-             (user_id, guild_id, channel_id, service_type, model,
-              image_count, image_size, estimated_cost_usd, cost_bucket)
-             VALUES (?, ?, ?, 'dalle', 'dall-e-3', ?, ?, ?, ?)",
+            "INSERT INTO conversation_history (user_id, channel_id, role, content, persona, guild_id) VALUES (?, ?, ?, ?, ?, ?)"
         )?;
         statement.bind((1, user_id))?;
-        statement.bind((2, guild_id.unwrap_or("")))?;
-        statement.bind((3, channel_id.unwrap_or("")))?;
-        statement.bind((4, image_count as i64))?;
-        statement.bind((5, image_size))?;
-        statement.bind((6, estimated_cost))?;
-        statement.bind((7, cost_bucket))?;
+        statement.bind((2, channel_id))?;
+        statement.bind((3, role))?;
+        statement.bind((4, content))?;
+        statement.bind((5, persona.unwrap_or("")))?;
+        statement.bind((6, guild_id))?;
         statement.next()?;
-
-        // Update daily aggregate
-        drop(statement);
-        let mut agg_stmt = conn.prepare(
-            "INSERT INTO openai_usage_daily
-             (date, guild_id, user_id, service_type, request_count, total_images, total_cost_usd, cost_bucket)
-             VALUES (?, ?, ?, 'dalle', 1, ?, ?, ?)
-             ON CONFLICT(date, guild_id, user_id, service_type) DO UPDATE SET
-             request_count = request_count + 1,
-             total_images = total_images + excluded.total_images,
-             total_cost_usd = total_cost_usd + excluded.total_cost_usd",
-        )?;
-        agg_stmt.bind((1, date.as_str()))?;
-        agg_stmt.bind((2, guild_id.unwrap_or("")))?;
-        agg_stmt.bind((3, user_id))?;
-        agg_stmt.bind((4, image_count as i64))?;
-        agg_stmt.bind((5, estimated_cost))?;
-        agg_stmt.bind((6, cost_bucket))?;
-        agg_stmt.next()?;
-
         Ok(())
     }
 
-    /// Get total cost grouped by cost bucket
-    /// Returns Vec of (bucket_name, total_cost_usd)
-    pub async fn get_cost_by_bucket(&self, period_days: Option<u32>) -> Result<Vec<(String, f64)>> {
-        let conn = self.connection.lock().await;
+    /// Store a batch of messages in a single transaction
+    ///
+    /// Used by the write-behind `MessageWriter` so high-volume channels cost one
+    /// commit per batch instead of one per message.
+    pub async fn store_messages_batch(&self, messages: &[StoredMessage]) -> Result<()> {
+        if messages.is_empty() {
+            return Ok(());
+        }
 
-        let query = match period_days {
-            Some(days) => {
-                let mut stmt = conn.prepare(
-                    "SELECT cost_bucket, SUM(estimated_cost_usd) as cost
-                     FROM openai_usage
-                     WHERE timestamp >= datetime('now', ? || ' days')
-                     GROUP BY cost_bucket
-                     ORDER BY cost DESC",
-                )?;
-                stmt.bind((1, -(days as i64)))?;
-                let mut results = Vec::new();
-                while let sqlite::State::Row = stmt.next()? {
-                    let bucket: String = stmt.read(0)?;
-                    let cost: f64 = stmt.read(1)?;
-                    results.push((bucket, cost));
-                }
-                results
-            }
-            None => {
-                let mut stmt = conn.prepare(
-                    "SELECT cost_bucket, SUM(estimated_cost_usd) as cost
-                     FROM openai_usage
-                     GROUP BY cost_bucket
-                     ORDER BY cost DESC",
-                )?;
-                let mut results = Vec::new();
-                while let sqlite::State::Row = stmt.next()? {
-                    let bucket: String = stmt.read(0)?;
-                    let cost: f64 = stmt.read(1)?;
-                    results.push((bucket, cost));
-                }
-                results
+        let conn = self.connection.lock().await?;
+        conn.execute("BEGIN IMMEDIATE")?;
+
+        let result = (|| -> Result<()> {
+            let mut statement = conn.prepare(
+                "INSERT INTO conversation_history (user_id, channel_id, role, content, persona, guild_id) VALUES (?, ?, ?, ?, ?, ?)"
+            )?;
+            for message in messages {
+                statement.reset()?;
+                statement.bind((1, message.user_id.as_str()))?;
+                statement.bind((2, message.channel_id.as_str()))?;
+                statement.bind((3, message.role.as_str()))?;
+                statement.bind((4, message.content.as_str()))?;
+                statement.bind((5, message.persona.as_deref().unwrap_or("")))?;
+                statement.bind((6, message.guild_id.as_deref()))?;
+                statement.next()?;
             }
-        };
+            Ok(())
+        })();
 
-        Ok(query)
+        match result {
+            Ok(()) => {
+                conn.execute("COMMIT")?;
+                Ok(())
+            }
+            Err(e) => {
+                let _ = conn.execute("ROLLBACK");
+                Err(e)
+            }
+        }
     }
 
-    /// Get usage statistics for a user within a date range
-    /// Returns (service_type, request_count, tokens, audio_seconds, images, cost)
-    pub async fn get_user_usage_stats(
+    pub async fn get_conversation_history(
         &self,
         user_id: &str,
-        days: i64,
-    ) -> Result<Vec<(String, i64, i64, f64, i64, f64)>> {
-        let conn = self.connection.lock().await;
+        channel_id: &str,
+        limit: i64,
+    ) -> Result<Vec<(String, String)>> {
+        let conn = self.connection.lock().await?;
         let mut statement = conn.prepare(
-            "SELECT service_type,
-                    SUM(request_count) as requests,
-                    SUM(total_tokens) as tokens,
-                    SUM(total_audio_seconds) as audio_secs,
-                    SUM(total_images) as images,
-                    SUM(total_cost_usd) as cost
-             FROM openai_usage_daily
-             WHERE user_id = ? AND date >= date('now', ? || ' days')
-             GROUP BY service_type",
+            "SELECT role, content FROM conversation_history
+             WHERE user_id = ? AND channel_id = ?
+             ORDER BY timestamp DESC, id DESC
+             LIMIT ?",
         )?;
         statement.bind((1, user_id))?;
-        statement.bind((2, format!("-{days}").as_str()))?;
+        statement.bind((2, channel_id))?;
+        statement.bind((3, limit))?;
 
-        let mut results = Vec::new();
+        let mut history = Vec::new();
         while let Ok(State::Row) = statement.next() {
-            let service_type = statement.read::<String, _>(0)?;
-            let requests = statement.read::<i64, _>(1)?;
-            let tokens = statement.read::<i64, _>(2)?;
-            let audio_secs = statement.read::<f64, _>(3)?;
-            let images = statement.read::<i64, _>(4)?;
-            let cost = statement.read::<f64, _>(5)?;
-            results.push((service_type, requests, tokens, audio_secs, images, cost));
+            let role = statement.read::<String, _>("role")?;
+            let content = statement.read::<String, _>("content")?;
+            history.push((role, content));
         }
-        Ok(results)
+
+        // Reverse to get chronological order (oldest first)
+        history.reverse();
+        Ok(history)
     }
 
-    /// Get usage statistics for an entire guild within a date range
-    /// Includes DM usage from users who have interacted in this guild
-    /// Returns (service_type, request_count, tokens, audio_seconds, images, cost)
-    pub async fn get_guild_usage_stats(
+    /// Seed a new thread with a copy of another conversation's history
+    ///
+    /// The copies carry no guild so forks don't duplicate guild search results.
+    pub async fn fork_conversation(
         &self,
-        guild_id: &str,
-        days: i64,
-    ) -> Result<Vec<(String, i64, i64, f64, i64, f64)>> {
-        let conn = self.connection.lock().await;
-        let days_str = format!("-{days}");
+        thread_id: &str,
+        source_channel_id: &str,
+        user_id: &str,
+        messages: &[(String, String)],
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        conn.execute("BEGIN IMMEDIATE")?;
+
+        let result = (|| -> Result<()> {
+            let mut statement = conn.prepare(
+                "INSERT INTO conversation_history (user_id, channel_id, role, content, persona) VALUES (?, ?, ?, ?, '')",
+            )?;
+            for (role, content) in messages {
+                statement.reset()?;
+                statement.bind((1, user_id))?;
+                statement.bind((2, thread_id))?;
+                statement.bind((3, role.as_str()))?;
+                statement.bind((4, content.as_str()))?;
+                statement.next()?;
+            }
+
+            let mut statement = conn.prepare(
+                "INSERT OR REPLACE INTO conversation_forks (thread_id, source_channel_id, user_id, message_count)
+                 VALUES (?, ?, ?, ?)",
+            )?;
+            statement.bind((1, thread_id))?;
+            statement.bind((2, source_channel_id))?;
+            statement.bind((3, user_id))?;
+            statement.bind((4, messages.len() as i64))?;
+            statement.next()?;
+            Ok(())
+        })();
+
+        match result {
+            Ok(()) => {
+                conn.execute("COMMIT")?;
+                info!(
+                    "Forked {} messages from channel {source_channel_id} into thread {thread_id}",
+                    messages.len()
+                );
+                Ok(())
+            }
+            Err(e) => {
+                let _ = conn.execute("ROLLBACK");
+                Err(e)
+            }
+        }
+    }
+
+    /// Where a forked thread came from, if it is one
+    pub async fn get_conversation_fork(&self, thread_id: &str) -> Result<Option<ConversationFork>> {
+        let conn = self.connection.lock().await?;
         let mut statement = conn.prepare(
-            "SELECT service_type,
-                    SUM(request_count) as requests,
-                    SUM(total_tokens) as tokens,
-                    SUM(total_audio_seconds) as audio_secs,
-                    SUM(total_images) as images,
-                    SUM(total_cost_usd) as cost
-             FROM openai_usage_daily
-             WHERE (guild_id = ? OR (guild_id = '' AND user_id IN (
-                 SELECT DISTINCT user_id FROM openai_usage_daily WHERE guild_id = ?
-             )))
-             AND date >= date('now', ? || ' days')
-             GROUP BY service_type",
+            "SELECT source_channel_id, user_id, message_count FROM conversation_forks WHERE thread_id = ?",
         )?;
-        statement.bind((1, guild_id))?;
-        statement.bind((2, guild_id))?;
-        statement.bind((3, days_str.as_str()))?;
+        statement.bind((1, thread_id))?;
 
-        let mut results = Vec::new();
-        while let Ok(State::Row) = statement.next() {
-            let service_type = statement.read::<String, _>(0)?;
-            let requests = statement.read::<i64, _>(1)?;
-            let tokens = statement.read::<i64, _>(2)?;
-            let audio_secs = statement.read::<f64, _>(3)?;
-            let images = statement.read::<i64, _>(4)?;
-            let cost = statement.read::<f64, _>(5)?;
-            results.push((service_type, requests, tokens, audio_secs, images, cost));
+        if let Ok(State::Row) = statement.next() {
+            Ok(Some(ConversationFork {
+                thread_id: thread_id.to_string(),
+                source_channel_id: statement.read::<String, _>(0)?,
+                user_id: statement.read::<String, _>(1)?,
+                message_count: statement.read::<i64, _>(2)?,
+            }))
+        } else {
+            Ok(None)
         }
-        Ok(results)
     }
 
-    /// Get top users by cost for a guild
-    /// Includes DM usage from users who have interacted in this guild
-    /// Returns (user_id, request_count, total_cost)
-    pub async fn get_guild_top_users_by_cost(
+    /// Context copied into a forked thread (oldest first, empty for other channels)
+    pub async fn get_fork_context(
         &self,
-        guild_id: &str,
-        days: i64,
+        thread_id: &str,
         limit: i64,
-    ) -> Result<Vec<(String, i64, f64)>> {
-        let conn = self.connection.lock().await;
-        let days_str = format!("-{days}");
+    ) -> Result<Vec<(String, String)>> {
+        let conn = self.connection.lock().await?;
         let mut statement = conn.prepare(
-            "SELECT user_id,
-                    SUM(request_count) as requests,
-                    SUM(total_cost_usd) as cost
-             FROM openai_usage_daily
-             WHERE (guild_id = ? OR (guild_id = '' AND user_id IN (
-                 SELECT DISTINCT user_id FROM openai_usage_daily WHERE guild_id = ?
-             )))
-             AND user_id != ''
-             AND date >= date('now', ? || ' days')
-             GROUP BY user_id
-             ORDER BY cost DESC
+            "SELECT h.role, h.content FROM conversation_history h
+             JOIN conversation_forks f ON h.channel_id = f.thread_id AND h.user_id = f.user_id
+             WHERE f.thread_id = ?
+             ORDER BY h.id DESC
              LIMIT ?",
         )?;
-        statement.bind((1, guild_id))?;
-        statement.bind((2, guild_id))?;
-        statement.bind((3, days_str.as_str()))?;
-        statement.bind((4, limit))?;
+        statement.bind((1, thread_id))?;
+        statement.bind((2, limit))?;
 
-        let mut results = Vec::new();
+        let mut history = Vec::new();
         while let Ok(State::Row) = statement.next() {
-            let user_id = statement.read::<String, _>(0)?;
-            let requests = statement.read::<i64, _>(1)?;
-            let cost = statement.read::<f64, _>(2)?;
-            results.push((user_id, requests, cost));
+            history.push((
+                statement.read::<String, _>(0)?,
+                statement.read::<String, _>(1)?,
+            ));
         }
-        Ok(results)
+        history.reverse();
+        Ok(history)
     }
 
-    /// Get global usage statistics across all users and guilds
-    /// Returns (total_cost, period_cost, total_tokens, total_calls, cost_by_service, cost_by_bucket, daily_breakdown, top_users)
-    #[allow(clippy::type_complexity)]
-    pub async fn get_global_usage_stats(
+    /// Remove the most recent user message and the replies after it
+    ///
+    /// Returns the removed (role, content) rows, oldest first; empty if there
+    /// was no user message to undo.
+    pub async fn undo_last_exchange(
         &self,
-        period_days: Option<u32>,
-    ) -> Result<(
-        f64,
-        f64,
-        u64,
-        u64,
-        Vec<(String, f64)>,
-        Vec<(String, f64)>,
-        Vec<(String, f64)>,
-        Vec<(String, Option<String>, f64)>,
-    )> {
-        let conn = self.connection.lock().await;
-
-        // Total cost (all time)
-        let total_cost: f64 = {
-            let mut stmt =
-                conn.prepare("SELECT COALESCE(SUM(total_cost_usd), 0) FROM openai_usage_daily")?;
-            if stmt.next()? == State::Row {
-                stmt.read::<f64, _>(0)?
-            } else {
-                0.0
-            }
+        user_id: &str,
+        channel_id: &str,
+    ) -> Result<Vec<(String, String)>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT MAX(id) FROM conversation_history
+             WHERE user_id = ? AND channel_id = ? AND role = 'user'",
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, channel_id))?;
+        let last_user_id = match statement.next() {
+            Ok(State::Row) => statement.read::<Option<i64>, _>(0)?,
+            _ => None,
         };
-
-        // Period cost
-        let period_cost: f64 = if let Some(days) = period_days {
-            let days_str = format!("-{days}");
-            let mut stmt = conn.prepare(
-                "SELECT COALESCE(SUM(total_cost_usd), 0) FROM openai_usage_daily WHERE date >= date('now', ? || ' days')"
-            )?;
-            stmt.bind((1, days_str.as_str()))?;
-            if stmt.next()? == State::Row {
-                stmt.read::<f64, _>(0)?
-            } else {
-                0.0
-            }
-        } else {
-            total_cost
+        drop(statement);
+        let Some(last_user_id) = last_user_id else {
+            return Ok(Vec::new());
         };
 
-        // Total tokens (all time)
-        let total_tokens: u64 = {
-            let mut stmt =
-                conn.prepare("SELECT COALESCE(SUM(total_tokens), 0) FROM openai_usage_daily")?;
-            if stmt.next()? == State::Row {
-                stmt.read::<i64, _>(0)? as u64
-            } else {
-                0
-            }
-        };
+        let mut statement = conn.prepare(
+            "SELECT role, content FROM conversation_history
+             WHERE user_id = ? AND channel_id = ? AND id >= ?
+             ORDER BY id",
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, channel_id))?;
+        statement.bind((3, last_user_id))?;
+        let mut removed = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            removed.push((
+                statement.read::<String, _>(0)?,
+                statement.read::<String, _>(1)?,
+            ));
+        }
+        drop(statement);
 
-        // Total calls (all time)
-        let total_calls: u64 = {
-            let mut stmt =
-                conn.prepare("SELECT COALESCE(SUM(request_count), 0) FROM openai_usage_daily")?;
-            if stmt.next()? == State::Row {
-                stmt.read::<i64, _>(0)? as u64
-            } else {
-                0
-            }
-        };
+        let mut statement = conn.prepare(
+            "DELETE FROM conversation_history WHERE user_id = ? AND channel_id = ? AND id >= ?",
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, channel_id))?;
+        statement.bind((3, last_user_id))?;
+        statement.next()?;
 
-        // Cost by service (for period or all time)
-        let cost_by_service: Vec<(String, f64)> = {
-            let mut results = Vec::new();
-            let query = if let Some(days) = period_days {
-                format!(
-                    "SELECT service_type, SUM(total_cost_usd) as cost FROM openai_usage_daily \
-                     WHERE date >= date('now', '-{days} days') GROUP BY service_type ORDER BY cost DESC"
-                )
-            } else {
-                "SELECT service_type, SUM(total_cost_usd) as cost FROM openai_usage_daily \
-                 GROUP BY service_type ORDER BY cost DESC"
-                    .to_string()
-            };
-            let mut stmt = conn.prepare(&query)?;
-            while let Ok(State::Row) = stmt.next() {
-                let service = stmt.read::<String, _>(0)?;
-                let cost = stmt.read::<f64, _>(1)?;
-                results.push((service, cost));
-            }
-            results
-        };
+        info!(
+            "Undid last exchange ({} messages) for user {user_id} in channel {channel_id}",
+            removed.len()
+        );
+        Ok(removed)
+    }
 
-        // Cost by bucket (for period or all time)
-        let cost_by_bucket: Vec<(String, f64)> = {
-            let mut results = Vec::new();
-            let query = if let Some(days) = period_days {
-                format!(
-                    "SELECT cost_bucket, SUM(estimated_cost_usd) as cost FROM openai_usage \
-                     WHERE timestamp >= datetime('now', '-{days} days') GROUP BY cost_bucket ORDER BY cost DESC"
-                )
-            } else {
-                "SELECT cost_bucket, SUM(estimated_cost_usd) as cost FROM openai_usage \
-                 GROUP BY cost_bucket ORDER BY cost DESC"
-                    .to_string()
-            };
-            let mut stmt = conn.prepare(&query)?;
-            while let Ok(State::Row) = stmt.next() {
-                let bucket = stmt.read::<String, _>(0)?;
-                let cost = stmt.read::<f64, _>(1)?;
-                results.push((bucket, cost));
-            }
-            results
-        };
+    pub async fn clear_conversation_history(&self, user_id: &str, channel_id: &str) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("DELETE FROM conversation_history WHERE user_id = ? AND channel_id = ?")?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, channel_id))?;
+        statement.next()?;
+        info!("Cleared conversation history for user {user_id} in channel {channel_id}");
+        Ok(())
+    }
 
-        // Daily breakdown (last 14 days or period)
-        let daily_breakdown: Vec<(String, f64)> = {
-            let mut results = Vec::new();
-            let days = period_days.unwrap_or(14);
-            let mut stmt = conn.prepare(
-                "SELECT date, SUM(total_cost_usd) as cost FROM openai_usage_daily \
-                 WHERE date >= date('now', ? || ' days') GROUP BY date ORDER BY date ASC",
-            )?;
-            let days_str = format!("-{days}");
-            stmt.bind((1, days_str.as_str()))?;
-            while let Ok(State::Row) = stmt.next() {
-                let date = stmt.read::<String, _>(0)?;
-                let cost = stmt.read::<f64, _>(1)?;
-                results.push((date, cost));
-            }
-            results
+    /// Full-text search over stored messages, newest first
+    ///
+    /// `guild_id` restricts results to one guild (always set for /search);
+    /// `None` searches everything and is reserved for the bot owner via the TUI.
+    pub async fn search_messages(
+        &self,
+        query: &str,
+        guild_id: Option<&str>,
+        channel_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<MessageSearchResult>> {
+        let match_query = match fts_match_query(query) {
+            Some(q) => q,
+            None => return Ok(Vec::new()),
         };
 
-        // Top users by cost (for period or all time, limit 10) with cached usernames
-        let top_users: Vec<(String, Option<String>, f64)> = {
-            let mut results = Vec::new();
-            let query = if let Some(days) = period_days {
-                format!(
-                    "SELECT u.user_id, uc.username, SUM(u.total_cost_usd) as cost \
-                     FROM openai_usage_daily u \
-                     LEFT JOIN user_cache uc ON u.user_id = uc.user_id \
-                     WHERE u.user_id != '' AND u.date >= date('now', '-{days} days') \
-                     GROUP BY u.user_id ORDER BY cost DESC LIMIT 10"
-                )
-            } else {
-                "SELECT u.user_id, uc.username, SUM(u.total_cost_usd) as cost \
-                 FROM openai_usage_daily u \
-                 LEFT JOIN user_cache uc ON u.user_id = uc.user_id \
-                 WHERE u.user_id != '' GROUP BY u.user_id ORDER BY cost DESC LIMIT 10"
-                    .to_string()
-            };
-            let mut stmt = conn.prepare(&query)?;
-            while let Ok(State::Row) = stmt.next() {
-                let user_id = stmt.read::<String, _>(0)?;
-                let username = stmt.read::<Option<String>, _>(1)?;
-                let cost = stmt.read::<f64, _>(2)?;
-                results.push((user_id, username, cost));
-            }
-            results
-        };
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT ch.id, ch.guild_id, ch.channel_id, ch.user_id,
+                    COALESCE(uc.display_name, uc.username), ch.role,
+                    snippet(conversation_fts, 0, '**', '**', '...', 24), ch.timestamp
+             FROM conversation_fts
+             JOIN conversation_history ch ON ch.id = conversation_fts.rowid
+             LEFT JOIN user_cache uc ON uc.user_id = ch.user_id
+             WHERE conversation_fts MATCH ?1
+               AND (?2 IS NULL OR ch.guild_id = ?2)
+               AND (?3 IS NULL OR ch.channel_id = ?3)
+             ORDER BY ch.timestamp DESC, ch.id DESC
+             LIMIT ?4",
+        )?;
+        statement.bind((1, match_query.as_str()))?;
+        statement.bind((2, guild_id))?;
+        statement.bind((3, channel_id))?;
+        statement.bind((4, limit))?;
 
-        Ok((
-            total_cost,
-            period_cost,
-            total_tokens,
-            total_calls,
-            cost_by_service,
-            cost_by_bucket,
-            daily_breakdown,
-            top_users,
-        ))
-    }
+        let mut results = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            results.push(MessageSearchResult {
+                id: statement.read::<i64, _>(0)?,
+                guild_id: statement.read::<Option<String>, _>(1)?,
+                channel_id: statement.read::<String, _>(2)?,
+                user_id: statement.read::<String, _>(3)?,
+                username: statement.read::<Option<String>, _>(4)?,
+                role: statement.read::<String, _>(5)?,
+                snippet: statement.read::<String, _>(6)?,
+                timestamp: statement.read::<String, _>(7)?,
+            });
+        }
 
-    /// Cleanup old raw usage data (keep last N days)
-    pub async fn cleanup_old_openai_usage(&self, days: i64) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn
-            .prepare("DELETE FROM openai_usage WHERE timestamp < datetime('now', ? || ' days')")?;
-        statement.bind((1, format!("-{days}").as_str()))?;
-        statement.next()?;
-        info!("Cleaned up openai_usage older than {days} days");
-        Ok(())
+        Ok(results)
     }
 
-    /// Cleanup old daily aggregates (keep last N days)
-    pub async fn cleanup_old_openai_usage_daily(&self, days: i64) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement =
-            conn.prepare("DELETE FROM openai_usage_daily WHERE date < date('now', ? || ' days')")?;
+    pub async fn cleanup_old_messages(&self, days: i64) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "DELETE FROM conversation_history WHERE timestamp < datetime('now', ? || ' days')",
+        )?;
         statement.bind((1, format!("-{days}").as_str()))?;
         statement.next()?;
-        info!("Cleaned up openai_usage_daily older than {days} days");
+        info!("Cleaned up conversation history older than {days} days");
         Ok(())
     }
 
-    // DM Interaction Tracking Methods
-
-    /// Create a new DM session
-    pub async fn create_dm_session(
+    // Message Metadata Methods
+    pub async fn store_message_metadata(
         &self,
-        session_id: &str,
+        message_id: &str,
         user_id: &str,
         channel_id: &str,
+        attachment_urls: Option<&str>,
+        embed_data: Option<&str>,
+        reactions: Option<&str>,
     ) -> Result<()> {
-        let conn = self.connection.lock().await;
+        let conn = self.connection.lock().await?;
         let mut statement = conn.prepare(
-            "INSERT INTO dm_sessions (session_id, user_id, channel_id) VALUES (?, ?, ?)",
+            "INSERT INTO message_metadata (message_id, user_id, channel_id, attachment_urls, embed_data, reactions)
+             VALUES (?, ?, ?, ?, ?, ?)"
         )?;
-        statement.bind((1, session_id))?;
+        statement.bind((1, message_id))?;
         statement.bind((2, user_id))?;
         statement.bind((3, channel_id))?;
+        statement.bind((4, attachment_urls.unwrap_or("")))?;
+        statement.bind((5, embed_data.unwrap_or("")))?;
+        statement.bind((6, reactions.unwrap_or("")))?;
         statement.next()?;
+        Ok(())
+    }
 
-        // Also create metrics row
-        let mut metrics_stmt =
-            conn.prepare("INSERT INTO dm_session_metrics (session_id) VALUES (?)")?;
-        metrics_stmt.bind((1, session_id))?;
-        metrics_stmt.next()?;
+    pub async fn update_message_metadata_reactions(
+        &self,
+        message_id: &str,
+        reactions: &str,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("UPDATE message_metadata SET reactions = ? WHERE message_id = ?")?;
+        statement.bind((1, reactions))?;
+        statement.bind((2, message_id))?;
+        statement.next()?;
+        Ok(())
+    }
 
+    pub async fn mark_message_deleted(&self, message_id: &str) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "UPDATE message_metadata SET deleted_at = CURRENT_TIMESTAMP WHERE message_id = ?",
+        )?;
+        statement.bind((1, message_id))?;
+        statement.next()?;
         Ok(())
     }
 
-    /// End a DM session
-    pub async fn end_dm_session(&self, session_id: &str, reason: &str) -> Result<()> {
-        let conn = self.connection.lock().await;
+    pub async fn mark_message_edited(&self, message_id: &str) -> Result<()> {
+        let conn = self.connection.lock().await?;
         let mut statement = conn.prepare(
-            "UPDATE dm_sessions SET ended_at = CURRENT_TIMESTAMP, end_reason = ? WHERE session_id = ?"
+            "UPDATE message_metadata SET edited_at = CURRENT_TIMESTAMP WHERE message_id = ?",
         )?;
-        statement.bind((1, reason))?;
-        statement.bind((2, session_id))?;
+        statement.bind((1, message_id))?;
         statement.next()?;
         Ok(())
     }
 
-    /// Update DM session activity
-    pub async fn update_dm_session_activity(
-        &self,
-        session_id: &str,
-        msg_count: i32,
-        user_chars: i32,
-        bot_chars: i32,
-        avg_response_time: i32,
-    ) -> Result<()> {
-        let conn = self.connection.lock().await;
+    // Interaction Session Methods
+    pub async fn start_session(&self, user_id: &str, guild_id: Option<&str>) -> Result<i64> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("INSERT INTO interaction_sessions (user_id, guild_id) VALUES (?, ?)")?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, guild_id.unwrap_or("")))?;
+        statement.next()?;
+
+        // Get the last inserted row id
+        let mut stmt = conn.prepare("SELECT last_insert_rowid()")?;
+        stmt.next()?;
+        let session_id = stmt.read::<i64, _>(0)?;
+        Ok(session_id)
+    }
+
+    pub async fn update_session_activity(&self, session_id: i64) -> Result<()> {
+        let conn = self.connection.lock().await?;
         let mut statement = conn.prepare(
-            "UPDATE dm_sessions
-             SET message_count = ?,
-                 total_user_chars = ?,
-                 total_bot_chars = ?,
-                 avg_response_time_ms = ?,
-                 last_activity_at = CURRENT_TIMESTAMP
-             WHERE session_id = ?",
+            "UPDATE interaction_sessions
+             SET message_count = message_count + 1, last_activity = CURRENT_TIMESTAMP
+             WHERE id = ?",
         )?;
-        statement.bind((1, msg_count as i64))?;
-        statement.bind((2, user_chars as i64))?;
-        statement.bind((3, bot_chars as i64))?;
-        statement.bind((4, avg_response_time as i64))?;
-        statement.bind((5, session_id))?;
+        statement.bind((1, session_id))?;
         statement.next()?;
         Ok(())
     }
 
-    /// Log a DM event
-    pub async fn log_dm_event(
-        &self,
-        session_id: &str,
-        event_type: &str,
-        user_id: &str,
-        channel_id: &str,
-        event_data: Option<&str>,
-    ) -> Result<()> {
-        let conn = self.connection.lock().await;
+    pub async fn end_session(&self, session_id: i64) -> Result<()> {
+        let conn = self.connection.lock().await?;
         let mut statement = conn.prepare(
-            "INSERT INTO dm_events (session_id, event_type, user_id, channel_id, event_data)
-             VALUES (?, ?, ?, ?, ?)",
+            "UPDATE interaction_sessions SET session_end = CURRENT_TIMESTAMP WHERE id = ?",
         )?;
         statement.bind((1, session_id))?;
-        statement.bind((2, event_type))?;
-        statement.bind((3, user_id))?;
-        statement.bind((4, channel_id))?;
-        statement.bind((5, event_data.unwrap_or("")))?;
         statement.next()?;
         Ok(())
     }
 
-    /// Update DM session metrics
-    pub async fn update_dm_session_metrics(
+    // User Bookmark Methods
+    pub async fn add_bookmark(
         &self,
-        session_id: &str,
-        api_type: &str,
-        tokens: u32,
-        cost: f64,
+        user_id: &str,
+        channel_id: &str,
+        message_id: &str,
+        bookmark_name: Option<&str>,
+        bookmark_note: Option<&str>,
     ) -> Result<()> {
-        let conn = self.connection.lock().await;
-
-        let (api_field, tokens_update) = match api_type {
-            "chat" => (
-                "chat_calls = chat_calls + 1",
-                format!("total_tokens = total_tokens + {tokens}"),
-            ),
-            "whisper" => ("whisper_calls = whisper_calls + 1", String::new()),
-            "dalle" => ("dalle_calls = dalle_calls + 1", String::new()),
-            _ => return Ok(()),
-        };
-
-        let sql = if tokens_update.is_empty() {
-            format!(
-                "UPDATE dm_session_metrics
-                 SET {api_field},
-                     total_api_calls = total_api_calls + 1,
-                     total_api_cost_usd = total_api_cost_usd + ?,
-                     updated_at = CURRENT_TIMESTAMP
-                 WHERE session_id = ?"
-            )
-        } else {
-            format!(
-                "UPDATE dm_session_metrics
-                 SET {api_field},
-                     {tokens_update},
-                     total_api_calls = total_api_calls + 1,
-                     total_api_cost_usd = total_api_cost_usd + ?,
-                     updated_at = CURRENT_TIMESTAMP
-                 WHERE session_id = ?"
-            )
-        };
-
-        let mut statement = conn.prepare(&sql)?;
-        statement.bind((1, cost))?;
-        statement.bind((2, session_id))?;
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO user_bookmarks (user_id, channel_id, message_id, bookmark_name, bookmark_note)
+             VALUES (?, ?, ?, ?, ?)"
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, channel_id))?;
+        statement.bind((3, message_id))?;
+        statement.bind((4, bookmark_name.unwrap_or("")))?;
+        statement.bind((5, bookmark_note.unwrap_or("")))?;
         statement.next()?;
+        info!("Added bookmark for user {user_id}");
         Ok(())
     }
 
-    /// Increment DM session feature counter
-    pub async fn increment_dm_session_feature(
+    pub async fn get_user_bookmarks(
         &self,
-        session_id: &str,
-        feature: &str,
-    ) -> Result<()> {
-        let conn = self.connection.lock().await;
-
-        let field = match feature {
-            "audio" => "audio_transcriptions",
-            "slash_command" => "slash_commands_used",
-            _ => return Ok(()),
-        };
+        user_id: &str,
+    ) -> Result<Vec<(String, String, String, String)>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT message_id, channel_id, bookmark_name, bookmark_note
+             FROM user_bookmarks WHERE user_id = ?
+             ORDER BY created_at DESC",
+        )?;
+        statement.bind((1, user_id))?;
 
-        let sql = format!(
-            "UPDATE dm_session_metrics
-             SET {field} = {field} + 1, updated_at = CURRENT_TIMESTAMP
-             WHERE session_id = ?"
-        );
+        let mut bookmarks = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            let message_id = statement.read::<String, _>(0)?;
+            let channel_id = statement.read::<String, _>(1)?;
+            let bookmark_name = statement.read::<String, _>(2)?;
+            let bookmark_note = statement.read::<String, _>(3)?;
+            bookmarks.push((message_id, channel_id, bookmark_name, bookmark_note));
+        }
+        Ok(bookmarks)
+    }
 
-        let mut statement = conn.prepare(&sql)?;
-        statement.bind((1, session_id))?;
+    pub async fn delete_bookmark(&self, user_id: &str, message_id: &str) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("DELETE FROM user_bookmarks WHERE user_id = ? AND message_id = ?")?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, message_id))?;
         statement.next()?;
         Ok(())
     }
 
-    /// Get user DM stats for the last N days
-    pub async fn get_user_dm_stats(&self, user_id: &str, days: i64) -> Result<DmStats> {
-        let conn = self.connection.lock().await;
+    // Reminder Methods
+    pub async fn add_reminder(
+        &self,
+        user_id: &str,
+        channel_id: &str,
+        reminder_text: &str,
+        remind_at: &str,
+    ) -> Result<i64> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO reminders (user_id, channel_id, reminder_text, remind_at)
+             VALUES (?, ?, ?, ?)",
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, channel_id))?;
+        statement.bind((3, reminder_text))?;
+        statement.bind((4, remind_at))?;
+        statement.next()?;
 
-        // Get session counts and averages
-        let mut stmt = conn.prepare(
-            "SELECT
-                COUNT(*) as session_count,
-                SUM(message_count) as total_messages,
-                SUM(user_message_count) as user_messages,
-                SUM(bot_message_count) as bot_messages,
-                AVG(avg_response_time_ms) as avg_response_time,
-                AVG((julianday(ended_at) - julianday(started_at)) * 24 * 60) as avg_duration_min
-             FROM dm_sessions
-             WHERE user_id = ?
-             AND started_at >= datetime('now', ? || ' days')
-             AND ended_at IS NOT NULL",
+        let mut stmt = conn.prepare("SELECT last_insert_rowid()")?;
+        stmt.next()?;
+        let reminder_id = stmt.read::<i64, _>(0)?;
+        info!("Added reminder {reminder_id} for user {user_id}");
+        Ok(reminder_id)
+    }
+
+    pub async fn get_pending_reminders(&self) -> Result<Vec<(i64, String, String, String)>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT id, user_id, channel_id, reminder_text
+             FROM reminders
+             WHERE completed = 0 AND remind_at <= datetime('now')
+               AND channel_id NOT LIKE '%:%'
+             ORDER BY remind_at ASC",
         )?;
-        stmt.bind((1, user_id))?;
-        stmt.bind((2, format!("-{days}").as_str()))?;
 
-        let (
-            session_count,
-            total_messages,
-            user_messages,
-            bot_messages,
-            avg_response_time,
-            avg_duration,
-        ) = if let Ok(State::Row) = stmt.next() {
-            (
-                stmt.read::<i64, _>(0).unwrap_or(0),
-                stmt.read::<i64, _>(1).unwrap_or(0),
-                stmt.read::<i64, _>(2).unwrap_or(0),
-                stmt.read::<i64, _>(3).unwrap_or(0),
-                stmt.read::<i64, _>(4).unwrap_or(0),
-                stmt.read::<f64, _>(5).unwrap_or(0.0),
-            )
-        } else {
-            (0, 0, 0, 0, 0, 0.0)
-        };
+        let mut reminders = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            let id = statement.read::<i64, _>(0)?;
+            let user_id = statement.read::<String, _>(1)?;
+            let channel_id = statement.read::<String, _>(2)?;
+            let reminder_text = statement.read::<String, _>(3)?;
+            reminders.push((id, user_id, channel_id, reminder_text));
+        }
+        Ok(reminders)
+    }
 
-        // Get API metrics
-        let mut api_stmt = conn.prepare(
-            "SELECT
-                SUM(sm.total_api_calls) as api_calls,
-                SUM(sm.total_tokens) as tokens,
-                SUM(sm.total_api_cost_usd) as cost,
-                SUM(sm.chat_calls) as chat_calls,
-                SUM(sm.whisper_calls) as whisper_calls,
-                SUM(sm.dalle_calls) as dalle_calls,
-                SUM(sm.audio_transcriptions) as audio_count,
-                SUM(sm.slash_commands_used) as slash_count
-             FROM dm_session_metrics sm
-             JOIN dm_sessions s ON sm.session_id = s.session_id
-             WHERE s.user_id = ?
-             AND s.started_at >= datetime('now', ? || ' days')",
+    /// Due reminders set from another front end, whose channel IDs carry a
+    /// `<prefix>:` that keeps them away from the Discord scheduler
+    pub async fn get_pending_bridge_reminders(
+        &self,
+        prefix: &str,
+    ) -> Result<Vec<(i64, String, String, String)>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT id, user_id, channel_id, reminder_text
+             FROM reminders
+             WHERE completed = 0 AND remind_at <= datetime('now')
+               AND channel_id LIKE ? || ':%'
+             ORDER BY remind_at ASC",
         )?;
-        api_stmt.bind((1, user_id))?;
-        api_stmt.bind((2, format!("-{days}").as_str()))?;
+        statement.bind((1, prefix))?;
 
-        let (
-            api_calls,
-            tokens,
-            cost,
-            chat_calls,
-            whisper_calls,
-            dalle_calls,
-            audio_count,
-            slash_count,
-        ) = if let Ok(State::Row) = api_stmt.next() {
-            (
-                api_stmt.read::<i64, _>(0).unwrap_or(0),
-                api_stmt.read::<i64, _>(1).unwrap_or(0),
-                api_stmt.read::<f64, _>(2).unwrap_or(0.0),
-                api_stmt.read::<i64, _>(3).unwrap_or(0),
-                api_stmt.read::<i64, _>(4).unwrap_or(0),
-                api_stmt.read::<i64, _>(5).unwrap_or(0),
-                api_stmt.read::<i64, _>(6).unwrap_or(0),
-                api_stmt.read::<i64, _>(7).unwrap_or(0),
-            )
-        } else {
-            (0, 0, 0.0, 0, 0, 0, 0, 0)
-        };
+        let mut reminders = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            let id = statement.read::<i64, _>(0)?;
+            let user_id = statement.read::<String, _>(1)?;
+            let channel_id = statement.read::<String, _>(2)?;
+            let reminder_text = statement.read::<String, _>(3)?;
+            reminders.push((id, user_id, channel_id, reminder_text));
+        }
+        Ok(reminders)
+    }
 
-        Ok(DmStats {
-            session_count,
-            total_messages,
-            user_messages,
-            bot_messages,
-            avg_response_time_ms: avg_response_time,
-            avg_session_duration_min: avg_duration,
-            api_calls,
-            total_tokens: tokens,
-            total_cost_usd: cost,
-            chat_calls,
-            whisper_calls,
-            dalle_calls,
-            audio_transcriptions: audio_count,
-            slash_commands_used: slash_count,
-        })
+    pub async fn complete_reminder(&self, reminder_id: i64) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "UPDATE reminders SET completed = 1, completed_at = CURRENT_TIMESTAMP WHERE id = ?",
+        )?;
+        statement.bind((1, reminder_id))?;
+        statement.next()?;
+        Ok(())
     }
 
-    /// Get user's recent DM sessions
-    pub async fn get_user_recent_sessions(
+    pub async fn get_user_reminders(
         &self,
         user_id: &str,
-        limit: i64,
-    ) -> Result<Vec<SessionInfo>> {
-        let conn = self.connection.lock().await;
+    ) -> Result<Vec<(i64, String, String, String)>> {
+        let conn = self.connection.lock().await?;
         let mut statement = conn.prepare(
-            "SELECT session_id, started_at, ended_at, message_count, avg_response_time_ms
-             FROM dm_sessions
-             WHERE user_id = ?
-             ORDER BY started_at DESC
-             LIMIT ?",
+            "SELECT id, channel_id, reminder_text, remind_at
+             FROM reminders
+             WHERE user_id = ? AND completed = 0
+             ORDER BY remind_at ASC",
         )?;
         statement.bind((1, user_id))?;
-        statement.bind((2, limit))?;
 
-        let mut sessions = Vec::new();
+        let mut reminders = Vec::new();
         while let Ok(State::Row) = statement.next() {
-            sessions.push(SessionInfo {
-                session_id: statement.read::<String, _>(0)?,
-                started_at: statement.read::<String, _>(1)?,
-                ended_at: statement.read::<Option<String>, _>(2)?,
-                message_count: statement.read::<i64, _>(3)?,
-                avg_response_time_ms: statement.read::<i64, _>(4).unwrap_or(0),
-            });
+            let id = statement.read::<i64, _>(0)?;
+            let channel_id = statement.read::<String, _>(1)?;
+            let reminder_text = statement.read::<String, _>(2)?;
+            let remind_at = statement.read::<String, _>(3)?;
+            reminders.push((id, channel_id, reminder_text, remind_at));
+        }
+        Ok(reminders)
+    }
+
+    pub async fn delete_reminder(&self, reminder_id: i64, user_id: &str) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare("DELETE FROM reminders WHERE id = ? AND user_id = ?")?;
+        statement.bind((1, reminder_id))?;
+        statement.bind((2, user_id))?;
+        statement.next()?;
+
+        // Check if a row was actually deleted
+        let mut check = conn.prepare("SELECT changes()")?;
+        check.next()?;
+        let changes = check.read::<i64, _>(0)?;
+
+        if changes > 0 {
+            info!("Deleted reminder {reminder_id} for user {user_id}");
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    // Custom Command Methods
+    pub async fn add_custom_command(
+        &self,
+        command_name: &str,
+        response_text: &str,
+        created_by_user_id: &str,
+        guild_id: Option<&str>,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let is_global = guild_id.is_none();
+        let mut statement = conn.prepare(
+            "INSERT OR REPLACE INTO custom_commands (command_name, response_text, created_by_user_id, guild_id, is_global, updated_at)
+             VALUES (?, ?, ?, ?, ?, CURRENT_TIMESTAMP)"
+        )?;
+        statement.bind((1, command_name))?;
+        statement.bind((2, response_text))?;
+        statement.bind((3, created_by_user_id))?;
+        statement.bind((4, guild_id.unwrap_or("")))?;
+        statement.bind((5, if is_global { 1i64 } else { 0i64 }))?;
+        statement.next()?;
+        info!("Added custom command: {command_name}");
+        Ok(())
+    }
+
+    pub async fn get_custom_command(
+        &self,
+        command_name: &str,
+        guild_id: Option<&str>,
+    ) -> Result<Option<String>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT response_text FROM custom_commands
+             WHERE command_name = ? AND (guild_id = ? OR is_global = 1)
+             ORDER BY is_global ASC
+             LIMIT 1",
+        )?;
+        statement.bind((1, command_name))?;
+        statement.bind((2, guild_id.unwrap_or("")))?;
+
+        if let Ok(State::Row) = statement.next() {
+            Ok(Some(statement.read::<String, _>(0)?))
+        } else {
+            Ok(None)
         }
+    }
+
+    pub async fn delete_custom_command(
+        &self,
+        command_name: &str,
+        guild_id: Option<&str>,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("DELETE FROM custom_commands WHERE command_name = ? AND guild_id = ?")?;
+        statement.bind((1, command_name))?;
+        statement.bind((2, guild_id.unwrap_or("")))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    // Analytics Methods
+    pub async fn increment_daily_stat(&self, stat_type: &str) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
+
+        match stat_type {
+            "message" => {
+                conn.execute(
+                    "INSERT INTO daily_analytics (date, total_messages) VALUES (?, 1)
+                     ON CONFLICT(date) DO UPDATE SET total_messages = total_messages + 1",
+                )?;
+            }
+            "command" => {
+                conn.execute(
+                    "INSERT INTO daily_analytics (date, total_commands) VALUES (?, 1)
+                     ON CONFLICT(date) DO UPDATE SET total_commands = total_commands + 1",
+                )?;
+            }
+            "error" => {
+                conn.execute(
+                    "INSERT INTO daily_analytics (date, total_errors) VALUES (?, 1)
+                     ON CONFLICT(date) DO UPDATE SET total_errors = total_errors + 1",
+                )?;
+            }
+            _ => {}
+        }
+
+        let mut statement = conn.prepare(
+            "INSERT INTO daily_analytics (date, total_messages) VALUES (?, 0)
+             ON CONFLICT(date) DO NOTHING",
+        )?;
+        statement.bind((1, date.as_str()))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    pub async fn add_performance_metric(
+        &self,
+        metric_type: &str,
+        value: f64,
+        unit: Option<&str>,
+        metadata: Option<&str>,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO performance_metrics (metric_type, value, unit, metadata) VALUES (?, ?, ?, ?)"
+        )?;
+        statement.bind((1, metric_type))?;
+        statement.bind((2, value))?;
+        statement.bind((3, unit.unwrap_or("")))?;
+        statement.bind((4, metadata.unwrap_or("")))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    // System Metrics Methods (for /sysinfo command)
+
+    /// Store a system metric snapshot (uses performance_metrics table)
+    pub async fn store_system_metric(&self, metric_type: &str, value: f64) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO performance_metrics (metric_type, value, unit, metadata) VALUES (?, ?, 'system', '')"
+        )?;
+        statement.bind((1, metric_type))?;
+        statement.bind((2, value))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Get historical metrics data for a specific metric type
+    /// Returns (unix_timestamp, value) pairs ordered by time ascending
+    ///
+    /// Spans up to two days read the raw 1-minute samples; longer spans read the
+    /// hourly or daily averages, with hours not yet downsampled averaged from
+    /// raw rows so the newest hour is never missing.
+    pub async fn get_metrics_history(
+        &self,
+        metric_type: &str,
+        hours: i64,
+    ) -> Result<Vec<(i64, f64)>> {
+        let conn = self.connection.lock().await?;
+        let tier = MetricsTier::for_span(hours);
+        let mut statement = match tier {
+            MetricsTier::Minute => conn.prepare(
+                "SELECT strftime('%s', timestamp) as unix_time, value
+                 FROM performance_metrics
+                 WHERE metric_type = ?1 AND timestamp >= datetime('now', ?2 || ' hours')
+                 ORDER BY timestamp ASC",
+            )?,
+            MetricsTier::Hour => conn.prepare(
+                "SELECT unix_time, value FROM (
+                     SELECT strftime('%s', bucket) as unix_time, avg_value as value
+                     FROM system_metrics_rollup
+                     WHERE tier = 'hour' AND metric_type = ?1
+                       AND bucket >= datetime('now', ?2 || ' hours')
+                     UNION ALL
+                     SELECT strftime('%s', strftime('%Y-%m-%d %H:00:00', timestamp)), AVG(value)
+                     FROM performance_metrics
+                     WHERE metric_type = ?1 AND timestamp >= COALESCE(
+                         (SELECT setting_value FROM bot_settings WHERE setting_key = ?3),
+                         ''
+                     )
+                     GROUP BY 1
+                 )
+                 ORDER BY unix_time + 0 ASC",
+            )?,
+            MetricsTier::Day => conn.prepare(
+                "SELECT strftime('%s', bucket) as unix_time, avg_value
+                 FROM system_metrics_rollup
+                 WHERE tier = 'day' AND metric_type = ?1
+                   AND bucket >= date('now', ?2 || ' hours')
+                 ORDER BY bucket ASC",
+            )?,
+        };
+        statement.bind((1, metric_type))?;
+        statement.bind((2, format!("-{hours}").as_str()))?;
+        if tier == MetricsTier::Hour {
+            statement.bind((3, METRICS_WATERMARK_KEY))?;
+        }
+
+        let mut results = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            let timestamp_str = statement.read::<String, _>(0)?;
+            let timestamp = timestamp_str.parse::<i64>().unwrap_or(0);
+            let value = statement.read::<f64, _>(1)?;
+            results.push((timestamp, value));
+        }
+        Ok(results)
+    }
+
+    /// Fold completed hours of raw system metrics into the hourly and daily tiers
+    ///
+    /// Same watermark scheme as `rollup_analytics`: raw rows between the
+    /// watermark and the start of the current hour become hourly averages, the
+    /// touched days are rebuilt from the hourly rows, and the watermark advances
+    /// in the same transaction. Returns the number of hourly buckets written.
+    pub async fn downsample_system_metrics(&self) -> Result<i64> {
+        let conn = self.connection.lock().await?;
+
+        let mut statement =
+            conn.prepare("SELECT setting_value FROM bot_settings WHERE setting_key = ?")?;
+        statement.bind((1, METRICS_WATERMARK_KEY))?;
+        let watermark = match statement.next()? {
+            State::Row => statement.read::<Option<String>, _>(0)?.unwrap_or_default(),
+            State::Done => String::new(),
+        };
+
+        drop(statement);
+        let mut statement = conn.prepare("SELECT strftime('%Y-%m-%d %H:00:00', 'now')")?;
+        statement.next()?;
+        let current_hour = statement.read::<String, _>(0)?;
+        drop(statement);
+
+        if watermark >= current_hour {
+            return Ok(0);
+        }
+
+        conn.execute("BEGIN IMMEDIATE")?;
+
+        let result = (|| -> Result<i64> {
+            let mut statement = conn.prepare(
+                "SELECT COUNT(DISTINCT metric_type || strftime('%Y-%m-%d %H', timestamp))
+                 FROM performance_metrics
+                 WHERE unit = 'system' AND timestamp >= ?1 AND timestamp < ?2",
+            )?;
+            statement.bind((1, watermark.as_str()))?;
+            statement.bind((2, current_hour.as_str()))?;
+            statement.next()?;
+            let buckets = statement.read::<i64, _>(0)?;
+
+            drop(statement);
+            let mut statement = conn.prepare(
+                "INSERT INTO system_metrics_rollup
+                    (tier, bucket, metric_type, avg_value, min_value, max_value, samples)
+                 SELECT 'hour', strftime('%Y-%m-%d %H:00:00', timestamp), metric_type,
+                        AVG(value), MIN(value), MAX(value), COUNT(*)
+                 FROM performance_metrics
+                 WHERE unit = 'system' AND timestamp >= ?1 AND timestamp < ?2
+                 GROUP BY 2, 3
+                 ON CONFLICT(tier, metric_type, bucket) DO UPDATE SET
+                    avg_value = excluded.avg_value,
+                    min_value = excluded.min_value,
+                    max_value = excluded.max_value,
+                    samples = excluded.samples",
+            )?;
+            statement.bind((1, watermark.as_str()))?;
+            statement.bind((2, current_hour.as_str()))?;
+            statement.next()?;
+
+            // Rebuild whole days, weighting each hour by its sample count
+            drop(statement);
+            let mut statement = conn.prepare(
+                "INSERT INTO system_metrics_rollup
+                    (tier, bucket, metric_type, avg_value, min_value, max_value, samples)
+                 SELECT 'day', substr(bucket, 1, 10), metric_type,
+                        SUM(avg_value * samples) / SUM(samples), MIN(min_value),
+                        MAX(max_value), SUM(samples)
+                 FROM system_metrics_rollup
+                 WHERE tier = 'hour' AND bucket >= substr(?1, 1, 10) AND bucket < ?2
+                 GROUP BY 2, 3
+                 ON CONFLICT(tier, metric_type, bucket) DO UPDATE SET
+                    avg_value = excluded.avg_value,
+                    min_value = excluded.min_value,
+                    max_value = excluded.max_value,
+                    samples = excluded.samples",
+            )?;
+            statement.bind((1, watermark.as_str()))?;
+            statement.bind((2, current_hour.as_str()))?;
+            statement.next()?;
+
+            drop(statement);
+            let mut statement = conn.prepare(
+                "INSERT OR REPLACE INTO bot_settings (setting_key, setting_value, updated_at)
+                 VALUES (?, ?, CURRENT_TIMESTAMP)",
+            )?;
+            statement.bind((1, METRICS_WATERMARK_KEY))?;
+            statement.bind((2, current_hour.as_str()))?;
+            statement.next()?;
+
+            Ok(buckets)
+        })();
+
+        match result {
+            Ok(buckets) => {
+                conn.execute("COMMIT")?;
+                Ok(buckets)
+            }
+            Err(e) => {
+                let _ = conn.execute("ROLLBACK");
+                Err(e)
+            }
+        }
+    }
+
+    /// Cleanup each system metrics tier past its retention
+    ///
+    /// Raw rows are kept for `MINUTE_METRICS_RETENTION_DAYS`, hourly rows for
+    /// `HOURLY_METRICS_RETENTION_DAYS` and daily rows for `daily_days`. Also
+    /// drops the legacy `cpu`/`memory` rows older TUI versions logged.
+    pub async fn cleanup_old_metrics(&self, daily_days: i64) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "DELETE FROM performance_metrics
+             WHERE (unit = 'system' OR metric_type IN ('cpu', 'memory'))
+               AND timestamp < datetime('now', ? || ' days')",
+        )?;
+        statement.bind((1, format!("-{MINUTE_METRICS_RETENTION_DAYS}").as_str()))?;
+        statement.next()?;
+
+        for (tier, days) in [
+            (MetricsTier::Hour, HOURLY_METRICS_RETENTION_DAYS),
+            (
+                MetricsTier::Day,
+                daily_days.max(HOURLY_METRICS_RETENTION_DAYS),
+            ),
+        ] {
+            let mut statement = conn.prepare(
+                "DELETE FROM system_metrics_rollup
+                 WHERE tier = ? AND bucket < datetime('now', ? || ' days')",
+            )?;
+            statement.bind((1, tier.id()))?;
+            statement.bind((2, format!("-{days}").as_str()))?;
+            statement.next()?;
+        }
+        info!("Cleaned up system metrics past their tier retention");
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn log_error(
+        &self,
+        error_type: &str,
+        error_message: &str,
+        stack_trace: Option<&str>,
+        user_id: Option<&str>,
+        channel_id: Option<&str>,
+        command: Option<&str>,
+        metadata: Option<&str>,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO error_logs (error_type, error_message, stack_trace, user_id, channel_id, command, metadata)
+             VALUES (?, ?, ?, ?, ?, ?, ?)"
+        )?;
+        statement.bind((1, error_type))?;
+        statement.bind((2, error_message))?;
+        statement.bind((3, stack_trace.unwrap_or("")))?;
+        statement.bind((4, user_id.unwrap_or("")))?;
+        statement.bind((5, channel_id.unwrap_or("")))?;
+        statement.bind((6, command.unwrap_or("")))?;
+        statement.bind((7, metadata.unwrap_or("")))?;
+        statement.next()?;
+
+        // Also increment daily error count
+        self.increment_daily_stat("error").await?;
+        Ok(())
+    }
+
+    // Feature Flag Methods
+    pub async fn set_feature_flag(
+        &self,
+        feature_name: &str,
+        enabled: bool,
+        user_id: Option<&str>,
+        guild_id: Option<&str>,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT OR REPLACE INTO feature_flags (feature_name, enabled, user_id, guild_id, updated_at)
+             VALUES (?, ?, ?, ?, CURRENT_TIMESTAMP)"
+        )?;
+        statement.bind((1, feature_name))?;
+        statement.bind((2, if enabled { 1i64 } else { 0i64 }))?;
+        statement.bind((3, user_id.unwrap_or("")))?;
+        statement.bind((4, guild_id.unwrap_or("")))?;
+        statement.next()?;
+        self.settings_cache.feature_flags.remove(&(
+            feature_name.to_string(),
+            user_id.unwrap_or("").to_string(),
+            guild_id.unwrap_or("").to_string(),
+        ));
+        Ok(())
+    }
+
+    /// Check if a feature is enabled for a guild
+    /// Returns true by default if no record exists (features are enabled unless explicitly disabled)
+    pub async fn is_feature_enabled(
+        &self,
+        feature_name: &str,
+        user_id: Option<&str>,
+        guild_id: Option<&str>,
+    ) -> Result<bool> {
+        let cache_key = (
+            feature_name.to_string(),
+            user_id.unwrap_or("").to_string(),
+            guild_id.unwrap_or("").to_string(),
+        );
+        let cache = &self.settings_cache;
+        if let Some(enabled) = cache.feature_flags.get(&cache_key, cache.ttl) {
+            return Ok(enabled);
+        }
+
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT enabled FROM feature_flags
+             WHERE feature_name = ? AND user_id = ? AND guild_id = ?
+             LIMIT 1",
+        )?;
+        statement.bind((1, feature_name))?;
+        statement.bind((2, user_id.unwrap_or("")))?;
+        statement.bind((3, guild_id.unwrap_or("")))?;
+
+        let enabled = if let Ok(State::Row) = statement.next() {
+            statement.read::<i64, _>(0)? == 1
+        } else {
+            // Default to enabled if no explicit setting exists
+            true
+        };
+        cache.feature_flags.insert(cache_key, enabled);
+        Ok(enabled)
+    }
+
+    /// Get all feature flags for a guild
+    /// Returns a map of feature_name -> enabled status
+    pub async fn get_guild_feature_flags(
+        &self,
+        guild_id: &str,
+    ) -> Result<std::collections::HashMap<String, bool>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT feature_name, enabled FROM feature_flags
+             WHERE guild_id = ? AND user_id = ''",
+        )?;
+        statement.bind((1, guild_id))?;
+
+        let mut flags = std::collections::HashMap::new();
+        while let Ok(State::Row) = statement.next() {
+            let feature_name = statement.read::<String, _>(0)?;
+            let enabled = statement.read::<i64, _>(1)? == 1;
+            flags.insert(feature_name, enabled);
+        }
+        Ok(flags)
+    }
+
+    /// Record a feature toggle action in the audit trail
+    pub async fn record_feature_toggle(
+        &self,
+        feature_name: &str,
+        version: &str,
+        guild_id: Option<&str>,
+        toggled_by: &str,
+        enabled: bool,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO feature_versions (feature_name, version, guild_id, toggled_by, enabled)
+             VALUES (?, ?, ?, ?, ?)",
+        )?;
+        statement.bind((1, feature_name))?;
+        statement.bind((2, version))?;
+        statement.bind((3, guild_id.unwrap_or("")))?;
+        statement.bind((4, toggled_by))?;
+        statement.bind((5, if enabled { 1i64 } else { 0i64 }))?;
+        statement.next()?;
+        info!("Recorded feature toggle: {feature_name} -> {enabled} by {toggled_by}");
+        Ok(())
+    }
+
+    // Guild Settings Methods
+    pub async fn set_guild_setting(
+        &self,
+        guild_id: &str,
+        setting_key: &str,
+        setting_value: &str,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT OR REPLACE INTO guild_settings (guild_id, setting_key, setting_value, updated_at)
+             VALUES (?, ?, ?, CURRENT_TIMESTAMP)"
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, setting_key))?;
+        statement.bind((3, setting_value))?;
+        statement.next()?;
+
+        let cache = &self.settings_cache;
+        cache
+            .guild_settings
+            .remove(&(guild_id.to_string(), setting_key.to_string()));
+        if setting_key == "default_verbosity" {
+            // Channels without an override inherit the guild default
+            cache.channel_verbosity.retain(|(gid, _)| gid != guild_id);
+        }
+        Ok(())
+    }
+
+    pub async fn get_guild_setting(
+        &self,
+        guild_id: &str,
+        setting_key: &str,
+    ) -> Result<Option<String>> {
+        let cache_key = (guild_id.to_string(), setting_key.to_string());
+        let cache = &self.settings_cache;
+        if let Some(value) = cache.guild_settings.get(&cache_key, cache.ttl) {
+            return Ok(value);
+        }
+
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT setting_value FROM guild_settings WHERE guild_id = ? AND setting_key = ?",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, setting_key))?;
+
+        let value = if let Ok(State::Row) = statement.next() {
+            Some(statement.read::<String, _>(0)?)
+        } else {
+            None
+        };
+        cache.guild_settings.insert(cache_key, value.clone());
+        Ok(value)
+    }
+
+    /// Remove a guild setting so it falls back to its default
+    pub async fn delete_guild_setting(&self, guild_id: &str, setting_key: &str) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("DELETE FROM guild_settings WHERE guild_id = ? AND setting_key = ?")?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, setting_key))?;
+        statement.next()?;
+
+        let cache = &self.settings_cache;
+        cache
+            .guild_settings
+            .remove(&(guild_id.to_string(), setting_key.to_string()));
+        if setting_key == "default_verbosity" {
+            cache.channel_verbosity.retain(|(gid, _)| gid != guild_id);
+        }
+        Ok(())
+    }
+
+    /// Guilds that have any of the given settings stored
+    pub async fn get_guilds_with_settings(&self, setting_keys: &[&str]) -> Result<Vec<String>> {
+        let conn = self.connection.lock().await?;
+        let placeholders = vec!["?"; setting_keys.len()].join(", ");
+        let mut statement = conn.prepare(format!(
+            "SELECT DISTINCT guild_id FROM guild_settings WHERE setting_key IN ({placeholders})"
+        ))?;
+        for (i, key) in setting_keys.iter().enumerate() {
+            statement.bind((i + 1, *key))?;
+        }
+
+        let mut guilds = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            guilds.push(statement.read::<String, _>(0)?);
+        }
+        Ok(guilds)
+    }
+
+    /// Every setting stored for a guild, sorted by key
+    pub async fn get_all_guild_settings(&self, guild_id: &str) -> Result<Vec<(String, String)>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT setting_key, setting_value FROM guild_settings
+             WHERE guild_id = ? ORDER BY setting_key",
+        )?;
+        statement.bind((1, guild_id))?;
+
+        let mut settings = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            let key = statement.read::<String, _>(0)?;
+            let value = statement.read::<Option<String>, _>(1)?;
+            settings.push((key, value.unwrap_or_default()));
+        }
+        Ok(settings)
+    }
+
+    // Privacy Methods
+
+    /// Record a user's /privacy choice; `None` reverts to the default
+    pub async fn set_privacy_choice(
+        &self,
+        user_id: &str,
+        option: PrivacyOption,
+        allowed: Option<bool>,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = match allowed {
+            Some(allowed) => {
+                let mut stmt = conn.prepare(
+                    "INSERT INTO user_privacy (user_id, option, allowed, updated_at)
+                     VALUES (?, ?, ?, CURRENT_TIMESTAMP)
+                     ON CONFLICT(user_id, option) DO UPDATE SET
+                     allowed = excluded.allowed,
+                     updated_at = CURRENT_TIMESTAMP",
+                )?;
+                stmt.bind((3, allowed as i64))?;
+                stmt
+            }
+            None => conn.prepare("DELETE FROM user_privacy WHERE user_id = ? AND option = ?")?,
+        };
+        statement.bind((1, user_id))?;
+        statement.bind((2, option.key()))?;
+        statement.next()?;
+
+        self.settings_cache
+            .user_privacy
+            .remove(&user_id.to_string());
+        info!(
+            "Set privacy {} for user {user_id} to {allowed:?}",
+            option.key()
+        );
+        Ok(())
+    }
+
+    /// A user's explicit /privacy choices as (option, allowed)
+    pub async fn get_privacy_choices(&self, user_id: &str) -> Result<Vec<(PrivacyOption, bool)>> {
+        let cache = &self.settings_cache;
+        if let Some(choices) = cache.user_privacy.get(&user_id.to_string(), cache.ttl) {
+            return Ok(choices);
+        }
+
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("SELECT option, allowed FROM user_privacy WHERE user_id = ?")?;
+        statement.bind((1, user_id))?;
+
+        let mut choices = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            let key = statement.read::<String, _>(0)?;
+            if let Some(option) = PrivacyOption::from_key(&key) {
+                choices.push((option, statement.read::<i64, _>(1)? != 0));
+            }
+        }
+        cache
+            .user_privacy
+            .insert(user_id.to_string(), choices.clone());
+        Ok(choices)
+    }
+
+    /// Effective privacy settings for a user in a guild (or DMs when `None`)
+    /// Cascade: user choice -> guild default -> allowed
+    pub async fn get_privacy_settings(
+        &self,
+        user_id: &str,
+        guild_id: Option<&str>,
+    ) -> Result<PrivacySettings> {
+        let choices = self.get_privacy_choices(user_id).await?;
+        let mut settings = PrivacySettings::default();
+
+        for option in PrivacyOption::ALL {
+            if let Some((_, allowed)) = choices.iter().find(|(o, _)| *o == option) {
+                settings.set(option, *allowed);
+            } else if let (Some(gid), Some(key)) = (guild_id, option.guild_setting()) {
+                if let Some(value) = self.get_guild_setting(gid, key).await? {
+                    settings.set(option, value != "disabled");
+                }
+            }
+        }
+        Ok(settings)
+    }
+
+    /// A user's answer to the DM consent prompt; `None` if never answered
+    pub async fn get_dm_consent(&self, user_id: &str) -> Result<Option<bool>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare("SELECT accepted FROM dm_consent WHERE user_id = ?")?;
+        statement.bind((1, user_id))?;
+
+        if let Ok(State::Row) = statement.next() {
+            Ok(Some(statement.read::<i64, _>(0)? != 0))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Record whether a user accepted DM processing
+    pub async fn set_dm_consent(&self, user_id: &str, accepted: bool) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO dm_consent (user_id, accepted, decided_at)
+             VALUES (?, ?, CURRENT_TIMESTAMP)
+             ON CONFLICT(user_id) DO UPDATE SET
+             accepted = excluded.accepted,
+             decided_at = CURRENT_TIMESTAMP",
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, accepted as i64))?;
+        statement.next()?;
+
+        info!(
+            "User {user_id} {} DM consent",
+            if accepted { "gave" } else { "declined" }
+        );
+        Ok(())
+    }
+
+    // Access Rule Methods
+
+    /// Block or allowlist a user or channel in a guild; `None` removes the entry
+    pub async fn set_access_rule(
+        &self,
+        guild_id: &str,
+        target: AccessTarget,
+        target_id: &str,
+        rule: Option<AccessRule>,
+        set_by: &str,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        match rule {
+            Some(rule) => {
+                let mut statement = conn.prepare(
+                    "INSERT INTO access_rules (guild_id, target_type, target_id, rule, created_by, created_at)
+                     VALUES (?, ?, ?, ?, ?, CURRENT_TIMESTAMP)
+                     ON CONFLICT(guild_id, target_type, target_id) DO UPDATE SET
+                     rule = excluded.rule,
+                     created_by = excluded.created_by,
+                     created_at = CURRENT_TIMESTAMP",
+                )?;
+                statement.bind((1, guild_id))?;
+                statement.bind((2, target.key()))?;
+                statement.bind((3, target_id))?;
+                statement.bind((4, rule.key()))?;
+                statement.bind((5, set_by))?;
+                statement.next()?;
+            }
+            None => {
+                let mut statement = conn.prepare(
+                    "DELETE FROM access_rules WHERE guild_id = ? AND target_type = ? AND target_id = ?",
+                )?;
+                statement.bind((1, guild_id))?;
+                statement.bind((2, target.key()))?;
+                statement.bind((3, target_id))?;
+                statement.next()?;
+            }
+        }
+
+        self.settings_cache
+            .access_rules
+            .remove(&guild_id.to_string());
+        info!(
+            "Access rule for {} {target_id} in guild {guild_id} set to {:?} by {set_by}",
+            target.key(),
+            rule.map(|r| r.key())
+        );
+        Ok(())
+    }
+
+    /// A guild's block and allow lists
+    pub async fn get_access_rules(&self, guild_id: &str) -> Result<AccessRules> {
+        let cache = &self.settings_cache;
+        if let Some(rules) = cache.access_rules.get(&guild_id.to_string(), cache.ttl) {
+            return Ok(rules);
+        }
+
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT target_type, target_id, rule FROM access_rules
+             WHERE guild_id = ? ORDER BY created_at",
+        )?;
+        statement.bind((1, guild_id))?;
+
+        let mut rules = AccessRules::default();
+        while let Ok(State::Row) = statement.next() {
+            let target_type = statement.read::<String, _>(0)?;
+            let target_id = statement.read::<String, _>(1)?;
+            let rule = statement.read::<String, _>(2)?;
+            let list = match (target_type.as_str(), rule.as_str()) {
+                ("user", "block") => &mut rules.blocked_users,
+                ("channel", "block") => &mut rules.blocked_channels,
+                ("user", "allow") => &mut rules.allowed_users,
+                ("channel", "allow") => &mut rules.allowed_channels,
+                _ => continue,
+            };
+            list.push(target_id);
+        }
+        cache
+            .access_rules
+            .insert(guild_id.to_string(), rules.clone());
+        Ok(rules)
+    }
+
+    // Celebration Methods
+
+    /// Save a member's birthday or anniversary, replacing an earlier date
+    ///
+    /// Moving the date forgets the last wish, so the new date is celebrated
+    /// even if the old one already was this year.
+    pub async fn set_celebration(&self, celebration: &Celebration) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO celebrations (guild_id, user_id, kind, month, day, year, timezone)
+             VALUES (?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(guild_id, user_id, kind) DO UPDATE SET
+             last_wished_year = CASE
+                 WHEN month = excluded.month AND day = excluded.day THEN last_wished_year
+                 ELSE NULL
+             END,
+             month = excluded.month,
+             day = excluded.day,
+             year = excluded.year,
+             timezone = excluded.timezone",
+        )?;
+        statement.bind((1, celebration.guild_id.as_str()))?;
+        statement.bind((2, celebration.user_id.as_str()))?;
+        statement.bind((3, celebration.kind.key()))?;
+        statement.bind((4, celebration.month as i64))?;
+        statement.bind((5, celebration.day as i64))?;
+        statement.bind((6, celebration.year.map(i64::from)))?;
+        statement.bind((7, celebration.timezone.to_setting().as_str()))?;
+        statement.next()?;
+
+        info!(
+            "Saved {} {:02}-{:02} for user {} in guild {}",
+            celebration.kind.key(),
+            celebration.month,
+            celebration.day,
+            celebration.user_id,
+            celebration.guild_id
+        );
+        Ok(())
+    }
+
+    /// Remove a member's birthday or anniversary (both when `kind` is `None`)
+    ///
+    /// Returns whether anything was removed.
+    pub async fn remove_celebration(
+        &self,
+        guild_id: &str,
+        user_id: &str,
+        kind: Option<CelebrationKind>,
+    ) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "DELETE FROM celebrations
+             WHERE guild_id = ? AND user_id = ? AND (? IS NULL OR kind = ?)",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, user_id))?;
+        statement.bind((3, kind.map(|k| k.key())))?;
+        statement.bind((4, kind.map(|k| k.key())))?;
+        statement.next()?;
+        Ok(conn.change_count() > 0)
+    }
+
+    /// Saved dates in a guild, opted-out members included
+    pub async fn get_guild_celebrations(&self, guild_id: &str) -> Result<Vec<Celebration>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT guild_id, user_id, kind, month, day, year, utc_offset_minutes, last_wished_year,
+                    timezone
+             FROM celebrations WHERE guild_id = ? ORDER BY month, day",
+        )?;
+        statement.bind((1, guild_id))?;
+        Self::read_celebrations(&mut statement)
+    }
+
+    /// Saved dates of every member who hasn't opted out, across all guilds
+    pub async fn get_wishable_celebrations(&self) -> Result<Vec<Celebration>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT c.guild_id, c.user_id, c.kind, c.month, c.day, c.year,
+                    c.utc_offset_minutes, c.last_wished_year, c.timezone
+             FROM celebrations c
+             WHERE NOT EXISTS (
+                 SELECT 1 FROM celebration_opt_outs o
+                 WHERE o.guild_id = c.guild_id AND o.user_id = c.user_id
+             )
+             ORDER BY c.guild_id, c.month, c.day",
+        )?;
+        Self::read_celebrations(&mut statement)
+    }
+
+    fn read_celebrations(statement: &mut sqlite::Statement) -> Result<Vec<Celebration>> {
+        let mut celebrations = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            let Some(kind) = CelebrationKind::from_key(&statement.read::<String, _>(2)?) else {
+                continue;
+            };
+            celebrations.push(Celebration {
+                guild_id: statement.read::<String, _>(0)?,
+                user_id: statement.read::<String, _>(1)?,
+                kind,
+                month: statement.read::<i64, _>(3)? as u32,
+                day: statement.read::<i64, _>(4)? as u32,
+                year: statement.read::<Option<i64>, _>(5)?.map(|y| y as i32),
+                timezone: statement
+                    .read::<Option<String>, _>(8)?
+                    .and_then(|value| Zone::from_setting(&value))
+                    .unwrap_or(Zone::Fixed(statement.read::<i64, _>(6)? as i32)),
+                last_wished_year: statement.read::<Option<i64>, _>(7)?.map(|y| y as i32),
+            });
+        }
+        Ok(celebrations)
+    }
+
+    /// Record that a date was celebrated in the member's local `year`
+    pub async fn mark_celebration_wished(
+        &self,
+        guild_id: &str,
+        user_id: &str,
+        kind: CelebrationKind,
+        year: i32,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "UPDATE celebrations SET last_wished_year = ?
+             WHERE guild_id = ? AND user_id = ? AND kind = ?",
+        )?;
+        statement.bind((1, year as i64))?;
+        statement.bind((2, guild_id))?;
+        statement.bind((3, user_id))?;
+        statement.bind((4, kind.key()))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Opt a member out of (or back into) public wishes in a guild
+    pub async fn set_celebration_opt_out(
+        &self,
+        guild_id: &str,
+        user_id: &str,
+        opted_out: bool,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let query = if opted_out {
+            "INSERT OR IGNORE INTO celebration_opt_outs (guild_id, user_id) VALUES (?, ?)"
+        } else {
+            "DELETE FROM celebration_opt_outs WHERE guild_id = ? AND user_id = ?"
+        };
+        let mut statement = conn.prepare(query)?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, user_id))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    pub async fn is_celebration_opted_out(&self, guild_id: &str, user_id: &str) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("SELECT 1 FROM celebration_opt_outs WHERE guild_id = ? AND user_id = ?")?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, user_id))?;
+        Ok(matches!(statement.next(), Ok(State::Row)))
+    }
+
+    // Trivia Methods
+
+    /// Add one finished game's score to a member's trivia record
+    ///
+    /// `won` marks a game the member finished on top of the scoreboard.
+    pub async fn record_trivia_result(
+        &self,
+        guild_id: &str,
+        user_id: &str,
+        points: i64,
+        correct_answers: i64,
+        won: bool,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO trivia_scores (guild_id, user_id, points, correct_answers, games_played, games_won, updated_at)
+             VALUES (?, ?, ?, ?, 1, ?, CURRENT_TIMESTAMP)
+             ON CONFLICT(guild_id, user_id) DO UPDATE SET
+             points = points + excluded.points,
+             correct_answers = correct_answers + excluded.correct_answers,
+             games_played = games_played + 1,
+             games_won = games_won + excluded.games_won,
+             updated_at = CURRENT_TIMESTAMP",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, user_id))?;
+        statement.bind((3, points))?;
+        statement.bind((4, correct_answers))?;
+        statement.bind((5, won as i64))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Top trivia players in a guild, by points
+    pub async fn get_trivia_leaderboard(
+        &self,
+        guild_id: &str,
+        limit: usize,
+    ) -> Result<Vec<TriviaStanding>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT user_id, points, correct_answers, games_played, games_won FROM trivia_scores
+             WHERE guild_id = ?
+             ORDER BY points DESC, correct_answers DESC, updated_at ASC
+             LIMIT ?",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, limit as i64))?;
+
+        let mut standings = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            standings.push(TriviaStanding {
+                user_id: statement.read::<String, _>(0)?,
+                points: statement.read::<i64, _>(1)?,
+                correct_answers: statement.read::<i64, _>(2)?,
+                games_played: statement.read::<i64, _>(3)?,
+                games_won: statement.read::<i64, _>(4)?,
+            });
+        }
+        Ok(standings)
+    }
+
+    /// A member's trivia record in one guild
+    pub async fn get_trivia_standing(
+        &self,
+        guild_id: &str,
+        user_id: &str,
+    ) -> Result<Option<TriviaStanding>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT user_id, points, correct_answers, games_played, games_won FROM trivia_scores
+             WHERE guild_id = ? AND user_id = ?",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, user_id))?;
+        if let Ok(State::Row) = statement.next() {
+            Ok(Some(TriviaStanding {
+                user_id: statement.read::<String, _>(0)?,
+                points: statement.read::<i64, _>(1)?,
+                correct_answers: statement.read::<i64, _>(2)?,
+                games_played: statement.read::<i64, _>(3)?,
+                games_won: statement.read::<i64, _>(4)?,
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    // Debate Methods
+
+    /// Record a concluded debate so the audience can vote on it
+    pub async fn record_debate_result(&self, result: &DebateResult) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO debate_results
+             (thread_id, guild_id, initiator_id, persona1_id, persona2_id, rounds)
+             VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT(thread_id) DO UPDATE SET
+             rounds = excluded.rounds,
+             concluded_at = CURRENT_TIMESTAMP",
+        )?;
+        statement.bind((1, result.thread_id.as_str()))?;
+        statement.bind((2, result.guild_id.as_deref()))?;
+        statement.bind((3, result.initiator_id.as_str()))?;
+        statement.bind((4, result.persona1_id.as_str()))?;
+        statement.bind((5, result.persona2_id.as_str()))?;
+        statement.bind((6, result.rounds))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    pub async fn get_debate_result(&self, thread_id: &str) -> Result<Option<DebateResult>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT thread_id, guild_id, initiator_id, persona1_id, persona2_id, rounds
+             FROM debate_results WHERE thread_id = ?",
+        )?;
+        statement.bind((1, thread_id))?;
+        if let Ok(State::Row) = statement.next() {
+            Ok(Some(DebateResult {
+                thread_id: statement.read::<String, _>(0)?,
+                guild_id: statement.read::<Option<String>, _>(1)?,
+                initiator_id: statement.read::<String, _>(2)?,
+                persona1_id: statement.read::<String, _>(3)?,
+                persona2_id: statement.read::<String, _>(4)?,
+                rounds: statement.read::<i64, _>(5)?,
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Record (or change) a member's pick for who won a debate
+    pub async fn record_debate_vote(
+        &self,
+        thread_id: &str,
+        user_id: &str,
+        persona_id: &str,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO debate_votes (thread_id, user_id, persona_id) VALUES (?, ?, ?)
+             ON CONFLICT(thread_id, user_id) DO UPDATE SET
+             persona_id = excluded.persona_id,
+             voted_at = CURRENT_TIMESTAMP",
+        )?;
+        statement.bind((1, thread_id))?;
+        statement.bind((2, user_id))?;
+        statement.bind((3, persona_id))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Votes per persona for a debate, most votes first
+    pub async fn get_debate_tally(&self, thread_id: &str) -> Result<Vec<(String, i64)>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT persona_id, COUNT(*) AS votes FROM debate_votes
+             WHERE thread_id = ?
+             GROUP BY persona_id
+             ORDER BY votes DESC, persona_id",
+        )?;
+        statement.bind((1, thread_id))?;
+        let mut tally = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            tally.push((
+                statement.read::<String, _>(0)?,
+                statement.read::<i64, _>(1)?,
+            ));
+        }
+        Ok(tally)
+    }
+
+    /// Members who picked `persona_id` in a debate's audience vote
+    pub async fn get_debate_voters(
+        &self,
+        thread_id: &str,
+        persona_id: &str,
+    ) -> Result<Vec<String>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT user_id FROM debate_votes WHERE thread_id = ? AND persona_id = ? ORDER BY voted_at",
+        )?;
+        statement.bind((1, thread_id))?;
+        statement.bind((2, persona_id))?;
+        let mut voters = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            voters.push(statement.read::<String, _>(0)?);
+        }
+        Ok(voters)
+    }
+
+    // Profile Methods
+
+    /// Aggregate a member's stats in a guild for /profile
+    ///
+    /// Only activity in `guild_id` is counted, commands included.
+    pub async fn get_user_profile(
+        &self,
+        user_id: &str,
+        guild_id: &str,
+        top_commands: usize,
+    ) -> Result<UserProfile> {
+        let trivia = self.get_trivia_standing(guild_id, user_id).await?;
+        let conn = self.connection.lock().await?;
+        let mut profile = UserProfile {
+            trivia,
+            ..UserProfile::default()
+        };
+
+        let mut statement = conn.prepare(
+            "SELECT persona, COUNT(*) AS cnt FROM conversation_history
+             WHERE user_id = ? AND guild_id = ? AND persona IS NOT NULL AND persona != ''
+             GROUP BY persona
+             ORDER BY cnt DESC
+             LIMIT 1",
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, guild_id))?;
+        if let Ok(State::Row) = statement.next() {
+            profile.favorite_persona = Some(statement.read::<String, _>(0)?);
+        }
+
+        drop(statement);
+        let mut statement = conn.prepare(
+            "SELECT COALESCE(SUM(role = 'user'), 0), COALESCE(SUM(role = 'assistant'), 0)
+             FROM conversation_history
+             WHERE user_id = ? AND guild_id = ?",
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, guild_id))?;
+        if let Ok(State::Row) = statement.next() {
+            profile.message_count = statement.read::<i64, _>(0)?;
+            profile.bot_replies = statement.read::<i64, _>(1)?;
+        }
+
+        // Rolled-up days plus raw rows the rollup hasn't folded in yet
+        drop(statement);
+        let mut statement = conn.prepare(
+            "SELECT command, SUM(invocations) AS total FROM (
+                 SELECT command, invocations FROM command_usage_daily
+                 WHERE user_id = ?1 AND guild_id = ?4
+                 UNION ALL
+                 SELECT command, 1 FROM usage_stats
+                 WHERE user_id = ?1 AND guild_id = ?4 AND timestamp >= COALESCE(
+                     (SELECT setting_value FROM bot_settings WHERE setting_key = ?2),
+                     ''
+                 )
+             )
+             GROUP BY command
+             ORDER BY total DESC, command
+             LIMIT ?3",
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, ROLLUP_WATERMARK_KEY))?;
+        statement.bind((3, top_commands as i64))?;
+        statement.bind((4, guild_id))?;
+        while let Ok(State::Row) = statement.next() {
+            profile.top_commands.push((
+                statement.read::<String, _>(0)?,
+                statement.read::<i64, _>(1)?,
+            ));
+        }
+
+        drop(statement);
+        let mut statement = conn.prepare(
+            "SELECT COALESCE(SUM(total_audio_seconds), 0) FROM openai_usage_rollup_live
+             WHERE user_id = ? AND guild_id = ? AND service_type = 'whisper'",
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, guild_id))?;
+        if let Ok(State::Row) = statement.next() {
+            profile.transcription_seconds = statement.read::<f64, _>(0)?;
+        }
+
+        drop(statement);
+        let mut statement = conn.prepare(
+            "SELECT COUNT(*) FROM debate_results WHERE initiator_id = ? AND guild_id = ?",
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, guild_id))?;
+        if let Ok(State::Row) = statement.next() {
+            profile.debates_started = statement.read::<i64, _>(0)?;
+        }
+
+        // A win is a vote for the side that strictly leads the audience vote
+        drop(statement);
+        let mut statement = conn.prepare(
+            "SELECT COUNT(*),
+                    COALESCE(SUM(
+                        (SELECT COUNT(*) FROM debate_votes o
+                         WHERE o.thread_id = v.thread_id AND o.persona_id = v.persona_id)
+                        > (SELECT COUNT(*) FROM debate_votes o
+                           WHERE o.thread_id = v.thread_id AND o.persona_id != v.persona_id)
+                    ), 0)
+             FROM debate_votes v
+             JOIN debate_results r ON r.thread_id = v.thread_id
+             WHERE v.user_id = ? AND r.guild_id = ?",
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, guild_id))?;
+        if let Ok(State::Row) = statement.next() {
+            profile.debate_votes = statement.read::<i64, _>(0)?;
+            profile.debate_wins = statement.read::<i64, _>(1)?;
+        }
+
+        Ok(profile)
+    }
+
+    /// Times a member has used `command`, across all guilds
+    pub async fn get_user_command_count(&self, user_id: &str, command: &str) -> Result<i64> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT COALESCE(SUM(invocations), 0) FROM (
+                 SELECT invocations FROM command_usage_daily WHERE user_id = ?1 AND command = ?2
+                 UNION ALL
+                 SELECT 1 FROM usage_stats
+                 WHERE user_id = ?1 AND command = ?2 AND timestamp >= COALESCE(
+                     (SELECT setting_value FROM bot_settings WHERE setting_key = ?3),
+                     ''
+                 )
+             )",
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, command))?;
+        statement.bind((3, ROLLUP_WATERMARK_KEY))?;
+        if let Ok(State::Row) = statement.next() {
+            Ok(statement.read::<i64, _>(0)?)
+        } else {
+            Ok(0)
+        }
+    }
+
+    // Achievement Methods
+
+    /// Store a badge for a member; `false` when they already had it
+    pub async fn award_achievement(
+        &self,
+        guild_id: &str,
+        user_id: &str,
+        achievement: &str,
+    ) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT OR IGNORE INTO achievements (guild_id, user_id, achievement) VALUES (?, ?, ?)",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, user_id))?;
+        statement.bind((3, achievement))?;
+        statement.next()?;
+        Ok(conn.change_count() > 0)
+    }
+
+    /// Badge keys a member earned in a guild, oldest first
+    pub async fn get_user_achievements(
+        &self,
+        guild_id: &str,
+        user_id: &str,
+    ) -> Result<Vec<String>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT achievement FROM achievements
+             WHERE guild_id = ? AND user_id = ?
+             ORDER BY earned_at, achievement",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, user_id))?;
+        let mut achievements = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            achievements.push(statement.read::<String, _>(0)?);
+        }
+        Ok(achievements)
+    }
+
+    // Leveling Methods
+
+    /// Add XP to a member unless they were granted some in the last `cooldown_secs`
+    ///
+    /// Returns the member's XP before and after, or `None` while on cooldown.
+    pub async fn grant_member_xp(
+        &self,
+        guild_id: &str,
+        user_id: &str,
+        amount: i64,
+        now: i64,
+        cooldown_secs: i64,
+    ) -> Result<Option<(i64, i64)>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT xp, last_grant_at FROM member_xp WHERE guild_id = ? AND user_id = ?",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, user_id))?;
+        let (before, last_grant_at) = if let Ok(State::Row) = statement.next() {
+            (
+                statement.read::<i64, _>(0)?,
+                Some(statement.read::<i64, _>(1)?),
+            )
+        } else {
+            (0, None)
+        };
+        if last_grant_at.is_some_and(|last| now - last < cooldown_secs) {
+            return Ok(None);
+        }
+
+        drop(statement);
+        let mut statement = conn.prepare(
+            "INSERT INTO member_xp (guild_id, user_id, xp, grants, last_grant_at)
+             VALUES (?, ?, ?, 1, ?)
+             ON CONFLICT(guild_id, user_id) DO UPDATE SET
+             xp = xp + excluded.xp,
+             grants = grants + 1,
+             last_grant_at = excluded.last_grant_at",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, user_id))?;
+        statement.bind((3, amount))?;
+        statement.bind((4, now))?;
+        statement.next()?;
+        Ok(Some((before, before + amount)))
+    }
+
+    /// A member's XP and 1-based rank in a guild
+    pub async fn get_member_rank(
+        &self,
+        guild_id: &str,
+        user_id: &str,
+    ) -> Result<Option<(MemberXp, i64)>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT m.xp, m.grants,
+                    (SELECT COUNT(*) FROM member_xp o WHERE o.guild_id = m.guild_id AND o.xp > m.xp) + 1
+             FROM member_xp m
+             WHERE m.guild_id = ? AND m.user_id = ?",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, user_id))?;
+        if let Ok(State::Row) = statement.next() {
+            Ok(Some((
+                MemberXp {
+                    user_id: user_id.to_string(),
+                    xp: statement.read::<i64, _>(0)?,
+                    grants: statement.read::<i64, _>(1)?,
+                },
+                statement.read::<i64, _>(2)?,
+            )))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Members with the most XP in a guild, earliest to get there first on ties
+    pub async fn get_xp_leaderboard(&self, guild_id: &str, limit: usize) -> Result<Vec<MemberXp>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT user_id, xp, grants FROM member_xp
+             WHERE guild_id = ? AND xp > 0
+             ORDER BY xp DESC, last_grant_at ASC
+             LIMIT ?",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, limit as i64))?;
+        let mut members = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            members.push(MemberXp {
+                user_id: statement.read::<String, _>(0)?,
+                xp: statement.read::<i64, _>(1)?,
+                grants: statement.read::<i64, _>(2)?,
+            });
+        }
+        Ok(members)
+    }
+
+    /// Give `role_id` to members reaching `level`, replacing that level's reward
+    pub async fn set_level_reward(&self, guild_id: &str, level: i64, role_id: &str) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO level_rewards (guild_id, level, role_id) VALUES (?, ?, ?)
+             ON CONFLICT(guild_id, level) DO UPDATE SET role_id = excluded.role_id",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, level))?;
+        statement.bind((3, role_id))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    pub async fn remove_level_reward(&self, guild_id: &str, level: i64) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("DELETE FROM level_rewards WHERE guild_id = ? AND level = ?")?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, level))?;
+        statement.next()?;
+        Ok(conn.change_count() > 0)
+    }
+
+    /// A guild's level rewards as `(level, role_id)`, lowest level first
+    pub async fn get_level_rewards(&self, guild_id: &str) -> Result<Vec<(i64, String)>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT level, role_id FROM level_rewards WHERE guild_id = ? ORDER BY level",
+        )?;
+        statement.bind((1, guild_id))?;
+        let mut rewards = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            rewards.push((
+                statement.read::<i64, _>(0)?,
+                statement.read::<String, _>(1)?,
+            ));
+        }
+        Ok(rewards)
+    }
+
+    // Channel Topic Methods
+
+    /// Create or replace a channel's topic schedule, starting its rotation over
+    pub async fn set_channel_topic_schedule(&self, schedule: &ChannelTopicSchedule) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO channel_topic_schedules
+             (channel_id, guild_id, mode, topics, theme, interval_hours, next_run_at, next_index, last_topic, created_by)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(channel_id) DO UPDATE SET
+             guild_id = excluded.guild_id,
+             mode = excluded.mode,
+             topics = excluded.topics,
+             theme = excluded.theme,
+             interval_hours = excluded.interval_hours,
+             next_run_at = excluded.next_run_at,
+             next_index = excluded.next_index,
+             last_topic = excluded.last_topic,
+             created_by = excluded.created_by,
+             updated_at = CURRENT_TIMESTAMP",
+        )?;
+        statement.bind((1, schedule.channel_id.as_str()))?;
+        statement.bind((2, schedule.guild_id.as_str()))?;
+        statement.bind((3, schedule.mode.as_str()))?;
+        statement.bind((4, schedule.topics.join("\n").as_str()))?;
+        statement.bind((5, schedule.theme.as_deref()))?;
+        statement.bind((6, schedule.interval_hours))?;
+        statement.bind((7, schedule.next_run_at))?;
+        statement.bind((8, schedule.next_index))?;
+        statement.bind((9, schedule.last_topic.as_deref()))?;
+        statement.bind((10, schedule.created_by.as_str()))?;
+        statement.next()?;
+
+        info!(
+            "Saved {} topic schedule for channel {} (every {}h)",
+            schedule.mode, schedule.channel_id, schedule.interval_hours
+        );
+        Ok(())
+    }
+
+    /// Remove a channel's topic schedule; returns whether one existed
+    pub async fn delete_channel_topic_schedule(
+        &self,
+        guild_id: &str,
+        channel_id: &str,
+    ) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn
+            .prepare("DELETE FROM channel_topic_schedules WHERE guild_id = ? AND channel_id = ?")?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, channel_id))?;
+        statement.next()?;
+        Ok(conn.change_count() > 0)
+    }
+
+    /// A guild's topic schedules, soonest update first
+    pub async fn get_channel_topic_schedules(
+        &self,
+        guild_id: &str,
+    ) -> Result<Vec<ChannelTopicSchedule>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT guild_id, channel_id, mode, topics, theme, interval_hours, next_run_at,
+                    next_index, last_topic, created_by
+             FROM channel_topic_schedules WHERE guild_id = ? ORDER BY next_run_at",
+        )?;
+        statement.bind((1, guild_id))?;
+        Self::read_channel_topic_schedules(&mut statement)
+    }
+
+    /// Topic schedules across all guilds whose next update is at or before `now`
+    pub async fn get_due_channel_topic_schedules(
+        &self,
+        now: i64,
+    ) -> Result<Vec<ChannelTopicSchedule>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT guild_id, channel_id, mode, topics, theme, interval_hours, next_run_at,
+                    next_index, last_topic, created_by
+             FROM channel_topic_schedules WHERE next_run_at <= ? ORDER BY next_run_at",
+        )?;
+        statement.bind((1, now))?;
+        Self::read_channel_topic_schedules(&mut statement)
+    }
+
+    fn read_channel_topic_schedules(
+        statement: &mut sqlite::Statement,
+    ) -> Result<Vec<ChannelTopicSchedule>> {
+        let mut schedules = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            let topics = statement.read::<String, _>(3)?;
+            schedules.push(ChannelTopicSchedule {
+                guild_id: statement.read::<String, _>(0)?,
+                channel_id: statement.read::<String, _>(1)?,
+                mode: statement.read::<String, _>(2)?,
+                topics: topics
+                    .lines()
+                    .filter(|t| !t.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+                theme: statement.read::<Option<String>, _>(4)?,
+                interval_hours: statement.read::<i64, _>(5)?,
+                next_run_at: statement.read::<i64, _>(6)?,
+                next_index: statement.read::<i64, _>(7)?,
+                last_topic: statement.read::<Option<String>, _>(8)?,
+                created_by: statement.read::<String, _>(9)?,
+            });
+        }
+        Ok(schedules)
+    }
+
+    /// Record an update: when the next one is due, the next rotation index and the topic set
+    pub async fn advance_channel_topic_schedule(
+        &self,
+        channel_id: &str,
+        next_run_at: i64,
+        next_index: i64,
+        last_topic: Option<&str>,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "UPDATE channel_topic_schedules
+             SET next_run_at = ?, next_index = ?, last_topic = COALESCE(?, last_topic)
+             WHERE channel_id = ?",
+        )?;
+        statement.bind((1, next_run_at))?;
+        statement.bind((2, next_index))?;
+        statement.bind((3, last_topic))?;
+        statement.bind((4, channel_id))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    // Question of the Day Methods
+
+    /// Create or replace a guild's question of the day schedule
+    pub async fn set_qotd_config(&self, config: &QotdConfig) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO qotd_configs
+             (guild_id, channel_id, hour, utc_offset_minutes, threads, digest_channel_id, next_run_at, created_by)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(guild_id) DO UPDATE SET
+             channel_id = excluded.channel_id,
+             hour = excluded.hour,
+             utc_offset_minutes = excluded.utc_offset_minutes,
+             threads = excluded.threads,
+             digest_channel_id = excluded.digest_channel_id,
+             next_run_at = excluded.next_run_at,
+             created_by = excluded.created_by,
+             updated_at = CURRENT_TIMESTAMP",
+        )?;
+        statement.bind((1, config.guild_id.as_str()))?;
+        statement.bind((2, config.channel_id.as_str()))?;
+        statement.bind((3, config.hour))?;
+        statement.bind((4, config.utc_offset_minutes as i64))?;
+        statement.bind((5, config.threads as i64))?;
+        statement.bind((6, config.digest_channel_id.as_deref()))?;
+        statement.bind((7, config.next_run_at))?;
+        statement.bind((8, config.created_by.as_str()))?;
+        statement.next()?;
+
+        info!(
+            "Saved question of the day schedule for guild {} in channel {}",
+            config.guild_id, config.channel_id
+        );
+        Ok(())
+    }
+
+    /// A guild's question of the day schedule
+    pub async fn get_qotd_config(&self, guild_id: &str) -> Result<Option<QotdConfig>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT guild_id, channel_id, hour, utc_offset_minutes, threads, digest_channel_id,
+                    next_run_at, created_by
+             FROM qotd_configs WHERE guild_id = ?",
+        )?;
+        statement.bind((1, guild_id))?;
+        Ok(Self::read_qotd_configs(&mut statement)?.pop())
+    }
+
+    /// Question of the day schedules across all guilds whose next question is at or before `now`
+    pub async fn get_due_qotd_configs(&self, now: i64) -> Result<Vec<QotdConfig>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT guild_id, channel_id, hour, utc_offset_minutes, threads, digest_channel_id,
+                    next_run_at, created_by
+             FROM qotd_configs WHERE next_run_at <= ? ORDER BY next_run_at",
+        )?;
+        statement.bind((1, now))?;
+        Self::read_qotd_configs(&mut statement)
+    }
+
+    fn read_qotd_configs(statement: &mut sqlite::Statement) -> Result<Vec<QotdConfig>> {
+        let mut configs = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            configs.push(QotdConfig {
+                guild_id: statement.read::<String, _>(0)?,
+                channel_id: statement.read::<String, _>(1)?,
+                hour: statement.read::<i64, _>(2)?,
+                utc_offset_minutes: statement.read::<i64, _>(3)? as i32,
+                threads: statement.read::<i64, _>(4)? != 0,
+                digest_channel_id: statement.read::<Option<String>, _>(5)?,
+                next_run_at: statement.read::<i64, _>(6)?,
+                created_by: statement.read::<String, _>(7)?,
+            });
+        }
+        Ok(configs)
+    }
+
+    /// Stop a guild's questions of the day; returns whether a schedule existed
+    pub async fn delete_qotd_config(&self, guild_id: &str) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare("DELETE FROM qotd_configs WHERE guild_id = ?")?;
+        statement.bind((1, guild_id))?;
+        statement.next()?;
+        Ok(conn.change_count() > 0)
+    }
+
+    /// Set when a guild's next question of the day is due
+    pub async fn advance_qotd_config(&self, guild_id: &str, next_run_at: i64) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("UPDATE qotd_configs SET next_run_at = ? WHERE guild_id = ?")?;
+        statement.bind((1, next_run_at))?;
+        statement.bind((2, guild_id))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Queue a question for a guild; returns its id
+    pub async fn add_qotd_question(
+        &self,
+        guild_id: &str,
+        question: &str,
+        added_by: &str,
+    ) -> Result<i64> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("INSERT INTO qotd_queue (guild_id, question, added_by) VALUES (?, ?, ?)")?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, question))?;
+        statement.bind((3, added_by))?;
+        statement.next()?;
+        drop(statement);
+
+        let mut statement = conn.prepare("SELECT last_insert_rowid()")?;
+        statement.next()?;
+        Ok(statement.read::<i64, _>(0)?)
+    }
+
+    /// A guild's queued questions, next one first
+    pub async fn get_qotd_queue(&self, guild_id: &str) -> Result<Vec<QotdQuestion>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT id, question, added_by FROM qotd_queue WHERE guild_id = ? ORDER BY id",
+        )?;
+        statement.bind((1, guild_id))?;
+
+        let mut queue = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            queue.push(QotdQuestion {
+                id: statement.read::<i64, _>(0)?,
+                question: statement.read::<String, _>(1)?,
+                added_by: statement.read::<String, _>(2)?,
+            });
+        }
+        Ok(queue)
+    }
+
+    /// Take a question out of a guild's queue: the one with `id`, or the next one
+    pub async fn remove_qotd_question(
+        &self,
+        guild_id: &str,
+        id: Option<i64>,
+    ) -> Result<Option<QotdQuestion>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT id, question, added_by FROM qotd_queue
+             WHERE guild_id = ? AND (? IS NULL OR id = ?)
+             ORDER BY id LIMIT 1",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, id))?;
+        statement.bind((3, id))?;
+        let question = match statement.next()? {
+            State::Row => QotdQuestion {
+                id: statement.read::<i64, _>(0)?,
+                question: statement.read::<String, _>(1)?,
+                added_by: statement.read::<String, _>(2)?,
+            },
+            State::Done => return Ok(None),
+        };
+        drop(statement);
+
+        let mut statement = conn.prepare("DELETE FROM qotd_queue WHERE id = ?")?;
+        statement.bind((1, question.id))?;
+        statement.next()?;
+        Ok(Some(question))
+    }
+
+    /// Record a posted question of the day
+    pub async fn record_qotd_post(&self, post: &QotdPost) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT OR REPLACE INTO qotd_posts
+             (message_id, guild_id, channel_id, thread_id, question, posted_at)
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )?;
+        statement.bind((1, post.message_id.as_str()))?;
+        statement.bind((2, post.guild_id.as_str()))?;
+        statement.bind((3, post.channel_id.as_str()))?;
+        statement.bind((4, post.thread_id.as_deref()))?;
+        statement.bind((5, post.question.as_str()))?;
+        statement.bind((6, post.posted_at))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// A guild's posted questions whose best answer hasn't been picked yet, oldest first
+    pub async fn get_undigested_qotd_posts(&self, guild_id: &str) -> Result<Vec<QotdPost>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT guild_id, channel_id, message_id, thread_id, question, posted_at
+             FROM qotd_posts WHERE guild_id = ? AND digested = 0 ORDER BY posted_at",
+        )?;
+        statement.bind((1, guild_id))?;
+
+        let mut posts = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            posts.push(QotdPost {
+                guild_id: statement.read::<String, _>(0)?,
+                channel_id: statement.read::<String, _>(1)?,
+                message_id: statement.read::<String, _>(2)?,
+                thread_id: statement.read::<Option<String>, _>(3)?,
+                question: statement.read::<String, _>(4)?,
+                posted_at: statement.read::<i64, _>(5)?,
+            });
+        }
+        Ok(posts)
+    }
+
+    /// Mark a question digested, archiving its best answer if it had one
+    pub async fn finish_qotd_post(
+        &self,
+        message_id: &str,
+        best_answer: Option<&QotdAnswer>,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "UPDATE qotd_posts
+             SET digested = 1, best_user_id = ?, best_answer = ?, best_reactions = ?
+             WHERE message_id = ?",
+        )?;
+        statement.bind((1, best_answer.map(|a| a.user_id.as_str())))?;
+        statement.bind((2, best_answer.map(|a| a.content.as_str())))?;
+        statement.bind((3, best_answer.map(|a| a.reactions)))?;
+        statement.bind((4, message_id))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// The questions a guild was asked most recently, newest first
+    pub async fn get_recent_qotd_questions(
+        &self,
+        guild_id: &str,
+        limit: i64,
+    ) -> Result<Vec<String>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT question FROM qotd_posts WHERE guild_id = ? ORDER BY posted_at DESC LIMIT ?",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, limit))?;
+
+        let mut questions = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            questions.push(statement.read::<String, _>(0)?);
+        }
+        Ok(questions)
+    }
+
+    // Suggestion Methods
+
+    /// Save a new suggestion and return its ID
+    pub async fn create_suggestion(
+        &self,
+        guild_id: &str,
+        channel_id: &str,
+        author_id: &str,
+        content: &str,
+    ) -> Result<i64> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO suggestions (guild_id, channel_id, author_id, content) VALUES (?, ?, ?, ?)",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, channel_id))?;
+        statement.bind((3, author_id))?;
+        statement.bind((4, content))?;
+        statement.next()?;
+
+        let mut statement = conn.prepare("SELECT last_insert_rowid()")?;
+        statement.next()?;
+        Ok(statement.read::<i64, _>(0)?)
+    }
+
+    /// Remember the message a suggestion was posted as
+    pub async fn set_suggestion_message(&self, id: i64, message_id: &str) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare("UPDATE suggestions SET message_id = ? WHERE id = ?")?;
+        statement.bind((1, message_id))?;
+        statement.bind((2, id))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// A suggestion with its current vote tally
+    pub async fn get_suggestion(&self, id: i64) -> Result<Option<Suggestion>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(format!(
+            "{SUGGESTION_COLUMNS} FROM suggestions s WHERE s.id = ?"
+        ))?;
+        statement.bind((1, id))?;
+        Ok(Self::read_suggestions(&mut statement)?.pop())
+    }
+
+    /// A guild's suggestions, newest first, optionally only those with `status`
+    pub async fn get_suggestions(
+        &self,
+        guild_id: &str,
+        status: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Suggestion>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(format!(
+            "{SUGGESTION_COLUMNS} FROM suggestions s
+             WHERE s.guild_id = ? AND (? IS NULL OR s.status = ?)
+             ORDER BY s.id DESC LIMIT ?"
+        ))?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, status))?;
+        statement.bind((3, status))?;
+        statement.bind((4, limit))?;
+        Self::read_suggestions(&mut statement)
+    }
+
+    fn read_suggestions(statement: &mut sqlite::Statement) -> Result<Vec<Suggestion>> {
+        let mut suggestions = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            suggestions.push(Suggestion {
+                id: statement.read::<i64, _>(0)?,
+                guild_id: statement.read::<String, _>(1)?,
+                channel_id: statement.read::<String, _>(2)?,
+                message_id: statement.read::<Option<String>, _>(3)?,
+                author_id: statement.read::<String, _>(4)?,
+                content: statement.read::<String, _>(5)?,
+                status: statement.read::<String, _>(6)?,
+                status_note: statement.read::<Option<String>, _>(7)?,
+                reviewed_by: statement.read::<Option<String>, _>(8)?,
+                upvotes: statement.read::<i64, _>(9)?,
+                downvotes: statement.read::<i64, _>(10)?,
+            });
+        }
+        Ok(suggestions)
+    }
+
+    /// Cast `vote` (1 or -1) on a suggestion; casting the same vote again withdraws it
+    ///
+    /// Returns the member's vote afterwards, 0 when withdrawn.
+    pub async fn cast_suggestion_vote(
+        &self,
+        suggestion_id: i64,
+        user_id: &str,
+        vote: i64,
+    ) -> Result<i64> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "DELETE FROM suggestion_votes WHERE suggestion_id = ? AND user_id = ? AND vote = ?",
+        )?;
+        statement.bind((1, suggestion_id))?;
+        statement.bind((2, user_id))?;
+        statement.bind((3, vote))?;
+        statement.next()?;
+        if conn.change_count() > 0 {
+            return Ok(0);
+        }
+
+        let mut statement = conn.prepare(
+            "INSERT INTO suggestion_votes (suggestion_id, user_id, vote) VALUES (?, ?, ?)
+             ON CONFLICT(suggestion_id, user_id) DO UPDATE SET vote = excluded.vote",
+        )?;
+        statement.bind((1, suggestion_id))?;
+        statement.bind((2, user_id))?;
+        statement.bind((3, vote))?;
+        statement.next()?;
+        Ok(vote)
+    }
+
+    /// Delete a suggestion and its votes, e.g. when it couldn't be posted
+    pub async fn delete_suggestion(&self, id: i64) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare("DELETE FROM suggestion_votes WHERE suggestion_id = ?")?;
+        statement.bind((1, id))?;
+        statement.next()?;
+        let mut statement = conn.prepare("DELETE FROM suggestions WHERE id = ?")?;
+        statement.bind((1, id))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Set a suggestion's status; returns false when it isn't in `guild_id`
+    pub async fn set_suggestion_status(
+        &self,
+        guild_id: &str,
+        id: i64,
+        status: &str,
+        note: Option<&str>,
+        reviewed_by: &str,
+    ) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "UPDATE suggestions
+             SET status = ?, status_note = ?, reviewed_by = ?, updated_at = CURRENT_TIMESTAMP
+             WHERE guild_id = ? AND id = ?",
+        )?;
+        statement.bind((1, status))?;
+        statement.bind((2, note))?;
+        statement.bind((3, reviewed_by))?;
+        statement.bind((4, guild_id))?;
+        statement.bind((5, id))?;
+        statement.next()?;
+        Ok(conn.change_count() > 0)
+    }
+
+    // Support Ticket Methods
+
+    /// Record a ticket opened in `thread_id` and return its ID
+    pub async fn create_support_ticket(
+        &self,
+        guild_id: &str,
+        thread_id: &str,
+        requester_id: &str,
+        subject: &str,
+        opened_at: i64,
+    ) -> Result<i64> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO support_tickets (guild_id, thread_id, requester_id, subject, opened_at)
+             VALUES (?, ?, ?, ?, ?)",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, thread_id))?;
+        statement.bind((3, requester_id))?;
+        statement.bind((4, subject))?;
+        statement.bind((5, opened_at))?;
+        statement.next()?;
+
+        let mut statement = conn.prepare("SELECT last_insert_rowid()")?;
+        statement.next()?;
+        Ok(statement.read::<i64, _>(0)?)
+    }
+
+    /// The ticket living in `thread_id`
+    pub async fn get_support_ticket_by_thread(
+        &self,
+        thread_id: &str,
+    ) -> Result<Option<SupportTicket>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT id, guild_id, thread_id, requester_id, subject, status, opened_at, closed_at,
+                    closed_by, first_line_answered, summary
+             FROM support_tickets WHERE thread_id = ?",
+        )?;
+        statement.bind((1, thread_id))?;
+
+        if let Ok(State::Row) = statement.next() {
+            Ok(Some(SupportTicket {
+                id: statement.read::<i64, _>(0)?,
+                guild_id: statement.read::<String, _>(1)?,
+                thread_id: statement.read::<String, _>(2)?,
+                requester_id: statement.read::<String, _>(3)?,
+                subject: statement.read::<String, _>(4)?,
+                status: statement.read::<String, _>(5)?,
+                opened_at: statement.read::<i64, _>(6)?,
+                closed_at: statement.read::<Option<i64>, _>(7)?,
+                closed_by: statement.read::<Option<String>, _>(8)?,
+                first_line_answered: statement.read::<i64, _>(9)? != 0,
+                summary: statement.read::<Option<String>, _>(10)?,
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Note that the persona answered a ticket from the knowledge base
+    pub async fn mark_support_first_line(&self, id: i64) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("UPDATE support_tickets SET first_line_answered = 1 WHERE id = ?")?;
+        statement.bind((1, id))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Close an open ticket; returns false when it was already closed
+    pub async fn close_support_ticket(
+        &self,
+        id: i64,
+        closed_by: &str,
+        closed_at: i64,
+        summary: Option<&str>,
+    ) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "UPDATE support_tickets SET status = 'closed', closed_by = ?, closed_at = ?, summary = ?
+             WHERE id = ? AND status = 'open'",
+        )?;
+        statement.bind((1, closed_by))?;
+        statement.bind((2, closed_at))?;
+        statement.bind((3, summary))?;
+        statement.bind((4, id))?;
+        statement.next()?;
+        Ok(conn.change_count() > 0)
+    }
+
+    /// Ticket metrics for a guild as of `now`
+    pub async fn get_support_stats(&self, guild_id: &str, now: i64) -> Result<SupportStats> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT
+                COALESCE(SUM(status = 'open'), 0),
+                COALESCE(SUM(status = 'closed'), 0),
+                CAST(AVG(CASE WHEN status = 'closed' THEN closed_at - opened_at END) AS INTEGER),
+                COALESCE(SUM(first_line_answered), 0),
+                COALESCE(SUM(opened_at >= ?), 0)
+             FROM support_tickets WHERE guild_id = ?",
+        )?;
+        statement.bind((1, now - 7 * 86_400))?;
+        statement.bind((2, guild_id))?;
+        statement.next()?;
+        Ok(SupportStats {
+            open: statement.read::<i64, _>(0)?,
+            closed: statement.read::<i64, _>(1)?,
+            avg_resolution_secs: statement.read::<Option<i64>, _>(2)?,
+            first_line_answered: statement.read::<i64, _>(3)?,
+            opened_last_week: statement.read::<i64, _>(4)?,
+        })
+    }
+
+    /// Add a knowledge base entry and return its ID
+    pub async fn add_support_kb_entry(
+        &self,
+        guild_id: &str,
+        title: &str,
+        content: &str,
+        created_by: &str,
+    ) -> Result<i64> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO support_kb (guild_id, title, content, created_by) VALUES (?, ?, ?, ?)",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, title))?;
+        statement.bind((3, content))?;
+        statement.bind((4, created_by))?;
+        statement.next()?;
+
+        let mut statement = conn.prepare("SELECT last_insert_rowid()")?;
+        statement.next()?;
+        Ok(statement.read::<i64, _>(0)?)
+    }
+
+    /// A guild's knowledge base, oldest entry first
+    pub async fn get_support_kb(&self, guild_id: &str) -> Result<Vec<SupportKbEntry>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn
+            .prepare("SELECT id, title, content FROM support_kb WHERE guild_id = ? ORDER BY id")?;
+        statement.bind((1, guild_id))?;
+
+        let mut entries = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            entries.push(SupportKbEntry {
+                id: statement.read::<i64, _>(0)?,
+                title: statement.read::<String, _>(1)?,
+                content: statement.read::<String, _>(2)?,
+            });
+        }
+        Ok(entries)
+    }
+
+    /// Remove a knowledge base entry; returns whether the guild had it
+    pub async fn delete_support_kb_entry(&self, guild_id: &str, id: i64) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare("DELETE FROM support_kb WHERE guild_id = ? AND id = ?")?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, id))?;
+        statement.next()?;
+        Ok(conn.change_count() > 0)
+    }
+
+    // Anonymous Question Methods
+
+    /// Store an anonymous question with its initial status and return its ID
+    pub async fn create_anon_question(
+        &self,
+        guild_id: &str,
+        author_id: &str,
+        content: &str,
+        status: &str,
+        created_at: i64,
+    ) -> Result<i64> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO anon_questions (guild_id, author_id, content, status, created_at)
+             VALUES (?, ?, ?, ?, ?)",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, author_id))?;
+        statement.bind((3, content))?;
+        statement.bind((4, status))?;
+        statement.bind((5, created_at))?;
+        statement.next()?;
+
+        let mut statement = conn.prepare("SELECT last_insert_rowid()")?;
+        statement.next()?;
+        Ok(statement.read::<i64, _>(0)?)
+    }
+
+    /// A guild's anonymous question by ID
+    pub async fn get_anon_question(&self, guild_id: &str, id: i64) -> Result<Option<AnonQuestion>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT id, guild_id, author_id, content, status, created_at, reviewed_by, message_id
+             FROM anon_questions WHERE guild_id = ? AND id = ?",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, id))?;
+        Ok(Self::read_anon_questions(&mut statement)?.pop())
+    }
+
+    /// Questions awaiting moderation, oldest first
+    pub async fn get_pending_anon_questions(
+        &self,
+        guild_id: &str,
+        limit: i64,
+    ) -> Result<Vec<AnonQuestion>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT id, guild_id, author_id, content, status, created_at, reviewed_by, message_id
+             FROM anon_questions WHERE guild_id = ? AND status = 'pending'
+             ORDER BY id LIMIT ?",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, limit))?;
+        Self::read_anon_questions(&mut statement)
+    }
+
+    fn read_anon_questions(statement: &mut sqlite::Statement) -> Result<Vec<AnonQuestion>> {
+        let mut questions = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            questions.push(AnonQuestion {
+                id: statement.read::<i64, _>(0)?,
+                guild_id: statement.read::<String, _>(1)?,
+                author_id: statement.read::<String, _>(2)?,
+                content: statement.read::<String, _>(3)?,
+                status: statement.read::<String, _>(4)?,
+                created_at: statement.read::<i64, _>(5)?,
+                reviewed_by: statement.read::<Option<String>, _>(6)?,
+                message_id: statement.read::<Option<String>, _>(7)?,
+            });
+        }
+        Ok(questions)
+    }
+
+    /// Approve or reject a pending question; returns false when it wasn't pending
+    pub async fn review_anon_question(
+        &self,
+        guild_id: &str,
+        id: i64,
+        status: &str,
+        reviewed_by: &str,
+    ) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "UPDATE anon_questions SET status = ?, reviewed_by = ?
+             WHERE guild_id = ? AND id = ? AND status = 'pending'",
+        )?;
+        statement.bind((1, status))?;
+        statement.bind((2, reviewed_by))?;
+        statement.bind((3, guild_id))?;
+        statement.bind((4, id))?;
+        statement.next()?;
+        Ok(conn.change_count() > 0)
+    }
+
+    /// Remember the message a question was posted as
+    pub async fn set_anon_question_message(&self, id: i64, message_id: &str) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("UPDATE anon_questions SET message_id = ? WHERE id = ?")?;
+        statement.bind((1, message_id))?;
+        statement.bind((2, id))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    // Verification Methods
+
+    /// Start (or restart) a member's verification
+    pub async fn upsert_pending_verification(&self, pending: &PendingVerification) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT OR REPLACE INTO verification_pending
+                (guild_id, user_id, channel_id, message_id, answer, deadline)
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )?;
+        statement.bind((1, pending.guild_id.as_str()))?;
+        statement.bind((2, pending.user_id.as_str()))?;
+        statement.bind((3, pending.channel_id.as_str()))?;
+        statement.bind((4, pending.message_id.as_deref()))?;
+        statement.bind((5, pending.answer.as_deref()))?;
+        statement.bind((6, pending.deadline))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// A member's pending verification, if they have one
+    pub async fn get_pending_verification(
+        &self,
+        guild_id: &str,
+        user_id: &str,
+    ) -> Result<Option<PendingVerification>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT guild_id, user_id, channel_id, message_id, answer, deadline
+             FROM verification_pending WHERE guild_id = ? AND user_id = ?",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, user_id))?;
+        Ok(Self::read_pending_verifications(&mut statement)?.pop())
+    }
+
+    /// Pending verifications whose deadline has passed
+    pub async fn get_expired_verifications(&self, now: i64) -> Result<Vec<PendingVerification>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT guild_id, user_id, channel_id, message_id, answer, deadline
+             FROM verification_pending WHERE deadline IS NOT NULL AND deadline <= ?",
+        )?;
+        statement.bind((1, now))?;
+        Self::read_pending_verifications(&mut statement)
+    }
+
+    fn read_pending_verifications(
+        statement: &mut sqlite::Statement,
+    ) -> Result<Vec<PendingVerification>> {
+        let mut pending = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            pending.push(PendingVerification {
+                guild_id: statement.read::<String, _>(0)?,
+                user_id: statement.read::<String, _>(1)?,
+                channel_id: statement.read::<String, _>(2)?,
+                message_id: statement.read::<Option<String>, _>(3)?,
+                answer: statement.read::<Option<String>, _>(4)?,
+                deadline: statement.read::<Option<i64>, _>(5)?,
+            });
+        }
+        Ok(pending)
+    }
+
+    /// Finish a member's verification; returns whether one was pending
+    pub async fn delete_pending_verification(&self, guild_id: &str, user_id: &str) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("DELETE FROM verification_pending WHERE guild_id = ? AND user_id = ?")?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, user_id))?;
+        statement.next()?;
+        Ok(conn.change_count() > 0)
+    }
+
+    /// Drop every pending verification in a guild, returning how many there were
+    pub async fn clear_pending_verifications(&self, guild_id: &str) -> Result<usize> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare("DELETE FROM verification_pending WHERE guild_id = ?")?;
+        statement.bind((1, guild_id))?;
+        statement.next()?;
+        Ok(conn.change_count())
+    }
+
+    /// Number of members still waiting to verify in a guild
+    pub async fn count_pending_verifications(&self, guild_id: &str) -> Result<i64> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("SELECT COUNT(*) FROM verification_pending WHERE guild_id = ?")?;
+        statement.bind((1, guild_id))?;
+        statement.next()?;
+        Ok(statement.read::<i64, _>(0)?)
+    }
+
+    // Auto-Response Methods
+
+    /// Store a new trigger and return its ID
+    pub async fn add_autoresponse(&self, trigger: &AutoResponse) -> Result<i64> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO autoresponses
+             (guild_id, pattern, match_kind, response, response_kind, cooldown_secs, enabled, priority, created_by)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )?;
+        statement.bind((1, trigger.guild_id.as_str()))?;
+        statement.bind((2, trigger.pattern.as_str()))?;
+        statement.bind((3, trigger.match_kind.as_str()))?;
+        statement.bind((4, trigger.response.as_str()))?;
+        statement.bind((5, trigger.response_kind.as_str()))?;
+        statement.bind((6, trigger.cooldown_secs))?;
+        statement.bind((7, trigger.enabled as i64))?;
+        statement.bind((8, trigger.priority))?;
+        statement.bind((9, trigger.created_by.as_str()))?;
+        statement.next()?;
+        self.settings_cache.autoresponses.remove(&trigger.guild_id);
+
+        let mut statement = conn.prepare("SELECT last_insert_rowid()")?;
+        statement.next()?;
+        Ok(statement.read::<i64, _>(0)?)
+    }
+
+    /// A guild's triggers in evaluation order: highest priority first, then oldest
+    pub async fn get_autoresponses(&self, guild_id: &str) -> Result<Vec<AutoResponse>> {
+        let cache = &self.settings_cache;
+        if let Some(triggers) = cache.autoresponses.get(&guild_id.to_string(), cache.ttl) {
+            return Ok(triggers);
+        }
+
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT id, guild_id, pattern, match_kind, response, response_kind, cooldown_secs,
+                    enabled, priority, created_by
+             FROM autoresponses WHERE guild_id = ?
+             ORDER BY priority DESC, id",
+        )?;
+        statement.bind((1, guild_id))?;
+        let triggers = Self::read_autoresponses(&mut statement)?;
+        cache
+            .autoresponses
+            .insert(guild_id.to_string(), triggers.clone());
+        Ok(triggers)
+    }
+
+    /// Count a guild's triggers
+    pub async fn count_autoresponses(&self, guild_id: &str) -> Result<i64> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("SELECT COUNT(*) FROM autoresponses WHERE guild_id = ?")?;
+        statement.bind((1, guild_id))?;
+        statement.next()?;
+        Ok(statement.read::<i64, _>(0)?)
+    }
+
+    fn read_autoresponses(statement: &mut sqlite::Statement) -> Result<Vec<AutoResponse>> {
+        let mut triggers = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            triggers.push(AutoResponse {
+                id: statement.read::<i64, _>(0)?,
+                guild_id: statement.read::<String, _>(1)?,
+                pattern: statement.read::<String, _>(2)?,
+                match_kind: statement.read::<String, _>(3)?,
+                response: statement.read::<String, _>(4)?,
+                response_kind: statement.read::<String, _>(5)?,
+                cooldown_secs: statement.read::<i64, _>(6)?,
+                enabled: statement.read::<i64, _>(7)? != 0,
+                priority: statement.read::<i64, _>(8)?,
+                created_by: statement.read::<String, _>(9)?,
+            });
+        }
+        Ok(triggers)
+    }
+
+    /// Delete a guild's trigger; returns false when it didn't exist
+    pub async fn remove_autoresponse(&self, guild_id: &str, id: i64) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("DELETE FROM autoresponses WHERE guild_id = ? AND id = ?")?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, id))?;
+        statement.next()?;
+        self.settings_cache
+            .autoresponses
+            .remove(&guild_id.to_string());
+        Ok(conn.change_count() > 0)
+    }
+
+    /// Turn a guild's trigger on or off; returns false when it didn't exist
+    pub async fn set_autoresponse_enabled(
+        &self,
+        guild_id: &str,
+        id: i64,
+        enabled: bool,
+    ) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("UPDATE autoresponses SET enabled = ? WHERE guild_id = ? AND id = ?")?;
+        statement.bind((1, enabled as i64))?;
+        statement.bind((2, guild_id))?;
+        statement.bind((3, id))?;
+        statement.next()?;
+        self.settings_cache
+            .autoresponses
+            .remove(&guild_id.to_string());
+        Ok(conn.change_count() > 0)
+    }
+
+    /// Change where a guild's trigger sits in the evaluation order
+    pub async fn set_autoresponse_priority(
+        &self,
+        guild_id: &str,
+        id: i64,
+        priority: i64,
+    ) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("UPDATE autoresponses SET priority = ? WHERE guild_id = ? AND id = ?")?;
+        statement.bind((1, priority))?;
+        statement.bind((2, guild_id))?;
+        statement.bind((3, id))?;
+        statement.next()?;
+        self.settings_cache
+            .autoresponses
+            .remove(&guild_id.to_string());
+        Ok(conn.change_count() > 0)
+    }
+
+    // Prompt Alias Methods
+
+    /// Create or replace an alias in its scope
+    pub async fn upsert_prompt_alias(&self, alias: &PromptAlias) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO prompt_aliases (scope, scope_id, name, template, persona, created_by)
+             VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT(scope, scope_id, name) DO UPDATE SET
+                template = excluded.template,
+                persona = excluded.persona,
+                created_by = excluded.created_by",
+        )?;
+        statement.bind((1, alias.scope.as_str()))?;
+        statement.bind((2, alias.scope_id.as_str()))?;
+        statement.bind((3, alias.name.as_str()))?;
+        statement.bind((4, alias.template.as_str()))?;
+        statement.bind((5, alias.persona.as_deref()))?;
+        statement.bind((6, alias.created_by.as_str()))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// An alias by name within one scope
+    pub async fn get_prompt_alias(
+        &self,
+        scope: &str,
+        scope_id: &str,
+        name: &str,
+    ) -> Result<Option<PromptAlias>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT scope, scope_id, name, template, persona, created_by
+             FROM prompt_aliases WHERE scope = ? AND scope_id = ? AND name = ?",
+        )?;
+        statement.bind((1, scope))?;
+        statement.bind((2, scope_id))?;
+        statement.bind((3, name))?;
+        Ok(Self::read_prompt_aliases(&mut statement)?.pop())
+    }
+
+    /// All aliases in one scope, by name
+    pub async fn get_prompt_aliases(
+        &self,
+        scope: &str,
+        scope_id: &str,
+    ) -> Result<Vec<PromptAlias>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT scope, scope_id, name, template, persona, created_by
+             FROM prompt_aliases WHERE scope = ? AND scope_id = ? ORDER BY name",
+        )?;
+        statement.bind((1, scope))?;
+        statement.bind((2, scope_id))?;
+        Self::read_prompt_aliases(&mut statement)
+    }
+
+    fn read_prompt_aliases(statement: &mut sqlite::Statement) -> Result<Vec<PromptAlias>> {
+        let mut aliases = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            aliases.push(PromptAlias {
+                scope: statement.read::<String, _>(0)?,
+                scope_id: statement.read::<String, _>(1)?,
+                name: statement.read::<String, _>(2)?,
+                template: statement.read::<String, _>(3)?,
+                persona: statement.read::<Option<String>, _>(4)?,
+                created_by: statement.read::<String, _>(5)?,
+            });
+        }
+        Ok(aliases)
+    }
+
+    /// Delete an alias; returns false when it didn't exist
+    pub async fn delete_prompt_alias(
+        &self,
+        scope: &str,
+        scope_id: &str,
+        name: &str,
+    ) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn
+            .prepare("DELETE FROM prompt_aliases WHERE scope = ? AND scope_id = ? AND name = ?")?;
+        statement.bind((1, scope))?;
+        statement.bind((2, scope_id))?;
+        statement.bind((3, name))?;
+        statement.next()?;
+        Ok(conn.change_count() > 0)
+    }
+
+    /// Count the aliases in one scope
+    pub async fn count_prompt_aliases(&self, scope: &str, scope_id: &str) -> Result<i64> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("SELECT COUNT(*) FROM prompt_aliases WHERE scope = ? AND scope_id = ?")?;
+        statement.bind((1, scope))?;
+        statement.bind((2, scope_id))?;
+        statement.next()?;
+        Ok(statement.read::<i64, _>(0)?)
+    }
+
+    // Prompt Library Methods
+
+    /// Create or replace a prompt in a guild's library, keeping its use count
+    pub async fn upsert_guild_prompt(&self, prompt: &GuildPrompt) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO guild_prompts (guild_id, name, category, description, template, created_by)
+             VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT(guild_id, name) DO UPDATE SET
+                category = excluded.category,
+                description = excluded.description,
+                template = excluded.template,
+                created_by = excluded.created_by",
+        )?;
+        statement.bind((1, prompt.guild_id.as_str()))?;
+        statement.bind((2, prompt.name.as_str()))?;
+        statement.bind((3, prompt.category.as_str()))?;
+        statement.bind((4, prompt.description.as_deref()))?;
+        statement.bind((5, prompt.template.as_str()))?;
+        statement.bind((6, prompt.created_by.as_str()))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// A guild's prompt by name
+    pub async fn get_guild_prompt(
+        &self,
+        guild_id: &str,
+        name: &str,
+    ) -> Result<Option<GuildPrompt>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT guild_id, name, category, description, template, created_by, uses
+             FROM guild_prompts WHERE guild_id = ? AND name = ?",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, name))?;
+        Ok(Self::read_guild_prompts(&mut statement)?.pop())
+    }
+
+    /// A guild's prompts by category then name, optionally only one category
+    pub async fn get_guild_prompts(
+        &self,
+        guild_id: &str,
+        category: Option<&str>,
+    ) -> Result<Vec<GuildPrompt>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT guild_id, name, category, description, template, created_by, uses
+             FROM guild_prompts WHERE guild_id = ? AND (? IS NULL OR category = ?)
+             ORDER BY category, name",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, category))?;
+        statement.bind((3, category))?;
+        Self::read_guild_prompts(&mut statement)
+    }
+
+    fn read_guild_prompts(statement: &mut sqlite::Statement) -> Result<Vec<GuildPrompt>> {
+        let mut prompts = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            prompts.push(GuildPrompt {
+                guild_id: statement.read::<String, _>(0)?,
+                name: statement.read::<String, _>(1)?,
+                category: statement.read::<String, _>(2)?,
+                description: statement.read::<Option<String>, _>(3)?,
+                template: statement.read::<String, _>(4)?,
+                created_by: statement.read::<String, _>(5)?,
+                uses: statement.read::<i64, _>(6)?,
+            });
+        }
+        Ok(prompts)
+    }
+
+    /// Delete a guild's prompt; returns false when it didn't exist
+    pub async fn delete_guild_prompt(&self, guild_id: &str, name: &str) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("DELETE FROM guild_prompts WHERE guild_id = ? AND name = ?")?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, name))?;
+        statement.next()?;
+        Ok(conn.change_count() > 0)
+    }
+
+    /// Count a guild's prompts
+    pub async fn count_guild_prompts(&self, guild_id: &str) -> Result<i64> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("SELECT COUNT(*) FROM guild_prompts WHERE guild_id = ?")?;
+        statement.bind((1, guild_id))?;
+        statement.next()?;
+        Ok(statement.read::<i64, _>(0)?)
+    }
+
+    /// Count one run of a guild's prompt
+    pub async fn record_guild_prompt_use(&self, guild_id: &str, name: &str) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn
+            .prepare("UPDATE guild_prompts SET uses = uses + 1 WHERE guild_id = ? AND name = ?")?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, name))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    // Scheduled Message Methods
+
+    /// Queue a message and return its ID
+    pub async fn add_scheduled_message(&self, message: &ScheduledMessage) -> Result<i64> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO scheduled_messages (guild_id, channel_id, user_id, content, persona, send_at)
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )?;
+        statement.bind((1, message.guild_id.as_str()))?;
+        statement.bind((2, message.channel_id.as_str()))?;
+        statement.bind((3, message.user_id.as_str()))?;
+        statement.bind((4, message.content.as_str()))?;
+        statement.bind((5, message.persona.as_deref()))?;
+        statement.bind((6, message.send_at))?;
+        statement.next()?;
+
+        let mut statement = conn.prepare("SELECT last_insert_rowid()")?;
+        statement.next()?;
+        Ok(statement.read::<i64, _>(0)?)
+    }
+
+    /// Queued messages whose time has come, oldest first
+    pub async fn get_due_scheduled_messages(&self, now: i64) -> Result<Vec<ScheduledMessage>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT id, guild_id, channel_id, user_id, content, persona, send_at
+             FROM scheduled_messages WHERE send_at <= ?
+             ORDER BY send_at, id",
+        )?;
+        statement.bind((1, now))?;
+        Self::read_scheduled_messages(&mut statement)
+    }
+
+    /// A guild's queued messages, soonest first; only `user_id`'s when given
+    pub async fn get_scheduled_messages(
+        &self,
+        guild_id: &str,
+        user_id: Option<&str>,
+    ) -> Result<Vec<ScheduledMessage>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT id, guild_id, channel_id, user_id, content, persona, send_at
+             FROM scheduled_messages WHERE guild_id = ? AND (? IS NULL OR user_id = ?)
+             ORDER BY send_at, id",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, user_id))?;
+        statement.bind((3, user_id))?;
+        Self::read_scheduled_messages(&mut statement)
+    }
+
+    fn read_scheduled_messages(statement: &mut sqlite::Statement) -> Result<Vec<ScheduledMessage>> {
+        let mut messages = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            messages.push(ScheduledMessage {
+                id: statement.read::<i64, _>(0)?,
+                guild_id: statement.read::<String, _>(1)?,
+                channel_id: statement.read::<String, _>(2)?,
+                user_id: statement.read::<String, _>(3)?,
+                content: statement.read::<String, _>(4)?,
+                persona: statement.read::<Option<String>, _>(5)?,
+                send_at: statement.read::<i64, _>(6)?,
+            });
+        }
+        Ok(messages)
+    }
+
+    /// Count a member's queued messages in a guild
+    pub async fn count_scheduled_messages(&self, guild_id: &str, user_id: &str) -> Result<i64> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT COUNT(*) FROM scheduled_messages WHERE guild_id = ? AND user_id = ?",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, user_id))?;
+        statement.next()?;
+        Ok(statement.read::<i64, _>(0)?)
+    }
+
+    /// Drop a queued message; only `user_id`'s when given. Returns whether one was removed
+    pub async fn delete_scheduled_message(
+        &self,
+        id: i64,
+        guild_id: &str,
+        user_id: Option<&str>,
+    ) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "DELETE FROM scheduled_messages
+             WHERE id = ? AND guild_id = ? AND (? IS NULL OR user_id = ?)",
+        )?;
+        statement.bind((1, id))?;
+        statement.bind((2, guild_id))?;
+        statement.bind((3, user_id))?;
+        statement.bind((4, user_id))?;
+        statement.next()?;
+        Ok(conn.change_count() > 0)
+    }
+
+    // Countdown Methods
+
+    /// Store a new countdown and return its ID
+    pub async fn add_countdown(&self, countdown: &Countdown) -> Result<i64> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO countdowns (guild_id, channel_id, message_id, title, ends_at, role_id, created_by)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )?;
+        statement.bind((1, countdown.guild_id.as_str()))?;
+        statement.bind((2, countdown.channel_id.as_str()))?;
+        statement.bind((3, countdown.message_id.as_deref()))?;
+        statement.bind((4, countdown.title.as_str()))?;
+        statement.bind((5, countdown.ends_at))?;
+        statement.bind((6, countdown.role_id.as_deref()))?;
+        statement.bind((7, countdown.created_by.as_str()))?;
+        statement.next()?;
+
+        let mut statement = conn.prepare("SELECT last_insert_rowid()")?;
+        statement.next()?;
+        Ok(statement.read::<i64, _>(0)?)
+    }
+
+    /// Remember the message a countdown was posted as
+    pub async fn set_countdown_message(&self, id: i64, message_id: &str) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare("UPDATE countdowns SET message_id = ? WHERE id = ?")?;
+        statement.bind((1, message_id))?;
+        statement.bind((2, id))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// A countdown by ID
+    pub async fn get_countdown(&self, id: i64) -> Result<Option<Countdown>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT id, guild_id, channel_id, message_id, title, ends_at, role_id, created_by
+             FROM countdowns WHERE id = ?",
+        )?;
+        statement.bind((1, id))?;
+        Ok(Self::read_countdowns(&mut statement)?.pop())
+    }
+
+    /// Every posted countdown, soonest first
+    pub async fn get_active_countdowns(&self) -> Result<Vec<Countdown>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT id, guild_id, channel_id, message_id, title, ends_at, role_id, created_by
+             FROM countdowns WHERE message_id IS NOT NULL
+             ORDER BY ends_at, id",
+        )?;
+        Self::read_countdowns(&mut statement)
+    }
+
+    /// A guild's countdowns, soonest first
+    pub async fn get_guild_countdowns(&self, guild_id: &str) -> Result<Vec<Countdown>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT id, guild_id, channel_id, message_id, title, ends_at, role_id, created_by
+             FROM countdowns WHERE guild_id = ?
+             ORDER BY ends_at, id",
+        )?;
+        statement.bind((1, guild_id))?;
+        Self::read_countdowns(&mut statement)
+    }
+
+    fn read_countdowns(statement: &mut sqlite::Statement) -> Result<Vec<Countdown>> {
+        let mut countdowns = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            countdowns.push(Countdown {
+                id: statement.read::<i64, _>(0)?,
+                guild_id: statement.read::<String, _>(1)?,
+                channel_id: statement.read::<String, _>(2)?,
+                message_id: statement.read::<Option<String>, _>(3)?,
+                title: statement.read::<String, _>(4)?,
+                ends_at: statement.read::<i64, _>(5)?,
+                role_id: statement.read::<Option<String>, _>(6)?,
+                created_by: statement.read::<String, _>(7)?,
+            });
+        }
+        Ok(countdowns)
+    }
+
+    /// Count a guild's countdowns
+    pub async fn count_countdowns(&self, guild_id: &str) -> Result<i64> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare("SELECT COUNT(*) FROM countdowns WHERE guild_id = ?")?;
+        statement.bind((1, guild_id))?;
+        statement.next()?;
+        Ok(statement.read::<i64, _>(0)?)
+    }
+
+    /// Stop a countdown; returns whether one was removed
+    pub async fn delete_countdown(&self, id: i64) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare("DELETE FROM countdowns WHERE id = ?")?;
+        statement.bind((1, id))?;
+        statement.next()?;
+        Ok(conn.change_count() > 0)
+    }
+
+    // Initiative Methods
+
+    /// Add a combatant to a thread's order, replacing one with the same name
+    pub async fn upsert_initiative_entry(&self, entry: &InitiativeEntry) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO initiative_entries (thread_id, guild_id, name, initiative, modifier, added_by)
+             VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT(thread_id, name) DO UPDATE SET
+                name = excluded.name,
+                initiative = excluded.initiative,
+                modifier = excluded.modifier,
+                added_by = excluded.added_by",
+        )?;
+        statement.bind((1, entry.thread_id.as_str()))?;
+        statement.bind((2, entry.guild_id.as_str()))?;
+        statement.bind((3, entry.name.as_str()))?;
+        statement.bind((4, entry.initiative))?;
+        statement.bind((5, entry.modifier))?;
+        statement.bind((6, entry.added_by.as_str()))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// A thread's combatants in turn order: highest initiative first, ties
+    /// to the higher modifier
+    pub async fn get_initiative_order(&self, thread_id: &str) -> Result<Vec<InitiativeEntry>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT thread_id, guild_id, name, initiative, modifier, added_by
+             FROM initiative_entries WHERE thread_id = ?
+             ORDER BY initiative DESC, modifier DESC, created_at, name",
+        )?;
+        statement.bind((1, thread_id))?;
+        let mut entries = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            entries.push(InitiativeEntry {
+                thread_id: statement.read::<String, _>(0)?,
+                guild_id: statement.read::<String, _>(1)?,
+                name: statement.read::<String, _>(2)?,
+                initiative: statement.read::<i64, _>(3)?,
+                modifier: statement.read::<i64, _>(4)?,
+                added_by: statement.read::<String, _>(5)?,
+            });
+        }
+        Ok(entries)
+    }
+
+    /// Remove a combatant by name, ignoring case; returns whether one was removed
+    pub async fn delete_initiative_entry(&self, thread_id: &str, name: &str) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("DELETE FROM initiative_entries WHERE thread_id = ? AND name = ?")?;
+        statement.bind((1, thread_id))?;
+        statement.bind((2, name))?;
+        statement.next()?;
+        Ok(conn.change_count() > 0)
+    }
+
+    /// A thread's current turn index and round, `(0, 1)` before the first turn
+    pub async fn get_initiative_turn(&self, thread_id: &str) -> Result<(usize, i64)> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("SELECT turn, round FROM initiative_turns WHERE thread_id = ?")?;
+        statement.bind((1, thread_id))?;
+        if let Ok(State::Row) = statement.next() {
+            let turn = statement.read::<i64, _>(0)?;
+            return Ok((turn.max(0) as usize, statement.read::<i64, _>(1)?));
+        }
+        Ok((0, 1))
+    }
+
+    /// Save a thread's current turn index and round
+    pub async fn set_initiative_turn(
+        &self,
+        thread_id: &str,
+        turn: usize,
+        round: i64,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO initiative_turns (thread_id, turn, round) VALUES (?, ?, ?)
+             ON CONFLICT(thread_id) DO UPDATE SET turn = excluded.turn, round = excluded.round",
+        )?;
+        statement.bind((1, thread_id))?;
+        statement.bind((2, turn as i64))?;
+        statement.bind((3, round))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// End a thread's encounter; returns how many combatants were removed
+    pub async fn clear_initiative(&self, thread_id: &str) -> Result<usize> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare("DELETE FROM initiative_entries WHERE thread_id = ?")?;
+        statement.bind((1, thread_id))?;
+        statement.next()?;
+        let removed = conn.change_count();
+        let mut statement = conn.prepare("DELETE FROM initiative_turns WHERE thread_id = ?")?;
+        statement.bind((1, thread_id))?;
+        statement.next()?;
+        Ok(removed)
+    }
+
+    // DM History Methods
+
+    /// Title a DM session; the first title is kept
+    pub async fn set_dm_session_title(&self, session_id: &str, title: &str) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn
+            .prepare("INSERT OR IGNORE INTO dm_session_titles (session_id, title) VALUES (?, ?)")?;
+        statement.bind((1, session_id))?;
+        statement.bind((2, title))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Whether a DM session has a title yet
+    pub async fn has_dm_session_title(&self, session_id: &str) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare("SELECT 1 FROM dm_session_titles WHERE session_id = ?")?;
+        statement.bind((1, session_id))?;
+        Ok(matches!(statement.next(), Ok(State::Row)))
+    }
+
+    fn read_dm_session_summaries(
+        statement: &mut sqlite::Statement,
+    ) -> Result<Vec<DmSessionSummary>> {
+        let mut sessions = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            sessions.push(DmSessionSummary {
+                session_id: statement.read::<String, _>(0)?,
+                channel_id: statement.read::<String, _>(1)?,
+                title: statement.read::<Option<String>, _>(2)?,
+                started_at: statement.read::<String, _>(3)?,
+                ended_at: statement.read::<Option<String>, _>(4)?,
+                message_count: statement.read::<i64, _>(5)?,
+            });
+        }
+        Ok(sessions)
+    }
+
+    /// A user's DM sessions with their titles, newest first
+    pub async fn get_dm_session_summaries(
+        &self,
+        user_id: &str,
+        limit: i64,
+    ) -> Result<Vec<DmSessionSummary>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT s.session_id, s.channel_id, t.title, s.started_at, s.ended_at, s.message_count
+             FROM dm_sessions s
+             LEFT JOIN dm_session_titles t ON t.session_id = s.session_id
+             WHERE s.user_id = ?
+             ORDER BY s.started_at DESC, s.id DESC
+             LIMIT ?",
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, limit))?;
+        Self::read_dm_session_summaries(&mut statement)
+    }
+
+    /// One of a user's DM sessions; None if it isn't theirs
+    pub async fn get_dm_session_summary(
+        &self,
+        user_id: &str,
+        session_id: &str,
+    ) -> Result<Option<DmSessionSummary>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT s.session_id, s.channel_id, t.title, s.started_at, s.ended_at, s.message_count
+             FROM dm_sessions s
+             LEFT JOIN dm_session_titles t ON t.session_id = s.session_id
+             WHERE s.user_id = ? AND s.session_id = ?",
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, session_id))?;
+        Ok(Self::read_dm_session_summaries(&mut statement)?
+            .into_iter()
+            .next())
+    }
+
+    /// The last `limit` stored messages of a user's DM session, oldest first
+    ///
+    /// Messages are matched to the session by channel and time, with a minute
+    /// of slack on both ends since the session row is written asynchronously.
+    pub async fn get_dm_session_messages(
+        &self,
+        user_id: &str,
+        session_id: &str,
+        limit: i64,
+    ) -> Result<Vec<(String, String)>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT h.role, h.content
+             FROM dm_sessions s
+             JOIN conversation_history h
+               ON h.user_id = s.user_id AND h.channel_id = s.channel_id
+             WHERE s.user_id = ? AND s.session_id = ?
+               AND h.timestamp >= datetime(s.started_at, '-1 minute')
+               AND h.timestamp <= datetime(COALESCE(s.ended_at, CURRENT_TIMESTAMP), '+1 minute')
+             ORDER BY h.timestamp DESC, h.id DESC
+             LIMIT ?",
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, session_id))?;
+        statement.bind((3, limit))?;
+
+        let mut messages = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            messages.push((
+                statement.read::<String, _>(0)?,
+                statement.read::<String, _>(1)?,
+            ));
+        }
+        messages.reverse();
+        Ok(messages)
+    }
+
+    // Pinned Memory Methods
+
+    /// Pin a message as a memory; returns false if it was already pinned
+    pub async fn add_pinned_memory(
+        &self,
+        user_id: &str,
+        channel_id: &str,
+        message_id: &str,
+        content: &str,
+        author_name: &str,
+    ) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT OR IGNORE INTO pinned_memories (user_id, channel_id, message_id, content, author_name)
+             VALUES (?, ?, ?, ?, ?)",
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, channel_id))?;
+        statement.bind((3, message_id))?;
+        statement.bind((4, content))?;
+        statement.bind((5, author_name))?;
+        statement.next()?;
+        Ok(conn.change_count() > 0)
+    }
+
+    fn read_pinned_memories(statement: &mut sqlite::Statement) -> Result<Vec<PinnedMemory>> {
+        let mut memories = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            memories.push(PinnedMemory {
+                id: statement.read::<i64, _>(0)?,
+                user_id: statement.read::<String, _>(1)?,
+                channel_id: statement.read::<String, _>(2)?,
+                content: statement.read::<String, _>(3)?,
+                author_name: statement.read::<String, _>(4)?,
+                created_at: statement.read::<String, _>(5)?,
+            });
+        }
+        Ok(memories)
+    }
+
+    /// A user's memories, oldest first; all channels when `channel_id` is None
+    pub async fn get_pinned_memories(
+        &self,
+        user_id: &str,
+        channel_id: Option<&str>,
+    ) -> Result<Vec<PinnedMemory>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT id, user_id, channel_id, content, author_name, created_at
+             FROM pinned_memories
+             WHERE user_id = ? AND (? IS NULL OR channel_id = ?)
+             ORDER BY created_at ASC, id ASC",
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, channel_id))?;
+        statement.bind((3, channel_id))?;
+        Self::read_pinned_memories(&mut statement)
+    }
+
+    /// Forget one of a user's memories; returns false if it isn't theirs
+    pub async fn delete_pinned_memory(&self, user_id: &str, id: i64) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("DELETE FROM pinned_memories WHERE id = ? AND user_id = ?")?;
+        statement.bind((1, id))?;
+        statement.bind((2, user_id))?;
+        statement.next()?;
+        Ok(conn.change_count() > 0)
+    }
+
+    /// Forget a user's memories in a channel, or everywhere; returns how many
+    pub async fn clear_pinned_memories(
+        &self,
+        user_id: &str,
+        channel_id: Option<&str>,
+    ) -> Result<usize> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "DELETE FROM pinned_memories WHERE user_id = ? AND (? IS NULL OR channel_id = ?)",
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, channel_id))?;
+        statement.bind((3, channel_id))?;
+        statement.next()?;
+        Ok(conn.change_count())
+    }
+
+    // Glossary Methods
+
+    /// Add or redefine a guild's glossary term; returns true when it was new
+    pub async fn upsert_glossary_term(&self, entry: &GlossaryEntry) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("SELECT 1 FROM glossary_terms WHERE guild_id = ? AND term = ?")?;
+        statement.bind((1, entry.guild_id.as_str()))?;
+        statement.bind((2, entry.term.as_str()))?;
+        let exists = matches!(statement.next(), Ok(State::Row));
+
+        let mut statement = conn.prepare(
+            "INSERT INTO glossary_terms (guild_id, term, definition, created_by)
+             VALUES (?, ?, ?, ?)
+             ON CONFLICT(guild_id, term) DO UPDATE SET
+                term = excluded.term,
+                definition = excluded.definition,
+                created_by = excluded.created_by",
+        )?;
+        statement.bind((1, entry.guild_id.as_str()))?;
+        statement.bind((2, entry.term.as_str()))?;
+        statement.bind((3, entry.definition.as_str()))?;
+        statement.bind((4, entry.created_by.as_str()))?;
+        statement.next()?;
+        Ok(!exists)
+    }
+
+    /// A guild's glossary, alphabetically
+    pub async fn get_glossary_terms(&self, guild_id: &str) -> Result<Vec<GlossaryEntry>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT guild_id, term, definition, created_by
+             FROM glossary_terms WHERE guild_id = ? ORDER BY term",
+        )?;
+        statement.bind((1, guild_id))?;
+        Self::read_glossary_entries(&mut statement)
+    }
+
+    fn read_glossary_entries(statement: &mut sqlite::Statement) -> Result<Vec<GlossaryEntry>> {
+        let mut entries = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            entries.push(GlossaryEntry {
+                guild_id: statement.read::<String, _>(0)?,
+                term: statement.read::<String, _>(1)?,
+                definition: statement.read::<String, _>(2)?,
+                created_by: statement.read::<String, _>(3)?,
+            });
+        }
+        Ok(entries)
+    }
+
+    /// Remove a guild's glossary term (any case); returns false when it didn't exist
+    pub async fn delete_glossary_term(&self, guild_id: &str, term: &str) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("DELETE FROM glossary_terms WHERE guild_id = ? AND term = ?")?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, term))?;
+        statement.next()?;
+        Ok(conn.change_count() > 0)
+    }
+
+    /// Count a guild's glossary terms
+    pub async fn count_glossary_terms(&self, guild_id: &str) -> Result<i64> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("SELECT COUNT(*) FROM glossary_terms WHERE guild_id = ?")?;
+        statement.bind((1, guild_id))?;
+        statement.next()?;
+        Ok(statement.read::<i64, _>(0)?)
+    }
+
+    // Standup Methods
+
+    /// Save a guild's standup schedule, keeping its run history
+    pub async fn set_standup_config(&self, config: &StandupConfig) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO standup_configs
+             (guild_id, channel_id, prompt_minute, utc_offset_minutes, collect_minutes, weekdays_only, enabled)
+             VALUES (?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(guild_id) DO UPDATE SET
+             channel_id = excluded.channel_id,
+             prompt_minute = excluded.prompt_minute,
+             utc_offset_minutes = excluded.utc_offset_minutes,
+             collect_minutes = excluded.collect_minutes,
+             weekdays_only = excluded.weekdays_only,
+             enabled = excluded.enabled,
+             updated_at = CURRENT_TIMESTAMP",
+        )?;
+        statement.bind((1, config.guild_id.as_str()))?;
+        statement.bind((2, config.channel_id.as_str()))?;
+        statement.bind((3, config.prompt_minute as i64))?;
+        statement.bind((4, config.utc_offset_minutes as i64))?;
+        statement.bind((5, config.collect_minutes as i64))?;
+        statement.bind((6, config.weekdays_only as i64))?;
+        statement.bind((7, config.enabled as i64))?;
+        statement.next()?;
+
+        info!(
+            "Saved standup schedule for guild {} (channel {}, {} min after midnight)",
+            config.guild_id, config.channel_id, config.prompt_minute
+        );
+        Ok(())
+    }
+
+    pub async fn get_standup_config(&self, guild_id: &str) -> Result<Option<StandupConfig>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT guild_id, channel_id, prompt_minute, utc_offset_minutes, collect_minutes,
+                    weekdays_only, enabled, last_prompt_date, last_prompt_at, last_summary_date
+             FROM standup_configs WHERE guild_id = ?",
+        )?;
+        statement.bind((1, guild_id))?;
+        Ok(Self::read_standup_configs(&mut statement)?.pop())
+    }
+
+    /// Every enabled standup schedule
+    pub async fn get_standup_configs(&self) -> Result<Vec<StandupConfig>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT guild_id, channel_id, prompt_minute, utc_offset_minutes, collect_minutes,
+                    weekdays_only, enabled, last_prompt_date, last_prompt_at, last_summary_date
+             FROM standup_configs WHERE enabled = 1",
+        )?;
+        Self::read_standup_configs(&mut statement)
+    }
+
+    fn read_standup_configs(statement: &mut sqlite::Statement) -> Result<Vec<StandupConfig>> {
+        let mut configs = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            configs.push(StandupConfig {
+                guild_id: statement.read::<String, _>(0)?,
+                channel_id: statement.read::<String, _>(1)?,
+                prompt_minute: statement.read::<i64, _>(2)? as u32,
+                utc_offset_minutes: statement.read::<i64, _>(3)? as i32,
+                collect_minutes: statement.read::<i64, _>(4)? as u32,
+                weekdays_only: statement.read::<i64, _>(5)? != 0,
+                enabled: statement.read::<i64, _>(6)? != 0,
+                last_prompt_date: statement.read::<Option<String>, _>(7)?,
+                last_prompt_at: statement.read::<Option<i64>, _>(8)?,
+                last_summary_date: statement.read::<Option<String>, _>(9)?,
+            });
+        }
+        Ok(configs)
+    }
+
+    /// Record that a guild's prompts for local `date` went out at `at`
+    pub async fn mark_standup_prompted(&self, guild_id: &str, date: &str, at: i64) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "UPDATE standup_configs SET last_prompt_date = ?, last_prompt_at = ? WHERE guild_id = ?",
+        )?;
+        statement.bind((1, date))?;
+        statement.bind((2, at))?;
+        statement.bind((3, guild_id))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Record that the summary for local `date` was posted, closing that day's answers
+    pub async fn mark_standup_summarized(&self, guild_id: &str, date: &str) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("UPDATE standup_configs SET last_summary_date = ? WHERE guild_id = ?")?;
+        statement.bind((1, date))?;
+        statement.bind((2, guild_id))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Add a member to (or remove them from) a guild's standup
+    ///
+    /// Returns whether anything changed.
+    pub async fn set_standup_member(
+        &self,
+        guild_id: &str,
+        user_id: &str,
+        joined: bool,
+    ) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let query = if joined {
+            "INSERT OR IGNORE INTO standup_members (guild_id, user_id) VALUES (?, ?)"
+        } else {
+            "DELETE FROM standup_members WHERE guild_id = ? AND user_id = ?"
+        };
+        let mut statement = conn.prepare(query)?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, user_id))?;
+        statement.next()?;
+        Ok(conn.change_count() > 0)
+    }
+
+    /// Skip a member's standups through local date `until` (or clear the skip)
+    ///
+    /// Returns false when the member hasn't joined.
+    pub async fn set_standup_skip(
+        &self,
+        guild_id: &str,
+        user_id: &str,
+        until: Option<&str>,
+    ) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "UPDATE standup_members SET skip_until = ? WHERE guild_id = ? AND user_id = ?",
+        )?;
+        statement.bind((1, until))?;
+        statement.bind((2, guild_id))?;
+        statement.bind((3, user_id))?;
+        statement.next()?;
+        Ok(conn.change_count() > 0)
+    }
+
+    pub async fn get_standup_members(&self, guild_id: &str) -> Result<Vec<StandupMember>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT user_id, skip_until FROM standup_members WHERE guild_id = ? ORDER BY joined_at",
+        )?;
+        statement.bind((1, guild_id))?;
+
+        let mut members = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            members.push(StandupMember {
+                user_id: statement.read::<String, _>(0)?,
+                skip_until: statement.read::<Option<String>, _>(1)?,
+            });
+        }
+        Ok(members)
+    }
+
+    /// Start a member's entry for local `date`; pre-skipped entries are already closed
+    pub async fn create_standup_entry(
+        &self,
+        guild_id: &str,
+        user_id: &str,
+        date: &str,
+        skipped: bool,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT OR IGNORE INTO standup_entries (guild_id, user_id, standup_date, skipped, prompted_at)
+             VALUES (?, ?, ?, ?, ?)",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, user_id))?;
+        statement.bind((3, date))?;
+        statement.bind((4, skipped as i64))?;
+        statement.bind((5, chrono::Utc::now().timestamp()))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// The oldest standup still waiting on this member's DM answers
+    ///
+    /// Entries close once their summary has been posted.
+    pub async fn get_open_standup_entry(&self, user_id: &str) -> Result<Option<StandupEntry>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT e.guild_id, e.user_id, e.standup_date, e.step, e.yesterday, e.today,
+                    e.blockers, e.skipped
+             FROM standup_entries e
+             JOIN standup_configs c ON c.guild_id = e.guild_id
+             WHERE e.user_id = ? AND e.step < 3 AND e.skipped = 0
+               AND (c.last_summary_date IS NULL OR c.last_summary_date != e.standup_date)
+             ORDER BY e.prompted_at, e.guild_id
+             LIMIT 1",
+        )?;
+        statement.bind((1, user_id))?;
+        Ok(Self::read_standup_entries(&mut statement)?.pop())
+    }
+
+    /// Save a member's answers and progress
+    pub async fn save_standup_entry(&self, entry: &StandupEntry) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "UPDATE standup_entries SET step = ?, yesterday = ?, today = ?, blockers = ?, skipped = ?
+             WHERE guild_id = ? AND user_id = ? AND standup_date = ?",
+        )?;
+        statement.bind((1, entry.step as i64))?;
+        statement.bind((2, entry.yesterday.as_deref()))?;
+        statement.bind((3, entry.today.as_deref()))?;
+        statement.bind((4, entry.blockers.as_deref()))?;
+        statement.bind((5, entry.skipped as i64))?;
+        statement.bind((6, entry.guild_id.as_str()))?;
+        statement.bind((7, entry.user_id.as_str()))?;
+        statement.bind((8, entry.standup_date.as_str()))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Every member's entry for a guild's standup on local `date`
+    pub async fn get_standup_entries(
+        &self,
+        guild_id: &str,
+        date: &str,
+    ) -> Result<Vec<StandupEntry>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT guild_id, user_id, standup_date, step, yesterday, today, blockers, skipped
+             FROM standup_entries WHERE guild_id = ? AND standup_date = ?
+             ORDER BY prompted_at, user_id",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, date))?;
+        Self::read_standup_entries(&mut statement)
+    }
+
+    fn read_standup_entries(statement: &mut sqlite::Statement) -> Result<Vec<StandupEntry>> {
+        let mut entries = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            entries.push(StandupEntry {
+                guild_id: statement.read::<String, _>(0)?,
+                user_id: statement.read::<String, _>(1)?,
+                standup_date: statement.read::<String, _>(2)?,
+                step: statement.read::<i64, _>(3)? as u32,
+                yesterday: statement.read::<Option<String>, _>(4)?,
+                today: statement.read::<Option<String>, _>(5)?,
+                blockers: statement.read::<Option<String>, _>(6)?,
+                skipped: statement.read::<i64, _>(7)? != 0,
+            });
+        }
+        Ok(entries)
+    }
+
+    /// Save a new meeting poll and return its ID
+    pub async fn create_meeting_poll(&self, poll: &MeetingPoll) -> Result<i64> {
+        let conn = self.connection.lock().await?;
+        let slots = poll
+            .slots
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        let mut statement = conn.prepare(
+            "INSERT INTO meeting_polls
+             (guild_id, channel_id, organizer_id, title, slots, timezone, duration_minutes,
+              create_event, remind_minutes)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )?;
+        statement.bind((1, poll.guild_id.as_str()))?;
+        statement.bind((2, poll.channel_id.as_str()))?;
+        statement.bind((3, poll.organizer_id.as_str()))?;
+        statement.bind((4, poll.title.as_str()))?;
+        statement.bind((5, slots.as_str()))?;
+        statement.bind((6, poll.timezone.to_setting().as_str()))?;
+        statement.bind((7, poll.duration_minutes as i64))?;
+        statement.bind((8, poll.create_event as i64))?;
+        statement.bind((9, poll.remind_minutes as i64))?;
+        statement.next()?;
+
+        let mut stmt = conn.prepare("SELECT last_insert_rowid()")?;
+        stmt.next()?;
+        Ok(stmt.read::<i64, _>(0)?)
+    }
+
+    pub async fn get_meeting_poll(&self, poll_id: i64) -> Result<Option<MeetingPoll>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT id, guild_id, channel_id, organizer_id, title, slots, utc_offset_minutes,
+                    duration_minutes, create_event, remind_minutes, chosen_slot, timezone
+             FROM meeting_polls WHERE id = ?",
+        )?;
+        statement.bind((1, poll_id))?;
+
+        if let Ok(State::Row) = statement.next() {
+            Ok(Some(MeetingPoll {
+                id: statement.read::<i64, _>(0)?,
+                guild_id: statement.read::<String, _>(1)?,
+                channel_id: statement.read::<String, _>(2)?,
+                organizer_id: statement.read::<String, _>(3)?,
+                title: statement.read::<String, _>(4)?,
+                slots: statement
+                    .read::<String, _>(5)?
+                    .split(',')
+                    .filter_map(|s| s.parse().ok())
+                    .collect(),
+                timezone: statement
+                    .read::<Option<String>, _>(11)?
+                    .and_then(|value| Zone::from_setting(&value))
+                    .unwrap_or(Zone::Fixed(statement.read::<i64, _>(6)? as i32)),
+                duration_minutes: statement.read::<i64, _>(7)? as u32,
+                create_event: statement.read::<i64, _>(8)? != 0,
+                remind_minutes: statement.read::<i64, _>(9)? as u32,
+                chosen_slot: statement
+                    .read::<Option<i64>, _>(10)?
+                    .map(|slot| slot as usize),
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Flip a member's availability for one slot of an open poll
+    ///
+    /// Returns whether the member is now marked available.
+    pub async fn toggle_meeting_availability(
+        &self,
+        poll_id: i64,
+        slot_index: usize,
+        user_id: &str,
+    ) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "DELETE FROM meeting_availability WHERE poll_id = ? AND slot_index = ? AND user_id = ?",
+        )?;
+        statement.bind((1, poll_id))?;
+        statement.bind((2, slot_index as i64))?;
+        statement.bind((3, user_id))?;
+        statement.next()?;
+        if conn.change_count() > 0 {
+            return Ok(false);
+        }
+
+        let mut statement = conn.prepare(
+            "INSERT INTO meeting_availability (poll_id, slot_index, user_id) VALUES (?, ?, ?)",
+        )?;
+        statement.bind((1, poll_id))?;
+        statement.bind((2, slot_index as i64))?;
+        statement.bind((3, user_id))?;
+        statement.next()?;
+        Ok(true)
+    }
+
+    /// Members available for each slot, indexed like `MeetingPoll::slots`
+    pub async fn get_meeting_availability(
+        &self,
+        poll_id: i64,
+        slot_count: usize,
+    ) -> Result<Vec<Vec<String>>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT slot_index, user_id FROM meeting_availability
+             WHERE poll_id = ? ORDER BY rowid",
+        )?;
+        statement.bind((1, poll_id))?;
+
+        let mut availability = vec![Vec::new(); slot_count];
+        while let Ok(State::Row) = statement.next() {
+            let slot = statement.read::<i64, _>(0)? as usize;
+            let user_id = statement.read::<String, _>(1)?;
+            if let Some(users) = availability.get_mut(slot) {
+                users.push(user_id);
+            }
+        }
+        Ok(availability)
+    }
+
+    pub async fn set_issue_tracker_config(&self, config: &IssueTrackerConfig) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT OR REPLACE INTO issue_tracker_configs
+             (guild_id, provider, project, api_token, base_url, email, issue_type, summarize, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP)",
+        )?;
+        statement.bind((1, config.guild_id.as_str()))?;
+        statement.bind((2, config.provider.key()))?;
+        statement.bind((3, config.project.as_str()))?;
+        statement.bind((4, config.api_token.as_str()))?;
+        statement.bind((5, config.base_url.as_deref()))?;
+        statement.bind((6, config.email.as_deref()))?;
+        statement.bind((7, config.issue_type.as_deref()))?;
+        statement.bind((8, config.summarize as i64))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    pub async fn get_issue_tracker_config(
+        &self,
+        guild_id: &str,
+    ) -> Result<Option<IssueTrackerConfig>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT guild_id, provider, project, api_token, base_url, email, issue_type, summarize
+             FROM issue_tracker_configs WHERE guild_id = ?",
+        )?;
+        statement.bind((1, guild_id))?;
+
+        if let Ok(State::Row) = statement.next() {
+            let provider = statement.read::<String, _>(1)?;
+            let Some(provider) = IssueProvider::from_key(&provider) else {
+                warn!("Unknown issue tracker provider '{provider}' for guild {guild_id}");
+                return Ok(None);
+            };
+            Ok(Some(IssueTrackerConfig {
+                guild_id: statement.read::<String, _>(0)?,
+                provider,
+                project: statement.read::<String, _>(2)?,
+                api_token: statement.read::<String, _>(3)?,
+                base_url: statement.read::<Option<String>, _>(4)?,
+                email: statement.read::<Option<String>, _>(5)?,
+                issue_type: statement.read::<Option<String>, _>(6)?,
+                summarize: statement.read::<i64, _>(7)? != 0,
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Forget a guild's tracker credentials; returns whether one was configured
+    pub async fn delete_issue_tracker_config(&self, guild_id: &str) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare("DELETE FROM issue_tracker_configs WHERE guild_id = ?")?;
+        statement.bind((1, guild_id))?;
+        statement.next()?;
+        Ok(conn.change_count() > 0)
+    }
+
+    /// Subscribe a URL to bot events; returns the webhook id
+    pub async fn add_guild_webhook(
+        &self,
+        guild_id: &str,
+        url: &str,
+        format: WebhookFormat,
+        events: &[&str],
+        template: Option<&str>,
+        created_by: &str,
+    ) -> Result<i64> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO guild_webhooks (guild_id, url, format, events, template, created_by)
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, url))?;
+        statement.bind((3, format.key()))?;
+        statement.bind((4, events.join(",").as_str()))?;
+        statement.bind((5, template))?;
+        statement.bind((6, created_by))?;
+        statement.next()?;
+        drop(statement);
+
+        let mut statement = conn.prepare("SELECT last_insert_rowid()")?;
+        statement.next()?;
+        Ok(statement.read::<i64, _>(0)?)
+    }
+
+    /// A guild's webhooks, oldest first
+    pub async fn get_guild_webhooks(&self, guild_id: &str) -> Result<Vec<GuildWebhook>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT id, guild_id, url, format, events, template, last_error
+             FROM guild_webhooks WHERE guild_id = ? ORDER BY id",
+        )?;
+        statement.bind((1, guild_id))?;
+
+        let mut webhooks = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            let id = statement.read::<i64, _>(0)?;
+            let format = statement.read::<String, _>(3)?;
+            let Some(format) = WebhookFormat::from_key(&format) else {
+                warn!("Unknown webhook format '{format}' for webhook {id}");
+                continue;
+            };
+            webhooks.push(GuildWebhook {
+                id,
+                guild_id: statement.read::<String, _>(1)?,
+                url: statement.read::<String, _>(2)?,
+                format,
+                events: statement
+                    .read::<String, _>(4)?
+                    .split(',')
+                    .filter(|e| !e.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+                template: statement.read::<Option<String>, _>(5)?,
+                last_error: statement.read::<Option<String>, _>(6)?,
+            });
+        }
+        Ok(webhooks)
+    }
+
+    /// Remove one of a guild's webhooks; returns whether it existed
+    pub async fn delete_guild_webhook(&self, guild_id: &str, webhook_id: i64) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("DELETE FROM guild_webhooks WHERE id = ? AND guild_id = ?")?;
+        statement.bind((1, webhook_id))?;
+        statement.bind((2, guild_id))?;
+        statement.next()?;
+        Ok(conn.change_count() > 0)
+    }
+
+    /// Record the outcome of a delivery; `None` clears the last error
+    pub async fn set_webhook_last_error(&self, webhook_id: i64, error: Option<&str>) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("UPDATE guild_webhooks SET last_error = ? WHERE id = ?")?;
+        statement.bind((1, error))?;
+        statement.bind((2, webhook_id))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Issue a Telegram link code, replacing any the user had pending
+    pub async fn create_telegram_link_code(
+        &self,
+        discord_user_id: &str,
+        code: &str,
+        expires_at: &str,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("DELETE FROM telegram_link_codes WHERE discord_user_id = ?")?;
+        statement.bind((1, discord_user_id))?;
+        statement.next()?;
+        drop(statement);
+
+        let mut statement = conn.prepare(
+            "INSERT OR REPLACE INTO telegram_link_codes (code, discord_user_id, expires_at)
+             VALUES (?, ?, ?)",
+        )?;
+        statement.bind((1, code))?;
+        statement.bind((2, discord_user_id))?;
+        statement.bind((3, expires_at))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Link a Telegram account with an unexpired code
+    ///
+    /// The code is used up either way. Returns the Discord user ID it was
+    /// issued to, or `None` if it was unknown or expired.
+    pub async fn redeem_telegram_link_code(
+        &self,
+        code: &str,
+        telegram_user_id: &str,
+    ) -> Result<Option<String>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT discord_user_id FROM telegram_link_codes
+             WHERE code = ? AND expires_at > datetime('now')",
+        )?;
+        statement.bind((1, code))?;
+        let discord_user_id = match statement.next()? {
+            State::Row => Some(statement.read::<String, _>(0)?),
+            State::Done => None,
+        };
+        drop(statement);
+
+        let mut statement = conn.prepare("DELETE FROM telegram_link_codes WHERE code = ?")?;
+        statement.bind((1, code))?;
+        statement.next()?;
+        drop(statement);
+
+        if let Some(discord_user_id) = &discord_user_id {
+            let mut statement = conn.prepare(
+                "INSERT OR REPLACE INTO telegram_links (telegram_user_id, discord_user_id)
+                 VALUES (?, ?)",
+            )?;
+            statement.bind((1, telegram_user_id))?;
+            statement.bind((2, discord_user_id.as_str()))?;
+            statement.next()?;
+            info!("Linked Telegram user {telegram_user_id} to {discord_user_id}");
+        }
+        Ok(discord_user_id)
+    }
+
+    /// The Discord user a Telegram account is linked to, and the Discord
+    /// channel its conversation continues, if any
+    pub async fn get_telegram_link(
+        &self,
+        telegram_user_id: &str,
+    ) -> Result<Option<(String, Option<String>)>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT discord_user_id, continue_channel_id FROM telegram_links
+             WHERE telegram_user_id = ?",
+        )?;
+        statement.bind((1, telegram_user_id))?;
+        match statement.next()? {
+            State::Row => Ok(Some((
+                statement.read::<String, _>(0)?,
+                statement.read::<Option<String>, _>(1)?,
+            ))),
+            State::Done => Ok(None),
+        }
+    }
+
+    /// Point a linked Telegram chat at a Discord channel's history; `None` detaches it
+    pub async fn set_telegram_continue_channel(
+        &self,
+        telegram_user_id: &str,
+        channel_id: Option<&str>,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "UPDATE telegram_links SET continue_channel_id = ? WHERE telegram_user_id = ?",
+        )?;
+        statement.bind((1, channel_id))?;
+        statement.bind((2, telegram_user_id))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Remove every Telegram link for a Discord user; returns how many there were
+    pub async fn delete_telegram_links(&self, discord_user_id: &str) -> Result<usize> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare("DELETE FROM telegram_links WHERE discord_user_id = ?")?;
+        statement.bind((1, discord_user_id))?;
+        statement.next()?;
+        Ok(conn.change_count())
+    }
+
+    /// Unlink one Telegram account; returns whether it was linked
+    pub async fn delete_telegram_link(&self, telegram_user_id: &str) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("DELETE FROM telegram_links WHERE telegram_user_id = ?")?;
+        statement.bind((1, telegram_user_id))?;
+        statement.next()?;
+        Ok(conn.change_count() > 0)
+    }
+
+    /// The Discord channel the user most recently chatted with the bot in
+    pub async fn get_latest_conversation_channel(&self, user_id: &str) -> Result<Option<String>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT channel_id FROM conversation_history
+             WHERE user_id = ? AND channel_id NOT LIKE '%:%'
+             ORDER BY id DESC LIMIT 1",
+        )?;
+        statement.bind((1, user_id))?;
+        match statement.next()? {
+            State::Row => Ok(Some(statement.read::<String, _>(0)?)),
+            State::Done => Ok(None),
+        }
+    }
+
+    /// Close a poll on the chosen slot
+    ///
+    /// Returns false when the poll was already closed.
+    pub async fn close_meeting_poll(&self, poll_id: i64, chosen_slot: usize) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "UPDATE meeting_polls SET chosen_slot = ? WHERE id = ? AND chosen_slot IS NULL",
+        )?;
+        statement.bind((1, chosen_slot as i64))?;
+        statement.bind((2, poll_id))?;
+        statement.next()?;
+        Ok(conn.change_count() > 0)
+    }
+
+    // Bot Settings Methods (global, not per-guild)
+    pub async fn set_bot_setting(&self, setting_key: &str, setting_value: &str) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT OR REPLACE INTO bot_settings (setting_key, setting_value, updated_at)
+             VALUES (?, ?, CURRENT_TIMESTAMP)",
+        )?;
+        statement.bind((1, setting_key))?;
+        statement.bind((2, setting_value))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    pub async fn get_bot_setting(&self, setting_key: &str) -> Result<Option<String>> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("SELECT setting_value FROM bot_settings WHERE setting_key = ?")?;
+        statement.bind((1, setting_key))?;
+
+        if let Ok(State::Row) = statement.next() {
+            Ok(Some(statement.read::<String, _>(0)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    // Component State Methods
+    pub async fn save_component_state(
+        &self,
+        feature: &str,
+        state_key: &str,
+        payload: &str,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT OR REPLACE INTO component_state (feature, state_key, payload, expires_at, updated_at)
+             VALUES (?, ?, ?, ?, strftime('%s', 'now'))",
+        )?;
+        statement.bind((1, feature))?;
+        statement.bind((2, state_key))?;
+        statement.bind((3, payload))?;
+        statement.bind((4, expires_at))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Stored payload for a component, ignoring expired rows
+    pub async fn get_component_state(
+        &self,
+        feature: &str,
+        state_key: &str,
+    ) -> Result<Option<String>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT payload FROM component_state
+             WHERE feature = ? AND state_key = ?
+               AND (expires_at IS NULL OR expires_at > strftime('%s', 'now'))",
+        )?;
+        statement.bind((1, feature))?;
+        statement.bind((2, state_key))?;
+
+        if let Ok(State::Row) = statement.next() {
+            Ok(Some(statement.read::<String, _>(0)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Every unexpired (state_key, payload) stored for a feature
+    pub async fn list_component_states(&self, feature: &str) -> Result<Vec<(String, String)>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT state_key, payload FROM component_state
+             WHERE feature = ?
+               AND (expires_at IS NULL OR expires_at > strftime('%s', 'now'))",
+        )?;
+        statement.bind((1, feature))?;
+
+        let mut states = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            states.push((
+                statement.read::<String, _>(0)?,
+                statement.read::<String, _>(1)?,
+            ));
+        }
+        Ok(states)
+    }
+
+    pub async fn delete_component_state(&self, feature: &str, state_key: &str) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("DELETE FROM component_state WHERE feature = ? AND state_key = ?")?;
+        statement.bind((1, feature))?;
+        statement.bind((2, state_key))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Delete expired component state, returning the number of rows removed
+    pub async fn purge_expired_component_state(&self) -> Result<i64> {
+        let conn = self.connection.lock().await?;
+        conn.execute(
+            "DELETE FROM component_state
+             WHERE expires_at IS NOT NULL AND expires_at <= strftime('%s', 'now')",
+        )?;
+        let mut check = conn.prepare("SELECT changes()")?;
+        check.next()?;
+        Ok(check.read::<i64, _>(0)?)
+    }
+
+    // Extended User Preferences Methods
+    pub async fn set_user_preference(
+        &self,
+        user_id: &str,
+        preference_key: &str,
+        preference_value: &str,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT OR REPLACE INTO extended_user_preferences (user_id, preference_key, preference_value, updated_at)
+             VALUES (?, ?, ?, CURRENT_TIMESTAMP)"
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, preference_key))?;
+        statement.bind((3, preference_value))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    pub async fn get_user_preference(
+        &self,
+        user_id: &str,
+        preference_key: &str,
+    ) -> Result<Option<String>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT preference_value FROM extended_user_preferences WHERE user_id = ? AND preference_key = ?"
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, preference_key))?;
+
+        if let Ok(State::Row) = statement.next() {
+            Ok(Some(statement.read::<String, _>(0)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    // Conflict Detection & Mediation Methods
+
+    pub async fn record_conflict_detection(
+        &self,
+        channel_id: &str,
+        guild_id: Option<&str>,
+        participants: &str, // JSON array of user IDs
+        detection_type: &str,
+        confidence: f32,
+        last_message_id: &str,
+    ) -> Result<i64> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO conflict_detection
+             (channel_id, guild_id, participants, detection_type, confidence_score, last_message_id)
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )?;
+        statement.bind((1, channel_id))?;
+        statement.bind((2, guild_id.unwrap_or("")))?;
+        statement.bind((3, participants))?;
+        statement.bind((4, detection_type))?;
+        statement.bind((5, confidence as f64))?;
+        statement.bind((6, last_message_id))?;
+        statement.next()?;
+
+        // Get the ID of the inserted row
+        let mut id_statement = conn.prepare("SELECT last_insert_rowid()")?;
+        id_statement.next()?;
+        let conflict_id = id_statement.read::<i64, _>(0)?;
+
+        info!("Recorded conflict detection in channel {channel_id} with confidence {confidence}");
+        Ok(conflict_id)
+    }
+
+    pub async fn mark_conflict_resolved(&self, conflict_id: i64) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "UPDATE conflict_detection SET resolved_at = CURRENT_TIMESTAMP WHERE id = ?",
+        )?;
+        statement.bind((1, conflict_id))?;
+        statement.next()?;
+        info!("Marked conflict {conflict_id} as resolved");
+        Ok(())
+    }
+
+    pub async fn mark_mediation_triggered(&self, conflict_id: i64, message_id: &str) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "UPDATE conflict_detection
+             SET mediation_triggered = 1, mediation_message_id = ?
+             WHERE id = ?",
+        )?;
+        statement.bind((1, message_id))?;
+        statement.bind((2, conflict_id))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    pub async fn get_channel_active_conflict(&self, channel_id: &str) -> Result<Option<i64>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT id FROM conflict_detection
+             WHERE channel_id = ? AND resolved_at IS NULL
+             ORDER BY last_detected DESC LIMIT 1",
+        )?;
+        statement.bind((1, channel_id))?;
+
+        if let Ok(State::Row) = statement.next() {
+            Ok(Some(statement.read::<i64, _>(0)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub async fn record_mediation(
+        &self,
+        conflict_id: i64,
+        channel_id: &str,
+        message_text: &str,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO mediation_history (conflict_id, channel_id, mediation_message)
+             VALUES (?, ?, ?)",
+        )?;
+        statement.bind((1, conflict_id))?;
+        statement.bind((2, channel_id))?;
+        statement.bind((3, message_text))?;
+        statement.next()?;
+        info!("Recorded mediation for conflict {conflict_id}");
+        Ok(())
+    }
+
+    /// Get the timestamp of the last mediation in a channel
+    pub async fn get_last_mediation_timestamp(&self, channel_id: &str) -> Result<Option<i64>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT strftime('%s', mh.created_at) as unix_time
+             FROM mediation_history mh
+             WHERE mh.channel_id = ?
+             ORDER BY mh.created_at DESC
+             LIMIT 1",
+        )?;
+        statement.bind((1, channel_id))?;
+
+        if let Ok(State::Row) = statement.next() {
+            let timestamp_str = statement.read::<String, _>(0)?;
+            Ok(Some(timestamp_str.parse::<i64>()?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub async fn get_recent_channel_messages(
+        &self,
+        channel_id: &str,
+        limit: usize,
+    ) -> Result<Vec<(String, String, String)>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT user_id, content, strftime('%s', timestamp) as unix_time
+             FROM conversation_history
+             WHERE channel_id = ?
+             ORDER BY timestamp DESC
+             LIMIT ?",
+        )?;
+        statement.bind((1, channel_id))?;
+        statement.bind((2, limit as i64))?;
+
+        let mut messages = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            let user_id = statement.read::<String, _>(0)?;
+            let content = statement.read::<String, _>(1)?;
+            let timestamp = statement.read::<String, _>(2)?;
+            messages.push((user_id, content, timestamp));
+        }
+
+        // Reverse to get chronological order
+        messages.reverse();
+        Ok(messages)
+    }
+
+    /// Get recent channel messages that occurred after a specific timestamp
+    /// This is used to avoid re-analyzing messages that have already been mediated
+    pub async fn get_recent_channel_messages_since(
+        &self,
+        channel_id: &str,
+        since_timestamp: i64,
+        limit: usize,
+    ) -> Result<Vec<(String, String, String)>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT user_id, content, strftime('%s', timestamp) as unix_time
+             FROM conversation_history
+             WHERE channel_id = ?
+               AND CAST(strftime('%s', timestamp) AS INTEGER) > ?
+             ORDER BY timestamp DESC
+             LIMIT ?",
+        )?;
+        statement.bind((1, channel_id))?;
+        statement.bind((2, since_timestamp))?;
+        statement.bind((3, limit as i64))?;
+
+        let mut messages = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            let user_id = statement.read::<String, _>(0)?;
+            let content = statement.read::<String, _>(1)?;
+            let timestamp = statement.read::<String, _>(2)?;
+            messages.push((user_id, content, timestamp));
+        }
+
+        // Reverse to get chronological order
+        messages.reverse();
+        Ok(messages)
+    }
+
+    /// Users' messages in a channel since a unix timestamp, oldest first
+    ///
+    /// Returns (content, unix_time); bot replies are left out.
+    pub async fn get_channel_user_messages_since(
+        &self,
+        channel_id: &str,
+        since_timestamp: i64,
+        limit: usize,
+    ) -> Result<Vec<(String, i64)>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT content, CAST(strftime('%s', timestamp) AS INTEGER) as unix_time
+             FROM conversation_history
+             WHERE channel_id = ? AND role = 'user'
+               AND CAST(strftime('%s', timestamp) AS INTEGER) >= ?
+             ORDER BY timestamp DESC
+             LIMIT ?",
+        )?;
+        statement.bind((1, channel_id))?;
+        statement.bind((2, since_timestamp))?;
+        statement.bind((3, limit as i64))?;
+
+        let mut messages = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            messages.push((
+                statement.read::<String, _>(0)?,
+                statement.read::<i64, _>(1)?,
+            ));
+        }
+
+        // Reverse to get chronological order
+        messages.reverse();
+        Ok(messages)
+    }
+
+    pub async fn update_user_interaction_pattern(
+        &self,
+        user_id_a: &str,
+        user_id_b: &str,
+        channel_id: &str,
+        is_conflict: bool,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+
+        // Ensure user_id_a is always lexicographically smaller (for consistent lookups)
+        let (user_a, user_b) = if user_id_a < user_id_b {
+            (user_id_a, user_id_b)
+        } else {
+            (user_id_b, user_id_a)
+        };
+
+        let conflict_increment = if is_conflict { 1 } else { 0 };
+
+        let mut statement = conn.prepare(
+            "INSERT INTO user_interaction_patterns
+             (user_id_a, user_id_b, channel_id, interaction_count, conflict_incidents, last_interaction)
+             VALUES (?, ?, ?, 1, ?, CURRENT_TIMESTAMP)
+             ON CONFLICT(user_id_a, user_id_b, channel_id) DO UPDATE SET
+             interaction_count = interaction_count + 1,
+             conflict_incidents = conflict_incidents + ?,
+             last_interaction = CURRENT_TIMESTAMP"
+        )?;
+        statement.bind((1, user_a))?;
+        statement.bind((2, user_b))?;
+        statement.bind((3, channel_id))?;
+        statement.bind((4, conflict_increment))?;
+        statement.bind((5, conflict_increment))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    // Channel Settings Methods
+
+    /// Get verbosity for a channel, falling back to guild default, then "concise"
+    pub async fn get_channel_verbosity(&self, guild_id: &str, channel_id: &str) -> Result<String> {
+        let cache_key = (guild_id.to_string(), channel_id.to_string());
+        let cache = &self.settings_cache;
+        if let Some(verbosity) = cache.channel_verbosity.get(&cache_key, cache.ttl) {
+            return Ok(verbosity);
+        }
+
+        let verbosity = self.load_channel_verbosity(guild_id, channel_id).await?;
+        cache.channel_verbosity.insert(cache_key, verbosity.clone());
+        Ok(verbosity)
+    }
+
+    async fn load_channel_verbosity(&self, guild_id: &str, channel_id: &str) -> Result<String> {
+        let conn = self.connection.lock().await?;
+
+        // First try channel-specific setting
+        let mut statement = conn.prepare(
+            "SELECT verbosity FROM channel_settings WHERE guild_id = ? AND channel_id = ?",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, channel_id))?;
+
+        if let Ok(State::Row) = statement.next() {
+            return Ok(statement.read::<String, _>(0)?);
+        }
+
+        // Fall back to guild default
+        drop(statement);
+        let mut guild_stmt = conn.prepare(
+            "SELECT setting_value FROM guild_settings WHERE guild_id = ? AND setting_key = 'default_verbosity'"
+        )?;
+        guild_stmt.bind((1, guild_id))?;
+
+        if let Ok(State::Row) = guild_stmt.next() {
+            return Ok(guild_stmt.read::<String, _>(0)?);
+        }
+
+        // Default to concise
+        Ok("concise".to_string())
+    }
+
+    /// Set verbosity for a specific channel
+    pub async fn set_channel_verbosity(
+        &self,
+        guild_id: &str,
+        channel_id: &str,
+        verbosity: &str,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO channel_settings (guild_id, channel_id, verbosity, updated_at)
+             VALUES (?, ?, ?, CURRENT_TIMESTAMP)
+             ON CONFLICT(guild_id, channel_id) DO UPDATE SET
+             verbosity = excluded.verbosity,
+             updated_at = CURRENT_TIMESTAMP",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, channel_id))?;
+        statement.bind((3, verbosity))?;
+        statement.next()?;
+        self.settings_cache
+            .channel_verbosity
+            .remove(&(guild_id.to_string(), channel_id.to_string()));
+        info!("Set verbosity for channel {channel_id} to {verbosity}");
+        Ok(())
+    }
+
+    /// Get all settings for a channel (verbosity, conflict_enabled, persona)
+    pub async fn get_channel_settings(
+        &self,
+        guild_id: &str,
+        channel_id: &str,
+    ) -> Result<(String, bool, Option<String>)> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT verbosity, conflict_enabled, default_persona FROM channel_settings WHERE guild_id = ? AND channel_id = ?"
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, channel_id))?;
+
+        if let Ok(State::Row) = statement.next() {
+            let verbosity = statement.read::<String, _>(0)?;
+            let conflict_enabled = statement.read::<i64, _>(1)? == 1;
+            let persona = statement.read::<Option<String>, _>(2)?;
+            Ok((verbosity, conflict_enabled, persona))
+        } else {
+            // Return defaults
+            Ok(("concise".to_string(), true, None))
+        }
+    }
+
+    /// Set whether conflict detection is enabled for a channel
+    pub async fn set_channel_conflict_enabled(
+        &self,
+        guild_id: &str,
+        channel_id: &str,
+        enabled: bool,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO channel_settings (guild_id, channel_id, conflict_enabled, updated_at)
+             VALUES (?, ?, ?, CURRENT_TIMESTAMP)
+             ON CONFLICT(guild_id, channel_id) DO UPDATE SET
+             conflict_enabled = excluded.conflict_enabled,
+             updated_at = CURRENT_TIMESTAMP",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, channel_id))?;
+        statement.bind((3, if enabled { 1i64 } else { 0i64 }))?;
+        statement.next()?;
+        info!("Set conflict_enabled for channel {channel_id} to {enabled}");
+        Ok(())
+    }
+
+    /// Get max_paragraphs for a channel (0 = no limit)
+    pub async fn get_channel_max_paragraphs(&self, guild_id: &str, channel_id: &str) -> Result<i64> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT max_paragraphs FROM channel_settings WHERE guild_id = ? AND channel_id = ?",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, channel_id))?;
+
+        if let Ok(State::Row) = statement.next() {
+            let value: Option<i64> = statement.read(0)?;
+            Ok(value.unwrap_or(0))
+        } else {
+            Ok(0) // Default: no limit
+        }
+    }
+
+    /// Set max_paragraphs for a channel (0 = no limit, 1-10 = enforced limit)
+    pub async fn set_channel_max_paragraphs(
+        &self,
+        guild_id: &str,
+        channel_id: &str,
+        max_paragraphs: i64,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO channel_settings (guild_id, channel_id, max_paragraphs, updated_at)
+             VALUES (?, ?, ?, CURRENT_TIMESTAMP)
+             ON CONFLICT(guild_id, channel_id) DO UPDATE SET
+             max_paragraphs = excluded.max_paragraphs,
+             updated_at = CURRENT_TIMESTAMP",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, channel_id))?;
+        statement.bind((3, max_paragraphs))?;
+        statement.next()?;
+        info!("Set max_paragraphs for channel {channel_id} to {max_paragraphs}");
+        Ok(())
+    }
+
+    /// A channel_settings column read on every message, as text, through the settings cache
+    async fn get_channel_column(
+        &self,
+        guild_id: &str,
+        channel_id: &str,
+        column: &'static str,
+    ) -> Result<Option<String>> {
+        let cache_key = (guild_id.to_string(), channel_id.to_string(), column);
+        let cache = &self.settings_cache;
+        if let Some(value) = cache.channel_columns.get(&cache_key, cache.ttl) {
+            return Ok(value);
+        }
+
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(format!(
+            "SELECT CAST({column} AS TEXT) FROM channel_settings WHERE guild_id = ? AND channel_id = ?"
+        ))?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, channel_id))?;
+
+        let value = if let Ok(State::Row) = statement.next() {
+            statement.read::<Option<String>, _>(0)?
+        } else {
+            None
+        };
+        cache.channel_columns.insert(cache_key, value.clone());
+        Ok(value)
+    }
+
+    /// Drop a cached channel_settings column after its setter writes it
+    fn forget_channel_column(&self, guild_id: &str, channel_id: &str, column: &'static str) {
+        self.settings_cache.channel_columns.remove(&(
+            guild_id.to_string(),
+            channel_id.to_string(),
+            column,
+        ));
+    }
+
+    /// Get the link preview mode for a channel (`reply`, `thread` or `disabled`)
+    pub async fn get_channel_link_previews(
+        &self,
+        guild_id: &str,
+        channel_id: &str,
+    ) -> Result<String> {
+        Ok(self
+            .get_channel_column(guild_id, channel_id, "link_previews")
+            .await?
+            .unwrap_or_else(|| "disabled".to_string()))
+    }
+
+    /// Set the link preview mode for a channel
+    pub async fn set_channel_link_previews(
+        &self,
+        guild_id: &str,
+        channel_id: &str,
+        mode: &str,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO channel_settings (guild_id, channel_id, link_previews, updated_at)
+             VALUES (?, ?, ?, CURRENT_TIMESTAMP)
+             ON CONFLICT(guild_id, channel_id) DO UPDATE SET
+             link_previews = excluded.link_previews,
+             updated_at = CURRENT_TIMESTAMP",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, channel_id))?;
+        statement.bind((3, mode))?;
+        statement.next()?;
+        self.forget_channel_column(guild_id, channel_id, "link_previews");
+        info!("Set link_previews for channel {channel_id} to {mode}");
+        Ok(())
+    }
+
+    /// Get the designated language code for a channel, if one is set
+    pub async fn get_channel_language(
+        &self,
+        guild_id: &str,
+        channel_id: &str,
+    ) -> Result<Option<String>> {
+        self.get_channel_column(guild_id, channel_id, "language")
+            .await
+    }
+
+    /// Set or clear (`None`) the designated language for a channel
+    pub async fn set_channel_language(
+        &self,
+        guild_id: &str,
+        channel_id: &str,
+        language: Option<&str>,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO channel_settings (guild_id, channel_id, language, updated_at)
+             VALUES (?, ?, ?, CURRENT_TIMESTAMP)
+             ON CONFLICT(guild_id, channel_id) DO UPDATE SET
+             language = excluded.language,
+             updated_at = CURRENT_TIMESTAMP",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, channel_id))?;
+        statement.bind((3, language))?;
+        statement.next()?;
+        self.forget_channel_column(guild_id, channel_id, "language");
+        info!("Set language for channel {channel_id} to {language:?}");
+        Ok(())
+    }
+
+    /// Whether hourly sentiment is tracked for a channel
+    pub async fn get_channel_sentiment_tracking(
+        &self,
+        guild_id: &str,
+        channel_id: &str,
+    ) -> Result<bool> {
+        Ok(self
+            .get_channel_column(guild_id, channel_id, "sentiment_tracking")
+            .await?
+            .is_some_and(|value| value == "1"))
+    }
+
+    /// Turn hourly sentiment tracking on or off for a channel
+    pub async fn set_channel_sentiment_tracking(
+        &self,
+        guild_id: &str,
+        channel_id: &str,
+        enabled: bool,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO channel_settings (guild_id, channel_id, sentiment_tracking, updated_at)
+             VALUES (?, ?, ?, CURRENT_TIMESTAMP)
+             ON CONFLICT(guild_id, channel_id) DO UPDATE SET
+             sentiment_tracking = excluded.sentiment_tracking,
+             updated_at = CURRENT_TIMESTAMP",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, channel_id))?;
+        statement.bind((3, if enabled { 1i64 } else { 0i64 }))?;
+        statement.next()?;
+        self.forget_channel_column(guild_id, channel_id, "sentiment_tracking");
+        info!("Set sentiment_tracking for channel {channel_id} to {enabled}");
+        Ok(())
+    }
+
+    /// Whether clock times in a channel's messages are converted to timestamps
+    pub async fn get_channel_time_detection(
+        &self,
+        guild_id: &str,
+        channel_id: &str,
+    ) -> Result<bool> {
+        Ok(self
+            .get_channel_column(guild_id, channel_id, "time_detection")
+            .await?
+            .is_some_and(|value| value == "1"))
+    }
+
+    /// Turn time detection on or off for a channel
+    pub async fn set_channel_time_detection(
+        &self,
+        guild_id: &str,
+        channel_id: &str,
+        enabled: bool,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO channel_settings (guild_id, channel_id, time_detection, updated_at)
+             VALUES (?, ?, ?, CURRENT_TIMESTAMP)
+             ON CONFLICT(guild_id, channel_id) DO UPDATE SET
+             time_detection = excluded.time_detection,
+             updated_at = CURRENT_TIMESTAMP",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, channel_id))?;
+        statement.bind((3, if enabled { 1i64 } else { 0i64 }))?;
+        statement.next()?;
+        self.forget_channel_column(guild_id, channel_id, "time_detection");
+        info!("Set time_detection for channel {channel_id} to {enabled}");
+        Ok(())
+    }
+
+    /// The roleplay scene set for a channel, if any
+    pub async fn get_channel_scene(
+        &self,
+        guild_id: &str,
+        channel_id: &str,
+    ) -> Result<Option<String>> {
+        self.get_channel_column(guild_id, channel_id, "scene").await
+    }
+
+    /// Set or clear (`None`) the roleplay scene for a channel
+    pub async fn set_channel_scene(
+        &self,
+        guild_id: &str,
+        channel_id: &str,
+        scene: Option<&str>,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO channel_settings (guild_id, channel_id, scene, updated_at)
+             VALUES (?, ?, ?, CURRENT_TIMESTAMP)
+             ON CONFLICT(guild_id, channel_id) DO UPDATE SET
+             scene = excluded.scene,
+             updated_at = CURRENT_TIMESTAMP",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, channel_id))?;
+        statement.bind((3, scene))?;
+        statement.next()?;
+        self.forget_channel_column(guild_id, channel_id, "scene");
+        info!(
+            "Set scene for channel {channel_id} ({} chars)",
+            scene.map_or(0, |s| s.chars().count())
+        );
+        Ok(())
+    }
+
+    /// Channels with sentiment tracking on, as (guild_id, channel_id)
+    pub async fn get_sentiment_tracked_channels(&self) -> Result<Vec<(String, String)>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT guild_id, channel_id FROM channel_settings
+             WHERE sentiment_tracking = 1
+             ORDER BY guild_id, channel_id",
+        )?;
+
+        let mut channels = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            channels.push((
+                statement.read::<String, _>(0)?,
+                statement.read::<String, _>(1)?,
+            ));
+        }
+        Ok(channels)
+    }
+
+    /// Add one message's sentiment score to its channel's hourly bucket
+    pub async fn record_channel_sentiment(
+        &self,
+        guild_id: &str,
+        channel_id: &str,
+        hour: i64,
+        score: f64,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO channel_sentiment_hourly (channel_id, hour, guild_id, messages, score_sum)
+             VALUES (?, ?, ?, 1, ?)
+             ON CONFLICT(channel_id, hour) DO UPDATE SET
+             messages = messages + 1,
+             score_sum = score_sum + excluded.score_sum",
+        )?;
+        statement.bind((1, channel_id))?;
+        statement.bind((2, hour))?;
+        statement.bind((3, guild_id))?;
+        statement.bind((4, score))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Hourly sentiment for a channel since a unix timestamp, oldest first
+    pub async fn get_channel_sentiment(
+        &self,
+        channel_id: &str,
+        since: i64,
+    ) -> Result<Vec<SentimentPoint>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT hour, messages, score_sum FROM channel_sentiment_hourly
+             WHERE channel_id = ? AND hour >= ?
+             ORDER BY hour",
+        )?;
+        statement.bind((1, channel_id))?;
+        statement.bind((2, since))?;
+
+        let mut points = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            let messages = statement.read::<i64, _>(1)?;
+            let score_sum = statement.read::<f64, _>(2)?;
+            points.push(SentimentPoint {
+                hour: statement.read::<i64, _>(0)?,
+                messages,
+                score: if messages > 0 {
+                    score_sum / messages as f64
+                } else {
+                    0.0
+                },
+            });
+        }
+        Ok(points)
+    }
+
+    /// Delete hourly sentiment older than `days`
+    pub async fn cleanup_channel_sentiment(&self, days: i64) -> Result<usize> {
+        let conn = self.connection.lock().await?;
+        let cutoff = chrono::Utc::now().timestamp() - days * 86_400;
+        let mut statement = conn.prepare("DELETE FROM channel_sentiment_hourly WHERE hour < ?")?;
+        statement.bind((1, cutoff))?;
+        statement.next()?;
+        Ok(conn.change_count())
+    }
+
+    /// Check if a user has the bot admin role for a guild
+    pub async fn has_bot_admin_role(&self, guild_id: &str, user_roles: &[String]) -> Result<bool> {
+        // Get the bot admin role ID from guild settings
+        let admin_role = self.get_guild_setting(guild_id, "bot_admin_role").await?;
+
+        if let Some(role_id) = admin_role {
+            Ok(user_roles.iter().any(|r| r == &role_id))
+        } else {
+            // No bot admin role set - only Discord admins can manage
+            Ok(false)
+        }
+    }
+
+    // OpenAI Usage Tracking Methods
+
+    /// Log a ChatCompletion (GPT) usage event
+    #[allow(clippy::too_many_arguments)]
+    pub async fn log_openai_chat_usage(
+        &self,
+        model: &str,
+        input_tokens: u32,
+        output_tokens: u32,
+        total_tokens: u32,
+        estimated_cost: f64,
+        user_id: &str,
+        guild_id: Option<&str>,
+        channel_id: Option<&str>,
+        request_id: Option<&str>,
+        cost_bucket: &str,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
+
+        // Insert into raw usage table
+        let mut statement = conn.prepare(
+            "INSERT INTO openai_usage
+             (request_id, user_id, guild_id, channel_id, service_type, model,
+              input_tokens, output_tokens, total_tokens, estimated_cost_usd, cost_bucket)
+             VALUES (?, ?, ?, ?, 'chat', ?, ?, ?, ?, ?, ?)",
+        )?;
+        statement.bind((1, request_id.unwrap_or("")))?;
+        statement.bind((2, user_id))?;
+        statement.bind((3, guild_id.unwrap_or("")))?;
+        statement.bind((4, channel_id.unwrap_or("")))?;
+        statement.bind((5, model))?;
+        statement.bind((6, input_tokens as i64))?;
+        statement.bind((7, output_tokens as i64))?;
+        statement.bind((8, total_tokens as i64))?;
+        statement.bind((9, estimated_cost))?;
+        statement.bind((10, cost_bucket))?;
+        statement.next()?;
+
+        // Update daily aggregate
+        drop(statement);
+        let mut agg_stmt = conn.prepare(
+            "INSERT INTO openai_usage_daily
+             (date, guild_id, user_id, service_type, request_count, total_tokens, total_cost_usd, cost_bucket)
+             VALUES (?, ?, ?, 'chat', 1, ?, ?, ?)
+             ON CONFLICT(date, guild_id, user_id, service_type) DO UPDATE SET
+             request_count = request_count + 1,
+             total_tokens = total_tokens + excluded.total_tokens,
+             total_cost_usd = total_cost_usd + excluded.total_cost_usd",
+        )?;
+        agg_stmt.bind((1, date.as_str()))?;
+        agg_stmt.bind((2, guild_id.unwrap_or("")))?;
+        agg_stmt.bind((3, user_id))?;
+        agg_stmt.bind((4, total_tokens as i64))?;
+        agg_stmt.bind((5, estimated_cost))?;
+        agg_stmt.bind((6, cost_bucket))?;
+        agg_stmt.next()?;
+
+        Ok(())
+    }
+
+    /// Log a Whisper (audio transcription) usage event
+    pub async fn log_openai_whisper_usage(
+        &self,
+        audio_duration_seconds: f64,
+        estimated_cost: f64,
+        user_id: &str,
+        guild_id: Option<&str>,
+        channel_id: Option<&str>,
+        cost_bucket: &str,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
+
+        // Insert into raw usage table
+        let mut statement = conn.prepare(
+            "INSERT INTO openai_usage
+             (user_id, guild_id, channel_id, service_type, model,
+              audio_duration_seconds, estimated_cost_usd, cost_bucket)
+             VALUES (?, ?, ?, 'whisper', 'whisper-1', ?, ?, ?)",
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, guild_id.unwrap_or("")))?;
+        statement.bind((3, channel_id.unwrap_or("")))?;
+        statement.bind((4, audio_duration_seconds))?;
+        statement.bind((5, estimated_cost))?;
+        statement.bind((6, cost_bucket))?;
+        statement.next()?;
+
+        // Update daily aggregate
+        drop(statement);
+        let mut agg_stmt = conn.prepare(
+            "INSERT INTO openai_usage_daily
+             (date, guild_id, user_id, service_type, request_count, total_audio_seconds, total_cost_usd, cost_bucket)
+             VALUES (?, ?, ?, 'whisper', 1, ?, ?, ?)
+             ON CONFLICT(date, guild_id, user_id, service_type) DO UPDATE SET
+             request_count = request_count + 1,
+             total_audio_seconds = total_audio_seconds + excluded.total_audio_seconds,
+             total_cost_usd = total_cost_usd + excluded.total_cost_usd",
+        )?;
+        agg_stmt.bind((1, date.as_str()))?;
+        agg_stmt.bind((2, guild_id.unwrap_or("")))?;
+        agg_stmt.bind((3, user_id))?;
+        agg_stmt.bind((4, audio_duration_seconds))?;
+        agg_stmt.bind((5, estimated_cost))?;
+        agg_stmt.bind((6, cost_bucket))?;
+        agg_stmt.next()?;
+
+        Ok(())
+    }
+
+    /// Log an embeddings usage event
+    #[allow(clippy::too_many_arguments)]
+    pub async fn log_openai_embedding_usage(
+        &self,
+        model: &str,
+        tokens: u32,
+        estimated_cost: f64,
+        user_id: &str,
+        guild_id: Option<&str>,
+        channel_id: Option<&str>,
+        cost_bucket: &str,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
+
+        // Insert into raw usage table
+        let mut statement = conn.prepare(
+            "INSERT INTO openai_usage
+             (user_id, guild_id, channel_id, service_type, model,
+              input_tokens, total_tokens, estimated_cost_usd, cost_bucket)
+             VALUES (?, ?, ?, 'embedding', ?, ?, ?, ?, ?)",
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, guild_id.unwrap_or("")))?;
+        statement.bind((3, channel_id.unwrap_or("")))?;
+        statement.bind((4, model))?;
+        statement.bind((5, tokens as i64))?;
+        statement.bind((6, tokens as i64))?;
+        statement.bind((7, estimated_cost))?;
+        statement.bind((8, cost_bucket))?;
+        statement.next()?;
+
+        // Update daily aggregate
+        drop(statement);
+        let mut agg_stmt = conn.prepare(
+            "INSERT INTO openai_usage_daily
+             (date, guild_id, user_id, service_type, request_count, total_tokens, total_cost_usd, cost_bucket)
+             VALUES (?, ?, ?, 'embedding', 1, ?, ?, ?)
+             ON CONFLICT(date, guild_id, user_id, service_type) DO UPDATE SET
+             request_count = request_count + 1,
+             total_tokens = total_tokens + excluded.total_tokens,
+             total_cost_usd = total_cost_usd + excluded.total_cost_usd",
+        )?;
+        agg_stmt.bind((1, date.as_str()))?;
+        agg_stmt.bind((2, guild_id.unwrap_or("")))?;
+        agg_stmt.bind((3, user_id))?;
+        agg_stmt.bind((4, tokens as i64))?;
+        agg_stmt.bind((5, estimated_cost))?;
+        agg_stmt.bind((6, cost_bucket))?;
+        agg_stmt.next()?;
+
+        Ok(())
+    }
+
+    /// Log a DALL-E (image generation) usage event
+    #[allow(clippy::too_many_arguments)]
+    pub async fn log_openai_dalle_usage(
+        &self,
+        image_size: &str,
+        image_count: u32,
+        estimated_cost: f64,
+        prompt_hash: Option<&str>,
+        user_id: &str,
+        guild_id: Option<&str>,
+        channel_id: Option<&str>,
+        cost_bucket: &str,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
+
+        // Insert into raw usage table
+        let mut statement = conn.prepare(
+            "INSERT INTO openai_usage
+             (user_id, guild_id, channel_id, service_type, model,
+              image_count, image_size, estimated_cost_usd, cost_bucket, prompt_hash)
+             VALUES (?, ?, ?, 'dalle', 'dall-e-3', ?, ?, ?, ?, ?)",
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, guild_id.unwrap_or("")))?;
+        statement.bind((3, channel_id.unwrap_or("")))?;
+        statement.bind((4, image_count as i64))?;
+        statement.bind((5, image_size))?;
+        statement.bind((6, estimated_cost))?;
+        statement.bind((7, cost_bucket))?;
+        statement.bind((8, prompt_hash))?;
+        statement.next()?;
+
+        // Update daily aggregate
+        drop(statement);
+        let mut agg_stmt = conn.prepare(
+            "INSERT INTO openai_usage_daily
+             (date, guild_id, user_id, service_type, request_count, total_images, total_cost_usd, cost_bucket)
+             VALUES (?, ?, ?, 'dalle', 1, ?, ?, ?)
+             ON CONFLICT(date, guild_id, user_id, service_type) DO UPDATE SET
+             request_count = request_count + 1,
+             total_images = total_images + excluded.total_images,
+             total_cost_usd = total_cost_usd + excluded.total_cost_usd",
+        )?;
+        agg_stmt.bind((1, date.as_str()))?;
+        agg_stmt.bind((2, guild_id.unwrap_or("")))?;
+        agg_stmt.bind((3, user_id))?;
+        agg_stmt.bind((4, image_count as i64))?;
+        agg_stmt.bind((5, estimated_cost))?;
+        agg_stmt.bind((6, cost_bucket))?;
+        agg_stmt.next()?;
+
+        Ok(())
+    }
+
+    /// Get total cost grouped by cost bucket
+    /// Returns Vec of (bucket_name, total_cost_usd)
+    pub async fn get_cost_by_bucket(&self, period_days: Option<u32>) -> Result<Vec<(String, f64)>> {
+        let conn = self.connection.lock().await?;
+
+        let query = match period_days {
+            Some(days) => {
+                let mut stmt = conn.prepare(
+                    "SELECT cost_bucket, SUM(total_cost_usd) as cost
+                     FROM openai_usage_rollup_live
+                     WHERE date >= date('now', ? || ' days')
+                     GROUP BY cost_bucket
+                     ORDER BY cost DESC",
+                )?;
+                stmt.bind((1, -(days as i64)))?;
+                let mut results = Vec::new();
+                while let sqlite::State::Row = stmt.next()? {
+                    let bucket: String = stmt.read(0)?;
+                    let cost: f64 = stmt.read(1)?;
+                    results.push((bucket, cost));
+                }
+                results
+            }
+            None => {
+                let mut stmt = conn.prepare(
+                    "SELECT cost_bucket, SUM(total_cost_usd) as cost
+                     FROM openai_usage_rollup_live
+                     GROUP BY cost_bucket
+                     ORDER BY cost DESC",
+                )?;
+                let mut results = Vec::new();
+                while let sqlite::State::Row = stmt.next()? {
+                    let bucket: String = stmt.read(0)?;
+                    let cost: f64 = stmt.read(1)?;
+                    results.push((bucket, cost));
+                }
+                results
+            }
+        };
+
+        Ok(query)
+    }
+
+    /// Get usage statistics for a user within a date range
+    /// Returns (service_type, request_count, tokens, audio_seconds, images, cost)
+    pub async fn get_user_usage_stats(
+        &self,
+        user_id: &str,
+        days: i64,
+    ) -> Result<Vec<(String, i64, i64, f64, i64, f64)>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT service_type,
+                    SUM(request_count) as requests,
+                    SUM(total_tokens) as tokens,
+                    SUM(total_audio_seconds) as audio_secs,
+                    SUM(total_images) as images,
+                    SUM(total_cost_usd) as cost
+             FROM openai_usage_daily
+             WHERE user_id = ? AND date >= date('now', ? || ' days')
+             GROUP BY service_type",
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, format!("-{days}").as_str()))?;
+
+        let mut results = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            let service_type = statement.read::<String, _>(0)?;
+            let requests = statement.read::<i64, _>(1)?;
+            let tokens = statement.read::<i64, _>(2)?;
+            let audio_secs = statement.read::<f64, _>(3)?;
+            let images = statement.read::<i64, _>(4)?;
+            let cost = statement.read::<f64, _>(5)?;
+            results.push((service_type, requests, tokens, audio_secs, images, cost));
+        }
+        Ok(results)
+    }
+
+    /// Get a user's usage statistics within a single guild
+    /// Returns (service_type, request_count, tokens, audio_seconds, images, cost)
+    pub async fn get_user_guild_usage_stats(
+        &self,
+        user_id: &str,
+        guild_id: &str,
+        days: i64,
+    ) -> Result<Vec<(String, i64, i64, f64, i64, f64)>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT service_type,
+                    SUM(request_count) as requests,
+                    SUM(total_tokens) as tokens,
+                    SUM(total_audio_seconds) as audio_secs,
+                    SUM(total_images) as images,
+                    SUM(total_cost_usd) as cost
+             FROM openai_usage_daily
+             WHERE user_id = ? AND guild_id = ? AND date >= date('now', ? || ' days')
+             GROUP BY service_type",
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, guild_id))?;
+        statement.bind((3, format!("-{days}").as_str()))?;
+
+        let mut results = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            let service_type = statement.read::<String, _>(0)?;
+            let requests = statement.read::<i64, _>(1)?;
+            let tokens = statement.read::<i64, _>(2)?;
+            let audio_secs = statement.read::<f64, _>(3)?;
+            let images = statement.read::<i64, _>(4)?;
+            let cost = statement.read::<f64, _>(5)?;
+            results.push((service_type, requests, tokens, audio_secs, images, cost));
+        }
+        Ok(results)
+    }
+
+    /// Get usage statistics for an entire guild within a date range
+    /// Only usage recorded in this guild is counted; members' DM usage is private
+    /// Returns (service_type, request_count, tokens, audio_seconds, images, cost)
+    pub async fn get_guild_usage_stats(
+        &self,
+        guild_id: &str,
+        days: i64,
+    ) -> Result<Vec<(String, i64, i64, f64, i64, f64)>> {
+        let conn = self.connection.lock().await?;
+        let days_str = format!("-{days}");
+        let mut statement = conn.prepare(
+            "SELECT service_type,
+                    SUM(request_count) as requests,
+                    SUM(total_tokens) as tokens,
+                    SUM(total_audio_seconds) as audio_secs,
+                    SUM(total_images) as images,
+                    SUM(total_cost_usd) as cost
+             FROM openai_usage_daily
+             WHERE guild_id = ?
+             AND date >= date('now', ? || ' days')
+             GROUP BY service_type",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, days_str.as_str()))?;
+
+        let mut results = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            let service_type = statement.read::<String, _>(0)?;
+            let requests = statement.read::<i64, _>(1)?;
+            let tokens = statement.read::<i64, _>(2)?;
+            let audio_secs = statement.read::<f64, _>(3)?;
+            let images = statement.read::<i64, _>(4)?;
+            let cost = statement.read::<f64, _>(5)?;
+            results.push((service_type, requests, tokens, audio_secs, images, cost));
+        }
+        Ok(results)
+    }
+
+    /// Get a breakdown of DALL-E spend for a guild: per channel, per user, and
+    /// the most expensive prompt hashes
+    pub async fn get_guild_image_cost_breakdown(
+        &self,
+        guild_id: &str,
+        days: i64,
+        limit: i64,
+    ) -> Result<ImageCostBreakdown> {
+        let conn = self.connection.lock().await?;
+        let days_str = format!("-{days}");
+
+        let mut breakdown = ImageCostBreakdown {
+            days,
+            ..Default::default()
+        };
+
+        // Totals across the whole period
+        let mut statement = conn.prepare(
+            "SELECT COALESCE(SUM(total_images), 0), COALESCE(SUM(total_cost_usd), 0.0)
+             FROM openai_usage_rollup_live
+             WHERE service_type = 'dalle' AND guild_id = ?
+             AND date >= date('now', ? || ' days')",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, days_str.as_str()))?;
+        if let Ok(State::Row) = statement.next() {
+            breakdown.total_images = statement.read::<i64, _>(0)?;
+            breakdown.total_cost = statement.read::<f64, _>(1)?;
+        }
+
+        // Per-channel spend
+        drop(statement);
+        let mut statement = conn.prepare(
+            "SELECT channel_id, SUM(request_count) as requests, SUM(total_images) as images,
+                    SUM(total_cost_usd) as cost
+             FROM openai_usage_rollup_live
+             WHERE service_type = 'dalle' AND guild_id = ?
+             AND date >= date('now', ? || ' days')
+             GROUP BY channel_id
+             ORDER BY cost DESC
+             LIMIT ?",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, days_str.as_str()))?;
+        statement.bind((3, limit))?;
+        while let Ok(State::Row) = statement.next() {
+            breakdown.by_channel.push(ImageCostEntry {
+                key: statement.read::<String, _>(0)?,
+                requests: statement.read::<i64, _>(1)?,
+                images: statement.read::<i64, _>(2)?,
+                cost: statement.read::<f64, _>(3)?,
+            });
+        }
+
+        // Per-user spend
+        drop(statement);
+        let mut statement = conn.prepare(
+            "SELECT user_id, SUM(request_count) as requests, SUM(total_images) as images,
+                    SUM(total_cost_usd) as cost
+             FROM openai_usage_rollup_live
+             WHERE service_type = 'dalle' AND guild_id = ?
+             AND date >= date('now', ? || ' days')
+             GROUP BY user_id
+             ORDER BY cost DESC
+             LIMIT ?",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, days_str.as_str()))?;
+        statement.bind((3, limit))?;
+        while let Ok(State::Row) = statement.next() {
+            breakdown.by_user.push(ImageCostEntry {
+                key: statement.read::<String, _>(0)?,
+                requests: statement.read::<i64, _>(1)?,
+                images: statement.read::<i64, _>(2)?,
+                cost: statement.read::<f64, _>(3)?,
+            });
+        }
+
+        // Most expensive prompts (hashed, rows logged before hashing are skipped)
+        drop(statement);
+        let mut statement = conn.prepare(
+            "SELECT prompt_hash, SUM(request_count) as requests, SUM(total_images) as images,
+                    SUM(total_cost_usd) as cost
+             FROM openai_usage_rollup_live
+             WHERE service_type = 'dalle' AND guild_id = ?
+             AND prompt_hash != ''
+             AND date >= date('now', ? || ' days')
+             GROUP BY prompt_hash
+             ORDER BY cost DESC
+             LIMIT ?",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, days_str.as_str()))?;
+        statement.bind((3, limit))?;
+        while let Ok(State::Row) = statement.next() {
+            breakdown.top_prompts.push(ImageCostEntry {
+                key: statement.read::<String, _>(0)?,
+                requests: statement.read::<i64, _>(1)?,
+                images: statement.read::<i64, _>(2)?,
+                cost: statement.read::<f64, _>(3)?,
+            });
+        }
+
+        Ok(breakdown)
+    }
+
+    /// Message, conflict and bot activity for a guild over the last `days` days
+    ///
+    /// Message counts cover passive guild messages (role `user`), so they
+    /// follow `/privacy` and history retention like everything else stored.
+    pub async fn get_server_activity(&self, guild_id: &str, days: i64) -> Result<ServerActivity> {
+        let conn = self.connection.lock().await?;
+        let since = format!("-{days} days");
+        let before = format!("-{} days", days * 2);
+        let mut activity = ServerActivity {
+            days,
+            ..Default::default()
+        };
+
+        let mut statement = conn.prepare(
+            "SELECT COUNT(*), COUNT(DISTINCT user_id) FROM conversation_history
+             WHERE guild_id = ? AND role = 'user' AND timestamp >= datetime('now', ?)",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, since.as_str()))?;
+        if let Ok(State::Row) = statement.next() {
+            activity.messages = statement.read::<i64, _>(0)?;
+            activity.active_members = statement.read::<i64, _>(1)?;
+        }
+
+        drop(statement);
+        let mut statement = conn.prepare(
+            "SELECT COUNT(*) FROM conversation_history
+             WHERE guild_id = ? AND role = 'user'
+             AND timestamp >= datetime('now', ?) AND timestamp < datetime('now', ?)",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, before.as_str()))?;
+        statement.bind((3, since.as_str()))?;
+        if let Ok(State::Row) = statement.next() {
+            activity.previous_messages = statement.read::<i64, _>(0)?;
+        }
+
+        drop(statement);
+        let mut statement = conn.prepare(
+            "SELECT channel_id, COUNT(*) AS messages FROM conversation_history
+             WHERE guild_id = ? AND role = 'user' AND timestamp >= datetime('now', ?)
+             GROUP BY channel_id ORDER BY messages DESC LIMIT 3",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, since.as_str()))?;
+        while let Ok(State::Row) = statement.next() {
+            activity.top_channels.push((
+                statement.read::<String, _>(0)?,
+                statement.read::<i64, _>(1)?,
+            ));
+        }
+
+        drop(statement);
+        let mut statement = conn.prepare(
+            "SELECT COUNT(*), COALESCE(SUM(mediation_triggered), 0) FROM conflict_detection
+             WHERE guild_id = ? AND first_detected >= datetime('now', ?)",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, since.as_str()))?;
+        if let Ok(State::Row) = statement.next() {
+            activity.conflicts = statement.read::<i64, _>(0)?;
+            activity.mediations = statement.read::<i64, _>(1)?;
+        }
+
+        drop(statement);
+        let mut statement = conn.prepare(
+            "SELECT cost_bucket, SUM(request_count) AS requests, SUM(total_cost_usd)
+             FROM openai_usage_rollup_live
+             WHERE guild_id = ? AND date >= date('now', ?)
+             GROUP BY cost_bucket ORDER BY requests DESC",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, since.as_str()))?;
+        while let Ok(State::Row) = statement.next() {
+            let bucket = statement.read::<String, _>(0)?;
+            let requests = statement.read::<i64, _>(1)?;
+            activity.bot_requests += requests;
+            activity.cost_usd += statement.read::<f64, _>(2)?;
+            activity.top_features.push((bucket, requests));
+        }
+
+        Ok(activity)
+    }
+
+    /// Text of the most recent passive guild messages from the last `days` days
+    pub async fn get_recent_guild_message_texts(
+        &self,
+        guild_id: &str,
+        days: i64,
+        limit: i64,
+    ) -> Result<Vec<String>> {
+        let conn = self.connection.lock().await?;
+        let since = format!("-{days} days");
+        let mut statement = conn.prepare(
+            "SELECT content FROM conversation_history
+             WHERE guild_id = ? AND role = 'user' AND timestamp >= datetime('now', ?)
+             ORDER BY id DESC LIMIT ?",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, since.as_str()))?;
+        statement.bind((3, limit))?;
+
+        let mut messages = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            messages.push(statement.read::<String, _>(0)?);
+        }
+        Ok(messages)
+    }
+
+    /// Plugin AI summary spend for a guild, per plugin name
+    ///
+    /// Reads raw usage rows tagged `plugin:<name>:<stage>`; returns (plugin, requests, cost).
+    pub async fn get_guild_plugin_summary_costs(
+        &self,
+        guild_id: &str,
+        days: i64,
+    ) -> Result<Vec<(String, i64, f64)>> {
+        let conn = self.connection.lock().await?;
+        let days_str = format!("-{days}");
+        let mut statement = conn.prepare(
+            "SELECT substr(request_id, 8, instr(substr(request_id, 8), ':') - 1) as plugin,
+                    COUNT(*) as requests,
+                    SUM(estimated_cost_usd) as cost
+             FROM openai_usage
+             WHERE guild_id = ?
+             AND cost_bucket = 'plugin'
+             AND request_id LIKE 'plugin:%:%'
+             AND timestamp >= datetime('now', ? || ' days')
+             GROUP BY plugin
+             ORDER BY cost DESC",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, days_str.as_str()))?;
+
+        let mut results = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            let plugin = statement.read::<String, _>(0)?;
+            let requests = statement.read::<i64, _>(1)?;
+            let cost = statement.read::<f64, _>(2)?;
+            results.push((plugin, requests, cost));
+        }
+        Ok(results)
+    }
+
+    /// Get top users by cost for a guild
+    /// Only usage recorded in this guild is counted; members' DM usage is private
+    /// Returns (user_id, request_count, total_cost)
+    pub async fn get_guild_top_users_by_cost(
+        &self,
+        guild_id: &str,
+        days: i64,
+        limit: i64,
+    ) -> Result<Vec<(String, i64, f64)>> {
+        let conn = self.connection.lock().await?;
+        let days_str = format!("-{days}");
+        let mut statement = conn.prepare(
+            "SELECT user_id,
+                    SUM(request_count) as requests,
+                    SUM(total_cost_usd) as cost
+             FROM openai_usage_daily
+             WHERE guild_id = ?
+             AND user_id != ''
+             AND date >= date('now', ? || ' days')
+             GROUP BY user_id
+             ORDER BY cost DESC
+             LIMIT ?",
+        )?;
+        statement.bind((1, guild_id))?;
+        statement.bind((2, days_str.as_str()))?;
+        statement.bind((3, limit))?;
+
+        let mut results = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            let user_id = statement.read::<String, _>(0)?;
+            let requests = statement.read::<i64, _>(1)?;
+            let cost = statement.read::<f64, _>(2)?;
+            results.push((user_id, requests, cost));
+        }
+        Ok(results)
+    }
+
+    /// Get global usage statistics across all users and guilds
+    /// Returns (total_cost, period_cost, total_tokens, total_calls, cost_by_service, cost_by_bucket, daily_breakdown, top_users)
+    #[allow(clippy::type_complexity)]
+    pub async fn get_global_usage_stats(
+        &self,
+        period_days: Option<u32>,
+    ) -> Result<(
+        f64,
+        f64,
+        u64,
+        u64,
+        Vec<(String, f64)>,
+        Vec<(String, f64)>,
+        Vec<(String, f64)>,
+        Vec<(String, Option<String>, f64)>,
+    )> {
+        let conn = self.connection.lock().await?;
+
+        // Total cost (all time)
+        let total_cost: f64 = {
+            let mut stmt =
+                conn.prepare("SELECT COALESCE(SUM(total_cost_usd), 0) FROM openai_usage_daily")?;
+            if stmt.next()? == State::Row {
+                stmt.read::<f64, _>(0)?
+            } else {
+                0.0
+            }
+        };
+
+        // Period cost
+        let period_cost: f64 = if let Some(days) = period_days {
+            let days_str = format!("-{days}");
+            let mut stmt = conn.prepare(
+                "SELECT COALESCE(SUM(total_cost_usd), 0) FROM openai_usage_daily WHERE date >= date('now', ? || ' days')"
+            )?;
+            stmt.bind((1, days_str.as_str()))?;
+            if stmt.next()? == State::Row {
+                stmt.read::<f64, _>(0)?
+            } else {
+                0.0
+            }
+        } else {
+            total_cost
+        };
+
+        // Total tokens (all time)
+        let total_tokens: u64 = {
+            let mut stmt =
+                conn.prepare("SELECT COALESCE(SUM(total_tokens), 0) FROM openai_usage_daily")?;
+            if stmt.next()? == State::Row {
+                stmt.read::<i64, _>(0)? as u64
+            } else {
+                0
+            }
+        };
+
+        // Total calls (all time)
+        let total_calls: u64 = {
+            let mut stmt =
+                conn.prepare("SELECT COALESCE(SUM(request_count), 0) FROM openai_usage_daily")?;
+            if stmt.next()? == State::Row {
+                stmt.read::<i64, _>(0)? as u64
+            } else {
+                0
+            }
+        };
+
+        // Cost by service (for period or all time)
+        let cost_by_service: Vec<(String, f64)> = {
+            let mut results = Vec::new();
+            let query = if let Some(days) = period_days {
+                format!(
+                    "SELECT service_type, SUM(total_cost_usd) as cost FROM openai_usage_daily \
+                     WHERE date >= date('now', '-{days} days') GROUP BY service_type ORDER BY cost DESC"
+                )
+            } else {
+                "SELECT service_type, SUM(total_cost_usd) as cost FROM openai_usage_daily \
+                 GROUP BY service_type ORDER BY cost DESC"
+                    .to_string()
+            };
+            let mut stmt = conn.prepare(&query)?;
+            while let Ok(State::Row) = stmt.next() {
+                let service = stmt.read::<String, _>(0)?;
+                let cost = stmt.read::<f64, _>(1)?;
+                results.push((service, cost));
+            }
+            results
+        };
+
+        // Cost by bucket (for period or all time)
+        let cost_by_bucket: Vec<(String, f64)> = {
+            let mut results = Vec::new();
+            let query = if let Some(days) = period_days {
+                format!(
+                    "SELECT cost_bucket, SUM(total_cost_usd) as cost FROM openai_usage_rollup_live \
+                     WHERE date >= date('now', '-{days} days') GROUP BY cost_bucket ORDER BY cost DESC"
+                )
+            } else {
+                "SELECT cost_bucket, SUM(total_cost_usd) as cost FROM openai_usage_rollup_live \
+                 GROUP BY cost_bucket ORDER BY cost DESC"
+                    .to_string()
+            };
+            let mut stmt = conn.prepare(&query)?;
+            while let Ok(State::Row) = stmt.next() {
+                let bucket = stmt.read::<String, _>(0)?;
+                let cost = stmt.read::<f64, _>(1)?;
+                results.push((bucket, cost));
+            }
+            results
+        };
+
+        // Daily breakdown (last 14 days or period)
+        let daily_breakdown: Vec<(String, f64)> = {
+            let mut results = Vec::new();
+            let days = period_days.unwrap_or(14);
+            let mut stmt = conn.prepare(
+                "SELECT date, SUM(total_cost_usd) as cost FROM openai_usage_daily \
+                 WHERE date >= date('now', ? || ' days') GROUP BY date ORDER BY date ASC",
+            )?;
+            let days_str = format!("-{days}");
+            stmt.bind((1, days_str.as_str()))?;
+            while let Ok(State::Row) = stmt.next() {
+                let date = stmt.read::<String, _>(0)?;
+                let cost = stmt.read::<f64, _>(1)?;
+                results.push((date, cost));
+            }
+            results
+        };
+
+        // Top users by cost (for period or all time, limit 10) with cached usernames
+        let top_users: Vec<(String, Option<String>, f64)> = {
+            let mut results = Vec::new();
+            let query = if let Some(days) = period_days {
+                format!(
+                    "SELECT u.user_id, uc.username, SUM(u.total_cost_usd) as cost \
+                     FROM openai_usage_daily u \
+                     LEFT JOIN user_cache uc ON u.user_id = uc.user_id \
+                     WHERE u.user_id != '' AND u.date >= date('now', '-{days} days') \
+                     GROUP BY u.user_id ORDER BY cost DESC LIMIT 10"
+                )
+            } else {
+                "SELECT u.user_id, uc.username, SUM(u.total_cost_usd) as cost \
+                 FROM openai_usage_daily u \
+                 LEFT JOIN user_cache uc ON u.user_id = uc.user_id \
+                 WHERE u.user_id != '' GROUP BY u.user_id ORDER BY cost DESC LIMIT 10"
+                    .to_string()
+            };
+            let mut stmt = conn.prepare(&query)?;
+            while let Ok(State::Row) = stmt.next() {
+                let user_id = stmt.read::<String, _>(0)?;
+                let username = stmt.read::<Option<String>, _>(1)?;
+                let cost = stmt.read::<f64, _>(2)?;
+                results.push((user_id, username, cost));
+            }
+            results
+        };
+
+        Ok((
+            total_cost,
+            period_cost,
+            total_tokens,
+            total_calls,
+            cost_by_service,
+            cost_by_bucket,
+            daily_breakdown,
+            top_users,
+        ))
+    }
+
+    /// Cleanup old raw usage data (keep last N days)
+    pub async fn cleanup_old_openai_usage(&self, days: i64) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn
+            .prepare("DELETE FROM openai_usage WHERE timestamp < datetime('now', ? || ' days')")?;
+        statement.bind((1, format!("-{days}").as_str()))?;
+        statement.next()?;
+        info!("Cleaned up openai_usage older than {days} days");
+        Ok(())
+    }
+
+    /// Cleanup old daily aggregates (keep last N days)
+    pub async fn cleanup_old_openai_usage_daily(&self, days: i64) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("DELETE FROM openai_usage_daily WHERE date < date('now', ? || ' days')")?;
+        statement.bind((1, format!("-{days}").as_str()))?;
+        statement.next()?;
+        info!("Cleaned up openai_usage_daily older than {days} days");
+        Ok(())
+    }
+
+    /// Fold completed hours of raw usage and command rows into the rollup tables
+    ///
+    /// Everything between the stored watermark and the start of the current hour
+    /// is aggregated into `openai_usage_rollup_hourly` and `command_usage_daily`,
+    /// the touched days of `openai_usage_rollup_daily` are rebuilt from the hourly
+    /// rows, and the watermark advances. Runs in one transaction, so a crash
+    /// never counts an hour twice. Returns the number of raw usage rows folded in.
+    pub async fn rollup_analytics(&self) -> Result<i64> {
+        let conn = self.connection.lock().await?;
+
+        let mut statement =
+            conn.prepare("SELECT setting_value FROM bot_settings WHERE setting_key = ?")?;
+        statement.bind((1, ROLLUP_WATERMARK_KEY))?;
+        let watermark = match statement.next()? {
+            State::Row => statement.read::<Option<String>, _>(0)?.unwrap_or_default(),
+            State::Done => String::new(),
+        };
+
+        drop(statement);
+        let mut statement = conn.prepare("SELECT strftime('%Y-%m-%d %H:00:00', 'now')")?;
+        statement.next()?;
+        let current_hour = statement.read::<String, _>(0)?;
+        drop(statement);
+
+        if watermark >= current_hour {
+            return Ok(0);
+        }
+
+        conn.execute("BEGIN IMMEDIATE")?;
+
+        let result = (|| -> Result<i64> {
+            let mut statement = conn.prepare(
+                "SELECT COUNT(*) FROM openai_usage WHERE timestamp >= ?1 AND timestamp < ?2",
+            )?;
+            statement.bind((1, watermark.as_str()))?;
+            statement.bind((2, current_hour.as_str()))?;
+            statement.next()?;
+            let rows = statement.read::<i64, _>(0)?;
+
+            drop(statement);
+            let mut statement = conn.prepare(
+                "INSERT INTO openai_usage_rollup_hourly
+                    (hour, guild_id, channel_id, user_id, service_type, cost_bucket, prompt_hash,
+                     request_count, total_tokens, total_audio_seconds, total_images, total_cost_usd)
+                 SELECT strftime('%Y-%m-%d %H:00:00', timestamp), COALESCE(guild_id, ''),
+                        COALESCE(channel_id, ''), user_id, service_type, cost_bucket,
+                        COALESCE(prompt_hash, ''), COUNT(*), SUM(total_tokens),
+                        SUM(audio_duration_seconds), SUM(image_count), SUM(estimated_cost_usd)
+                 FROM openai_usage
+                 WHERE timestamp >= ?1 AND timestamp < ?2
+                 GROUP BY 1, 2, 3, 4, 5, 6, 7
+                 ON CONFLICT(hour, guild_id, channel_id, user_id, service_type, cost_bucket, prompt_hash)
+                 DO UPDATE SET
+                    request_count = excluded.request_count,
+                    total_tokens = excluded.total_tokens,
+                    total_audio_seconds = excluded.total_audio_seconds,
+                    total_images = excluded.total_images,
+                    total_cost_usd = excluded.total_cost_usd",
+            )?;
+            statement.bind((1, watermark.as_str()))?;
+            statement.bind((2, current_hour.as_str()))?;
+            statement.next()?;
+
+            // Rebuild whole days so a day split across runs sums every hour
+            drop(statement);
+            let mut statement = conn.prepare(
+                "INSERT INTO openai_usage_rollup_daily
+                    (date, guild_id, channel_id, user_id, service_type, cost_bucket, prompt_hash,
+                     request_count, total_tokens, total_audio_seconds, total_images, total_cost_usd)
+                 SELECT substr(hour, 1, 10), guild_id, channel_id, user_id, service_type,
+                        cost_bucket, prompt_hash, SUM(request_count), SUM(total_tokens),
+                        SUM(total_audio_seconds), SUM(total_images), SUM(total_cost_usd)
+                 FROM openai_usage_rollup_hourly
+                 WHERE hour >= substr(?1, 1, 10) AND hour < ?2
+                 GROUP BY 1, 2, 3, 4, 5, 6, 7
+                 ON CONFLICT(date, guild_id, channel_id, user_id, service_type, cost_bucket, prompt_hash)
+                 DO UPDATE SET
+                    request_count = excluded.request_count,
+                    total_tokens = excluded.total_tokens,
+                    total_audio_seconds = excluded.total_audio_seconds,
+                    total_images = excluded.total_images,
+                    total_cost_usd = excluded.total_cost_usd",
+            )?;
+            statement.bind((1, watermark.as_str()))?;
+            statement.bind((2, current_hour.as_str()))?;
+            statement.next()?;
+
+            drop(statement);
+            let mut statement = conn.prepare(
+                "INSERT INTO command_usage_daily (date, guild_id, user_id, command, persona, invocations)
+                 SELECT date(timestamp), COALESCE(guild_id, ''), user_id, command,
+                        COALESCE(persona, ''), COUNT(*)
+                 FROM usage_stats
+                 WHERE timestamp >= ?1 AND timestamp < ?2
+                 GROUP BY 1, 2, 3, 4, 5
+                 ON CONFLICT(date, guild_id, user_id, command, persona)
+                 DO UPDATE SET invocations = invocations + excluded.invocations",
+            )?;
+            statement.bind((1, watermark.as_str()))?;
+            statement.bind((2, current_hour.as_str()))?;
+            statement.next()?;
+
+            drop(statement);
+            let mut statement = conn.prepare(
+                "INSERT OR REPLACE INTO bot_settings (setting_key, setting_value, updated_at)
+                 VALUES (?, ?, CURRENT_TIMESTAMP)",
+            )?;
+            statement.bind((1, ROLLUP_WATERMARK_KEY))?;
+            statement.bind((2, current_hour.as_str()))?;
+            statement.next()?;
+
+            Ok(rows)
+        })();
+
+        match result {
+            Ok(rows) => {
+                conn.execute("COMMIT")?;
+                Ok(rows)
+            }
+            Err(e) => {
+                let _ = conn.execute("ROLLBACK");
+                Err(e)
+            }
+        }
+    }
+
+    /// Cleanup raw command usage rows (keep last N days)
+    pub async fn cleanup_old_usage_stats(&self, days: i64) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn
+            .prepare("DELETE FROM usage_stats WHERE timestamp < datetime('now', ? || ' days')")?;
+        statement.bind((1, format!("-{days}").as_str()))?;
+        statement.next()?;
+        info!("Cleaned up usage_stats older than {days} days");
+        Ok(())
+    }
+
+    /// Cleanup analytics rollups (hourly rows are kept just long enough to rebuild days)
+    pub async fn cleanup_old_rollups(&self, days: i64) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "DELETE FROM openai_usage_rollup_hourly WHERE hour < datetime('now', ? || ' days')",
+        )?;
+        statement.bind((
+            1,
+            format!("-{}", days.clamp(2, HOURLY_ROLLUP_RETENTION_DAYS)).as_str(),
+        ))?;
+        statement.next()?;
+
+        for table in ["openai_usage_rollup_daily", "command_usage_daily"] {
+            let mut statement = conn.prepare(format!(
+                "DELETE FROM {table} WHERE date < date('now', ? || ' days')"
+            ))?;
+            statement.bind((1, format!("-{days}").as_str()))?;
+            statement.next()?;
+        }
+        info!("Cleaned up analytics rollups older than {days} days");
+        Ok(())
+    }
+
+    // DM Interaction Tracking Methods
+
+    /// Create a new DM session
+    pub async fn create_dm_session(
+        &self,
+        session_id: &str,
+        user_id: &str,
+        channel_id: &str,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO dm_sessions (session_id, user_id, channel_id) VALUES (?, ?, ?)",
+        )?;
+        statement.bind((1, session_id))?;
+        statement.bind((2, user_id))?;
+        statement.bind((3, channel_id))?;
+        statement.next()?;
+
+        // Also create metrics row
+        let mut metrics_stmt =
+            conn.prepare("INSERT INTO dm_session_metrics (session_id) VALUES (?)")?;
+        metrics_stmt.bind((1, session_id))?;
+        metrics_stmt.next()?;
+
+        Ok(())
+    }
+
+    /// End a DM session
+    pub async fn end_dm_session(&self, session_id: &str, reason: &str) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "UPDATE dm_sessions SET ended_at = CURRENT_TIMESTAMP, end_reason = ? WHERE session_id = ?"
+        )?;
+        statement.bind((1, reason))?;
+        statement.bind((2, session_id))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Update DM session activity
+    #[allow(clippy::too_many_arguments)]
+    pub async fn update_dm_session_activity(
+        &self,
+        session_id: &str,
+        msg_count: i32,
+        user_msg_count: i32,
+        bot_msg_count: i32,
+        user_chars: i32,
+        bot_chars: i32,
+        avg_response_time: i32,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "UPDATE dm_sessions
+             SET message_count = ?,
+                 user_message_count = ?,
+                 bot_message_count = ?,
+                 total_user_chars = ?,
+                 total_bot_chars = ?,
+                 avg_response_time_ms = ?,
+                 last_activity_at = CURRENT_TIMESTAMP
+             WHERE session_id = ?",
+        )?;
+        statement.bind((1, msg_count as i64))?;
+        statement.bind((2, user_msg_count as i64))?;
+        statement.bind((3, bot_msg_count as i64))?;
+        statement.bind((4, user_chars as i64))?;
+        statement.bind((5, bot_chars as i64))?;
+        statement.bind((6, avg_response_time as i64))?;
+        statement.bind((7, session_id))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Get DM sessions that were never ended (e.g. left open by a restart)
+    pub async fn get_open_dm_sessions(&self) -> Result<Vec<OpenDmSession>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT session_id, user_id, channel_id, started_at, last_activity_at,
+                    message_count, user_message_count, bot_message_count,
+                    total_user_chars, total_bot_chars, COALESCE(avg_response_time_ms, 0)
+             FROM dm_sessions
+             WHERE ended_at IS NULL
+             ORDER BY last_activity_at DESC",
+        )?;
+
+        let mut sessions = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            sessions.push(OpenDmSession {
+                session_id: statement.read::<String, _>(0)?,
+                user_id: statement.read::<String, _>(1)?,
+                channel_id: statement.read::<String, _>(2)?,
+                started_at: statement.read::<String, _>(3)?,
+                last_activity_at: statement.read::<String, _>(4)?,
+                message_count: statement.read::<i64, _>(5)?,
+                user_message_count: statement.read::<i64, _>(6)?,
+                bot_message_count: statement.read::<i64, _>(7)?,
+                total_user_chars: statement.read::<i64, _>(8)?,
+                total_bot_chars: statement.read::<i64, _>(9)?,
+                avg_response_time_ms: statement.read::<i64, _>(10)?,
+            });
+        }
+        Ok(sessions)
+    }
+
+    /// End a DM session as of its last recorded activity rather than now,
+    /// so downtime isn't counted towards the session duration
+    pub async fn end_dm_session_at_last_activity(
+        &self,
+        session_id: &str,
+        reason: &str,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "UPDATE dm_sessions SET ended_at = last_activity_at, end_reason = ?
+             WHERE session_id = ? AND ended_at IS NULL",
+        )?;
+        statement.bind((1, reason))?;
+        statement.bind((2, session_id))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Log a DM event
+    pub async fn log_dm_event(
+        &self,
+        session_id: &str,
+        event_type: &str,
+        user_id: &str,
+        channel_id: &str,
+        event_data: Option<&str>,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO dm_events (session_id, event_type, user_id, channel_id, event_data)
+             VALUES (?, ?, ?, ?, ?)",
+        )?;
+        statement.bind((1, session_id))?;
+        statement.bind((2, event_type))?;
+        statement.bind((3, user_id))?;
+        statement.bind((4, channel_id))?;
+        statement.bind((5, event_data.unwrap_or("")))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Update DM session metrics
+    pub async fn update_dm_session_metrics(
+        &self,
+        session_id: &str,
+        api_type: &str,
+        tokens: u32,
+        cost: f64,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+
+        let (api_field, tokens_update) = match api_type {
+            "chat" => (
+                "chat_calls = chat_calls + 1",
+                format!("total_tokens = total_tokens + {tokens}"),
+            ),
+            "whisper" => ("whisper_calls = whisper_calls + 1", String::new()),
+            "dalle" => ("dalle_calls = dalle_calls + 1", String::new()),
+            _ => return Ok(()),
+        };
+
+        let sql = if tokens_update.is_empty() {
+            format!(
+                "UPDATE dm_session_metrics
+                 SET {api_field},
+                     total_api_calls = total_api_calls + 1,
+                     total_api_cost_usd = total_api_cost_usd + ?,
+                     updated_at = CURRENT_TIMESTAMP
+                 WHERE session_id = ?"
+            )
+        } else {
+            format!(
+                "UPDATE dm_session_metrics
+                 SET {api_field},
+                     {tokens_update},
+                     total_api_calls = total_api_calls + 1,
+                     total_api_cost_usd = total_api_cost_usd + ?,
+                     updated_at = CURRENT_TIMESTAMP
+                 WHERE session_id = ?"
+            )
+        };
+
+        let mut statement = conn.prepare(&sql)?;
+        statement.bind((1, cost))?;
+        statement.bind((2, session_id))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Increment DM session feature counter
+    pub async fn increment_dm_session_feature(
+        &self,
+        session_id: &str,
+        feature: &str,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+
+        let field = match feature {
+            "audio" => "audio_transcriptions",
+            "slash_command" => "slash_commands_used",
+            _ => return Ok(()),
+        };
+
+        let sql = format!(
+            "UPDATE dm_session_metrics
+             SET {field} = {field} + 1, updated_at = CURRENT_TIMESTAMP
+             WHERE session_id = ?"
+        );
+
+        let mut statement = conn.prepare(&sql)?;
+        statement.bind((1, session_id))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Get user DM stats for the last N days
+    pub async fn get_user_dm_stats(&self, user_id: &str, days: i64) -> Result<DmStats> {
+        let conn = self.connection.lock().await?;
+
+        // Get session counts and averages
+        let mut stmt = conn.prepare(
+            "SELECT
+                COUNT(*) as session_count,
+                SUM(message_count) as total_messages,
+                SUM(user_message_count) as user_messages,
+                SUM(bot_message_count) as bot_messages,
+                AVG(avg_response_time_ms) as avg_response_time,
+                AVG((julianday(ended_at) - julianday(started_at)) * 24 * 60) as avg_duration_min
+             FROM dm_sessions
+             WHERE user_id = ?
+             AND started_at >= datetime('now', ? || ' days')
+             AND ended_at IS NOT NULL",
+        )?;
+        stmt.bind((1, user_id))?;
+        stmt.bind((2, format!("-{days}").as_str()))?;
+
+        let (
+            session_count,
+            total_messages,
+            user_messages,
+            bot_messages,
+            avg_response_time,
+            avg_duration,
+        ) = if let Ok(State::Row) = stmt.next() {
+            (
+                stmt.read::<i64, _>(0).unwrap_or(0),
+                stmt.read::<i64, _>(1).unwrap_or(0),
+                stmt.read::<i64, _>(2).unwrap_or(0),
+                stmt.read::<i64, _>(3).unwrap_or(0),
+                stmt.read::<i64, _>(4).unwrap_or(0),
+                stmt.read::<f64, _>(5).unwrap_or(0.0),
+            )
+        } else {
+            (0, 0, 0, 0, 0, 0.0)
+        };
+
+        // Get API metrics
+        let mut api_stmt = conn.prepare(
+            "SELECT
+                SUM(sm.total_api_calls) as api_calls,
+                SUM(sm.total_tokens) as tokens,
+                SUM(sm.total_api_cost_usd) as cost,
+                SUM(sm.chat_calls) as chat_calls,
+                SUM(sm.whisper_calls) as whisper_calls,
+                SUM(sm.dalle_calls) as dalle_calls,
+                SUM(sm.audio_transcriptions) as audio_count,
+                SUM(sm.slash_commands_used) as slash_count
+             FROM dm_session_metrics sm
+             JOIN dm_sessions s ON sm.session_id = s.session_id
+             WHERE s.user_id = ?
+             AND s.started_at >= datetime('now', ? || ' days')",
+        )?;
+        api_stmt.bind((1, user_id))?;
+        api_stmt.bind((2, format!("-{days}").as_str()))?;
+
+        let (
+            api_calls,
+            tokens,
+            cost,
+            chat_calls,
+            whisper_calls,
+            dalle_calls,
+            audio_count,
+            slash_count,
+        ) = if let Ok(State::Row) = api_stmt.next() {
+            (
+                api_stmt.read::<i64, _>(0).unwrap_or(0),
+                api_stmt.read::<i64, _>(1).unwrap_or(0),
+                api_stmt.read::<f64, _>(2).unwrap_or(0.0),
+                api_stmt.read::<i64, _>(3).unwrap_or(0),
+                api_stmt.read::<i64, _>(4).unwrap_or(0),
+                api_stmt.read::<i64, _>(5).unwrap_or(0),
+                api_stmt.read::<i64, _>(6).unwrap_or(0),
+                api_stmt.read::<i64, _>(7).unwrap_or(0),
+            )
+        } else {
+            (0, 0, 0.0, 0, 0, 0, 0, 0)
+        };
+
+        Ok(DmStats {
+            session_count,
+            total_messages,
+            user_messages,
+            bot_messages,
+            avg_response_time_ms: avg_response_time,
+            avg_session_duration_min: avg_duration,
+            api_calls,
+            total_tokens: tokens,
+            total_cost_usd: cost,
+            chat_calls,
+            whisper_calls,
+            dalle_calls,
+            audio_transcriptions: audio_count,
+            slash_commands_used: slash_count,
+        })
+    }
+
+    /// Get user's recent DM sessions
+    pub async fn get_user_recent_sessions(
+        &self,
+        user_id: &str,
+        limit: i64,
+    ) -> Result<Vec<SessionInfo>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT session_id, started_at, ended_at, message_count, avg_response_time_ms
+             FROM dm_sessions
+             WHERE user_id = ?
+             ORDER BY started_at DESC
+             LIMIT ?",
+        )?;
+        statement.bind((1, user_id))?;
+        statement.bind((2, limit))?;
+
+        let mut sessions = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            sessions.push(SessionInfo {
+                session_id: statement.read::<String, _>(0)?,
+                started_at: statement.read::<String, _>(1)?,
+                ended_at: statement.read::<Option<String>, _>(2)?,
+                message_count: statement.read::<i64, _>(3)?,
+                avg_response_time_ms: statement.read::<i64, _>(4).unwrap_or(0),
+            });
+        }
+
+        Ok(sessions)
+    }
+
+    /// Cleanup old DM events (keep last N days)
+    pub async fn cleanup_old_dm_events(&self, days: i64) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("DELETE FROM dm_events WHERE timestamp < datetime('now', ? || ' days')")?;
+        statement.bind((1, format!("-{days}").as_str()))?;
+        statement.next()?;
+        info!("Cleaned up dm_events older than {days} days");
+        Ok(())
+    }
+
+    // Plugin Job Methods
+
+    /// Create a new plugin job record
+    pub async fn create_plugin_job(&self, job: &crate::features::plugins::job::Job) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let params_json = serde_json::to_string(&job.params)?;
+
+        let mut statement = conn.prepare(
+            "INSERT INTO plugin_jobs (id, plugin_name, user_id, guild_id, channel_id, status, params, started_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+        )?;
+        statement.bind((1, job.id.as_str()))?;
+        statement.bind((2, job.plugin_name.as_str()))?;
+        statement.bind((3, job.user_id.as_str()))?;
+        statement.bind((4, job.guild_id.as_deref().unwrap_or("")))?;
+        statement.bind((5, job.channel_id.as_str()))?;
+        statement.bind((6, job.status.to_string().as_str()))?;
+        statement.bind((7, params_json.as_str()))?;
+        statement.bind((8, job.started_at.to_rfc3339().as_str()))?;
+        statement.next()?;
+
+        Ok(())
+    }
+
+    /// Update a plugin job record
+    pub async fn update_plugin_job(&self, job: &crate::features::plugins::job::Job) -> Result<()> {
+        let conn = self.connection.lock().await?;
+
+        let completed_at = job.completed_at.map(|t| t.to_rfc3339()).unwrap_or_default();
+
+        let result_preview = job
+            .result
+            .as_ref()
+            .map(|r| r.chars().take(1000).collect::<String>())
+            .unwrap_or_default();
+
+        let error = job.error.as_ref().cloned().unwrap_or_default();
+
+        let thread_id = job.thread_id.as_deref().unwrap_or("");
+
+        let mut statement = conn.prepare(
+            "UPDATE plugin_jobs SET
+                status = ?,
+                thread_id = ?,
+                result = ?,
+                error = ?,
+                completed_at = CASE WHEN ? = '' THEN NULL ELSE ? END
+             WHERE id = ?",
+        )?;
+        statement.bind((1, job.status.to_string().as_str()))?;
+        statement.bind((2, thread_id))?;
+        statement.bind((3, result_preview.as_str()))?;
+        statement.bind((4, error.as_str()))?;
+        statement.bind((5, completed_at.as_str()))?;
+        statement.bind((6, completed_at.as_str()))?;
+        statement.bind((7, job.id.as_str()))?;
+        statement.next()?;
+
+        Ok(())
+    }
+
+    /// Get incomplete plugin jobs (for crash recovery)
+    pub async fn get_incomplete_plugin_jobs(
+        &self,
+    ) -> Result<Vec<crate::features::plugins::job::Job>> {
+        use crate::features::plugins::job::{Job, JobStatus};
+        use std::collections::HashMap;
+
+        let conn = self.connection.lock().await?;
+        let mut jobs = Vec::new();
+
+        let mut statement = conn.prepare(
+            "SELECT id, plugin_name, user_id, guild_id, channel_id, thread_id, status, params, started_at
+             FROM plugin_jobs
+             WHERE status IN ('pending', 'running')
+             ORDER BY started_at ASC"
+        )?;
+
+        while let Ok(State::Row) = statement.next() {
+            let params_json: String = statement.read::<String, _>(7)?;
+            let params: HashMap<String, String> =
+                serde_json::from_str(&params_json).unwrap_or_default();
+            let guild_id: String = statement.read(3)?;
+            let thread_id: String = statement.read(5)?;
+            let status_str: String = statement.read(6)?;
+            let started_at_str: String = statement.read(8)?;
+
+            let status = status_str
+                .parse::<JobStatus>()
+                .unwrap_or(JobStatus::Pending);
+            let started_at = chrono::DateTime::parse_from_rfc3339(&started_at_str)
+                .map(|dt| dt.with_timezone(&chrono::Utc))
+                .unwrap_or_else(|_| chrono::Utc::now());
+
+            jobs.push(Job {
+                id: statement.read(0)?,
+                plugin_name: statement.read(1)?,
+                user_id: statement.read(2)?,
+                guild_id: if guild_id.is_empty() {
+                    None
+                } else {
+                    Some(guild_id)
+                },
+                channel_id: statement.read(4)?,
+                thread_id: if thread_id.is_empty() {
+                    None
+                } else {
+                    Some(thread_id)
+                },
+                status,
+                params,
+                started_at,
+                completed_at: None,
+                result: None,
+                error: None,
+                parent_playlist_id: None, // Recovery doesn't load parent - handled separately
+            });
+        }
+
+        Ok(jobs)
+    }
+
+    /// Cleanup old completed plugin jobs (keep last N days)
+    pub async fn cleanup_old_plugin_jobs(&self, days: i64) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "DELETE FROM plugin_jobs WHERE completed_at < datetime('now', ? || ' days')",
+        )?;
+        statement.bind((1, format!("-{days}").as_str()))?;
+        statement.next()?;
+        info!("Cleaned up plugin_jobs older than {days} days");
+        Ok(())
+    }
+
+    // Transcript Cache Methods
+
+    /// Store (or replace) the transcript produced for a video
+    pub async fn store_cached_transcript(
+        &self,
+        video_id: &str,
+        transcript: &str,
+        title: Option<&str>,
+        job_id: &str,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT OR REPLACE INTO transcript_cache (video_id, transcript, title, job_id)
+             VALUES (?, ?, ?, ?)",
+        )?;
+        statement.bind((1, video_id))?;
+        statement.bind((2, transcript))?;
+        statement.bind((3, title))?;
+        statement.bind((4, job_id))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Look up a cached transcript, counting the hit
+    pub async fn get_cached_transcript(&self, video_id: &str) -> Result<Option<CachedTranscript>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "UPDATE transcript_cache
+             SET hit_count = hit_count + 1, last_hit_at = CURRENT_TIMESTAMP
+             WHERE video_id = ?",
+        )?;
+        statement.bind((1, video_id))?;
+        statement.next()?;
+        if conn.change_count() == 0 {
+            return Ok(None);
+        }
+
+        let mut statement = conn.prepare(
+            "SELECT transcript, title, job_id, created_at, hit_count
+             FROM transcript_cache WHERE video_id = ?",
+        )?;
+        statement.bind((1, video_id))?;
+        match statement.next()? {
+            State::Row => Ok(Some(CachedTranscript {
+                video_id: video_id.to_string(),
+                transcript: statement.read::<String, _>(0)?,
+                title: statement.read::<Option<String>, _>(1)?,
+                job_id: statement.read::<String, _>(2)?,
+                created_at: statement.read::<String, _>(3)?,
+                hit_count: statement.read::<i64, _>(4)?,
+            })),
+            State::Done => Ok(None),
+        }
+    }
+
+    /// Which of these video IDs already have a cached transcript (without counting hits)
+    pub async fn get_archived_video_ids(&self, video_ids: &[String]) -> Result<HashSet<String>> {
+        let conn = self.connection.lock().await?;
+        let mut archived = HashSet::new();
+        for video_id in video_ids {
+            let mut statement =
+                conn.prepare("SELECT 1 FROM transcript_cache WHERE video_id = ?")?;
+            statement.bind((1, video_id.as_str()))?;
+            if let State::Row = statement.next()? {
+                archived.insert(video_id.clone());
+            }
+        }
+        Ok(archived)
+    }
+
+    /// Remember the transcript a job posted into a thread (replacing any earlier one)
+    pub async fn store_thread_transcript(
+        &self,
+        thread_id: &str,
+        job_id: &str,
+        title: Option<&str>,
+        transcript: &str,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT OR REPLACE INTO thread_transcripts (thread_id, job_id, title, transcript)
+             VALUES (?, ?, ?, ?)",
+        )?;
+        statement.bind((1, thread_id))?;
+        statement.bind((2, job_id))?;
+        statement.bind((3, title))?;
+        statement.bind((4, transcript))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// The transcript posted into a thread, if any
+    pub async fn get_thread_transcript(&self, thread_id: &str) -> Result<Option<ThreadTranscript>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT job_id, title, transcript FROM thread_transcripts WHERE thread_id = ?",
+        )?;
+        statement.bind((1, thread_id))?;
+        match statement.next()? {
+            State::Row => Ok(Some(ThreadTranscript {
+                thread_id: thread_id.to_string(),
+                job_id: statement.read::<String, _>(0)?,
+                title: statement.read::<Option<String>, _>(1)?,
+                transcript: statement.read::<String, _>(2)?,
+            })),
+            State::Done => Ok(None),
+        }
+    }
+
+    // Transcribe Subscription Methods
+
+    /// Save a new channel subscription, returning its ID
+    pub async fn create_transcribe_subscription(
+        &self,
+        subscription: &TranscribeSubscription,
+    ) -> Result<i64> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "INSERT INTO transcribe_subscriptions
+             (guild_id, channel_id, source_url, title, user_id, interval_minutes, max_per_check,
+              last_checked_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )?;
+        statement.bind((1, subscription.guild_id.as_str()))?;
+        statement.bind((2, subscription.channel_id.as_str()))?;
+        statement.bind((3, subscription.source_url.as_str()))?;
+        statement.bind((4, subscription.title.as_deref()))?;
+        statement.bind((5, subscription.user_id.as_str()))?;
+        statement.bind((6, subscription.interval_minutes as i64))?;
+        statement.bind((7, subscription.max_per_check as i64))?;
+        statement.bind((8, subscription.last_checked_at))?;
+        statement.next()?;
+
+        let mut stmt = conn.prepare("SELECT last_insert_rowid()")?;
+        stmt.next()?;
+        Ok(stmt.read::<i64, _>(0)?)
+    }
+
+    /// A guild's channel subscriptions, oldest first
+    pub async fn get_guild_transcribe_subscriptions(
+        &self,
+        guild_id: &str,
+    ) -> Result<Vec<TranscribeSubscription>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT id, guild_id, channel_id, source_url, title, user_id, interval_minutes,
+                    max_per_check, last_checked_at
+             FROM transcribe_subscriptions WHERE guild_id = ? ORDER BY id",
+        )?;
+        statement.bind((1, guild_id))?;
+        Self::read_transcribe_subscriptions(&mut statement)
+    }
+
+    /// Every channel subscription
+    pub async fn get_transcribe_subscriptions(&self) -> Result<Vec<TranscribeSubscription>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT id, guild_id, channel_id, source_url, title, user_id, interval_minutes,
+                    max_per_check, last_checked_at
+             FROM transcribe_subscriptions ORDER BY id",
+        )?;
+        Self::read_transcribe_subscriptions(&mut statement)
+    }
+
+    fn read_transcribe_subscriptions(
+        statement: &mut sqlite::Statement,
+    ) -> Result<Vec<TranscribeSubscription>> {
+        let mut subscriptions = Vec::new();
+        while let State::Row = statement.next()? {
+            subscriptions.push(TranscribeSubscription {
+                id: statement.read::<i64, _>(0)?,
+                guild_id: statement.read::<String, _>(1)?,
+                channel_id: statement.read::<String, _>(2)?,
+                source_url: statement.read::<String, _>(3)?,
+                title: statement.read::<Option<String>, _>(4)?,
+                user_id: statement.read::<String, _>(5)?,
+                interval_minutes: statement.read::<i64, _>(6)? as u32,
+                max_per_check: statement.read::<i64, _>(7)? as u32,
+                last_checked_at: statement.read::<Option<i64>, _>(8)?,
+            });
+        }
+        Ok(subscriptions)
+    }
+
+    /// Remove a guild's subscription and its seen uploads; false if it doesn't exist
+    pub async fn delete_transcribe_subscription(&self, guild_id: &str, id: i64) -> Result<bool> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("DELETE FROM transcribe_subscriptions WHERE id = ? AND guild_id = ?")?;
+        statement.bind((1, id))?;
+        statement.bind((2, guild_id))?;
+        statement.next()?;
+        if conn.change_count() == 0 {
+            return Ok(false);
+        }
+
+        let mut statement =
+            conn.prepare("DELETE FROM transcribe_subscription_videos WHERE subscription_id = ?")?;
+        statement.bind((1, id))?;
+        statement.next()?;
+        Ok(true)
+    }
+
+    /// Record when a subscription's channel was last polled
+    pub async fn mark_transcribe_subscription_checked(&self, id: i64, at: i64) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut statement =
+            conn.prepare("UPDATE transcribe_subscriptions SET last_checked_at = ? WHERE id = ?")?;
+        statement.bind((1, at))?;
+        statement.bind((2, id))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Mark uploads as handled so later polls skip them
+    pub async fn mark_subscription_videos_seen(
+        &self,
+        subscription_id: i64,
+        video_ids: &[String],
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        for video_id in video_ids {
+            let mut statement = conn.prepare(
+                "INSERT OR IGNORE INTO transcribe_subscription_videos (subscription_id, video_id)
+                 VALUES (?, ?)",
+            )?;
+            statement.bind((1, subscription_id))?;
+            statement.bind((2, video_id.as_str()))?;
+            statement.next()?;
+        }
+        Ok(())
+    }
+
+    /// Uploads a subscription has already handled
+    pub async fn get_subscription_seen_videos(
+        &self,
+        subscription_id: i64,
+    ) -> Result<HashSet<String>> {
+        let conn = self.connection.lock().await?;
+        let mut statement = conn.prepare(
+            "SELECT video_id FROM transcribe_subscription_videos WHERE subscription_id = ?",
+        )?;
+        statement.bind((1, subscription_id))?;
+        let mut seen = HashSet::new();
+        while let State::Row = statement.next()? {
+            seen.insert(statement.read::<String, _>(0)?);
+        }
+        Ok(seen)
+    }
+
+    // Playlist Job Methods
+
+    /// Create a new playlist job record
+    pub async fn create_playlist_job(
+        &self,
+        job: &crate::features::plugins::job::PlaylistJob,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+
+        let mut statement = conn.prepare(
+            "INSERT INTO playlist_jobs (
+                id, user_id, guild_id, channel_id, playlist_url, playlist_id,
+                playlist_title, total_videos, status, max_videos, started_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )?;
+        statement.bind((1, job.id.as_str()))?;
+        statement.bind((2, job.user_id.as_str()))?;
+        statement.bind((3, job.guild_id.as_deref().unwrap_or("")))?;
+        statement.bind((4, job.channel_id.as_str()))?;
+        statement.bind((5, job.playlist_url.as_str()))?;
+        statement.bind((6, job.playlist_id.as_str()))?;
+        statement.bind((7, job.playlist_title.as_deref().unwrap_or("")))?;
+        statement.bind((8, job.total_videos as i64))?;
+        statement.bind((9, job.status.to_string().as_str()))?;
+        statement.bind((10, job.max_videos.map(|v| v as i64).unwrap_or(-1)))?;
+        statement.bind((11, job.started_at.to_rfc3339().as_str()))?;
+        statement.next()?;
+
+        Ok(())
+    }
+
+    /// Update a playlist job record
+    pub async fn update_playlist_job(
+        &self,
+        job: &crate::features::plugins::job::PlaylistJob,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+
+        let completed_at = job.completed_at.map(|t| t.to_rfc3339()).unwrap_or_default();
+
+        let cancelled_at = job.cancelled_at.map(|t| t.to_rfc3339()).unwrap_or_default();
+
+        let mut statement = conn.prepare(
+            "UPDATE playlist_jobs SET
+                thread_id = ?,
+                playlist_title = ?,
+                completed_videos = ?,
+                failed_videos = ?,
+                skipped_videos = ?,
+                status = ?,
+                current_video_job_id = ?,
+                error = ?,
+                completed_at = CASE WHEN ? = '' THEN NULL ELSE ? END,
+                cancelled_at = CASE WHEN ? = '' THEN NULL ELSE ? END,
+                cancelled_by = ?
+             WHERE id = ?",
+        )?;
+        statement.bind((1, job.thread_id.as_deref().unwrap_or("")))?;
+        statement.bind((2, job.playlist_title.as_deref().unwrap_or("")))?;
+        statement.bind((3, job.completed_videos as i64))?;
+        statement.bind((4, job.failed_videos as i64))?;
+        statement.bind((5, job.skipped_videos as i64))?;
+        statement.bind((6, job.status.to_string().as_str()))?;
+        statement.bind((7, job.current_video_job_id.as_deref().unwrap_or("")))?;
+        statement.bind((8, job.error.as_deref().unwrap_or("")))?;
+        statement.bind((9, completed_at.as_str()))?;
+        statement.bind((10, completed_at.as_str()))?;
+        statement.bind((11, cancelled_at.as_str()))?;
+        statement.bind((12, cancelled_at.as_str()))?;
+        statement.bind((13, job.cancelled_by.as_deref().unwrap_or("")))?;
+        statement.bind((14, job.id.as_str()))?;
+        statement.next()?;
+
+        Ok(())
+    }
+
+    /// Get incomplete playlist jobs (for crash recovery / auto-resume)
+    pub async fn get_incomplete_playlist_jobs(
+        &self,
+    ) -> Result<Vec<crate::features::plugins::job::PlaylistJob>> {
+        use crate::features::plugins::job::{PlaylistJob, PlaylistJobStatus};
+
+        let conn = self.connection.lock().await?;
+        let mut jobs = Vec::new();
+
+        let mut statement = conn.prepare(
+            "SELECT id, user_id, guild_id, channel_id, thread_id, playlist_url, playlist_id,
+                    playlist_title, total_videos, completed_videos, failed_videos, skipped_videos,
+                    status, max_videos, current_video_job_id, error, started_at
+             FROM playlist_jobs
+             WHERE status IN ('pending', 'running', 'paused')
+             ORDER BY started_at ASC",
+        )?;
+
+        while let Ok(State::Row) = statement.next() {
+            let guild_id: String = statement.read(2)?;
+            let thread_id: String = statement.read(4)?;
+            let playlist_title: String = statement.read(7)?;
+            let status_str: String = statement.read(12)?;
+            let max_videos: i64 = statement.read(13)?;
+            let current_video_job_id: String = statement.read(14)?;
+            let error: String = statement.read(15)?;
+            let started_at_str: String = statement.read(16)?;
+
+            let status = status_str
+                .parse::<PlaylistJobStatus>()
+                .unwrap_or(PlaylistJobStatus::Pending);
+            let started_at = chrono::DateTime::parse_from_rfc3339(&started_at_str)
+                .map(|dt| dt.with_timezone(&chrono::Utc))
+                .unwrap_or_else(|_| chrono::Utc::now());
+
+            jobs.push(PlaylistJob {
+                id: statement.read(0)?,
+                user_id: statement.read(1)?,
+                guild_id: if guild_id.is_empty() {
+                    None
+                } else {
+                    Some(guild_id)
+                },
+                channel_id: statement.read(3)?,
+                thread_id: if thread_id.is_empty() {
+                    None
+                } else {
+                    Some(thread_id)
+                },
+                playlist_url: statement.read(5)?,
+                playlist_id: statement.read(6)?,
+                playlist_title: if playlist_title.is_empty() {
+                    None
+                } else {
+                    Some(playlist_title)
+                },
+                total_videos: statement.read::<i64, _>(8)? as u32,
+                completed_videos: statement.read::<i64, _>(9)? as u32,
+                failed_videos: statement.read::<i64, _>(10)? as u32,
+                skipped_videos: statement.read::<i64, _>(11)? as u32,
+                status,
+                max_videos: if max_videos < 0 {
+                    None
+                } else {
+                    Some(max_videos as u32)
+                },
+                current_video_job_id: if current_video_job_id.is_empty() {
+                    None
+                } else {
+                    Some(current_video_job_id)
+                },
+                error: if error.is_empty() { None } else { Some(error) },
+                started_at,
+                completed_at: None,
+                cancelled_at: None,
+                cancelled_by: None,
+            });
+        }
+
+        Ok(jobs)
+    }
+
+    /// Get active playlist jobs for a user
+    pub async fn get_user_active_playlist_jobs(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<crate::features::plugins::job::PlaylistJob>> {
+        use crate::features::plugins::job::{PlaylistJob, PlaylistJobStatus};
+
+        let conn = self.connection.lock().await?;
+        let mut jobs = Vec::new();
+
+        let mut statement = conn.prepare(
+            "SELECT id, user_id, guild_id, channel_id, thread_id, playlist_url, playlist_id,
+                    playlist_title, total_videos, completed_videos, failed_videos, skipped_videos,
+                    status, max_videos, current_video_job_id, error, started_at
+             FROM playlist_jobs
+             WHERE user_id = ? AND status IN ('pending', 'running', 'paused')
+             ORDER BY started_at DESC",
+        )?;
+        statement.bind((1, user_id))?;
+
+        while let Ok(State::Row) = statement.next() {
+            let guild_id: String = statement.read(2)?;
+            let thread_id: String = statement.read(4)?;
+            let playlist_title: String = statement.read(7)?;
+            let status_str: String = statement.read(12)?;
+            let max_videos: i64 = statement.read(13)?;
+            let current_video_job_id: String = statement.read(14)?;
+            let error: String = statement.read(15)?;
+            let started_at_str: String = statement.read(16)?;
+
+            let status = status_str
+                .parse::<PlaylistJobStatus>()
+                .unwrap_or(PlaylistJobStatus::Pending);
+            let started_at = chrono::DateTime::parse_from_rfc3339(&started_at_str)
+                .map(|dt| dt.with_timezone(&chrono::Utc))
+                .unwrap_or_else(|_| chrono::Utc::now());
+
+            jobs.push(PlaylistJob {
+                id: statement.read(0)?,
+                user_id: statement.read(1)?,
+                guild_id: if guild_id.is_empty() {
+                    None
+                } else {
+                    Some(guild_id)
+                },
+                channel_id: statement.read(3)?,
+                thread_id: if thread_id.is_empty() {
+                    None
+                } else {
+                    Some(thread_id)
+                },
+                playlist_url: statement.read(5)?,
+                playlist_id: statement.read(6)?,
+                playlist_title: if playlist_title.is_empty() {
+                    None
+                } else {
+                    Some(playlist_title)
+                },
+                total_videos: statement.read::<i64, _>(8)? as u32,
+                completed_videos: statement.read::<i64, _>(9)? as u32,
+                failed_videos: statement.read::<i64, _>(10)? as u32,
+                skipped_videos: statement.read::<i64, _>(11)? as u32,
+                status,
+                max_videos: if max_videos < 0 {
+                    None
+                } else {
+                    Some(max_videos as u32)
+                },
+                current_video_job_id: if current_video_job_id.is_empty() {
+                    None
+                } else {
+                    Some(current_video_job_id)
+                },
+                error: if error.is_empty() { None } else { Some(error) },
+                started_at,
+                completed_at: None,
+                cancelled_at: None,
+                cancelled_by: None,
+            });
+        }
+
+        Ok(jobs)
+    }
+
+    /// Get completed video job IDs for a playlist (for resume functionality)
+    pub async fn get_completed_video_job_ids(&self, playlist_job_id: &str) -> Result<Vec<String>> {
+        let conn = self.connection.lock().await?;
+        let mut ids = Vec::new();
+
+        let mut statement = conn.prepare(
+            "SELECT params FROM plugin_jobs
+             WHERE parent_playlist_id = ? AND status = 'completed'",
+        )?;
+        statement.bind((1, playlist_job_id))?;
+
+        while let Ok(State::Row) = statement.next() {
+            let params_json: String = statement.read(0)?;
+            if let Ok(params) =
+                serde_json::from_str::<std::collections::HashMap<String, String>>(&params_json)
+            {
+                if let Some(url) = params.get("url") {
+                    // Extract video ID from URL
+                    if let Some(vid) = url.split("v=").nth(1).and_then(|s| s.split('&').next()) {
+                        ids.push(vid.to_string());
+                    }
+                }
+            }
+        }
+
+        Ok(ids)
+    }
+
+    // ============================================================================
+    // TUI Analytics Methods
+    // ============================================================================
+
+    /// Get user list with aggregated stats
+    pub async fn get_user_list(&self, limit: u32) -> Result<Vec<UserListEntry>> {
+        let conn = self.connection.lock().await?;
+        let mut users = Vec::new();
+
+        // Get users with their total cost and activity from openai_usage_daily, with cached usernames
+        let mut stmt = conn.prepare(
+            "SELECT
+                u.user_id,
+                uc.username,
+                SUM(u.total_cost_usd) as total_cost,
+                SUM(u.total_tokens) as total_tokens,
+                SUM(u.request_count) as total_calls,
+                MAX(u.date) as last_activity
+             FROM openai_usage_daily u
+             LEFT JOIN user_cache uc ON u.user_id = uc.user_id
+             WHERE u.user_id != ''
+             GROUP BY u.user_id
+             ORDER BY total_cost DESC
+             LIMIT ?",
+        )?;
+        stmt.bind((1, limit as i64))?;
+
+        while let Ok(State::Row) = stmt.next() {
+            users.push(UserListEntry {
+                user_id: stmt.read::<String, _>(0)?,
+                username: stmt.read::<Option<String>, _>(1)?,
+                total_cost: stmt.read::<f64, _>(2)?,
+                total_tokens: stmt.read::<i64, _>(3)? as u64,
+                total_calls: stmt.read::<i64, _>(4)? as u64,
+                dm_session_count: 0, // Enriched below
+                last_activity: stmt.read::<Option<String>, _>(5)?,
+            });
+        }
+
+        // Enrich with DM session counts
+        for user in &mut users {
+            let mut session_stmt =
+                conn.prepare("SELECT COUNT(*) FROM dm_sessions WHERE user_id = ?")?;
+            session_stmt.bind((1, user.user_id.as_str()))?;
+            if let Ok(State::Row) = session_stmt.next() {
+                user.dm_session_count = session_stmt.read::<i64, _>(0)? as u64;
+            }
+        }
+
+        Ok(users)
+    }
+
+    /// Get detailed user statistics
+    pub async fn get_user_details(&self, user_id: &str) -> Result<UserDetails> {
+        let conn = self.connection.lock().await?;
+
+        // Get aggregated usage stats
+        let mut usage_stmt = conn.prepare(
+            "SELECT
+                SUM(total_cost_usd) as total_cost,
+                SUM(total_tokens) as total_tokens,
+                SUM(request_count) as total_calls,
+                MIN(date) as first_seen,
+                MAX(date) as last_activity
+             FROM openai_usage_daily
+             WHERE user_id = ?",
+        )?;
+        usage_stmt.bind((1, user_id))?;
+
+        let (total_cost, total_tokens, total_calls, first_seen, last_activity) =
+            if let Ok(State::Row) = usage_stmt.next() {
+                (
+                    usage_stmt.read::<f64, _>(0).unwrap_or(0.0),
+                    usage_stmt.read::<i64, _>(1).unwrap_or(0) as u64,
+                    usage_stmt.read::<i64, _>(2).unwrap_or(0) as u64,
+                    usage_stmt.read::<Option<String>, _>(3)?,
+                    usage_stmt.read::<Option<String>, _>(4)?,
+                )
+            } else {
+                (0.0, 0, 0, None, None)
+            };
+
+        // Get DM session aggregate stats
+        drop(usage_stmt);
+        let mut dm_agg_stmt = conn.prepare(
+            "SELECT
+                COUNT(*) as session_count,
+                SUM(message_count) as total_messages
+             FROM dm_sessions
+             WHERE user_id = ?",
+        )?;
+        dm_agg_stmt.bind((1, user_id))?;
+
+        let (dm_session_count, message_count) = if let Ok(State::Row) = dm_agg_stmt.next() {
+            (
+                dm_agg_stmt.read::<i64, _>(0).unwrap_or(0) as u64,
+                dm_agg_stmt.read::<i64, _>(1).unwrap_or(0) as u64,
+            )
+        } else {
+            (0, 0)
+        };
+
+        // Get DM session metrics aggregates
+        drop(dm_agg_stmt);
+        let mut metrics_stmt = conn.prepare(
+            "SELECT
+                SUM(sm.chat_calls) as chat_calls,
+                SUM(sm.whisper_calls) as whisper_calls,
+                SUM(sm.dalle_calls) as dalle_calls
+             FROM dm_session_metrics sm
+             JOIN dm_sessions s ON sm.session_id = s.session_id
+             WHERE s.user_id = ?",
+        )?;
+        metrics_stmt.bind((1, user_id))?;
+
+        let (chat_calls, whisper_calls, dalle_calls) = if let Ok(State::Row) = metrics_stmt.next() {
+            (
+                metrics_stmt.read::<i64, _>(0).unwrap_or(0) as u64,
+                metrics_stmt.read::<i64, _>(1).unwrap_or(0) as u64,
+                metrics_stmt.read::<i64, _>(2).unwrap_or(0) as u64,
+            )
+        } else {
+            (0, 0, 0)
+        };
+
+        // Get favorite persona
+        drop(metrics_stmt);
+        let mut persona_stmt = conn.prepare(
+            "SELECT persona, COUNT(*) as cnt
+             FROM conversation_history
+             WHERE user_id = ? AND persona IS NOT NULL AND persona != ''
+             GROUP BY persona
+             ORDER BY cnt DESC
+             LIMIT 1",
+        )?;
+        persona_stmt.bind((1, user_id))?;
+
+        let favorite_persona = if let Ok(State::Row) = persona_stmt.next() {
+            persona_stmt.read::<Option<String>, _>(0)?
+        } else {
+            None
+        };
+
+        // Get cached username
+        drop(persona_stmt);
+        let mut username_stmt =
+            conn.prepare("SELECT username FROM user_cache WHERE user_id = ?")?;
+        username_stmt.bind((1, user_id))?;
+        let username = if let Ok(State::Row) = username_stmt.next() {
+            username_stmt.read::<Option<String>, _>(0)?
+        } else {
+            None
+        };
+
+        Ok(UserDetails {
+            user_id: user_id.to_string(),
+            username,
+            total_cost,
+            total_tokens,
+            total_calls,
+            dm_session_count,
+            message_count,
+            chat_calls,
+            whisper_calls,
+            dalle_calls,
+            first_seen,
+            last_activity,
+            favorite_persona,
+        })
+    }
+
+    /// Get DM sessions for a user
+    pub async fn get_user_dm_sessions(
+        &self,
+        user_id: &str,
+        limit: u32,
+    ) -> Result<Vec<DmSessionEntry>> {
+        let conn = self.connection.lock().await?;
+
+        let mut stmt = conn.prepare(
+            "SELECT
+                s.session_id,
+                s.started_at,
+                s.ended_at,
+                s.message_count,
+                COALESCE(m.total_api_cost_usd, 0) as api_cost,
+                COALESCE(m.total_tokens, 0) as total_tokens
+             FROM dm_sessions s
+             LEFT JOIN dm_session_metrics m ON s.session_id = m.session_id
+             WHERE s.user_id = ?
+             ORDER BY s.started_at DESC
+             LIMIT ?",
+        )?;
+        stmt.bind((1, user_id))?;
+        stmt.bind((2, limit as i64))?;
+
+        let mut sessions = Vec::new();
+        while let Ok(State::Row) = stmt.next() {
+            sessions.push(DmSessionEntry {
+                session_id: stmt.read::<String, _>(0)?,
+                started_at: stmt.read::<String, _>(1)?,
+                ended_at: stmt.read::<Option<String>, _>(2)?,
+                message_count: stmt.read::<i64, _>(3)? as u32,
+                api_cost: stmt.read::<f64, _>(4)?,
+                total_tokens: stmt.read::<i64, _>(5)? as u64,
+            });
+        }
+
+        Ok(sessions)
+    }
+
+    /// Get recent errors from error_logs
+    pub async fn get_recent_errors(&self, limit: u32) -> Result<Vec<ErrorLogEntry>> {
+        let conn = self.connection.lock().await?;
+
+        let mut stmt = conn.prepare(
+            "SELECT id, error_type, error_message, stack_trace, user_id, channel_id, command, timestamp
+             FROM error_logs
+             ORDER BY timestamp DESC
+             LIMIT ?"
+        )?;
+        stmt.bind((1, limit as i64))?;
+
+        let mut errors = Vec::new();
+        while let Ok(State::Row) = stmt.next() {
+            errors.push(ErrorLogEntry {
+                id: stmt.read::<i64, _>(0)?,
+                error_type: stmt.read::<String, _>(1)?,
+                error_message: stmt.read::<String, _>(2)?,
+                stack_trace: stmt.read::<Option<String>, _>(3)?,
+                user_id: stmt.read::<Option<String>, _>(4)?,
+                channel_id: stmt.read::<Option<String>, _>(5)?,
+                command: stmt.read::<Option<String>, _>(6)?,
+                timestamp: stmt.read::<String, _>(7)?,
+            });
+        }
+
+        Ok(errors)
+    }
+
+    /// Get historical performance metrics at the tier that fits `hours`
+    pub async fn get_historical_metrics(
+        &self,
+        metric_type: &str,
+        hours: u32,
+    ) -> Result<Vec<(i64, f64)>> {
+        self.get_metrics_history(metric_type, hours as i64).await
+    }
+
+    /// OpenAI spend so far today (UTC), including rows not yet rolled up
+    pub async fn get_spend_today(&self) -> Result<f64> {
+        let conn = self.connection.lock().await?;
+        let mut stmt = conn.prepare(
+            "SELECT COALESCE(SUM(total_cost_usd), 0.0)
+             FROM openai_usage_rollup_live
+             WHERE date = date('now')",
+        )?;
+        stmt.next()?;
+        Ok(stmt.read::<f64, _>(0)?)
+    }
+
+    /// OpenAI spend recorded in a guild so far today (UTC)
+    pub async fn get_guild_spend_today(&self, guild_id: &str) -> Result<f64> {
+        let conn = self.connection.lock().await?;
+        let mut stmt = conn.prepare(
+            "SELECT COALESCE(SUM(total_cost_usd), 0.0)
+             FROM openai_usage_daily
+             WHERE guild_id = ? AND date = date('now')",
+        )?;
+        stmt.bind((1, guild_id))?;
+        stmt.next()?;
+        Ok(stmt.read::<f64, _>(0)?)
+    }
+
+    /// Get daily cost trend for sparkline
+    pub async fn get_daily_cost_trend(&self, days: u32) -> Result<Vec<(String, f64)>> {
+        let conn = self.connection.lock().await?;
+
+        let mut stmt = conn.prepare(
+            "SELECT date, SUM(total_cost_usd) as cost
+             FROM openai_usage_daily
+             WHERE date >= date('now', ? || ' days')
+             GROUP BY date
+             ORDER BY date ASC",
+        )?;
+        stmt.bind((1, format!("-{days}").as_str()))?;
+
+        let mut trend = Vec::new();
+        while let Ok(State::Row) = stmt.next() {
+            let date = stmt.read::<String, _>(0)?;
+            let cost = stmt.read::<f64, _>(1)?;
+            trend.push((date, cost));
+        }
+
+        Ok(trend)
+    }
+
+    /// Log a performance metric
+    pub async fn log_performance_metric(
+        &self,
+        metric_type: &str,
+        value: f64,
+        unit: Option<&str>,
+        metadata: Option<&str>,
+    ) -> Result<()> {
+        let conn = self.connection.lock().await?;
+        let mut stmt = conn.prepare(
+            "INSERT INTO performance_metrics (metric_type, value, unit, metadata) VALUES (?, ?, ?, ?)"
+        )?;
+        stmt.bind((1, metric_type))?;
+        stmt.bind((2, value))?;
+        stmt.bind((3, unit.unwrap_or("")))?;
+        stmt.bind((4, metadata.unwrap_or("")))?;
+        stmt.next()?;
+        Ok(())
+    }
+
+    /// Get channel message history from conversation_history
+    pub async fn get_channel_messages(
+        &self,
+        channel_id: &str,
+        limit: u32,
+    ) -> Result<Vec<ConversationMessage>> {
+        let conn = self.connection.lock().await?;
+
+        let mut stmt = conn.prepare(
+            "SELECT user_id, role, content, persona, timestamp
+             FROM conversation_history
+             WHERE channel_id = ?
+             ORDER BY timestamp DESC
+             LIMIT ?",
+        )?;
+        stmt.bind((1, channel_id))?;
+        stmt.bind((2, limit as i64))?;
+
+        let mut messages = Vec::new();
+        while let Ok(State::Row) = stmt.next() {
+            messages.push(ConversationMessage {
+                user_id: stmt.read::<String, _>(0)?,
+                role: stmt.read::<String, _>(1)?,
+                content: stmt.read::<String, _>(2)?,
+                persona: stmt.read::<Option<String>, _>(3)?,
+                timestamp: stmt.read::<String, _>(4)?,
+            });
+        }
+
+        // Reverse to get chronological order
+        messages.reverse();
+        Ok(messages)
+    }
+
+    /// One member's exchange with the bot in a channel, oldest first
+    pub async fn get_user_channel_messages(
+        &self,
+        user_id: &str,
+        channel_id: &str,
+        limit: u32,
+    ) -> Result<Vec<ConversationMessage>> {
+        let conn = self.connection.lock().await?;
+
+        let mut stmt = conn.prepare(
+            "SELECT user_id, role, content, persona, timestamp
+             FROM conversation_history
+             WHERE user_id = ? AND channel_id = ?
+             ORDER BY timestamp DESC, id DESC
+             LIMIT ?",
+        )?;
+        stmt.bind((1, user_id))?;
+        stmt.bind((2, channel_id))?;
+        stmt.bind((3, limit as i64))?;
+
+        let mut messages = Vec::new();
+        while let Ok(State::Row) = stmt.next() {
+            messages.push(ConversationMessage {
+                user_id: stmt.read::<String, _>(0)?,
+                role: stmt.read::<String, _>(1)?,
+                content: stmt.read::<String, _>(2)?,
+                persona: stmt.read::<Option<String>, _>(3)?,
+                timestamp: stmt.read::<String, _>(4)?,
+            });
+        }
+
+        messages.reverse();
+        Ok(messages)
+    }
+
+    /// Get channels with conversation history, optionally filtered by guild
+    /// Returns channels from channel_settings with message counts from conversation_history
+    pub async fn get_channels_with_history(
+        &self,
+        guild_id: Option<&str>,
+    ) -> Result<Vec<ChannelHistoryEntry>> {
+        let conn = self.connection.lock().await?;
+
+        // Query channels that have settings OR have conversation history
+        // The channel_settings table has guild_id, so we can filter by guild
+        let query = if guild_id.is_some() {
+            "SELECT
+                cs.channel_id,
+                cs.guild_id,
+                COUNT(ch.id) as message_count,
+                MAX(ch.timestamp) as last_activity
+             FROM channel_settings cs
+             LEFT JOIN conversation_history ch ON cs.channel_id = ch.channel_id
+             WHERE cs.guild_id = ?
+             GROUP BY cs.guild_id, cs.channel_id
+             ORDER BY last_activity DESC NULLS LAST"
+        } else {
+            // Get all channels with history (for browsing all)
+            "SELECT
+                COALESCE(cs.channel_id, ch.channel_id) as channel_id,
+                cs.guild_id,
+                COUNT(ch.id) as message_count,
+                MAX(ch.timestamp) as last_activity
+             FROM (
+                 SELECT DISTINCT channel_id FROM conversation_history
+             ) ch_ids
+             LEFT JOIN channel_settings cs ON ch_ids.channel_id = cs.channel_id
+             LEFT JOIN conversation_history ch ON ch_ids.channel_id = ch.channel_id
+             GROUP BY ch_ids.channel_id, cs.guild_id
+             ORDER BY last_activity DESC NULLS LAST"
+        };
+
+        let mut stmt = conn.prepare(query)?;
+
+        if let Some(gid) = guild_id {
+            stmt.bind((1, gid))?;
+        }
+
+        let mut entries = Vec::new();
+        while let Ok(State::Row) = stmt.next() {
+            entries.push(ChannelHistoryEntry {
+                channel_id: stmt.read::<String, _>(0)?,
+                guild_id: stmt.read::<Option<String>, _>(1)?,
+                message_count: stmt.read::<i64, _>(2)?,
+                last_activity: stmt.read::<Option<String>, _>(3)?,
+            });
+        }
+
+        Ok(entries)
+    }
+}
+
+/// User list entry for TUI
+#[derive(Debug, Clone)]
+pub struct UserListEntry {
+    pub user_id: String,
+    pub username: Option<String>,
+    pub total_cost: f64,
+    pub total_tokens: u64,
+    pub total_calls: u64,
+    pub dm_session_count: u64,
+    pub last_activity: Option<String>,
+}
+
+/// Detailed user info for TUI
+#[derive(Debug, Clone)]
+pub struct UserDetails {
+    pub user_id: String,
+    pub username: Option<String>,
+    pub total_cost: f64,
+    pub total_tokens: u64,
+    pub total_calls: u64,
+    pub dm_session_count: u64,
+    pub message_count: u64,
+    pub chat_calls: u64,
+    pub whisper_calls: u64,
+    pub dalle_calls: u64,
+    pub first_seen: Option<String>,
+    pub last_activity: Option<String>,
+    pub favorite_persona: Option<String>,
+}
+
+/// DM session entry for TUI
+#[derive(Debug, Clone)]
+pub struct DmSessionEntry {
+    pub session_id: String,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    pub message_count: u32,
+    pub api_cost: f64,
+    pub total_tokens: u64,
+}
+
+/// Error log entry for TUI
+#[derive(Debug, Clone)]
+pub struct ErrorLogEntry {
+    pub id: i64,
+    pub error_type: String,
+    pub error_message: String,
+    pub stack_trace: Option<String>,
+    pub user_id: Option<String>,
+    pub channel_id: Option<String>,
+    pub command: Option<String>,
+    pub timestamp: String,
+}
+
+/// A conversation message queued for batched storage
+#[derive(Debug, Clone)]
+pub struct StoredMessage {
+    pub guild_id: Option<String>,
+    pub user_id: String,
+    pub channel_id: String,
+    pub role: String,
+    pub content: String,
+    pub persona: Option<String>,
+}
+
+/// A thread seeded with a copy of another conversation by /fork
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversationFork {
+    pub thread_id: String,
+    pub source_channel_id: String,
+    pub user_id: String,
+    pub message_count: i64,
+}
+
+/// A stored message matching a full-text search
+#[derive(Debug, Clone)]
+pub struct MessageSearchResult {
+    pub id: i64,
+    pub guild_id: Option<String>,
+    pub channel_id: String,
+    pub user_id: String,
+    /// Cached display name, if the user has been seen
+    pub username: Option<String>,
+    pub role: String,
+    /// Matching excerpt with hits wrapped in `**`
+    pub snippet: String,
+    pub timestamp: String,
+}
+
+/// Convert free-form user input into a safe FTS5 MATCH expression
+///
+/// Each word becomes a quoted phrase (so operators and punctuation are
+/// literal) and all words must match. A trailing `*` keeps prefix matching.
+pub fn fts_match_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .filter_map(|word| {
+            let prefix = word.len() > 1 && word.ends_with('*');
+            let term: String = word
+                .trim_end_matches('*')
+                .chars()
+                .filter(|c| *c != '"')
+                .collect();
+            if term.is_empty() {
+                None
+            } else if prefix {
+                Some(format!("\"{term}\"*"))
+            } else {
+                Some(format!("\"{term}\""))
+            }
+        })
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// Conversation message for TUI
+#[derive(Debug, Clone)]
+pub struct ConversationMessage {
+    pub user_id: String,
+    pub role: String,
+    pub content: String,
+    pub persona: Option<String>,
+    pub timestamp: String,
+}
 
-        Ok(sessions)
-    }
+/// Channel with conversation history entry for TUI
+#[derive(Debug, Clone)]
+pub struct ChannelHistoryEntry {
+    pub channel_id: String,
+    pub guild_id: Option<String>,
+    pub message_count: i64,
+    pub last_activity: Option<String>,
+}
 
-    /// Cleanup old DM events (keep last N days)
-    pub async fn cleanup_old_dm_events(&self, days: i64) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement =
-            conn.prepare("DELETE FROM dm_events WHERE timestamp < datetime('now', ? || ' days')")?;
-        statement.bind((1, format!("-{days}").as_str()))?;
-        statement.next()?;
-        info!("Cleaned up dm_events older than {days} days");
-        Ok(())
-    }
+/// A DM session row without an end time
+#[derive(Debug, Clone)]
+pub struct OpenDmSession {
+    pub session_id: String,
+    pub user_id: String,
+    pub channel_id: String,
+    pub started_at: String,
+    pub last_activity_at: String,
+    pub message_count: i64,
+    pub user_message_count: i64,
+    pub bot_message_count: i64,
+    pub total_user_chars: i64,
+    pub total_bot_chars: i64,
+    pub avg_response_time_ms: i64,
+}
 
-    // Plugin Job Methods
+/// DM statistics for a user
+#[derive(Debug, Clone)]
+pub struct DmStats {
+    pub session_count: i64,
+    pub total_messages: i64,
+    pub user_messages: i64,
+    pub bot_messages: i64,
+    pub avg_response_time_ms: i64,
+    pub avg_session_duration_min: f64,
+    pub api_calls: i64,
+    pub total_tokens: i64,
+    pub total_cost_usd: f64,
+    pub chat_calls: i64,
+    pub whisper_calls: i64,
+    pub dalle_calls: i64,
+    pub audio_transcriptions: i64,
+    pub slash_commands_used: i64,
+}
 
-    /// Create a new plugin job record
-    pub async fn create_plugin_job(&self, job: &crate::features::plugins::job::Job) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let params_json = serde_json::to_string(&job.params)?;
+/// Session information
+#[derive(Debug, Clone)]
+pub struct SessionInfo {
+    pub session_id: String,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    pub message_count: i64,
+    pub avg_response_time_ms: i64,
+}
 
-        let mut statement = conn.prepare(
-            "INSERT INTO plugin_jobs (id, plugin_name, user_id, guild_id, channel_id, status, params, started_at)
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
-        )?;
-        statement.bind((1, job.id.as_str()))?;
-        statement.bind((2, job.plugin_name.as_str()))?;
-        statement.bind((3, job.user_id.as_str()))?;
-        statement.bind((4, job.guild_id.as_deref().unwrap_or("")))?;
-        statement.bind((5, job.channel_id.as_str()))?;
-        statement.bind((6, job.status.to_string().as_str()))?;
-        statement.bind((7, params_json.as_str()))?;
-        statement.bind((8, job.started_at.to_rfc3339().as_str()))?;
-        statement.next()?;
+/// One row of an image cost breakdown (keyed by channel, user, or prompt hash)
+#[derive(Debug, Clone, Default)]
+pub struct ImageCostEntry {
+    pub key: String,
+    pub requests: i64,
+    pub images: i64,
+    pub cost: f64,
+}
 
-        Ok(())
-    }
+/// DALL-E spend for a guild over a period, grouped several ways
+#[derive(Debug, Clone, Default)]
+pub struct ImageCostBreakdown {
+    pub days: i64,
+    pub total_images: i64,
+    pub total_cost: f64,
+    pub by_channel: Vec<ImageCostEntry>,
+    pub by_user: Vec<ImageCostEntry>,
+    pub top_prompts: Vec<ImageCostEntry>,
+}
 
-    /// Update a plugin job record
-    pub async fn update_plugin_job(&self, job: &crate::features::plugins::job::Job) -> Result<()> {
-        let conn = self.connection.lock().await;
+/// A guild's activity over a period, as aggregated for the weekly report
+#[derive(Debug, Clone, Default)]
+pub struct ServerActivity {
+    pub days: i64,
+    pub messages: i64,
+    /// Messages in the period of the same length before this one
+    pub previous_messages: i64,
+    pub active_members: i64,
+    /// (channel_id, messages), busiest first
+    pub top_channels: Vec<(String, i64)>,
+    pub conflicts: i64,
+    pub mediations: i64,
+    pub bot_requests: i64,
+    /// (cost bucket, requests), most used first
+    pub top_features: Vec<(String, i64)>,
+    pub cost_usd: f64,
+}
 
-        let completed_at = job.completed_at.map(|t| t.to_rfc3339()).unwrap_or_default();
+/// A channel's average sentiment for one hour
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SentimentPoint {
+    /// Unix timestamp of the start of the hour
+    pub hour: i64,
+    pub messages: i64,
+    /// Mean message score, -1.0 (hostile) to 1.0 (friendly)
+    pub score: f64,
+}
 
-        let result_preview = job
-            .result
-            .as_ref()
-            .map(|r| r.chars().take(1000).collect::<String>())
-            .unwrap_or_default();
+/// A transcript stored by an earlier job for the same video
+#[derive(Debug, Clone)]
+pub struct CachedTranscript {
+    pub video_id: String,
+    pub transcript: String,
+    pub title: Option<String>,
+    /// Job that produced the transcript
+    pub job_id: String,
+    pub created_at: String,
+    /// Times the cache has served this transcript (including this lookup)
+    pub hit_count: i64,
+}
 
-        let error = job.error.as_ref().cloned().unwrap_or_default();
+/// A transcript a plugin job posted into a thread
+#[derive(Debug, Clone)]
+pub struct ThreadTranscript {
+    pub thread_id: String,
+    pub job_id: String,
+    pub title: Option<String>,
+    pub transcript: String,
+}
 
-        let thread_id = job.thread_id.as_deref().unwrap_or("");
+/// A YouTube channel whose new uploads are transcribed into a Discord channel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscribeSubscription {
+    pub id: i64,
+    pub guild_id: String,
+    /// Channel the transcript threads are created in
+    pub channel_id: String,
+    /// The YouTube channel's uploads tab
+    pub source_url: String,
+    pub title: Option<String>,
+    /// Member who subscribed; jobs run on their behalf
+    pub user_id: String,
+    pub interval_minutes: u32,
+    /// Most new uploads transcribed per poll
+    pub max_per_check: u32,
+    /// Unix time of the latest poll
+    pub last_checked_at: Option<i64>,
+}
 
-        let mut statement = conn.prepare(
-            "UPDATE plugin_jobs SET
-                status = ?,
-                thread_id = ?,
-                result = ?,
-                error = ?,
-                completed_at = CASE WHEN ? = '' THEN NULL ELSE ? END
-             WHERE id = ?",
-        )?;
-        statement.bind((1, job.status.to_string().as_str()))?;
-        statement.bind((2, thread_id))?;
-        statement.bind((3, result_preview.as_str()))?;
-        statement.bind((4, error.as_str()))?;
-        statement.bind((5, completed_at.as_str()))?;
-        statement.bind((6, completed_at.as_str()))?;
-        statement.bind((7, job.id.as_str()))?;
-        statement.next()?;
+impl ImageCostBreakdown {
+    /// Average images generated per day over the period
+    pub fn avg_images_per_day(&self) -> f64 {
+        if self.days <= 0 {
+            return 0.0;
+        }
+        self.total_images as f64 / self.days as f64
+    }
+}
 
-        Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::plugins::job::{Job, JobStatus};
+    use chrono::Utc;
+    use std::collections::HashMap;
+
+    fn temp_db_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("persona-{name}-{}.db", uuid::Uuid::new_v4()))
+            .to_string_lossy()
+            .to_string()
     }
 
-    /// Get incomplete plugin jobs (for crash recovery)
-    pub async fn get_incomplete_plugin_jobs(
-        &self,
-    ) -> Result<Vec<crate::features::plugins::job::Job>> {
-        use crate::features::plugins::job::{Job, JobStatus};
-        use std::collections::HashMap;
+    fn cleanup(path: &str) {
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{path}{suffix}"));
+        }
+    }
 
-        let conn = self.connection.lock().await;
-        let mut jobs = Vec::new();
+    async fn raw_execute(db: &Database, sql: &str) {
+        let conn = db.connection.lock().await.unwrap();
+        conn.execute(sql).unwrap();
+    }
 
-        let mut statement = conn.prepare(
-            "SELECT id, plugin_name, user_id, guild_id, channel_id, thread_id, status, params, started_at
-             FROM plugin_jobs
-             WHERE status IN ('pending', 'running')
-             ORDER BY started_at ASC"
-        )?;
+    #[tokio::test]
+    async fn test_component_state_round_trip_and_expiry() {
+        let db = Database::new(":memory:").await.unwrap();
+        let past = Utc::now().timestamp() - 10;
+
+        db.save_component_state("debate", "1", "{\"a\":1}", None)
+            .await
+            .unwrap();
+        db.save_component_state("council", "2", "{}", Some(past))
+            .await
+            .unwrap();
+        assert_eq!(
+            db.get_component_state("debate", "1").await.unwrap(),
+            Some("{\"a\":1}".to_string())
+        );
+        assert_eq!(db.get_component_state("council", "2").await.unwrap(), None);
+
+        db.save_component_state("debate", "1", "{\"a\":2}", None)
+            .await
+            .unwrap();
+        assert_eq!(
+            db.get_component_state("debate", "1").await.unwrap(),
+            Some("{\"a\":2}".to_string())
+        );
 
-        while let Ok(State::Row) = statement.next() {
-            let params_json: String = statement.read::<String, _>(7)?;
-            let params: HashMap<String, String> =
-                serde_json::from_str(&params_json).unwrap_or_default();
-            let guild_id: String = statement.read(3)?;
-            let thread_id: String = statement.read(5)?;
-            let status_str: String = statement.read(6)?;
-            let started_at_str: String = statement.read(8)?;
+        assert_eq!(
+            db.list_component_states("debate").await.unwrap(),
+            vec![("1".to_string(), "{\"a\":2}".to_string())]
+        );
+        assert!(db.list_component_states("council").await.unwrap().is_empty());
 
-            let status = status_str
-                .parse::<JobStatus>()
-                .unwrap_or(JobStatus::Pending);
-            let started_at = chrono::DateTime::parse_from_rfc3339(&started_at_str)
-                .map(|dt| dt.with_timezone(&chrono::Utc))
-                .unwrap_or_else(|_| chrono::Utc::now());
+        assert_eq!(db.purge_expired_component_state().await.unwrap(), 1);
+        db.delete_component_state("debate", "1").await.unwrap();
+        assert_eq!(db.get_component_state("debate", "1").await.unwrap(), None);
+    }
 
-            jobs.push(Job {
-                id: statement.read(0)?,
-                plugin_name: statement.read(1)?,
-                user_id: statement.read(2)?,
-                guild_id: if guild_id.is_empty() {
-                    None
-                } else {
-                    Some(guild_id)
-                },
-                channel_id: statement.read(4)?,
-                thread_id: if thread_id.is_empty() {
-                    None
-                } else {
-                    Some(thread_id)
-                },
-                status,
-                params,
-                started_at,
-                completed_at: None,
-                result: None,
-                error: None,
-                parent_playlist_id: None, // Recovery doesn't load parent - handled separately
-            });
-        }
+    #[tokio::test]
+    async fn test_guild_setting_cached_and_invalidated_on_write() {
+        let db = Database::new(":memory:").await.unwrap();
+        db.set_guild_setting("g1", "mention_responses", "enabled")
+            .await
+            .unwrap();
+        assert_eq!(
+            db.get_guild_setting("g1", "mention_responses").await.unwrap(),
+            Some("enabled".to_string())
+        );
 
-        Ok(jobs)
+        // Out-of-band edits are hidden until the entry expires
+        raw_execute(
+            &db,
+            "UPDATE guild_settings SET setting_value = 'disabled' WHERE guild_id = 'g1'",
+        )
+        .await;
+        assert_eq!(
+            db.get_guild_setting("g1", "mention_responses").await.unwrap(),
+            Some("enabled".to_string())
+        );
+
+        // Writes through Database invalidate immediately
+        db.set_guild_setting("g1", "mention_responses", "disabled")
+            .await
+            .unwrap();
+        assert_eq!(
+            db.get_guild_setting("g1", "mention_responses").await.unwrap(),
+            Some("disabled".to_string())
+        );
     }
 
-    /// Cleanup old completed plugin jobs (keep last N days)
-    pub async fn cleanup_old_plugin_jobs(&self, days: i64) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut statement = conn.prepare(
-            "DELETE FROM plugin_jobs WHERE completed_at < datetime('now', ? || ' days')",
-        )?;
-        statement.bind((1, format!("-{days}").as_str()))?;
-        statement.next()?;
-        info!("Cleaned up plugin_jobs older than {days} days");
-        Ok(())
+    #[tokio::test]
+    async fn test_zero_ttl_disables_settings_cache() {
+        let db = Database::new(":memory:")
+            .await
+            .unwrap()
+            .with_settings_cache_ttl(Duration::ZERO);
+        assert!(db
+            .is_feature_enabled("reminders", None, Some("g1"))
+            .await
+            .unwrap());
+
+        raw_execute(
+            &db,
+            "INSERT INTO feature_flags (feature_name, enabled, user_id, guild_id)
+             VALUES ('reminders', 0, '', 'g1')",
+        )
+        .await;
+        assert!(!db
+            .is_feature_enabled("reminders", None, Some("g1"))
+            .await
+            .unwrap());
     }
 
-    // Playlist Job Methods
+    #[tokio::test]
+    async fn test_guild_default_verbosity_invalidates_channels() {
+        let db = Database::new(":memory:").await.unwrap();
+        assert_eq!(db.get_channel_verbosity("g1", "c1").await.unwrap(), "concise");
 
-    /// Create a new playlist job record
-    pub async fn create_playlist_job(
-        &self,
-        job: &crate::features::plugins::job::PlaylistJob,
-    ) -> Result<()> {
-        let conn = self.connection.lock().await;
+        db.set_guild_setting("g1", "default_verbosity", "detailed")
+            .await
+            .unwrap();
+        assert_eq!(db.get_channel_verbosity("g1", "c1").await.unwrap(), "detailed");
 
-        let mut statement = conn.prepare(
-            "INSERT INTO playlist_jobs (
-                id, user_id, guild_id, channel_id, playlist_url, playlist_id,
-                playlist_title, total_videos, status, max_videos, started_at
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
-        )?;
-        statement.bind((1, job.id.as_str()))?;
-        statement.bind((2, job.user_id.as_str()))?;
-        statement.bind((3, job.guild_id.as_deref().unwrap_or("")))?;
-        statement.bind((4, job.channel_id.as_str()))?;
-        statement.bind((5, job.playlist_url.as_str()))?;
-        statement.bind((6, job.playlist_id.as_str()))?;
-        statement.bind((7, job.playlist_title.as_deref().unwrap_or("")))?;
-        statement.bind((8, job.total_videos as i64))?;
-        statement.bind((9, job.status.to_string().as_str()))?;
-        statement.bind((10, job.max_videos.map(|v| v as i64).unwrap_or(-1)))?;
-        statement.bind((11, job.started_at.to_rfc3339().as_str()))?;
-        statement.next()?;
+        db.set_channel_verbosity("g1", "c1", "normal").await.unwrap();
+        assert_eq!(db.get_channel_verbosity("g1", "c1").await.unwrap(), "normal");
+    }
 
-        Ok(())
+    #[test]
+    fn test_fts_match_query_quotes_terms() {
+        assert_eq!(fts_match_query("hello world"), Some("\"hello\" \"world\"".to_string()));
+        assert_eq!(fts_match_query("deploy*"), Some("\"deploy\"*".to_string()));
+        assert_eq!(
+            fts_match_query("say \"NOT\" OR -x"),
+            Some("\"say\" \"NOT\" \"OR\" \"-x\"".to_string())
+        );
+        assert_eq!(fts_match_query("  \"\" * "), None);
     }
 
-    /// Update a playlist job record
-    pub async fn update_playlist_job(
-        &self,
-        job: &crate::features::plugins::job::PlaylistJob,
-    ) -> Result<()> {
-        let conn = self.connection.lock().await;
+    #[tokio::test]
+    async fn test_search_messages_scoped_to_guild() {
+        let db = Database::new(":memory:").await.unwrap();
+        db.store_guild_message(Some("g1"), "u1", "c1", "user", "the deploy failed again", None)
+            .await
+            .unwrap();
+        db.store_messages_batch(&[StoredMessage {
+            guild_id: Some("g2".to_string()),
+            user_id: "u2".to_string(),
+            channel_id: "c2".to_string(),
+            role: "user".to_string(),
+            content: "deployment looks fine here".to_string(),
+            persona: None,
+        }])
+        .await
+        .unwrap();
+
+        let g1 = db.search_messages("deploy", Some("g1"), None, 10).await.unwrap();
+        assert_eq!(g1.len(), 1);
+        assert_eq!(g1[0].channel_id, "c1");
+        assert!(g1[0].snippet.contains("**deploy**"));
+
+        // Prefix search spans guilds when unscoped
+        let all = db.search_messages("deploy*", None, None, 10).await.unwrap();
+        assert_eq!(all.len(), 2);
+
+        let none = db.search_messages("deploy", Some("g2"), None, 10).await.unwrap();
+        assert!(none.is_empty());
+    }
 
-        let completed_at = job.completed_at.map(|t| t.to_rfc3339()).unwrap_or_default();
+    #[tokio::test]
+    async fn test_memory_database_uses_single_connection() {
+        let db = Database::with_pool_size(":memory:", 8).await.unwrap();
+        assert_eq!(db.connection.size(), 1);
 
-        let cancelled_at = job.cancelled_at.map(|t| t.to_rfc3339()).unwrap_or_default();
+        db.store_message("u1", "c1", "user", "hello", None)
+            .await
+            .unwrap();
+        let history = db.get_conversation_history("u1", "c1", 10).await.unwrap();
+        assert_eq!(history.len(), 1);
+    }
 
-        let mut statement = conn.prepare(
-            "UPDATE playlist_jobs SET
-                thread_id = ?,
-                playlist_title = ?,
-                completed_videos = ?,
-                failed_videos = ?,
-                skipped_videos = ?,
-                status = ?,
-                current_video_job_id = ?,
-                error = ?,
-                completed_at = CASE WHEN ? = '' THEN NULL ELSE ? END,
-                cancelled_at = CASE WHEN ? = '' THEN NULL ELSE ? END,
-                cancelled_by = ?
-             WHERE id = ?",
-        )?;
-        statement.bind((1, job.thread_id.as_deref().unwrap_or("")))?;
-        statement.bind((2, job.playlist_title.as_deref().unwrap_or("")))?;
-        statement.bind((3, job.completed_videos as i64))?;
-        statement.bind((4, job.failed_videos as i64))?;
-        statement.bind((5, job.skipped_videos as i64))?;
-        statement.bind((6, job.status.to_string().as_str()))?;
-        statement.bind((7, job.current_video_job_id.as_deref().unwrap_or("")))?;
-        statement.bind((8, job.error.as_deref().unwrap_or("")))?;
-        statement.bind((9, completed_at.as_str()))?;
-        statement.bind((10, completed_at.as_str()))?;
-        statement.bind((11, cancelled_at.as_str()))?;
-        statement.bind((12, cancelled_at.as_str()))?;
-        statement.bind((13, job.cancelled_by.as_deref().unwrap_or("")))?;
-        statement.bind((14, job.id.as_str()))?;
-        statement.next()?;
+    #[tokio::test]
+    async fn test_file_database_enables_wal() {
+        let path = temp_db_path("wal");
+        let db = Database::with_pool_size(&path, 2).await.unwrap();
+        assert_eq!(db.connection.size(), 2);
+
+        let conn = db.connection.lock().await.unwrap();
+        let mut statement = conn.prepare("PRAGMA journal_mode").unwrap();
+        assert_eq!(statement.next().unwrap(), State::Row);
+        let mode = statement.read::<String, _>(0).unwrap();
+        assert_eq!(mode.to_lowercase(), "wal");
+        drop(statement);
+        drop(conn);
 
-        Ok(())
+        cleanup(&path);
     }
 
-    /// Get incomplete playlist jobs (for crash recovery / auto-resume)
-    pub async fn get_incomplete_playlist_jobs(
-        &self,
-    ) -> Result<Vec<crate::features::plugins::job::PlaylistJob>> {
-        use crate::features::plugins::job::{PlaylistJob, PlaylistJobStatus};
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_job_writes_and_message_storage() {
+        const WORKERS: usize = 8;
+        const JOBS_PER_WORKER: usize = 5;
+        const MESSAGES_PER_WORKER: usize = 40;
+
+        let path = temp_db_path("stress");
+        let db = Database::new(&path).await.unwrap();
+
+        let mut handles = Vec::new();
+        for worker in 0..WORKERS {
+            let db = db.clone();
+            handles.push(tokio::spawn(async move {
+                let user_id = format!("user{worker}");
+                let channel_id = format!("channel{worker}");
+
+                for i in 0..MESSAGES_PER_WORKER {
+                    db.store_message(&user_id, &channel_id, "user", &format!("msg {i}"), None)
+                        .await?;
+
+                    if i % (MESSAGES_PER_WORKER / JOBS_PER_WORKER) == 0 {
+                        let mut job = Job {
+                            id: uuid::Uuid::new_v4().to_string(),
+                            plugin_name: "stress".to_string(),
+                            user_id: user_id.clone(),
+                            guild_id: Some("guild".to_string()),
+                            channel_id: channel_id.clone(),
+                            thread_id: None,
+                            status: JobStatus::Pending,
+                            params: HashMap::new(),
+                            started_at: Utc::now(),
+                            completed_at: None,
+                            result: None,
+                            error: None,
+                            parent_playlist_id: None,
+                        };
+                        db.create_plugin_job(&job).await?;
+                        job.status = JobStatus::Running;
+                        db.update_plugin_job(&job).await?;
+                    }
+                }
+                anyhow::Ok(())
+            }));
+        }
 
-        let conn = self.connection.lock().await;
-        let mut jobs = Vec::new();
+        for handle in handles {
+            handle.await.unwrap().expect("concurrent write failed");
+        }
 
-        let mut statement = conn.prepare(
-            "SELECT id, user_id, guild_id, channel_id, thread_id, playlist_url, playlist_id,
-                    playlist_title, total_videos, completed_videos, failed_videos, skipped_videos,
-                    status, max_videos, current_video_job_id, error, started_at
-             FROM playlist_jobs
-             WHERE status IN ('pending', 'running', 'paused')
-             ORDER BY started_at ASC",
-        )?;
+        for worker in 0..WORKERS {
+            let history = db
+                .get_conversation_history(&format!("user{worker}"), &format!("channel{worker}"), 1000)
+                .await
+                .unwrap();
+            assert_eq!(history.len(), MESSAGES_PER_WORKER);
+        }
 
-        while let Ok(State::Row) = statement.next() {
-            let guild_id: String = statement.read(2)?;
-            let thread_id: String = statement.read(4)?;
-            let playlist_title: String = statement.read(7)?;
-            let status_str: String = statement.read(12)?;
-            let max_videos: i64 = statement.read(13)?;
-            let current_video_job_id: String = statement.read(14)?;
-            let error: String = statement.read(15)?;
-            let started_at_str: String = statement.read(16)?;
+        let jobs = db.get_incomplete_plugin_jobs().await.unwrap();
+        assert_eq!(jobs.len(), WORKERS * JOBS_PER_WORKER);
 
-            let status = status_str
-                .parse::<PlaylistJobStatus>()
-                .unwrap_or(PlaylistJobStatus::Pending);
-            let started_at = chrono::DateTime::parse_from_rfc3339(&started_at_str)
-                .map(|dt| dt.with_timezone(&chrono::Utc))
-                .unwrap_or_else(|_| chrono::Utc::now());
+        cleanup(&path);
+    }
 
-            jobs.push(PlaylistJob {
-                id: statement.read(0)?,
-                user_id: statement.read(1)?,
-                guild_id: if guild_id.is_empty() {
-                    None
-                } else {
-                    Some(guild_id)
-                },
-                channel_id: statement.read(3)?,
-                thread_id: if thread_id.is_empty() {
-                    None
-                } else {
-                    Some(thread_id)
-                },
-                playlist_url: statement.read(5)?,
-                playlist_id: statement.read(6)?,
-                playlist_title: if playlist_title.is_empty() {
-                    None
-                } else {
-                    Some(playlist_title)
-                },
-                total_videos: statement.read::<i64, _>(8)? as u32,
-                completed_videos: statement.read::<i64, _>(9)? as u32,
-                failed_videos: statement.read::<i64, _>(10)? as u32,
-                skipped_videos: statement.read::<i64, _>(11)? as u32,
-                status,
-                max_videos: if max_videos < 0 {
-                    None
-                } else {
-                    Some(max_videos as u32)
-                },
-                current_video_job_id: if current_video_job_id.is_empty() {
-                    None
-                } else {
-                    Some(current_video_job_id)
-                },
-                error: if error.is_empty() { None } else { Some(error) },
-                started_at,
-                completed_at: None,
-                cancelled_at: None,
-                cancelled_by: None,
-            });
-        }
+    async fn insert_usage(db: &Database, age: &str, service: &str, channel: &str, cost: f64) {
+        raw_execute(
+            db,
+            &format!(
+                "INSERT INTO openai_usage (user_id, guild_id, channel_id, service_type, model,
+                    image_count, estimated_cost_usd, cost_bucket, prompt_hash, timestamp)
+                 VALUES ('u1', 'g1', '{channel}', '{service}', 'm', 1, {cost}, '{service}',
+                    'hash1', datetime('now', '{age}'))"
+            ),
+        )
+        .await;
+    }
 
-        Ok(jobs)
+    #[tokio::test]
+    async fn test_rollup_preserves_reports_and_outlives_raw_rows() {
+        let db = Database::new(":memory:").await.unwrap();
+        insert_usage(&db, "-2 days", "dalle", "c1", 0.04).await;
+        insert_usage(&db, "-2 days", "dalle", "c1", 0.04).await;
+        insert_usage(&db, "-1 days", "dalle", "c2", 0.08).await;
+        insert_usage(&db, "+0 seconds", "chat", "c1", 0.01).await;
+
+        let before = db.get_cost_by_bucket(Some(7)).await.unwrap();
+
+        // The current hour stays raw until it completes
+        assert_eq!(db.rollup_analytics().await.unwrap(), 3);
+        assert_eq!(db.rollup_analytics().await.unwrap(), 0);
+        assert_eq!(db.get_cost_by_bucket(Some(7)).await.unwrap(), before);
+
+        // Reports keep working once raw rows past retention are gone
+        raw_execute(
+            &db,
+            "DELETE FROM openai_usage WHERE timestamp < datetime('now', '-1 hours')",
+        )
+        .await;
+        assert_eq!(db.get_cost_by_bucket(Some(7)).await.unwrap(), before);
+
+        let breakdown = db.get_guild_image_cost_breakdown("g1", 7, 10).await.unwrap();
+        assert_eq!(breakdown.total_images, 3);
+        assert!((breakdown.total_cost - 0.16).abs() < 1e-9);
+        assert_eq!(breakdown.by_channel[0].key, "c2");
+        assert_eq!(breakdown.by_channel[1].requests, 2);
+        assert_eq!(breakdown.top_prompts[0].key, "hash1");
+        assert_eq!(breakdown.top_prompts[0].requests, 3);
     }
 
-    /// Get active playlist jobs for a user
-    pub async fn get_user_active_playlist_jobs(
-        &self,
-        user_id: &str,
-    ) -> Result<Vec<crate::features::plugins::job::PlaylistJob>> {
-        use crate::features::plugins::job::{PlaylistJob, PlaylistJobStatus};
+    #[tokio::test]
+    async fn test_rollup_counts_command_usage() {
+        let db = Database::new(":memory:").await.unwrap();
+        raw_execute(
+            &db,
+            "INSERT INTO usage_stats (user_id, guild_id, command, persona, timestamp) VALUES
+                ('u1', 'g1', 'ask', 'obi', datetime('now', '-3 hours')),
+                ('u1', 'g2', 'ask', 'obi', datetime('now', '-2 hours')),
+                ('u1', NULL, 'ping', '', datetime('now', '-2 hours'))",
+        )
+        .await;
+        db.rollup_analytics().await.unwrap();
+        db.cleanup_old_usage_stats(0).await.unwrap();
+
+        // Rolled-up days keep their guild
+        let profile = db.get_user_profile("u1", "g1", 3).await.unwrap();
+        assert_eq!(profile.top_commands, vec![("ask".to_string(), 1)]);
+
+        let conn = db.connection.lock().await.unwrap();
+        let mut statement = conn
+            .prepare("SELECT SUM(invocations) FROM command_usage_daily WHERE command = 'ask'")
+            .unwrap();
+        statement.next().unwrap();
+        assert_eq!(statement.read::<i64, _>(0).unwrap(), 2);
+    }
 
-        let conn = self.connection.lock().await;
-        let mut jobs = Vec::new();
+    #[tokio::test]
+    async fn test_downsample_system_metrics_into_tiers() {
+        let db = Database::new(":memory:").await.unwrap();
+        raw_execute(
+            &db,
+            "INSERT INTO performance_metrics (metric_type, value, unit, timestamp) VALUES
+                ('system_cpu_percent', 10, 'system', datetime('now', '-3 days', 'start of day', '+1 hours')),
+                ('system_cpu_percent', 30, 'system', datetime('now', '-3 days', 'start of day', '+1 hours', '+5 minutes')),
+                ('system_cpu_percent', 50, 'system', datetime('now', '-3 days', 'start of day', '+2 hours')),
+                ('system_cpu_percent', 90, 'system', datetime('now')),
+                ('cpu', 99, '%', datetime('now', '-3 days'))",
+        )
+        .await;
+
+        assert_eq!(db.downsample_system_metrics().await.unwrap(), 2);
+        assert_eq!(db.downsample_system_metrics().await.unwrap(), 0);
+        db.cleanup_old_metrics(DEFAULT_ROLLUP_RETENTION_DAYS)
+            .await
+            .unwrap();
+
+        // Raw rows past retention are gone, their hours and day survive
+        let minute = db
+            .get_metrics_history("system_cpu_percent", 24)
+            .await
+            .unwrap();
+        assert_eq!(minute.len(), 1);
+        let hourly: Vec<f64> = db
+            .get_metrics_history("system_cpu_percent", 24 * 7)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(_, v)| v)
+            .collect();
+        assert_eq!(hourly, vec![20.0, 50.0, 90.0]);
+        let daily = db
+            .get_metrics_history("system_cpu_percent", 24 * 60)
+            .await
+            .unwrap();
+        assert_eq!(daily.len(), 1);
+        assert!((daily[0].1 - 30.0).abs() < 1e-9);
+        assert!(db
+            .get_metrics_history("cpu", 24 * 7)
+            .await
+            .unwrap()
+            .is_empty());
+
+        assert_eq!(MetricsTier::for_span(24), MetricsTier::Minute);
+        assert_eq!(MetricsTier::for_span(24 * 30), MetricsTier::Hour);
+        assert_eq!(MetricsTier::for_span(24 * 90), MetricsTier::Day);
+    }
 
-        let mut statement = conn.prepare(
-            "SELECT id, user_id, guild_id, channel_id, thread_id, playlist_url, playlist_id,
-                    playlist_title, total_videos, completed_videos, failed_videos, skipped_videos,
-                    status, max_videos, current_video_job_id, error, started_at
-             FROM playlist_jobs
-             WHERE user_id = ? AND status IN ('pending', 'running', 'paused')
-             ORDER BY started_at DESC",
-        )?;
-        statement.bind((1, user_id))?;
+    async fn open_dm_session(db: &Database, session_id: &str, channel_id: &str, idle: &str) {
+        db.create_dm_session(session_id, "u1", channel_id).await.unwrap();
+        db.update_dm_session_activity(session_id, 4, 2, 2, 40, 200, 1500)
+            .await
+            .unwrap();
+        raw_execute(
+            db,
+            &format!(
+                "UPDATE dm_sessions
+                 SET started_at = datetime('now', '{idle}', '-20 minutes'),
+                     last_activity_at = datetime('now', '{idle}')
+                 WHERE session_id = '{session_id}'"
+            ),
+        )
+        .await;
+    }
 
-        while let Ok(State::Row) = statement.next() {
-            let guild_id: String = statement.read(2)?;
-            let thread_id: String = statement.read(4)?;
-            let playlist_title: String = statement.read(7)?;
-            let status_str: String = statement.read(12)?;
-            let max_videos: i64 = statement.read(13)?;
-            let current_video_job_id: String = statement.read(14)?;
-            let error: String = statement.read(15)?;
-            let started_at_str: String = statement.read(16)?;
+    #[tokio::test]
+    async fn test_reconcile_resumes_recent_and_closes_stale_dm_sessions() {
+        use crate::features::analytics::InteractionTracker;
 
-            let status = status_str
-                .parse::<PlaylistJobStatus>()
-                .unwrap_or(PlaylistJobStatus::Pending);
-            let started_at = chrono::DateTime::parse_from_rfc3339(&started_at_str)
-                .map(|dt| dt.with_timezone(&chrono::Utc))
-                .unwrap_or_else(|_| chrono::Utc::now());
+        let db = Database::new(":memory:").await.unwrap();
+        open_dm_session(&db, "recent", "c1", "-5 minutes").await;
+        open_dm_session(&db, "stale", "c2", "-3 hours").await;
+        open_dm_session(&db, "superseded", "c1", "-10 minutes").await;
 
-            jobs.push(PlaylistJob {
-                id: statement.read(0)?,
-                user_id: statement.read(1)?,
-                guild_id: if guild_id.is_empty() {
-                    None
-                } else {
-                    Some(guild_id)
-                },
-                channel_id: statement.read(3)?,
-                thread_id: if thread_id.is_empty() {
-                    None
-                } else {
-                    Some(thread_id)
-                },
-                playlist_url: statement.read(5)?,
-                playlist_id: statement.read(6)?,
-                playlist_title: if playlist_title.is_empty() {
-                    None
-                } else {
-                    Some(playlist_title)
-                },
-                total_videos: statement.read::<i64, _>(8)? as u32,
-                completed_videos: statement.read::<i64, _>(9)? as u32,
-                failed_videos: statement.read::<i64, _>(10)? as u32,
-                skipped_videos: statement.read::<i64, _>(11)? as u32,
-                status,
-                max_videos: if max_videos < 0 {
-                    None
-                } else {
-                    Some(max_videos as u32)
-                },
-                current_video_job_id: if current_video_job_id.is_empty() {
-                    None
-                } else {
-                    Some(current_video_job_id)
-                },
-                error: if error.is_empty() { None } else { Some(error) },
-                started_at,
-                completed_at: None,
-                cancelled_at: None,
-                cancelled_by: None,
-            });
-        }
+        let tracker = InteractionTracker::new(db.clone());
+        assert_eq!(tracker.reconcile_open_sessions().await.unwrap(), (1, 2));
 
-        Ok(jobs)
+        // The resumed session keeps its id instead of starting a new one
+        assert_eq!(tracker.get_or_create_session("u1", "c1"), "recent");
+
+        let open = db.get_open_dm_sessions().await.unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].session_id, "recent");
+        assert_eq!(open[0].user_message_count, 2);
+        assert_eq!(open[0].avg_response_time_ms, 1500);
+
+        // Stale sessions end at their last activity, not at restart time
+        let stats = db.get_user_dm_stats("u1", 7).await.unwrap();
+        assert!((stats.avg_session_duration_min - 20.0).abs() < 0.1);
     }
 
-    /// Get completed video job IDs for a playlist (for resume functionality)
-    pub async fn get_completed_video_job_ids(&self, playlist_job_id: &str) -> Result<Vec<String>> {
-        let conn = self.connection.lock().await;
-        let mut ids = Vec::new();
+    #[tokio::test]
+    async fn test_personas_do_not_leak_across_guilds() {
+        let db = Database::new(":memory:").await.unwrap();
+        db.set_user_persona_in_guild("u1", Some("g1"), "chef")
+            .await
+            .unwrap();
+        db.set_user_persona_in_guild("u1", None, "muppet")
+            .await
+            .unwrap();
+        db.set_guild_setting("g2", "default_persona", "obi")
+            .await
+            .unwrap();
+
+        let in_g1 = db
+            .get_user_persona_with_guild("u1", Some("g1"))
+            .await
+            .unwrap();
+        assert_eq!(in_g1, "chef");
+        // Neither the g1 choice nor the DM choice applies in g2
+        let in_g2 = db
+            .get_user_persona_with_guild("u1", Some("g2"))
+            .await
+            .unwrap();
+        assert_eq!(in_g2, "obi");
+        let in_g2 = db
+            .get_persona_with_channel("u1", "g2", "c2", None)
+            .await
+            .unwrap();
+        assert_eq!(in_g2, "obi");
+        // DMs keep the global preference
+        assert_eq!(db.get_user_persona("u1").await.unwrap(), "muppet");
+        assert_eq!(
+            db.get_user_persona_with_guild("u1", None).await.unwrap(),
+            "muppet"
+        );
+    }
 
-        let mut statement = conn.prepare(
-            "SELECT params FROM plugin_jobs
-             WHERE parent_playlist_id = ? AND status = 'completed'",
-        )?;
-        statement.bind((1, playlist_job_id))?;
+    #[tokio::test]
+    async fn test_usage_stats_do_not_leak_across_guilds() {
+        let db = Database::new(":memory:").await.unwrap();
+        raw_execute(
+            &db,
+            "INSERT INTO openai_usage_daily
+                 (date, guild_id, user_id, service_type, request_count, total_cost_usd)
+             VALUES (date('now'), 'g1', 'u1', 'chat', 2, 0.5),
+                    (date('now'), 'g2', 'u1', 'chat', 3, 1.0),
+                    (date('now'), '', 'u1', 'chat', 4, 2.0)",
+        )
+        .await;
+
+        let g1 = db.get_guild_usage_stats("g1", 1).await.unwrap();
+        assert_eq!(g1.len(), 1);
+        assert_eq!(g1[0].1, 2);
+
+        let top = db.get_guild_top_users_by_cost("g1", 1, 10).await.unwrap();
+        assert_eq!(top, vec![("u1".to_string(), 2, 0.5)]);
+
+        let mine = db.get_user_guild_usage_stats("u1", "g2", 1).await.unwrap();
+        assert_eq!(mine[0].1, 3);
+        let everywhere = db.get_user_usage_stats("u1", 1).await.unwrap();
+        assert_eq!(everywhere[0].1, 9);
+    }
 
-        while let Ok(State::Row) = statement.next() {
-            let params_json: String = statement.read(0)?;
-            if let Ok(params) =
-                serde_json::from_str::<std::collections::HashMap<String, String>>(&params_json)
-            {
-                if let Some(url) = params.get("url") {
-                    // Extract video ID from URL
-                    if let Some(vid) = url.split("v=").nth(1).and_then(|s| s.split('&').next()) {
-                        ids.push(vid.to_string());
-                    }
-                }
-            }
+    #[tokio::test]
+    async fn test_guild_plugin_summary_costs() {
+        let db = Database::new(":memory:").await.unwrap();
+        for (request_id, guild, cost) in [
+            ("plugin:transcribe:video_summary", "g1", 0.02),
+            ("plugin:transcribe:chunk_summary", "g1", 0.01),
+            ("plugin:notes:result_summary", "g1", 0.005),
+            ("plugin:notes:result_summary", "g2", 1.0),
+            ("plugin_result_summary", "g1", 3.0),
+        ] {
+            db.log_openai_chat_usage(
+                "gpt-4o-mini",
+                10,
+                10,
+                20,
+                cost,
+                "u1",
+                Some(guild),
+                None,
+                Some(request_id),
+                "plugin",
+            )
+            .await
+            .unwrap();
         }
 
-        Ok(ids)
+        let costs = db.get_guild_plugin_summary_costs("g1", 7).await.unwrap();
+        assert_eq!(costs.len(), 2);
+        assert_eq!(costs[0].0, "transcribe");
+        assert_eq!(costs[0].1, 2);
+        assert!((costs[0].2 - 0.03).abs() < 1e-9);
+        assert_eq!(costs[1].0, "notes");
     }
 
-    // ============================================================================
-    // TUI Analytics Methods
-    // ============================================================================
+    #[tokio::test]
+    async fn test_thread_transcript_roundtrip() {
+        let db = Database::new(":memory:").await.unwrap();
+        assert!(db.get_thread_transcript("t1").await.unwrap().is_none());
+
+        db.store_thread_transcript("t1", "job-1", Some("Talk"), "hello")
+            .await
+            .unwrap();
+        db.store_thread_transcript("t1", "job-2", None, "hello again")
+            .await
+            .unwrap();
+        let stored = db.get_thread_transcript("t1").await.unwrap().unwrap();
+        assert_eq!(stored.job_id, "job-2");
+        assert_eq!(stored.title, None);
+        assert_eq!(stored.transcript, "hello again");
+    }
 
-    /// Get user list with aggregated stats
-    pub async fn get_user_list(&self, limit: u32) -> Result<Vec<UserListEntry>> {
-        let conn = self.connection.lock().await;
-        let mut users = Vec::new();
+    #[tokio::test]
+    async fn test_transcribe_subscription_lifecycle() {
+        let db = Database::new(":memory:").await.unwrap();
+        let mut subscription = TranscribeSubscription {
+            id: 0,
+            guild_id: "g1".to_string(),
+            channel_id: "c1".to_string(),
+            source_url: "https://www.youtube.com/@someone/videos".to_string(),
+            title: Some("Someone".to_string()),
+            user_id: "u1".to_string(),
+            interval_minutes: 360,
+            max_per_check: 3,
+            last_checked_at: Some(100),
+        };
+        subscription.id = db
+            .create_transcribe_subscription(&subscription)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            db.get_guild_transcribe_subscriptions("g1").await.unwrap(),
+            vec![subscription.clone()]
+        );
+        assert!(db
+            .get_guild_transcribe_subscriptions("g2")
+            .await
+            .unwrap()
+            .is_empty());
+
+        db.mark_transcribe_subscription_checked(subscription.id, 500)
+            .await
+            .unwrap();
+        db.mark_subscription_videos_seen(subscription.id, &["a".to_string(), "b".to_string()])
+            .await
+            .unwrap();
+        db.mark_subscription_videos_seen(subscription.id, &["b".to_string()])
+            .await
+            .unwrap();
+        let all = db.get_transcribe_subscriptions().await.unwrap();
+        assert_eq!(all[0].last_checked_at, Some(500));
+        assert_eq!(
+            db.get_subscription_seen_videos(subscription.id)
+                .await
+                .unwrap(),
+            HashSet::from(["a".to_string(), "b".to_string()])
+        );
 
-        // Get users with their total cost and activity from openai_usage_daily, with cached usernames
-        let mut stmt = conn.prepare(
-            "SELECT
-                u.user_id,
-                uc.username,
-                SUM(u.total_cost_usd) as total_cost,
-                SUM(u.total_tokens) as total_tokens,
-                SUM(u.request_count) as total_calls,
-                MAX(u.date) as last_activity
-             FROM openai_usage_daily u
-             LEFT JOIN user_cache uc ON u.user_id = uc.user_id
-             WHERE u.user_id != ''
-             GROUP BY u.user_id
-             ORDER BY total_cost DESC
-             LIMIT ?",
-        )?;
-        stmt.bind((1, limit as i64))?;
+        // Only the owning guild can remove it
+        assert!(!db
+            .delete_transcribe_subscription("g2", subscription.id)
+            .await
+            .unwrap());
+        assert!(db
+            .delete_transcribe_subscription("g1", subscription.id)
+            .await
+            .unwrap());
+        assert!(db.get_transcribe_subscriptions().await.unwrap().is_empty());
+        assert!(db
+            .get_subscription_seen_videos(subscription.id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_transcript_cache_roundtrip() {
+        let db = Database::new(":memory:").await.unwrap();
+        assert!(db
+            .get_cached_transcript("dQw4w9WgXcQ")
+            .await
+            .unwrap()
+            .is_none());
+
+        db.store_cached_transcript("dQw4w9WgXcQ", "never gonna", Some("Song"), "job-1")
+            .await
+            .unwrap();
+        let first = db
+            .get_cached_transcript("dQw4w9WgXcQ")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(first.transcript, "never gonna");
+        assert_eq!(first.title.as_deref(), Some("Song"));
+        assert_eq!(first.job_id, "job-1");
+        assert_eq!(first.hit_count, 1);
+
+        // A re-transcription replaces the entry
+        db.store_cached_transcript("dQw4w9WgXcQ", "never gonna give", None, "job-2")
+            .await
+            .unwrap();
+        let second = db
+            .get_cached_transcript("dQw4w9WgXcQ")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(second.transcript, "never gonna give");
+        assert_eq!(second.job_id, "job-2");
+        assert_eq!(second.hit_count, 1);
+
+        let archived = db
+            .get_archived_video_ids(&["dQw4w9WgXcQ".to_string(), "missing".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(archived, HashSet::from(["dQw4w9WgXcQ".to_string()]));
+    }
+
+    #[tokio::test]
+    async fn test_history_does_not_leak_across_guilds() {
+        let db = Database::new(":memory:").await.unwrap();
+        db.store_guild_message(Some("g1"), "u1", "c1", "user", "secret plans", None)
+            .await
+            .unwrap();
+        db.store_guild_message(Some("g2"), "u1", "c2", "user", "public plans", None)
+            .await
+            .unwrap();
+
+        let history = db.get_conversation_history("u1", "c2", 10).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].1, "public plans");
+
+        let found = db
+            .search_messages("plans", Some("g2"), None, 10)
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].channel_id, "c2");
+    }
+
+    #[tokio::test]
+    async fn test_guild_id_backfill_migration() {
+        let db = Database::new(":memory:").await.unwrap();
+        raw_execute(&db, "DROP TABLE guild_user_personas").await;
+        raw_execute(
+            &db,
+            "INSERT INTO channel_settings (guild_id, channel_id) VALUES ('g1', 'c1')",
+        )
+        .await;
+        raw_execute(
+            &db,
+            "INSERT INTO conversation_history (user_id, channel_id, role, content)
+             VALUES ('u1', 'c1', 'user', 'hello'), ('u1', 'dm', 'user', 'hi')",
+        )
+        .await;
+        raw_execute(
+            &db,
+            "INSERT INTO user_preferences (user_id, default_persona) VALUES ('u1', 'muppet')",
+        )
+        .await;
+
+        db.init_tables().await.unwrap();
+
+        let found = db
+            .search_messages("hello", Some("g1"), None, 10)
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        // The preference carries into the guild the user was active in, not others
+        assert_eq!(
+            db.get_user_persona_with_guild("u1", Some("g1"))
+                .await
+                .unwrap(),
+            "muppet"
+        );
+        assert_ne!(
+            db.get_user_persona_with_guild("u1", Some("g2"))
+                .await
+                .unwrap(),
+            "muppet"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_privacy_choice_overrides_guild_default() {
+        let db = Database::new(":memory:").await.unwrap();
+        assert_eq!(
+            db.get_privacy_settings("u1", Some("g1")).await.unwrap(),
+            PrivacySettings::default()
+        );
+
+        db.set_guild_setting("g1", "privacy_conflict_analysis", "disabled")
+            .await
+            .unwrap();
+        db.set_privacy_choice("u1", PrivacyOption::DmHistory, Some(false))
+            .await
+            .unwrap();
+        let in_g1 = db.get_privacy_settings("u1", Some("g1")).await.unwrap();
+        assert!(!in_g1.dm_history);
+        assert!(!in_g1.conflict_analysis);
+        assert!(in_g1.analytics);
+        // Guild defaults stay in their guild
+        let in_dm = db.get_privacy_settings("u1", None).await.unwrap();
+        assert!(in_dm.conflict_analysis);
+
+        db.set_privacy_choice("u1", PrivacyOption::ConflictAnalysis, Some(true))
+            .await
+            .unwrap();
+        assert!(
+            db.get_privacy_settings("u1", Some("g1"))
+                .await
+                .unwrap()
+                .conflict_analysis
+        );
+
+        db.set_privacy_choice("u1", PrivacyOption::DmHistory, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            db.get_privacy_choices("u1").await.unwrap(),
+            vec![(PrivacyOption::ConflictAnalysis, true)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dm_consent_round_trip() {
+        let db = Database::new(":memory:").await.unwrap();
+        assert_eq!(db.get_dm_consent("u1").await.unwrap(), None);
+
+        db.set_dm_consent("u1", false).await.unwrap();
+        assert_eq!(db.get_dm_consent("u1").await.unwrap(), Some(false));
+        db.set_dm_consent("u1", true).await.unwrap();
+        assert_eq!(db.get_dm_consent("u1").await.unwrap(), Some(true));
+        assert_eq!(db.get_dm_consent("u2").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_access_rules_block_and_allow() {
+        let db = Database::new(":memory:").await.unwrap();
+        let here = vec!["c1".to_string(), "cat1".to_string()];
+        assert!(db.get_access_rules("g1").await.unwrap().is_empty());
+
+        db.set_access_rule(
+            "g1",
+            AccessTarget::User,
+            "griefer",
+            Some(AccessRule::Block),
+            "admin",
+        )
+        .await
+        .unwrap();
+        let rules = db.get_access_rules("g1").await.unwrap();
+        assert!(!rules.permits("griefer", &here));
+        assert!(rules.permits("u1", &here));
+        // Lists are per guild
+        assert!(db.get_access_rules("g2").await.unwrap().is_empty());
+
+        // Allowlisting a category admits its channels and nothing else
+        db.set_access_rule(
+            "g1",
+            AccessTarget::Channel,
+            "cat1",
+            Some(AccessRule::Allow),
+            "admin",
+        )
+        .await
+        .unwrap();
+        let rules = db.get_access_rules("g1").await.unwrap();
+        assert!(rules.permits("u1", &here));
+        assert!(!rules.permits("u1", &["c2".to_string()]));
+
+        // Re-listing a target replaces its rule; removing clears it
+        db.set_access_rule(
+            "g1",
+            AccessTarget::User,
+            "griefer",
+            Some(AccessRule::Allow),
+            "admin",
+        )
+        .await
+        .unwrap();
+        let rules = db.get_access_rules("g1").await.unwrap();
+        assert!(rules.permits("griefer", &here));
+        assert!(!rules.permits("u1", &here));
+        db.set_access_rule("g1", AccessTarget::User, "griefer", None, "admin")
+            .await
+            .unwrap();
+        assert_eq!(
+            db.get_access_rules("g1").await.unwrap().allowed_channels,
+            vec!["cat1".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_celebrations_and_opt_out() {
+        let db = Database::new(":memory:").await.unwrap();
+        let birthday = Celebration {
+            guild_id: "g1".to_string(),
+            user_id: "u1".to_string(),
+            kind: CelebrationKind::Birthday,
+            month: 3,
+            day: 14,
+            year: None,
+            timezone: Zone::Named(chrono_tz::Tz::America__New_York),
+            last_wished_year: None,
+        };
+        db.set_celebration(&birthday).await.unwrap();
+        db.set_celebration(&Celebration {
+            kind: CelebrationKind::Anniversary,
+            month: 6,
+            day: 1,
+            year: Some(2019),
+            ..birthday.clone()
+        })
+        .await
+        .unwrap();
+        assert_eq!(db.get_guild_celebrations("g1").await.unwrap().len(), 2);
+        assert!(db.get_guild_celebrations("g2").await.unwrap().is_empty());
+
+        // Re-saving the same date keeps the last wish; moving it clears it
+        db.mark_celebration_wished("g1", "u1", CelebrationKind::Birthday, 2026)
+            .await
+            .unwrap();
+        db.set_celebration(&birthday).await.unwrap();
+        let saved = db.get_guild_celebrations("g1").await.unwrap();
+        assert_eq!(
+            saved[0],
+            Celebration {
+                last_wished_year: Some(2026),
+                ..birthday.clone()
+            }
+        );
+        db.set_celebration(&Celebration {
+            day: 15,
+            ..birthday.clone()
+        })
+        .await
+        .unwrap();
+        assert_eq!(
+            db.get_guild_celebrations("g1").await.unwrap()[0].last_wished_year,
+            None
+        );
+
+        // Opting out hides the member from wishes but keeps their dates
+        db.set_celebration_opt_out("g1", "u1", true).await.unwrap();
+        assert!(db.is_celebration_opted_out("g1", "u1").await.unwrap());
+        assert!(db.get_wishable_celebrations().await.unwrap().is_empty());
+        assert_eq!(db.get_guild_celebrations("g1").await.unwrap().len(), 2);
+        db.set_celebration_opt_out("g1", "u1", false).await.unwrap();
+        assert_eq!(db.get_wishable_celebrations().await.unwrap().len(), 2);
+
+        assert!(db
+            .remove_celebration("g1", "u1", Some(CelebrationKind::Anniversary))
+            .await
+            .unwrap());
+        assert!(!db
+            .remove_celebration("g1", "u1", Some(CelebrationKind::Anniversary))
+            .await
+            .unwrap());
+
+        // Dates saved before zone names were stored keep their fixed offset
+        raw_execute(
+            &db,
+            "UPDATE celebrations SET timezone = NULL, utc_offset_minutes = -300 WHERE user_id = 'u1'",
+        )
+        .await;
+        let legacy = db.get_guild_celebrations("g1").await.unwrap();
+        assert_eq!(legacy[0].timezone, Zone::Fixed(-300));
+
+        assert!(db.remove_celebration("g1", "u1", None).await.unwrap());
+        assert!(db.get_guild_celebrations("g1").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_trivia_leaderboard_accumulates_games() {
+        let db = Database::new(":memory:").await.unwrap();
+        db.record_trivia_result("g1", "u1", 24, 2, false)
+            .await
+            .unwrap();
+        db.record_trivia_result("g1", "u2", 30, 3, true)
+            .await
+            .unwrap();
+        db.record_trivia_result("g1", "u1", 13, 1, true)
+            .await
+            .unwrap();
+        db.record_trivia_result("g2", "u3", 99, 9, true)
+            .await
+            .unwrap();
+
+        let board = db.get_trivia_leaderboard("g1", 10).await.unwrap();
+        assert_eq!(
+            board[0],
+            TriviaStanding {
+                user_id: "u1".to_string(),
+                points: 37,
+                correct_answers: 3,
+                games_played: 2,
+                games_won: 1,
+            }
+        );
+        assert_eq!(board[1].user_id, "u2");
+        assert_eq!(board.len(), 2);
+        assert_eq!(db.get_trivia_leaderboard("g1", 1).await.unwrap().len(), 1);
+        assert_eq!(
+            db.get_trivia_standing("g1", "u2").await.unwrap(),
+            Some(board[1].clone())
+        );
+        assert_eq!(db.get_trivia_standing("g2", "u1").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_user_profile_aggregates_stats() {
+        let db = Database::new(":memory:").await.unwrap();
+        db.store_guild_message(Some("g1"), "u1", "c1", "user", "hi", None)
+            .await
+            .unwrap();
+        db.store_guild_message(Some("g1"), "u1", "c1", "user", "hello", None)
+            .await
+            .unwrap();
+        db.store_guild_message(Some("g1"), "u1", "c1", "assistant", "hey", Some("chef"))
+            .await
+            .unwrap();
+        db.store_guild_message(Some("g2"), "u1", "c9", "user", "elsewhere", None)
+            .await
+            .unwrap();
+        for (guild_id, command) in [
+            (Some("g1"), "ask"),
+            (Some("g1"), "ask"),
+            (Some("g1"), "trivia"),
+            (Some("g2"), "imagine"),
+            (None, "imagine"),
+        ] {
+            db.log_usage("u1", guild_id, command, None).await.unwrap();
+        }
+        db.log_openai_whisper_usage(90.0, 0.009, "u1", Some("g1"), Some("c1"), "transcription")
+            .await
+            .unwrap();
+        db.record_trivia_result("g1", "u1", 20, 2, true)
+            .await
+            .unwrap();
+
+        for thread in ["t1", "t2"] {
+            db.record_debate_result(&DebateResult {
+                thread_id: thread.to_string(),
+                guild_id: Some("g1".to_string()),
+                initiator_id: "u1".to_string(),
+                persona1_id: "obi".to_string(),
+                persona2_id: "chef".to_string(),
+                rounds: 4,
+            })
+            .await
+            .unwrap();
+        }
+        // t1: u1's pick wins 2-1; t2: tied, so no win
+        db.record_debate_vote("t1", "u1", "obi").await.unwrap();
+        db.record_debate_vote("t1", "u2", "obi").await.unwrap();
+        db.record_debate_vote("t1", "u3", "chef").await.unwrap();
+        db.record_debate_vote("t2", "u1", "chef").await.unwrap();
+        db.record_debate_vote("t2", "u2", "chef").await.unwrap();
+        // Changing a vote replaces it
+        db.record_debate_vote("t2", "u2", "obi").await.unwrap();
+        assert_eq!(
+            db.get_debate_tally("t2").await.unwrap(),
+            vec![("chef".to_string(), 1), ("obi".to_string(), 1)]
+        );
+        assert_eq!(
+            db.get_debate_voters("t1", "obi").await.unwrap(),
+            vec!["u1".to_string(), "u2".to_string()]
+        );
+        assert_eq!(db.get_user_command_count("u1", "ask").await.unwrap(), 2);
+        assert_eq!(db.get_user_command_count("u1", "imagine").await.unwrap(), 2);
+        assert_eq!(db.get_user_command_count("u1", "fetch").await.unwrap(), 0);
+
+        let profile = db.get_user_profile("u1", "g1", 3).await.unwrap();
+        assert_eq!(profile.favorite_persona.as_deref(), Some("chef"));
+        assert_eq!(profile.message_count, 2);
+        assert_eq!(profile.bot_replies, 1);
+        assert_eq!(
+            profile.top_commands,
+            vec![("ask".to_string(), 2), ("trivia".to_string(), 1)]
+        );
+        assert!((profile.transcription_seconds - 90.0).abs() < 1e-9);
+        assert_eq!(profile.debates_started, 2);
+        assert_eq!(profile.debate_votes, 2);
+        assert_eq!(profile.debate_wins, 1);
+        assert_eq!(profile.trivia.unwrap().games_won, 1);
+
+        let empty = db.get_user_profile("u9", "g1", 3).await.unwrap();
+        assert_eq!(empty, UserProfile::default());
+    }
 
-        while let Ok(State::Row) = stmt.next() {
-            users.push(UserListEntry {
-                user_id: stmt.read::<String, _>(0)?,
-                username: stmt.read::<Option<String>, _>(1)?,
-                total_cost: stmt.read::<f64, _>(2)?,
-                total_tokens: stmt.read::<i64, _>(3)? as u64,
-                total_calls: stmt.read::<i64, _>(4)? as u64,
-                dm_session_count: 0, // Enriched below
-                last_activity: stmt.read::<Option<String>, _>(5)?,
-            });
+    #[tokio::test]
+    async fn test_achievements_awarded_once_per_guild() {
+        let db = Database::new(":memory:").await.unwrap();
+        for (guild, key, new) in [
+            ("g1", "trivia_champion", true),
+            ("g1", "curious_mind", true),
+            ("g1", "trivia_champion", false),
+            ("g2", "trivia_champion", true),
+        ] {
+            assert_eq!(db.award_achievement(guild, "u1", key).await.unwrap(), new);
         }
 
-        // Enrich with DM session counts
-        for user in &mut users {
-            let mut session_stmt =
-                conn.prepare("SELECT COUNT(*) FROM dm_sessions WHERE user_id = ?")?;
-            session_stmt.bind((1, user.user_id.as_str()))?;
-            if let Ok(State::Row) = session_stmt.next() {
-                user.dm_session_count = session_stmt.read::<i64, _>(0)? as u64;
-            }
+        let earned = db.get_user_achievements("g1", "u1").await.unwrap();
+        assert_eq!(earned.len(), 2);
+        assert!(earned.contains(&"curious_mind".to_string()));
+        let none = db.get_user_achievements("g1", "u2").await.unwrap();
+        assert!(none.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_member_xp_cooldown_and_ranking() {
+        let db = Database::new(":memory:").await.unwrap();
+        let cases = [
+            ("u1", 15, 1000, Some((0, 15))),
+            // Still cooling down
+            ("u1", 15, 1059, None),
+            ("u1", 25, 1060, Some((15, 40))),
+            ("u2", 15, 1000, Some((0, 15))),
+            ("u3", 50, 1000, Some((0, 50))),
+        ];
+        for (user, amount, now, expected) in cases {
+            let granted = db.grant_member_xp("g1", user, amount, now, 60).await;
+            assert_eq!(granted.unwrap(), expected);
         }
 
-        Ok(users)
+        let (u1, rank) = db.get_member_rank("g1", "u1").await.unwrap().unwrap();
+        assert_eq!((u1.xp, u1.grants, rank), (40, 2, 2));
+        assert!(db.get_member_rank("g2", "u1").await.unwrap().is_none());
+
+        let board = db.get_xp_leaderboard("g1", 2).await.unwrap();
+        let ids: Vec<&str> = board.iter().map(|m| m.user_id.as_str()).collect();
+        assert_eq!(ids, ["u3", "u1"]);
     }
 
-    /// Get detailed user statistics
-    pub async fn get_user_details(&self, user_id: &str) -> Result<UserDetails> {
-        let conn = self.connection.lock().await;
+    #[tokio::test]
+    async fn test_level_rewards() {
+        let db = Database::new(":memory:").await.unwrap();
+        db.set_level_reward("g1", 10, "r10").await.unwrap();
+        db.set_level_reward("g1", 5, "r5").await.unwrap();
+        db.set_level_reward("g1", 10, "r10b").await.unwrap();
+        assert_eq!(
+            db.get_level_rewards("g1").await.unwrap(),
+            vec![(5, "r5".to_string()), (10, "r10b".to_string())]
+        );
+        assert!(db.remove_level_reward("g1", 5).await.unwrap());
+        assert!(!db.remove_level_reward("g1", 5).await.unwrap());
+        assert_eq!(db.get_level_rewards("g1").await.unwrap().len(), 1);
+    }
 
-        // Get aggregated usage stats
-        let mut usage_stmt = conn.prepare(
-            "SELECT
-                SUM(total_cost_usd) as total_cost,
-                SUM(total_tokens) as total_tokens,
-                SUM(request_count) as total_calls,
-                MIN(date) as first_seen,
-                MAX(date) as last_activity
-             FROM openai_usage_daily
-             WHERE user_id = ?",
-        )?;
-        usage_stmt.bind((1, user_id))?;
+    #[tokio::test]
+    async fn test_standup_entries_close_with_summary() {
+        let db = Database::new(":memory:").await.unwrap();
+        let config = StandupConfig {
+            guild_id: "g1".to_string(),
+            channel_id: "c1".to_string(),
+            prompt_minute: 9 * 60 + 30,
+            utc_offset_minutes: 120,
+            collect_minutes: 60,
+            weekdays_only: true,
+            enabled: true,
+            last_prompt_date: None,
+            last_prompt_at: None,
+            last_summary_date: None,
+        };
+        db.set_standup_config(&config).await.unwrap();
+        db.mark_standup_prompted("g1", "2026-10-15", 1_000)
+            .await
+            .unwrap();
+
+        // Changing the schedule keeps the run history
+        db.set_standup_config(&StandupConfig {
+            collect_minutes: 90,
+            ..config.clone()
+        })
+        .await
+        .unwrap();
+        let saved = db.get_standup_config("g1").await.unwrap().unwrap();
+        assert_eq!(saved.collect_minutes, 90);
+        assert_eq!(saved.last_prompt_date.as_deref(), Some("2026-10-15"));
+
+        assert!(db.set_standup_member("g1", "u1", true).await.unwrap());
+        assert!(!db.set_standup_member("g1", "u1", true).await.unwrap());
+        assert!(db
+            .set_standup_skip("g1", "u1", Some("2026-10-20"))
+            .await
+            .unwrap());
+        assert!(!db.set_standup_skip("g1", "u2", None).await.unwrap());
+        let members = db.get_standup_members("g1").await.unwrap();
+        assert_eq!(members[0].skip_until.as_deref(), Some("2026-10-20"));
+
+        db.create_standup_entry("g1", "u1", "2026-10-15", false)
+            .await
+            .unwrap();
+        let mut entry = db.get_open_standup_entry("u1").await.unwrap().unwrap();
+        assert_eq!(entry.step, 0);
+        entry.yesterday = Some("Shipped the parser".to_string());
+        entry.step = 1;
+        db.save_standup_entry(&entry).await.unwrap();
+        let entries = db.get_standup_entries("g1", "2026-10-15").await.unwrap();
+        assert_eq!(entries[0].yesterday.as_deref(), Some("Shipped the parser"));
+
+        // Once the summary is out, late answers have nowhere to go
+        db.mark_standup_summarized("g1", "2026-10-15")
+            .await
+            .unwrap();
+        assert!(db.get_open_standup_entry("u1").await.unwrap().is_none());
+    }
 
-        let (total_cost, total_tokens, total_calls, first_seen, last_activity) =
-            if let Ok(State::Row) = usage_stmt.next() {
-                (
-                    usage_stmt.read::<f64, _>(0).unwrap_or(0.0),
-                    usage_stmt.read::<i64, _>(1).unwrap_or(0) as u64,
-                    usage_stmt.read::<i64, _>(2).unwrap_or(0) as u64,
-                    usage_stmt.read::<Option<String>, _>(3)?,
-                    usage_stmt.read::<Option<String>, _>(4)?,
-                )
-            } else {
-                (0.0, 0, 0, None, None)
-            };
+    #[tokio::test]
+    async fn test_meeting_poll_availability_and_close() {
+        let db = Database::new(":memory:").await.unwrap();
+        let poll = MeetingPoll {
+            id: 0,
+            guild_id: "g1".to_string(),
+            channel_id: "c1".to_string(),
+            organizer_id: "u1".to_string(),
+            title: "Planning".to_string(),
+            slots: vec![1_800_000_000, 1_800_003_600],
+            timezone: Zone::Named(chrono_tz::Tz::America__New_York),
+            duration_minutes: 45,
+            create_event: true,
+            remind_minutes: 15,
+            chosen_slot: None,
+        };
+        let id = db.create_meeting_poll(&poll).await.unwrap();
+        let stored = db.get_meeting_poll(id).await.unwrap().unwrap();
+        assert_eq!(stored, MeetingPoll { id, ..poll });
+
+        assert!(db.toggle_meeting_availability(id, 1, "u2").await.unwrap());
+        assert!(db.toggle_meeting_availability(id, 0, "u3").await.unwrap());
+        assert!(db.toggle_meeting_availability(id, 1, "u3").await.unwrap());
+        assert!(!db.toggle_meeting_availability(id, 0, "u3").await.unwrap());
+        assert_eq!(
+            db.get_meeting_availability(id, 2).await.unwrap(),
+            vec![vec![], vec!["u2".to_string(), "u3".to_string()]]
+        );
 
-        // Get DM session aggregate stats
-        drop(usage_stmt);
-        let mut dm_agg_stmt = conn.prepare(
-            "SELECT
-                COUNT(*) as session_count,
-                SUM(message_count) as total_messages
-             FROM dm_sessions
-             WHERE user_id = ?",
-        )?;
-        dm_agg_stmt.bind((1, user_id))?;
+        assert!(db.close_meeting_poll(id, 1).await.unwrap());
+        assert!(!db.close_meeting_poll(id, 0).await.unwrap());
+        let closed = db.get_meeting_poll(id).await.unwrap().unwrap();
+        assert_eq!(closed.chosen_slot, Some(1));
+
+        // Polls saved before zone names were stored keep their fixed offset
+        raw_execute(
+            &db,
+            &format!("UPDATE meeting_polls SET timezone = NULL, utc_offset_minutes = -300 WHERE id = {id}"),
+        )
+        .await;
+        let legacy = db.get_meeting_poll(id).await.unwrap().unwrap();
+        assert_eq!(legacy.timezone, Zone::Fixed(-300));
+    }
 
-        let (dm_session_count, message_count) = if let Ok(State::Row) = dm_agg_stmt.next() {
-            (
-                dm_agg_stmt.read::<i64, _>(0).unwrap_or(0) as u64,
-                dm_agg_stmt.read::<i64, _>(1).unwrap_or(0) as u64,
-            )
-        } else {
-            (0, 0)
+    #[tokio::test]
+    async fn test_issue_tracker_config_roundtrip() {
+        let db = Database::new(":memory:").await.unwrap();
+        assert_eq!(db.get_issue_tracker_config("g1").await.unwrap(), None);
+
+        let config = IssueTrackerConfig {
+            guild_id: "g1".to_string(),
+            provider: IssueProvider::Jira,
+            project: "OPS".to_string(),
+            api_token: "token".to_string(),
+            base_url: Some("https://acme.atlassian.net".to_string()),
+            email: Some("bot@acme.test".to_string()),
+            issue_type: None,
+            summarize: false,
         };
+        db.set_issue_tracker_config(&config).await.unwrap();
+        assert_eq!(
+            db.get_issue_tracker_config("g1").await.unwrap(),
+            Some(config.clone())
+        );
 
-        // Get DM session metrics aggregates
-        drop(dm_agg_stmt);
-        let mut metrics_stmt = conn.prepare(
-            "SELECT
-                SUM(sm.chat_calls) as chat_calls,
-                SUM(sm.whisper_calls) as whisper_calls,
-                SUM(sm.dalle_calls) as dalle_calls
-             FROM dm_session_metrics sm
-             JOIN dm_sessions s ON sm.session_id = s.session_id
-             WHERE s.user_id = ?",
-        )?;
-        metrics_stmt.bind((1, user_id))?;
+        let linear = IssueTrackerConfig {
+            provider: IssueProvider::Linear,
+            base_url: None,
+            email: None,
+            summarize: true,
+            ..config
+        };
+        db.set_issue_tracker_config(&linear).await.unwrap();
+        assert_eq!(
+            db.get_issue_tracker_config("g1").await.unwrap(),
+            Some(linear)
+        );
 
-        let (chat_calls, whisper_calls, dalle_calls) = if let Ok(State::Row) = metrics_stmt.next() {
-            (
-                metrics_stmt.read::<i64, _>(0).unwrap_or(0) as u64,
-                metrics_stmt.read::<i64, _>(1).unwrap_or(0) as u64,
-                metrics_stmt.read::<i64, _>(2).unwrap_or(0) as u64,
+        assert!(db.delete_issue_tracker_config("g1").await.unwrap());
+        assert!(!db.delete_issue_tracker_config("g1").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_guild_webhooks_roundtrip() {
+        let db = Database::new(":memory:").await.unwrap();
+        let id = db
+            .add_guild_webhook(
+                "g1",
+                "https://hooks.slack.test/a",
+                WebhookFormat::Slack,
+                &["job_completed", "budget_threshold"],
+                None,
+                "u1",
             )
-        } else {
-            (0, 0, 0)
-        };
+            .await
+            .unwrap();
+
+        let webhooks = db.get_guild_webhooks("g1").await.unwrap();
+        assert_eq!(webhooks.len(), 1);
+        assert_eq!(webhooks[0].id, id);
+        assert_eq!(webhooks[0].format, WebhookFormat::Slack);
+        assert_eq!(webhooks[0].events, ["job_completed", "budget_threshold"]);
+        assert!(db.get_guild_webhooks("g2").await.unwrap().is_empty());
+
+        db.set_webhook_last_error(id, Some("HTTP 500"))
+            .await
+            .unwrap();
+        assert_eq!(
+            db.get_guild_webhooks("g1").await.unwrap()[0].last_error,
+            Some("HTTP 500".to_string())
+        );
 
-        // Get favorite persona
-        drop(metrics_stmt);
-        let mut persona_stmt = conn.prepare(
-            "SELECT persona, COUNT(*) as cnt
-             FROM conversation_history
-             WHERE user_id = ? AND persona IS NOT NULL AND persona != ''
-             GROUP BY persona
-             ORDER BY cnt DESC
-             LIMIT 1",
-        )?;
-        persona_stmt.bind((1, user_id))?;
+        // Another guild can't remove it
+        assert!(!db.delete_guild_webhook("g2", id).await.unwrap());
+        assert!(db.delete_guild_webhook("g1", id).await.unwrap());
+        assert!(db.get_guild_webhooks("g1").await.unwrap().is_empty());
+    }
 
-        let favorite_persona = if let Ok(State::Row) = persona_stmt.next() {
-            persona_stmt.read::<Option<String>, _>(0)?
-        } else {
+    #[tokio::test]
+    async fn test_telegram_link_codes() {
+        let db = Database::new(":memory:").await.unwrap();
+        db.create_telegram_link_code("u1", "OLD111", "2999-01-01 00:00:00")
+            .await
+            .unwrap();
+        db.create_telegram_link_code("u1", "ABC123", "2999-01-01 00:00:00")
+            .await
+            .unwrap();
+        db.create_telegram_link_code("u2", "EXP000", "2000-01-01 00:00:00")
+            .await
+            .unwrap();
+
+        // Issuing a new code replaces the old one, and expired codes don't work
+        assert_eq!(
+            db.redeem_telegram_link_code("OLD111", "t1").await.unwrap(),
+            None
+        );
+        assert_eq!(
+            db.redeem_telegram_link_code("EXP000", "t1").await.unwrap(),
+            None
+        );
+        assert_eq!(
+            db.redeem_telegram_link_code("ABC123", "t1").await.unwrap(),
+            Some("u1".to_string())
+        );
+        assert_eq!(
+            db.redeem_telegram_link_code("ABC123", "t2").await.unwrap(),
             None
+        );
+        assert_eq!(
+            db.get_telegram_link("t1").await.unwrap(),
+            Some(("u1".to_string(), None))
+        );
+
+        db.store_message("u1", "100", "user", "hi", None)
+            .await
+            .unwrap();
+        db.store_message("u1", "telegram:5", "user", "hey", None)
+            .await
+            .unwrap();
+        let channel = db.get_latest_conversation_channel("u1").await.unwrap();
+        assert_eq!(channel.as_deref(), Some("100"));
+        db.set_telegram_continue_channel("t1", channel.as_deref())
+            .await
+            .unwrap();
+        assert_eq!(
+            db.get_telegram_link("t1").await.unwrap(),
+            Some(("u1".to_string(), Some("100".to_string())))
+        );
+
+        assert!(db.delete_telegram_link("t1").await.unwrap());
+        assert!(!db.delete_telegram_link("t1").await.unwrap());
+        assert_eq!(db.get_telegram_link("t1").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_bridge_reminders_stay_off_discord_scheduler() {
+        let db = Database::new(":memory:").await.unwrap();
+        db.add_reminder("u1", "100", "discord", "2000-01-01 00:00:00")
+            .await
+            .unwrap();
+        db.add_reminder("u1", "telegram:5", "telegram", "2000-01-01 00:00:00")
+            .await
+            .unwrap();
+
+        let discord = db.get_pending_reminders().await.unwrap();
+        assert_eq!(discord.len(), 1);
+        assert_eq!(discord[0].3, "discord");
+        let telegram = db.get_pending_bridge_reminders("telegram").await.unwrap();
+        assert_eq!(telegram.len(), 1);
+        assert_eq!(telegram[0].2, "telegram:5");
+    }
+
+    #[tokio::test]
+    async fn test_get_all_guild_settings() {
+        let db = Database::new(":memory:").await.unwrap();
+        db.set_guild_setting("g1", "b_key", "2").await.unwrap();
+        db.set_guild_setting("g1", "a_key", "1").await.unwrap();
+        db.set_guild_setting("g2", "a_key", "other").await.unwrap();
+
+        assert_eq!(
+            db.get_all_guild_settings("g1").await.unwrap(),
+            vec![
+                ("a_key".to_string(), "1".to_string()),
+                ("b_key".to_string(), "2".to_string())
+            ]
+        );
+        assert!(db.get_all_guild_settings("g3").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_category_persona_sits_between_channel_and_user() {
+        let db = Database::new(":memory:").await.unwrap();
+        db.set_guild_setting("g1", "default_persona", "obi")
+            .await
+            .unwrap();
+        db.set_user_persona_in_guild("u1", Some("g1"), "muppet")
+            .await
+            .unwrap();
+        db.set_category_persona("g1", "cooking", Some("chef"))
+            .await
+            .unwrap();
+
+        // Category beats the user's own preference
+        let persona = db
+            .get_persona_with_channel("u1", "g1", "c1", Some("cooking"))
+            .await
+            .unwrap();
+        assert_eq!(persona, "chef");
+
+        // Channels outside the category keep the normal cascade
+        let persona = db
+            .get_persona_with_channel("u1", "g1", "c2", Some("general"))
+            .await
+            .unwrap();
+        assert_eq!(persona, "muppet");
+
+        // A channel override still wins
+        db.set_channel_persona("g1", "c1", Some("pirate"))
+            .await
+            .unwrap();
+        let persona = db
+            .get_persona_with_channel("u1", "g1", "c1", Some("cooking"))
+            .await
+            .unwrap();
+        assert_eq!(persona, "pirate");
+
+        assert_eq!(
+            db.get_category_personas("g1").await.unwrap(),
+            vec![("cooking".to_string(), "chef".to_string())]
+        );
+        db.set_category_persona("g1", "cooking", None)
+            .await
+            .unwrap();
+        assert!(db.get_category_personas("g1").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fork_copies_history_without_touching_source() {
+        let db = Database::new(":memory:").await.unwrap();
+        db.store_guild_message(Some("g1"), "u1", "c1", "user", "Plan a trip", None)
+            .await
+            .unwrap();
+        db.store_guild_message(Some("g1"), "u1", "c1", "assistant", "Where to?", None)
+            .await
+            .unwrap();
+
+        let history = db.get_conversation_history("u1", "c1", 50).await.unwrap();
+        db.fork_conversation("t1", "c1", "u1", &history)
+            .await
+            .unwrap();
+
+        assert_eq!(db.get_fork_context("t1", 50).await.unwrap(), history);
+        assert_eq!(db.get_fork_context("t1", 1).await.unwrap(), history[1..]);
+        assert!(db.get_fork_context("c1", 50).await.unwrap().is_empty());
+
+        let fork = db.get_conversation_fork("t1").await.unwrap().unwrap();
+        assert_eq!(fork.source_channel_id, "c1");
+        assert_eq!(fork.message_count, 2);
+
+        // The original conversation and guild search are unchanged
+        assert_eq!(
+            db.get_conversation_history("u1", "c1", 50).await.unwrap(),
+            history
+        );
+        let hits = db
+            .search_messages("trip", Some("g1"), None, 10)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_undo_last_exchange_removes_latest_pair() {
+        let db = Database::new(":memory:").await.unwrap();
+        for (role, content) in [
+            ("user", "first question"),
+            ("assistant", "first answer"),
+            ("user", "bad prompt"),
+            ("assistant", "derailed answer"),
+        ] {
+            db.store_message("u1", "c1", role, content, None)
+                .await
+                .unwrap();
+        }
+        db.store_message("u2", "c1", "user", "someone else", None)
+            .await
+            .unwrap();
+
+        let removed = db.undo_last_exchange("u1", "c1").await.unwrap();
+        assert_eq!(
+            removed,
+            vec![
+                ("user".to_string(), "bad prompt".to_string()),
+                ("assistant".to_string(), "derailed answer".to_string()),
+            ]
+        );
+        assert_eq!(
+            db.get_conversation_history("u1", "c1", 10).await.unwrap(),
+            vec![
+                ("user".to_string(), "first question".to_string()),
+                ("assistant".to_string(), "first answer".to_string()),
+            ]
+        );
+        let other = db.get_conversation_history("u2", "c1", 10).await.unwrap();
+        assert_eq!(other.len(), 1);
+
+        db.undo_last_exchange("u1", "c1").await.unwrap();
+        assert!(db.undo_last_exchange("u1", "c1").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_server_activity() {
+        let db = Database::new(":memory:").await.unwrap();
+        for (user, channel, content) in [
+            ("u1", "c1", "release day"),
+            ("u2", "c1", "the release is out"),
+            ("u1", "c2", "lunch?"),
+        ] {
+            db.store_guild_message(Some("g1"), user, channel, "user", content, None)
+                .await
+                .unwrap();
+        }
+        db.store_guild_message(Some("g1"), "bot", "c1", "assistant", "Congrats!", None)
+            .await
+            .unwrap();
+        db.store_guild_message(Some("g2"), "u3", "c9", "user", "elsewhere", None)
+            .await
+            .unwrap();
+
+        let activity = db.get_server_activity("g1", 7).await.unwrap();
+        assert_eq!(activity.messages, 3);
+        assert_eq!(activity.previous_messages, 0);
+        assert_eq!(activity.active_members, 2);
+        assert_eq!(activity.top_channels[0], ("c1".to_string(), 2));
+        assert_eq!(activity.conflicts, 0);
+
+        let texts = db.get_recent_guild_message_texts("g1", 7, 2).await.unwrap();
+        assert_eq!(texts, vec!["lunch?", "the release is out"]);
+    }
+
+    #[tokio::test]
+    async fn test_channel_user_messages_since() {
+        let db = Database::new(":memory:").await.unwrap();
+        db.store_message("u1", "c1", "user", "first", None)
+            .await
+            .unwrap();
+        db.store_message("bot", "c1", "assistant", "reply", None)
+            .await
+            .unwrap();
+        db.store_message("u2", "c2", "user", "other channel", None)
+            .await
+            .unwrap();
+
+        let now = chrono::Utc::now().timestamp();
+        let messages = db
+            .get_channel_user_messages_since("c1", now - 60, 10)
+            .await
+            .unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].0, "first");
+        assert!((messages[0].1 - now).abs() < 60);
+
+        let future = db
+            .get_channel_user_messages_since("c1", now + 3600, 10)
+            .await
+            .unwrap();
+        assert!(future.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_channel_topic_schedules() {
+        let db = Database::new(":memory:").await.unwrap();
+        let mut schedule = ChannelTopicSchedule {
+            guild_id: "g1".to_string(),
+            channel_id: "c1".to_string(),
+            mode: "rotate".to_string(),
+            topics: vec!["Rust news".to_string(), "Show your setup".to_string()],
+            theme: None,
+            interval_hours: 24,
+            next_run_at: 1000,
+            next_index: 0,
+            last_topic: None,
+            created_by: "u1".to_string(),
         };
+        db.set_channel_topic_schedule(&schedule).await.unwrap();
+        schedule.channel_id = "c2".to_string();
+        schedule.mode = "question".to_string();
+        schedule.topics = Vec::new();
+        schedule.theme = Some("books".to_string());
+        schedule.next_run_at = 5000;
+        db.set_channel_topic_schedule(&schedule).await.unwrap();
+
+        let saved = db.get_channel_topic_schedules("g1").await.unwrap();
+        assert_eq!(saved.len(), 2);
+        assert_eq!(saved[0].topics, ["Rust news", "Show your setup"]);
+        assert_eq!(saved[1], schedule);
+
+        let due = db.get_due_channel_topic_schedules(2000).await.unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].channel_id, "c1");
+
+        db.advance_channel_topic_schedule("c1", 87_400, 1, Some("Rust news"))
+            .await
+            .unwrap();
+        // A failed update moves the schedule on without forgetting the last topic
+        db.advance_channel_topic_schedule("c1", 173_800, 1, None)
+            .await
+            .unwrap();
+        let saved = db.get_channel_topic_schedules("g1").await.unwrap();
+        assert_eq!(saved[1].channel_id, "c1");
+        assert_eq!(saved[1].next_run_at, 173_800);
+        assert_eq!(saved[1].next_index, 1);
+        assert_eq!(saved[1].last_topic.as_deref(), Some("Rust news"));
+
+        assert!(db.delete_channel_topic_schedule("g1", "c1").await.unwrap());
+        assert!(!db.delete_channel_topic_schedule("g1", "c1").await.unwrap());
+        assert!(!db.delete_channel_topic_schedule("g2", "c2").await.unwrap());
+        assert_eq!(db.get_channel_topic_schedules("g1").await.unwrap().len(), 1);
+    }
 
-        // Get cached username
-        drop(persona_stmt);
-        let mut username_stmt =
-            conn.prepare("SELECT username FROM user_cache WHERE user_id = ?")?;
-        username_stmt.bind((1, user_id))?;
-        let username = if let Ok(State::Row) = username_stmt.next() {
-            username_stmt.read::<Option<String>, _>(0)?
-        } else {
+    #[tokio::test]
+    async fn test_qotd_config() {
+        let db = Database::new(":memory:").await.unwrap();
+        assert_eq!(db.get_qotd_config("g1").await.unwrap(), None);
+
+        let mut config = QotdConfig {
+            guild_id: "g1".to_string(),
+            channel_id: "c1".to_string(),
+            hour: 9,
+            utc_offset_minutes: -300,
+            threads: true,
+            digest_channel_id: None,
+            next_run_at: 1000,
+            created_by: "u1".to_string(),
+        };
+        db.set_qotd_config(&config).await.unwrap();
+        config.digest_channel_id = Some("c2".to_string());
+        db.set_qotd_config(&config).await.unwrap();
+        assert_eq!(db.get_qotd_config("g1").await.unwrap(), Some(config));
+
+        assert!(db.get_due_qotd_configs(999).await.unwrap().is_empty());
+        assert_eq!(db.get_due_qotd_configs(1000).await.unwrap().len(), 1);
+        db.advance_qotd_config("g1", 87_400).await.unwrap();
+        assert!(db.get_due_qotd_configs(2000).await.unwrap().is_empty());
+
+        assert!(db.delete_qotd_config("g1").await.unwrap());
+        assert!(!db.delete_qotd_config("g1").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_qotd_queue() {
+        let db = Database::new(":memory:").await.unwrap();
+        let first = db
+            .add_qotd_question("g1", "Tabs or spaces?", "u1")
+            .await
+            .unwrap();
+        let second = db
+            .add_qotd_question("g1", "Best pizza topping?", "u2")
+            .await
+            .unwrap();
+        db.add_qotd_question("g2", "Elsewhere?", "u3")
+            .await
+            .unwrap();
+        assert_ne!(first, second);
+
+        let queue = db.get_qotd_queue("g1").await.unwrap();
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue[0].question, "Tabs or spaces?");
+
+        // Another guild's id can't be removed
+        let other = db.get_qotd_queue("g2").await.unwrap()[0].id;
+        assert_eq!(
+            db.remove_qotd_question("g1", Some(other)).await.unwrap(),
             None
+        );
+
+        let removed = db.remove_qotd_question("g1", Some(second)).await.unwrap();
+        assert_eq!(removed.unwrap().added_by, "u2");
+        let next = db.remove_qotd_question("g1", None).await.unwrap();
+        assert_eq!(next.unwrap().id, first);
+        assert_eq!(db.remove_qotd_question("g1", None).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_qotd_posts() {
+        let db = Database::new(":memory:").await.unwrap();
+        for (message_id, posted_at) in [("m1", 100), ("m2", 200)] {
+            db.record_qotd_post(&QotdPost {
+                guild_id: "g1".to_string(),
+                channel_id: "c1".to_string(),
+                message_id: message_id.to_string(),
+                thread_id: None,
+                question: format!("Question {message_id}"),
+                posted_at,
+            })
+            .await
+            .unwrap();
+        }
+        assert_eq!(
+            db.get_recent_qotd_questions("g1", 5).await.unwrap(),
+            ["Question m2", "Question m1"]
+        );
+
+        let answer = QotdAnswer {
+            user_id: "u1".to_string(),
+            content: "Spaces".to_string(),
+            reactions: 3,
         };
+        db.finish_qotd_post("m1", Some(&answer)).await.unwrap();
+        let pending = db.get_undigested_qotd_posts("g1").await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].message_id, "m2");
 
-        Ok(UserDetails {
-            user_id: user_id.to_string(),
-            username,
-            total_cost,
-            total_tokens,
-            total_calls,
-            dm_session_count,
-            message_count,
-            chat_calls,
-            whisper_calls,
-            dalle_calls,
-            first_seen,
-            last_activity,
-            favorite_persona,
-        })
+        db.finish_qotd_post("m2", None).await.unwrap();
+        assert!(db.get_undigested_qotd_posts("g1").await.unwrap().is_empty());
     }
 
-    /// Get DM sessions for a user
-    pub async fn get_user_dm_sessions(
-        &self,
-        user_id: &str,
-        limit: u32,
-    ) -> Result<Vec<DmSessionEntry>> {
-        let conn = self.connection.lock().await;
+    #[tokio::test]
+    async fn test_suggestions() {
+        let db = Database::new(":memory:").await.unwrap();
+        let first = db
+            .create_suggestion("g1", "c1", "u1", "Add a music channel")
+            .await
+            .unwrap();
+        let second = db
+            .create_suggestion("g1", "c1", "u2", "Weekly game night")
+            .await
+            .unwrap();
+        db.set_suggestion_message(first, "m1").await.unwrap();
+
+        assert_eq!(db.cast_suggestion_vote(first, "u2", 1).await.unwrap(), 1);
+        assert_eq!(db.cast_suggestion_vote(first, "u3", 1).await.unwrap(), 1);
+        // Switching sides, then withdrawing by voting the same way again
+        assert_eq!(db.cast_suggestion_vote(first, "u3", -1).await.unwrap(), -1);
+        assert_eq!(db.cast_suggestion_vote(first, "u4", -1).await.unwrap(), -1);
+        assert_eq!(db.cast_suggestion_vote(first, "u4", -1).await.unwrap(), 0);
+
+        let saved = db.get_suggestion(first).await.unwrap().unwrap();
+        assert_eq!(saved.message_id.as_deref(), Some("m1"));
+        assert_eq!(saved.status, "open");
+        assert_eq!((saved.upvotes, saved.downvotes), (1, 1));
+        assert_eq!(db.get_suggestion(999).await.unwrap(), None);
+
+        assert!(!db
+            .set_suggestion_status("g2", first, "accepted", None, "admin")
+            .await
+            .unwrap());
+        assert!(db
+            .set_suggestion_status("g1", first, "accepted", Some("Next week"), "admin")
+            .await
+            .unwrap());
+        let saved = db.get_suggestion(first).await.unwrap().unwrap();
+        assert_eq!(saved.status_note.as_deref(), Some("Next week"));
+        assert_eq!(saved.reviewed_by.as_deref(), Some("admin"));
+
+        let all = db.get_suggestions("g1", None, 10).await.unwrap();
+        assert_eq!(
+            all.iter().map(|s| s.id).collect::<Vec<_>>(),
+            [second, first]
+        );
+        let open = db.get_suggestions("g1", Some("open"), 10).await.unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].id, second);
 
-        let mut stmt = conn.prepare(
-            "SELECT
-                s.session_id,
-                s.started_at,
-                s.ended_at,
-                s.message_count,
-                COALESCE(m.total_api_cost_usd, 0) as api_cost,
-                COALESCE(m.total_tokens, 0) as total_tokens
-             FROM dm_sessions s
-             LEFT JOIN dm_session_metrics m ON s.session_id = m.session_id
-             WHERE s.user_id = ?
-             ORDER BY s.started_at DESC
-             LIMIT ?",
-        )?;
-        stmt.bind((1, user_id))?;
-        stmt.bind((2, limit as i64))?;
+        db.delete_suggestion(first).await.unwrap();
+        assert_eq!(db.get_suggestion(first).await.unwrap(), None);
+        assert_eq!(db.get_suggestions("g1", None, 10).await.unwrap().len(), 1);
+    }
 
-        let mut sessions = Vec::new();
-        while let Ok(State::Row) = stmt.next() {
-            sessions.push(DmSessionEntry {
-                session_id: stmt.read::<String, _>(0)?,
-                started_at: stmt.read::<String, _>(1)?,
-                ended_at: stmt.read::<Option<String>, _>(2)?,
-                message_count: stmt.read::<i64, _>(3)? as u32,
-                api_cost: stmt.read::<f64, _>(4)?,
-                total_tokens: stmt.read::<i64, _>(5)? as u64,
-            });
-        }
+    #[tokio::test]
+    async fn test_support_tickets() {
+        let db = Database::new(":memory:").await.unwrap();
+        assert_eq!(
+            db.get_support_stats("g1", 1_000_000).await.unwrap(),
+            SupportStats::default()
+        );
 
-        Ok(sessions)
+        let first = db
+            .create_support_ticket("g1", "t1", "u1", "Can't log in", 100_000)
+            .await
+            .unwrap();
+        db.create_support_ticket("g1", "t2", "u2", "Billing", 900_000)
+            .await
+            .unwrap();
+        db.mark_support_first_line(first).await.unwrap();
+
+        let ticket = db
+            .get_support_ticket_by_thread("t1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(ticket.id, first);
+        assert_eq!(ticket.status, "open");
+        assert!(ticket.first_line_answered);
+        assert_eq!(db.get_support_ticket_by_thread("t9").await.unwrap(), None);
+
+        assert!(db
+            .close_support_ticket(first, "mod", 103_600, Some("Reset the password"))
+            .await
+            .unwrap());
+        assert!(!db
+            .close_support_ticket(first, "mod", 200_000, None)
+            .await
+            .unwrap());
+        let ticket = db
+            .get_support_ticket_by_thread("t1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(ticket.status, "closed");
+        assert_eq!(ticket.closed_at, Some(103_600));
+        assert_eq!(ticket.summary.as_deref(), Some("Reset the password"));
+
+        let stats = db.get_support_stats("g1", 1_000_000).await.unwrap();
+        assert_eq!(
+            stats,
+            SupportStats {
+                open: 1,
+                closed: 1,
+                avg_resolution_secs: Some(3600),
+                first_line_answered: 1,
+                opened_last_week: 1,
+            }
+        );
     }
 
-    /// Get recent errors from error_logs
-    pub async fn get_recent_errors(&self, limit: u32) -> Result<Vec<ErrorLogEntry>> {
-        let conn = self.connection.lock().await;
+    #[tokio::test]
+    async fn test_support_kb() {
+        let db = Database::new(":memory:").await.unwrap();
+        let id = db
+            .add_support_kb_entry(
+                "g1",
+                "Password reset",
+                "Use the link on the login page.",
+                "u1",
+            )
+            .await
+            .unwrap();
+        db.add_support_kb_entry("g2", "Other", "Elsewhere", "u2")
+            .await
+            .unwrap();
+
+        let kb = db.get_support_kb("g1").await.unwrap();
+        assert_eq!(kb.len(), 1);
+        assert_eq!(kb[0].title, "Password reset");
+
+        assert!(!db.delete_support_kb_entry("g2", id).await.unwrap());
+        assert!(db.delete_support_kb_entry("g1", id).await.unwrap());
+        assert!(db.get_support_kb("g1").await.unwrap().is_empty());
+    }
 
-        let mut stmt = conn.prepare(
-            "SELECT id, error_type, error_message, stack_trace, user_id, channel_id, command, timestamp
-             FROM error_logs
-             ORDER BY timestamp DESC
-             LIMIT ?"
-        )?;
-        stmt.bind((1, limit as i64))?;
+    #[tokio::test]
+    async fn test_anon_questions() {
+        let db = Database::new(":memory:").await.unwrap();
+        let pending = db
+            .create_anon_question("g1", "u1", "Is the raid still on?", "pending", 100)
+            .await
+            .unwrap();
+        let posted = db
+            .create_anon_question("g1", "u2", "Who made the logo?", "posted", 200)
+            .await
+            .unwrap();
+        db.create_anon_question("g2", "u3", "Elsewhere", "pending", 300)
+            .await
+            .unwrap();
+
+        let queue = db.get_pending_anon_questions("g1", 10).await.unwrap();
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue[0].id, pending);
+        assert_eq!(queue[0].author_id, "u1");
+
+        assert!(!db
+            .review_anon_question("g1", posted, "rejected", "mod")
+            .await
+            .unwrap());
+        assert!(!db
+            .review_anon_question("g2", pending, "posted", "mod")
+            .await
+            .unwrap());
+        assert!(db
+            .review_anon_question("g1", pending, "posted", "mod")
+            .await
+            .unwrap());
+        db.set_anon_question_message(pending, "m1").await.unwrap();
+
+        let question = db.get_anon_question("g1", pending).await.unwrap().unwrap();
+        assert_eq!(question.status, "posted");
+        assert_eq!(question.reviewed_by.as_deref(), Some("mod"));
+        assert_eq!(question.message_id.as_deref(), Some("m1"));
+        assert!(db
+            .get_pending_anon_questions("g1", 10)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(db.get_anon_question("g2", pending).await.unwrap(), None);
+    }
 
-        let mut errors = Vec::new();
-        while let Ok(State::Row) = stmt.next() {
-            errors.push(ErrorLogEntry {
-                id: stmt.read::<i64, _>(0)?,
-                error_type: stmt.read::<String, _>(1)?,
-                error_message: stmt.read::<String, _>(2)?,
-                stack_trace: stmt.read::<Option<String>, _>(3)?,
-                user_id: stmt.read::<Option<String>, _>(4)?,
-                channel_id: stmt.read::<Option<String>, _>(5)?,
-                command: stmt.read::<Option<String>, _>(6)?,
-                timestamp: stmt.read::<String, _>(7)?,
-            });
-        }
+    #[tokio::test]
+    async fn test_pending_verifications() {
+        let db = Database::new(":memory:").await.unwrap();
+        let mut pending = PendingVerification {
+            guild_id: "g1".to_string(),
+            user_id: "u1".to_string(),
+            channel_id: "c1".to_string(),
+            message_id: Some("m1".to_string()),
+            answer: Some("7".to_string()),
+            deadline: Some(1_000),
+        };
+        db.upsert_pending_verification(&pending).await.unwrap();
+        db.upsert_pending_verification(&PendingVerification {
+            user_id: "u2".to_string(),
+            answer: None,
+            deadline: None,
+            ..pending.clone()
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            db.get_pending_verification("g1", "u1").await.unwrap(),
+            Some(pending.clone())
+        );
+        assert_eq!(db.count_pending_verifications("g1").await.unwrap(), 2);
+        assert!(db.get_expired_verifications(999).await.unwrap().is_empty());
+        let expired = db.get_expired_verifications(1_000).await.unwrap();
+        assert_eq!(expired, vec![pending.clone()]);
+
+        pending.deadline = Some(5_000);
+        db.upsert_pending_verification(&pending).await.unwrap();
+        assert!(db
+            .get_expired_verifications(1_000)
+            .await
+            .unwrap()
+            .is_empty());
+
+        assert!(db.delete_pending_verification("g1", "u1").await.unwrap());
+        assert!(!db.delete_pending_verification("g1", "u1").await.unwrap());
+        assert_eq!(db.get_pending_verification("g1", "u1").await.unwrap(), None);
+        assert_eq!(db.count_pending_verifications("g1").await.unwrap(), 1);
+        assert_eq!(db.clear_pending_verifications("g1").await.unwrap(), 1);
+        assert_eq!(db.count_pending_verifications("g1").await.unwrap(), 0);
+    }
 
-        Ok(errors)
+    #[tokio::test]
+    async fn test_autoresponses() {
+        let db = Database::new(":memory:").await.unwrap();
+        let trigger = |guild: &str, pattern: &str, priority: i64| AutoResponse {
+            id: 0,
+            guild_id: guild.to_string(),
+            pattern: pattern.to_string(),
+            match_kind: "keyword".to_string(),
+            response: "See #faq".to_string(),
+            response_kind: "reply".to_string(),
+            cooldown_secs: 60,
+            enabled: true,
+            priority,
+            created_by: "admin".to_string(),
+        };
+        let low = db.add_autoresponse(&trigger("g1", "faq", 0)).await.unwrap();
+        let high = db
+            .add_autoresponse(&trigger("g1", "rules", 5))
+            .await
+            .unwrap();
+        db.add_autoresponse(&trigger("g2", "other", 0))
+            .await
+            .unwrap();
+
+        let ids: Vec<i64> = db
+            .get_autoresponses("g1")
+            .await
+            .unwrap()
+            .iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(ids, [high, low]);
+        assert_eq!(db.count_autoresponses("g1").await.unwrap(), 2);
+
+        assert!(db.set_autoresponse_priority("g1", low, 10).await.unwrap());
+        assert!(db
+            .set_autoresponse_enabled("g1", high, false)
+            .await
+            .unwrap());
+        assert!(!db
+            .set_autoresponse_enabled("g2", high, false)
+            .await
+            .unwrap());
+        let triggers = db.get_autoresponses("g1").await.unwrap();
+        assert_eq!(triggers[0].id, low);
+        assert_eq!(triggers[0].priority, 10);
+        assert!(!triggers[1].enabled);
+
+        assert!(!db.remove_autoresponse("g2", low).await.unwrap());
+        assert!(db.remove_autoresponse("g1", low).await.unwrap());
+        assert_eq!(db.count_autoresponses("g1").await.unwrap(), 1);
+        assert_eq!(db.get_autoresponses("g1").await.unwrap().len(), 1);
+
+        // Triggers are cached per guild; out-of-band edits wait for the TTL
+        raw_execute(&db, "DELETE FROM autoresponses WHERE guild_id = 'g1'").await;
+        assert_eq!(db.get_autoresponses("g1").await.unwrap().len(), 1);
+        db.clear_settings_cache();
+        assert!(db.get_autoresponses("g1").await.unwrap().is_empty());
     }
 
-    /// Get historical performance metrics
-    pub async fn get_historical_metrics(
-        &self,
-        metric_type: &str,
-        hours: u32,
-    ) -> Result<Vec<(i64, f64)>> {
-        let conn = self.connection.lock().await;
+    #[tokio::test]
+    async fn test_prompt_aliases() {
+        let db = Database::new(":memory:").await.unwrap();
+        let alias = |scope: &str, scope_id: &str, name: &str, template: &str| PromptAlias {
+            scope: scope.to_string(),
+            scope_id: scope_id.to_string(),
+            name: name.to_string(),
+            template: template.to_string(),
+            persona: None,
+            created_by: "u1".to_string(),
+        };
+        db.upsert_prompt_alias(&alias("user", "u1", "tldr", "Summarize ${text}"))
+            .await
+            .unwrap();
+        db.upsert_prompt_alias(&alias("user", "u1", "eli5", "Explain ${topic}"))
+            .await
+            .unwrap();
+        db.upsert_prompt_alias(&alias("guild", "g1", "tldr", "Shared ${text}"))
+            .await
+            .unwrap();
+
+        let names: Vec<String> = db
+            .get_prompt_aliases("user", "u1")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|a| a.name)
+            .collect();
+        assert_eq!(names, ["eli5", "tldr"]);
+        assert_eq!(db.count_prompt_aliases("guild", "g1").await.unwrap(), 1);
+
+        // Saving under an existing name replaces the template
+        let mut updated = alias("user", "u1", "tldr", "Shorten ${text}");
+        updated.persona = Some("obi".to_string());
+        db.upsert_prompt_alias(&updated).await.unwrap();
+        assert_eq!(
+            db.get_prompt_alias("user", "u1", "tldr").await.unwrap(),
+            Some(updated)
+        );
+        assert_eq!(db.count_prompt_aliases("user", "u1").await.unwrap(), 2);
+
+        assert!(db.delete_prompt_alias("user", "u1", "tldr").await.unwrap());
+        assert!(!db.delete_prompt_alias("user", "u1", "tldr").await.unwrap());
+        assert!(db
+            .get_prompt_alias("guild", "g1", "tldr")
+            .await
+            .unwrap()
+            .is_some());
+    }
 
-        let mut stmt = conn.prepare(
-            "SELECT
-                strftime('%s', timestamp) as ts,
-                value
-             FROM performance_metrics
-             WHERE metric_type = ?
-             AND timestamp >= datetime('now', ? || ' hours')
-             ORDER BY timestamp ASC",
-        )?;
-        stmt.bind((1, metric_type))?;
-        stmt.bind((2, format!("-{hours}").as_str()))?;
+    #[tokio::test]
+    async fn test_guild_prompts() {
+        let db = Database::new(":memory:").await.unwrap();
+        let prompt = |guild: &str, name: &str, category: &str| GuildPrompt {
+            guild_id: guild.to_string(),
+            name: name.to_string(),
+            category: category.to_string(),
+            description: None,
+            template: "Write release notes for ${version}".to_string(),
+            created_by: "u1".to_string(),
+            uses: 0,
+        };
+        db.upsert_guild_prompt(&prompt("g1", "notes", "writing"))
+            .await
+            .unwrap();
+        db.upsert_guild_prompt(&prompt("g1", "logo", "art"))
+            .await
+            .unwrap();
+        db.upsert_guild_prompt(&prompt("g2", "notes", "writing"))
+            .await
+            .unwrap();
+
+        let names: Vec<String> = db
+            .get_guild_prompts("g1", None)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(names, ["logo", "notes"]);
+        assert_eq!(
+            db.get_guild_prompts("g1", Some("art")).await.unwrap().len(),
+            1
+        );
+        assert_eq!(db.count_guild_prompts("g1").await.unwrap(), 2);
+
+        db.record_guild_prompt_use("g1", "notes").await.unwrap();
+        db.record_guild_prompt_use("g1", "notes").await.unwrap();
+        // Replacing a prompt keeps its use count
+        let mut updated = prompt("g1", "notes", "docs");
+        updated.description = Some("Changelog entries".to_string());
+        db.upsert_guild_prompt(&updated).await.unwrap();
+        let stored = db.get_guild_prompt("g1", "notes").await.unwrap().unwrap();
+        assert_eq!(stored.category, "docs");
+        assert_eq!(stored.description.as_deref(), Some("Changelog entries"));
+        assert_eq!(stored.uses, 2);
+        assert_eq!(
+            db.get_guild_prompt("g2", "notes")
+                .await
+                .unwrap()
+                .unwrap()
+                .uses,
+            0
+        );
 
-        let mut data_points = Vec::new();
-        while let Ok(State::Row) = stmt.next() {
-            let ts = stmt.read::<String, _>(0)?.parse::<i64>().unwrap_or(0);
-            let value = stmt.read::<f64, _>(1)?;
-            data_points.push((ts, value));
-        }
+        assert!(db.delete_guild_prompt("g1", "notes").await.unwrap());
+        assert!(!db.delete_guild_prompt("g1", "notes").await.unwrap());
+    }
 
-        Ok(data_points)
+    #[tokio::test]
+    async fn test_user_channel_messages() {
+        let db = Database::new(":memory:").await.unwrap();
+        db.store_message("u1", "c1", "user", "Hello there", None)
+            .await
+            .unwrap();
+        db.store_message("u1", "c1", "assistant", "General Kenobi", Some("obi"))
+            .await
+            .unwrap();
+        db.store_message("u2", "c1", "user", "Someone else", None)
+            .await
+            .unwrap();
+        db.store_message("u1", "c2", "user", "Elsewhere", None)
+            .await
+            .unwrap();
+
+        let messages = db.get_user_channel_messages("u1", "c1", 10).await.unwrap();
+        let contents: Vec<&str> = messages.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, ["Hello there", "General Kenobi"]);
+        assert_eq!(messages[1].persona.as_deref(), Some("obi"));
+
+        let latest = db.get_user_channel_messages("u1", "c1", 1).await.unwrap();
+        assert_eq!(latest[0].content, "General Kenobi");
     }
 
-    /// Get daily cost trend for sparkline
-    pub async fn get_daily_cost_trend(&self, days: u32) -> Result<Vec<(String, f64)>> {
-        let conn = self.connection.lock().await;
+    #[tokio::test]
+    async fn test_scheduled_messages() {
+        let db = Database::new(":memory:").await.unwrap();
+        let message = |user: &str, content: &str, send_at: i64| ScheduledMessage {
+            id: 0,
+            guild_id: "g1".to_string(),
+            channel_id: "c1".to_string(),
+            user_id: user.to_string(),
+            content: content.to_string(),
+            persona: None,
+            send_at,
+        };
+        let later = db
+            .add_scheduled_message(&message("u1", "Later", 2000))
+            .await
+            .unwrap();
+        let soon = db
+            .add_scheduled_message(&ScheduledMessage {
+                persona: Some("obi".to_string()),
+                ..message("u1", "Soon", 1000)
+            })
+            .await
+            .unwrap();
+        db.add_scheduled_message(&message("u2", "Theirs", 1500))
+            .await
+            .unwrap();
+
+        let due = db.get_due_scheduled_messages(1500).await.unwrap();
+        let contents: Vec<&str> = due.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, ["Soon", "Theirs"]);
+        assert_eq!(due[0].persona.as_deref(), Some("obi"));
+
+        let mine = db.get_scheduled_messages("g1", Some("u1")).await.unwrap();
+        assert_eq!(mine.iter().map(|m| m.id).collect::<Vec<_>>(), [soon, later]);
+        assert_eq!(
+            db.get_scheduled_messages("g1", None).await.unwrap().len(),
+            3
+        );
+        assert_eq!(db.count_scheduled_messages("g1", "u1").await.unwrap(), 2);
+
+        // Members can only cancel their own; managers pass no user
+        assert!(!db
+            .delete_scheduled_message(later, "g1", Some("u2"))
+            .await
+            .unwrap());
+        assert!(!db
+            .delete_scheduled_message(later, "g2", None)
+            .await
+            .unwrap());
+        assert!(db
+            .delete_scheduled_message(later, "g1", Some("u1"))
+            .await
+            .unwrap());
+        assert!(db.delete_scheduled_message(soon, "g1", None).await.unwrap());
+        assert_eq!(db.count_scheduled_messages("g1", "u1").await.unwrap(), 0);
+    }
 
-        let mut stmt = conn.prepare(
-            "SELECT date, SUM(total_cost_usd) as cost
-             FROM openai_usage_daily
-             WHERE date >= date('now', ? || ' days')
-             GROUP BY date
-             ORDER BY date ASC",
-        )?;
-        stmt.bind((1, format!("-{days}").as_str()))?;
+    #[tokio::test]
+    async fn test_countdowns() {
+        let db = Database::new(":memory:").await.unwrap();
+        let countdown = |guild: &str, title: &str, ends_at: i64| Countdown {
+            id: 0,
+            guild_id: guild.to_string(),
+            channel_id: "c1".to_string(),
+            message_id: None,
+            title: title.to_string(),
+            ends_at,
+            role_id: None,
+            created_by: "u1".to_string(),
+        };
+        let launch = db
+            .add_countdown(&Countdown {
+                role_id: Some("r1".to_string()),
+                ..countdown("g1", "Launch", 2000)
+            })
+            .await
+            .unwrap();
+        let party = db
+            .add_countdown(&countdown("g1", "Party", 1000))
+            .await
+            .unwrap();
+        db.add_countdown(&countdown("g2", "Elsewhere", 500))
+            .await
+            .unwrap();
+
+        // Only posted countdowns are updated
+        assert!(db.get_active_countdowns().await.unwrap().is_empty());
+        db.set_countdown_message(launch, "m1").await.unwrap();
+        db.set_countdown_message(party, "m2").await.unwrap();
+        let active = db.get_active_countdowns().await.unwrap();
+        assert_eq!(
+            active.iter().map(|c| c.id).collect::<Vec<_>>(),
+            [party, launch]
+        );
 
-        let mut trend = Vec::new();
-        while let Ok(State::Row) = stmt.next() {
-            let date = stmt.read::<String, _>(0)?;
-            let cost = stmt.read::<f64, _>(1)?;
-            trend.push((date, cost));
-        }
+        let stored = db.get_countdown(launch).await.unwrap().unwrap();
+        assert_eq!(stored.message_id.as_deref(), Some("m1"));
+        assert_eq!(stored.role_id.as_deref(), Some("r1"));
+        assert_eq!(db.get_guild_countdowns("g1").await.unwrap().len(), 2);
+        assert_eq!(db.count_countdowns("g2").await.unwrap(), 1);
 
-        Ok(trend)
+        assert!(db.delete_countdown(party).await.unwrap());
+        assert!(!db.delete_countdown(party).await.unwrap());
+        assert!(db.get_countdown(party).await.unwrap().is_none());
+        assert_eq!(db.count_countdowns("g1").await.unwrap(), 1);
     }
 
-    /// Log a performance metric
-    pub async fn log_performance_metric(
-        &self,
-        metric_type: &str,
-        value: f64,
-        unit: Option<&str>,
-        metadata: Option<&str>,
-    ) -> Result<()> {
-        let conn = self.connection.lock().await;
-        let mut stmt = conn.prepare(
-            "INSERT INTO performance_metrics (metric_type, value, unit, metadata) VALUES (?, ?, ?, ?)"
-        )?;
-        stmt.bind((1, metric_type))?;
-        stmt.bind((2, value))?;
-        stmt.bind((3, unit.unwrap_or("")))?;
-        stmt.bind((4, metadata.unwrap_or("")))?;
-        stmt.next()?;
-        Ok(())
+    #[tokio::test]
+    async fn test_initiative() {
+        let db = Database::new(":memory:").await.unwrap();
+        let entry = |name: &str, initiative: i64, modifier: i64| InitiativeEntry {
+            thread_id: "t1".to_string(),
+            guild_id: "g1".to_string(),
+            name: name.to_string(),
+            initiative,
+            modifier,
+            added_by: "u1".to_string(),
+        };
+        db.upsert_initiative_entry(&entry("Goblin", 12, 2))
+            .await
+            .unwrap();
+        db.upsert_initiative_entry(&entry("Aria", 18, 3))
+            .await
+            .unwrap();
+        db.upsert_initiative_entry(&entry("Bram", 12, 4))
+            .await
+            .unwrap();
+        // Same name, any case, replaces the combatant
+        db.upsert_initiative_entry(&entry("goblin", 9, 2))
+            .await
+            .unwrap();
+
+        let order = db.get_initiative_order("t1").await.unwrap();
+        let names: Vec<&str> = order.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["Aria", "Bram", "goblin"]);
+        assert!(db.get_initiative_order("t2").await.unwrap().is_empty());
+
+        assert_eq!(db.get_initiative_turn("t1").await.unwrap(), (0, 1));
+        db.set_initiative_turn("t1", 2, 3).await.unwrap();
+        assert_eq!(db.get_initiative_turn("t1").await.unwrap(), (2, 3));
+
+        assert!(db.delete_initiative_entry("t1", "BRAM").await.unwrap());
+        assert!(!db.delete_initiative_entry("t1", "Bram").await.unwrap());
+        assert_eq!(db.clear_initiative("t1").await.unwrap(), 2);
+        assert!(db.get_initiative_order("t1").await.unwrap().is_empty());
+        assert_eq!(db.get_initiative_turn("t1").await.unwrap(), (0, 1));
     }
 
-    /// Get channel message history from conversation_history
-    pub async fn get_channel_messages(
-        &self,
-        channel_id: &str,
-        limit: u32,
-    ) -> Result<Vec<ConversationMessage>> {
-        let conn = self.connection.lock().await;
-
-        let mut stmt = conn.prepare(
-            "SELECT user_id, role, content, persona, timestamp
-             FROM conversation_history
-             WHERE channel_id = ?
-             ORDER BY timestamp DESC
-             LIMIT ?",
-        )?;
-        stmt.bind((1, channel_id))?;
-        stmt.bind((2, limit as i64))?;
+    #[tokio::test]
+    async fn test_persona_resolution_respects_guardrails() {
+        let db = Database::new(":memory:").await.unwrap();
+        db.set_channel_persona("g1", "c1", Some("noir"))
+            .await
+            .unwrap();
+        db.set_guild_setting("g1", "default_persona", "noir")
+            .await
+            .unwrap();
+        assert_eq!(
+            db.get_persona_with_channel("u1", "g1", "c1", None)
+                .await
+                .unwrap(),
+            "noir"
+        );
 
-        let mut messages = Vec::new();
-        while let Ok(State::Row) = stmt.next() {
-            messages.push(ConversationMessage {
-                user_id: stmt.read::<String, _>(0)?,
-                role: stmt.read::<String, _>(1)?,
-                content: stmt.read::<String, _>(2)?,
-                persona: stmt.read::<Option<String>, _>(3)?,
-                timestamp: stmt.read::<String, _>(4)?,
-            });
-        }
+        db.set_guild_setting("g1", "guardrail_profile", "strict")
+            .await
+            .unwrap();
+        assert_eq!(
+            db.get_persona_with_channel("u1", "g1", "c1", None)
+                .await
+                .unwrap(),
+            "obi"
+        );
+        assert_eq!(
+            db.get_user_persona_with_guild("u1", Some("g1"))
+                .await
+                .unwrap(),
+            "obi"
+        );
+    }
 
-        // Reverse to get chronological order
-        messages.reverse();
-        Ok(messages)
+    #[tokio::test]
+    async fn test_dm_session_titles_and_messages() {
+        let db = Database::new(":memory:").await.unwrap();
+        db.create_dm_session("s1", "u1", "dm1").await.unwrap();
+        db.store_message("u1", "dm1", "user", "How do I brine a turkey?", None)
+            .await
+            .unwrap();
+        db.store_message("u1", "dm1", "assistant", "Salt, water, time.", Some("chef"))
+            .await
+            .unwrap();
+        db.store_message("u1", "other", "user", "Not this one", None)
+            .await
+            .unwrap();
+
+        let sessions = db.get_dm_session_summaries("u1", 10).await.unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].title, None);
+        assert!(!db.has_dm_session_title("s1").await.unwrap());
+
+        db.set_dm_session_title("s1", "Brining a turkey")
+            .await
+            .unwrap();
+        db.set_dm_session_title("s1", "Something else")
+            .await
+            .unwrap();
+        assert!(db.has_dm_session_title("s1").await.unwrap());
+        let session = db
+            .get_dm_session_summary("u1", "s1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(session.title.as_deref(), Some("Brining a turkey"));
+        assert!(db
+            .get_dm_session_summary("u2", "s1")
+            .await
+            .unwrap()
+            .is_none());
+
+        let messages = db.get_dm_session_messages("u1", "s1", 10).await.unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].0, "user");
+        assert_eq!(messages[1].1, "Salt, water, time.");
+        assert!(db
+            .get_dm_session_messages("u2", "s1", 10)
+            .await
+            .unwrap()
+            .is_empty());
     }
 
-    /// Get channels with conversation history, optionally filtered by guild
-    /// Returns channels from channel_settings with message counts from conversation_history
-    pub async fn get_channels_with_history(
-        &self,
-        guild_id: Option<&str>,
-    ) -> Result<Vec<ChannelHistoryEntry>> {
-        let conn = self.connection.lock().await;
+    #[tokio::test]
+    async fn test_pinned_memories() {
+        let db = Database::new(":memory:").await.unwrap();
+        assert!(db
+            .add_pinned_memory("u1", "c1", "m1", "My cat is called Miso", "Ada")
+            .await
+            .unwrap());
+        assert!(!db
+            .add_pinned_memory("u1", "c1", "m1", "My cat is called Miso", "Ada")
+            .await
+            .unwrap());
+        db.add_pinned_memory("u1", "c2", "m2", "Deploys are on Fridays", "Obi")
+            .await
+            .unwrap();
+        db.add_pinned_memory("u2", "c1", "m1", "Someone else's", "Ada")
+            .await
+            .unwrap();
+
+        let here = db.get_pinned_memories("u1", Some("c1")).await.unwrap();
+        assert_eq!(here.len(), 1);
+        assert_eq!(here[0].content, "My cat is called Miso");
+        assert_eq!(db.get_pinned_memories("u1", None).await.unwrap().len(), 2);
+
+        assert!(!db.delete_pinned_memory("u2", here[0].id).await.unwrap());
+        assert!(db.delete_pinned_memory("u1", here[0].id).await.unwrap());
+        assert_eq!(db.clear_pinned_memories("u1", None).await.unwrap(), 1);
+        assert_eq!(db.get_pinned_memories("u2", None).await.unwrap().len(), 1);
+    }
 
-        // Query channels that have settings OR have conversation history
-        // The channel_settings table has guild_id, so we can filter by guild
-        let query = if guild_id.is_some() {
-            "SELECT
-                cs.channel_id,
-                cs.guild_id,
-                COUNT(ch.id) as message_count,
-                MAX(ch.timestamp) as last_activity
-             FROM channel_settings cs
-             LEFT JOIN conversation_history ch ON cs.channel_id = ch.channel_id
-             WHERE cs.guild_id = ?
-             GROUP BY cs.guild_id, cs.channel_id
-             ORDER BY last_activity DESC NULLS LAST"
-        } else {
-            // Get all channels with history (for browsing all)
-            "SELECT
-                COALESCE(cs.channel_id, ch.channel_id) as channel_id,
-                cs.guild_id,
-                COUNT(ch.id) as message_count,
-                MAX(ch.timestamp) as last_activity
-             FROM (
-                 SELECT DISTINCT channel_id FROM conversation_history
-             ) ch_ids
-             LEFT JOIN channel_settings cs ON ch_ids.channel_id = cs.channel_id
-             LEFT JOIN conversation_history ch ON ch_ids.channel_id = ch.channel_id
-             GROUP BY ch_ids.channel_id, cs.guild_id
-             ORDER BY last_activity DESC NULLS LAST"
-        };
+    #[tokio::test]
+    async fn test_channel_scene() {
+        let db = Database::new(":memory:").await.unwrap();
+        assert_eq!(db.get_channel_scene("g1", "c1").await.unwrap(), None);
+
+        db.set_channel_verbosity("g1", "c1", "detailed")
+            .await
+            .unwrap();
+        db.set_channel_scene("g1", "c1", Some("A rain-soaked port city"))
+            .await
+            .unwrap();
+        assert_eq!(
+            db.get_channel_scene("g1", "c1").await.unwrap().as_deref(),
+            Some("A rain-soaked port city")
+        );
+        assert_eq!(db.get_channel_scene("g1", "c2").await.unwrap(), None);
 
-        let mut stmt = conn.prepare(query)?;
+        db.set_channel_scene("g1", "c1", None).await.unwrap();
+        assert_eq!(db.get_channel_scene("g1", "c1").await.unwrap(), None);
+        let (verbosity, _, _) = db.get_channel_settings("g1", "c1").await.unwrap();
+        assert_eq!(verbosity, "detailed");
+    }
 
-        if let Some(gid) = guild_id {
-            stmt.bind((1, gid))?;
-        }
+    #[tokio::test]
+    async fn test_glossary_terms() {
+        let db = Database::new(":memory:").await.unwrap();
+        let entry = |guild: &str, term: &str, definition: &str| GlossaryEntry {
+            guild_id: guild.to_string(),
+            term: term.to_string(),
+            definition: definition.to_string(),
+            created_by: "u1".to_string(),
+        };
+        assert!(db
+            .upsert_glossary_term(&entry("g1", "PvP", "Player versus player"))
+            .await
+            .unwrap());
+        assert!(db
+            .upsert_glossary_term(&entry("g1", "Nexus", "Our staging server"))
+            .await
+            .unwrap());
+        assert!(db
+            .upsert_glossary_term(&entry("g2", "PvP", "Pay via PayPal"))
+            .await
+            .unwrap());
+
+        // Terms are unique per guild regardless of case
+        assert!(!db
+            .upsert_glossary_term(&entry("g1", "PVP", "Player vs player combat"))
+            .await
+            .unwrap());
+        let terms = db.get_glossary_terms("g1").await.unwrap();
+        assert_eq!(terms.len(), 2);
+        assert_eq!(terms[0].term, "Nexus");
+        assert_eq!(terms[1].term, "PVP");
+        assert_eq!(terms[1].definition, "Player vs player combat");
+        assert_eq!(db.count_glossary_terms("g1").await.unwrap(), 2);
+
+        assert!(db.delete_glossary_term("g1", "pvp").await.unwrap());
+        assert!(!db.delete_glossary_term("g1", "pvp").await.unwrap());
+        assert_eq!(db.count_glossary_terms("g1").await.unwrap(), 1);
+        assert_eq!(db.count_glossary_terms("g2").await.unwrap(), 1);
+    }
 
-        let mut entries = Vec::new();
-        while let Ok(State::Row) = stmt.next() {
-            entries.push(ChannelHistoryEntry {
-                channel_id: stmt.read::<String, _>(0)?,
-                guild_id: stmt.read::<Option<String>, _>(1)?,
-                message_count: stmt.read::<i64, _>(2)?,
-                last_activity: stmt.read::<Option<String>, _>(3)?,
-            });
-        }
+    #[tokio::test]
+    async fn test_channel_columns_cached_and_invalidated_on_write() {
+        let db = Database::new(":memory:").await.unwrap();
+        assert!(!db.get_channel_sentiment_tracking("g1", "c1").await.unwrap());
+        db.set_channel_sentiment_tracking("g1", "c1", true)
+            .await
+            .unwrap();
+        db.set_channel_scene("g1", "c1", Some("A tavern"))
+            .await
+            .unwrap();
+        assert!(db.get_channel_sentiment_tracking("g1", "c1").await.unwrap());
+        assert_eq!(
+            db.get_channel_scene("g1", "c1").await.unwrap(),
+            Some("A tavern".to_string())
+        );
 
-        Ok(entries)
+        // Out-of-band edits are hidden until the entry expires
+        raw_execute(
+            &db,
+            "UPDATE channel_settings SET sentiment_tracking = 0, scene = NULL",
+        )
+        .await;
+        assert!(db.get_channel_sentiment_tracking("g1", "c1").await.unwrap());
+
+        // Each setter invalidates only its own column
+        db.set_channel_scene("g1", "c1", Some("A ship"))
+            .await
+            .unwrap();
+        assert!(db.get_channel_sentiment_tracking("g1", "c1").await.unwrap());
+        assert_eq!(
+            db.get_channel_scene("g1", "c1").await.unwrap(),
+            Some("A ship".to_string())
+        );
+        db.set_channel_time_detection("g1", "c1", true)
+            .await
+            .unwrap();
+        assert!(db.get_channel_time_detection("g1", "c1").await.unwrap());
+        db.set_channel_link_previews("g1", "c1", "thread")
+            .await
+            .unwrap();
+        assert_eq!(
+            db.get_channel_link_previews("g1", "c1").await.unwrap(),
+            "thread"
+        );
     }
-}
-
-/// User list entry for TUI
-#[derive(Debug, Clone)]
-pub struct UserListEntry {
-    pub user_id: String,
-    pub username: Option<String>,
-    pub total_cost: f64,
-    pub total_tokens: u64,
-    pub total_calls: u64,
-    pub dm_session_count: u64,
-    pub last_activity: Option<String>,
-}
 
-/// Detailed user info for TUI
-#[derive(Debug, Clone)]
-pub struct UserDetails {
-    pub user_id: String,
-    pub username: Option<String>,
-    pub total_cost: f64,
-    pub total_tokens: u64,
-    pub total_calls: u64,
-    pub dm_session_count: u64,
-    pub message_count: u64,
-    pub chat_calls: u64,
-    pub whisper_calls: u64,
-    pub dalle_calls: u64,
-    pub first_seen: Option<String>,
-    pub last_activity: Option<String>,
-    pub favorite_persona: Option<String>,
-}
+    #[tokio::test]
+    async fn test_command_usage_daily_rebuilt_with_guild() {
+        let db = Database::new(":memory:").await.unwrap();
+        raw_execute(&db, "DROP TABLE command_usage_daily").await;
+        raw_execute(
+            &db,
+            "CREATE TABLE command_usage_daily (
+                date TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                command TEXT NOT NULL,
+                persona TEXT NOT NULL DEFAULT '',
+                invocations INTEGER DEFAULT 0,
+                UNIQUE(date, user_id, command, persona)
+            )",
+        )
+        .await;
+        raw_execute(
+            &db,
+            "INSERT INTO command_usage_daily VALUES ('2026-01-01', 'u1', 'ask', '', 4)",
+        )
+        .await;
+
+        db.init_tables().await.unwrap();
+        // Old days are kept outside any guild
+        let profile = db.get_user_profile("u1", "g1", 3).await.unwrap();
+        assert!(profile.top_commands.is_empty());
+        assert_eq!(db.get_user_command_count("u1", "ask").await.unwrap(), 4);
+    }
 
-/// DM session entry for TUI
-#[derive(Debug, Clone)]
-pub struct DmSessionEntry {
-    pub session_id: String,
-    pub started_at: String,
-    pub ended_at: Option<String>,
-    pub message_count: u32,
-    pub api_cost: f64,
-    pub total_tokens: u64,
-}
+    #[tokio::test]
+    async fn test_channel_language() {
+        let db = Database::new(":memory:").await.unwrap();
+        assert_eq!(db.get_channel_language("g1", "c1").await.unwrap(), None);
+        db.set_channel_language("g1", "c1", Some("fr"))
+            .await
+            .unwrap();
+        assert_eq!(
+            db.get_channel_language("g1", "c1")
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("fr")
+        );
+        assert_eq!(db.get_channel_language("g1", "c2").await.unwrap(), None);
+        db.set_channel_language("g1", "c1", None).await.unwrap();
+        assert_eq!(db.get_channel_language("g1", "c1").await.unwrap(), None);
+    }
 
-/// Error log entry for TUI
-#[derive(Debug, Clone)]
-pub struct ErrorLogEntry {
-    pub id: i64,
-    pub error_type: String,
-    pub error_message: String,
-    pub stack_trace: Option<String>,
-    pub user_id: Option<String>,
-    pub channel_id: Option<String>,
-    pub command: Option<String>,
-    pub timestamp: String,
-}
+    #[tokio::test]
+    async fn test_channel_time_detection() {
+        let db = Database::new(":memory:").await.unwrap();
+        assert!(!db.get_channel_time_detection("g1", "c1").await.unwrap());
+        db.set_channel_time_detection("g1", "c1", true)
+            .await
+            .unwrap();
+        assert!(db.get_channel_time_detection("g1", "c1").await.unwrap());
+        assert!(!db.get_channel_time_detection("g1", "c2").await.unwrap());
+        db.set_channel_time_detection("g1", "c1", false)
+            .await
+            .unwrap();
+        assert!(!db.get_channel_time_detection("g1", "c1").await.unwrap());
+    }
 
-/// Conversation message for TUI
-#[derive(Debug, Clone)]
-pub struct ConversationMessage {
-    pub user_id: String,
-    pub role: String,
-    pub content: String,
-    pub persona: Option<String>,
-    pub timestamp: String,
-}
+    #[tokio::test]
+    async fn test_channel_sentiment() {
+        let db = Database::new(":memory:").await.unwrap();
+        assert!(!db.get_channel_sentiment_tracking("g1", "c1").await.unwrap());
+        db.set_channel_sentiment_tracking("g1", "c1", true)
+            .await
+            .unwrap();
+        assert!(db.get_channel_sentiment_tracking("g1", "c1").await.unwrap());
+        assert_eq!(
+            db.get_sentiment_tracked_channels().await.unwrap(),
+            vec![("g1".to_string(), "c1".to_string())]
+        );
 
-/// Channel with conversation history entry for TUI
-#[derive(Debug, Clone)]
-pub struct ChannelHistoryEntry {
-    pub channel_id: String,
-    pub guild_id: Option<String>,
-    pub message_count: i64,
-    pub last_activity: Option<String>,
-}
+        let hour = chrono::Utc::now().timestamp() / 3600 * 3600;
+        db.record_channel_sentiment("g1", "c1", hour - 3600, -0.5)
+            .await
+            .unwrap();
+        db.record_channel_sentiment("g1", "c1", hour, 0.8)
+            .await
+            .unwrap();
+        db.record_channel_sentiment("g1", "c1", hour, 0.2)
+            .await
+            .unwrap();
+
+        let points = db.get_channel_sentiment("c1", hour - 7200).await.unwrap();
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].score, -0.5);
+        assert_eq!(points[1].messages, 2);
+        assert!((points[1].score - 0.5).abs() < 1e-9);
+        assert_eq!(db.get_channel_sentiment("c1", hour).await.unwrap().len(), 1);
+    }
 
-/// DM statistics for a user
-#[derive(Debug, Clone)]
-pub struct DmStats {
-    pub session_count: i64,
-    pub total_messages: i64,
-    pub user_messages: i64,
-    pub bot_messages: i64,
-    pub avg_response_time_ms: i64,
-    pub avg_session_duration_min: f64,
-    pub api_calls: i64,
-    pub total_tokens: i64,
-    pub total_cost_usd: f64,
-    pub chat_calls: i64,
-    pub whisper_calls: i64,
-    pub dalle_calls: i64,
-    pub audio_transcriptions: i64,
-    pub slash_commands_used: i64,
-}
+    #[tokio::test]
+    async fn test_migration_report() {
+        let db = Database::new(":memory:").await.unwrap();
+        let report = db.migration_report();
+        assert!(report
+            .created_tables
+            .contains(&"conversation_history".to_string()));
+        assert!(report.added_columns.is_empty());
+        assert_eq!(report.table_count, report.created_tables.len());
+
+        let mut before = BTreeMap::new();
+        before.insert("plugin_jobs".to_string(), vec!["id".to_string()]);
+        let mut after = before.clone();
+        after
+            .get_mut("plugin_jobs")
+            .unwrap()
+            .push("parent_playlist_id".to_string());
+        after.insert(
+            "category_settings".to_string(),
+            vec!["guild_id".to_string()],
+        );
 
-/// Session information
-#[derive(Debug, Clone)]
-pub struct SessionInfo {
-    pub session_id: String,
-    pub started_at: String,
-    pub ended_at: Option<String>,
-    pub message_count: i64,
-    pub avg_response_time_ms: i64,
+        let report = MigrationReport::between(&before, &after);
+        assert_eq!(report.created_tables, vec!["category_settings".to_string()]);
+        assert_eq!(
+            report.added_columns,
+            vec!["plugin_jobs.parent_playlist_id".to_string()]
+        );
+        assert_eq!(report.table_count, 2);
+        assert!(MigrationReport::between(&after, &after).is_empty());
+    }
 }
@@ -0,0 +1,226 @@
+//! # Feature: Achievements
+//!
+//! Members earn badges for milestones across features: /ask uses, debate wins,
+//! transcribed audio and trivia wins. Features call [`check`] after recording
+//! activity; badges a member newly qualifies for are stored once per guild,
+//! announced in the channel picked with `/set_channel achievements` and listed
+//! on `/profile`. Progress is read from the same analytics as `/profile`, so
+//! members who opted out of analytics don't earn badges.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with /ask, debate, transcription and trivia badges
+
+use anyhow::Result;
+use log::info;
+use serenity::http::Http;
+use serenity::model::id::ChannelId;
+
+use crate::database::Database;
+
+/// Feature id for toggles
+pub const ACHIEVEMENTS_FEATURE: &str = "achievements";
+
+/// Guild setting holding the channel new badges are announced in
+pub const CHANNEL_SETTING: &str = "achievement_channel";
+
+/// What a member has to reach for a badge
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Milestone {
+    AskUses(i64),
+    DebateWins(i64),
+    TranscribedHours(i64),
+    TriviaWins(i64),
+}
+
+/// A badge and the milestone that earns it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Achievement {
+    /// Stored key; never rename once shipped
+    pub key: &'static str,
+    pub emoji: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub milestone: Milestone,
+}
+
+/// Every badge, in the order they are listed
+pub const ACHIEVEMENTS: &[Achievement] = &[
+    Achievement {
+        key: "curious_mind",
+        emoji: "💬",
+        name: "Curious Mind",
+        description: "Asked 100 questions with /ask",
+        milestone: Milestone::AskUses(100),
+    },
+    Achievement {
+        key: "debate_victor",
+        emoji: "🏛️",
+        name: "Debate Victor",
+        description: "Backed the winning side of a debate",
+        milestone: Milestone::DebateWins(1),
+    },
+    Achievement {
+        key: "good_listener",
+        emoji: "🎙️",
+        name: "Good Listener",
+        description: "Had 10 hours of audio transcribed",
+        milestone: Milestone::TranscribedHours(10),
+    },
+    Achievement {
+        key: "trivia_champion",
+        emoji: "🧠",
+        name: "Trivia Champion",
+        description: "Won a trivia game",
+        milestone: Milestone::TriviaWins(1),
+    },
+];
+
+/// A member's totals that milestones are measured against
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Progress {
+    pub ask_uses: i64,
+    pub debate_wins: i64,
+    pub transcription_seconds: f64,
+    pub trivia_wins: i64,
+}
+
+impl Progress {
+    /// Load a member's totals in a guild; /ask uses are counted across guilds
+    pub async fn load(database: &Database, guild_id: &str, user_id: &str) -> Result<Self> {
+        let profile = database.get_user_profile(user_id, guild_id, 0).await?;
+        Ok(Self {
+            ask_uses: database.get_user_command_count(user_id, "ask").await?,
+            debate_wins: profile.debate_wins,
+            transcription_seconds: profile.transcription_seconds,
+            trivia_wins: profile.trivia.map(|t| t.games_won).unwrap_or(0),
+        })
+    }
+
+    fn reached(&self, milestone: Milestone) -> bool {
+        match milestone {
+            Milestone::AskUses(n) => self.ask_uses >= n,
+            Milestone::DebateWins(n) => self.debate_wins >= n,
+            Milestone::TranscribedHours(n) => self.transcription_seconds >= (n * 3600) as f64,
+            Milestone::TriviaWins(n) => self.trivia_wins >= n,
+        }
+    }
+}
+
+/// Look up a badge by its stored key
+pub fn get_achievement(key: &str) -> Option<&'static Achievement> {
+    ACHIEVEMENTS.iter().find(|a| a.key == key)
+}
+
+/// Badges whose milestones `progress` has reached
+pub fn unlocked(progress: &Progress) -> Vec<&'static Achievement> {
+    ACHIEVEMENTS
+        .iter()
+        .filter(|a| progress.reached(a.milestone))
+        .collect()
+}
+
+/// Award any badges a member newly qualifies for and announce them
+///
+/// `pending` is added to the stored totals, for activity that is logged in the
+/// background and may not be queryable yet (e.g. transcription seconds).
+/// Returns the badges awarded by this call.
+pub async fn check(
+    database: &Database,
+    http: &Http,
+    guild_id: &str,
+    user_id: &str,
+    pending: Progress,
+) -> Result<Vec<&'static Achievement>> {
+    if !database
+        .is_feature_enabled(ACHIEVEMENTS_FEATURE, None, Some(guild_id))
+        .await?
+        || !database
+            .get_privacy_settings(user_id, Some(guild_id))
+            .await?
+            .analytics
+    {
+        return Ok(Vec::new());
+    }
+
+    let mut progress = Progress::load(database, guild_id, user_id).await?;
+    progress.ask_uses += pending.ask_uses;
+    progress.debate_wins += pending.debate_wins;
+    progress.transcription_seconds += pending.transcription_seconds;
+    progress.trivia_wins += pending.trivia_wins;
+
+    let mut awarded = Vec::new();
+    for achievement in unlocked(&progress) {
+        if database
+            .award_achievement(guild_id, user_id, achievement.key)
+            .await?
+        {
+            info!(
+                "🏅 {user_id} earned {} in guild {guild_id}",
+                achievement.key
+            );
+            awarded.push(achievement);
+        }
+    }
+
+    if !awarded.is_empty() {
+        if let Some(channel_id) = database
+            .get_guild_setting(guild_id, CHANNEL_SETTING)
+            .await?
+        {
+            let lines = awarded
+                .iter()
+                .map(|a| format!("{} **{}**: {}", a.emoji, a.name, a.description))
+                .collect::<Vec<_>>()
+                .join("\n");
+            ChannelId(channel_id.parse::<u64>()?)
+                .send_message(http, |m| {
+                    m.content(format!("<@{user_id}>")).embed(|e| {
+                        e.title("🏅 Achievement unlocked")
+                            .description(lines)
+                            .color(0xF1C40F)
+                    })
+                })
+                .await?;
+        }
+    }
+    Ok(awarded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_achievement_keys_are_unique() {
+        for achievement in ACHIEVEMENTS {
+            assert_eq!(get_achievement(achievement.key), Some(achievement));
+        }
+        assert_eq!(get_achievement("unknown"), None);
+    }
+
+    #[test]
+    fn test_unlocked_milestones() {
+        assert!(unlocked(&Progress::default()).is_empty());
+
+        let progress = Progress {
+            ask_uses: 100,
+            debate_wins: 0,
+            transcription_seconds: 10.0 * 3600.0 - 1.0,
+            trivia_wins: 2,
+        };
+        let keys: Vec<&str> = unlocked(&progress).iter().map(|a| a.key).collect();
+        assert_eq!(keys, ["curious_mind", "trivia_champion"]);
+
+        let progress = Progress {
+            debate_wins: 1,
+            transcription_seconds: 10.0 * 3600.0,
+            ..Progress::default()
+        };
+        let keys: Vec<&str> = unlocked(&progress).iter().map(|a| a.key).collect();
+        assert_eq!(keys, ["debate_victor", "good_listener"]);
+    }
+}
@@ -0,0 +1,262 @@
+//! # Feature: Prompt Aliases
+//!
+//! `/alias create` saves a prompt template under a short name, personal by
+//! default or shared with the whole server. `/alias run` fills the template's
+//! `${arg}` placeholders and sends the result through the `/ask` pipeline.
+//! Arguments fill placeholders in the order they first appear; quote an
+//! argument to keep its words together, and anything left over goes to the
+//! last placeholder. `${args}` always stands for everything typed, and a
+//! template without placeholders gets the arguments added after it.
+//!
+//! - **Version**: 1.1.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.1.0: Validation and expansion messages are worded to suit the prompt library too
+//! - 1.0.0: Initial release with personal and shared aliases and positional placeholders
+
+use regex::Regex;
+use std::sync::OnceLock;
+
+use crate::database::PromptAlias;
+
+/// Feature id for toggles
+pub const ALIASES_FEATURE: &str = "aliases";
+
+/// Scope of aliases only their creator can run
+pub const USER_SCOPE: &str = "user";
+
+/// Scope of aliases everyone in a guild can run
+pub const GUILD_SCOPE: &str = "guild";
+
+/// Placeholder replaced by everything typed after the alias name
+pub const ALL_ARGS: &str = "args";
+
+/// Personal aliases a member may keep
+pub const MAX_USER_ALIASES: i64 = 25;
+
+/// Shared aliases a guild may keep
+pub const MAX_GUILD_ALIASES: i64 = 50;
+
+/// Longest alias name
+pub const MAX_NAME_CHARS: usize = 32;
+
+/// Longest template; expanded prompts must still fit /ask's limit
+pub const MAX_TEMPLATE_CHARS: usize = 1500;
+
+/// Longest expanded prompt, matching /ask's prompt option
+pub const MAX_PROMPT_CHARS: usize = 2000;
+
+/// Characters of each template shown by `/alias list`
+const LIST_PREVIEW_CHARS: usize = 80;
+
+fn placeholder_regex() -> &'static Regex {
+    static PLACEHOLDER: OnceLock<Regex> = OnceLock::new();
+    PLACEHOLDER.get_or_init(|| Regex::new(r"\$\{([A-Za-z0-9_]+)\}").expect("valid regex"))
+}
+
+/// Lowercase and check an alias or library prompt name: letters, digits, `-` and `_`
+pub fn validate_name(input: &str) -> Result<String, String> {
+    let name = input.trim().to_lowercase();
+    if name.is_empty() || name.chars().count() > MAX_NAME_CHARS {
+        return Err(format!("Names must be 1-{MAX_NAME_CHARS} characters."));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err("Names can only use letters, digits, `-` and `_`.".to_string());
+    }
+    Ok(name)
+}
+
+/// Trim and check a template's length
+pub fn validate_template(input: &str) -> Result<String, String> {
+    let template = input.trim();
+    if template.is_empty() {
+        return Err("The prompt template can't be empty.".to_string());
+    }
+    if template.chars().count() > MAX_TEMPLATE_CHARS {
+        return Err(format!(
+            "Prompt templates must be at most {MAX_TEMPLATE_CHARS} characters."
+        ));
+    }
+    Ok(template.to_string())
+}
+
+/// Named placeholders in the order they first appear, without `${args}`
+pub fn placeholders(template: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for capture in placeholder_regex().captures_iter(template) {
+        let name = &capture[1];
+        if name != ALL_ARGS && !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// Split arguments on whitespace, keeping double-quoted runs together
+pub fn split_args(input: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut started = false;
+    for c in input.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                started = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if started {
+                    args.push(std::mem::take(&mut current));
+                    started = false;
+                }
+            }
+            c => {
+                current.push(c);
+                started = true;
+            }
+        }
+    }
+    if started {
+        args.push(current);
+    }
+    args
+}
+
+/// Fill a template's placeholders from the typed arguments
+pub fn expand(template: &str, input: &str) -> Result<String, String> {
+    let names = placeholders(template);
+    let mut args = split_args(input);
+    if args.len() < names.len() {
+        return Err(format!(
+            "Fill in {} value{}: {}",
+            names.len(),
+            if names.len() == 1 { "" } else { "s" },
+            names
+                .iter()
+                .map(|n| format!("`{n}`"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    // Leftover arguments belong to the last placeholder
+    if !names.is_empty() && args.len() > names.len() {
+        let rest = args.split_off(names.len() - 1).join(" ");
+        args.push(rest);
+    }
+
+    if !placeholder_regex().is_match(template) {
+        let prompt = format!("{template} {}", input.trim()).trim().to_string();
+        return check_length(prompt);
+    }
+
+    let prompt = placeholder_regex()
+        .replace_all(template, |capture: &regex::Captures| {
+            let name = &capture[1];
+            if name == ALL_ARGS {
+                return input.trim().to_string();
+            }
+            names
+                .iter()
+                .position(|n| n == name)
+                .and_then(|i| args.get(i).cloned())
+                .unwrap_or_default()
+        })
+        .trim()
+        .to_string();
+    check_length(prompt)
+}
+
+fn check_length(prompt: String) -> Result<String, String> {
+    if prompt.is_empty() {
+        return Err("That fills in to an empty prompt.".to_string());
+    }
+    if prompt.chars().count() > MAX_PROMPT_CHARS {
+        return Err(format!(
+            "The expanded prompt is over {MAX_PROMPT_CHARS} characters."
+        ));
+    }
+    Ok(prompt)
+}
+
+/// One line of `/alias list`
+pub fn list_line(alias: &PromptAlias) -> String {
+    let mut preview: String = alias
+        .template
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(LIST_PREVIEW_CHARS)
+        .collect();
+    if alias.template.chars().count() > LIST_PREVIEW_CHARS {
+        preview.push('…');
+    }
+    let persona = alias
+        .persona
+        .as_deref()
+        .map(|p| format!(" · {p}"))
+        .unwrap_or_default();
+    format!("`{}`{persona} — {}", alias.name, preview.replace('`', "'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_name() {
+        assert_eq!(validate_name(" TLDR "), Ok("tldr".to_string()));
+        assert_eq!(validate_name("eli-5_x"), Ok("eli-5_x".to_string()));
+        assert!(validate_name("two words").is_err());
+        assert!(validate_name("").is_err());
+        assert!(validate_name(&"a".repeat(MAX_NAME_CHARS + 1)).is_err());
+    }
+
+    #[test]
+    fn test_placeholders_in_order() {
+        assert_eq!(
+            placeholders("Compare ${a} and ${b}, then ${a} again with ${args}"),
+            ["a", "b"]
+        );
+        assert!(placeholders("No placeholders").is_empty());
+    }
+
+    #[test]
+    fn test_split_args_quotes() {
+        assert_eq!(
+            split_args(r#"rust "borrow checker"  go"#),
+            ["rust", "borrow checker", "go"]
+        );
+        assert_eq!(split_args(r#""""#), [""]);
+        assert!(split_args("   ").is_empty());
+    }
+
+    #[test]
+    fn test_expand() {
+        assert_eq!(
+            expand("Explain ${topic} simply", "quantum physics"),
+            Ok("Explain quantum physics simply".to_string())
+        );
+        assert_eq!(
+            expand("Compare ${a} with ${b}", r#""hash maps" b-trees"#),
+            Ok("Compare hash maps with b-trees".to_string())
+        );
+        assert_eq!(
+            expand("Summarize: ${args}", "  the whole thing "),
+            Ok("Summarize: the whole thing".to_string())
+        );
+        assert_eq!(
+            expand("Write a haiku about", "autumn rain"),
+            Ok("Write a haiku about autumn rain".to_string())
+        );
+        assert_eq!(
+            expand("Compare ${a} with ${b}", "one"),
+            Err("Fill in 2 values: `a`, `b`".to_string())
+        );
+    }
+}
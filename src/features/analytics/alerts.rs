@@ -0,0 +1,428 @@
+//! # Feature: Metric Alerts
+//!
+//! Threshold rules (`ALERT_RULES`, e.g. `memory>90,error_rate>5,daily_spend>20`)
+//! evaluated by the metrics loop every minute. A rule that crosses its
+//! threshold notifies the ops channel (`ALERT_CHANNEL_ID`) or the owner by DM,
+//! then stays quiet for `ALERT_COOLDOWN_MINUTES` while it keeps firing.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: false
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with memory, error rate and daily spend rules
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use log::{info, warn};
+use serenity::builder::CreateEmbed;
+use serenity::http::Http;
+use serenity::model::id::{ChannelId, UserId};
+use serenity::utils::Color;
+use std::fmt;
+use std::sync::{Mutex, OnceLock};
+
+use crate::database::Database;
+
+/// Rules used when ALERT_RULES isn't set
+pub const DEFAULT_ALERT_RULES: &str = "memory>90,error_rate>5,daily_spend>20";
+
+/// Minutes a firing rule waits before notifying again
+pub const DEFAULT_ALERT_COOLDOWN_MINUTES: i64 = 60;
+
+/// What a rule watches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertMetric {
+    /// System memory used, percent
+    Memory,
+    /// ERROR-level log lines per minute
+    ErrorRate,
+    /// OpenAI spend today (UTC), USD
+    DailySpend,
+}
+
+impl AlertMetric {
+    pub const ALL: [AlertMetric; 3] = [
+        AlertMetric::Memory,
+        AlertMetric::ErrorRate,
+        AlertMetric::DailySpend,
+    ];
+
+    /// Name used in ALERT_RULES
+    pub fn id(&self) -> &'static str {
+        match self {
+            AlertMetric::Memory => "memory",
+            AlertMetric::ErrorRate => "error_rate",
+            AlertMetric::DailySpend => "daily_spend",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.id() == id)
+    }
+
+    /// Value with its unit, e.g. "91.2%", "6.0/min", "$21.40"
+    pub fn format_value(&self, value: f64) -> String {
+        match self {
+            AlertMetric::Memory => format!("{value:.1}%"),
+            AlertMetric::ErrorRate => format!("{value:.1}/min"),
+            AlertMetric::DailySpend => format!("${value:.2}"),
+        }
+    }
+}
+
+/// `metric > threshold`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AlertRule {
+    pub metric: AlertMetric,
+    pub threshold: f64,
+}
+
+impl fmt::Display for AlertRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} > {}",
+            self.metric.id(),
+            self.metric.format_value(self.threshold)
+        )
+    }
+}
+
+/// Parse a comma-separated rule list
+///
+/// Returns the valid rules and a message for each entry that was skipped.
+pub fn parse_rules(spec: &str) -> (Vec<AlertRule>, Vec<String>) {
+    let mut rules = Vec::new();
+    let mut errors = Vec::new();
+    for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let parsed = entry.split_once('>').and_then(|(metric, threshold)| {
+            let metric = AlertMetric::from_id(metric.trim())?;
+            let threshold = threshold
+                .trim()
+                .trim_start_matches('$')
+                .trim_end_matches('%');
+            Some(AlertRule {
+                metric,
+                threshold: threshold.parse().ok()?,
+            })
+        });
+        match parsed {
+            Some(rule) => rules.push(rule),
+            None => errors.push(format!(
+                "`{entry}` isn't a rule; use `memory>90`, `error_rate>5` or `daily_spend>20`"
+            )),
+        }
+    }
+    (rules, errors)
+}
+
+/// Metric values from one metrics loop tick (`None` when unavailable)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AlertSample {
+    pub memory_percent: Option<f64>,
+    pub errors_per_minute: Option<f64>,
+    pub daily_spend: Option<f64>,
+}
+
+impl AlertSample {
+    fn value(&self, metric: AlertMetric) -> Option<f64> {
+        match metric {
+            AlertMetric::Memory => self.memory_percent,
+            AlertMetric::ErrorRate => self.errors_per_minute,
+            AlertMetric::DailySpend => self.daily_spend,
+        }
+    }
+}
+
+/// A rule and what the last evaluation saw
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleStatus {
+    pub rule: AlertRule,
+    pub last_value: Option<f64>,
+    pub firing: bool,
+    pub last_notified: Option<DateTime<Utc>>,
+}
+
+/// A rule that needs a notification
+#[derive(Debug, Clone, PartialEq)]
+pub struct FiredAlert {
+    pub rule: AlertRule,
+    pub value: f64,
+}
+
+/// Evaluates rules and tracks firing state and cooldowns
+pub struct AlertEngine {
+    statuses: Mutex<Vec<RuleStatus>>,
+    cooldown: Duration,
+    channel_id: Option<u64>,
+}
+
+impl AlertEngine {
+    pub fn new(rules: Vec<AlertRule>, cooldown_minutes: i64, channel_id: Option<u64>) -> Self {
+        let statuses = rules
+            .into_iter()
+            .map(|rule| RuleStatus {
+                rule,
+                last_value: None,
+                firing: false,
+                last_notified: None,
+            })
+            .collect();
+        Self {
+            statuses: Mutex::new(statuses),
+            cooldown: Duration::minutes(cooldown_minutes.max(0)),
+            channel_id,
+        }
+    }
+
+    /// Rules from ALERT_RULES, ALERT_COOLDOWN_MINUTES and ALERT_CHANNEL_ID
+    pub fn from_env() -> Self {
+        let spec = std::env::var("ALERT_RULES").unwrap_or_else(|_| DEFAULT_ALERT_RULES.to_string());
+        let (rules, errors) = parse_rules(&spec);
+        for error in errors {
+            warn!("ALERT_RULES: {error}");
+        }
+        let cooldown = std::env::var("ALERT_COOLDOWN_MINUTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_ALERT_COOLDOWN_MINUTES);
+        let channel_id = std::env::var("ALERT_CHANNEL_ID")
+            .ok()
+            .and_then(|v| v.parse().ok());
+        Self::new(rules, cooldown, channel_id)
+    }
+
+    /// Record `sample` and return the rules that should notify now
+    ///
+    /// A rule notifies when it crosses its threshold and again every cooldown
+    /// while it stays above it; recovering re-arms it immediately.
+    pub fn evaluate(&self, sample: &AlertSample, now: DateTime<Utc>) -> Vec<FiredAlert> {
+        let mut statuses = self.statuses.lock().unwrap_or_else(|e| e.into_inner());
+        let mut fired = Vec::new();
+        for status in statuses.iter_mut() {
+            let Some(value) = sample.value(status.rule.metric) else {
+                continue;
+            };
+            status.last_value = Some(value);
+            let was_firing = status.firing;
+            status.firing = value > status.rule.threshold;
+            if !status.firing {
+                continue;
+            }
+            let cooled_down = status
+                .last_notified
+                .is_none_or(|at| now - at >= self.cooldown);
+            if !was_firing || cooled_down {
+                status.last_notified = Some(now);
+                fired.push(FiredAlert {
+                    rule: status.rule,
+                    value,
+                });
+            }
+        }
+        fired
+    }
+
+    pub fn statuses(&self) -> Vec<RuleStatus> {
+        self.statuses
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Markdown list for /alerts
+    pub fn format_statuses(&self, now: DateTime<Utc>) -> String {
+        let statuses = self.statuses();
+        if statuses.is_empty() {
+            return "No alert rules configured. Set `ALERT_RULES`, e.g. `memory>90,error_rate>5,daily_spend>20`.".to_string();
+        }
+
+        let destination = match self.channel_id {
+            Some(id) => format!("<#{id}>"),
+            None => "owner DM".to_string(),
+        };
+        let mut out = format!(
+            "**Alert Rules** (notify: {destination}, cooldown: {}m)\n",
+            self.cooldown.num_minutes()
+        );
+        for status in statuses {
+            let icon = if status.firing { "🔴" } else { "🟢" };
+            let value = status
+                .last_value
+                .map(|v| status.rule.metric.format_value(v))
+                .unwrap_or_else(|| "no data".to_string());
+            out.push_str(&format!("{icon} `{}` · now {value}", status.rule));
+            if let Some(at) = status.last_notified {
+                out.push_str(&format!(" · last alert <t:{}:R>", at.timestamp()));
+                let quiet_until = at + self.cooldown;
+                if status.firing && quiet_until > now {
+                    out.push_str(&format!(" · muted until <t:{}:t>", quiet_until.timestamp()));
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Send fired alerts to the ops channel, or the owner by DM
+    ///
+    /// The owner is the startup notification owner, falling back to the
+    /// application owner.
+    pub async fn notify(&self, http: &Http, database: &Database, alerts: &[FiredAlert]) {
+        if alerts.is_empty() {
+            return;
+        }
+        let embed = alert_embed(alerts);
+        let result = match self.channel_id {
+            Some(channel_id) => send_to_channel(http, ChannelId(channel_id), embed).await,
+            None => send_to_owner(http, database, embed).await,
+        };
+        match result {
+            Ok(()) => info!("Sent {} metric alert(s)", alerts.len()),
+            Err(e) => warn!("Failed to send metric alerts: {e}"),
+        }
+    }
+}
+
+fn alert_embed(alerts: &[FiredAlert]) -> CreateEmbed {
+    let mut embed = CreateEmbed::default();
+    embed
+        .title("🚨 Metric alert")
+        .color(Color::RED)
+        .timestamp(Utc::now().to_rfc3339());
+    for alert in alerts {
+        embed.field(
+            alert.rule.metric.id(),
+            format!(
+                "{} (threshold {})",
+                alert.rule.metric.format_value(alert.value),
+                alert.rule.metric.format_value(alert.rule.threshold)
+            ),
+            true,
+        );
+    }
+    embed
+}
+
+async fn send_to_channel(http: &Http, channel: ChannelId, embed: CreateEmbed) -> Result<()> {
+    channel.send_message(http, |m| m.set_embed(embed)).await?;
+    Ok(())
+}
+
+async fn send_to_owner(http: &Http, database: &Database, embed: CreateEmbed) -> Result<()> {
+    let configured_owner = database
+        .get_bot_setting("startup_notify_owner_id")
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| v.parse::<u64>().ok());
+    let owner_id = match configured_owner {
+        Some(id) => id,
+        None => http.get_current_application_info().await?.owner.id.0,
+    };
+    let dm = UserId(owner_id).create_dm_channel(http).await?;
+    dm.send_message(http, |m| m.set_embed(embed)).await?;
+    Ok(())
+}
+
+static ALERTS: OnceLock<AlertEngine> = OnceLock::new();
+
+/// Shared alert engine, configured from the environment on first use
+pub fn alert_engine() -> &'static AlertEngine {
+    ALERTS.get_or_init(AlertEngine::from_env)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rules() {
+        let (rules, errors) =
+            parse_rules(" memory>90 , error_rate > 2.5,daily_spend>$20,cpu>5,memory");
+        assert_eq!(
+            rules,
+            vec![
+                AlertRule {
+                    metric: AlertMetric::Memory,
+                    threshold: 90.0
+                },
+                AlertRule {
+                    metric: AlertMetric::ErrorRate,
+                    threshold: 2.5
+                },
+                AlertRule {
+                    metric: AlertMetric::DailySpend,
+                    threshold: 20.0
+                },
+            ]
+        );
+        assert_eq!(errors.len(), 2);
+        assert_eq!(parse_rules(DEFAULT_ALERT_RULES).0.len(), 3);
+        assert_eq!(rules[2].to_string(), "daily_spend > $20.00");
+    }
+
+    #[test]
+    fn test_evaluate_respects_cooldown_and_rearms() {
+        let (rules, _) = parse_rules("memory>90,daily_spend>20");
+        let engine = AlertEngine::new(rules, 60, None);
+        let start = Utc::now();
+        let hot = AlertSample {
+            memory_percent: Some(95.0),
+            errors_per_minute: None,
+            daily_spend: Some(5.0),
+        };
+        let cool = AlertSample {
+            memory_percent: Some(50.0),
+            ..hot
+        };
+
+        let fired = engine.evaluate(&hot, start);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].rule.metric, AlertMetric::Memory);
+        assert!(engine
+            .evaluate(&hot, start + Duration::minutes(30))
+            .is_empty());
+        assert_eq!(
+            engine.evaluate(&hot, start + Duration::minutes(61)).len(),
+            1
+        );
+
+        // Recovering re-arms the rule without waiting out the cooldown
+        assert!(engine
+            .evaluate(&cool, start + Duration::minutes(62))
+            .is_empty());
+        assert_eq!(
+            engine.evaluate(&hot, start + Duration::minutes(63)).len(),
+            1
+        );
+
+        let statuses = engine.statuses();
+        assert!(statuses[0].firing);
+        assert!(!statuses[1].firing);
+        assert_eq!(statuses[1].last_value, Some(5.0));
+    }
+
+    #[test]
+    fn test_format_statuses() {
+        let engine = AlertEngine::new(parse_rules("error_rate>5").0, 15, Some(42));
+        let now = Utc::now();
+        assert!(engine.format_statuses(now).contains("no data"));
+
+        engine.evaluate(
+            &AlertSample {
+                errors_per_minute: Some(7.0),
+                ..Default::default()
+            },
+            now,
+        );
+        let text = engine.format_statuses(now);
+        assert!(text.contains("<#42>"));
+        assert!(text.contains("🔴 `error_rate > 5.0/min` · now 7.0/min"));
+        assert!(text.contains("muted until"));
+        assert!(AlertEngine::new(Vec::new(), 15, None)
+            .format_statuses(now)
+            .starts_with("No alert rules"));
+    }
+}
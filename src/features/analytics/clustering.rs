@@ -0,0 +1,182 @@
+//! # Clustering
+//!
+//! Spherical k-means over embedding vectors: vectors are compared by cosine
+//! similarity and centroids are re-normalized each round. Initial centroids
+//! are picked farthest-first from the first vector, so the same input always
+//! gives the same clusters.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use super::embeddings::cosine_similarity;
+
+/// Rounds of reassignment before giving up on convergence
+const MAX_ITERATIONS: usize = 25;
+
+/// Vectors assigned to one centroid
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cluster {
+    /// Indices into the clustered vectors, nearest to the centroid first
+    pub members: Vec<usize>,
+    pub centroid: Vec<f64>,
+}
+
+/// A cluster count suited to `n` vectors: roughly `sqrt(n / 2)`, between 2 and `max`
+pub fn suggested_k(n: usize, max: usize) -> usize {
+    (((n as f64) / 2.0).sqrt().round() as usize)
+        .clamp(2, max.max(2))
+        .min(n)
+}
+
+/// Group `vectors` into at most `k` clusters, largest first
+///
+/// Empty clusters are dropped, so fewer than `k` may come back when vectors
+/// repeat.
+pub fn kmeans(vectors: &[Vec<f64>], k: usize) -> Vec<Cluster> {
+    if vectors.is_empty() || k == 0 {
+        return Vec::new();
+    }
+    let vectors: Vec<Vec<f64>> = vectors.iter().map(|v| normalized(v)).collect();
+    let mut centroids = initial_centroids(&vectors, k.min(vectors.len()));
+    let mut assignments = vec![usize::MAX; vectors.len()];
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut changed = false;
+        for (i, vector) in vectors.iter().enumerate() {
+            let nearest = nearest_centroid(vector, &centroids);
+            if assignments[i] != nearest {
+                assignments[i] = nearest;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+        for (c, centroid) in centroids.iter_mut().enumerate() {
+            let mut sum = vec![0.0; centroid.len()];
+            let mut count = 0;
+            for (vector, _) in vectors.iter().zip(&assignments).filter(|(_, a)| **a == c) {
+                for (s, x) in sum.iter_mut().zip(vector) {
+                    *s += x;
+                }
+                count += 1;
+            }
+            // An emptied cluster keeps its old centroid
+            if count > 0 {
+                *centroid = normalized(&sum);
+            }
+        }
+    }
+
+    let mut clusters: Vec<Cluster> = centroids
+        .into_iter()
+        .enumerate()
+        .map(|(c, centroid)| {
+            let mut members: Vec<usize> = (0..vectors.len())
+                .filter(|i| assignments[*i] == c)
+                .collect();
+            members.sort_by(|a, b| {
+                cosine_similarity(&vectors[*b], &centroid)
+                    .total_cmp(&cosine_similarity(&vectors[*a], &centroid))
+            });
+            Cluster { members, centroid }
+        })
+        .filter(|cluster| !cluster.members.is_empty())
+        .collect();
+    clusters.sort_by_key(|cluster| std::cmp::Reverse(cluster.members.len()));
+    clusters
+}
+
+/// Farthest-first: start at the first vector, then repeatedly take the vector
+/// least similar to every centroid picked so far
+fn initial_centroids(vectors: &[Vec<f64>], k: usize) -> Vec<Vec<f64>> {
+    let mut centroids = vec![vectors[0].clone()];
+    while centroids.len() < k {
+        let farthest = vectors
+            .iter()
+            .map(|v| {
+                centroids
+                    .iter()
+                    .map(|c| cosine_similarity(v, c))
+                    .fold(f64::MIN, f64::max)
+            })
+            .enumerate()
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+            .unwrap_or(0);
+        centroids.push(vectors[farthest].clone());
+    }
+    centroids
+}
+
+fn nearest_centroid(vector: &[f64], centroids: &[Vec<f64>]) -> usize {
+    centroids
+        .iter()
+        .enumerate()
+        .max_by(|a, b| {
+            cosine_similarity(vector, a.1)
+                .total_cmp(&cosine_similarity(vector, b.1))
+                // Prefer the lower index on ties
+                .then(b.0.cmp(&a.0))
+        })
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+fn normalized(v: &[f64]) -> Vec<f64> {
+    let magnitude = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if magnitude == 0.0 {
+        v.to_vec()
+    } else {
+        v.iter().map(|x| x / magnitude).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suggested_k() {
+        assert_eq!(suggested_k(3, 6), 2);
+        assert_eq!(suggested_k(50, 6), 5);
+        assert_eq!(suggested_k(500, 6), 6);
+        assert_eq!(suggested_k(1, 6), 1);
+    }
+
+    #[test]
+    fn test_kmeans_separates_groups() {
+        let vectors = vec![
+            vec![1.0, 0.1, 0.0],
+            vec![0.0, 1.0, 0.1],
+            vec![0.9, 0.0, 0.1],
+            vec![0.1, 0.9, 0.0],
+            vec![1.0, 0.0, 0.0],
+            vec![0.0, 0.0, 1.0],
+        ];
+        let clusters = kmeans(&vectors, 3);
+        assert_eq!(clusters.len(), 3);
+        assert_eq!(clusters[0].members.len(), 3);
+        let mut first = clusters[0].members.clone();
+        first.sort();
+        assert_eq!(first, vec![0, 2, 4]);
+        // Nearest the centroid first
+        assert_eq!(clusters[0].members[0], 4);
+        let mut second = clusters[1].members.clone();
+        second.sort();
+        assert_eq!(second, vec![1, 3]);
+        assert_eq!(clusters[2].members, vec![5]);
+    }
+
+    #[test]
+    fn test_kmeans_edge_cases() {
+        assert!(kmeans(&[], 3).is_empty());
+        let same = vec![vec![1.0, 0.0]; 4];
+        let clusters = kmeans(&same, 2);
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].members.len(), 4);
+    }
+}
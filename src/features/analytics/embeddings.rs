@@ -0,0 +1,105 @@
+//! # Embeddings
+//!
+//! Turns message text into embedding vectors through the OpenAI embeddings
+//! endpoint, behind a trait so clustering can be driven by fixed vectors in
+//! tests.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with the live OpenAI client and cosine similarity
+
+use anyhow::Result;
+use async_trait::async_trait;
+use openai::embeddings::Embeddings;
+use openai::Credentials;
+use std::sync::Arc;
+
+use super::runtime::runtime_counters;
+
+/// Embedding model used when none is configured
+pub const DEFAULT_EMBEDDING_MODEL: &str = "text-embedding-3-small";
+
+/// Inputs sent per embeddings request
+const MAX_INPUTS_PER_REQUEST: usize = 100;
+
+/// Embedding vectors for a batch of inputs, in input order
+#[derive(Debug, Clone, Default)]
+pub struct EmbeddingBatch {
+    pub vectors: Vec<Vec<f64>>,
+    /// Prompt tokens billed for the batch
+    pub tokens: u32,
+}
+
+/// Creates embedding vectors for a list of texts
+#[async_trait]
+pub trait EmbeddingClient: Send + Sync {
+    async fn embed(&self, model: &str, inputs: &[String]) -> Result<EmbeddingBatch>;
+}
+
+/// Live client backed by the OpenAI API
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OpenAiEmbeddingClient;
+
+#[async_trait]
+impl EmbeddingClient for OpenAiEmbeddingClient {
+    async fn embed(&self, model: &str, inputs: &[String]) -> Result<EmbeddingBatch> {
+        // The key is exported at startup; read per call so a missing key is an error, not a panic
+        let credentials = Credentials::new(
+            std::env::var("OPENAI_API_KEY")?,
+            std::env::var("OPENAI_BASE_URL").unwrap_or_default(),
+        );
+        let mut batch = EmbeddingBatch::default();
+        for chunk in inputs.chunks(MAX_INPUTS_PER_REQUEST) {
+            let _in_flight = runtime_counters().openai_request();
+            let input: Vec<&str> = chunk.iter().map(String::as_str).collect();
+            let embeddings = Embeddings::create(model, input, "", credentials.clone()).await?;
+            if embeddings.data.len() != chunk.len() {
+                anyhow::bail!(
+                    "Expected {} embeddings, got {}",
+                    chunk.len(),
+                    embeddings.data.len()
+                );
+            }
+            batch.tokens += embeddings.usage.total_tokens;
+            batch
+                .vectors
+                .extend(embeddings.data.into_iter().map(|embedding| embedding.vec));
+        }
+        Ok(batch)
+    }
+}
+
+/// Default client used when none is injected
+pub fn openai_embedding_client() -> Arc<dyn EmbeddingClient> {
+    Arc::new(OpenAiEmbeddingClient)
+}
+
+/// Cosine similarity of two vectors; 0.0 when either is all zeros
+pub fn cosine_similarity(a: &[f64], b: &[f64]) -> f64 {
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let magnitudes = magnitude(a) * magnitude(b);
+    if magnitudes == 0.0 {
+        0.0
+    } else {
+        dot / magnitudes
+    }
+}
+
+fn magnitude(v: &[f64]) -> f64 {
+    v.iter().map(|x| x * x).sum::<f64>().sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-9);
+        assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 3.0]).abs() < 1e-9);
+        assert!((cosine_similarity(&[1.0, 1.0], &[-1.0, -1.0]) + 1.0).abs() < 1e-9);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+    }
+}
@@ -2,21 +2,26 @@
 //!
 //! Tracks DM sessions, engagement metrics, and feature usage with event-driven architecture.
 //!
-//! - **Version**: 1.0.0
+//! - **Version**: 1.2.0
 //! - **Since**: 0.6.0
 //! - **Toggleable**: false
 //!
 //! ## Changelog
+//! - 1.2.0: Drop tracking events for users who opted out of analytics via /privacy
+//! - 1.1.0: Persist session counters per message and reconcile open sessions at startup
 //! - 1.0.0: Initial release with async event-driven tracking
 
-use crate::database::Database;
-use chrono::{DateTime, Duration, Utc};
+use crate::database::{Database, OpenDmSession};
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
 use dashmap::DashMap;
-use log::{debug, error, warn};
+use log::{debug, error, info, warn};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use uuid::Uuid;
 
+/// Minutes of inactivity after which a DM session ends
+const SESSION_TIMEOUT_MINUTES: i64 = 30;
+
 /// Types of API calls tracked
 #[derive(Debug, Clone)]
 pub enum ApiType {
@@ -99,6 +104,20 @@ pub enum TrackingEvent {
     },
 }
 
+impl TrackingEvent {
+    /// User the event belongs to; session ends only carry the session ID
+    fn user_id(&self) -> Option<&str> {
+        match self {
+            TrackingEvent::SessionStart { user_id, .. }
+            | TrackingEvent::MessageReceived { user_id, .. }
+            | TrackingEvent::MessageSent { user_id, .. }
+            | TrackingEvent::ApiCall { user_id, .. }
+            | TrackingEvent::FeatureUsed { user_id, .. } => Some(user_id),
+            TrackingEvent::SessionEnd { .. } => None,
+        }
+    }
+}
+
 /// Active session state tracked in memory
 #[derive(Debug, Clone)]
 struct SessionState {
@@ -112,7 +131,8 @@ struct SessionState {
     bot_message_count: i32,
     total_user_chars: i32,
     total_bot_chars: i32,
-    response_times: Vec<u64>,
+    response_time_total_ms: u64,
+    response_count: u64,
 }
 
 impl SessionState {
@@ -129,7 +149,30 @@ impl SessionState {
             bot_message_count: 0,
             total_user_chars: 0,
             total_bot_chars: 0,
-            response_times: Vec::new(),
+            response_time_total_ms: 0,
+            response_count: 0,
+        }
+    }
+
+    /// Rebuild in-memory state from a session persisted before a restart
+    fn from_open_session(open: &OpenDmSession) -> Self {
+        let now = Utc::now();
+        let started_at = parse_db_timestamp(&open.started_at).unwrap_or(now);
+        let last_activity = parse_db_timestamp(&open.last_activity_at).unwrap_or(started_at);
+        let response_count = open.bot_message_count.max(0) as u64;
+        SessionState {
+            session_id: open.session_id.clone(),
+            user_id: open.user_id.clone(),
+            channel_id: open.channel_id.clone(),
+            _started_at: started_at,
+            last_activity,
+            message_count: open.message_count as i32,
+            user_message_count: open.user_message_count as i32,
+            bot_message_count: open.bot_message_count as i32,
+            total_user_chars: open.total_user_chars as i32,
+            total_bot_chars: open.total_bot_chars as i32,
+            response_time_total_ms: open.avg_response_time_ms.max(0) as u64 * response_count,
+            response_count,
         }
     }
 
@@ -148,16 +191,16 @@ impl SessionState {
         self.message_count += 1;
         self.bot_message_count += 1;
         self.total_bot_chars += chars as i32;
-        self.response_times.push(response_time_ms);
+        self.response_time_total_ms += response_time_ms;
+        self.response_count += 1;
         self.update_activity();
     }
 
     fn avg_response_time(&self) -> i32 {
-        if self.response_times.is_empty() {
+        if self.response_count == 0 {
             return 0;
         }
-        let sum: u64 = self.response_times.iter().sum();
-        (sum / self.response_times.len() as u64) as i32
+        (self.response_time_total_ms / self.response_count) as i32
     }
 
     fn is_timed_out(&self, timeout_minutes: i64) -> bool {
@@ -166,11 +209,19 @@ impl SessionState {
     }
 }
 
+/// Parse a SQLite `CURRENT_TIMESTAMP` value (UTC)
+fn parse_db_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .map(|dt| dt.and_utc())
+}
+
 /// Handles async tracking of DM interactions without blocking responses
 #[derive(Clone)]
 pub struct InteractionTracker {
     sender: mpsc::UnboundedSender<TrackingEvent>,
     active_sessions: Arc<DashMap<String, SessionState>>,
+    database: Database,
 }
 
 impl InteractionTracker {
@@ -188,7 +239,7 @@ impl InteractionTracker {
 
         // Spawn session timeout cleanup task
         tokio::spawn(Self::cleanup_task(
-            database,
+            database.clone(),
             active_sessions.clone(),
             sender.clone(),
         ));
@@ -196,7 +247,49 @@ impl InteractionTracker {
         InteractionTracker {
             sender,
             active_sessions,
+            database,
+        }
+    }
+
+    /// Reconcile sessions left open by a previous run
+    ///
+    /// Sessions active within the timeout window are resumed with their persisted
+    /// counters; older ones are closed as `bot_restart` at their last activity.
+    /// Returns (resumed, closed).
+    pub async fn reconcile_open_sessions(&self) -> anyhow::Result<(usize, usize)> {
+        let mut resumed = 0;
+        let mut closed = 0;
+
+        for open in self.database.get_open_dm_sessions().await? {
+            let session = SessionState::from_open_session(&open);
+            let key = format!("{}:{}", session.user_id, session.channel_id);
+
+            // A newer open session for the same channel supersedes this one
+            // (rows are ordered newest first)
+            let superseded = self.active_sessions.contains_key(&key);
+            if superseded || session.is_timed_out(SESSION_TIMEOUT_MINUTES) {
+                let reason = SessionEndReason::BotRestart;
+                self.database
+                    .end_dm_session_at_last_activity(&open.session_id, reason.as_str())
+                    .await?;
+                self.database
+                    .log_dm_event(
+                        &open.session_id,
+                        "session_end",
+                        &open.user_id,
+                        &open.channel_id,
+                        Some(reason.as_str()),
+                    )
+                    .await?;
+                closed += 1;
+            } else {
+                self.active_sessions.insert(key, session);
+                resumed += 1;
+            }
         }
+
+        info!("DM session reconciliation: {resumed} resumed, {closed} closed");
+        Ok((resumed, closed))
     }
 
     /// Get or create a session for a DM channel
@@ -205,7 +298,7 @@ impl InteractionTracker {
 
         // Check if active session exists
         if let Some(session) = self.active_sessions.get(&key) {
-            if !session.is_timed_out(30) {
+            if !session.is_timed_out(SESSION_TIMEOUT_MINUTES) {
                 return session.session_id.clone();
             }
         }
@@ -363,6 +456,28 @@ impl InteractionTracker {
         active_sessions: &DashMap<String, SessionState>,
         event: TrackingEvent,
     ) -> anyhow::Result<()> {
+        // Users who opted out of analytics with /privacy leave no session trail
+        let user_id = match &event {
+            TrackingEvent::SessionEnd { session_id, .. } => active_sessions
+                .iter()
+                .find(|entry| &entry.value().session_id == session_id)
+                .map(|entry| entry.value().user_id.clone()),
+            other => other.user_id().map(str::to_string),
+        };
+        if let Some(user_id) = user_id {
+            if !database
+                .get_privacy_settings(&user_id, None)
+                .await?
+                .analytics
+            {
+                if let TrackingEvent::SessionEnd { session_id, .. } = &event {
+                    active_sessions.retain(|_, session| &session.session_id != session_id);
+                }
+                debug!("Skipped tracking event for user {user_id} (analytics opted out)");
+                return Ok(());
+            }
+        }
+
         match event {
             TrackingEvent::SessionStart {
                 session_id,
@@ -388,15 +503,7 @@ impl InteractionTracker {
                 if let Some(key) = key_to_remove {
                     if let Some((_, session)) = active_sessions.remove(&key) {
                         // Update session with final metrics
-                        database
-                            .update_dm_session_activity(
-                                &session_id,
-                                session.message_count,
-                                session.total_user_chars,
-                                session.total_bot_chars,
-                                session.avg_response_time(),
-                            )
-                            .await?;
+                        Self::persist_session(database, &session).await?;
 
                         database
                             .end_dm_session(&session_id, reason.as_str())
@@ -425,8 +532,12 @@ impl InteractionTracker {
             } => {
                 // Update active session state
                 let key = format!("{user_id}:{channel_id}");
-                if let Some(mut session) = active_sessions.get_mut(&key) {
+                let snapshot = active_sessions.get_mut(&key).map(|mut session| {
                     session.add_user_message(character_count);
+                    session.clone()
+                });
+                if let Some(session) = snapshot {
+                    Self::persist_session(database, &session).await?;
                 }
 
                 // Log event
@@ -454,8 +565,12 @@ impl InteractionTracker {
             } => {
                 // Update active session state
                 let key = format!("{user_id}:{channel_id}");
-                if let Some(mut session) = active_sessions.get_mut(&key) {
+                let snapshot = active_sessions.get_mut(&key).map(|mut session| {
                     session.add_bot_message(character_count, response_time_ms);
+                    session.clone()
+                });
+                if let Some(session) = snapshot {
+                    Self::persist_session(database, &session).await?;
                 }
 
                 // Log event
@@ -530,6 +645,21 @@ impl InteractionTracker {
         Ok(())
     }
 
+    /// Write a session's running counters so they survive a restart
+    async fn persist_session(database: &Database, session: &SessionState) -> anyhow::Result<()> {
+        database
+            .update_dm_session_activity(
+                &session.session_id,
+                session.message_count,
+                session.user_message_count,
+                session.bot_message_count,
+                session.total_user_chars,
+                session.total_bot_chars,
+                session.avg_response_time(),
+            )
+            .await
+    }
+
     /// Background cleanup task that times out idle sessions
     async fn cleanup_task(
         _database: Database,
@@ -545,7 +675,7 @@ impl InteractionTracker {
 
             // Find timed out sessions
             for entry in active_sessions.iter() {
-                if entry.value().is_timed_out(30) {
+                if entry.value().is_timed_out(SESSION_TIMEOUT_MINUTES) {
                     timed_out_sessions.push(entry.value().session_id.clone());
                 }
             }
@@ -1,18 +1,38 @@
 //! # Analytics Feature
 //!
-//! Usage tracking, interaction analytics, and system metrics.
+//! Usage tracking, interaction analytics, system metrics, and message topic
+//! clustering.
 //!
-//! - **Version**: 1.0.0
+//! - **Version**: 1.3.0
 //! - **Since**: 0.5.0
 //! - **Toggleable**: false
+//!
+//! ## Changelog
+//! - 1.3.0: Embeddings client and k-means clustering behind /topics trending topics
+//! - 1.2.0: Metric alert rules evaluated by the metrics loop
+//! - 1.1.0: Runtime counters for per-feature tasks and OpenAI in-flight requests
+//! - 1.0.0: Initial release
 
+pub mod alerts;
+pub mod clustering;
+pub mod embeddings;
 pub mod interaction_tracker;
+pub mod runtime;
 pub mod system_info;
+pub mod topics;
 pub mod usage_tracker;
 
+pub use alerts::{alert_engine, AlertEngine, AlertMetric, AlertRule, AlertSample};
+pub use clustering::{kmeans, suggested_k, Cluster};
+pub use embeddings::{
+    cosine_similarity, openai_embedding_client, EmbeddingBatch, EmbeddingClient,
+    OpenAiEmbeddingClient, DEFAULT_EMBEDDING_MODEL,
+};
 pub use interaction_tracker::InteractionTracker;
 pub use system_info::{
     format_bytes, format_bytes_signed, format_duration, format_history, get_db_file_size,
-    metrics_collection_loop, CurrentMetrics, DiskInfo, HistoricalSummary,
+    metrics_collection_loop, CurrentMetrics, DiskInfo, HistoricalSummary, NetworkThroughput,
+    StorageUsage,
 };
-pub use usage_tracker::{CostBucket, UsageTracker};
+pub use topics::{TopicFinder, TopicMessage, TrendingTopic, TOPICS_FEATURE};
+pub use usage_tracker::{prompt_hash, CostBucket, UsageTracker};
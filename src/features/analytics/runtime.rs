@@ -0,0 +1,247 @@
+//! # Runtime Counters
+//!
+//! Live counts for /sysinfo and the TUI dashboard: long-running tokio tasks per
+//! feature and OpenAI requests currently in flight. Counts are maintained by
+//! RAII guards, so a task that panics or a request that is cancelled still
+//! decrements its counter.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with per-feature task counts and OpenAI in-flight requests
+
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::task::JoinHandle;
+
+/// Feature a tracked task belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskKind {
+    /// Plugin job and playlist execution
+    Jobs,
+    /// Periodic loops (reminders, metrics collection, heartbeats) and webhook deliveries
+    Schedulers,
+    /// IPC server listener and TUI client connections
+    Ipc,
+    /// Council, debate and story turn generation, trivia games, link previews
+    Discussions,
+}
+
+impl TaskKind {
+    pub const ALL: [TaskKind; 4] = [
+        TaskKind::Jobs,
+        TaskKind::Schedulers,
+        TaskKind::Ipc,
+        TaskKind::Discussions,
+    ];
+
+    /// Stable identifier used in /sysinfo and IPC
+    pub fn id(&self) -> &'static str {
+        match self {
+            TaskKind::Jobs => "jobs",
+            TaskKind::Schedulers => "schedulers",
+            TaskKind::Ipc => "ipc",
+            TaskKind::Discussions => "discussions",
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            TaskKind::Jobs => 0,
+            TaskKind::Schedulers => 1,
+            TaskKind::Ipc => 2,
+            TaskKind::Discussions => 3,
+        }
+    }
+}
+
+impl fmt::Display for TaskKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+/// Process-wide runtime counters
+pub struct RuntimeCounters {
+    tasks: [AtomicUsize; 4],
+    openai_in_flight: AtomicUsize,
+}
+
+static COUNTERS: RuntimeCounters = RuntimeCounters::new();
+
+/// Shared runtime counters
+pub fn runtime_counters() -> &'static RuntimeCounters {
+    &COUNTERS
+}
+
+/// Decrements its counter when dropped
+#[must_use = "the counter is decremented as soon as the guard is dropped"]
+pub struct CounterGuard<'a> {
+    counter: &'a AtomicUsize,
+}
+
+impl<'a> CounterGuard<'a> {
+    fn new(counter: &'a AtomicUsize) -> Self {
+        counter.fetch_add(1, Ordering::Relaxed);
+        Self { counter }
+    }
+}
+
+impl Drop for CounterGuard<'_> {
+    fn drop(&mut self) {
+        self.counter.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl RuntimeCounters {
+    pub const fn new() -> Self {
+        Self {
+            tasks: [
+                AtomicUsize::new(0),
+                AtomicUsize::new(0),
+                AtomicUsize::new(0),
+                AtomicUsize::new(0),
+            ],
+            openai_in_flight: AtomicUsize::new(0),
+        }
+    }
+
+    /// Count the current task as `kind` until the guard is dropped
+    pub fn track_task(&self, kind: TaskKind) -> CounterGuard<'_> {
+        CounterGuard::new(&self.tasks[kind.index()])
+    }
+
+    /// Count an OpenAI request as in flight until the guard is dropped
+    pub fn openai_request(&self) -> CounterGuard<'_> {
+        CounterGuard::new(&self.openai_in_flight)
+    }
+
+    /// `tokio::spawn`, counting the task under `kind` while it runs
+    pub fn spawn<F>(&'static self, kind: TaskKind, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        tokio::spawn(async move {
+            let _guard = self.track_task(kind);
+            future.await
+        })
+    }
+
+    pub fn tasks(&self, kind: TaskKind) -> usize {
+        self.tasks[kind.index()].load(Ordering::Relaxed)
+    }
+
+    pub fn openai_in_flight(&self) -> usize {
+        self.openai_in_flight.load(Ordering::Relaxed)
+    }
+
+    pub fn snapshot(&self) -> RuntimeSnapshot {
+        RuntimeSnapshot {
+            alive_tasks: tokio::runtime::Handle::try_current()
+                .map(|h| h.metrics().num_alive_tasks())
+                .unwrap_or(0),
+            tasks_by_feature: TaskKind::ALL
+                .iter()
+                .map(|&kind| (kind.id().to_string(), self.tasks(kind)))
+                .collect(),
+            openai_in_flight: self.openai_in_flight(),
+        }
+    }
+}
+
+impl Default for RuntimeCounters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Point-in-time copy of the runtime counters
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RuntimeSnapshot {
+    /// Every task alive in the tokio runtime, tracked or not
+    pub alive_tasks: usize,
+    pub tasks_by_feature: Vec<(String, usize)>,
+    pub openai_in_flight: usize,
+}
+
+impl RuntimeSnapshot {
+    /// "jobs 2 · schedulers 3 · ipc 1 · discussions 0"
+    pub fn feature_summary(&self) -> String {
+        self.tasks_by_feature
+            .iter()
+            .map(|(id, count)| format!("{id} {count}"))
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
+}
+
+/// Spawn on the shared counters; see [`RuntimeCounters::spawn`]
+pub fn spawn_tracked<F>(kind: TaskKind, future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    runtime_counters().spawn(kind, future)
+}
+
+/// Await an OpenAI call, counting it as in flight until it completes
+pub async fn track_openai<F: Future>(request: F) -> F::Output {
+    let _in_flight = runtime_counters().openai_request();
+    request.await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guards_decrement_on_drop() {
+        let counters = RuntimeCounters::new();
+        let first = counters.openai_request();
+        let second = counters.openai_request();
+        assert_eq!(counters.openai_in_flight(), 2);
+        drop(first);
+        assert_eq!(counters.openai_in_flight(), 1);
+        drop(second);
+        assert_eq!(counters.openai_in_flight(), 0);
+
+        {
+            let _job = counters.track_task(TaskKind::Jobs);
+            assert_eq!(counters.tasks(TaskKind::Jobs), 1);
+            assert_eq!(counters.tasks(TaskKind::Ipc), 0);
+        }
+        assert_eq!(counters.tasks(TaskKind::Jobs), 0);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_counts_while_running() {
+        let counters: &'static RuntimeCounters = Box::leak(Box::new(RuntimeCounters::new()));
+        let (started_tx, started) = tokio::sync::oneshot::channel::<()>();
+        let (release, wait) = tokio::sync::oneshot::channel::<()>();
+        let handle = counters.spawn(TaskKind::Jobs, async move {
+            started_tx.send(()).unwrap();
+            let _ = wait.await;
+        });
+
+        started.await.unwrap();
+        assert_eq!(counters.tasks(TaskKind::Jobs), 1);
+        release.send(()).unwrap();
+        handle.await.unwrap();
+        assert_eq!(counters.tasks(TaskKind::Jobs), 0);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot() {
+        let counters = RuntimeCounters::new();
+        let _ipc = counters.track_task(TaskKind::Ipc);
+        let snapshot = counters.snapshot();
+        assert_eq!(snapshot.openai_in_flight, 0);
+        assert_eq!(
+            snapshot.feature_summary(),
+            "jobs 0 · schedulers 0 · ipc 1 · discussions 0"
+        );
+    }
+}
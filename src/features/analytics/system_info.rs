@@ -2,20 +2,30 @@
 //!
 //! System diagnostics and historical metrics tracking for the /sysinfo command.
 //!
-//! - **Version**: 1.1.0
+//! - **Version**: 1.6.0
 //! - **Since**: 0.3.0
 //! - **Toggleable**: false
 //!
 //! ## Changelog
+//! - 1.6.0: Daily cleanup prunes hourly channel sentiment older than 90 days
+//! - 1.5.0: Metrics loop evaluates alert rules and sends notifications
+//! - 1.4.0: 1-minute sampling downsampled into hourly and daily tiers, 30-day history with daily cost
+//! - 1.3.0: Database/WAL and transcript storage, network throughput, per-feature task and OpenAI in-flight counts
+//! - 1.2.0: Hourly analytics rollups and configurable raw usage retention
 //! - 1.1.0: Added OpenAI usage data cleanup integration
 //! - 1.0.0: Initial implementation with current metrics and historical tracking
 
 use crate::database::Database;
+use crate::features::analytics::alerts::{alert_engine, AlertSample};
+use crate::features::analytics::runtime::{runtime_counters, RuntimeSnapshot};
+use crate::features::plugins::chunker::TEMP_DIR_PREFIX;
+use crate::features::resilience::error_line_count;
 use log::{debug, info, warn};
+use serenity::http::Http;
 use std::path::Path;
 use std::sync::Arc;
-use std::time::Duration;
-use sysinfo::{Disks, ProcessRefreshKind, ProcessesToUpdate, System};
+use std::time::{Duration, Instant};
+use sysinfo::{Disks, Networks, ProcessRefreshKind, ProcessesToUpdate, System};
 
 /// Information about a disk/mount point
 pub struct DiskInfo {
@@ -24,6 +34,97 @@ pub struct DiskInfo {
     pub used: u64,
 }
 
+/// Disk used by the database and by transcripts being processed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StorageUsage {
+    pub db_bytes: u64,
+    /// SQLite `-wal` and `-shm` files next to the database
+    pub db_wal_bytes: u64,
+    /// Chunker working directories (downloaded audio and chunks)
+    pub transcript_bytes: u64,
+    pub transcript_dirs: usize,
+}
+
+impl StorageUsage {
+    pub fn measure(db_path: &str) -> Self {
+        Self::measure_in(db_path, &std::env::temp_dir())
+    }
+
+    fn measure_in(db_path: &str, temp_dir: &Path) -> Self {
+        let db_wal_bytes = ["-wal", "-shm"]
+            .iter()
+            .map(|suffix| get_db_file_size(&format!("{db_path}{suffix}")))
+            .sum();
+
+        let mut transcript_bytes = 0;
+        let mut transcript_dirs = 0;
+        if let Ok(entries) = std::fs::read_dir(temp_dir) {
+            for entry in entries.flatten() {
+                let is_chunker_dir = entry
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with(TEMP_DIR_PREFIX);
+                if is_chunker_dir && entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+                    transcript_dirs += 1;
+                    transcript_bytes += dir_size(&entry.path());
+                }
+            }
+        }
+
+        Self {
+            db_bytes: get_db_file_size(db_path),
+            db_wal_bytes,
+            transcript_bytes,
+            transcript_dirs,
+        }
+    }
+}
+
+/// Network throughput across all non-loopback interfaces
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NetworkThroughput {
+    pub rx_bytes_per_sec: u64,
+    pub tx_bytes_per_sec: u64,
+    /// Totals since the interfaces came up
+    pub total_rx: u64,
+    pub total_tx: u64,
+}
+
+/// Measures network throughput between `start` and `finish`
+pub struct NetworkSampler {
+    networks: Networks,
+    started: Instant,
+}
+
+impl NetworkSampler {
+    pub fn start() -> Self {
+        Self {
+            networks: Networks::new_with_refreshed_list(),
+            started: Instant::now(),
+        }
+    }
+
+    pub fn finish(mut self) -> NetworkThroughput {
+        self.networks.refresh();
+        let elapsed = self.started.elapsed().as_secs_f64().max(0.001);
+
+        let mut throughput = NetworkThroughput::default();
+        let (mut rx, mut tx) = (0u64, 0u64);
+        for (name, data) in self.networks.iter() {
+            if name == "lo" {
+                continue;
+            }
+            rx += data.received();
+            tx += data.transmitted();
+            throughput.total_rx += data.total_received();
+            throughput.total_tx += data.total_transmitted();
+        }
+        throughput.rx_bytes_per_sec = (rx as f64 / elapsed) as u64;
+        throughput.tx_bytes_per_sec = (tx as f64 / elapsed) as u64;
+        throughput
+    }
+}
+
 /// Current system metrics snapshot
 pub struct CurrentMetrics {
     pub hostname: String,
@@ -42,9 +143,36 @@ pub struct CurrentMetrics {
     pub disks: Vec<DiskInfo>,
     pub bot_memory: u64,
     pub db_size: u64,
+    pub storage: StorageUsage,
+    /// Only measured by [`CurrentMetrics::sample`]
+    pub network: Option<NetworkThroughput>,
+    pub runtime: RuntimeSnapshot,
 }
 
 impl CurrentMetrics {
+    /// Refresh CPU, memory, the bot process and network over a 200ms window,
+    /// then gather everything
+    pub async fn sample(db_path: &str) -> Self {
+        let mut sys = System::new();
+        let network = NetworkSampler::start();
+        sys.refresh_cpu_usage();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        sys.refresh_cpu_usage();
+        sys.refresh_memory();
+
+        if let Ok(pid) = sysinfo::get_current_pid() {
+            sys.refresh_processes_specifics(
+                ProcessesToUpdate::Some(&[pid]),
+                true,
+                ProcessRefreshKind::new().with_memory(),
+            );
+        }
+
+        let mut metrics = Self::gather(&sys, db_path);
+        metrics.network = Some(network.finish());
+        metrics
+    }
+
     /// Gather all current system metrics
     /// Note: For accurate CPU usage, caller should wait ~200ms between System refreshes
     pub fn gather(sys: &System, db_path: &str) -> Self {
@@ -93,6 +221,9 @@ impl CurrentMetrics {
             disks,
             bot_memory,
             db_size: get_db_file_size(db_path),
+            storage: StorageUsage::measure(db_path),
+            network: None,
+            runtime: runtime_counters().snapshot(),
         }
     }
 
@@ -132,6 +263,25 @@ impl CurrentMetrics {
             ));
         }
 
+        let storage_lines = format!(
+            "WAL:     {}\n\
+            Transcr: {} in {} job dir(s)\n",
+            format_bytes(self.storage.db_wal_bytes),
+            format_bytes(self.storage.transcript_bytes),
+            self.storage.transcript_dirs
+        );
+
+        let network_line = match &self.network {
+            Some(net) => format!(
+                "Net:     ↓ {}/s ↑ {}/s (total ↓ {} ↑ {})\n",
+                format_bytes(net.rx_bytes_per_sec),
+                format_bytes(net.tx_bytes_per_sec),
+                format_bytes(net.total_rx),
+                format_bytes(net.total_tx)
+            ),
+            None => String::new(),
+        };
+
         format!(
             "**System Information**\n```\n\
             Host:    {} ({} {})\n\
@@ -143,9 +293,13 @@ impl CurrentMetrics {
             \n\
             DB:      {}\n\
             {}\
+            {}\
+            {}\
             \n\
             Bot:     v{} | Up: {}\n\
             Process: {}\n\
+            Tasks:   {} alive | {}\n\
+            OpenAI:  {} in flight\n\
             Rust:    {} | Serenity: v0.11.6\n\
             ```",
             self.hostname,
@@ -164,10 +318,15 @@ impl CurrentMetrics {
             mem_percent,
             swap_line,
             format_bytes(self.db_size),
+            storage_lines,
+            network_line,
             disk_lines,
             crate::features::get_bot_version(),
             format_duration(bot_uptime_secs),
             format_bytes(self.bot_memory),
+            self.runtime.alive_tasks,
+            self.runtime.feature_summary(),
+            self.runtime.openai_in_flight,
             rustc_version_runtime::version(),
         )
     }
@@ -216,6 +375,7 @@ pub fn format_history(
     bot_memory: HistoricalSummary,
     system_memory: HistoricalSummary,
     system_cpu: HistoricalSummary,
+    daily_cost: HistoricalSummary,
     period_label: &str,
 ) -> String {
     let mut output = format!("**Metrics History ({period_label})**\n```\n");
@@ -279,10 +439,37 @@ pub fn format_history(
         output.push_str("Sys CPU      (no data)\n");
     }
 
+    // OpenAI cost per day
+    if daily_cost.has_data {
+        output.push_str(&format!(
+            "Cost/day     {:<11} {:<11} {:<11} -\n",
+            format!("${:.2}", daily_cost.current),
+            format!("${:.2}", daily_cost.average),
+            format!("${:.2}", daily_cost.peak),
+        ));
+    } else {
+        output.push_str("Cost/day     (no data)\n");
+    }
+
     output.push_str("```");
     output
 }
 
+/// Total size of the files under `path`, without following symlinks
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.path().symlink_metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
 /// Get the size of the database file in bytes
 pub fn get_db_file_size(path: &str) -> u64 {
     Path::new(path).metadata().map(|m| m.len()).unwrap_or(0)
@@ -333,12 +520,26 @@ pub fn format_duration(total_secs: u64) -> String {
 }
 
 /// Background task that collects system metrics periodically
-pub async fn metrics_collection_loop(db: Arc<Database>, db_path: String) {
-    let mut interval = tokio::time::interval(Duration::from_secs(300)); // 5 minutes
+///
+/// Samples every minute and evaluates the alert rules against the sample;
+/// each tick also downsamples completed hours of samples into the hourly and
+/// daily metrics tiers and folds raw usage into the analytics rollups. The daily cleanup keeps raw usage rows for
+/// `raw_retention_days`, and usage rollups and daily metrics for
+/// `rollup_retention_days`.
+pub async fn metrics_collection_loop(
+    http: Arc<Http>,
+    db: Arc<Database>,
+    db_path: String,
+    raw_retention_days: i64,
+    rollup_retention_days: i64,
+) {
+    let mut interval = tokio::time::interval(Duration::from_secs(60)); // 1 minute
     let mut sys = System::new();
     let mut cleanup_counter = 0u32;
+    let alerts = alert_engine();
+    let mut last_errors = (error_line_count(), Instant::now());
 
-    info!("System metrics collection task started (interval: 5 minutes)");
+    info!("System metrics collection task started (interval: 1 minute)");
 
     loop {
         interval.tick().await;
@@ -379,10 +580,12 @@ pub async fn metrics_collection_loop(db: Arc<Database>, db_path: String) {
 
         // Record system memory percentage
         let memory_total = sys.total_memory();
+        let mut memory_percent = None;
         if memory_total > 0 {
-            let memory_percent = (sys.used_memory() as f64 / memory_total as f64) * 100.0;
+            let percent = (sys.used_memory() as f64 / memory_total as f64) * 100.0;
+            memory_percent = Some(percent);
             if let Err(e) = db
-                .store_system_metric("system_memory_percent", memory_percent)
+                .store_system_metric("system_memory_percent", percent)
                 .await
             {
                 warn!("Failed to store system_memory metric: {e}");
@@ -399,27 +602,75 @@ pub async fn metrics_collection_loop(db: Arc<Database>, db_path: String) {
 
         debug!("System metrics recorded successfully");
 
-        // Cleanup old metrics once per day (288 intervals at 5 min each)
+        // Evaluate alert rules against this sample
+        let errors = error_line_count();
+        let minutes = last_errors.1.elapsed().as_secs_f64() / 60.0;
+        let errors_per_minute = (minutes > 0.0).then(|| (errors - last_errors.0) as f64 / minutes);
+        last_errors = (errors, Instant::now());
+        let daily_spend = match db.get_spend_today().await {
+            Ok(spend) => Some(spend),
+            Err(e) => {
+                warn!("Failed to read today's OpenAI spend for alerts: {e}");
+                None
+            }
+        };
+        let sample = AlertSample {
+            memory_percent,
+            errors_per_minute,
+            daily_spend,
+        };
+        let fired = alerts.evaluate(&sample, chrono::Utc::now());
+        alerts.notify(&http, &db, &fired).await;
+
+        // Downsample completed hours into the hourly and daily tiers
+        match db.downsample_system_metrics().await {
+            Ok(buckets) if buckets > 0 => debug!("Downsampled {buckets} hourly metric buckets"),
+            Ok(_) => {}
+            Err(e) => warn!("Failed to downsample system metrics: {e}"),
+        }
+
+        // Roll up completed hours so reports never wait on raw rows
+        match db.rollup_analytics().await {
+            Ok(rows) if rows > 0 => debug!("Rolled up {rows} raw usage rows"),
+            Ok(_) => {}
+            Err(e) => warn!("Failed to roll up analytics: {e}"),
+        }
+
+        // Cleanup old metrics once per day (1440 intervals at 1 min each)
         cleanup_counter += 1;
-        if cleanup_counter >= 288 {
+        if cleanup_counter >= 1440 {
             cleanup_counter = 0;
             info!("Running daily cleanup tasks");
 
-            // Cleanup system metrics (7 days)
-            if let Err(e) = db.cleanup_old_metrics(7).await {
+            // Cleanup system metrics tiers (minute 2 days, hour 35 days, day as rollups)
+            if let Err(e) = db.cleanup_old_metrics(rollup_retention_days).await {
                 warn!("Failed to cleanup old system metrics: {e}");
             }
 
-            // Cleanup raw OpenAI usage data (7 days - detailed request-level data)
-            if let Err(e) = db.cleanup_old_openai_usage(7).await {
+            // Cleanup raw usage rows (already rolled up, kept for request-level detail)
+            let raw_days = raw_retention_days.max(1);
+            if let Err(e) = db.cleanup_old_openai_usage(raw_days).await {
                 warn!("Failed to cleanup old OpenAI usage data: {e}");
             }
+            if let Err(e) = db.cleanup_old_usage_stats(raw_days).await {
+                warn!("Failed to cleanup old command usage data: {e}");
+            }
+
+            // Cleanup analytics rollups (long-range reporting)
+            if let Err(e) = db.cleanup_old_rollups(rollup_retention_days).await {
+                warn!("Failed to cleanup old analytics rollups: {e}");
+            }
 
             // Cleanup OpenAI daily aggregates (90 days - for historical trends)
             if let Err(e) = db.cleanup_old_openai_usage_daily(90).await {
                 warn!("Failed to cleanup old OpenAI usage daily data: {e}");
             }
 
+            // Cleanup hourly channel sentiment (90 days, same as the daily aggregates)
+            if let Err(e) = db.cleanup_channel_sentiment(90).await {
+                warn!("Failed to cleanup old channel sentiment data: {e}");
+            }
+
             info!("Daily cleanup tasks completed");
         }
     }
@@ -455,6 +706,35 @@ mod tests {
         assert_eq!(format_bytes_signed(0), "+0 B");
     }
 
+    #[test]
+    fn test_storage_usage_measures_wal_and_chunker_dirs() {
+        let base = std::env::temp_dir().join(format!("sysinfo-test-{}", uuid::Uuid::new_v4()));
+        let chunker = base.join(format!("{TEMP_DIR_PREFIX}abc"));
+        std::fs::create_dir_all(chunker.join("chunks")).unwrap();
+        std::fs::create_dir_all(base.join("unrelated")).unwrap();
+        std::fs::write(chunker.join("audio.mp3"), vec![0u8; 300]).unwrap();
+        std::fs::write(chunker.join("chunks/chunk_000.mp3"), vec![0u8; 200]).unwrap();
+        std::fs::write(base.join("unrelated/big.bin"), vec![0u8; 5000]).unwrap();
+
+        let db_path = base.join("persona.db");
+        std::fs::write(&db_path, vec![0u8; 100]).unwrap();
+        std::fs::write(base.join("persona.db-wal"), vec![0u8; 40]).unwrap();
+        std::fs::write(base.join("persona.db-shm"), vec![0u8; 2]).unwrap();
+
+        let usage = StorageUsage::measure_in(db_path.to_str().unwrap(), &base);
+        std::fs::remove_dir_all(&base).unwrap();
+
+        assert_eq!(
+            usage,
+            StorageUsage {
+                db_bytes: 100,
+                db_wal_bytes: 42,
+                transcript_bytes: 500,
+                transcript_dirs: 1,
+            }
+        );
+    }
+
     #[test]
     fn test_historical_summary_empty() {
         let summary = HistoricalSummary::from_data(&[]);
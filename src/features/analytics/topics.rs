@@ -0,0 +1,377 @@
+//! # Trending Topics
+//!
+//! Embeds a channel's recent messages, clusters them and names each cluster
+//! with one chat completion. A topic is rising when at least half of its
+//! messages fall in the last quarter of the window.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use log::warn;
+use openai::chat::{ChatCompletionMessage, ChatCompletionMessageRole};
+use std::sync::Arc;
+
+use super::clustering::{kmeans, suggested_k, Cluster};
+use super::embeddings::{EmbeddingClient, DEFAULT_EMBEDDING_MODEL};
+use super::usage_tracker::{CostBucket, UsageTracker};
+use crate::core::ChatClient;
+
+/// Feature id for toggles
+pub const TOPICS_FEATURE: &str = "topics";
+
+/// Most recent messages clustered per request
+pub const MAX_MESSAGES: usize = 300;
+
+/// Most topics reported
+pub const MAX_TOPICS: usize = 6;
+
+/// Topical messages needed before clustering is worth it
+pub const MIN_MESSAGES: usize = 10;
+
+/// Messages a cluster needs to be reported
+const MIN_TOPIC_MESSAGES: usize = 3;
+
+/// Example messages shown per topic
+const EXAMPLES_PER_TOPIC: usize = 2;
+
+/// Characters kept of each example
+const MAX_EXAMPLE_CHARS: usize = 140;
+
+/// Characters of each message sent for embedding
+const MAX_EMBED_CHARS: usize = 1000;
+
+/// Examples per cluster sent to the labelling prompt
+const LABEL_EXAMPLES: usize = 5;
+
+/// Cap on the labelling completion
+const MAX_LABEL_TOKENS: u64 = 200;
+
+/// A stored message with its unix timestamp
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopicMessage {
+    pub content: String,
+    pub timestamp: i64,
+}
+
+/// A discussion topic found in a channel
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrendingTopic {
+    pub label: String,
+    pub messages: usize,
+    /// Messages in the last quarter of the window
+    pub recent: usize,
+    pub rising: bool,
+    pub examples: Vec<String>,
+}
+
+/// Whether a message says enough to be worth clustering
+pub fn is_topical(content: &str) -> bool {
+    let content = content.trim();
+    !content.starts_with('/')
+        && !content.starts_with('!')
+        && content.chars().count() >= 15
+        && content.split_whitespace().count() >= 3
+}
+
+/// Finds trending topics in a set of messages
+#[derive(Clone)]
+pub struct TopicFinder {
+    embedding_client: Arc<dyn EmbeddingClient>,
+    embedding_model: String,
+    chat_client: Arc<dyn ChatClient>,
+    chat_model: String,
+    usage_tracker: UsageTracker,
+}
+
+impl TopicFinder {
+    pub fn new(
+        embedding_client: Arc<dyn EmbeddingClient>,
+        chat_client: Arc<dyn ChatClient>,
+        chat_model: String,
+        usage_tracker: UsageTracker,
+    ) -> Self {
+        Self {
+            embedding_client,
+            embedding_model: DEFAULT_EMBEDDING_MODEL.to_string(),
+            chat_client,
+            chat_model,
+            usage_tracker,
+        }
+    }
+
+    /// Cluster `messages` posted since `since` (unix seconds) into labelled topics
+    ///
+    /// Usage is logged against `user_id` in the guild and channel asked about.
+    pub async fn find(
+        &self,
+        messages: &[TopicMessage],
+        since: i64,
+        user_id: &str,
+        guild_id: Option<&str>,
+        channel_id: &str,
+    ) -> Result<Vec<TrendingTopic>> {
+        let messages: Vec<&TopicMessage> =
+            messages.iter().filter(|m| is_topical(&m.content)).collect();
+        if messages.len() < MIN_MESSAGES {
+            return Ok(Vec::new());
+        }
+
+        let inputs: Vec<String> = messages
+            .iter()
+            .map(|m| m.content.chars().take(MAX_EMBED_CHARS).collect())
+            .collect();
+        let batch = self
+            .embedding_client
+            .embed(&self.embedding_model, &inputs)
+            .await?;
+        self.usage_tracker.log_embedding(
+            &self.embedding_model,
+            batch.tokens,
+            user_id,
+            guild_id,
+            Some(channel_id),
+            CostBucket::Topics,
+        );
+
+        let clusters = kmeans(&batch.vectors, suggested_k(batch.vectors.len(), MAX_TOPICS));
+        let now = chrono::Utc::now().timestamp();
+        let mut topics = summarize_clusters(&messages, &clusters, since, now);
+        if topics.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let samples: Vec<Vec<&str>> = topics
+            .iter()
+            .map(|(cluster, _)| {
+                cluster
+                    .members
+                    .iter()
+                    .take(LABEL_EXAMPLES)
+                    .map(|i| inputs[*i].as_str())
+                    .collect()
+            })
+            .collect();
+        let labels = match self.label(&samples, user_id, guild_id, channel_id).await {
+            Ok(labels) => labels,
+            Err(e) => {
+                warn!("⚠️ Failed to label topics in {channel_id}: {e}");
+                Vec::new()
+            }
+        };
+        for (i, (_, topic)) in topics.iter_mut().enumerate() {
+            if let Some(Some(label)) = labels.get(i) {
+                topic.label = label.clone();
+            }
+        }
+        Ok(topics.into_iter().map(|(_, topic)| topic).collect())
+    }
+
+    /// Ask the chat model for a short label per cluster
+    async fn label(
+        &self,
+        samples: &[Vec<&str>],
+        user_id: &str,
+        guild_id: Option<&str>,
+        channel_id: &str,
+    ) -> Result<Vec<Option<String>>> {
+        let mut prompt = String::new();
+        for (i, examples) in samples.iter().enumerate() {
+            prompt.push_str(&format!("Group {}:\n", i + 1));
+            for example in examples {
+                prompt.push_str(&format!("- {}\n", example.replace('\n', " ")));
+            }
+        }
+        let completion = self
+            .chat_client
+            .create_chat_completion_limited(
+                &self.chat_model,
+                vec![
+                    ChatCompletionMessage {
+                        role: ChatCompletionMessageRole::System,
+                        content: Some(
+                            "Each group holds chat messages about one discussion topic. \
+                             Name each group's topic in 2-5 words. Answer with one line \
+                             per group as `<number>. <topic>` and nothing else."
+                                .to_string(),
+                        ),
+                        name: None,
+                        function_call: None,
+                        tool_call_id: None,
+                        tool_calls: None,
+                    },
+                    ChatCompletionMessage {
+                        role: ChatCompletionMessageRole::User,
+                        content: Some(prompt),
+                        name: None,
+                        function_call: None,
+                        tool_call_id: None,
+                        tool_calls: None,
+                    },
+                ],
+                Some(MAX_LABEL_TOKENS),
+            )
+            .await?;
+
+        if let Some(usage) = &completion.usage {
+            self.usage_tracker.log_chat(
+                &self.chat_model,
+                usage.prompt_tokens,
+                usage.completion_tokens,
+                usage.total_tokens,
+                user_id,
+                guild_id,
+                Some(channel_id),
+                None,
+                CostBucket::Topics,
+            );
+        }
+
+        let text = completion
+            .choices
+            .first()
+            .and_then(|choice| choice.message.content.clone())
+            .unwrap_or_default();
+        Ok(parse_labels(&text, samples.len()))
+    }
+}
+
+/// Turn clusters into topics, keeping large enough ones and ranking by size
+/// with recent messages counted twice
+///
+/// Each topic is returned with its cluster so the caller can label it; labels
+/// default to `Topic <n>`.
+fn summarize_clusters<'a>(
+    messages: &[&TopicMessage],
+    clusters: &'a [Cluster],
+    since: i64,
+    now: i64,
+) -> Vec<(&'a Cluster, TrendingTopic)> {
+    let recent_cutoff = since + (now - since) * 3 / 4;
+    let mut topics: Vec<(&Cluster, TrendingTopic)> = clusters
+        .iter()
+        .filter(|cluster| cluster.members.len() >= MIN_TOPIC_MESSAGES)
+        .map(|cluster| {
+            let recent = cluster
+                .members
+                .iter()
+                .filter(|i| messages[**i].timestamp >= recent_cutoff)
+                .count();
+            let topic = TrendingTopic {
+                label: String::new(),
+                messages: cluster.members.len(),
+                recent,
+                rising: recent * 2 >= cluster.members.len(),
+                examples: cluster
+                    .members
+                    .iter()
+                    .take(EXAMPLES_PER_TOPIC)
+                    .map(|i| {
+                        messages[*i]
+                            .content
+                            .trim()
+                            .chars()
+                            .take(MAX_EXAMPLE_CHARS)
+                            .collect()
+                    })
+                    .collect(),
+            };
+            (cluster, topic)
+        })
+        .collect();
+    topics.sort_by_key(|(_, topic)| std::cmp::Reverse(topic.messages + topic.recent));
+    topics.truncate(MAX_TOPICS);
+    for (i, (_, topic)) in topics.iter_mut().enumerate() {
+        topic.label = format!("Topic {}", i + 1);
+    }
+    topics
+}
+
+/// Read `<n>. <label>` lines into one slot per group
+fn parse_labels(text: &str, count: usize) -> Vec<Option<String>> {
+    let mut labels = vec![None; count];
+    for line in text.lines() {
+        let line = line.trim().trim_start_matches(['-', '*', ' ']);
+        let Some((number, label)) = line.split_once(['.', ')', ':']) else {
+            continue;
+        };
+        let number = number.trim().trim_start_matches("Group").trim();
+        let label = label.trim().trim_matches(['*', '"', '`']).trim();
+        if let Ok(n) = number.parse::<usize>() {
+            if (1..=count).contains(&n) && !label.is_empty() {
+                labels[n - 1] = Some(label.chars().take(60).collect());
+            }
+        }
+    }
+    labels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_topical() {
+        assert!(is_topical("anyone know why the build is failing"));
+        assert!(!is_topical("lol ok"));
+        assert!(!is_topical("/ask what is going on here"));
+        assert!(!is_topical("aaaaaaaaaaaaaaaaaaaaaaa"));
+    }
+
+    #[test]
+    fn test_parse_labels() {
+        let labels = parse_labels(
+            "1. Release build\n2) **Raid night**\nGroup 3: Music\n9. Nope",
+            3,
+        );
+        assert_eq!(
+            labels,
+            vec![
+                Some("Release build".to_string()),
+                Some("Raid night".to_string()),
+                Some("Music".to_string())
+            ]
+        );
+        assert_eq!(parse_labels("no numbers here", 2), vec![None, None]);
+    }
+
+    #[test]
+    fn test_summarize_clusters() {
+        let messages: Vec<TopicMessage> = (0..8)
+            .map(|i| TopicMessage {
+                content: format!("message number {i}"),
+                timestamp: if i < 4 { 100 } else { 190 },
+            })
+            .collect();
+        let refs: Vec<&TopicMessage> = messages.iter().collect();
+        let clusters = vec![
+            Cluster {
+                members: vec![0, 1, 2],
+                centroid: vec![],
+            },
+            Cluster {
+                members: vec![4, 5, 6],
+                centroid: vec![],
+            },
+            Cluster {
+                members: vec![3, 7],
+                centroid: vec![],
+            },
+        ];
+        let topics = summarize_clusters(&refs, &clusters, 0, 200);
+        assert_eq!(topics.len(), 2);
+        // Same size, but the recent cluster ranks first
+        assert_eq!(topics[0].1.recent, 3);
+        assert!(topics[0].1.rising);
+        assert_eq!(topics[0].1.label, "Topic 1");
+        assert_eq!(
+            topics[0].1.examples,
+            vec!["message number 4", "message number 5"]
+        );
+        assert_eq!(topics[1].1.recent, 0);
+        assert!(!topics[1].1.rising);
+    }
+}
@@ -1,13 +1,32 @@
 //! # Feature: OpenAI Usage Tracking
 //!
 //! Captures and stores OpenAI API usage metrics for cost analysis and monitoring.
-//! Supports ChatCompletion tokens, Whisper audio duration, and DALL-E image generation.
+//! Supports ChatCompletion tokens, Whisper audio duration, DALL-E image generation
+//! and embeddings.
 //!
-//! - **Version**: 1.2.0
+//! - **Version**: 1.20.0
 //! - **Since**: 0.5.0
 //! - **Toggleable**: false
 //!
 //! ## Changelog
+//! - 1.20.0: Birthdays cost bucket for birthday and anniversary wishes
+//! - 1.19.0: DmHistory cost bucket for DM session titles and /history recaps
+//! - 1.18.0: Dice cost bucket for persona-narrated /roll outcomes
+//! - 1.17.0: Convert cost bucket for persona-phrased /convert results
+//! - 1.16.0: SendLater cost bucket for persona rewrites of /send_later messages
+//! - 1.15.0: AutoResponse cost bucket for persona-generated /autoresponse replies
+//! - 1.14.0: Verification cost bucket for persona-asked verification questions
+//! - 1.13.0: AnonQuestions cost bucket for persona answers to anonymous questions
+//! - 1.12.0: Support cost bucket for first-line ticket answers and summaries
+//! - 1.11.0: Qotd cost bucket for persona-written questions of the day
+//! - 1.10.0: ChannelTopics cost bucket for scheduled questions of the day
+//! - 1.9.0: Language cost bucket for channel language nudges
+//! - 1.8.0: Embedding usage events and Topics cost bucket for /topics
+//! - 1.7.0: Report cost bucket for weekly server report narratives
+//! - 1.6.0: Ticket cost bucket for /ticket message summaries
+//! - 1.5.0: Story cost bucket for /story paragraphs and endings
+//! - 1.4.0: Trivia cost bucket for /trivia question generation
+//! - 1.3.0: DALL-E events carry a prompt hash for per-channel image cost breakdowns
 //! - 1.2.0: Updated pricing to February 2026, added GPT-5.x, GPT-4.1, O-series,
 //!          GPT Image 1.5, Sora, TTS, embeddings, and helper cost functions
 //! - 1.1.0: Added CostBucket categorization to track usage by feature purpose
@@ -356,6 +375,40 @@ pub enum CostBucket {
     Imagine,
     /// /fetch webpage summaries
     Fetch,
+    /// /trivia question generation
+    Trivia,
+    /// /story paragraphs and endings
+    Story,
+    /// /ticket message summaries
+    Ticket,
+    /// Weekly server report narratives
+    Report,
+    /// /topics embeddings and cluster labels
+    Topics,
+    /// Channel language nudges and translations
+    Language,
+    /// Scheduled channel topic questions of the day
+    ChannelTopics,
+    /// Persona-written /qotd questions
+    Qotd,
+    /// /support first-line answers and ticket summaries
+    Support,
+    /// Persona answers to /ask_anon questions
+    AnonQuestions,
+    /// Persona greetings asking new members their verification question
+    Verification,
+    /// Persona replies to /autoresponse triggers
+    AutoResponse,
+    /// Persona rewrites of /send_later messages
+    SendLater,
+    /// Persona phrasing of /convert results
+    Convert,
+    /// Persona narration of /roll outcomes
+    Dice,
+    /// DM session titles and /history recaps
+    DmHistory,
+    /// Birthday and anniversary wishes
+    Birthdays,
     /// Legacy data or unknown source
     Unknown,
 }
@@ -374,6 +427,23 @@ impl CostBucket {
             CostBucket::Transcription => "transcription",
             CostBucket::Imagine => "imagine",
             CostBucket::Fetch => "fetch",
+            CostBucket::Trivia => "trivia",
+            CostBucket::Story => "story",
+            CostBucket::Ticket => "ticket",
+            CostBucket::Report => "report",
+            CostBucket::Topics => "topics",
+            CostBucket::Language => "language",
+            CostBucket::ChannelTopics => "channel_topics",
+            CostBucket::Qotd => "qotd",
+            CostBucket::Support => "support",
+            CostBucket::AnonQuestions => "anon_questions",
+            CostBucket::Verification => "verification",
+            CostBucket::AutoResponse => "autoresponse",
+            CostBucket::SendLater => "send_later",
+            CostBucket::Convert => "convert",
+            CostBucket::Dice => "dice",
+            CostBucket::DmHistory => "dm_history",
+            CostBucket::Birthdays => "birthdays",
             CostBucket::Unknown => "unknown",
         }
     }
@@ -407,11 +477,38 @@ pub enum UsageEvent {
         size: String,
         quality: String,
         image_count: u32,
+        prompt_hash: Option<String>,
         user_id: String,
         guild_id: Option<String>,
         channel_id: Option<String>,
         cost_bucket: CostBucket,
     },
+    /// Embeddings API
+    Embedding {
+        model: String,
+        tokens: u32,
+        user_id: String,
+        guild_id: Option<String>,
+        channel_id: Option<String>,
+        cost_bucket: CostBucket,
+    },
+}
+
+/// Hash an image prompt so repeated prompts can be grouped without storing the text
+///
+/// Uses 64-bit FNV-1a over the trimmed, lowercased prompt so the value is stable
+/// across builds and restarts.
+pub fn prompt_hash(prompt: &str) -> String {
+    const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+    const FNV_PRIME: u64 = 0x100000001b3;
+
+    let normalized = prompt.trim().to_lowercase();
+    let mut hash = FNV_OFFSET;
+    for byte in normalized.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    format!("{hash:016x}")
 }
 
 /// Handles async logging of OpenAI usage without blocking API responses
@@ -485,11 +582,15 @@ impl UsageTracker {
     }
 
     /// Log a DALL-E image generation usage event (non-blocking)
+    ///
+    /// `prompt` is hashed before queuing; only the hash is persisted.
+    #[allow(clippy::too_many_arguments)]
     pub fn log_dalle(
         &self,
         size: &str,
         quality: &str,
         image_count: u32,
+        prompt: Option<&str>,
         user_id: &str,
         guild_id: Option<&str>,
         channel_id: Option<&str>,
@@ -499,6 +600,7 @@ impl UsageTracker {
             size: size.to_string(),
             quality: quality.to_string(),
             image_count,
+            prompt_hash: prompt.map(prompt_hash),
             user_id: user_id.to_string(),
             guild_id: guild_id.map(String::from),
             channel_id: channel_id.map(String::from),
@@ -510,6 +612,30 @@ impl UsageTracker {
         }
     }
 
+    /// Log an embeddings usage event (non-blocking)
+    pub fn log_embedding(
+        &self,
+        model: &str,
+        tokens: u32,
+        user_id: &str,
+        guild_id: Option<&str>,
+        channel_id: Option<&str>,
+        cost_bucket: CostBucket,
+    ) {
+        let event = UsageEvent::Embedding {
+            model: model.to_string(),
+            tokens,
+            user_id: user_id.to_string(),
+            guild_id: guild_id.map(String::from),
+            channel_id: channel_id.map(String::from),
+            cost_bucket,
+        };
+
+        if let Err(e) = self.sender.send(event) {
+            warn!("Failed to queue embedding usage event: {e}");
+        }
+    }
+
     /// Background task that processes usage events
     async fn background_logger(
         database: Database,
@@ -587,6 +713,7 @@ impl UsageTracker {
                 size,
                 quality,
                 image_count,
+                prompt_hash,
                 user_id,
                 guild_id,
                 channel_id,
@@ -599,6 +726,7 @@ impl UsageTracker {
                         size,
                         *image_count,
                         cost,
+                        prompt_hash.as_deref(),
                         user_id,
                         guild_id.as_deref(),
                         channel_id.as_deref(),
@@ -611,7 +739,54 @@ impl UsageTracker {
                     image_count, size, cost_bucket.as_str(), cost
                 );
             }
+            UsageEvent::Embedding {
+                model,
+                tokens,
+                user_id,
+                guild_id,
+                channel_id,
+                cost_bucket,
+            } => {
+                let cost = pricing::calculate_embedding_cost(model, *tokens);
+
+                database
+                    .log_openai_embedding_usage(
+                        model,
+                        *tokens,
+                        cost,
+                        user_id,
+                        guild_id.as_deref(),
+                        channel_id.as_deref(),
+                        cost_bucket.as_str(),
+                    )
+                    .await?;
+
+                debug!(
+                    "Logged embedding usage: {} tokens (model: {}, bucket: {}, cost: ${:.6})",
+                    tokens, model, cost_bucket.as_str(), cost
+                );
+            }
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompt_hash_is_stable_and_normalized() {
+        let a = prompt_hash("A cat in space");
+        let b = prompt_hash("  a CAT in space ");
+        assert_eq!(a, b);
+        assert_eq!(a.len(), 16);
+        assert_ne!(a, prompt_hash("a dog in space"));
+    }
+
+    #[test]
+    fn test_dalle_cost_wide_hd() {
+        let cost = pricing::calculate_dalle_cost("1792x1024", "hd", 2);
+        assert!((cost - pricing::DALLE3_HD_WIDE * 2.0).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,170 @@
+//! # Feature: Anonymous Questions
+//!
+//! `/ask_anon` relays a member's question to the channel picked with
+//! `/anon setup` without saying who asked. The author is stored privately so
+//! admins can look it up with `/anon reveal` when a question is abusive. With
+//! moderation on, questions wait in a queue (announced in an optional review
+//! channel) until an admin approves or rejects them. When persona answers are
+//! on, the channel's persona replies to each posted question.
+//!
+//! - **Version**: 1.0.1
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.1: The generic slash command logs and XP grants leave out /ask_anon authors
+//! - 1.0.0: Initial release with anonymous posting, moderation queue and persona answers
+
+use serenity::builder::CreateEmbed;
+
+use crate::database::AnonQuestion;
+
+/// Feature id for toggles
+pub const ANON_FEATURE: &str = "anon_questions";
+
+/// The command members ask anonymously with
+pub const ASK_COMMAND: &str = "ask_anon";
+
+/// Guild setting holding the channel questions are posted in
+pub const CHANNEL_SETTING: &str = "anon_channel";
+
+/// Guild setting holding the channel new questions are announced in for review
+pub const REVIEW_CHANNEL_SETTING: &str = "anon_review_channel";
+
+/// Guild setting turning the moderation queue on (`enabled`) or off
+pub const MODERATION_SETTING: &str = "anon_moderation";
+
+/// Guild setting turning persona answers on (`enabled`) or off
+pub const PERSONA_SETTING: &str = "anon_persona_answers";
+
+/// Stand-in user for persona lookups, so answers never depend on the author
+pub const ANON_USER_ID: &str = "system_anon";
+
+/// Longest question a member can ask
+pub const MAX_QUESTION_CHARS: usize = 1000;
+
+/// Pending questions shown by `/anon queue`
+pub const QUEUE_LIMIT: i64 = 20;
+
+/// Characters of each question shown in the queue
+const QUEUE_PREVIEW_CHARS: usize = 100;
+
+const EMBED_COLOR: u32 = 0x95A5A6;
+
+/// The invoker of `command` as it may appear in logs, hiding `/ask_anon` authors
+pub fn loggable_user<'a>(command: &str, user_id: &'a str) -> &'a str {
+    if command == ASK_COMMAND {
+        "anonymous"
+    } else {
+        user_id
+    }
+}
+
+/// Trim a question, rejecting empty or oversized ones
+pub fn validate_question(input: &str) -> Result<String, &'static str> {
+    let content = input.trim();
+    if content.is_empty() {
+        return Err("The question can't be empty.");
+    }
+    if content.chars().count() > MAX_QUESTION_CHARS {
+        return Err("Questions must be at most 1000 characters.");
+    }
+    Ok(content.to_string())
+}
+
+/// Embed a question is posted as; it never mentions the author
+pub fn question_embed(question: &AnonQuestion) -> CreateEmbed {
+    let mut embed = CreateEmbed::default();
+    embed
+        .title(format!("❓ Anonymous question #{}", question.id))
+        .description(&question.content)
+        .color(EMBED_COLOR)
+        .footer(|f| f.text("Ask your own with /ask_anon"));
+    embed
+}
+
+/// System prompt for the persona's reply to a posted question
+pub fn answer_prompt(persona_prompt: &str) -> String {
+    format!(
+        "{persona_prompt}\n\n\
+        A server member asked the following question anonymously. Answer it in your \
+        characteristic style in a few sentences. Don't guess who asked, and if the question \
+        is about the server itself and you don't know the answer, say a moderator can help."
+    )
+}
+
+/// One line of `/anon queue`
+pub fn queue_line(question: &AnonQuestion) -> String {
+    let mut preview: String = question
+        .content
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(QUEUE_PREVIEW_CHARS)
+        .collect();
+    if question.content.chars().count() > QUEUE_PREVIEW_CHARS {
+        preview.push('…');
+    }
+    format!("`#{}` <t:{}:R> {preview}", question.id, question.created_at)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn question(content: &str) -> AnonQuestion {
+        AnonQuestion {
+            id: 7,
+            guild_id: "1".to_string(),
+            author_id: "42".to_string(),
+            content: content.to_string(),
+            status: "pending".to_string(),
+            created_at: 1_700_000_000,
+            reviewed_by: None,
+            message_id: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_question() {
+        assert_eq!(
+            validate_question("  Is the event still on? "),
+            Ok("Is the event still on?".to_string())
+        );
+        assert!(validate_question("  ").is_err());
+        assert!(validate_question(&"x".repeat(1001)).is_err());
+    }
+
+    #[test]
+    fn test_loggable_user_hides_ask_anon_authors() {
+        assert_eq!(loggable_user(ASK_COMMAND, "42"), "anonymous");
+        assert_eq!(loggable_user("anon", "42"), "42");
+        assert_eq!(loggable_user("ask", "42"), "42");
+    }
+
+    #[test]
+    fn test_question_embed_hides_author() {
+        let embed = question_embed(&question("Who runs the wiki?"));
+        assert_eq!(embed.0["title"], "❓ Anonymous question #7");
+        assert_eq!(embed.0["description"], "Who runs the wiki?");
+        assert!(!embed.0.contains_key("author"));
+        assert!(!embed.0.contains_key("fields"));
+    }
+
+    #[test]
+    fn test_answer_prompt() {
+        let prompt = answer_prompt("You are Obi.");
+        assert!(prompt.starts_with("You are Obi.\n\n"));
+        assert!(prompt.contains("anonymously"));
+    }
+
+    #[test]
+    fn test_queue_line() {
+        assert_eq!(
+            queue_line(&question("Why  is\nthe raid late?")),
+            "`#7` <t:1700000000:R> Why is the raid late?"
+        );
+        assert!(queue_line(&question(&"word ".repeat(40))).ends_with('…'));
+    }
+}
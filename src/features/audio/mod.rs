@@ -8,4 +8,4 @@
 
 pub mod transcriber;
 
-pub use transcriber::{AudioTranscriber, TranscriptionResult};
+pub use transcriber::{AudioTranscriber, NoAudioTrackError, TranscriptionResult};
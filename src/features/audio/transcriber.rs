@@ -3,21 +3,35 @@
 //! Whisper-powered transcription of audio attachments with automatic format conversion.
 //! Supports a wide range of audio and video formats via ffmpeg conversion.
 //!
-//! - **Version**: 1.4.0
+//! - **Version**: 1.8.0
 //! - **Since**: 0.1.0
 //! - **Toggleable**: true
 //!
 //! ## Changelog
+//! - 1.8.0: Whisper and ffmpeg sit behind TranscriptionBackend so tests can inject them; chunks go to a directory removed even when a chunk fails
+//! - 1.7.0: Video attachments have their audio track extracted with ffmpeg before transcription; NoAudioTrackError for silent videos
+//! - 1.6.0: Files over Whisper's 25MB limit are split into overlapping chunks with ffmpeg and the transcripts stitched together
+//! - 1.5.1: Whisper calls are counted as OpenAI in-flight requests
+//! - 1.5.0: Guarded by the Whisper circuit breaker
 //! - 1.4.0: Added audio duration tracking for usage metrics via ffprobe
 //! - 1.3.0: Fixed double-posting bug, added configurable output mode (transcription_only/with_commentary)
 //! - 1.2.0: Added ffmpeg conversion for broader format support
 //! - 1.1.0: Added configurable transcription modes (always/mention_only/disabled)
 //! - 1.0.0: Initial release with Whisper API integration
 
+use crate::features::analytics::runtime::runtime_counters;
+use crate::features::plugins::chunker::{
+    overlapping_windows, split_windows, stitch_transcripts, TEMP_DIR_PREFIX,
+};
+use crate::features::resilience::{circuit_breakers, Dependency};
 use anyhow::Result;
+use async_trait::async_trait;
 use log::{debug, error, info, warn};
+use std::fmt;
+use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::time::Instant;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::fs;
 
 /// Result of audio transcription with duration for usage tracking
@@ -36,17 +50,77 @@ const SUPPORTED_FORMATS: &[&str] = &[
     // Whisper native
     ".mp3", ".mp4", ".m4a", ".wav", ".webm", ".mpeg", ".mpga",
     // Require conversion via ffmpeg
-    ".flac", ".ogg", ".aac", ".wma", ".mov", ".avi", ".mkv", ".opus", ".m4v",
+    ".flac", ".ogg", ".aac", ".wma", ".mov", ".avi", ".mkv", ".opus", ".m4v", ".wmv", ".flv",
+    ".3gp",
 ];
 
+/// Video containers; only their audio track is sent to Whisper
+const VIDEO_FORMATS: &[&str] = &[
+    ".mp4", ".webm", ".mov", ".avi", ".mkv", ".m4v", ".wmv", ".flv", ".3gp",
+];
+
+/// The video has no audio track to transcribe
+#[derive(Debug)]
+pub struct NoAudioTrackError;
+
+impl fmt::Display for NoAudioTrackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "That video doesn't have an audio track to transcribe.")
+    }
+}
+
+impl std::error::Error for NoAudioTrackError {}
+
+/// Largest file the Whisper API accepts
+const WHISPER_MAX_BYTES: u64 = 25 * 1024 * 1024;
+
+/// Chunk length for files over the Whisper limit
+const LARGE_FILE_CHUNK_SECS: u64 = 600;
+
+/// Seconds each chunk repeats from the start of the next
+const CHUNK_OVERLAP_SECS: u64 = 5;
+
+/// How long ffmpeg gets to cut one chunk
+const CHUNK_SPLIT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// The tools transcription shells out to: Whisper for speech, ffmpeg and
+/// ffprobe for audio tracks, conversion and chunking
+#[async_trait]
+pub trait TranscriptionBackend: Send + Sync {
+    /// Whisper transcript of a file within the upload limit
+    async fn transcribe(&self, file_path: &str) -> Result<String>;
+
+    /// Write a video's audio track next to it, returning the new file's path
+    fn extract_audio_track(&self, input_path: &str) -> Result<String>;
+
+    /// Write an mp3 copy next to the input, returning the new file's path
+    fn convert_to_mp3(&self, input_path: &str) -> Result<String>;
+
+    /// Duration in seconds, or 0.0 when it can't be read
+    fn audio_duration(&self, file_path: &str) -> f64;
+
+    /// Cut `(start, length)` windows into chunk files inside `chunk_dir`
+    async fn split(
+        &self,
+        file_path: &str,
+        windows: &[(u64, u64)],
+        chunk_dir: &Path,
+    ) -> Result<Vec<PathBuf>>;
+}
+
 #[derive(Clone)]
 pub struct AudioTranscriber {
-    openai_api_key: String,
+    backend: Arc<dyn TranscriptionBackend>,
 }
 
 impl AudioTranscriber {
     pub fn new(openai_api_key: String) -> Self {
-        AudioTranscriber { openai_api_key }
+        Self::with_backend(Arc::new(WhisperBackend { openai_api_key }))
+    }
+
+    /// Transcriber running on `backend` instead of the Whisper API and ffmpeg
+    pub fn with_backend(backend: Arc<dyn TranscriptionBackend>) -> Self {
+        AudioTranscriber { backend }
     }
 
     pub async fn transcribe_file(&self, file_path: &str) -> Result<String> {
@@ -60,6 +134,211 @@ impl AudioTranscriber {
             return Err(anyhow::anyhow!("Audio file not found: {}", file_path));
         }
 
+        self.backend.transcribe(file_path).await
+    }
+
+    /// Transcribe a file over Whisper's size limit in overlapping chunks and
+    /// stitch the results back together
+    ///
+    /// Chunks are cut into a fresh directory that is removed afterwards, also
+    /// when splitting or a chunk's transcription fails.
+    async fn transcribe_large_file(
+        &self,
+        file_path: &str,
+        duration_seconds: f64,
+    ) -> Result<String> {
+        if duration_seconds <= 0.0 {
+            return Err(anyhow::anyhow!(
+                "Couldn't read the duration of {file_path} to split it"
+            ));
+        }
+        let windows = overlapping_windows(
+            duration_seconds.ceil() as u64,
+            LARGE_FILE_CHUNK_SECS,
+            CHUNK_OVERLAP_SECS,
+        );
+
+        let chunk_dir =
+            std::env::temp_dir().join(format!("{TEMP_DIR_PREFIX}{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&chunk_dir).await?;
+        let transcript = self
+            .transcribe_chunks(file_path, &windows, &chunk_dir)
+            .await;
+        if let Err(e) = fs::remove_dir_all(&chunk_dir).await {
+            warn!("Failed to cleanup chunk directory {chunk_dir:?}: {e}");
+        }
+        transcript
+    }
+
+    async fn transcribe_chunks(
+        &self,
+        file_path: &str,
+        windows: &[(u64, u64)],
+        chunk_dir: &Path,
+    ) -> Result<String> {
+        let chunk_paths = self.backend.split(file_path, windows, chunk_dir).await?;
+        let mut parts = Vec::with_capacity(chunk_paths.len());
+        for (index, chunk_path) in chunk_paths.iter().enumerate() {
+            info!(
+                "Transcribing chunk {}/{} of {file_path}",
+                index + 1,
+                chunk_paths.len()
+            );
+            let chunk = chunk_path
+                .to_str()
+                .ok_or_else(|| anyhow::anyhow!("Invalid chunk path"))?;
+            parts.push(self.transcribe_file(chunk).await?);
+        }
+        Ok(stitch_transcripts(&parts))
+    }
+
+    /// Check if file is a supported audio/video format
+    fn is_audio_file(&self, file_path: &str) -> bool {
+        let file_path_lower = file_path.to_lowercase();
+        SUPPORTED_FORMATS
+            .iter()
+            .any(|ext| file_path_lower.ends_with(ext))
+    }
+
+    /// Check if file is a video whose audio track should be extracted
+    pub fn is_video_file(filename: &str) -> bool {
+        let lower = filename.to_lowercase();
+        VIDEO_FORMATS.iter().any(|ext| lower.ends_with(ext))
+    }
+
+    /// Check if file format needs conversion before sending to Whisper
+    fn needs_conversion(&self, filename: &str) -> bool {
+        let lower = filename.to_lowercase();
+        !WHISPER_NATIVE_FORMATS
+            .iter()
+            .any(|ext| lower.ends_with(ext))
+    }
+
+    /// Check if ffmpeg is available on the system
+    pub fn is_ffmpeg_available() -> bool {
+        Command::new("ffmpeg")
+            .arg("-version")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+
+    /// Download and transcribe with duration tracking
+    pub async fn download_and_transcribe_with_duration(
+        &self,
+        url: &str,
+        filename: &str,
+    ) -> Result<TranscriptionResult> {
+        let temp_file = format!("/tmp/discord_audio_{filename}");
+
+        info!("Downloading audio attachment: {filename}");
+
+        // Download the file
+        let output = Command::new("curl")
+            .args(["-o", &temp_file, url])
+            .output()?;
+
+        if !output.status.success() {
+            return Err(anyhow::anyhow!("Failed to download audio file"));
+        }
+
+        self.transcribe_downloaded(&temp_file, filename).await
+    }
+
+    /// Transcribe a downloaded attachment, removing it and any converted copy
+    async fn transcribe_downloaded(
+        &self,
+        temp_file: &str,
+        filename: &str,
+    ) -> Result<TranscriptionResult> {
+        let mut converted_file: Option<String> = None;
+
+        // Videos keep only their audio track; other formats Whisper can't read are converted
+        let file_to_transcribe = if Self::is_video_file(filename) || self.needs_conversion(filename)
+        {
+            let converted = if Self::is_video_file(filename) {
+                self.backend.extract_audio_track(temp_file)
+            } else {
+                info!("Format requires conversion: {filename}");
+                self.backend.convert_to_mp3(temp_file)
+            };
+
+            match converted {
+                Ok(mp3_path) => {
+                    converted_file = Some(mp3_path.clone());
+                    mp3_path
+                }
+                Err(e) => {
+                    // Cleanup original file before returning error
+                    let _ = fs::remove_file(temp_file).await;
+                    return Err(e);
+                }
+            }
+        } else {
+            temp_file.to_string()
+        };
+
+        // Get audio duration before transcription (for usage tracking)
+        let duration_seconds = self.backend.audio_duration(&file_to_transcribe);
+        info!("Audio duration: {duration_seconds:.1}s");
+
+        // Transcribe the file, in chunks when it's over Whisper's upload limit
+        let size = fs::metadata(&file_to_transcribe)
+            .await
+            .map(|m| m.len())
+            .unwrap_or(0);
+        let transcription = if size > WHISPER_MAX_BYTES {
+            info!(
+                "Audio is {:.1} MB, over Whisper's limit - transcribing in chunks",
+                size as f64 / (1024.0 * 1024.0)
+            );
+            self.transcribe_large_file(&file_to_transcribe, duration_seconds)
+                .await
+        } else {
+            self.transcribe_file(&file_to_transcribe).await
+        };
+
+        // Cleanup temp files
+        if let Err(e) = fs::remove_file(temp_file).await {
+            warn!("Failed to cleanup temp file {temp_file}: {e}");
+        }
+
+        if let Some(ref converted) = converted_file {
+            if let Err(e) = fs::remove_file(converted).await {
+                warn!("Failed to cleanup converted file {converted}: {e}");
+            }
+        }
+
+        transcription.map(|text| TranscriptionResult {
+            text,
+            duration_seconds,
+        })
+    }
+
+    /// Legacy method for backwards compatibility
+    pub async fn download_and_transcribe_attachment(
+        &self,
+        url: &str,
+        filename: &str,
+    ) -> Result<String> {
+        let result = self
+            .download_and_transcribe_with_duration(url, filename)
+            .await?;
+        Ok(result.text)
+    }
+}
+
+/// Live backend: the Whisper API through curl, and ffmpeg and ffprobe
+struct WhisperBackend {
+    openai_api_key: String,
+}
+
+#[async_trait]
+impl TranscriptionBackend for WhisperBackend {
+    async fn transcribe(&self, file_path: &str) -> Result<String> {
+        circuit_breakers().check(Dependency::Whisper)?;
+
+        let _in_flight = runtime_counters().openai_request();
         let output = Command::new("curl")
             .args([
                 "https://api.openai.com/v1/audio/transcriptions",
@@ -76,6 +355,7 @@ impl AudioTranscriber {
 
         if output.status.success() {
             let response = String::from_utf8(output.stdout)?;
+            Self::record_whisper_outcome(&response);
             let json: serde_json::Value = serde_json::from_str(&response)?;
 
             if let Some(text) = json.get("text").and_then(|t| t.as_str()) {
@@ -93,25 +373,60 @@ impl AudioTranscriber {
             }
         } else {
             let error_msg = String::from_utf8_lossy(&output.stderr);
+            circuit_breakers().record_failure(Dependency::Whisper, &error_msg);
             error!("Transcription failed: {error_msg}");
             Err(anyhow::anyhow!("Transcription failed: {}", error_msg))
         }
     }
 
-    /// Check if file is a supported audio/video format
-    fn is_audio_file(&self, file_path: &str) -> bool {
-        let file_path_lower = file_path.to_lowercase();
-        SUPPORTED_FORMATS
-            .iter()
-            .any(|ext| file_path_lower.ends_with(ext))
-    }
+    /// Extract a video's audio track to a mono 64 kbps mp3 using ffmpeg
+    ///
+    /// Speech needs far less than the source bitrate, so the result stays well
+    /// under Whisper's upload limit for most videos.
+    fn extract_audio_track(&self, input_path: &str) -> Result<String> {
+        let output_path = match input_path.rfind('.') {
+            Some(dot_pos) => format!("{}.audio.mp3", &input_path[..dot_pos]),
+            None => format!("{input_path}.audio.mp3"),
+        };
 
-    /// Check if file format needs conversion before sending to Whisper
-    fn needs_conversion(&self, filename: &str) -> bool {
-        let lower = filename.to_lowercase();
-        !WHISPER_NATIVE_FORMATS
-            .iter()
-            .any(|ext| lower.ends_with(ext))
+        info!("Extracting audio track from {input_path} via ffmpeg");
+        let start = Instant::now();
+
+        let output = Command::new("ffmpeg")
+            .args([
+                "-i",
+                input_path,
+                "-map",
+                "0:a:0", // First audio stream only
+                "-vn",
+                "-ac",
+                "1",
+                "-b:a",
+                "64k",
+                "-y",
+                &output_path,
+            ])
+            .output()?;
+
+        if output.status.success() {
+            info!("Audio extraction completed in {:?}", start.elapsed());
+            return Ok(output_path);
+        }
+
+        let _ = std::fs::remove_file(&output_path);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("matches no streams") || stderr.contains("does not contain any stream") {
+            return Err(NoAudioTrackError.into());
+        }
+        if stderr.contains("not found") || stderr.contains("No such file") {
+            error!("FFmpeg not found. Install with: apt install ffmpeg");
+            return Err(anyhow::anyhow!(
+                "FFmpeg is required for video transcription but not installed. Install with: apt install ffmpeg"
+            ));
+        }
+
+        error!("FFmpeg audio extraction failed: {stderr}");
+        Err(anyhow::anyhow!("FFmpeg audio extraction failed: {stderr}"))
     }
 
     /// Convert audio/video file to mp3 using ffmpeg
@@ -161,13 +476,43 @@ impl AudioTranscriber {
         }
     }
 
-    /// Check if ffmpeg is available on the system
-    pub fn is_ffmpeg_available() -> bool {
-        Command::new("ffmpeg")
-            .arg("-version")
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false)
+    fn audio_duration(&self, file_path: &str) -> f64 {
+        Self::get_audio_duration(file_path)
+    }
+
+    async fn split(
+        &self,
+        file_path: &str,
+        windows: &[(u64, u64)],
+        chunk_dir: &Path,
+    ) -> Result<Vec<PathBuf>> {
+        split_windows(
+            Path::new(file_path),
+            chunk_dir,
+            windows,
+            CHUNK_SPLIT_TIMEOUT,
+        )
+        .await
+    }
+}
+
+impl WhisperBackend {
+    /// Feed a Whisper response into the circuit breaker; invalid requests
+    /// (bad file, too large) don't count against the API
+    fn record_whisper_outcome(response: &str) {
+        let breakers = circuit_breakers();
+        match serde_json::from_str::<serde_json::Value>(response) {
+            Ok(json) => match json.get("error") {
+                Some(error)
+                    if error.get("type").and_then(|t| t.as_str())
+                        != Some("invalid_request_error") =>
+                {
+                    breakers.record_failure(Dependency::Whisper, &error.to_string())
+                }
+                _ => breakers.record_success(Dependency::Whisper),
+            },
+            Err(e) => breakers.record_failure(Dependency::Whisper, &e.to_string()),
+        }
     }
 
     /// Get audio duration in seconds using ffprobe
@@ -200,79 +545,293 @@ impl AudioTranscriber {
             }
         }
     }
+}
 
-    /// Download and transcribe with duration tracking
-    pub async fn download_and_transcribe_with_duration(
-        &self,
-        url: &str,
-        filename: &str,
-    ) -> Result<TranscriptionResult> {
-        let temp_file = format!("/tmp/discord_audio_{filename}");
-        let mut converted_file: Option<String> = None;
-
-        info!("Downloading audio attachment: {filename}");
-
-        // Download the file
-        let output = Command::new("curl")
-            .args(["-o", &temp_file, url])
-            .output()?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Backend that writes placeholder files instead of running ffmpeg and
+    /// answers each transcription with the file's name
+    #[derive(Default)]
+    struct FakeBackend {
+        /// Size of extracted and converted files
+        output_bytes: u64,
+        /// Chunk index whose transcription fails
+        failing_chunk: Option<usize>,
+        /// Videos have no audio track to extract
+        silent: bool,
+        transcribed: Mutex<Vec<String>>,
+        chunk_dirs: Mutex<Vec<PathBuf>>,
+    }
 
-        if !output.status.success() {
-            return Err(anyhow::anyhow!("Failed to download audio file"));
+    impl FakeBackend {
+        fn write_output(&self, input_path: &str, suffix: &str) -> Result<String> {
+            let output = format!("{input_path}.{suffix}.mp3");
+            std::fs::File::create(&output)?.set_len(self.output_bytes)?;
+            Ok(output)
         }
 
-        // Check if conversion is needed
-        let file_to_transcribe = if self.needs_conversion(filename) {
-            info!("Format requires conversion: {filename}");
+        fn transcribed(&self) -> Vec<String> {
+            self.transcribed.lock().unwrap().clone()
+        }
+    }
 
-            match self.convert_to_mp3(&temp_file) {
-                Ok(mp3_path) => {
-                    converted_file = Some(mp3_path.clone());
-                    mp3_path
-                }
-                Err(e) => {
-                    // Cleanup original file before returning error
-                    let _ = fs::remove_file(&temp_file).await;
-                    return Err(e);
-                }
+    #[async_trait]
+    impl TranscriptionBackend for FakeBackend {
+        async fn transcribe(&self, file_path: &str) -> Result<String> {
+            let index = self.transcribed.lock().unwrap().len();
+            self.transcribed.lock().unwrap().push(file_path.to_string());
+            if self.failing_chunk == Some(index) {
+                return Err(anyhow::anyhow!("Whisper returned 500"));
             }
-        } else {
-            temp_file.clone()
-        };
+            let name = Path::new(file_path).file_stem().unwrap().to_string_lossy();
+            Ok(format!("heard {name}"))
+        }
 
-        // Get audio duration before transcription (for usage tracking)
-        let duration_seconds = Self::get_audio_duration(&file_to_transcribe);
-        info!("Audio duration: {duration_seconds:.1}s");
+        fn extract_audio_track(&self, input_path: &str) -> Result<String> {
+            if self.silent {
+                return Err(NoAudioTrackError.into());
+            }
+            self.write_output(input_path, "audio")
+        }
 
-        // Transcribe the file
-        let transcription = self.transcribe_file(&file_to_transcribe).await;
+        fn convert_to_mp3(&self, input_path: &str) -> Result<String> {
+            self.write_output(input_path, "converted")
+        }
 
-        // Cleanup temp files
-        if let Err(e) = fs::remove_file(&temp_file).await {
-            warn!("Failed to cleanup temp file {temp_file}: {e}");
+        fn audio_duration(&self, _file_path: &str) -> f64 {
+            1500.0
         }
 
-        if let Some(ref converted) = converted_file {
-            if let Err(e) = fs::remove_file(converted).await {
-                warn!("Failed to cleanup converted file {converted}: {e}");
-            }
+        async fn split(
+            &self,
+            _file_path: &str,
+            windows: &[(u64, u64)],
+            chunk_dir: &Path,
+        ) -> Result<Vec<PathBuf>> {
+            self.chunk_dirs
+                .lock()
+                .unwrap()
+                .push(chunk_dir.to_path_buf());
+            windows
+                .iter()
+                .enumerate()
+                .map(|(index, _)| {
+                    let chunk = chunk_dir.join(format!("chunk_{index:03}.mp3"));
+                    std::fs::write(&chunk, b"chunk")?;
+                    Ok(chunk)
+                })
+                .collect()
         }
+    }
 
-        transcription.map(|text| TranscriptionResult {
-            text,
-            duration_seconds,
-        })
+    /// A downloaded attachment of `bytes` in its own temp directory
+    fn downloaded(filename: &str, bytes: u64) -> (PathBuf, String) {
+        let dir = std::env::temp_dir().join(format!("transcriber-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(filename);
+        std::fs::File::create(&path)
+            .unwrap()
+            .set_len(bytes)
+            .unwrap();
+        (dir, path.to_string_lossy().into_owned())
     }
 
-    /// Legacy method for backwards compatibility
-    pub async fn download_and_transcribe_attachment(
-        &self,
-        url: &str,
-        filename: &str,
-    ) -> Result<String> {
-        let result = self
-            .download_and_transcribe_with_duration(url, filename)
-            .await?;
-        Ok(result.text)
+    /// Files left in an attachment's temp directory
+    fn leftovers(dir: &Path) -> Vec<String> {
+        std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_files_within_the_limit_go_straight_to_whisper() {
+        let backend = Arc::new(FakeBackend::default());
+        let transcriber = AudioTranscriber::with_backend(backend.clone());
+        let (dir, file) = downloaded("note.mp3", WHISPER_MAX_BYTES);
+
+        let result = transcriber
+            .transcribe_downloaded(&file, "note.mp3")
+            .await
+            .unwrap();
+        assert_eq!(result.text, "heard note");
+        assert_eq!(result.duration_seconds, 1500.0);
+        assert_eq!(backend.transcribed(), vec![file]);
+        assert!(backend.chunk_dirs.lock().unwrap().is_empty());
+        assert!(leftovers(&dir).is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_files_over_the_limit_are_transcribed_in_chunks() {
+        let backend = Arc::new(FakeBackend::default());
+        let transcriber = AudioTranscriber::with_backend(backend.clone());
+        let (dir, file) = downloaded("lecture.mp3", WHISPER_MAX_BYTES + 1);
+
+        let result = transcriber
+            .transcribe_downloaded(&file, "lecture.mp3")
+            .await
+            .unwrap();
+        // 1500s in 600s windows
+        assert_eq!(
+            result.text,
+            "heard chunk_000 heard chunk_001 heard chunk_002"
+        );
+        assert_eq!(backend.transcribed().len(), 3);
+        assert!(!backend.transcribed().contains(&file));
+
+        let chunk_dirs = backend.chunk_dirs.lock().unwrap().clone();
+        assert_eq!(chunk_dirs.len(), 1);
+        assert!(!chunk_dirs[0].exists());
+        assert!(leftovers(&dir).is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failed_chunk_fails_the_transcript_and_cleans_up() {
+        let backend = Arc::new(FakeBackend {
+            failing_chunk: Some(1),
+            ..Default::default()
+        });
+        let transcriber = AudioTranscriber::with_backend(backend.clone());
+        let (dir, file) = downloaded("lecture.mp3", WHISPER_MAX_BYTES + 1);
+
+        let error = transcriber
+            .transcribe_downloaded(&file, "lecture.mp3")
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("Whisper returned 500"));
+        // Chunks after the failed one aren't sent
+        assert_eq!(backend.transcribed().len(), 2);
+
+        let chunk_dirs = backend.chunk_dirs.lock().unwrap().clone();
+        assert!(!chunk_dirs[0].exists());
+        assert!(leftovers(&dir).is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_converted_copies_are_removed() {
+        let backend = Arc::new(FakeBackend {
+            output_bytes: 1024,
+            ..Default::default()
+        });
+        let transcriber = AudioTranscriber::with_backend(backend.clone());
+        let (dir, file) = downloaded("memo.ogg", 4096);
+
+        let result = transcriber
+            .transcribe_downloaded(&file, "memo.ogg")
+            .await
+            .unwrap();
+        assert_eq!(result.text, "heard memo.ogg.converted");
+        assert_eq!(backend.transcribed(), vec![format!("{file}.converted.mp3")]);
+        assert!(leftovers(&dir).is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_video_audio_track_is_transcribed_and_removed() {
+        let backend = Arc::new(FakeBackend {
+            output_bytes: 1024,
+            ..Default::default()
+        });
+        let transcriber = AudioTranscriber::with_backend(backend.clone());
+        // The video itself is over the limit; only its track is sent
+        let (dir, file) = downloaded("clip.mov", WHISPER_MAX_BYTES * 4);
+
+        let result = transcriber
+            .transcribe_downloaded(&file, "clip.mov")
+            .await
+            .unwrap();
+        assert_eq!(result.text, "heard clip.mov.audio");
+        assert_eq!(backend.transcribed(), vec![format!("{file}.audio.mp3")]);
+        assert!(backend.chunk_dirs.lock().unwrap().is_empty());
+        assert!(leftovers(&dir).is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_native_video_formats_are_still_extracted() {
+        let backend = Arc::new(FakeBackend {
+            output_bytes: 1024,
+            ..Default::default()
+        });
+        let transcriber = AudioTranscriber::with_backend(backend.clone());
+        // Whisper reads mp4, but the video track would waste the upload
+        let (dir, file) = downloaded("clip.mp4", 4096);
+
+        transcriber
+            .transcribe_downloaded(&file, "clip.mp4")
+            .await
+            .unwrap();
+        assert_eq!(backend.transcribed(), vec![format!("{file}.audio.mp3")]);
+        assert!(leftovers(&dir).is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_long_video_audio_track_is_chunked() {
+        let backend = Arc::new(FakeBackend {
+            output_bytes: WHISPER_MAX_BYTES + 1,
+            ..Default::default()
+        });
+        let transcriber = AudioTranscriber::with_backend(backend.clone());
+        let (dir, file) = downloaded("stream.mkv", WHISPER_MAX_BYTES * 4);
+
+        let result = transcriber
+            .transcribe_downloaded(&file, "stream.mkv")
+            .await
+            .unwrap();
+        assert_eq!(
+            result.text,
+            "heard chunk_000 heard chunk_001 heard chunk_002"
+        );
+        let chunk_dirs = backend.chunk_dirs.lock().unwrap().clone();
+        assert_eq!(chunk_dirs.len(), 1);
+        assert!(!chunk_dirs[0].exists());
+        assert!(leftovers(&dir).is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failed_chunk_of_a_video_removes_the_extracted_track() {
+        let backend = Arc::new(FakeBackend {
+            output_bytes: WHISPER_MAX_BYTES + 1,
+            failing_chunk: Some(0),
+            ..Default::default()
+        });
+        let transcriber = AudioTranscriber::with_backend(backend.clone());
+        let (dir, file) = downloaded("stream.mkv", WHISPER_MAX_BYTES * 4);
+
+        assert!(transcriber
+            .transcribe_downloaded(&file, "stream.mkv")
+            .await
+            .is_err());
+        assert_eq!(backend.transcribed().len(), 1);
+        assert!(!backend.chunk_dirs.lock().unwrap()[0].exists());
+        assert!(leftovers(&dir).is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_silent_video_reports_no_audio_track() {
+        let backend = Arc::new(FakeBackend {
+            silent: true,
+            ..Default::default()
+        });
+        let transcriber = AudioTranscriber::with_backend(backend.clone());
+        let (dir, file) = downloaded("screen.webm", 4096);
+
+        let error = transcriber
+            .transcribe_downloaded(&file, "screen.webm")
+            .await
+            .unwrap_err();
+        assert!(error.downcast_ref::<NoAudioTrackError>().is_some());
+        assert!(backend.transcribed().is_empty());
+        assert!(leftovers(&dir).is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 }
@@ -0,0 +1,162 @@
+//! Per-trigger cooldowns and a cache of compiled patterns
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use regex::Regex;
+
+use super::{compile_pattern, MatchKind};
+use crate::database::AutoResponse;
+
+/// Outcome of checking a message against one trigger
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerDecision {
+    /// Respond with this trigger
+    Fire,
+    /// The message doesn't match (or the trigger is off)
+    NoMatch,
+    /// The message matches but the trigger fired recently
+    Cooldown,
+}
+
+/// A compiled pattern, remembered with the source it was built from
+struct CachedPattern {
+    source: String,
+    kind: MatchKind,
+    regex: Option<Regex>,
+}
+
+/// Tracks when each trigger last fired and keeps compiled patterns around
+#[derive(Default)]
+pub struct AutoResponseGate {
+    last_fired: DashMap<i64, DateTime<Utc>>,
+    patterns: DashMap<i64, CachedPattern>,
+}
+
+impl AutoResponseGate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decide whether `trigger` answers `content`, starting its cooldown if so
+    pub fn check(&self, trigger: &AutoResponse, content: &str) -> TriggerDecision {
+        self.check_at(trigger, content, Utc::now())
+    }
+
+    fn check_at(
+        &self,
+        trigger: &AutoResponse,
+        content: &str,
+        now: DateTime<Utc>,
+    ) -> TriggerDecision {
+        if !trigger.enabled || !self.matches(trigger, content) {
+            return TriggerDecision::NoMatch;
+        }
+        let cooldown = chrono::Duration::seconds(trigger.cooldown_secs);
+        if let Some(last) = self.last_fired.get(&trigger.id) {
+            if now - *last < cooldown {
+                return TriggerDecision::Cooldown;
+            }
+        }
+        self.last_fired.insert(trigger.id, now);
+        TriggerDecision::Fire
+    }
+
+    /// Match against the cached regex, recompiling when the trigger changed
+    fn matches(&self, trigger: &AutoResponse, content: &str) -> bool {
+        let kind = MatchKind::from_setting(Some(&trigger.match_kind));
+        let mut cached = self
+            .patterns
+            .entry(trigger.id)
+            .or_insert_with(|| CachedPattern {
+                source: trigger.pattern.clone(),
+                kind,
+                regex: compile_pattern(&trigger.pattern, kind).ok(),
+            });
+        if cached.source != trigger.pattern || cached.kind != kind {
+            *cached = CachedPattern {
+                source: trigger.pattern.clone(),
+                kind,
+                regex: compile_pattern(&trigger.pattern, kind).ok(),
+            };
+        }
+        cached
+            .regex
+            .as_ref()
+            .is_some_and(|regex| regex.is_match(content))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn trigger(id: i64, pattern: &str, cooldown_secs: i64) -> AutoResponse {
+        AutoResponse {
+            id,
+            guild_id: "1".to_string(),
+            pattern: pattern.to_string(),
+            match_kind: "keyword".to_string(),
+            response: "See #faq".to_string(),
+            response_kind: "reply".to_string(),
+            cooldown_secs,
+            enabled: true,
+            priority: 0,
+            created_by: "42".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_gate_cooldown() {
+        let gate = AutoResponseGate::new();
+        let start = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        let faq = trigger(1, "faq", 60);
+        assert_eq!(
+            gate.check_at(&faq, "the faq?", start),
+            TriggerDecision::Fire
+        );
+        assert_eq!(
+            gate.check_at(&faq, "faq again", start + chrono::Duration::seconds(30)),
+            TriggerDecision::Cooldown
+        );
+        assert_eq!(
+            gate.check_at(&faq, "faq again", start + chrono::Duration::seconds(61)),
+            TriggerDecision::Fire
+        );
+        // Cooldowns are per trigger
+        assert_eq!(
+            gate.check_at(&trigger(2, "rules", 60), "rules?", start),
+            TriggerDecision::Fire
+        );
+    }
+
+    #[test]
+    fn test_gate_no_match_and_disabled() {
+        let gate = AutoResponseGate::new();
+        let now = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        let mut faq = trigger(1, "faq", 0);
+        assert_eq!(gate.check_at(&faq, "hello", now), TriggerDecision::NoMatch);
+        faq.enabled = false;
+        assert_eq!(gate.check_at(&faq, "faq", now), TriggerDecision::NoMatch);
+    }
+
+    #[test]
+    fn test_gate_recompiles_changed_pattern() {
+        let gate = AutoResponseGate::new();
+        let now = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        assert_eq!(
+            gate.check_at(&trigger(1, "faq", 0), "rules", now),
+            TriggerDecision::NoMatch
+        );
+        assert_eq!(
+            gate.check_at(&trigger(1, "rules", 0), "rules", now),
+            TriggerDecision::Fire
+        );
+    }
+}
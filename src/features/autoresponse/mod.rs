@@ -0,0 +1,249 @@
+//! # Feature: Auto-Responses
+//!
+//! Admins define triggers with `/autoresponse add`: a keyword (matched as a
+//! whole word, ignoring case) or a regex, answered with either a canned reply
+//! or a persona-generated response following the admin's instructions. Plain
+//! guild messages are checked before the mention path, highest priority
+//! first; the first trigger that matches decides, and each trigger has its
+//! own cooldown so a busy channel doesn't get the same reply over and over.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with keyword and regex triggers, canned and persona replies, cooldowns and priorities
+
+pub mod gate;
+pub mod responder;
+
+pub use gate::{AutoResponseGate, TriggerDecision};
+pub use responder::AutoResponder;
+
+use regex::{Regex, RegexBuilder};
+
+use crate::database::AutoResponse;
+
+/// Feature id for toggles
+pub const AUTORESPONSE_FEATURE: &str = "autoresponse";
+
+/// Triggers a guild may define
+pub const MAX_TRIGGERS_PER_GUILD: i64 = 50;
+
+/// Longest keyword or regex
+pub const MAX_PATTERN_CHARS: usize = 200;
+
+/// Longest canned reply or persona instructions
+pub const MAX_RESPONSE_CHARS: usize = 1500;
+
+/// Cooldown used when `/autoresponse add` doesn't set one
+pub const DEFAULT_COOLDOWN_SECS: i64 = 60;
+
+/// Longest cooldown a trigger can have (one day)
+pub const MAX_COOLDOWN_SECS: i64 = 86_400;
+
+/// Compiled regexes are kept small so a pattern can't stall message handling
+const REGEX_SIZE_LIMIT: usize = 1 << 16;
+
+/// Characters of the response shown by `/autoresponse list`
+const LIST_PREVIEW_CHARS: usize = 60;
+
+/// How a trigger's pattern is matched against messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchKind {
+    /// The pattern appears as a whole word or phrase, ignoring case
+    Keyword,
+    /// The pattern is a regex, ignoring case
+    Regex,
+}
+
+impl MatchKind {
+    /// Parse the stored value, defaulting to keyword matching
+    pub fn from_setting(value: Option<&str>) -> Self {
+        match value {
+            Some("regex") => Self::Regex,
+            _ => Self::Keyword,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Keyword => "keyword",
+            Self::Regex => "regex",
+        }
+    }
+}
+
+/// What a trigger answers with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseKind {
+    /// Post the response text as written
+    Reply,
+    /// Have the channel's persona write a reply following the response text
+    Persona,
+}
+
+impl ResponseKind {
+    /// Parse the stored value, defaulting to a canned reply
+    pub fn from_setting(value: Option<&str>) -> Self {
+        match value {
+            Some("persona") => Self::Persona,
+            _ => Self::Reply,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Reply => "reply",
+            Self::Persona => "persona",
+        }
+    }
+}
+
+/// Compile a trigger's pattern; keywords are escaped and anchored to word boundaries
+pub fn compile_pattern(pattern: &str, kind: MatchKind) -> Result<Regex, String> {
+    let source = match kind {
+        MatchKind::Regex => pattern.to_string(),
+        MatchKind::Keyword => {
+            // \b only makes sense next to word characters, so "!help" still matches
+            let is_word = |c: char| c.is_alphanumeric() || c == '_';
+            let start = if pattern.starts_with(is_word) {
+                r"\b"
+            } else {
+                ""
+            };
+            let end = if pattern.ends_with(is_word) {
+                r"\b"
+            } else {
+                ""
+            };
+            format!("{start}{}{end}", regex::escape(pattern))
+        }
+    };
+    RegexBuilder::new(&source)
+        .case_insensitive(true)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(|e| format!("That pattern isn't a valid regex: {e}"))
+}
+
+/// Trim and check a new trigger's pattern, compiling it to catch bad regexes
+pub fn validate_pattern(input: &str, kind: MatchKind) -> Result<String, String> {
+    let pattern = input.trim();
+    if pattern.is_empty() {
+        return Err("The pattern can't be empty.".to_string());
+    }
+    if pattern.chars().count() > MAX_PATTERN_CHARS {
+        return Err(format!(
+            "Patterns must be at most {MAX_PATTERN_CHARS} characters."
+        ));
+    }
+    let regex = compile_pattern(pattern, kind)?;
+    if regex.is_match("") {
+        return Err("That pattern matches every message.".to_string());
+    }
+    Ok(pattern.to_string())
+}
+
+/// Trim and check a new trigger's reply or persona instructions
+pub fn validate_response(input: &str) -> Result<String, String> {
+    let response = input.trim();
+    if response.is_empty() {
+        return Err("The response can't be empty.".to_string());
+    }
+    if response.chars().count() > MAX_RESPONSE_CHARS {
+        return Err(format!(
+            "Responses must be at most {MAX_RESPONSE_CHARS} characters."
+        ));
+    }
+    Ok(response.to_string())
+}
+
+/// Fill a canned reply's `{user}` placeholder with a mention of the author
+pub fn render_reply(template: &str, user_id: u64) -> String {
+    template.replace("{user}", &format!("<@{user_id}>"))
+}
+
+/// One line of `/autoresponse list`
+pub fn list_line(trigger: &AutoResponse) -> String {
+    let mut preview: String = trigger
+        .response
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(LIST_PREVIEW_CHARS)
+        .collect();
+    if trigger.response.chars().count() > LIST_PREVIEW_CHARS {
+        preview.push('…');
+    }
+    format!(
+        "{} `#{}` {} `{}` → {} {preview} · priority {} · {}s cooldown",
+        if trigger.enabled { "🟢" } else { "⚪" },
+        trigger.id,
+        trigger.match_kind,
+        trigger.pattern.replace('`', "'"),
+        if ResponseKind::from_setting(Some(&trigger.response_kind)) == ResponseKind::Persona {
+            "🎭"
+        } else {
+            "💬"
+        },
+        trigger.priority,
+        trigger.cooldown_secs,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keyword_matches_whole_words() {
+        let regex = compile_pattern("faq", MatchKind::Keyword).unwrap();
+        assert!(regex.is_match("where's the FAQ?"));
+        assert!(!regex.is_match("faqs are long"));
+
+        let regex = compile_pattern("!help", MatchKind::Keyword).unwrap();
+        assert!(regex.is_match("!help please"));
+
+        let regex = compile_pattern("a.b", MatchKind::Keyword).unwrap();
+        assert!(!regex.is_match("axb"));
+    }
+
+    #[test]
+    fn test_regex_patterns() {
+        let regex = compile_pattern(r"when (is|does) .* start", MatchKind::Regex).unwrap();
+        assert!(regex.is_match("When does the event start?"));
+        assert!(compile_pattern("(unclosed", MatchKind::Regex).is_err());
+    }
+
+    #[test]
+    fn test_validate_pattern() {
+        assert_eq!(
+            validate_pattern("  rules ", MatchKind::Keyword),
+            Ok("rules".to_string())
+        );
+        assert!(validate_pattern("   ", MatchKind::Keyword).is_err());
+        assert!(validate_pattern(".*", MatchKind::Regex).is_err());
+        assert!(validate_pattern(&"a".repeat(MAX_PATTERN_CHARS + 1), MatchKind::Keyword).is_err());
+    }
+
+    #[test]
+    fn test_render_reply() {
+        assert_eq!(
+            render_reply("Hi {user}, see #rules", 42),
+            "Hi <@42>, see #rules"
+        );
+    }
+
+    #[test]
+    fn test_kinds_round_trip() {
+        for kind in [MatchKind::Keyword, MatchKind::Regex] {
+            assert_eq!(MatchKind::from_setting(Some(kind.as_str())), kind);
+        }
+        for kind in [ResponseKind::Reply, ResponseKind::Persona] {
+            assert_eq!(ResponseKind::from_setting(Some(kind.as_str())), kind);
+        }
+        assert_eq!(MatchKind::from_setting(None), MatchKind::Keyword);
+    }
+}
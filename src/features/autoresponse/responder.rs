@@ -0,0 +1,127 @@
+//! Write persona-generated replies for auto-response triggers
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use openai::chat::ChatCompletionMessageRole;
+use serenity::model::channel::Message;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::core::{chat_message, ChatClient};
+use crate::features::analytics::{CostBucket, UsageTracker};
+use crate::features::guardrails::ChannelGrounding;
+
+/// Cap on generated tokens; auto-responses are short
+const MAX_REPLY_TOKENS: u64 = 300;
+
+/// Characters of the triggering message sent to the model
+const MAX_MESSAGE_CHARS: usize = 1500;
+
+/// Discord's message length limit
+const MAX_REPLY_CHARS: usize = 2000;
+
+/// Writes persona replies to triggered messages through the shared chat client
+#[derive(Clone)]
+pub struct AutoResponder {
+    chat_client: Arc<dyn ChatClient>,
+    openai_model: String,
+    usage_tracker: UsageTracker,
+}
+
+impl AutoResponder {
+    pub fn new(
+        chat_client: Arc<dyn ChatClient>,
+        openai_model: String,
+        usage_tracker: UsageTracker,
+    ) -> Self {
+        Self {
+            chat_client,
+            openai_model,
+            usage_tracker,
+        }
+    }
+
+    /// Reply to `msg` in the persona's voice, following the trigger's `instructions`
+    ///
+    /// `grounding` is the channel's safety profile and scene.
+    /// Returns `Ok(None)` when the model came back empty.
+    pub async fn respond(
+        &self,
+        persona_prompt: &str,
+        grounding: &ChannelGrounding,
+        instructions: &str,
+        msg: &Message,
+        request_id: Uuid,
+    ) -> Result<Option<String>> {
+        let content: String = msg.content.chars().take(MAX_MESSAGE_CHARS).collect();
+        let completion = self
+            .chat_client
+            .create_chat_completion_limited(
+                &self.openai_model,
+                vec![
+                    chat_message(
+                        ChatCompletionMessageRole::System,
+                        grounding.persona_prompt(&format!(
+                            "{persona_prompt}\n\n{}",
+                            reply_prompt(instructions)
+                        )),
+                    ),
+                    chat_message(ChatCompletionMessageRole::User, content),
+                ],
+                Some(MAX_REPLY_TOKENS),
+            )
+            .await?;
+
+        if let Some(usage) = &completion.usage {
+            self.usage_tracker.log_chat(
+                &self.openai_model,
+                usage.prompt_tokens,
+                usage.completion_tokens,
+                usage.total_tokens,
+                &msg.author.id.to_string(),
+                msg.guild_id.map(|id| id.to_string()).as_deref(),
+                Some(&msg.channel_id.to_string()),
+                Some(&request_id.to_string()),
+                CostBucket::AutoResponse,
+            );
+        }
+
+        let reply = completion
+            .choices
+            .first()
+            .and_then(|choice| choice.message.content.clone())
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        if reply.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(reply.chars().take(MAX_REPLY_CHARS).collect()))
+    }
+}
+
+/// Instructions appended to the persona prompt
+fn reply_prompt(instructions: &str) -> String {
+    format!(
+        "A server member's message matched one of this server's automatic responses. Reply to \
+         it in your characteristic style in a few sentences, following the server admins' \
+         instructions below. Don't mention that the reply is automatic.\n\n\
+         Admin instructions: {instructions}"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reply_prompt_includes_instructions() {
+        let prompt = reply_prompt("Point them to #faq");
+        assert!(prompt.ends_with("Admin instructions: Point them to #faq"));
+    }
+}
@@ -0,0 +1,205 @@
+//! # Feature: Birthdays & Anniversaries
+//!
+//! Members save a birthday and an anniversary with `/birthday set`, along with
+//! their time zone: an IANA name such as `Europe/Berlin` or a UTC offset,
+//! defaulting to the zone set with `/time zone`. A background task checks every
+//! 15 minutes and, once it is past 09:00 on the member's local date, posts a persona-voiced wish in the
+//! channel chosen with `/set_channel birthdays`. Each date is wished once per
+//! local year. Members can opt out of public wishes without deleting their dates.
+//!
+//! - **Version**: 1.1.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.1.0: Local dates follow the member's IANA zone through daylight saving; dates saved with a UTC offset keep it
+//! - 1.0.2: Wishes follow the channel's guardrail profile and scene, and log cost under their own bucket
+//! - 1.0.1: UTC offset helpers moved to `core::time`
+//! - 1.0.0: Initial release with UTC offsets, opt-out and anniversary year counts
+
+pub mod scheduler;
+
+pub use scheduler::BirthdayScheduler;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Timelike, Utc};
+
+use crate::database::Celebration;
+
+/// Guild setting holding the channel wishes are posted in
+pub const CHANNEL_SETTING: &str = "birthday_channel";
+
+/// Local hour from which a date is wished, so nobody is greeted at midnight
+pub const WISH_HOUR: u32 = 9;
+
+/// Parse `MM-DD` or `YYYY-MM-DD` into month, day and optional year
+pub fn parse_date(value: &str) -> Result<(u32, u32, Option<i32>), &'static str> {
+    const INVALID: &str = "Invalid date. Use `MM-DD` (e.g. `03-14`) or `YYYY-MM-DD`.";
+    let parts: Vec<&str> = value.trim().split(['-', '/']).collect();
+    let (year, month, day) = match parts.as_slice() {
+        [month, day] => (None, *month, *day),
+        [year, month, day] => (
+            Some(year.parse::<i32>().map_err(|_| INVALID)?),
+            *month,
+            *day,
+        ),
+        _ => return Err(INVALID),
+    };
+    let month = month.parse::<u32>().map_err(|_| INVALID)?;
+    let day = day.parse::<u32>().map_err(|_| INVALID)?;
+
+    // 2000 is a leap year, so February 29th is accepted without a year
+    NaiveDate::from_ymd_opt(year.unwrap_or(2000), month, day).ok_or(INVALID)?;
+    if year.is_some_and(|y| y < 1900 || y > Utc::now().year()) {
+        return Err("The year must be between 1900 and this year.");
+    }
+    Ok((month, day, year))
+}
+
+/// Display a month and day as `March 14`
+pub fn format_date(month: u32, day: u32) -> String {
+    NaiveDate::from_ymd_opt(2000, month, day)
+        .map(|d| d.format("%B %-d").to_string())
+        .unwrap_or_else(|| format!("{month:02}-{day:02}"))
+}
+
+/// Whether a month and day falls on `date`; February 29th is celebrated on
+/// the 28th in common years
+pub fn occurs_on(month: u32, day: u32, date: NaiveDate) -> bool {
+    if month == 2 && day == 29 && NaiveDate::from_ymd_opt(date.year(), 2, 29).is_none() {
+        return date.month() == 2 && date.day() == 28;
+    }
+    date.month() == month && date.day() == day
+}
+
+/// Whether a date should be wished now: it's the member's local date, past
+/// [`WISH_HOUR`], and it hasn't been wished this local year
+pub fn is_due(celebration: &Celebration, now: DateTime<Utc>) -> bool {
+    let local = celebration.timezone.local_time(now);
+    local.hour() >= WISH_HOUR
+        && occurs_on(celebration.month, celebration.day, local.date())
+        && celebration.last_wished_year != Some(local.year())
+}
+
+/// Days from `today` until the next occurrence (0 when it's today)
+pub fn days_until(month: u32, day: u32, today: NaiveDate) -> i64 {
+    (0..=366)
+        .find(|&offset| occurs_on(month, day, today + Duration::days(offset)))
+        .unwrap_or(0)
+}
+
+/// Completed years for an anniversary in `local_year`
+pub fn years_since(celebration: &Celebration, local_year: i32) -> Option<i32> {
+    celebration
+        .year
+        .map(|year| local_year - year)
+        .filter(|years| *years > 0)
+}
+
+/// English ordinal, e.g. `1st`, `12th`, `23rd`
+pub fn ordinal(n: i32) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{n}{suffix}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Zone;
+    use crate::database::CelebrationKind;
+    use chrono::TimeZone;
+    use chrono_tz::Tz;
+
+    fn birthday(month: u32, day: u32, utc_offset_minutes: i32) -> Celebration {
+        Celebration {
+            guild_id: "g1".to_string(),
+            user_id: "u1".to_string(),
+            kind: CelebrationKind::Birthday,
+            month,
+            day,
+            year: None,
+            timezone: Zone::Fixed(utc_offset_minutes),
+            last_wished_year: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(parse_date("03-14"), Ok((3, 14, None)));
+        assert_eq!(parse_date("2019/06/01"), Ok((6, 1, Some(2019))));
+        assert_eq!(parse_date("02-29"), Ok((2, 29, None)));
+        assert!(parse_date("2019-02-29").is_err());
+        assert!(parse_date("13-01").is_err());
+        assert!(parse_date("March 14").is_err());
+        assert!(parse_date("1800-01-01").is_err());
+    }
+
+    #[test]
+    fn test_is_due_uses_local_date_and_hour() {
+        // 20:00 UTC on March 13th is already 09:00 on the 14th in UTC+13
+        let now = Utc.with_ymd_and_hms(2026, 3, 13, 20, 0, 0).unwrap();
+        assert!(is_due(&birthday(3, 14, 13 * 60), now));
+        assert!(!is_due(&birthday(3, 14, 0), now));
+        // Before the wish hour locally
+        assert!(!is_due(&birthday(3, 13, -12 * 60), now));
+        assert!(is_due(&birthday(3, 13, 0), now));
+
+        let wished = Celebration {
+            last_wished_year: Some(2026),
+            ..birthday(3, 13, 0)
+        };
+        assert!(!is_due(&wished, now));
+    }
+
+    #[test]
+    fn test_is_due_follows_daylight_saving() {
+        let berlin = |month, day| Celebration {
+            timezone: Zone::Named(Tz::Europe__Berlin),
+            ..birthday(month, day, 0)
+        };
+        // 07:30 UTC is 09:30 in Berlin in summer but 08:30 in winter
+        let summer = Utc.with_ymd_and_hms(2026, 7, 1, 7, 30, 0).unwrap();
+        let winter = Utc.with_ymd_and_hms(2026, 12, 1, 7, 30, 0).unwrap();
+        assert!(is_due(&berlin(7, 1), summer));
+        assert!(!is_due(&berlin(12, 1), winter));
+        // A fixed UTC+1 saved before zone names ignores summer time
+        assert!(!is_due(&birthday(7, 1, 60), summer));
+    }
+
+    #[test]
+    fn test_leap_day_and_days_until() {
+        let feb_28 = NaiveDate::from_ymd_opt(2027, 2, 28).unwrap();
+        assert!(occurs_on(2, 29, feb_28));
+        assert!(!occurs_on(
+            2,
+            29,
+            NaiveDate::from_ymd_opt(2028, 2, 28).unwrap()
+        ));
+
+        let today = NaiveDate::from_ymd_opt(2026, 12, 30).unwrap();
+        assert_eq!(days_until(12, 30, today), 0);
+        assert_eq!(days_until(1, 2, today), 3);
+        assert_eq!(days_until(12, 29, today), 364);
+    }
+
+    #[test]
+    fn test_years_since_and_ordinal() {
+        let anniversary = Celebration {
+            kind: CelebrationKind::Anniversary,
+            year: Some(2019),
+            ..birthday(6, 1, 0)
+        };
+        assert_eq!(years_since(&anniversary, 2026), Some(7));
+        assert_eq!(years_since(&anniversary, 2019), None);
+        assert_eq!(years_since(&birthday(6, 1, 0), 2026), None);
+        assert_eq!(ordinal(1), "1st");
+        assert_eq!(ordinal(12), "12th");
+        assert_eq!(ordinal(23), "23rd");
+        assert_eq!(format_date(3, 14), "March 14");
+    }
+}
@@ -0,0 +1,344 @@
+//! Background task posting birthday and anniversary wishes
+//!
+//! - **Version**: 1.0.1
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.1: Wishes go through the shared chat client, grounded in the channel's profile and scene
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use chrono::{Datelike, Utc};
+use log::{debug, error, info, warn};
+use openai::chat::ChatCompletionMessageRole;
+use serenity::http::Http;
+use serenity::model::id::ChannelId;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::interval;
+
+use super::{is_due, ordinal, years_since, CHANNEL_SETTING};
+use crate::core::{chat_message, ChatClient};
+use crate::database::{Celebration, CelebrationKind, Database};
+use crate::features::analytics::{CostBucket, UsageTracker};
+use crate::features::guardrails::ChannelGrounding;
+use crate::features::personas::themes::{apply_theme, ACTIVE_THEME_SETTING};
+use crate::features::personas::PersonaManager;
+
+/// Cap on generated tokens; a wish is two or three sentences
+const MAX_WISH_TOKENS: u64 = 200;
+
+pub struct BirthdayScheduler {
+    database: Database,
+    persona_manager: PersonaManager,
+    chat_client: Arc<dyn ChatClient>,
+    openai_model: String,
+    usage_tracker: UsageTracker,
+}
+
+impl BirthdayScheduler {
+    pub fn new(
+        database: Database,
+        chat_client: Arc<dyn ChatClient>,
+        openai_model: String,
+        usage_tracker: UsageTracker,
+    ) -> Self {
+        Self {
+            database,
+            persona_manager: PersonaManager::new(),
+            chat_client,
+            openai_model,
+            usage_tracker,
+        }
+    }
+
+    /// Start the birthday scheduler loop
+    /// This should be spawned as a tokio task
+    pub async fn run(&self, http: Arc<Http>) {
+        let mut check_interval = interval(Duration::from_secs(15 * 60));
+
+        info!("🎂 Birthday scheduler started");
+
+        loop {
+            check_interval.tick().await;
+
+            if let Err(e) = self.process_due_celebrations(&http).await {
+                error!("❌ Error processing birthdays: {e}");
+            }
+        }
+    }
+
+    async fn process_due_celebrations(&self, http: &Arc<Http>) -> Result<()> {
+        let now = Utc::now();
+        let due: Vec<Celebration> = self
+            .database
+            .get_wishable_celebrations()
+            .await?
+            .into_iter()
+            .filter(|c| is_due(c, now))
+            .collect();
+
+        if due.is_empty() {
+            debug!("🎂 No birthdays or anniversaries due");
+            return Ok(());
+        }
+
+        // Guilds without a birthday channel (or with the feature off) are
+        // skipped without marking, so setting a channel later still wishes today
+        let mut channels: HashMap<String, Option<String>> = HashMap::new();
+        for celebration in due {
+            let guild_id = celebration.guild_id.clone();
+            if !channels.contains_key(&guild_id) {
+                let channel = self.wish_channel(&guild_id).await?;
+                channels.insert(guild_id.clone(), channel);
+            }
+            let Some(channel_id) = channels[&guild_id].as_deref() else {
+                continue;
+            };
+
+            let local_year = celebration.timezone.local_time(now).year();
+            match self
+                .deliver_wish(http, &celebration, channel_id, local_year)
+                .await
+            {
+                Ok(_) => info!(
+                    "🎂 Wished {} a happy {} in guild {guild_id}",
+                    celebration.user_id,
+                    celebration.kind.key()
+                ),
+                Err(e) => warn!(
+                    "⚠️ Failed to deliver {} wish for {}: {e}",
+                    celebration.kind.key(),
+                    celebration.user_id
+                ),
+            }
+            // Marked either way so a broken channel doesn't retry every 15 minutes
+            self.database
+                .mark_celebration_wished(
+                    &guild_id,
+                    &celebration.user_id,
+                    celebration.kind,
+                    local_year,
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn wish_channel(&self, guild_id: &str) -> Result<Option<String>> {
+        if !self
+            .database
+            .is_feature_enabled("birthdays", None, Some(guild_id))
+            .await?
+        {
+            return Ok(None);
+        }
+        self.database
+            .get_guild_setting(guild_id, CHANNEL_SETTING)
+            .await
+    }
+
+    async fn deliver_wish(
+        &self,
+        http: &Arc<Http>,
+        celebration: &Celebration,
+        channel_id: &str,
+        local_year: i32,
+    ) -> Result<()> {
+        let guild_id = celebration.guild_id.as_str();
+        let persona_name = self
+            .database
+            .get_persona_with_channel(&celebration.user_id, guild_id, channel_id, None)
+            .await
+            .unwrap_or_else(|_| "obi".to_string());
+        let theme = self
+            .database
+            .get_guild_setting(guild_id, ACTIVE_THEME_SETTING)
+            .await?;
+        let system_prompt = apply_theme(
+            self.persona_manager.get_system_prompt(&persona_name, None),
+            theme.as_deref(),
+        );
+        let grounding =
+            ChannelGrounding::for_channel(&self.database, Some(guild_id), Some(channel_id)).await?;
+
+        let occasion = occasion(celebration, local_year);
+        let wish = self
+            .generate_wish(
+                &system_prompt,
+                &grounding,
+                &occasion,
+                celebration,
+                channel_id,
+            )
+            .await
+            .unwrap_or_else(|e| {
+                warn!("⚠️ Failed to generate birthday wish, using fallback: {e}");
+                fallback_wish(celebration.kind, &occasion)
+            });
+
+        let channel = ChannelId(channel_id.parse::<u64>()?);
+        channel
+            .say(http, format!("<@{}>\n\n{wish}", celebration.user_id))
+            .await?;
+        Ok(())
+    }
+
+    async fn generate_wish(
+        &self,
+        persona_prompt: &str,
+        grounding: &ChannelGrounding,
+        occasion: &str,
+        celebration: &Celebration,
+        channel_id: &str,
+    ) -> Result<String> {
+        let completion = self
+            .chat_client
+            .create_chat_completion_limited(
+                &self.openai_model,
+                vec![
+                    chat_message(
+                        ChatCompletionMessageRole::System,
+                        grounding.persona_prompt(&wish_prompt(persona_prompt, occasion)),
+                    ),
+                    chat_message(
+                        ChatCompletionMessageRole::User,
+                        format!("Please wish me a happy {occasion}."),
+                    ),
+                ],
+                Some(MAX_WISH_TOKENS),
+            )
+            .await?;
+
+        if let Some(usage) = &completion.usage {
+            self.usage_tracker.log_chat(
+                &self.openai_model,
+                usage.prompt_tokens,
+                usage.completion_tokens,
+                usage.total_tokens,
+                &celebration.user_id,
+                Some(&celebration.guild_id),
+                Some(channel_id),
+                None,
+                CostBucket::Birthdays,
+            );
+        }
+
+        completion
+            .choices
+            .first()
+            .and_then(|choice| choice.message.content.clone())
+            .filter(|content| !content.trim().is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Empty completion"))
+    }
+}
+
+/// The persona's prompt with the task of wishing a member well
+fn wish_prompt(persona_prompt: &str, occasion: &str) -> String {
+    format!(
+        "{persona_prompt}\n\n\
+        Your task is to congratulate a member of this server on their {occasion} \
+        in your characteristic style. Keep it to 2-3 warm, in-character sentences. \
+        They are mentioned above your message, so don't address them by name or \
+        use placeholders, and don't guess their age."
+    )
+}
+
+/// What is being celebrated, e.g. `birthday` or `7th anniversary`
+fn occasion(celebration: &Celebration, local_year: i32) -> String {
+    match (celebration.kind, years_since(celebration, local_year)) {
+        (CelebrationKind::Anniversary, Some(years)) => {
+            format!("{} anniversary", ordinal(years))
+        }
+        (kind, _) => kind.key().to_string(),
+    }
+}
+
+fn fallback_wish(kind: CelebrationKind, occasion: &str) -> String {
+    match kind {
+        CelebrationKind::Birthday => "🎂 Happy birthday! Wishing you a wonderful day.".to_string(),
+        CelebrationKind::Anniversary => format!("🎉 Happy {occasion}! Here's to many more."),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Zone;
+    use crate::features::guardrails::GUARDRAIL_SETTING;
+    use crate::testing::MockChatClient;
+
+    #[test]
+    fn test_occasion() {
+        let anniversary = Celebration {
+            guild_id: "g1".to_string(),
+            user_id: "u1".to_string(),
+            kind: CelebrationKind::Anniversary,
+            month: 6,
+            day: 1,
+            year: Some(2019),
+            timezone: Zone::Fixed(0),
+            last_wished_year: None,
+        };
+        assert_eq!(occasion(&anniversary, 2026), "7th anniversary");
+        let undated = Celebration {
+            year: None,
+            ..anniversary.clone()
+        };
+        assert_eq!(occasion(&undated, 2026), "anniversary");
+        let birthday = Celebration {
+            kind: CelebrationKind::Birthday,
+            ..anniversary
+        };
+        assert_eq!(occasion(&birthday, 2026), "birthday");
+    }
+
+    #[tokio::test]
+    async fn test_wish_is_grounded_in_the_channel() {
+        let database = Database::new(":memory:").await.unwrap();
+        database
+            .set_guild_setting("g1", GUARDRAIL_SETTING, "strict")
+            .await
+            .unwrap();
+        let client = Arc::new(MockChatClient::with_default_reply(
+            "Happy birthday, young one.",
+        ));
+        let scheduler = BirthdayScheduler::new(
+            database.clone(),
+            client.clone(),
+            "gpt-test".to_string(),
+            UsageTracker::new(database.clone()),
+        );
+        let celebration = Celebration {
+            guild_id: "g1".to_string(),
+            user_id: "u1".to_string(),
+            kind: CelebrationKind::Birthday,
+            month: 6,
+            day: 1,
+            year: None,
+            timezone: Zone::Fixed(0),
+            last_wished_year: None,
+        };
+
+        let grounding = ChannelGrounding::for_channel(&database, Some("g1"), Some("c1"))
+            .await
+            .unwrap();
+        let wish = scheduler
+            .generate_wish("You are Obi.", &grounding, "birthday", &celebration, "c1")
+            .await
+            .unwrap();
+        assert_eq!(wish, "Happy birthday, young one.");
+
+        let request = &client.requests()[0];
+        assert_eq!(request.max_tokens, Some(MAX_WISH_TOKENS));
+        let system_prompt = request.system_prompt().unwrap();
+        assert!(system_prompt.starts_with("## Safety Profile: Strict"));
+        assert!(system_prompt.contains("You are Obi."));
+        assert_eq!(
+            request.last_user_message(),
+            Some("Please wish me a happy birthday.")
+        );
+    }
+}
@@ -0,0 +1,201 @@
+//! # Channel Topics Feature
+//!
+//! Scheduled channel topic updates set up with `/channel_topic schedule`. A
+//! channel either rotates through a list of topics admins wrote, or gets a
+//! fresh AI-written question of the day as its topic. Each schedule runs every
+//! few hours (daily by default), optionally lined up to a local hour, and the
+//! bot needs Manage Channels in the channel to change its topic.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with topic rotation and AI question of the day
+
+pub mod scheduler;
+pub mod writer;
+
+pub use scheduler::TopicScheduler;
+pub use writer::TopicWriter;
+
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+
+use crate::core::local_time;
+
+/// Feature id for toggles
+pub const CHANNEL_TOPICS_FEATURE: &str = "channel_topics";
+
+/// Discord's limit on channel topic length
+pub const MAX_TOPIC_CHARS: usize = 1024;
+
+/// Topics a rotation may hold
+pub const MAX_ROTATION_TOPICS: usize = 25;
+
+/// Hours between updates when none were given
+pub const DEFAULT_INTERVAL_HOURS: i64 = 24;
+
+/// Longest gap between updates a schedule may have (one week)
+pub const MAX_INTERVAL_HOURS: i64 = 168;
+
+/// Start of every question of the day topic
+pub const QUESTION_PREFIX: &str = "❓ Question of the day: ";
+
+/// How a scheduled channel gets its next topic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopicMode {
+    /// Cycle through topics written by admins
+    Rotate,
+    /// Ask the model for a new question of the day
+    Question,
+}
+
+impl TopicMode {
+    /// Value stored in the schedule
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TopicMode::Rotate => "rotate",
+            TopicMode::Question => "question",
+        }
+    }
+
+    pub fn from_setting(value: &str) -> Option<Self> {
+        match value {
+            "rotate" => Some(TopicMode::Rotate),
+            "question" => Some(TopicMode::Question),
+            _ => None,
+        }
+    }
+}
+
+/// Split `|`-separated rotation topics, rejecting empty or oversized lists
+pub fn parse_topics(input: &str) -> Result<Vec<String>, &'static str> {
+    let topics: Vec<String> = input
+        .split('|')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect();
+    if topics.is_empty() {
+        return Err("Give at least one topic, separating several with `|`.");
+    }
+    if topics.len() > MAX_ROTATION_TOPICS {
+        return Err("A rotation can hold at most 25 topics.");
+    }
+    if topics.iter().any(|t| t.chars().count() > MAX_TOPIC_CHARS) {
+        return Err("Each topic must be at most 1024 characters.");
+    }
+    Ok(topics)
+}
+
+/// The rotation topic at `index` and the index to use next time
+pub fn rotation_topic(topics: &[String], index: i64) -> Option<(&str, i64)> {
+    if topics.is_empty() {
+        return None;
+    }
+    let index = index.rem_euclid(topics.len() as i64);
+    Some((&topics[index as usize], (index + 1) % topics.len() as i64))
+}
+
+/// Unix time of a new schedule's first update
+///
+/// Right away without an `hour`; otherwise the next time the local clock
+/// (at `utc_offset_minutes`) reads `hour`:00.
+pub fn first_run_at(now: DateTime<Utc>, hour: Option<u32>, utc_offset_minutes: i32) -> i64 {
+    let Some(at) = hour.and_then(|h| NaiveTime::from_hms_opt(h, 0, 0)) else {
+        return now.timestamp();
+    };
+    let local_now = local_time(now, utc_offset_minutes);
+    let mut local_run = local_now.date().and_time(at);
+    if local_run <= local_now {
+        local_run += Duration::days(1);
+    }
+    (local_run - Duration::minutes(utc_offset_minutes as i64))
+        .and_utc()
+        .timestamp()
+}
+
+/// The first run after `now`, stepping from `next_run_at` in whole intervals
+///
+/// Updates missed while the bot was offline are skipped rather than replayed.
+pub fn next_run_after(next_run_at: i64, interval_hours: i64, now: i64) -> i64 {
+    let interval = interval_hours.max(1) * 3600;
+    if next_run_at > now {
+        return next_run_at;
+    }
+    next_run_at + ((now - next_run_at) / interval + 1) * interval
+}
+
+/// Trim a topic to Discord's length limit
+pub fn clamp_topic(topic: &str) -> String {
+    topic.trim().chars().take(MAX_TOPIC_CHARS).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_topic_mode_round_trip() {
+        for mode in [TopicMode::Rotate, TopicMode::Question] {
+            assert_eq!(TopicMode::from_setting(mode.as_str()), Some(mode));
+        }
+        assert_eq!(TopicMode::from_setting("daily"), None);
+    }
+
+    #[test]
+    fn test_parse_topics() {
+        assert_eq!(
+            parse_topics(" Rust news | | Show your setup|Off-topic ").unwrap(),
+            vec!["Rust news", "Show your setup", "Off-topic"]
+        );
+        assert!(parse_topics(" | ").is_err());
+        assert!(parse_topics(&vec!["t"; 26].join("|")).is_err());
+        assert!(parse_topics(&"x".repeat(1025)).is_err());
+    }
+
+    #[test]
+    fn test_rotation_topic_wraps() {
+        let topics = vec!["a".to_string(), "b".to_string()];
+        assert_eq!(rotation_topic(&topics, 0), Some(("a", 1)));
+        assert_eq!(rotation_topic(&topics, 1), Some(("b", 0)));
+        // A rotation shortened since the index was saved
+        assert_eq!(rotation_topic(&topics, 5), Some(("b", 0)));
+        assert_eq!(rotation_topic(&[], 0), None);
+    }
+
+    #[test]
+    fn test_first_run_at() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 8, 30, 0).unwrap();
+        assert_eq!(first_run_at(now, None, 0), now.timestamp());
+        // 09:00 UTC is still ahead today
+        assert_eq!(
+            first_run_at(now, Some(9), 0),
+            Utc.with_ymd_and_hms(2026, 3, 10, 9, 0, 0)
+                .unwrap()
+                .timestamp()
+        );
+        // 09:00 at UTC+2 (07:00 UTC) has passed, so tomorrow
+        assert_eq!(
+            first_run_at(now, Some(9), 120),
+            Utc.with_ymd_and_hms(2026, 3, 11, 7, 0, 0)
+                .unwrap()
+                .timestamp()
+        );
+    }
+
+    #[test]
+    fn test_next_run_after_skips_missed_runs() {
+        assert_eq!(next_run_after(1000, 1, 500), 1000);
+        assert_eq!(next_run_after(1000, 1, 1000), 1000 + 3600);
+        // Offline for three and a half intervals
+        assert_eq!(next_run_after(0, 2, 7200 * 3 + 3600), 7200 * 4);
+    }
+
+    #[test]
+    fn test_clamp_topic() {
+        assert_eq!(clamp_topic("  hi  "), "hi");
+        assert_eq!(clamp_topic(&"y".repeat(2000)).len(), MAX_TOPIC_CHARS);
+    }
+}
@@ -0,0 +1,146 @@
+//! Background task applying scheduled channel topic updates
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use chrono::Utc;
+use log::{error, info, warn};
+use serenity::http::Http;
+use serenity::model::id::ChannelId;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::interval;
+
+use super::{
+    clamp_topic, next_run_after, rotation_topic, TopicMode, TopicWriter, CHANNEL_TOPICS_FEATURE,
+    QUESTION_PREFIX,
+};
+use crate::database::{ChannelTopicSchedule, Database};
+use crate::features::guardrails::ChannelGrounding;
+
+pub struct TopicScheduler {
+    database: Database,
+    writer: TopicWriter,
+}
+
+impl TopicScheduler {
+    pub fn new(database: Database, writer: TopicWriter) -> Self {
+        Self { database, writer }
+    }
+
+    /// Start the channel topic scheduler loop
+    /// This should be spawned as a tokio task
+    pub async fn run(&self, http: Arc<Http>) {
+        let mut check_interval = interval(Duration::from_secs(5 * 60));
+
+        info!("🏷️ Channel topic scheduler started");
+
+        loop {
+            check_interval.tick().await;
+
+            if let Err(e) = self.process_due_topics(&http).await {
+                error!("❌ Error processing channel topics: {e}");
+            }
+        }
+    }
+
+    async fn process_due_topics(&self, http: &Arc<Http>) -> Result<()> {
+        let now = Utc::now().timestamp();
+        for schedule in self.database.get_due_channel_topic_schedules(now).await? {
+            let next_run_at = next_run_after(schedule.next_run_at, schedule.interval_hours, now);
+            if !self
+                .database
+                .is_feature_enabled(CHANNEL_TOPICS_FEATURE, None, Some(&schedule.guild_id))
+                .await?
+            {
+                // Paused rather than replayed in a burst when the feature comes back
+                self.database
+                    .advance_channel_topic_schedule(
+                        &schedule.channel_id,
+                        next_run_at,
+                        schedule.next_index,
+                        None,
+                    )
+                    .await?;
+                continue;
+            }
+
+            let (topic, next_index) = match self.update_topic(http, &schedule).await {
+                Ok((topic, next_index)) => {
+                    info!("🏷️ Updated topic of channel {}", schedule.channel_id);
+                    (Some(topic), next_index)
+                }
+                Err(e) => {
+                    warn!(
+                        "⚠️ Failed to update topic of channel {}: {e}",
+                        schedule.channel_id
+                    );
+                    (None, schedule.next_index)
+                }
+            };
+            // Moved on either way so a broken channel doesn't retry every 5 minutes
+            self.database
+                .advance_channel_topic_schedule(
+                    &schedule.channel_id,
+                    next_run_at,
+                    next_index,
+                    topic.as_deref(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Set the channel's next topic, returning it and the next rotation index
+    async fn update_topic(
+        &self,
+        http: &Arc<Http>,
+        schedule: &ChannelTopicSchedule,
+    ) -> Result<(String, i64)> {
+        let channel_id = ChannelId(schedule.channel_id.parse::<u64>()?);
+        let (topic, next_index) = match TopicMode::from_setting(&schedule.mode) {
+            Some(TopicMode::Rotate) => {
+                let (topic, next_index) = rotation_topic(&schedule.topics, schedule.next_index)
+                    .ok_or_else(|| anyhow::anyhow!("Rotation has no topics"))?;
+                (clamp_topic(topic), next_index)
+            }
+            Some(TopicMode::Question) => {
+                let channel_name = channel_id
+                    .to_channel(http)
+                    .await?
+                    .guild()
+                    .map(|c| c.name)
+                    .unwrap_or_default();
+                let previous = schedule
+                    .last_topic
+                    .as_deref()
+                    .map(|t| t.strip_prefix(QUESTION_PREFIX).unwrap_or(t));
+                let grounding = ChannelGrounding::for_channel(
+                    &self.database,
+                    Some(&schedule.guild_id),
+                    Some(&schedule.channel_id),
+                )
+                .await?;
+                let topic = self
+                    .writer
+                    .question(
+                        &schedule.guild_id,
+                        &schedule.channel_id,
+                        &channel_name,
+                        &grounding,
+                        schedule.theme.as_deref(),
+                        previous,
+                    )
+                    .await?;
+                (topic, schedule.next_index)
+            }
+            None => anyhow::bail!("Unknown topic mode {}", schedule.mode),
+        };
+        channel_id.edit(http, |c| c.topic(&topic)).await?;
+        Ok((topic, next_index))
+    }
+}
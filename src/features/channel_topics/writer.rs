@@ -0,0 +1,136 @@
+//! Write a question of the day for a channel topic
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use openai::chat::ChatCompletionMessageRole;
+use std::sync::Arc;
+
+use super::{clamp_topic, QUESTION_PREFIX};
+use crate::core::{chat_message, ChatClient};
+use crate::features::analytics::{CostBucket, UsageTracker};
+use crate::features::guardrails::ChannelGrounding;
+
+/// Cap on generated tokens; a topic is one question
+const MAX_QUESTION_TOKENS: u64 = 120;
+
+/// User id usage is logged under for scheduled topics
+const TOPIC_USER_ID: &str = "system_topics";
+
+/// Writes questions of the day through the shared chat client
+#[derive(Clone)]
+pub struct TopicWriter {
+    chat_client: Arc<dyn ChatClient>,
+    openai_model: String,
+    usage_tracker: UsageTracker,
+}
+
+impl TopicWriter {
+    pub fn new(
+        chat_client: Arc<dyn ChatClient>,
+        openai_model: String,
+        usage_tracker: UsageTracker,
+    ) -> Self {
+        Self {
+            chat_client,
+            openai_model,
+            usage_tracker,
+        }
+    }
+
+    /// Write a new question of the day for `channel_name`
+    ///
+    /// `theme` steers the subject and `previous` (the last question set) is
+    /// passed along so the same question doesn't come back next time. The
+    /// channel's safety profile comes from `grounding`.
+    pub async fn question(
+        &self,
+        guild_id: &str,
+        channel_id: &str,
+        channel_name: &str,
+        grounding: &ChannelGrounding,
+        theme: Option<&str>,
+        previous: Option<&str>,
+    ) -> Result<String> {
+        let completion = self
+            .chat_client
+            .create_chat_completion_limited(
+                &self.openai_model,
+                vec![
+                    chat_message(
+                        ChatCompletionMessageRole::System,
+                        grounding.task_prompt(QUESTION_PROMPT),
+                    ),
+                    chat_message(
+                        ChatCompletionMessageRole::User,
+                        question_request(channel_name, theme, previous),
+                    ),
+                ],
+                Some(MAX_QUESTION_TOKENS),
+            )
+            .await?;
+
+        if let Some(usage) = &completion.usage {
+            self.usage_tracker.log_chat(
+                &self.openai_model,
+                usage.prompt_tokens,
+                usage.completion_tokens,
+                usage.total_tokens,
+                TOPIC_USER_ID,
+                Some(guild_id),
+                Some(channel_id),
+                None,
+                CostBucket::ChannelTopics,
+            );
+        }
+
+        let question = completion
+            .choices
+            .first()
+            .and_then(|choice| choice.message.content.clone())
+            .map(|content| {
+                clamp_topic(content.trim_matches(|c: char| c == '"' || c.is_whitespace()))
+            })
+            .unwrap_or_default();
+        if question.is_empty() {
+            anyhow::bail!("Empty completion");
+        }
+        Ok(format!("{QUESTION_PREFIX}{question}"))
+    }
+}
+
+const QUESTION_PROMPT: &str = "You write the question of the day shown as a Discord channel's \
+    topic. Reply with only one open, friendly question that anyone in the channel could answer \
+    in a message or two. At most 200 characters, no quotes, no hashtags, no preamble.";
+
+/// The user message asking for a question
+fn question_request(channel_name: &str, theme: Option<&str>, previous: Option<&str>) -> String {
+    let mut request = format!("Channel: #{channel_name}");
+    if let Some(theme) = theme {
+        request.push_str(&format!("\nTheme: {theme}"));
+    }
+    if let Some(previous) = previous {
+        request.push_str(&format!(
+            "\nPrevious question (ask something else): {previous}"
+        ));
+    }
+    request
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_question_request() {
+        assert_eq!(question_request("general", None, None), "Channel: #general");
+        assert_eq!(
+            question_request("books", Some("sci-fi"), Some("Favourite author?")),
+            "Channel: #books\nTheme: sci-fi\nPrevious question (ask something else): Favourite author?"
+        );
+    }
+}
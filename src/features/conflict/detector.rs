@@ -1,13 +1,15 @@
 //! # Feature: Conflict Detection
 //!
 //! Detects heated discussions using keyword analysis, caps detection, and
-//! punctuation patterns. Provides confidence scoring for conflict intensity.
+//! punctuation patterns. Provides confidence scoring for conflict intensity,
+//! and a signed per-message sentiment score for channel mood timelines.
 //!
-//! - **Version**: 1.0.0
+//! - **Version**: 1.1.0
 //! - **Since**: 0.1.0
 //! - **Toggleable**: true
 //!
 //! ## Changelog
+//! - 1.1.0: get_sentiment_score with positive keywords and whole-word matching
 //! - 1.0.0: Initial release with 50+ hostile keywords and pattern detection
 
 use regex::Regex;
@@ -122,6 +124,47 @@ const HOSTILE_KEYWORDS: &[&str] = &[
     "scrub",
 ];
 
+/// Friendly words and phrases that lift a message's sentiment
+/// Matched as whole words, unlike `HOSTILE_KEYWORDS`
+const POSITIVE_KEYWORDS: &[&str] = &[
+    "thanks",
+    "thank you",
+    "thx",
+    "ty",
+    "appreciate",
+    "appreciated",
+    "love",
+    "loved",
+    "great",
+    "awesome",
+    "amazing",
+    "nice",
+    "cool",
+    "perfect",
+    "excellent",
+    "fantastic",
+    "brilliant",
+    "beautiful",
+    "congrats",
+    "congratulations",
+    "well done",
+    "good job",
+    "welcome",
+    "glad",
+    "happy",
+    "excited",
+    "fun",
+    "helpful",
+    "agreed",
+    "yay",
+    "lol",
+    "lmao",
+    "haha",
+];
+
+/// Emoji that lift a message's sentiment (substring matched)
+const POSITIVE_EMOJI: &[&str] = &["😂", "🤣", "😊", "😄", "😍", "❤️", "🎉", "👍", "🙏", "🥳"];
+
 /// Detector for identifying heated arguments and conflicts in conversations
 #[derive(Clone)]
 pub struct ConflictDetector {
@@ -277,6 +320,42 @@ impl ConflictDetector {
         score.min(1.0)
     }
 
+    /// Signed sentiment of a single message, from -1.0 (hostile) to 1.0 (friendly)
+    ///
+    /// Keywords are matched as whole words so "hello" doesn't count as "hell";
+    /// shouting and excessive punctuation pull the score down like in
+    /// [`Self::get_conflict_score`].
+    pub fn get_sentiment_score(&self, content: &str) -> f32 {
+        let words: Vec<String> = content
+            .split_whitespace()
+            .map(|w| {
+                w.trim_matches(|c: char| !c.is_alphanumeric() && c != '*')
+                    .to_lowercase()
+            })
+            .filter(|w| !w.is_empty())
+            .collect();
+        let padded = format!(" {} ", words.join(" "));
+        let count = |keywords: &[&str]| {
+            keywords
+                .iter()
+                .filter(|keyword| padded.contains(&format!(" {keyword} ")))
+                .count()
+        };
+
+        let emoji = POSITIVE_EMOJI.iter().filter(|e| content.contains(*e)).count();
+        let positive = ((count(POSITIVE_KEYWORDS) + emoji) as f32 * 0.35).min(0.9);
+
+        let mut negative = (count(HOSTILE_KEYWORDS) as f32 * 0.4).min(0.8);
+        if self.calculate_caps_percentage(content) > 0.5 && words.len() >= 2 {
+            negative += 0.2;
+        }
+        if self.excessive_punctuation.is_match(content) && positive == 0.0 {
+            negative += 0.1;
+        }
+
+        (positive - negative).clamp(-1.0, 1.0)
+    }
+
     /// Calculate percentage of uppercase characters
     fn calculate_caps_percentage(&self, text: &str) -> f32 {
         let letters: Vec<char> = text.chars().filter(|c| c.is_alphabetic()).collect();
@@ -363,6 +442,18 @@ mod tests {
         assert!(score < 0.2, "Calm message should have low score");
     }
 
+    #[test]
+    fn test_sentiment_score() {
+        let detector = ConflictDetector::new();
+
+        assert!(detector.get_sentiment_score("thanks, this is awesome 🎉") > 0.5);
+        assert!(detector.get_sentiment_score("you're an idiot, shut up") < -0.5);
+        assert_eq!(detector.get_sentiment_score("the meeting moved to 3pm"), 0.0);
+        // Whole words only: no "hell" in hello, no "ty" in city
+        assert_eq!(detector.get_sentiment_score("hello from the city"), 0.0);
+        assert!(detector.get_sentiment_score("WHAT IS THIS GARBAGE???") <= -0.6);
+    }
+
     #[test]
     fn test_caps_detection() {
         let detector = ConflictDetector::new();
@@ -2,12 +2,18 @@
 //!
 //! Detects heated discussions and provides Obi-Wan themed mediation.
 //!
-//! - **Version**: 1.0.0
+//! - **Version**: 1.1.0
 //! - **Since**: 0.1.0
 //! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.1.0: Recent messages are read from an in-memory window instead of flushing stored history
+//! - 1.0.0: Initial release
 
 pub mod detector;
 pub mod mediator;
+pub mod window;
 
 pub use detector::ConflictDetector;
 pub use mediator::ConflictMediator;
+pub use window::MessageWindow;
@@ -0,0 +1,120 @@
+//! # Feature: Conflict Message Window
+//!
+//! The last few messages of each channel, kept in memory for conflict
+//! detection so it doesn't read stored history (and force the write-behind
+//! writer to flush) on every message. A channel's window is seeded from the
+//! database the first time detection runs there; after that each message is
+//! appended as it arrives.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: false
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use dashmap::DashMap;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+/// Messages kept per channel, matching what detection reads
+pub const WINDOW_SIZE: usize = 10;
+
+/// (user_id, content, unix timestamp) as conflict detection reads them
+pub type WindowMessage = (String, String, String);
+
+/// Recent messages per channel
+#[derive(Clone, Default)]
+pub struct MessageWindow {
+    channels: Arc<DashMap<String, VecDeque<WindowMessage>>>,
+}
+
+impl MessageWindow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a message to a seeded channel's window; unseeded channels are
+    /// left alone so their first read still comes from the database
+    pub fn record(&self, channel_id: &str, user_id: &str, content: &str, timestamp: i64) {
+        if let Some(mut window) = self.channels.get_mut(channel_id) {
+            window.push_back((
+                user_id.to_string(),
+                content.to_string(),
+                timestamp.to_string(),
+            ));
+            while window.len() > WINDOW_SIZE {
+                window.pop_front();
+            }
+        }
+    }
+
+    /// Replace a channel's window with messages read from the database (oldest first)
+    pub fn seed(&self, channel_id: &str, messages: &[WindowMessage]) {
+        let start = messages.len().saturating_sub(WINDOW_SIZE);
+        self.channels.insert(
+            channel_id.to_string(),
+            messages[start..].iter().cloned().collect(),
+        );
+    }
+
+    /// A seeded channel's messages after `since` (oldest first), or None if
+    /// the channel hasn't been seeded yet
+    pub fn since(&self, channel_id: &str, since: Option<i64>) -> Option<Vec<WindowMessage>> {
+        let window = self.channels.get(channel_id)?;
+        Some(
+            window
+                .iter()
+                .filter(|(_, _, timestamp)| {
+                    since.is_none_or(|since| timestamp.parse::<i64>().is_ok_and(|ts| ts > since))
+                })
+                .cloned()
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(user: &str, content: &str, timestamp: i64) -> WindowMessage {
+        (user.to_string(), content.to_string(), timestamp.to_string())
+    }
+
+    #[test]
+    fn test_unseeded_channel_reads_nothing() {
+        let window = MessageWindow::new();
+        window.record("c1", "u1", "hello", 100);
+        assert_eq!(window.since("c1", None), None);
+    }
+
+    #[test]
+    fn test_record_after_seed() {
+        let window = MessageWindow::new();
+        window.seed("c1", &[message("u1", "first", 100)]);
+        window.record("c1", "u2", "second", 200);
+        assert_eq!(
+            window.since("c1", None).unwrap(),
+            vec![message("u1", "first", 100), message("u2", "second", 200)]
+        );
+        assert_eq!(
+            window.since("c1", Some(100)).unwrap(),
+            vec![message("u2", "second", 200)]
+        );
+    }
+
+    #[test]
+    fn test_window_is_capped() {
+        let window = MessageWindow::new();
+        let seeded: Vec<_> = (0..15).map(|i| message("u1", "x", i)).collect();
+        window.seed("c1", &seeded);
+        assert_eq!(window.since("c1", None).unwrap().len(), WINDOW_SIZE);
+
+        window.record("c1", "u2", "newest", 99);
+        let messages = window.since("c1", None).unwrap();
+        assert_eq!(messages.len(), WINDOW_SIZE);
+        assert_eq!(messages.last(), Some(&message("u2", "newest", 99)));
+        assert_eq!(messages[0].2, "6");
+    }
+}
@@ -0,0 +1,134 @@
+//! # Feature: Conversation Export
+//!
+//! The "Export Conversation" message context menu packages a recent exchange
+//! with the bot as a Markdown file. In a thread it exports the thread's
+//! messages; elsewhere it exports the stored conversation between the bot and
+//! the member whose message was picked (or the invoker, when the message is
+//! the bot's own). Each entry names its speaker, with bot replies attributed
+//! to the persona that wrote them, and carries a UTC timestamp.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with thread and stored-history exports
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+/// Feature id for toggles
+pub const EXPORT_FEATURE: &str = "conversation_export";
+
+/// Name of the message context menu command
+pub const EXPORT_COMMAND: &str = "Export Conversation";
+
+/// Messages included in one export
+pub const EXPORT_LIMIT: u32 = 100;
+
+/// One message in an export
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportEntry {
+    pub speaker: String,
+    /// Whether a persona (the bot) wrote it
+    pub from_bot: bool,
+    pub timestamp: DateTime<Utc>,
+    pub content: String,
+}
+
+/// Parse a `conversation_history` timestamp (SQLite `CURRENT_TIMESTAMP`, UTC)
+pub fn parse_stored_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .map(|naive| naive.and_utc())
+}
+
+fn format_timestamp(timestamp: &DateTime<Utc>) -> String {
+    timestamp.format("%Y-%m-%d %H:%M UTC").to_string()
+}
+
+/// Render an export as a Markdown document
+///
+/// `source` describes where the messages came from, e.g. the channel name.
+pub fn render_markdown(
+    source: &str,
+    entries: &[ExportEntry],
+    exported_at: DateTime<Utc>,
+) -> String {
+    let mut markdown = format!(
+        "# Conversation export\n\n\
+         - **Source:** {source}\n\
+         - **Messages:** {}\n\
+         - **Exported:** {}\n",
+        entries.len(),
+        format_timestamp(&exported_at)
+    );
+    if let (Some(first), Some(last)) = (entries.first(), entries.last()) {
+        markdown.push_str(&format!(
+            "- **Covers:** {} – {}\n",
+            format_timestamp(&first.timestamp),
+            format_timestamp(&last.timestamp)
+        ));
+    }
+    for entry in entries {
+        markdown.push_str(&format!(
+            "\n---\n\n### {} {} · {}\n\n{}\n",
+            if entry.from_bot { "🎭" } else { "🧑" },
+            entry.speaker,
+            format_timestamp(&entry.timestamp),
+            entry.content.trim()
+        ));
+    }
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_stored_timestamp() {
+        assert_eq!(
+            parse_stored_timestamp("2024-05-01 12:30:00"),
+            Some(Utc.with_ymd_and_hms(2024, 5, 1, 12, 30, 0).unwrap())
+        );
+        assert_eq!(parse_stored_timestamp("yesterday"), None);
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let at = Utc.with_ymd_and_hms(2024, 5, 1, 12, 30, 0).unwrap();
+        let entries = vec![
+            ExportEntry {
+                speaker: "alice".to_string(),
+                from_bot: false,
+                timestamp: at,
+                content: "How do lifetimes work?".to_string(),
+            },
+            ExportEntry {
+                speaker: "Obi-Wan".to_string(),
+                from_bot: true,
+                timestamp: at + chrono::Duration::minutes(1),
+                content: "  Patience, young one.  ".to_string(),
+            },
+        ];
+        let markdown = render_markdown("#help", &entries, at + chrono::Duration::hours(1));
+        assert!(markdown.starts_with("# Conversation export\n\n- **Source:** #help\n"));
+        assert!(markdown.contains("- **Messages:** 2\n"));
+        assert!(markdown.contains("- **Covers:** 2024-05-01 12:30 UTC – 2024-05-01 12:31 UTC\n"));
+        assert!(
+            markdown.contains("### 🧑 alice · 2024-05-01 12:30 UTC\n\nHow do lifetimes work?\n")
+        );
+        assert!(
+            markdown.ends_with("### 🎭 Obi-Wan · 2024-05-01 12:31 UTC\n\nPatience, young one.\n")
+        );
+    }
+
+    #[test]
+    fn test_render_empty_export() {
+        let at = Utc.with_ymd_and_hms(2024, 5, 1, 12, 30, 0).unwrap();
+        let markdown = render_markdown("#help", &[], at);
+        assert!(markdown.contains("- **Messages:** 0\n"));
+        assert!(!markdown.contains("Covers"));
+    }
+}
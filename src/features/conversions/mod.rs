@@ -0,0 +1,427 @@
+//! # Feature: Conversions
+//!
+//! `/convert` turns an amount from one unit or currency into another. Units
+//! (length, mass, volume, temperature, speed, area and data) convert from a
+//! built-in table. Currencies use exchange rates fetched from a configurable
+//! API and cached in memory, falling back to the last fetched rates and then
+//! to bundled approximate rates when the API can't be reached. The member's
+//! persona can optionally phrase the result in character.
+//!
+//! - **Version**: 1.1.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.1.0: Conversion resolution and result text moved here from the /convert handler, for voice commands
+//! - 1.0.0: Initial release with unit tables, cached live exchange rates and persona phrasing
+
+pub mod rates;
+
+use rates::exchange_rates;
+
+/// Feature id for toggles
+pub const CONVERT_FEATURE: &str = "convert";
+
+/// What a unit measures; only units of the same dimension convert
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dimension {
+    Length,
+    Mass,
+    Volume,
+    Temperature,
+    Speed,
+    Area,
+    Data,
+}
+
+impl Dimension {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Dimension::Length => "length",
+            Dimension::Mass => "mass",
+            Dimension::Volume => "volume",
+            Dimension::Temperature => "temperature",
+            Dimension::Speed => "speed",
+            Dimension::Area => "area",
+            Dimension::Data => "data",
+        }
+    }
+}
+
+/// A unit in the conversion table
+///
+/// A value in this unit is `value * factor + offset` in the dimension's base
+/// unit (metres, kilograms, litres, kelvin, metres per second, square metres
+/// and bytes). Only temperatures have an offset.
+#[derive(Debug, PartialEq)]
+pub struct Unit {
+    pub symbol: &'static str,
+    pub dimension: Dimension,
+    factor: f64,
+    offset: f64,
+    aliases: &'static [&'static str],
+}
+
+const fn unit(
+    symbol: &'static str,
+    dimension: Dimension,
+    factor: f64,
+    aliases: &'static [&'static str],
+) -> Unit {
+    Unit {
+        symbol,
+        dimension,
+        factor,
+        offset: 0.0,
+        aliases,
+    }
+}
+
+const UNITS: &[Unit] = &[
+    // Length
+    unit(
+        "mm",
+        Dimension::Length,
+        0.001,
+        &["millimeter", "millimetre"],
+    ),
+    unit("cm", Dimension::Length, 0.01, &["centimeter", "centimetre"]),
+    unit("m", Dimension::Length, 1.0, &["meter", "metre"]),
+    unit("km", Dimension::Length, 1000.0, &["kilometer", "kilometre"]),
+    unit("in", Dimension::Length, 0.0254, &["inch", "inches", "\""]),
+    unit("ft", Dimension::Length, 0.3048, &["foot", "feet", "'"]),
+    unit("yd", Dimension::Length, 0.9144, &["yard"]),
+    unit("mi", Dimension::Length, 1609.344, &["mile"]),
+    unit("nmi", Dimension::Length, 1852.0, &["nautical mile"]),
+    // Mass
+    unit("mg", Dimension::Mass, 0.000_001, &["milligram"]),
+    unit("g", Dimension::Mass, 0.001, &["gram"]),
+    unit("kg", Dimension::Mass, 1.0, &["kilogram", "kilo"]),
+    unit("t", Dimension::Mass, 1000.0, &["tonne", "metric ton"]),
+    unit("oz", Dimension::Mass, 0.028_349_523_125, &["ounce"]),
+    unit("lb", Dimension::Mass, 0.453_592_37, &["pound"]),
+    unit("st", Dimension::Mass, 6.350_293_18, &["stone"]),
+    // Volume
+    unit(
+        "ml",
+        Dimension::Volume,
+        0.001,
+        &["milliliter", "millilitre"],
+    ),
+    unit("l", Dimension::Volume, 1.0, &["liter", "litre"]),
+    unit(
+        "tsp",
+        Dimension::Volume,
+        0.004_928_921_593_75,
+        &["teaspoon"],
+    ),
+    unit(
+        "tbsp",
+        Dimension::Volume,
+        0.014_786_764_781_25,
+        &["tablespoon"],
+    ),
+    unit(
+        "floz",
+        Dimension::Volume,
+        0.029_573_529_562_5,
+        &["fl oz", "fluid ounce"],
+    ),
+    unit("cup", Dimension::Volume, 0.236_588_236_5, &[]),
+    unit("pt", Dimension::Volume, 0.473_176_473, &["pint"]),
+    unit("qt", Dimension::Volume, 0.946_352_946, &["quart"]),
+    unit("gal", Dimension::Volume, 3.785_411_784, &["gallon"]),
+    // Temperature
+    Unit {
+        symbol: "°C",
+        dimension: Dimension::Temperature,
+        factor: 1.0,
+        offset: 273.15,
+        aliases: &["celsius", "degc", "centigrade"],
+    },
+    Unit {
+        symbol: "°F",
+        dimension: Dimension::Temperature,
+        factor: 5.0 / 9.0,
+        offset: 459.67 * 5.0 / 9.0,
+        aliases: &["fahrenheit", "degf"],
+    },
+    unit("K", Dimension::Temperature, 1.0, &["kelvin"]),
+    // Speed
+    unit(
+        "m/s",
+        Dimension::Speed,
+        1.0,
+        &["mps", "meters per second", "metres per second"],
+    ),
+    unit(
+        "km/h",
+        Dimension::Speed,
+        1000.0 / 3600.0,
+        &["kph", "kmh", "kilometers per hour", "kilometres per hour"],
+    ),
+    unit(
+        "mph",
+        Dimension::Speed,
+        0.447_04,
+        &["mi/h", "miles per hour"],
+    ),
+    unit("kn", Dimension::Speed, 1852.0 / 3600.0, &["knot", "kt"]),
+    // Area
+    unit(
+        "m²",
+        Dimension::Area,
+        1.0,
+        &["m2", "sqm", "square meter", "square metre"],
+    ),
+    unit(
+        "km²",
+        Dimension::Area,
+        1_000_000.0,
+        &["km2", "square kilometer", "square kilometre"],
+    ),
+    unit(
+        "ft²",
+        Dimension::Area,
+        0.092_903_04,
+        &["ft2", "sqft", "square foot", "square feet"],
+    ),
+    unit("ha", Dimension::Area, 10_000.0, &["hectare"]),
+    unit("acre", Dimension::Area, 4_046.856_422_4, &["ac"]),
+    // Data
+    unit("B", Dimension::Data, 1.0, &["byte"]),
+    unit("KB", Dimension::Data, 1e3, &["kilobyte"]),
+    unit("MB", Dimension::Data, 1e6, &["megabyte"]),
+    unit("GB", Dimension::Data, 1e9, &["gigabyte"]),
+    unit("TB", Dimension::Data, 1e12, &["terabyte"]),
+    unit("KiB", Dimension::Data, 1024.0, &["kibibyte"]),
+    unit("MiB", Dimension::Data, 1_048_576.0, &["mebibyte"]),
+    unit("GiB", Dimension::Data, 1_073_741_824.0, &["gibibyte"]),
+];
+
+/// Look a unit up by symbol or name, case-insensitively and ignoring a
+/// plural `s`
+pub fn find_unit(input: &str) -> Option<&'static Unit> {
+    let lower = input.trim().trim_start_matches('°').to_lowercase();
+    let matches = |name: &str| {
+        UNITS.iter().find(|unit| {
+            unit.symbol.trim_start_matches('°').to_lowercase() == name
+                || unit.aliases.contains(&name)
+        })
+    };
+    // `lbs` is pounds, but `ms` isn't metres
+    matches(&lower).or_else(|| {
+        lower
+            .strip_suffix('s')
+            .filter(|stem| stem.len() > 1)
+            .and_then(matches)
+    })
+}
+
+/// Convert between two units of the same dimension
+pub fn convert_units(amount: f64, from: &Unit, to: &Unit) -> Result<f64, String> {
+    if from.dimension != to.dimension {
+        return Err(format!(
+            "Can't convert {} ({}) to {} ({}).",
+            from.symbol,
+            from.dimension.label(),
+            to.symbol,
+            to.dimension.label()
+        ));
+    }
+    Ok((amount * from.factor + from.offset - to.offset) / to.factor)
+}
+
+/// Normalise an ISO 4217 style currency code, like `usd` to `USD`
+pub fn currency_code(input: &str) -> Option<String> {
+    let input = input.trim();
+    (input.len() == 3 && input.chars().all(|c| c.is_ascii_alphabetic()))
+        .then(|| input.to_ascii_uppercase())
+}
+
+/// Format an amount for display: thousands separators and at most
+/// `decimals` decimal places, without trailing zeros
+pub fn format_amount(value: f64, decimals: usize) -> String {
+    if !value.is_finite() {
+        return value.to_string();
+    }
+    // Tiny values would round to zero; show them with significant digits
+    if value != 0.0 && value.abs() < 0.5 * 10f64.powi(-(decimals as i32)) {
+        return format!("{value:.3e}");
+    }
+    let formatted = format!("{:.*}", decimals, value.abs());
+    let (whole, fraction) = formatted.split_once('.').unwrap_or((&formatted, ""));
+    let fraction = fraction.trim_end_matches('0');
+
+    let mut grouped = String::new();
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    let sign = if value < 0.0 && (grouped != "0" || !fraction.is_empty()) {
+        "-"
+    } else {
+        ""
+    };
+    if fraction.is_empty() {
+        format!("{sign}{grouped}")
+    } else {
+        format!("{sign}{grouped}.{fraction}")
+    }
+}
+
+/// What a conversion request resolved to
+#[derive(Debug, Clone, PartialEq)]
+pub enum Conversion {
+    Units(&'static Unit, &'static Unit),
+    Currency(String, String),
+}
+
+impl Conversion {
+    /// Work out whether `from` and `to` are units or currency codes
+    pub fn resolve(from: &str, to: &str) -> Result<Self, String> {
+        if let (Some(from_unit), Some(to_unit)) = (find_unit(from), find_unit(to)) {
+            return Ok(Conversion::Units(from_unit, to_unit));
+        }
+        match (currency_code(from), currency_code(to)) {
+            (Some(from), Some(to)) if from == to => {
+                Err("Pick two different currencies.".to_string())
+            }
+            (Some(from), Some(to)) => Ok(Conversion::Currency(from, to)),
+            _ => Err(format!(
+                "I don't know how to convert `{}` to `{}`. Use unit names like `mi`, `°F` or `kg`, or three-letter currency codes like `USD`.",
+                from.trim(),
+                to.trim()
+            )),
+        }
+    }
+
+    /// Convert an amount, returning the result line and, for currencies, a
+    /// note on the rate's age
+    pub async fn run(&self, amount: f64) -> Result<(String, Option<String>), String> {
+        match self {
+            Conversion::Units(from, to) => {
+                let result = convert_units(amount, from, to)?;
+                Ok((
+                    format!(
+                        "📏 **{} {}** = **{} {}**",
+                        format_amount(amount, 4),
+                        from.symbol,
+                        format_amount(result, 4),
+                        to.symbol
+                    ),
+                    None,
+                ))
+            }
+            Conversion::Currency(from, to) => {
+                let quote = exchange_rates()
+                    .quote(from, to)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok((
+                    format!(
+                        "💱 **{} {from}** = **{} {to}**",
+                        format_amount(amount, 2),
+                        format_amount(amount * quote.rate, 2)
+                    ),
+                    Some(quote.source.note()),
+                ))
+            }
+        }
+    }
+}
+
+/// Prompt for phrasing a conversion result in character
+pub const PHRASING_PROMPT: &str = "A member asked you to convert an amount. In your \
+    characteristic style, tell them the result in one or two sentences. Keep every number and \
+    unit exactly as given, and don't add any other figures. No preamble.";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    #[test]
+    fn test_find_unit() {
+        assert_eq!(find_unit("km").unwrap().symbol, "km");
+        assert_eq!(find_unit("Miles").unwrap().symbol, "mi");
+        assert_eq!(find_unit("°f").unwrap().symbol, "°F");
+        assert_eq!(find_unit("C").unwrap().symbol, "°C");
+        assert_eq!(find_unit("fl oz").unwrap().symbol, "floz");
+        assert_eq!(find_unit("m2").unwrap().symbol, "m²");
+        assert_eq!(find_unit("gib").unwrap().symbol, "GiB");
+        assert_eq!(find_unit("lbs").unwrap().symbol, "lb");
+        assert!(find_unit("ms").is_none());
+        assert!(find_unit("usd").is_none());
+    }
+
+    #[test]
+    fn test_convert_units() {
+        let unit = |s| find_unit(s).unwrap();
+        assert!(approx(
+            convert_units(1.0, unit("mi"), unit("km")).unwrap(),
+            1.609344
+        ));
+        assert!(approx(
+            convert_units(100.0, unit("c"), unit("f")).unwrap(),
+            212.0
+        ));
+        assert!(approx(
+            convert_units(-40.0, unit("f"), unit("c")).unwrap(),
+            -40.0
+        ));
+        assert!(approx(
+            convert_units(0.0, unit("c"), unit("k")).unwrap(),
+            273.15
+        ));
+        assert!(approx(
+            convert_units(1.0, unit("gal"), unit("cup")).unwrap(),
+            16.0
+        ));
+        assert!(approx(
+            convert_units(1.0, unit("GiB"), unit("MiB")).unwrap(),
+            1024.0
+        ));
+        assert_eq!(
+            convert_units(1.0, unit("kg"), unit("m")).unwrap_err(),
+            "Can't convert kg (mass) to m (length)."
+        );
+    }
+
+    #[test]
+    fn test_resolve() {
+        assert!(matches!(
+            Conversion::resolve("mi", "km"),
+            Ok(Conversion::Units(_, _))
+        ));
+        // CUP is both a unit and a currency; a currency partner decides
+        assert_eq!(
+            Conversion::resolve("cup", "usd"),
+            Ok(Conversion::Currency("CUP".to_string(), "USD".to_string()))
+        );
+        assert!(Conversion::resolve("eur", "EUR").is_err());
+        assert!(Conversion::resolve("parsecs", "km").is_err());
+    }
+
+    #[test]
+    fn test_currency_code() {
+        assert_eq!(currency_code(" eur "), Some("EUR".to_string()));
+        assert_eq!(currency_code("euro"), None);
+        assert_eq!(currency_code("U$D"), None);
+    }
+
+    #[test]
+    fn test_format_amount() {
+        assert_eq!(format_amount(1234567.891, 2), "1,234,567.89");
+        assert_eq!(format_amount(1609.344, 4), "1,609.344");
+        assert_eq!(format_amount(212.0, 4), "212");
+        assert_eq!(format_amount(-40.0, 2), "-40");
+        assert_eq!(format_amount(-0.001, 2), "-1.000e-3");
+        assert_eq!(format_amount(0.0, 2), "0");
+    }
+}
@@ -0,0 +1,279 @@
+//! Exchange rates for currency conversions
+//!
+//! Rates are fetched per base currency from a configurable API and cached in
+//! memory. When the API can't be reached, the last fetched rates are used
+//! however old they are, then bundled approximate rates for major
+//! currencies, so `/convert` keeps working offline.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use log::{debug, warn};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Default rates API; `{base}` is replaced with the base currency code
+pub const DEFAULT_RATES_URL: &str = "https://open.er-api.com/v6/latest/{base}";
+
+/// How long fetched rates are used before fetching again
+const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(3600);
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Approximate units per US dollar, used when no fetched rates are available
+const FALLBACK_USD_RATES: &[(&str, f64)] = &[
+    ("USD", 1.0),
+    ("EUR", 0.92),
+    ("GBP", 0.79),
+    ("JPY", 150.0),
+    ("CNY", 7.2),
+    ("INR", 83.0),
+    ("CAD", 1.36),
+    ("AUD", 1.52),
+    ("NZD", 1.64),
+    ("CHF", 0.88),
+    ("SEK", 10.5),
+    ("NOK", 10.7),
+    ("DKK", 6.9),
+    ("PLN", 4.0),
+    ("CZK", 23.0),
+    ("MXN", 17.0),
+    ("BRL", 5.0),
+    ("ZAR", 18.5),
+    ("KRW", 1340.0),
+    ("SGD", 1.35),
+    ("HKD", 7.8),
+    ("TRY", 32.0),
+];
+
+/// Rates API settings, read from the environment
+#[derive(Debug, Clone)]
+pub struct RatesConfig {
+    /// URL template with a `{base}` placeholder (`EXCHANGE_RATES_URL`)
+    pub url: String,
+    /// How long fetched rates stay fresh (`EXCHANGE_RATES_CACHE_SECS`)
+    pub cache_ttl: Duration,
+}
+
+impl RatesConfig {
+    pub fn from_env() -> Self {
+        Self {
+            url: std::env::var("EXCHANGE_RATES_URL")
+                .unwrap_or_else(|_| DEFAULT_RATES_URL.to_string()),
+            cache_ttl: std::env::var("EXCHANGE_RATES_CACHE_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_CACHE_TTL),
+        }
+    }
+}
+
+/// The `rates` map most free rates APIs return
+#[derive(Debug, Deserialize)]
+struct RatesResponse {
+    #[serde(alias = "conversion_rates")]
+    rates: HashMap<String, f64>,
+}
+
+/// Rates fetched for one base currency
+#[derive(Debug, Clone)]
+struct CachedRates {
+    rates: HashMap<String, f64>,
+    fetched_at: DateTime<Utc>,
+}
+
+/// Where a quoted rate came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateSource {
+    /// Fetched within the cache lifetime
+    Live(DateTime<Utc>),
+    /// The API couldn't be reached; these were fetched at the given time
+    Stale(DateTime<Utc>),
+    /// The API couldn't be reached and nothing was ever fetched
+    Bundled,
+}
+
+impl RateSource {
+    /// Note shown under a conversion about the rate's age
+    pub fn note(&self) -> String {
+        match self {
+            RateSource::Live(at) => format!("Rate as of <t:{}:R>", at.timestamp()),
+            RateSource::Stale(at) => format!(
+                "⚠️ Rates service unreachable, using rates from <t:{}:R>",
+                at.timestamp()
+            ),
+            RateSource::Bundled => {
+                "⚠️ Rates service unreachable, using approximate offline rates".to_string()
+            }
+        }
+    }
+}
+
+/// Units of `to` per unit of `from`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateQuote {
+    pub rate: f64,
+    pub source: RateSource,
+}
+
+/// Cached exchange rate lookups
+pub struct ExchangeRates {
+    config: RatesConfig,
+    client: reqwest::Client,
+    cache: DashMap<String, CachedRates>,
+}
+
+impl ExchangeRates {
+    pub fn new(config: RatesConfig) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+            cache: DashMap::new(),
+        }
+    }
+
+    /// Rate from one currency code to another
+    pub async fn quote(&self, from: &str, to: &str) -> Result<RateQuote> {
+        let now = Utc::now();
+        let cached = self.cache.get(from).map(|entry| entry.clone());
+        if let Some(cached) = &cached {
+            let age = (now - cached.fetched_at).to_std().unwrap_or_default();
+            if age < self.config.cache_ttl {
+                return Self::lookup(cached, to, RateSource::Live(cached.fetched_at));
+            }
+        }
+
+        match self.fetch(from).await {
+            Ok(rates) => {
+                let fetched = CachedRates {
+                    rates,
+                    fetched_at: now,
+                };
+                self.cache.insert(from.to_string(), fetched.clone());
+                Self::lookup(&fetched, to, RateSource::Live(now))
+            }
+            Err(e) => {
+                warn!("⚠️ Exchange rates for {from} unavailable: {e}");
+                if let Some(cached) = &cached {
+                    return Self::lookup(cached, to, RateSource::Stale(cached.fetched_at));
+                }
+                fallback_rate(from, to)
+                    .map(|rate| RateQuote {
+                        rate,
+                        source: RateSource::Bundled,
+                    })
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "The exchange rates service is unreachable and I have no offline rate for {from} to {to}."
+                        )
+                    })
+            }
+        }
+    }
+
+    fn lookup(cached: &CachedRates, to: &str, source: RateSource) -> Result<RateQuote> {
+        cached
+            .rates
+            .get(to)
+            .map(|&rate| RateQuote { rate, source })
+            .ok_or_else(|| anyhow::anyhow!("I don't know the currency {to}."))
+    }
+
+    async fn fetch(&self, base: &str) -> Result<HashMap<String, f64>> {
+        let url = self.config.url.replace("{base}", base);
+        debug!("💱 Fetching exchange rates for {base}");
+        let response: RatesResponse = self
+            .client
+            .get(&url)
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(response.rates)
+    }
+}
+
+/// Approximate rate from the bundled table, crossed through US dollars
+fn fallback_rate(from: &str, to: &str) -> Option<f64> {
+    let per_usd = |code: &str| {
+        FALLBACK_USD_RATES
+            .iter()
+            .find(|(c, _)| *c == code)
+            .map(|(_, rate)| *rate)
+    };
+    Some(per_usd(to)? / per_usd(from)?)
+}
+
+/// Shared rates cache, configured from the environment on first use
+pub fn exchange_rates() -> &'static ExchangeRates {
+    static RATES: OnceLock<ExchangeRates> = OnceLock::new();
+    RATES.get_or_init(|| ExchangeRates::new(RatesConfig::from_env()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offline_rates() -> ExchangeRates {
+        ExchangeRates::new(RatesConfig {
+            // Nothing listens on the discard port, so fetches fail fast
+            url: "http://127.0.0.1:9/{base}".to_string(),
+            cache_ttl: Duration::from_secs(60),
+        })
+    }
+
+    #[test]
+    fn test_fallback_rate() {
+        assert_eq!(fallback_rate("USD", "JPY"), Some(150.0));
+        assert!((fallback_rate("EUR", "USD").unwrap() - 1.0 / 0.92).abs() < 1e-9);
+        assert_eq!(fallback_rate("USD", "XYZ"), None);
+    }
+
+    #[tokio::test]
+    async fn test_quote_from_fresh_cache() {
+        let rates = offline_rates();
+        let fetched_at = Utc::now();
+        rates.cache.insert(
+            "USD".to_string(),
+            CachedRates {
+                rates: HashMap::from([("EUR".to_string(), 0.9)]),
+                fetched_at,
+            },
+        );
+        let quote = rates.quote("USD", "EUR").await.unwrap();
+        assert_eq!(quote.rate, 0.9);
+        assert_eq!(quote.source, RateSource::Live(fetched_at));
+        assert!(rates.quote("USD", "XYZ").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_quote_offline_fallbacks() {
+        let rates = offline_rates();
+        let fetched_at = Utc::now() - chrono::Duration::hours(2);
+        rates.cache.insert(
+            "USD".to_string(),
+            CachedRates {
+                rates: HashMap::from([("EUR".to_string(), 0.95)]),
+                fetched_at,
+            },
+        );
+        let stale = rates.quote("USD", "EUR").await.unwrap();
+        assert_eq!(stale.rate, 0.95);
+        assert_eq!(stale.source, RateSource::Stale(fetched_at));
+
+        let bundled = rates.quote("GBP", "GBP").await.unwrap();
+        assert_eq!(bundled.source, RateSource::Bundled);
+        assert!(rates.quote("XYZ", "USD").await.is_err());
+    }
+}
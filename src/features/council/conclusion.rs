@@ -0,0 +1,311 @@
+//! # Council Conclusion Votes
+//!
+//! When a council is concluded, each member votes on a proposed conclusion
+//! (agree, disagree or abstain, with a one-line rationale). The tally is
+//! posted before the final synthesis, which takes the votes into account.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use serde::Deserialize;
+use serenity::builder::CreateEmbed;
+
+use crate::features::personas::PersonaManager;
+
+/// Longest rationale kept from a vote, in characters
+pub const MAX_RATIONALE_CHARS: usize = 200;
+
+/// Instructions appended to a member's persona prompt when voting
+pub const VOTE_PROMPT: &str = "The council discussion is over and a conclusion has been proposed. \
+Vote on it in character. Reply with only a JSON object: \
+{\"vote\": \"agree\" | \"disagree\" | \"abstain\", \"rationale\": \"one sentence explaining your vote\"}";
+
+/// System prompt for drafting a conclusion when the user doesn't propose one
+pub const DRAFT_PROMPT: &str =
+    "You are the neutral moderator of a council discussion between several personas. \
+Propose a single conclusion the council could adopt, in one or two sentences. \
+State the position itself; don't attribute it to anyone or describe the discussion.";
+
+/// System prompt for the final synthesis after the vote
+pub const SYNTHESIS_PROMPT: &str = "You are the neutral moderator of a council discussion between several personas. \
+Write the council's final synthesis in one or two short paragraphs: the key points of agreement, the main disagreements, \
+and how the vote on the proposed conclusion came out. Refer to members by name and don't take sides.";
+
+/// A member's vote on the proposed conclusion
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoteChoice {
+    Agree,
+    Disagree,
+    Abstain,
+}
+
+impl VoteChoice {
+    /// Parse a vote word, ignoring case
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "agree" | "yes" | "aye" => Some(Self::Agree),
+            "disagree" | "no" | "nay" => Some(Self::Disagree),
+            "abstain" => Some(Self::Abstain),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Agree => "Agree",
+            Self::Disagree => "Disagree",
+            Self::Abstain => "Abstain",
+        }
+    }
+
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            Self::Agree => "✅",
+            Self::Disagree => "❌",
+            Self::Abstain => "➖",
+        }
+    }
+}
+
+/// One council member's vote
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CouncilVote {
+    pub persona_id: String,
+    pub choice: VoteChoice,
+    pub rationale: String,
+}
+
+impl CouncilVote {
+    /// An abstention recorded when a member couldn't vote
+    pub fn no_vote(persona_id: &str) -> Self {
+        Self {
+            persona_id: persona_id.to_string(),
+            choice: VoteChoice::Abstain,
+            rationale: "No vote was cast.".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct VoteReply {
+    vote: String,
+    #[serde(default)]
+    rationale: String,
+}
+
+/// Cut a rationale to one line of MAX_RATIONALE_CHARS characters
+fn clip_rationale(text: &str) -> String {
+    let line = text.lines().next().unwrap_or_default().trim();
+    if line.chars().count() <= MAX_RATIONALE_CHARS {
+        return line.to_string();
+    }
+    let clipped: String = line.chars().take(MAX_RATIONALE_CHARS - 1).collect();
+    format!("{}…", clipped.trim_end())
+}
+
+/// Parse a member's vote reply
+///
+/// Accepts the requested JSON object, with or without a code fence, and
+/// falls back to a reply that starts with the vote word.
+pub fn parse_vote(persona_id: &str, reply: &str) -> Option<CouncilVote> {
+    let trimmed = reply.trim();
+    let json = match (trimmed.find('{'), trimmed.rfind('}')) {
+        (Some(start), Some(end)) if start < end => Some(&trimmed[start..=end]),
+        _ => None,
+    };
+    if let Some(parsed) = json.and_then(|j| serde_json::from_str::<VoteReply>(j).ok()) {
+        return Some(CouncilVote {
+            persona_id: persona_id.to_string(),
+            choice: VoteChoice::parse(&parsed.vote)?,
+            rationale: clip_rationale(&parsed.rationale),
+        });
+    }
+
+    let (word, rest) = trimmed
+        .split_once(|c: char| !c.is_alphabetic())
+        .unwrap_or((trimmed, ""));
+    Some(CouncilVote {
+        persona_id: persona_id.to_string(),
+        choice: VoteChoice::parse(word)?,
+        rationale: clip_rationale(rest.trim_start_matches(|c: char| !c.is_alphanumeric())),
+    })
+}
+
+/// Vote counts for the tally embed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VoteTally {
+    pub agree: usize,
+    pub disagree: usize,
+    pub abstain: usize,
+}
+
+impl VoteTally {
+    pub fn count(votes: &[CouncilVote]) -> Self {
+        let mut tally = Self::default();
+        for vote in votes {
+            match vote.choice {
+                VoteChoice::Agree => tally.agree += 1,
+                VoteChoice::Disagree => tally.disagree += 1,
+                VoteChoice::Abstain => tally.abstain += 1,
+            }
+        }
+        tally
+    }
+
+    /// Outcome by simple majority of the members who didn't abstain
+    pub fn outcome(&self) -> &'static str {
+        if self.agree > self.disagree {
+            "Adopted"
+        } else if self.disagree > self.agree {
+            "Rejected"
+        } else if self.agree == 0 {
+            "No votes"
+        } else {
+            "Tied"
+        }
+    }
+}
+
+/// The votes as text for the synthesis prompt
+pub fn format_votes(votes: &[CouncilVote], persona_manager: &PersonaManager) -> String {
+    votes
+        .iter()
+        .map(|vote| {
+            format!(
+                "- {}: {} ({})",
+                member_name(&vote.persona_id, persona_manager),
+                vote.choice.label(),
+                vote.rationale
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Embed showing each member's vote and the tally
+pub fn tally_embed(
+    conclusion: &str,
+    votes: &[CouncilVote],
+    persona_manager: &PersonaManager,
+) -> CreateEmbed {
+    let tally = VoteTally::count(votes);
+    let mut description = format!("**Proposed conclusion:** {conclusion}\n\n");
+    for vote in votes {
+        description.push_str(&format!(
+            "{} **{}** — {}\n",
+            vote.choice.emoji(),
+            member_name(&vote.persona_id, persona_manager),
+            vote.rationale
+        ));
+    }
+
+    let mut embed = CreateEmbed::default();
+    embed
+        .title(format!("Council Vote: {}", tally.outcome()))
+        .description(description)
+        .footer(|f| {
+            f.text(format!(
+                "Agree {} · Disagree {} · Abstain {}",
+                tally.agree, tally.disagree, tally.abstain
+            ))
+        })
+        .color(0x9B59B6);
+    embed
+}
+
+fn member_name(persona_id: &str, persona_manager: &PersonaManager) -> String {
+    persona_manager
+        .get_persona(persona_id)
+        .map(|p| p.name.clone())
+        .unwrap_or_else(|| persona_id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vote_json() {
+        let vote = parse_vote(
+            "obi",
+            "```json\n{\"vote\": \"Agree\", \"rationale\": \"Balance is wise.\"}\n```",
+        )
+        .unwrap();
+        assert_eq!(vote.choice, VoteChoice::Agree);
+        assert_eq!(vote.rationale, "Balance is wise.");
+        assert_eq!(vote.persona_id, "obi");
+    }
+
+    #[test]
+    fn test_parse_vote_plain_fallback() {
+        let vote = parse_vote("chef", "Disagree - the sauce matters more.").unwrap();
+        assert_eq!(vote.choice, VoteChoice::Disagree);
+        assert_eq!(vote.rationale, "the sauce matters more.");
+
+        assert!(parse_vote("chef", "{\"vote\": \"maybe\"}").is_none());
+        assert!(parse_vote("chef", "I refuse to participate").is_none());
+    }
+
+    #[test]
+    fn test_rationale_is_one_clipped_line() {
+        let long = "word ".repeat(100);
+        let reply = format!("{{\"vote\": \"abstain\", \"rationale\": \"{long}\"}}");
+        let vote = parse_vote("zen", &reply).unwrap();
+        assert_eq!(vote.choice, VoteChoice::Abstain);
+        assert!(vote.rationale.chars().count() <= MAX_RATIONALE_CHARS);
+        assert!(vote.rationale.ends_with('…'));
+
+        let vote = parse_vote("zen", "agree: first line\nsecond line").unwrap();
+        assert_eq!(vote.rationale, "first line");
+    }
+
+    #[test]
+    fn test_vote_tally_outcome() {
+        let vote = |choice| CouncilVote {
+            persona_id: "obi".to_string(),
+            choice,
+            rationale: String::new(),
+        };
+        let votes = [
+            vote(VoteChoice::Agree),
+            vote(VoteChoice::Agree),
+            vote(VoteChoice::Disagree),
+            vote(VoteChoice::Abstain),
+        ];
+        let tally = VoteTally::count(&votes);
+        assert_eq!(
+            tally,
+            VoteTally {
+                agree: 2,
+                disagree: 1,
+                abstain: 1
+            }
+        );
+        assert_eq!(tally.outcome(), "Adopted");
+        assert_eq!(VoteTally::count(&votes[2..]).outcome(), "Rejected");
+        assert_eq!(VoteTally::count(&votes[1..3]).outcome(), "Tied");
+        assert_eq!(VoteTally::count(&votes[3..]).outcome(), "No votes");
+    }
+
+    #[test]
+    fn test_tally_embed() {
+        let votes = vec![
+            CouncilVote {
+                persona_id: "obi".to_string(),
+                choice: VoteChoice::Agree,
+                rationale: "It brings balance.".to_string(),
+            },
+            CouncilVote::no_vote("chef"),
+        ];
+        let embed = tally_embed("Cereal is soup.", &votes, &PersonaManager::new());
+
+        assert_eq!(embed.0["title"], "Council Vote: Adopted");
+        let description = embed.0["description"].as_str().unwrap();
+        assert!(description.contains("Cereal is soup."));
+        assert!(description.contains("✅ **Obi-Wan"));
+        assert!(description.contains("No vote was cast."));
+    }
+}
@@ -0,0 +1,512 @@
+//! # Council Generation
+//!
+//! Opening statements from council members. `/council` posts them one by one
+//! in a thread; `/ask_all` runs them concurrently and replies in place.
+//! `/conclude` uses the same generator for the members' votes and the
+//! closing synthesis.
+//!
+//! - **Version**: 1.3.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.3.0: with_scene appends a channel's roleplay scene to every turn
+//! - 1.2.0: with_guardrails applies a guild's guardrail profile to every turn
+//! - 1.1.0: Conclusion drafts, member votes and the final synthesis for /conclude
+//! - 1.0.0: Extracted from the /council handler
+
+use log::{error, warn};
+use openai::chat::{ChatCompletionMessage, ChatCompletionMessageRole};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::conclusion::{
+    format_votes, parse_vote, CouncilVote, DRAFT_PROMPT, SYNTHESIS_PROMPT, VOTE_PROMPT,
+};
+use crate::core::ChatClient;
+use crate::features::analytics::{CostBucket, UsageTracker};
+use crate::features::guardrails::GuardrailProfile;
+use crate::features::personas::PersonaManager;
+use crate::features::scenes;
+
+/// Who a council turn is billed to
+#[derive(Debug, Clone)]
+pub struct TurnUsage {
+    pub user_id: String,
+    pub guild_id: Option<String>,
+    pub channel_id: String,
+    pub request_id: Uuid,
+    pub cost_bucket: CostBucket,
+}
+
+/// Generates council member responses with the shared council prompt
+#[derive(Clone)]
+pub struct CouncilGenerator {
+    chat_client: Arc<dyn ChatClient>,
+    model: String,
+    usage_tracker: UsageTracker,
+    persona_manager: PersonaManager,
+    guardrails: GuardrailProfile,
+    scene: Option<String>,
+}
+
+impl CouncilGenerator {
+    pub fn new(
+        chat_client: Arc<dyn ChatClient>,
+        model: String,
+        usage_tracker: UsageTracker,
+        persona_manager: PersonaManager,
+    ) -> Self {
+        Self {
+            chat_client,
+            model,
+            usage_tracker,
+            persona_manager,
+            guardrails: GuardrailProfile::default(),
+            scene: None,
+        }
+    }
+
+    /// Apply a guild's guardrail profile to the system prompt of every turn
+    pub fn with_guardrails(mut self, guardrails: GuardrailProfile) -> Self {
+        self.guardrails = guardrails;
+        self
+    }
+
+    /// Append a channel's roleplay scene to the system prompt of every turn
+    pub fn with_scene(mut self, scene: Option<String>) -> Self {
+        self.scene = scene;
+        self
+    }
+
+    /// System prompt for a council member's opening statement
+    pub fn opening_prompt(
+        &self,
+        persona_id: &str,
+        rules: Option<&str>,
+        prior_context: Option<&str>,
+    ) -> String {
+        let system_prompt = self.persona_manager.get_system_prompt(persona_id, None);
+        let name = self
+            .persona_manager
+            .get_persona(persona_id)
+            .map(|p| p.name.clone())
+            .unwrap_or_else(|| persona_id.to_string());
+
+        let rules_section = rules
+            .map(|r| {
+                format!(
+                    "\n\n## Ground Rules\nThe following rules and definitions apply to this discussion:\n{r}\n"
+                )
+            })
+            .unwrap_or_default();
+        let prior_section = prior_context
+            .map(|c| format!("\n\n{c}\n"))
+            .unwrap_or_default();
+
+        format!(
+            "{system_prompt}{rules_section}{prior_section}\n\nYou are participating in a council discussion with other personas. \
+            Share your unique perspective on the topic. Be concise but thoughtful. \
+            You are speaking as {name} - stay true to your character."
+        )
+    }
+
+    /// A council member's opening statement on `topic`
+    ///
+    /// Failures are logged and replaced with an in-character placeholder so
+    /// one member's error doesn't stop the others.
+    pub async fn opening_statement(
+        &self,
+        persona_id: &str,
+        topic: &str,
+        rules: Option<&str>,
+        prior_context: Option<&str>,
+        usage: &TurnUsage,
+    ) -> String {
+        let name = self
+            .persona_manager
+            .get_persona(persona_id)
+            .map(|p| p.name.clone())
+            .unwrap_or_else(|| persona_id.to_string());
+        match self
+            .complete(
+                self.opening_prompt(persona_id, rules, prior_context),
+                topic.to_string(),
+                usage,
+            )
+            .await
+        {
+            Ok(reply) => reply.unwrap_or_else(|| "I have no words at this time.".to_string()),
+            Err(e) => {
+                error!(
+                    "[{}] Council: Failed to get response from {name}: {e}",
+                    usage.request_id
+                );
+                format!("*{name} is momentarily lost in thought...*")
+            }
+        }
+    }
+
+    /// Run one system + user completion, logging its usage
+    async fn complete(
+        &self,
+        system_prompt: String,
+        user_message: String,
+        usage: &TurnUsage,
+    ) -> anyhow::Result<Option<String>> {
+        let messages = vec![
+            ChatCompletionMessage {
+                role: ChatCompletionMessageRole::System,
+                content: Some(scenes::with_scene(
+                    self.guardrails.apply(&system_prompt),
+                    self.scene.as_deref(),
+                )),
+                name: None,
+                function_call: None,
+                tool_call_id: None,
+                tool_calls: None,
+            },
+            ChatCompletionMessage {
+                role: ChatCompletionMessageRole::User,
+                content: Some(user_message),
+                name: None,
+                function_call: None,
+                tool_call_id: None,
+                tool_calls: None,
+            },
+        ];
+
+        let completion = self
+            .chat_client
+            .create_chat_completion(&self.model, messages)
+            .await?;
+        if let Some(tokens) = &completion.usage {
+            self.usage_tracker.log_chat(
+                &self.model,
+                tokens.prompt_tokens,
+                tokens.completion_tokens,
+                tokens.total_tokens,
+                &usage.user_id,
+                usage.guild_id.as_deref(),
+                Some(&usage.channel_id),
+                Some(&usage.request_id.to_string()),
+                usage.cost_bucket,
+            );
+        }
+        Ok(completion
+            .choices
+            .first()
+            .and_then(|c| c.message.content.clone()))
+    }
+
+    /// Opening statements from several personas at once, in the given order
+    pub async fn opening_statements(
+        &self,
+        persona_ids: &[String],
+        topic: &str,
+        usage: &TurnUsage,
+    ) -> Vec<(String, String)> {
+        let handles: Vec<_> = persona_ids
+            .iter()
+            .map(|persona_id| {
+                let generator = self.clone();
+                let persona_id = persona_id.clone();
+                let topic = topic.to_string();
+                let usage = usage.clone();
+                tokio::spawn(async move {
+                    let response = generator
+                        .opening_statement(&persona_id, &topic, None, None, &usage)
+                        .await;
+                    (persona_id, response)
+                })
+            })
+            .collect();
+
+        let mut responses = Vec::with_capacity(handles.len());
+        for (persona_id, handle) in persona_ids.iter().zip(handles) {
+            match handle.await {
+                Ok(response) => responses.push(response),
+                Err(e) => {
+                    error!(
+                        "[{}] Council: {persona_id} task failed: {e}",
+                        usage.request_id
+                    );
+                    responses.push((
+                        persona_id.clone(),
+                        format!("*{persona_id} is momentarily lost in thought...*"),
+                    ));
+                }
+            }
+        }
+        responses
+    }
+
+    /// A conclusion for the council to vote on, drafted from the discussion
+    pub async fn draft_conclusion(&self, discussion: &str, usage: &TurnUsage) -> Option<String> {
+        match self
+            .complete(DRAFT_PROMPT.to_string(), discussion.to_string(), usage)
+            .await
+        {
+            Ok(reply) => reply
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty()),
+            Err(e) => {
+                error!(
+                    "[{}] Council: Failed to draft a conclusion: {e}",
+                    usage.request_id
+                );
+                None
+            }
+        }
+    }
+
+    /// A member's vote on `conclusion`; errors and unreadable replies abstain
+    pub async fn cast_vote(
+        &self,
+        persona_id: &str,
+        discussion: &str,
+        conclusion: &str,
+        usage: &TurnUsage,
+    ) -> CouncilVote {
+        let system_prompt = self.persona_manager.get_system_prompt(persona_id, None);
+        let reply = self
+            .complete(
+                format!("{system_prompt}\n\n{VOTE_PROMPT}"),
+                format!("{discussion}\n\nProposed conclusion: {conclusion}"),
+                usage,
+            )
+            .await;
+        match reply {
+            Ok(Some(reply)) => parse_vote(persona_id, &reply).unwrap_or_else(|| {
+                warn!(
+                    "[{}] Council: Unreadable vote from {persona_id}: {reply}",
+                    usage.request_id
+                );
+                CouncilVote::no_vote(persona_id)
+            }),
+            Ok(None) => CouncilVote::no_vote(persona_id),
+            Err(e) => {
+                error!(
+                    "[{}] Council: Failed to get a vote from {persona_id}: {e}",
+                    usage.request_id
+                );
+                CouncilVote::no_vote(persona_id)
+            }
+        }
+    }
+
+    /// Every member's vote at once, in the given order
+    pub async fn cast_votes(
+        &self,
+        persona_ids: &[String],
+        discussion: &str,
+        conclusion: &str,
+        usage: &TurnUsage,
+    ) -> Vec<CouncilVote> {
+        let handles: Vec<_> = persona_ids
+            .iter()
+            .map(|persona_id| {
+                let generator = self.clone();
+                let persona_id = persona_id.clone();
+                let discussion = discussion.to_string();
+                let conclusion = conclusion.to_string();
+                let usage = usage.clone();
+                tokio::spawn(async move {
+                    generator
+                        .cast_vote(&persona_id, &discussion, &conclusion, &usage)
+                        .await
+                })
+            })
+            .collect();
+
+        let mut votes = Vec::with_capacity(handles.len());
+        for (persona_id, handle) in persona_ids.iter().zip(handles) {
+            votes.push(handle.await.unwrap_or_else(|e| {
+                error!(
+                    "[{}] Council: {persona_id} vote task failed: {e}",
+                    usage.request_id
+                );
+                CouncilVote::no_vote(persona_id)
+            }));
+        }
+        votes
+    }
+
+    /// The moderator's closing synthesis of the discussion and the vote
+    pub async fn final_synthesis(
+        &self,
+        discussion: &str,
+        conclusion: &str,
+        votes: &[CouncilVote],
+        usage: &TurnUsage,
+    ) -> Option<String> {
+        let user_message = format!(
+            "{discussion}\n\nProposed conclusion: {conclusion}\n\nVotes:\n{}",
+            format_votes(votes, &self.persona_manager)
+        );
+        match self
+            .complete(SYNTHESIS_PROMPT.to_string(), user_message, usage)
+            .await
+        {
+            Ok(reply) => reply,
+            Err(e) => {
+                error!(
+                    "[{}] Council: Failed to write the synthesis: {e}",
+                    usage.request_id
+                );
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use crate::features::council::conclusion::VoteChoice;
+    use crate::testing::MockChatClient;
+
+    async fn generator(client: Arc<MockChatClient>) -> CouncilGenerator {
+        let database = Database::new(":memory:").await.unwrap();
+        CouncilGenerator::new(
+            client,
+            "gpt-test".to_string(),
+            UsageTracker::new(database),
+            PersonaManager::new(),
+        )
+    }
+
+    fn usage() -> TurnUsage {
+        TurnUsage {
+            user_id: "u1".to_string(),
+            guild_id: None,
+            channel_id: "c1".to_string(),
+            request_id: Uuid::new_v4(),
+            cost_bucket: CostBucket::Ask,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_opening_statements_keep_persona_order() {
+        let client = Arc::new(MockChatClient::with_default_reply("My view."));
+        let generator = generator(client.clone()).await;
+        let persona_ids = vec!["chef".to_string(), "obi".to_string(), "zen".to_string()];
+
+        let responses = generator
+            .opening_statements(&persona_ids, "Is cereal soup?", &usage())
+            .await;
+
+        let order: Vec<&str> = responses.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(order, vec!["chef", "obi", "zen"]);
+        assert!(responses.iter().all(|(_, text)| text == "My view."));
+
+        let requests = client.requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests
+            .iter()
+            .all(|r| r.last_user_message() == Some("Is cereal soup?")));
+        assert!(requests
+            .iter()
+            .all(|r| r.system_prompt().unwrap().contains("council discussion")));
+    }
+
+    #[tokio::test]
+    async fn test_guardrails_apply_to_every_turn() {
+        let client = Arc::new(MockChatClient::with_default_reply("My view."));
+        let generator = generator(client.clone())
+            .await
+            .with_guardrails(GuardrailProfile::Strict);
+
+        generator
+            .opening_statements(&["chef".to_string()], "Is cereal soup?", &usage())
+            .await;
+        generator.draft_conclusion("Chef: Yes.", &usage()).await;
+
+        let requests = client.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests.iter().all(|r| r
+            .system_prompt()
+            .unwrap()
+            .starts_with("## Safety Profile: Strict")));
+    }
+
+    #[tokio::test]
+    async fn test_cast_vote_parses_reply() {
+        let client = Arc::new(MockChatClient::new());
+        client.push_reply(r#"{"vote": "disagree", "rationale": "Soup needs heat."}"#);
+        let generator = generator(client.clone()).await;
+
+        let vote = generator
+            .cast_vote(
+                "chef",
+                "Original topic: cereal",
+                "Cereal is soup.",
+                &usage(),
+            )
+            .await;
+        assert_eq!(vote.choice, VoteChoice::Disagree);
+        assert_eq!(vote.rationale, "Soup needs heat.");
+
+        let request = &client.requests()[0];
+        assert!(request.system_prompt().unwrap().contains("\"abstain\""));
+        assert!(request
+            .last_user_message()
+            .unwrap()
+            .ends_with("Proposed conclusion: Cereal is soup."));
+    }
+
+    #[tokio::test]
+    async fn test_failed_or_unreadable_votes_abstain() {
+        let client = Arc::new(MockChatClient::with_default_reply("Hmm, let me think."));
+        client.push_error("boom");
+        let generator = generator(client).await;
+        let persona_ids = vec!["obi".to_string(), "zen".to_string()];
+
+        let votes = generator
+            .cast_votes(
+                &persona_ids,
+                "Original topic: tea",
+                "Tea is best.",
+                &usage(),
+            )
+            .await;
+        assert_eq!(votes.len(), 2);
+        assert_eq!(votes[0].persona_id, "obi");
+        assert!(votes
+            .iter()
+            .all(|v| *v == CouncilVote::no_vote(&v.persona_id)));
+    }
+
+    #[tokio::test]
+    async fn test_final_synthesis_sees_votes() {
+        let client = Arc::new(MockChatClient::with_default_reply("The council agreed."));
+        let generator = generator(client.clone()).await;
+        let votes = vec![CouncilVote {
+            persona_id: "obi".to_string(),
+            choice: VoteChoice::Agree,
+            rationale: "Balance.".to_string(),
+        }];
+
+        let synthesis = generator
+            .final_synthesis("Original topic: tea", "Tea is best.", &votes, &usage())
+            .await;
+        assert_eq!(synthesis.as_deref(), Some("The council agreed."));
+        let request = &client.requests()[0];
+        assert!(request
+            .last_user_message()
+            .unwrap()
+            .contains("- Obi-Wan: Agree (Balance.)"));
+    }
+
+    #[tokio::test]
+    async fn test_failed_statement_becomes_placeholder() {
+        let client = Arc::new(MockChatClient::new());
+        client.push_error("boom");
+        let generator = generator(client).await;
+
+        let response = generator
+            .opening_statement("obi", "Hello there", Some("No shouting"), None, &usage())
+            .await;
+        assert!(response.contains("momentarily lost in thought"));
+    }
+}
@@ -2,18 +2,28 @@
 //!
 //! Multi-persona discussion threads with follow-up question support.
 //!
-//! - **Version**: 2.0.0
+//! - **Version**: 2.3.0
 //! - **Since**: 3.31.0
 //!
 //! ## Changelog
+//! - 2.3.0: Members vote on a proposed conclusion at /conclude
+//! - 2.2.0: CouncilGenerator shares opening statements with /ask_all
+//! - 2.1.0: Serializable state for restart persistence
 //! - 2.0.0: Interoperability with debate, rules parameter, interactive buttons
 //! - 1.0.0: Initial implementation with state tracking
 
+pub mod conclusion;
+pub mod generation;
+
+pub use conclusion::{tally_embed, CouncilVote, VoteChoice, VoteTally};
+pub use generation::{CouncilGenerator, TurnUsage};
+
 use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
 use std::sync::OnceLock;
 
 /// Active council state for follow-up questions
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CouncilState {
     /// The original prompt/topic
     pub topic: String,
@@ -34,7 +44,7 @@ pub struct CouncilState {
 }
 
 /// A message in the council conversation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CouncilMessage {
     /// "user" or "assistant"
     pub role: String,
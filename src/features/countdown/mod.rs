@@ -0,0 +1,183 @@
+//! # Feature: Countdowns
+//!
+//! `/countdown start` posts an embed counting down to an event. A background
+//! task edits every running countdown once a minute; at zero the embed shows
+//! the event has arrived and an optional role is pinged. Countdowns live in
+//! the database, so they pick up where they left off after a restart, and a
+//! guild can run several at once.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with live embeds, role pings and restart persistence
+
+pub mod scheduler;
+
+pub use scheduler::CountdownScheduler;
+
+use serenity::builder::CreateEmbed;
+
+use crate::database::Countdown;
+
+/// Feature id for toggles
+pub const COUNTDOWN_FEATURE: &str = "countdown";
+
+/// Countdowns a guild may run at once
+pub const MAX_COUNTDOWNS_PER_GUILD: i64 = 10;
+
+/// Longest countdown title
+pub const MAX_TITLE_CHARS: usize = 100;
+
+/// Furthest ahead a countdown can end
+pub const MAX_DURATION_SECS: i64 = 365 * 24 * 60 * 60;
+
+const RUNNING_COLOR: u32 = 0x5865F2;
+const FINISHED_COLOR: u32 = 0x2ECC71;
+
+/// Trim a countdown title, rejecting empty or oversized ones
+pub fn validate_title(input: &str) -> Result<String, &'static str> {
+    let title = input.split_whitespace().collect::<Vec<_>>().join(" ");
+    if title.is_empty() {
+        return Err("The title can't be empty.");
+    }
+    if title.chars().count() > MAX_TITLE_CHARS {
+        return Err("Titles must be at most 100 characters.");
+    }
+    Ok(title)
+}
+
+/// Time left as days, hours and minutes, rounded up to the minute
+pub fn format_remaining(seconds: i64) -> String {
+    if seconds <= 0 {
+        return "now".to_string();
+    }
+    let minutes = (seconds + 59) / 60;
+    let (days, hours, minutes) = (minutes / 1440, minutes % 1440 / 60, minutes % 60);
+    let mut parts = Vec::new();
+    if days > 0 {
+        parts.push(format!("{days}d"));
+    }
+    if days > 0 || hours > 0 {
+        parts.push(format!("{hours}h"));
+    }
+    parts.push(format!("{minutes}m"));
+    parts.join(" ")
+}
+
+/// The embed for a countdown `now`, running or finished
+pub fn countdown_embed(countdown: &Countdown, now: i64) -> CreateEmbed {
+    let remaining = countdown.ends_at - now;
+    let mut embed = CreateEmbed::default();
+    if remaining > 0 {
+        embed
+            .title(format!("⏳ {}", countdown.title))
+            .description(format!(
+                "**{}** to go\nEnds <t:{}:F>",
+                format_remaining(remaining),
+                countdown.ends_at
+            ))
+            .color(RUNNING_COLOR)
+            .footer(|f| {
+                f.text(format!(
+                    "Countdown #{} · updates every minute",
+                    countdown.id
+                ))
+            });
+    } else {
+        embed
+            .title(format!("🎉 {}", countdown.title))
+            .description(format!("It's here! Ended <t:{}:F>", countdown.ends_at))
+            .color(FINISHED_COLOR)
+            .footer(|f| f.text(format!("Countdown #{}", countdown.id)));
+    }
+    embed
+}
+
+/// The message sent when a countdown reaches zero
+pub fn finished_message(countdown: &Countdown) -> String {
+    match &countdown.role_id {
+        Some(role_id) => format!("🔔 <@&{role_id}> **{}** is here!", countdown.title),
+        None => format!("🔔 **{}** is here!", countdown.title),
+    }
+}
+
+/// One countdown for `/countdown list`
+pub fn list_line(countdown: &Countdown) -> String {
+    let role = countdown
+        .role_id
+        .as_deref()
+        .map(|r| format!(" · pings <@&{r}>"))
+        .unwrap_or_default();
+    format!(
+        "**#{}** {} <t:{}:R> in <#{}>{role}",
+        countdown.id, countdown.title, countdown.ends_at, countdown.channel_id
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn countdown(role_id: Option<&str>) -> Countdown {
+        Countdown {
+            id: 4,
+            guild_id: "1".to_string(),
+            channel_id: "2".to_string(),
+            message_id: Some("3".to_string()),
+            title: "Launch".to_string(),
+            ends_at: 10_000,
+            role_id: role_id.map(str::to_string),
+            created_by: "5".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_validate_title() {
+        assert_eq!(
+            validate_title("  Game   night "),
+            Ok("Game night".to_string())
+        );
+        assert!(validate_title("  ").is_err());
+        assert!(validate_title(&"x".repeat(MAX_TITLE_CHARS + 1)).is_err());
+    }
+
+    #[test]
+    fn test_format_remaining() {
+        assert_eq!(format_remaining(0), "now");
+        assert_eq!(format_remaining(1), "1m");
+        assert_eq!(format_remaining(60), "1m");
+        assert_eq!(format_remaining(61), "2m");
+        assert_eq!(format_remaining(3600), "1h 0m");
+        assert_eq!(format_remaining(90_000), "1d 1h 0m");
+        assert_eq!(format_remaining(86_400 + 120), "1d 0h 2m");
+    }
+
+    #[test]
+    fn test_countdown_embed() {
+        let running = countdown_embed(&countdown(None), 10_000 - 3600);
+        assert_eq!(running.0["title"], "⏳ Launch");
+        assert_eq!(
+            running.0["description"],
+            "**1h 0m** to go\nEnds <t:10000:F>"
+        );
+
+        let finished = countdown_embed(&countdown(None), 10_000);
+        assert_eq!(finished.0["title"], "🎉 Launch");
+        assert_eq!(finished.0["color"], FINISHED_COLOR);
+    }
+
+    #[test]
+    fn test_finished_message_and_list_line() {
+        assert_eq!(
+            finished_message(&countdown(Some("9"))),
+            "🔔 <@&9> **Launch** is here!"
+        );
+        assert_eq!(finished_message(&countdown(None)), "🔔 **Launch** is here!");
+        assert_eq!(
+            list_line(&countdown(Some("9"))),
+            "**#4** Launch <t:10000:R> in <#2> · pings <@&9>"
+        );
+    }
+}
@@ -0,0 +1,139 @@
+//! Background task updating `/countdown` embeds every minute
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use chrono::Utc;
+use log::{error, info, warn};
+use serenity::http::{Http, StatusCode};
+use serenity::model::id::{ChannelId, MessageId, RoleId};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::interval;
+
+use super::{countdown_embed, finished_message, COUNTDOWN_FEATURE};
+use crate::database::{Countdown, Database};
+
+pub struct CountdownScheduler {
+    database: Database,
+}
+
+impl CountdownScheduler {
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+
+    /// Start the countdown update loop
+    /// This should be spawned as a tokio task
+    pub async fn run(&self, http: Arc<Http>) {
+        let mut check_interval = interval(Duration::from_secs(60));
+
+        info!("⏳ Countdown scheduler started");
+
+        loop {
+            check_interval.tick().await;
+
+            if let Err(e) = self.update_countdowns(&http).await {
+                error!("❌ Error updating countdowns: {e}");
+            }
+        }
+    }
+
+    async fn update_countdowns(&self, http: &Arc<Http>) -> Result<()> {
+        let now = Utc::now().timestamp();
+        for countdown in self.database.get_active_countdowns().await? {
+            // Paused while disabled; picks up again when turned back on
+            if !self
+                .database
+                .is_feature_enabled(COUNTDOWN_FEATURE, None, Some(&countdown.guild_id))
+                .await?
+            {
+                continue;
+            }
+            let finished = countdown.ends_at <= now;
+            match self.edit(http, &countdown, now).await {
+                Ok(()) => {}
+                Err(e) if is_not_found(&e) => {
+                    info!(
+                        "⏳ Countdown #{} message is gone, stopping it",
+                        countdown.id
+                    );
+                    self.database.delete_countdown(countdown.id).await?;
+                    continue;
+                }
+                Err(e) => warn!("⚠️ Failed to update countdown #{}: {e}", countdown.id),
+            }
+            if finished {
+                self.database.delete_countdown(countdown.id).await?;
+                if let Err(e) = self.announce(http, &countdown).await {
+                    warn!("⚠️ Failed to announce countdown #{}: {e}", countdown.id);
+                }
+                info!(
+                    "🎉 Countdown #{} finished in guild {}",
+                    countdown.id, countdown.guild_id
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn edit(
+        &self,
+        http: &Arc<Http>,
+        countdown: &Countdown,
+        now: i64,
+    ) -> serenity::Result<()> {
+        let (Some(channel_id), Some(message_id)) = (
+            countdown.channel_id.parse::<u64>().ok(),
+            countdown
+                .message_id
+                .as_deref()
+                .and_then(|m| m.parse::<u64>().ok()),
+        ) else {
+            return Ok(());
+        };
+        let embed = countdown_embed(countdown, now);
+        ChannelId(channel_id)
+            .edit_message(http, MessageId(message_id), |m| m.set_embed(embed))
+            .await?;
+        Ok(())
+    }
+
+    /// Reply to the countdown at zero, pinging its role
+    async fn announce(&self, http: &Arc<Http>, countdown: &Countdown) -> Result<()> {
+        let channel_id = ChannelId(countdown.channel_id.parse::<u64>()?);
+        let role = countdown
+            .role_id
+            .as_deref()
+            .and_then(|r| r.parse::<u64>().ok())
+            .map(RoleId);
+        let reference = countdown
+            .message_id
+            .as_deref()
+            .and_then(|m| m.parse::<u64>().ok())
+            .map(|m| (channel_id, MessageId(m)));
+        channel_id
+            .send_message(http, |m| {
+                m.content(finished_message(countdown))
+                    .allowed_mentions(|a| a.roles(role));
+                if let Some(reference) = reference {
+                    m.reference_message(reference);
+                }
+                m
+            })
+            .await?;
+        Ok(())
+    }
+}
+
+/// Whether Discord reported the channel or message as deleted
+fn is_not_found(error: &serenity::Error) -> bool {
+    match error {
+        serenity::Error::Http(http) => http.status_code() == Some(StatusCode::NOT_FOUND),
+        _ => false,
+    }
+}
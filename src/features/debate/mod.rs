@@ -2,14 +2,73 @@
 //!
 //! Orchestrates threaded debates between two personas on a given topic.
 //!
-//! - **Version**: 1.1.0
+//! - **Version**: 1.4.0
 //! - **Since**: 3.27.0
 //! - **Toggleable**: true
 //!
 //! ## Changelog
+//! - 1.4.0: Re-export rebuttal_message for /ask's devil's advocate
+//! - 1.3.0: record_result stores concluded debates for the audience vote and /profile
+//! - 1.2.0: notify_concluded for debate_concluded webhooks
 //! - 1.1.0: Added continue debate button and state management
 //! - 1.0.0: Initial implementation with threaded debates
 
 pub mod orchestrator;
 
-pub use orchestrator::{get_active_debates, DebateOrchestrator, DebateState, CONTINUE_ROUNDS};
+pub use orchestrator::{
+    get_active_debates, rebuttal_message, DebateOrchestrator, DebateState, CONTINUE_ROUNDS,
+};
+
+use log::warn;
+
+use crate::database::{Database, DebateResult};
+use crate::features::personas::PersonaManager;
+use crate::features::webhooks::{self, WebhookEvent};
+
+/// Tell the guild's `debate_concluded` webhooks a debate ended
+pub fn notify_concluded(
+    database: &Database,
+    persona_manager: &PersonaManager,
+    guild_id: &str,
+    thread_id: u64,
+    state: &DebateState,
+) {
+    let name = |id: &String| {
+        persona_manager
+            .get_persona(id)
+            .map(|p| p.name.clone())
+            .unwrap_or_else(|| id.clone())
+    };
+    webhooks::notify(
+        database,
+        guild_id,
+        WebhookEvent::DebateConcluded,
+        vec![
+            ("topic", state.config.topic.clone()),
+            ("persona1", name(&state.config.persona1_id)),
+            ("persona2", name(&state.config.persona2_id)),
+            ("rounds", state.total_rounds_completed.to_string()),
+            ("thread_id", thread_id.to_string()),
+        ],
+    );
+}
+
+/// Store a concluded debate so its vote buttons and /profile stats can find it
+pub async fn record_result(
+    database: &Database,
+    guild_id: Option<&str>,
+    thread_id: u64,
+    state: &DebateState,
+) {
+    let result = DebateResult {
+        thread_id: thread_id.to_string(),
+        guild_id: guild_id.map(str::to_string),
+        initiator_id: state.config.initiator_id.clone(),
+        persona1_id: state.config.persona1_id.clone(),
+        persona2_id: state.config.persona2_id.clone(),
+        rounds: state.total_rounds_completed,
+    };
+    if let Err(e) = database.record_debate_result(&result).await {
+        warn!("Failed to record debate result for thread {thread_id}: {e}");
+    }
+}
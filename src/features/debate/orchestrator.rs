@@ -2,10 +2,15 @@
 //!
 //! Manages the flow of a debate between two personas in a Discord thread.
 //!
-//! - **Version**: 2.0.0
+//! - **Version**: 2.5.0
 //! - **Since**: 3.27.0
 //!
 //! ## Changelog
+//! - 2.5.0: rebuttal_prompt and rebuttal_message for /ask's devil's advocate
+//! - 2.4.0: Opening statements carry the config's prior context, for imported transcripts
+//! - 2.3.0: end_debate returns the ended state
+//! - 2.2.0: Long responses continue in extra embeds instead of being cut at a byte offset
+//! - 2.1.0: Serializable state for restart persistence, namespaced button IDs
 //! - 2.0.0: Interoperability with council, rules parameter, opening-only default, interactive buttons
 //! - 1.2.0: Tag-team debates and thread history
 //! - 1.1.0: Continue and end buttons
@@ -14,6 +19,7 @@
 use anyhow::Result;
 use dashmap::DashMap;
 use log::{debug, error, info};
+use serde::{Deserialize, Serialize};
 use serenity::builder::CreateEmbed;
 use serenity::model::application::component::ButtonStyle;
 use serenity::model::id::ChannelId;
@@ -21,10 +27,12 @@ use serenity::prelude::Context;
 use std::sync::OnceLock;
 use tokio::time::{sleep, Duration};
 
+use crate::core::{chunk_for_embed, continuation_embed, persona_embed};
+use crate::features::discussion::debate_button_id;
 use crate::features::personas::{Persona, PersonaManager};
 
 /// Active debate state for continuation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DebateState {
     pub config: DebateConfig,
     pub history: Vec<(String, String)>,
@@ -48,7 +56,7 @@ pub fn get_active_debates() -> &'static DashMap<u64, DebateState> {
 pub const CONTINUE_ROUNDS: i64 = 4;
 
 /// Configuration for a debate session
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DebateConfig {
     /// First debater persona ID
     pub persona1_id: String,
@@ -70,6 +78,9 @@ pub struct DebateConfig {
     pub rules: Option<String>,
     /// Whether to only do opening statements (default true)
     pub opening_only: bool,
+    /// Summary of a discussion held elsewhere, from /debate import
+    #[serde(default)]
+    pub prior_context: Option<String>,
 }
 
 /// Orchestrates a debate between two personas
@@ -95,6 +106,19 @@ impl DebateOrchestrator {
         self.build_debate_prompt_full(persona, opponent_name, topic, is_opening, None, None, None)
     }
 
+    /// System prompt for `persona_id` answering an opponent in a debate on `topic`
+    ///
+    /// The debate's rebuttal turn, also used by /ask's devil's advocate.
+    pub fn rebuttal_prompt(
+        &self,
+        persona_id: &str,
+        opponent_name: &str,
+        topic: &str,
+    ) -> Option<String> {
+        let persona = self.persona_manager.get_persona(persona_id)?;
+        Some(self.build_debate_prompt(persona, opponent_name, topic, false))
+    }
+
     /// Build the system prompt with optional context about previous debaters
     fn build_debate_prompt_with_context(
         &self,
@@ -218,38 +242,51 @@ Guidelines:
         )
     }
 
-    /// Build an embed for a debate response
-    fn build_debate_embed(
+    /// Build the embeds for a debate response (long responses continue in extra embeds)
+    fn build_debate_embeds(
         &self,
         persona: &Persona,
         persona_id: &str,
         response: &str,
         round: i64,
         total_rounds: i64,
-    ) -> CreateEmbed {
-        let mut embed = CreateEmbed::default();
-
-        embed.author(|a| {
-            a.name(&persona.name);
-            if let Some(portrait_url) = self.persona_manager.get_portrait_url(persona_id) {
-                a.icon_url(portrait_url);
-            }
-            a
-        });
-
-        embed.color(persona.color);
-
-        // Truncate if needed (Discord embed description limit is 4096)
-        let text = if response.len() > 4000 {
-            format!("{}...", &response[..3997])
-        } else {
-            response.to_string()
-        };
-        embed.description(text);
-
-        embed.footer(|f| f.text(format!("Response {round}/{total_rounds}")));
+    ) -> Vec<CreateEmbed> {
+        let chunks = chunk_for_embed(response);
+        let last = chunks.len() - 1;
+
+        chunks
+            .iter()
+            .enumerate()
+            .map(|(i, chunk)| {
+                let mut embed = if i == 0 {
+                    let mut embed = persona_embed(persona, chunk);
+                    if let Some(portrait_url) = self.persona_manager.get_portrait_url(persona_id) {
+                        embed.author(|a| a.name(&persona.name).icon_url(portrait_url));
+                    }
+                    embed
+                } else {
+                    continuation_embed(persona, chunk)
+                };
+                if i == last {
+                    embed.footer(|f| f.text(format!("Response {round}/{total_rounds}")));
+                }
+                embed
+            })
+            .collect()
+    }
 
-        embed
+    /// Send a response's embeds in order, stopping at the first failure
+    async fn send_embeds(
+        ctx: &Context,
+        thread_id: ChannelId,
+        embeds: Vec<CreateEmbed>,
+    ) -> serenity::Result<()> {
+        for embed in embeds {
+            thread_id
+                .send_message(&ctx.http, |m| m.set_embed(embed))
+                .await?;
+        }
+        Ok(())
     }
 
     /// Run a complete debate in a thread
@@ -306,17 +343,29 @@ Guidelines:
             );
 
             // Build the prompt for this turn (with context about previous debaters if tag-team)
-            let system_prompt = self.build_debate_prompt_with_context(
-                current_persona,
-                &opponent_persona.name,
-                &config.topic,
-                is_opening,
-                if is_opening && is_tag_team {
-                    config.previous_debaters.as_ref()
-                } else {
-                    None
-                },
-            );
+            let system_prompt = if is_opening && config.prior_context.is_some() {
+                self.build_debate_prompt_full(
+                    current_persona,
+                    &opponent_persona.name,
+                    &config.topic,
+                    is_opening,
+                    config.previous_debaters.as_ref(),
+                    None,
+                    config.prior_context.as_deref(),
+                )
+            } else {
+                self.build_debate_prompt_with_context(
+                    current_persona,
+                    &opponent_persona.name,
+                    &config.topic,
+                    is_opening,
+                    if is_opening && is_tag_team {
+                        config.previous_debaters.as_ref()
+                    } else {
+                        None
+                    },
+                )
+            };
 
             // Build the user message (context for the AI)
             let user_message = if is_opening && is_tag_team {
@@ -360,8 +409,8 @@ Guidelines:
             // Add to history for context
             history.push(("assistant".to_string(), response.clone()));
 
-            // Build and send the embed
-            let embed = self.build_debate_embed(
+            // Build and send the embeds
+            let embeds = self.build_debate_embeds(
                 current_persona,
                 current_persona_id,
                 &response,
@@ -369,10 +418,7 @@ Guidelines:
                 config.rounds,
             );
 
-            if let Err(e) = thread_id
-                .send_message(&ctx.http, |m| m.set_embed(embed))
-                .await
-            {
+            if let Err(e) = Self::send_embeds(ctx, thread_id, embeds).await {
                 error!("Failed to send debate message: {e}");
                 break;
             }
@@ -402,13 +448,13 @@ Guidelines:
                 m.set_embed(closing_embed).components(|c| {
                     c.create_action_row(|row| {
                         row.create_button(|btn| {
-                            btn.custom_id(format!("debate_continue_{}", thread_id.0))
+                            btn.custom_id(debate_button_id("continue", thread_id.0))
                                 .label(format!("Continue (+{CONTINUE_ROUNDS} rounds)"))
                                 .style(ButtonStyle::Primary)
                                 .emoji('🎭')
                         })
                         .create_button(|btn| {
-                            btn.custom_id(format!("debate_end_{}", thread_id.0))
+                            btn.custom_id(debate_button_id("end", thread_id.0))
                                 .label("End Debate")
                                 .style(ButtonStyle::Secondary)
                         })
@@ -524,7 +570,7 @@ Guidelines:
 
             history.push(("assistant".to_string(), response.clone()));
 
-            let embed = self.build_debate_embed(
+            let embeds = self.build_debate_embeds(
                 current_persona,
                 current_persona_id,
                 &response,
@@ -532,10 +578,7 @@ Guidelines:
                 end_round,
             );
 
-            if let Err(e) = thread_id
-                .send_message(&ctx.http, |m| m.set_embed(embed))
-                .await
-            {
+            if let Err(e) = Self::send_embeds(ctx, thread_id, embeds).await {
                 error!("Failed to send debate message: {e}");
                 break;
             }
@@ -564,13 +607,13 @@ Guidelines:
                 m.set_embed(closing_embed).components(|c| {
                     c.create_action_row(|row| {
                         row.create_button(|btn| {
-                            btn.custom_id(format!("debate_continue_{}", thread_id.0))
+                            btn.custom_id(debate_button_id("continue", thread_id.0))
                                 .label(format!("Continue (+{CONTINUE_ROUNDS} rounds)"))
                                 .style(ButtonStyle::Primary)
                                 .emoji('🎭')
                         })
                         .create_button(|btn| {
-                            btn.custom_id(format!("debate_end_{}", thread_id.0))
+                            btn.custom_id(debate_button_id("end", thread_id.0))
                                 .label("End Debate")
                                 .style(ButtonStyle::Secondary)
                         })
@@ -667,27 +710,25 @@ Guidelines:
         };
         get_active_debates().insert(thread_id.0, updated_state);
 
-        // Build and send the embed
-        let embed = self.build_debate_embed(
+        // Build and send the embeds
+        let embeds = self.build_debate_embeds(
             &persona,
             persona_id,
             &response,
             state.total_rounds_completed + 1,
             state.total_rounds_completed + 1,
         );
-
-        thread_id
-            .send_message(&ctx.http, |m| m.set_embed(embed))
-            .await?;
+        Self::send_embeds(ctx, thread_id, embeds).await?;
 
         info!("Single response from {} sent", persona.name);
         Ok(())
     }
 
-    /// End a debate and clean up state
-    pub fn end_debate(thread_id: u64) {
-        get_active_debates().remove(&thread_id);
+    /// End a debate and clean up state, returning it if it was still active
+    pub fn end_debate(thread_id: u64) -> Option<DebateState> {
+        let state = get_active_debates().remove(&thread_id).map(|(_, s)| s);
         info!("Debate ended and state cleaned up for thread {thread_id}");
+        state
     }
 
     /// Build the closing embed for the debate
@@ -712,6 +753,14 @@ Guidelines:
     }
 }
 
+/// User message asking for a devil's advocate rebuttal of `answer`
+pub fn rebuttal_message(opponent_name: &str, question: &str, answer: &str) -> String {
+    format!(
+        "Asked \"{question}\", {opponent_name} answered:\n\n{answer}\n\n\
+        Play devil's advocate: make the strongest case against that answer, even if you'd normally agree with it."
+    )
+}
+
 impl Default for DebateOrchestrator {
     fn default() -> Self {
         Self::new()
@@ -735,6 +784,7 @@ mod tests {
             previous_debaters: None,
             rules: None,
             opening_only: false,
+            prior_context: None,
         };
 
         assert_eq!(config.persona1_id, "obi");
@@ -756,6 +806,7 @@ mod tests {
             previous_debaters: None,
             rules: Some("Be respectful of coding styles".to_string()),
             opening_only: true,
+            prior_context: None,
         };
 
         assert!(config.opening_only);
@@ -782,6 +833,7 @@ mod tests {
             previous_debaters: Some(("Sage".to_string(), "Cynic".to_string())),
             rules: None,
             opening_only: false,
+            prior_context: None,
         };
 
         assert!(config.initial_history.is_some());
@@ -813,6 +865,25 @@ mod tests {
         assert!(prompt.contains("pineapple on pizza"));
     }
 
+    #[test]
+    fn test_rebuttal_prompt() {
+        let orchestrator = DebateOrchestrator::new();
+
+        let prompt = orchestrator
+            .rebuttal_prompt("muppet", "Obi-Wan", "Is cereal soup?")
+            .unwrap();
+        assert!(prompt.contains("Respond to your opponent"));
+        assert!(prompt.contains("Is cereal soup?"));
+        assert!(orchestrator
+            .rebuttal_prompt("nobody", "Obi-Wan", "Is cereal soup?")
+            .is_none());
+
+        let message = rebuttal_message("Obi-Wan", "Is cereal soup?", "It is not.");
+        assert!(message.contains("Obi-Wan answered"));
+        assert!(message.contains("It is not."));
+        assert!(message.contains("devil's advocate"));
+    }
+
     #[test]
     fn test_build_debate_prompt_response() {
         let orchestrator = DebateOrchestrator::new();
@@ -0,0 +1,534 @@
+//! # Feature: Dice & RPG Toolkit
+//!
+//! `/roll` rolls dice notation like `3d6+2`, `4d6kh3` or `d20 adv`, with an
+//! optional persona narration of the outcome. `/initiative` keeps a turn
+//! order per thread, stored so an ongoing session survives restarts and
+//! picks up where the table left off.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with dice notation, advantage, narration and per-thread initiative
+
+use crate::database::InitiativeEntry;
+
+/// Feature id for toggles
+pub const DICE_FEATURE: &str = "dice";
+
+/// Dice rolled by one expression, so `1000d1000` can't flood a reply
+pub const MAX_DICE: u32 = 100;
+
+/// Largest die
+pub const MAX_SIDES: u32 = 1000;
+
+/// Terms in one expression
+const MAX_TERMS: usize = 20;
+
+/// Largest flat modifier
+const MAX_CONSTANT: i64 = 10_000;
+
+/// Combatants in one thread's initiative order
+pub const MAX_INITIATIVE_ENTRIES: usize = 25;
+
+/// Longest combatant name
+pub const MAX_COMBATANT_CHARS: usize = 40;
+
+/// Roll a single die twice and keep the better or worse result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Advantage {
+    None,
+    Advantage,
+    Disadvantage,
+}
+
+impl Advantage {
+    pub fn from_option(value: Option<&str>) -> Self {
+        match value {
+            Some("advantage") => Advantage::Advantage,
+            Some("disadvantage") => Advantage::Disadvantage,
+            _ => Advantage::None,
+        }
+    }
+}
+
+/// Which dice of a group count towards the total
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Keep {
+    Highest(u32),
+    Lowest(u32),
+}
+
+/// One `+` or `-` separated part of an expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Term {
+    Dice {
+        count: u32,
+        sides: u32,
+        keep: Option<Keep>,
+    },
+    Constant(i64),
+}
+
+/// A parsed dice expression; each term carries its sign
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiceExpr {
+    pub terms: Vec<(i64, Term)>,
+}
+
+/// The dice rolled for one term, with whether each counted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RolledTerm {
+    pub sign: i64,
+    pub term: Term,
+    pub rolls: Vec<(u32, bool)>,
+    pub value: i64,
+}
+
+/// An expression's rolls and total
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiceRoll {
+    pub terms: Vec<RolledTerm>,
+    pub total: i64,
+}
+
+impl DiceExpr {
+    /// Parse notation like `3d6+2`, `d%`, `4d6kh3`, `2d20kl1` or `d20 adv`
+    pub fn parse(input: &str, advantage: Advantage) -> Result<Self, String> {
+        let mut input = input.trim().to_lowercase();
+        let mut advantage = advantage;
+        for (suffix, mode) in [
+            ("advantage", Advantage::Advantage),
+            ("adv", Advantage::Advantage),
+            ("disadvantage", Advantage::Disadvantage),
+            ("dis", Advantage::Disadvantage),
+        ] {
+            if let Some(rest) = input.strip_suffix(suffix) {
+                if rest.ends_with(char::is_whitespace) {
+                    input = rest.to_string();
+                    if advantage == Advantage::None {
+                        advantage = mode;
+                    }
+                    break;
+                }
+            }
+        }
+        let input: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+        if input.is_empty() {
+            return Err("Give me some dice to roll, like `d20` or `3d6+2`.".to_string());
+        }
+
+        let mut terms = Vec::new();
+        let mut sign = 1;
+        let mut current = String::new();
+        for c in input.chars().chain(std::iter::once('+')) {
+            if c == '+' || c == '-' {
+                if current.is_empty() {
+                    if terms.is_empty() && c == '-' && sign == 1 {
+                        sign = -1;
+                        continue;
+                    }
+                    return Err(format!(
+                        "`{input}` has an operator with nothing next to it."
+                    ));
+                }
+                terms.push((sign, Self::parse_term(&current)?));
+                current.clear();
+                sign = if c == '-' { -1 } else { 1 };
+            } else {
+                current.push(c);
+            }
+        }
+        if terms.len() > MAX_TERMS {
+            return Err(format!("That's more than {MAX_TERMS} terms."));
+        }
+
+        if advantage != Advantage::None {
+            let Some((_, Term::Dice { count, keep, .. })) = terms.iter_mut().find(|(_, t)| {
+                matches!(
+                    t,
+                    Term::Dice {
+                        count: 1,
+                        keep: None,
+                        ..
+                    }
+                )
+            }) else {
+                return Err("Advantage needs a single die to roll twice, like `d20`.".to_string());
+            };
+            *count = 2;
+            *keep = Some(match advantage {
+                Advantage::Disadvantage => Keep::Lowest(1),
+                _ => Keep::Highest(1),
+            });
+        }
+
+        let dice: u32 = terms
+            .iter()
+            .map(|(_, t)| match t {
+                Term::Dice { count, .. } => *count,
+                Term::Constant(_) => 0,
+            })
+            .sum();
+        if dice == 0 {
+            return Err("There are no dice in that. Try something like `d20+5`.".to_string());
+        }
+        if dice > MAX_DICE {
+            return Err(format!("That's more than {MAX_DICE} dice in one roll."));
+        }
+        Ok(Self { terms })
+    }
+
+    fn parse_term(term: &str) -> Result<Term, String> {
+        let Some((count, rest)) = term.split_once('d') else {
+            return term
+                .parse::<i64>()
+                .ok()
+                .filter(|n| *n <= MAX_CONSTANT)
+                .map(Term::Constant)
+                .ok_or_else(|| format!("`{term}` isn't a die or a number up to {MAX_CONSTANT}."));
+        };
+        let count: u32 = if count.is_empty() {
+            1
+        } else {
+            count
+                .parse()
+                .ok()
+                .filter(|c| (1..=MAX_DICE).contains(c))
+                .ok_or_else(|| format!("`{term}` needs 1 to {MAX_DICE} dice."))?
+        };
+        let (sides, keep) = match rest.find('k') {
+            Some(at) => {
+                let (sides, keep) = rest.split_at(at);
+                let amount = |n: &str| {
+                    n.parse::<u32>()
+                        .ok()
+                        .filter(|n| (1..=count).contains(n))
+                        .ok_or_else(|| format!("`{term}` keeps between 1 and {count} dice."))
+                };
+                let keep = match keep {
+                    "k" => Keep::Highest(1),
+                    _ if keep.starts_with("kh") => Keep::Highest(amount(&keep[2..])?),
+                    _ if keep.starts_with("kl") => Keep::Lowest(amount(&keep[2..])?),
+                    _ => Keep::Highest(amount(&keep[1..])?),
+                };
+                (sides, Some(keep))
+            }
+            None => (rest, None),
+        };
+        let sides = match sides {
+            "%" => 100,
+            _ => sides
+                .parse::<u32>()
+                .ok()
+                .filter(|s| (2..=MAX_SIDES).contains(s))
+                .ok_or_else(|| format!("`{term}` needs dice with 2 to {MAX_SIDES} sides."))?,
+        };
+        Ok(Term::Dice { count, sides, keep })
+    }
+
+    /// Roll the expression, `die(sides)` giving one roll from 1 to `sides`
+    pub fn roll_with(&self, mut die: impl FnMut(u32) -> u32) -> DiceRoll {
+        let terms: Vec<RolledTerm> = self
+            .terms
+            .iter()
+            .map(|(sign, term)| match term {
+                Term::Constant(n) => RolledTerm {
+                    sign: *sign,
+                    term: term.clone(),
+                    rolls: Vec::new(),
+                    value: *n,
+                },
+                Term::Dice { count, sides, keep } => {
+                    let values: Vec<u32> = (0..*count).map(|_| die(*sides)).collect();
+                    let kept = kept_dice(&values, *keep);
+                    let value = values
+                        .iter()
+                        .zip(&kept)
+                        .filter(|(_, k)| **k)
+                        .map(|(v, _)| *v as i64)
+                        .sum();
+                    RolledTerm {
+                        sign: *sign,
+                        term: term.clone(),
+                        rolls: values.into_iter().zip(kept).collect(),
+                        value,
+                    }
+                }
+            })
+            .collect();
+        let total = terms.iter().map(|t| t.sign * t.value).sum();
+        DiceRoll { terms, total }
+    }
+
+    /// Roll with the thread-local RNG
+    pub fn roll(&self) -> DiceRoll {
+        use rand::Rng;
+        let mut rng = rand::rng();
+        self.roll_with(|sides| rng.random_range(1..=sides))
+    }
+}
+
+/// Which of the rolled values a keep rule counts
+fn kept_dice(values: &[u32], keep: Option<Keep>) -> Vec<bool> {
+    let Some(keep) = keep else {
+        return vec![true; values.len()];
+    };
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    let amount = match keep {
+        Keep::Highest(n) => {
+            order.sort_by(|a, b| values[*b].cmp(&values[*a]));
+            n
+        }
+        Keep::Lowest(n) => {
+            order.sort_by(|a, b| values[*a].cmp(&values[*b]));
+            n
+        }
+    };
+    let mut kept = vec![false; values.len()];
+    for index in order.into_iter().take(amount as usize) {
+        kept[index] = true;
+    }
+    kept
+}
+
+impl DiceRoll {
+    /// The rolls written out, like `[4, ~~2~~, 6] + 2`, dropped dice struck
+    pub fn breakdown(&self) -> String {
+        let mut out = String::new();
+        for (i, term) in self.terms.iter().enumerate() {
+            match (i, term.sign) {
+                (0, -1) => out.push('-'),
+                (0, _) => {}
+                (_, -1) => out.push_str(" - "),
+                _ => out.push_str(" + "),
+            }
+            match term.term {
+                Term::Constant(n) => out.push_str(&n.to_string()),
+                Term::Dice { .. } => {
+                    let rolls: Vec<String> = term
+                        .rolls
+                        .iter()
+                        .map(|(v, kept)| {
+                            if *kept {
+                                v.to_string()
+                            } else {
+                                format!("~~{v}~~")
+                            }
+                        })
+                        .collect();
+                    out.push_str(&format!("[{}]", rolls.join(", ")));
+                }
+            }
+        }
+        out
+    }
+
+    /// A natural 20 or 1 on a lone d20, worth calling out
+    pub fn natural(&self) -> Option<u32> {
+        let dice: Vec<&RolledTerm> = self
+            .terms
+            .iter()
+            .filter(|t| matches!(t.term, Term::Dice { .. }))
+            .collect();
+        match dice.as_slice() {
+            [RolledTerm {
+                term: Term::Dice { sides: 20, .. },
+                rolls,
+                ..
+            }] => {
+                let kept: Vec<u32> = rolls.iter().filter(|(_, k)| *k).map(|(v, _)| *v).collect();
+                match kept.as_slice() {
+                    [20] => Some(20),
+                    [1] => Some(1),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Check a combatant name and return it trimmed
+pub fn validate_combatant(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Give the combatant a name.".to_string());
+    }
+    if name.chars().count() > MAX_COMBATANT_CHARS {
+        return Err(format!(
+            "Names can be up to {MAX_COMBATANT_CHARS} characters."
+        ));
+    }
+    Ok(name.to_string())
+}
+
+/// The initiative order, marking whose turn it is
+pub fn initiative_lines(entries: &[InitiativeEntry], turn: usize) -> Vec<String> {
+    entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let marker = if i == turn { "▶️" } else { "▫️" };
+            let modifier = match entry.modifier {
+                0 => String::new(),
+                m => format!(" ({m:+})"),
+            };
+            format!("{marker} **{}** {}{modifier}", entry.initiative, entry.name)
+        })
+        .collect()
+}
+
+/// Turn and round after removing the combatant at `removed`, keeping the
+/// current combatant's turn where possible
+pub fn turn_after_removal(
+    turn: usize,
+    round: i64,
+    removed: usize,
+    remaining: usize,
+) -> (usize, i64) {
+    if remaining == 0 {
+        return (0, 1);
+    }
+    let turn = if removed < turn { turn - 1 } else { turn };
+    if turn >= remaining {
+        (0, round + 1)
+    } else {
+        (turn, round)
+    }
+}
+
+/// Prompt for narrating a roll in character
+pub const NARRATION_PROMPT: &str = "A member of a tabletop game just rolled dice. In your \
+    characteristic style, narrate the outcome in one to three sentences, like a game master \
+    describing what happens. Keep the total exactly as given and don't invent other numbers. \
+    A natural 20 is a spectacular success and a natural 1 a memorable failure. No preamble.";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed(values: &[u32]) -> impl FnMut(u32) -> u32 + '_ {
+        let mut iter = values.iter();
+        move |_| *iter.next().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let expr = DiceExpr::parse("3d6 + 2", Advantage::None).unwrap();
+        assert_eq!(
+            expr.terms,
+            [
+                (
+                    1,
+                    Term::Dice {
+                        count: 3,
+                        sides: 6,
+                        keep: None
+                    }
+                ),
+                (1, Term::Constant(2))
+            ]
+        );
+        let expr = DiceExpr::parse("4d6kh3-d%", Advantage::None).unwrap();
+        assert_eq!(
+            expr.terms[0].1,
+            Term::Dice {
+                count: 4,
+                sides: 6,
+                keep: Some(Keep::Highest(3))
+            }
+        );
+        assert_eq!(
+            expr.terms[1],
+            (
+                -1,
+                Term::Dice {
+                    count: 1,
+                    sides: 100,
+                    keep: None
+                }
+            )
+        );
+
+        assert!(DiceExpr::parse("", Advantage::None).is_err());
+        assert!(DiceExpr::parse("5", Advantage::None).is_err());
+        assert!(DiceExpr::parse("d1", Advantage::None).is_err());
+        assert!(DiceExpr::parse("101d6", Advantage::None).is_err());
+        assert!(DiceExpr::parse("2d6kh3", Advantage::None).is_err());
+        assert!(DiceExpr::parse("d20++2", Advantage::None).is_err());
+        assert!(DiceExpr::parse("fireball", Advantage::None).is_err());
+    }
+
+    #[test]
+    fn test_parse_advantage() {
+        let expected = |keep| {
+            (
+                1,
+                Term::Dice {
+                    count: 2,
+                    sides: 20,
+                    keep: Some(keep),
+                },
+            )
+        };
+        let expr = DiceExpr::parse("d20+5 adv", Advantage::None).unwrap();
+        assert_eq!(expr.terms[0], expected(Keep::Highest(1)));
+        let expr = DiceExpr::parse("d20", Advantage::Disadvantage).unwrap();
+        assert_eq!(expr.terms[0], expected(Keep::Lowest(1)));
+        // The option wins over a suffix
+        let expr = DiceExpr::parse("d20 dis", Advantage::Advantage).unwrap();
+        assert_eq!(expr.terms[0], expected(Keep::Highest(1)));
+        assert!(DiceExpr::parse("2d6 adv", Advantage::None).is_err());
+    }
+
+    #[test]
+    fn test_roll_and_breakdown() {
+        let expr = DiceExpr::parse("4d6kh3 - 1", Advantage::None).unwrap();
+        let roll = expr.roll_with(fixed(&[4, 2, 6, 5]));
+        assert_eq!(roll.total, 14);
+        assert_eq!(roll.breakdown(), "[4, ~~2~~, 6, 5] - 1");
+        assert_eq!(roll.natural(), None);
+
+        let expr = DiceExpr::parse("-d4+3", Advantage::None).unwrap();
+        assert_eq!(expr.roll_with(fixed(&[4])).total, -1);
+
+        let expr = DiceExpr::parse("d20+3", Advantage::Disadvantage).unwrap();
+        let roll = expr.roll_with(fixed(&[20, 1]));
+        assert_eq!(roll.total, 4);
+        assert_eq!(roll.natural(), Some(1));
+        assert_eq!(roll.breakdown(), "[~~20~~, 1] + 3");
+
+        let roll = DiceExpr::parse("3d20", Advantage::None)
+            .unwrap()
+            .roll_with(fixed(&[20, 20, 20]));
+        assert_eq!(roll.natural(), None);
+    }
+
+    #[test]
+    fn test_roll_in_range() {
+        let expr = DiceExpr::parse("10d6", Advantage::None).unwrap();
+        for _ in 0..50 {
+            let total = expr.roll().total;
+            assert!((10..=60).contains(&total));
+        }
+    }
+
+    #[test]
+    fn test_validate_combatant() {
+        assert_eq!(validate_combatant("  Goblin 2 ").unwrap(), "Goblin 2");
+        assert!(validate_combatant(" ").is_err());
+        assert!(validate_combatant(&"x".repeat(MAX_COMBATANT_CHARS + 1)).is_err());
+    }
+
+    #[test]
+    fn test_turn_after_removal() {
+        // Removing someone who already went keeps the current combatant up
+        assert_eq!(turn_after_removal(2, 1, 0, 3), (1, 1));
+        // Removing the current last combatant starts the next round
+        assert_eq!(turn_after_removal(2, 1, 2, 2), (0, 2));
+        assert_eq!(turn_after_removal(1, 1, 1, 3), (1, 1));
+        assert_eq!(turn_after_removal(0, 4, 0, 0), (0, 1));
+    }
+}
@@ -0,0 +1,260 @@
+//! # Feature: Answer Disclosure
+//!
+//! An optional response mode where the model reports how confident it is and
+//! what its answer rests on. The persona answers as usual, then ends with a
+//! `DISCLOSURE:` line holding a JSON object, which is stripped from the answer
+//! and shown as the embed footer. Servers opt in with
+//! `/set_guild answer_disclosure enabled`; the `disclosure` option on `/ask`
+//! overrides that for one question.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with confidence, sources and assumptions in the footer
+
+use anyhow::Result;
+use serde::Deserialize;
+
+use crate::database::Database;
+
+/// Feature id for toggles
+pub const DISCLOSURE_FEATURE: &str = "disclosure";
+
+/// Guild setting turning disclosure on for every answer (`enabled`)
+pub const DISCLOSURE_SETTING: &str = "answer_disclosure";
+
+/// Marker starting the disclosure line at the end of an answer
+const MARKER: &str = "DISCLOSURE:";
+
+/// Most sources or assumptions listed in the footer
+const MAX_ITEMS: usize = 3;
+
+/// Longest source or assumption shown, in characters
+const MAX_ITEM_CHARS: usize = 120;
+
+/// Instructions appended to the system prompt in disclosure mode
+pub const DISCLOSURE_PROMPT: &str = "\n\n## Confidence Disclosure\n\
+After your answer, add one final line starting with `DISCLOSURE:` followed by a JSON object: \
+{\"confidence\": \"high\" | \"medium\" | \"low\", \"sources\": [\"where the information comes from\"], \
+\"assumptions\": [\"anything you assumed to answer\"]}. \
+Only list sources you actually know of, such as well-known references, documentation or the conversation itself; \
+never invent titles or links. Use empty lists when there's nothing to list. \
+Don't mention the disclosure line in your answer.";
+
+/// How sure the model is of its answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Confidence {
+    High,
+    Medium,
+    Low,
+}
+
+impl Confidence {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "high" => Some(Self::High),
+            "medium" | "moderate" => Some(Self::Medium),
+            "low" => Some(Self::Low),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::High => "🟢 High",
+            Self::Medium => "🟡 Medium",
+            Self::Low => "🔴 Low",
+        }
+    }
+}
+
+/// Confidence, sources and assumptions reported with an answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Disclosure {
+    pub confidence: Confidence,
+    pub sources: Vec<String>,
+    pub assumptions: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct RawDisclosure {
+    confidence: String,
+    #[serde(default)]
+    sources: Vec<String>,
+    #[serde(default)]
+    assumptions: Vec<String>,
+}
+
+/// Trim, drop empty items and cap the list for the footer
+fn clean_items(items: Vec<String>) -> Vec<String> {
+    items
+        .into_iter()
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .take(MAX_ITEMS)
+        .map(|item| {
+            if item.chars().count() <= MAX_ITEM_CHARS {
+                item
+            } else {
+                let clipped: String = item.chars().take(MAX_ITEM_CHARS - 1).collect();
+                format!("{}…", clipped.trim_end())
+            }
+        })
+        .collect()
+}
+
+impl Disclosure {
+    /// Footer text, e.g. `Confidence: 🟡 Medium · Sources: X; Y · Assumptions: Z`
+    pub fn footer_text(&self) -> String {
+        let mut parts = vec![format!("Confidence: {}", self.confidence.label())];
+        if !self.sources.is_empty() {
+            parts.push(format!("Sources: {}", self.sources.join("; ")));
+        }
+        if !self.assumptions.is_empty() {
+            parts.push(format!("Assumptions: {}", self.assumptions.join("; ")));
+        }
+        parts.join(" · ")
+    }
+}
+
+/// The system prompt with disclosure instructions appended
+pub fn with_disclosure_prompt(system_prompt: &str) -> String {
+    format!("{system_prompt}{DISCLOSURE_PROMPT}")
+}
+
+/// Split a response into the answer and its disclosure
+///
+/// The disclosure line is removed from the answer even when it can't be
+/// parsed, so a malformed one never shows up in the reply.
+pub fn split_disclosure(response: &str) -> (String, Option<Disclosure>) {
+    let Some(start) = response.rfind(MARKER) else {
+        return (response.to_string(), None);
+    };
+    let answer = response[..start]
+        .trim_end()
+        .trim_end_matches("```")
+        .trim_end()
+        .to_string();
+    let tail = &response[start + MARKER.len()..];
+    let json = match (tail.find('{'), tail.rfind('}')) {
+        (Some(open), Some(close)) if open < close => &tail[open..=close],
+        _ => return (answer, None),
+    };
+    let disclosure = serde_json::from_str::<RawDisclosure>(json)
+        .ok()
+        .and_then(|raw| {
+            Some(Disclosure {
+                confidence: Confidence::parse(&raw.confidence)?,
+                sources: clean_items(raw.sources),
+                assumptions: clean_items(raw.assumptions),
+            })
+        });
+    (answer, disclosure)
+}
+
+/// Whether an answer in `guild_id` uses disclosure mode
+///
+/// `requested` is the command's own option and wins over the guild setting.
+/// DMs only use it when asked for.
+pub async fn is_enabled(
+    database: &Database,
+    guild_id: Option<&str>,
+    requested: Option<bool>,
+) -> Result<bool> {
+    if !database
+        .is_feature_enabled(DISCLOSURE_FEATURE, None, guild_id)
+        .await?
+    {
+        return Ok(false);
+    }
+    if let Some(requested) = requested {
+        return Ok(requested);
+    }
+    Ok(match guild_id {
+        Some(guild_id) => {
+            database
+                .get_guild_setting(guild_id, DISCLOSURE_SETTING)
+                .await?
+                .as_deref()
+                == Some("enabled")
+        }
+        None => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_disclosure() {
+        let response = "Rust was first released in 2015.\n\n\
+            DISCLOSURE: {\"confidence\": \"High\", \"sources\": [\"The Rust blog\", \" \"], \"assumptions\": []}";
+        let (answer, disclosure) = split_disclosure(response);
+
+        assert_eq!(answer, "Rust was first released in 2015.");
+        let disclosure = disclosure.unwrap();
+        assert_eq!(disclosure.confidence, Confidence::High);
+        assert_eq!(disclosure.sources, ["The Rust blog"]);
+        assert!(disclosure.assumptions.is_empty());
+        assert_eq!(
+            disclosure.footer_text(),
+            "Confidence: 🟢 High · Sources: The Rust blog"
+        );
+    }
+
+    #[test]
+    fn test_split_disclosure_without_or_with_bad_line() {
+        let (answer, disclosure) = split_disclosure("Just an answer.");
+        assert_eq!(answer, "Just an answer.");
+        assert!(disclosure.is_none());
+
+        let (answer, disclosure) = split_disclosure("An answer.\nDISCLOSURE: not json");
+        assert_eq!(answer, "An answer.");
+        assert!(disclosure.is_none());
+
+        let (answer, disclosure) =
+            split_disclosure("An answer.\nDISCLOSURE: {\"confidence\": \"certain\"}");
+        assert_eq!(answer, "An answer.");
+        assert!(disclosure.is_none());
+    }
+
+    #[test]
+    fn test_disclosure_items_are_capped() {
+        let long = "x".repeat(MAX_ITEM_CHARS + 20);
+        let response = format!(
+            "Answer.\nDISCLOSURE: {{\"confidence\": \"moderate\", \"assumptions\": [\"a\", \"b\", \"c\", \"d\", \"{long}\"]}}"
+        );
+        let disclosure = split_disclosure(&response).1.unwrap();
+        assert_eq!(disclosure.confidence, Confidence::Medium);
+        assert_eq!(disclosure.assumptions, ["a", "b", "c"]);
+        assert_eq!(clean_items(vec![long])[0].chars().count(), MAX_ITEM_CHARS);
+        assert_eq!(
+            disclosure.footer_text(),
+            "Confidence: 🟡 Medium · Assumptions: a; b; c"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_is_enabled() {
+        let db = Database::new(":memory:").await.unwrap();
+        assert!(!is_enabled(&db, Some("g1"), None).await.unwrap());
+        assert!(is_enabled(&db, Some("g1"), Some(true)).await.unwrap());
+        assert!(!is_enabled(&db, None, None).await.unwrap());
+
+        db.set_guild_setting("g1", DISCLOSURE_SETTING, "enabled")
+            .await
+            .unwrap();
+        assert!(is_enabled(&db, Some("g1"), None).await.unwrap());
+        assert!(!is_enabled(&db, Some("g1"), Some(false)).await.unwrap());
+    }
+
+    #[test]
+    fn test_with_disclosure_prompt() {
+        let prompt = with_disclosure_prompt("You are a chef.");
+        assert!(prompt.starts_with("You are a chef."));
+        assert!(prompt.contains("DISCLOSURE:"));
+    }
+}
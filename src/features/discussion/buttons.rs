@@ -1,13 +1,18 @@
 //! # Discussion Button Components
 //!
 //! Button builders for council and debate interactive controls.
+//!
+//! Buttons use namespaced `council:*` / `debate:*` custom IDs routed by the
+//! `ComponentRegistry`; the state they act on is persisted (see `persistence`)
+//! so they keep working after a restart.
 
 use serenity::builder::CreateComponents;
 use serenity::model::application::component::ButtonStyle;
 
+use crate::commands::components::{ComponentId, PAYLOAD_SEPARATOR};
 use crate::features::personas::PersonaManager;
 
-/// Button ID prefixes for routing
+/// Button ID prefixes used before namespaced custom IDs (still routed for old messages)
 pub const SPEAKER_COUNCIL_PREFIX: &str = "speaker_council_";
 pub const CONTINUE_COUNCIL_PREFIX: &str = "continue_council_";
 pub const DISMISS_COUNCIL_PREFIX: &str = "dismiss_council_";
@@ -15,6 +20,16 @@ pub const HEAR_DEBATE_PREFIX: &str = "hear_debate_";
 pub const CONTINUE_DEBATE_PREFIX: &str = "debate_continue_";
 pub const END_DEBATE_PREFIX: &str = "debate_end_";
 
+/// Custom ID for a council control button acting on a thread
+pub fn council_button_id(action: &str, thread_id: u64) -> String {
+    ComponentId::new("council", action, thread_id.to_string()).to_string()
+}
+
+/// Custom ID for a debate control button acting on a thread
+pub fn debate_button_id(action: &str, thread_id: u64) -> String {
+    ComponentId::new("debate", action, thread_id.to_string()).to_string()
+}
+
 /// Create council control buttons after opening statements
 ///
 /// Includes speaker selection buttons and continue/dismiss controls.
@@ -37,7 +52,12 @@ pub fn create_council_buttons(
                 .get_persona(id)
                 .map(|p| p.name.clone())
                 .unwrap_or_else(|| id.clone());
-            let custom_id = format!("{SPEAKER_COUNCIL_PREFIX}{thread_id}_{id}_{idx}");
+            let custom_id = ComponentId::new(
+                "council",
+                "speaker",
+                format!("{thread_id}{PAYLOAD_SEPARATOR}{id}{PAYLOAD_SEPARATOR}{idx}"),
+            )
+            .to_string();
             let label = if name.len() > 20 {
                 format!("{}...", &name[..17])
             } else {
@@ -79,12 +99,12 @@ pub fn create_council_buttons(
     // Add control row
     components.create_action_row(|row| {
         row.create_button(|btn| {
-            btn.custom_id(format!("{CONTINUE_COUNCIL_PREFIX}{thread_id}"))
+            btn.custom_id(council_button_id("continue", thread_id))
                 .label("Continue Discussion")
                 .style(ButtonStyle::Success)
         })
         .create_button(|btn| {
-            btn.custom_id(format!("{DISMISS_COUNCIL_PREFIX}{thread_id}"))
+            btn.custom_id(council_button_id("dismiss", thread_id))
                 .label("Dismiss Council")
                 .style(ButtonStyle::Secondary)
         })
@@ -116,16 +136,26 @@ pub fn create_debate_buttons(
     // Debater selection row
     components.create_action_row(|row| {
         row.create_button(|btn| {
-            btn.custom_id(format!(
-                "{HEAR_DEBATE_PREFIX}{thread_id}_{persona1_id}"
-            ))
+            btn.custom_id(
+                ComponentId::new(
+                    "debate",
+                    "hear",
+                    format!("{thread_id}{PAYLOAD_SEPARATOR}{persona1_id}"),
+                )
+                .to_string(),
+            )
             .label(format!("Hear {p1_name}"))
             .style(ButtonStyle::Primary)
         })
         .create_button(|btn| {
-            btn.custom_id(format!(
-                "{HEAR_DEBATE_PREFIX}{thread_id}_{persona2_id}"
-            ))
+            btn.custom_id(
+                ComponentId::new(
+                    "debate",
+                    "hear",
+                    format!("{thread_id}{PAYLOAD_SEPARATOR}{persona2_id}"),
+                )
+                .to_string(),
+            )
             .label(format!("Hear {p2_name}"))
             .style(ButtonStyle::Primary)
         })
@@ -134,12 +164,12 @@ pub fn create_debate_buttons(
     // Control row
     components.create_action_row(|row| {
         row.create_button(|btn| {
-            btn.custom_id(format!("{CONTINUE_DEBATE_PREFIX}{thread_id}"))
+            btn.custom_id(debate_button_id("continue", thread_id))
                 .label("Continue (+4 rounds)")
                 .style(ButtonStyle::Success)
         })
         .create_button(|btn| {
-            btn.custom_id(format!("{END_DEBATE_PREFIX}{thread_id}"))
+            btn.custom_id(debate_button_id("end", thread_id))
                 .label("End Debate")
                 .style(ButtonStyle::Secondary)
         })
@@ -148,6 +178,38 @@ pub fn create_debate_buttons(
     components
 }
 
+/// Create "who won?" vote buttons for a concluded debate
+pub fn create_debate_vote_buttons(
+    thread_id: u64,
+    persona1_id: &str,
+    persona2_id: &str,
+    persona_manager: &PersonaManager,
+) -> CreateComponents {
+    let mut components = CreateComponents::default();
+    components.create_action_row(|row| {
+        for persona_id in [persona1_id, persona2_id] {
+            let name = persona_manager
+                .get_persona(persona_id)
+                .map(|p| p.name.clone())
+                .unwrap_or_else(|| persona_id.to_string());
+            row.create_button(|btn| {
+                btn.custom_id(
+                    ComponentId::new(
+                        "debate",
+                        "vote",
+                        format!("{thread_id}{PAYLOAD_SEPARATOR}{persona_id}"),
+                    )
+                    .to_string(),
+                )
+                .label(format!("🗳️ {name} won"))
+                .style(ButtonStyle::Primary)
+            });
+        }
+        row
+    });
+    components
+}
+
 /// Create a simple "awaiting input" button set for paused discussions
 pub fn create_awaiting_buttons(
     thread_id: u64,
@@ -159,12 +221,12 @@ pub fn create_awaiting_buttons(
         super::DiscussionType::Council => {
             components.create_action_row(|row| {
                 row.create_button(|btn| {
-                    btn.custom_id(format!("{CONTINUE_COUNCIL_PREFIX}{thread_id}"))
+                    btn.custom_id(council_button_id("continue", thread_id))
                         .label("Continue Discussion")
                         .style(ButtonStyle::Success)
                 })
                 .create_button(|btn| {
-                    btn.custom_id(format!("{DISMISS_COUNCIL_PREFIX}{thread_id}"))
+                    btn.custom_id(council_button_id("dismiss", thread_id))
                         .label("Dismiss Council")
                         .style(ButtonStyle::Secondary)
                 })
@@ -173,12 +235,12 @@ pub fn create_awaiting_buttons(
         super::DiscussionType::Debate => {
             components.create_action_row(|row| {
                 row.create_button(|btn| {
-                    btn.custom_id(format!("{CONTINUE_DEBATE_PREFIX}{thread_id}"))
+                    btn.custom_id(debate_button_id("continue", thread_id))
                         .label("Continue Debate")
                         .style(ButtonStyle::Success)
                 })
                 .create_button(|btn| {
-                    btn.custom_id(format!("{END_DEBATE_PREFIX}{thread_id}"))
+                    btn.custom_id(debate_button_id("end", thread_id))
                         .label("End Debate")
                         .style(ButtonStyle::Secondary)
                 })
@@ -189,7 +251,15 @@ pub fn create_awaiting_buttons(
     components
 }
 
-/// Parse a council speaker button custom_id
+/// Parse a `thread_id/persona_id[/index]` button payload
+pub fn parse_discussion_target(payload: &str) -> Option<(u64, String)> {
+    let mut parts = payload.split(PAYLOAD_SEPARATOR);
+    let thread_id = parts.next()?.parse().ok()?;
+    let persona_id = parts.next().filter(|p| !p.is_empty())?;
+    Some((thread_id, persona_id.to_string()))
+}
+
+/// Parse a legacy council speaker button custom_id
 ///
 /// Returns (thread_id, persona_id) if valid
 pub fn parse_council_speaker_id(custom_id: &str) -> Option<(u64, String)> {
@@ -205,7 +275,7 @@ pub fn parse_council_speaker_id(custom_id: &str) -> Option<(u64, String)> {
     }
 }
 
-/// Parse a debate hear button custom_id
+/// Parse a legacy debate hear button custom_id
 ///
 /// Returns (thread_id, persona_id) if valid
 pub fn parse_debate_hear_id(custom_id: &str) -> Option<(u64, String)> {
@@ -245,6 +315,21 @@ mod tests {
         assert_eq!(persona_id, "muppet");
     }
 
+    #[test]
+    fn test_parse_discussion_target() {
+        assert_eq!(
+            parse_discussion_target("123/obi/0"),
+            Some((123, "obi".to_string()))
+        );
+        assert_eq!(
+            parse_discussion_target("123/muppet"),
+            Some((123, "muppet".to_string()))
+        );
+        assert!(parse_discussion_target("123").is_none());
+        assert!(parse_discussion_target("abc/obi").is_none());
+        assert_eq!(debate_button_id("continue", 9), "debate:continue:9");
+    }
+
     #[test]
     fn test_invalid_parse() {
         assert!(parse_council_speaker_id("invalid_id").is_none());
@@ -134,8 +134,8 @@ pub fn format_prior_context(context: &ThreadContext, persona_manager: &PersonaMa
             };
 
             // Truncate long messages for the summary
-            let content_preview = if msg.content.len() > 200 {
-                format!("{}...", &msg.content[..197])
+            let content_preview = if msg.content.chars().count() > 200 {
+                format!("{}...", msg.content.chars().take(197).collect::<String>())
             } else {
                 msg.content.clone()
             };
@@ -2,17 +2,25 @@
 //!
 //! Shared types and utilities for council and debate interoperability.
 //!
-//! - **Version**: 1.0.0
+//! - **Version**: 1.3.1
 //! - **Since**: 3.33.0
 //!
 //! ## Changelog
+//! - 1.3.1: Persisted councils and debates are restored at startup instead of per message
+//! - 1.3.0: Transcript parser for /debate import, char-safe prior context previews
+//! - 1.2.0: Audience vote buttons on concluded debates
+//! - 1.1.0: Namespaced button IDs and state persistence across restarts
 //! - 1.0.0: Initial implementation with shared types and context detection
 
 pub mod buttons;
 pub mod context;
+pub mod persistence;
+pub mod transcript;
 
 pub use buttons::*;
 pub use context::*;
+pub use persistence::*;
+pub use transcript::*;
 
 use serde::{Deserialize, Serialize};
 
@@ -0,0 +1,204 @@
+//! # Discussion State Persistence
+//!
+//! Council and debate state lives in in-memory maps; buttons posted in a
+//! thread outlive the process. Snapshots are written to the `component_state`
+//! table whenever a session is left awaiting a button press and restored into
+//! the maps at startup, or when a button arrives for a thread the process
+//! doesn't know.
+
+use anyhow::Result;
+use log::{info, warn};
+
+use super::DiscussionType;
+use crate::database::Database;
+use crate::features::council::{get_active_councils, CouncilState};
+use crate::features::debate::{get_active_debates, DebateState};
+
+/// How long an idle council or debate can be resumed from its buttons
+pub const DISCUSSION_STATE_TTL_SECS: i64 = 7 * 24 * 60 * 60;
+
+/// Component feature namespace for a discussion type
+pub fn discussion_feature(kind: DiscussionType) -> &'static str {
+    match kind {
+        DiscussionType::Council => "council",
+        DiscussionType::Debate => "debate",
+    }
+}
+
+/// Persist the current in-memory state for a thread (or forget it if the session ended)
+pub async fn save_discussion_state(
+    database: &Database,
+    kind: DiscussionType,
+    thread_id: u64,
+) -> Result<()> {
+    let snapshot = match kind {
+        DiscussionType::Council => get_active_councils()
+            .get(&thread_id)
+            .map(|state| serde_json::to_string(&*state))
+            .transpose()?,
+        DiscussionType::Debate => get_active_debates()
+            .get(&thread_id)
+            .map(|state| serde_json::to_string(&*state))
+            .transpose()?,
+    };
+
+    let feature = discussion_feature(kind);
+    match snapshot {
+        Some(payload) => {
+            let expires_at = chrono::Utc::now().timestamp() + DISCUSSION_STATE_TTL_SECS;
+            database
+                .save_component_state(feature, &thread_id.to_string(), &payload, Some(expires_at))
+                .await
+        }
+        None => {
+            database
+                .delete_component_state(feature, &thread_id.to_string())
+                .await
+        }
+    }
+}
+
+/// Like `save_discussion_state`, but only logs failures
+pub async fn save_discussion_state_logged(
+    database: &Database,
+    kind: DiscussionType,
+    thread_id: u64,
+) {
+    if let Err(e) = save_discussion_state(database, kind, thread_id).await {
+        warn!("Failed to persist {kind} state for thread {thread_id}: {e}");
+    }
+}
+
+/// Load persisted state into the in-memory map if the process doesn't have it
+///
+/// Returns true when state is available in memory afterwards.
+pub async fn restore_discussion_state(
+    database: &Database,
+    kind: DiscussionType,
+    thread_id: u64,
+) -> Result<bool> {
+    let in_memory = match kind {
+        DiscussionType::Council => get_active_councils().contains_key(&thread_id),
+        DiscussionType::Debate => get_active_debates().contains_key(&thread_id),
+    };
+    if in_memory {
+        return Ok(true);
+    }
+
+    let Some(payload) = database
+        .get_component_state(discussion_feature(kind), &thread_id.to_string())
+        .await?
+    else {
+        return Ok(false);
+    };
+
+    insert_snapshot(kind, thread_id, &payload)?;
+    info!("Restored {kind} state for thread {thread_id} from the database");
+    Ok(true)
+}
+
+/// Load every persisted session of a kind into memory, returning how many were restored
+///
+/// Run once at startup so per-message thread checks only read the in-memory maps.
+pub async fn restore_all_discussion_states(
+    database: &Database,
+    kind: DiscussionType,
+) -> Result<usize> {
+    let mut restored = 0;
+    for (state_key, payload) in database
+        .list_component_states(discussion_feature(kind))
+        .await?
+    {
+        let Ok(thread_id) = state_key.parse::<u64>() else {
+            continue;
+        };
+        match insert_snapshot(kind, thread_id, &payload) {
+            Ok(()) => restored += 1,
+            Err(e) => warn!("Skipping unreadable {kind} state for thread {thread_id}: {e}"),
+        }
+    }
+    Ok(restored)
+}
+
+/// Put a persisted snapshot into the in-memory map unless the thread is already there
+fn insert_snapshot(kind: DiscussionType, thread_id: u64, payload: &str) -> Result<()> {
+    match kind {
+        DiscussionType::Council => {
+            let state: CouncilState = serde_json::from_str(payload)?;
+            get_active_councils().entry(thread_id).or_insert(state);
+        }
+        DiscussionType::Debate => {
+            let state: DebateState = serde_json::from_str(payload)?;
+            get_active_debates().entry(thread_id).or_insert(state);
+        }
+    }
+    Ok(())
+}
+
+/// Drop persisted state for an ended session
+pub async fn forget_discussion_state(
+    database: &Database,
+    kind: DiscussionType,
+    thread_id: u64,
+) -> Result<()> {
+    database
+        .delete_component_state(discussion_feature(kind), &thread_id.to_string())
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_council_state_survives_restart() {
+        let database = Database::new(":memory:").await.unwrap();
+        let thread_id = 910_000_000_001;
+
+        let mut state = CouncilState::new(
+            "Tabs or spaces?".to_string(),
+            vec!["obi".to_string(), "chef".to_string()],
+            "user1".to_string(),
+            None,
+        );
+        state.add_persona_response("obi", "Spaces, young one.".to_string());
+        state.mark_opening_complete();
+        get_active_councils().insert(thread_id, state);
+        save_discussion_state(&database, DiscussionType::Council, thread_id)
+            .await
+            .unwrap();
+
+        // Simulate a restart: the in-memory map is empty again
+        get_active_councils().remove(&thread_id);
+        assert!(
+            restore_discussion_state(&database, DiscussionType::Council, thread_id)
+                .await
+                .unwrap()
+        );
+        let restored = get_active_councils().get(&thread_id).unwrap().clone();
+        assert_eq!(restored.topic, "Tabs or spaces?");
+        assert_eq!(restored.history.len(), 1);
+        assert!(restored.opening_complete);
+
+        // Startup restores every persisted session at once
+        get_active_councils().remove(&thread_id);
+        assert!(
+            restore_all_discussion_states(&database, DiscussionType::Council)
+                .await
+                .unwrap()
+                >= 1
+        );
+        assert!(get_active_councils().contains_key(&thread_id));
+
+        // Ending the session removes the snapshot
+        get_active_councils().remove(&thread_id);
+        save_discussion_state(&database, DiscussionType::Council, thread_id)
+            .await
+            .unwrap();
+        assert!(
+            !restore_discussion_state(&database, DiscussionType::Council, thread_id)
+                .await
+                .unwrap()
+        );
+    }
+}
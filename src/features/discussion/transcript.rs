@@ -0,0 +1,334 @@
+//! # Transcript Import
+//!
+//! Parses a transcript of a discussion held elsewhere (another chat app, a
+//! forum thread, a copied Discord conversation) into a `ThreadContext`, so
+//! `/debate import` can have personas pick the argument up where it left off.
+//!
+//! Recognised line formats, each optionally preceded by a `[timestamp]` or
+//! `12:34` time:
+//! - `Name: message`
+//! - `**Name:** message` or `**Name**: message`
+//! - `<Name> message`
+//! - A `Name — Today at 3:04 PM` header with the message on the lines below
+//!
+//! Lines without a speaker continue the previous message. Discord flattens
+//! text pasted into a command option onto one line, so a single-line
+//! transcript is split into messages on `|` instead.
+
+use regex::Regex;
+use std::sync::OnceLock;
+
+use super::{DiscussionMessage, DiscussionType, ThreadContext};
+
+/// Largest transcript file accepted by /debate import
+pub const MAX_TRANSCRIPT_BYTES: u64 = 100 * 1024;
+
+/// Most recent messages kept from an imported transcript
+pub const MAX_IMPORTED_MESSAGES: usize = 40;
+
+/// Longest single imported message, in characters
+pub const MAX_IMPORTED_MESSAGE_CHARS: usize = 1500;
+
+/// Longest speaker name accepted
+const MAX_SPEAKER_CHARS: usize = 32;
+
+/// Plain `Name:` speakers with more words than this are read as prose
+const MAX_SPEAKER_WORDS: usize = 3;
+
+/// A transcript parsed for import
+#[derive(Debug, Clone)]
+pub struct ImportedTranscript {
+    /// Context holding the kept messages, with speaker names as participants
+    pub context: ThreadContext,
+    /// Older messages dropped to stay within MAX_IMPORTED_MESSAGES
+    pub dropped: usize,
+}
+
+impl ImportedTranscript {
+    /// The first two speakers, who the new debaters take over from
+    pub fn previous_speakers(&self) -> Option<(String, String)> {
+        match self.context.participants.as_slice() {
+            [first, second, ..] => Some((first.clone(), second.clone())),
+            _ => None,
+        }
+    }
+
+    /// Debate history seeded from the transcript
+    ///
+    /// Imported messages go in as user turns naming their speaker, so the
+    /// debaters don't mistake them for something they said themselves.
+    pub fn history(&self) -> Vec<(String, String)> {
+        self.context
+            .messages
+            .iter()
+            .map(|msg| {
+                let speaker = msg.speaker.as_deref().unwrap_or("Unknown");
+                ("user".to_string(), format!("{speaker}: {}", msg.content))
+            })
+            .collect()
+    }
+}
+
+fn timestamp_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"^(?:\[[^\]]{1,40}\]|\(?\d{1,2}:\d{2}(?::\d{2})?(?:\s?[AaPp][Mm])?\)?)\s*[-–—]?\s*",
+        )
+        .unwrap()
+    })
+}
+
+fn speaker_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^(?:\*\*(?P<bold>[^*]+?):?\*\*:?|<(?P<irc>[^>]+)>|(?P<plain>[^\s:*<>\[][^:]*?):)\s+(?P<text>\S.*)$")
+            .unwrap()
+    })
+}
+
+fn header_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^(?P<name>\S.*?)\s+[—–-]\s+(?:Today|Yesterday|\d{1,2}/\d{1,2}/\d{2,4})\b.*$")
+            .unwrap()
+    })
+}
+
+/// Accept a speaker name if it looks like one
+fn speaker_name(name: &str, plain: bool) -> Option<String> {
+    let name = name.trim();
+    let valid = !name.is_empty()
+        && name.chars().count() <= MAX_SPEAKER_CHARS
+        && (!plain || name.split_whitespace().count() <= MAX_SPEAKER_WORDS);
+    valid.then(|| name.to_string())
+}
+
+/// Split a line into its speaker and what they said
+fn parse_speaker_line(line: &str) -> Option<(String, String)> {
+    let caps = speaker_regex().captures(line)?;
+    let speaker = if let Some(name) = caps.name("bold").or(caps.name("irc")) {
+        speaker_name(name.as_str(), false)?
+    } else {
+        speaker_name(caps.name("plain")?.as_str(), true)?
+    };
+    Some((speaker, caps["text"].trim().to_string()))
+}
+
+/// Cut a message to MAX_IMPORTED_MESSAGE_CHARS on a character boundary
+fn clip_message(content: &str) -> String {
+    if content.chars().count() <= MAX_IMPORTED_MESSAGE_CHARS {
+        return content.to_string();
+    }
+    let clipped: String = content
+        .chars()
+        .take(MAX_IMPORTED_MESSAGE_CHARS - 1)
+        .collect();
+    format!("{}…", clipped.trim_end())
+}
+
+/// Parse a transcript into a ThreadContext for the given topic
+///
+/// Consecutive messages from the same speaker are merged. Returns a message
+/// for the user when the text has no recognisable speakers or fewer than
+/// two messages.
+pub fn parse_transcript(
+    text: &str,
+    topic: &str,
+    rules: Option<String>,
+) -> Result<ImportedTranscript, String> {
+    let mut turns: Vec<(String, String)> = Vec::new();
+    let lines: Vec<&str> = if text.trim().contains('\n') {
+        text.lines().collect()
+    } else {
+        text.split('|').collect()
+    };
+
+    for raw in lines {
+        let line = raw.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let stripped = timestamp_regex().replace(line, "");
+
+        if let Some((speaker, said)) = parse_speaker_line(&stripped) {
+            match turns.last_mut() {
+                Some((last, content)) if *last == speaker => {
+                    content.push('\n');
+                    content.push_str(&said);
+                }
+                _ => turns.push((speaker, said)),
+            }
+            continue;
+        }
+        if let Some(speaker) = header_regex()
+            .captures(line)
+            .and_then(|caps| speaker_name(&caps["name"], false))
+        {
+            if turns.last().map(|(last, _)| last) != Some(&speaker) {
+                turns.push((speaker, String::new()));
+            }
+            continue;
+        }
+        // Anything before the first speaker is a preamble and is skipped
+        if let Some((_, content)) = turns.last_mut() {
+            if !content.is_empty() {
+                content.push('\n');
+            }
+            content.push_str(line);
+        }
+    }
+
+    turns.retain(|(_, content)| !content.is_empty());
+    if turns.is_empty() {
+        return Err(
+            "I couldn't find any speakers in that transcript. Put each message on its own line as `Name: message`."
+                .to_string(),
+        );
+    }
+    if turns.len() < 2 {
+        return Err("A transcript needs at least two messages to continue from.".to_string());
+    }
+
+    let dropped = turns.len().saturating_sub(MAX_IMPORTED_MESSAGES);
+    let mut participants: Vec<String> = Vec::new();
+    let mut messages = Vec::new();
+    for (speaker, content) in turns.into_iter().skip(dropped) {
+        if !participants.contains(&speaker) {
+            participants.push(speaker.clone());
+        }
+        messages.push(DiscussionMessage::persona(speaker, clip_message(&content)));
+    }
+
+    let mut context = ThreadContext::new(DiscussionType::Debate, topic, participants);
+    context.messages = messages;
+    Ok(ImportedTranscript {
+        context: context.with_rules(rules),
+        dropped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_transcript_formats() {
+        let text = "Exported from another server\n\
+            Alice: Tabs are objectively better.\n\
+            They let everyone pick a width.\n\
+            [10:02 PM] **Bob:** Spaces render the same everywhere.\n\
+            <carol> Both of you are wrong\n\
+            12:05 Alice: Visit https://example.com for proof";
+        let imported = parse_transcript(text, "Tabs or spaces?", None).unwrap();
+        let ctx = &imported.context;
+
+        assert_eq!(ctx.participants, ["Alice", "Bob", "carol"]);
+        assert_eq!(ctx.message_count(), 4);
+        assert_eq!(
+            ctx.messages[0].content,
+            "Tabs are objectively better.\nThey let everyone pick a width."
+        );
+        assert_eq!(ctx.messages[1].speaker.as_deref(), Some("Bob"));
+        assert_eq!(
+            ctx.messages[3].content,
+            "Visit https://example.com for proof"
+        );
+        assert_eq!(
+            imported.previous_speakers(),
+            Some(("Alice".to_string(), "Bob".to_string()))
+        );
+        assert_eq!(imported.dropped, 0);
+    }
+
+    #[test]
+    fn test_parse_discord_copy_headers() {
+        let text = "Dana — Today at 3:04 PM\n\
+            Pineapple belongs on pizza.\n\
+            Eli — Today at 3:05 PM\n\
+            It absolutely does not.\n\
+            Not even a little.";
+        let imported = parse_transcript(text, "Pizza", None).unwrap();
+        let ctx = &imported.context;
+
+        assert_eq!(ctx.participants, ["Dana", "Eli"]);
+        assert_eq!(
+            ctx.messages[1].content,
+            "It absolutely does not.\nNot even a little."
+        );
+    }
+
+    #[test]
+    fn test_parse_transcript_merges_and_ignores_prose_colons() {
+        let text = "Alice: First point.\n\
+            Alice: Second point.\n\
+            Here is the thing I keep saying: it depends.\n\
+            Bob: Fair.";
+        let imported = parse_transcript(text, "Topic", None).unwrap();
+        let ctx = &imported.context;
+
+        assert_eq!(ctx.message_count(), 2);
+        assert_eq!(
+            ctx.messages[0].content,
+            "First point.\nSecond point.\nHere is the thing I keep saying: it depends."
+        );
+    }
+
+    #[test]
+    fn test_parse_single_line_paste() {
+        let imported = parse_transcript(
+            "Alice: Cats rule. | Bob: Dogs drool? | Alice: Exactly.",
+            "Pets",
+            None,
+        )
+        .unwrap();
+        let ctx = &imported.context;
+
+        assert_eq!(ctx.message_count(), 3);
+        assert_eq!(ctx.messages[1].content, "Dogs drool?");
+    }
+
+    #[test]
+    fn test_parse_transcript_rejects_unusable_text() {
+        assert!(parse_transcript("just some notes\nwith no speakers", "Topic", None).is_err());
+        assert!(parse_transcript("Alice: only me", "Topic", None).is_err());
+        assert!(parse_transcript("", "Topic", None).is_err());
+    }
+
+    #[test]
+    fn test_parse_transcript_limits() {
+        let text: String = (0..MAX_IMPORTED_MESSAGES + 5)
+            .map(|i| {
+                let speaker = if i % 2 == 0 { "Alice" } else { "Bob" };
+                format!("{speaker}: message {i}\n")
+            })
+            .collect();
+        let imported = parse_transcript(&text, "Topic", None).unwrap();
+        assert_eq!(imported.dropped, 5);
+        assert_eq!(imported.context.message_count(), MAX_IMPORTED_MESSAGES);
+        assert_eq!(imported.context.messages[0].content, "message 5");
+
+        let long = format!(
+            "Alice: {}\nBob: ok",
+            "é".repeat(MAX_IMPORTED_MESSAGE_CHARS + 10)
+        );
+        let imported = parse_transcript(&long, "Topic", None).unwrap();
+        let first = &imported.context.messages[0].content;
+        assert_eq!(first.chars().count(), MAX_IMPORTED_MESSAGE_CHARS);
+        assert!(first.ends_with('…'));
+    }
+
+    #[test]
+    fn test_imported_history_names_speakers() {
+        let imported =
+            parse_transcript("Alice: Yes.\nBob: No.", "Topic", Some("Be kind".into())).unwrap();
+        assert_eq!(
+            imported.history(),
+            [
+                ("user".to_string(), "Alice: Yes.".to_string()),
+                ("user".to_string(), "Bob: No.".to_string()),
+            ]
+        );
+        assert_eq!(imported.context.rules.as_deref(), Some("Be kind"));
+    }
+}
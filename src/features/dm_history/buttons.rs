@@ -0,0 +1,201 @@
+//! Recap and resume DM sessions picked from /history
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::{error, info};
+use serenity::model::application::interaction::message_component::MessageComponentInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::prelude::Context;
+
+use super::{
+    resume_button, session_line, session_title, SessionWriter, DM_HISTORY_FEATURE, RECAP_MESSAGES,
+    RESUME_MESSAGES,
+};
+use crate::commands::components::{ComponentHandler, ComponentId};
+use crate::commands::InteractionResponder;
+use crate::database::{Database, StoredMessage};
+
+/// Routes the /history select menu and resume button
+pub struct HistoryMenu {
+    database: Database,
+    writer: SessionWriter,
+}
+
+impl HistoryMenu {
+    pub fn new(database: Database, writer: SessionWriter) -> Self {
+        Self { database, writer }
+    }
+
+    /// Show a recap of the chosen session with a resume button
+    async fn handle_view(
+        &self,
+        ctx: &Context,
+        interaction: &MessageComponentInteraction,
+        session_id: &str,
+    ) -> Result<()> {
+        let user_id = interaction.user.id.to_string();
+        let Some(session) = self
+            .database
+            .get_dm_session_summary(&user_id, session_id)
+            .await?
+        else {
+            return InteractionResponder::for_component(interaction)
+                .reply_ephemeral(ctx, "That conversation no longer exists.")
+                .await;
+        };
+        let messages = self
+            .database
+            .get_dm_session_messages(&user_id, session_id, RECAP_MESSAGES)
+            .await?;
+        if messages.is_empty() {
+            return InteractionResponder::for_component(interaction)
+                .reply_ephemeral(
+                    ctx,
+                    "I don't have any stored messages from that conversation (DM history may have been off or cleared).",
+                )
+                .await;
+        }
+
+        // Writing the recap can take longer than Discord's 3 second window
+        interaction
+            .create_interaction_response(&ctx.http, |response| {
+                response
+                    .kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                    .interaction_response_data(|message| message.ephemeral(true))
+            })
+            .await?;
+
+        info!("📜 /history recap | User: {user_id} | Session: {session_id}");
+        match self.writer.recap(&user_id, &messages).await {
+            Ok(recap) => {
+                let components = resume_button(session_id);
+                interaction
+                    .edit_original_interaction_response(&ctx.http, |response| {
+                        response
+                            .content(format!("{}\n\n{recap}", session_line(&session)))
+                            .set_components(components)
+                    })
+                    .await?;
+            }
+            Err(e) => {
+                error!("Failed to recap DM session {session_id}: {e}");
+                interaction
+                    .edit_original_interaction_response(&ctx.http, |response| {
+                        response.content("❌ I couldn't write a recap of that conversation.")
+                    })
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Copy the session's last messages back into the DM history
+    async fn handle_resume(
+        &self,
+        ctx: &Context,
+        interaction: &MessageComponentInteraction,
+        session_id: &str,
+    ) -> Result<()> {
+        let user_id = interaction.user.id.to_string();
+        let Some(session) = self
+            .database
+            .get_dm_session_summary(&user_id, session_id)
+            .await?
+        else {
+            return InteractionResponder::for_component(interaction)
+                .reply_ephemeral(ctx, "That conversation no longer exists.")
+                .await;
+        };
+        let privacy = self.database.get_privacy_settings(&user_id, None).await?;
+        if !privacy.dm_history {
+            return InteractionResponder::for_component(interaction)
+                .reply_ephemeral(
+                    ctx,
+                    "🔒 DM history is off in your `/privacy` settings, so I can't carry a conversation forward.",
+                )
+                .await;
+        }
+        let messages = self
+            .database
+            .get_dm_session_messages(&user_id, session_id, RESUME_MESSAGES)
+            .await?;
+        if messages.is_empty() {
+            return InteractionResponder::for_component(interaction)
+                .reply_ephemeral(
+                    ctx,
+                    "I don't have any stored messages from that conversation to resume.",
+                )
+                .await;
+        }
+
+        let copies: Vec<StoredMessage> = messages
+            .into_iter()
+            .map(|(role, content)| StoredMessage {
+                guild_id: None,
+                user_id: user_id.clone(),
+                channel_id: session.channel_id.clone(),
+                role,
+                content,
+                persona: None,
+            })
+            .collect();
+        self.database.store_messages_batch(&copies).await?;
+        info!(
+            "📜 /history resume | User: {user_id} | Session: {session_id} | Messages: {}",
+            copies.len()
+        );
+
+        interaction
+            .create_interaction_response(&ctx.http, |response| {
+                response
+                    .kind(InteractionResponseType::UpdateMessage)
+                    .interaction_response_data(|message| {
+                        message
+                            .content(format!(
+                                "↩️ Resumed **{}**. Send me a DM to pick up where you left off.",
+                                session_title(&session)
+                            ))
+                            .components(|c| c)
+                    })
+            })
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ComponentHandler for HistoryMenu {
+    fn features(&self) -> &'static [&'static str] {
+        &[DM_HISTORY_FEATURE]
+    }
+
+    async fn handle_component(
+        &self,
+        ctx: &Context,
+        interaction: &MessageComponentInteraction,
+        id: &ComponentId,
+    ) -> Result<()> {
+        match id.action.as_str() {
+            "view" => match interaction.data.values.first() {
+                Some(session_id) => self.handle_view(ctx, interaction, session_id).await,
+                None => {
+                    InteractionResponder::for_component(interaction)
+                        .reply_ephemeral(ctx, "Pick a conversation.")
+                        .await
+                }
+            },
+            "resume" => self.handle_resume(ctx, interaction, &id.payload).await,
+            _ => {
+                InteractionResponder::for_component(interaction)
+                    .reply_ephemeral(ctx, "Unknown history action.")
+                    .await
+            }
+        }
+    }
+}
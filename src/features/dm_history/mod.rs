@@ -0,0 +1,167 @@
+//! # Feature: DM History
+//!
+//! Titles for DM sessions and the `/history` browser. After the first reply
+//! of a DM session the bot writes a short title from the opening exchange;
+//! `/history` lists the user's recent sessions with their titles and dates
+//! and a select menu that shows a recap of the chosen session, with a button
+//! to resume it.
+//!
+//! Sessions come from DM interaction tracking (`dm_sessions`) and messages
+//! from the stored DM history, so users who opted out of either see less.
+//! Resuming copies the session's last messages back into the DM history,
+//! where the next reply picks them up as recent context.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with session titles, /history, recaps and resume
+
+pub mod buttons;
+pub mod writer;
+
+pub use buttons::HistoryMenu;
+pub use writer::SessionWriter;
+
+use serenity::builder::CreateComponents;
+use serenity::model::application::component::ButtonStyle;
+
+use crate::commands::components::ComponentId;
+use crate::database::DmSessionSummary;
+use crate::features::conversation_export::parse_stored_timestamp;
+
+/// Feature id for toggles and the component namespace
+pub const DM_HISTORY_FEATURE: &str = "dm_history";
+
+/// Longest generated title kept
+pub const MAX_TITLE_CHARS: usize = 60;
+
+/// Sessions listed by /history (select menus hold at most 25)
+pub const HISTORY_LIMIT: i64 = 10;
+
+/// Messages read for a recap
+pub const RECAP_MESSAGES: i64 = 40;
+
+/// Messages copied back into the DM history on resume
+pub const RESUME_MESSAGES: i64 = 20;
+
+/// Shown for sessions that haven't been titled yet
+const UNTITLED: &str = "Untitled conversation";
+
+/// Discord's limit on select option labels
+const OPTION_LABEL_LIMIT: usize = 100;
+
+/// A generated title cleaned up for display, or None if nothing is left
+pub fn clean_title(raw: &str) -> Option<String> {
+    let title: String = raw
+        .lines()
+        .map(|line| line.trim_matches(|c: char| c == '"' || c == '*' || c.is_whitespace()))
+        .find(|line| !line.is_empty())?
+        .trim_end_matches('.')
+        .chars()
+        .take(MAX_TITLE_CHARS)
+        .collect();
+    Some(title).filter(|t| !t.is_empty())
+}
+
+/// The session's title, or a placeholder
+pub fn session_title(session: &DmSessionSummary) -> &str {
+    session.title.as_deref().unwrap_or(UNTITLED)
+}
+
+/// One line of the /history list, e.g. `**Brining a turkey** — <t:…:f> · 6 messages`
+pub fn session_line(session: &DmSessionSummary) -> String {
+    let started = parse_stored_timestamp(&session.started_at)
+        .map(|at| format!("<t:{}:f>", at.timestamp()))
+        .unwrap_or_else(|| session.started_at.clone());
+    format!(
+        "**{}** — {started} · {} messages",
+        session_title(session),
+        session.message_count
+    )
+}
+
+/// Select menu of sessions; option values are session ids
+pub fn history_menu(sessions: &[DmSessionSummary]) -> CreateComponents {
+    let mut components = CreateComponents::default();
+    components.create_action_row(|row| {
+        row.create_select_menu(|menu| {
+            menu.custom_id(ComponentId::new(DM_HISTORY_FEATURE, "view", "").to_string())
+                .placeholder("Pick a conversation to recap")
+                .options(|options| {
+                    for session in sessions {
+                        let date = parse_stored_timestamp(&session.started_at)
+                            .map(|at| at.format("%b %-d, %Y").to_string())
+                            .unwrap_or_else(|| session.started_at.clone());
+                        options.create_option(|option| {
+                            option
+                                .label(
+                                    session_title(session)
+                                        .chars()
+                                        .take(OPTION_LABEL_LIMIT)
+                                        .collect::<String>(),
+                                )
+                                .value(&session.session_id)
+                                .description(format!("{date} · {} messages", session.message_count))
+                        });
+                    }
+                    options
+                })
+        })
+    });
+    components
+}
+
+/// Button resuming a session
+pub fn resume_button(session_id: &str) -> CreateComponents {
+    let mut components = CreateComponents::default();
+    components.create_action_row(|row| {
+        row.create_button(|btn| {
+            btn.custom_id(ComponentId::new(DM_HISTORY_FEATURE, "resume", session_id).to_string())
+                .label("Resume this conversation")
+                .emoji('↩')
+                .style(ButtonStyle::Primary)
+        })
+    });
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(title: Option<&str>) -> DmSessionSummary {
+        DmSessionSummary {
+            session_id: "s1".to_string(),
+            channel_id: "dm1".to_string(),
+            title: title.map(str::to_string),
+            started_at: "2025-10-09 08:53:20".to_string(),
+            ended_at: None,
+            message_count: 6,
+        }
+    }
+
+    #[test]
+    fn test_clean_title() {
+        assert_eq!(
+            clean_title("\"Brining a turkey.\"\n"),
+            Some("Brining a turkey".to_string())
+        );
+        assert_eq!(
+            clean_title("\n**Rust lifetimes**\nExtra"),
+            Some("Rust lifetimes".to_string())
+        );
+        assert_eq!(clean_title(" \"\" "), None);
+        assert_eq!(clean_title(&"a".repeat(80)).unwrap().len(), MAX_TITLE_CHARS);
+    }
+
+    #[test]
+    fn test_session_line() {
+        assert_eq!(
+            session_line(&session(Some("Brining a turkey"))),
+            "**Brining a turkey** — <t:1760000000:f> · 6 messages"
+        );
+        assert!(session_line(&session(None)).starts_with("**Untitled conversation**"));
+    }
+}
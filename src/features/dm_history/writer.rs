@@ -0,0 +1,182 @@
+//! Write DM session titles and recaps
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use openai::chat::ChatCompletionMessageRole;
+use std::sync::Arc;
+
+use super::clean_title;
+use crate::core::{chat_message, ChatClient};
+use crate::features::analytics::{CostBucket, UsageTracker};
+
+/// Cap on generated tokens for a title
+const MAX_TITLE_TOKENS: u64 = 30;
+
+/// Cap on generated tokens for a recap
+const MAX_RECAP_TOKENS: u64 = 400;
+
+/// Characters of each message passed to the model
+const MAX_EXCERPT_CHARS: usize = 1000;
+
+const TITLE_PROMPT: &str = "You name conversations. Reply with only a short title \
+    (at most 6 words) for the conversation below: no quotes, no trailing period, no emoji.";
+
+const RECAP_PROMPT: &str = "You summarize a past conversation between a user and an assistant \
+    so the user can pick it up again. Write a short recap: one sentence on the topic, then up to \
+    five bullet points covering what was asked, what was answered and anything left open. \
+    Address the user as \"you\".";
+
+/// Writes session titles and recaps through the shared chat client
+#[derive(Clone)]
+pub struct SessionWriter {
+    chat_client: Arc<dyn ChatClient>,
+    openai_model: String,
+    usage_tracker: UsageTracker,
+}
+
+impl SessionWriter {
+    pub fn new(
+        chat_client: Arc<dyn ChatClient>,
+        openai_model: String,
+        usage_tracker: UsageTracker,
+    ) -> Self {
+        Self {
+            chat_client,
+            openai_model,
+            usage_tracker,
+        }
+    }
+
+    /// Title a session from its opening exchange
+    pub async fn title(&self, user_id: &str, user_message: &str, reply: &str) -> Result<String> {
+        let exchange = transcript(&[
+            ("user".to_string(), user_message.to_string()),
+            ("assistant".to_string(), reply.to_string()),
+        ]);
+        let raw = self
+            .complete(user_id, TITLE_PROMPT, exchange, MAX_TITLE_TOKENS)
+            .await?;
+        clean_title(&raw).ok_or_else(|| anyhow::anyhow!("Empty completion"))
+    }
+
+    /// Recap a session's messages (oldest first)
+    pub async fn recap(&self, user_id: &str, messages: &[(String, String)]) -> Result<String> {
+        let recap = self
+            .complete(
+                user_id,
+                RECAP_PROMPT,
+                transcript(messages),
+                MAX_RECAP_TOKENS,
+            )
+            .await?;
+        let recap = recap.trim();
+        if recap.is_empty() {
+            anyhow::bail!("Empty completion");
+        }
+        Ok(recap.to_string())
+    }
+
+    async fn complete(
+        &self,
+        user_id: &str,
+        system_prompt: &str,
+        conversation: String,
+        max_tokens: u64,
+    ) -> Result<String> {
+        let completion = self
+            .chat_client
+            .create_chat_completion_limited(
+                &self.openai_model,
+                vec![
+                    chat_message(ChatCompletionMessageRole::System, system_prompt.to_string()),
+                    chat_message(ChatCompletionMessageRole::User, conversation),
+                ],
+                Some(max_tokens),
+            )
+            .await?;
+
+        if let Some(usage) = &completion.usage {
+            self.usage_tracker.log_chat(
+                &self.openai_model,
+                usage.prompt_tokens,
+                usage.completion_tokens,
+                usage.total_tokens,
+                user_id,
+                None,
+                None,
+                None,
+                CostBucket::DmHistory,
+            );
+        }
+
+        Ok(completion
+            .choices
+            .first()
+            .and_then(|choice| choice.message.content.clone())
+            .unwrap_or_default())
+    }
+}
+
+/// Messages as a `User:` / `Assistant:` transcript
+fn transcript(messages: &[(String, String)]) -> String {
+    messages
+        .iter()
+        .map(|(role, content)| {
+            let speaker = if role == "assistant" {
+                "Assistant"
+            } else {
+                "User"
+            };
+            let excerpt: String = content.chars().take(MAX_EXCERPT_CHARS).collect();
+            format!("{speaker}: {excerpt}")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use crate::testing::MockChatClient;
+
+    #[test]
+    fn test_transcript() {
+        assert_eq!(
+            transcript(&[
+                ("user".to_string(), "Hi".to_string()),
+                ("assistant".to_string(), "Hello!".to_string()),
+            ]),
+            "User: Hi\n\nAssistant: Hello!"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_title_is_cleaned_and_capped() {
+        let database = Database::new(":memory:").await.unwrap();
+        let client = Arc::new(MockChatClient::with_default_reply("\"Brining a turkey.\""));
+        let writer = SessionWriter::new(
+            client.clone(),
+            "gpt-test".to_string(),
+            UsageTracker::new(database),
+        );
+
+        let title = writer
+            .title("u1", "How do I brine a turkey?", "Salt, water, time.")
+            .await
+            .unwrap();
+        assert_eq!(title, "Brining a turkey");
+
+        let request = &client.requests()[0];
+        assert_eq!(request.max_tokens, Some(MAX_TITLE_TOKENS));
+        assert_eq!(
+            request.last_user_message(),
+            Some("User: How do I brine a turkey?\n\nAssistant: Salt, water, time.")
+        );
+    }
+}
@@ -0,0 +1,226 @@
+//! # Feature: Guild Glossary
+//!
+//! Server-specific terms and acronyms defined by admins with `/glossary add`.
+//! When a message sent to a persona mentions a glossary term (as a whole
+//! word, ignoring case), its definition is added to the system prompt so the
+//! answer uses the community's meaning rather than a guess.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with /glossary add, list and remove and prompt grounding
+
+use anyhow::Result;
+
+use crate::database::{Database, GlossaryEntry};
+
+/// Feature id for toggles
+pub const GLOSSARY_FEATURE: &str = "glossary";
+
+/// Longest term accepted
+pub const MAX_TERM_CHARS: usize = 50;
+
+/// Longest definition accepted
+pub const MAX_DEFINITION_CHARS: usize = 500;
+
+/// Terms a guild's glossary may hold
+pub const MAX_TERMS_PER_GUILD: i64 = 200;
+
+/// Most definitions added to one prompt
+pub const MAX_MATCHED_TERMS: usize = 10;
+
+const GLOSSARY_HEADER: &str = "## Server Glossary\n\
+These terms have a specific meaning in this community. When they come up, use these definitions \
+rather than any general meaning:";
+
+/// Trim and check a glossary term
+pub fn validate_term(input: &str) -> Result<String, String> {
+    let term = input.split_whitespace().collect::<Vec<_>>().join(" ");
+    if term.is_empty() || term.chars().count() > MAX_TERM_CHARS {
+        return Err(format!("Terms must be 1-{MAX_TERM_CHARS} characters."));
+    }
+    if !term.chars().any(char::is_alphanumeric) {
+        return Err("Terms need at least one letter or digit.".to_string());
+    }
+    Ok(term)
+}
+
+/// Trim and check a definition's length
+pub fn validate_definition(input: &str) -> Result<String, String> {
+    let definition = input.trim();
+    if definition.is_empty() {
+        return Err("The definition can't be empty.".to_string());
+    }
+    if definition.chars().count() > MAX_DEFINITION_CHARS {
+        return Err(format!(
+            "Definitions must be at most {MAX_DEFINITION_CHARS} characters."
+        ));
+    }
+    Ok(definition.to_string())
+}
+
+/// Whether `term` appears in `text` as a whole word, ignoring case
+fn mentions(text: &str, term: &str) -> bool {
+    let text = text.to_lowercase();
+    let term = term.to_lowercase();
+    let mut start = 0;
+    while let Some(found) = text[start..].find(&term) {
+        let at = start + found;
+        let end = at + term.len();
+        let boundary_before = text[..at]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !c.is_alphanumeric());
+        let boundary_after = text[end..]
+            .chars()
+            .next()
+            .is_none_or(|c| !c.is_alphanumeric());
+        if boundary_before && boundary_after {
+            return true;
+        }
+        start = at + term.chars().next().map_or(1, char::len_utf8);
+    }
+    false
+}
+
+/// Entries whose term appears in the message, at most `MAX_MATCHED_TERMS`
+pub fn matching_entries<'a>(entries: &'a [GlossaryEntry], message: &str) -> Vec<&'a GlossaryEntry> {
+    entries
+        .iter()
+        .filter(|entry| mentions(message, &entry.term))
+        .take(MAX_MATCHED_TERMS)
+        .collect()
+}
+
+/// One line of `/glossary list` and of the prompt section
+pub fn list_line(entry: &GlossaryEntry) -> String {
+    format!("- **{}**: {}", entry.term, entry.definition)
+}
+
+/// System prompt section defining the matched terms, if there are any
+pub fn glossary_section(entries: &[&GlossaryEntry]) -> Option<String> {
+    if entries.is_empty() {
+        return None;
+    }
+    let lines = entries
+        .iter()
+        .map(|entry| list_line(entry))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(format!("{GLOSSARY_HEADER}\n{lines}"))
+}
+
+/// The system prompt with definitions of the glossary terms the message mentions
+pub async fn apply_glossary(
+    database: &Database,
+    guild_id: Option<&str>,
+    user_message: &str,
+    system_prompt: String,
+) -> Result<String> {
+    let Some(guild_id) = guild_id else {
+        return Ok(system_prompt);
+    };
+    if !database
+        .is_feature_enabled(GLOSSARY_FEATURE, None, Some(guild_id))
+        .await?
+    {
+        return Ok(system_prompt);
+    }
+    let entries = database.get_glossary_terms(guild_id).await?;
+    let matched = matching_entries(&entries, user_message);
+    Ok(match glossary_section(&matched) {
+        Some(section) => format!("{system_prompt}\n\n{section}"),
+        None => system_prompt,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(term: &str, definition: &str) -> GlossaryEntry {
+        GlossaryEntry {
+            guild_id: "g1".to_string(),
+            term: term.to_string(),
+            definition: definition.to_string(),
+            created_by: "u1".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_validate_term() {
+        assert_eq!(
+            validate_term("  Raid   Night "),
+            Ok("Raid Night".to_string())
+        );
+        assert_eq!(validate_term("C++"), Ok("C++".to_string()));
+        assert!(validate_term("   ").is_err());
+        assert!(validate_term("???").is_err());
+        assert!(validate_term(&"a".repeat(MAX_TERM_CHARS + 1)).is_err());
+        assert!(validate_definition(" ").is_err());
+        assert!(validate_definition(&"a".repeat(MAX_DEFINITION_CHARS + 1)).is_err());
+    }
+
+    #[test]
+    fn test_mentions_whole_words() {
+        assert!(mentions("Anyone up for pvp tonight?", "PvP"));
+        assert!(mentions("Is Raid Night on Friday", "raid night"));
+        assert!(mentions("(GM)", "GM"));
+        assert!(mentions("What's new in C++?", "C++"));
+        assert!(!mentions("Get some sleep", "GM"));
+        assert!(!mentions("pvpers unite", "PvP"));
+        assert!(mentions("ÉTÉ starts soon, été!", "été"));
+    }
+
+    #[test]
+    fn test_matching_entries() {
+        let entries = vec![
+            entry("GM", "Guild master"),
+            entry("Nexus", "Our staging server"),
+            entry("PvP", "Player versus player"),
+        ];
+        let matched: Vec<&str> = matching_entries(&entries, "Ask the GM if nexus is up")
+            .iter()
+            .map(|e| e.term.as_str())
+            .collect();
+        assert_eq!(matched, ["GM", "Nexus"]);
+        assert!(matching_entries(&entries, "Hello there").is_empty());
+
+        let many: Vec<GlossaryEntry> = (0..20).map(|i| entry(&format!("t{i}"), "x")).collect();
+        let message = (0..20)
+            .map(|i| format!("t{i}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        assert_eq!(matching_entries(&many, &message).len(), MAX_MATCHED_TERMS);
+    }
+
+    #[tokio::test]
+    async fn test_apply_glossary() {
+        let db = Database::new(":memory:").await.unwrap();
+        db.upsert_glossary_term(&entry("Nexus", "Our staging server"))
+            .await
+            .unwrap();
+
+        let prompt = apply_glossary(&db, Some("g1"), "Is nexus down?", "Base".to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            prompt,
+            format!("Base\n\n{GLOSSARY_HEADER}\n- **Nexus**: Our staging server")
+        );
+        assert_eq!(
+            apply_glossary(&db, Some("g1"), "Hello", "Base".to_string())
+                .await
+                .unwrap(),
+            "Base"
+        );
+        assert_eq!(
+            apply_glossary(&db, None, "Is nexus down?", "Base".to_string())
+                .await
+                .unwrap(),
+            "Base"
+        );
+    }
+}
@@ -0,0 +1,395 @@
+//! # Feature: Guardrail Profiles
+//!
+//! Per-guild safety levels. A guild picks `strict`, `standard` or `relaxed`
+//! with `/guardrails set`, and the profile is applied in one place for each
+//! concern so every generation path behaves the same:
+//! - The safety preamble is prepended to the system prompt by the shared
+//!   generation paths (`get_ai_response`, council, story and debate turns)
+//!   and, through [`ChannelGrounding`], by every writer that posts generated
+//!   text in a channel (auto-responses, language nudges, verification
+//!   greetings, send-later rewrites, question of the day, channel topics,
+//!   link previews, server reports and birthday wishes)
+//! - Persona resolution in the database falls back from personas the
+//!   profile doesn't allow, and explicit persona choices are refused
+//! - Conflict mediation uses the profile's threshold unless the guild set
+//!   `conflict_sensitivity` itself
+//!
+//! `standard` is the bot's normal behaviour and adds nothing. Channels marked
+//! NSFW can use a profile of their own and refuse `/imagine` (see `nsfw`).
+//!
+//! - **Version**: 1.2.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.2.0: ChannelGrounding applies the channel's profile and scene to every channel writer
+//! - 1.1.0: NSFW channel awareness with a separate profile and /imagine policy
+//! - 1.0.0: Initial release with strict, standard and relaxed profiles
+
+pub mod nsfw;
+
+pub use nsfw::{
+    imagine_allowed, nsfw_channels, NsfwChannels, NSFW_IMAGINE_SETTING, NSFW_PROFILE_SETTING,
+};
+
+use anyhow::Result;
+
+use crate::database::Database;
+use crate::features::scenes::{channel_scene, with_scene};
+
+/// Feature id for toggles
+pub const GUARDRAILS_FEATURE: &str = "guardrails";
+
+/// Guild setting holding the profile name
+pub const GUARDRAIL_SETTING: &str = "guardrail_profile";
+
+/// Personas unavailable under the strict profile
+pub const STRICT_BLOCKED_PERSONAS: &[&str] = &["noir"];
+
+/// Persona used instead of one the profile doesn't allow
+pub const FALLBACK_PERSONA: &str = "obi";
+
+const STRICT_PREAMBLE: &str = "## Safety Profile: Strict\n\
+This server uses strict content guidelines. Keep every reply suitable for all ages: \
+no profanity, sexual content, graphic violence, or instructions for dangerous or illegal activities. \
+If asked for any of these, decline briefly and in character, and offer a safe alternative. \
+Treat medical, legal and financial questions as general information and suggest a qualified professional.";
+
+const RELAXED_PREAMBLE: &str = "## Safety Profile: Relaxed\n\
+This server is for adults and allows a relaxed tone. Casual profanity, dark humour and mature themes \
+in fiction are fine when the conversation calls for them. \
+Never produce harassment or hate toward real people, sexual content involving minors, \
+or instructions that could cause serious harm.";
+
+/// A guild's safety level
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GuardrailProfile {
+    Strict,
+    #[default]
+    Standard,
+    Relaxed,
+}
+
+impl GuardrailProfile {
+    /// All profiles, strictest first
+    pub const ALL: [Self; 3] = [Self::Strict, Self::Standard, Self::Relaxed];
+
+    /// Parse a profile name, ignoring case
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "strict" => Some(Self::Strict),
+            "standard" => Some(Self::Standard),
+            "relaxed" => Some(Self::Relaxed),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Strict => "strict",
+            Self::Standard => "standard",
+            Self::Relaxed => "relaxed",
+        }
+    }
+
+    /// One-line summary for `/guardrails show`
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Strict => {
+                "All-ages replies, more sensitive conflict mediation, some personas unavailable"
+            }
+            Self::Standard => "The bot's default behaviour",
+            Self::Relaxed => {
+                "Mature themes and casual language allowed, less sensitive conflict mediation"
+            }
+        }
+    }
+
+    /// Safety preamble for the system prompt, if the profile has one
+    pub fn preamble(&self) -> Option<&'static str> {
+        match self {
+            Self::Strict => Some(STRICT_PREAMBLE),
+            Self::Standard => None,
+            Self::Relaxed => Some(RELAXED_PREAMBLE),
+        }
+    }
+
+    /// The system prompt with the profile's preamble in front
+    pub fn apply(&self, system_prompt: &str) -> String {
+        match self.preamble() {
+            Some(preamble) => format!("{preamble}\n\n{system_prompt}"),
+            None => system_prompt.to_string(),
+        }
+    }
+
+    /// Conflict detection threshold, or None to keep the configured default
+    pub fn conflict_threshold(&self) -> Option<f32> {
+        match self {
+            Self::Strict => Some(0.35),
+            Self::Standard => None,
+            Self::Relaxed => Some(0.7),
+        }
+    }
+
+    /// Whether members can talk to `persona_id` under this profile
+    pub fn allows_persona(&self, persona_id: &str) -> bool {
+        match self {
+            Self::Strict => !STRICT_BLOCKED_PERSONAS.contains(&persona_id),
+            Self::Standard | Self::Relaxed => true,
+        }
+    }
+
+    /// `persona_id`, or the fallback persona if the profile doesn't allow it
+    pub fn resolve_persona(&self, persona_id: String) -> String {
+        if self.allows_persona(&persona_id) {
+            persona_id
+        } else {
+            FALLBACK_PERSONA.to_string()
+        }
+    }
+
+    /// Message refusing a persona the profile doesn't allow
+    pub fn blocked_persona_message(&self, persona_name: &str) -> String {
+        format!(
+            "❌ {persona_name} isn't available on this server's {} safety profile.",
+            self.as_str()
+        )
+    }
+
+    /// The guild's profile; DMs and guilds with the feature off are standard
+    pub async fn for_guild(database: &Database, guild_id: Option<&str>) -> Result<Self> {
+        let Some(guild_id) = guild_id else {
+            return Ok(Self::Standard);
+        };
+        if !database
+            .is_feature_enabled(GUARDRAILS_FEATURE, None, Some(guild_id))
+            .await?
+        {
+            return Ok(Self::Standard);
+        }
+        Ok(database
+            .get_guild_setting(guild_id, GUARDRAIL_SETTING)
+            .await?
+            .as_deref()
+            .and_then(Self::parse)
+            .unwrap_or_default())
+    }
+
+    /// The profile for a channel
+    ///
+    /// NSFW channels use the guild's NSFW profile when one is set; every
+    /// other channel uses the guild's profile.
+    pub async fn for_channel(
+        database: &Database,
+        guild_id: Option<&str>,
+        channel_id: Option<&str>,
+    ) -> Result<Self> {
+        if let (Some(guild), Some(channel)) = (guild_id, channel_id) {
+            if nsfw_channels().is_nsfw(channel)
+                && database
+                    .is_feature_enabled(GUARDRAILS_FEATURE, None, Some(guild))
+                    .await?
+            {
+                if let Some(profile) = database
+                    .get_guild_setting(guild, NSFW_PROFILE_SETTING)
+                    .await?
+                    .as_deref()
+                    .and_then(Self::parse)
+                {
+                    return Ok(profile);
+                }
+            }
+        }
+        Self::for_guild(database, guild_id).await
+    }
+}
+
+/// The system prompt with the safety preamble for `channel_id` applied
+pub async fn apply_guardrails(
+    database: &Database,
+    guild_id: Option<&str>,
+    channel_id: Option<&str>,
+    system_prompt: &str,
+) -> Result<String> {
+    Ok(
+        GuardrailProfile::for_channel(database, guild_id, channel_id)
+            .await?
+            .apply(system_prompt),
+    )
+}
+
+/// What a channel adds to any prompt whose reply is posted there
+///
+/// Writers outside the shared generation paths take one of these so they
+/// can't skip the channel's safety profile (NSFW-aware) or its scene.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChannelGrounding {
+    pub profile: GuardrailProfile,
+    pub scene: Option<String>,
+}
+
+impl ChannelGrounding {
+    /// The grounding for a channel; DMs and unknown channels get the defaults
+    pub async fn for_channel(
+        database: &Database,
+        guild_id: Option<&str>,
+        channel_id: Option<&str>,
+    ) -> Result<Self> {
+        Ok(Self {
+            profile: GuardrailProfile::for_channel(database, guild_id, channel_id).await?,
+            scene: channel_scene(database, guild_id, channel_id).await?,
+        })
+    }
+
+    /// A persona's system prompt with the safety preamble in front and the scene after
+    pub fn persona_prompt(&self, system_prompt: &str) -> String {
+        with_scene(self.profile.apply(system_prompt), self.scene.as_deref())
+    }
+
+    /// A prompt not spoken by a persona (summaries, topics) gets only the safety preamble
+    pub fn task_prompt(&self, system_prompt: &str) -> String {
+        self.profile.apply(system_prompt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_parse_round_trip() {
+        for profile in GuardrailProfile::ALL {
+            assert_eq!(GuardrailProfile::parse(profile.as_str()), Some(profile));
+        }
+        assert_eq!(
+            GuardrailProfile::parse(" Strict "),
+            Some(GuardrailProfile::Strict)
+        );
+        assert_eq!(GuardrailProfile::parse("lenient"), None);
+    }
+
+    #[test]
+    fn test_apply_preamble() {
+        assert_eq!(
+            GuardrailProfile::Standard.apply("You are a chef."),
+            "You are a chef."
+        );
+
+        let strict = GuardrailProfile::Strict.apply("You are a chef.");
+        assert!(strict.starts_with("## Safety Profile: Strict"));
+        assert!(strict.ends_with("\n\nYou are a chef."));
+        assert!(GuardrailProfile::Relaxed
+            .apply("You are a chef.")
+            .starts_with("## Safety Profile: Relaxed"));
+    }
+
+    #[test]
+    fn test_persona_availability() {
+        assert!(!GuardrailProfile::Strict.allows_persona("noir"));
+        assert!(GuardrailProfile::Strict.allows_persona("chef"));
+        assert!(GuardrailProfile::Relaxed.allows_persona("noir"));
+        assert_eq!(
+            GuardrailProfile::Strict.resolve_persona("noir".to_string()),
+            FALLBACK_PERSONA
+        );
+        assert_eq!(
+            GuardrailProfile::Standard.resolve_persona("noir".to_string()),
+            "noir"
+        );
+    }
+
+    #[test]
+    fn test_conflict_threshold() {
+        assert_eq!(GuardrailProfile::Strict.conflict_threshold(), Some(0.35));
+        assert_eq!(GuardrailProfile::Standard.conflict_threshold(), None);
+        assert_eq!(GuardrailProfile::Relaxed.conflict_threshold(), Some(0.7));
+    }
+
+    #[tokio::test]
+    async fn test_for_guild() {
+        let db = Database::new(":memory:").await.unwrap();
+        assert_eq!(
+            GuardrailProfile::for_guild(&db, Some("g1")).await.unwrap(),
+            GuardrailProfile::Standard
+        );
+
+        db.set_guild_setting("g1", GUARDRAIL_SETTING, "strict")
+            .await
+            .unwrap();
+        assert_eq!(
+            GuardrailProfile::for_guild(&db, Some("g1")).await.unwrap(),
+            GuardrailProfile::Strict
+        );
+        assert_eq!(
+            GuardrailProfile::for_guild(&db, None).await.unwrap(),
+            GuardrailProfile::Standard
+        );
+        let prompt = apply_guardrails(&db, Some("g1"), None, "Hi").await.unwrap();
+        assert!(prompt.starts_with("## Safety Profile: Strict"));
+    }
+
+    #[tokio::test]
+    async fn test_for_channel_uses_nsfw_profile() {
+        let db = Database::new(":memory:").await.unwrap();
+        let mut channel: serenity::model::channel::GuildChannel =
+            serde_json::from_value(serde_json::json!({
+                "id": "9000000101",
+                "guild_id": "1",
+                "type": 0,
+                "name": "after-dark",
+                "nsfw": true,
+            }))
+            .unwrap();
+        nsfw_channels().record_channel(&channel);
+        channel.id = serenity::model::id::ChannelId(9_000_000_102);
+        channel.nsfw = false;
+        nsfw_channels().record_channel(&channel);
+        db.set_guild_setting("g1", GUARDRAIL_SETTING, "strict")
+            .await
+            .unwrap();
+
+        let profile = |channel| GuardrailProfile::for_channel(&db, Some("g1"), Some(channel));
+        assert_eq!(
+            profile("9000000101").await.unwrap(),
+            GuardrailProfile::Strict
+        );
+
+        db.set_guild_setting("g1", NSFW_PROFILE_SETTING, "relaxed")
+            .await
+            .unwrap();
+        assert_eq!(
+            profile("9000000101").await.unwrap(),
+            GuardrailProfile::Relaxed
+        );
+        assert_eq!(
+            profile("9000000102").await.unwrap(),
+            GuardrailProfile::Strict
+        );
+    }
+
+    #[tokio::test]
+    async fn test_channel_grounding() {
+        let db = Database::new(":memory:").await.unwrap();
+        let plain = ChannelGrounding::for_channel(&db, Some("g1"), Some("c1"))
+            .await
+            .unwrap();
+        assert_eq!(plain, ChannelGrounding::default());
+        assert_eq!(plain.persona_prompt("You are a bard."), "You are a bard.");
+
+        db.set_guild_setting("g1", GUARDRAIL_SETTING, "strict")
+            .await
+            .unwrap();
+        db.set_channel_scene("g1", "c1", Some("A tavern in Eldmoor"))
+            .await
+            .unwrap();
+        let grounding = ChannelGrounding::for_channel(&db, Some("g1"), Some("c1"))
+            .await
+            .unwrap();
+        let persona = grounding.persona_prompt("You are a bard.");
+        assert!(persona.starts_with("## Safety Profile: Strict"));
+        assert!(persona.ends_with("A tavern in Eldmoor"));
+        let task = grounding.task_prompt("Summarize the page.");
+        assert!(task.starts_with("## Safety Profile: Strict"));
+        assert!(task.ends_with("Summarize the page."));
+    }
+}
@@ -0,0 +1,183 @@
+//! # NSFW Channels
+//!
+//! Tracks which channels Discord marks as age-restricted, so the guardrail
+//! profile and `/imagine` can treat them differently. The bot fills the
+//! registry from gateway events (guild create, channel and thread events);
+//! threads follow their parent channel's flag.
+//!
+//! Each guild configures NSFW channels with `/guardrails nsfw`:
+//! - `nsfw_guardrail_profile` is the profile used there instead of the
+//!   server's (unset keeps the server's)
+//! - `nsfw_imagine` allows `/imagine` there (`enabled`); it's refused by
+//!   default
+
+use anyhow::Result;
+use dashmap::{DashMap, DashSet};
+use serenity::model::channel::{Channel, ChannelType, GuildChannel};
+use serenity::model::guild::Guild;
+use std::sync::OnceLock;
+
+use super::GUARDRAILS_FEATURE;
+use crate::database::Database;
+
+/// Guild setting holding the profile used in NSFW channels
+pub const NSFW_PROFILE_SETTING: &str = "nsfw_guardrail_profile";
+
+/// Guild setting allowing /imagine in NSFW channels (`enabled`)
+pub const NSFW_IMAGINE_SETTING: &str = "nsfw_imagine";
+
+/// NSFW flags of the channels the bot can see
+#[derive(Debug, Default)]
+pub struct NsfwChannels {
+    nsfw: DashSet<u64>,
+    thread_parents: DashMap<u64, u64>,
+}
+
+impl NsfwChannels {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a channel's flag, or a thread's parent
+    pub fn record_channel(&self, channel: &GuildChannel) {
+        match channel.kind {
+            ChannelType::PublicThread | ChannelType::PrivateThread | ChannelType::NewsThread => {
+                if let Some(parent_id) = channel.parent_id {
+                    self.thread_parents.insert(channel.id.0, parent_id.0);
+                }
+            }
+            _ if channel.nsfw => {
+                self.nsfw.insert(channel.id.0);
+            }
+            _ => {
+                self.nsfw.remove(&channel.id.0);
+            }
+        }
+    }
+
+    /// Record every channel and active thread of a guild
+    pub fn record_guild(&self, guild: &Guild) {
+        for channel in guild.channels.values() {
+            if let Channel::Guild(channel) = channel {
+                self.record_channel(channel);
+            }
+        }
+        for thread in &guild.threads {
+            self.record_channel(thread);
+        }
+    }
+
+    /// Forget a deleted channel or thread
+    pub fn forget(&self, channel_id: u64) {
+        self.nsfw.remove(&channel_id);
+        self.thread_parents.remove(&channel_id);
+    }
+
+    /// Whether a channel, or a thread's parent, is marked NSFW
+    pub fn is_nsfw(&self, channel_id: &str) -> bool {
+        let Ok(channel_id) = channel_id.parse::<u64>() else {
+            return false;
+        };
+        let channel_id = self
+            .thread_parents
+            .get(&channel_id)
+            .map(|parent| *parent)
+            .unwrap_or(channel_id);
+        self.nsfw.contains(&channel_id)
+    }
+}
+
+/// Global registry shared by the event handler and every generation path
+static NSFW_CHANNELS: OnceLock<NsfwChannels> = OnceLock::new();
+
+/// Get or initialize the global NSFW channel registry
+pub fn nsfw_channels() -> &'static NsfwChannels {
+    NSFW_CHANNELS.get_or_init(NsfwChannels::new)
+}
+
+/// Whether `/imagine` may run in a channel
+///
+/// NSFW channels refuse it unless the guild set `nsfw_imagine` to `enabled`.
+/// With guardrails off, NSFW channels are treated like any other.
+pub async fn imagine_allowed(
+    database: &Database,
+    guild_id: Option<&str>,
+    channel_id: &str,
+) -> Result<bool> {
+    let Some(guild_id) = guild_id else {
+        return Ok(true);
+    };
+    if !nsfw_channels().is_nsfw(channel_id)
+        || !database
+            .is_feature_enabled(GUARDRAILS_FEATURE, None, Some(guild_id))
+            .await?
+    {
+        return Ok(true);
+    }
+    Ok(database
+        .get_guild_setting(guild_id, NSFW_IMAGINE_SETTING)
+        .await?
+        .as_deref()
+        == Some("enabled"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serenity::model::id::ChannelId;
+
+    fn channel(id: u64, kind: ChannelType, nsfw: bool, parent: Option<u64>) -> GuildChannel {
+        let mut channel: GuildChannel = serde_json::from_value(serde_json::json!({
+            "id": id.to_string(),
+            "guild_id": "1",
+            "type": 0,
+            "name": "general",
+        }))
+        .unwrap();
+        channel.kind = kind;
+        channel.nsfw = nsfw;
+        channel.parent_id = parent.map(ChannelId);
+        channel
+    }
+
+    #[test]
+    fn test_threads_follow_their_parent() {
+        let registry = NsfwChannels::new();
+        registry.record_channel(&channel(10, ChannelType::Text, true, None));
+        registry.record_channel(&channel(11, ChannelType::Text, false, None));
+        registry.record_channel(&channel(20, ChannelType::PublicThread, false, Some(10)));
+
+        assert!(registry.is_nsfw("10"));
+        assert!(!registry.is_nsfw("11"));
+        assert!(registry.is_nsfw("20"));
+        assert!(!registry.is_nsfw("not-a-channel"));
+
+        registry.record_channel(&channel(10, ChannelType::Text, false, None));
+        assert!(!registry.is_nsfw("20"));
+
+        registry.record_channel(&channel(10, ChannelType::Text, true, None));
+        registry.forget(20);
+        assert!(!registry.is_nsfw("20"));
+    }
+
+    #[tokio::test]
+    async fn test_imagine_allowed() {
+        let db = Database::new(":memory:").await.unwrap();
+        nsfw_channels().record_channel(&channel(9_000_000_001, ChannelType::Text, true, None));
+
+        assert!(imagine_allowed(&db, Some("g1"), "9000000002")
+            .await
+            .unwrap());
+        assert!(!imagine_allowed(&db, Some("g1"), "9000000001")
+            .await
+            .unwrap());
+        assert!(imagine_allowed(&db, None, "9000000001").await.unwrap());
+
+        db.set_guild_setting("g1", NSFW_IMAGINE_SETTING, "enabled")
+            .await
+            .unwrap();
+        assert!(imagine_allowed(&db, Some("g1"), "9000000001")
+            .await
+            .unwrap());
+    }
+}
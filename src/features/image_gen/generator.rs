@@ -3,13 +3,17 @@
 //! DALL-E 3 powered image creation with configurable size (square, landscape, portrait)
 //! and style (vivid, natural) options.
 //!
-//! - **Version**: 1.0.0
+//! - **Version**: 1.1.1
 //! - **Since**: 0.2.0
 //! - **Toggleable**: true
 //!
 //! ## Changelog
+//! - 1.1.1: DALL-E calls are counted as OpenAI in-flight requests
+//! - 1.1.0: Guarded by the DALL-E circuit breaker
 //! - 1.0.0: Initial release with DALL-E 3 integration
 
+use crate::features::analytics::runtime::runtime_counters;
+use crate::features::resilience::{circuit_breakers, Dependency};
 use anyhow::Result;
 use log::{debug, error, info};
 use serde::{Deserialize, Serialize};
@@ -140,7 +144,10 @@ impl ImageGenerator {
             response_format: "url".to_string(),
         };
 
+        circuit_breakers().check(Dependency::DallE)?;
+
         debug!("Sending request to OpenAI DALL-E API");
+        let _in_flight = runtime_counters().openai_request();
         let response = self
             .client
             .post("https://api.openai.com/v1/images/generations")
@@ -148,9 +155,22 @@ impl ImageGenerator {
             .header("Content-Type", "application/json")
             .json(&request)
             .send()
-            .await?;
+            .await;
+        let response = match response {
+            Ok(response) => response,
+            Err(e) => {
+                circuit_breakers().record_failure(Dependency::DallE, &e.to_string());
+                return Err(e.into());
+            }
+        };
 
         let status = response.status();
+        // Rejected prompts are the caller's problem, not an outage
+        if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            circuit_breakers().record_failure(Dependency::DallE, status.as_str());
+        } else {
+            circuit_breakers().record_success(Dependency::DallE);
+        }
         let response_text = response.text().await?;
 
         if status.is_success() {
@@ -0,0 +1,173 @@
+//! # Architecture Map
+//!
+//! Module/feature graph generated from the feature registry, so `/introspect
+//! overview` always matches what is actually compiled in. Rendered as embed
+//! fields for Discord and as a Mermaid flowchart attachment.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use crate::features::{get_bot_version, get_features, Feature};
+
+/// Embed field values are capped at 1024 characters
+const FIELD_CHARS: usize = 1024;
+
+/// Filename of the Mermaid attachment
+pub const MERMAID_FILENAME: &str = "architecture.mmd";
+
+/// One line per feature: name, version, toggleable flag and dependencies
+fn feature_line(feature: &Feature) -> String {
+    let toggle = if feature.toggleable { " 🔀" } else { "" };
+    let deps = if feature.dependencies.is_empty() {
+        String::new()
+    } else {
+        format!(" ← {}", feature.dependencies.join(", "))
+    };
+    format!("**{}** `{}`{toggle}{deps}", feature.name, feature.version)
+}
+
+/// Plain-text map for prompts: `id vVERSION [toggleable] -> deps`
+pub fn architecture_text() -> String {
+    let mut out = format!("persona v{} feature registry\n", get_bot_version());
+    for feature in get_features() {
+        let toggle = if feature.toggleable {
+            " [toggleable]"
+        } else {
+            ""
+        };
+        let deps = if feature.dependencies.is_empty() {
+            String::new()
+        } else {
+            format!(" -> depends on {}", feature.dependencies.join(", "))
+        };
+        out.push_str(&format!(
+            "- {} v{}{toggle}{deps}: {}\n",
+            feature.id, feature.version, feature.description
+        ));
+    }
+    out
+}
+
+/// Embed fields (name, value) grouped into core and toggleable features
+///
+/// Groups that don't fit one field are split into numbered fields.
+pub fn architecture_fields() -> Vec<(String, String)> {
+    let (toggleable, core): (Vec<&Feature>, Vec<&Feature>) =
+        get_features().iter().partition(|f| f.toggleable);
+
+    let mut fields = Vec::new();
+    for (title, features) in [("Core", core), ("Toggleable 🔀", toggleable)] {
+        let lines: Vec<String> = features.iter().map(|f| feature_line(f)).collect();
+        let chunks = chunk_lines(&lines, FIELD_CHARS);
+        let total = chunks.len();
+        for (i, chunk) in chunks.into_iter().enumerate() {
+            let name = if total > 1 {
+                format!("{title} ({}/{total})", i + 1)
+            } else {
+                title.to_string()
+            };
+            fields.push((name, chunk));
+        }
+    }
+    fields
+}
+
+/// Mermaid flowchart with one node per feature and an edge per dependency
+///
+/// Toggleable features get the `toggleable` class so renderers can style
+/// them differently.
+pub fn architecture_mermaid() -> String {
+    let mut out = String::from("flowchart LR\n");
+    out.push_str(&format!(
+        "    %% persona v{} - generated from the feature registry\n",
+        get_bot_version()
+    ));
+    for feature in get_features() {
+        out.push_str(&format!(
+            "    {}[\"{}<br/>v{}\"]\n",
+            feature.id,
+            feature.name.replace('"', "'"),
+            feature.version
+        ));
+    }
+    for feature in get_features() {
+        for dep in feature.dependencies {
+            out.push_str(&format!("    {} --> {dep}\n", feature.id));
+        }
+    }
+    let toggleable: Vec<&str> = get_features()
+        .iter()
+        .filter(|f| f.toggleable)
+        .map(|f| f.id)
+        .collect();
+    if !toggleable.is_empty() {
+        out.push_str("    classDef toggleable stroke-dasharray: 5 5\n");
+        out.push_str(&format!("    class {} toggleable\n", toggleable.join(",")));
+    }
+    out
+}
+
+/// Group lines into newline-joined chunks of at most `max_chars` each
+fn chunk_lines(lines: &[String], max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for line in lines {
+        if !current.is_empty() && current.len() + 1 + line.len() > max_chars {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push('\n');
+        }
+        current.push_str(line);
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mermaid_has_every_feature_and_dependency() {
+        let mermaid = architecture_mermaid();
+        assert!(mermaid.starts_with("flowchart LR\n"));
+        for feature in get_features() {
+            assert!(mermaid.contains(&format!("    {}[", feature.id)));
+            for dep in feature.dependencies {
+                assert!(mermaid.contains(&format!("    {} --> {dep}\n", feature.id)));
+            }
+        }
+        assert!(mermaid.contains("class ") && mermaid.contains("toggleable"));
+    }
+
+    #[test]
+    fn test_fields_cover_registry_within_embed_limits() {
+        let fields = architecture_fields();
+        let listed: usize = fields.iter().map(|(_, v)| v.lines().count()).sum();
+        assert_eq!(listed, get_features().len());
+        assert!(fields.iter().all(|(_, v)| v.len() <= FIELD_CHARS));
+        assert!(fields[0].0.starts_with("Core"));
+    }
+
+    #[test]
+    fn test_chunk_lines() {
+        let lines: Vec<String> = (0..10).map(|i| format!("line {i}")).collect();
+        let chunks = chunk_lines(&lines, 20);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.len() <= 20));
+        assert_eq!(chunks.join("\n"), lines.join("\n"));
+    }
+
+    #[test]
+    fn test_architecture_text() {
+        let text = architecture_text();
+        assert!(text.contains("- council v"));
+        assert!(text.contains("depends on personas, discussion"));
+    }
+}
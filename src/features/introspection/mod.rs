@@ -2,10 +2,22 @@
 //!
 //! Bot can explain its own internals and architecture.
 //!
-//! - **Version**: 1.0.0
+//! - **Version**: 1.2.1
 //! - **Since**: 0.1.0
 //! - **Toggleable**: false
+//!
+//! ## Changelog
+//! - 1.2.1: /introspect file no longer follows symlinks to files outside the allowlist
+//! - 1.2.0: /introspect file reads allowlisted repository source files
+//! - 1.1.0: Live architecture map generated from the feature registry
+//! - 1.0.0: Initial release
 
+pub mod architecture;
 pub mod service;
+pub mod source;
 
+pub use architecture::{
+    architecture_fields, architecture_mermaid, architecture_text, MERMAID_FILENAME,
+};
 pub use service::get_component_snippet;
+pub use source::{SourceAllowlist, SourceError, SourceSnippet};
@@ -19,7 +19,7 @@ pub fn get_component_snippet(component: &str) -> (&'static str, &'static str) {
 
 pub struct Handler {
     command_handler: Arc<CommandHandler>,      // Processes all user commands
-    component_handler: Arc<MessageComponentHandler>, // Handles buttons, modals
+    components: Arc<ComponentRegistry>,        // Routes buttons, modals
     guild_id: Option<GuildId>,                 // Development mode guild
 }
 
@@ -235,7 +235,8 @@ async fn handle_ai_command(&self, command: &ApplicationCommandInteraction) {
             r#"// SQLite database - my long-term memory
 
 pub struct Database {
-    connection: Arc<Mutex<Connection>>,  // Thread-safe connection
+    connection: Arc<ConnectionPool>,  // Pooled WAL connections with busy timeout
+    settings_cache: Arc<SettingsCache>,  // TTL cache for guild settings and feature flags
 }
 
 // Tables I maintain:
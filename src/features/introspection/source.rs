@@ -0,0 +1,305 @@
+//! # Source Introspection
+//!
+//! Reads repository source files for `/introspect file:<path>` so personas can
+//! explain modules that have no curated snippet. Only paths under an allowlist
+//! (`INTROSPECT_ALLOWLIST`, relative to `INTROSPECT_SOURCE_DIR`) are readable;
+//! traversal, hidden files and symlinks escaping the source root are refused.
+//!
+//! - **Version**: 1.0.1
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.1: Symlinks must land on an allowlisted, non-hidden file inside the root
+//! - 1.0.0: Initial release
+
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+
+/// Allowlist used when INTROSPECT_ALLOWLIST isn't set
+pub const DEFAULT_ALLOWLIST: &[&str] = &["src/", "prompt/", "docs/", "Cargo.toml", "README.md"];
+
+/// Snippets are cut here so the prompt stays well inside the context window
+pub const MAX_SNIPPET_CHARS: usize = 12_000;
+
+/// Why a path can't be introspected
+///
+/// The `Display` text is safe to show to users as-is.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SourceError {
+    /// Absolute, traversing or hidden path
+    InvalidPath(String),
+    NotAllowed(String),
+    NotFound(String),
+    Unreadable(String),
+}
+
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceError::InvalidPath(path) => write!(
+                f,
+                "`{path}` isn't a valid source path. Use a path relative to the repository, like `src/main.rs`."
+            ),
+            SourceError::NotAllowed(path) => {
+                write!(f, "`{path}` isn't on the introspection allowlist.")
+            }
+            SourceError::NotFound(path) => write!(f, "`{path}` doesn't exist."),
+            SourceError::Unreadable(path) => write!(f, "`{path}` couldn't be read as text."),
+        }
+    }
+}
+
+impl std::error::Error for SourceError {}
+
+/// A source file read for introspection
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceSnippet {
+    /// Normalized path relative to the source root
+    pub path: String,
+    pub content: String,
+    pub total_lines: usize,
+    pub truncated: bool,
+}
+
+impl SourceSnippet {
+    /// Code fence language for the file extension
+    pub fn language(&self) -> &'static str {
+        match Path::new(&self.path).extension().and_then(|e| e.to_str()) {
+            Some("rs") => "rust",
+            Some("md") => "markdown",
+            Some("toml") => "toml",
+            Some("json") => "json",
+            Some("sql") => "sql",
+            _ => "",
+        }
+    }
+}
+
+/// Which repository files `/introspect file` may read
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceAllowlist {
+    pub root: PathBuf,
+    /// Entries ending in `/` allow a directory tree, others a single file
+    pub entries: Vec<String>,
+}
+
+impl SourceAllowlist {
+    pub fn new(root: impl Into<PathBuf>, entries: Vec<String>) -> Self {
+        Self {
+            root: root.into(),
+            entries,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let root = std::env::var("INTROSPECT_SOURCE_DIR").unwrap_or_else(|_| ".".to_string());
+        let entries = std::env::var("INTROSPECT_ALLOWLIST")
+            .ok()
+            .map(|v| {
+                v.split(',')
+                    .map(|e| e.trim().to_string())
+                    .filter(|e| !e.is_empty())
+                    .collect()
+            })
+            .unwrap_or_else(|| DEFAULT_ALLOWLIST.iter().map(|e| e.to_string()).collect());
+        Self::new(root, entries)
+    }
+
+    /// Whether a normalized relative path matches an allowlist entry
+    pub fn allows(&self, path: &str) -> bool {
+        self.entries.iter().any(|entry| {
+            if entry.ends_with('/') {
+                path.starts_with(entry.as_str())
+            } else {
+                path == entry
+            }
+        })
+    }
+
+    /// Validate `requested` and resolve it to a file inside the source root
+    ///
+    /// Returns the normalized relative path and the canonical on-disk path.
+    pub fn resolve(&self, requested: &str) -> Result<(String, PathBuf), SourceError> {
+        let path = normalize(requested)?;
+        if !self.allows(&path) {
+            return Err(SourceError::NotAllowed(path));
+        }
+
+        let not_found = || SourceError::NotFound(path.clone());
+        let root = self.root.canonicalize().map_err(|_| not_found())?;
+        let resolved = root.join(&path).canonicalize().map_err(|_| not_found())?;
+        // A symlink inside an allowed directory must not lead out of the repository,
+        // nor to a file the allowlist wouldn't serve under its real name
+        let target = resolved
+            .strip_prefix(&root)
+            .ok()
+            .and_then(Path::to_str)
+            .and_then(|target| normalize(target).ok());
+        match target {
+            Some(target) if self.allows(&target) && resolved.is_file() => Ok((path, resolved)),
+            _ => Err(SourceError::NotAllowed(path)),
+        }
+    }
+
+    /// Read an allowlisted file, capped at `MAX_SNIPPET_CHARS`
+    pub async fn read(&self, requested: &str) -> Result<SourceSnippet, SourceError> {
+        let (path, resolved) = self.resolve(requested)?;
+        let content = tokio::fs::read_to_string(&resolved)
+            .await
+            .map_err(|_| SourceError::Unreadable(path.clone()))?;
+
+        let total_lines = content.lines().count();
+        let truncated = content.len() > MAX_SNIPPET_CHARS;
+        let content = if truncated {
+            let mut end = MAX_SNIPPET_CHARS;
+            while !content.is_char_boundary(end) {
+                end -= 1;
+            }
+            // Cut at a line boundary so the model doesn't see half a statement
+            let end = content[..end].rfind('\n').unwrap_or(end);
+            content[..end].to_string()
+        } else {
+            content
+        };
+
+        Ok(SourceSnippet {
+            path,
+            content,
+            total_lines,
+            truncated,
+        })
+    }
+}
+
+/// `./src\\foo.rs` → `src/foo.rs`; rejects absolute, `..` and hidden components
+fn normalize(requested: &str) -> Result<String, SourceError> {
+    let cleaned = requested.trim().replace('\\', "/");
+    let invalid = || SourceError::InvalidPath(requested.trim().to_string());
+    if cleaned.is_empty() {
+        return Err(invalid());
+    }
+
+    let mut parts = Vec::new();
+    for component in Path::new(&cleaned).components() {
+        match component {
+            Component::CurDir => {}
+            Component::Normal(part) => {
+                let part = part.to_str().ok_or_else(invalid)?;
+                if part.starts_with('.') {
+                    return Err(invalid());
+                }
+                parts.push(part);
+            }
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                return Err(invalid())
+            }
+        }
+    }
+    if parts.is_empty() {
+        return Err(invalid());
+    }
+    Ok(parts.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo_allowlist() -> SourceAllowlist {
+        SourceAllowlist::new(
+            env!("CARGO_MANIFEST_DIR"),
+            DEFAULT_ALLOWLIST.iter().map(|e| e.to_string()).collect(),
+        )
+    }
+
+    #[test]
+    fn test_normalize_rejects_escapes() {
+        assert_eq!(normalize("./src/lib.rs").unwrap(), "src/lib.rs");
+        assert_eq!(normalize(" src\\lib.rs ").unwrap(), "src/lib.rs");
+        for bad in [
+            "",
+            "/etc/passwd",
+            "../secrets",
+            "src/../../x",
+            ".env",
+            "src/.hidden",
+        ] {
+            assert!(
+                matches!(normalize(bad), Err(SourceError::InvalidPath(_))),
+                "{bad} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_allowlist_entries() {
+        let allowlist = repo_allowlist();
+        assert!(allowlist.allows("src/features/mod.rs"));
+        assert!(allowlist.allows("Cargo.toml"));
+        assert!(!allowlist.allows("Cargo.lock"));
+        assert!(!allowlist.allows("srcfoo/x.rs"));
+        assert!(matches!(
+            allowlist.resolve("Cargo.lock"),
+            Err(SourceError::NotAllowed(_))
+        ));
+        assert!(matches!(
+            allowlist.resolve("src/does_not_exist.rs"),
+            Err(SourceError::NotFound(_))
+        ));
+        // Directories aren't files
+        assert!(matches!(
+            allowlist.resolve("src/features"),
+            Err(SourceError::NotAllowed(_))
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_out_of_root_is_refused() {
+        let base = std::env::temp_dir().join(format!("introspect-{}", uuid::Uuid::new_v4()));
+        let root = base.join("repo");
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(base.join("secret.txt"), "token").unwrap();
+        std::os::unix::fs::symlink(base.join("secret.txt"), root.join("src/link.rs")).unwrap();
+
+        let allowlist = SourceAllowlist::new(&root, vec!["src/".to_string()]);
+        let result = allowlist.resolve("src/link.rs");
+        std::fs::remove_dir_all(&base).unwrap();
+        assert!(matches!(result, Err(SourceError::NotAllowed(_))));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_to_unlisted_file_is_refused() {
+        let root = std::env::temp_dir().join(format!("introspect-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join(".env"), "TOKEN=x").unwrap();
+        std::fs::write(root.join("Cargo.lock"), "").unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+        std::os::unix::fs::symlink(root.join(".env"), root.join("src/env.rs")).unwrap();
+        std::os::unix::fs::symlink(root.join("Cargo.lock"), root.join("src/lock.rs")).unwrap();
+        std::os::unix::fs::symlink(root.join("src/main.rs"), root.join("src/alias.rs")).unwrap();
+
+        let allowlist = SourceAllowlist::new(&root, vec!["src/".to_string()]);
+        let env = allowlist.resolve("src/env.rs");
+        let lock = allowlist.resolve("src/lock.rs");
+        let alias = allowlist.resolve("src/alias.rs");
+        std::fs::remove_dir_all(&root).unwrap();
+        assert!(matches!(env, Err(SourceError::NotAllowed(_))));
+        assert!(matches!(lock, Err(SourceError::NotAllowed(_))));
+        assert_eq!(alias.unwrap().0, "src/alias.rs");
+    }
+
+    #[tokio::test]
+    async fn test_read_allowlisted_file() {
+        let snippet = repo_allowlist()
+            .read("src/features/introspection/source.rs")
+            .await
+            .unwrap();
+        assert_eq!(snippet.path, "src/features/introspection/source.rs");
+        assert_eq!(snippet.language(), "rust");
+        assert!(snippet.content.contains("Source Introspection"));
+        assert!(snippet.content.len() <= MAX_SNIPPET_CHARS);
+        assert!(snippet.total_lines > 0);
+    }
+}
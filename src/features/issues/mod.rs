@@ -0,0 +1,452 @@
+//! # Issues Feature
+//!
+//! File Discord messages as Jira or Linear issues with `/ticket` or the
+//! "Create Ticket" message context menu. Each guild configures its own
+//! workspace and API token; the message can be summarized into a title and
+//! description first, and the created issue's link is posted back.
+//!
+//! - **Version**: 1.0.1
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.1: API tokens are masked fully unless long, and kept out of interaction recordings
+//! - 1.0.0: Initial implementation with Jira Cloud and Linear
+
+use anyhow::Result;
+use log::{error, info};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::database::{IssueProvider, IssueTrackerConfig};
+
+/// Feature id for toggles
+pub const ISSUES_FEATURE: &str = "issues";
+
+/// Longest issue title sent to the tracker
+pub const MAX_TITLE_CHARS: usize = 120;
+
+const LINEAR_API_URL: &str = "https://api.linear.app/graphql";
+
+/// Default Jira issue type when the guild didn't pick one
+pub const DEFAULT_JIRA_ISSUE_TYPE: &str = "Task";
+
+/// `/ticket config` option carrying the tracker's API token
+pub const TOKEN_OPTION: &str = "token";
+
+/// Shorter tokens don't get their last characters shown
+const MIN_HINTED_TOKEN_CHARS: usize = 16;
+
+/// Title and description about to be filed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssueDraft {
+    pub title: String,
+    pub description: String,
+}
+
+/// Identifier and browser link of a filed issue
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreatedIssue {
+    pub key: String,
+    pub url: String,
+}
+
+/// Jump link for a guild message
+pub fn message_link(guild_id: &str, channel_id: u64, message_id: u64) -> String {
+    format!("https://discord.com/channels/{guild_id}/{channel_id}/{message_id}")
+}
+
+/// Parse a message link or bare message ID into `(channel, message)`
+///
+/// The channel is only known for links; bare IDs refer to the current channel.
+pub fn parse_message_reference(input: &str) -> Option<(Option<u64>, u64)> {
+    let input = input.trim();
+    if let Ok(id) = input.parse() {
+        return Some((None, id));
+    }
+    let path = input
+        .split("/channels/")
+        .nth(1)?
+        .trim_end_matches('/')
+        .split('/')
+        .collect::<Vec<_>>();
+    match path.as_slice() {
+        [_guild, channel, message] => Some((Some(channel.parse().ok()?), message.parse().ok()?)),
+        _ => None,
+    }
+}
+
+fn truncate_title(title: &str) -> String {
+    let title = title.trim();
+    if title.chars().count() > MAX_TITLE_CHARS {
+        let clipped: String = title.chars().take(MAX_TITLE_CHARS - 1).collect();
+        format!("{}…", clipped.trim_end())
+    } else {
+        title.to_string()
+    }
+}
+
+fn source_footer(author: &str, link: &str) -> String {
+    format!("Reported from Discord by {author}: {link}")
+}
+
+/// File the message as-is: first line as the title, full text as the description
+pub fn draft_from_message(content: &str, author: &str, link: &str) -> IssueDraft {
+    let first_line = content
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("Issue reported from Discord");
+    IssueDraft {
+        title: truncate_title(first_line),
+        description: format!("{}\n\n{}", content.trim(), source_footer(author, link)),
+    }
+}
+
+/// System prompt asking for a ticket title and description as JSON
+pub fn summary_prompt() -> String {
+    "You turn chat messages into issue tracker tickets. Write a short, specific \
+     title (under 100 characters, no trailing period) and a clear description covering \
+     what happened, what was expected, and any steps or details mentioned. Don't invent \
+     facts that aren't in the message.\n\n\
+     Reply with JSON only: {\"title\": \"...\", \"description\": \"...\"}"
+        .to_string()
+}
+
+#[derive(Deserialize)]
+struct RawSummary {
+    title: String,
+    description: String,
+}
+
+/// Read the model's summary, falling back to the unsummarized draft
+pub fn parse_summary(raw: &str, author: &str, link: &str, fallback: IssueDraft) -> IssueDraft {
+    let trimmed = raw.trim();
+    let json = trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|rest| rest.strip_suffix("```"))
+        .unwrap_or(trimmed)
+        .trim();
+    match serde_json::from_str::<RawSummary>(json) {
+        Ok(summary) if !summary.title.trim().is_empty() => IssueDraft {
+            title: truncate_title(&summary.title),
+            description: format!(
+                "{}\n\n{}",
+                summary.description.trim(),
+                source_footer(author, link)
+            ),
+        },
+        _ => fallback,
+    }
+}
+
+/// A token as shown back to admins: its last four characters at most, and
+/// none of a short one
+pub fn mask_token(token: &str) -> String {
+    let chars: Vec<char> = token.chars().collect();
+    if chars.len() < MIN_HINTED_TOKEN_CHARS {
+        return "••••".to_string();
+    }
+    let hint: String = chars[chars.len() - 4..].iter().collect();
+    format!("••••{hint}")
+}
+
+/// Check a config has everything its provider needs
+pub fn validate_config(config: &IssueTrackerConfig) -> Result<(), &'static str> {
+    if config.project.trim().is_empty() || config.api_token.trim().is_empty() {
+        return Err("A project and API token are required.");
+    }
+    if config.provider == IssueProvider::Jira {
+        let base_url = config.base_url.as_deref().unwrap_or_default();
+        if !base_url.starts_with("https://") {
+            return Err("Jira needs your site URL, e.g. `https://acme.atlassian.net`.");
+        }
+        if config.email.as_deref().unwrap_or_default().is_empty() {
+            return Err("Jira needs the email address the API token belongs to.");
+        }
+    }
+    Ok(())
+}
+
+/// Jira Cloud `POST /rest/api/2/issue` body
+pub fn jira_request(config: &IssueTrackerConfig, draft: &IssueDraft) -> Value {
+    json!({
+        "fields": {
+            "project": { "key": config.project },
+            "summary": draft.title,
+            "description": draft.description,
+            "issuetype": {
+                "name": config.issue_type.as_deref().unwrap_or(DEFAULT_JIRA_ISSUE_TYPE)
+            },
+        }
+    })
+}
+
+/// Linear `issueCreate` GraphQL request
+pub fn linear_request(config: &IssueTrackerConfig, draft: &IssueDraft) -> Value {
+    json!({
+        "query": "mutation IssueCreate($input: IssueCreateInput!) { \
+                  issueCreate(input: $input) { success issue { identifier url } } }",
+        "variables": {
+            "input": {
+                "teamId": config.project,
+                "title": draft.title,
+                "description": draft.description,
+            }
+        }
+    })
+}
+
+/// Read the created issue from a Jira response
+pub fn parse_jira_response(base_url: &str, body: &Value) -> Option<CreatedIssue> {
+    let key = body["key"].as_str()?;
+    Some(CreatedIssue {
+        key: key.to_string(),
+        url: format!("{}/browse/{key}", base_url.trim_end_matches('/')),
+    })
+}
+
+/// Read the created issue from a Linear response
+pub fn parse_linear_response(body: &Value) -> Option<CreatedIssue> {
+    let issue = &body["data"]["issueCreate"]["issue"];
+    Some(CreatedIssue {
+        key: issue["identifier"].as_str()?.to_string(),
+        url: issue["url"].as_str()?.to_string(),
+    })
+}
+
+/// First error message in a Jira or Linear error response
+fn error_message(body: &Value) -> Option<String> {
+    body["errorMessages"][0]
+        .as_str()
+        .or_else(|| body["errors"][0]["message"].as_str())
+        .map(str::to_string)
+        .or_else(|| {
+            // Jira reports field problems as {"errors": {"field": "message"}}
+            body["errors"]
+                .as_object()
+                .and_then(|errors| errors.iter().next())
+                .map(|(field, message)| format!("{field}: {}", message.as_str().unwrap_or("")))
+        })
+}
+
+/// Files drafts in a guild's tracker
+#[derive(Clone, Default)]
+pub struct IssueClient {
+    client: reqwest::Client,
+}
+
+impl IssueClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn create_issue(
+        &self,
+        config: &IssueTrackerConfig,
+        draft: &IssueDraft,
+    ) -> Result<CreatedIssue> {
+        let request = match config.provider {
+            IssueProvider::Jira => {
+                let base_url = config.base_url.as_deref().unwrap_or_default();
+                self.client
+                    .post(format!(
+                        "{}/rest/api/2/issue",
+                        base_url.trim_end_matches('/')
+                    ))
+                    .basic_auth(
+                        config.email.as_deref().unwrap_or_default(),
+                        Some(&config.api_token),
+                    )
+                    .json(&jira_request(config, draft))
+            }
+            IssueProvider::Linear => self
+                .client
+                .post(LINEAR_API_URL)
+                .header("Authorization", &config.api_token)
+                .json(&linear_request(config, draft)),
+        };
+
+        let response = request.send().await?;
+        let status = response.status();
+        let body: Value = response.json().await.unwrap_or(Value::Null);
+        let created = match config.provider {
+            IssueProvider::Jira => {
+                parse_jira_response(config.base_url.as_deref().unwrap_or_default(), &body)
+            }
+            IssueProvider::Linear => parse_linear_response(&body),
+        };
+
+        match created {
+            Some(issue) if status.is_success() => {
+                info!(
+                    "🎫 Created {} issue {} for guild {}",
+                    config.provider.key(),
+                    issue.key,
+                    config.guild_id
+                );
+                Ok(issue)
+            }
+            _ => {
+                let message = error_message(&body).unwrap_or_else(|| status.to_string());
+                error!(
+                    "🎫 {} rejected issue for guild {}: {message}",
+                    config.provider.key(),
+                    config.guild_id
+                );
+                Err(anyhow::anyhow!("{message}"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jira() -> IssueTrackerConfig {
+        IssueTrackerConfig {
+            guild_id: "g1".to_string(),
+            provider: IssueProvider::Jira,
+            project: "OPS".to_string(),
+            api_token: "token".to_string(),
+            base_url: Some("https://acme.atlassian.net/".to_string()),
+            email: Some("bot@acme.test".to_string()),
+            issue_type: Some("Bug".to_string()),
+            summarize: true,
+        }
+    }
+
+    #[test]
+    fn test_parse_message_reference() {
+        assert_eq!(parse_message_reference(" 42 "), Some((None, 42)));
+        assert_eq!(
+            parse_message_reference("https://discord.com/channels/1/2/3"),
+            Some((Some(2), 3))
+        );
+        assert_eq!(
+            parse_message_reference("https://ptb.discord.com/channels/1/2/3/"),
+            Some((Some(2), 3))
+        );
+        assert_eq!(
+            parse_message_reference("https://discord.com/channels/1/2"),
+            None
+        );
+        assert_eq!(parse_message_reference("hello"), None);
+    }
+
+    #[test]
+    fn test_draft_from_message_uses_first_line() {
+        let draft = draft_from_message(
+            "\nLogin fails on Safari\nSteps: open /login, submit",
+            "ada",
+            "https://discord.com/channels/1/2/3",
+        );
+        assert_eq!(draft.title, "Login fails on Safari");
+        assert!(draft
+            .description
+            .starts_with("Login fails on Safari\nSteps"));
+        assert!(draft
+            .description
+            .ends_with("Reported from Discord by ada: https://discord.com/channels/1/2/3"));
+
+        let long = draft_from_message(&"x".repeat(300), "ada", "link");
+        assert_eq!(long.title.chars().count(), MAX_TITLE_CHARS);
+    }
+
+    #[test]
+    fn test_parse_summary_falls_back_on_bad_json() {
+        let fallback = draft_from_message("it broke", "ada", "link");
+        let summary = parse_summary(
+            "```json\n{\"title\": \"Checkout crashes\", \"description\": \"Steps...\"}\n```",
+            "ada",
+            "link",
+            fallback.clone(),
+        );
+        assert_eq!(summary.title, "Checkout crashes");
+        assert_eq!(
+            summary.description,
+            "Steps...\n\nReported from Discord by ada: link"
+        );
+        assert_eq!(
+            parse_summary("Sure! Here's a ticket", "ada", "link", fallback.clone()),
+            fallback
+        );
+    }
+
+    #[test]
+    fn test_mask_token() {
+        assert_eq!(mask_token("lin_api_0123456789ABCD"), "••••ABCD");
+        assert_eq!(mask_token("short-secret"), "••••");
+        assert_eq!(mask_token(""), "••••");
+    }
+
+    #[test]
+    fn test_validate_config() {
+        assert!(validate_config(&jira()).is_ok());
+        let no_url = IssueTrackerConfig {
+            base_url: Some("acme.atlassian.net".to_string()),
+            ..jira()
+        };
+        assert!(validate_config(&no_url).is_err());
+        let no_email = IssueTrackerConfig {
+            email: None,
+            ..jira()
+        };
+        assert!(validate_config(&no_email).is_err());
+        let linear = IssueTrackerConfig {
+            provider: IssueProvider::Linear,
+            base_url: None,
+            email: None,
+            ..jira()
+        };
+        assert!(validate_config(&linear).is_ok());
+    }
+
+    #[test]
+    fn test_request_bodies() {
+        let draft = IssueDraft {
+            title: "Checkout crashes".to_string(),
+            description: "Steps".to_string(),
+        };
+        let body = jira_request(&jira(), &draft);
+        assert_eq!(body["fields"]["project"]["key"], "OPS");
+        assert_eq!(body["fields"]["summary"], "Checkout crashes");
+        assert_eq!(body["fields"]["issuetype"]["name"], "Bug");
+
+        let linear = IssueTrackerConfig {
+            provider: IssueProvider::Linear,
+            ..jira()
+        };
+        let body = linear_request(&linear, &draft);
+        assert_eq!(body["variables"]["input"]["teamId"], "OPS");
+        assert!(body["query"].as_str().unwrap().contains("issueCreate"));
+    }
+
+    #[test]
+    fn test_parse_responses() {
+        let jira_body = json!({"id": "10001", "key": "OPS-12", "self": "..."});
+        assert_eq!(
+            parse_jira_response("https://acme.atlassian.net/", &jira_body),
+            Some(CreatedIssue {
+                key: "OPS-12".to_string(),
+                url: "https://acme.atlassian.net/browse/OPS-12".to_string(),
+            })
+        );
+
+        let linear_body = json!({"data": {"issueCreate": {"success": true, "issue": {
+            "identifier": "ENG-7", "url": "https://linear.app/acme/issue/ENG-7"
+        }}}});
+        assert_eq!(parse_linear_response(&linear_body).unwrap().key, "ENG-7");
+
+        assert_eq!(
+            error_message(&json!({"errorMessages": [], "errors": {"summary": "required"}})),
+            Some("summary: required".to_string())
+        );
+        assert_eq!(
+            error_message(&json!({"errors": [{"message": "Authentication required"}]})),
+            Some("Authentication required".to_string())
+        );
+    }
+}
@@ -0,0 +1,97 @@
+//! Per-channel daily nudge cap and per-member cooldown
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use chrono::{DateTime, NaiveDate, Utc};
+use dashmap::DashMap;
+use std::collections::HashMap;
+
+use super::{MAX_NUDGES_PER_DAY, USER_COOLDOWN};
+
+/// Outcome of asking the gate for a nudge
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NudgeDecision {
+    /// Post the nudge
+    Allow,
+    /// The channel has had its nudges for today (UTC)
+    DailyCap,
+    /// This member was nudged in the channel recently
+    UserCooldown,
+}
+
+#[derive(Default)]
+struct ChannelNudges {
+    day: Option<NaiveDate>,
+    sent_today: usize,
+    last_nudged: HashMap<u64, DateTime<Utc>>,
+}
+
+/// Tracks how many nudges each channel has had today and who got them
+#[derive(Default)]
+pub struct NudgeGate {
+    channels: DashMap<u64, ChannelNudges>,
+}
+
+impl NudgeGate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decide whether `user_id` may be nudged in `channel_id`, counting it if so
+    pub fn check(&self, channel_id: u64, user_id: u64) -> NudgeDecision {
+        self.check_at(channel_id, user_id, Utc::now())
+    }
+
+    fn check_at(&self, channel_id: u64, user_id: u64, now: DateTime<Utc>) -> NudgeDecision {
+        let mut channel = self.channels.entry(channel_id).or_default();
+        let today = now.date_naive();
+        if channel.day != Some(today) {
+            channel.day = Some(today);
+            channel.sent_today = 0;
+        }
+        let cooldown = chrono::Duration::from_std(USER_COOLDOWN).unwrap_or_default();
+        channel.last_nudged.retain(|_, at| now - *at < cooldown);
+
+        if channel.last_nudged.contains_key(&user_id) {
+            return NudgeDecision::UserCooldown;
+        }
+        if channel.sent_today >= MAX_NUDGES_PER_DAY {
+            return NudgeDecision::DailyCap;
+        }
+        channel.sent_today += 1;
+        channel.last_nudged.insert(user_id, now);
+        NudgeDecision::Allow
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_gate_cooldown_and_daily_cap() {
+        let gate = NudgeGate::new();
+        let start = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        assert_eq!(gate.check_at(1, 10, start), NudgeDecision::Allow);
+        assert_eq!(gate.check_at(1, 10, start), NudgeDecision::UserCooldown);
+        // Other channels have their own cap and cooldowns
+        assert_eq!(gate.check_at(2, 10, start), NudgeDecision::Allow);
+
+        for user in 11..10 + MAX_NUDGES_PER_DAY as u64 {
+            assert_eq!(gate.check_at(1, user, start), NudgeDecision::Allow);
+        }
+        assert_eq!(gate.check_at(1, 99, start), NudgeDecision::DailyCap);
+
+        // The cooldown ends but the cap holds for the rest of the day
+        let later = start + chrono::Duration::from_std(USER_COOLDOWN).unwrap();
+        assert_eq!(gate.check_at(1, 10, later), NudgeDecision::DailyCap);
+
+        let tomorrow = Utc.with_ymd_and_hms(2024, 5, 2, 0, 0, 0).unwrap();
+        assert_eq!(gate.check_at(1, 99, tomorrow), NudgeDecision::Allow);
+    }
+}
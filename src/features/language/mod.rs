@@ -0,0 +1,217 @@
+//! # Language Enforcement Feature
+//!
+//! Opt-in per channel (`/set_channel language <code>`): plain messages that
+//! are clearly written in another supported language get a gentle,
+//! persona-voiced reply reminding the author of the channel's language, with
+//! their message translated. Detection is a cheap stopword count, so only
+//! confident mismatches reach the model. Short messages, code, links and
+//! members with the guild's `language_exempt_role` are left alone, and each
+//! channel gets a few nudges per day.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with stopword detection, exempt role, daily cap and translated nudges
+
+pub mod gate;
+pub mod nudger;
+
+pub use gate::{NudgeDecision, NudgeGate};
+pub use nudger::LanguageNudger;
+
+use regex::Regex;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Feature id for toggles
+pub const LANGUAGE_FEATURE: &str = "language_enforcement";
+
+/// Guild setting holding a role whose members are never nudged
+pub const EXEMPT_ROLE_SETTING: &str = "language_exempt_role";
+
+/// Nudges a channel may get per UTC day
+pub const MAX_NUDGES_PER_DAY: usize = 5;
+
+/// A member isn't nudged again in the same channel for this long
+pub const USER_COOLDOWN: Duration = Duration::from_secs(30 * 60);
+
+/// Words a message needs before its language is judged
+pub const MIN_WORDS: usize = 4;
+
+/// Stopword hits the detected language needs
+const MIN_HITS: usize = 2;
+
+/// A language the detector knows
+#[derive(Debug, PartialEq, Eq)]
+pub struct Language {
+    /// ISO 639-1 code used as the channel setting value
+    pub code: &'static str,
+    pub name: &'static str,
+    /// Common words distinctive enough to tell the languages apart
+    stopwords: &'static [&'static str],
+}
+
+/// Languages a channel can be designated, in setting order
+pub const LANGUAGES: &[Language] = &[
+    Language {
+        code: "en",
+        name: "English",
+        stopwords: &[
+            "the", "and", "is", "are", "you", "that", "this", "with", "have", "what", "for", "not",
+            "but", "they", "it's", "i'm", "don't", "would", "there", "just", "about",
+        ],
+    },
+    Language {
+        code: "es",
+        name: "Spanish",
+        stopwords: &[
+            "el", "los", "las", "y", "por", "pero", "está", "muy", "como", "qué", "también",
+            "esto", "yo", "tengo", "hola", "gracias", "para", "una", "con", "del",
+        ],
+    },
+    Language {
+        code: "fr",
+        name: "French",
+        stopwords: &[
+            "le", "les", "est", "et", "je", "vous", "nous", "pas", "une", "des", "avec", "pour",
+            "mais", "c'est", "très", "aussi", "bonjour", "merci", "qui", "sur",
+        ],
+    },
+    Language {
+        code: "de",
+        name: "German",
+        stopwords: &[
+            "der", "die", "das", "und", "ist", "ich", "nicht", "ein", "eine", "mit", "auf", "auch",
+            "wir", "sie", "aber", "danke", "sehr", "haben", "wie", "bin",
+        ],
+    },
+    Language {
+        code: "pt",
+        name: "Portuguese",
+        stopwords: &[
+            "o", "os", "não", "é", "com", "mas", "você", "eu", "isso", "muito", "também",
+            "obrigado", "obrigada", "são", "uma", "tudo", "então", "ele", "ela",
+        ],
+    },
+    Language {
+        code: "it",
+        name: "Italian",
+        stopwords: &[
+            "il", "gli", "che", "è", "non", "sono", "per", "ma", "anche", "molto", "questo", "io",
+            "grazie", "ciao", "della", "perché", "come", "tutto", "cosa",
+        ],
+    },
+    Language {
+        code: "nl",
+        name: "Dutch",
+        stopwords: &[
+            "het", "een", "niet", "ik", "dat", "voor", "maar", "ook", "wij", "zijn", "heb", "dank",
+            "wel", "hoe", "jij", "zo", "naar", "wat", "dit",
+        ],
+    },
+];
+
+/// Look up a language by its setting code
+pub fn find_language(code: &str) -> Option<&'static Language> {
+    LANGUAGES.iter().find(|l| l.code == code)
+}
+
+fn noise_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    // Code, links, mentions and custom emoji say nothing about the language
+    RE.get_or_init(|| {
+        Regex::new(r"(?s)```.*?```|`[^`]*`|https?://\S+|<[@#:a-zA-Z!&]*\d+>").unwrap()
+    })
+}
+
+/// Lowercase words of a message with code, links, mentions and emoji removed
+pub fn words(content: &str) -> Vec<String> {
+    noise_regex()
+        .replace_all(content, " ")
+        .split(|c: char| !(c.is_alphanumeric() || c == '\''))
+        .filter(|w| !w.is_empty() && w.chars().any(char::is_alphabetic))
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// The language a message is clearly written in, if any
+///
+/// Needs [`MIN_WORDS`] words and at least twice the stopword hits of the
+/// runner-up, so mixed or ambiguous messages come back `None`.
+pub fn detect_language(content: &str) -> Option<&'static Language> {
+    let words = words(content);
+    if words.len() < MIN_WORDS {
+        return None;
+    }
+    let mut scores: Vec<(usize, &'static Language)> = LANGUAGES
+        .iter()
+        .map(|language| {
+            let hits = words
+                .iter()
+                .filter(|w| language.stopwords.contains(&w.as_str()))
+                .count();
+            (hits, language)
+        })
+        .collect();
+    scores.sort_by_key(|(hits, _)| std::cmp::Reverse(*hits));
+    let (best, language) = scores[0];
+    let runner_up = scores.get(1).map(|(hits, _)| *hits).unwrap_or(0);
+    (best >= MIN_HITS && best >= runner_up * 2).then_some(language)
+}
+
+/// The language to nudge about: a confident detection other than the channel's own
+pub fn off_language(content: &str, designated: &Language) -> Option<&'static Language> {
+    detect_language(content).filter(|detected| detected.code != designated.code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_language() {
+        assert_eq!(find_language("de").unwrap().name, "German");
+        assert!(find_language("xx").is_none());
+    }
+
+    #[test]
+    fn test_words_strip_noise() {
+        assert_eq!(
+            words(
+                "Hey <@123> look at `let x` https://a.example ```\ncode\n``` <:wave:42> it's 2024!"
+            ),
+            vec!["hey", "look", "at", "it's"]
+        );
+    }
+
+    #[test]
+    fn test_detect_language() {
+        let cases = [
+            ("what do you think about the new update, is it good?", "en"),
+            ("hola, ¿qué tal? yo también tengo el juego", "es"),
+            ("bonjour, je ne sais pas si c'est une bonne idée", "fr"),
+            ("ich habe das nicht gesehen, aber danke", "de"),
+            ("eu não sei, mas isso é muito bom", "pt"),
+            ("ciao, non so se questo è vero ma grazie", "it"),
+            ("ik heb dat niet gezien maar dank je wel", "nl"),
+        ];
+        for (text, code) in cases {
+            assert_eq!(detect_language(text).map(|l| l.code), Some(code), "{text}");
+        }
+        // Too short, or no clear winner
+        assert!(detect_language("lol ok").is_none());
+        assert!(detect_language("gg wp see ya").is_none());
+    }
+
+    #[test]
+    fn test_off_language() {
+        let english = find_language("en").unwrap();
+        assert_eq!(
+            off_language("ich habe das nicht gesehen, aber danke", english).map(|l| l.code),
+            Some("de")
+        );
+        assert!(off_language("what do you think about the new update", english).is_none());
+    }
+}
@@ -0,0 +1,143 @@
+//! Write a persona-voiced language reminder with the message translated
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use log::info;
+use openai::chat::ChatCompletionMessageRole;
+use serenity::model::channel::Message;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::Language;
+use crate::core::{chat_message, ChatClient};
+use crate::features::analytics::{CostBucket, UsageTracker};
+use crate::features::guardrails::ChannelGrounding;
+
+/// Cap on generated tokens; a reminder and a translated message
+const MAX_NUDGE_TOKENS: u64 = 400;
+
+/// Characters of the original message sent for translating
+const MAX_MESSAGE_CHARS: usize = 1500;
+
+/// Discord's message length limit
+const MAX_NUDGE_CHARS: usize = 2000;
+
+/// Writes language nudges through the shared chat client
+#[derive(Clone)]
+pub struct LanguageNudger {
+    chat_client: Arc<dyn ChatClient>,
+    openai_model: String,
+    usage_tracker: UsageTracker,
+}
+
+impl LanguageNudger {
+    pub fn new(
+        chat_client: Arc<dyn ChatClient>,
+        openai_model: String,
+        usage_tracker: UsageTracker,
+    ) -> Self {
+        Self {
+            chat_client,
+            openai_model,
+            usage_tracker,
+        }
+    }
+
+    /// Write a nudge for `msg`, written in `detected` in a channel meant for `designated`
+    ///
+    /// `persona_prompt` is the system prompt of the persona active in the
+    /// channel, so the reminder sounds like the bot members already know, and
+    /// `grounding` is the channel's safety profile and scene.
+    /// Returns `Ok(None)` when the model judged the message not worth a nudge.
+    pub async fn nudge(
+        &self,
+        persona_prompt: &str,
+        grounding: &ChannelGrounding,
+        designated: &Language,
+        detected: &Language,
+        msg: &Message,
+        request_id: Uuid,
+    ) -> Result<Option<String>> {
+        let content: String = msg.content.chars().take(MAX_MESSAGE_CHARS).collect();
+        let completion = self
+            .chat_client
+            .create_chat_completion_limited(
+                &self.openai_model,
+                vec![
+                    chat_message(
+                        ChatCompletionMessageRole::System,
+                        grounding.persona_prompt(&format!(
+                            "{persona_prompt}\n\n{}",
+                            nudge_prompt(designated, detected)
+                        )),
+                    ),
+                    chat_message(ChatCompletionMessageRole::User, content),
+                ],
+                Some(MAX_NUDGE_TOKENS),
+            )
+            .await?;
+
+        if let Some(usage) = &completion.usage {
+            self.usage_tracker.log_chat(
+                &self.openai_model,
+                usage.prompt_tokens,
+                usage.completion_tokens,
+                usage.total_tokens,
+                &msg.author.id.to_string(),
+                msg.guild_id.map(|id| id.to_string()).as_deref(),
+                Some(&msg.channel_id.to_string()),
+                Some(&request_id.to_string()),
+                CostBucket::Language,
+            );
+        }
+
+        let nudge = completion
+            .choices
+            .first()
+            .and_then(|choice| choice.message.content.clone())
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        if nudge.is_empty() || nudge == "SKIP" {
+            return Ok(None);
+        }
+        info!(
+            "[{request_id}] 🌐 Nudging {} toward {} in {}",
+            msg.author.id, designated.code, msg.channel_id
+        );
+        Ok(Some(nudge.chars().take(MAX_NUDGE_CHARS).collect()))
+    }
+}
+
+/// Instructions appended to the persona prompt
+fn nudge_prompt(designated: &Language, detected: &Language) -> String {
+    format!(
+        "This channel is for conversation in {target}. The member's next message looks like \
+         {source}. Reply in {target} with one short, friendly sentence in your own voice \
+         reminding them this channel uses {target}; don't scold and don't repeat their name. \
+         Then add a new line starting with \"Translation:\" followed by their message \
+         translated into {target}. If the message is not actually {source} (names, quotes, \
+         slang or mostly {target}), reply with only: SKIP",
+        target = designated.name,
+        source = detected.name,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::language::find_language;
+
+    #[test]
+    fn test_nudge_prompt_names_both_languages() {
+        let prompt = nudge_prompt(find_language("en").unwrap(), find_language("fr").unwrap());
+        assert!(prompt.starts_with("This channel is for conversation in English."));
+        assert!(prompt.contains("looks like French"));
+        assert!(prompt.contains("\"Translation:\""));
+    }
+}
@@ -0,0 +1,234 @@
+//! # Feature: Leveling
+//!
+//! Opt-in per guild with `/leveling on`. Members earn XP for chatting and more
+//! for using the bot, at most once per [`COOLDOWN_SECS`] so spamming doesn't
+//! pay. Reaching a level is announced in the configured channel (or where it
+//! happened) and hands out any role rewards set with `/leveling reward`.
+//! `/rank` and `/leaderboard` show where members stand. Members who opted out
+//! of analytics don't earn XP.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with message and interaction XP, cooldowns, announcements and role rewards
+
+use anyhow::Result;
+use log::{info, warn};
+use serenity::http::Http;
+use serenity::model::id::ChannelId;
+
+use crate::database::Database;
+
+/// Feature id for toggles
+pub const LEVELING_FEATURE: &str = "leveling";
+
+/// Guild setting turning XP on (`enabled`)
+pub const ENABLED_SETTING: &str = "leveling";
+
+/// Guild setting holding the channel level-ups are announced in
+pub const CHANNEL_SETTING: &str = "leveling_channel";
+
+/// Minimum seconds between two XP grants to the same member
+pub const COOLDOWN_SECS: i64 = 60;
+
+/// Messages shorter than this earn nothing
+pub const MIN_MESSAGE_CHARS: usize = 5;
+
+/// Highest level a role reward can be set for
+pub const MAX_REWARD_LEVEL: i64 = 100;
+
+/// Where XP came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XpSource {
+    /// A plain guild message
+    Message,
+    /// A slash command or other bot interaction
+    Interaction,
+}
+
+impl XpSource {
+    pub fn xp(self) -> i64 {
+        match self {
+            XpSource::Message => 15,
+            XpSource::Interaction => 25,
+        }
+    }
+}
+
+/// XP needed to go from `level` to the next one
+pub fn xp_to_next(level: i64) -> i64 {
+    5 * level * level + 50 * level + 100
+}
+
+/// A member's level and how far they are into it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LevelProgress {
+    pub level: i64,
+    /// XP earned since reaching `level`
+    pub current: i64,
+    /// XP `level` takes in total
+    pub needed: i64,
+}
+
+/// Level reached with `xp` total XP
+pub fn level_progress(xp: i64) -> LevelProgress {
+    let mut level = 0;
+    let mut remaining = xp.max(0);
+    while remaining >= xp_to_next(level) {
+        remaining -= xp_to_next(level);
+        level += 1;
+    }
+    LevelProgress {
+        level,
+        current: remaining,
+        needed: xp_to_next(level),
+    }
+}
+
+/// Text progress bar `width` blocks wide
+pub fn progress_bar(current: i64, needed: i64, width: usize) -> String {
+    let filled = if needed > 0 {
+        ((current.clamp(0, needed) * width as i64) / needed) as usize
+    } else {
+        0
+    };
+    format!("{}{}", "▰".repeat(filled), "▱".repeat(width - filled))
+}
+
+/// Whether a guild has opted into XP
+pub async fn is_enabled(database: &Database, guild_id: &str) -> Result<bool> {
+    Ok(database
+        .is_feature_enabled(LEVELING_FEATURE, None, Some(guild_id))
+        .await?
+        && database
+            .get_guild_setting(guild_id, ENABLED_SETTING)
+            .await?
+            .as_deref()
+            == Some("enabled"))
+}
+
+/// Grant XP for activity in `channel_id`, announcing level-ups and handing out role rewards
+///
+/// Returns the new level when the grant crossed into one.
+pub async fn grant(
+    database: &Database,
+    http: &Http,
+    guild_id: &str,
+    user_id: &str,
+    channel_id: &str,
+    source: XpSource,
+) -> Result<Option<i64>> {
+    if !is_enabled(database, guild_id).await?
+        || !database
+            .get_privacy_settings(user_id, Some(guild_id))
+            .await?
+            .analytics
+    {
+        return Ok(None);
+    }
+    let now = chrono::Utc::now().timestamp();
+    let Some((before, after)) = database
+        .grant_member_xp(guild_id, user_id, source.xp(), now, COOLDOWN_SECS)
+        .await?
+    else {
+        return Ok(None);
+    };
+    let old_level = level_progress(before).level;
+    let new_level = level_progress(after).level;
+    if new_level <= old_level {
+        return Ok(None);
+    }
+    info!("⬆️ {user_id} reached level {new_level} in guild {guild_id}");
+
+    let mut granted = Vec::new();
+    for (level, role_id) in database.get_level_rewards(guild_id).await? {
+        if level <= old_level || level > new_level {
+            continue;
+        }
+        match http
+            .add_member_role(
+                guild_id.parse()?,
+                user_id.parse()?,
+                role_id.parse()?,
+                Some(&format!("Reached level {level}")),
+            )
+            .await
+        {
+            Ok(()) => granted.push(role_id),
+            Err(e) => warn!("⚠️ Failed to give level {level} role {role_id} to {user_id}: {e}"),
+        }
+    }
+
+    let channel = database
+        .get_guild_setting(guild_id, CHANNEL_SETTING)
+        .await?
+        .unwrap_or_else(|| channel_id.to_string());
+    ChannelId(channel.parse::<u64>()?)
+        .say(http, level_up_message(user_id, new_level, &granted))
+        .await?;
+    Ok(Some(new_level))
+}
+
+fn level_up_message(user_id: &str, level: i64, roles: &[String]) -> String {
+    let mut message = format!("🎉 <@{user_id}> reached **level {level}**!");
+    if !roles.is_empty() {
+        let roles = roles
+            .iter()
+            .map(|id| format!("<@&{id}>"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        message.push_str(&format!(" Unlocked {roles}."));
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_progress() {
+        assert_eq!(xp_to_next(0), 100);
+        assert_eq!(xp_to_next(1), 155);
+        assert_eq!(
+            level_progress(0),
+            LevelProgress {
+                level: 0,
+                current: 0,
+                needed: 100
+            }
+        );
+        assert_eq!(level_progress(99).level, 0);
+        assert_eq!(
+            level_progress(100),
+            LevelProgress {
+                level: 1,
+                current: 0,
+                needed: 155
+            }
+        );
+        assert_eq!(level_progress(300).level, 2);
+        assert_eq!(level_progress(-5).level, 0);
+    }
+
+    #[test]
+    fn test_progress_bar() {
+        assert_eq!(progress_bar(0, 100, 5), "▱▱▱▱▱");
+        assert_eq!(progress_bar(50, 100, 4), "▰▰▱▱");
+        assert_eq!(progress_bar(150, 100, 3), "▰▰▰");
+    }
+
+    #[test]
+    fn test_level_up_message() {
+        assert_eq!(
+            level_up_message("1", 3, &[]),
+            "🎉 <@1> reached **level 3**!"
+        );
+        assert_eq!(
+            level_up_message("1", 5, &["9".to_string()]),
+            "🎉 <@1> reached **level 5**! Unlocked <@&9>."
+        );
+    }
+}
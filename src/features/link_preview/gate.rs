@@ -0,0 +1,199 @@
+//! Bare URL detection and the per-channel rate limit / dedup gate
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use dashmap::DashMap;
+use regex::Regex;
+use std::collections::{HashMap, VecDeque};
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use super::{DEDUP_WINDOW, MAX_PREVIEWS_PER_WINDOW, RATE_WINDOW};
+
+/// Punctuation that ends a sentence rather than the URL before it
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', '\'', '"', '*', '_', '~'];
+
+fn url_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"https?://[^\s<>|`]+").unwrap())
+}
+
+/// Find bare URLs in a message, in order and without repeats
+///
+/// URLs inside code spans or blocks and URLs wrapped in `<...>` (Discord's
+/// "don't embed this" syntax) are left alone.
+pub fn extract_urls(content: &str) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+    let outside_code = content
+        .split("```")
+        .step_by(2)
+        .flat_map(|block| block.split('`').step_by(2));
+
+    for text in outside_code {
+        for m in url_regex().find_iter(text) {
+            if text[..m.start()].ends_with('<') {
+                continue;
+            }
+            let mut url = m.as_str().trim_end_matches(TRAILING_PUNCTUATION);
+            // "(see https://example.com)" - keep parentheses that belong to the URL
+            while url.ends_with(')') && url.matches(')').count() > url.matches('(').count() {
+                url = url[..url.len() - 1].trim_end_matches(TRAILING_PUNCTUATION);
+            }
+            if reqwest::Url::parse(url).is_ok_and(|u| u.host_str().is_some())
+                && !urls.iter().any(|u| u == url)
+            {
+                urls.push(url.to_string());
+            }
+        }
+    }
+    urls
+}
+
+/// Key a URL is deduplicated by: lowercase host, no fragment, no trailing slash
+pub fn normalize_url(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(mut parsed) => {
+            parsed.set_fragment(None);
+            parsed.as_str().trim_end_matches('/').to_string()
+        }
+        Err(_) => url.trim_end_matches('/').to_string(),
+    }
+}
+
+/// Outcome of asking the gate for a preview
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreviewDecision {
+    /// Fetch and post the preview
+    Allow,
+    /// The URL was previewed in this channel recently
+    Duplicate,
+    /// The channel has had its previews for this window
+    RateLimited,
+}
+
+#[derive(Default)]
+struct ChannelPreviews {
+    sent: VecDeque<Instant>,
+    urls: HashMap<String, Instant>,
+}
+
+/// Per-channel preview rate limit and recently-previewed URL memory
+#[derive(Default)]
+pub struct PreviewGate {
+    channels: DashMap<u64, ChannelPreviews>,
+}
+
+impl PreviewGate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decide whether `url` may be previewed in `channel_id`, counting it if so
+    pub fn check(&self, channel_id: u64, url: &str) -> PreviewDecision {
+        self.check_at(channel_id, url, Instant::now())
+    }
+
+    fn check_at(&self, channel_id: u64, url: &str, now: Instant) -> PreviewDecision {
+        let mut channel = self.channels.entry(channel_id).or_default();
+        channel
+            .urls
+            .retain(|_, seen| now.duration_since(*seen) < DEDUP_WINDOW);
+        while channel
+            .sent
+            .front()
+            .is_some_and(|sent| now.duration_since(*sent) >= RATE_WINDOW)
+        {
+            channel.sent.pop_front();
+        }
+
+        let key = normalize_url(url);
+        if channel.urls.contains_key(&key) {
+            return PreviewDecision::Duplicate;
+        }
+        if channel.sent.len() >= MAX_PREVIEWS_PER_WINDOW {
+            return PreviewDecision::RateLimited;
+        }
+        channel.sent.push_back(now);
+        channel.urls.insert(key, now);
+        PreviewDecision::Allow
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_urls() {
+        let content = "look at https://example.com/post?id=1, and (see https://en.wikipedia.org/wiki/Rust_(programming_language)).";
+        assert_eq!(
+            extract_urls(content),
+            vec![
+                "https://example.com/post?id=1",
+                "https://en.wikipedia.org/wiki/Rust_(programming_language)"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_urls_skips_code_and_suppressed() {
+        let content = "<https://hidden.example> `https://inline.example` ```\nhttps://block.example\n``` https://shown.example https://shown.example";
+        assert_eq!(extract_urls(content), vec!["https://shown.example"]);
+        assert!(extract_urls("no links here, just http:// text").is_empty());
+    }
+
+    #[test]
+    fn test_normalize_url() {
+        assert_eq!(
+            normalize_url("https://Example.com/Page/#intro"),
+            "https://example.com/Page"
+        );
+        assert_eq!(normalize_url("https://example.com"), "https://example.com");
+    }
+
+    #[test]
+    fn test_gate_dedups_and_rate_limits() {
+        let gate = PreviewGate::new();
+        let start = Instant::now();
+        assert_eq!(
+            gate.check_at(1, "https://a.example", start),
+            PreviewDecision::Allow
+        );
+        assert_eq!(
+            gate.check_at(1, "https://A.example/#top", start),
+            PreviewDecision::Duplicate
+        );
+        // Other channels have their own memory
+        assert_eq!(
+            gate.check_at(2, "https://a.example", start),
+            PreviewDecision::Allow
+        );
+
+        for i in 1..MAX_PREVIEWS_PER_WINDOW {
+            let url = format!("https://{i}.example");
+            assert_eq!(gate.check_at(1, &url, start), PreviewDecision::Allow);
+        }
+        assert_eq!(
+            gate.check_at(1, "https://late.example", start),
+            PreviewDecision::RateLimited
+        );
+
+        let later = start + RATE_WINDOW;
+        assert_eq!(
+            gate.check_at(1, "https://late.example", later),
+            PreviewDecision::Allow
+        );
+        assert_eq!(
+            gate.check_at(1, "https://a.example", later),
+            PreviewDecision::Duplicate
+        );
+        assert_eq!(
+            gate.check_at(1, "https://a.example", start + DEDUP_WINDOW),
+            PreviewDecision::Allow
+        );
+    }
+}
@@ -0,0 +1,57 @@
+//! # Link Preview Feature
+//!
+//! Opt-in per channel (`/set_channel link_previews`): bare URLs posted in
+//! plain messages are fetched and summarized in a short reply or a thread
+//! started from the message. Each channel gets a handful of previews per
+//! window, and a URL already previewed in the channel is skipped for a while.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with reply/thread modes, per-channel rate limit and dedup
+
+pub mod gate;
+pub mod summarizer;
+
+pub use gate::{extract_urls, normalize_url, PreviewDecision, PreviewGate};
+pub use summarizer::{post_link_previews, LinkPreview, LinkPreviewer};
+
+use std::time::Duration;
+
+/// Feature id for toggles
+pub const LINK_PREVIEW_FEATURE: &str = "link_previews";
+
+/// Channel setting values for `/set_channel link_previews`
+pub const LINK_PREVIEW_MODES: &[&str] = &["reply", "thread", "disabled"];
+
+/// URLs previewed from a single message
+pub const MAX_URLS_PER_MESSAGE: usize = 2;
+
+/// Previews a channel may get per [`RATE_WINDOW`]
+pub const MAX_PREVIEWS_PER_WINDOW: usize = 3;
+
+/// Window the per-channel preview limit applies to
+pub const RATE_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+/// A URL previewed in a channel is not previewed there again for this long
+pub const DEDUP_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+/// How a channel shows link previews
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkPreviewMode {
+    Reply,
+    Thread,
+}
+
+impl LinkPreviewMode {
+    /// Parse a stored channel setting; `disabled` and unknown values are `None`
+    pub fn from_setting(value: &str) -> Option<Self> {
+        match value {
+            "reply" => Some(LinkPreviewMode::Reply),
+            "thread" => Some(LinkPreviewMode::Thread),
+            _ => None,
+        }
+    }
+}
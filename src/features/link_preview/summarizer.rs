@@ -0,0 +1,263 @@
+//! Fetch a linked page, summarize it and post the preview
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use log::{debug, info};
+use openai::chat::ChatCompletionMessageRole;
+use scraper::{Html, Selector};
+use serenity::http::Http;
+use serenity::model::channel::{ChannelType, Message};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::LinkPreviewMode;
+use crate::commands::handlers::fetch::FetchHandler;
+use crate::core::{chat_message, detect_content_kind, download_file, ChatClient, ContentKind};
+use crate::features::analytics::{CostBucket, UsageTracker};
+use crate::features::guardrails::ChannelGrounding;
+use crate::features::resilience::chaos;
+
+/// Largest page fetched for a preview
+const MAX_PAGE_BYTES: u64 = 2 * 1024 * 1024;
+
+/// Page fetch timeout (seconds)
+const FETCH_TIMEOUT_SECS: u64 = 15;
+
+/// Characters of page text sent for summarizing
+const MAX_PAGE_CHARS: usize = 12_000;
+
+/// Cap on generated tokens; previews are a few sentences
+const MAX_SUMMARY_TOKENS: u64 = 300;
+
+/// Discord's limit on thread names
+const MAX_THREAD_NAME_CHARS: usize = 100;
+
+const SUMMARY_PROMPT: &str = "You write link previews for a Discord channel. \
+    Summarize the webpage you are given in 2-3 plain sentences: what it is and its key point. \
+    No headings, no bullet lists, no preamble like \"This page\". \
+    If the content is an error page, a login wall or cookie notice, reply with only: SKIP";
+
+/// A summarized page ready to post
+#[derive(Debug, Clone)]
+pub struct LinkPreview {
+    pub url: String,
+    pub title: Option<String>,
+    pub summary: String,
+}
+
+/// Fetches pages and summarizes them through the shared chat client
+#[derive(Clone)]
+pub struct LinkPreviewer {
+    chat_client: Arc<dyn ChatClient>,
+    openai_model: String,
+    usage_tracker: UsageTracker,
+}
+
+impl LinkPreviewer {
+    pub fn new(
+        chat_client: Arc<dyn ChatClient>,
+        openai_model: String,
+        usage_tracker: UsageTracker,
+    ) -> Self {
+        Self {
+            chat_client,
+            openai_model,
+            usage_tracker,
+        }
+    }
+
+    /// Fetch `url` and summarize it under the channel's safety profile from `grounding`
+    ///
+    /// Returns `Ok(None)` for pages that aren't HTML or text, have no readable
+    /// text, or that the model judged not worth previewing.
+    pub async fn summarize(
+        &self,
+        url: &str,
+        grounding: &ChannelGrounding,
+        user_id: &str,
+        guild_id: Option<&str>,
+        channel_id: &str,
+        request_id: Uuid,
+    ) -> Result<Option<LinkPreview>> {
+        let downloaded = download_file(url, MAX_PAGE_BYTES, FETCH_TIMEOUT_SECS).await?;
+        let kind = detect_content_kind(&downloaded.content_type, url);
+        let page = String::from_utf8_lossy(&downloaded.bytes);
+        let (title, text) = match kind {
+            ContentKind::Html => (
+                page_title(&page),
+                FetchHandler::extract_text(&page, request_id),
+            ),
+            ContentKind::PlainText => (None, page.to_string()),
+            _ => {
+                debug!("[{request_id}] 🔗 Not previewing {url}: {kind:?} content");
+                return Ok(None);
+            }
+        };
+        if text.trim().is_empty() {
+            return Ok(None);
+        }
+        let text: String = text.chars().take(MAX_PAGE_CHARS).collect();
+
+        let completion = self
+            .chat_client
+            .create_chat_completion_limited(
+                &self.openai_model,
+                vec![
+                    chat_message(
+                        ChatCompletionMessageRole::System,
+                        grounding.task_prompt(SUMMARY_PROMPT),
+                    ),
+                    chat_message(
+                        ChatCompletionMessageRole::User,
+                        format!(
+                            "URL: {url}\nTitle: {}\n\n{text}",
+                            title.as_deref().unwrap_or("(none)")
+                        ),
+                    ),
+                ],
+                Some(MAX_SUMMARY_TOKENS),
+            )
+            .await?;
+
+        if let Some(usage) = &completion.usage {
+            self.usage_tracker.log_chat(
+                &self.openai_model,
+                usage.prompt_tokens,
+                usage.completion_tokens,
+                usage.total_tokens,
+                user_id,
+                guild_id,
+                Some(channel_id),
+                Some(&request_id.to_string()),
+                CostBucket::Fetch,
+            );
+        }
+
+        let summary = completion
+            .choices
+            .first()
+            .and_then(|choice| choice.message.content.clone())
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        if summary.is_empty() || summary == "SKIP" {
+            return Ok(None);
+        }
+        info!(
+            "[{request_id}] 🔗 Summarized {url} ({} chars)",
+            summary.len()
+        );
+        Ok(Some(LinkPreview {
+            url: url.to_string(),
+            title,
+            summary,
+        }))
+    }
+}
+
+/// The page's `<title>`, whitespace collapsed
+fn page_title(html: &str) -> Option<String> {
+    let selector = Selector::parse("title").ok()?;
+    let document = Html::parse_document(html);
+    let title = document
+        .select(&selector)
+        .next()?
+        .text()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!title.is_empty()).then_some(title)
+}
+
+/// Preview text: bold title (when known) above the summary, URL kept out so
+/// Discord doesn't embed the link a second time
+fn format_preview(preview: &LinkPreview) -> String {
+    match &preview.title {
+        Some(title) => format!("🔗 **{title}**\n{}", preview.summary),
+        None => format!("🔗 {}", preview.summary),
+    }
+}
+
+/// Post previews as one reply to `msg` or in a thread started from it
+pub async fn post_link_previews(
+    http: &Arc<Http>,
+    msg: &Message,
+    previews: &[LinkPreview],
+    mode: LinkPreviewMode,
+) -> Result<()> {
+    let Some(first) = previews.first() else {
+        return Ok(());
+    };
+    match mode {
+        LinkPreviewMode::Reply => {
+            let content = previews
+                .iter()
+                .map(format_preview)
+                .collect::<Vec<_>>()
+                .join("\n\n");
+            msg.channel_id
+                .send_message(http, |m| {
+                    m.content(content)
+                        .reference_message(msg)
+                        .allowed_mentions(|a| a.replied_user(false))
+                })
+                .await?;
+        }
+        LinkPreviewMode::Thread => {
+            let name: String = first
+                .title
+                .as_deref()
+                .unwrap_or(&first.url)
+                .chars()
+                .take(MAX_THREAD_NAME_CHARS)
+                .collect();
+            chaos().discord_fault(&format!(
+                "channels/{}/messages/{}/threads",
+                msg.channel_id, msg.id
+            ))?;
+            let thread = msg
+                .channel_id
+                .create_public_thread(http, msg.id, |t| {
+                    t.name(name)
+                        .kind(ChannelType::PublicThread)
+                        .auto_archive_duration(1440)
+                })
+                .await?;
+            for preview in previews {
+                thread.id.say(http, format_preview(preview)).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_title() {
+        let html =
+            "<html><head><title>\n  Rust 1.80\n  released </title></head><body></body></html>";
+        assert_eq!(page_title(html).as_deref(), Some("Rust 1.80 released"));
+        assert_eq!(page_title("<html><body>hi</body></html>"), None);
+    }
+
+    #[test]
+    fn test_format_preview() {
+        let preview = LinkPreview {
+            url: "https://example.com/notes".to_string(),
+            title: Some("Release notes".to_string()),
+            summary: "Version 2 ships today.".to_string(),
+        };
+        assert_eq!(
+            format_preview(&preview),
+            "🔗 **Release notes**\nVersion 2 ships today."
+        );
+    }
+}
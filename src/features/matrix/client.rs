@@ -0,0 +1,138 @@
+//! Minimal Matrix client-server API client
+//!
+//! Just the endpoints the bridge needs: whoami, sync, join, typing and
+//! sending text messages, authenticated with an access token.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::{Context, Result};
+use reqwest::{Method, RequestBuilder, Url};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// How long a sync request waits on the homeserver for new events
+pub const SYNC_TIMEOUT_MS: u64 = 30_000;
+
+/// Only room messages are needed; everything else is filtered server-side
+const SYNC_FILTER: &str = r#"{"presence":{"types":[]},"account_data":{"types":[]},"room":{"timeline":{"types":["m.room.message"],"limit":20},"state":{"types":[]},"ephemeral":{"types":[]},"account_data":{"types":[]}}}"#;
+
+pub struct MatrixClient {
+    http: reqwest::Client,
+    homeserver: Url,
+    access_token: String,
+    txn_counter: AtomicU64,
+}
+
+impl MatrixClient {
+    pub fn new(homeserver: &str, access_token: String) -> Result<Self> {
+        let homeserver = Url::parse(homeserver)
+            .with_context(|| format!("Invalid MATRIX_HOMESERVER '{homeserver}'"))?;
+        Ok(Self {
+            http: reqwest::Client::new(),
+            homeserver,
+            access_token,
+            txn_counter: AtomicU64::new(0),
+        })
+    }
+
+    /// Build a request to `/_matrix/client/v3/<segments>`, escaping each segment
+    fn request(&self, method: Method, segments: &[&str]) -> RequestBuilder {
+        let mut url = self.homeserver.clone();
+        if let Ok(mut path) = url.path_segments_mut() {
+            path.pop_if_empty().extend(["_matrix", "client", "v3"]);
+            path.extend(segments);
+        }
+        self.http
+            .request(method, url)
+            .bearer_auth(&self.access_token)
+    }
+
+    async fn send(request: RequestBuilder) -> Result<Value> {
+        let response = request.send().await?;
+        let status = response.status();
+        let body: Value = response.json().await.unwrap_or(Value::Null);
+        if !status.is_success() {
+            let error = body["error"].as_str().unwrap_or("no details");
+            anyhow::bail!("Matrix API returned {status}: {error}");
+        }
+        Ok(body)
+    }
+
+    /// The bot's own Matrix user ID
+    pub async fn whoami(&self) -> Result<String> {
+        let body = Self::send(self.request(Method::GET, &["account", "whoami"])).await?;
+        body["user_id"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("whoami response had no user_id"))
+    }
+
+    /// Long-poll for events after `since`; `timeout_ms` of 0 returns immediately
+    pub async fn sync(&self, since: Option<&str>, timeout_ms: u64) -> Result<Value> {
+        let mut query = vec![
+            ("timeout", timeout_ms.to_string()),
+            ("filter", SYNC_FILTER.to_string()),
+        ];
+        if let Some(since) = since {
+            query.push(("since", since.to_string()));
+        }
+        let request = self
+            .request(Method::GET, &["sync"])
+            .query(&query)
+            .timeout(Duration::from_millis(timeout_ms) + Duration::from_secs(30));
+        Self::send(request).await
+    }
+
+    pub async fn join(&self, room_id: &str) -> Result<()> {
+        Self::send(
+            self.request(Method::POST, &["join", room_id])
+                .json(&json!({})),
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_typing(&self, room_id: &str, user_id: &str, typing: bool) -> Result<()> {
+        let body = if typing {
+            json!({ "typing": true, "timeout": SYNC_TIMEOUT_MS })
+        } else {
+            json!({ "typing": false })
+        };
+        Self::send(
+            self.request(Method::PUT, &["rooms", room_id, "typing", user_id])
+                .json(&body),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Send a notice with a plain body and an HTML rendering
+    pub async fn send_notice(&self, room_id: &str, body: &str, html: &str) -> Result<()> {
+        // Unique per access token, including across restarts
+        let txn_id = format!(
+            "persona-{}-{}",
+            chrono::Utc::now().timestamp_millis(),
+            self.txn_counter.fetch_add(1, Ordering::Relaxed)
+        );
+        let content = json!({
+            "msgtype": "m.notice",
+            "body": body,
+            "format": "org.matrix.custom.html",
+            "formatted_body": html,
+        });
+        Self::send(
+            self.request(
+                Method::PUT,
+                &["rooms", room_id, "send", "m.room.message", &txn_id],
+            )
+            .json(&content),
+        )
+        .await?;
+        Ok(())
+    }
+}
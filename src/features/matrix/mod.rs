@@ -0,0 +1,452 @@
+//! # Matrix Bridge
+//!
+//! Optional front end that answers in Matrix rooms with the same persona chat
+//! pipeline as Discord DMs: the user's persona, stored conversation history,
+//! rate limits and usage tracking all go through the shared database.
+//!
+//! The bot replies to every message in a two-person room and to messages
+//! that mention it elsewhere. `!persona <name>` switches persona,
+//! `!personas` lists them and `!reset` clears the room's history. Invites
+//! are accepted automatically.
+//!
+//! Built only with the `matrix` cargo feature and started when
+//! `MATRIX_HOMESERVER` and `MATRIX_ACCESS_TOKEN` are set.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: false
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+pub mod client;
+
+pub use client::MatrixClient;
+
+use anyhow::Result;
+use log::{debug, error, info, warn};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::command_handler::CommandHandler;
+use crate::database::Database;
+use crate::features::personas::PersonaManager;
+use client::SYNC_TIMEOUT_MS;
+
+/// Pause after a failed sync before trying again
+const SYNC_RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// Homeserver and credentials for the bridge account
+#[derive(Debug, Clone)]
+pub struct MatrixConfig {
+    pub homeserver: String,
+    pub access_token: String,
+}
+
+impl MatrixConfig {
+    /// `None` unless both MATRIX_HOMESERVER and MATRIX_ACCESS_TOKEN are set
+    pub fn from_env() -> Option<Self> {
+        let homeserver = std::env::var("MATRIX_HOMESERVER").ok()?;
+        let access_token = std::env::var("MATRIX_ACCESS_TOKEN").ok()?;
+        if homeserver.trim().is_empty() || access_token.trim().is_empty() {
+            return None;
+        }
+        Some(Self {
+            homeserver: homeserver.trim().to_string(),
+            access_token: access_token.trim().to_string(),
+        })
+    }
+}
+
+/// A text message someone else sent in a joined room
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoomMessage {
+    pub room_id: String,
+    pub sender: String,
+    pub body: String,
+}
+
+/// What a sync response means for the bridge
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SyncBatch {
+    pub next_batch: String,
+    /// Rooms the bot was invited to
+    pub invites: Vec<String>,
+    pub messages: Vec<RoomMessage>,
+    /// Joined member counts the homeserver reported in this batch
+    pub member_counts: Vec<(String, u64)>,
+}
+
+/// Pull invites, text messages and member counts out of a `/sync` response
+pub fn parse_sync(body: &Value, own_user_id: &str) -> SyncBatch {
+    let mut batch = SyncBatch {
+        next_batch: body["next_batch"].as_str().unwrap_or_default().to_string(),
+        ..SyncBatch::default()
+    };
+    if let Some(invites) = body["rooms"]["invite"].as_object() {
+        batch.invites = invites.keys().cloned().collect();
+    }
+    let Some(joined) = body["rooms"]["join"].as_object() else {
+        return batch;
+    };
+    for (room_id, room) in joined {
+        if let Some(count) = room["summary"]["m.joined_member_count"].as_u64() {
+            batch.member_counts.push((room_id.clone(), count));
+        }
+        let Some(events) = room["timeline"]["events"].as_array() else {
+            continue;
+        };
+        for event in events {
+            let sender = event["sender"].as_str().unwrap_or_default();
+            let content = &event["content"];
+            if event["type"] != "m.room.message"
+                || sender == own_user_id
+                || content["msgtype"] != "m.text"
+            {
+                continue;
+            }
+            if let Some(body) = content["body"].as_str() {
+                batch.messages.push(RoomMessage {
+                    room_id: room_id.clone(),
+                    sender: sender.to_string(),
+                    body: body.to_string(),
+                });
+            }
+        }
+    }
+    batch
+}
+
+/// `@persona:example.org` → `persona`
+fn localpart(user_id: &str) -> &str {
+    user_id
+        .trim_start_matches('@')
+        .split(':')
+        .next()
+        .unwrap_or_default()
+}
+
+/// Whether a message addresses the bot by user ID or localpart
+pub fn mentions_bot(body: &str, user_id: &str) -> bool {
+    let body = body.to_lowercase();
+    body.contains(&user_id.to_lowercase()) || body.contains(&localpart(user_id).to_lowercase())
+}
+
+/// Message text with a leading mention of the bot removed
+pub fn strip_mention(body: &str, user_id: &str) -> String {
+    let trimmed = body.trim();
+    for prefix in [user_id, localpart(user_id)] {
+        if trimmed.len() >= prefix.len()
+            && trimmed.is_char_boundary(prefix.len())
+            && trimmed[..prefix.len()].eq_ignore_ascii_case(prefix)
+        {
+            return trimmed[prefix.len()..]
+                .trim_start_matches([':', ','])
+                .trim()
+                .to_string();
+        }
+    }
+    trimmed.to_string()
+}
+
+/// Bang commands the bridge handles itself
+#[derive(Debug, PartialEq, Eq)]
+pub enum BridgeCommand {
+    ListPersonas,
+    SetPersona(String),
+    Reset,
+}
+
+pub fn parse_command(text: &str) -> Option<BridgeCommand> {
+    let mut words = text.split_whitespace();
+    match words.next()? {
+        "!personas" => Some(BridgeCommand::ListPersonas),
+        "!persona" => match words.next() {
+            Some(name) => Some(BridgeCommand::SetPersona(name.to_lowercase())),
+            None => Some(BridgeCommand::ListPersonas),
+        },
+        "!reset" => Some(BridgeCommand::Reset),
+        _ => None,
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Plain and HTML bodies for a persona reply, headed with the persona's name
+pub fn format_reply(persona_name: &str, reply: &str) -> (String, String) {
+    let plain = format!("{persona_name}: {reply}");
+    let html = format!(
+        "<strong>{}</strong><br>{}",
+        escape_html(persona_name),
+        escape_html(reply).replace('\n', "<br>")
+    );
+    (plain, html)
+}
+
+/// Connects the persona chat pipeline to a Matrix account
+pub struct MatrixBridge {
+    client: MatrixClient,
+    handler: CommandHandler,
+    database: Database,
+    persona_manager: PersonaManager,
+    /// Last joined member count per room, to spot direct chats
+    member_counts: HashMap<String, u64>,
+}
+
+impl MatrixBridge {
+    pub fn new(config: &MatrixConfig, handler: CommandHandler, database: Database) -> Result<Self> {
+        Ok(Self {
+            client: MatrixClient::new(&config.homeserver, config.access_token.clone())?,
+            handler,
+            database,
+            persona_manager: PersonaManager::new(),
+            member_counts: HashMap::new(),
+        })
+    }
+
+    /// Sync forever, answering messages as they arrive
+    /// This should be spawned as a tokio task
+    pub async fn run(mut self) {
+        let user_id = match self.client.whoami().await {
+            Ok(user_id) => user_id,
+            Err(e) => {
+                error!("❌ Matrix bridge couldn't log in: {e}");
+                return;
+            }
+        };
+        info!("🟩 Matrix bridge connected as {user_id}");
+
+        // Skip whatever was sent while the bot was offline
+        let mut since = loop {
+            match self.client.sync(None, 0).await {
+                Ok(body) => {
+                    let batch = parse_sync(&body, &user_id);
+                    self.member_counts.extend(batch.member_counts);
+                    self.join_rooms(&batch.invites).await;
+                    break batch.next_batch;
+                }
+                Err(e) => {
+                    warn!("Matrix initial sync failed: {e}");
+                    tokio::time::sleep(SYNC_RETRY_DELAY).await;
+                }
+            }
+        };
+
+        loop {
+            let body = match self.client.sync(Some(&since), SYNC_TIMEOUT_MS).await {
+                Ok(body) => body,
+                Err(e) => {
+                    warn!("Matrix sync failed: {e}");
+                    tokio::time::sleep(SYNC_RETRY_DELAY).await;
+                    continue;
+                }
+            };
+            let batch = parse_sync(&body, &user_id);
+            if !batch.next_batch.is_empty() {
+                since = batch.next_batch;
+            }
+            self.member_counts.extend(batch.member_counts);
+            self.join_rooms(&batch.invites).await;
+
+            for message in batch.messages {
+                let direct = self.member_counts.get(&message.room_id) == Some(&2);
+                if !direct && !mentions_bot(&message.body, &user_id) {
+                    continue;
+                }
+                if let Err(e) = self.handle_message(&user_id, &message).await {
+                    error!(
+                        "❌ Matrix message in {} from {} failed: {e}",
+                        message.room_id, message.sender
+                    );
+                }
+            }
+        }
+    }
+
+    async fn join_rooms(&self, rooms: &[String]) {
+        for room_id in rooms {
+            match self.client.join(room_id).await {
+                Ok(()) => info!("🟩 Joined Matrix room {room_id}"),
+                Err(e) => warn!("Couldn't join Matrix room {room_id}: {e}"),
+            }
+        }
+    }
+
+    async fn handle_message(&self, own_user_id: &str, message: &RoomMessage) -> Result<()> {
+        let request_id = Uuid::new_v4();
+        let text = strip_mention(&message.body, own_user_id);
+        if text.is_empty() {
+            return Ok(());
+        }
+        debug!(
+            "[{request_id}] 🟩 Matrix message | Room: {} | Sender: {}",
+            message.room_id, message.sender
+        );
+
+        if let Some(command) = parse_command(&text) {
+            let reply = self
+                .run_command(&message.sender, &message.room_id, command)
+                .await?;
+            return self
+                .client
+                .send_notice(&message.room_id, &reply, &escape_html(&reply))
+                .await;
+        }
+
+        let _ = self
+            .client
+            .set_typing(&message.room_id, own_user_id, true)
+            .await;
+        let result = self
+            .handler
+            .bridge_chat(&message.sender, &message.room_id, &text, request_id)
+            .await;
+        let _ = self
+            .client
+            .set_typing(&message.room_id, own_user_id, false)
+            .await;
+
+        let (plain, html) = match result {
+            Ok(Some((persona_id, reply))) => {
+                let name = self
+                    .persona_manager
+                    .get_persona(&persona_id)
+                    .map(|p| p.name.clone())
+                    .unwrap_or(persona_id);
+                format_reply(&name, &reply)
+            }
+            Ok(None) => {
+                let text = "⏳ You're sending messages too quickly. Please wait a moment.";
+                (text.to_string(), text.to_string())
+            }
+            Err(e) => {
+                error!("[{request_id}] ❌ Matrix chat failed: {e}");
+                let text = CommandHandler::chat_error_message(&e);
+                (text.clone(), escape_html(&text))
+            }
+        };
+        self.client
+            .send_notice(&message.room_id, &plain, &html)
+            .await
+    }
+
+    async fn run_command(
+        &self,
+        sender: &str,
+        room_id: &str,
+        command: BridgeCommand,
+    ) -> Result<String> {
+        Ok(match command {
+            BridgeCommand::ListPersonas => {
+                let mut names: Vec<String> = self
+                    .persona_manager
+                    .list_personas()
+                    .into_iter()
+                    .map(|(id, persona)| format!("{id} ({})", persona.name))
+                    .collect();
+                names.sort();
+                format!(
+                    "Personas: {}\nSwitch with !persona <name>",
+                    names.join(", ")
+                )
+            }
+            BridgeCommand::SetPersona(name) => match self.persona_manager.get_persona(&name) {
+                Some(persona) => {
+                    self.database.set_user_persona(sender, &name).await?;
+                    format!("Now chatting with {}.", persona.name)
+                }
+                None => format!("Unknown persona '{name}'. Try !personas."),
+            },
+            BridgeCommand::Reset => {
+                self.database
+                    .clear_conversation_history(sender, room_id)
+                    .await?;
+                "Conversation history for this room cleared.".to_string()
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const BOT: &str = "@persona:example.org";
+
+    #[test]
+    fn test_parse_sync() {
+        let body = json!({
+            "next_batch": "s2",
+            "rooms": {
+                "invite": { "!new:example.org": {} },
+                "join": {
+                    "!dm:example.org": {
+                        "summary": { "m.joined_member_count": 2 },
+                        "timeline": { "events": [
+                            { "type": "m.room.message", "sender": "@alice:example.org",
+                              "content": { "msgtype": "m.text", "body": "hello" } },
+                            { "type": "m.room.message", "sender": BOT,
+                              "content": { "msgtype": "m.notice", "body": "echo" } },
+                            { "type": "m.room.message", "sender": "@bob:example.org",
+                              "content": { "msgtype": "m.image", "body": "cat.png" } }
+                        ] }
+                    }
+                }
+            }
+        });
+        let batch = parse_sync(&body, BOT);
+        assert_eq!(batch.next_batch, "s2");
+        assert_eq!(batch.invites, ["!new:example.org"]);
+        assert_eq!(batch.member_counts, [("!dm:example.org".to_string(), 2)]);
+        assert_eq!(
+            batch.messages,
+            [RoomMessage {
+                room_id: "!dm:example.org".to_string(),
+                sender: "@alice:example.org".to_string(),
+                body: "hello".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_mentions() {
+        assert!(mentions_bot("hey Persona, what's up", BOT));
+        assert!(mentions_bot("@persona:example.org hi", BOT));
+        assert!(!mentions_bot("hello everyone", BOT));
+
+        assert_eq!(
+            strip_mention("persona: tell me a joke", BOT),
+            "tell me a joke"
+        );
+        assert_eq!(strip_mention("@persona:example.org hi", BOT), "hi");
+        assert_eq!(strip_mention("hi persona", BOT), "hi persona");
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(
+            parse_command("!personas"),
+            Some(BridgeCommand::ListPersonas)
+        );
+        assert_eq!(
+            parse_command("!persona Yoda"),
+            Some(BridgeCommand::SetPersona("yoda".to_string()))
+        );
+        assert_eq!(parse_command("!reset"), Some(BridgeCommand::Reset));
+        assert_eq!(parse_command("hello !reset"), None);
+    }
+
+    #[test]
+    fn test_format_reply_escapes_html() {
+        let (plain, html) = format_reply("Obi-Wan", "a < b\nindeed");
+        assert_eq!(plain, "Obi-Wan: a < b\nindeed");
+        assert_eq!(html, "<strong>Obi-Wan</strong><br>a &lt; b<br>indeed");
+    }
+}
@@ -0,0 +1,275 @@
+//! Availability toggles and slot picking for meeting polls
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{TimeZone, Utc};
+use log::{info, warn};
+use serenity::model::application::interaction::message_component::MessageComponentInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::model::guild::ScheduledEventType;
+use serenity::model::id::{ChannelId, GuildId};
+use serenity::model::Timestamp;
+use serenity::prelude::Context;
+
+use super::{best_slot, poll_buttons, poll_embed, MEETINGS_FEATURE};
+use crate::commands::components::{ComponentHandler, ComponentId};
+use crate::commands::InteractionResponder;
+use crate::database::{Database, MeetingPoll};
+
+/// Routes meeting poll buttons
+pub struct MeetingButtons {
+    database: Database,
+}
+
+impl MeetingButtons {
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+
+    /// Re-render the poll message in place
+    async fn update_poll(
+        &self,
+        ctx: &Context,
+        interaction: &MessageComponentInteraction,
+        poll: &MeetingPoll,
+    ) -> Result<()> {
+        let availability = self
+            .database
+            .get_meeting_availability(poll.id, poll.slots.len())
+            .await?;
+        let embed = poll_embed(poll, &availability);
+        let components = poll_buttons(poll.id, poll.slots.len(), poll.chosen_slot.is_some());
+        interaction
+            .create_interaction_response(&ctx.http, |response| {
+                response
+                    .kind(InteractionResponseType::UpdateMessage)
+                    .interaction_response_data(|message| {
+                        message.set_embed(embed).set_components(components)
+                    })
+            })
+            .await?;
+        Ok(())
+    }
+
+    async fn handle_toggle(
+        &self,
+        ctx: &Context,
+        interaction: &MessageComponentInteraction,
+        poll: &MeetingPoll,
+        slot: usize,
+    ) -> Result<()> {
+        if poll.chosen_slot.is_some() {
+            return InteractionResponder::for_component(interaction)
+                .reply_ephemeral(ctx, "This poll is already closed.")
+                .await;
+        }
+        if slot >= poll.slots.len() {
+            return InteractionResponder::for_component(interaction)
+                .reply_ephemeral(ctx, "That option no longer exists.")
+                .await;
+        }
+        let user_id = interaction.user.id.to_string();
+        let available = self
+            .database
+            .toggle_meeting_availability(poll.id, slot, &user_id)
+            .await?;
+        info!(
+            "📅 Meeting poll {} | User: {user_id} | Option {} {}",
+            poll.id,
+            slot + 1,
+            if available { "available" } else { "withdrawn" }
+        );
+        self.update_poll(ctx, interaction, poll).await
+    }
+
+    async fn handle_pick(
+        &self,
+        ctx: &Context,
+        interaction: &MessageComponentInteraction,
+        mut poll: MeetingPoll,
+    ) -> Result<()> {
+        let can_manage = interaction
+            .member
+            .as_ref()
+            .and_then(|m| m.permissions)
+            .is_some_and(|p| p.manage_events());
+        if interaction.user.id.to_string() != poll.organizer_id && !can_manage {
+            return InteractionResponder::for_component(interaction)
+                .reply_ephemeral(
+                    ctx,
+                    "Only the organizer (or someone who can manage events) can pick the time.",
+                )
+                .await;
+        }
+
+        let availability = self
+            .database
+            .get_meeting_availability(poll.id, poll.slots.len())
+            .await?;
+        let Some(chosen) = best_slot(&availability) else {
+            return InteractionResponder::for_component(interaction)
+                .reply_ephemeral(ctx, "Nobody has marked their availability yet.")
+                .await;
+        };
+        if !self.database.close_meeting_poll(poll.id, chosen).await? {
+            return InteractionResponder::for_component(interaction)
+                .reply_ephemeral(ctx, "This poll is already closed.")
+                .await;
+        }
+        poll.chosen_slot = Some(chosen);
+        self.update_poll(ctx, interaction, &poll).await?;
+
+        let start = poll.slots[chosen];
+        let participants = &availability[chosen];
+        info!(
+            "📅 Meeting poll {} closed on option {} ({} available)",
+            poll.id,
+            chosen + 1,
+            participants.len()
+        );
+
+        let mut notes = Vec::new();
+        if poll.create_event {
+            match self.create_event(ctx, &poll, start).await {
+                Ok(()) => notes.push("🗓️ Added to the server's events.".to_string()),
+                Err(e) => {
+                    warn!(
+                        "⚠️ Failed to create scheduled event for poll {}: {e}",
+                        poll.id
+                    );
+                    notes.push(
+                        "⚠️ Couldn't create the server event; I need the Manage Events permission."
+                            .to_string(),
+                    );
+                }
+            }
+        }
+        if poll.remind_minutes > 0 {
+            let reminded = self.add_reminders(&poll, start, participants).await?;
+            if reminded > 0 {
+                notes.push(format!(
+                    "⏰ I'll remind everyone who's coming {} minutes before.",
+                    poll.remind_minutes
+                ));
+            }
+        }
+
+        let mentions = participants
+            .iter()
+            .map(|u| format!("<@{u}>"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut announcement = format!(
+            "📅 **{}** is set for <t:{start}:F> (<t:{start}:R>)!\n{mentions}",
+            poll.title
+        );
+        for note in notes {
+            announcement.push('\n');
+            announcement.push_str(&note);
+        }
+        interaction.channel_id.say(&ctx.http, announcement).await?;
+        Ok(())
+    }
+
+    async fn create_event(&self, ctx: &Context, poll: &MeetingPoll, start: i64) -> Result<()> {
+        let guild = GuildId(poll.guild_id.parse()?);
+        let start_time = Timestamp::from_unix_timestamp(start)?;
+        let end_time =
+            Timestamp::from_unix_timestamp(start + i64::from(poll.duration_minutes) * 60)?;
+        let location = match poll.channel_id.parse::<u64>() {
+            Ok(id) => ChannelId(id)
+                .name(&ctx.cache)
+                .await
+                .map(|name| format!("#{name}"))
+                .unwrap_or_else(|| "Discord".to_string()),
+            Err(_) => "Discord".to_string(),
+        };
+        guild
+            .create_scheduled_event(&ctx.http, |event| {
+                event
+                    .name(&poll.title)
+                    .description(format!(
+                        "Scheduled with /meet ({} min)",
+                        poll.duration_minutes
+                    ))
+                    .kind(ScheduledEventType::External)
+                    .location(location)
+                    .start_time(start_time)
+                    .end_time(end_time)
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Queue a reminder for each participant; returns how many were added
+    async fn add_reminders(
+        &self,
+        poll: &MeetingPoll,
+        start: i64,
+        participants: &[String],
+    ) -> Result<usize> {
+        let remind_at = start - i64::from(poll.remind_minutes) * 60;
+        let Some(remind_at) = Utc
+            .timestamp_opt(remind_at, 0)
+            .single()
+            .filter(|at| *at > Utc::now())
+        else {
+            return Ok(0);
+        };
+        let remind_at = remind_at.format("%Y-%m-%d %H:%M:%S").to_string();
+        let text = format!(
+            "\"{}\" starts in {} minutes (<t:{start}:t>)",
+            poll.title, poll.remind_minutes
+        );
+        for user_id in participants {
+            self.database
+                .add_reminder(user_id, &poll.channel_id, &text, &remind_at)
+                .await?;
+        }
+        Ok(participants.len())
+    }
+}
+
+#[async_trait]
+impl ComponentHandler for MeetingButtons {
+    fn features(&self) -> &'static [&'static str] {
+        &[MEETINGS_FEATURE]
+    }
+
+    async fn handle_component(
+        &self,
+        ctx: &Context,
+        interaction: &MessageComponentInteraction,
+        id: &ComponentId,
+    ) -> Result<()> {
+        let parts = id.payload_parts();
+        let poll = match parts.first().and_then(|p| p.parse().ok()) {
+            Some(poll_id) => self.database.get_meeting_poll(poll_id).await?,
+            None => None,
+        };
+        let Some(poll) = poll else {
+            return InteractionResponder::for_component(interaction)
+                .reply_ephemeral(ctx, "This poll no longer exists.")
+                .await;
+        };
+
+        match (
+            id.action.as_str(),
+            parts.get(1).and_then(|s| s.parse().ok()),
+        ) {
+            ("toggle", Some(slot)) => self.handle_toggle(ctx, interaction, &poll, slot).await,
+            ("pick", _) => self.handle_pick(ctx, interaction, poll).await,
+            _ => {
+                InteractionResponder::for_component(interaction)
+                    .reply_ephemeral(ctx, "Unknown poll action.")
+                    .await
+            }
+        }
+    }
+}
@@ -0,0 +1,293 @@
+//! # Meetings Feature
+//!
+//! `/meet propose` availability polls: members toggle the time slots they can
+//! make, and the organizer picks the best one, optionally creating a Discord
+//! Scheduled Event and reminders for everyone who said they'd be there.
+//!
+//! Slots are entered in the organizer's zone (an IANA name, so a poll that
+//! spans a daylight saving change still lands each slot on the right hour)
+//! and stored as unix times, so the poll renders them with Discord
+//! timestamps in each viewer's own timezone.
+//!
+//! - **Version**: 1.1.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.1.0: Slots are read in IANA zones through `core::time`, defaulting to the organizer's /time zone
+//! - 1.0.0: Initial implementation
+
+pub mod buttons;
+
+pub use buttons::MeetingButtons;
+
+use chrono::NaiveDateTime;
+use serenity::builder::{CreateComponents, CreateEmbed};
+use serenity::model::application::component::ButtonStyle;
+
+use crate::commands::components::{ComponentId, PAYLOAD_SEPARATOR};
+use crate::core::Zone;
+use crate::database::MeetingPoll;
+
+/// Feature id for toggles and the component namespace
+pub const MEETINGS_FEATURE: &str = "meetings";
+
+/// Slots per poll (one row of buttons)
+pub const MAX_SLOTS: usize = 5;
+
+pub const DEFAULT_DURATION_MINUTES: u32 = 60;
+pub const DEFAULT_REMIND_MINUTES: u32 = 15;
+
+/// Members listed per slot before collapsing into a count
+const MAX_LISTED_PER_SLOT: usize = 10;
+
+/// Parse `YYYY-MM-DD HH:MM` slots separated by commas, semicolons or newlines
+///
+/// Times are local to `zone`; the result is sorted unix start times, all
+/// after `now`.
+pub fn parse_slots(input: &str, zone: &Zone, now: i64) -> Result<Vec<i64>, String> {
+    let mut slots = Vec::new();
+    for raw in input
+        .split([',', ';', '\n'])
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        let local = NaiveDateTime::parse_from_str(raw, "%Y-%m-%d %H:%M")
+            .map_err(|_| format!("`{raw}` isn't a time like `2026-10-20 15:00`"))?;
+        let start = zone.to_unix(local);
+        if start <= now {
+            return Err(format!("`{raw}` is already in the past"));
+        }
+        if !slots.contains(&start) {
+            slots.push(start);
+        }
+    }
+    if slots.is_empty() {
+        return Err(
+            "Give at least one time, like `2026-10-20 15:00, 2026-10-21 09:30`".to_string(),
+        );
+    }
+    if slots.len() > MAX_SLOTS {
+        return Err(format!("A poll can offer at most {MAX_SLOTS} times"));
+    }
+    slots.sort_unstable();
+    Ok(slots)
+}
+
+/// The slot most members can make, earliest first on ties; None if nobody answered
+pub fn best_slot(availability: &[Vec<String>]) -> Option<usize> {
+    availability
+        .iter()
+        .enumerate()
+        .filter(|(_, users)| !users.is_empty())
+        .max_by(|(a_index, a), (b_index, b)| a.len().cmp(&b.len()).then(b_index.cmp(a_index)))
+        .map(|(index, _)| index)
+}
+
+/// Poll embed showing every slot with who can make it
+pub fn poll_embed(poll: &MeetingPoll, availability: &[Vec<String>]) -> CreateEmbed {
+    let leading = best_slot(availability);
+    let mut description = String::new();
+    for (index, start) in poll.slots.iter().enumerate() {
+        let users = availability.get(index).map(Vec::as_slice).unwrap_or(&[]);
+        let marker = match (poll.chosen_slot, leading) {
+            (Some(chosen), _) if chosen == index => "✅ ",
+            (None, Some(best)) if best == index => "⭐ ",
+            _ => "",
+        };
+        description.push_str(&format!(
+            "{marker}**{}.** <t:{start}:F> (<t:{start}:R>)\n",
+            index + 1
+        ));
+        let mentions = users
+            .iter()
+            .take(MAX_LISTED_PER_SLOT)
+            .map(|u| format!("<@{u}>"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        description.push_str(&match users.len() {
+            0 => "└ nobody yet\n".to_string(),
+            n if n > MAX_LISTED_PER_SLOT => {
+                format!("└ {n} available: {mentions} +{}\n", n - MAX_LISTED_PER_SLOT)
+            }
+            n => format!("└ {n} available: {mentions}\n"),
+        });
+    }
+
+    let mut embed = CreateEmbed::default();
+    embed
+        .title(format!("📅 {}", poll.title))
+        .description(description)
+        .color(if poll.chosen_slot.is_some() {
+            0x2ECC71
+        } else {
+            0x5865F2
+        })
+        .footer(|f| {
+            f.text(match poll.chosen_slot {
+                Some(_) => "Poll closed".to_string(),
+                None => format!(
+                    "Proposed in {}, shown in your timezone · tap every option you can make",
+                    poll.timezone
+                ),
+            })
+        });
+    embed.field("Organizer", format!("<@{}>", poll.organizer_id), true);
+    embed.field("Length", format!("{} min", poll.duration_minutes), true);
+    embed
+}
+
+/// One toggle button per slot, plus the organizer's "pick" button
+pub fn poll_buttons(poll_id: i64, slot_count: usize, closed: bool) -> CreateComponents {
+    let mut components = CreateComponents::default();
+    components.create_action_row(|row| {
+        for index in 0..slot_count.min(MAX_SLOTS) {
+            let id = ComponentId::new(
+                MEETINGS_FEATURE,
+                "toggle",
+                format!("{poll_id}{PAYLOAD_SEPARATOR}{index}"),
+            );
+            row.create_button(|btn| {
+                btn.custom_id(id.to_string())
+                    .label(format!("Option {}", index + 1))
+                    .style(ButtonStyle::Primary)
+                    .disabled(closed)
+            });
+        }
+        row
+    });
+    components.create_action_row(|row| {
+        row.create_button(|btn| {
+            btn.custom_id(
+                ComponentId::new(MEETINGS_FEATURE, "pick", poll_id.to_string()).to_string(),
+            )
+            .label("Pick best time")
+            .emoji('✅')
+            .style(ButtonStyle::Success)
+            .disabled(closed)
+        })
+    });
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_760_000_000; // 2025-10-09 08:53:20 UTC
+
+    fn poll() -> MeetingPoll {
+        MeetingPoll {
+            id: 7,
+            guild_id: "g1".to_string(),
+            channel_id: "c1".to_string(),
+            organizer_id: "1".to_string(),
+            title: "Sprint planning".to_string(),
+            slots: vec![1_760_100_000, 1_760_200_000, 1_760_300_000],
+            timezone: Zone::Fixed(-300),
+            duration_minutes: 45,
+            create_event: false,
+            remind_minutes: 15,
+            chosen_slot: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_slots_applies_offset() {
+        let utc = Zone::Fixed(0);
+        let slots =
+            parse_slots("2025-10-21 09:30; 2025-10-20 15:00", &Zone::Fixed(120), NOW).unwrap();
+        // 15:00 at UTC+2 is 13:00 UTC, and slots come back in order
+        assert_eq!(
+            slots,
+            vec![
+                NaiveDateTime::parse_from_str("2025-10-20 13:00", "%Y-%m-%d %H:%M")
+                    .unwrap()
+                    .and_utc()
+                    .timestamp(),
+                NaiveDateTime::parse_from_str("2025-10-21 07:30", "%Y-%m-%d %H:%M")
+                    .unwrap()
+                    .and_utc()
+                    .timestamp(),
+            ]
+        );
+
+        assert!(parse_slots("2025-10-01 10:00", &utc, NOW)
+            .unwrap_err()
+            .contains("in the past"));
+        assert!(parse_slots("tomorrow at 3", &utc, NOW).is_err());
+        assert!(parse_slots(" , ", &utc, NOW).is_err());
+        let too_many = (10..16)
+            .map(|day| format!("2025-10-{day} 10:00"))
+            .collect::<Vec<_>>()
+            .join(",");
+        assert!(parse_slots(&too_many, &utc, NOW).is_err());
+    }
+
+    #[test]
+    fn test_parse_slots_follows_daylight_saving() {
+        // Berlin leaves summer time on 2025-10-26, so 15:00 is 13:00 UTC before and 14:00 after
+        let berlin = Zone::Named(chrono_tz::Tz::Europe__Berlin);
+        let at = |value| {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M")
+                .unwrap()
+                .and_utc()
+                .timestamp()
+        };
+        assert_eq!(
+            parse_slots("2025-10-25 15:00, 2025-10-27 15:00", &berlin, NOW).unwrap(),
+            vec![at("2025-10-25 13:00"), at("2025-10-27 14:00")]
+        );
+    }
+
+    #[test]
+    fn test_best_slot_prefers_most_then_earliest() {
+        let users = |ids: &[&str]| ids.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(best_slot(&[vec![], vec![]]), None);
+        assert_eq!(
+            best_slot(&[users(&["a"]), users(&["a", "b"]), users(&["b", "c"])]),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_poll_embed_marks_leading_and_chosen_slots() {
+        let availability = vec![vec![], vec!["2".to_string(), "3".to_string()], vec![]];
+        let embed = poll_embed(&poll(), &availability);
+        let description = embed.0["description"].as_str().unwrap();
+        assert!(description.contains("⭐ **2.** <t:1760200000:F>"));
+        assert!(description.contains("└ 2 available: <@2> <@3>"));
+        assert!(description.contains("└ nobody yet"));
+        assert!(embed.0["footer"]["text"]
+            .as_str()
+            .unwrap()
+            .contains("UTC-05:00"));
+
+        let closed = MeetingPoll {
+            chosen_slot: Some(1),
+            ..poll()
+        };
+        let embed = poll_embed(&closed, &availability);
+        assert!(embed.0["description"]
+            .as_str()
+            .unwrap()
+            .contains("✅ **2.**"));
+        assert_eq!(embed.0["footer"]["text"], "Poll closed");
+    }
+
+    #[test]
+    fn test_poll_buttons_encode_poll_and_slot() {
+        let components = poll_buttons(7, 3, false);
+        let toggles = components.0[0]["components"].as_array().unwrap();
+        assert_eq!(toggles.len(), 3);
+        let id = ComponentId::parse(toggles[2]["custom_id"].as_str().unwrap()).unwrap();
+        assert_eq!(id.feature, MEETINGS_FEATURE);
+        assert_eq!(id.payload_parts(), ["7", "2"]);
+
+        let pick = &components.0[1]["components"][0];
+        assert_eq!(pick["custom_id"], "meetings:pick:7");
+
+        let closed = poll_buttons(7, 3, true);
+        assert_eq!(closed.0[0]["components"][0]["disabled"], true);
+    }
+}
@@ -0,0 +1,138 @@
+//! # Feature: Pinned Memories
+//!
+//! The "Remember this" message context menu pins a message (the bot's or
+//! anyone's) as a memory for the member in that channel. Pinned memories are
+//! added to the system prompt of every reply to that member there, so they
+//! outrank the rolling conversation history and never scroll out of it.
+//! Members list and forget their memories with `/memory`.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with the Remember this context menu and /memory
+
+use anyhow::Result;
+
+use crate::database::{Database, PinnedMemory};
+
+/// Feature id for toggles
+pub const MEMORIES_FEATURE: &str = "memories";
+
+/// Name of the message context menu command
+pub const REMEMBER_COMMAND: &str = "Remember this";
+
+/// Memories a member can pin per channel
+pub const MAX_MEMORIES_PER_CHANNEL: usize = 20;
+
+/// Longest memory kept; longer messages are cut
+pub const MAX_MEMORY_CHARS: usize = 1000;
+
+/// Stored as the author of the bot's own messages
+pub const BOT_AUTHOR: &str = "assistant";
+
+const MEMORY_HEADER: &str = "## Pinned Memories\n\
+The user pinned these messages for you to remember. They take priority over the conversation \
+history: keep them in mind, and trust them over older messages when the two disagree.";
+
+/// Text to pin from a message's content or, for embeds, their description
+pub fn memory_text(content: &str, embed_description: Option<&str>) -> Option<String> {
+    let text = Some(content.trim())
+        .filter(|c| !c.is_empty())
+        .or_else(|| embed_description.map(str::trim).filter(|d| !d.is_empty()))?;
+    Some(text.chars().take(MAX_MEMORY_CHARS).collect())
+}
+
+/// Who a memory came from, for prompts and lists
+pub fn author_label(memory: &PinnedMemory) -> &str {
+    if memory.author_name == BOT_AUTHOR {
+        "You"
+    } else {
+        &memory.author_name
+    }
+}
+
+/// System prompt section listing pinned memories, if there are any
+pub fn memory_section(memories: &[PinnedMemory]) -> Option<String> {
+    if memories.is_empty() {
+        return None;
+    }
+    let lines = memories
+        .iter()
+        .map(|m| format!("- {} said: {}", author_label(m), m.content))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(format!("{MEMORY_HEADER}\n{lines}"))
+}
+
+/// The system prompt with the member's pinned memories for the channel appended
+pub async fn apply_memories(
+    database: &Database,
+    user_id: Option<&str>,
+    guild_id: Option<&str>,
+    channel_id: Option<&str>,
+    system_prompt: String,
+) -> Result<String> {
+    let (Some(user_id), Some(channel_id)) = (user_id, channel_id) else {
+        return Ok(system_prompt);
+    };
+    if !database
+        .is_feature_enabled(MEMORIES_FEATURE, None, guild_id)
+        .await?
+    {
+        return Ok(system_prompt);
+    }
+    let memories = database
+        .get_pinned_memories(user_id, Some(channel_id))
+        .await?;
+    Ok(match memory_section(&memories) {
+        Some(section) => format!("{system_prompt}\n\n{section}"),
+        None => system_prompt,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_text() {
+        assert_eq!(memory_text("  hi  ", None), Some("hi".to_string()));
+        assert_eq!(
+            memory_text("", Some("From the embed")),
+            Some("From the embed".to_string())
+        );
+        assert_eq!(memory_text(" ", Some(" ")), None);
+        assert_eq!(
+            memory_text(&"a".repeat(1500), None).unwrap().len(),
+            MAX_MEMORY_CHARS
+        );
+    }
+
+    #[tokio::test]
+    async fn test_apply_memories() {
+        let db = Database::new(":memory:").await.unwrap();
+        let prompt = apply_memories(&db, Some("u1"), None, Some("c1"), "Base".to_string())
+            .await
+            .unwrap();
+        assert_eq!(prompt, "Base");
+
+        db.add_pinned_memory("u1", "c1", "m1", "My cat is called Miso", "Ada")
+            .await
+            .unwrap();
+        db.add_pinned_memory("u1", "c1", "m2", "Use metric units", BOT_AUTHOR)
+            .await
+            .unwrap();
+        let prompt = apply_memories(&db, Some("u1"), None, Some("c1"), "Base".to_string())
+            .await
+            .unwrap();
+        assert!(prompt.starts_with("Base\n\n## Pinned Memories\n"));
+        assert!(prompt.ends_with("- Ada said: My cat is called Miso\n- You said: Use metric units"));
+
+        let other = apply_memories(&db, Some("u1"), None, Some("c2"), "Base".to_string())
+            .await
+            .unwrap();
+        assert_eq!(other, "Base");
+    }
+}
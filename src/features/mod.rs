@@ -3,28 +3,72 @@
 //! Central registry for all bot features with version tracking and runtime toggles,
 //! plus all feature module declarations.
 //!
-//! - **Version**: 2.0.0
+//! - **Version**: 2.1.0
 //! - **Since**: 0.2.0
 //! - **Toggleable**: false
 //!
 //! ## Changelog
+//! - 2.1.0: Features declare their dependencies for the architecture map
 //! - 2.0.0: Reorganized as parent module with feature subdirectories
 //! - 1.0.0: Initial feature registry implementation
 
 // Feature submodules
+pub mod achievements;
+pub mod aliases;
 pub mod analytics;
+pub mod anon_questions;
 pub mod audio;
+pub mod autoresponse;
+pub mod birthdays;
+pub mod channel_topics;
 pub mod conflict;
+pub mod conversation_export;
+pub mod conversions;
 pub mod council;
+pub mod countdown;
 pub mod debate;
+pub mod dice;
+pub mod disclosure;
 pub mod discussion;
+pub mod dm_history;
+pub mod glossary;
+pub mod guardrails;
 pub mod image_gen;
 pub mod introspection;
+pub mod issues;
+pub mod language;
+pub mod leveling;
+pub mod link_preview;
+#[cfg(feature = "matrix")]
+pub mod matrix;
+pub mod meetings;
+pub mod memories;
+pub mod ocr;
 pub mod personas;
 pub mod plugins;
+pub mod presence;
+pub mod prompt_library;
+pub mod qotd;
 pub mod rate_limiting;
 pub mod reminders;
+pub mod resilience;
+pub mod scenes;
+pub mod send_later;
+pub mod sentiment;
+pub mod server_report;
+pub mod standup;
 pub mod startup;
+pub mod story;
+pub mod suggestions;
+pub mod support;
+#[cfg(feature = "telegram")]
+pub mod telegram;
+pub mod timezones;
+pub mod trivia;
+pub mod updater;
+pub mod verification;
+pub mod voice_commands;
+pub mod webhooks;
 
 // Re-export commonly used items from submodules
 pub use analytics::{
@@ -33,6 +77,7 @@ pub use analytics::{
     UsageTracker,
 };
 pub use audio::{AudioTranscriber, TranscriptionResult};
+pub use birthdays::BirthdayScheduler;
 pub use conflict::{ConflictDetector, ConflictMediator};
 pub use council::{get_active_councils, CouncilMessage, CouncilState};
 pub use debate::{
@@ -40,17 +85,22 @@ pub use debate::{
     CONTINUE_ROUNDS,
 };
 pub use discussion::{
-    create_awaiting_buttons, create_council_buttons, create_debate_buttons, detect_thread_context,
-    format_prior_context, parse_council_speaker_id, parse_debate_hear_id, DiscussionMessage,
-    DiscussionType, ThreadContext, CONTINUE_COUNCIL_PREFIX, CONTINUE_DEBATE_PREFIX,
-    DISMISS_COUNCIL_PREFIX, END_DEBATE_PREFIX, HEAR_DEBATE_PREFIX, SPEAKER_COUNCIL_PREFIX,
+    create_awaiting_buttons, create_council_buttons, create_debate_buttons,
+    create_debate_vote_buttons, detect_thread_context,
+    format_prior_context, parse_council_speaker_id, parse_debate_hear_id, parse_discussion_target,
+    restore_all_discussion_states, restore_discussion_state, save_discussion_state,
+    save_discussion_state_logged, DiscussionMessage, DiscussionType, ThreadContext, CONTINUE_COUNCIL_PREFIX,
+    CONTINUE_DEBATE_PREFIX, DISMISS_COUNCIL_PREFIX, END_DEBATE_PREFIX, HEAR_DEBATE_PREFIX,
+    SPEAKER_COUNCIL_PREFIX,
 };
 pub use image_gen::{GeneratedImage, ImageGenerator, ImageSize, ImageStyle};
 pub use introspection::get_component_snippet;
 pub use personas::{Persona, PersonaManager};
 pub use plugins::{JobManager, OutputHandler, Plugin, PluginConfig, PluginExecutor, PluginManager};
-pub use rate_limiting::RateLimiter;
+pub use presence::PresenceRotator;
+pub use rate_limiting::{RateLimiter, SimilarityThrottle};
 pub use reminders::ReminderScheduler;
+pub use resilience::{circuit_breakers, CircuitOpenError, Dependency};
 pub use startup::StartupNotifier;
 
 // ============================================================================
@@ -70,6 +120,8 @@ pub struct Feature {
     pub since: &'static str,
     /// Can be toggled at runtime by admins
     pub toggleable: bool,
+    /// IDs of the features this one builds on
+    pub dependencies: &'static [&'static str],
     /// Brief description
     pub description: &'static str,
 }
@@ -79,9 +131,10 @@ pub const FEATURES: &[Feature] = &[
     Feature {
         id: "personas",
         name: "Persona System",
-        version: "1.6.0",
+        version: "1.8.0",
         since: "0.1.0",
         toggleable: false,
+        dependencies: &[],
         description: "Multi-personality AI responses with 17 distinct personas including software dev specialists",
     },
     Feature {
@@ -90,55 +143,62 @@ pub const FEATURES: &[Feature] = &[
         version: "1.0.0",
         since: "0.1.0",
         toggleable: true,
+        dependencies: &["personas"],
         description: "Scheduled reminder system with persona-aware delivery",
     },
     Feature {
         id: "conflict_detection",
         name: "Conflict Detection",
-        version: "1.0.0",
+        version: "1.1.0",
         since: "0.1.0",
         toggleable: true,
+        dependencies: &[],
         description: "Detects heated discussions using keyword and pattern analysis",
     },
     Feature {
         id: "conflict_mediation",
         name: "Conflict Mediation",
-        version: "1.0.0",
+        version: "1.1.0",
         since: "0.1.0",
         toggleable: true,
+        dependencies: &["conflict_detection", "personas"],
         description: "Obi-Wan themed interventions for heated conversations",
     },
     Feature {
         id: "image_generation",
         name: "Image Generation",
-        version: "1.0.0",
+        version: "1.1.1",
         since: "0.2.0",
         toggleable: true,
+        dependencies: &["usage_tracking"],
         description: "DALL-E 3 powered image creation with size and style options",
     },
     Feature {
         id: "audio_transcription",
         name: "Audio Transcription",
-        version: "1.3.0",
+        version: "1.8.0",
         since: "0.1.0",
         toggleable: true,
+        dependencies: &["usage_tracking"],
         description: "Whisper-powered transcription with configurable output modes",
     },
     Feature {
         id: "introspection",
         name: "Self-Introspection",
-        version: "1.0.0",
+        version: "1.2.1",
         since: "0.1.0",
         toggleable: false,
-        description: "Bot can explain its own internals and architecture",
+        dependencies: &["personas", "usage_tracking"],
+        description: "Bot can explain its own internals, allowlisted source files and a live architecture map built from this registry",
     },
     Feature {
         id: "rate_limiting",
         name: "Rate Limiting",
-        version: "1.0.0",
+        version: "1.1.1",
         since: "0.1.0",
         toggleable: false,
-        description: "Prevents spam with configurable request limits per user",
+        dependencies: &[],
+        description: "Prevents spam with per-user request limits and near-duplicate prompt cooldowns",
     },
     Feature {
         id: "verbosity_control",
@@ -146,6 +206,7 @@ pub const FEATURES: &[Feature] = &[
         version: "1.0.0",
         since: "0.1.0",
         toggleable: false,
+        dependencies: &["guild_settings"],
         description: "Per-channel response length settings (concise/normal/detailed)",
     },
     Feature {
@@ -154,71 +215,89 @@ pub const FEATURES: &[Feature] = &[
         version: "1.0.0",
         since: "0.1.0",
         toggleable: false,
+        dependencies: &[],
         description: "Server-wide configuration and defaults",
     },
     Feature {
         id: "system_info",
         name: "System Information",
-        version: "1.0.0",
+        version: "1.6.0",
         since: "0.3.0",
         toggleable: false,
-        description: "System diagnostics and historical resource metrics tracking",
+        dependencies: &[],
+        description: "System diagnostics, storage, network, task and OpenAI in-flight metrics with 1-minute/1-hour/1-day history tiers",
+    },
+    Feature {
+        id: "alerts",
+        name: "Metric Alerts",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: false,
+        dependencies: &["system_info", "usage_tracking"],
+        description: "Memory, error rate and daily spend rules with cooldowns, sent to an ops channel or owner DM",
     },
     Feature {
         id: "startup_notification",
         name: "Startup Notification",
-        version: "1.9.0",
+        version: "1.11.0",
         since: "0.4.0",
         toggleable: true,
-        description: "Rich notifications when bot comes online with plugin versions and detailed commit threads",
+        dependencies: &["system_info"],
+        description: "Rich notifications when bot comes online with deployment diff, migrations, plugin status and commit threads",
     },
     Feature {
         id: "usage_tracking",
         name: "Usage Tracking",
-        version: "1.2.0",
+        version: "1.3.0",
         since: "0.5.0",
         toggleable: false,
+        dependencies: &[],
         description: "OpenAI API usage and cost tracking with /usage command",
     },
     Feature {
         id: "dm_interaction_tracking",
         name: "DM Interaction Tracking",
-        version: "1.0.0",
+        version: "1.2.0",
         since: "0.6.0",
         toggleable: false,
-        description: "Comprehensive DM session and engagement metrics with user-facing analytics",
+        dependencies: &["usage_tracking"],
+        description: "Comprehensive DM session and engagement metrics with user-facing analytics; honours /privacy opt-outs",
     },
     Feature {
         id: "plugins",
         name: "Plugin System",
-        version: "4.0.1",
+        version: "4.10.0",
         since: "0.9.0",
         toggleable: true,
+        dependencies: &["audio_transcription", "usage_tracking"],
         description: "Config-based CLI command plugins with background execution via /plugins subcommands",
     },
     Feature {
         id: "debate",
         name: "Persona Debates",
-        version: "2.0.0",
+        version: "2.3.0",
         since: "3.27.0",
         toggleable: true,
-        description: "Threaded debates with interactive controls, rules support, and council interoperability",
+        dependencies: &["personas", "discussion"],
+        description: "Threaded debates with interactive controls, rules support, council interoperability, an audience vote on who won and /debate import to continue a transcript from elsewhere",
     },
     Feature {
         id: "council",
         name: "Persona Council",
-        version: "2.0.0",
+        version: "2.3.0",
         since: "3.31.0",
         toggleable: true,
-        description: "Multi-persona discussions with interactive controls, rules support, and debate interoperability",
+        dependencies: &["personas", "discussion"],
+        description: "Multi-persona discussions with interactive controls, rules support, debate interoperability and a member vote on the conclusion at /conclude",
     },
     Feature {
         id: "discussion",
         name: "Discussion Interoperability",
-        version: "1.0.0",
+        version: "1.3.1",
         since: "3.33.0",
         toggleable: false,
-        description: "Shared context and controls between council and debate sessions",
+        dependencies: &[],
+        description: "Shared context and controls between council and debate sessions; buttons survive restarts; parses imported transcripts",
     },
     Feature {
         id: "web_fetch",
@@ -226,8 +305,403 @@ pub const FEATURES: &[Feature] = &[
         version: "1.1.0",
         since: "4.2.0",
         toggleable: false,
+        dependencies: &["personas", "usage_tracking"],
         description: "Fetch webpages and files with persona-flavored summaries, Q&A, and file downloads",
     },
+    Feature {
+        id: "circuit_breakers",
+        name: "Circuit Breakers",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: false,
+        dependencies: &[],
+        description: "Temporarily disables features whose external dependency is failing",
+    },
+    Feature {
+        id: "fault_injection",
+        name: "Fault Injection",
+        version: "1.2.0",
+        since: "4.7.0",
+        toggleable: false,
+        dependencies: &["circuit_breakers"],
+        description: "Hidden chaos flags that inject OpenAI, Discord and database failures",
+    },
+    Feature {
+        id: "crash_reporter",
+        name: "Crash Reporter",
+        version: "1.1.0",
+        since: "4.7.0",
+        toggleable: false,
+        dependencies: &["startup_notification"],
+        description: "Panic hook with log breadcrumbs, DM'd to the owner on the next startup",
+    },
+    Feature {
+        id: "self_update",
+        name: "Self-Update",
+        version: "1.0.1",
+        since: "4.7.0",
+        toggleable: false,
+        dependencies: &["plugins"],
+        description: "Owner-only /admin update from GitHub releases with checksum check and graceful restart",
+    },
+    Feature {
+        id: "activity_status",
+        name: "Activity Status Rotation",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: false,
+        dependencies: &["plugins"],
+        description: "Rotating presence messages with live server and job counts, set with /admin presence",
+    },
+    Feature {
+        id: "persona_themes",
+        name: "Persona Rotation & Seasonal Themes",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: false,
+        dependencies: &["personas", "guild_settings"],
+        description: "Daily guild default persona rotation and dated seasonal prompt themes with automatic reversion",
+    },
+    Feature {
+        id: "birthdays",
+        name: "Birthdays & Anniversaries",
+        version: "1.1.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["personas", "guild_settings"],
+        description: "Persona-voiced birthday and anniversary wishes on each member's local date, with opt-out",
+    },
+    Feature {
+        id: "trivia",
+        name: "Trivia",
+        version: "1.2.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["personas"],
+        description: "Persona-hosted quiz games with button or typed answers, speed scoring and a per-guild leaderboard",
+    },
+    Feature {
+        id: "story",
+        name: "Story Collaboration",
+        version: "1.0.1",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["personas", "council"],
+        description: "Threads where a persona and members alternate paragraphs, ending with a written conclusion and Markdown export",
+    },
+    Feature {
+        id: "standup",
+        name: "Daily Standups",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["guild_settings"],
+        description: "Scheduled DM check-ins for yesterday, today and blockers, compiled into a summary in a team channel",
+    },
+    Feature {
+        id: "meetings",
+        name: "Meeting Polls",
+        version: "1.1.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["reminders"],
+        description: "Availability polls with per-slot buttons that pick the best time and optionally create a server event and reminders",
+    },
+    Feature {
+        id: "issues",
+        name: "Issue Tracker Tickets",
+        version: "1.0.1",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["guild_settings", "usage_tracking"],
+        description: "File messages as Jira or Linear issues from /ticket or the Create Ticket context menu, with optional AI summaries and per-guild credentials",
+    },
+    Feature {
+        id: "webhooks",
+        name: "Outbound Webhooks",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["guild_settings", "usage_tracking"],
+        description: "POST job, debate, conflict and daily budget events to per-guild Slack, Matrix or generic webhooks with templates and retries",
+    },
+    Feature {
+        id: "image_ocr",
+        name: "Image OCR",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["personas"],
+        description: "Text in image attachments is read with tesseract and added to the attachment context so it can be asked about",
+    },
+    Feature {
+        id: "link_previews",
+        name: "Link Previews",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["guild_settings", "usage_tracking"],
+        description: "Opt-in per channel: bare URLs in plain messages are fetched and summarized in a short reply or thread, rate-limited and deduplicated per channel",
+    },
+    Feature {
+        id: "server_report",
+        name: "Server Reports",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["guild_settings", "usage_tracking", "personas"],
+        description: "Weekly state of the server report in an admin channel: activity trends, busiest channels, trending topics, conflicts and bot spend, narrated by the default persona",
+    },
+    Feature {
+        id: "topics",
+        name: "Trending Topics",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["usage_tracking"],
+        description: "/topics embeds a channel's recent messages, clusters them with k-means and lists the trending topics with example messages",
+    },
+    Feature {
+        id: "sentiment",
+        name: "Channel Sentiment",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["conflict_detection", "guild_settings"],
+        description: "Opt-in per channel: messages are scored for sentiment and stored hourly, shown as a mood timeline in the TUI Stats screen and via /mood",
+    },
+    Feature {
+        id: "profiles",
+        name: "Profile Cards",
+        version: "1.1.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["personas", "usage_tracking", "debate", "trivia"],
+        description: "/profile shows a member's favorite persona, message counts, top commands, transcription minutes, debate wins, trivia record, badges and join date",
+    },
+    Feature {
+        id: "achievements",
+        name: "Achievements",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["usage_tracking", "debate", "trivia", "audio_transcription"],
+        description: "Badges for milestones like 100 /ask uses, a first debate win or 10 hours transcribed, announced in an optional channel and shown on /profile",
+    },
+    Feature {
+        id: "leveling",
+        name: "XP & Levels",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &[],
+        description: "Opt-in per-guild XP for chatting and using the bot with cooldowns, level-up announcements, role rewards, /rank and /leaderboard",
+    },
+    Feature {
+        id: "language_enforcement",
+        name: "Channel Language",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &[],
+        description: "Per-channel designated language with gentle persona-voiced nudges and a translation when members post in another language, with an exempt role and a daily cap",
+    },
+    Feature {
+        id: "channel_topics",
+        name: "Scheduled Channel Topics",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &[],
+        description: "Rotate a channel's topic through admin-written topics or set an AI-written question of the day on a schedule with /channel_topic",
+    },
+    Feature {
+        id: "qotd",
+        name: "Question of the Day",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["personas"],
+        description: "Daily question from an admin queue or written by the channel's persona, with optional answer threads and a digest of the best-reacted answer, managed with /qotd",
+    },
+    Feature {
+        id: "suggestions",
+        name: "Suggestion Box",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &[],
+        description: "/suggest posts ideas to a suggestion channel with upvote and downvote buttons; admins mark them Accepted, Declined or Implemented and /suggestions lists them by status",
+    },
+    Feature {
+        id: "support",
+        name: "Support Tickets",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["personas"],
+        description: "/support open creates a private thread with the support role where the persona can answer first from the knowledge base; /support close archives it with an AI summary and admins get ticket metrics",
+    },
+    Feature {
+        id: "anon_questions",
+        name: "Anonymous Questions",
+        version: "1.0.1",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["personas"],
+        description: "/ask_anon posts a question to a configured channel without the author's name, optionally after moderator approval, with an optional persona answer; admins can reveal the author to handle abuse",
+    },
+    Feature {
+        id: "verification",
+        name: "Verification Gate",
+        version: "1.0.1",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["personas"],
+        description: "New members click a button or answer a simple persona-posed question in a gated channel to get the member role, with an optional timeout kick, configured with /verification",
+    },
+    Feature {
+        id: "autoresponse",
+        name: "Auto-Responses",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["personas"],
+        description: "Admins define keyword or regex triggers with /autoresponse that answer matching messages with a canned reply or a persona-generated response, with per-trigger cooldowns, enable/disable and priority ordering",
+    },
+    Feature {
+        id: "aliases",
+        name: "Prompt Aliases",
+        version: "1.1.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["personas"],
+        description: "/alias create saves personal or server-shared prompt templates with ${arg} placeholders; /alias run fills them in and asks a persona through the /ask pipeline",
+    },
+    Feature {
+        id: "prompt_library",
+        name: "Prompt Library",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["personas", "image_generation"],
+        description: "A per-server /prompts library of named prompts with categories and ${variable} placeholders, run through /ask or /imagine, with JSON export and import",
+    },
+    Feature {
+        id: "conversation_export",
+        name: "Conversation Export",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["personas"],
+        description: "The Export Conversation context menu sends a Markdown file of a thread or a member's stored exchange with the bot, with persona attribution and timestamps",
+    },
+    Feature {
+        id: "send_later",
+        name: "Send Later",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["personas"],
+        description: "/send_later queues a message for a channel at a future time, optionally rewritten by the member's persona, with list and cancel",
+    },
+    Feature {
+        id: "countdown",
+        name: "Countdowns",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &[],
+        description: "/countdown posts embeds counting down to an event, updated every minute and pinging an optional role at zero, persisted across restarts",
+    },
+    Feature {
+        id: "timezones",
+        name: "Time Zones",
+        version: "1.1.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &[],
+        description: "/time zone stores a member's time zone (IANA name or UTC offset) and /time convert shares times as Discord timestamps; opted-in channels get clock times in messages converted automatically",
+    },
+    Feature {
+        id: "convert",
+        name: "Conversions",
+        version: "1.1.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &[],
+        description: "/convert turns amounts between units and currencies, with exchange rates from a configurable API cached in memory and offline fallbacks, optionally phrased by the member's persona",
+    },
+    Feature {
+        id: "dice",
+        name: "Dice & RPG Toolkit",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &[],
+        description: "/roll rolls dice notation with keep-highest, advantage and optional persona narration; /initiative keeps a stored turn order per thread for ongoing sessions",
+    },
+    Feature {
+        id: "voice_commands",
+        name: "Voice Commands",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["audio_transcription"],
+        description: "Transcribed voice messages starting with a wake phrase like \"hey bot\" run as spoken /remind, /roll and /convert commands, with the confirmation posted as text",
+    },
+    Feature {
+        id: "disclosure",
+        name: "Answer Disclosure",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["personas"],
+        description: "Optional /ask mode where the model states its confidence, sources and assumptions, shown in the embed footer; on per server with answer_disclosure or per question with the disclosure option",
+    },
+    Feature {
+        id: "guardrails",
+        name: "Guardrail Profiles",
+        version: "1.2.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["personas", "conflict_detection"],
+        description: "Per-server strict, standard or relaxed safety profile that sets the system-prompt safety preamble, the conflict mediation threshold and which personas are available; NSFW channels can use their own profile and refuse /imagine",
+    },
+    Feature {
+        id: "dm_history",
+        name: "DM History",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["dm_interaction_tracking", "personas"],
+        description: "Generated titles for DM sessions and /history, which lists past sessions with titles and dates and offers a recap or resumes a session's context from a select menu",
+    },
+    Feature {
+        id: "memories",
+        name: "Pinned Memories",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["personas"],
+        description: "Remember this message context menu that pins a message as a memory for the member in that channel, added to the system prompt ahead of the conversation history; listed and forgotten with /memory",
+    },    Feature {
+        id: "scenes",
+        name: "Channel Scenes",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["personas"],
+        description: "Scene or world description set per channel with /set_channel scene, added to every persona prompt there (mentions, /ask, councils, debates and stories) so roleplay channels stay in-world",
+    },    Feature {
+        id: "glossary",
+        name: "Guild Glossary",
+        version: "1.0.0",
+        since: "4.7.0",
+        toggleable: true,
+        dependencies: &["personas"],
+        description: "Server-specific terms and acronyms defined with /glossary add; definitions of the terms a message mentions are added to the persona's system prompt so answers use the community's vocabulary",
+    },
 ];
 
 /// Get all registered features
@@ -310,6 +784,20 @@ mod tests {
         assert_eq!(ids.len(), original_len, "Feature IDs should be unique");
     }
 
+    #[test]
+    fn test_feature_dependencies_are_registered() {
+        for feature in get_features() {
+            for dep in feature.dependencies {
+                assert!(
+                    get_feature(dep).is_some(),
+                    "{} depends on unregistered feature {dep}",
+                    feature.id
+                );
+                assert_ne!(*dep, feature.id, "{} depends on itself", feature.id);
+            }
+        }
+    }
+
     #[test]
     fn test_format_features_list() {
         let output = format_features_list();
@@ -0,0 +1,28 @@
+//! # OCR Feature
+//!
+//! Read the text in image attachments (screenshots, whiteboards, photos of
+//! documents) with tesseract so it joins the attachment context and can be
+//! asked about like a text file.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with tesseract, per-guild toggle and size limits
+
+pub mod reader;
+
+pub use reader::{clean_ocr_text, is_image_file, is_tesseract_available, read_image_attachment};
+
+/// Feature id for toggles
+pub const OCR_FEATURE: &str = "image_ocr";
+
+/// Largest image read (Discord allows bigger uploads, tesseract gets slow)
+pub const MAX_IMAGE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Images read from a single message
+pub const MAX_IMAGES_PER_MESSAGE: usize = 4;
+
+/// Characters of recognized text kept per image
+pub const MAX_TEXT_CHARS: usize = 20_000;
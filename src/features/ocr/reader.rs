@@ -0,0 +1,164 @@
+//! Download an image attachment and run tesseract over it
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use log::{debug, info, warn};
+use std::process::Stdio;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+use tokio::time::timeout;
+
+use super::{MAX_IMAGE_BYTES, MAX_TEXT_CHARS};
+
+/// Image formats tesseract reads
+const IMAGE_FORMATS: &[&str] = &[
+    ".png", ".jpg", ".jpeg", ".webp", ".bmp", ".gif", ".tif", ".tiff",
+];
+
+/// Longest tesseract run before giving up on an image
+const OCR_TIMEOUT_SECS: u64 = 30;
+
+/// Recognized text shorter than this is treated as "no text in the image"
+const MIN_TEXT_CHARS: usize = 3;
+
+/// Check if file is an image OCR can read
+pub fn is_image_file(filename: &str) -> bool {
+    let lower = filename.to_lowercase();
+    IMAGE_FORMATS.iter().any(|ext| lower.ends_with(ext))
+}
+
+/// Check (once) whether tesseract is installed
+pub fn is_tesseract_available() -> bool {
+    static AVAILABLE: OnceLock<bool> = OnceLock::new();
+    *AVAILABLE.get_or_init(|| {
+        let available = std::process::Command::new("tesseract")
+            .arg("--version")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false);
+        if !available {
+            warn!(
+                "tesseract not found - image OCR disabled. Install with: apt install tesseract-ocr"
+            );
+        }
+        available
+    })
+}
+
+/// Tidy tesseract output: trim lines, drop lines with no letters or digits
+/// (border and noise artifacts) and collapse runs of blank lines
+pub fn clean_ocr_text(raw: &str) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    for line in raw.lines().map(str::trim_end) {
+        if line.trim().is_empty() {
+            if lines.last().is_some_and(|l| !l.is_empty()) {
+                lines.push("");
+            }
+        } else if line.chars().any(char::is_alphanumeric) {
+            lines.push(line);
+        }
+    }
+    lines.join("\n").trim_end().to_string()
+}
+
+/// Read the text in an image attachment
+///
+/// Returns `Ok(None)` when the image is too large or contains no readable text.
+pub async fn read_image_attachment(url: &str, filename: &str, size: u64) -> Result<Option<String>> {
+    if size > MAX_IMAGE_BYTES {
+        debug!("Skipping OCR for {filename}: {size} bytes is over the limit");
+        return Ok(None);
+    }
+
+    let bytes = reqwest::Client::new()
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+    let extension = filename
+        .rsplit_once('.')
+        .map(|(_, ext)| ext)
+        .unwrap_or("png");
+    let image_path = std::env::temp_dir().join(format!(
+        "persona_ocr_{}.{}",
+        uuid::Uuid::new_v4(),
+        extension.to_lowercase()
+    ));
+    tokio::fs::write(&image_path, &bytes).await?;
+
+    info!("Running OCR on image attachment: {filename}");
+    let start = Instant::now();
+    let mut cmd = Command::new("tesseract");
+    cmd.arg(&image_path)
+        .arg("stdout")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let result = timeout(Duration::from_secs(OCR_TIMEOUT_SECS), cmd.output()).await;
+
+    if let Err(e) = tokio::fs::remove_file(&image_path).await {
+        warn!("Failed to cleanup OCR temp file {image_path:?}: {e}");
+    }
+
+    let output = match result {
+        Ok(output) => output?,
+        Err(_) => {
+            return Err(anyhow::anyhow!(
+                "OCR timed out after {OCR_TIMEOUT_SECS} seconds"
+            ))
+        }
+    };
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow::anyhow!("tesseract failed: {stderr}"));
+    }
+
+    let text = clean_ocr_text(&String::from_utf8_lossy(&output.stdout));
+    info!(
+        "OCR read {} characters from {filename} in {:?}",
+        text.len(),
+        start.elapsed()
+    );
+    if text.chars().filter(|c| c.is_alphanumeric()).count() < MIN_TEXT_CHARS {
+        return Ok(None);
+    }
+
+    if text.chars().count() > MAX_TEXT_CHARS {
+        let truncated: String = text.chars().take(MAX_TEXT_CHARS).collect();
+        return Ok(Some(format!(
+            "{truncated}\n\n[... truncated, showing first {MAX_TEXT_CHARS} characters ...]"
+        )));
+    }
+    Ok(Some(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_image_file() {
+        assert!(is_image_file("Screenshot 2024-01-01.PNG"));
+        assert!(is_image_file("whiteboard.jpeg"));
+        assert!(!is_image_file("notes.txt"));
+        assert!(!is_image_file("clip.mp4"));
+    }
+
+    #[test]
+    fn test_clean_ocr_text() {
+        let raw = "  Meeting notes  \n|\n\n\n- ship v2\n  — \n\n\nOwner: Sam\n\u{c}";
+        assert_eq!(
+            clean_ocr_text(raw),
+            "  Meeting notes\n\n- ship v2\n\nOwner: Sam"
+        );
+        assert_eq!(clean_ocr_text("\n | \n ~~ \n"), "");
+    }
+}
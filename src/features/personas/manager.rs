@@ -4,11 +4,13 @@
 //! noir, zen, bard, coach, scientist, gamer, architect, debugger, reviewer, devops, designer).
 //! Each persona has a unique system prompt loaded from prompt/*.md files at compile time.
 //!
-//! - **Version**: 1.6.0
+//! - **Version**: 1.8.0
 //! - **Since**: 0.1.0
 //! - **Toggleable**: false
 //!
 //! ## Changelog
+//! - 1.8.0: Verbosity directives come from the shared verbosity presets
+//! - 1.7.0: Per-persona and per-command temperature, top_p and max_tokens overrides
 //! - 1.6.0: Added 5 software development personas - architect, debugger, reviewer, devops, designer
 //! - 1.5.0: Added SVG portrait assets and portrait URL generation
 //! - 1.4.0: Added embed responses with persona colors and optional portrait support
@@ -21,6 +23,44 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::env;
 
+use super::verbosity;
+use crate::core::ModelParams;
+
+/// Model parameters stored with a persona
+///
+/// `defaults` apply to every reply in the persona's voice; `commands` override
+/// them for single commands (e.g. `ask`, `chat`), field by field.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PersonaSampling {
+    #[serde(default)]
+    pub defaults: ModelParams,
+    #[serde(default)]
+    pub commands: HashMap<String, ModelParams>,
+}
+
+impl PersonaSampling {
+    pub fn new(defaults: ModelParams) -> Self {
+        Self {
+            defaults,
+            commands: HashMap::new(),
+        }
+    }
+
+    /// Add an override for one command
+    pub fn with_command(mut self, command: &str, params: ModelParams) -> Self {
+        self.commands.insert(command.to_string(), params);
+        self
+    }
+
+    /// Parameters for a command, with its override applied over the defaults
+    pub fn for_command(&self, command: Option<&str>) -> ModelParams {
+        match command.and_then(|name| self.commands.get(name)) {
+            Some(overrides) => self.defaults.merge(*overrides),
+            None => self.defaults,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Persona {
     pub name: String,
@@ -30,6 +70,9 @@ pub struct Persona {
     pub portrait_url: Option<String>,
     /// Embed accent color (Discord color format)
     pub color: u32,
+    /// Temperature, top_p and max_tokens overrides
+    #[serde(default)]
+    pub sampling: PersonaSampling,
 }
 
 #[derive(Debug, Clone)]
@@ -55,6 +98,7 @@ impl PersonaManager {
             description: "A wise Jedi Master who speaks with patience, diplomacy, and philosophical insight".to_string(),
             portrait_url: None,
             color: 0x4A90D9, // Calm blue - Jedi wisdom
+            sampling: PersonaSampling::default(),
         });
 
         personas.insert("muppet".to_string(), Persona {
@@ -63,6 +107,7 @@ impl PersonaManager {
             description: "A warm, enthusiastic friend who brings Muppet-style joy, humor, and heart to every conversation!".to_string(),
             portrait_url: None,
             color: 0xFF6B35, // Warm orange - Muppet energy
+            sampling: PersonaSampling::new(ModelParams::default().with_temperature(1.0)),
         });
 
         personas.insert(
@@ -73,6 +118,7 @@ impl PersonaManager {
                 description: "A passionate chef who shares recipes and cooking wisdom".to_string(),
                 portrait_url: None,
                 color: 0xE74C3C, // Culinary red
+                sampling: PersonaSampling::default(),
             },
         );
 
@@ -84,6 +130,7 @@ impl PersonaManager {
                 description: "A patient teacher who explains things clearly".to_string(),
                 portrait_url: None,
                 color: 0x27AE60, // Educational green
+                sampling: PersonaSampling::default(),
             },
         );
 
@@ -95,6 +142,7 @@ impl PersonaManager {
                 description: "An analyst who breaks things down into clear steps".to_string(),
                 portrait_url: None,
                 color: 0x3498DB, // Professional blue
+                sampling: PersonaSampling::new(ModelParams::default().with_temperature(0.0)),
             },
         );
 
@@ -104,6 +152,7 @@ impl PersonaManager {
             description: "A future-focused big-picture thinker who transforms chaos into actionable plans".to_string(),
             portrait_url: None,
             color: 0x9B59B6, // Futuristic purple
+            sampling: PersonaSampling::new(ModelParams::default().with_temperature(1.0)),
         });
 
         personas.insert(
@@ -116,6 +165,7 @@ impl PersonaManager {
                         .to_string(),
                 portrait_url: None,
                 color: 0x2C3E50, // Dark noir gray
+                sampling: PersonaSampling::default(),
             },
         );
 
@@ -128,6 +178,7 @@ impl PersonaManager {
                     .to_string(),
                 portrait_url: None,
                 color: 0x1ABC9C, // Peaceful teal
+                sampling: PersonaSampling::default(),
             },
         );
 
@@ -141,6 +192,8 @@ impl PersonaManager {
                         .to_string(),
                 portrait_url: None,
                 color: 0xF39C12, // Fantasy gold
+                sampling: PersonaSampling::new(ModelParams::default().with_temperature(1.1))
+                    .with_command("ask", ModelParams::default().with_temperature(0.9)),
             },
         );
 
@@ -154,6 +207,7 @@ impl PersonaManager {
                         .to_string(),
                 portrait_url: None,
                 color: 0xE67E22, // Energetic orange
+                sampling: PersonaSampling::default(),
             },
         );
 
@@ -166,6 +220,7 @@ impl PersonaManager {
                     .to_string(),
                 portrait_url: None,
                 color: 0x00CED1, // Scientific cyan
+                sampling: PersonaSampling::new(ModelParams::default().with_temperature(0.3)),
             },
         );
 
@@ -178,6 +233,7 @@ impl PersonaManager {
                     .to_string(),
                 portrait_url: None,
                 color: 0x9146FF, // Twitch purple
+                sampling: PersonaSampling::default(),
             },
         );
 
@@ -192,6 +248,7 @@ impl PersonaManager {
                         .to_string(),
                 portrait_url: None,
                 color: 0x34495E, // Blueprint slate
+                sampling: PersonaSampling::default(),
             },
         );
 
@@ -205,6 +262,7 @@ impl PersonaManager {
                         .to_string(),
                 portrait_url: None,
                 color: 0xC0392B, // Error red
+                sampling: PersonaSampling::new(ModelParams::default().with_temperature(0.2)),
             },
         );
 
@@ -218,6 +276,7 @@ impl PersonaManager {
                         .to_string(),
                 portrait_url: None,
                 color: 0x27AE60, // Approval green
+                sampling: PersonaSampling::default(),
             },
         );
 
@@ -231,6 +290,7 @@ impl PersonaManager {
                         .to_string(),
                 portrait_url: None,
                 color: 0x2980B9, // Pipeline blue
+                sampling: PersonaSampling::default(),
             },
         );
 
@@ -243,6 +303,7 @@ impl PersonaManager {
                     .to_string(),
                 portrait_url: None,
                 color: 0xE91E63, // Creative pink
+                sampling: PersonaSampling::default(),
             },
         );
 
@@ -257,6 +318,14 @@ impl PersonaManager {
         self.personas.iter().collect()
     }
 
+    /// Model parameters for a persona and command (defaults for unknown personas)
+    pub fn model_params(&self, persona_id: &str, command: Option<&str>) -> ModelParams {
+        self.personas
+            .get(persona_id)
+            .map(|p| p.sampling.for_command(command))
+            .unwrap_or_default()
+    }
+
     /// Get the portrait URL for a persona.
     ///
     /// Uses the persona's custom portrait_url if set, otherwise generates one
@@ -326,16 +395,10 @@ impl PersonaManager {
 
     /// Apply verbosity suffix to a prompt
     fn apply_verbosity_suffix(&self, prompt: &str, verbosity: &str) -> String {
-        let suffix = match verbosity {
-            "concise" => "\n\n## Response Style\nKeep responses brief and to the point. Aim for 2-3 sentences unless the topic truly requires more. If more detail might help, end with \"Want me to elaborate?\"",
-            "detailed" => "\n\n## Response Style\nProvide comprehensive, detailed explanations. Include examples, context, and thorough coverage of the topic. The user wants depth.",
-            _ => "", // "normal" gets no suffix - use base prompt as-is
-        };
-
-        if suffix.is_empty() {
-            prompt.to_string()
-        } else {
-            format!("{prompt}{suffix}")
+        // "normal" gets no suffix - use base prompt as-is
+        match verbosity::preset(verbosity).directive {
+            Some(suffix) => format!("{prompt}{suffix}"),
+            None => prompt.to_string(),
         }
     }
 }
@@ -400,6 +463,28 @@ mod tests {
         assert!(recipe_prompt.contains("recipe"));
     }
 
+    #[test]
+    fn test_model_params_per_persona_and_command() {
+        let manager = PersonaManager::new();
+        assert_eq!(
+            manager.model_params("analyst", Some("ask")).temperature,
+            Some(0.0)
+        );
+        assert_eq!(manager.model_params("bard", None).temperature, Some(1.1));
+        assert_eq!(
+            manager.model_params("bard", Some("ask")).temperature,
+            Some(0.9)
+        );
+        assert_eq!(
+            manager.model_params("obi", Some("ask")),
+            ModelParams::default()
+        );
+        assert_eq!(
+            manager.model_params("unknown", None),
+            ModelParams::default()
+        );
+    }
+
     #[test]
     fn test_persona_descriptions() {
         let manager = PersonaManager::new();
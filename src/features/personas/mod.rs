@@ -2,11 +2,13 @@
 //!
 //! Multi-personality AI response system with 17 distinct personas.
 //!
-//! - **Version**: 1.3.0
+//! - **Version**: 1.5.0
 //! - **Since**: 0.1.0
 //! - **Toggleable**: false
 //!
 //! ## Changelog
+//! - 1.5.0: Add verbosity module with response length presets and token budgets
+//! - 1.4.0: Add themes module for daily persona rotation and seasonal themes
 //! - 1.3.0: Add prompt_builder module for fluent system prompt construction
 //! - 1.2.0: Add shared choices module for slash commands
 //! - 1.1.0: Add apply_paragraph_limit() for max_paragraphs response control
@@ -15,7 +17,11 @@
 pub mod choices;
 pub mod manager;
 pub mod prompt_builder;
+pub mod themes;
+pub mod verbosity;
 
 pub use choices::{add_persona_choices, is_valid_persona, PERSONA_CHOICES};
-pub use manager::{apply_paragraph_limit, Persona, PersonaManager};
+pub use manager::{apply_paragraph_limit, Persona, PersonaManager, PersonaSampling};
 pub use prompt_builder::PromptBuilder;
+pub use themes::{apply_theme, ThemeScheduler};
+pub use verbosity::{response_budget, VerbosityPreset, VERBOSITY_PRESETS};
@@ -0,0 +1,478 @@
+//! # Feature: Persona Rotation and Seasonal Themes
+//!
+//! A background scheduler that rotates a guild's default persona once a day
+//! (`/set_guild persona_rotation`) and switches on seasonal prompt modifiers
+//! such as a Halloween tone on their dates (`/set_guild seasonal_themes`).
+//! Both revert on their own: the admin's own default persona comes back when
+//! rotation is disabled, and a theme is cleared once its dates have passed.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: false
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with daily persona rotation and five seasonal themes
+
+use crate::database::Database;
+use crate::features::personas::is_valid_persona;
+use anyhow::Result;
+use chrono::{Datelike, NaiveDate, Utc};
+use log::{error, info};
+use std::time::Duration;
+use tokio::time::interval;
+
+/// Guild setting holding the rotation (comma-separated personas or `disabled`)
+pub const ROTATION_SETTING: &str = "persona_rotation";
+
+/// Guild setting holding the seasonal theme schedule
+pub const THEMES_SETTING: &str = "seasonal_themes";
+
+/// Guild setting the scheduler keeps pointed at the theme active today
+pub const ACTIVE_THEME_SETTING: &str = "active_theme";
+
+/// The admin's own default persona, remembered while rotation overrides it
+/// (empty when the guild had none)
+const ROTATION_BASE_SETTING: &str = "persona_rotation_base";
+
+/// A built-in seasonal prompt modifier with its default dates
+#[derive(Debug, PartialEq, Eq)]
+pub struct SeasonalTheme {
+    pub key: &'static str,
+    pub name: &'static str,
+    /// Inclusive (month, day) range; may wrap around the new year
+    pub start: (u32, u32),
+    pub end: (u32, u32),
+    pub modifier: &'static str,
+}
+
+/// Built-in themes; when dates overlap the earlier entry wins
+pub const SEASONAL_THEMES: &[SeasonalTheme] = &[
+    SeasonalTheme {
+        key: "new_year",
+        name: "New Year",
+        start: (12, 31),
+        end: (1, 2),
+        modifier: "It's New Year. Feel free to be upbeat about fresh starts and resolutions when it fits the conversation.",
+    },
+    SeasonalTheme {
+        key: "winter_holidays",
+        name: "Winter Holidays",
+        start: (12, 15),
+        end: (12, 30),
+        modifier: "It's the winter holiday season. Be warm and festive, with the occasional seasonal reference.",
+    },
+    SeasonalTheme {
+        key: "valentines",
+        name: "Valentine's Day",
+        start: (2, 14),
+        end: (2, 14),
+        modifier: "It's Valentine's Day. A light, friendly nod to the occasion is welcome; keep it inclusive.",
+    },
+    SeasonalTheme {
+        key: "april_fools",
+        name: "April Fools' Day",
+        start: (4, 1),
+        end: (4, 1),
+        modifier: "It's April Fools' Day. A harmless joke is welcome, but answers must stay accurate and never mislead the user.",
+    },
+    SeasonalTheme {
+        key: "halloween",
+        name: "Halloween",
+        start: (10, 24),
+        end: (10, 31),
+        modifier: "It's Halloween season. Lean into a playful, spooky tone with the odd eerie metaphor, without getting in the way of a useful answer.",
+    },
+];
+
+/// A theme enabled for a guild, with its (possibly customised) dates
+#[derive(Debug, PartialEq, Eq)]
+pub struct ScheduledTheme {
+    pub theme: &'static SeasonalTheme,
+    pub start: (u32, u32),
+    pub end: (u32, u32),
+}
+
+impl ScheduledTheme {
+    fn is_active(&self, date: NaiveDate) -> bool {
+        let today = (date.month(), date.day());
+        if self.start <= self.end {
+            self.start <= today && today <= self.end
+        } else {
+            today >= self.start || today <= self.end
+        }
+    }
+}
+
+/// Look up a built-in theme by key
+pub fn find_theme(key: &str) -> Option<&'static SeasonalTheme> {
+    SEASONAL_THEMES.iter().find(|t| t.key == key)
+}
+
+/// Parse a `seasonal_themes` value
+///
+/// `enabled` turns on every built-in theme on its default dates, `disabled`
+/// none. Otherwise a comma-separated list of theme keys, each optionally with
+/// its own dates: `halloween@10-15..10-31, winter_holidays`.
+pub fn parse_theme_schedule(value: &str) -> Result<Vec<ScheduledTheme>, &'static str> {
+    let default_dates = |theme: &'static SeasonalTheme| ScheduledTheme {
+        theme,
+        start: theme.start,
+        end: theme.end,
+    };
+    match value.trim() {
+        "enabled" => return Ok(SEASONAL_THEMES.iter().map(default_dates).collect()),
+        "disabled" => return Ok(Vec::new()),
+        _ => {}
+    }
+
+    let mut schedule = Vec::new();
+    for item in value.split(',').map(str::trim).filter(|i| !i.is_empty()) {
+        let (key, dates) = match item.split_once('@') {
+            Some((key, dates)) => (key.trim(), Some(dates.trim())),
+            None => (item, None),
+        };
+        let theme = find_theme(key).ok_or(
+            "Unknown theme. Use: `new_year`, `winter_holidays`, `valentines`, `april_fools` or `halloween`.",
+        )?;
+        let scheduled = match dates {
+            None => default_dates(theme),
+            Some(dates) => {
+                let (start, end) = dates.split_once("..").ok_or(
+                    "Invalid dates. Use `theme@MM-DD..MM-DD`, e.g. `halloween@10-15..10-31`.",
+                )?;
+                ScheduledTheme {
+                    theme,
+                    start: parse_month_day(start)?,
+                    end: parse_month_day(end)?,
+                }
+            }
+        };
+        schedule.push(scheduled);
+    }
+    if schedule.is_empty() {
+        return Err("Use `enabled`, `disabled` or a list of themes like `halloween@10-15..10-31, new_year`.");
+    }
+    Ok(schedule)
+}
+
+fn parse_month_day(value: &str) -> Result<(u32, u32), &'static str> {
+    let invalid = "Invalid date. Use `MM-DD`, e.g. `10-31`.";
+    let (month, day) = value.trim().split_once('-').ok_or(invalid)?;
+    let month: u32 = month.parse().map_err(|_| invalid)?;
+    let day: u32 = day.parse().map_err(|_| invalid)?;
+    // 2024 is a leap year, so Feb 29 is accepted
+    NaiveDate::from_ymd_opt(2024, month, day).ok_or(invalid)?;
+    Ok((month, day))
+}
+
+/// The first scheduled theme whose dates include `date`
+pub fn active_theme(
+    schedule: &[ScheduledTheme],
+    date: NaiveDate,
+) -> Option<&'static SeasonalTheme> {
+    schedule.iter().find(|s| s.is_active(date)).map(|s| s.theme)
+}
+
+/// Parse a `persona_rotation` value: `disabled` or two or more persona IDs
+pub fn parse_rotation(value: &str) -> Result<Vec<String>, &'static str> {
+    if value.trim() == "disabled" {
+        return Ok(Vec::new());
+    }
+    let personas: Vec<String> = value
+        .split(',')
+        .map(|p| p.trim().to_lowercase())
+        .filter(|p| !p.is_empty())
+        .collect();
+    if personas.iter().any(|p| !is_valid_persona(p)) {
+        return Err("Unknown persona in rotation. Use persona IDs like `obi, chef, bard`.");
+    }
+    if personas.len() < 2 {
+        return Err("List at least two personas to rotate between, e.g. `obi, chef, bard`.");
+    }
+    Ok(personas)
+}
+
+/// The persona a rotation lands on for `date` (advances once per UTC day)
+pub fn rotation_pick(personas: &[String], date: NaiveDate) -> Option<&str> {
+    if personas.is_empty() {
+        return None;
+    }
+    let day = date.num_days_from_ce() as usize;
+    Some(&personas[day % personas.len()])
+}
+
+/// Append the active theme's modifier to a system prompt
+pub fn apply_theme(prompt: String, theme_key: Option<&str>) -> String {
+    match theme_key.and_then(find_theme) {
+        Some(theme) => format!("{prompt}\n\n## Seasonal Theme\n{}", theme.modifier),
+        None => prompt,
+    }
+}
+
+/// Bring a guild's default persona and active theme in line with its schedule
+/// for `today`, reverting whatever no longer applies
+pub async fn apply_guild_themes(
+    database: &Database,
+    guild_id: &str,
+    today: NaiveDate,
+) -> Result<()> {
+    let rotation = database
+        .get_guild_setting(guild_id, ROTATION_SETTING)
+        .await?
+        .and_then(|v| parse_rotation(&v).ok())
+        .unwrap_or_default();
+    let base = database
+        .get_guild_setting(guild_id, ROTATION_BASE_SETTING)
+        .await?;
+    let current = database
+        .get_guild_setting(guild_id, "default_persona")
+        .await?;
+
+    if let Some(pick) = rotation_pick(&rotation, today) {
+        if base.is_none() {
+            database
+                .set_guild_setting(
+                    guild_id,
+                    ROTATION_BASE_SETTING,
+                    current.as_deref().unwrap_or(""),
+                )
+                .await?;
+        }
+        if current.as_deref() != Some(pick) {
+            database
+                .set_guild_setting(guild_id, "default_persona", pick)
+                .await?;
+            info!("🎭 Rotated default persona for guild {guild_id} to {pick}");
+        }
+    } else if let Some(base) = base {
+        // Rotation was turned off: put the admin's own default back
+        if base.is_empty() {
+            database
+                .delete_guild_setting(guild_id, "default_persona")
+                .await?;
+        } else {
+            database
+                .set_guild_setting(guild_id, "default_persona", &base)
+                .await?;
+        }
+        database
+            .delete_guild_setting(guild_id, ROTATION_BASE_SETTING)
+            .await?;
+        info!("🎭 Persona rotation ended for guild {guild_id}, default persona restored");
+    }
+
+    let schedule = database
+        .get_guild_setting(guild_id, THEMES_SETTING)
+        .await?
+        .and_then(|v| parse_theme_schedule(&v).ok())
+        .unwrap_or_default();
+    let theme = active_theme(&schedule, today).map(|t| t.key);
+    let active = database
+        .get_guild_setting(guild_id, ACTIVE_THEME_SETTING)
+        .await?;
+    if active.as_deref() != theme {
+        match theme {
+            Some(key) => {
+                database
+                    .set_guild_setting(guild_id, ACTIVE_THEME_SETTING, key)
+                    .await?;
+                info!("🎃 Seasonal theme {key} active for guild {guild_id}");
+            }
+            None => {
+                database
+                    .delete_guild_setting(guild_id, ACTIVE_THEME_SETTING)
+                    .await?;
+                info!("🎃 Seasonal theme ended for guild {guild_id}");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Keep the default persona pinned when an admin changes it mid-rotation, so
+/// it is the one restored when rotation ends
+pub async fn update_rotation_base(
+    database: &Database,
+    guild_id: &str,
+    persona: &str,
+) -> Result<()> {
+    if database
+        .get_guild_setting(guild_id, ROTATION_BASE_SETTING)
+        .await?
+        .is_some()
+    {
+        database
+            .set_guild_setting(guild_id, ROTATION_BASE_SETTING, persona)
+            .await?;
+    }
+    Ok(())
+}
+
+pub struct ThemeScheduler {
+    database: Database,
+}
+
+impl ThemeScheduler {
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+
+    /// Start the theme scheduler loop
+    /// This should be spawned as a tokio task
+    pub async fn run(&self) {
+        let mut check_interval = interval(Duration::from_secs(15 * 60));
+
+        info!("🎭 Persona theme scheduler started");
+
+        loop {
+            check_interval.tick().await;
+
+            if let Err(e) = self.apply_all().await {
+                error!("❌ Error applying persona themes: {e}");
+            }
+        }
+    }
+
+    async fn apply_all(&self) -> Result<()> {
+        let today = Utc::now().date_naive();
+        let guilds = self
+            .database
+            .get_guilds_with_settings(&[
+                ROTATION_SETTING,
+                ROTATION_BASE_SETTING,
+                THEMES_SETTING,
+                ACTIVE_THEME_SETTING,
+            ])
+            .await?;
+        for guild_id in guilds {
+            if let Err(e) = apply_guild_themes(&self.database, &guild_id, today).await {
+                error!("❌ Failed to apply persona themes for guild {guild_id}: {e}");
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_parse_theme_schedule() {
+        assert_eq!(
+            parse_theme_schedule("enabled").unwrap().len(),
+            SEASONAL_THEMES.len()
+        );
+        assert!(parse_theme_schedule("disabled").unwrap().is_empty());
+
+        let schedule = parse_theme_schedule("halloween@10-15..10-31, valentines").unwrap();
+        assert_eq!(schedule[0].start, (10, 15));
+        assert_eq!(schedule[1].start, (2, 14));
+
+        assert!(parse_theme_schedule("easter").is_err());
+        assert!(parse_theme_schedule("halloween@10-15").is_err());
+        assert!(parse_theme_schedule("halloween@13-01..13-05").is_err());
+        assert!(parse_theme_schedule("").is_err());
+    }
+
+    #[test]
+    fn test_active_theme_dates() {
+        let schedule = parse_theme_schedule("enabled").unwrap();
+        assert_eq!(
+            active_theme(&schedule, date(10, 31)).unwrap().key,
+            "halloween"
+        );
+        assert_eq!(
+            active_theme(&schedule, date(12, 20)).unwrap().key,
+            "winter_holidays"
+        );
+        // Wraps around the new year
+        assert_eq!(
+            active_theme(&schedule, date(12, 31)).unwrap().key,
+            "new_year"
+        );
+        assert_eq!(active_theme(&schedule, date(1, 2)).unwrap().key, "new_year");
+        assert!(active_theme(&schedule, date(1, 3)).is_none());
+        assert!(active_theme(&schedule, date(11, 1)).is_none());
+    }
+
+    #[test]
+    fn test_rotation() {
+        assert!(parse_rotation("disabled").unwrap().is_empty());
+        assert!(parse_rotation("obi").is_err());
+        assert!(parse_rotation("obi, nobody").is_err());
+
+        let personas = parse_rotation("Obi, chef, bard").unwrap();
+        let picks: Vec<&str> = (1..=3)
+            .map(|d| rotation_pick(&personas, date(3, d)).unwrap())
+            .collect();
+        assert_eq!(picks.len(), 3);
+        assert!(picks.contains(&"obi") && picks.contains(&"chef") && picks.contains(&"bard"));
+        assert_eq!(
+            rotation_pick(&personas, date(3, 1)),
+            rotation_pick(&personas, date(3, 4))
+        );
+        assert!(rotation_pick(&[], date(3, 1)).is_none());
+    }
+
+    #[test]
+    fn test_apply_theme() {
+        let prompt = apply_theme("Base".to_string(), Some("halloween"));
+        assert!(prompt.starts_with("Base\n\n## Seasonal Theme\n"));
+        assert_eq!(apply_theme("Base".to_string(), None), "Base");
+        assert_eq!(apply_theme("Base".to_string(), Some("gone")), "Base");
+    }
+
+    #[tokio::test]
+    async fn test_apply_guild_themes_reverts() {
+        let db = Database::new(":memory:").await.unwrap();
+        db.set_guild_setting("g1", "default_persona", "zen")
+            .await
+            .unwrap();
+        db.set_guild_setting("g1", ROTATION_SETTING, "obi, chef")
+            .await
+            .unwrap();
+        db.set_guild_setting("g1", THEMES_SETTING, "halloween")
+            .await
+            .unwrap();
+
+        apply_guild_themes(&db, "g1", date(10, 30)).await.unwrap();
+        let rotated = db.get_guild_setting("g1", "default_persona").await.unwrap();
+        assert!(matches!(rotated.as_deref(), Some("obi") | Some("chef")));
+        assert_eq!(
+            db.get_guild_setting("g1", ACTIVE_THEME_SETTING)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("halloween")
+        );
+
+        db.set_guild_setting("g1", ROTATION_SETTING, "disabled")
+            .await
+            .unwrap();
+        apply_guild_themes(&db, "g1", date(11, 1)).await.unwrap();
+        assert_eq!(
+            db.get_guild_setting("g1", "default_persona")
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("zen")
+        );
+        assert_eq!(
+            db.get_guild_setting("g1", ACTIVE_THEME_SETTING)
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            db.get_guilds_with_settings(&[ROTATION_BASE_SETTING, ACTIVE_THEME_SETTING])
+                .await
+                .unwrap(),
+            Vec::<String>::new()
+        );
+    }
+}
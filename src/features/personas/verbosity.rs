@@ -0,0 +1,120 @@
+//! # Verbosity Presets
+//!
+//! Response length presets (`concise`, `normal`, `detailed`) for channels and
+//! guilds. Each preset has a prompt directive telling the persona how long to
+//! answer and a completion token budget sent with the request, so a long
+//! answer is never generated just to be cut off. A channel's `max_paragraphs`
+//! tightens the budget further.
+
+use crate::core::ModelParams;
+
+/// A response length preset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerbosityPreset {
+    pub id: &'static str,
+    /// Short description for autocomplete
+    pub label: &'static str,
+    /// Appended to the system prompt; `normal` uses the prompt as-is
+    pub directive: Option<&'static str>,
+    /// Completion token budget for one reply
+    pub max_tokens: u64,
+}
+
+/// All presets, shortest first
+pub const VERBOSITY_PRESETS: [VerbosityPreset; 3] = [
+    VerbosityPreset {
+        id: "concise",
+        label: "Brief responses (2-3 sentences)",
+        directive: Some("\n\n## Response Style\nKeep responses brief and to the point. Aim for 2-3 sentences unless the topic truly requires more. If more detail might help, end with \"Want me to elaborate?\""),
+        max_tokens: 500,
+    },
+    VerbosityPreset {
+        id: "normal",
+        label: "Balanced responses",
+        directive: None,
+        max_tokens: 1500,
+    },
+    VerbosityPreset {
+        id: "detailed",
+        label: "Comprehensive responses",
+        directive: Some("\n\n## Response Style\nProvide comprehensive, detailed explanations. Include examples, context, and thorough coverage of the topic. The user wants depth. Finish your answer well within about 1500 words."),
+        max_tokens: 3000,
+    },
+];
+
+/// Tokens budgeted per paragraph when `max_paragraphs` is set
+const TOKENS_PER_PARAGRAPH: u64 = 250;
+
+/// Room for a heading, list markup or a closing line beyond the paragraphs
+const PARAGRAPH_HEADROOM: u64 = 200;
+
+/// The preset for a verbosity setting; unknown values are `normal`
+pub fn preset(verbosity: &str) -> &'static VerbosityPreset {
+    VERBOSITY_PRESETS
+        .iter()
+        .find(|p| p.id == verbosity)
+        .unwrap_or(&VERBOSITY_PRESETS[1])
+}
+
+/// Autocomplete text for a preset, e.g. `normal - Balanced responses, up to ~1500 tokens`
+pub fn autocomplete_label(preset: &VerbosityPreset) -> String {
+    format!(
+        "{} - {}, up to ~{} tokens",
+        preset.id, preset.label, preset.max_tokens
+    )
+}
+
+/// Completion token budget for a verbosity level and paragraph limit (0 = none)
+pub fn response_budget(verbosity: &str, max_paragraphs: i64) -> u64 {
+    let budget = preset(verbosity).max_tokens;
+    match u64::try_from(max_paragraphs) {
+        Ok(paragraphs) if paragraphs > 0 => {
+            budget.min(paragraphs * TOKENS_PER_PARAGRAPH + PARAGRAPH_HEADROOM)
+        }
+        _ => budget,
+    }
+}
+
+/// `params` with the reply capped at the budget for the verbosity and paragraph limit
+pub fn budgeted(params: ModelParams, verbosity: &str, max_paragraphs: i64) -> ModelParams {
+    params.with_token_cap(response_budget(verbosity, max_paragraphs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_lookup() {
+        assert_eq!(preset("concise").max_tokens, 500);
+        assert_eq!(preset("detailed").id, "detailed");
+        assert_eq!(preset("verbose").id, "normal");
+        assert!(preset("normal").directive.is_none());
+    }
+
+    #[test]
+    fn test_response_budget() {
+        assert_eq!(response_budget("normal", 0), 1500);
+        assert_eq!(response_budget("detailed", 2), 700);
+        assert_eq!(response_budget("concise", 10), 500);
+        assert_eq!(response_budget("concise", -1), 500);
+    }
+
+    #[test]
+    fn test_budgeted_keeps_lower_persona_cap() {
+        let params = ModelParams::default().with_temperature(0.0);
+        assert_eq!(budgeted(params, "concise", 0).max_tokens, Some(500));
+        assert_eq!(budgeted(params, "concise", 0).temperature, Some(0.0));
+
+        let capped = ModelParams::default().with_max_tokens(300);
+        assert_eq!(budgeted(capped, "detailed", 0).max_tokens, Some(300));
+    }
+
+    #[test]
+    fn test_autocomplete_label() {
+        assert_eq!(
+            autocomplete_label(preset("concise")),
+            "concise - Brief responses (2-3 sentences), up to ~500 tokens"
+        );
+    }
+}
@@ -0,0 +1,90 @@
+//! Buttons on plugin job messages
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with "notify me" for users who joined a running job
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serenity::builder::CreateComponents;
+use serenity::model::application::component::ButtonStyle;
+use serenity::model::application::interaction::message_component::MessageComponentInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::prelude::Context;
+use std::sync::Arc;
+
+use super::JobManager;
+use crate::commands::components::{ComponentHandler, ComponentId};
+
+/// Component namespace for plugin job buttons
+pub const JOBS_FEATURE: &str = "job";
+
+/// "Notify me when done" button for a running job
+pub fn watch_button(job_id: &str) -> CreateComponents {
+    let mut components = CreateComponents::default();
+    components.create_action_row(|row| {
+        row.create_button(|btn| {
+            btn.custom_id(ComponentId::new(JOBS_FEATURE, "watch", job_id).to_string())
+                .label("Notify me when done")
+                .emoji('🔔')
+                .style(ButtonStyle::Secondary)
+        })
+    });
+    components
+}
+
+/// Routes plugin job buttons
+pub struct JobButtons {
+    job_manager: Arc<JobManager>,
+}
+
+impl JobButtons {
+    pub fn new(job_manager: Arc<JobManager>) -> Self {
+        Self { job_manager }
+    }
+}
+
+#[async_trait]
+impl ComponentHandler for JobButtons {
+    fn features(&self) -> &'static [&'static str] {
+        &[JOBS_FEATURE]
+    }
+
+    async fn handle_component(
+        &self,
+        ctx: &Context,
+        interaction: &MessageComponentInteraction,
+        id: &ComponentId,
+    ) -> Result<()> {
+        let content = match id.action.as_str() {
+            "watch" => {
+                if self
+                    .job_manager
+                    .watch_job(&ctx.http, &id.payload, interaction.user.id.0)
+                {
+                    "🔔 I'll ping you when this job finishes."
+                } else if self
+                    .job_manager
+                    .get_job(&id.payload)
+                    .is_some_and(|j| j.is_active())
+                {
+                    "You're already on the list for this job."
+                } else {
+                    "This job has already finished."
+                }
+            }
+            _ => "Unknown job action.",
+        };
+
+        interaction
+            .create_interaction_response(&ctx.http, |response| {
+                response
+                    .kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|message| message.content(content).ephemeral(true))
+            })
+            .await?;
+        Ok(())
+    }
+}
@@ -3,13 +3,19 @@
 //! Download and split audio files into manageable chunks for transcription.
 //! Uses yt-dlp for downloading and ffmpeg for splitting.
 //!
-//! - **Version**: 1.1.0
+//! - **Version**: 1.4.0
 //! - **Since**: 3.0.0
 //!
 //! ## Changelog
+//! - 1.4.0: split_windows() cuts chunks into a caller-owned directory
+//! - 1.3.0: Added split_overlapping(), overlapping_windows() and stitch_transcripts() for overlapping chunks
+//! - 1.2.1: Working directory prefix is public so /sysinfo can measure transcript storage
+//! - 1.2.0: Downloads are guarded by the yt-dlp circuit breaker
 //! - 1.1.0: Added configurable download command support for Docker-based downloads
 //! - 1.0.0: Initial release with audio download and chunking support
 
+use super::youtube::record_ytdlp_outcome;
+use crate::features::resilience::{circuit_breakers, Dependency};
 use anyhow::{Context, Result};
 use log::{info, warn};
 use std::path::{Path, PathBuf};
@@ -17,6 +23,9 @@ use std::time::Duration;
 use tokio::process::Command;
 use tokio::time::timeout;
 
+/// Chunker working directories are `<temp dir>/persona_chunker_<uuid>`
+pub const TEMP_DIR_PREFIX: &str = "persona_chunker_";
+
 /// Configuration for audio chunking
 #[derive(Debug, Clone)]
 pub struct ChunkerConfig {
@@ -79,7 +88,7 @@ impl AudioChunker {
     pub async fn new(config: ChunkerConfig) -> Result<Self> {
         // Create a unique temp directory
         let temp_dir =
-            std::env::temp_dir().join(format!("persona_chunker_{}", uuid::Uuid::new_v4()));
+            std::env::temp_dir().join(format!("{TEMP_DIR_PREFIX}{}", uuid::Uuid::new_v4()));
         tokio::fs::create_dir_all(&temp_dir)
             .await
             .context("Failed to create temp directory")?;
@@ -107,6 +116,7 @@ impl AudioChunker {
         let output_path = self.temp_dir.join("audio.mp3");
 
         info!("Downloading audio from: {url}");
+        circuit_breakers().check(Dependency::YtDlp)?;
 
         let result = if let Some(ref download_cmd) = self.config.download_command {
             // Use custom download command (typically Docker-based)
@@ -124,6 +134,7 @@ impl AudioChunker {
             );
             self.download_with_ytdlp(url).await
         };
+        record_ytdlp_outcome(&result);
 
         match result {
             Ok(()) => {
@@ -378,6 +389,37 @@ impl AudioChunker {
         }
     }
 
+    /// Split an audio file into overlapping, re-encoded chunks
+    ///
+    /// Each chunk starts `chunk_duration_secs` after the previous one and runs
+    /// `overlap_secs` longer, so words cut at a boundary appear whole in one of
+    /// them (see [`stitch_transcripts`]). Chunks are re-encoded as mono 64 kbps
+    /// mp3, keeping a 10-minute chunk around 5 MB whatever the source bitrate.
+    pub async fn split_overlapping(
+        &self,
+        audio_path: &Path,
+        duration_secs: u64,
+        overlap_secs: u64,
+    ) -> Result<SplitResult> {
+        let chunk_dir = self.temp_dir.join("chunks");
+        tokio::fs::create_dir_all(&chunk_dir).await?;
+
+        let windows =
+            overlapping_windows(duration_secs, self.config.chunk_duration_secs, overlap_secs);
+        let chunk_paths = split_windows(
+            audio_path,
+            &chunk_dir,
+            &windows,
+            Duration::from_secs(self.config.split_timeout_secs),
+        )
+        .await?;
+        let total_chunks = chunk_paths.len();
+        Ok(SplitResult {
+            chunk_paths,
+            total_chunks,
+        })
+    }
+
     /// Check if the audio needs chunking based on duration
     ///
     /// Returns true if duration exceeds chunk_duration_secs, false otherwise.
@@ -429,6 +471,117 @@ impl Drop for AudioChunker {
     }
 }
 
+/// Cut `(start, length)` windows of an audio file into re-encoded mono 64 kbps
+/// mp3 chunks in `chunk_dir`, returning their paths in order
+pub async fn split_windows(
+    audio_path: &Path,
+    chunk_dir: &Path,
+    windows: &[(u64, u64)],
+    split_timeout: Duration,
+) -> Result<Vec<PathBuf>> {
+    info!(
+        "Splitting audio into {} overlapping chunks: {:?}",
+        windows.len(),
+        audio_path
+    );
+
+    let mut chunk_paths = Vec::with_capacity(windows.len());
+    for (index, (start, length)) in windows.iter().enumerate() {
+        let chunk_path = chunk_dir.join(format!("chunk_{index:03}.mp3"));
+        let mut cmd = Command::new("ffmpeg");
+        cmd.args([
+            "-ss",
+            &start.to_string(),
+            "-t",
+            &length.to_string(),
+            "-i",
+            audio_path.to_str().unwrap(),
+            "-vn",
+            "-ac",
+            "1",
+            "-b:a",
+            "64k",
+            "-y",
+            chunk_path.to_str().unwrap(),
+        ])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true);
+
+        match timeout(split_timeout, cmd.output()).await {
+            Ok(Ok(output)) if output.status.success() => chunk_paths.push(chunk_path),
+            Ok(Ok(output)) => {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                return Err(anyhow::anyhow!("ffmpeg split failed: {}", stderr));
+            }
+            Ok(Err(e)) => return Err(anyhow::anyhow!("Failed to execute ffmpeg: {}", e)),
+            Err(_) => {
+                return Err(anyhow::anyhow!(
+                    "Split timed out after {} seconds",
+                    split_timeout.as_secs()
+                ))
+            }
+        }
+    }
+
+    if chunk_paths.is_empty() {
+        return Err(anyhow::anyhow!("No chunks generated from audio split"));
+    }
+    Ok(chunk_paths)
+}
+
+/// `(start, length)` in seconds of each overlapping chunk covering `duration_secs`
+pub fn overlapping_windows(
+    duration_secs: u64,
+    chunk_secs: u64,
+    overlap_secs: u64,
+) -> Vec<(u64, u64)> {
+    let chunk_secs = chunk_secs.max(1);
+    (0..duration_secs.max(1))
+        .step_by(chunk_secs as usize)
+        .map(|start| (start, chunk_secs + overlap_secs))
+        .collect()
+}
+
+/// Words compared when looking for the text two overlapping chunks share
+const MAX_OVERLAP_WORDS: usize = 60;
+
+/// Join transcripts of overlapping chunks, dropping the words each chunk
+/// repeats from the end of the previous one
+///
+/// Matching ignores case and punctuation; at least three words must line up so
+/// short coincidences ("and the") are kept.
+pub fn stitch_transcripts(parts: &[String]) -> String {
+    fn normalize(word: &str) -> String {
+        word.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
+    }
+
+    let mut stitched: Vec<&str> = Vec::new();
+    for part in parts {
+        let words: Vec<&str> = part.split_whitespace().collect();
+        let tail_start = stitched.len().saturating_sub(MAX_OVERLAP_WORDS);
+        let tail: Vec<String> = stitched[tail_start..]
+            .iter()
+            .map(|w| normalize(w))
+            .collect();
+        let head: Vec<String> = words
+            .iter()
+            .take(MAX_OVERLAP_WORDS)
+            .map(|w| normalize(w))
+            .collect();
+
+        let overlap = (3..=tail.len().min(head.len()))
+            .rev()
+            .find(|&n| tail[tail.len() - n..] == head[..n])
+            .unwrap_or(0);
+        stitched.extend(&words[overlap..]);
+    }
+    stitched.join(" ")
+}
+
 /// Represents the progress of chunked transcription
 #[derive(Debug, Clone)]
 pub struct ChunkProgress {
@@ -494,6 +647,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_overlapping_windows() {
+        assert_eq!(
+            overlapping_windows(1500, 600, 5),
+            vec![(0, 605), (600, 605), (1200, 605)]
+        );
+        assert_eq!(overlapping_windows(600, 600, 5), vec![(0, 605)]);
+        assert_eq!(overlapping_windows(0, 600, 5), vec![(0, 605)]);
+    }
+
+    #[test]
+    fn test_stitch_transcripts_drops_repeated_overlap() {
+        let parts = vec![
+            "We start here. The quick brown fox jumps".to_string(),
+            "quick brown fox jumps over the lazy dog.".to_string(),
+            "Something new entirely.".to_string(),
+        ];
+        assert_eq!(
+            stitch_transcripts(&parts),
+            "We start here. The quick brown fox jumps over the lazy dog. Something new entirely."
+        );
+    }
+
+    #[test]
+    fn test_stitch_transcripts_ignores_short_coincidences() {
+        let parts = vec!["this and the".to_string(), "And the next part".to_string()];
+        assert_eq!(stitch_transcripts(&parts), "this and the And the next part");
+    }
+
     #[test]
     fn test_chunker_config_default() {
         let config = ChunkerConfig::default();
@@ -3,10 +3,15 @@
 //! YAML-based plugin configuration with full schema validation.
 //! Supports both monolithic `plugins.yaml` and per-plugin directory (`plugins/`) loading.
 //!
-//! - **Version**: 4.0.0
+//! - **Version**: 4.5.0
 //! - **Since**: 0.9.0
 //!
 //! ## Changelog
+//! - 4.5.0: Added PlaylistConfig::effective_max_videos()
+//! - 4.4.0: Added OutputConfig.timestamp_links for YouTube deep links in transcripts
+//! - 4.3.0: Added ChunkingConfig.cache_transcripts (default on)
+//! - 4.2.0: Added per-plugin SummarizationConfig (enabled, model, prompt, max_tokens)
+//! - 4.1.0: Added OutputTemplates (Tera success/error/video templates), checked at load
 //! - 4.0.0: Added PluginType presets (shell/api/docker/virtual), RawPlugin with resolve(),
 //!   per-file directory loading (load_dir/load_auto), script sugar, command name inference
 //! - 3.4.0: Added chunk_summary_prompt to OutputConfig for casual per-chunk summaries
@@ -102,6 +107,38 @@ impl PluginConfig {
                     ));
                 }
             }
+
+            // Validate summarization settings
+            let summarization = &plugin.output.summarization;
+            if summarization.max_tokens == Some(0) {
+                return Err(anyhow::anyhow!(
+                    "summarization.max_tokens must be positive in plugin '{}'",
+                    plugin.name
+                ));
+            }
+            if summarization
+                .model
+                .as_deref()
+                .is_some_and(|m| m.trim().is_empty())
+            {
+                return Err(anyhow::anyhow!(
+                    "summarization.model is empty in plugin '{}'",
+                    plugin.name
+                ));
+            }
+
+            // Validate output templates parse
+            let templates = &plugin.output.templates;
+            for (kind, template) in [
+                ("success", &templates.success),
+                ("error", &templates.error),
+                ("video", &templates.video),
+            ] {
+                if let Some(template) = template {
+                    crate::features::plugins::templates::check(kind, template)
+                        .map_err(|e| anyhow::anyhow!("{} in plugin '{}'", e, plugin.name))?;
+                }
+            }
         }
         Ok(())
     }
@@ -301,6 +338,10 @@ pub struct ChunkingConfig {
     /// Set higher to reduce API calls for very long videos
     #[serde(default = "default_one")]
     pub cumulative_summary_interval: u32,
+
+    /// Reuse transcripts from earlier jobs for the same video ID
+    #[serde(default = "default_true")]
+    pub cache_transcripts: bool,
 }
 
 impl Default for ChunkingConfig {
@@ -317,6 +358,7 @@ impl Default for ChunkingConfig {
             download_args: Vec::new(),
             cumulative_summaries: false,    // off by default
             cumulative_summary_interval: 1, // every chunk when enabled
+            cache_transcripts: true,
         }
     }
 }
@@ -382,6 +424,88 @@ pub struct OutputConfig {
     /// Parameter name containing the source URL to post first in thread
     /// When set, uses structured output: URL -> Summary -> File
     pub source_param: Option<String>,
+
+    /// Tera templates replacing the built-in message layouts
+    #[serde(default)]
+    pub templates: OutputTemplates,
+
+    /// AI summarization stage (model, prompt, length cap, on/off)
+    #[serde(default)]
+    pub summarization: SummarizationConfig,
+
+    /// Turn transcript timestamps into YouTube deep links (`&t=95s`) in posted
+    /// transcript chunks and part headings
+    #[serde(default)]
+    pub timestamp_links: bool,
+}
+
+impl OutputConfig {
+    /// Prompt for whole-output summaries, or None when summarization is off
+    pub fn active_summary_prompt(&self) -> Option<&str> {
+        if !self.summarization.enabled {
+            return None;
+        }
+        self.summarization
+            .prompt
+            .as_deref()
+            .or(self.summary_prompt.as_deref())
+    }
+
+    /// Prompt for per-chunk summaries, falling back to the summary prompt
+    pub fn active_chunk_summary_prompt(&self) -> Option<&str> {
+        if !self.summarization.enabled {
+            return None;
+        }
+        self.chunk_summary_prompt
+            .as_deref()
+            .or_else(|| self.active_summary_prompt())
+    }
+}
+
+/// Per-plugin AI summarization settings
+///
+/// With `enabled: false` the plugin posts raw output only.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SummarizationConfig {
+    /// Whether to generate AI summaries at all
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Chat model for summaries (defaults to the bot's OPENAI_MODEL)
+    pub model: Option<String>,
+
+    /// Prompt with ${output} placeholder (defaults to `summary_prompt`)
+    pub prompt: Option<String>,
+
+    /// Upper bound on tokens generated per summary
+    pub max_tokens: Option<u64>,
+}
+
+impl Default for SummarizationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            model: None,
+            prompt: None,
+            max_tokens: None,
+        }
+    }
+}
+
+/// Tera templates for plugin messages
+///
+/// Variables: `title`, `url`, `duration`, `stdout`, `summary`, `error`, `index`, `total`.
+/// File attachments are still posted after a rendered success or video message.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct OutputTemplates {
+    /// Replaces the summary / inline output message of a successful run
+    pub success: Option<String>,
+
+    /// Replaces the error message (takes precedence over `error_template`)
+    pub error: Option<String>,
+
+    /// Replaces the header and summary posted for each playlist video
+    pub video: Option<String>,
 }
 
 /// Playlist-specific configuration
@@ -430,6 +554,18 @@ impl Default for PlaylistConfig {
     }
 }
 
+impl PlaylistConfig {
+    /// Videos to process for a request, capped by max_videos_per_request (0 = no cap)
+    pub fn effective_max_videos(&self, requested: Option<u32>) -> u32 {
+        let wanted = requested.unwrap_or(self.default_max_videos);
+        if self.max_videos_per_request > 0 {
+            wanted.min(self.max_videos_per_request)
+        } else {
+            wanted
+        }
+    }
+}
+
 // Default value functions
 fn default_true() -> bool {
     true
@@ -661,6 +797,9 @@ pub struct RawOutputConfig {
     pub chunk_summary_prompt: Option<String>,
     pub error_template: Option<String>,
     pub source_param: Option<String>,
+    pub templates: Option<OutputTemplates>,
+    pub summarization: Option<SummarizationConfig>,
+    pub timestamp_links: Option<bool>,
 }
 
 impl RawPlugin {
@@ -747,6 +886,9 @@ impl RawPlugin {
                 chunk_summary_prompt: raw_out.chunk_summary_prompt,
                 error_template: raw_out.error_template,
                 source_param: raw_out.source_param,
+                templates: raw_out.templates.unwrap_or_default(),
+                summarization: raw_out.summarization.unwrap_or_default(),
+                timestamp_links: raw_out.timestamp_links.unwrap_or(false),
             },
             None => {
                 let mut out = OutputConfig::default();
@@ -1270,4 +1412,91 @@ output:
         assert!(!plugin.output.create_thread);
         assert_eq!(plugin.output.max_inline_length, 2000);
     }
+
+    #[test]
+    fn test_output_templates_parse_and_validate() {
+        let yaml = r#"
+name: notes
+description: Notes plugin
+version: "1.0.0"
+type: shell
+execution:
+  script: echo hi
+output:
+  templates:
+    success: "**{{ title }}**\n{{ summary }}"
+    error: "⚠️ {{ error }}"
+"#;
+        let raw: RawPlugin = serde_yaml::from_str(yaml).unwrap();
+        let mut plugin = raw.resolve();
+        assert_eq!(
+            plugin.output.templates.success.as_deref(),
+            Some("**{{ title }}**\n{{ summary }}")
+        );
+        assert!(plugin.output.templates.video.is_none());
+
+        let mut config = PluginConfig {
+            plugins: vec![plugin.clone()],
+        };
+        assert!(config.validate().is_ok());
+
+        plugin.output.templates.video = Some("{% for x in %}".to_string());
+        config.plugins = vec![plugin];
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("Invalid video template"));
+        assert!(err.contains("plugin 'notes'"));
+    }
+
+    #[test]
+    fn test_summarization_config() {
+        let yaml = r#"
+name: digest
+description: Digest plugin
+version: "1.0.0"
+type: shell
+execution:
+  script: cat notes.txt
+output:
+  summary_prompt: "Summarize: ${output}"
+  summarization:
+    model: gpt-4o-mini
+    max_tokens: 400
+"#;
+        let raw: RawPlugin = serde_yaml::from_str(yaml).unwrap();
+        let mut plugin = raw.resolve();
+        let summarization = &plugin.output.summarization;
+        assert!(summarization.enabled);
+        assert_eq!(summarization.model.as_deref(), Some("gpt-4o-mini"));
+        assert_eq!(summarization.max_tokens, Some(400));
+        assert_eq!(
+            plugin.output.active_summary_prompt(),
+            Some("Summarize: ${output}")
+        );
+        assert_eq!(
+            plugin.output.active_chunk_summary_prompt(),
+            Some("Summarize: ${output}")
+        );
+
+        // The stage's own prompt wins over summary_prompt
+        plugin.output.summarization.prompt = Some("Digest: ${output}".to_string());
+        assert_eq!(
+            plugin.output.active_summary_prompt(),
+            Some("Digest: ${output}")
+        );
+
+        // Disabled: raw output only
+        plugin.output.summarization.enabled = false;
+        assert_eq!(plugin.output.active_summary_prompt(), None);
+        assert_eq!(plugin.output.active_chunk_summary_prompt(), None);
+
+        plugin.output.summarization.max_tokens = Some(0);
+        let config = PluginConfig {
+            plugins: vec![plugin],
+        };
+        assert!(config
+            .validate()
+            .unwrap_err()
+            .to_string()
+            .contains("max_tokens must be positive"));
+    }
 }
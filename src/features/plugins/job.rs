@@ -3,20 +3,45 @@
 //! Track long-running plugin executions with database persistence for crash recovery.
 //! Supports both single video jobs and multi-video playlist jobs.
 //!
-//! - **Version**: 2.0.0
+//! - **Version**: 2.4.0
 //! - **Since**: 0.9.0
 //!
 //! ## Changelog
+//! - 2.4.0: Index active jobs by (guild, plugin, normalized URL) so duplicate requests join the running job; watchers are pinged on completion
+//! - 2.3.0: Completed jobs notify the guild's job_completed webhooks
+//! - 2.2.0: Count active jobs so a self-update can drain them before restarting
+//! - 2.1.0: Expose the backing database for paginated job listings
 //! - 2.0.0: Added PlaylistJob for multi-video transcription with progress tracking
 //! - 1.0.0: Initial release with single job tracking
 
 use crate::database::Database;
+use crate::features::plugins::youtube;
+use crate::features::webhooks::{self, WebhookEvent};
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use dashmap::DashMap;
 use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
+use serenity::http::Http;
+use serenity::model::id::ChannelId;
 use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+/// Normalize a job's source URL so different spellings of one video match
+///
+/// YouTube URLs reduce to their video ID; anything else drops the fragment
+/// and trailing slash.
+pub fn job_source_key(url: &str) -> String {
+    if let Some(video_id) = youtube::video_cache_key(url) {
+        return format!("youtube:{video_id}");
+    }
+    let url = url.trim();
+    let url = url.split('#').next().unwrap_or(url);
+    url.trim_end_matches('/').to_string()
+}
+
+/// Index key for active jobs: (guild, plugin, normalized source URL)
+type SourceKey = (String, String, String);
 
 /// Status of a plugin job
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -214,6 +239,13 @@ pub struct PlaylistJob {
     pub cancelled_by: Option<String>,
 }
 
+impl Job {
+    /// Check if the job is still pending or running
+    pub fn is_active(&self) -> bool {
+        matches!(self.status, JobStatus::Pending | JobStatus::Running)
+    }
+}
+
 impl PlaylistJob {
     /// Check if the job is still active (can process more videos)
     pub fn is_active(&self) -> bool {
@@ -255,6 +287,15 @@ pub struct JobManager {
 
     /// Database for persistence
     database: Database,
+
+    /// Active top-level jobs by guild, plugin and source URL, for duplicate detection
+    active_sources: DashMap<SourceKey, String>,
+
+    /// Users waiting for a completion ping, by job ID
+    watchers: DashMap<String, Vec<u64>>,
+
+    /// HTTP client for completion pings, captured from the first watch request
+    http: OnceLock<Arc<Http>>,
 }
 
 impl JobManager {
@@ -264,9 +305,17 @@ impl JobManager {
             jobs: DashMap::new(),
             playlist_jobs: DashMap::new(),
             database,
+            active_sources: DashMap::new(),
+            watchers: DashMap::new(),
+            http: OnceLock::new(),
         }
     }
 
+    /// Database the jobs are persisted to
+    pub fn database(&self) -> &Database {
+        &self.database
+    }
+
     /// Create a new pending job
     pub async fn create_job(
         &self,
@@ -308,6 +357,9 @@ impl JobManager {
         };
 
         // Store in memory
+        if let Some(key) = Self::source_key(&job) {
+            self.active_sources.insert(key, id.clone());
+        }
         self.jobs.insert(id.clone(), job.clone());
 
         // Persist to database
@@ -344,6 +396,23 @@ impl JobManager {
             job.result = Some(result);
             self.update_job_in_db(&job).await?;
             info!("Job {job_id} completed successfully");
+            self.finish_job(&job, "finished ✅");
+
+            // Playlist videos are reported through their playlist, not one by one
+            if let (Some(guild_id), None) = (&job.guild_id, &job.parent_playlist_id) {
+                webhooks::notify(
+                    &self.database,
+                    guild_id,
+                    WebhookEvent::JobCompleted,
+                    vec![
+                        ("job_id", job.id.clone()),
+                        ("plugin", job.plugin_name.clone()),
+                        ("user_id", job.user_id.clone()),
+                        ("channel_id", job.channel_id.clone()),
+                        ("result", job.result.clone().unwrap_or_default()),
+                    ],
+                );
+            }
         }
         Ok(())
     }
@@ -356,6 +425,7 @@ impl JobManager {
             job.error = Some(error);
             self.update_job_in_db(&job).await?;
             warn!("Job {job_id} failed");
+            self.finish_job(&job, "failed ❌");
         }
         Ok(())
     }
@@ -397,6 +467,77 @@ impl JobManager {
         jobs
     }
 
+    /// The pending or running job already processing this URL for this guild and plugin
+    pub fn find_active_job(&self, guild_id: &str, plugin_name: &str, url: &str) -> Option<Job> {
+        let key = (
+            guild_id.to_string(),
+            plugin_name.to_string(),
+            job_source_key(url),
+        );
+        let job_id = self.active_sources.get(&key)?.clone();
+        self.get_job(&job_id).filter(Job::is_active)
+    }
+
+    /// Ping `user_id` when the job finishes; false if they're already watching
+    /// or the job is no longer active
+    pub fn watch_job(&self, http: &Arc<Http>, job_id: &str, user_id: u64) -> bool {
+        if !self.jobs.get(job_id).is_some_and(|j| j.is_active()) {
+            return false;
+        }
+        let _ = self.http.set(http.clone());
+
+        let mut watchers = self.watchers.entry(job_id.to_string()).or_default();
+        if watchers.contains(&user_id) {
+            return false;
+        }
+        watchers.push(user_id);
+        true
+    }
+
+    fn source_key(job: &Job) -> Option<SourceKey> {
+        if job.parent_playlist_id.is_some() {
+            return None;
+        }
+        Some((
+            job.guild_id.clone()?,
+            job.plugin_name.clone(),
+            job_source_key(job.params.get("url")?),
+        ))
+    }
+
+    /// Drop a finished job from the source index and ping its watchers
+    fn finish_job(&self, job: &Job, outcome: &str) {
+        if let Some(key) = Self::source_key(job) {
+            self.active_sources.remove_if(&key, |_, id| *id == job.id);
+        }
+
+        let Some((_, watchers)) = self.watchers.remove(&job.id) else {
+            return;
+        };
+        let Some(http) = self.http.get().cloned() else {
+            return;
+        };
+        let channel = job
+            .thread_id
+            .as_deref()
+            .unwrap_or(&job.channel_id)
+            .parse::<u64>();
+        let Ok(channel) = channel else {
+            return;
+        };
+        let mentions: Vec<String> = watchers.iter().map(|id| format!("<@{id}>")).collect();
+        let content = format!(
+            "🔔 {} the `{}` job you were waiting on has {outcome}",
+            mentions.join(" "),
+            job.plugin_name
+        );
+        tokio::spawn(async move {
+            if let Err(e) = ChannelId(channel).say(&http, content).await {
+                warn!("Failed to ping job watchers: {e}");
+            }
+        });
+    }
+
     /// Check if a user is within cooldown period for a plugin
     pub fn check_cooldown(&self, user_id: &str, plugin_name: &str, cooldown_seconds: u64) -> bool {
         if cooldown_seconds == 0 {
@@ -427,6 +568,9 @@ impl JobManager {
 
         // Load into memory cache
         for job in &jobs {
+            if let Some(key) = Self::source_key(job) {
+                self.active_sources.insert(key, job.id.clone());
+            }
             self.jobs.insert(job.id.clone(), job.clone());
         }
 
@@ -457,6 +601,26 @@ impl JobManager {
         }
     }
 
+    /// Jobs and playlists still pending or running
+    pub fn active_job_count(&self) -> usize {
+        let jobs = self
+            .jobs
+            .iter()
+            .filter(|j| matches!(j.status, JobStatus::Pending | JobStatus::Running))
+            .count();
+        let playlists = self
+            .playlist_jobs
+            .iter()
+            .filter(|j| {
+                matches!(
+                    j.status,
+                    PlaylistJobStatus::Pending | PlaylistJobStatus::Running
+                )
+            })
+            .count();
+        jobs + playlists
+    }
+
     /// Get counts of jobs by status
     pub fn get_stats(&self) -> HashMap<JobStatus, usize> {
         let mut stats = HashMap::new();
@@ -691,4 +855,69 @@ mod tests {
         assert_eq!("failed".parse::<JobStatus>().unwrap(), JobStatus::Failed);
         assert!("invalid".parse::<JobStatus>().is_err());
     }
+
+    #[tokio::test]
+    async fn test_active_job_count() {
+        let manager = JobManager::new(Database::new(":memory:").await.unwrap());
+        assert_eq!(manager.active_job_count(), 0);
+
+        let first = manager
+            .create_job("transcribe", "u1", None, "c1", HashMap::new())
+            .await
+            .unwrap();
+        let second = manager
+            .create_job("transcribe", "u1", None, "c1", HashMap::new())
+            .await
+            .unwrap();
+        manager.start_job(&second).await.unwrap();
+        assert_eq!(manager.active_job_count(), 2);
+
+        manager
+            .complete_job(&first, "done".to_string())
+            .await
+            .unwrap();
+        manager.fail_job(&second, "boom".to_string()).await.unwrap();
+        assert_eq!(manager.active_job_count(), 0);
+    }
+
+    #[test]
+    fn test_job_source_key_normalizes_urls() {
+        assert_eq!(
+            job_source_key("https://youtu.be/dQw4w9WgXcQ"),
+            job_source_key("https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=42")
+        );
+        assert_eq!(
+            job_source_key(" https://example.com/talk/#intro"),
+            "https://example.com/talk"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_active_job_by_source() {
+        let manager = JobManager::new(Database::new(":memory:").await.unwrap());
+        let params = HashMap::from([(
+            "url".to_string(),
+            "https://youtu.be/dQw4w9WgXcQ".to_string(),
+        )]);
+        let job_id = manager
+            .create_job("transcribe", "u1", Some("g1"), "c1", params)
+            .await
+            .unwrap();
+
+        let url = "https://www.youtube.com/watch?v=dQw4w9WgXcQ";
+        assert_eq!(
+            manager
+                .find_active_job("g1", "transcribe", url)
+                .map(|j| j.id),
+            Some(job_id.clone())
+        );
+        assert!(manager.find_active_job("g2", "transcribe", url).is_none());
+        assert!(manager.find_active_job("g1", "summarize", url).is_none());
+
+        manager
+            .complete_job(&job_id, "done".to_string())
+            .await
+            .unwrap();
+        assert!(manager.find_active_job("g1", "transcribe", url).is_none());
+    }
 }
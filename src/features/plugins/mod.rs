@@ -5,11 +5,24 @@
 //! Now includes multi-video playlist transcription with progress tracking and chunked streaming
 //! for long videos with per-chunk summaries.
 //!
-//! - **Version**: 4.0.1
+//! - **Version**: 4.10.0
 //! - **Since**: 0.9.0
 //! - **Toggleable**: true
 //!
 //! ## Changelog
+//! - 4.10.0: Added subscriptions module: polled YouTube channel subscriptions transcribe new uploads
+//! - 4.9.0: Playlist URLs are enumerated and filtered (date, title regex, skip-existing) before the playlist job is created
+//! - 4.8.0: Transcripts posted into threads are stored so mentions there are answered from the transcript
+//! - 4.7.0: Inline transcript parts link timestamps and part headings into the video when output.timestamp_links is set
+//! - 4.6.0: Duplicate requests for a URL already being processed join the running job
+//! - 4.5.0: Reuse cached transcripts by YouTube video ID instead of re-running yt-dlp and Whisper
+//! - 4.4.0: Jobs use a per-plugin OutputHandler; summarization.enabled=false skips all AI summaries
+//! - 4.3.0: Added templates module for Tera output templates; playlist videos pass their duration to the output handler
+//! - 4.2.0: Plugin configuration can be replaced at runtime (IPC reload); lookups return owned plugins
+//! - 4.1.1: Job tasks are counted in /sysinfo runtime metrics
+//! - 4.1.0: Inline full transcripts are posted as a paginated embed
+//! - 4.0.3: Full transcripts are split with core::chunk_text instead of raw byte chunks
+//! - 4.0.2: Made substitute_params public for the benchmark suite
 //! - 4.0.1: Fix silent failure for short video transcription (YouTube Shorts) - replace
 //!   let _ = with error logging, add diagnostic logging, remove duplicate fetch_youtube_title
 //! - 4.0.0: Plugin type presets (shell/api/docker/virtual), per-file directory loading,
@@ -45,29 +58,42 @@
 //! - 1.1.0: Added structured output posting with source_param, thread from interaction response
 //! - 1.0.0: Initial release with config-based plugins, CLI executor, and job system
 
+pub mod buttons;
 pub mod chunker;
 pub mod commands;
 pub mod config;
 pub mod executor;
 pub mod job;
 pub mod output;
+pub mod subscriptions;
+pub mod templates;
+pub mod transcript_qa;
 pub mod youtube;
 
+pub use buttons::{watch_button, JobButtons, JOBS_FEATURE};
 pub use chunker::{AudioChunker, ChunkProgress, ChunkStatus, ChunkerConfig};
 pub use commands::create_plugins_command;
-pub use config::{ChunkingConfig, Plugin, PluginConfig, PluginType, RawPlugin};
+pub use config::{
+    ChunkingConfig, OutputTemplates, Plugin, PluginConfig, PluginType, RawPlugin,
+    SummarizationConfig,
+};
 pub use executor::{ExecutionResult, PluginExecutor};
-pub use job::{Job, JobManager, JobStatus, PlaylistJob, PlaylistJobStatus};
+pub use job::{job_source_key, Job, JobManager, JobStatus, PlaylistJob, PlaylistJobStatus};
 pub use output::{
     count_words, format_transcript_sentences, format_word_count, OutputFormat, OutputHandler,
-    UserContext,
+    UserContext, VideoResult,
 };
+pub use subscriptions::{SubscriptionPoller, SUBSCRIPTION_PLUGIN};
 pub use youtube::{
-    enumerate_playlist, fetch_video_metadata, format_description_preview, parse_youtube_url,
-    PlaylistInfo, PlaylistItem, VideoMetadata, YouTubeUrl, YouTubeUrlType,
+    channel_uploads_url, enumerate_channel_uploads, enumerate_playlist, fetch_video_metadata,
+    format_description_preview, parse_youtube_url, PlaylistFilter, PlaylistInfo, PlaylistItem,
+    VideoMetadata, YouTubeUrl, YouTubeUrlType,
 };
 
-use crate::database::Database;
+use crate::commands::pagination::PaginatedEmbed;
+use crate::core::chunk_text;
+use crate::database::{CachedTranscript, Database};
+use crate::features::analytics::runtime::{spawn_tracked, TaskKind};
 
 /// Get the first 8 characters of a job ID for display
 pub fn short_job_id(id: &str) -> &str {
@@ -81,13 +107,14 @@ use anyhow::Result;
 use log::{error, info, warn};
 use serenity::http::Http;
 use serenity::model::id::ChannelId;
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
 
 /// Central manager for the plugin system
 #[derive(Clone)]
 pub struct PluginManager {
-    pub config: PluginConfig,
+    /// Live configuration, swapped as a whole on reload
+    pub config: Arc<RwLock<PluginConfig>>,
     pub executor: PluginExecutor,
     pub job_manager: Arc<JobManager>,
     pub output_handler: OutputHandler,
@@ -102,7 +129,7 @@ impl PluginManager {
         allowed_commands: Vec<String>,
     ) -> Self {
         Self {
-            config,
+            config: Arc::new(RwLock::new(config)),
             executor: PluginExecutor::new(allowed_commands),
             job_manager: Arc::new(JobManager::new(database)),
             output_handler: OutputHandler::new(openai_model),
@@ -121,16 +148,30 @@ impl PluginManager {
         Ok(Self::new(config, database, openai_model, allowed_commands))
     }
 
+    /// Snapshot of the loaded plugins
+    pub fn plugins(&self) -> Vec<Plugin> {
+        self.config
+            .read()
+            .map(|config| config.plugins.clone())
+            .unwrap_or_default()
+    }
+
+    /// Swap in a freshly loaded configuration; running jobs keep the plugin they started with
+    pub fn replace_config(&self, config: PluginConfig) {
+        if let Ok(mut current) = self.config.write() {
+            *current = config;
+        }
+    }
+
     /// Get a plugin by name
-    pub fn get_plugin(&self, name: &str) -> Option<&Plugin> {
-        self.config.plugins.iter().find(|p| p.name == name)
+    pub fn get_plugin(&self, name: &str) -> Option<Plugin> {
+        self.plugins().into_iter().find(|p| p.name == name)
     }
 
     /// Get a plugin by command name
-    pub fn get_plugin_by_command(&self, command_name: &str) -> Option<&Plugin> {
-        self.config
-            .plugins
-            .iter()
+    pub fn get_plugin_by_command(&self, command_name: &str) -> Option<Plugin> {
+        self.plugins()
+            .into_iter()
             .find(|p| p.enabled && p.command.name == command_name)
     }
 
@@ -264,13 +305,13 @@ impl PluginManager {
 
         let job_manager = self.job_manager.clone();
         let executor = self.executor.clone();
-        let output_handler = self.output_handler.clone();
+        let output_handler = self.output_handler.for_plugin(&plugin);
         let job_id_clone = job_id.clone();
         let user_id_clone = user_id.clone();
         let guild_id_clone = guild_id.clone();
         let channel_id_str = channel_id.to_string();
 
-        tokio::spawn(async move {
+        spawn_tracked(TaskKind::Jobs, async move {
             // Create user context for usage tracking
             let user_context = UserContext {
                 user_id: user_id_clone,
@@ -493,6 +534,18 @@ impl PluginManager {
                         if let Err(e) = post_result {
                             error!("Failed to post result: {e}");
                         }
+                        if source_url.is_some() {
+                            let thread_output = (is_thread || output_channel != channel_id)
+                                .then_some(output_channel);
+                            remember_thread_transcript(
+                                job_manager.database(),
+                                thread_output,
+                                &job_id_clone,
+                                None,
+                                &exec_result.stdout,
+                            )
+                            .await;
+                        }
 
                         // Mark job complete
                         let preview = exec_result.stdout.chars().take(500).collect::<String>();
@@ -514,12 +567,7 @@ impl PluginManager {
                         };
 
                         if let Err(e) = output_handler
-                            .post_error(
-                                &http,
-                                output_channel,
-                                &error_msg,
-                                plugin.output.error_template.as_deref(),
-                            )
+                            .post_error(&http, output_channel, &error_msg, &plugin.output)
                             .await
                         {
                             error!("Failed to post error: {e}");
@@ -533,12 +581,7 @@ impl PluginManager {
                 Err(e) => {
                     let error_msg = format!("Execution error: {e}");
                     if let Err(post_err) = output_handler
-                        .post_error(
-                            &http,
-                            output_channel,
-                            &error_msg,
-                            plugin.output.error_template.as_deref(),
-                        )
+                        .post_error(&http, output_channel, &error_msg, &plugin.output)
                         .await
                     {
                         error!("Failed to post error: {post_err}");
@@ -568,25 +611,37 @@ impl PluginManager {
         channel_id: ChannelId,
         interaction_info: Option<(u64, String)>,
         max_videos: Option<u32>,
+        filter: youtube::PlaylistFilter,
     ) -> Result<String> {
         let playlist_config = plugin.playlist.clone().unwrap_or_default();
 
         // Apply limits: if max_videos_per_request is 0, no hard limit applies
-        let effective_max = if playlist_config.max_videos_per_request > 0 {
-            // Admin has set a hard limit
-            max_videos
-                .unwrap_or(playlist_config.default_max_videos)
-                .min(playlist_config.max_videos_per_request)
+        let effective_max = playlist_config.effective_max_videos(max_videos);
+
+        // Filter before the job exists so an empty result never starts one
+        let listed = playlist_info.items.len();
+        let archived = if filter.skip_existing {
+            let ids: Vec<String> = playlist_info
+                .items
+                .iter()
+                .map(|v| v.video_id.clone())
+                .collect();
+            self.job_manager
+                .database()
+                .get_archived_video_ids(&ids)
+                .await?
         } else {
-            // No hard limit - use user's value or default
-            max_videos.unwrap_or(playlist_config.default_max_videos)
+            HashSet::new()
         };
+        let matching = filter.apply(playlist_info.items, &archived);
+        let filtered_out = listed - matching.len();
+        if matching.is_empty() {
+            return Err(anyhow::anyhow!(
+                "None of the {listed} listed videos match the playlist filters"
+            ));
+        }
 
-        let videos: Vec<_> = playlist_info
-            .items
-            .into_iter()
-            .take(effective_max as usize)
-            .collect();
+        let videos: Vec<_> = matching.into_iter().take(effective_max as usize).collect();
         let total_videos = videos.len() as u32;
 
         // Create playlist job record
@@ -606,7 +661,7 @@ impl PluginManager {
 
         let job_manager = self.job_manager.clone();
         let executor = self.executor.clone();
-        let output_handler = self.output_handler.clone();
+        let output_handler = self.output_handler.for_plugin(&plugin);
         let playlist_job_id_clone = playlist_job_id.clone();
         let playlist_title = playlist_info.title.clone();
         let playlist_url = format!("https://www.youtube.com/playlist?list={}", playlist_info.id);
@@ -616,7 +671,7 @@ impl PluginManager {
         let chunking_config = plugin.execution.chunking.clone().unwrap_or_default();
         let max_output_bytes = plugin.execution.max_output_bytes;
 
-        tokio::spawn(async move {
+        spawn_tracked(TaskKind::Jobs, async move {
             // Create user context for usage tracking
             let user_context = UserContext {
                 user_id: user_id_clone,
@@ -690,7 +745,12 @@ impl PluginManager {
 
                             // Post playlist URL inside the thread (first message in thread)
                             let thread_id = ChannelId(thread.id.0);
-                            let url_content = format!("**{playlist_title}**\n{playlist_url}");
+                            let mut url_content = format!("**{playlist_title}**\n{playlist_url}");
+                            if filtered_out > 0 {
+                                url_content.push_str(&format!(
+                                    "\n🔎 {filtered_out} of {listed} videos skipped by filters"
+                                ));
+                            }
                             let _ = thread_id.say(&http, &url_content).await;
 
                             Some(thread_id)
@@ -816,16 +876,40 @@ impl PluginManager {
                 // Mark video job as running
                 let _ = job_manager.start_job(&video_job_id).await;
 
-                // Execute transcription using chunked download path
-                // This ensures --no-playlist is used for each video
-                let result = Self::transcribe_single_video(
-                    &executor,
+                // Reuse a cached transcript, otherwise transcribe using the chunked
+                // download path (this ensures --no-playlist is used for each video)
+                let cached = lookup_cached_transcript(
+                    job_manager.database(),
                     &chunking_config,
-                    &video.url,
-                    &params,
-                    max_output_bytes,
+                    Some(&video.video_id),
                 )
                 .await;
+                let cached_note = cached.as_ref().map(cached_transcript_note);
+                let result = match cached {
+                    Some(cached) => Ok(cached.transcript),
+                    None => {
+                        let result = Self::transcribe_single_video(
+                            &executor,
+                            &chunking_config,
+                            &video.url,
+                            &params,
+                            max_output_bytes,
+                        )
+                        .await;
+                        if let Ok(ref transcript) = result {
+                            store_cached_transcript(
+                                job_manager.database(),
+                                &chunking_config,
+                                Some(&video.video_id),
+                                transcript,
+                                Some(&video.title),
+                                &video_job_id,
+                            )
+                            .await;
+                        }
+                        result
+                    }
+                };
 
                 match result {
                     Ok(transcript) => {
@@ -834,11 +918,14 @@ impl PluginManager {
                             .post_video_result(
                                 &http,
                                 output_channel,
-                                video_index,
-                                total_videos,
-                                &video.title,
-                                &video.url,
-                                &transcript,
+                                &VideoResult {
+                                    index: video_index,
+                                    total: total_videos,
+                                    title: &video.title,
+                                    url: &video.url,
+                                    duration: video.duration,
+                                    output: &transcript,
+                                },
                                 &plugin.output,
                                 Some(&user_context),
                             )
@@ -846,6 +933,9 @@ impl PluginManager {
                         {
                             warn!("Failed to post video result: {e}");
                         }
+                        if let Some(note) = cached_note {
+                            let _ = output_channel.say(&http, note).await;
+                        }
 
                         // Add to combined transcript
                         let separator = "=".repeat(60);
@@ -941,6 +1031,14 @@ impl PluginManager {
                         combined,
                     )
                     .await;
+                remember_thread_transcript(
+                    job_manager.database(),
+                    thread_channel,
+                    &playlist_job_id_clone,
+                    Some(&playlist_title),
+                    &combined_transcript,
+                )
+                .await;
             }
 
             // Mark playlist job complete
@@ -956,6 +1054,56 @@ impl PluginManager {
         Ok(playlist_job_id)
     }
 
+    /// Enumerate a playlist URL, apply the request's filters and start a playlist job
+    ///
+    /// Reads `max_videos` plus the filter options (`published_after`, `title_include`,
+    /// `title_exclude`, `skip_existing`) from `params`.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn execute_playlist_url(
+        &self,
+        http: Arc<Http>,
+        plugin: Plugin,
+        url: &str,
+        params: &HashMap<String, String>,
+        user_id: String,
+        guild_id: Option<String>,
+        channel_id: ChannelId,
+        interaction_info: Option<(u64, String)>,
+    ) -> Result<String> {
+        let playlist_id = parse_youtube_url(url)?
+            .playlist_id
+            .ok_or_else(|| anyhow::anyhow!("Not a playlist URL: {url}"))?;
+        let filter = youtube::PlaylistFilter::from_params(params)?;
+        let max_videos = params.get("max_videos").and_then(|v| v.parse().ok());
+
+        // Filters need the whole playlist; otherwise list only what will be transcribed
+        let list_limit = if filter.is_active() {
+            None
+        } else {
+            Some(
+                plugin
+                    .playlist
+                    .clone()
+                    .unwrap_or_default()
+                    .effective_max_videos(max_videos),
+            )
+        };
+        let playlist_info = enumerate_playlist(&playlist_id, list_limit).await?;
+
+        self.execute_playlist(
+            http,
+            plugin,
+            playlist_info,
+            user_id,
+            guild_id,
+            channel_id,
+            interaction_info,
+            max_videos,
+            filter,
+        )
+        .await
+    }
+
     /// Transcribe a single video using the chunked download path
     ///
     /// This helper function is used by playlist transcription to process individual videos.
@@ -1071,14 +1219,14 @@ impl PluginManager {
 
         let job_manager = self.job_manager.clone();
         let executor = self.executor.clone();
-        let output_handler = self.output_handler.clone();
+        let output_handler = self.output_handler.for_plugin(&plugin);
         let job_id_clone = job_id.clone();
         let params = params.clone(); // Clone params for the spawned task
         let user_id_clone = user_id.clone();
         let guild_id_clone = guild_id.clone();
         let channel_id_str = channel_id.to_string();
 
-        tokio::spawn(async move {
+        spawn_tracked(TaskKind::Jobs, async move {
             // Create user context for usage tracking
             let user_context = UserContext {
                 user_id: user_id_clone,
@@ -1089,10 +1237,15 @@ impl PluginManager {
             // Extract user options from params
             // "summaries" replaces "summary_style" with clearer naming:
             //   each = per-chunk summaries, periodic = windowed combined, all = both, none = no summaries
-            let summaries = params
-                .get("summaries")
-                .map(|s| s.as_str())
-                .unwrap_or("each");
+            // Plugins with summarization disabled post raw transcripts only
+            let summaries = if plugin.output.summarization.enabled {
+                params
+                    .get("summaries")
+                    .map(|s| s.as_str())
+                    .unwrap_or("each")
+            } else {
+                "none"
+            };
             // "summary_interval" controls chunks between periodic summaries (default: 5, range: 2-20)
             let summary_interval: u32 = params
                 .get("summary_interval")
@@ -1294,6 +1447,57 @@ impl PluginManager {
 
             let start_time = std::time::Instant::now();
 
+            // Transcripts posted into a thread back questions asked there later
+            let thread_output =
+                (is_thread || output_channel != channel_id).then_some(output_channel);
+
+            // Reuse an earlier transcript of the same video instead of downloading again
+            let video_id = youtube::video_cache_key(&url);
+            if let Some(cached) = lookup_cached_transcript(
+                job_manager.database(),
+                &chunking_config,
+                video_id.as_deref(),
+            )
+            .await
+            {
+                info!(
+                    "[{job_id_clone}] Using cached transcript for {} from job {}",
+                    cached.video_id, cached.job_id
+                );
+                let _ = output_channel
+                    .say(&http, cached_transcript_note(&cached))
+                    .await;
+                if let Err(e) = output_handler
+                    .post_structured_result(
+                        &http,
+                        output_channel,
+                        &url,
+                        &cached.transcript,
+                        &plugin.output,
+                        true,
+                        Some(&user_context),
+                    )
+                    .await
+                {
+                    error!("Failed to post cached transcript: {e}");
+                }
+                remember_thread_transcript(
+                    job_manager.database(),
+                    thread_output,
+                    &job_id_clone,
+                    Some(&video_title),
+                    &cached.transcript,
+                )
+                .await;
+                if let Err(e) = job_manager
+                    .complete_job(&job_id_clone, "completed (cached transcript)".to_string())
+                    .await
+                {
+                    warn!("Failed to mark job complete: {e}");
+                }
+                return;
+            }
+
             // STEP 2: Post initial status - downloading
             let progress_msg_id = output_handler
                 .post_chunking_started(&http, output_channel, &video_title)
@@ -1314,12 +1518,7 @@ impl PluginManager {
                 Err(e) => {
                     let error_msg = format!("Failed to initialize chunker: {e}");
                     let _ = output_handler
-                        .post_error(
-                            &http,
-                            output_channel,
-                            &error_msg,
-                            plugin.output.error_template.as_deref(),
-                        )
+                        .post_error(&http, output_channel, &error_msg, &plugin.output)
                         .await;
                     let _ = job_manager.fail_job(&job_id_clone, error_msg).await;
                     return;
@@ -1338,12 +1537,7 @@ impl PluginManager {
                 Err(e) => {
                     let error_msg = format!("Failed to download audio: {e}");
                     let _ = output_handler
-                        .post_error(
-                            &http,
-                            output_channel,
-                            &error_msg,
-                            plugin.output.error_template.as_deref(),
-                        )
+                        .post_error(&http, output_channel, &error_msg, &plugin.output)
                         .await;
                     let _ = job_manager.fail_job(&job_id_clone, error_msg).await;
                     let _ = chunker.cleanup().await;
@@ -1396,6 +1590,23 @@ impl PluginManager {
                         }
 
                         if exec_result.success {
+                            store_cached_transcript(
+                                job_manager.database(),
+                                &chunking_config,
+                                video_id.as_deref(),
+                                &exec_result.stdout,
+                                Some(&video_title),
+                                &job_id_clone,
+                            )
+                            .await;
+                            remember_thread_transcript(
+                                job_manager.database(),
+                                thread_output,
+                                &job_id_clone,
+                                Some(&video_title),
+                                &exec_result.stdout,
+                            )
+                            .await;
                             if let Err(e) = output_handler
                                 .post_structured_result(
                                     &http,
@@ -1414,7 +1625,7 @@ impl PluginManager {
                                         &http,
                                         output_channel,
                                         &format!("Failed to post transcription result: {e}"),
-                                        plugin.output.error_template.as_deref(),
+                                        &plugin.output,
                                     )
                                     .await;
                             }
@@ -1431,12 +1642,7 @@ impl PluginManager {
                                 exec_result.stderr
                             };
                             if let Err(e) = output_handler
-                                .post_error(
-                                    &http,
-                                    output_channel,
-                                    &error_msg,
-                                    plugin.output.error_template.as_deref(),
-                                )
+                                .post_error(&http, output_channel, &error_msg, &plugin.output)
                                 .await
                             {
                                 error!("Failed to post transcription error: {e}");
@@ -1449,12 +1655,7 @@ impl PluginManager {
                     Err(e) => {
                         error!("Short video transcription execution error: {e}");
                         if let Err(post_err) = output_handler
-                            .post_error(
-                                &http,
-                                output_channel,
-                                &e.to_string(),
-                                plugin.output.error_template.as_deref(),
-                            )
+                            .post_error(&http, output_channel, &e.to_string(), &plugin.output)
                             .await
                         {
                             error!("Failed to post execution error: {post_err}");
@@ -1473,12 +1674,7 @@ impl PluginManager {
                 Err(e) => {
                     let error_msg = format!("Failed to split audio: {e}");
                     let _ = output_handler
-                        .post_error(
-                            &http,
-                            output_channel,
-                            &error_msg,
-                            plugin.output.error_template.as_deref(),
-                        )
+                        .post_error(&http, output_channel, &error_msg, &plugin.output)
                         .await;
                     let _ = job_manager.fail_job(&job_id_clone, error_msg).await;
                     let _ = chunker.cleanup().await;
@@ -1566,22 +1762,38 @@ impl PluginManager {
                                     .post_file(&http, output_channel, &formatted, &chunk_filename)
                                     .await;
                             } else {
-                                // Post as text message with block quote formatting
-                                let msg = format!(
-                                    "### 📜 Part {chunk_num}/{total_chunks}\n\n>>> {chunk_content}"
-                                );
-                                let _ = output_channel.say(&http, &msg).await;
+                                // Post as text message with block quote formatting,
+                                // linking timestamps into the video when configured
+                                let linked_video = video_id
+                                    .as_deref()
+                                    .filter(|_| plugin.output.timestamp_links);
+                                if let Some(id) = linked_video {
+                                    let offset = index as u64 * chunk_duration_secs;
+                                    let heading = format!(
+                                        "### 📜 [Part {chunk_num}/{total_chunks}]({})",
+                                        youtube::timestamp_url(id, offset)
+                                    );
+                                    let _ = output_channel.say(&http, &heading).await;
+                                    // Links can push a part past one message; quote every piece
+                                    let linked =
+                                        youtube::link_timestamps(chunk_content, id, offset);
+                                    for piece in chunk_text(&linked, 1900) {
+                                        let _ =
+                                            output_channel.say(&http, format!(">>> {piece}")).await;
+                                    }
+                                } else {
+                                    let msg = format!(
+                                        "### 📜 Part {chunk_num}/{total_chunks}\n\n>>> {chunk_content}"
+                                    );
+                                    let _ = output_channel.say(&http, &msg).await;
+                                }
                             }
 
                             // Generate chunk summary for accumulation (needed for periodic or overall)
                             // Skip entirely if summaries is "none"
                             if summaries != "none" {
                                 // Use chunk_summary_prompt if available, fallback to summary_prompt
-                                let prompt_to_use = plugin
-                                    .output
-                                    .chunk_summary_prompt
-                                    .as_ref()
-                                    .or(plugin.output.summary_prompt.as_ref());
+                                let prompt_to_use = plugin.output.active_chunk_summary_prompt();
 
                                 if let Some(base_prompt) = prompt_to_use {
                                     // Build prompt with custom instructions if provided
@@ -1590,7 +1802,7 @@ impl PluginManager {
                                             "{base_prompt}\n\nAdditional instructions: {custom}"
                                         )
                                     } else {
-                                        base_prompt.clone()
+                                        base_prompt.to_string()
                                     };
 
                                     if let Some(chunk_summary) = output_handler
@@ -1819,16 +2031,17 @@ impl PluginManager {
                         )
                         .await;
                 } else {
-                    // Post as text messages (split if needed)
-                    let _ = output_channel.say(&http, "📜 **Full Transcript:**").await;
-                    // Split into 1900-char chunks to stay under Discord limit
-                    let transcript_chunks: Vec<&str> = combined_transcript
-                        .as_bytes()
-                        .chunks(1900)
-                        .map(|c| std::str::from_utf8(c).unwrap_or(""))
-                        .collect();
-                    for chunk in transcript_chunks {
-                        let _ = output_channel.say(&http, chunk).await;
+                    // Post as a paginated embed instead of a wall of messages
+                    let transcript = PaginatedEmbed::from_text(
+                        "📜 Full Transcript",
+                        &format_transcript_sentences(&combined_transcript),
+                    )
+                    .with_footer(format!("{word_count} words"));
+                    if let Err(e) = transcript
+                        .send(job_manager.database(), &http, output_channel)
+                        .await
+                    {
+                        warn!("[{job_id_clone}] Failed to post transcript pages: {e}");
                     }
                 }
             }
@@ -1837,6 +2050,23 @@ impl PluginManager {
             let _ = chunker.cleanup().await;
 
             if failed_chunks == 0 {
+                store_cached_transcript(
+                    job_manager.database(),
+                    &chunking_config,
+                    video_id.as_deref(),
+                    &combined_transcript,
+                    Some(&video_title),
+                    &job_id_clone,
+                )
+                .await;
+                remember_thread_transcript(
+                    job_manager.database(),
+                    thread_output,
+                    &job_id_clone,
+                    Some(&video_title),
+                    &combined_transcript,
+                )
+                .await;
                 let _ = job_manager
                     .complete_job(
                         &job_id_clone,
@@ -1919,6 +2149,80 @@ async fn create_thread_with_retry(
         .unwrap_or_else(|| anyhow::anyhow!("Thread creation failed after {} retries", max_retries)))
 }
 
+/// Transcript from an earlier job for this video, when the plugin caches transcripts
+async fn lookup_cached_transcript(
+    database: &Database,
+    chunking: &ChunkingConfig,
+    video_id: Option<&str>,
+) -> Option<CachedTranscript> {
+    let video_id = video_id.filter(|_| chunking.cache_transcripts)?;
+    match database.get_cached_transcript(video_id).await {
+        Ok(cached) => cached,
+        Err(e) => {
+            warn!("Transcript cache lookup failed for {video_id}: {e}");
+            None
+        }
+    }
+}
+
+/// Remember a finished transcript so later requests for the video can reuse it
+async fn store_cached_transcript(
+    database: &Database,
+    chunking: &ChunkingConfig,
+    video_id: Option<&str>,
+    transcript: &str,
+    title: Option<&str>,
+    job_id: &str,
+) {
+    let Some(video_id) = video_id.filter(|_| chunking.cache_transcripts) else {
+        return;
+    };
+    if transcript.trim().is_empty() {
+        return;
+    }
+    match database
+        .store_cached_transcript(video_id, transcript, title, job_id)
+        .await
+    {
+        Ok(()) => info!(
+            "Cached transcript for video {video_id} ({} chars)",
+            transcript.len()
+        ),
+        Err(e) => warn!("Failed to cache transcript for {video_id}: {e}"),
+    }
+}
+
+/// Keep a transcript posted into a thread so questions asked there can be answered from it
+async fn remember_thread_transcript(
+    database: &Database,
+    thread: Option<ChannelId>,
+    job_id: &str,
+    title: Option<&str>,
+    transcript: &str,
+) {
+    let Some(thread) = thread else {
+        return;
+    };
+    if transcript.trim().is_empty() {
+        return;
+    }
+    if let Err(e) = database
+        .store_thread_transcript(&thread.to_string(), job_id, title, transcript)
+        .await
+    {
+        warn!("Failed to store transcript for thread {thread}: {e}");
+    }
+}
+
+/// Note posted alongside a transcript served from the cache
+fn cached_transcript_note(cached: &CachedTranscript) -> String {
+    format!(
+        "♻️ *Cached transcript from job `{}` ({} UTC), not re-transcribed*",
+        short_job_id(&cached.job_id),
+        cached.created_at
+    )
+}
+
 /// Finalize the ephemeral interaction response
 ///
 /// Updates the ephemeral "thinking" message to show the final status.
@@ -1939,7 +2243,7 @@ async fn finalize_interaction_response(interaction_info: &Option<(u64, String)>,
 }
 
 /// Substitute ${param} placeholders in a string
-fn substitute_params(template: &str, params: &HashMap<String, String>) -> String {
+pub fn substitute_params(template: &str, params: &HashMap<String, String>) -> String {
     let mut result = template.to_string();
     for (key, value) in params {
         let placeholder = format!("${{{key}}}");
@@ -3,10 +3,16 @@
 //! Create Discord threads for plugin output, handle large responses with file attachments,
 //! and generate AI summaries. Supports both single video and playlist transcription.
 //!
-//! - **Version**: 3.4.1
+//! - **Version**: 3.9.1
 //! - **Since**: 0.9.0
 //!
 //! ## Changelog
+//! - 3.9.1: post_video_result() takes the video and its transcript as a VideoResult
+//! - 3.9.0: Summaries honour per-plugin summarization model and token cap via for_plugin(); costs are tagged plugin:<name>:<stage>
+//! - 3.8.0: Success, error and per-video messages can be laid out with plugin Tera templates
+//! - 3.7.0: Inline output uses the shared markdown-aware splitter from core::response
+//! - 3.6.0: Thread creation honours chaos-injected Discord 429s to exercise the retry path
+//! - 3.5.0: AI summaries go through an injectable ChatClient
 //! - 3.4.1: Added error logging to AI summary OpenAI API calls for better diagnostics
 //! - 3.4.0: Added escape_markdown() for safe embedding of user text in markdown formatting
 //! - 3.3.0: Added output_format support, sentence-per-line transcript formatting, word count helpers
@@ -17,12 +23,16 @@
 //! - 1.1.0: Added structured output posting (URL -> summary -> file)
 //! - 1.0.0: Initial release
 
-use crate::core::sanitize_filename;
+use crate::core::{
+    chunk_text, openai_chat_client, sanitize_filename, truncate_for_message, ChatClient,
+};
 use crate::features::analytics::{CostBucket, UsageTracker};
-use crate::features::plugins::config::OutputConfig;
+use crate::features::plugins::config::{OutputConfig, Plugin};
+use crate::features::plugins::templates::{self, TemplateVars};
+use crate::features::resilience::chaos;
 use anyhow::Result;
 use log::{error, info, warn};
-use openai::chat::{ChatCompletion, ChatCompletionMessage, ChatCompletionMessageRole};
+use openai::chat::{ChatCompletionMessage, ChatCompletionMessageRole};
 use serenity::http::Http;
 use serenity::model::channel::{AttachmentType, ChannelType, GuildChannel};
 use serenity::model::id::{ChannelId, MessageId};
@@ -37,11 +47,29 @@ pub struct UserContext {
     pub channel_id: Option<String>,
 }
 
+/// One transcribed playlist video, as posted in the playlist thread
+pub struct VideoResult<'a> {
+    /// 1-based position in the playlist
+    pub index: u32,
+    pub total: u32,
+    pub title: &'a str,
+    pub url: &'a str,
+    /// Duration in seconds, when known
+    pub duration: Option<u64>,
+    /// Transcript text
+    pub output: &'a str,
+}
+
 /// Handler for plugin output posting
 #[derive(Clone)]
 pub struct OutputHandler {
     openai_model: String,
+    chat_client: Arc<dyn ChatClient>,
     usage_tracker: Option<UsageTracker>,
+    /// Token cap for summaries (plugin summarization.max_tokens)
+    summary_max_tokens: Option<u64>,
+    /// Plugin whose summaries this handler generates, for cost attribution
+    plugin_name: Option<String>,
 }
 
 impl OutputHandler {
@@ -49,8 +77,30 @@ impl OutputHandler {
     pub fn new(openai_model: String) -> Self {
         Self {
             openai_model,
+            chat_client: openai_chat_client(),
             usage_tracker: None,
+            summary_max_tokens: None,
+            plugin_name: None,
+        }
+    }
+
+    /// A copy configured with a plugin's summarization model and token cap,
+    /// logging summary costs under the plugin's name
+    pub fn for_plugin(&self, plugin: &Plugin) -> Self {
+        let summarization = &plugin.output.summarization;
+        let mut handler = self.clone();
+        if let Some(ref model) = summarization.model {
+            handler.openai_model = model.clone();
         }
+        handler.summary_max_tokens = summarization.max_tokens;
+        handler.plugin_name = Some(plugin.name.clone());
+        handler
+    }
+
+    /// Builder method to replace the ChatClient used for summaries
+    pub fn with_chat_client(mut self, chat_client: Arc<dyn ChatClient>) -> Self {
+        self.chat_client = chat_client;
+        self
     }
 
     /// Builder method to add a UsageTracker
@@ -80,6 +130,12 @@ impl OutputHandler {
             "Creating thread '{thread_name}' in channel {channel_id} from message {message_id} (archive: {archive_duration} min)"
         );
 
+        chaos()
+            .discord_fault(&format!(
+                "channels/{channel_id}/messages/{message_id}/threads"
+            ))
+            .map_err(|e| anyhow::anyhow!("Failed to create thread: {}", e))?;
+
         channel_id
             .create_public_thread(http, message_id, |t| {
                 t.name(thread_name)
@@ -132,41 +188,54 @@ impl OutputHandler {
 
         // Check if we should use file attachment
         let use_file = config.post_as_file && output.len() > config.max_inline_length;
+        let template = config.templates.success.as_deref();
+
+        // Generate summary if configured and something will show it
+        let summary = match config.active_summary_prompt() {
+            Some(prompt) if use_file || template.is_some() => Some(
+                self.generate_summary_with_tracking(
+                    output,
+                    prompt,
+                    user_context,
+                    Some("result_summary"),
+                )
+                .await,
+            ),
+            _ => None,
+        };
+
+        // A success template replaces the summary / inline message
+        let rendered = template.and_then(|t| {
+            let vars = TemplateVars {
+                stdout: Some(output.to_string()),
+                summary: summary.as_ref().and_then(|s| s.as_ref().ok()).cloned(),
+                ..Default::default()
+            };
+            render_template("success", t, &vars)
+        });
+        if let Some(message) = rendered {
+            say_chunked(http, channel_id, &message).await?;
+            if use_file {
+                let filename = output_filename(config, "output.txt");
+                self.post_file(http, channel_id, output, &filename).await?;
+            }
+            return Ok(());
+        }
 
         if use_file {
-            // Generate summary if configured
-            let summary = if let Some(ref prompt) = config.summary_prompt {
-                match self
-                    .generate_summary_with_tracking(
-                        output,
-                        prompt,
-                        user_context,
-                        Some("result_summary"),
-                    )
-                    .await
-                {
-                    Ok(s) => s,
-                    Err(e) => {
-                        warn!("Failed to generate summary: {e}");
-                        format!("**Output** ({} characters)", output.len())
-                    }
+            let summary = match summary {
+                Some(Ok(s)) => s,
+                Some(Err(e)) => {
+                    warn!("Failed to generate summary: {e}");
+                    format!("**Output** ({} characters)", output.len())
                 }
-            } else {
-                format!("**Output** ({} characters)", output.len())
+                None => format!("**Output** ({} characters)", output.len()),
             };
 
             // Post summary
             channel_id.say(http, &summary).await?;
 
-            // Generate filename
-            let filename = config
-                .file_name_template
-                .as_deref()
-                .unwrap_or("output.txt")
-                .replace(
-                    "${timestamp}",
-                    &chrono::Utc::now().format("%Y%m%d_%H%M%S").to_string(),
-                );
+            let filename = output_filename(config, "output.txt");
 
             // Post file attachment
             let file_bytes = output.as_bytes().to_vec();
@@ -182,7 +251,7 @@ impl OutputHandler {
             info!("Posted output as file attachment");
         } else {
             // Post inline, splitting if necessary
-            let chunks = split_message(output, 1900);
+            let chunks = chunk_text(output, 1900);
 
             for (i, chunk) in chunks.iter().enumerate() {
                 if i == 0 {
@@ -204,22 +273,25 @@ impl OutputHandler {
         http: &Arc<Http>,
         channel_id: ChannelId,
         error: &str,
-        error_template: Option<&str>,
+        config: &OutputConfig,
     ) -> Result<()> {
-        let message = if let Some(template) = error_template {
-            template.replace("${error}", error)
-        } else {
-            format!("**Error:** {error}")
-        };
-
-        // Truncate if too long
-        let message = if message.len() > 1900 {
-            format!("{}...", &message[..1897])
-        } else {
-            message
+        let rendered = config.templates.error.as_deref().and_then(|t| {
+            let vars = TemplateVars {
+                error: Some(error.to_string()),
+                ..Default::default()
+            };
+            render_template("error", t, &vars)
+        });
+        let message = match (rendered, config.error_template.as_deref()) {
+            (Some(message), _) => message,
+            (None, Some(template)) => template.replace("${error}", error),
+            (None, None) => format!("**Error:** {error}"),
         };
+        if message.is_empty() {
+            return Ok(());
+        }
 
-        channel_id.say(http, &message).await?;
+        channel_id.say(http, truncate_for_message(&message)).await?;
         Ok(())
     }
 
@@ -248,36 +320,44 @@ impl OutputHandler {
             return Ok(());
         }
 
-        // 2. Generate and post the summary
-        if let Some(ref prompt) = config.summary_prompt {
-            match self
-                .generate_summary_with_tracking(output, prompt, user_context, Some("video_summary"))
-                .await
-            {
-                Ok(summary) => {
-                    // Post summary, splitting if needed
-                    let summary_chunks = split_message(&summary, 1900);
-                    for chunk in summary_chunks {
-                        channel_id.say(http, &chunk).await?;
-                    }
-                    info!("Posted AI summary");
-                }
-                Err(e) => {
-                    warn!("Failed to generate summary: {e}");
-                    channel_id.say(http, "*Summary generation failed*").await?;
-                }
+        // 2. Generate and post the summary (or the plugin's success template)
+        let summary = match config.active_summary_prompt() {
+            Some(prompt) => Some(
+                self.generate_summary_with_tracking(
+                    output,
+                    prompt,
+                    user_context,
+                    Some("video_summary"),
+                )
+                .await,
+            ),
+            None => None,
+        };
+        let rendered = config.templates.success.as_deref().and_then(|t| {
+            let vars = TemplateVars {
+                url: Some(source_url.to_string()),
+                stdout: Some(output.to_string()),
+                summary: summary.as_ref().and_then(|s| s.as_ref().ok()).cloned(),
+                ..Default::default()
+            };
+            render_template("success", t, &vars)
+        });
+        match (rendered, summary) {
+            (Some(message), _) => say_chunked(http, channel_id, &message).await?,
+            (None, Some(Ok(summary))) => {
+                // Post summary, splitting if needed
+                say_chunked(http, channel_id, &summary).await?;
+                info!("Posted AI summary");
             }
+            (None, Some(Err(e))) => {
+                warn!("Failed to generate summary: {e}");
+                channel_id.say(http, "*Summary generation failed*").await?;
+            }
+            (None, None) => {}
         }
 
         // 3. Post the full transcript as a file attachment
-        let filename = config
-            .file_name_template
-            .as_deref()
-            .unwrap_or("transcript.txt")
-            .replace(
-                "${timestamp}",
-                &chrono::Utc::now().format("%Y%m%d_%H%M%S").to_string(),
-            );
+        let filename = output_filename(config, "transcript.txt");
 
         let file_bytes = output.as_bytes().to_vec();
         channel_id
@@ -344,49 +424,74 @@ impl OutputHandler {
         &self,
         http: &Arc<Http>,
         channel_id: ChannelId,
-        index: u32,
-        total: u32,
-        video_title: &str,
-        video_url: &str,
-        output: &str,
+        video: &VideoResult<'_>,
         config: &OutputConfig,
         user_context: Option<&UserContext>,
     ) -> Result<()> {
-        // Post video header with separator
-        let header = format!(
-            "---\n**[{index}/{total}] {video_title}**\n{video_url}"
-        );
-        channel_id.say(http, &header).await?;
-
-        if output.is_empty() {
-            channel_id.say(http, "*No transcript generated*").await?;
-            return Ok(());
-        }
-
-        // Generate and post summary if configured
-        if let Some(ref prompt) = config.summary_prompt {
-            match self
-                .generate_summary_with_tracking(
+        let VideoResult {
+            index,
+            total,
+            title: video_title,
+            url: video_url,
+            duration,
+            output,
+        } = *video;
+        let summary = match config.active_summary_prompt() {
+            Some(prompt) if !output.is_empty() => Some(
+                self.generate_summary_with_tracking(
                     output,
                     prompt,
                     user_context,
                     Some("playlist_video_summary"),
                 )
-                .await
-            {
-                Ok(summary) => {
-                    let summary_chunks = split_message(&summary, 1900);
-                    for chunk in summary_chunks {
-                        channel_id.say(http, &chunk).await?;
-                    }
-                }
-                Err(e) => {
+                .await,
+            ),
+            _ => None,
+        };
+
+        // A video template replaces the header and summary
+        let rendered = config.templates.video.as_deref().and_then(|t| {
+            let vars = TemplateVars {
+                title: Some(video_title.to_string()),
+                url: Some(video_url.to_string()),
+                duration: duration.map(|secs| {
+                    crate::features::plugins::youtube::format_duration(
+                        std::time::Duration::from_secs(secs),
+                    )
+                }),
+                stdout: Some(output.to_string()),
+                summary: summary.as_ref().and_then(|s| s.as_ref().ok()).cloned(),
+                index: Some(index),
+                total: Some(total),
+                ..Default::default()
+            };
+            render_template("video", t, &vars)
+        });
+
+        if let Some(message) = rendered {
+            say_chunked(http, channel_id, &message).await?;
+        } else {
+            // Post video header with separator
+            let header = format!(
+                "---\n**[{index}/{total}] {video_title}**\n{video_url}"
+            );
+            channel_id.say(http, &header).await?;
+
+            match summary {
+                Some(Ok(summary)) => say_chunked(http, channel_id, &summary).await?,
+                Some(Err(e)) => {
                     warn!("Failed to generate summary for video {index}: {e}");
                     channel_id.say(http, "*Summary unavailable*").await?;
                 }
+                None => {}
             }
         }
 
+        if output.is_empty() {
+            channel_id.say(http, "*No transcript generated*").await?;
+            return Ok(());
+        }
+
         // Post transcript file
         let filename = format!(
             "transcript_{:03}_{}.txt",
@@ -625,7 +730,7 @@ impl OutputHandler {
 
         // Generate and post AI summary if we have transcript
         if let Some(transcript) = combined_transcript {
-            if let Some(ref prompt) = config.summary_prompt {
+            if let Some(prompt) = config.active_summary_prompt() {
                 match self
                     .generate_summary_with_tracking(
                         transcript,
@@ -636,7 +741,7 @@ impl OutputHandler {
                     .await
                 {
                     Ok(ai_summary) => {
-                        let summary_chunks = split_message(&ai_summary, 1900);
+                        let summary_chunks = chunk_text(&ai_summary, 1900);
                         for chunk in summary_chunks {
                             channel_id.say(http, &chunk).await?;
                         }
@@ -798,43 +903,45 @@ impl OutputHandler {
 
         info!("Generating AI summary for output ({} chars)", output.len());
 
-        let completion = ChatCompletion::builder(
-            &self.openai_model,
-            vec![
-                ChatCompletionMessage {
-                    role: ChatCompletionMessageRole::System,
-                    content: Some(
-                        "You are a helpful assistant that creates concise summaries. \
-                         Keep summaries brief and focused on the key points."
-                            .to_string(),
-                    ),
-                    name: None,
-                    function_call: None,
-                    tool_call_id: None,
-                    tool_calls: None,
-                },
-                ChatCompletionMessage {
-                    role: ChatCompletionMessageRole::User,
-                    content: Some(prompt),
-                    name: None,
-                    function_call: None,
-                    tool_call_id: None,
-                    tool_calls: None,
-                },
-            ],
-        )
-        .create()
-        .await
-        .map_err(|e| {
-            error!("Plugin summary OpenAI API error: {e}");
-            anyhow::anyhow!("OpenAI API error: {e}")
-        })?;
+        let completion = self
+            .chat_client
+            .create_chat_completion_limited(
+                &self.openai_model,
+                vec![
+                    ChatCompletionMessage {
+                        role: ChatCompletionMessageRole::System,
+                        content: Some(
+                            "You are a helpful assistant that creates concise summaries. \
+                             Keep summaries brief and focused on the key points."
+                                .to_string(),
+                        ),
+                        name: None,
+                        function_call: None,
+                        tool_call_id: None,
+                        tool_calls: None,
+                    },
+                    ChatCompletionMessage {
+                        role: ChatCompletionMessageRole::User,
+                        content: Some(prompt),
+                        name: None,
+                        function_call: None,
+                        tool_call_id: None,
+                        tool_calls: None,
+                    },
+                ],
+                self.summary_max_tokens,
+            )
+            .await
+            .map_err(|e| {
+                error!("Plugin summary OpenAI API error: {e}");
+                anyhow::anyhow!("OpenAI API error: {e}")
+            })?;
 
         // Log usage if tracker and user context are available
         if let (Some(tracker), Some(ctx), Some(usage)) =
             (&self.usage_tracker, user_context, &completion.usage)
         {
-            let request_id = request_context.map(|c| format!("plugin_{c}"));
+            let request_id = summary_request_id(self.plugin_name.as_deref(), request_context);
             tracker.log_chat(
                 &self.openai_model,
                 usage.prompt_tokens,
@@ -847,10 +954,11 @@ impl OutputHandler {
                 CostBucket::Plugin,
             );
             info!(
-                "Logged plugin AI usage: {} tokens for user {} ({})",
+                "Logged plugin AI usage: {} tokens for user {} ({}, plugin {})",
                 usage.total_tokens,
                 ctx.user_id,
-                request_context.unwrap_or("summary")
+                request_context.unwrap_or("summary"),
+                self.plugin_name.as_deref().unwrap_or("unknown")
             );
         }
 
@@ -880,6 +988,53 @@ pub fn escape_markdown(text: &str) -> String {
         .collect()
 }
 
+/// Usage request ID for a plugin summary: `plugin:<name>:<context>` so costs
+/// can be grouped per plugin, or the legacy `plugin_<context>` without a name
+pub fn summary_request_id(
+    plugin_name: Option<&str>,
+    request_context: Option<&str>,
+) -> Option<String> {
+    match (plugin_name, request_context) {
+        (Some(name), context) => Some(format!("plugin:{name}:{}", context.unwrap_or("summary"))),
+        (None, Some(context)) => Some(format!("plugin_{context}")),
+        (None, None) => None,
+    }
+}
+
+/// Render a plugin output template; `None` means use the built-in layout
+fn render_template(kind: &str, template: &str, vars: &TemplateVars) -> Option<String> {
+    match templates::render(template, vars) {
+        Ok(message) => Some(message),
+        Err(e) => {
+            warn!("Failed to render {kind} template, using default layout: {e}");
+            None
+        }
+    }
+}
+
+/// Post text split into Discord-sized messages (nothing for empty text)
+async fn say_chunked(http: &Arc<Http>, channel_id: ChannelId, text: &str) -> Result<()> {
+    if text.is_empty() {
+        return Ok(());
+    }
+    for chunk in chunk_text(text, 1900) {
+        channel_id.say(http, &chunk).await?;
+    }
+    Ok(())
+}
+
+/// Attachment filename from the plugin's template, with ${timestamp} filled in
+fn output_filename(config: &OutputConfig, default: &str) -> String {
+    config
+        .file_name_template
+        .as_deref()
+        .unwrap_or(default)
+        .replace(
+            "${timestamp}",
+            &chrono::Utc::now().format("%Y%m%d_%H%M%S").to_string(),
+        )
+}
+
 /// Truncate a string to max length, adding ellipsis if needed
 fn truncate_str(s: &str, max_len: usize) -> String {
     if s.len() <= max_len {
@@ -1005,63 +1160,21 @@ pub fn format_word_count(count: usize) -> String {
     }
 }
 
-/// Split a message into chunks that fit within Discord's character limit
-fn split_message(content: &str, max_len: usize) -> Vec<String> {
-    if content.len() <= max_len {
-        return vec![content.to_string()];
-    }
-
-    let mut chunks = Vec::new();
-    let mut current = String::new();
-
-    for line in content.lines() {
-        // Check if adding this line would exceed the limit
-        if current.len() + line.len() + 1 > max_len {
-            if !current.is_empty() {
-                chunks.push(current);
-            }
-
-            // If a single line is too long, split it
-            if line.len() > max_len {
-                let mut remaining = line;
-                while remaining.len() > max_len {
-                    chunks.push(remaining[..max_len].to_string());
-                    remaining = &remaining[max_len..];
-                }
-                current = remaining.to_string();
-            } else {
-                current = line.to_string();
-            }
-        } else {
-            if !current.is_empty() {
-                current.push('\n');
-            }
-            current.push_str(line);
-        }
-    }
-
-    if !current.is_empty() {
-        chunks.push(current);
-    }
-
-    chunks
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn test_split_message_short() {
-        let chunks = split_message("hello world", 100);
+    fn test_chunk_text_short() {
+        let chunks = chunk_text("hello world", 100);
         assert_eq!(chunks.len(), 1);
         assert_eq!(chunks[0], "hello world");
     }
 
     #[test]
-    fn test_split_message_long() {
+    fn test_chunk_text_long() {
         let content = "line 1\nline 2\nline 3\nline 4\nline 5";
-        let chunks = split_message(content, 15);
+        let chunks = chunk_text(content, 15);
         assert!(chunks.len() > 1);
 
         // Verify all content is preserved
@@ -1072,9 +1185,9 @@ mod tests {
     }
 
     #[test]
-    fn test_split_message_very_long_line() {
+    fn test_chunk_text_very_long_line() {
         let content = "a".repeat(100);
-        let chunks = split_message(&content, 30);
+        let chunks = chunk_text(&content, 30);
         assert!(chunks.len() > 1);
 
         // Total length should match
@@ -1162,4 +1275,60 @@ mod tests {
         // Empty string
         assert_eq!(escape_markdown(""), "");
     }
+
+    #[test]
+    fn test_summary_request_id() {
+        assert_eq!(
+            summary_request_id(Some("transcribe"), Some("video_summary")).as_deref(),
+            Some("plugin:transcribe:video_summary")
+        );
+        assert_eq!(
+            summary_request_id(Some("notes"), None).as_deref(),
+            Some("plugin:notes:summary")
+        );
+        assert_eq!(
+            summary_request_id(None, Some("chunk_summary")).as_deref(),
+            Some("plugin_chunk_summary")
+        );
+        assert_eq!(summary_request_id(None, None), None);
+    }
+
+    #[tokio::test]
+    async fn test_for_plugin_uses_summarization_model_and_cap() {
+        use crate::features::plugins::config::RawPlugin;
+        use crate::testing::mock_openai::MockChatClient;
+
+        let yaml = r#"
+name: digest
+description: Digest plugin
+version: "1.0.0"
+type: shell
+execution:
+  script: cat notes.txt
+output:
+  summarization:
+    model: gpt-4o-mini
+    max_tokens: 300
+"#;
+        let plugin = serde_yaml::from_str::<RawPlugin>(yaml).unwrap().resolve();
+        let mock = Arc::new(MockChatClient::new());
+        mock.push_reply("short digest");
+        let base = OutputHandler::new("gpt-5.1".to_string()).with_chat_client(mock.clone());
+
+        let summary = base
+            .for_plugin(&plugin)
+            .generate_summary_for_text("long notes", "Digest: ${output}")
+            .await;
+        assert_eq!(summary.as_deref(), Some("short digest"));
+
+        let request = &mock.requests()[0];
+        assert_eq!(request.model, "gpt-4o-mini");
+        assert_eq!(request.max_tokens, Some(300));
+        assert_eq!(request.last_user_message(), Some("Digest: long notes"));
+
+        // The shared handler keeps the bot's defaults
+        base.generate_summary_for_text("x", "${output}").await;
+        assert_eq!(mock.requests()[1].model, "gpt-5.1");
+        assert_eq!(mock.requests()[1].max_tokens, None);
+    }
 }
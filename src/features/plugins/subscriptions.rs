@@ -0,0 +1,289 @@
+//! # Channel Subscriptions
+//!
+//! Poll subscribed YouTube channels and transcribe new uploads into threads in
+//! the subscription's Discord channel. Uploads present when the subscription is
+//! created are marked seen, so only videos published afterwards are transcribed.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with per-subscription poll interval and upload limit
+
+use anyhow::Result;
+use chrono::Utc;
+use log::{debug, error, info, warn};
+use serenity::http::Http;
+use serenity::model::id::ChannelId;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::interval;
+
+use super::youtube::{self, PlaylistItem};
+use super::{Plugin, PluginManager};
+use crate::database::TranscribeSubscription;
+
+/// Plugin that transcribes subscription uploads
+pub const SUBSCRIPTION_PLUGIN: &str = "transcribe";
+
+/// Latest uploads looked at per poll
+pub const FEED_WINDOW: u32 = 15;
+
+/// Hours between polls when the subscriber doesn't choose
+pub const DEFAULT_INTERVAL_HOURS: u32 = 6;
+
+/// Uploads transcribed per poll when the subscriber doesn't choose
+pub const DEFAULT_MAX_PER_CHECK: u32 = 2;
+
+/// Subscriptions a guild can hold at once
+pub const MAX_SUBSCRIPTIONS_PER_GUILD: usize = 10;
+
+/// Whether a subscription should be polled at unix time `now`
+pub fn subscription_due(subscription: &TranscribeSubscription, now: i64) -> bool {
+    subscription
+        .last_checked_at
+        .is_none_or(|at| now - at >= subscription.interval_minutes as i64 * 60)
+}
+
+/// Unseen uploads from a newest-first feed, oldest first, at most `limit`
+///
+/// Uploads beyond the limit stay unseen and are picked up by the next poll.
+pub fn pick_new_uploads(
+    feed: &[PlaylistItem],
+    seen: &HashSet<String>,
+    limit: usize,
+) -> Vec<PlaylistItem> {
+    feed.iter()
+        .rev()
+        .filter(|item| !seen.contains(&item.video_id))
+        .take(limit)
+        .cloned()
+        .collect()
+}
+
+/// Background task polling channel subscriptions
+pub struct SubscriptionPoller {
+    plugin_manager: Arc<PluginManager>,
+}
+
+impl SubscriptionPoller {
+    pub fn new(plugin_manager: Arc<PluginManager>) -> Self {
+        Self { plugin_manager }
+    }
+
+    /// Start the subscription poll loop
+    /// This should be spawned as a tokio task
+    pub async fn run(&self, http: Arc<Http>) {
+        let mut check_interval = interval(Duration::from_secs(60));
+
+        info!("📡 Subscription poller started");
+
+        loop {
+            check_interval.tick().await;
+
+            if let Err(e) = self.process_subscriptions(&http).await {
+                error!("❌ Error polling subscriptions: {e}");
+            }
+        }
+    }
+
+    async fn process_subscriptions(&self, http: &Arc<Http>) -> Result<()> {
+        let database = self.plugin_manager.job_manager.database();
+        let now = Utc::now().timestamp();
+        let due: Vec<_> = database
+            .get_transcribe_subscriptions()
+            .await?
+            .into_iter()
+            .filter(|s| subscription_due(s, now))
+            .collect();
+        if due.is_empty() {
+            return Ok(());
+        }
+
+        let Some(plugin) = self.plugin_manager.get_plugin(SUBSCRIPTION_PLUGIN) else {
+            debug!("📡 {SUBSCRIPTION_PLUGIN} plugin not loaded, skipping subscriptions");
+            return Ok(());
+        };
+        if !plugin.enabled {
+            return Ok(());
+        }
+
+        for subscription in due {
+            // Marked first so a broken channel waits for its next interval
+            database
+                .mark_transcribe_subscription_checked(subscription.id, now)
+                .await?;
+            if !database
+                .is_feature_enabled("plugins", None, Some(&subscription.guild_id))
+                .await?
+            {
+                continue;
+            }
+            if let Err(e) = self.check_subscription(http, &plugin, &subscription).await {
+                warn!(
+                    "⚠️ Failed to poll subscription #{} ({}): {e}",
+                    subscription.id, subscription.source_url
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Start jobs for a subscription's new uploads
+    async fn check_subscription(
+        &self,
+        http: &Arc<Http>,
+        plugin: &Plugin,
+        subscription: &TranscribeSubscription,
+    ) -> Result<()> {
+        let database = self.plugin_manager.job_manager.database();
+        let feed =
+            youtube::enumerate_channel_uploads(&subscription.source_url, Some(FEED_WINDOW)).await?;
+        let seen = database
+            .get_subscription_seen_videos(subscription.id)
+            .await?;
+        let uploads = pick_new_uploads(&feed.items, &seen, subscription.max_per_check as usize);
+        if uploads.is_empty() {
+            debug!("📡 No new uploads for subscription #{}", subscription.id);
+            return Ok(());
+        }
+
+        info!(
+            "📡 Subscription #{}: {} new upload(s) from {}",
+            subscription.id,
+            uploads.len(),
+            subscription.source_url
+        );
+        let channel_id = ChannelId(subscription.channel_id.parse()?);
+        for upload in &uploads {
+            // Seen even if the job fails to start, so one bad video doesn't block the feed
+            database
+                .mark_subscription_videos_seen(
+                    subscription.id,
+                    std::slice::from_ref(&upload.video_id),
+                )
+                .await?;
+
+            if let Some(job) = self.plugin_manager.job_manager.find_active_job(
+                &subscription.guild_id,
+                &plugin.name,
+                &upload.url,
+            ) {
+                debug!(
+                    "📡 {} is already being transcribed by job {}",
+                    upload.url, job.id
+                );
+                continue;
+            }
+            match self
+                .start_upload(http, plugin, subscription, channel_id, upload)
+                .await
+            {
+                Ok(job_id) => info!("📡 Started job {job_id} for {}", upload.url),
+                Err(e) => warn!("⚠️ Failed to start transcription of {}: {e}", upload.url),
+            }
+        }
+        Ok(())
+    }
+
+    /// Transcribe one upload into a new thread, chunked when the plugin supports it
+    async fn start_upload(
+        &self,
+        http: &Arc<Http>,
+        plugin: &Plugin,
+        subscription: &TranscribeSubscription,
+        channel_id: ChannelId,
+        upload: &PlaylistItem,
+    ) -> Result<String> {
+        let mut params: HashMap<String, String> = plugin
+            .command
+            .options
+            .iter()
+            .filter_map(|opt| Some((opt.name.clone(), opt.default.clone()?)))
+            .collect();
+        params.insert("url".to_string(), upload.url.clone());
+
+        if self.plugin_manager.should_use_chunking(plugin) {
+            self.plugin_manager
+                .execute_chunked_transcription(
+                    http.clone(),
+                    plugin.clone(),
+                    upload.url.clone(),
+                    upload.title.clone(),
+                    params,
+                    subscription.user_id.clone(),
+                    Some(subscription.guild_id.clone()),
+                    channel_id,
+                    None,
+                    false,
+                )
+                .await
+        } else {
+            self.plugin_manager
+                .execute_plugin(
+                    http.clone(),
+                    plugin.clone(),
+                    params,
+                    subscription.user_id.clone(),
+                    Some(subscription.guild_id.clone()),
+                    channel_id,
+                    None,
+                    false,
+                )
+                .await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn upload(video_id: &str) -> PlaylistItem {
+        PlaylistItem {
+            video_id: video_id.to_string(),
+            title: video_id.to_string(),
+            url: format!("https://www.youtube.com/watch?v={video_id}"),
+            duration: None,
+            index: 0,
+            description: None,
+            upload_date: None,
+        }
+    }
+
+    #[test]
+    fn test_subscription_due() {
+        let mut subscription = TranscribeSubscription {
+            id: 1,
+            guild_id: "g".to_string(),
+            channel_id: "1".to_string(),
+            source_url: "https://www.youtube.com/@someone/videos".to_string(),
+            title: None,
+            user_id: "u".to_string(),
+            interval_minutes: 60,
+            max_per_check: 2,
+            last_checked_at: None,
+        };
+        assert!(subscription_due(&subscription, 1_000));
+
+        subscription.last_checked_at = Some(1_000);
+        assert!(!subscription_due(&subscription, 1_000 + 59 * 60));
+        assert!(subscription_due(&subscription, 1_000 + 60 * 60));
+    }
+
+    #[test]
+    fn test_pick_new_uploads_oldest_first_within_limit() {
+        // Feeds are newest first
+        let feed = ["e", "d", "c", "b", "a"].map(upload);
+        let seen = HashSet::from(["a".to_string(), "c".to_string()]);
+
+        let picked: Vec<_> = pick_new_uploads(&feed, &seen, 2)
+            .into_iter()
+            .map(|item| item.video_id)
+            .collect();
+        assert_eq!(picked, vec!["b", "d"]);
+
+        assert!(pick_new_uploads(&feed[..1], &HashSet::from(["e".to_string()]), 2).is_empty());
+    }
+}
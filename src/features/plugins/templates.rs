@@ -0,0 +1,116 @@
+//! # Output Templates
+//!
+//! Render plugin-defined Tera templates for success, error and per-video messages.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with title, url, duration, stdout, summary and error variables
+
+use anyhow::Result;
+use serde::Serialize;
+use tera::{Context, Tera};
+
+/// Variables available to output templates
+///
+/// Fields that don't apply to a message (e.g. `error` on success) are left unset,
+/// so templates can test them with `{% if summary %}`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TemplateVars {
+    pub title: Option<String>,
+    pub url: Option<String>,
+    /// Human-readable duration, e.g. "1h 5m"
+    pub duration: Option<String>,
+    pub stdout: Option<String>,
+    pub summary: Option<String>,
+    pub error: Option<String>,
+    /// Position in a playlist (1-based)
+    pub index: Option<u32>,
+    pub total: Option<u32>,
+}
+
+/// Render a template with the given variables (no HTML escaping)
+pub fn render(template: &str, vars: &TemplateVars) -> Result<String> {
+    let context = Context::from_serialize(vars)?;
+    Ok(Tera::one_off(template, &context, false)?.trim().to_string())
+}
+
+/// Check a template parses, naming it in the error
+pub fn check(name: &str, template: &str) -> Result<()> {
+    Tera::default()
+        .add_raw_template(name, template)
+        .map_err(|e| anyhow::anyhow!("Invalid {name} template: {}", error_chain(&e)))
+}
+
+/// Tera nests the useful parse message in its source chain
+fn error_chain(error: &tera::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(error);
+    while let Some(e) = source {
+        message.push_str(": ");
+        message.push_str(&e.to_string());
+        source = e.source();
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_video_template() {
+        let vars = TemplateVars {
+            title: Some("Talk".to_string()),
+            url: Some("https://youtu.be/x".to_string()),
+            duration: Some("12m".to_string()),
+            index: Some(2),
+            total: Some(5),
+            ..Default::default()
+        };
+        let out = render(
+            "**{{ index }}/{{ total }} {{ title }}** ({{ duration }}) <{{ url }}>\n{% if summary %}{{ summary }}{% endif %}",
+            &vars,
+        )
+        .unwrap();
+        assert_eq!(out, "**2/5 Talk** (12m) <https://youtu.be/x>");
+    }
+
+    #[test]
+    fn test_unset_variables_render_empty() {
+        let vars = TemplateVars::default();
+        assert_eq!(render("[{{ summary }}]", &vars).unwrap(), "[]");
+    }
+
+    #[test]
+    fn test_render_does_not_escape() {
+        let vars = TemplateVars {
+            stdout: Some("a < b && c".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            render("```\n{{ stdout }}\n```", &vars).unwrap(),
+            "```\na < b && c\n```"
+        );
+    }
+
+    #[test]
+    fn test_render_filters() {
+        let vars = TemplateVars {
+            error: Some("exit code 1".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            render("⚠️ {{ error | upper }}", &vars).unwrap(),
+            "⚠️ EXIT CODE 1"
+        );
+    }
+
+    #[test]
+    fn test_check_reports_syntax_errors() {
+        assert!(check("success", "{{ summary }}").is_ok());
+        let err = check("error", "{% if error %}oops").unwrap_err();
+        assert!(err.to_string().starts_with("Invalid error template"));
+    }
+}
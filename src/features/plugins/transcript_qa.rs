@@ -0,0 +1,148 @@
+//! # Transcript Q&A
+//!
+//! Answer questions asked in a transcription thread from the transcript posted there.
+//! Long transcripts are split into excerpts and only those sharing the most
+//! (rarest) words with the question are sent to the model.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with keyword retrieval over transcript excerpts
+
+use std::collections::HashSet;
+
+use crate::core::chunk_text;
+use crate::database::ThreadTranscript;
+
+/// Characters per transcript excerpt
+const EXCERPT_CHARS: usize = 1500;
+
+/// Transcript characters sent with a question (whole transcript when shorter)
+const CONTEXT_CHARS: usize = 12_000;
+
+/// Words too common to say anything about relevance
+const STOPWORDS: &[&str] = &[
+    "the", "and", "for", "are", "but", "not", "you", "all", "any", "can", "had", "her", "was",
+    "one", "our", "out", "has", "him", "his", "how", "its", "who", "did", "does", "what", "when",
+    "where", "which", "why", "with", "that", "this", "they", "them", "then", "there", "from",
+    "have", "about", "into", "just", "like", "said", "say", "says", "video", "talk", "speaker",
+];
+
+/// Lowercased words worth matching on
+fn keywords(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 3)
+        .map(str::to_lowercase)
+        .filter(|w| !STOPWORDS.contains(&w.as_str()))
+        .collect()
+}
+
+/// Excerpts of `transcript` most relevant to `question`, in transcript order
+///
+/// Each excerpt scores the inverse document frequency of the question words it
+/// contains; the best are kept until `budget` characters are used. Returns the
+/// whole transcript when it already fits.
+pub fn select_excerpts(transcript: &str, question: &str, budget: usize) -> Vec<String> {
+    if transcript.len() <= budget {
+        return vec![transcript.to_string()];
+    }
+
+    let excerpts = chunk_text(transcript, EXCERPT_CHARS);
+    let excerpt_words: Vec<HashSet<String>> = excerpts.iter().map(|e| keywords(e)).collect();
+    let total = excerpts.len() as f64;
+
+    let question_words = keywords(question);
+    let weights: Vec<(&String, f64)> = question_words
+        .iter()
+        .filter_map(|word| {
+            let found_in = excerpt_words.iter().filter(|w| w.contains(word)).count();
+            (found_in > 0).then(|| (word, (total / found_in as f64).ln() + 1.0))
+        })
+        .collect();
+
+    let mut ranked: Vec<(usize, f64)> = excerpt_words
+        .iter()
+        .enumerate()
+        .map(|(index, words)| {
+            let score = weights
+                .iter()
+                .filter(|(word, _)| words.contains(*word))
+                .map(|(_, weight)| weight)
+                .sum();
+            (index, score)
+        })
+        .collect();
+    // Best first; ties (including no matches at all) favour the start of the transcript
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let mut used = 0;
+    let mut chosen = Vec::new();
+    for (index, _) in ranked {
+        if used + excerpts[index].len() > budget && !chosen.is_empty() {
+            break;
+        }
+        used += excerpts[index].len();
+        chosen.push(index);
+    }
+    chosen.sort_unstable();
+    chosen.into_iter().map(|i| excerpts[i].clone()).collect()
+}
+
+/// Context block put in front of a question asked in a transcript thread
+pub fn question_context(transcript: &ThreadTranscript, question: &str) -> String {
+    let excerpts = select_excerpts(&transcript.transcript, question, CONTEXT_CHARS);
+    let title = transcript.title.as_deref().unwrap_or("this video");
+    let scope = if excerpts.len() == 1 && excerpts[0].len() == transcript.transcript.len() {
+        "full transcript".to_string()
+    } else {
+        format!("{} excerpts most relevant to the question", excerpts.len())
+    };
+
+    let mut context = format!("Transcript of \"{title}\" posted in this thread ({scope}):\n\n");
+    for excerpt in &excerpts {
+        context.push_str(excerpt.trim());
+        context.push_str("\n\n[...]\n\n");
+    }
+    context.push_str(
+        "Answer the question below from this transcript. If the transcript doesn't cover it, \
+         say so instead of guessing.\n\nQuestion: ",
+    );
+    context
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_transcript_is_sent_whole() {
+        let excerpts = select_excerpts("Short talk about rust.", "what about rust?", 1000);
+        assert_eq!(excerpts, vec!["Short talk about rust.".to_string()]);
+    }
+
+    #[test]
+    fn test_select_excerpts_prefers_matching_sections() {
+        let filler = "Nothing much happens here at all. ".repeat(60);
+        let transcript = format!(
+            "{filler}\n\nThe borrow checker enforces ownership rules at compile time.\n\n{filler}"
+        );
+        let excerpts = select_excerpts(&transcript, "How does the borrow checker work?", 2000);
+
+        assert_eq!(excerpts.len(), 1);
+        assert!(excerpts[0].contains("borrow checker"));
+    }
+
+    #[test]
+    fn test_select_excerpts_keeps_transcript_order() {
+        let paragraph = |word: &str| format!("{word} ").repeat(160);
+        let transcript = ["omega", "filler", "filler", "filler", "alpha"]
+            .map(paragraph)
+            .join("\n\n");
+        let excerpts = select_excerpts(&transcript, "alpha then omega", 2500);
+
+        assert_eq!(excerpts.len(), 2);
+        assert!(excerpts[0].starts_with("omega"));
+        assert!(excerpts[1].starts_with("alpha"));
+    }
+}
@@ -2,21 +2,53 @@
 //!
 //! Parse YouTube URLs and enumerate playlist videos using yt-dlp.
 //!
-//! - **Version**: 1.2.0
+//! - **Version**: 1.7.0
 //! - **Since**: 1.0.0
 //!
 //! ## Changelog
+//! - 1.7.0: Added channel_uploads_url() and enumerate_channel_uploads() for channel subscriptions
+//! - 1.6.0: PlaylistItem carries upload_date; PlaylistFilter for date, title regex and skip-existing filters
+//! - 1.5.0: Added link_timestamps() and timestamp_url() for transcript deep links
+//! - 1.4.0: Added video_cache_key() for the transcript cache
+//! - 1.3.0: yt-dlp and oEmbed calls are guarded by circuit breakers
 //! - 1.2.0: Added video_url() method to get clean video URLs without playlist parameters
 //! - 1.1.0: Added VideoMetadata, fetch_video_metadata, format_description_preview for video descriptions
 //! - 1.0.0: Initial release with URL parsing and yt-dlp playlist enumeration
 
+use crate::features::resilience::{circuit_breakers, Dependency};
 use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDate};
 use log::{debug, info, warn};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::process::Stdio;
 use tokio::process::Command;
 
+/// yt-dlp errors caused by the video itself rather than yt-dlp or YouTube being broken
+const YTDLP_VIDEO_ERRORS: &[&str] = &[
+    "Video unavailable",
+    "Private video",
+    "Unsupported URL",
+    "is not a valid URL",
+    "members-only",
+];
+
+/// Feed a yt-dlp outcome into the circuit breaker
+pub(crate) fn record_ytdlp_outcome<T>(result: &Result<T>) {
+    match result {
+        Err(e) => {
+            let message = e.to_string();
+            if YTDLP_VIDEO_ERRORS.iter().any(|m| message.contains(m)) {
+                circuit_breakers().record_success(Dependency::YtDlp);
+            } else {
+                circuit_breakers().record_failure(Dependency::YtDlp, &message);
+            }
+        }
+        Ok(_) => circuit_breakers().record_success(Dependency::YtDlp),
+    }
+}
+
 /// Type of YouTube URL detected
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum YouTubeUrlType {
@@ -85,6 +117,9 @@ pub struct PlaylistItem {
     pub index: usize,
     /// Video description (if available)
     pub description: Option<String>,
+    /// Upload date (approximate for flat playlist listings)
+    #[serde(default)]
+    pub upload_date: Option<NaiveDate>,
 }
 
 /// Video metadata from yt-dlp
@@ -185,6 +220,23 @@ pub fn parse_youtube_url(url: &str) -> Result<YouTubeUrl> {
     })
 }
 
+/// Transcript cache key for a URL: the bare video ID, so watch, youtu.be and
+/// shorts links (with or without playlist or timestamp parameters) share one entry
+pub fn video_cache_key(url: &str) -> Option<String> {
+    parse_youtube_url(url).ok()?.video_id
+}
+
+/// Uploads tab URL for a YouTube channel link (`/@handle`, `/channel/UC...`, `/c/name`
+/// or `/user/name`, optionally pointing at one of its tabs)
+pub fn channel_uploads_url(url: &str) -> Option<String> {
+    let re = Regex::new(
+        r"^https?://(?:www\.|m\.)?youtube\.com/(@[\w.-]+|channel/UC[\w-]+|c/[\w.-]+|user/[\w.-]+)(?:/[a-z]*)?/?(?:[?#].*)?$",
+    )
+    .ok()?;
+    let path = re.captures(url.trim())?.get(1)?.as_str();
+    Some(format!("https://www.youtube.com/{path}/videos"))
+}
+
 /// Enumerate videos in a playlist using yt-dlp
 ///
 /// Returns playlist info with all video items (up to max_videos if specified)
@@ -192,13 +244,39 @@ pub async fn enumerate_playlist(
     playlist_id: &str,
     max_videos: Option<u32>,
 ) -> Result<PlaylistInfo> {
+    circuit_breakers().check(Dependency::YtDlp)?;
     let playlist_url = format!("https://www.youtube.com/playlist?list={playlist_id}");
+    let result = run_enumerate_playlist(playlist_id, &playlist_url, max_videos).await;
+    record_ytdlp_outcome(&result);
+    result
+}
 
+/// Enumerate a channel's latest uploads (newest first) using yt-dlp
+///
+/// `uploads_url` comes from [`channel_uploads_url`]; the returned info uses it as its ID.
+pub async fn enumerate_channel_uploads(
+    uploads_url: &str,
+    max_videos: Option<u32>,
+) -> Result<PlaylistInfo> {
+    circuit_breakers().check(Dependency::YtDlp)?;
+    let result = run_enumerate_playlist(uploads_url, uploads_url, max_videos).await;
+    record_ytdlp_outcome(&result);
+    result
+}
+
+async fn run_enumerate_playlist(
+    playlist_id: &str,
+    playlist_url: &str,
+    max_videos: Option<u32>,
+) -> Result<PlaylistInfo> {
     info!("Enumerating playlist: {playlist_id}");
 
     // Build yt-dlp command for flat playlist extraction
     let mut cmd = Command::new("yt-dlp");
+    // approximate_date fills in upload dates without fetching every video page
     cmd.arg("--flat-playlist")
+        .arg("--extractor-args")
+        .arg("youtubetab:approximate_date")
         .arg("--dump-json")
         .arg("--no-warnings")
         .arg("--quiet");
@@ -208,7 +286,7 @@ pub async fn enumerate_playlist(
         cmd.arg("--playlist-end").arg(max.to_string());
     }
 
-    cmd.arg(&playlist_url)
+    cmd.arg(playlist_url)
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
 
@@ -272,6 +350,17 @@ pub async fn enumerate_playlist(
             .and_then(|v| v.as_str())
             .map(|s| s.to_string());
 
+        let upload_date = json
+            .get("upload_date")
+            .and_then(|v| v.as_str())
+            .and_then(|d| NaiveDate::parse_from_str(d, "%Y%m%d").ok())
+            .or_else(|| {
+                json.get("timestamp")
+                    .and_then(|v| v.as_i64())
+                    .and_then(|t| DateTime::from_timestamp(t, 0))
+                    .map(|t| t.date_naive())
+            });
+
         let item = PlaylistItem {
             video_id: video_id.clone(),
             title,
@@ -279,6 +368,7 @@ pub async fn enumerate_playlist(
             duration,
             index,
             description,
+            upload_date,
         };
 
         items.push(item);
@@ -303,8 +393,86 @@ pub async fn enumerate_playlist(
     })
 }
 
+/// Filters a user can put on a playlist request, applied before the job is created
+#[derive(Debug, Clone, Default)]
+pub struct PlaylistFilter {
+    /// Only videos uploaded on or after this date
+    pub published_after: Option<NaiveDate>,
+    /// Only videos whose title matches (case-insensitive)
+    pub title_include: Option<Regex>,
+    /// Drop videos whose title matches (case-insensitive)
+    pub title_exclude: Option<Regex>,
+    /// Drop videos already in the transcript archive
+    pub skip_existing: bool,
+}
+
+impl PlaylistFilter {
+    /// Read `published_after`, `title_include`, `title_exclude` and `skip_existing` options
+    pub fn from_params(params: &HashMap<String, String>) -> Result<Self> {
+        let param = |name: &str| params.get(name).map(|v| v.trim()).filter(|v| !v.is_empty());
+        let pattern = |name: &str| {
+            param(name)
+                .map(|p| {
+                    Regex::new(&format!("(?i){p}"))
+                        .map_err(|e| anyhow!("Invalid {name} pattern: {e}"))
+                })
+                .transpose()
+        };
+
+        Ok(Self {
+            published_after: param("published_after")
+                .map(|d| {
+                    NaiveDate::parse_from_str(d, "%Y-%m-%d")
+                        .map_err(|_| anyhow!("published_after must be a date like 2024-01-31"))
+                })
+                .transpose()?,
+            title_include: pattern("title_include")?,
+            title_exclude: pattern("title_exclude")?,
+            skip_existing: param("skip_existing").is_some_and(|v| v == "true"),
+        })
+    }
+
+    /// Whether any filter is set (the whole playlist must be listed to apply them)
+    pub fn is_active(&self) -> bool {
+        self.published_after.is_some()
+            || self.title_include.is_some()
+            || self.title_exclude.is_some()
+            || self.skip_existing
+    }
+
+    /// Videos passing every filter; `archived` holds IDs already transcribed
+    ///
+    /// Videos without a known upload date are dropped when a date filter is set.
+    pub fn apply(&self, items: Vec<PlaylistItem>, archived: &HashSet<String>) -> Vec<PlaylistItem> {
+        items
+            .into_iter()
+            .filter(|item| {
+                self.published_after
+                    .is_none_or(|after| item.upload_date.is_some_and(|d| d >= after))
+            })
+            .filter(|item| {
+                self.title_include
+                    .as_ref()
+                    .is_none_or(|re| re.is_match(&item.title))
+            })
+            .filter(|item| {
+                !self
+                    .title_exclude
+                    .as_ref()
+                    .is_some_and(|re| re.is_match(&item.title))
+            })
+            .filter(|item| !(self.skip_existing && archived.contains(&item.video_id)))
+            .collect()
+    }
+}
+
 /// Fetch YouTube video/playlist title via oEmbed API
 pub async fn fetch_youtube_title(url: &str) -> Option<String> {
+    if let Err(open) = circuit_breakers().check(Dependency::OEmbed) {
+        debug!("Skipping YouTube title lookup: {open}");
+        return None;
+    }
+
     let client = reqwest::Client::new();
     let resp = client
         .get("https://www.youtube.com/oembed")
@@ -314,6 +482,12 @@ pub async fn fetch_youtube_title(url: &str) -> Option<String> {
 
     match resp {
         Ok(r) => {
+            // 4xx means this video has no oEmbed data, not that the endpoint is down
+            if r.status().is_server_error() {
+                circuit_breakers().record_failure(Dependency::OEmbed, r.status().as_str());
+            } else {
+                circuit_breakers().record_success(Dependency::OEmbed);
+            }
             if let Ok(json) = r.json::<serde_json::Value>().await {
                 json.get("title")
                     .and_then(|t| t.as_str())
@@ -324,6 +498,7 @@ pub async fn fetch_youtube_title(url: &str) -> Option<String> {
         }
         Err(e) => {
             warn!("Failed to fetch YouTube title: {e}");
+            circuit_breakers().record_failure(Dependency::OEmbed, &e.to_string());
             None
         }
     }
@@ -334,6 +509,13 @@ pub async fn fetch_youtube_title(url: &str) -> Option<String> {
 /// Uses --no-download to quickly fetch metadata without downloading the video.
 /// Times out after 30 seconds.
 pub async fn fetch_video_metadata(url: &str) -> Result<VideoMetadata> {
+    circuit_breakers().check(Dependency::YtDlp)?;
+    let result = run_fetch_video_metadata(url).await;
+    record_ytdlp_outcome(&result);
+    result
+}
+
+async fn run_fetch_video_metadata(url: &str) -> Result<VideoMetadata> {
     use tokio::time::{timeout, Duration};
 
     info!("Fetching video metadata for: {url}");
@@ -438,6 +620,53 @@ pub fn format_duration(duration: std::time::Duration) -> String {
     }
 }
 
+/// Deep link to a moment in a YouTube video
+pub fn timestamp_url(video_id: &str, secs: u64) -> String {
+    format!("https://www.youtube.com/watch?v={video_id}&t={secs}s")
+}
+
+/// Format seconds as a video timestamp (`1:35`, `1:02:03`)
+pub fn format_timestamp(secs: u64) -> String {
+    let (hours, minutes, seconds) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+/// Turn timestamps at the start of transcript lines into YouTube deep links
+///
+/// Handles `1:35`, `[01:02:03.500]` and Whisper's `[00:01:35.000 --> 00:01:40.000]`
+/// prefixes. `offset_secs` shifts chunk-relative times to their position in the
+/// full video; the link text shows the shifted time.
+pub fn link_timestamps(text: &str, video_id: &str, offset_secs: u64) -> String {
+    let time = r"(?:\d{1,2}:)?\d{1,2}:\d{2}(?:[.,]\d{1,3})?";
+    let pattern = format!(r"(?m)^([ \t]*)\[?({time})(?:\s*-->\s*{time})?\]?");
+    let Ok(re) = Regex::new(&pattern) else {
+        return text.to_string();
+    };
+
+    re.replace_all(text, |caps: &regex::Captures| {
+        let secs = parse_timestamp(&caps[2]).unwrap_or(0) + offset_secs;
+        format!(
+            "{}[{}]({})",
+            &caps[1],
+            format_timestamp(secs),
+            timestamp_url(video_id, secs)
+        )
+    })
+    .into_owned()
+}
+
+/// Whole seconds in an `[H:]MM:SS[.mmm]` timestamp
+fn parse_timestamp(raw: &str) -> Option<u64> {
+    let whole = raw.split(['.', ',']).next()?;
+    whole.split(':').try_fold(0u64, |total, part| {
+        Some(total * 60 + part.parse::<u64>().ok()?)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -516,6 +745,64 @@ mod tests {
         assert_eq!(parsed.url_type, YouTubeUrlType::SingleVideo);
     }
 
+    #[test]
+    fn test_video_cache_key_normalizes_url_forms() {
+        for url in [
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+            "https://youtu.be/dQw4w9WgXcQ?t=42",
+            "https://www.youtube.com/shorts/dQw4w9WgXcQ",
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ&list=PLrAXtmErZgOeiKm4sgNOknGvNjby9efdf",
+        ] {
+            assert_eq!(
+                video_cache_key(url).as_deref(),
+                Some("dQw4w9WgXcQ"),
+                "{url}"
+            );
+        }
+        assert_eq!(
+            video_cache_key("https://www.youtube.com/playlist?list=PLabc"),
+            None
+        );
+        assert_eq!(video_cache_key("https://example.com/video"), None);
+    }
+
+    #[test]
+    fn test_channel_uploads_url() {
+        for (url, expected) in [
+            (
+                "https://www.youtube.com/@veritasium",
+                "https://www.youtube.com/@veritasium/videos",
+            ),
+            (
+                "https://youtube.com/@veritasium/streams?si=abc",
+                "https://www.youtube.com/@veritasium/videos",
+            ),
+            (
+                "https://www.youtube.com/channel/UCHnyfMqiRRG1u-2MsSQLbXA/",
+                "https://www.youtube.com/channel/UCHnyfMqiRRG1u-2MsSQLbXA/videos",
+            ),
+            (
+                "https://m.youtube.com/c/veritasium/videos",
+                "https://www.youtube.com/c/veritasium/videos",
+            ),
+            (
+                "https://www.youtube.com/user/1veritasium",
+                "https://www.youtube.com/user/1veritasium/videos",
+            ),
+        ] {
+            assert_eq!(channel_uploads_url(url).as_deref(), Some(expected), "{url}");
+        }
+        assert_eq!(
+            channel_uploads_url("https://www.youtube.com/watch?v=dQw4w9WgXcQ"),
+            None
+        );
+        assert_eq!(
+            channel_uploads_url("https://www.youtube.com/playlist?list=PLabc"),
+            None
+        );
+        assert_eq!(channel_uploads_url("https://example.com/@someone"), None);
+    }
+
     #[test]
     fn test_invalid_url() {
         let url = "https://example.com/video";
@@ -532,6 +819,7 @@ mod tests {
                 duration: Some(600), // 10 minutes
                 index: 0,
                 description: Some("Test description".to_string()),
+                upload_date: None,
             },
             PlaylistItem {
                 video_id: "def".to_string(),
@@ -540,6 +828,7 @@ mod tests {
                 duration: Some(300), // 5 minutes
                 index: 1,
                 description: None,
+                upload_date: None,
             },
         ];
 
@@ -560,6 +849,86 @@ mod tests {
         assert_eq!(format_description_preview(long, 3), expected);
     }
 
+    #[test]
+    fn test_link_timestamps() {
+        let text =
+            "[00:01:35.000 --> 00:01:40.000]  Hello there\n2:05 General Kenobi\nAt 3:30 we left";
+        assert_eq!(
+            link_timestamps(text, "dQw4w9WgXcQ", 0),
+            "[1:35](https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=95s)  Hello there\n\
+             [2:05](https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=125s) General Kenobi\n\
+             At 3:30 we left"
+        );
+    }
+
+    #[test]
+    fn test_link_timestamps_applies_chunk_offset() {
+        assert_eq!(
+            link_timestamps("[00:10] Next part", "abc", 3600),
+            "[1:00:10](https://www.youtube.com/watch?v=abc&t=3610s) Next part"
+        );
+    }
+
+    fn playlist_item(video_id: &str, title: &str, upload_date: Option<&str>) -> PlaylistItem {
+        PlaylistItem {
+            video_id: video_id.to_string(),
+            title: title.to_string(),
+            url: format!("https://www.youtube.com/watch?v={video_id}"),
+            duration: None,
+            index: 0,
+            description: None,
+            upload_date: upload_date.map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap()),
+        }
+    }
+
+    #[test]
+    fn test_playlist_filter_from_params() {
+        let params = HashMap::from([
+            ("published_after".to_string(), "2024-03-01".to_string()),
+            ("title_exclude".to_string(), "shorts?".to_string()),
+            ("skip_existing".to_string(), "true".to_string()),
+        ]);
+        let filter = PlaylistFilter::from_params(&params).unwrap();
+        assert!(filter.is_active());
+        assert!(filter.title_include.is_none());
+        assert!(filter.skip_existing);
+
+        assert!(!PlaylistFilter::from_params(&HashMap::new())
+            .unwrap()
+            .is_active());
+        let bad_date = HashMap::from([("published_after".to_string(), "March".to_string())]);
+        assert!(PlaylistFilter::from_params(&bad_date).is_err());
+        let bad_regex = HashMap::from([("title_include".to_string(), "(".to_string())]);
+        assert!(PlaylistFilter::from_params(&bad_regex).is_err());
+    }
+
+    #[test]
+    fn test_playlist_filter_apply() {
+        let params = HashMap::from([
+            ("published_after".to_string(), "2024-03-01".to_string()),
+            ("title_include".to_string(), "episode".to_string()),
+            ("title_exclude".to_string(), "trailer".to_string()),
+            ("skip_existing".to_string(), "true".to_string()),
+        ]);
+        let filter = PlaylistFilter::from_params(&params).unwrap();
+        let items = vec![
+            playlist_item("old", "Episode 1", Some("2024-01-10")),
+            playlist_item("new", "Episode 2", Some("2024-03-01")),
+            playlist_item("trailer", "Episode 3 trailer", Some("2024-04-01")),
+            playlist_item("other", "Bonus clip", Some("2024-04-01")),
+            playlist_item("undated", "Episode 4", None),
+            playlist_item("done", "EPISODE 5", Some("2024-05-01")),
+        ];
+        let archived = HashSet::from(["done".to_string()]);
+
+        let kept: Vec<_> = filter
+            .apply(items, &archived)
+            .into_iter()
+            .map(|item| item.video_id)
+            .collect();
+        assert_eq!(kept, vec!["new".to_string()]);
+    }
+
     #[test]
     fn test_format_duration() {
         assert_eq!(format_duration(std::time::Duration::from_secs(30)), "< 1m");
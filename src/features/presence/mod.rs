@@ -0,0 +1,299 @@
+//! # Feature: Activity Status Rotation
+//!
+//! Rotates the bot's Discord activity through a list of templates, filling in
+//! live stats: `{guilds}` (servers joined), `{jobs}` (running plugin jobs such
+//! as transcriptions) and `{version}`. A template whose stat is zero is skipped
+//! for that turn, so "Transcribing {jobs} videos" only shows while there is
+//! work. Templates start with the activity kind: `Playing`, `Listening to`,
+//! `Watching` or `Competing in`.
+//!
+//! Configured through bot settings (`presence_messages`, `presence_interval`)
+//! with `/admin presence`.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: false
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with guild/job/version placeholders
+
+use anyhow::Result;
+use log::{debug, info, warn};
+use serenity::model::gateway::Activity;
+use serenity::prelude::Context;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::database::Database;
+use crate::features::analytics::runtime::{spawn_tracked, TaskKind};
+use crate::features::get_bot_version;
+use crate::features::plugins::JobManager;
+
+/// Bot setting holding `|`-separated templates, or `disabled`
+pub const MESSAGES_SETTING: &str = "presence_messages";
+
+/// Bot setting holding the rotation interval in minutes
+pub const INTERVAL_SETTING: &str = "presence_interval";
+
+/// Templates used until an owner configures their own
+pub const DEFAULT_MESSAGES: &[&str] = &[
+    "Watching {guilds} servers",
+    "Playing /help for commands",
+    "Transcribing {jobs} videos",
+];
+
+pub const DEFAULT_INTERVAL_MINUTES: u64 = 5;
+pub const MAX_INTERVAL_MINUTES: u64 = 60;
+
+/// Discord activity kinds a template can start with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivityKind {
+    Playing,
+    Listening,
+    Watching,
+    Competing,
+}
+
+/// One rendered status line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusLine {
+    pub kind: ActivityKind,
+    pub text: String,
+}
+
+impl StatusLine {
+    /// Split the activity kind off a template line; unprefixed lines are `Playing`
+    pub fn parse(line: &str) -> Self {
+        let line = line.trim();
+        for (prefix, kind) in [
+            ("Playing ", ActivityKind::Playing),
+            ("Listening to ", ActivityKind::Listening),
+            ("Watching ", ActivityKind::Watching),
+            ("Competing in ", ActivityKind::Competing),
+        ] {
+            if line.len() > prefix.len()
+                && line.is_char_boundary(prefix.len())
+                && line[..prefix.len()].eq_ignore_ascii_case(prefix)
+            {
+                return Self {
+                    kind,
+                    text: line[prefix.len()..].trim().to_string(),
+                };
+            }
+        }
+        Self {
+            kind: ActivityKind::Playing,
+            text: line.to_string(),
+        }
+    }
+
+    fn to_activity(&self) -> Activity {
+        match self.kind {
+            ActivityKind::Playing => Activity::playing(&self.text),
+            ActivityKind::Listening => Activity::listening(&self.text),
+            ActivityKind::Watching => Activity::watching(&self.text),
+            ActivityKind::Competing => Activity::competing(&self.text),
+        }
+    }
+}
+
+/// Live values substituted into templates
+#[derive(Debug, Clone, Default)]
+pub struct PresenceStats {
+    pub guilds: usize,
+    pub jobs: usize,
+}
+
+/// Split a `presence_messages` value into templates (`None` when disabled)
+pub fn parse_messages(value: &str) -> Option<Vec<String>> {
+    if value.trim() == "disabled" {
+        return None;
+    }
+    let templates: Vec<String> = value
+        .split('|')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(String::from)
+        .collect();
+    (!templates.is_empty()).then_some(templates)
+}
+
+/// Fill in a template, or `None` when one of its stats is zero
+pub fn render_template(template: &str, stats: &PresenceStats) -> Option<StatusLine> {
+    let mut text = template.to_string();
+    for (placeholder, value) in [("{guilds}", stats.guilds), ("{jobs}", stats.jobs)] {
+        if text.contains(placeholder) {
+            if value == 0 {
+                return None;
+            }
+            text = text.replace(placeholder, &value.to_string());
+        }
+    }
+    text = text.replace("{version}", &format!("v{}", get_bot_version()));
+    Some(StatusLine::parse(&text))
+}
+
+/// Configured templates, the defaults when unset, or `None` when disabled
+pub async fn configured_messages(database: &Database) -> Result<Option<Vec<String>>> {
+    Ok(match database.get_bot_setting(MESSAGES_SETTING).await? {
+        Some(value) => parse_messages(&value),
+        None => Some(DEFAULT_MESSAGES.iter().map(|m| m.to_string()).collect()),
+    })
+}
+
+/// Configured rotation interval in minutes
+pub async fn configured_interval(database: &Database) -> Result<u64> {
+    Ok(database
+        .get_bot_setting(INTERVAL_SETTING)
+        .await?
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_INTERVAL_MINUTES)
+        .clamp(1, MAX_INTERVAL_MINUTES))
+}
+
+/// Periodically rotates the bot's activity
+pub struct PresenceRotator {
+    database: Database,
+    job_manager: Option<Arc<JobManager>>,
+    started: AtomicBool,
+}
+
+impl PresenceRotator {
+    pub fn new(database: Database, job_manager: Option<Arc<JobManager>>) -> Self {
+        Self {
+            database,
+            job_manager,
+            started: AtomicBool::new(false),
+        }
+    }
+
+    /// Start rotating on the first `ready`; reconnects reuse the running loop
+    pub fn start(self: &Arc<Self>, ctx: Context) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let rotator = Arc::clone(self);
+        spawn_tracked(TaskKind::Schedulers, async move {
+            rotator.run(ctx).await;
+        });
+    }
+
+    async fn run(&self, ctx: Context) {
+        info!("🟢 Activity status rotation started");
+        let mut turn = 0usize;
+        let mut showing = false;
+
+        loop {
+            let (templates, interval) = self.load_config().await;
+            let stats = PresenceStats {
+                guilds: ctx.cache.guild_count(),
+                jobs: self
+                    .job_manager
+                    .as_ref()
+                    .map(|jm| jm.active_job_count())
+                    .unwrap_or(0),
+            };
+            let lines: Vec<StatusLine> = templates
+                .iter()
+                .flatten()
+                .filter_map(|t| render_template(t, &stats))
+                .collect();
+
+            if lines.is_empty() {
+                if showing {
+                    ctx.reset_presence().await;
+                    showing = false;
+                }
+            } else {
+                let line = &lines[turn % lines.len()];
+                debug!("🟢 Setting activity: {:?} {}", line.kind, line.text);
+                ctx.set_activity(line.to_activity()).await;
+                showing = true;
+                turn = turn.wrapping_add(1);
+            }
+
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    async fn load_config(&self) -> (Option<Vec<String>>, Duration) {
+        let templates = configured_messages(&self.database)
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Failed to read {MESSAGES_SETTING}: {e}");
+                None
+            });
+        let minutes = configured_interval(&self.database)
+            .await
+            .unwrap_or(DEFAULT_INTERVAL_MINUTES);
+        (templates, Duration::from_secs(minutes * 60))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_line_parse() {
+        assert_eq!(
+            StatusLine::parse("Watching 12 servers"),
+            StatusLine {
+                kind: ActivityKind::Watching,
+                text: "12 servers".to_string()
+            }
+        );
+        assert_eq!(
+            StatusLine::parse("listening to podcasts").kind,
+            ActivityKind::Listening
+        );
+        assert_eq!(
+            StatusLine::parse("Competing in trivia").kind,
+            ActivityKind::Competing
+        );
+        let plain = StatusLine::parse("with fire");
+        assert_eq!(plain.kind, ActivityKind::Playing);
+        assert_eq!(plain.text, "with fire");
+    }
+
+    #[test]
+    fn test_parse_messages() {
+        assert_eq!(parse_messages("disabled"), None);
+        assert_eq!(parse_messages(" | "), None);
+        assert_eq!(
+            parse_messages("Watching {guilds} servers | Playing chess"),
+            Some(vec![
+                "Watching {guilds} servers".to_string(),
+                "Playing chess".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_render_template_skips_zero_stats() {
+        let stats = PresenceStats {
+            guilds: 12,
+            jobs: 0,
+        };
+        assert_eq!(
+            render_template("Watching {guilds} servers", &stats)
+                .unwrap()
+                .text,
+            "12 servers"
+        );
+        assert!(render_template("Transcribing {jobs} videos", &stats).is_none());
+
+        let busy = PresenceStats {
+            guilds: 12,
+            jobs: 3,
+        };
+        let line = render_template("Transcribing {jobs} videos", &busy).unwrap();
+        assert_eq!(line.kind, ActivityKind::Playing);
+        assert_eq!(line.text, "Transcribing 3 videos");
+        assert!(render_template("Playing {version}", &stats)
+            .unwrap()
+            .text
+            .starts_with('v'));
+    }
+}
@@ -0,0 +1,257 @@
+//! # Feature: Prompt Library
+//!
+//! A per-guild library of vetted prompts so a team can standardize how it
+//! asks. Members with Manage Server save prompts under a name and category
+//! with `/prompts save`; anyone runs them with `/prompts use`, filling the
+//! `${variable}` placeholders the same way `/alias run` does, through either
+//! the `/ask` or the `/imagine` pipeline. Libraries move between servers as
+//! JSON files with `/prompts export` and `/prompts import`.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with categories, variables, ask/imagine targets and JSON export/import
+
+use serde::{Deserialize, Serialize};
+
+use crate::database::GuildPrompt;
+use crate::features::aliases::{placeholders, validate_name, validate_template};
+
+/// Feature id for toggles
+pub const PROMPTS_FEATURE: &str = "prompt_library";
+
+/// Category used when a prompt is saved without one
+pub const DEFAULT_CATEGORY: &str = "general";
+
+/// Prompts a guild's library may hold
+pub const MAX_PROMPTS_PER_GUILD: i64 = 100;
+
+/// Longest description shown in `/prompts list`
+pub const MAX_DESCRIPTION_CHARS: usize = 200;
+
+/// Largest import file accepted
+pub const MAX_IMPORT_BYTES: u64 = 256 * 1024;
+
+/// `format` field identifying an export file
+pub const EXPORT_FORMAT: &str = "discord-bot-prompts";
+
+/// Current export file version
+pub const EXPORT_VERSION: u32 = 1;
+
+/// Which pipeline a library prompt runs through
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptTarget {
+    Ask,
+    Imagine,
+}
+
+impl PromptTarget {
+    /// Parse the `/prompts use` choice, defaulting to /ask
+    pub fn from_setting(value: Option<&str>) -> Self {
+        match value {
+            Some("imagine") => Self::Imagine,
+            _ => Self::Ask,
+        }
+    }
+}
+
+/// A library export file
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PromptExport {
+    pub format: String,
+    pub version: u32,
+    pub prompts: Vec<ExportedPrompt>,
+}
+
+/// One prompt in an export file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportedPrompt {
+    pub name: String,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub template: String,
+}
+
+/// Prompts read from an import file, plus the entries that were rejected
+#[derive(Debug, Default)]
+pub struct ParsedImport {
+    pub prompts: Vec<ExportedPrompt>,
+    pub rejected: Vec<String>,
+}
+
+/// Lowercase and check a category; same rules as prompt names
+pub fn validate_category(input: Option<&str>) -> Result<String, String> {
+    match input.map(str::trim).filter(|c| !c.is_empty()) {
+        Some(category) => validate_name(category)
+            .map_err(|_| "Categories can only use letters, digits, `-` and `_`.".to_string()),
+        None => Ok(DEFAULT_CATEGORY.to_string()),
+    }
+}
+
+/// Trim a description, dropping empty ones
+pub fn validate_description(input: Option<&str>) -> Result<Option<String>, String> {
+    let Some(description) = input.map(str::trim).filter(|d| !d.is_empty()) else {
+        return Ok(None);
+    };
+    if description.chars().count() > MAX_DESCRIPTION_CHARS {
+        return Err(format!(
+            "Descriptions must be at most {MAX_DESCRIPTION_CHARS} characters."
+        ));
+    }
+    Ok(Some(description.to_string()))
+}
+
+/// Serialize prompts into an export file
+pub fn export_json(prompts: &[GuildPrompt]) -> String {
+    let export = PromptExport {
+        format: EXPORT_FORMAT.to_string(),
+        version: EXPORT_VERSION,
+        prompts: prompts
+            .iter()
+            .map(|p| ExportedPrompt {
+                name: p.name.clone(),
+                category: Some(p.category.clone()),
+                description: p.description.clone(),
+                template: p.template.clone(),
+            })
+            .collect(),
+    };
+    serde_json::to_string_pretty(&export).unwrap_or_default()
+}
+
+/// Read an export file, normalizing each prompt and rejecting invalid ones
+pub fn parse_import(bytes: &[u8]) -> Result<ParsedImport, String> {
+    let export: PromptExport = serde_json::from_slice(bytes)
+        .map_err(|e| format!("That file isn't a prompt library export: {e}"))?;
+    if export.format != EXPORT_FORMAT {
+        return Err(format!(
+            "That file isn't a prompt library export (format `{}`).",
+            export.format
+        ));
+    }
+    if export.version > EXPORT_VERSION {
+        return Err(format!(
+            "That export is version {}; this bot reads up to version {EXPORT_VERSION}.",
+            export.version
+        ));
+    }
+
+    let mut parsed = ParsedImport::default();
+    for prompt in export.prompts {
+        let normalized = validate_name(&prompt.name).and_then(|name| {
+            Ok(ExportedPrompt {
+                name,
+                category: Some(validate_category(prompt.category.as_deref())?),
+                description: validate_description(prompt.description.as_deref())?,
+                template: validate_template(&prompt.template)?,
+            })
+        });
+        match normalized {
+            Ok(prompt) if !parsed.prompts.iter().any(|p| p.name == prompt.name) => {
+                parsed.prompts.push(prompt)
+            }
+            Ok(prompt) => parsed
+                .rejected
+                .push(format!("`{}`: listed twice", prompt.name)),
+            Err(e) => parsed.rejected.push(format!("`{}`: {e}", prompt.name)),
+        }
+    }
+    Ok(parsed)
+}
+
+/// One line of `/prompts list`
+pub fn list_line(prompt: &GuildPrompt) -> String {
+    let variables = placeholders(&prompt.template);
+    let mut line = format!("`{}`", prompt.name);
+    if !variables.is_empty() {
+        line.push_str(&format!(" ({})", variables.join(", ")));
+    }
+    if let Some(description) = &prompt.description {
+        line.push_str(&format!(" — {description}"));
+    }
+    if prompt.uses > 0 {
+        line.push_str(&format!(" · used {}×", prompt.uses));
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prompt(name: &str, category: &str) -> GuildPrompt {
+        GuildPrompt {
+            guild_id: "1".to_string(),
+            name: name.to_string(),
+            category: category.to_string(),
+            description: Some("Release notes".to_string()),
+            template: "Write release notes for ${version} covering ${changes}".to_string(),
+            created_by: "42".to_string(),
+            uses: 3,
+        }
+    }
+
+    #[test]
+    fn test_validate_category() {
+        assert_eq!(validate_category(None), Ok("general".to_string()));
+        assert_eq!(validate_category(Some("  ")), Ok("general".to_string()));
+        assert_eq!(validate_category(Some("Art")), Ok("art".to_string()));
+        assert!(validate_category(Some("two words")).is_err());
+    }
+
+    #[test]
+    fn test_export_round_trip() {
+        let json = export_json(&[prompt("notes", "writing"), prompt("logo", "art")]);
+        let parsed = parse_import(json.as_bytes()).unwrap();
+        assert!(parsed.rejected.is_empty());
+        assert_eq!(parsed.prompts.len(), 2);
+        assert_eq!(parsed.prompts[0].name, "notes");
+        assert_eq!(parsed.prompts[0].category.as_deref(), Some("writing"));
+        assert_eq!(
+            parsed.prompts[1].template,
+            "Write release notes for ${version} covering ${changes}"
+        );
+    }
+
+    #[test]
+    fn test_parse_import_rejects_bad_entries() {
+        let json = r#"{
+            "format": "discord-bot-prompts",
+            "version": 1,
+            "prompts": [
+                {"name": "Good", "template": "Summarize ${text}"},
+                {"name": "bad name", "template": "x"},
+                {"name": "empty", "template": "  "},
+                {"name": "good", "template": "Again"}
+            ]
+        }"#;
+        let parsed = parse_import(json.as_bytes()).unwrap();
+        assert_eq!(parsed.prompts.len(), 1);
+        assert_eq!(parsed.prompts[0].name, "good");
+        assert_eq!(parsed.prompts[0].category.as_deref(), Some("general"));
+        assert_eq!(parsed.rejected.len(), 3);
+        assert!(parsed.rejected[2].contains("listed twice"));
+    }
+
+    #[test]
+    fn test_parse_import_checks_format() {
+        assert!(parse_import(b"not json").is_err());
+        assert!(parse_import(br#"{"format": "other", "version": 1, "prompts": []}"#).is_err());
+        assert!(
+            parse_import(br#"{"format": "discord-bot-prompts", "version": 9, "prompts": []}"#)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_list_line() {
+        assert_eq!(
+            list_line(&prompt("notes", "writing")),
+            "`notes` (version, changes) — Release notes · used 3×"
+        );
+    }
+}
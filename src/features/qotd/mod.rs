@@ -0,0 +1,162 @@
+//! # Feature: Question of the Day
+//!
+//! A daily question posted in the channel chosen with `/qotd setup`, at a
+//! local hour. Questions queued with `/qotd add` go out first, oldest first;
+//! when the queue is empty the channel's persona writes one. Each question can
+//! get its own thread for answers. When the next question is due, the previous
+//! one's answers are scored by reactions and the best one is archived and
+//! posted as a short digest.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with a queue, persona-written fallback, answer threads and digests
+
+pub mod scheduler;
+pub mod writer;
+
+pub use scheduler::QotdScheduler;
+pub use writer::QuestionWriter;
+
+use crate::database::QotdAnswer;
+
+/// Feature id for toggles
+pub const QOTD_FEATURE: &str = "qotd";
+
+/// Longest question that can be queued
+pub const MAX_QUESTION_CHARS: usize = 300;
+
+/// Questions a guild may have waiting
+pub const MAX_QUEUE_LEN: usize = 50;
+
+/// Local hour questions go out at when none was given
+pub const DEFAULT_HOUR: i64 = 9;
+
+/// Discord's limit on thread names
+const MAX_THREAD_NAME_CHARS: usize = 100;
+
+/// Longest answer quoted in a digest
+const MAX_DIGEST_ANSWER_CHARS: usize = 500;
+
+/// Trim a question for the queue, rejecting empty or oversized ones
+pub fn validate_question(input: &str) -> Result<String, &'static str> {
+    let question = input.split_whitespace().collect::<Vec<_>>().join(" ");
+    if question.is_empty() {
+        return Err("The question can't be empty.");
+    }
+    if question.chars().count() > MAX_QUESTION_CHARS {
+        return Err("Questions must be at most 300 characters.");
+    }
+    Ok(question)
+}
+
+/// The message a question is posted as
+pub fn format_question(question: &str) -> String {
+    format!("❓ **Question of the day**\n\n{question}")
+}
+
+/// Name of the answer thread for a question
+pub fn thread_name(question: &str) -> String {
+    let name = format!("QOTD: {question}");
+    if name.chars().count() <= MAX_THREAD_NAME_CHARS {
+        return name;
+    }
+    let mut name: String = name.chars().take(MAX_THREAD_NAME_CHARS - 1).collect();
+    name.push('…');
+    name
+}
+
+/// The answer with the most reactions; the earliest wins a tie
+///
+/// Answers nobody reacted to don't count, so a quiet day has no best answer.
+pub fn pick_best_answer(answers: Vec<QotdAnswer>) -> Option<QotdAnswer> {
+    answers.into_iter().filter(|a| a.reactions > 0).fold(
+        None,
+        |best: Option<QotdAnswer>, answer| match &best {
+            Some(b) if b.reactions >= answer.reactions => best,
+            _ => Some(answer),
+        },
+    )
+}
+
+/// The digest posted for a question's best answer
+pub fn format_digest(question: &str, answer: &QotdAnswer) -> String {
+    let mut content: String = answer
+        .content
+        .trim()
+        .chars()
+        .take(MAX_DIGEST_ANSWER_CHARS)
+        .collect();
+    if answer.content.trim().chars().count() > MAX_DIGEST_ANSWER_CHARS {
+        content.push('…');
+    }
+    let quoted = content
+        .lines()
+        .map(|line| format!("> {line}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let reactions = match answer.reactions {
+        1 => "1 reaction".to_string(),
+        n => format!("{n} reactions"),
+    };
+    format!(
+        "📚 **QOTD digest:** {question}\n🏆 Top answer by <@{}> with {reactions}:\n{quoted}",
+        answer.user_id
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answer(user_id: &str, reactions: i64) -> QotdAnswer {
+        QotdAnswer {
+            user_id: user_id.to_string(),
+            content: format!("answer from {user_id}"),
+            reactions,
+        }
+    }
+
+    #[test]
+    fn test_validate_question() {
+        assert_eq!(
+            validate_question("  What's  your\nfavourite book? "),
+            Ok("What's your favourite book?".to_string())
+        );
+        assert!(validate_question(" \n ").is_err());
+        assert!(validate_question(&"x".repeat(301)).is_err());
+    }
+
+    #[test]
+    fn test_thread_name() {
+        assert_eq!(thread_name("Tabs or spaces?"), "QOTD: Tabs or spaces?");
+        let long = thread_name(&"x".repeat(200));
+        assert_eq!(long.chars().count(), MAX_THREAD_NAME_CHARS);
+        assert!(long.ends_with('…'));
+    }
+
+    #[test]
+    fn test_pick_best_answer() {
+        let best = pick_best_answer(vec![answer("a", 1), answer("b", 3), answer("c", 3)]);
+        assert_eq!(best.unwrap().user_id, "b");
+        assert_eq!(pick_best_answer(vec![answer("a", 0)]), None);
+        assert_eq!(pick_best_answer(Vec::new()), None);
+    }
+
+    #[test]
+    fn test_format_digest() {
+        let mut best = answer("42", 1);
+        best.content = "Spaces\nalways".to_string();
+        assert_eq!(
+            format_digest("Tabs or spaces?", &best),
+            "📚 **QOTD digest:** Tabs or spaces?\n🏆 Top answer by <@42> with 1 reaction:\n> Spaces\n> always"
+        );
+        best.content = "y".repeat(600);
+        best.reactions = 4;
+        let digest = format_digest("Q?", &best);
+        assert!(digest.contains("with 4 reactions"));
+        assert!(digest.ends_with('…'));
+    }
+}
@@ -0,0 +1,249 @@
+//! Background task posting questions of the day and their digests
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use chrono::Utc;
+use log::{error, info, warn};
+use serenity::http::Http;
+use serenity::model::channel::{ChannelType, Message};
+use serenity::model::id::{ChannelId, MessageId};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::interval;
+
+use super::writer::QOTD_USER_ID;
+use super::{
+    format_digest, format_question, pick_best_answer, thread_name, QuestionWriter, QOTD_FEATURE,
+};
+use crate::database::{Database, QotdAnswer, QotdConfig, QotdPost};
+use crate::features::channel_topics::next_run_after;
+use crate::features::guardrails::ChannelGrounding;
+use crate::features::personas::themes::{apply_theme, ACTIVE_THEME_SETTING};
+use crate::features::personas::PersonaManager;
+
+/// Recent questions shown to the writer so it doesn't repeat itself
+const RECENT_QUESTIONS: i64 = 10;
+
+/// Answers read per question when picking the best one
+const MAX_ANSWERS: u64 = 100;
+
+pub struct QotdScheduler {
+    database: Database,
+    writer: QuestionWriter,
+    persona_manager: PersonaManager,
+}
+
+impl QotdScheduler {
+    pub fn new(database: Database, writer: QuestionWriter) -> Self {
+        Self {
+            database,
+            writer,
+            persona_manager: PersonaManager::new(),
+        }
+    }
+
+    /// Start the question of the day scheduler loop
+    /// This should be spawned as a tokio task
+    pub async fn run(&self, http: Arc<Http>) {
+        let mut check_interval = interval(Duration::from_secs(5 * 60));
+
+        info!("❓ Question of the day scheduler started");
+
+        loop {
+            check_interval.tick().await;
+
+            if let Err(e) = self.process_due_questions(&http).await {
+                error!("❌ Error processing questions of the day: {e}");
+            }
+        }
+    }
+
+    async fn process_due_questions(&self, http: &Arc<Http>) -> Result<()> {
+        let now = Utc::now().timestamp();
+        for config in self.database.get_due_qotd_configs(now).await? {
+            let next_run_at = next_run_after(config.next_run_at, 24, now);
+            if self
+                .database
+                .is_feature_enabled(QOTD_FEATURE, None, Some(&config.guild_id))
+                .await?
+            {
+                if let Err(e) = self.digest_previous(http, &config).await {
+                    warn!(
+                        "⚠️ Failed to digest questions of the day in guild {}: {e}",
+                        config.guild_id
+                    );
+                }
+                match self.post_question(http, &config, now).await {
+                    Ok(_) => info!("❓ Posted question of the day in guild {}", config.guild_id),
+                    Err(e) => warn!(
+                        "⚠️ Failed to post question of the day in guild {}: {e}",
+                        config.guild_id
+                    ),
+                }
+            }
+            // Moved on either way so a broken channel doesn't retry every 5 minutes,
+            // and days spent disabled are skipped rather than replayed
+            self.database
+                .advance_qotd_config(&config.guild_id, next_run_at)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Archive the best answer of each question that hasn't been digested yet
+    async fn digest_previous(&self, http: &Arc<Http>, config: &QotdConfig) -> Result<()> {
+        let digest_channel = config
+            .digest_channel_id
+            .as_deref()
+            .unwrap_or(&config.channel_id)
+            .parse::<u64>()
+            .map(ChannelId)?;
+        for post in self
+            .database
+            .get_undigested_qotd_posts(&config.guild_id)
+            .await?
+        {
+            let best = match self.answers(http, &post).await {
+                Ok(answers) => pick_best_answer(answers),
+                Err(e) => {
+                    warn!("⚠️ Failed to read answers to {}: {e}", post.message_id);
+                    None
+                }
+            };
+            if let Some(best) = &best {
+                digest_channel
+                    .send_message(http, |m| {
+                        m.content(format_digest(&post.question, best))
+                            .allowed_mentions(|a| a.empty_parse())
+                    })
+                    .await?;
+            }
+            self.database
+                .finish_qotd_post(&post.message_id, best.as_ref())
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Members' answers to a question: its thread's messages, or replies to it
+    async fn answers(&self, http: &Arc<Http>, post: &QotdPost) -> Result<Vec<QotdAnswer>> {
+        let question_id = MessageId(post.message_id.parse::<u64>()?);
+        let messages = match &post.thread_id {
+            Some(thread_id) => {
+                ChannelId(thread_id.parse::<u64>()?)
+                    .messages(http, |r| r.limit(MAX_ANSWERS))
+                    .await?
+            }
+            None => ChannelId(post.channel_id.parse::<u64>()?)
+                .messages(http, |r| r.after(question_id).limit(MAX_ANSWERS))
+                .await?
+                .into_iter()
+                .filter(|m| {
+                    m.message_reference
+                        .as_ref()
+                        .and_then(|r| r.message_id)
+                        .is_some_and(|id| id == question_id)
+                })
+                .collect(),
+        };
+        // Messages come newest first; the earliest answer should win a tie
+        Ok(messages.into_iter().rev().filter_map(answer).collect())
+    }
+
+    /// Post the next queued question, or a persona-written one when the queue is empty
+    async fn post_question(&self, http: &Arc<Http>, config: &QotdConfig, now: i64) -> Result<()> {
+        let question = match self
+            .database
+            .remove_qotd_question(&config.guild_id, None)
+            .await?
+        {
+            Some(queued) => queued.question,
+            None => self.write_question(config).await?,
+        };
+
+        let channel_id = ChannelId(config.channel_id.parse::<u64>()?);
+        let message = channel_id.say(http, format_question(&question)).await?;
+        let thread_id = if config.threads {
+            match channel_id
+                .create_public_thread(http, message.id, |t| {
+                    t.name(thread_name(&question))
+                        .kind(ChannelType::PublicThread)
+                        .auto_archive_duration(1440)
+                })
+                .await
+            {
+                Ok(thread) => Some(thread.id.to_string()),
+                Err(e) => {
+                    warn!("⚠️ Failed to open a thread for the question of the day: {e}");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        self.database
+            .record_qotd_post(&QotdPost {
+                guild_id: config.guild_id.clone(),
+                channel_id: config.channel_id.clone(),
+                message_id: message.id.to_string(),
+                thread_id,
+                question,
+                posted_at: now,
+            })
+            .await
+    }
+
+    /// Have the channel's persona write a question
+    async fn write_question(&self, config: &QotdConfig) -> Result<String> {
+        let persona_name = self
+            .database
+            .get_persona_with_channel(QOTD_USER_ID, &config.guild_id, &config.channel_id, None)
+            .await
+            .unwrap_or_else(|_| "obi".to_string());
+        let theme = self
+            .database
+            .get_guild_setting(&config.guild_id, ACTIVE_THEME_SETTING)
+            .await?;
+        let persona_prompt = apply_theme(
+            self.persona_manager.get_system_prompt(&persona_name, None),
+            theme.as_deref(),
+        );
+        let grounding = ChannelGrounding::for_channel(
+            &self.database,
+            Some(&config.guild_id),
+            Some(&config.channel_id),
+        )
+        .await?;
+        let recent = self
+            .database
+            .get_recent_qotd_questions(&config.guild_id, RECENT_QUESTIONS)
+            .await?;
+        self.writer
+            .question(
+                &config.guild_id,
+                &config.channel_id,
+                &persona_prompt,
+                &grounding,
+                &recent,
+            )
+            .await
+    }
+}
+
+/// A member's message as a scored answer; bots and empty messages don't count
+fn answer(message: Message) -> Option<QotdAnswer> {
+    if message.author.bot || message.content.trim().is_empty() {
+        return None;
+    }
+    Some(QotdAnswer {
+        user_id: message.author.id.to_string(),
+        reactions: message.reactions.iter().map(|r| r.count as i64).sum(),
+        content: message.content,
+    })
+}
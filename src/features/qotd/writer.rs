@@ -0,0 +1,134 @@
+//! Write a question of the day in a persona's voice
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use openai::chat::ChatCompletionMessageRole;
+use std::sync::Arc;
+
+use super::MAX_QUESTION_CHARS;
+use crate::core::{chat_message, ChatClient};
+use crate::features::analytics::{CostBucket, UsageTracker};
+use crate::features::guardrails::ChannelGrounding;
+
+/// Cap on generated tokens; the reply is one question
+const MAX_QUESTION_TOKENS: u64 = 150;
+
+/// User id usage is logged under for scheduled questions
+pub const QOTD_USER_ID: &str = "system_qotd";
+
+/// Writes questions of the day through the shared chat client
+#[derive(Clone)]
+pub struct QuestionWriter {
+    chat_client: Arc<dyn ChatClient>,
+    openai_model: String,
+    usage_tracker: UsageTracker,
+}
+
+impl QuestionWriter {
+    pub fn new(
+        chat_client: Arc<dyn ChatClient>,
+        openai_model: String,
+        usage_tracker: UsageTracker,
+    ) -> Self {
+        Self {
+            chat_client,
+            openai_model,
+            usage_tracker,
+        }
+    }
+
+    /// Write a question in the voice of `persona_prompt`
+    ///
+    /// `grounding` is the channel's safety profile and scene, and `recent`
+    /// holds the guild's latest questions so they aren't repeated.
+    pub async fn question(
+        &self,
+        guild_id: &str,
+        channel_id: &str,
+        persona_prompt: &str,
+        grounding: &ChannelGrounding,
+        recent: &[String],
+    ) -> Result<String> {
+        let completion = self
+            .chat_client
+            .create_chat_completion_limited(
+                &self.openai_model,
+                vec![
+                    chat_message(
+                        ChatCompletionMessageRole::System,
+                        grounding.persona_prompt(&format!("{persona_prompt}\n\n{QUESTION_PROMPT}")),
+                    ),
+                    chat_message(ChatCompletionMessageRole::User, question_request(recent)),
+                ],
+                Some(MAX_QUESTION_TOKENS),
+            )
+            .await?;
+
+        if let Some(usage) = &completion.usage {
+            self.usage_tracker.log_chat(
+                &self.openai_model,
+                usage.prompt_tokens,
+                usage.completion_tokens,
+                usage.total_tokens,
+                QOTD_USER_ID,
+                Some(guild_id),
+                Some(channel_id),
+                None,
+                CostBucket::Qotd,
+            );
+        }
+
+        let question: String = completion
+            .choices
+            .first()
+            .and_then(|choice| choice.message.content.clone())
+            .map(|content| {
+                content
+                    .trim_matches(|c: char| c == '"' || c.is_whitespace())
+                    .chars()
+                    .take(MAX_QUESTION_CHARS)
+                    .collect()
+            })
+            .unwrap_or_default();
+        if question.is_empty() {
+            anyhow::bail!("Empty completion");
+        }
+        Ok(question)
+    }
+}
+
+const QUESTION_PROMPT: &str = "Your task is to ask this Discord server its question of the day, \
+    in your characteristic style. Reply with only the question: one open, friendly question that \
+    anyone could answer in a message or two. At most 250 characters, no preamble, no hashtags.";
+
+/// The user message asking for a question
+fn question_request(recent: &[String]) -> String {
+    if recent.is_empty() {
+        return "Ask today's question.".to_string();
+    }
+    let recent = recent
+        .iter()
+        .map(|q| format!("- {q}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("Ask today's question. Recent questions (ask something different):\n{recent}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_question_request() {
+        assert_eq!(question_request(&[]), "Ask today's question.");
+        assert_eq!(
+            question_request(&["Tabs or spaces?".to_string(), "Best snack?".to_string()]),
+            "Ask today's question. Recent questions (ask something different):\n- Tabs or spaces?\n- Best snack?"
+        );
+    }
+}
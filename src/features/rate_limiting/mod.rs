@@ -1,11 +1,14 @@
 //! # Rate Limiting Feature
 //!
-//! Prevents spam with configurable request limits per user.
+//! Prevents spam with configurable request limits per user, and throttles
+//! users who repeat near-identical prompts.
 //!
-//! - **Version**: 1.0.0
+//! - **Version**: 1.1.1
 //! - **Since**: 0.1.0
 //! - **Toggleable**: false
 
 pub mod limiter;
+pub mod similarity;
 
 pub use limiter::RateLimiter;
+pub use similarity::{throttle_warning_embed, SimilarityThrottle, ThrottleDecision};
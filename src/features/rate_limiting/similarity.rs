@@ -0,0 +1,346 @@
+//! # Feature: Prompt Similarity Throttling
+//!
+//! Catches users who repeat near-identical prompts to drain API budget while
+//! staying under the raw request rate limit. Each user's recent prompts are
+//! kept as word-frequency vectors; when a new prompt has a cosine similarity
+//! above the threshold with enough of them, the user earns a strike and is put
+//! on a cooldown that grows with every strike.
+//!
+//! - **Version**: 1.0.1
+//! - **Since**: 4.7.0
+//! - **Toggleable**: false
+//!
+//! ## Changelog
+//! - 1.0.1: Users with no recent prompts, cooldown or strikes are dropped from memory
+//! - 1.0.0: Initial release with escalating cooldowns and a warning embed
+
+use dashmap::DashMap;
+use serenity::builder::CreateEmbed;
+use serenity::utils::Color;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::features::analytics::format_duration;
+
+/// Prompts older than this no longer count towards a strike
+const PROMPT_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+/// How many recent prompts are remembered per user
+const MAX_RECENT_PROMPTS: usize = 8;
+
+/// Cosine similarity at or above which two prompts count as repeats
+const SIMILARITY_THRESHOLD: f64 = 0.9;
+
+/// Earlier near-identical prompts needed (within the window) for a strike
+const REPEATS_FOR_STRIKE: usize = 2;
+
+/// Prompts shorter than this many words are never compared ("thanks", "ok")
+const MIN_WORDS: usize = 3;
+
+/// Cooldown per strike; the last entry repeats for further strikes
+const COOLDOWNS: [Duration; 4] = [
+    Duration::from_secs(60),
+    Duration::from_secs(5 * 60),
+    Duration::from_secs(15 * 60),
+    Duration::from_secs(60 * 60),
+];
+
+/// Strikes are forgiven after this long without a new one
+const STRIKE_RESET: Duration = Duration::from_secs(2 * 60 * 60);
+
+/// How often users with nothing left to remember are dropped
+const SWEEP_INTERVAL: Duration = PROMPT_WINDOW;
+
+/// Outcome of checking a prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThrottleDecision {
+    /// Process the prompt normally
+    Allow,
+    /// This prompt earned a strike; warn the user and drop it
+    Warn { cooldown: Duration, strikes: u32 },
+    /// Still cooling down from an earlier strike; drop the prompt quietly
+    Cooldown { remaining: Duration },
+}
+
+#[derive(Default)]
+struct UserPrompts {
+    recent: VecDeque<(Instant, HashMap<String, f64>)>,
+    strikes: u32,
+    last_strike: Option<Instant>,
+    cooldown_until: Option<Instant>,
+}
+
+impl UserPrompts {
+    /// Whether forgetting this user would change no later decision
+    fn is_idle(&self, now: Instant) -> bool {
+        self.cooldown_until.is_none_or(|until| now >= until)
+            && self
+                .last_strike
+                .is_none_or(|at| now.duration_since(at) >= STRIKE_RESET)
+            && self
+                .recent
+                .iter()
+                .all(|(at, _)| now.duration_since(*at) >= PROMPT_WINDOW)
+    }
+}
+
+/// Per-user near-duplicate prompt detector with escalating cooldowns
+#[derive(Default)]
+pub struct SimilarityThrottle {
+    users: DashMap<String, UserPrompts>,
+    last_sweep: Mutex<Option<Instant>>,
+}
+
+impl SimilarityThrottle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record `prompt` for `user_id` and decide whether it may be processed
+    pub fn check(&self, user_id: &str, prompt: &str) -> ThrottleDecision {
+        self.check_at(user_id, prompt, Instant::now())
+    }
+
+    fn check_at(&self, user_id: &str, prompt: &str, now: Instant) -> ThrottleDecision {
+        self.sweep_if_due(now);
+
+        let vector = word_vector(prompt);
+        let comparable = vector.values().sum::<f64>() >= MIN_WORDS as f64;
+        let mut state = if comparable {
+            self.users.entry(user_id.to_string()).or_default()
+        } else {
+            // Short prompts are never recorded, so they only need an existing cooldown
+            match self.users.get_mut(user_id) {
+                Some(state) => state,
+                None => return ThrottleDecision::Allow,
+            }
+        };
+
+        if let Some(until) = state.cooldown_until {
+            if now < until {
+                return ThrottleDecision::Cooldown {
+                    remaining: until - now,
+                };
+            }
+            state.cooldown_until = None;
+        }
+        if state
+            .last_strike
+            .is_some_and(|at| now.duration_since(at) >= STRIKE_RESET)
+        {
+            state.strikes = 0;
+            state.last_strike = None;
+        }
+
+        if !comparable {
+            return ThrottleDecision::Allow;
+        }
+
+        state
+            .recent
+            .retain(|(at, _)| now.duration_since(*at) < PROMPT_WINDOW);
+        let repeats = state
+            .recent
+            .iter()
+            .filter(|(_, earlier)| cosine_similarity(earlier, &vector) >= SIMILARITY_THRESHOLD)
+            .count();
+
+        if repeats >= REPEATS_FOR_STRIKE {
+            let cooldown = COOLDOWNS[(state.strikes as usize).min(COOLDOWNS.len() - 1)];
+            state.strikes += 1;
+            state.last_strike = Some(now);
+            state.cooldown_until = Some(now + cooldown);
+            // Start over after the cooldown; repeating again earns the next strike
+            state.recent.clear();
+            return ThrottleDecision::Warn {
+                cooldown,
+                strikes: state.strikes,
+            };
+        }
+
+        state.recent.push_back((now, vector));
+        if state.recent.len() > MAX_RECENT_PROMPTS {
+            state.recent.pop_front();
+        }
+        ThrottleDecision::Allow
+    }
+
+    /// Drop idle users, at most once per `SWEEP_INTERVAL`
+    fn sweep_if_due(&self, now: Instant) {
+        {
+            let mut last_sweep = self.last_sweep.lock().unwrap();
+            if last_sweep.is_some_and(|at| now.duration_since(at) < SWEEP_INTERVAL) {
+                return;
+            }
+            *last_sweep = Some(now);
+        }
+        self.users.retain(|_, state| !state.is_idle(now));
+    }
+}
+
+/// Lowercased word counts, ignoring punctuation
+fn word_vector(text: &str) -> HashMap<String, f64> {
+    let mut counts = HashMap::new();
+    for word in text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+    {
+        *counts.entry(word.to_lowercase()).or_insert(0.0) += 1.0;
+    }
+    counts
+}
+
+fn cosine_similarity(a: &HashMap<String, f64>, b: &HashMap<String, f64>) -> f64 {
+    let dot: f64 = a
+        .iter()
+        .filter_map(|(word, count)| b.get(word).map(|other| count * other))
+        .sum();
+    let norm_a = a.values().map(|v| v * v).sum::<f64>().sqrt();
+    let norm_b = b.values().map(|v| v * v).sum::<f64>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+/// Embed sent when a user earns a strike
+pub fn throttle_warning_embed(cooldown: Duration, strikes: u32) -> CreateEmbed {
+    let mut embed = CreateEmbed::default();
+    embed
+        .title("⚠️ Slow down")
+        .description(format!(
+            "You've sent several nearly identical prompts in a row, so I'm ignoring your \
+             prompts for **{}**. Repeating them again will lengthen the pause.",
+            format_duration(cooldown.as_secs())
+        ))
+        .color(Color::from_rgb(250, 166, 26))
+        .footer(|f| f.text(format!("Strike {strikes}")));
+    embed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPAM: &str = "write me a very long essay about cats";
+
+    #[test]
+    fn test_cosine_similarity() {
+        let a = word_vector("Write me an essay about cats!");
+        assert!(
+            (cosine_similarity(&a, &word_vector("write me an essay about cats")) - 1.0).abs()
+                < 1e-9
+        );
+        assert!(
+            cosine_similarity(&a, &word_vector("write me an essay about dogs"))
+                < SIMILARITY_THRESHOLD
+        );
+        assert_eq!(cosine_similarity(&a, &word_vector("")), 0.0);
+    }
+
+    #[test]
+    fn test_distinct_prompts_allowed() {
+        let throttle = SimilarityThrottle::new();
+        for prompt in [
+            "what is the capital of france",
+            "how do I sort a vec in rust",
+            "tell me a joke about penguins",
+            "summarize the last meeting notes",
+        ] {
+            assert_eq!(throttle.check("u1", prompt), ThrottleDecision::Allow);
+        }
+    }
+
+    #[test]
+    fn test_repeats_earn_escalating_cooldowns() {
+        let throttle = SimilarityThrottle::new();
+        let start = Instant::now();
+        assert_eq!(
+            throttle.check_at("u1", SPAM, start),
+            ThrottleDecision::Allow
+        );
+        assert_eq!(
+            throttle.check_at("u1", "Write me a very long essay about cats.", start),
+            ThrottleDecision::Allow
+        );
+        // Other users are unaffected
+        assert_eq!(
+            throttle.check_at("u2", SPAM, start),
+            ThrottleDecision::Allow
+        );
+        assert_eq!(
+            throttle.check_at("u1", SPAM, start),
+            ThrottleDecision::Warn {
+                cooldown: COOLDOWNS[0],
+                strikes: 1
+            }
+        );
+        assert!(matches!(
+            throttle.check_at(
+                "u1",
+                "something else entirely now",
+                start + Duration::from_secs(30)
+            ),
+            ThrottleDecision::Cooldown { .. }
+        ));
+
+        let later = start + COOLDOWNS[0];
+        for _ in 0..REPEATS_FOR_STRIKE {
+            assert_eq!(
+                throttle.check_at("u1", SPAM, later),
+                ThrottleDecision::Allow
+            );
+        }
+        assert_eq!(
+            throttle.check_at("u1", SPAM, later),
+            ThrottleDecision::Warn {
+                cooldown: COOLDOWNS[1],
+                strikes: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_idle_users_are_forgotten() {
+        let throttle = SimilarityThrottle::new();
+        let start = Instant::now();
+        throttle.check_at("u1", "thanks!", start);
+        assert_eq!(throttle.users.len(), 0);
+
+        throttle.check_at("u1", SPAM, start);
+        for _ in 0..REPEATS_FOR_STRIKE {
+            throttle.check_at("u2", SPAM, start);
+        }
+        assert!(matches!(
+            throttle.check_at("u2", SPAM, start),
+            ThrottleDecision::Warn { .. }
+        ));
+        assert_eq!(throttle.users.len(), 2);
+
+        // u1's prompt has gone stale, but u2's strike still counts
+        throttle.check_at("u3", "ok", start + PROMPT_WINDOW);
+        assert!(!throttle.users.contains_key("u1"));
+        assert!(throttle.users.contains_key("u2"));
+
+        throttle.check_at("u3", "ok", start + STRIKE_RESET);
+        assert_eq!(throttle.users.len(), 0);
+    }
+
+    #[test]
+    fn test_short_and_stale_prompts_ignored() {
+        let throttle = SimilarityThrottle::new();
+        let start = Instant::now();
+        for _ in 0..5 {
+            assert_eq!(
+                throttle.check_at("u1", "thanks!", start),
+                ThrottleDecision::Allow
+            );
+        }
+        for i in 0..5 {
+            let at = start + PROMPT_WINDOW * i;
+            assert_eq!(throttle.check_at("u1", SPAM, at), ThrottleDecision::Allow);
+        }
+    }
+}
@@ -4,15 +4,17 @@
 //! for due reminders every 60 seconds and delivers them in the user's preferred
 //! persona style.
 //!
-//! - **Version**: 1.1.0
+//! - **Version**: 1.1.1
 //! - **Since**: 0.1.0
 //! - **Toggleable**: true
 //!
 //! ## Changelog
+//! - 1.1.1: Reminder generation is counted as an OpenAI in-flight request
 //! - 1.1.0: Added OpenAI usage tracking for reminder message generation
 //! - 1.0.0: Initial release with time parsing (30m, 2h, 1d, 1h30m) and persona delivery
 
 use crate::database::Database;
+use crate::features::analytics::runtime::runtime_counters;
 use crate::features::analytics::{CostBucket, UsageTracker};
 use crate::features::personas::PersonaManager;
 use anyhow::Result;
@@ -150,6 +152,7 @@ impl ReminderScheduler {
             The reminder message is: \"{reminder_text}\""
         );
 
+        let _in_flight = runtime_counters().openai_request();
         let chat_completion = ChatCompletion::builder(
             &self.openai_model,
             vec![
@@ -0,0 +1,293 @@
+//! # Feature: Fault Injection
+//!
+//! Hidden chaos flags that make OpenAI calls time out, Discord calls return 429
+//! and database calls fail at configurable rates, so retry paths, circuit
+//! breakers and user-facing error messages can be exercised before a real
+//! incident does it for us. All rates default to zero.
+//!
+//! - **Version**: 1.2.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: false
+//!
+//! ## Changelog
+//! - 1.2.0: ChaosChatClient forwards completions with parameter overrides
+//! - 1.1.0: ChaosChatClient forwards capped completions
+//! - 1.0.0: Initial release with OpenAI timeout, Discord 429 and database error faults
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::warn;
+use openai::chat::{ChatCompletion, ChatCompletionMessage};
+use rand::Rng;
+use serenity::http::error::{DiscordJsonError, ErrorResponse};
+use serenity::http::{HttpError, StatusCode};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+
+use crate::core::{ChatClient, ModelParams};
+
+/// Kinds of fault that can be injected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    OpenAiTimeout,
+    DiscordRateLimit,
+    DatabaseError,
+}
+
+impl Fault {
+    pub const ALL: [Fault; 3] = [
+        Fault::OpenAiTimeout,
+        Fault::DiscordRateLimit,
+        Fault::DatabaseError,
+    ];
+
+    /// Stable identifier used in logs
+    pub fn id(&self) -> &'static str {
+        match self {
+            Fault::OpenAiTimeout => "openai_timeout",
+            Fault::DiscordRateLimit => "discord_429",
+            Fault::DatabaseError => "database_error",
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            Fault::OpenAiTimeout => 0,
+            Fault::DiscordRateLimit => 1,
+            Fault::DatabaseError => 2,
+        }
+    }
+}
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+/// Injection probability per fault (0.0 - 1.0)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ChaosConfig {
+    pub openai_timeout_rate: f64,
+    pub discord_429_rate: f64,
+    pub database_error_rate: f64,
+}
+
+impl ChaosConfig {
+    pub fn rate(&self, fault: Fault) -> f64 {
+        match fault {
+            Fault::OpenAiTimeout => self.openai_timeout_rate,
+            Fault::DiscordRateLimit => self.discord_429_rate,
+            Fault::DatabaseError => self.database_error_rate,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        Fault::ALL.iter().any(|&fault| self.rate(fault) > 0.0)
+    }
+}
+
+/// Rolls the dice for each fault and counts how many were injected
+pub struct Chaos {
+    config: ChaosConfig,
+    injected: [AtomicU64; 3],
+}
+
+impl Chaos {
+    pub fn new(config: ChaosConfig) -> Self {
+        let clamp = |rate: f64| {
+            if rate.is_finite() {
+                rate.clamp(0.0, 1.0)
+            } else {
+                0.0
+            }
+        };
+        Self {
+            config: ChaosConfig {
+                openai_timeout_rate: clamp(config.openai_timeout_rate),
+                discord_429_rate: clamp(config.discord_429_rate),
+                database_error_rate: clamp(config.database_error_rate),
+            },
+            injected: Default::default(),
+        }
+    }
+
+    pub fn config(&self) -> ChaosConfig {
+        self.config
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.is_enabled()
+    }
+
+    /// Decide whether to inject `fault` for the current call
+    pub fn should_inject(&self, fault: Fault) -> bool {
+        let rate = self.config.rate(fault);
+        if rate <= 0.0 || !rand::rng().random_bool(rate) {
+            return false;
+        }
+        self.injected[fault.index()].fetch_add(1, Ordering::Relaxed);
+        true
+    }
+
+    /// Number of times `fault` has been injected since startup
+    pub fn injected(&self, fault: Fault) -> u64 {
+        self.injected[fault.index()].load(Ordering::Relaxed)
+    }
+
+    /// Fail with a simulated OpenAI timeout
+    pub fn openai_fault(&self) -> Result<()> {
+        if self.should_inject(Fault::OpenAiTimeout) {
+            warn!("🐒 Chaos: injecting OpenAI timeout");
+            anyhow::bail!("OpenAI request timed out (chaos: injected timeout)");
+        }
+        Ok(())
+    }
+
+    /// Fail with a simulated Discord 429 for `route` (a `serenity::Error` underneath)
+    pub fn discord_fault(&self, route: &str) -> Result<()> {
+        if self.should_inject(Fault::DiscordRateLimit) {
+            warn!("🐒 Chaos: injecting Discord 429 on {route}");
+            return Err(rate_limited_error(route).into());
+        }
+        Ok(())
+    }
+
+    /// Fail with a simulated database error for `operation`
+    pub fn database_fault(&self, operation: &str) -> Result<()> {
+        if self.should_inject(Fault::DatabaseError) {
+            warn!("🐒 Chaos: injecting database error in {operation}");
+            anyhow::bail!("database is locked (chaos: injected error in {operation})");
+        }
+        Ok(())
+    }
+}
+
+/// Build the error serenity returns for a rate-limited request
+fn rate_limited_error(route: &str) -> serenity::Error {
+    let url = reqwest::Url::parse("https://discord.com/api/v10/")
+        .and_then(|base| base.join(route.trim_start_matches('/')))
+        .expect("static Discord URL is valid");
+    // DiscordJsonError is non-exhaustive, so build it the way serenity does: from JSON
+    let error: DiscordJsonError = serde_json::from_value(serde_json::json!({
+        "code": 0,
+        "message": "You are being rate limited. (chaos: injected 429)",
+    }))
+    .expect("static Discord error body is valid");
+    serenity::Error::Http(Box::new(HttpError::UnsuccessfulRequest(ErrorResponse {
+        status_code: StatusCode::TOO_MANY_REQUESTS,
+        url,
+        error,
+    })))
+}
+
+/// Chat client wrapper that injects OpenAI timeouts before delegating
+pub struct ChaosChatClient {
+    inner: Arc<dyn ChatClient>,
+}
+
+impl ChaosChatClient {
+    pub fn new(inner: Arc<dyn ChatClient>) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl ChatClient for ChaosChatClient {
+    async fn create_chat_completion(
+        &self,
+        model: &str,
+        messages: Vec<ChatCompletionMessage>,
+    ) -> Result<ChatCompletion> {
+        chaos().openai_fault()?;
+        self.inner.create_chat_completion(model, messages).await
+    }
+
+    async fn create_chat_completion_limited(
+        &self,
+        model: &str,
+        messages: Vec<ChatCompletionMessage>,
+        max_tokens: Option<u64>,
+    ) -> Result<ChatCompletion> {
+        chaos().openai_fault()?;
+        self.inner
+            .create_chat_completion_limited(model, messages, max_tokens)
+            .await
+    }
+
+    async fn create_chat_completion_with_params(
+        &self,
+        model: &str,
+        messages: Vec<ChatCompletionMessage>,
+        params: ModelParams,
+    ) -> Result<ChatCompletion> {
+        chaos().openai_fault()?;
+        self.inner
+            .create_chat_completion_with_params(model, messages, params)
+            .await
+    }
+}
+
+static CHAOS: OnceLock<Chaos> = OnceLock::new();
+
+/// Install the fault injection rates (first call wins; call once at startup)
+pub fn init_chaos(config: ChaosConfig) -> &'static Chaos {
+    let chaos = CHAOS.get_or_init(|| Chaos::new(config));
+    if chaos.is_enabled() {
+        let config = chaos.config();
+        warn!(
+            "🐒 Chaos fault injection ENABLED: openai_timeout={:.2}, discord_429={:.2}, database_error={:.2}",
+            config.openai_timeout_rate, config.discord_429_rate, config.database_error_rate
+        );
+    }
+    chaos
+}
+
+/// Global fault injector (disabled unless `init_chaos` was called with non-zero rates)
+pub fn chaos() -> &'static Chaos {
+    CHAOS.get_or_init(|| Chaos::new(ChaosConfig::default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_chaos_never_injects() {
+        let chaos = Chaos::new(ChaosConfig::default());
+        assert!(!chaos.is_enabled());
+        for _ in 0..100 {
+            assert!(chaos.openai_fault().is_ok());
+            assert!(chaos.discord_fault("channels/1/messages").is_ok());
+            assert!(chaos.database_fault("test").is_ok());
+        }
+        assert_eq!(chaos.injected(Fault::DatabaseError), 0);
+    }
+
+    #[test]
+    fn test_full_rate_always_injects_and_counts() {
+        let chaos = Chaos::new(ChaosConfig {
+            openai_timeout_rate: 1.0,
+            discord_429_rate: 5.0,
+            database_error_rate: f64::NAN,
+        });
+        assert_eq!(chaos.config().discord_429_rate, 1.0);
+        assert_eq!(chaos.config().database_error_rate, 0.0);
+
+        let err = chaos.openai_fault().unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+
+        let err = chaos.discord_fault("channels/1/threads").unwrap_err();
+        match err.downcast_ref::<serenity::Error>().unwrap() {
+            serenity::Error::Http(http) => {
+                assert_eq!(http.status_code(), Some(StatusCode::TOO_MANY_REQUESTS));
+            }
+            other => panic!("expected HTTP error, got {other:?}"),
+        }
+
+        assert!(chaos.database_fault("test").is_ok());
+        assert_eq!(chaos.injected(Fault::OpenAiTimeout), 1);
+        assert_eq!(chaos.injected(Fault::DiscordRateLimit), 1);
+    }
+}
@@ -0,0 +1,494 @@
+//! # Feature: Circuit Breakers
+//!
+//! Per-dependency error-rate tracking. When a dependency fails too often the
+//! breaker opens and the features that rely on it are disabled with a friendly
+//! message until a cooldown passes and a single probe request succeeds.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: false
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release covering OpenAI chat, DALL-E, Whisper, yt-dlp and oEmbed
+
+use dashmap::DashMap;
+use log::{info, warn};
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Sliding window used to compute the error rate
+const WINDOW: Duration = Duration::from_secs(300);
+
+/// Minimum calls in the window before the error rate can trip the breaker
+const MIN_REQUESTS: usize = 5;
+
+/// Error rate at or above which the breaker opens
+const FAILURE_RATIO: f64 = 0.5;
+
+/// How long an open breaker rejects calls before letting a probe through
+const COOLDOWN: Duration = Duration::from_secs(120);
+
+/// External dependencies guarded by a breaker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dependency {
+    OpenAiChat,
+    DallE,
+    Whisper,
+    YtDlp,
+    OEmbed,
+}
+
+impl Dependency {
+    pub const ALL: [Dependency; 5] = [
+        Dependency::OpenAiChat,
+        Dependency::DallE,
+        Dependency::Whisper,
+        Dependency::YtDlp,
+        Dependency::OEmbed,
+    ];
+
+    /// Stable identifier used in logs and IPC
+    pub fn id(&self) -> &'static str {
+        match self {
+            Dependency::OpenAiChat => "openai_chat",
+            Dependency::DallE => "dalle",
+            Dependency::Whisper => "whisper",
+            Dependency::YtDlp => "yt_dlp",
+            Dependency::OEmbed => "oembed",
+        }
+    }
+
+    /// Human-readable dependency name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Dependency::OpenAiChat => "OpenAI chat",
+            Dependency::DallE => "DALL-E",
+            Dependency::Whisper => "Whisper",
+            Dependency::YtDlp => "yt-dlp",
+            Dependency::OEmbed => "YouTube oEmbed",
+        }
+    }
+
+    /// The user-facing feature that goes away while the breaker is open
+    pub fn feature(&self) -> &'static str {
+        match self {
+            Dependency::OpenAiChat => "AI responses",
+            Dependency::DallE => "Image generation",
+            Dependency::Whisper => "Audio transcription",
+            Dependency::YtDlp => "Video transcription",
+            Dependency::OEmbed => "Video title lookup",
+        }
+    }
+}
+
+/// Breaker state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+    /// Calls flow normally
+    Closed,
+    /// Calls are rejected until the cooldown passes
+    Open,
+    /// One probe call is allowed to test recovery
+    HalfOpen,
+}
+
+impl BreakerState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BreakerState::Closed => "closed",
+            BreakerState::Open => "open",
+            BreakerState::HalfOpen => "half_open",
+        }
+    }
+}
+
+/// Returned instead of calling a dependency whose breaker is open
+///
+/// The `Display` text is safe to show to users as-is.
+#[derive(Debug, Clone)]
+pub struct CircuitOpenError {
+    pub dependency: Dependency,
+    pub retry_after: Duration,
+}
+
+impl fmt::Display for CircuitOpenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let minutes = self.retry_after.as_secs().div_ceil(60).max(1);
+        write!(
+            f,
+            "{} is temporarily unavailable because {} is having problems. \
+             Please try again in about {} minute{}.",
+            self.dependency.feature(),
+            self.dependency.name(),
+            minutes,
+            if minutes == 1 { "" } else { "s" }
+        )
+    }
+}
+
+impl std::error::Error for CircuitOpenError {}
+
+/// Point-in-time view of one breaker, for /status and IPC
+#[derive(Debug, Clone)]
+pub struct BreakerStatus {
+    pub dependency: Dependency,
+    pub state: BreakerState,
+    pub requests: usize,
+    pub failures: usize,
+    pub retry_after: Option<Duration>,
+    pub trips: u32,
+    pub last_error: Option<String>,
+}
+
+struct Breaker {
+    state: BreakerState,
+    /// (when, succeeded) for calls inside the window
+    outcomes: VecDeque<(Instant, bool)>,
+    opened_at: Option<Instant>,
+    probe_started: Option<Instant>,
+    trips: u32,
+    last_error: Option<String>,
+}
+
+impl Breaker {
+    fn new() -> Self {
+        Breaker {
+            state: BreakerState::Closed,
+            outcomes: VecDeque::new(),
+            opened_at: None,
+            probe_started: None,
+            trips: 0,
+            last_error: None,
+        }
+    }
+
+    fn prune(&mut self, now: Instant) {
+        while let Some(&(at, _)) = self.outcomes.front() {
+            if now.duration_since(at) > WINDOW {
+                self.outcomes.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn failures(&self) -> usize {
+        self.outcomes.iter().filter(|(_, ok)| !ok).count()
+    }
+
+    fn retry_after(&self, now: Instant) -> Duration {
+        self.opened_at
+            .map(|at| COOLDOWN.saturating_sub(now.duration_since(at)))
+            .unwrap_or(Duration::ZERO)
+    }
+
+    fn open(&mut self, now: Instant) {
+        self.state = BreakerState::Open;
+        self.opened_at = Some(now);
+        self.probe_started = None;
+        self.trips += 1;
+    }
+}
+
+/// Registry of breakers, one per dependency
+pub struct CircuitBreakers {
+    breakers: DashMap<Dependency, Breaker>,
+}
+
+impl CircuitBreakers {
+    pub fn new() -> Self {
+        let breakers = DashMap::new();
+        for dependency in Dependency::ALL {
+            breakers.insert(dependency, Breaker::new());
+        }
+        CircuitBreakers { breakers }
+    }
+
+    /// Check whether a call may proceed
+    ///
+    /// An open breaker moves to half-open once the cooldown has passed and lets
+    /// exactly one probe through; a probe that never reports back is replaced
+    /// after another cooldown.
+    pub fn check(&self, dependency: Dependency) -> Result<(), CircuitOpenError> {
+        let now = Instant::now();
+        let mut breaker = self.breakers.entry(dependency).or_insert_with(Breaker::new);
+
+        match breaker.state {
+            BreakerState::Closed => Ok(()),
+            BreakerState::Open => {
+                let retry_after = breaker.retry_after(now);
+                if retry_after.is_zero() {
+                    info!("Circuit breaker half-open for {}", dependency.name());
+                    breaker.state = BreakerState::HalfOpen;
+                    breaker.probe_started = Some(now);
+                    Ok(())
+                } else {
+                    Err(CircuitOpenError {
+                        dependency,
+                        retry_after,
+                    })
+                }
+            }
+            BreakerState::HalfOpen => {
+                let stale_probe = breaker
+                    .probe_started
+                    .is_none_or(|at| now.duration_since(at) >= COOLDOWN);
+                if stale_probe {
+                    breaker.probe_started = Some(now);
+                    Ok(())
+                } else {
+                    Err(CircuitOpenError {
+                        dependency,
+                        retry_after: Duration::from_secs(60),
+                    })
+                }
+            }
+        }
+    }
+
+    /// Like `check`, but never claims the half-open probe slot
+    ///
+    /// For early bail-outs (before deferring or downloading) ahead of the
+    /// guarded call itself.
+    pub fn peek(&self, dependency: Dependency) -> Result<(), CircuitOpenError> {
+        let now = Instant::now();
+        let breaker = self.breakers.entry(dependency).or_insert_with(Breaker::new);
+
+        let retry_after = match breaker.state {
+            BreakerState::Closed => return Ok(()),
+            BreakerState::Open => breaker.retry_after(now),
+            BreakerState::HalfOpen => match breaker.probe_started {
+                Some(at) if now.duration_since(at) < COOLDOWN => Duration::from_secs(60),
+                _ => Duration::ZERO,
+            },
+        };
+
+        if retry_after.is_zero() {
+            Ok(())
+        } else {
+            Err(CircuitOpenError {
+                dependency,
+                retry_after,
+            })
+        }
+    }
+
+    /// Record a successful call
+    pub fn record_success(&self, dependency: Dependency) {
+        let now = Instant::now();
+        let mut breaker = self.breakers.entry(dependency).or_insert_with(Breaker::new);
+
+        if breaker.state != BreakerState::Closed {
+            info!("Circuit breaker closed for {}", dependency.name());
+            breaker.state = BreakerState::Closed;
+            breaker.opened_at = None;
+            breaker.probe_started = None;
+            breaker.outcomes.clear();
+        }
+        breaker.outcomes.push_back((now, true));
+        breaker.prune(now);
+    }
+
+    /// Record a failed call, opening the breaker if the error rate is too high
+    pub fn record_failure(&self, dependency: Dependency, error: &str) {
+        let now = Instant::now();
+        let mut breaker = self.breakers.entry(dependency).or_insert_with(Breaker::new);
+
+        breaker.last_error = Some(error.chars().take(200).collect());
+        breaker.outcomes.push_back((now, false));
+        breaker.prune(now);
+
+        match breaker.state {
+            BreakerState::HalfOpen => {
+                warn!(
+                    "Circuit breaker re-opened for {}: {error}",
+                    dependency.name()
+                );
+                breaker.open(now);
+            }
+            BreakerState::Closed => {
+                let requests = breaker.outcomes.len();
+                let failures = breaker.failures();
+                if requests >= MIN_REQUESTS && failures as f64 / requests as f64 >= FAILURE_RATIO {
+                    warn!(
+                        "Circuit breaker opened for {} ({failures}/{requests} failed): {error}",
+                        dependency.name()
+                    );
+                    breaker.open(now);
+                }
+            }
+            BreakerState::Open => {}
+        }
+    }
+
+    /// Record the outcome of a call
+    pub fn record<T, E: fmt::Display>(&self, dependency: Dependency, result: &Result<T, E>) {
+        match result {
+            Ok(_) => self.record_success(dependency),
+            Err(e) => self.record_failure(dependency, &e.to_string()),
+        }
+    }
+
+    /// Current state of every breaker, in `Dependency::ALL` order
+    pub fn snapshot(&self) -> Vec<BreakerStatus> {
+        let now = Instant::now();
+        Dependency::ALL
+            .iter()
+            .map(|&dependency| {
+                let mut breaker = self.breakers.entry(dependency).or_insert_with(Breaker::new);
+                breaker.prune(now);
+                BreakerStatus {
+                    dependency,
+                    state: breaker.state,
+                    requests: breaker.outcomes.len(),
+                    failures: breaker.failures(),
+                    retry_after: (breaker.state == BreakerState::Open)
+                        .then(|| breaker.retry_after(now)),
+                    trips: breaker.trips,
+                    last_error: breaker.last_error.clone(),
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for CircuitBreakers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Format breaker states for /status
+pub fn format_breaker_status(statuses: &[BreakerStatus]) -> String {
+    statuses
+        .iter()
+        .map(|status| {
+            let name = status.dependency.name();
+            match status.state {
+                BreakerState::Closed if status.failures > 0 => format!(
+                    "✅ {name} ({}/{} failed recently)",
+                    status.failures, status.requests
+                ),
+                BreakerState::Closed => format!("✅ {name}"),
+                BreakerState::HalfOpen => format!("⚠️ {name}: recovering (probing)"),
+                BreakerState::Open => format!(
+                    "⛔ {name}: {} disabled, retry in {}s",
+                    status.dependency.feature(),
+                    status.retry_after.unwrap_or_default().as_secs()
+                ),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Global breaker registry shared by every call site
+static CIRCUIT_BREAKERS: OnceLock<CircuitBreakers> = OnceLock::new();
+
+/// Get or initialize the global breaker registry
+pub fn circuit_breakers() -> &'static CircuitBreakers {
+    CIRCUIT_BREAKERS.get_or_init(CircuitBreakers::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fail(breakers: &CircuitBreakers, n: usize) {
+        for _ in 0..n {
+            breakers.record_failure(Dependency::DallE, "500 Internal Server Error");
+        }
+    }
+
+    #[test]
+    fn test_breaker_stays_closed_below_min_requests() {
+        let breakers = CircuitBreakers::new();
+        fail(&breakers, MIN_REQUESTS - 1);
+        assert!(breakers.check(Dependency::DallE).is_ok());
+    }
+
+    #[test]
+    fn test_breaker_opens_on_error_rate() {
+        let breakers = CircuitBreakers::new();
+        for _ in 0..3 {
+            breakers.record_success(Dependency::DallE);
+        }
+        fail(&breakers, 3);
+
+        let err = breakers.check(Dependency::DallE).unwrap_err();
+        assert_eq!(err.dependency, Dependency::DallE);
+        assert!(err
+            .to_string()
+            .starts_with("Image generation is temporarily unavailable"));
+
+        // Other dependencies are unaffected
+        assert!(breakers.check(Dependency::Whisper).is_ok());
+
+        let status = &breakers.snapshot()[1];
+        assert_eq!(status.state, BreakerState::Open);
+        assert_eq!(status.failures, 3);
+        assert_eq!(status.trips, 1);
+    }
+
+    #[test]
+    fn test_half_open_probe_closes_or_reopens() {
+        let breakers = CircuitBreakers::new();
+        fail(&breakers, MIN_REQUESTS);
+
+        // Pretend the cooldown has elapsed
+        breakers
+            .breakers
+            .get_mut(&Dependency::DallE)
+            .unwrap()
+            .opened_at = Some(Instant::now() - COOLDOWN);
+
+        assert!(breakers.peek(Dependency::DallE).is_ok());
+        assert!(breakers.check(Dependency::DallE).is_ok());
+        // Only one probe at a time
+        assert!(breakers.peek(Dependency::DallE).is_err());
+        assert!(breakers.check(Dependency::DallE).is_err());
+
+        breakers.record_failure(Dependency::DallE, "still down");
+        assert_eq!(breakers.snapshot()[1].state, BreakerState::Open);
+        assert_eq!(breakers.snapshot()[1].trips, 2);
+
+        breakers
+            .breakers
+            .get_mut(&Dependency::DallE)
+            .unwrap()
+            .opened_at = Some(Instant::now() - COOLDOWN);
+        assert!(breakers.check(Dependency::DallE).is_ok());
+        breakers.record_success(Dependency::DallE);
+        assert_eq!(breakers.snapshot()[1].state, BreakerState::Closed);
+        assert!(breakers.check(Dependency::DallE).is_ok());
+    }
+
+    #[test]
+    fn test_format_breaker_status() {
+        let breakers = CircuitBreakers::new();
+        breakers.record_failure(Dependency::Whisper, "timeout");
+        fail(&breakers, MIN_REQUESTS);
+
+        let output = format_breaker_status(&breakers.snapshot());
+        assert!(output.starts_with("✅ OpenAI chat\n"));
+        assert!(output.contains("⛔ DALL-E: Image generation disabled, retry in"));
+        assert!(output.contains("✅ Whisper (1/1 failed recently)"));
+    }
+
+    #[test]
+    fn test_circuit_open_message_pluralizes_minutes() {
+        let err = CircuitOpenError {
+            dependency: Dependency::Whisper,
+            retry_after: Duration::from_secs(30),
+        };
+        assert!(err.to_string().ends_with("about 1 minute."));
+
+        let err = CircuitOpenError {
+            dependency: Dependency::Whisper,
+            retry_after: Duration::from_secs(121),
+        };
+        assert!(err.to_string().ends_with("about 3 minutes."));
+    }
+}
@@ -0,0 +1,424 @@
+//! # Feature: Crash Reporter
+//!
+//! Keeps the last log lines in memory as breadcrumbs. A panic hook (and the
+//! top-level error path in `main`) writes them to a crash report file along
+//! with the panic message and location; on the next startup the report is
+//! DM'd to the owner so silent crashes leave a trace outside systemd logs.
+//!
+//! - **Version**: 1.1.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: false
+//!
+//! ## Changelog
+//! - 1.1.0: Counts ERROR-level log lines for the error rate alert
+//! - 1.0.0: Initial release with breadcrumb logger, panic hook and startup DM
+
+use anyhow::Result;
+use log::{info, warn, Level, Log, Metadata, Record};
+use serde::{Deserialize, Serialize};
+use serenity::builder::CreateEmbed;
+use serenity::http::Http;
+use serenity::model::id::UserId;
+use serenity::utils::Color;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use crate::database::Database;
+use crate::features::get_bot_version;
+
+/// Log lines kept for a crash report
+pub const BREADCRUMB_CAPACITY: usize = 50;
+
+/// Default crash report location (overridden by CRASH_REPORT_PATH)
+pub const DEFAULT_CRASH_REPORT_PATH: &str = "crash_report.json";
+
+/// Embed descriptions allow 4096 characters; leave room for the code fence
+const BREADCRUMB_EMBED_CHARS: usize = 3900;
+
+/// Embed field values allow 1024 characters; leave room for the code fence
+const FIELD_CHARS: usize = 1000;
+
+/// Fixed-size ring of recent log lines
+#[derive(Debug)]
+pub struct Breadcrumbs {
+    lines: VecDeque<String>,
+    capacity: usize,
+}
+
+impl Breadcrumbs {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, line: String) {
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+    }
+
+    /// Oldest first
+    pub fn snapshot(&self) -> Vec<String> {
+        self.lines.iter().cloned().collect()
+    }
+}
+
+static BREADCRUMBS: OnceLock<Mutex<Breadcrumbs>> = OnceLock::new();
+static CRASH_REPORT_PATH: OnceLock<PathBuf> = OnceLock::new();
+static PENDING_REPORT: Mutex<Option<CrashReport>> = Mutex::new(None);
+static ERROR_LINES: AtomicU64 = AtomicU64::new(0);
+
+fn breadcrumbs() -> &'static Mutex<Breadcrumbs> {
+    BREADCRUMBS.get_or_init(|| Mutex::new(Breadcrumbs::new(BREADCRUMB_CAPACITY)))
+}
+
+/// Record a breadcrumb outside the logger
+pub fn record_breadcrumb(line: String) {
+    breadcrumbs()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(line);
+}
+
+/// Current breadcrumbs, oldest first
+///
+/// Uses `try_lock` so a panic raised while a log line is being recorded
+/// can't deadlock the panic hook.
+fn current_breadcrumbs() -> Vec<String> {
+    match breadcrumbs().try_lock() {
+        Ok(crumbs) => crumbs.snapshot(),
+        Err(std::sync::TryLockError::Poisoned(e)) => e.into_inner().snapshot(),
+        Err(std::sync::TryLockError::WouldBlock) => Vec::new(),
+    }
+}
+
+/// ERROR-level lines logged since startup
+pub fn error_line_count() -> u64 {
+    ERROR_LINES.load(Ordering::Relaxed)
+}
+
+/// `env_logger` wrapper that also keeps every emitted line as a breadcrumb
+pub struct BreadcrumbLogger {
+    inner: env_logger::Logger,
+}
+
+impl BreadcrumbLogger {
+    /// Install as the global logger
+    pub fn init(mut builder: env_logger::Builder) {
+        let inner = builder.build();
+        let max_level = inner.filter();
+        if log::set_boxed_logger(Box::new(BreadcrumbLogger { inner })).is_ok() {
+            log::set_max_level(max_level);
+        }
+    }
+}
+
+impl Log for BreadcrumbLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.inner.matches(record) {
+            if record.level() == Level::Error {
+                ERROR_LINES.fetch_add(1, Ordering::Relaxed);
+            }
+            record_breadcrumb(format!(
+                "{} {:<5} {}: {}",
+                chrono::Utc::now().format("%H:%M:%S"),
+                record.level(),
+                record.target(),
+                record.args()
+            ));
+        }
+        self.inner.log(record);
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+/// What went wrong
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrashKind {
+    Panic,
+    /// `main` returned an error
+    FatalError,
+}
+
+/// A crash persisted to disk for delivery on the next startup
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub kind: CrashKind,
+    pub message: String,
+    /// `file:line:column` for panics
+    pub location: Option<String>,
+    pub thread: Option<String>,
+    pub version: String,
+    /// Unix timestamp
+    pub occurred_at: i64,
+    pub breadcrumbs: Vec<String>,
+}
+
+impl CrashReport {
+    fn new(kind: CrashKind, message: String, location: Option<String>) -> Self {
+        Self {
+            kind,
+            message,
+            location,
+            thread: std::thread::current().name().map(str::to_string),
+            version: get_bot_version().to_string(),
+            occurred_at: chrono::Utc::now().timestamp(),
+            breadcrumbs: current_breadcrumbs(),
+        }
+    }
+
+    fn from_panic(info: &std::panic::PanicHookInfo) -> Self {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Box<dyn Any> panic payload".to_string());
+        let location = info
+            .location()
+            .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+        Self::new(CrashKind::Panic, message, location)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Read and remove a saved report
+    pub fn take(path: &Path) -> Result<Option<Self>> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        std::fs::remove_file(path)?;
+        Ok(Some(serde_json::from_str(&contents)?))
+    }
+
+    pub fn embed(&self) -> CreateEmbed {
+        let mut embed = CreateEmbed::default();
+        let title = match self.kind {
+            CrashKind::Panic => "💥 Crash Report: Panic",
+            CrashKind::FatalError => "💥 Crash Report: Fatal Error",
+        };
+        embed
+            .title(title)
+            .color(Color::from_rgb(237, 66, 69))
+            .field(
+                "Message",
+                format!("```\n{}\n```", truncate(&self.message, FIELD_CHARS)),
+                false,
+            );
+        if let Some(location) = &self.location {
+            embed.field("Location", format!("`{location}`"), false);
+        }
+        embed.field("Version", format!("`v{}`", self.version), true);
+        if let Some(thread) = &self.thread {
+            embed.field("Thread", format!("`{thread}`"), true);
+        }
+        embed.field("When", format!("<t:{}:F>", self.occurred_at), true);
+
+        if self.breadcrumbs.is_empty() {
+            embed.description("No breadcrumbs were recorded.");
+        } else {
+            embed.description(format!(
+                "**Last {} log lines:**\n```\n{}\n```",
+                self.breadcrumbs.len(),
+                tail_lines(&self.breadcrumbs, BREADCRUMB_EMBED_CHARS)
+            ));
+        }
+        embed
+    }
+}
+
+/// Install the panic hook and load any report left by the previous run
+///
+/// Call once, right after logging is set up.
+pub fn install_crash_reporter(path: impl Into<PathBuf>) {
+    let path = path.into();
+    match CrashReport::take(&path) {
+        Ok(Some(report)) => {
+            warn!("Previous run ended with a crash: {}", report.message);
+            *PENDING_REPORT.lock().unwrap_or_else(|e| e.into_inner()) = Some(report);
+        }
+        Ok(None) => {}
+        Err(e) => warn!("Failed to read crash report {}: {e}", path.display()),
+    }
+    let _ = CRASH_REPORT_PATH.set(path.clone());
+
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let report = CrashReport::from_panic(info);
+        if let Err(e) = report.save(&path) {
+            eprintln!("Failed to write crash report {}: {e}", path.display());
+        }
+        previous(info);
+    }));
+}
+
+/// Persist an error that is about to end the process
+pub fn record_fatal_error(error: &anyhow::Error) {
+    let Some(path) = CRASH_REPORT_PATH.get() else {
+        return;
+    };
+    let report = CrashReport::new(CrashKind::FatalError, format!("{error:#}"), None);
+    if let Err(e) = report.save(path) {
+        warn!("Failed to write crash report {}: {e}", path.display());
+    }
+}
+
+/// DM the previous run's crash report to the owner, if there is one
+///
+/// The owner is the startup notification owner, falling back to the
+/// application owner. An undelivered report is retried on the next start.
+pub async fn deliver_pending_crash_report(http: &Http, database: &Database) {
+    let Some(report) = PENDING_REPORT
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take()
+    else {
+        return;
+    };
+
+    let configured_owner = database
+        .get_bot_setting("startup_notify_owner_id")
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| v.parse::<u64>().ok());
+    let owner_id = match configured_owner {
+        Some(id) => Some(id),
+        None => http
+            .get_current_application_info()
+            .await
+            .ok()
+            .map(|app| app.owner.id.0),
+    };
+
+    let result = match owner_id {
+        Some(owner_id) => send_report(http, UserId(owner_id), &report).await,
+        None => Err(anyhow::anyhow!("no owner to send it to")),
+    };
+    match result {
+        Ok(()) => info!("Delivered crash report from the previous run"),
+        Err(e) => {
+            warn!("Failed to deliver crash report: {e}");
+            // Put it back on disk for the next start unless this run already crashed
+            if let Some(path) = CRASH_REPORT_PATH.get().filter(|p| !p.exists()) {
+                if let Err(e) = report.save(path) {
+                    warn!("Failed to keep undelivered crash report: {e}");
+                }
+            }
+        }
+    }
+}
+
+async fn send_report(http: &Http, owner: UserId, report: &CrashReport) -> Result<()> {
+    let dm = owner.create_dm_channel(http).await?;
+    let embed = report.embed();
+    dm.send_message(http, |m| m.set_embed(embed)).await?;
+    Ok(())
+}
+
+/// The newest lines that fit in `max_chars`, oldest first
+fn tail_lines(lines: &[String], max_chars: usize) -> String {
+    let mut kept = Vec::new();
+    let mut len = 0;
+    for line in lines.iter().rev() {
+        let line = truncate(line, 300);
+        if len + line.len() + 1 > max_chars {
+            break;
+        }
+        len += line.len() + 1;
+        kept.push(line);
+    }
+    kept.reverse();
+    kept.join("\n")
+}
+
+/// Truncate on a char boundary, marking the cut with an ellipsis
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.len() <= max_chars {
+        return text.to_string();
+    }
+    let mut end = max_chars.saturating_sub('…'.len_utf8());
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}…", &text[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> CrashReport {
+        CrashReport {
+            kind: CrashKind::Panic,
+            message: "index out of bounds".to_string(),
+            location: Some("src/database.rs:42:9".to_string()),
+            thread: Some("tokio-runtime-worker".to_string()),
+            version: "4.6.1".to_string(),
+            occurred_at: 1_700_000_000,
+            breadcrumbs: vec!["12:00:00 INFO  persona: hello".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_breadcrumbs_keep_newest() {
+        let mut crumbs = Breadcrumbs::new(3);
+        for i in 0..5 {
+            crumbs.push(format!("line {i}"));
+        }
+        assert_eq!(crumbs.snapshot(), vec!["line 2", "line 3", "line 4"]);
+    }
+
+    #[test]
+    fn test_report_round_trip_removes_file() {
+        let path = std::env::temp_dir().join(format!("crash-{}.json", uuid::Uuid::new_v4()));
+        assert!(CrashReport::take(&path).unwrap().is_none());
+
+        report().save(&path).unwrap();
+        assert_eq!(CrashReport::take(&path).unwrap(), Some(report()));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_report_embed() {
+        let embed = report().embed();
+        let json = serde_json::to_string(&embed.0).unwrap();
+        assert!(json.contains("Crash Report: Panic"));
+        assert!(json.contains("index out of bounds"));
+        assert!(json.contains("src/database.rs:42:9"));
+        assert!(json.contains("persona: hello"));
+    }
+
+    #[test]
+    fn test_tail_lines_prefers_newest() {
+        let lines: Vec<String> = (0..100).map(|i| format!("line {i:03}")).collect();
+        let tail = tail_lines(&lines, 30);
+        assert_eq!(tail, "line 097\nline 098\nline 099");
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("short", 10), "short");
+        let cut = truncate(&"é".repeat(20), 10);
+        assert!(cut.len() <= 10);
+        assert!(cut.ends_with('…'));
+    }
+}
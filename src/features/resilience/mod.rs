@@ -0,0 +1,22 @@
+//! # Resilience Feature
+//!
+//! Circuit breakers that disable features backed by a failing external dependency,
+//! hidden fault injection flags for exercising them, and a crash reporter.
+//!
+//! - **Version**: 1.2.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: false
+
+pub mod chaos;
+pub mod circuit_breaker;
+pub mod crash;
+
+pub use chaos::{chaos, init_chaos, Chaos, ChaosChatClient, ChaosConfig, Fault};
+pub use circuit_breaker::{
+    circuit_breakers, format_breaker_status, BreakerState, BreakerStatus, CircuitBreakers,
+    CircuitOpenError, Dependency,
+};
+pub use crash::{
+    deliver_pending_crash_report, error_line_count, install_crash_reporter, record_fatal_error,
+    BreadcrumbLogger, CrashReport,
+};
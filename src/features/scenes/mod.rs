@@ -0,0 +1,113 @@
+//! # Feature: Channel Scenes
+//!
+//! Roleplay servers attach a scene or world description to a channel with
+//! `/set_channel scene`. The scene is appended to every persona prompt
+//! generated for that channel: mentions, `/ask`, councils, debates and
+//! stories, so the bot stays in-world there. Threads don't inherit their
+//! parent's scene; set one on the thread itself with the `channel` option.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with per-channel scenes
+
+use anyhow::Result;
+
+use crate::database::Database;
+
+/// Feature id for toggles
+pub const SCENES_FEATURE: &str = "scenes";
+
+/// Longest scene accepted by /set_channel scene
+pub const MAX_SCENE_CHARS: usize = 2000;
+
+const SCENE_HEADER: &str = "## Scene\n\
+This channel is a roleplay space set in the world described below. Stay in this world: \
+answer as your persona would inside it, keep its names, places and rules consistent, \
+and don't mention being a bot or step outside the fiction unless someone asks out of character \
+(for example in brackets or with OOC).";
+
+/// The system prompt with a scene appended, if there is one
+pub fn with_scene(system_prompt: String, scene: Option<&str>) -> String {
+    match scene.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(scene) => format!("{system_prompt}\n\n{SCENE_HEADER}\n\n{scene}"),
+        None => system_prompt,
+    }
+}
+
+/// The scene for a channel; DMs and guilds with the feature off have none
+pub async fn channel_scene(
+    database: &Database,
+    guild_id: Option<&str>,
+    channel_id: Option<&str>,
+) -> Result<Option<String>> {
+    let (Some(guild_id), Some(channel_id)) = (guild_id, channel_id) else {
+        return Ok(None);
+    };
+    if !database
+        .is_feature_enabled(SCENES_FEATURE, None, Some(guild_id))
+        .await?
+    {
+        return Ok(None);
+    }
+    database.get_channel_scene(guild_id, channel_id).await
+}
+
+/// The system prompt with the channel's scene appended
+pub async fn apply_scene(
+    database: &Database,
+    guild_id: Option<&str>,
+    channel_id: Option<&str>,
+    system_prompt: String,
+) -> Result<String> {
+    let scene = channel_scene(database, guild_id, channel_id).await?;
+    Ok(with_scene(system_prompt, scene.as_deref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_scene() {
+        assert_eq!(
+            with_scene("You are a bard.".to_string(), None),
+            "You are a bard."
+        );
+        assert_eq!(
+            with_scene("You are a bard.".to_string(), Some("  ")),
+            "You are a bard."
+        );
+
+        let prompt = with_scene("You are a bard.".to_string(), Some("A tavern in Eldmoor"));
+        assert!(prompt.starts_with("You are a bard.\n\n## Scene\n"));
+        assert!(prompt.ends_with("\n\nA tavern in Eldmoor"));
+    }
+
+    #[tokio::test]
+    async fn test_apply_scene() {
+        let db = Database::new(":memory:").await.unwrap();
+        db.set_channel_scene("g1", "c1", Some("A tavern in Eldmoor"))
+            .await
+            .unwrap();
+
+        let prompt = apply_scene(&db, Some("g1"), Some("c1"), "Base".to_string())
+            .await
+            .unwrap();
+        assert!(prompt.ends_with("A tavern in Eldmoor"));
+        assert_eq!(
+            apply_scene(&db, Some("g1"), Some("c2"), "Base".to_string())
+                .await
+                .unwrap(),
+            "Base"
+        );
+        assert_eq!(
+            apply_scene(&db, None, Some("c1"), "Base".to_string())
+                .await
+                .unwrap(),
+            "Base"
+        );
+    }
+}
@@ -0,0 +1,110 @@
+//! # Feature: Send Later
+//!
+//! Messages queued with `/send_later` and posted to a chosen channel once
+//! their time comes. A message can be handed to the member's persona to be
+//! rewritten in its voice when it goes out. Members list and cancel their own
+//! messages; members with Manage Messages see and cancel everyone's.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with persona rewrites, listing and cancelling
+
+pub mod rewriter;
+pub mod scheduler;
+
+pub use rewriter::MessageRewriter;
+pub use scheduler::SendLaterScheduler;
+
+use crate::database::ScheduledMessage;
+
+/// Feature id for toggles
+pub const SEND_LATER_FEATURE: &str = "send_later";
+
+/// Discord's limit on message content
+pub const MAX_MESSAGE_CHARS: usize = 2000;
+
+/// Furthest ahead a message can be queued
+pub const MAX_DELAY_SECS: i64 = 30 * 24 * 60 * 60;
+
+/// Messages a member may have waiting per guild
+pub const MAX_PENDING_PER_USER: i64 = 25;
+
+/// Characters of each message shown by `/send_later list`
+const PREVIEW_CHARS: usize = 100;
+
+/// Trim a message for the queue, rejecting empty or oversized ones
+pub fn validate_message(input: &str) -> Result<String, &'static str> {
+    let content = input.trim();
+    if content.is_empty() {
+        return Err("The message can't be empty.");
+    }
+    if content.chars().count() > MAX_MESSAGE_CHARS {
+        return Err("Messages must be at most 2000 characters.");
+    }
+    Ok(content.to_string())
+}
+
+/// One queued message for `/send_later list`; `show_author` names who queued it
+pub fn list_line(message: &ScheduledMessage, show_author: bool) -> String {
+    let author = if show_author {
+        format!(" by <@{}>", message.user_id)
+    } else {
+        String::new()
+    };
+    let persona = message
+        .persona
+        .as_deref()
+        .map(|p| format!(" · 🎭 {p}"))
+        .unwrap_or_default();
+    let flat = message.content.replace('\n', " ");
+    let mut preview: String = flat.chars().take(PREVIEW_CHARS).collect();
+    if flat.chars().count() > PREVIEW_CHARS {
+        preview.push('…');
+    }
+    format!(
+        "**#{}** <t:{}:R> in <#{}>{author}{persona}\n> {preview}\n",
+        message.id, message.send_at, message.channel_id
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(content: &str, persona: Option<&str>) -> ScheduledMessage {
+        ScheduledMessage {
+            id: 7,
+            guild_id: "1".to_string(),
+            channel_id: "2".to_string(),
+            user_id: "3".to_string(),
+            content: content.to_string(),
+            persona: persona.map(str::to_string),
+            send_at: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn test_validate_message() {
+        assert_eq!(validate_message("  Hello  "), Ok("Hello".to_string()));
+        assert!(validate_message("   ").is_err());
+        assert!(validate_message(&"x".repeat(MAX_MESSAGE_CHARS)).is_ok());
+        assert!(validate_message(&"x".repeat(MAX_MESSAGE_CHARS + 1)).is_err());
+    }
+
+    #[test]
+    fn test_list_line() {
+        assert_eq!(
+            list_line(&message("Standup\nin five", None), false),
+            "**#7** <t:1700000000:R> in <#2>\n> Standup in five\n"
+        );
+        assert_eq!(
+            list_line(&message("Hi", Some("obi")), true),
+            "**#7** <t:1700000000:R> in <#2> by <@3> · 🎭 obi\n> Hi\n"
+        );
+        let long = list_line(&message(&"x".repeat(150), None), false);
+        assert!(long.ends_with(&format!("> {}…\n", "x".repeat(PREVIEW_CHARS))));
+    }
+}
@@ -0,0 +1,95 @@
+//! Rewrite a queued message in a persona's voice
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use openai::chat::ChatCompletionMessageRole;
+use std::sync::Arc;
+
+use super::MAX_MESSAGE_CHARS;
+use crate::core::{chat_message, ChatClient};
+use crate::database::ScheduledMessage;
+use crate::features::analytics::{CostBucket, UsageTracker};
+use crate::features::guardrails::ChannelGrounding;
+
+/// Cap on generated tokens; the rewrite should stay close to the original's length
+const MAX_REWRITE_TOKENS: u64 = 600;
+
+/// Rewrites scheduled messages through the shared chat client
+#[derive(Clone)]
+pub struct MessageRewriter {
+    chat_client: Arc<dyn ChatClient>,
+    openai_model: String,
+    usage_tracker: UsageTracker,
+}
+
+impl MessageRewriter {
+    pub fn new(
+        chat_client: Arc<dyn ChatClient>,
+        openai_model: String,
+        usage_tracker: UsageTracker,
+    ) -> Self {
+        Self {
+            chat_client,
+            openai_model,
+            usage_tracker,
+        }
+    }
+
+    /// Rewrite `message` in the voice of `persona_prompt`, grounded in its channel
+    pub async fn rewrite(
+        &self,
+        message: &ScheduledMessage,
+        persona_prompt: &str,
+        grounding: &ChannelGrounding,
+    ) -> Result<String> {
+        let completion = self
+            .chat_client
+            .create_chat_completion_limited(
+                &self.openai_model,
+                vec![
+                    chat_message(
+                        ChatCompletionMessageRole::System,
+                        grounding.persona_prompt(&format!("{persona_prompt}\n\n{REWRITE_PROMPT}")),
+                    ),
+                    chat_message(ChatCompletionMessageRole::User, message.content.clone()),
+                ],
+                Some(MAX_REWRITE_TOKENS),
+            )
+            .await?;
+
+        if let Some(usage) = &completion.usage {
+            self.usage_tracker.log_chat(
+                &self.openai_model,
+                usage.prompt_tokens,
+                usage.completion_tokens,
+                usage.total_tokens,
+                &message.user_id,
+                Some(&message.guild_id),
+                Some(&message.channel_id),
+                None,
+                CostBucket::SendLater,
+            );
+        }
+
+        let rewritten: String = completion
+            .choices
+            .first()
+            .and_then(|choice| choice.message.content.clone())
+            .map(|content| content.trim().chars().take(MAX_MESSAGE_CHARS).collect())
+            .unwrap_or_default();
+        if rewritten.is_empty() {
+            anyhow::bail!("Empty completion");
+        }
+        Ok(rewritten)
+    }
+}
+
+const REWRITE_PROMPT: &str = "A member of this Discord server scheduled the message they send \
+    you to be posted to a channel. Rewrite it in your characteristic style, keeping every fact, \
+    date, link and mention exactly as written and keeping it about the same length. Reply with \
+    only the rewritten message, no preamble.";
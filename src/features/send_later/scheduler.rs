@@ -0,0 +1,128 @@
+//! Background task posting `/send_later` messages when they're due
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use chrono::Utc;
+use log::{error, info, warn};
+use serenity::builder::ParseValue;
+use serenity::http::Http;
+use serenity::model::id::ChannelId;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::interval;
+
+use super::{MessageRewriter, SEND_LATER_FEATURE};
+use crate::database::{Database, ScheduledMessage};
+use crate::features::guardrails::ChannelGrounding;
+use crate::features::personas::themes::{apply_theme, ACTIVE_THEME_SETTING};
+use crate::features::personas::PersonaManager;
+
+pub struct SendLaterScheduler {
+    database: Database,
+    rewriter: MessageRewriter,
+    persona_manager: PersonaManager,
+}
+
+impl SendLaterScheduler {
+    pub fn new(database: Database, rewriter: MessageRewriter) -> Self {
+        Self {
+            database,
+            rewriter,
+            persona_manager: PersonaManager::new(),
+        }
+    }
+
+    /// Start the scheduled message loop
+    /// This should be spawned as a tokio task
+    pub async fn run(&self, http: Arc<Http>) {
+        let mut check_interval = interval(Duration::from_secs(60));
+
+        info!("📨 Send later scheduler started");
+
+        loop {
+            check_interval.tick().await;
+
+            if let Err(e) = self.process_due_messages(&http).await {
+                error!("❌ Error processing scheduled messages: {e}");
+            }
+        }
+    }
+
+    async fn process_due_messages(&self, http: &Arc<Http>) -> Result<()> {
+        let now = Utc::now().timestamp();
+        for message in self.database.get_due_scheduled_messages(now).await? {
+            // Removed before sending so a failure can't post it twice
+            self.database
+                .delete_scheduled_message(message.id, &message.guild_id, None)
+                .await?;
+            if !self
+                .database
+                .is_feature_enabled(SEND_LATER_FEATURE, None, Some(&message.guild_id))
+                .await?
+            {
+                info!(
+                    "📨 Dropped scheduled message #{} in guild {}: send later is disabled",
+                    message.id, message.guild_id
+                );
+                continue;
+            }
+            match self.send(http, &message).await {
+                Ok(()) => info!(
+                    "📨 Sent scheduled message #{} from {} to channel {}",
+                    message.id, message.user_id, message.channel_id
+                ),
+                Err(e) => warn!("⚠️ Failed to send scheduled message #{}: {e}", message.id),
+            }
+        }
+        Ok(())
+    }
+
+    async fn send(&self, http: &Arc<Http>, message: &ScheduledMessage) -> Result<()> {
+        let content = match &message.persona {
+            Some(persona) => match self.rewrite(message, persona).await {
+                Ok(rewritten) => rewritten,
+                Err(e) => {
+                    warn!(
+                        "⚠️ Persona rewrite of scheduled message #{} failed, sending it as written: {e}",
+                        message.id
+                    );
+                    message.content.clone()
+                }
+            },
+            None => message.content.clone(),
+        };
+        // Members can ping each other, but a queued message never pings roles or @everyone
+        ChannelId(message.channel_id.parse::<u64>()?)
+            .send_message(http, |m| {
+                m.content(content)
+                    .allowed_mentions(|a| a.parse(ParseValue::Users))
+            })
+            .await?;
+        Ok(())
+    }
+
+    async fn rewrite(&self, message: &ScheduledMessage, persona: &str) -> Result<String> {
+        let theme = self
+            .database
+            .get_guild_setting(&message.guild_id, ACTIVE_THEME_SETTING)
+            .await?;
+        let persona_prompt = apply_theme(
+            self.persona_manager.get_system_prompt(persona, None),
+            theme.as_deref(),
+        );
+        let grounding = ChannelGrounding::for_channel(
+            &self.database,
+            Some(&message.guild_id),
+            Some(&message.channel_id),
+        )
+        .await?;
+        self.rewriter
+            .rewrite(message, &persona_prompt, &grounding)
+            .await
+    }
+}
@@ -0,0 +1,157 @@
+//! # Channel Sentiment Feature
+//!
+//! Opt-in per channel (`/set_channel sentiment_tracking`): each stored message
+//! is scored with [`ConflictDetector::get_sentiment_score`] and added to an
+//! hourly bucket for the channel. `/mood` and the TUI Stats screen render the
+//! buckets as a sparkline with a rolling average weighted by message count.
+//!
+//! [`ConflictDetector::get_sentiment_score`]: crate::features::conflict::ConflictDetector::get_sentiment_score
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with hourly buckets, rolling average, sparkline and mood labels
+
+use crate::database::SentimentPoint;
+
+/// Feature id for toggles
+pub const SENTIMENT_FEATURE: &str = "sentiment";
+
+/// Longest window /mood and the TUI look back over, in hours
+pub const MAX_MOOD_HOURS: i64 = 168;
+
+/// Hours averaged for the rolling mood
+pub const ROLLING_WINDOW_HOURS: i64 = 6;
+
+/// Blocks used by [`sparkline`], lowest first
+const SPARK_BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Unix timestamp of the start of the hour containing `timestamp`
+pub fn hour_start(timestamp: i64) -> i64 {
+    timestamp - timestamp.rem_euclid(3600)
+}
+
+/// Mean score over all points, weighted by message count; `None` without messages
+pub fn weighted_average(points: &[SentimentPoint]) -> Option<f64> {
+    let messages: i64 = points.iter().map(|p| p.messages).sum();
+    if messages == 0 {
+        return None;
+    }
+    let sum: f64 = points.iter().map(|p| p.score * p.messages as f64).sum();
+    Some(sum / messages as f64)
+}
+
+/// Weighted average over the `window_hours` hours ending with the hour containing `until`
+pub fn rolling_average(points: &[SentimentPoint], until: i64, window_hours: i64) -> Option<f64> {
+    weighted_average(&rolling_window(points, until, window_hours))
+}
+
+/// Points within the `window_hours` hours ending with the hour containing `until`
+pub fn rolling_window(
+    points: &[SentimentPoint],
+    until: i64,
+    window_hours: i64,
+) -> Vec<SentimentPoint> {
+    let cutoff = hour_start(until) - (window_hours - 1) * 3600;
+    points
+        .iter()
+        .filter(|p| p.hour >= cutoff && p.hour <= until)
+        .copied()
+        .collect()
+}
+
+/// One score per hour from `since` through `until`, `None` for quiet hours
+pub fn hourly_series(points: &[SentimentPoint], since: i64, until: i64) -> Vec<Option<f64>> {
+    let first = hour_start(since);
+    let mut hour = first;
+    let mut series = Vec::new();
+    while hour <= until {
+        series.push(
+            points
+                .iter()
+                .find(|p| p.hour == hour && p.messages > 0)
+                .map(|p| p.score),
+        );
+        hour += 3600;
+    }
+    series
+}
+
+/// Text sparkline mapping -1.0..1.0 onto block heights; quiet hours are spaces
+pub fn sparkline(values: &[Option<f64>]) -> String {
+    values
+        .iter()
+        .map(|value| match value {
+            Some(score) => {
+                let level = ((score.clamp(-1.0, 1.0) + 1.0) / 2.0 * 7.0).round() as usize;
+                SPARK_BLOCKS[level.min(7)]
+            }
+            None => ' ',
+        })
+        .collect()
+}
+
+/// Emoji and label for a mood score
+pub fn mood_label(score: f64) -> (&'static str, &'static str) {
+    if score >= 0.4 {
+        ("😄", "Very positive")
+    } else if score >= 0.1 {
+        ("🙂", "Positive")
+    } else if score > -0.1 {
+        ("😐", "Neutral")
+    } else if score > -0.4 {
+        ("😕", "Tense")
+    } else {
+        ("😠", "Heated")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(hour: i64, messages: i64, score: f64) -> SentimentPoint {
+        SentimentPoint {
+            hour: hour * 3600,
+            messages,
+            score,
+        }
+    }
+
+    #[test]
+    fn test_hour_start() {
+        assert_eq!(hour_start(7200), 7200);
+        assert_eq!(hour_start(7200 + 3599), 7200);
+        assert_eq!(hour_start(-1), -3600);
+    }
+
+    #[test]
+    fn test_averages() {
+        let points = vec![point(0, 1, -1.0), point(5, 3, 0.5), point(6, 1, 1.0)];
+        assert!((weighted_average(&points).unwrap() - 0.3).abs() < 1e-9);
+        // Only hours 5 and 6 fall in a 2-hour window ending in hour 6
+        assert!((rolling_average(&points, 6 * 3600 + 59, 2).unwrap() - 0.625).abs() < 1e-9);
+        assert_eq!(rolling_window(&points, 6 * 3600, 2).len(), 2);
+        assert_eq!(weighted_average(&[]), None);
+        assert_eq!(rolling_average(&points, 20 * 3600, 6), None);
+    }
+
+    #[test]
+    fn test_hourly_series_and_sparkline() {
+        let points = vec![point(1, 2, -1.0), point(3, 1, 1.0)];
+        let series = hourly_series(&points, 3600 + 60, 3 * 3600 + 10);
+        assert_eq!(series, vec![Some(-1.0), None, Some(1.0)]);
+        assert_eq!(sparkline(&series), "▁ █");
+        assert_eq!(sparkline(&[Some(0.0)]), "▅");
+    }
+
+    #[test]
+    fn test_mood_label() {
+        assert_eq!(mood_label(0.6).1, "Very positive");
+        assert_eq!(mood_label(0.0).1, "Neutral");
+        assert_eq!(mood_label(-0.2).1, "Tense");
+        assert_eq!(mood_label(-0.9).1, "Heated");
+    }
+}
@@ -0,0 +1,158 @@
+//! # Server Report Feature
+//!
+//! A weekly "state of the server" report admins schedule with `/report
+//! schedule`: message volume and its trend, the busiest channels, trending
+//! topics clustered from stored messages, conflict incidents, bot usage and
+//! spend, wrapped in a short narrative written by the guild's default persona
+//! and posted as an embed in an admin channel.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with weekly schedule, keyword topics and persona narrative
+
+pub mod reporter;
+pub mod scheduler;
+pub mod topics;
+
+pub use reporter::ServerReporter;
+pub use scheduler::ReportScheduler;
+pub use topics::{cluster_topics, Topic};
+
+use chrono::{DateTime, Datelike, Timelike, Utc, Weekday};
+
+use crate::core::local_time;
+
+/// Feature id for toggles
+pub const REPORT_FEATURE: &str = "server_report";
+
+/// Guild setting holding the channel reports are posted in
+pub const CHANNEL_SETTING: &str = "server_report_channel";
+
+/// Guild setting holding the report weekday (`mon`..`sun`)
+pub const DAY_SETTING: &str = "server_report_day";
+
+/// Guild setting holding the local hour reports go out
+pub const HOUR_SETTING: &str = "server_report_hour";
+
+/// Local hour used when none was given
+pub const DEFAULT_HOUR: u32 = 9;
+
+/// Guild setting holding the UTC offset in minutes
+pub const OFFSET_SETTING: &str = "server_report_utc_offset";
+
+/// Guild setting holding the ISO week of the last report sent
+pub const LAST_SENT_SETTING: &str = "server_report_last_week";
+
+/// Days each report covers
+pub const REPORT_DAYS: i64 = 7;
+
+/// Parse a weekday name or its first three letters
+pub fn parse_weekday(value: &str) -> Option<Weekday> {
+    let value = value.trim().to_lowercase();
+    std::iter::successors(Some(Weekday::Mon), |day| Some(day.succ()))
+        .take(7)
+        .find(|day| {
+            let name = weekday_name(*day).to_lowercase();
+            value == name || value == name[..3]
+        })
+}
+
+/// Full English name of a weekday
+pub fn weekday_name(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "Monday",
+        Weekday::Tue => "Tuesday",
+        Weekday::Wed => "Wednesday",
+        Weekday::Thu => "Thursday",
+        Weekday::Fri => "Friday",
+        Weekday::Sat => "Saturday",
+        Weekday::Sun => "Sunday",
+    }
+}
+
+/// ISO week key such as `2026-W42`
+pub fn week_key(now: DateTime<Utc>, utc_offset_minutes: i32) -> String {
+    let week = local_time(now, utc_offset_minutes).date().iso_week();
+    format!("{}-W{:02}", week.year(), week.week())
+}
+
+/// ISO week whose report is due at `now`, if it hasn't been sent
+///
+/// A report is due from the scheduled day and hour until the end of that
+/// (local) week, so a bot that was down at the time still sends it late
+/// rather than skipping a week.
+pub fn report_due(
+    day: Weekday,
+    hour: u32,
+    utc_offset_minutes: i32,
+    last_sent: Option<&str>,
+    now: DateTime<Utc>,
+) -> Option<String> {
+    let local = local_time(now, utc_offset_minutes);
+    let today = local.weekday().num_days_from_monday();
+    let scheduled = day.num_days_from_monday();
+    let reached = today > scheduled || (today == scheduled && local.hour() >= hour);
+    let week = week_key(now, utc_offset_minutes);
+    (reached && last_sent != Some(week.as_str())).then_some(week)
+}
+
+/// Week-over-week change, e.g. `+25%`, `-10%`, or `new` when last week was empty
+pub fn format_trend(current: i64, previous: i64) -> String {
+    if previous == 0 {
+        return if current == 0 { "±0%" } else { "new" }.to_string();
+    }
+    let change = (current - previous) as f64 / previous as f64 * 100.0;
+    if change.round() == 0.0 {
+        "±0%".to_string()
+    } else {
+        format!("{change:+.0}%")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_weekday() {
+        assert_eq!(parse_weekday("mon"), Some(Weekday::Mon));
+        assert_eq!(parse_weekday("Friday"), Some(Weekday::Fri));
+        assert_eq!(parse_weekday(" SUN "), Some(Weekday::Sun));
+        assert_eq!(parse_weekday("mondays"), None);
+        assert_eq!(parse_weekday("xy"), None);
+    }
+
+    #[test]
+    fn test_report_due() {
+        // Wednesday 2026-10-14 10:00 UTC, ISO week 42
+        let now = Utc.with_ymd_and_hms(2026, 10, 14, 10, 0, 0).unwrap();
+        assert_eq!(week_key(now, 0), "2026-W42");
+
+        assert_eq!(
+            report_due(Weekday::Mon, 9, 0, None, now),
+            Some("2026-W42".to_string())
+        );
+        assert_eq!(report_due(Weekday::Mon, 9, 0, Some("2026-W42"), now), None);
+        assert_eq!(
+            report_due(Weekday::Wed, 10, 0, Some("2026-W41"), now),
+            Some("2026-W42".to_string())
+        );
+        assert_eq!(report_due(Weekday::Wed, 11, 0, None, now), None);
+        assert_eq!(report_due(Weekday::Fri, 9, 0, None, now), None);
+        // Already 11:00 on Wednesday at UTC+1
+        assert!(report_due(Weekday::Wed, 11, 60, None, now).is_some());
+    }
+
+    #[test]
+    fn test_format_trend() {
+        assert_eq!(format_trend(125, 100), "+25%");
+        assert_eq!(format_trend(90, 100), "-10%");
+        assert_eq!(format_trend(100, 100), "±0%");
+        assert_eq!(format_trend(5, 0), "new");
+        assert_eq!(format_trend(0, 0), "±0%");
+    }
+}
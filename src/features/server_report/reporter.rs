@@ -0,0 +1,312 @@
+//! Aggregate a guild's week and write it up as a persona-narrated embed
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use log::warn;
+use openai::chat::{ChatCompletionMessage, ChatCompletionMessageRole};
+use serenity::builder::CreateEmbed;
+use std::sync::Arc;
+
+use super::{cluster_topics, format_trend, Topic, REPORT_DAYS};
+use crate::core::{persona_embed, ChatClient};
+use crate::database::{Database, ServerActivity};
+use crate::features::analytics::{CostBucket, UsageTracker};
+use crate::features::guardrails::ChannelGrounding;
+use crate::features::personas::themes::{apply_theme, ACTIVE_THEME_SETTING};
+use crate::features::personas::PersonaManager;
+
+/// Stored messages clustered into topics
+const MAX_TOPIC_MESSAGES: i64 = 500;
+
+/// Topics shown in a report
+const MAX_TOPICS: usize = 5;
+
+/// Cap on the narrative's generated tokens
+const MAX_NARRATIVE_TOKENS: u64 = 400;
+
+/// User id usage is logged under for scheduled reports
+const REPORT_USER_ID: &str = "system_report";
+
+/// Builds weekly server reports
+#[derive(Clone)]
+pub struct ServerReporter {
+    database: Database,
+    persona_manager: PersonaManager,
+    chat_client: Arc<dyn ChatClient>,
+    openai_model: String,
+    usage_tracker: UsageTracker,
+}
+
+impl ServerReporter {
+    pub fn new(
+        database: Database,
+        chat_client: Arc<dyn ChatClient>,
+        openai_model: String,
+        usage_tracker: UsageTracker,
+    ) -> Self {
+        Self {
+            database,
+            persona_manager: PersonaManager::new(),
+            chat_client,
+            openai_model,
+            usage_tracker,
+        }
+    }
+
+    /// Build the report embed for the last [`REPORT_DAYS`] days of a guild
+    pub async fn build(&self, guild_id: &str, channel_id: &str) -> Result<CreateEmbed> {
+        let activity = self
+            .database
+            .get_server_activity(guild_id, REPORT_DAYS)
+            .await?;
+        let messages = self
+            .database
+            .get_recent_guild_message_texts(guild_id, REPORT_DAYS, MAX_TOPIC_MESSAGES)
+            .await?;
+        let topics = cluster_topics(&messages, MAX_TOPICS);
+
+        let persona_id = self
+            .database
+            .get_guild_setting(guild_id, "default_persona")
+            .await?
+            .unwrap_or_else(|| "obi".to_string());
+        let persona = self
+            .persona_manager
+            .get_persona_with_portrait(&persona_id)
+            .or_else(|| self.persona_manager.get_persona_with_portrait("obi"))
+            .ok_or_else(|| anyhow::anyhow!("No persona available for the report"))?;
+        let theme = self
+            .database
+            .get_guild_setting(guild_id, ACTIVE_THEME_SETTING)
+            .await?;
+        let system_prompt = apply_theme(
+            self.persona_manager.get_system_prompt(&persona_id, None),
+            theme.as_deref(),
+        );
+        let grounding =
+            ChannelGrounding::for_channel(&self.database, Some(guild_id), Some(channel_id)).await?;
+
+        let facts = format_facts(&activity, &topics);
+        let narrative = self
+            .write_narrative(&system_prompt, &grounding, &facts, guild_id, channel_id)
+            .await
+            .unwrap_or_else(|e| {
+                warn!("⚠️ Failed to write server report narrative for {guild_id}: {e}");
+                "Here's how the server did this week.".to_string()
+            });
+
+        let mut embed = persona_embed(&persona, &narrative);
+        embed.title("📊 State of the Server");
+        add_activity_fields(&mut embed, &activity, &topics);
+        embed.footer(|f| f.text(format!("Last {REPORT_DAYS} days")));
+        Ok(embed)
+    }
+
+    async fn write_narrative(
+        &self,
+        persona_prompt: &str,
+        grounding: &ChannelGrounding,
+        facts: &str,
+        guild_id: &str,
+        channel_id: &str,
+    ) -> Result<String> {
+        let system_prompt = format!(
+            "{persona_prompt}\n\n\
+            Your task is to write this week's \"state of the server\" report for the \
+            server's admins, in your characteristic style. Use only the facts given. \
+            Write 1-2 short paragraphs: how busy the server was compared to last week, \
+            what people talked about, and anything worth an admin's attention (conflicts, \
+            spend). No headings or bullet lists; the numbers are shown beside your text."
+        );
+        let completion = self
+            .chat_client
+            .create_chat_completion_limited(
+                &self.openai_model,
+                vec![
+                    ChatCompletionMessage {
+                        role: ChatCompletionMessageRole::System,
+                        content: Some(grounding.persona_prompt(&system_prompt)),
+                        name: None,
+                        function_call: None,
+                        tool_call_id: None,
+                        tool_calls: None,
+                    },
+                    ChatCompletionMessage {
+                        role: ChatCompletionMessageRole::User,
+                        content: Some(facts.to_string()),
+                        name: None,
+                        function_call: None,
+                        tool_call_id: None,
+                        tool_calls: None,
+                    },
+                ],
+                Some(MAX_NARRATIVE_TOKENS),
+            )
+            .await?;
+
+        if let Some(usage) = &completion.usage {
+            self.usage_tracker.log_chat(
+                &self.openai_model,
+                usage.prompt_tokens,
+                usage.completion_tokens,
+                usage.total_tokens,
+                REPORT_USER_ID,
+                Some(guild_id),
+                Some(channel_id),
+                None,
+                CostBucket::Report,
+            );
+        }
+
+        completion
+            .choices
+            .first()
+            .and_then(|choice| choice.message.content.clone())
+            .filter(|content| !content.trim().is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Empty completion"))
+    }
+}
+
+/// The week's numbers as plain text for the narrative prompt
+fn format_facts(activity: &ServerActivity, topics: &[Topic]) -> String {
+    let mut facts = format!(
+        "Messages this week: {} ({} vs the week before, which had {})\n\
+         Active members: {}\n\
+         Conflicts detected: {} ({} mediated)\n\
+         Bot requests: {}, AI spend ${:.2}\n",
+        activity.messages,
+        format_trend(activity.messages, activity.previous_messages),
+        activity.previous_messages,
+        activity.active_members,
+        activity.conflicts,
+        activity.mediations,
+        activity.bot_requests,
+        activity.cost_usd,
+    );
+    if !activity.top_features.is_empty() {
+        let features: Vec<String> = activity
+            .top_features
+            .iter()
+            .take(3)
+            .map(|(feature, requests)| format!("{feature} ({requests})"))
+            .collect();
+        facts.push_str(&format!(
+            "Most used bot features: {}\n",
+            features.join(", ")
+        ));
+    }
+    if topics.is_empty() {
+        facts.push_str("Topics: no clear topics this week\n");
+    } else {
+        facts.push_str("Topics (messages, examples):\n");
+        for topic in topics {
+            facts.push_str(&format!(
+                "- {} ({}): {}\n",
+                topic.label,
+                topic.messages,
+                topic.examples.join(" | ")
+            ));
+        }
+    }
+    facts
+}
+
+fn add_activity_fields(embed: &mut CreateEmbed, activity: &ServerActivity, topics: &[Topic]) {
+    embed.field(
+        "💬 Messages",
+        format!(
+            "{} ({})",
+            activity.messages,
+            format_trend(activity.messages, activity.previous_messages)
+        ),
+        true,
+    );
+    embed.field(
+        "👥 Active members",
+        activity.active_members.to_string(),
+        true,
+    );
+    embed.field(
+        "⚖️ Conflicts",
+        format!("{} ({} mediated)", activity.conflicts, activity.mediations),
+        true,
+    );
+
+    if !activity.top_channels.is_empty() {
+        let channels: Vec<String> = activity
+            .top_channels
+            .iter()
+            .map(|(channel_id, messages)| format!("<#{channel_id}> · {messages}"))
+            .collect();
+        embed.field("📈 Busiest channels", channels.join("\n"), true);
+    }
+    if !topics.is_empty() {
+        let lines: Vec<String> = topics
+            .iter()
+            .map(|topic| format!("**{}** · {}", topic.label, topic.messages))
+            .collect();
+        embed.field("🔥 Trending topics", lines.join("\n"), true);
+    }
+
+    let features: Vec<String> = activity
+        .top_features
+        .iter()
+        .take(3)
+        .map(|(feature, requests)| format!("{feature} · {requests}"))
+        .collect();
+    embed.field(
+        "🤖 Bot usage",
+        format!(
+            "{} requests · ${:.2}{}",
+            activity.bot_requests,
+            activity.cost_usd,
+            if features.is_empty() {
+                String::new()
+            } else {
+                format!("\n{}", features.join("\n"))
+            }
+        ),
+        false,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_facts() {
+        let activity = ServerActivity {
+            days: 7,
+            messages: 120,
+            previous_messages: 100,
+            active_members: 14,
+            top_channels: vec![("1".to_string(), 80)],
+            conflicts: 2,
+            mediations: 1,
+            bot_requests: 30,
+            top_features: vec![("ask".to_string(), 20), ("fetch".to_string(), 10)],
+            cost_usd: 0.4567,
+        };
+        let topics = vec![Topic {
+            label: "release / build".to_string(),
+            messages: 12,
+            examples: vec!["the build is red".to_string()],
+        }];
+        let facts = format_facts(&activity, &topics);
+        assert!(facts.contains("Messages this week: 120 (+20% vs the week before, which had 100)"));
+        assert!(facts.contains("Conflicts detected: 2 (1 mediated)"));
+        assert!(facts.contains("AI spend $0.46"));
+        assert!(facts.contains("Most used bot features: ask (20), fetch (10)"));
+        assert!(facts.contains("- release / build (12): the build is red"));
+
+        let quiet = format_facts(&ServerActivity::default(), &[]);
+        assert!(quiet.contains("no clear topics"));
+        assert!(!quiet.contains("Most used"));
+    }
+}
@@ -0,0 +1,113 @@
+//! Background task posting scheduled weekly server reports
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use chrono::Utc;
+use log::{debug, error, info, warn};
+use serenity::http::Http;
+use serenity::model::id::ChannelId;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::interval;
+
+use super::{
+    parse_weekday, report_due, ServerReporter, CHANNEL_SETTING, DAY_SETTING, DEFAULT_HOUR,
+    HOUR_SETTING, LAST_SENT_SETTING, OFFSET_SETTING, REPORT_FEATURE,
+};
+use crate::database::Database;
+
+pub struct ReportScheduler {
+    database: Database,
+    reporter: ServerReporter,
+}
+
+impl ReportScheduler {
+    pub fn new(database: Database, reporter: ServerReporter) -> Self {
+        Self { database, reporter }
+    }
+
+    /// Start the report scheduler loop
+    /// This should be spawned as a tokio task
+    pub async fn run(&self, http: Arc<Http>) {
+        let mut check_interval = interval(Duration::from_secs(15 * 60));
+
+        info!("📊 Server report scheduler started");
+
+        loop {
+            check_interval.tick().await;
+
+            if let Err(e) = self.process_due_reports(&http).await {
+                error!("❌ Error processing server reports: {e}");
+            }
+        }
+    }
+
+    async fn process_due_reports(&self, http: &Arc<Http>) -> Result<()> {
+        let now = Utc::now();
+        for guild_id in self
+            .database
+            .get_guilds_with_settings(&[CHANNEL_SETTING])
+            .await?
+        {
+            if !self
+                .database
+                .is_feature_enabled(REPORT_FEATURE, None, Some(&guild_id))
+                .await?
+            {
+                continue;
+            }
+            let db = &self.database;
+            let Some(channel_id) = db.get_guild_setting(&guild_id, CHANNEL_SETTING).await? else {
+                continue;
+            };
+            let day = db.get_guild_setting(&guild_id, DAY_SETTING).await?;
+            let Some(day) = day.as_deref().and_then(parse_weekday) else {
+                continue;
+            };
+            let hour: u32 = db
+                .get_guild_setting(&guild_id, HOUR_SETTING)
+                .await?
+                .and_then(|h| h.parse().ok())
+                .unwrap_or(DEFAULT_HOUR);
+            let offset: i32 = db
+                .get_guild_setting(&guild_id, OFFSET_SETTING)
+                .await?
+                .and_then(|o| o.parse().ok())
+                .unwrap_or(0);
+            let last_sent = db.get_guild_setting(&guild_id, LAST_SENT_SETTING).await?;
+
+            let Some(week) = report_due(day, hour, offset, last_sent.as_deref(), now) else {
+                debug!("📊 No server report due for guild {guild_id}");
+                continue;
+            };
+
+            match self.deliver_report(http, &guild_id, &channel_id).await {
+                Ok(_) => info!("📊 Posted {week} server report for guild {guild_id}"),
+                Err(e) => warn!("⚠️ Failed to post server report for guild {guild_id}: {e}"),
+            }
+            // Marked either way so a broken channel doesn't retry every 15 minutes
+            self.database
+                .set_guild_setting(&guild_id, LAST_SENT_SETTING, &week)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn deliver_report(
+        &self,
+        http: &Arc<Http>,
+        guild_id: &str,
+        channel_id: &str,
+    ) -> Result<()> {
+        let embed = self.reporter.build(guild_id, channel_id).await?;
+        ChannelId(channel_id.parse::<u64>()?)
+            .send_message(http, |m| m.set_embed(embed))
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,245 @@
+//! Keyword clustering of stored messages into discussion topics
+//!
+//! Messages are grouped greedily: the word used in the most (still
+//! ungrouped) messages becomes a topic and takes every message containing it,
+//! then the next word is picked from what's left. Cheap enough to run over a
+//! week of messages without an API call.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Messages a word must appear in to count as a topic
+const MIN_TOPIC_MESSAGES: usize = 3;
+
+/// Example messages kept per topic
+const EXAMPLES_PER_TOPIC: usize = 2;
+
+/// Characters kept of each example message
+const MAX_EXAMPLE_CHARS: usize = 120;
+
+/// Shortest word considered
+const MIN_WORD_CHARS: usize = 4;
+
+/// Common words that say nothing about what is being discussed
+const STOPWORDS: &[&str] = &[
+    "about",
+    "after",
+    "again",
+    "also",
+    "anyone",
+    "anything",
+    "been",
+    "before",
+    "being",
+    "could",
+    "didn't",
+    "does",
+    "doesn't",
+    "doing",
+    "don't",
+    "even",
+    "every",
+    "from",
+    "going",
+    "gonna",
+    "good",
+    "have",
+    "haven't",
+    "here",
+    "just",
+    "know",
+    "like",
+    "lmao",
+    "looks",
+    "make",
+    "maybe",
+    "more",
+    "much",
+    "need",
+    "only",
+    "other",
+    "people",
+    "pretty",
+    "really",
+    "right",
+    "same",
+    "should",
+    "some",
+    "something",
+    "still",
+    "sure",
+    "than",
+    "thank",
+    "thanks",
+    "that",
+    "that's",
+    "their",
+    "them",
+    "then",
+    "there",
+    "these",
+    "they",
+    "thing",
+    "things",
+    "think",
+    "this",
+    "those",
+    "time",
+    "today",
+    "very",
+    "want",
+    "wasn't",
+    "well",
+    "were",
+    "what",
+    "what's",
+    "when",
+    "where",
+    "which",
+    "while",
+    "will",
+    "with",
+    "won't",
+    "would",
+    "yeah",
+    "your",
+    "you're",
+];
+
+/// A discussion topic found in a set of messages
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Topic {
+    /// Defining word, plus a frequent companion word when there is one
+    pub label: String,
+    pub messages: usize,
+    pub examples: Vec<String>,
+}
+
+/// Distinct topic words in a message
+fn words(message: &str) -> BTreeSet<String> {
+    message
+        .split_whitespace()
+        .filter(|token| !token.contains("://") && !token.starts_with('<'))
+        .flat_map(|token| token.split(|c: char| !c.is_alphanumeric() && c != '\''))
+        .map(|word| word.trim_matches('\'').to_lowercase())
+        .filter(|word| {
+            word.chars().count() >= MIN_WORD_CHARS
+                && !word.chars().all(|c| c.is_ascii_digit())
+                && !STOPWORDS.contains(&word.as_str())
+        })
+        .collect()
+}
+
+/// Group messages into at most `max_topics` topics, largest first
+pub fn cluster_topics(messages: &[String], max_topics: usize) -> Vec<Topic> {
+    let mut remaining: Vec<(&String, BTreeSet<String>)> =
+        messages.iter().map(|m| (m, words(m))).collect();
+    let mut topics = Vec::new();
+
+    while topics.len() < max_topics {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for (_, message_words) in &remaining {
+            for word in message_words {
+                *counts.entry(word.as_str()).or_default() += 1;
+            }
+        }
+        // BTreeMap order makes ties resolve alphabetically, so output is stable
+        let Some((keyword, count)) = counts.into_iter().fold(
+            None,
+            |best: Option<(&str, usize)>, (word, count)| match best {
+                Some((_, best_count)) if best_count >= count => best,
+                _ => Some((word, count)),
+            },
+        ) else {
+            break;
+        };
+        if count < MIN_TOPIC_MESSAGES {
+            break;
+        }
+        let keyword = keyword.to_string();
+
+        let (members, rest): (Vec<_>, Vec<_>) = remaining
+            .into_iter()
+            .partition(|(_, message_words)| message_words.contains(&keyword));
+        remaining = rest;
+
+        topics.push(Topic {
+            label: label(&keyword, &members),
+            messages: members.len(),
+            examples: members
+                .iter()
+                .take(EXAMPLES_PER_TOPIC)
+                .map(|(message, _)| message.chars().take(MAX_EXAMPLE_CHARS).collect())
+                .collect(),
+        });
+    }
+
+    topics
+}
+
+/// `keyword`, or `keyword / companion` when another word shows up in at
+/// least half of the topic's messages
+fn label(keyword: &str, members: &[(&String, BTreeSet<String>)]) -> String {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for (_, message_words) in members {
+        for word in message_words.iter().filter(|w| w.as_str() != keyword) {
+            *counts.entry(word.as_str()).or_default() += 1;
+        }
+    }
+    let companion = counts
+        .into_iter()
+        .filter(|(_, count)| count * 2 >= members.len() && *count >= 2)
+        .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(a.0)));
+    match companion {
+        Some((word, _)) => format!("{keyword} / {word}"),
+        None => keyword.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(texts: &[&str]) -> Vec<String> {
+        texts.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn test_words() {
+        let found = words("Anyone tried the new Release? https://example.com/release <@123> 2024");
+        assert_eq!(
+            found,
+            BTreeSet::from(["release".to_string(), "tried".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_cluster_topics() {
+        let msgs = messages(&[
+            "the release build is broken",
+            "release notes are up",
+            "who is fixing the release build?",
+            "raid tonight at nine",
+            "raid signups are open",
+            "bring potions to the raid",
+            "random chatter",
+        ]);
+        let topics = cluster_topics(&msgs, 5);
+        assert_eq!(topics.len(), 2);
+        assert_eq!(topics[0].label, "raid");
+        assert_eq!(topics[0].messages, 3);
+        assert_eq!(topics[1].label, "release / build");
+        assert_eq!(
+            topics[1].examples,
+            vec!["the release build is broken", "release notes are up"]
+        );
+
+        assert_eq!(cluster_topics(&msgs, 1).len(), 1);
+        assert!(cluster_topics(&messages(&["hello", "world"]), 5).is_empty());
+    }
+}
@@ -0,0 +1,328 @@
+//! # Standup Feature
+//!
+//! Daily async standups: at a guild's configured local time the bot DMs each
+//! member who joined with `/standup join`, asks for yesterday / today /
+//! blockers one question at a time, and posts a compiled summary in the team
+//! channel once the collection window closes.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+pub mod scheduler;
+
+pub use scheduler::StandupScheduler;
+
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Timelike, Utc, Weekday};
+use log::info;
+
+use crate::core::local_time;
+use crate::database::{Database, StandupConfig, StandupEntry, StandupMember};
+
+/// Feature id for toggles
+pub const STANDUP_FEATURE: &str = "standup";
+
+/// Default minutes members have to reply
+pub const DEFAULT_COLLECT_MINUTES: u32 = 120;
+pub const MIN_COLLECT_MINUTES: u32 = 15;
+pub const MAX_COLLECT_MINUTES: u32 = 720;
+
+/// Longest answer kept per question
+pub const MAX_ANSWER_CHARS: usize = 600;
+
+/// Questions asked in order; `StandupEntry::step` counts the answered ones
+pub const QUESTIONS: [&str; 3] = [
+    "What did you get done yesterday?",
+    "What are you working on today?",
+    "Is anything blocking you?",
+];
+
+/// Parse a 24-hour `HH:MM` time into minutes after midnight
+pub fn parse_time(input: &str) -> Result<u32, &'static str> {
+    const ERROR: &str = "Use a 24-hour time like 09:30";
+    let (hours, minutes) = input.trim().split_once(':').ok_or(ERROR)?;
+    let hours: u32 = hours.parse().map_err(|_| ERROR)?;
+    let minutes: u32 = minutes.parse().map_err(|_| ERROR)?;
+    if hours > 23 || minutes > 59 {
+        return Err(ERROR);
+    }
+    Ok(hours * 60 + minutes)
+}
+
+/// Format minutes after midnight as `HH:MM`
+pub fn format_time(minute_of_day: u32) -> String {
+    format!("{:02}:{:02}", minute_of_day / 60, minute_of_day % 60)
+}
+
+/// Local date of `now` for a guild, as stored in `standup_entries`
+pub fn local_date(now: DateTime<Utc>, utc_offset_minutes: i32) -> NaiveDate {
+    local_time(now, utc_offset_minutes).date()
+}
+
+/// Local date on which today's prompts are due, if they are and haven't gone out
+///
+/// Prompts are only sent until the collection window would have closed, so a
+/// bot that was down all morning doesn't ask for a standup at midnight.
+pub fn prompt_due(config: &StandupConfig, now: DateTime<Utc>) -> Option<NaiveDate> {
+    let local = local_time(now, config.utc_offset_minutes);
+    let date = local.date();
+    if config.weekdays_only && matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+        return None;
+    }
+    let minute = local.hour() * 60 + local.minute();
+    let already = config.last_prompt_date.as_deref() == Some(date_key(date).as_str());
+    (!already
+        && minute >= config.prompt_minute
+        && minute < config.prompt_minute + config.collect_minutes)
+        .then_some(date)
+}
+
+/// Local date whose summary is due, once the collection window has closed
+pub fn summary_due(config: &StandupConfig, now: DateTime<Utc>) -> Option<String> {
+    let date = config.last_prompt_date.as_ref()?;
+    let prompted_at = config.last_prompt_at?;
+    let closes_at = prompted_at + i64::from(config.collect_minutes) * 60;
+    (config.last_summary_date.as_ref() != Some(date) && now.timestamp() >= closes_at)
+        .then(|| date.clone())
+}
+
+/// `YYYY-MM-DD` key for a local date
+pub fn date_key(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// Whether a member's `/standup skip` covers `date`
+pub fn is_skipping(member: &StandupMember, date: &str) -> bool {
+    member
+        .skip_until
+        .as_deref()
+        .is_some_and(|until| until >= date)
+}
+
+/// Last local date covered by skipping `days` standups starting `today`
+pub fn skip_until(today: NaiveDate, days: u32) -> String {
+    date_key(today + Duration::days(i64::from(days.max(1)) - 1))
+}
+
+/// Whether a DM reply means "skip today's standup"
+pub fn is_skip_reply(text: &str) -> bool {
+    matches!(
+        text.trim().trim_start_matches('/').to_lowercase().as_str(),
+        "skip" | "pass"
+    )
+}
+
+/// Whether a blockers answer actually reports a blocker
+pub fn has_blocker(answer: &str) -> bool {
+    !matches!(
+        answer
+            .trim()
+            .trim_end_matches(['.', '!'])
+            .to_lowercase()
+            .as_str(),
+        "" | "no" | "none" | "nope" | "nothing" | "n/a" | "na" | "-" | "nah" | "all good"
+    )
+}
+
+/// Store `text` as the answer to the entry's current question
+///
+/// Returns the next question, or `None` once all three are answered.
+pub fn apply_answer(entry: &mut StandupEntry, text: &str) -> Option<&'static str> {
+    let answer: String = text.trim().chars().take(MAX_ANSWER_CHARS).collect();
+    match entry.step {
+        0 => entry.yesterday = Some(answer),
+        1 => entry.today = Some(answer),
+        _ => entry.blockers = Some(answer),
+    }
+    entry.step = (entry.step + 1).min(QUESTIONS.len() as u32);
+    QUESTIONS.get(entry.step as usize).copied()
+}
+
+/// Record a DM as the member's answer to their oldest open standup
+///
+/// Returns the bot's reply, or `None` when the member has no open standup
+/// and the DM should be handled as a normal conversation.
+pub async fn handle_dm_reply(
+    database: &Database,
+    user_id: &str,
+    text: &str,
+) -> Result<Option<String>> {
+    if text.trim().is_empty() {
+        return Ok(None);
+    }
+    let Some(mut entry) = database.get_open_standup_entry(user_id).await? else {
+        return Ok(None);
+    };
+
+    let mut reply = if is_skip_reply(text) {
+        entry.skipped = true;
+        "👍 Skipped. I'll leave you out of today's summary.".to_string()
+    } else {
+        match apply_answer(&mut entry, text) {
+            Some(question) => question.to_string(),
+            None => "✅ Thanks, your standup is in!".to_string(),
+        }
+    };
+    database.save_standup_entry(&entry).await?;
+    info!(
+        "🗒️ Standup answer {} from {user_id} for guild {} on {}",
+        entry.step, entry.guild_id, entry.standup_date
+    );
+
+    // Another guild's standup may be waiting on the same member
+    if entry.skipped || entry.step as usize == QUESTIONS.len() {
+        if let Some(next) = database.get_open_standup_entry(user_id).await? {
+            reply.push_str(&format!(
+                "\n\nYou have another standup waiting. {}",
+                QUESTIONS[next.step as usize]
+            ));
+        }
+    }
+    Ok(Some(reply))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn config() -> StandupConfig {
+        StandupConfig {
+            guild_id: "g1".to_string(),
+            channel_id: "c1".to_string(),
+            prompt_minute: 9 * 60 + 30,
+            utc_offset_minutes: 120,
+            collect_minutes: 60,
+            weekdays_only: true,
+            enabled: true,
+            last_prompt_date: None,
+            last_prompt_at: None,
+            last_summary_date: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_and_format_time() {
+        assert_eq!(parse_time("09:30"), Ok(570));
+        assert_eq!(parse_time(" 0:05 "), Ok(5));
+        assert!(parse_time("24:00").is_err());
+        assert!(parse_time("9.30").is_err());
+        assert_eq!(format_time(570), "09:30");
+    }
+
+    #[test]
+    fn test_prompt_due_in_local_window() {
+        let config = config();
+        // Thursday 2026-10-15, 07:29 UTC is 09:29 at UTC+2
+        let early = Utc.with_ymd_and_hms(2026, 10, 15, 7, 29, 0).unwrap();
+        assert_eq!(prompt_due(&config, early), None);
+        let on_time = Utc.with_ymd_and_hms(2026, 10, 15, 7, 30, 0).unwrap();
+        assert_eq!(
+            prompt_due(&config, on_time),
+            NaiveDate::from_ymd_opt(2026, 10, 15)
+        );
+        // Past the collection window the day is let go
+        let late = Utc.with_ymd_and_hms(2026, 10, 15, 8, 30, 0).unwrap();
+        assert_eq!(prompt_due(&config, late), None);
+
+        let prompted = StandupConfig {
+            last_prompt_date: Some("2026-10-15".to_string()),
+            ..config.clone()
+        };
+        assert_eq!(prompt_due(&prompted, on_time), None);
+
+        // Saturday
+        let weekend = Utc.with_ymd_and_hms(2026, 10, 17, 7, 45, 0).unwrap();
+        assert_eq!(prompt_due(&config, weekend), None);
+        let every_day = StandupConfig {
+            weekdays_only: false,
+            ..config
+        };
+        assert!(prompt_due(&every_day, weekend).is_some());
+    }
+
+    #[test]
+    fn test_summary_due_after_window() {
+        let prompted_at = Utc.with_ymd_and_hms(2026, 10, 15, 7, 30, 0).unwrap();
+        let config = StandupConfig {
+            last_prompt_date: Some("2026-10-15".to_string()),
+            last_prompt_at: Some(prompted_at.timestamp()),
+            ..config()
+        };
+        assert_eq!(
+            summary_due(&config, prompted_at + Duration::minutes(59)),
+            None
+        );
+        assert_eq!(
+            summary_due(&config, prompted_at + Duration::minutes(60)).as_deref(),
+            Some("2026-10-15")
+        );
+
+        let posted = StandupConfig {
+            last_summary_date: Some("2026-10-15".to_string()),
+            ..config
+        };
+        assert_eq!(summary_due(&posted, prompted_at + Duration::hours(3)), None);
+    }
+
+    #[test]
+    fn test_skip_helpers() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+        assert_eq!(skip_until(today, 1), "2026-10-15");
+        assert_eq!(skip_until(today, 3), "2026-10-17");
+
+        let member = StandupMember {
+            user_id: "u1".to_string(),
+            skip_until: Some("2026-10-17".to_string()),
+        };
+        assert!(is_skipping(&member, "2026-10-17"));
+        assert!(!is_skipping(&member, "2026-10-18"));
+
+        assert!(is_skip_reply(" Skip "));
+        assert!(is_skip_reply("/skip"));
+        assert!(!is_skip_reply("skipped lunch"));
+    }
+
+    #[test]
+    fn test_apply_answer_walks_questions() {
+        let mut entry = StandupEntry::default();
+        assert_eq!(
+            apply_answer(&mut entry, "Fixed the login bug"),
+            Some(QUESTIONS[1])
+        );
+        assert_eq!(apply_answer(&mut entry, "Reviews"), Some(QUESTIONS[2]));
+        assert_eq!(apply_answer(&mut entry, "None."), None);
+        assert_eq!(entry.step, 3);
+        assert_eq!(entry.yesterday.as_deref(), Some("Fixed the login bug"));
+        assert!(!has_blocker(entry.blockers.as_deref().unwrap()));
+        assert!(has_blocker("Waiting on API keys"));
+    }
+
+    #[tokio::test]
+    async fn test_dm_replies_fill_entry_then_fall_through() {
+        let database = Database::new(":memory:").await.unwrap();
+        database.set_standup_config(&config()).await.unwrap();
+        database
+            .create_standup_entry("g1", "u1", "2026-10-15", false)
+            .await
+            .unwrap();
+
+        let reply = handle_dm_reply(&database, "u1", "Shipped it")
+            .await
+            .unwrap();
+        assert_eq!(reply.as_deref(), Some(QUESTIONS[1]));
+        handle_dm_reply(&database, "u1", "Testing").await.unwrap();
+        let done = handle_dm_reply(&database, "u1", "none").await.unwrap();
+        assert!(done.unwrap().contains("your standup is in"));
+
+        // With nothing open, DMs go back to normal conversation
+        assert_eq!(
+            handle_dm_reply(&database, "u1", "hello").await.unwrap(),
+            None
+        );
+    }
+}
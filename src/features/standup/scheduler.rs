@@ -0,0 +1,339 @@
+//! Background task sending standup prompts and posting summaries
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use chrono::{NaiveDate, Utc};
+use log::{debug, error, info, warn};
+use serenity::builder::CreateEmbed;
+use serenity::http::Http;
+use serenity::model::id::{ChannelId, GuildId, UserId};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::interval;
+
+use super::{
+    date_key, has_blocker, is_skipping, prompt_due, summary_due, QUESTIONS, STANDUP_FEATURE,
+};
+use crate::database::{Database, StandupConfig, StandupEntry};
+
+/// Members shown with their own field in a summary (Discord allows 25)
+const MAX_SUMMARY_FIELDS: usize = 20;
+
+/// Longest answer shown in a summary field
+const MAX_FIELD_ANSWER_CHARS: usize = 300;
+
+pub struct StandupScheduler {
+    database: Database,
+}
+
+impl StandupScheduler {
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+
+    /// Start the standup scheduler loop
+    /// This should be spawned as a tokio task
+    pub async fn run(&self, http: Arc<Http>) {
+        let mut check_interval = interval(Duration::from_secs(60));
+
+        info!("🗒️ Standup scheduler started");
+
+        loop {
+            check_interval.tick().await;
+
+            if let Err(e) = self.process_standups(&http).await {
+                error!("❌ Error processing standups: {e}");
+            }
+        }
+    }
+
+    async fn process_standups(&self, http: &Arc<Http>) -> Result<()> {
+        let now = Utc::now();
+        for config in self.database.get_standup_configs().await? {
+            if !self
+                .database
+                .is_feature_enabled(STANDUP_FEATURE, None, Some(&config.guild_id))
+                .await?
+            {
+                continue;
+            }
+
+            if let Some(date) = summary_due(&config, now) {
+                if let Err(e) = self.post_summary(http, &config, &date).await {
+                    warn!(
+                        "⚠️ Failed to post standup summary for guild {}: {e}",
+                        config.guild_id
+                    );
+                }
+                // Marked either way so a broken channel doesn't retry every minute
+                self.database
+                    .mark_standup_summarized(&config.guild_id, &date)
+                    .await?;
+            }
+
+            if let Some(date) = prompt_due(&config, now) {
+                self.send_prompts(http, &config, date).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// DM every member who isn't skipping today
+    async fn send_prompts(
+        &self,
+        http: &Arc<Http>,
+        config: &StandupConfig,
+        date: NaiveDate,
+    ) -> Result<()> {
+        let guild_id = config.guild_id.as_str();
+        let date = date_key(date);
+        // Marked first so a crash mid-round doesn't prompt everyone twice
+        self.database
+            .mark_standup_prompted(guild_id, &date, Utc::now().timestamp())
+            .await?;
+
+        let members = self.database.get_standup_members(guild_id).await?;
+        if members.is_empty() {
+            debug!("🗒️ No standup members in guild {guild_id}");
+            return Ok(());
+        }
+        let guild_name = match guild_id.parse::<u64>() {
+            Ok(id) => GuildId(id)
+                .to_partial_guild(http)
+                .await
+                .map(|g| g.name)
+                .unwrap_or_else(|_| "your team".to_string()),
+            Err(_) => "your team".to_string(),
+        };
+
+        let mut prompted = 0;
+        for member in &members {
+            let skipping = is_skipping(member, &date);
+            self.database
+                .create_standup_entry(guild_id, &member.user_id, &date, skipping)
+                .await?;
+            if skipping {
+                continue;
+            }
+            match self.prompt_member(http, &member.user_id, &guild_name).await {
+                Ok(_) => prompted += 1,
+                Err(e) => warn!(
+                    "⚠️ Failed to DM standup prompt to {} for guild {guild_id}: {e}",
+                    member.user_id
+                ),
+            }
+        }
+        info!(
+            "🗒️ Sent {prompted}/{} standup prompts for guild {guild_id} ({date})",
+            members.len()
+        );
+        Ok(())
+    }
+
+    async fn prompt_member(&self, http: &Arc<Http>, user_id: &str, guild_name: &str) -> Result<()> {
+        let user = UserId(user_id.parse()?);
+        let dm = user.create_dm_channel(http).await?;
+        dm.say(
+            http,
+            format!(
+                "🗒️ **Daily standup for {guild_name}**\n\
+                Three quick questions; just reply here. Say `skip` to sit this one out.\n\n{}",
+                QUESTIONS[0]
+            ),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn post_summary(
+        &self,
+        http: &Arc<Http>,
+        config: &StandupConfig,
+        date: &str,
+    ) -> Result<()> {
+        let entries = self
+            .database
+            .get_standup_entries(&config.guild_id, date)
+            .await?;
+        if entries.is_empty() {
+            debug!(
+                "🗒️ No standup entries for guild {} on {date}",
+                config.guild_id
+            );
+            return Ok(());
+        }
+
+        let mut names = HashMap::new();
+        for entry in entries.iter().filter(|e| e.step > 0 && !e.skipped) {
+            let name = match entry.user_id.parse::<u64>() {
+                Ok(id) => UserId(id)
+                    .to_user(http)
+                    .await
+                    .map(|u| u.name)
+                    .unwrap_or_else(|_| entry.user_id.clone()),
+                Err(_) => entry.user_id.clone(),
+            };
+            names.insert(entry.user_id.clone(), name);
+        }
+
+        let channel = ChannelId(config.channel_id.parse()?);
+        let embed = summary_embed(date, &entries, &names);
+        channel.send_message(http, |m| m.set_embed(embed)).await?;
+        info!(
+            "🗒️ Posted standup summary for guild {} ({date}, {} entries)",
+            config.guild_id,
+            entries.len()
+        );
+        Ok(())
+    }
+}
+
+fn clip(answer: &str) -> String {
+    if answer.chars().count() > MAX_FIELD_ANSWER_CHARS {
+        let clipped: String = answer.chars().take(MAX_FIELD_ANSWER_CHARS - 1).collect();
+        format!("{clipped}…")
+    } else {
+        answer.to_string()
+    }
+}
+
+/// Compile a day's entries into one embed; `names` maps user IDs to display names
+fn summary_embed(
+    date: &str,
+    entries: &[StandupEntry],
+    names: &HashMap<String, String>,
+) -> CreateEmbed {
+    let answered: Vec<&StandupEntry> = entries
+        .iter()
+        .filter(|e| e.step > 0 && !e.skipped)
+        .collect();
+    let skipped: Vec<String> = entries
+        .iter()
+        .filter(|e| e.skipped)
+        .map(|e| format!("<@{}>", e.user_id))
+        .collect();
+    let missing: Vec<String> = entries
+        .iter()
+        .filter(|e| e.step == 0 && !e.skipped)
+        .map(|e| format!("<@{}>", e.user_id))
+        .collect();
+    let blocked: Vec<String> = answered
+        .iter()
+        .filter(|e| e.blockers.as_deref().is_some_and(has_blocker))
+        .map(|e| format!("<@{}>", e.user_id))
+        .collect();
+
+    let mut description = String::new();
+    if answered.is_empty() {
+        description.push_str("Nobody answered today.\n");
+    }
+    if !blocked.is_empty() {
+        description.push_str(&format!("🚧 **Blocked:** {}\n", blocked.join(", ")));
+    }
+    if !skipped.is_empty() {
+        description.push_str(&format!("⏭️ Skipped: {}\n", skipped.join(", ")));
+    }
+    if !missing.is_empty() {
+        description.push_str(&format!("🔇 No reply: {}\n", missing.join(", ")));
+    }
+
+    let mut embed = CreateEmbed::default();
+    embed
+        .title(format!("🗒️ Standup: {date}"))
+        .description(description)
+        .footer(|f| {
+            f.text(format!(
+                "{} answered · {} skipped · {} no reply",
+                answered.len(),
+                skipped.len(),
+                missing.len()
+            ))
+        })
+        .color(if blocked.is_empty() {
+            0x2ECC71
+        } else {
+            0xE67E22
+        });
+
+    for entry in answered.iter().take(MAX_SUMMARY_FIELDS) {
+        let mut value = String::new();
+        for (label, answer) in [
+            ("Yesterday", &entry.yesterday),
+            ("Today", &entry.today),
+            ("Blockers", &entry.blockers),
+        ] {
+            if let Some(answer) = answer {
+                value.push_str(&format!("**{label}:** {}\n", clip(answer)));
+            }
+        }
+        let name = names
+            .get(&entry.user_id)
+            .cloned()
+            .unwrap_or_else(|| entry.user_id.clone());
+        embed.field(name, value, false);
+    }
+    if answered.len() > MAX_SUMMARY_FIELDS {
+        embed.field(
+            "…",
+            format!("and {} more answers", answered.len() - MAX_SUMMARY_FIELDS),
+            false,
+        );
+    }
+    embed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(user: &str, step: u32, skipped: bool) -> StandupEntry {
+        StandupEntry {
+            guild_id: "g1".to_string(),
+            user_id: user.to_string(),
+            standup_date: "2026-10-15".to_string(),
+            step,
+            skipped,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_summary_embed_groups_members() {
+        let mut ada = entry("1", 3, false);
+        ada.yesterday = Some("Parser".to_string());
+        ada.today = Some("Tests".to_string());
+        ada.blockers = Some("Waiting on CI access".to_string());
+        let mut grace = entry("2", 2, false);
+        grace.yesterday = Some("Docs".to_string());
+        grace.today = Some("Release".to_string());
+        let entries = vec![ada, grace, entry("3", 0, true), entry("4", 0, false)];
+        let names = HashMap::from([("1".to_string(), "ada".to_string())]);
+
+        let embed = summary_embed("2026-10-15", &entries, &names);
+        let description = embed.0["description"].as_str().unwrap();
+        assert!(description.contains("🚧 **Blocked:** <@1>"));
+        assert!(description.contains("Skipped: <@3>"));
+        assert!(description.contains("No reply: <@4>"));
+
+        let fields = embed.0["fields"].as_array().unwrap();
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0]["name"], "ada");
+        assert!(fields[0]["value"]
+            .as_str()
+            .unwrap()
+            .contains("**Blockers:** Waiting on CI access"));
+        // Unresolved names fall back to the ID; missing answers are left out
+        assert_eq!(fields[1]["name"], "2");
+        assert!(!fields[1]["value"].as_str().unwrap().contains("Blockers"));
+        assert_eq!(
+            embed.0["footer"]["text"],
+            "2 answered · 1 skipped · 1 no reply"
+        );
+    }
+}
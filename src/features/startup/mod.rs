@@ -2,10 +2,12 @@
 //!
 //! Rich notifications when bot comes online.
 //!
-//! - **Version**: 1.1.0
+//! - **Version**: 1.2.0
 //! - **Since**: 0.4.0
 //! - **Toggleable**: true
 
 pub mod notification;
+pub mod report;
 
 pub use notification::StartupNotifier;
+pub use report::{DeploymentDiff, PluginLoadStatus, StartupReport};
@@ -4,11 +4,13 @@
 //! Supports DM to bot owner and/or specific guild channels.
 //! Configuration is stored in the database and managed via /set_guild_setting.
 //!
-//! - **Version**: 1.9.0
+//! - **Version**: 1.11.0
 //! - **Since**: 0.4.0
 //! - **Toggleable**: true
 //!
 //! ## Changelog
+//! - 1.11.0: DM the previous run's crash report on first Ready
+//! - 1.10.0: Previous version, feature-version diff, migrations, plugin load status and degraded-start warnings
 //! - 1.9.0: Store DM notifications in conversation history for context continuity
 //! - 1.8.0: Add configurable commit counts for DM and channel notifications
 //! - 1.7.0: Add GitHub commit links to startup notification and thread messages
@@ -23,6 +25,10 @@
 use crate::database::Database;
 use crate::features::get_bot_version;
 use crate::features::plugins::Plugin;
+use crate::features::resilience::deliver_pending_crash_report;
+use crate::features::startup::report::{
+    current_feature_versions, record_deployment, DeploymentDiff, StartupReport,
+};
 use log::{info, warn};
 use serenity::builder::CreateEmbed;
 use serenity::http::Http;
@@ -224,12 +230,22 @@ pub fn format_commit_for_thread(commit: &CommitInfo, repo_url: Option<&str>) ->
 /// Handles sending startup notifications to configured destinations
 pub struct StartupNotifier {
     database: Arc<Database>,
+    report: StartupReport,
 }
 
 impl StartupNotifier {
     /// Creates a new StartupNotifier with database access
     pub fn new(database: Arc<Database>) -> Self {
-        Self { database }
+        Self {
+            database,
+            report: StartupReport::default(),
+        }
+    }
+
+    /// Include migration, plugin and degraded-subsystem results from startup
+    pub fn with_report(mut self, report: StartupReport) -> Self {
+        self.report = report;
+        self
     }
 
     /// Sends startup notifications if enabled and this is the first Ready event
@@ -240,6 +256,22 @@ impl StartupNotifier {
             return;
         }
 
+        // Record this boot's versions even when notifications are off, so the
+        // next diff is always against the previous boot
+        let deployment = match record_deployment(&self.database).await {
+            Ok(diff) => diff,
+            Err(e) => {
+                warn!("Failed to record deployment versions: {e}");
+                DeploymentDiff::compute(None, None, get_bot_version(), &current_feature_versions())
+            }
+        };
+        if self.report.is_degraded() {
+            warn!("Degraded start: {}", self.report.degraded.join("; "));
+        }
+
+        // Crash reports go to the owner whether or not notifications are enabled
+        deliver_pending_crash_report(http, &self.database).await;
+
         // Read settings from database
         let enabled = self
             .database
@@ -276,8 +308,9 @@ impl StartupNotifier {
             return;
         }
 
-        let embed = Self::build_embed(ready, plugins);
-        let notification_text = Self::build_notification_text(ready, plugins);
+        let embed = Self::build_embed(ready, plugins, &self.report, &deployment);
+        let notification_text =
+            Self::build_notification_text(ready, plugins, &self.report, &deployment);
 
         // Send to owner DM (includes commit messages inline since DMs can't have threads)
         if let Some(oid) = owner_id {
@@ -300,7 +333,12 @@ impl StartupNotifier {
     }
 
     /// Builds the rich embed for the startup notification
-    fn build_embed(ready: &Ready, plugins: &[Plugin]) -> CreateEmbed {
+    fn build_embed(
+        ready: &Ready,
+        plugins: &[Plugin],
+        report: &StartupReport,
+        deployment: &DeploymentDiff,
+    ) -> CreateEmbed {
         let version = get_bot_version();
         let timestamp = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -309,10 +347,16 @@ impl StartupNotifier {
 
         let mut embed = CreateEmbed::default();
 
-        // Title and color
-        embed
-            .title(format!("{} is Online!", ready.user.name))
-            .color(Color::from_rgb(87, 242, 135)); // Discord green
+        // Title and color (amber when optional subsystems failed)
+        if report.is_degraded() {
+            embed
+                .title(format!("{} is Online (Degraded)", ready.user.name))
+                .color(Color::from_rgb(250, 166, 26));
+        } else {
+            embed
+                .title(format!("{} is Online!", ready.user.name))
+                .color(Color::from_rgb(87, 242, 135)); // Discord green
+        }
 
         // Include updateMessage.txt content as description if it exists
         if let Ok(update_message) = std::fs::read_to_string("updateMessage.txt") {
@@ -330,6 +374,7 @@ impl StartupNotifier {
 
         // Basic info fields (inline)
         embed.field("Version", format!("`v{version}`"), true);
+        embed.field("Previous Version", deployment.version_line(), true);
         embed.field("Guilds", ready.guilds.len().to_string(), true);
 
         // Shard info if available
@@ -337,6 +382,17 @@ impl StartupNotifier {
             embed.field("Shard", format!("{}/{}", shard[0] + 1, shard[1]), true);
         }
 
+        if report.is_degraded() {
+            let warnings: Vec<String> = report.degraded.iter().map(|d| format!("- {d}")).collect();
+            embed.field("⚠️ Degraded Start", warnings.join("\n"), false);
+        }
+
+        if let Some(changes) = deployment.feature_summary() {
+            embed.field("Feature Changes", changes, false);
+        }
+        embed.field("Migrations", report.migration_summary(), false);
+        embed.field("Plugin Loading", report.plugins.summary(), false);
+
         // Plugin versions in multi-column layout
         let enabled_plugins: Vec<_> = plugins.iter().filter(|p| p.enabled).collect();
         if !enabled_plugins.is_empty() {
@@ -379,15 +435,36 @@ impl StartupNotifier {
     }
 
     /// Builds a plain text representation of the startup notification for conversation history
-    fn build_notification_text(ready: &Ready, plugins: &[Plugin]) -> String {
+    fn build_notification_text(
+        ready: &Ready,
+        plugins: &[Plugin],
+        report: &StartupReport,
+        deployment: &DeploymentDiff,
+    ) -> String {
         let version = get_bot_version();
         let mut text = format!(
-            "🟢 **{} is Online!**\n\nVersion: v{}\nConnected to {} guild(s)",
+            "🟢 **{} is Online!**\n\nVersion: v{}\nPrevious: {}\nConnected to {} guild(s)",
             ready.user.name,
             version,
+            deployment.version_line(),
             ready.guilds.len()
         );
 
+        if report.is_degraded() {
+            text.push_str(&format!(
+                "\n\n**⚠️ Degraded Start:**\n- {}",
+                report.degraded.join("\n- ")
+            ));
+        }
+        if let Some(changes) = deployment.feature_summary() {
+            text.push_str(&format!("\n\n**Feature Changes:**\n{changes}"));
+        }
+        text.push_str(&format!(
+            "\n\n**Migrations:** {}\n**Plugin Loading:** {}",
+            report.migration_summary(),
+            report.plugins.summary()
+        ));
+
         // Include updateMessage.txt content if it exists
         if let Ok(update_message) = std::fs::read_to_string("updateMessage.txt") {
             let trimmed = update_message.trim();
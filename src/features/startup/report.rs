@@ -0,0 +1,371 @@
+//! # Startup Report
+//!
+//! What changed since the previous boot and what didn't come up cleanly:
+//! the previously running version, feature versions that moved, schema
+//! migrations, plugin loading and optional subsystems that failed to start.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use crate::database::{Database, MigrationReport};
+use crate::features::{get_bot_version, get_features};
+
+/// bot_settings key holding the version that last booted
+pub const LAST_VERSION_KEY: &str = "last_boot_version";
+
+/// bot_settings key holding the feature versions that last booted (JSON map)
+pub const LAST_FEATURES_KEY: &str = "last_boot_features";
+
+/// Embed field values are capped at 1024 characters
+const FIELD_CHARS: usize = 1024;
+
+/// How the plugin configuration loaded
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum PluginLoadStatus {
+    /// No plugin config on disk
+    #[default]
+    NotConfigured,
+    Loaded {
+        count: usize,
+        source: String,
+    },
+    Failed(String),
+}
+
+impl PluginLoadStatus {
+    pub fn summary(&self) -> String {
+        match self {
+            PluginLoadStatus::NotConfigured => "No plugin config found".to_string(),
+            PluginLoadStatus::Loaded { count, source } => {
+                format!("✅ {count} plugin(s) loaded from {source}")
+            }
+            PluginLoadStatus::Failed(e) => format!("❌ Failed to load: {e}"),
+        }
+    }
+}
+
+/// Startup results gathered in `main` before the gateway connects
+#[derive(Debug, Clone, Default)]
+pub struct StartupReport {
+    pub migrations: MigrationReport,
+    pub plugins: PluginLoadStatus,
+    /// Optional subsystems that failed to start, with the error
+    pub degraded: Vec<String>,
+}
+
+impl StartupReport {
+    pub fn new(migrations: MigrationReport) -> Self {
+        Self {
+            migrations,
+            ..Default::default()
+        }
+    }
+
+    /// Record an optional subsystem that failed to start
+    pub fn degraded(&mut self, subsystem: &str, error: impl Display) {
+        self.degraded.push(format!("{subsystem}: {error}"));
+    }
+
+    pub fn is_degraded(&self) -> bool {
+        !self.degraded.is_empty()
+    }
+
+    /// One-paragraph summary of the schema changes
+    pub fn migration_summary(&self) -> String {
+        let report = &self.migrations;
+        if report.is_empty() {
+            return format!("No schema changes ({} tables)", report.table_count);
+        }
+
+        let mut parts = Vec::new();
+        if !report.created_tables.is_empty() {
+            parts.push(format!(
+                "Created {} table(s): {}",
+                report.created_tables.len(),
+                report.created_tables.join(", ")
+            ));
+        }
+        if !report.added_columns.is_empty() {
+            parts.push(format!(
+                "Added {} column(s): {}",
+                report.added_columns.len(),
+                report.added_columns.join(", ")
+            ));
+        }
+        cap_chars(&parts.join("\n"), FIELD_CHARS)
+    }
+}
+
+/// A feature whose version differs from the previous boot
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeatureChange {
+    pub id: String,
+    /// None: the feature is new
+    pub from: Option<String>,
+    /// None: the feature was removed
+    pub to: Option<String>,
+}
+
+impl FeatureChange {
+    fn line(&self) -> String {
+        match (&self.from, &self.to) {
+            (None, Some(to)) => format!("+ {} `{to}`", self.id),
+            (Some(from), None) => format!("- {} `{from}`", self.id),
+            (Some(from), Some(to)) => format!("~ {} `{from}` → `{to}`", self.id),
+            (None, None) => format!("? {}", self.id),
+        }
+    }
+}
+
+/// What was deployed since the previous boot
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeploymentDiff {
+    pub previous_version: Option<String>,
+    pub current_version: String,
+    pub feature_changes: Vec<FeatureChange>,
+}
+
+impl DeploymentDiff {
+    /// Compare the previous boot's stored versions with the current ones
+    ///
+    /// Without stored feature versions (first boot) no feature changes are listed.
+    pub fn compute(
+        previous_version: Option<String>,
+        previous_features: Option<&str>,
+        current_version: &str,
+        current_features: &BTreeMap<String, String>,
+    ) -> Self {
+        let previous: Option<BTreeMap<String, String>> =
+            previous_features.and_then(|json| serde_json::from_str(json).ok());
+
+        let mut feature_changes = Vec::new();
+        if let Some(previous) = previous {
+            for (id, version) in current_features {
+                match previous.get(id) {
+                    Some(old) if old == version => {}
+                    old => feature_changes.push(FeatureChange {
+                        id: id.clone(),
+                        from: old.cloned(),
+                        to: Some(version.clone()),
+                    }),
+                }
+            }
+            for (id, old) in &previous {
+                if !current_features.contains_key(id) {
+                    feature_changes.push(FeatureChange {
+                        id: id.clone(),
+                        from: Some(old.clone()),
+                        to: None,
+                    });
+                }
+            }
+        }
+
+        Self {
+            previous_version,
+            current_version: current_version.to_string(),
+            feature_changes,
+        }
+    }
+
+    /// "`v1` → `v2`", a restart note, or a first-boot note
+    pub fn version_line(&self) -> String {
+        match &self.previous_version {
+            None => "First recorded boot".to_string(),
+            Some(previous) if *previous == self.current_version => {
+                format!("`v{previous}` (restart, same version)")
+            }
+            Some(previous) => format!("`v{previous}` → `v{}`", self.current_version),
+        }
+    }
+
+    /// Feature changes, one per line, capped to fit an embed field
+    pub fn feature_summary(&self) -> Option<String> {
+        if self.feature_changes.is_empty() {
+            return None;
+        }
+        let lines: Vec<String> = self
+            .feature_changes
+            .iter()
+            .map(FeatureChange::line)
+            .collect();
+        Some(cap_lines(&lines, FIELD_CHARS))
+    }
+}
+
+/// Current feature id → version map from the registry
+pub fn current_feature_versions() -> BTreeMap<String, String> {
+    get_features()
+        .iter()
+        .map(|f| (f.id.to_string(), f.version.to_string()))
+        .collect()
+}
+
+/// Diff against the previous boot, then store this boot's versions
+pub async fn record_deployment(database: &Database) -> Result<DeploymentDiff> {
+    let previous_version = database.get_bot_setting(LAST_VERSION_KEY).await?;
+    let previous_features = database.get_bot_setting(LAST_FEATURES_KEY).await?;
+    let current_features = current_feature_versions();
+
+    let diff = DeploymentDiff::compute(
+        previous_version,
+        previous_features.as_deref(),
+        get_bot_version(),
+        &current_features,
+    );
+
+    database
+        .set_bot_setting(LAST_VERSION_KEY, get_bot_version())
+        .await?;
+    database
+        .set_bot_setting(
+            LAST_FEATURES_KEY,
+            &serde_json::to_string(&current_features)?,
+        )
+        .await?;
+
+    Ok(diff)
+}
+
+/// Join lines, replacing whatever doesn't fit with "… and N more"
+fn cap_lines(lines: &[String], max_chars: usize) -> String {
+    let mut out = String::new();
+    for (i, line) in lines.iter().enumerate() {
+        let remaining = lines.len() - i;
+        let more = format!("\n… and {remaining} more");
+        if out.len() + line.len() + 1 + more.len() > max_chars {
+            out.push_str(more.trim_start_matches('\n'));
+            return out;
+        }
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(line);
+    }
+    out
+}
+
+/// Truncate to `max_chars` bytes on a char boundary
+fn cap_chars(text: &str, max_chars: usize) -> String {
+    if text.len() <= max_chars {
+        return text.to_string();
+    }
+    let mut end = max_chars.saturating_sub('…'.len_utf8());
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}…", &text[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn features(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(id, v)| (id.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_deployment_diff_lists_added_changed_removed() {
+        let previous = serde_json::to_string(&features(&[
+            ("council", "2.1.0"),
+            ("debate", "1.0.0"),
+            ("old_thing", "0.9.0"),
+        ]))
+        .unwrap();
+        let current = features(&[("council", "2.2.0"), ("debate", "1.0.0"), ("fork", "1.0.0")]);
+
+        let diff = DeploymentDiff::compute(
+            Some("4.6.0".to_string()),
+            Some(&previous),
+            "4.6.1",
+            &current,
+        );
+
+        assert_eq!(diff.version_line(), "`v4.6.0` → `v4.6.1`");
+        let summary = diff.feature_summary().unwrap();
+        assert_eq!(
+            summary,
+            "~ council `2.1.0` → `2.2.0`\n+ fork `1.0.0`\n- old_thing `0.9.0`"
+        );
+    }
+
+    #[test]
+    fn test_first_boot_has_no_feature_changes() {
+        let current = features(&[("council", "2.2.0")]);
+        let diff = DeploymentDiff::compute(None, None, "4.6.1", &current);
+        assert_eq!(diff.version_line(), "First recorded boot");
+        assert!(diff.feature_summary().is_none());
+
+        let restart = DeploymentDiff::compute(Some("4.6.1".to_string()), None, "4.6.1", &current);
+        assert!(restart.version_line().contains("restart"));
+    }
+
+    #[tokio::test]
+    async fn test_record_deployment_persists_current_versions() {
+        let database = Database::new(":memory:").await.unwrap();
+        database
+            .set_bot_setting(LAST_VERSION_KEY, "0.0.1")
+            .await
+            .unwrap();
+
+        let diff = record_deployment(&database).await.unwrap();
+        assert_eq!(diff.previous_version.as_deref(), Some("0.0.1"));
+        assert!(diff.feature_changes.is_empty());
+
+        let diff = record_deployment(&database).await.unwrap();
+        assert_eq!(diff.previous_version.as_deref(), Some(get_bot_version()));
+        assert!(diff.feature_changes.is_empty());
+    }
+
+    #[test]
+    fn test_cap_lines() {
+        let lines: Vec<String> = (0..100).map(|i| format!("line number {i}")).collect();
+        let capped = cap_lines(&lines, 100);
+        assert!(capped.len() <= 100);
+        assert!(capped.ends_with("more"));
+        assert_eq!(cap_lines(&lines[..2], 100), "line number 0\nline number 1");
+    }
+
+    #[test]
+    fn test_report_summaries() {
+        let mut report = StartupReport::new(MigrationReport {
+            created_tables: vec!["category_settings".to_string()],
+            added_columns: vec!["plugin_jobs.parent_playlist_id".to_string()],
+            table_count: 40,
+        });
+        assert!(!report.is_degraded());
+        report.degraded("IPC server", "address in use");
+        assert!(report.is_degraded());
+        assert_eq!(report.degraded[0], "IPC server: address in use");
+
+        let summary = report.migration_summary();
+        assert!(summary.contains("Created 1 table(s): category_settings"));
+        assert!(summary.contains("Added 1 column(s): plugin_jobs.parent_playlist_id"));
+
+        let clean = StartupReport::new(MigrationReport {
+            table_count: 40,
+            ..Default::default()
+        });
+        assert_eq!(clean.migration_summary(), "No schema changes (40 tables)");
+
+        assert_eq!(
+            PluginLoadStatus::Loaded {
+                count: 3,
+                source: "plugins/".to_string()
+            }
+            .summary(),
+            "✅ 3 plugin(s) loaded from plugins/"
+        );
+    }
+}
@@ -0,0 +1,359 @@
+//! # Story Feature
+//!
+//! Collaborative stories in a thread: the persona and members take turns
+//! adding paragraphs until someone runs `/story conclude`, which writes an
+//! ending and exports the whole story as a Markdown file.
+//!
+//! - **Version**: 1.0.1
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.1: Persisted stories are restored at startup instead of per message
+//! - 1.0.0: Initial implementation with restart persistence
+
+pub mod writer;
+
+pub use writer::StoryWriter;
+
+use anyhow::Result;
+use dashmap::DashMap;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+use crate::database::Database;
+
+/// Feature id for toggles and the component_state namespace
+pub const STORY_FEATURE: &str = "story";
+
+/// How long an idle story can be picked back up after a restart
+pub const STORY_STATE_TTL_SECS: i64 = 30 * 24 * 60 * 60;
+
+/// Longest paragraph a member can add
+pub const MAX_CONTRIBUTION_CHARS: usize = 1500;
+
+/// Paragraphs after which the story has to be concluded
+pub const MAX_PARTS: usize = 60;
+
+/// Paragraphs sent back to the model when writing the next one
+const CONTEXT_PARTS: usize = 20;
+
+/// Whose paragraph comes next
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StoryTurn {
+    /// Waiting for any member to add a paragraph
+    Members,
+    /// The persona is writing; member messages are ignored until it's done
+    Persona,
+}
+
+/// One paragraph of the story
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoryPart {
+    /// Member ID, or None for the persona
+    pub author_id: Option<String>,
+    /// Display name at the time of writing
+    pub author_name: String,
+    pub text: String,
+}
+
+/// Active story state (keyed by thread ID)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoryState {
+    /// The premise the story was started with
+    pub premise: String,
+    /// Persona co-writing the story
+    pub persona_id: String,
+    /// User who started the story
+    pub initiator_id: String,
+    pub guild_id: Option<String>,
+    pub parts: Vec<StoryPart>,
+    pub turn: StoryTurn,
+}
+
+impl StoryState {
+    pub fn new(
+        premise: String,
+        persona_id: String,
+        initiator_id: String,
+        guild_id: Option<String>,
+    ) -> Self {
+        Self {
+            premise,
+            persona_id,
+            initiator_id,
+            guild_id,
+            parts: Vec::new(),
+            turn: StoryTurn::Persona,
+        }
+    }
+
+    /// Add a member's paragraph and hand the turn to the persona
+    pub fn add_member_part(&mut self, author_id: &str, author_name: &str, text: String) {
+        self.parts.push(StoryPart {
+            author_id: Some(author_id.to_string()),
+            author_name: author_name.to_string(),
+            text,
+        });
+        self.turn = StoryTurn::Persona;
+    }
+
+    /// Add the persona's paragraph and hand the turn back to members
+    pub fn add_persona_part(&mut self, persona_name: &str, text: String) {
+        self.parts.push(StoryPart {
+            author_id: None,
+            author_name: persona_name.to_string(),
+            text,
+        });
+        self.turn = StoryTurn::Members;
+    }
+
+    /// Whether the story has reached MAX_PARTS
+    pub fn is_full(&self) -> bool {
+        self.parts.len() >= MAX_PARTS
+    }
+
+    /// The story so far as plain prose, most recent paragraphs only
+    pub fn recent_text(&self) -> String {
+        let skip = self.parts.len().saturating_sub(CONTEXT_PARTS);
+        let mut text = if skip > 0 {
+            "[…earlier paragraphs omitted…]\n\n".to_string()
+        } else {
+            String::new()
+        };
+        text.push_str(
+            &self.parts[skip..]
+                .iter()
+                .map(|p| p.text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+        );
+        text
+    }
+
+    /// Members who contributed, in order of first paragraph
+    pub fn contributors(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for part in self.parts.iter().filter(|p| p.author_id.is_some()) {
+            if !names.contains(&part.author_name.as_str()) {
+                names.push(&part.author_name);
+            }
+        }
+        names
+    }
+
+    /// The full story as a Markdown document for export
+    pub fn to_markdown(&self, title: &str, persona_name: &str) -> String {
+        let mut doc = format!("# {title}\n\n*Premise: {}*\n\n", self.premise);
+        for part in &self.parts {
+            doc.push_str(&part.text);
+            doc.push_str("\n\n");
+        }
+        doc.push_str("---\n\n");
+        doc.push_str(&format!("Written by {persona_name}"));
+        let contributors = self.contributors();
+        if !contributors.is_empty() {
+            doc.push_str(&format!(" with {}", contributors.join(", ")));
+        }
+        doc.push('\n');
+        doc
+    }
+}
+
+/// Export filename for a story title, e.g. `the-door-in-the-sea.md`
+pub fn story_filename(title: &str) -> String {
+    let slug = title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    let slug: String = slug.chars().take(50).collect();
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "story.md".to_string()
+    } else {
+        format!("{slug}.md")
+    }
+}
+
+/// Global storage for active stories (keyed by thread ID)
+static ACTIVE_STORIES: OnceLock<DashMap<u64, StoryState>> = OnceLock::new();
+
+/// Get or initialize the active stories map
+pub fn get_active_stories() -> &'static DashMap<u64, StoryState> {
+    ACTIVE_STORIES.get_or_init(DashMap::new)
+}
+
+/// Persist the in-memory story for a thread (or forget it if the story ended)
+pub async fn save_story_state(database: &Database, thread_id: u64) -> Result<()> {
+    let snapshot = get_active_stories()
+        .get(&thread_id)
+        .map(|state| serde_json::to_string(&*state))
+        .transpose()?;
+    match snapshot {
+        Some(payload) => {
+            let expires_at = chrono::Utc::now().timestamp() + STORY_STATE_TTL_SECS;
+            database
+                .save_component_state(
+                    STORY_FEATURE,
+                    &thread_id.to_string(),
+                    &payload,
+                    Some(expires_at),
+                )
+                .await
+        }
+        None => forget_story_state(database, thread_id).await,
+    }
+}
+
+/// Like `save_story_state`, but only logs failures
+pub async fn save_story_state_logged(database: &Database, thread_id: u64) {
+    if let Err(e) = save_story_state(database, thread_id).await {
+        warn!("Failed to persist story state for thread {thread_id}: {e}");
+    }
+}
+
+/// Load a persisted story into memory if the process doesn't have it
+///
+/// Returns true when the story is available in memory afterwards.
+pub async fn restore_story_state(database: &Database, thread_id: u64) -> Result<bool> {
+    if get_active_stories().contains_key(&thread_id) {
+        return Ok(true);
+    }
+    let Some(payload) = database
+        .get_component_state(STORY_FEATURE, &thread_id.to_string())
+        .await?
+    else {
+        return Ok(false);
+    };
+
+    insert_snapshot(thread_id, &payload)?;
+    info!("Restored story state for thread {thread_id} from the database");
+    Ok(true)
+}
+
+/// Load every persisted story into memory, returning how many were restored
+///
+/// Run once at startup so per-message thread checks only read the in-memory map.
+pub async fn restore_all_story_states(database: &Database) -> Result<usize> {
+    let mut restored = 0;
+    for (state_key, payload) in database.list_component_states(STORY_FEATURE).await? {
+        let Ok(thread_id) = state_key.parse::<u64>() else {
+            continue;
+        };
+        match insert_snapshot(thread_id, &payload) {
+            Ok(()) => restored += 1,
+            Err(e) => warn!("Skipping unreadable story state for thread {thread_id}: {e}"),
+        }
+    }
+    Ok(restored)
+}
+
+/// Put a persisted story into the in-memory map unless the thread is already there
+fn insert_snapshot(thread_id: u64, payload: &str) -> Result<()> {
+    let mut state: StoryState = serde_json::from_str(payload)?;
+    // A paragraph that was being written when the process stopped is lost
+    state.turn = StoryTurn::Members;
+    get_active_stories().entry(thread_id).or_insert(state);
+    Ok(())
+}
+
+/// Drop persisted state for an ended story
+pub async fn forget_story_state(database: &Database, thread_id: u64) -> Result<()> {
+    database
+        .delete_component_state(STORY_FEATURE, &thread_id.to_string())
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn story() -> StoryState {
+        StoryState::new(
+            "A lighthouse keeper finds a door in the sea".to_string(),
+            "obi".to_string(),
+            "u1".to_string(),
+            Some("g1".to_string()),
+        )
+    }
+
+    #[test]
+    fn test_turns_alternate() {
+        let mut state = story();
+        assert_eq!(state.turn, StoryTurn::Persona);
+
+        state.add_persona_part("Obi-Wan", "The lamp flickered.".to_string());
+        assert_eq!(state.turn, StoryTurn::Members);
+
+        state.add_member_part("u2", "Ada", "A knock came from below.".to_string());
+        assert_eq!(state.turn, StoryTurn::Persona);
+        assert_eq!(state.parts[1].author_id.as_deref(), Some("u2"));
+    }
+
+    #[test]
+    fn test_recent_text_trims_old_parts() {
+        let mut state = story();
+        for i in 0..CONTEXT_PARTS + 3 {
+            state.add_persona_part("Obi-Wan", format!("Paragraph {i}."));
+        }
+        let text = state.recent_text();
+        assert!(text.starts_with("[…earlier paragraphs omitted…]"));
+        assert!(!text.contains("Paragraph 2."));
+        assert!(text.contains("Paragraph 3."));
+        assert!(text.ends_with(&format!("Paragraph {}.", CONTEXT_PARTS + 2)));
+    }
+
+    #[test]
+    fn test_markdown_export_credits_members_once() {
+        let mut state = story();
+        state.add_persona_part("Obi-Wan", "The lamp flickered.".to_string());
+        state.add_member_part("u2", "Ada", "A knock came from below.".to_string());
+        state.add_persona_part("Obi-Wan", "He went down.".to_string());
+        state.add_member_part("u2", "Ada", "The door was open.".to_string());
+        state.add_member_part("u3", "Grace", "Salt wind rushed in.".to_string());
+
+        let doc = state.to_markdown("The Door in the Sea", "Obi-Wan");
+        assert!(doc.starts_with("# The Door in the Sea\n\n*Premise: A lighthouse keeper"));
+        assert!(doc.contains("The lamp flickered.\n\nA knock came from below."));
+        assert!(doc.ends_with("Written by Obi-Wan with Ada, Grace\n"));
+    }
+
+    #[test]
+    fn test_story_filename() {
+        assert_eq!(
+            story_filename("The Door in the Sea!"),
+            "the-door-in-the-sea.md"
+        );
+        assert_eq!(story_filename("???"), "story.md");
+    }
+
+    #[tokio::test]
+    async fn test_story_state_survives_restart() {
+        let database = Database::new(":memory:").await.unwrap();
+        let thread_id = 920_000_000_001;
+
+        let mut state = story();
+        state.add_persona_part("Obi-Wan", "The lamp flickered.".to_string());
+        state.add_member_part("u2", "Ada", "A knock came from below.".to_string());
+        get_active_stories().insert(thread_id, state);
+        save_story_state(&database, thread_id).await.unwrap();
+
+        // Simulate a restart mid-paragraph
+        get_active_stories().remove(&thread_id);
+        assert!(restore_story_state(&database, thread_id).await.unwrap());
+        let restored = get_active_stories().get(&thread_id).unwrap().clone();
+        assert_eq!(restored.parts.len(), 2);
+        assert_eq!(restored.turn, StoryTurn::Members);
+
+        get_active_stories().remove(&thread_id);
+        assert!(restore_all_story_states(&database).await.unwrap() >= 1);
+        assert!(get_active_stories().contains_key(&thread_id));
+
+        get_active_stories().remove(&thread_id);
+        save_story_state(&database, thread_id).await.unwrap();
+        assert!(!restore_story_state(&database, thread_id).await.unwrap());
+    }
+}
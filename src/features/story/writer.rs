@@ -0,0 +1,370 @@
+//! # Story Writer
+//!
+//! Persona-voiced paragraphs and endings for collaborative stories.
+//!
+//! - **Version**: 1.2.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.2.0: with_scene appends a channel's roleplay scene to every paragraph
+//! - 1.1.0: with_guardrails applies a guild's guardrail profile to every paragraph
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use log::{error, info};
+use openai::chat::{ChatCompletionMessage, ChatCompletionMessageRole};
+use serenity::http::Http;
+use serenity::model::id::ChannelId;
+use std::sync::Arc;
+
+use super::{get_active_stories, save_story_state_logged, StoryState, StoryTurn};
+use crate::core::{persona_embed, ChatClient};
+use crate::database::Database;
+use crate::features::analytics::UsageTracker;
+use crate::features::council::TurnUsage;
+use crate::features::guardrails::GuardrailProfile;
+use crate::features::personas::PersonaManager;
+use crate::features::scenes;
+
+/// Longest title kept from the model's ending
+const MAX_TITLE_CHARS: usize = 80;
+
+/// What the persona is asked to write
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Beat {
+    Opening,
+    Continue,
+    Ending,
+}
+
+/// Writes the persona's side of a collaborative story
+#[derive(Clone)]
+pub struct StoryWriter {
+    chat_client: Arc<dyn ChatClient>,
+    model: String,
+    usage_tracker: UsageTracker,
+    persona_manager: PersonaManager,
+    guardrails: GuardrailProfile,
+    scene: Option<String>,
+}
+
+impl StoryWriter {
+    pub fn new(
+        chat_client: Arc<dyn ChatClient>,
+        model: String,
+        usage_tracker: UsageTracker,
+        persona_manager: PersonaManager,
+    ) -> Self {
+        Self {
+            chat_client,
+            model,
+            usage_tracker,
+            persona_manager,
+            guardrails: GuardrailProfile::default(),
+            scene: None,
+        }
+    }
+
+    /// Apply a guild's guardrail profile to the system prompt of every paragraph
+    pub fn with_guardrails(mut self, guardrails: GuardrailProfile) -> Self {
+        self.guardrails = guardrails;
+        self
+    }
+
+    /// Append a channel's roleplay scene to the system prompt of every paragraph
+    pub fn with_scene(mut self, scene: Option<String>) -> Self {
+        self.scene = scene;
+        self
+    }
+
+    /// The first paragraph, written from the premise alone
+    pub async fn opening(&self, state: &StoryState, usage: &TurnUsage) -> Result<String> {
+        self.write(state, Beat::Opening, usage).await
+    }
+
+    /// The persona's next paragraph, following on from the latest one
+    pub async fn next_paragraph(&self, state: &StoryState, usage: &TurnUsage) -> Result<String> {
+        self.write(state, Beat::Continue, usage).await
+    }
+
+    /// A closing paragraph and a title for the finished story
+    pub async fn ending(&self, state: &StoryState, usage: &TurnUsage) -> Result<(String, String)> {
+        let raw = self.write(state, Beat::Ending, usage).await?;
+        Ok(split_title(&raw, &state.premise))
+    }
+
+    /// Write and post the persona's paragraph for the story in `thread_id`,
+    /// then hand the turn back to members and persist the story
+    ///
+    /// A failed paragraph is skipped with an in-character note rather than
+    /// leaving the story stuck on the persona's turn.
+    pub async fn take_turn(
+        &self,
+        http: &Http,
+        database: &Database,
+        thread_id: ChannelId,
+        usage: &TurnUsage,
+    ) {
+        let Some(state) = get_active_stories().get(&thread_id.0).map(|s| s.clone()) else {
+            return;
+        };
+        let persona = self
+            .persona_manager
+            .get_persona_with_portrait(&state.persona_id);
+        let name = persona
+            .as_ref()
+            .map(|p| p.name.clone())
+            .unwrap_or_else(|| state.persona_id.clone());
+        let beat = if state.parts.is_empty() {
+            Beat::Opening
+        } else {
+            Beat::Continue
+        };
+
+        let _ = thread_id.broadcast_typing(http).await;
+        let posted = match self.write(&state, beat, usage).await {
+            Ok(paragraph) => {
+                let sent = match &persona {
+                    Some(persona) => {
+                        thread_id
+                            .send_message(http, |m| m.set_embed(persona_embed(persona, &paragraph)))
+                            .await
+                    }
+                    None => thread_id.say(http, &paragraph).await,
+                };
+                sent.map(|_| paragraph)
+                    .map_err(|e| anyhow::anyhow!("Failed to post paragraph: {e}"))
+            }
+            Err(e) => Err(e),
+        };
+
+        let paragraph = posted
+            .map_err(|e| error!("[{}] Story: {name} failed to write: {e}", usage.request_id))
+            .ok();
+        let wrote = paragraph.is_some();
+        let full = {
+            // Gone if the story was concluded while the paragraph was being written
+            let Some(mut story) = get_active_stories().get_mut(&thread_id.0) else {
+                return;
+            };
+            match paragraph {
+                Some(paragraph) => story.add_persona_part(&name, paragraph),
+                None => story.turn = StoryTurn::Members,
+            }
+            story.is_full()
+        };
+
+        if wrote {
+            info!(
+                "[{}] Story: {name} added a paragraph in {thread_id}",
+                usage.request_id
+            );
+        } else {
+            let _ = thread_id
+                .say(
+                    http,
+                    format!(
+                        "*{name} loses the thread for a moment… \
+                         add the next paragraph to keep the story going.*"
+                    ),
+                )
+                .await;
+        }
+        if full {
+            let _ = thread_id
+                .say(
+                    http,
+                    "📖 This story has reached its length limit. Run `/story conclude` \
+                     for the ending and the full text.",
+                )
+                .await;
+        }
+        save_story_state_logged(database, thread_id.0).await;
+    }
+
+    async fn write(&self, state: &StoryState, beat: Beat, usage: &TurnUsage) -> Result<String> {
+        let name = self
+            .persona_manager
+            .get_persona(&state.persona_id)
+            .map(|p| p.name.clone())
+            .unwrap_or_else(|| state.persona_id.clone());
+        let system_prompt = scenes::with_scene(
+            self.guardrails.apply(&story_prompt(
+                &self
+                    .persona_manager
+                    .get_system_prompt(&state.persona_id, None),
+                &name,
+                beat,
+            )),
+            self.scene.as_deref(),
+        );
+        let user_message = if state.parts.is_empty() {
+            format!("Premise: {}", state.premise)
+        } else {
+            format!(
+                "Premise: {}\n\nThe story so far:\n\n{}",
+                state.premise,
+                state.recent_text()
+            )
+        };
+
+        let messages = vec![
+            ChatCompletionMessage {
+                role: ChatCompletionMessageRole::System,
+                content: Some(system_prompt),
+                name: None,
+                function_call: None,
+                tool_call_id: None,
+                tool_calls: None,
+            },
+            ChatCompletionMessage {
+                role: ChatCompletionMessageRole::User,
+                content: Some(user_message),
+                name: None,
+                function_call: None,
+                tool_call_id: None,
+                tool_calls: None,
+            },
+        ];
+
+        let completion = self
+            .chat_client
+            .create_chat_completion(&self.model, messages)
+            .await?;
+        if let Some(tokens) = &completion.usage {
+            self.usage_tracker.log_chat(
+                &self.model,
+                tokens.prompt_tokens,
+                tokens.completion_tokens,
+                tokens.total_tokens,
+                &usage.user_id,
+                usage.guild_id.as_deref(),
+                Some(&usage.channel_id),
+                Some(&usage.request_id.to_string()),
+                usage.cost_bucket,
+            );
+        }
+        completion
+            .choices
+            .first()
+            .and_then(|c| c.message.content.clone())
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Empty story response"))
+    }
+}
+
+/// System prompt for one of the persona's story beats
+fn story_prompt(persona_prompt: &str, name: &str, beat: Beat) -> String {
+    let task = match beat {
+        Beat::Opening => {
+            "Open the story with a single paragraph that sets the scene and leaves \
+             room for others to take it somewhere."
+        }
+        Beat::Continue => {
+            "Write the next single paragraph. Build on what the last writer added, \
+             even if it surprises you, and end on something the next writer can pick up."
+        }
+        Beat::Ending => {
+            "Write one final paragraph that brings the story to a satisfying close, \
+             tying up the threads the writers started. On the first line write \
+             \"Title: \" followed by a short title for the whole story, then the paragraph."
+        }
+    };
+    format!(
+        "{persona_prompt}\n\n\
+        You are {name}, co-writing a story in turns with members of a Discord server. \
+        {task} Write only story prose in your own voice and style, 60-150 words, with no \
+        headings, commentary or questions to the readers."
+    )
+}
+
+/// Split an ending into (title, paragraph), falling back to the premise
+fn split_title(raw: &str, premise: &str) -> (String, String) {
+    let trimmed = raw.trim();
+    let (first, rest) = trimmed.split_once('\n').unwrap_or((trimmed, ""));
+    let title = first
+        .trim()
+        .trim_start_matches('#')
+        .trim()
+        .strip_prefix("Title:")
+        .map(|t| t.trim().trim_matches(|c| c == '*' || c == '"').trim());
+    match title {
+        Some(title) if !title.is_empty() && !rest.trim().is_empty() => (
+            title.chars().take(MAX_TITLE_CHARS).collect(),
+            rest.trim().to_string(),
+        ),
+        _ => (
+            premise.chars().take(MAX_TITLE_CHARS).collect(),
+            trimmed.to_string(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use crate::features::analytics::CostBucket;
+    use crate::testing::MockChatClient;
+    use uuid::Uuid;
+
+    async fn writer(client: Arc<MockChatClient>) -> StoryWriter {
+        let database = Database::new(":memory:").await.unwrap();
+        StoryWriter::new(
+            client,
+            "gpt-test".to_string(),
+            UsageTracker::new(database),
+            PersonaManager::new(),
+        )
+    }
+
+    fn usage() -> TurnUsage {
+        TurnUsage {
+            user_id: "u1".to_string(),
+            guild_id: None,
+            channel_id: "c1".to_string(),
+            request_id: Uuid::new_v4(),
+            cost_bucket: CostBucket::Story,
+        }
+    }
+
+    #[test]
+    fn test_split_title() {
+        let (title, ending) = split_title(
+            "Title: **The Door in the Sea**\nAnd the tide carried him home.",
+            "premise",
+        );
+        assert_eq!(title, "The Door in the Sea");
+        assert_eq!(ending, "And the tide carried him home.");
+
+        let (title, ending) = split_title("And the tide carried him home.", "A door in the sea");
+        assert_eq!(title, "A door in the sea");
+        assert_eq!(ending, "And the tide carried him home.");
+    }
+
+    #[tokio::test]
+    async fn test_next_paragraph_sends_story_so_far() {
+        let client = Arc::new(MockChatClient::with_default_reply("  The tide rose.  "));
+        let writer = writer(client.clone()).await;
+        let mut state = StoryState::new(
+            "A door in the sea".to_string(),
+            "obi".to_string(),
+            "u1".to_string(),
+            None,
+        );
+        state.add_persona_part("Obi-Wan", "The lamp flickered.".to_string());
+        state.add_member_part("u2", "Ada", "A knock came from below.".to_string());
+
+        let paragraph = writer.next_paragraph(&state, &usage()).await.unwrap();
+        assert_eq!(paragraph, "The tide rose.");
+
+        let request = &client.requests()[0];
+        let sent = request.last_user_message().unwrap();
+        assert!(sent.contains("The lamp flickered.\n\nA knock came from below."));
+        assert!(request
+            .system_prompt()
+            .unwrap()
+            .contains("Build on what the last writer added"));
+    }
+}
@@ -0,0 +1,97 @@
+//! Vote buttons on posted suggestions
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use serenity::model::application::interaction::message_component::MessageComponentInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::prelude::Context;
+
+use super::{
+    status_of, suggestion_buttons, suggestion_embed, SuggestionStatus, SUGGESTIONS_FEATURE,
+};
+use crate::commands::components::{ComponentHandler, ComponentId};
+use crate::commands::InteractionResponder;
+use crate::database::Database;
+
+/// Routes suggestion vote buttons
+pub struct SuggestionButtons {
+    database: Database,
+}
+
+impl SuggestionButtons {
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+}
+
+#[async_trait]
+impl ComponentHandler for SuggestionButtons {
+    fn features(&self) -> &'static [&'static str] {
+        &[SUGGESTIONS_FEATURE]
+    }
+
+    async fn handle_component(
+        &self,
+        ctx: &Context,
+        interaction: &MessageComponentInteraction,
+        id: &ComponentId,
+    ) -> Result<()> {
+        let parts = id.payload_parts();
+        let vote = match (id.action.as_str(), parts.get(1).copied()) {
+            ("vote", Some("up")) => 1,
+            ("vote", Some("down")) => -1,
+            _ => {
+                return InteractionResponder::for_component(interaction)
+                    .reply_ephemeral(ctx, "Unknown suggestion action.")
+                    .await
+            }
+        };
+        let suggestion = match parts.first().and_then(|p| p.parse().ok()) {
+            Some(suggestion_id) => self.database.get_suggestion(suggestion_id).await?,
+            None => None,
+        };
+        let Some(suggestion) = suggestion else {
+            return InteractionResponder::for_component(interaction)
+                .reply_ephemeral(ctx, "This suggestion no longer exists.")
+                .await;
+        };
+        if status_of(&suggestion) != SuggestionStatus::Open {
+            return InteractionResponder::for_component(interaction)
+                .reply_ephemeral(ctx, "Voting on this suggestion has closed.")
+                .await;
+        }
+
+        let user_id = interaction.user.id.to_string();
+        let now = self
+            .database
+            .cast_suggestion_vote(suggestion.id, &user_id, vote)
+            .await?;
+        info!(
+            "💡 Suggestion {} | User: {user_id} | Vote {now}",
+            suggestion.id
+        );
+
+        let Some(suggestion) = self.database.get_suggestion(suggestion.id).await? else {
+            return Ok(());
+        };
+        let embed = suggestion_embed(&suggestion);
+        let components = suggestion_buttons(&suggestion);
+        interaction
+            .create_interaction_response(&ctx.http, |response| {
+                response
+                    .kind(InteractionResponseType::UpdateMessage)
+                    .interaction_response_data(|message| {
+                        message.set_embed(embed).set_components(components)
+                    })
+            })
+            .await?;
+        Ok(())
+    }
+}
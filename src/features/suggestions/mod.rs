@@ -0,0 +1,296 @@
+//! # Suggestions Feature
+//!
+//! `/suggest` posts a member's idea to the channel picked with
+//! `/suggestions channel`, with upvote and downvote buttons. Votes are stored
+//! per member, so voting again withdraws and the other button switches sides.
+//! Admins mark suggestions Accepted, Declined or Implemented with
+//! `/suggestions mark`, which updates the posted embed and closes voting, and
+//! `/suggestions list` shows them by status.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with vote buttons, status workflow and listing
+
+pub mod buttons;
+
+pub use buttons::SuggestionButtons;
+
+use anyhow::Result;
+use serenity::builder::{CreateComponents, CreateEmbed};
+use serenity::http::Http;
+use serenity::model::application::component::ButtonStyle;
+use serenity::model::id::{ChannelId, MessageId};
+
+use crate::commands::components::{ComponentId, PAYLOAD_SEPARATOR};
+use crate::database::Suggestion;
+
+/// Feature id for toggles and the component namespace
+pub const SUGGESTIONS_FEATURE: &str = "suggestions";
+
+/// Guild setting holding the channel suggestions are posted in
+pub const CHANNEL_SETTING: &str = "suggestion_channel";
+
+/// Longest suggestion a member can post
+pub const MAX_SUGGESTION_CHARS: usize = 1000;
+
+/// Suggestions shown by `/suggestions list`
+pub const LIST_LIMIT: i64 = 20;
+
+/// Characters of each suggestion shown in a list
+const LIST_PREVIEW_CHARS: usize = 80;
+
+/// Where a suggestion is in the review workflow
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuggestionStatus {
+    Open,
+    Accepted,
+    Declined,
+    Implemented,
+}
+
+impl SuggestionStatus {
+    pub const ALL: [SuggestionStatus; 4] = [
+        SuggestionStatus::Open,
+        SuggestionStatus::Accepted,
+        SuggestionStatus::Declined,
+        SuggestionStatus::Implemented,
+    ];
+
+    /// Value stored in the database
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SuggestionStatus::Open => "open",
+            SuggestionStatus::Accepted => "accepted",
+            SuggestionStatus::Declined => "declined",
+            SuggestionStatus::Implemented => "implemented",
+        }
+    }
+
+    pub fn from_setting(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.as_str() == value)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SuggestionStatus::Open => "🗳️ Open for votes",
+            SuggestionStatus::Accepted => "✅ Accepted",
+            SuggestionStatus::Declined => "❌ Declined",
+            SuggestionStatus::Implemented => "🚀 Implemented",
+        }
+    }
+
+    fn color(self) -> u32 {
+        match self {
+            SuggestionStatus::Open => 0x5865F2,
+            SuggestionStatus::Accepted => 0x2ECC71,
+            SuggestionStatus::Declined => 0xE74C3C,
+            SuggestionStatus::Implemented => 0x9B59B6,
+        }
+    }
+}
+
+/// A suggestion's status, treating unknown values as open
+pub fn status_of(suggestion: &Suggestion) -> SuggestionStatus {
+    SuggestionStatus::from_setting(&suggestion.status).unwrap_or(SuggestionStatus::Open)
+}
+
+/// Trim a suggestion, rejecting empty or oversized ones
+pub fn validate_suggestion(input: &str) -> Result<String, &'static str> {
+    let content = input.trim();
+    if content.is_empty() {
+        return Err("The suggestion can't be empty.");
+    }
+    if content.chars().count() > MAX_SUGGESTION_CHARS {
+        return Err("Suggestions must be at most 1000 characters.");
+    }
+    Ok(content.to_string())
+}
+
+/// Embed showing a suggestion, its votes and its status
+pub fn suggestion_embed(suggestion: &Suggestion) -> CreateEmbed {
+    let status = status_of(suggestion);
+    let mut embed = CreateEmbed::default();
+    embed
+        .title(format!("💡 Suggestion #{}", suggestion.id))
+        .description(&suggestion.content)
+        .color(status.color())
+        .field("Suggested by", format!("<@{}>", suggestion.author_id), true)
+        .field(
+            "Votes",
+            format!("👍 {} · 👎 {}", suggestion.upvotes, suggestion.downvotes),
+            true,
+        )
+        .field("Status", status.label(), true);
+    if status != SuggestionStatus::Open {
+        let reviewer = suggestion
+            .reviewed_by
+            .as_deref()
+            .map(|id| format!("<@{id}>"))
+            .unwrap_or_else(|| "an admin".to_string());
+        let reason = match &suggestion.status_note {
+            Some(note) => format!("{note}\n— {reviewer}"),
+            None => format!("Marked by {reviewer}"),
+        };
+        embed.field("Response", reason, false);
+        embed.footer(|f| f.text("Voting closed"));
+    }
+    embed
+}
+
+/// Vote buttons showing the tally, disabled once the suggestion is reviewed
+pub fn suggestion_buttons(suggestion: &Suggestion) -> CreateComponents {
+    let closed = status_of(suggestion) != SuggestionStatus::Open;
+    let mut components = CreateComponents::default();
+    components.create_action_row(|row| {
+        for (direction, emoji, count, style) in [
+            ("up", '👍', suggestion.upvotes, ButtonStyle::Success),
+            ("down", '👎', suggestion.downvotes, ButtonStyle::Danger),
+        ] {
+            let id = ComponentId::new(
+                SUGGESTIONS_FEATURE,
+                "vote",
+                format!("{}{PAYLOAD_SEPARATOR}{direction}", suggestion.id),
+            );
+            row.create_button(|btn| {
+                btn.custom_id(id.to_string())
+                    .label(count.to_string())
+                    .emoji(emoji)
+                    .style(style)
+                    .disabled(closed)
+            });
+        }
+        row
+    });
+    components
+}
+
+/// One line of `/suggestions list`
+pub fn list_line(suggestion: &Suggestion) -> String {
+    let mut preview: String = suggestion
+        .content
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(LIST_PREVIEW_CHARS)
+        .collect();
+    if suggestion.content.chars().count() > LIST_PREVIEW_CHARS {
+        preview.push('…');
+    }
+    let link = match &suggestion.message_id {
+        Some(message_id) => format!(
+            " [↗](https://discord.com/channels/{}/{}/{message_id})",
+            suggestion.guild_id, suggestion.channel_id
+        ),
+        None => String::new(),
+    };
+    format!(
+        "`#{}` {} · 👍 {} 👎 {} · {preview}{link}",
+        suggestion.id,
+        status_of(suggestion).label(),
+        suggestion.upvotes,
+        suggestion.downvotes
+    )
+}
+
+/// Re-render a posted suggestion after its votes or status changed
+pub async fn refresh_message(http: &Http, suggestion: &Suggestion) -> Result<()> {
+    let Some(message_id) = &suggestion.message_id else {
+        return Ok(());
+    };
+    let embed = suggestion_embed(suggestion);
+    let components = suggestion_buttons(suggestion);
+    ChannelId(suggestion.channel_id.parse()?)
+        .edit_message(http, MessageId(message_id.parse()?), |m| {
+            m.set_embed(embed).set_components(components)
+        })
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn suggestion(status: &str) -> Suggestion {
+        Suggestion {
+            id: 12,
+            guild_id: "1".to_string(),
+            channel_id: "2".to_string(),
+            message_id: Some("3".to_string()),
+            author_id: "4".to_string(),
+            content: "Add a music channel".to_string(),
+            status: status.to_string(),
+            status_note: None,
+            reviewed_by: None,
+            upvotes: 5,
+            downvotes: 2,
+        }
+    }
+
+    #[test]
+    fn test_status_round_trip() {
+        for status in SuggestionStatus::ALL {
+            assert_eq!(
+                SuggestionStatus::from_setting(status.as_str()),
+                Some(status)
+            );
+        }
+        assert_eq!(SuggestionStatus::from_setting("pending"), None);
+        assert_eq!(status_of(&suggestion("bogus")), SuggestionStatus::Open);
+    }
+
+    #[test]
+    fn test_validate_suggestion() {
+        assert_eq!(
+            validate_suggestion("  More emotes "),
+            Ok("More emotes".to_string())
+        );
+        assert!(validate_suggestion("   ").is_err());
+        assert!(validate_suggestion(&"x".repeat(1001)).is_err());
+    }
+
+    #[test]
+    fn test_suggestion_embed() {
+        let embed = suggestion_embed(&suggestion("open"));
+        assert_eq!(embed.0["title"], "💡 Suggestion #12");
+        assert_eq!(embed.0["fields"][1]["value"], "👍 5 · 👎 2");
+        assert_eq!(embed.0["fields"].as_array().unwrap().len(), 3);
+
+        let mut declined = suggestion("declined");
+        declined.status_note = Some("Not this season".to_string());
+        declined.reviewed_by = Some("9".to_string());
+        let embed = suggestion_embed(&declined);
+        assert_eq!(embed.0["fields"][2]["value"], "❌ Declined");
+        assert_eq!(embed.0["fields"][3]["value"], "Not this season\n— <@9>");
+        assert_eq!(embed.0["footer"]["text"], "Voting closed");
+    }
+
+    #[test]
+    fn test_suggestion_buttons() {
+        let components = suggestion_buttons(&suggestion("open"));
+        let buttons = components.0[0]["components"].as_array().unwrap();
+        assert_eq!(buttons.len(), 2);
+        assert_eq!(buttons[0]["custom_id"], "suggestions:vote:12/up");
+        assert_eq!(buttons[1]["label"], "2");
+        assert_eq!(buttons[0]["disabled"], false);
+
+        let closed = suggestion_buttons(&suggestion("implemented"));
+        assert_eq!(closed.0[0]["components"][1]["disabled"], true);
+    }
+
+    #[test]
+    fn test_list_line() {
+        assert_eq!(
+            list_line(&suggestion("accepted")),
+            "`#12` ✅ Accepted · 👍 5 👎 2 · Add a music channel [↗](https://discord.com/channels/1/2/3)"
+        );
+        let mut long = suggestion("open");
+        long.content = "word ".repeat(40);
+        long.message_id = None;
+        assert!(list_line(&long).ends_with('…'));
+    }
+}
@@ -0,0 +1,274 @@
+//! # Feature: Support Tickets
+//!
+//! `/support open` creates a private thread with the requester and the support
+//! role chosen with `/support setup`. When first-line answers are on, the
+//! channel's persona reads the most relevant knowledge base entries (managed
+//! with `/support kb`) and answers in the thread if they cover the question.
+//! `/support close` summarizes the conversation, stores the summary and
+//! archives the thread; `/support stats` shows ticket metrics for admins.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with private threads, knowledge base answers, summaries and metrics
+
+use std::collections::HashSet;
+
+use crate::database::{SupportKbEntry, SupportStats};
+
+/// Feature id for toggles
+pub const SUPPORT_FEATURE: &str = "support";
+
+/// Guild setting holding the role added to every ticket
+pub const ROLE_SETTING: &str = "support_role";
+
+/// Guild setting holding the channel ticket threads are created in
+pub const CHANNEL_SETTING: &str = "support_channel";
+
+/// Guild setting turning first-line answers on (`enabled`) or off
+pub const FIRST_LINE_SETTING: &str = "support_first_line";
+
+/// Knowledge base entries a guild may keep
+pub const MAX_KB_ENTRIES: usize = 50;
+
+/// Entries shown to the persona for a first-line answer
+pub const KB_CONTEXT_ENTRIES: usize = 3;
+
+/// Reply the persona gives when the knowledge base doesn't cover a ticket
+pub const NO_ANSWER: &str = "NO_ANSWER";
+
+/// Most recent transcript characters sent for a closing summary
+const MAX_TRANSCRIPT_CHARS: usize = 8000;
+
+/// Discord's limit on thread names
+const MAX_THREAD_NAME_CHARS: usize = 100;
+
+/// Words too common to say anything about relevance
+const STOPWORDS: &[&str] = &[
+    "the", "and", "for", "are", "but", "not", "you", "your", "with", "can", "cant", "how", "what",
+    "when", "why", "this", "that", "have", "has", "was", "were", "does", "doesn", "don", "from",
+    "into", "about", "there", "their", "they", "get", "got", "any", "all", "out", "just", "help",
+];
+
+/// Name of a ticket's private thread
+pub fn thread_name(requester: &str, subject: &str) -> String {
+    let name = format!("🎫 {requester}: {}", subject.trim());
+    if name.chars().count() <= MAX_THREAD_NAME_CHARS {
+        return name;
+    }
+    let mut name: String = name.chars().take(MAX_THREAD_NAME_CHARS - 1).collect();
+    name.push('…');
+    name
+}
+
+/// Lowercased words of three letters or more, minus stopwords
+fn keywords(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|w| w.chars().count() >= 3 && !STOPWORDS.contains(&w.as_str()))
+        .collect()
+}
+
+/// Knowledge base entries sharing keywords with `query`, most overlap first
+///
+/// Title matches count double, since titles name what an entry is about.
+pub fn relevant_entries<'a>(
+    query: &str,
+    entries: &'a [SupportKbEntry],
+    limit: usize,
+) -> Vec<&'a SupportKbEntry> {
+    let query = keywords(query);
+    let mut scored: Vec<(usize, &SupportKbEntry)> = entries
+        .iter()
+        .map(|entry| {
+            let title = keywords(&entry.title).intersection(&query).count();
+            let content = keywords(&entry.content).intersection(&query).count();
+            (title * 2 + content, entry)
+        })
+        .filter(|(score, _)| *score > 0)
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(_, entry)| entry)
+        .collect()
+}
+
+/// System prompt for a first-line answer in the persona's voice
+pub fn first_line_prompt(persona_prompt: &str, entries: &[&SupportKbEntry]) -> String {
+    let articles = entries
+        .iter()
+        .map(|e| format!("### {}\n{}", e.title, e.content))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    format!(
+        "{persona_prompt}\n\n\
+        You are giving first-line support in a Discord ticket, in your characteristic style. \
+        Answer the member's request using only the knowledge base articles below. Keep it short \
+        and practical and mention that a team member will follow up if this doesn't solve it. \
+        If the articles don't cover the request, reply with exactly {NO_ANSWER} and nothing else.\n\n\
+        Knowledge base:\n{articles}"
+    )
+}
+
+/// The persona's first-line answer, or None when it had nothing to offer
+pub fn parse_first_line(reply: &str) -> Option<String> {
+    let reply = reply.trim();
+    if reply.is_empty() || reply.contains(NO_ANSWER) {
+        return None;
+    }
+    Some(reply.to_string())
+}
+
+pub const SUMMARY_PROMPT: &str = "You summarize Discord support tickets for the team's records. \
+    Given the conversation, reply with 3-5 short bullet points covering the problem, what was \
+    tried and how it was resolved (or that it wasn't). No preamble.";
+
+/// `author: message` lines, keeping the latest messages within the size limit
+pub fn transcript(messages: &[(String, String)]) -> String {
+    let mut lines = Vec::new();
+    let mut size = 0;
+    for (author, content) in messages.iter().rev() {
+        let line = format!("{author}: {}", content.trim());
+        size += line.chars().count() + 1;
+        if size > MAX_TRANSCRIPT_CHARS {
+            break;
+        }
+        lines.push(line);
+    }
+    lines.reverse();
+    lines.join("\n")
+}
+
+/// `2h 5m` style duration
+pub fn format_duration(secs: i64) -> String {
+    let minutes = secs.max(0) / 60;
+    match (minutes / 1440, minutes / 60 % 24, minutes % 60) {
+        (0, 0, m) => format!("{m}m"),
+        (0, h, m) => format!("{h}h {m}m"),
+        (d, h, _) => format!("{d}d {h}h"),
+    }
+}
+
+/// Body of `/support stats`
+pub fn format_stats(stats: &SupportStats) -> String {
+    let resolution = stats
+        .avg_resolution_secs
+        .map(format_duration)
+        .unwrap_or_else(|| "—".to_string());
+    let total = stats.open + stats.closed;
+    let first_line = if total > 0 {
+        format!(
+            "{} ({}%)",
+            stats.first_line_answered,
+            stats.first_line_answered * 100 / total
+        )
+    } else {
+        "0".to_string()
+    };
+    format!(
+        "🎫 **Support tickets**\n\
+        Open: **{}** · Closed: **{}** · Opened this week: **{}**\n\
+        Average time to close: **{resolution}**\n\
+        First-line answers from the knowledge base: **{first_line}**",
+        stats.open, stats.closed, stats.opened_last_week
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: i64, title: &str, content: &str) -> SupportKbEntry {
+        SupportKbEntry {
+            id,
+            title: title.to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_thread_name() {
+        assert_eq!(
+            thread_name("alice", " Can't log in "),
+            "🎫 alice: Can't log in"
+        );
+        let long = thread_name("bob", &"x".repeat(200));
+        assert_eq!(long.chars().count(), MAX_THREAD_NAME_CHARS);
+        assert!(long.ends_with('…'));
+    }
+
+    #[test]
+    fn test_relevant_entries() {
+        let kb = vec![
+            entry(1, "Password reset", "Use the reset link on the login page."),
+            entry(
+                2,
+                "Billing",
+                "Invoices are emailed monthly; the login page has them too.",
+            ),
+            entry(3, "Roles", "Ask a moderator for roles."),
+        ];
+        let found = relevant_entries("How do I reset my password?", &kb, 3);
+        assert_eq!(found.iter().map(|e| e.id).collect::<Vec<_>>(), [1]);
+
+        let found = relevant_entries("login page broken, invoices missing", &kb, 3);
+        assert_eq!(found.iter().map(|e| e.id).collect::<Vec<_>>(), [2, 1]);
+        assert_eq!(relevant_entries("login", &kb, 1).len(), 1);
+        assert!(relevant_entries("the and for", &kb, 3).is_empty());
+    }
+
+    #[test]
+    fn test_first_line_prompt_and_reply() {
+        let kb = [entry(1, "Password reset", "Use the reset link.")];
+        let prompt = first_line_prompt("You are Obi.", &kb.iter().collect::<Vec<_>>());
+        assert!(prompt.starts_with("You are Obi."));
+        assert!(prompt.ends_with("### Password reset\nUse the reset link."));
+
+        assert_eq!(parse_first_line(" NO_ANSWER "), None);
+        assert_eq!(parse_first_line(""), None);
+        assert_eq!(
+            parse_first_line(" Try the reset link. "),
+            Some("Try the reset link.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_transcript_keeps_latest() {
+        let messages = vec![
+            ("alice".to_string(), "x".repeat(MAX_TRANSCRIPT_CHARS)),
+            ("bob".to_string(), " Did you try restarting? ".to_string()),
+            ("alice".to_string(), "That worked".to_string()),
+        ];
+        assert_eq!(
+            transcript(&messages),
+            "bob: Did you try restarting?\nalice: That worked"
+        );
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(59), "0m");
+        assert_eq!(format_duration(3 * 3600 + 20 * 60), "3h 20m");
+        assert_eq!(format_duration(2 * 86_400 + 5 * 3600), "2d 5h");
+    }
+
+    #[test]
+    fn test_format_stats() {
+        let stats = SupportStats {
+            open: 1,
+            closed: 3,
+            avg_resolution_secs: Some(5400),
+            first_line_answered: 2,
+            opened_last_week: 4,
+        };
+        let text = format_stats(&stats);
+        assert!(text.contains("Open: **1** · Closed: **3** · Opened this week: **4**"));
+        assert!(text.contains("Average time to close: **1h 30m**"));
+        assert!(text.ends_with("**2 (50%)**"));
+        assert!(format_stats(&SupportStats::default()).contains("**—**"));
+    }
+}
@@ -0,0 +1,549 @@
+//! # Telegram Companion
+//!
+//! Optional Telegram bot that shares the Discord bot's database: the same
+//! personas, conversation history, `/ask` answers and reminders.
+//!
+//! Private chats are persona chats. Commands work everywhere:
+//! `/persona`, `/ask <persona> <question>`, `/remind <time> <message>`,
+//! `/reset`, and account linking. A Discord user runs `/telegram link` to get
+//! a one-time code and sends `/link <code>` here; from then on the Telegram
+//! account acts as them, and `/continue` picks up their latest Discord
+//! conversation. Unlinked users get a Telegram-only identity.
+//!
+//! Reminders set here are stored with a `telegram:<chat>` channel and
+//! delivered by this module rather than the Discord scheduler.
+//!
+//! Built only with the `telegram` cargo feature and started when
+//! `TELEGRAM_BOT_TOKEN` is set.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: false
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+use anyhow::Result;
+use chrono::Utc;
+use log::{debug, error, info, warn};
+use std::sync::Arc;
+use std::time::Duration;
+use teloxide::prelude::*;
+use teloxide::types::ChatAction;
+use tokio::time::interval;
+use uuid::Uuid;
+
+use crate::command_handler::CommandHandler;
+use crate::commands::handlers::remind::RemindHandler;
+use crate::core::response::chunk_text;
+use crate::database::Database;
+use crate::features::analytics::runtime::{spawn_tracked, TaskKind};
+use crate::features::personas::PersonaManager;
+
+/// Channel ID prefix for Telegram chats in shared tables
+pub const CHANNEL_PREFIX: &str = "telegram";
+
+/// Telegram rejects messages longer than this
+const MESSAGE_LIMIT: usize = 4096;
+
+/// How long a `/telegram link` code stays valid
+pub const LINK_CODE_MINUTES: i64 = 10;
+
+const RATE_LIMITED: &str = "⏳ You're sending messages too quickly. Please wait a moment.";
+
+const HELP: &str = "Chat with me here, or use:\n\
+    /persona [name] - show or switch persona\n\
+    /ask <persona> <question> - one-off answer from any persona\n\
+    /remind <time> <message> - e.g. /remind 1h30m stretch\n\
+    /reset - forget this conversation\n\
+    /link <code> - link your Discord account (get a code with /telegram link on Discord)\n\
+    /continue - carry on your latest Discord conversation (/continue off to stop)\n\
+    /unlink - unlink your Discord account";
+
+/// Bot token for the Telegram front end
+#[derive(Debug, Clone)]
+pub struct TelegramConfig {
+    pub bot_token: String,
+}
+
+impl TelegramConfig {
+    /// `None` unless TELEGRAM_BOT_TOKEN is set
+    pub fn from_env() -> Option<Self> {
+        let bot_token = std::env::var("TELEGRAM_BOT_TOKEN").ok()?;
+        if bot_token.trim().is_empty() {
+            return None;
+        }
+        Some(Self {
+            bot_token: bot_token.trim().to_string(),
+        })
+    }
+}
+
+/// `telegram:<chat id>`
+pub fn chat_channel(chat_id: i64) -> String {
+    format!("{CHANNEL_PREFIX}:{chat_id}")
+}
+
+/// The user and channel a Telegram message is handled as
+///
+/// Linked accounts act as their Discord user, in the Discord channel they
+/// chose to continue if any. Everyone else gets a `tg:` user of their own.
+pub fn resolve_identity(
+    telegram_user_id: u64,
+    chat_id: i64,
+    link: Option<(String, Option<String>)>,
+) -> (String, String) {
+    match link {
+        Some((discord_user_id, continue_channel)) => (
+            discord_user_id,
+            continue_channel.unwrap_or_else(|| chat_channel(chat_id)),
+        ),
+        None => (format!("tg:{telegram_user_id}"), chat_channel(chat_id)),
+    }
+}
+
+/// Slash commands the Telegram bot understands
+#[derive(Debug, PartialEq, Eq)]
+pub enum TelegramCommand {
+    Help,
+    Link(String),
+    Unlink,
+    Persona(Option<String>),
+    Ask {
+        persona: String,
+        prompt: String,
+    },
+    Remind {
+        time: String,
+        text: String,
+    },
+    Continue(bool),
+    Reset,
+    /// A known command with missing arguments; holds its usage line
+    Usage(&'static str),
+}
+
+/// Parse a `/command`, ignoring ones addressed to another bot in groups
+///
+/// Returns `None` for plain messages.
+pub fn parse_command(text: &str, bot_username: &str) -> Option<TelegramCommand> {
+    let text = text.trim();
+    let rest = text.strip_prefix('/')?;
+    let (word, args) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    let (name, target) = word.split_once('@').unwrap_or((word, bot_username));
+    if !target.eq_ignore_ascii_case(bot_username) {
+        return None;
+    }
+    let args = args.trim();
+
+    Some(match name.to_lowercase().as_str() {
+        "link" if args.is_empty() => TelegramCommand::Usage("/link <code>"),
+        "link" => TelegramCommand::Link(args.to_uppercase()),
+        "unlink" => TelegramCommand::Unlink,
+        "persona" => {
+            TelegramCommand::Persona(args.split_whitespace().next().map(str::to_lowercase))
+        }
+        "ask" => match args.split_once(char::is_whitespace) {
+            Some((persona, prompt)) if !prompt.trim().is_empty() => TelegramCommand::Ask {
+                persona: persona.to_lowercase(),
+                prompt: prompt.trim().to_string(),
+            },
+            _ => TelegramCommand::Usage("/ask <persona> <question>"),
+        },
+        "remind" => match args.split_once(char::is_whitespace) {
+            Some((time, text)) if !text.trim().is_empty() => TelegramCommand::Remind {
+                time: time.to_string(),
+                text: text.trim().to_string(),
+            },
+            _ => TelegramCommand::Usage("/remind <time> <message>, e.g. /remind 30m tea"),
+        },
+        "continue" => TelegramCommand::Continue(!args.eq_ignore_ascii_case("off")),
+        "reset" => TelegramCommand::Reset,
+        _ => TelegramCommand::Help,
+    })
+}
+
+/// Reply text headed with the persona's name
+pub fn format_reply(persona_name: &str, reply: &str) -> String {
+    format!("{persona_name}: {reply}")
+}
+
+/// Connects the persona chat pipeline to a Telegram bot
+pub struct TelegramBridge {
+    bot: Bot,
+    handler: CommandHandler,
+    database: Database,
+    persona_manager: PersonaManager,
+    username: String,
+}
+
+impl TelegramBridge {
+    pub fn new(config: &TelegramConfig, handler: CommandHandler, database: Database) -> Self {
+        Self {
+            bot: Bot::new(&config.bot_token),
+            handler,
+            database,
+            persona_manager: PersonaManager::new(),
+            username: String::new(),
+        }
+    }
+
+    /// Poll for updates forever and deliver Telegram reminders
+    /// This should be spawned as a tokio task
+    pub async fn run(mut self) {
+        match self.bot.get_me().await {
+            Ok(me) => self.username = me.username().to_string(),
+            Err(e) => {
+                error!("❌ Telegram bot couldn't log in: {e}");
+                return;
+            }
+        }
+        info!("✈️ Telegram bot connected as @{}", self.username);
+
+        let bridge = Arc::new(self);
+        let reminders = Arc::clone(&bridge);
+        spawn_tracked(TaskKind::Schedulers, async move {
+            reminders.run_reminders().await
+        });
+
+        let endpoint = Update::filter_message().endpoint(
+            |msg: Message, bridge: Arc<TelegramBridge>| async move {
+                if let Err(e) = bridge.handle_message(&msg).await {
+                    error!("❌ Telegram message in chat {} failed: {e}", msg.chat.id);
+                }
+                respond(())
+            },
+        );
+        Dispatcher::builder(bridge.bot.clone(), endpoint)
+            .dependencies(dptree::deps![bridge])
+            .default_handler(|_| async {})
+            .build()
+            .dispatch()
+            .await;
+    }
+
+    async fn send(&self, chat_id: ChatId, text: &str) -> Result<()> {
+        for chunk in chunk_text(text, MESSAGE_LIMIT) {
+            self.bot.send_message(chat_id, chunk).await?;
+        }
+        Ok(())
+    }
+
+    fn persona_name(&self, persona_id: &str) -> String {
+        self.persona_manager
+            .get_persona(persona_id)
+            .map(|p| p.name.clone())
+            .unwrap_or_else(|| persona_id.to_string())
+    }
+
+    async fn handle_message(&self, msg: &Message) -> Result<()> {
+        let (Some(text), Some(from)) = (msg.text(), msg.from.as_ref()) else {
+            return Ok(());
+        };
+        if from.is_bot {
+            return Ok(());
+        }
+        let request_id = Uuid::new_v4();
+        let telegram_user_id = from.id.0.to_string();
+        let link = self.database.get_telegram_link(&telegram_user_id).await?;
+        let linked = link.is_some();
+        let (user_id, channel_id) = resolve_identity(from.id.0, msg.chat.id.0, link);
+        debug!(
+            "[{request_id}] ✈️ Telegram message | Chat: {} | User: {user_id}",
+            msg.chat.id
+        );
+
+        let reply = match parse_command(text, &self.username) {
+            Some(command) => {
+                self.run_command(
+                    command,
+                    &telegram_user_id,
+                    linked,
+                    &user_id,
+                    msg.chat.id,
+                    &channel_id,
+                    request_id,
+                )
+                .await?
+            }
+            // Outside private chats only commands are answered
+            None if !msg.chat.is_private() || text.starts_with('/') => return Ok(()),
+            None => {
+                self.chat(&user_id, msg.chat.id, &channel_id, text, request_id)
+                    .await
+            }
+        };
+        self.send(msg.chat.id, &reply).await
+    }
+
+    async fn chat(
+        &self,
+        user_id: &str,
+        chat_id: ChatId,
+        channel_id: &str,
+        text: &str,
+        request_id: Uuid,
+    ) -> String {
+        let _ = self.bot.send_chat_action(chat_id, ChatAction::Typing).await;
+        match self
+            .handler
+            .bridge_chat(user_id, channel_id, text, request_id)
+            .await
+        {
+            Ok(Some((persona_id, reply))) => format_reply(&self.persona_name(&persona_id), &reply),
+            Ok(None) => RATE_LIMITED.to_string(),
+            Err(e) => {
+                error!("[{request_id}] ❌ Telegram chat failed: {e}");
+                CommandHandler::chat_error_message(&e)
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn run_command(
+        &self,
+        command: TelegramCommand,
+        telegram_user_id: &str,
+        linked: bool,
+        user_id: &str,
+        chat_id: ChatId,
+        channel_id: &str,
+        request_id: Uuid,
+    ) -> Result<String> {
+        Ok(match command {
+            TelegramCommand::Help => HELP.to_string(),
+            TelegramCommand::Usage(usage) => format!("Usage: {usage}"),
+            TelegramCommand::Link(code) => {
+                match self
+                    .database
+                    .redeem_telegram_link_code(&code, telegram_user_id)
+                    .await?
+                {
+                    Some(_) => "✅ Linked to your Discord account. Your persona, history and \
+                         reminders are shared now; /continue picks up your latest \
+                         Discord conversation."
+                        .to_string(),
+                    None => "❌ That code is invalid or expired. Run /telegram link on \
+                             Discord for a new one."
+                        .to_string(),
+                }
+            }
+            TelegramCommand::Unlink => {
+                if self.database.delete_telegram_link(telegram_user_id).await? {
+                    "✅ Unlinked from your Discord account.".to_string()
+                } else {
+                    "This Telegram account isn't linked.".to_string()
+                }
+            }
+            TelegramCommand::Continue(on) => {
+                if !linked {
+                    "❌ Link your Discord account first: run /telegram link on Discord, \
+                     then send /link <code> here."
+                        .to_string()
+                } else if !on {
+                    self.database
+                        .set_telegram_continue_channel(telegram_user_id, None)
+                        .await?;
+                    "✅ Back to a Telegram-only conversation.".to_string()
+                } else {
+                    match self
+                        .database
+                        .get_latest_conversation_channel(user_id)
+                        .await?
+                    {
+                        Some(channel) => {
+                            self.database
+                                .set_telegram_continue_channel(telegram_user_id, Some(&channel))
+                                .await?;
+                            "✅ Continuing your latest Discord conversation. \
+                             /continue off to stop."
+                                .to_string()
+                        }
+                        None => {
+                            "You don't have a Discord conversation to continue yet.".to_string()
+                        }
+                    }
+                }
+            }
+            TelegramCommand::Persona(None) => {
+                let current = self.database.get_user_persona(user_id).await?;
+                let mut names: Vec<String> = self
+                    .persona_manager
+                    .list_personas()
+                    .into_iter()
+                    .map(|(id, persona)| format!("{id} ({})", persona.name))
+                    .collect();
+                names.sort();
+                format!(
+                    "Current persona: {}\nPersonas: {}\nSwitch with /persona <name>",
+                    self.persona_name(&current),
+                    names.join(", ")
+                )
+            }
+            TelegramCommand::Persona(Some(name)) => match self.persona_manager.get_persona(&name) {
+                Some(persona) => {
+                    self.database.set_user_persona(user_id, &name).await?;
+                    format!("Now chatting with {}.", persona.name)
+                }
+                None => format!("Unknown persona '{name}'. Try /persona."),
+            },
+            TelegramCommand::Ask { persona, prompt } => {
+                if self.persona_manager.get_persona(&persona).is_none() {
+                    return Ok(format!("Unknown persona '{persona}'. Try /persona."));
+                }
+                let _ = self.bot.send_chat_action(chat_id, ChatAction::Typing).await;
+                match self
+                    .handler
+                    .bridge_ask(user_id, channel_id, &persona, &prompt, request_id)
+                    .await
+                {
+                    Ok(Some(reply)) => format_reply(&self.persona_name(&persona), &reply),
+                    Ok(None) => RATE_LIMITED.to_string(),
+                    Err(e) => {
+                        error!("[{request_id}] ❌ Telegram /ask failed: {e}");
+                        CommandHandler::chat_error_message(&e)
+                    }
+                }
+            }
+            TelegramCommand::Remind { time, text } => {
+                let Some(seconds) = RemindHandler::parse_duration(&time) else {
+                    return Ok(
+                        "❌ Invalid time format. Use formats like 30m, 2h, 1d, or 1h30m."
+                            .to_string(),
+                    );
+                };
+                let remind_at = (Utc::now() + chrono::Duration::seconds(seconds))
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string();
+                // Always delivered to this chat, even when continuing a Discord conversation
+                let id = self
+                    .database
+                    .add_reminder(user_id, &chat_channel(chat_id.0), &text, &remind_at)
+                    .await?;
+                info!("[{request_id}] ✈️ Telegram reminder #{id} for {user_id} at {remind_at}");
+                format!(
+                    "⏰ Got it! I'll remind you in {} about:\n{text}",
+                    RemindHandler::format_duration(seconds)
+                )
+            }
+            TelegramCommand::Reset => {
+                self.database
+                    .clear_conversation_history(user_id, channel_id)
+                    .await?;
+                "Conversation history cleared.".to_string()
+            }
+        })
+    }
+
+    /// Deliver due Telegram reminders every minute
+    async fn run_reminders(&self) {
+        let mut check_interval = interval(Duration::from_secs(60));
+        loop {
+            check_interval.tick().await;
+            if let Err(e) = self.deliver_reminders().await {
+                error!("❌ Error delivering Telegram reminders: {e}");
+            }
+        }
+    }
+
+    async fn deliver_reminders(&self) -> Result<()> {
+        for (id, user_id, channel_id, text) in self
+            .database
+            .get_pending_bridge_reminders(CHANNEL_PREFIX)
+            .await?
+        {
+            let chat_id = channel_id
+                .strip_prefix(CHANNEL_PREFIX)
+                .and_then(|rest| rest.strip_prefix(':'))
+                .and_then(|id| id.parse::<i64>().ok());
+            let persona = self
+                .database
+                .get_user_persona(&user_id)
+                .await
+                .unwrap_or_else(|_| "obi".to_string());
+            let message = format_reply(
+                &self.persona_name(&persona),
+                &format!("⏰ Reminder: {text}"),
+            );
+            match chat_id {
+                Some(chat_id) => {
+                    if let Err(e) = self.send(ChatId(chat_id), &message).await {
+                        warn!("⚠️ Failed to deliver Telegram reminder #{id}: {e}");
+                    }
+                }
+                None => warn!("⚠️ Telegram reminder #{id} has a bad channel '{channel_id}'"),
+            }
+            // Completed either way, like Discord reminders, to avoid repeats
+            self.database.complete_reminder(id).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOT: &str = "persona_bot";
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(parse_command("hello there", BOT), None);
+        assert_eq!(parse_command("/start", BOT), Some(TelegramCommand::Help));
+        assert_eq!(
+            parse_command("/link abc123", BOT),
+            Some(TelegramCommand::Link("ABC123".to_string()))
+        );
+        assert_eq!(
+            parse_command("/link", BOT),
+            Some(TelegramCommand::Usage("/link <code>"))
+        );
+        assert_eq!(
+            parse_command("/ask@Persona_Bot Obi what is the Force?", BOT),
+            Some(TelegramCommand::Ask {
+                persona: "obi".to_string(),
+                prompt: "what is the Force?".to_string(),
+            })
+        );
+        assert_eq!(parse_command("/ask@other_bot obi hi", BOT), None);
+        assert!(matches!(
+            parse_command("/ask obi", BOT),
+            Some(TelegramCommand::Usage(_))
+        ));
+        assert_eq!(
+            parse_command("/remind 1h30m  stretch your legs", BOT),
+            Some(TelegramCommand::Remind {
+                time: "1h30m".to_string(),
+                text: "stretch your legs".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_command("/persona Chef", BOT),
+            Some(TelegramCommand::Persona(Some("chef".to_string())))
+        );
+        assert_eq!(
+            parse_command("/continue off", BOT),
+            Some(TelegramCommand::Continue(false))
+        );
+        assert_eq!(
+            parse_command("/continue", BOT),
+            Some(TelegramCommand::Continue(true))
+        );
+    }
+
+    #[test]
+    fn test_resolve_identity() {
+        assert_eq!(
+            resolve_identity(42, 7, None),
+            ("tg:42".to_string(), "telegram:7".to_string())
+        );
+        assert_eq!(
+            resolve_identity(42, 7, Some(("111".to_string(), None))),
+            ("111".to_string(), "telegram:7".to_string())
+        );
+        assert_eq!(
+            resolve_identity(42, 7, Some(("111".to_string(), Some("222".to_string())))),
+            ("111".to_string(), "222".to_string())
+        );
+    }
+}
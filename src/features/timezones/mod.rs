@@ -0,0 +1,311 @@
+//! # Feature: Time Zones
+//!
+//! Members store their time zone with `/time zone`, as an IANA name such as
+//! `Europe/Berlin` (followed through daylight saving) or a fixed UTC offset. `/time convert` turns a
+//! time in the member's zone (or a given one) into Discord timestamp markup,
+//! which every reader sees in their own local time. Channels opted in with
+//! `/set_channel time_detection` get the same for clock times written in
+//! plain messages, read in the author's stored zone.
+//!
+//! - **Version**: 1.1.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.1.0: Store IANA zone names and convert through the tz database; offsets stored before are still read
+//! - 1.0.0: Initial release with stored offsets, /time convert and per-channel detection
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use regex::Regex;
+use std::sync::OnceLock;
+
+use crate::core::Zone;
+use crate::database::Database;
+
+/// Feature id for toggles
+pub const TIMEZONES_FEATURE: &str = "timezones";
+
+/// Extended user preference holding a member's zone, as stored by [`Zone::to_setting`]
+pub const TIMEZONE_PREFERENCE: &str = "timezone";
+
+/// Preference the zone was kept in as a fixed offset in minutes before 1.1.0
+const LEGACY_OFFSET_PREFERENCE: &str = "utc_offset_minutes";
+
+/// Times converted per message, so a schedule paste doesn't become a wall
+pub const MAX_DETECTED_TIMES: usize = 3;
+
+/// A time of day without a date is read as today unless it passed longer
+/// ago than this, then as tomorrow
+const PAST_GRACE_MINUTES: i64 = 60;
+
+fn clock_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(?i)\b(\d{1,2})(?::([0-5]\d))?\s*([ap]\.?m\.?)(?:\W|$)|\b(\d{1,2}):([0-5]\d)\b",
+        )
+        .unwrap()
+    })
+}
+
+fn noise_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    // Code, links, mentions and existing timestamps aren't times to convert
+    RE.get_or_init(|| {
+        Regex::new(r"(?s)```.*?```|`[^`]*`|https?://\S+|<[@#:a-zA-Z!&]*\d+(:[a-zA-Z])?>").unwrap()
+    })
+}
+
+/// An hour and minute from its captured parts, handling am/pm
+fn clock_from_parts(hour: &str, minute: Option<&str>, meridiem: Option<&str>) -> Option<NaiveTime> {
+    let mut hour: u32 = hour.parse().ok()?;
+    let minute: u32 = minute.map_or(Some(0), |m| m.parse().ok())?;
+    if let Some(meridiem) = meridiem {
+        if !(1..=12).contains(&hour) {
+            return None;
+        }
+        let pm = meridiem.to_ascii_lowercase().starts_with('p');
+        hour = match (hour, pm) {
+            (12, false) => 0,
+            (12, true) => 12,
+            (h, true) => h + 12,
+            (h, false) => h,
+        };
+    }
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+/// Parse a time of day like `15:30`, `3pm`, `3:30 pm`, `noon` or `midnight`
+pub fn parse_clock(input: &str) -> Option<NaiveTime> {
+    let input = input.trim();
+    match input.to_ascii_lowercase().as_str() {
+        "noon" => return NaiveTime::from_hms_opt(12, 0, 0),
+        "midnight" => return NaiveTime::from_hms_opt(0, 0, 0),
+        _ => {}
+    }
+    let captures = clock_regex().captures(input)?;
+    if captures.get(0)?.as_str().trim_end() != input {
+        return None;
+    }
+    match captures.get(1) {
+        Some(hour) => clock_from_parts(
+            hour.as_str(),
+            captures.get(2).map(|m| m.as_str()),
+            captures.get(3).map(|m| m.as_str()),
+        ),
+        None => clock_from_parts(&captures[4], Some(&captures[5]), None),
+    }
+}
+
+/// The unix time of `clock` in `zone` on the local day it next falls
+///
+/// Times that passed within the last hour stay on today, so "the call at
+/// 3pm" posted at 3:20pm still means the one that just started.
+pub fn next_occurrence(clock: NaiveTime, zone: &Zone, now: DateTime<Utc>) -> i64 {
+    let today = zone.local_time(now).date();
+    let at = zone.to_unix(today.and_time(clock));
+    if at < now.timestamp() - PAST_GRACE_MINUTES * 60 {
+        today
+            .succ_opt()
+            .map_or(at, |tomorrow| zone.to_unix(tomorrow.and_time(clock)))
+    } else {
+        at
+    }
+}
+
+/// Parse `/time convert` input: a time of day, optionally after a
+/// `YYYY-MM-DD` date, `today` or `tomorrow`
+pub fn parse_when(input: &str, zone: &Zone, now: DateTime<Utc>) -> Result<i64, &'static str> {
+    const INVALID: &str = "Give a time like `15:30`, `3pm`, `tomorrow 9am` or `2026-10-20 18:00`.";
+    let input = input.trim();
+    let (day, clock) = match input.split_once(char::is_whitespace) {
+        Some((day, clock)) => (Some(day.to_ascii_lowercase()), clock),
+        None => (None, input),
+    };
+    let clock = parse_clock(clock).ok_or(INVALID)?;
+    let today = zone.local_time(now).date();
+    let date = match day.as_deref() {
+        None => return Ok(next_occurrence(clock, zone, now)),
+        Some("today") => today,
+        Some("tomorrow") => today.succ_opt().ok_or(INVALID)?,
+        Some(date) => NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| INVALID)?,
+    };
+    Ok(zone.to_unix(date.and_time(clock)))
+}
+
+/// A member's stored zone, falling back to the offset kept before 1.1.0
+pub async fn member_zone(database: &Database, user_id: &str) -> Result<Option<Zone>> {
+    if let Some(value) = database
+        .get_user_preference(user_id, TIMEZONE_PREFERENCE)
+        .await?
+    {
+        return Ok(Zone::from_setting(&value));
+    }
+    Ok(database
+        .get_user_preference(user_id, LEGACY_OFFSET_PREFERENCE)
+        .await?
+        .and_then(|value| value.parse::<i32>().ok())
+        .map(Zone::Fixed))
+}
+
+/// Clock times written in a message, in order, without duplicates
+pub fn detect_times(content: &str) -> Vec<NaiveTime> {
+    let cleaned = noise_regex().replace_all(content, " ");
+    let mut times = Vec::new();
+    for captures in clock_regex().captures_iter(&cleaned) {
+        let time = match captures.get(1) {
+            Some(hour) => clock_from_parts(
+                hour.as_str(),
+                captures.get(2).map(|m| m.as_str()),
+                captures.get(3).map(|m| m.as_str()),
+            ),
+            None => clock_from_parts(&captures[4], Some(&captures[5]), None),
+        };
+        if let Some(time) = time.filter(|t| !times.contains(t)) {
+            times.push(time);
+            if times.len() == MAX_DETECTED_TIMES {
+                break;
+            }
+        }
+    }
+    times
+}
+
+/// Discord markup showing a time in each reader's zone, with how far off it is
+pub fn timestamp_markup(unix: i64) -> String {
+    format!("<t:{unix}:t> (<t:{unix}:R>)")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use chrono_tz::Tz;
+
+    fn clock(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_clock() {
+        assert_eq!(parse_clock("15:30"), Some(clock(15, 30)));
+        assert_eq!(parse_clock("3pm"), Some(clock(15, 0)));
+        assert_eq!(parse_clock("3:30 PM"), Some(clock(15, 30)));
+        assert_eq!(parse_clock("12am"), Some(clock(0, 0)));
+        assert_eq!(parse_clock("12 p.m."), Some(clock(12, 0)));
+        assert_eq!(parse_clock("noon"), Some(clock(12, 0)));
+        assert_eq!(parse_clock("midnight"), Some(clock(0, 0)));
+        assert_eq!(parse_clock("25:00"), None);
+        assert_eq!(parse_clock("13pm"), None);
+        assert_eq!(parse_clock("15:30 tomorrow"), None);
+        assert_eq!(parse_clock("soon"), None);
+    }
+
+    #[test]
+    fn test_next_occurrence() {
+        // 14:00 UTC is 16:00 at UTC+2
+        let plus_two = Zone::Fixed(120);
+        let now = Utc.with_ymd_and_hms(2026, 10, 15, 14, 0, 0).unwrap();
+        let today = |h, m| {
+            Utc.with_ymd_and_hms(2026, 10, 15, h, m, 0)
+                .unwrap()
+                .timestamp()
+        };
+
+        assert_eq!(next_occurrence(clock(18, 0), &plus_two, now), today(16, 0));
+        // Within the grace period it's the one that just happened
+        assert_eq!(
+            next_occurrence(clock(15, 30), &plus_two, now),
+            today(13, 30)
+        );
+        // Earlier than that it's tomorrow's
+        assert_eq!(
+            next_occurrence(clock(9, 0), &plus_two, now),
+            today(7, 0) + 24 * 60 * 60
+        );
+    }
+
+    #[test]
+    fn test_parse_when() {
+        let utc = Zone::Fixed(0);
+        let now = Utc.with_ymd_and_hms(2026, 10, 15, 14, 0, 0).unwrap();
+        let at = |d, h, m| {
+            Utc.with_ymd_and_hms(2026, 10, d, h, m, 0)
+                .unwrap()
+                .timestamp()
+        };
+
+        assert_eq!(
+            parse_when("9am", &Zone::Fixed(-300), now),
+            Ok(at(15, 14, 0))
+        );
+        assert_eq!(
+            parse_when("tomorrow 9am", &Zone::Fixed(-300), now),
+            Ok(at(16, 14, 0))
+        );
+        assert_eq!(parse_when("today 8:00", &utc, now), Ok(at(15, 8, 0)));
+        assert_eq!(
+            parse_when("2026-10-20 18:00", &Zone::Fixed(60), now),
+            Ok(at(20, 17, 0))
+        );
+        assert!(parse_when("someday 9am", &utc, now).is_err());
+        assert!(parse_when("later", &utc, now).is_err());
+
+        // Berlin leaves summer time on the 25th, so the same clock time moves an hour in UTC
+        let berlin = Zone::Named(Tz::Europe__Berlin);
+        assert_eq!(
+            parse_when("2026-10-24 18:00", &berlin, now),
+            Ok(at(24, 16, 0))
+        );
+        assert_eq!(
+            parse_when("2026-10-26 18:00", &berlin, now),
+            Ok(at(26, 17, 0))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_member_zone_reads_names_and_legacy_offsets() {
+        let database = Database::new(":memory:").await.unwrap();
+        assert_eq!(member_zone(&database, "u1").await.unwrap(), None);
+
+        database
+            .set_user_preference("u1", LEGACY_OFFSET_PREFERENCE, "-300")
+            .await
+            .unwrap();
+        assert_eq!(
+            member_zone(&database, "u1").await.unwrap(),
+            Some(Zone::Fixed(-300))
+        );
+
+        database
+            .set_user_preference("u1", TIMEZONE_PREFERENCE, "America/New_York")
+            .await
+            .unwrap();
+        assert_eq!(
+            member_zone(&database, "u1").await.unwrap(),
+            Some(Zone::Named(Tz::America__New_York))
+        );
+    }
+
+    #[test]
+    fn test_detect_times() {
+        assert_eq!(
+            detect_times("Raid at 8pm, or 21:30 if late. 8 PM works too"),
+            [clock(20, 0), clock(21, 30)]
+        );
+        assert_eq!(detect_times("Meet at 9:15am!"), [clock(9, 15)]);
+        assert!(detect_times("Score was 3-2, see https://x.io/10:30 and `12:00`").is_empty());
+        assert!(detect_times("Already converted <t:1700000000:t>").is_empty());
+        assert!(detect_times("I have 3 apples and 99:99 things").is_empty());
+        assert_eq!(
+            detect_times("1:00 2:00 3:00 4:00").len(),
+            MAX_DETECTED_TIMES
+        );
+    }
+
+    #[test]
+    fn test_timestamp_markup() {
+        assert_eq!(timestamp_markup(42), "<t:42:t> (<t:42:R>)");
+    }
+}
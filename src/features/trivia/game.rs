@@ -0,0 +1,410 @@
+//! Trivia game runner and answer buttons
+//!
+//! - **Version**: 1.2.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.2.0: Game winners are checked for achievements
+//! - 1.1.0: Finishing a game on top of the scoreboard counts as a win
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::{info, warn};
+use serenity::builder::{CreateComponents, CreateEmbed};
+use serenity::http::Http;
+use serenity::model::application::component::ButtonStyle;
+use serenity::model::application::interaction::message_component::MessageComponentInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::model::id::ChannelId;
+use serenity::prelude::Context;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::{
+    close_round, end_session, is_cancelled, open_round, record_answer, AnswerOutcome, Quiz,
+    Scoreboard, TriviaQuestion, LETTERS, TRIVIA_FEATURE,
+};
+use crate::commands::components::{ComponentHandler, ComponentId, PAYLOAD_SEPARATOR};
+use crate::database::Database;
+use crate::features::achievements::{self, Progress};
+
+/// Pause between a reveal and the next question
+const BETWEEN_QUESTIONS: Duration = Duration::from_secs(4);
+
+/// Players shown in the final standings
+const MAX_STANDINGS: usize = 10;
+
+/// System prompt asking the host persona for a quiz
+pub fn quiz_prompt(persona_prompt: &str, host_name: &str, topic: &str, count: usize) -> String {
+    format!(
+        "{persona_prompt}\n\n\
+        You are {host_name}, hosting a trivia game for a Discord server. Write {count} \
+        multiple-choice questions about: {topic}. Mix the difficulty, keep each question \
+        under 200 characters, and make sure exactly one of the four choices is correct and \
+        the facts are accurate.\n\n\
+        Reply with JSON only, in this shape:\n\
+        {{\"intro\": \"a one-sentence in-character welcome\", \"questions\": [\
+        {{\"question\": \"...\", \"choices\": [\"...\", \"...\", \"...\", \"...\"], \
+        \"answer\": 0, \"explanation\": \"one in-character sentence revealing the answer\"}}]}}\n\
+        \"answer\" is the index (0-3) of the correct choice. Vary where the correct choice appears."
+    )
+}
+
+/// Everything a running game needs
+pub struct TriviaGame {
+    pub channel_id: ChannelId,
+    pub guild_id: String,
+    pub topic: String,
+    pub host_name: String,
+    pub host_color: u32,
+    pub quiz: Quiz,
+    pub window: Duration,
+    pub database: Database,
+}
+
+/// Play every question, then post standings and update the leaderboard
+///
+/// The channel must already be claimed with `start_session`; it is released
+/// when the game ends or is stopped.
+pub async fn run_game(game: TriviaGame, http: Arc<Http>) {
+    let channel = game.channel_id;
+    if let Err(e) = play(&game, &http).await {
+        warn!("🎲 Trivia game in {channel} failed: {e}");
+        let _ = channel
+            .say(
+                &http,
+                "⚠️ The trivia game ran into a problem and had to stop.",
+            )
+            .await;
+    }
+    end_session(channel.0);
+}
+
+async fn play(game: &TriviaGame, http: &Arc<Http>) -> Result<()> {
+    let channel = game.channel_id;
+    let total = game.quiz.questions.len();
+    let mut board = Scoreboard::default();
+    let mut played = 0;
+
+    let intro = if game.quiz.intro.is_empty() {
+        format!(
+            "Welcome to trivia! {total} questions on **{}**.",
+            game.topic
+        )
+    } else {
+        game.quiz.intro.clone()
+    };
+    channel
+        .send_message(http, |m| {
+            m.embed(|e| {
+                e.author(|a| a.name(&game.host_name))
+                    .title(format!("🎲 Trivia: {}", game.topic))
+                    .description(format!(
+                        "{intro}\n\n{total} questions, {}s each. Answer with the buttons or \
+                         type the letter; the faster you answer, the more points you get.",
+                        game.window.as_secs()
+                    ))
+                    .color(game.host_color)
+            })
+        })
+        .await?;
+    tokio::time::sleep(BETWEEN_QUESTIONS).await;
+
+    for (index, question) in game.quiz.questions.iter().enumerate() {
+        if is_cancelled(channel.0) {
+            break;
+        }
+        let number = index + 1;
+        open_round(channel.0, number, &question.choices);
+        let mut message = channel
+            .send_message(http, |m| {
+                m.set_embed(question_embed(game, question, number, total))
+                    .set_components(answer_buttons(channel.0, number, game.window, false))
+            })
+            .await?;
+
+        // Wait out the window, checking once a second for /trivia stop
+        for _ in 0..game.window.as_secs() {
+            if is_cancelled(channel.0) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+
+        let answers = close_round(channel.0);
+        played += 1;
+        let _ = message
+            .edit(http, |m| {
+                m.set_components(answer_buttons(channel.0, number, game.window, true))
+            })
+            .await;
+
+        let winners = board.score_round(&answers, question.answer, game.window);
+        channel
+            .send_message(http, |m| {
+                m.set_embed(reveal_embed(game, question, &winners, answers.len()))
+            })
+            .await?;
+
+        if number < total {
+            tokio::time::sleep(BETWEEN_QUESTIONS).await;
+        }
+    }
+
+    let stopped = played < total;
+    let ranked = board.ranked();
+    // Everyone tied on top of a finished game with points takes the win
+    let top_points = ranked
+        .first()
+        .map(|(_, points, _)| *points)
+        .filter(|points| *points > 0 && !stopped);
+    let mut winners = Vec::new();
+    for (user_id, points, correct) in ranked {
+        let won = top_points == Some(points);
+        game.database
+            .record_trivia_result(&game.guild_id, &user_id, points, correct, won)
+            .await?;
+        if won {
+            winners.push(user_id);
+        }
+    }
+    channel
+        .send_message(http, |m| {
+            m.set_embed(standings_embed(game, &board, played, stopped))
+        })
+        .await?;
+    for user_id in winners {
+        if let Err(e) = achievements::check(
+            &game.database,
+            http,
+            &game.guild_id,
+            &user_id,
+            Progress::default(),
+        )
+        .await
+        {
+            warn!("⚠️ Failed to check achievements for {user_id}: {e}");
+        }
+    }
+    info!(
+        "🎲 Trivia game in {channel} finished after {played}/{total} questions with {} players",
+        board.ranked().len()
+    );
+    Ok(())
+}
+
+fn question_embed(
+    game: &TriviaGame,
+    question: &TriviaQuestion,
+    number: usize,
+    total: usize,
+) -> CreateEmbed {
+    let choices = question
+        .choices
+        .iter()
+        .zip(LETTERS)
+        .map(|(choice, letter)| format!("**{letter})** {choice}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut embed = CreateEmbed::default();
+    embed
+        .author(|a| a.name(&game.host_name))
+        .title(format!("Question {number}/{total}"))
+        .description(format!("{}\n\n{choices}", question.question))
+        .footer(|f| {
+            f.text(format!(
+                "Answer with the buttons or type A-D · {}s",
+                game.window.as_secs()
+            ))
+        })
+        .color(game.host_color);
+    embed
+}
+
+fn reveal_embed(
+    game: &TriviaGame,
+    question: &TriviaQuestion,
+    winners: &[(String, i64)],
+    answered: usize,
+) -> CreateEmbed {
+    let mut description = format!(
+        "✅ **{}) {}**",
+        LETTERS[question.answer], question.choices[question.answer]
+    );
+    if !question.explanation.is_empty() {
+        description.push_str(&format!("\n{}", question.explanation));
+    }
+    description.push_str("\n\n");
+    if winners.is_empty() {
+        description.push_str(if answered == 0 {
+            "Nobody answered this one."
+        } else {
+            "Nobody got it right!"
+        });
+    } else {
+        let list = winners
+            .iter()
+            .map(|(user_id, points)| format!("<@{user_id}> +{points}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        description.push_str(&format!("Correct: {list}"));
+    }
+
+    let mut embed = CreateEmbed::default();
+    embed
+        .author(|a| a.name(&game.host_name))
+        .description(description)
+        .footer(|f| f.text(format!("{}/{answered} correct", winners.len())))
+        .color(game.host_color);
+    embed
+}
+
+fn standings_embed(
+    game: &TriviaGame,
+    board: &Scoreboard,
+    played: usize,
+    stopped: bool,
+) -> CreateEmbed {
+    let medals = ["🥇", "🥈", "🥉"];
+    let standings = if board.is_empty() {
+        "Nobody played this time.".to_string()
+    } else {
+        board
+            .ranked()
+            .iter()
+            .take(MAX_STANDINGS)
+            .enumerate()
+            .map(|(i, (user_id, points, correct))| {
+                let place = medals
+                    .get(i)
+                    .map(|m| m.to_string())
+                    .unwrap_or_else(|| format!("{}.", i + 1));
+                format!("{place} <@{user_id}>: **{points}** pts ({correct} correct)")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let title = if stopped {
+        format!("🏁 Trivia stopped after {played} questions")
+    } else {
+        "🏁 Final standings".to_string()
+    };
+
+    let mut embed = CreateEmbed::default();
+    embed
+        .author(|a| a.name(&game.host_name))
+        .title(title)
+        .description(standings)
+        .footer(|f| f.text("Scores were added to /trivia leaderboard"))
+        .color(game.host_color);
+    embed
+}
+
+/// A-D answer buttons for question `number`
+pub fn answer_buttons(
+    channel_id: u64,
+    number: usize,
+    window: Duration,
+    disabled: bool,
+) -> CreateComponents {
+    let mut components = CreateComponents::default();
+    components.create_action_row(|row| {
+        for (choice, letter) in LETTERS.iter().enumerate() {
+            let id = ComponentId::new(
+                TRIVIA_FEATURE,
+                "answer",
+                format!("{channel_id}{PAYLOAD_SEPARATOR}{number}{PAYLOAD_SEPARATOR}{choice}"),
+            )
+            .expires_in(window + BETWEEN_QUESTIONS);
+            row.create_button(|btn| {
+                btn.custom_id(id.to_string())
+                    .label(*letter)
+                    .style(ButtonStyle::Primary)
+                    .disabled(disabled)
+            });
+        }
+        row
+    });
+    components
+}
+
+/// Routes trivia answer buttons
+pub struct TriviaButtons;
+
+impl TriviaButtons {
+    fn parse_payload(id: &ComponentId) -> Option<(u64, usize, usize)> {
+        match id.payload_parts().as_slice() {
+            [channel, number, choice] => Some((
+                channel.parse().ok()?,
+                number.parse().ok()?,
+                choice.parse().ok()?,
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[async_trait]
+impl ComponentHandler for TriviaButtons {
+    fn features(&self) -> &'static [&'static str] {
+        &[TRIVIA_FEATURE]
+    }
+
+    async fn handle_component(
+        &self,
+        ctx: &Context,
+        interaction: &MessageComponentInteraction,
+        id: &ComponentId,
+    ) -> Result<()> {
+        let outcome = match Self::parse_payload(id) {
+            Some((channel_id, number, choice)) => (
+                record_answer(channel_id, &interaction.user.id.to_string(), number, choice),
+                choice,
+            ),
+            None => (AnswerOutcome::Closed, 0),
+        };
+        let content = match outcome {
+            (AnswerOutcome::Recorded, choice) => format!("🔒 Locked in **{}**", LETTERS[choice]),
+            (AnswerOutcome::AlreadyAnswered, _) => {
+                "You've already locked in an answer for this question.".to_string()
+            }
+            (AnswerOutcome::Closed, _) => "⌛ This question is closed.".to_string(),
+        };
+        interaction
+            .create_interaction_response(&ctx.http, |response| {
+                response
+                    .kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|message| message.content(content).ephemeral(true))
+            })
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answer_buttons_encode_round_and_choice() {
+        let components = answer_buttons(42, 3, Duration::from_secs(20), false);
+        let buttons = components.0[0]["components"].as_array().unwrap();
+        assert_eq!(buttons.len(), 4);
+
+        let id = ComponentId::parse(buttons[1]["custom_id"].as_str().unwrap()).unwrap();
+        assert_eq!(id.feature, TRIVIA_FEATURE);
+        assert_eq!(TriviaButtons::parse_payload(&id), Some((42, 3, 1)));
+        assert!(id.expires_at.is_some());
+
+        let closed = answer_buttons(42, 3, Duration::from_secs(20), true);
+        assert_eq!(closed.0[0]["components"][0]["disabled"], true);
+    }
+
+    #[test]
+    fn test_quiz_prompt_mentions_count_and_topic() {
+        let prompt = quiz_prompt("You are a bard.", "The Bard", "space", 5);
+        assert!(prompt.starts_with("You are a bard."));
+        assert!(prompt.contains("Write 5 multiple-choice questions about: space"));
+    }
+}
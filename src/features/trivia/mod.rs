@@ -0,0 +1,475 @@
+//! # Feature: Trivia
+//!
+//! `/trivia start` has a persona host generate themed multiple-choice
+//! questions and runs them one at a time in the channel. Each question stays
+//! open for a time window; members answer with the A-D buttons or by typing
+//! the letter (or the answer itself). Correct answers score points plus a
+//! speed bonus, and each game's totals are added to the guild leaderboard
+//! shown by `/trivia leaderboard`.
+//!
+//! - **Version**: 1.2.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.2.0: Game winners can earn the Trivia Champion badge
+//! - 1.1.0: Members' game wins are recorded for /profile
+//! - 1.0.0: Initial release with persona hosts, button and message answers, and leaderboards
+
+pub mod game;
+
+pub use game::{quiz_prompt, run_game, TriviaButtons, TriviaGame};
+
+use dashmap::DashMap;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Component feature namespace for answer buttons
+pub const TRIVIA_FEATURE: &str = "trivia";
+
+pub const DEFAULT_QUESTIONS: usize = 5;
+pub const MAX_QUESTIONS: usize = 10;
+
+pub const DEFAULT_ANSWER_SECONDS: u64 = 20;
+pub const MIN_ANSWER_SECONDS: u64 = 10;
+pub const MAX_ANSWER_SECONDS: u64 = 60;
+
+/// Points for a correct answer
+pub const CORRECT_POINTS: i64 = 10;
+
+/// Extra points for answering instantly, shrinking to zero at the deadline
+pub const MAX_SPEED_BONUS: i64 = 5;
+
+/// Answer labels, in choice order
+pub const LETTERS: [&str; 4] = ["A", "B", "C", "D"];
+
+/// One multiple-choice question
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TriviaQuestion {
+    pub question: String,
+    pub choices: Vec<String>,
+    /// Index into `choices`
+    pub answer: usize,
+    /// The host's in-character remark shown with the answer
+    pub explanation: String,
+}
+
+#[derive(Deserialize)]
+struct RawQuestion {
+    question: String,
+    choices: Vec<String>,
+    answer: serde_json::Value,
+    #[serde(default)]
+    explanation: String,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawQuiz {
+    Wrapped {
+        #[serde(default)]
+        intro: String,
+        questions: Vec<RawQuestion>,
+    },
+    Bare(Vec<RawQuestion>),
+}
+
+/// A generated quiz: the host's opening line and its questions
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Quiz {
+    pub intro: String,
+    pub questions: Vec<TriviaQuestion>,
+}
+
+/// Parse the model's JSON quiz, dropping malformed questions
+///
+/// Accepts `{"intro": ..., "questions": [...]}` or a bare array, optionally
+/// wrapped in a code fence. Each question needs four choices and an answer
+/// given as an index (0-3) or a letter.
+pub fn parse_quiz(raw: &str) -> Quiz {
+    let trimmed = raw.trim();
+    let json = trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|rest| rest.strip_suffix("```"))
+        .unwrap_or(trimmed)
+        .trim();
+
+    let (intro, raw_questions) = match serde_json::from_str::<RawQuiz>(json) {
+        Ok(RawQuiz::Wrapped { intro, questions }) => (intro, questions),
+        Ok(RawQuiz::Bare(questions)) => (String::new(), questions),
+        Err(_) => return Quiz::default(),
+    };
+
+    let questions = raw_questions
+        .into_iter()
+        .filter_map(|q| {
+            let choices: Vec<String> = q.choices.iter().map(|c| c.trim().to_string()).collect();
+            if q.question.trim().is_empty()
+                || choices.len() != LETTERS.len()
+                || choices.iter().any(String::is_empty)
+            {
+                return None;
+            }
+            let answer = match &q.answer {
+                serde_json::Value::Number(n) => n.as_u64().map(|n| n as usize),
+                serde_json::Value::String(s) => letter_index(s),
+                _ => None,
+            }
+            .filter(|a| *a < choices.len())?;
+            Some(TriviaQuestion {
+                question: q.question.trim().to_string(),
+                choices,
+                answer,
+                explanation: q.explanation.trim().to_string(),
+            })
+        })
+        .collect();
+
+    Quiz {
+        intro: intro.trim().to_string(),
+        questions,
+    }
+}
+
+fn letter_index(text: &str) -> Option<usize> {
+    let text = text.trim().trim_end_matches([')', '.']);
+    LETTERS.iter().position(|l| l.eq_ignore_ascii_case(text))
+}
+
+/// Read a typed answer: a letter (`b`, `B)`), a number (`2`) or the choice text
+pub fn parse_answer(text: &str, choices: &[String]) -> Option<usize> {
+    let text = text.trim();
+    if let Some(index) = letter_index(text) {
+        return Some(index).filter(|i| *i < choices.len());
+    }
+    if let Ok(n) = text.parse::<usize>() {
+        return (1..=choices.len()).contains(&n).then(|| n - 1);
+    }
+    choices.iter().position(|c| c.eq_ignore_ascii_case(text))
+}
+
+/// Points for a correct answer given after `elapsed` of a `window`
+pub fn score_answer(elapsed: Duration, window: Duration) -> i64 {
+    let remaining = window.saturating_sub(elapsed).as_secs_f64() / window.as_secs_f64().max(1.0);
+    CORRECT_POINTS + (MAX_SPEED_BONUS as f64 * remaining).round() as i64
+}
+
+/// A locked-in answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Answer {
+    pub choice: usize,
+    pub elapsed: Duration,
+}
+
+/// Result of trying to answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnswerOutcome {
+    Recorded,
+    /// The member already locked in an answer this round
+    AlreadyAnswered,
+    /// No question is open (or the button is from an earlier one)
+    Closed,
+}
+
+/// A game running in one channel
+#[derive(Debug)]
+pub struct TriviaSession {
+    pub guild_id: String,
+    pub host_id: String,
+    round: Option<OpenRound>,
+    cancelled: bool,
+}
+
+#[derive(Debug)]
+struct OpenRound {
+    number: usize,
+    choices: Vec<String>,
+    opened_at: Instant,
+    answers: HashMap<String, Answer>,
+}
+
+/// Global storage for running games (keyed by channel ID)
+static ACTIVE_TRIVIA: OnceLock<DashMap<u64, TriviaSession>> = OnceLock::new();
+
+/// Get or initialize the running games map
+pub fn get_active_trivia() -> &'static DashMap<u64, TriviaSession> {
+    ACTIVE_TRIVIA.get_or_init(DashMap::new)
+}
+
+/// Claim a channel for a new game; false when one is already running there
+pub fn start_session(channel_id: u64, guild_id: &str, host_id: &str) -> bool {
+    let games = get_active_trivia();
+    if games.contains_key(&channel_id) {
+        return false;
+    }
+    games.insert(
+        channel_id,
+        TriviaSession {
+            guild_id: guild_id.to_string(),
+            host_id: host_id.to_string(),
+            round: None,
+            cancelled: false,
+        },
+    );
+    true
+}
+
+/// Start accepting answers for question `number`
+pub fn open_round(channel_id: u64, number: usize, choices: &[String]) {
+    if let Some(mut session) = get_active_trivia().get_mut(&channel_id) {
+        session.round = Some(OpenRound {
+            number,
+            choices: choices.to_vec(),
+            opened_at: Instant::now(),
+            answers: HashMap::new(),
+        });
+    }
+}
+
+/// Stop accepting answers and return them
+pub fn close_round(channel_id: u64) -> HashMap<String, Answer> {
+    get_active_trivia()
+        .get_mut(&channel_id)
+        .and_then(|mut session| session.round.take())
+        .map(|round| round.answers)
+        .unwrap_or_default()
+}
+
+/// Lock in a button answer for question `number`
+pub fn record_answer(
+    channel_id: u64,
+    user_id: &str,
+    number: usize,
+    choice: usize,
+) -> AnswerOutcome {
+    let Some(mut session) = get_active_trivia().get_mut(&channel_id) else {
+        return AnswerOutcome::Closed;
+    };
+    match session.round.as_mut() {
+        Some(round) if round.number == number && choice < round.choices.len() => {
+            lock_in(round, user_id, choice)
+        }
+        _ => AnswerOutcome::Closed,
+    }
+}
+
+/// Treat a channel message as an answer if a question is open and the text
+/// reads as one; `None` means the message isn't an answer
+pub fn record_message_answer(channel_id: u64, user_id: &str, text: &str) -> Option<AnswerOutcome> {
+    let mut session = get_active_trivia().get_mut(&channel_id)?;
+    let round = session.round.as_mut()?;
+    let choice = parse_answer(text, &round.choices)?;
+    Some(lock_in(round, user_id, choice))
+}
+
+fn lock_in(round: &mut OpenRound, user_id: &str, choice: usize) -> AnswerOutcome {
+    if round.answers.contains_key(user_id) {
+        return AnswerOutcome::AlreadyAnswered;
+    }
+    round.answers.insert(
+        user_id.to_string(),
+        Answer {
+            choice,
+            elapsed: round.opened_at.elapsed(),
+        },
+    );
+    AnswerOutcome::Recorded
+}
+
+/// Ask a running game to stop after the current question
+pub fn cancel_session(channel_id: u64) -> bool {
+    match get_active_trivia().get_mut(&channel_id) {
+        Some(mut session) => {
+            session.cancelled = true;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Who started the game running in a channel
+pub fn session_host(channel_id: u64) -> Option<String> {
+    get_active_trivia()
+        .get(&channel_id)
+        .map(|session| session.host_id.clone())
+}
+
+pub fn is_cancelled(channel_id: u64) -> bool {
+    get_active_trivia()
+        .get(&channel_id)
+        .is_none_or(|session| session.cancelled)
+}
+
+pub fn end_session(channel_id: u64) {
+    get_active_trivia().remove(&channel_id);
+}
+
+/// Running totals for one game
+#[derive(Debug, Clone, Default)]
+pub struct Scoreboard {
+    scores: HashMap<String, (i64, i64)>,
+}
+
+impl Scoreboard {
+    /// Score one closed round; returns the correct answerers with their
+    /// points, fastest first
+    pub fn score_round(
+        &mut self,
+        answers: &HashMap<String, Answer>,
+        correct: usize,
+        window: Duration,
+    ) -> Vec<(String, i64)> {
+        let mut winners: Vec<(&String, &Answer)> = answers
+            .iter()
+            .filter(|(_, a)| a.choice == correct)
+            .collect();
+        winners.sort_by_key(|(_, a)| a.elapsed);
+
+        // Everyone who answered took part, right or wrong
+        for user_id in answers.keys() {
+            self.scores.entry(user_id.clone()).or_default();
+        }
+        winners
+            .into_iter()
+            .map(|(user_id, answer)| {
+                let points = score_answer(answer.elapsed, window);
+                let entry = self.scores.entry(user_id.clone()).or_default();
+                entry.0 += points;
+                entry.1 += 1;
+                (user_id.clone(), points)
+            })
+            .collect()
+    }
+
+    /// (user_id, points, correct answers), best first
+    pub fn ranked(&self) -> Vec<(String, i64, i64)> {
+        let mut ranked: Vec<(String, i64, i64)> = self
+            .scores
+            .iter()
+            .map(|(user_id, (points, correct))| (user_id.clone(), *points, *correct))
+            .collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then(b.2.cmp(&a.2)).then(a.0.cmp(&b.0)));
+        ranked
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn choices() -> Vec<String> {
+        ["Mercury", "Venus", "Earth", "Mars"]
+            .iter()
+            .map(|c| c.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_quiz_formats() {
+        let wrapped = r#"```json
+{"intro": "Gather round!", "questions": [
+  {"question": "Hottest planet?", "choices": ["Mercury", "Venus", "Earth", "Mars"], "answer": 1, "explanation": "Greenhouse!"},
+  {"question": "Red planet?", "choices": ["Mercury", "Venus", "Earth", "Mars"], "answer": "D"},
+  {"question": "Too few", "choices": ["A", "B"], "answer": 0},
+  {"question": "Bad answer", "choices": ["A", "B", "C", "D"], "answer": 7}
+]}
+```"#;
+        let quiz = parse_quiz(wrapped);
+        assert_eq!(quiz.intro, "Gather round!");
+        assert_eq!(quiz.questions.len(), 2);
+        assert_eq!(quiz.questions[0].answer, 1);
+        assert_eq!(quiz.questions[0].explanation, "Greenhouse!");
+        assert_eq!(quiz.questions[1].answer, 3);
+
+        let bare = r#"[{"question": "Q?", "choices": ["a", "b", "c", "d"], "answer": 0}]"#;
+        assert_eq!(parse_quiz(bare).questions.len(), 1);
+        assert!(parse_quiz("Sorry, I can't do that").questions.is_empty());
+    }
+
+    #[test]
+    fn test_parse_answer() {
+        let choices = choices();
+        assert_eq!(parse_answer("b", &choices), Some(1));
+        assert_eq!(parse_answer(" D) ", &choices), Some(3));
+        assert_eq!(parse_answer("3", &choices), Some(2));
+        assert_eq!(parse_answer("venus", &choices), Some(1));
+        assert_eq!(parse_answer("5", &choices), None);
+        assert_eq!(parse_answer("I think it's Venus", &choices), None);
+    }
+
+    #[test]
+    fn test_score_answer_speed_bonus() {
+        let window = Duration::from_secs(20);
+        assert_eq!(score_answer(Duration::ZERO, window), 15);
+        assert_eq!(score_answer(Duration::from_secs(10), window), 13);
+        assert_eq!(score_answer(Duration::from_secs(25), window), 10);
+    }
+
+    #[test]
+    fn test_session_rounds_and_answers() {
+        let channel = 900_001;
+        assert!(start_session(channel, "g1", "host"));
+        assert!(!start_session(channel, "g1", "host"));
+
+        // Nothing is accepted before a question opens
+        assert_eq!(record_answer(channel, "u1", 1, 0), AnswerOutcome::Closed);
+        assert_eq!(record_message_answer(channel, "u1", "a"), None);
+
+        open_round(channel, 1, &choices());
+        assert_eq!(record_answer(channel, "u1", 1, 1), AnswerOutcome::Recorded);
+        assert_eq!(
+            record_answer(channel, "u1", 1, 2),
+            AnswerOutcome::AlreadyAnswered
+        );
+        // Buttons from an earlier question don't count
+        assert_eq!(record_answer(channel, "u2", 0, 1), AnswerOutcome::Closed);
+        assert_eq!(
+            record_message_answer(channel, "u2", "Mars"),
+            Some(AnswerOutcome::Recorded)
+        );
+        assert_eq!(record_message_answer(channel, "u3", "lol"), None);
+
+        let answers = close_round(channel);
+        assert_eq!(answers.len(), 2);
+        assert_eq!(answers["u1"].choice, 1);
+        assert_eq!(answers["u2"].choice, 3);
+
+        assert_eq!(session_host(channel).as_deref(), Some("host"));
+        assert!(!is_cancelled(channel));
+        assert!(cancel_session(channel));
+        assert!(is_cancelled(channel));
+        end_session(channel);
+        assert!(is_cancelled(channel));
+        assert!(start_session(channel, "g1", "host"));
+        end_session(channel);
+    }
+
+    #[test]
+    fn test_scoreboard_ranks_by_points() {
+        let window = Duration::from_secs(20);
+        let answer = |choice, secs| Answer {
+            choice,
+            elapsed: Duration::from_secs(secs),
+        };
+        let mut board = Scoreboard::default();
+        let round = HashMap::from([
+            ("slow".to_string(), answer(1, 15)),
+            ("fast".to_string(), answer(1, 2)),
+            ("wrong".to_string(), answer(0, 1)),
+        ]);
+        let winners = board.score_round(&round, 1, window);
+        assert_eq!(winners[0].0, "fast");
+        assert_eq!(winners[1].0, "slow");
+
+        let ranked = board.ranked();
+        assert_eq!(ranked[0].0, "fast");
+        assert_eq!(ranked[2], ("wrong".to_string(), 0, 0));
+    }
+}
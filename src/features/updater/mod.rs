@@ -0,0 +1,541 @@
+//! # Feature: Self-Update
+//!
+//! Owner-only updates from GitHub releases. `/admin update` checks the latest
+//! release of `UPDATE_GITHUB_REPO`; installing either runs `UPDATE_SCRIPT`
+//! with the release tag or downloads the release binary, verifies it against
+//! the release's SHA-256 checksum and swaps it in place. The bot then drains
+//! running plugin jobs and exits cleanly so systemd restarts it.
+//!
+//! - **Version**: 1.0.1
+//! - **Since**: 4.7.0
+//! - **Toggleable**: false
+//!
+//! ## Changelog
+//! - 1.0.1: A failed binary swap moves the previous binary back into place
+//! - 1.0.0: Initial release with GitHub release check, checksum-verified install and graceful restart
+
+use anyhow::{anyhow, bail, Context as _, Result};
+use log::{info, warn};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::sync::Notify;
+
+use crate::features::get_bot_version;
+
+/// Release asset installed when UPDATE_ASSET_NAME isn't set
+pub const DEFAULT_ASSET_NAME: &str = "bot-x86_64-unknown-linux-gnu";
+
+/// How long running jobs get to finish before restarting anyway
+pub const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// GitHub rejects API requests without a User-Agent
+const USER_AGENT: &str = "persona-bot-updater";
+
+/// Where and how updates are fetched
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdateConfig {
+    /// `owner/name` of the GitHub repository publishing releases
+    pub repo: Option<String>,
+    pub asset_name: String,
+    /// Run with the release tag instead of downloading the binary
+    pub script: Option<String>,
+    /// Token for private repositories
+    pub github_token: Option<String>,
+    pub drain_timeout: Duration,
+    /// Binary to replace; the running executable when unset
+    pub executable: Option<PathBuf>,
+}
+
+impl UpdateConfig {
+    pub fn from_env() -> Self {
+        Self {
+            repo: std::env::var("UPDATE_GITHUB_REPO").ok(),
+            asset_name: std::env::var("UPDATE_ASSET_NAME")
+                .unwrap_or_else(|_| DEFAULT_ASSET_NAME.to_string()),
+            script: std::env::var("UPDATE_SCRIPT").ok(),
+            github_token: std::env::var("UPDATE_GITHUB_TOKEN").ok(),
+            drain_timeout: std::env::var("UPDATE_DRAIN_TIMEOUT_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_DRAIN_TIMEOUT),
+            executable: None,
+        }
+    }
+}
+
+/// A published GitHub release
+#[derive(Debug, Clone, Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    pub html_url: String,
+    #[serde(default)]
+    pub body: Option<String>,
+    #[serde(default)]
+    pub assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+impl Release {
+    /// Version without the leading `v`
+    pub fn version(&self) -> &str {
+        self.tag_name.trim_start_matches('v')
+    }
+
+    /// Whether this release is newer than the running bot
+    pub fn is_newer(&self) -> bool {
+        is_newer_version(self.version(), get_bot_version())
+    }
+
+    fn asset(&self, name: &str) -> Option<&ReleaseAsset> {
+        self.assets.iter().find(|a| a.name == name)
+    }
+
+    /// `<asset>.sha256`, falling back to a combined `SHA256SUMS` file
+    fn checksum_asset(&self, asset_name: &str) -> Option<&ReleaseAsset> {
+        self.asset(&format!("{asset_name}.sha256"))
+            .or_else(|| self.asset("SHA256SUMS"))
+            .or_else(|| self.asset("sha256sums.txt"))
+    }
+}
+
+/// Compare dotted numeric versions (`4.10.0` > `4.9.3`); unparseable parts count as 0
+pub fn is_newer_version(candidate: &str, current: &str) -> bool {
+    fn parts(version: &str) -> Vec<u64> {
+        version
+            .split(['.', '-', '+'])
+            .take(3)
+            .map(|p| p.parse().unwrap_or(0))
+            .collect()
+    }
+    let (mut a, mut b) = (parts(candidate), parts(current));
+    a.resize(3, 0);
+    b.resize(3, 0);
+    a > b
+}
+
+/// Expected SHA-256 for `asset_name` from a `.sha256` or `SHA256SUMS` file
+///
+/// Accepts a bare hash, `<hash>  <file>` lines, or `<hash> *<file>` lines.
+pub fn parse_checksum(contents: &str, asset_name: &str) -> Option<String> {
+    let is_hash = |s: &str| s.len() == 64 && s.chars().all(|c| c.is_ascii_hexdigit());
+    let lines: Vec<&str> = contents.lines().filter(|l| !l.trim().is_empty()).collect();
+
+    for line in &lines {
+        let mut fields = line.split_whitespace();
+        let (Some(hash), file) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        let matches_file = file
+            .map(|f| f.trim_start_matches('*') == asset_name)
+            .unwrap_or(lines.len() == 1);
+        if is_hash(hash) && matches_file {
+            return Some(hash.to_lowercase());
+        }
+    }
+    None
+}
+
+/// Fetch the latest release of `repo`
+pub async fn latest_release(config: &UpdateConfig) -> Result<Release> {
+    let repo = config
+        .repo
+        .as_deref()
+        .ok_or_else(|| anyhow!("UPDATE_GITHUB_REPO is not set"))?;
+    let url = format!("https://api.github.com/repos/{repo}/releases/latest");
+
+    let mut request = reqwest::Client::new()
+        .get(&url)
+        .header("User-Agent", USER_AGENT)
+        .header("Accept", "application/vnd.github+json");
+    if let Some(token) = &config.github_token {
+        request = request.bearer_auth(token);
+    }
+
+    let response = request.send().await?;
+    if !response.status().is_success() {
+        bail!("GitHub returned {} for {url}", response.status());
+    }
+    Ok(response.json().await?)
+}
+
+/// Install `release`: run the update script, or download and swap the binary
+///
+/// Returns a short description of what was done.
+pub async fn install_release(config: &UpdateConfig, release: &Release) -> Result<String> {
+    if let Some(script) = &config.script {
+        let output = Command::new(script)
+            .arg(&release.tag_name)
+            .output()
+            .await
+            .with_context(|| format!("failed to run {script}"))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("{script} exited with {}: {}", output.status, stderr.trim());
+        }
+        info!("Update script {script} installed {}", release.tag_name);
+        return Ok(format!("Ran `{script} {}`", release.tag_name));
+    }
+
+    let asset = release
+        .asset(&config.asset_name)
+        .ok_or_else(|| anyhow!("release has no `{}` asset", config.asset_name))?;
+    let checksum_asset = release
+        .checksum_asset(&config.asset_name)
+        .ok_or_else(|| anyhow!("release has no checksum for `{}`", config.asset_name))?;
+
+    let client = reqwest::Client::new();
+    let checksums = download(&client, config, checksum_asset).await?;
+    let expected = parse_checksum(&String::from_utf8_lossy(&checksums), &config.asset_name)
+        .ok_or_else(|| {
+            anyhow!(
+                "no checksum for `{}` in {}",
+                config.asset_name,
+                checksum_asset.name
+            )
+        })?;
+
+    let exe = match &config.executable {
+        Some(path) => path.clone(),
+        None => std::env::current_exe()?,
+    };
+    let staged = sibling(&exe, "new");
+    let binary = download(&client, config, asset).await?;
+    tokio::fs::write(&staged, &binary).await?;
+
+    let actual = sha256_file(&staged).await?;
+    if actual != expected {
+        let _ = tokio::fs::remove_file(&staged).await;
+        bail!(
+            "checksum mismatch for {}: expected {expected}, got {actual}",
+            asset.name
+        );
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        tokio::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o755)).await?;
+    }
+
+    swap_binary(&exe, &staged).await?;
+    info!(
+        "Installed {} ({} bytes, sha256 {actual})",
+        release.tag_name,
+        binary.len()
+    );
+    Ok(format!(
+        "Installed `{}` ({} bytes, checksum verified)",
+        asset.name,
+        binary.len()
+    ))
+}
+
+async fn download(
+    client: &reqwest::Client,
+    config: &UpdateConfig,
+    asset: &ReleaseAsset,
+) -> Result<Vec<u8>> {
+    let mut request = client
+        .get(&asset.browser_download_url)
+        .header("User-Agent", USER_AGENT);
+    if let Some(token) = &config.github_token {
+        request = request.bearer_auth(token);
+    }
+    let response = request.send().await?;
+    if !response.status().is_success() {
+        bail!(
+            "download of {} failed with {}",
+            asset.name,
+            response.status()
+        );
+    }
+    Ok(response.bytes().await?.to_vec())
+}
+
+/// Move `staged` over `exe`, keeping the previous binary as `.old`
+///
+/// The `.old` copy lets a bad release be rolled back by hand. If the staged
+/// binary can't be moved in, the previous binary is put back so the bot
+/// still has something to restart into.
+async fn swap_binary(exe: &Path, staged: &Path) -> Result<()> {
+    let old = sibling(exe, "old");
+    tokio::fs::rename(exe, &old)
+        .await
+        .with_context(|| format!("failed to move {} aside", exe.display()))?;
+    if let Err(e) = tokio::fs::rename(staged, exe).await {
+        if let Err(restore) = tokio::fs::rename(&old, exe).await {
+            warn!(
+                "Failed to restore {} from {}: {restore}",
+                exe.display(),
+                old.display()
+            );
+        }
+        let _ = tokio::fs::remove_file(staged).await;
+        return Err(e).with_context(|| format!("failed to install {}", staged.display()));
+    }
+    Ok(())
+}
+
+/// SHA-256 of a file via `sha256sum`
+async fn sha256_file(path: &Path) -> Result<String> {
+    let output = Command::new("sha256sum").arg(path).output().await?;
+    if !output.status.success() {
+        bail!("sha256sum exited with {}", output.status);
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .next()
+        .map(str::to_lowercase)
+        .ok_or_else(|| anyhow!("sha256sum produced no output"))
+}
+
+/// `bot` -> `bot.<suffix>` in the same directory
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Wait until `active_jobs` reports nothing running, or `timeout` passes
+///
+/// Returns the number of jobs still running when it gave up.
+pub async fn drain_jobs(active_jobs: impl Fn() -> usize, timeout: Duration) -> usize {
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        let active = active_jobs();
+        if active == 0 || tokio::time::Instant::now() >= deadline {
+            return active;
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
+
+fn restart_notify() -> &'static Notify {
+    static RESTART: OnceLock<Notify> = OnceLock::new();
+    RESTART.get_or_init(Notify::new)
+}
+
+/// Ask `main` to shut down cleanly (systemd brings the new binary up)
+pub fn request_restart() {
+    warn!("Restart requested");
+    restart_notify().notify_one();
+}
+
+/// Resolves once a restart has been requested
+pub async fn restart_requested() {
+    restart_notify().notified().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(tag: &str, assets: &[&str]) -> Release {
+        Release {
+            tag_name: tag.to_string(),
+            html_url: format!("https://github.com/o/r/releases/tag/{tag}"),
+            body: None,
+            assets: assets
+                .iter()
+                .map(|name| ReleaseAsset {
+                    name: name.to_string(),
+                    browser_download_url: format!("https://example.com/{name}"),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_is_newer_version() {
+        assert!(is_newer_version("4.10.0", "4.9.3"));
+        assert!(is_newer_version("5.0", "4.99.99"));
+        assert!(!is_newer_version("4.6.1", "4.6.1"));
+        assert!(!is_newer_version("4.6.0", "4.6.1"));
+        assert!(is_newer_version("4.7.0-rc1", "4.6.1"));
+    }
+
+    #[test]
+    fn test_release_version_and_assets() {
+        let newer = release("v99.0.0", &["bot-x86_64-unknown-linux-gnu", "SHA256SUMS"]);
+        assert_eq!(newer.version(), "99.0.0");
+        assert!(newer.is_newer());
+        assert_eq!(
+            newer
+                .checksum_asset("bot-x86_64-unknown-linux-gnu")
+                .unwrap()
+                .name,
+            "SHA256SUMS"
+        );
+
+        let dedicated = release("v1.0.0", &["bot", "bot.sha256", "SHA256SUMS"]);
+        assert_eq!(dedicated.checksum_asset("bot").unwrap().name, "bot.sha256");
+    }
+
+    #[test]
+    fn test_parse_checksum() {
+        let hash = "a".repeat(64);
+        let other = "b".repeat(64);
+
+        assert_eq!(parse_checksum(&hash, "bot"), Some(hash.clone()));
+        assert_eq!(
+            parse_checksum(&format!("{hash}  bot\n"), "bot"),
+            Some(hash.clone())
+        );
+
+        let sums = format!("{other}  tui\n{hash} *bot\n");
+        assert_eq!(parse_checksum(&sums, "bot"), Some(hash.clone()));
+        assert_eq!(parse_checksum(&sums, "missing"), None);
+        assert_eq!(parse_checksum("not-a-hash  bot", "bot"), None);
+    }
+
+    #[test]
+    fn test_sibling() {
+        assert_eq!(
+            sibling(Path::new("/srv/bot/target/release/bot"), "new"),
+            PathBuf::from("/srv/bot/target/release/bot.new")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_drain_jobs() {
+        assert_eq!(drain_jobs(|| 0, Duration::from_secs(5)).await, 0);
+        assert_eq!(drain_jobs(|| 2, Duration::ZERO).await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_sha256_file() {
+        let path = std::env::temp_dir().join(format!("sha-{}", uuid::Uuid::new_v4()));
+        tokio::fs::write(&path, b"abc").await.unwrap();
+        let hash = sha256_file(&path).await.unwrap();
+        let _ = tokio::fs::remove_file(&path).await;
+        assert_eq!(
+            hash,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    /// Serve `files` by name over HTTP, returning the base URL
+    async fn serve_files(files: Vec<(&'static str, Vec<u8>)>) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = vec![0u8; 4096];
+                let read = stream.read(&mut request).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&request[..read]);
+                let path = request.split_whitespace().nth(1).unwrap_or("/");
+                let response = match files.iter().find(|(name, _)| path == format!("/{name}")) {
+                    Some((_, body)) => {
+                        let mut response = format!(
+                            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                            body.len()
+                        )
+                        .into_bytes();
+                        response.extend_from_slice(body);
+                        response
+                    }
+                    None => {
+                        b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                            .to_vec()
+                    }
+                };
+                let _ = stream.write_all(&response).await;
+            }
+        });
+        format!("http://{addr}")
+    }
+
+    /// A temp dir holding an `old` binary and a config that replaces it
+    async fn install_fixture(checksums: &str) -> (PathBuf, UpdateConfig, Release) {
+        let dir = std::env::temp_dir().join(format!("updater-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let exe = dir.join("bot");
+        tokio::fs::write(&exe, b"old").await.unwrap();
+
+        let base = serve_files(vec![
+            ("bot-linux", b"new".to_vec()),
+            ("bot-linux.sha256", checksums.as_bytes().to_vec()),
+        ])
+        .await;
+        let mut release = release("v9.9.9", &["bot-linux", "bot-linux.sha256"]);
+        for asset in &mut release.assets {
+            asset.browser_download_url = format!("{base}/{}", asset.name);
+        }
+        let config = UpdateConfig {
+            repo: Some("o/r".to_string()),
+            asset_name: "bot-linux".to_string(),
+            script: None,
+            github_token: None,
+            drain_timeout: Duration::ZERO,
+            executable: Some(exe),
+        };
+        (dir, config, release)
+    }
+
+    /// sha256 of `new`
+    const NEW_SHA256: &str = "11507a0e2f5e69d5dfa40a62a1bd7b6ee57e6bcd85c67c9b8431b36fff21c437";
+
+    #[tokio::test]
+    async fn test_install_release_swaps_binary() {
+        let (dir, config, release) = install_fixture(&format!("{NEW_SHA256}  bot-linux\n")).await;
+        let result = install_release(&config, &release).await;
+        let installed = tokio::fs::read(dir.join("bot")).await.unwrap();
+        let previous = tokio::fs::read(dir.join("bot.old")).await.unwrap();
+        let staged_left = dir.join("bot.new").exists();
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+
+        assert!(result.unwrap().contains("checksum verified"));
+        assert_eq!(installed, b"new");
+        assert_eq!(previous, b"old");
+        assert!(!staged_left);
+    }
+
+    #[tokio::test]
+    async fn test_install_release_keeps_binary_on_checksum_mismatch() {
+        let wrong = "0".repeat(64);
+        let (dir, config, release) = install_fixture(&format!("{wrong}  bot-linux\n")).await;
+        let result = install_release(&config, &release).await;
+        let kept = tokio::fs::read(dir.join("bot")).await.unwrap();
+        let old_left = dir.join("bot.old").exists();
+        let staged_left = dir.join("bot.new").exists();
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("checksum mismatch"));
+        assert_eq!(kept, b"old");
+        assert!(!old_left);
+        assert!(!staged_left);
+    }
+
+    #[tokio::test]
+    async fn test_swap_binary_restores_previous_on_failure() {
+        let dir = std::env::temp_dir().join(format!("updater-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let exe = dir.join("bot");
+        tokio::fs::write(&exe, b"old").await.unwrap();
+
+        // The staged binary vanished, so moving it into place fails
+        let result = swap_binary(&exe, &dir.join("bot.new")).await;
+        let kept = tokio::fs::read(&exe).await.unwrap();
+        let old_left = dir.join("bot.old").exists();
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+
+        assert!(result.is_err());
+        assert_eq!(kept, b"old");
+        assert!(!old_left);
+    }
+}
@@ -0,0 +1,74 @@
+//! The verify button on gated-channel prompts
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serenity::model::application::interaction::message_component::MessageComponentInteraction;
+use serenity::prelude::Context;
+
+use super::{complete_verification, VERIFICATION_FEATURE};
+use crate::commands::components::{ComponentHandler, ComponentId};
+use crate::commands::InteractionResponder;
+use crate::database::Database;
+
+/// Routes verify button clicks
+pub struct VerificationButtons {
+    database: Database,
+}
+
+impl VerificationButtons {
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+}
+
+#[async_trait]
+impl ComponentHandler for VerificationButtons {
+    fn features(&self) -> &'static [&'static str] {
+        &[VERIFICATION_FEATURE]
+    }
+
+    async fn handle_component(
+        &self,
+        ctx: &Context,
+        interaction: &MessageComponentInteraction,
+        id: &ComponentId,
+    ) -> Result<()> {
+        let user_id = interaction.user.id.to_string();
+        if id.action != "verify" {
+            return InteractionResponder::for_component(interaction)
+                .reply_ephemeral(ctx, "Unknown verification action.")
+                .await;
+        }
+        if id.payload != user_id {
+            return InteractionResponder::for_component(interaction)
+                .reply_ephemeral(
+                    ctx,
+                    "This button is for someone else. You'll get your own when you join.",
+                )
+                .await;
+        }
+        let Some(guild_id) = interaction.guild_id.map(|id| id.to_string()) else {
+            return Ok(());
+        };
+        let Some(pending) = self
+            .database
+            .get_pending_verification(&guild_id, &user_id)
+            .await?
+        else {
+            return InteractionResponder::for_component(interaction)
+                .reply_ephemeral(ctx, "You're already verified.")
+                .await;
+        };
+
+        complete_verification(&ctx.http, &self.database, &pending).await?;
+        InteractionResponder::for_component(interaction)
+            .reply_ephemeral(ctx, "✅ You're verified. Welcome!")
+            .await
+    }
+}
@@ -0,0 +1,242 @@
+//! Greet new members in the gated channel and start their verification
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use chrono::Utc;
+use log::{info, warn};
+use openai::chat::ChatCompletionMessageRole;
+use serenity::http::Http;
+use serenity::model::guild::Member;
+use serenity::model::id::ChannelId;
+use std::sync::Arc;
+
+use super::{
+    button_prompt, deadline_note, fallback_question, verify_button, Challenge, VerificationMode,
+    CHANNEL_SETTING, MODE_SETTING, ROLE_SETTING, TIMEOUT_SETTING, VERIFICATION_FEATURE,
+};
+use crate::core::{chat_message, ChatClient};
+use crate::database::{Database, PendingVerification};
+use crate::features::analytics::{CostBucket, UsageTracker};
+use crate::features::guardrails::ChannelGrounding;
+use crate::features::personas::themes::{apply_theme, ACTIVE_THEME_SETTING};
+use crate::features::personas::PersonaManager;
+
+/// Cap on generated tokens; the reply is a short greeting
+const MAX_GREETING_TOKENS: u64 = 150;
+
+/// User id usage is logged under, and persona lookups are made for
+pub const VERIFICATION_USER_ID: &str = "system_verification";
+
+/// Starts verification for members joining gated guilds
+pub struct VerificationGate {
+    database: Database,
+    chat_client: Arc<dyn ChatClient>,
+    openai_model: String,
+    usage_tracker: UsageTracker,
+    persona_manager: PersonaManager,
+}
+
+impl VerificationGate {
+    pub fn new(
+        database: Database,
+        chat_client: Arc<dyn ChatClient>,
+        openai_model: String,
+        usage_tracker: UsageTracker,
+    ) -> Self {
+        Self {
+            database,
+            chat_client,
+            openai_model,
+            usage_tracker,
+            persona_manager: PersonaManager::new(),
+        }
+    }
+
+    /// Post the verification prompt for a new member, when the guild is gated
+    pub async fn on_member_join(&self, http: &Http, member: &Member) -> Result<()> {
+        if member.user.bot {
+            return Ok(());
+        }
+        let guild_id = member.guild_id.to_string();
+        let user_id = member.user.id.to_string();
+        if !self
+            .database
+            .is_feature_enabled(VERIFICATION_FEATURE, None, Some(&guild_id))
+            .await?
+        {
+            return Ok(());
+        }
+        let (Some(channel_id), Some(_)) = (
+            self.database
+                .get_guild_setting(&guild_id, CHANNEL_SETTING)
+                .await?,
+            self.database
+                .get_guild_setting(&guild_id, ROLE_SETTING)
+                .await?,
+        ) else {
+            return Ok(());
+        };
+        let mode = VerificationMode::from_setting(
+            self.database
+                .get_guild_setting(&guild_id, MODE_SETTING)
+                .await?
+                .as_deref(),
+        );
+        let timeout_minutes = self
+            .database
+            .get_guild_setting(&guild_id, TIMEOUT_SETTING)
+            .await?
+            .and_then(|v| v.parse::<i64>().ok())
+            .unwrap_or(0);
+        let deadline = (timeout_minutes > 0).then(|| Utc::now().timestamp() + timeout_minutes * 60);
+
+        let channel = ChannelId(channel_id.parse()?);
+        let (message, answer) = match mode {
+            VerificationMode::Button => {
+                let components = verify_button(&user_id);
+                let content = format!("{}{}", button_prompt(&user_id), deadline_note(deadline));
+                let message = channel
+                    .send_message(http, |m| {
+                        m.content(content)
+                            .set_components(components)
+                            .allowed_mentions(|a| a.users([member.user.id]))
+                    })
+                    .await?;
+                (message, None)
+            }
+            VerificationMode::Question => {
+                let challenge = Challenge::random();
+                let greeting = match self
+                    .persona_greeting(&guild_id, &channel_id, &member.user.name, &challenge)
+                    .await
+                {
+                    Ok(greeting) => format!("<@{user_id}> {greeting}"),
+                    Err(e) => {
+                        warn!("⚠️ Persona verification greeting failed in guild {guild_id}: {e}");
+                        fallback_question(&user_id, &challenge)
+                    }
+                };
+                let content = format!("{greeting}{}", deadline_note(deadline));
+                let message = channel
+                    .send_message(http, |m| {
+                        m.content(content)
+                            .allowed_mentions(|a| a.users([member.user.id]))
+                    })
+                    .await?;
+                (message, Some(challenge.answer.to_string()))
+            }
+        };
+
+        self.database
+            .upsert_pending_verification(&PendingVerification {
+                guild_id: guild_id.clone(),
+                user_id: user_id.clone(),
+                channel_id,
+                message_id: Some(message.id.to_string()),
+                answer,
+                deadline,
+            })
+            .await?;
+        info!(
+            "🚪 Verification started for {user_id} in guild {guild_id} ({})",
+            mode.as_str()
+        );
+        Ok(())
+    }
+
+    /// Forget a member's pending verification when they leave
+    pub async fn on_member_leave(&self, http: &Http, guild_id: &str, user_id: &str) -> Result<()> {
+        if let Some(pending) = self
+            .database
+            .get_pending_verification(guild_id, user_id)
+            .await?
+        {
+            self.database
+                .delete_pending_verification(guild_id, user_id)
+                .await?;
+            super::delete_prompt(http, &pending).await;
+        }
+        Ok(())
+    }
+
+    /// Have the channel's persona welcome the member and ask the challenge
+    async fn persona_greeting(
+        &self,
+        guild_id: &str,
+        channel_id: &str,
+        member_name: &str,
+        challenge: &Challenge,
+    ) -> Result<String> {
+        let persona_name = self
+            .database
+            .get_persona_with_channel(VERIFICATION_USER_ID, guild_id, channel_id, None)
+            .await
+            .unwrap_or_else(|_| "obi".to_string());
+        let theme = self
+            .database
+            .get_guild_setting(guild_id, ACTIVE_THEME_SETTING)
+            .await?;
+        let persona_prompt = apply_theme(
+            self.persona_manager.get_system_prompt(&persona_name, None),
+            theme.as_deref(),
+        );
+        let grounding =
+            ChannelGrounding::for_channel(&self.database, Some(guild_id), Some(channel_id)).await?;
+        let completion = self
+            .chat_client
+            .create_chat_completion_limited(
+                &self.openai_model,
+                vec![
+                    chat_message(
+                        ChatCompletionMessageRole::System,
+                        grounding.persona_prompt(&format!("{persona_prompt}\n\n{GREETING_PROMPT}")),
+                    ),
+                    chat_message(
+                        ChatCompletionMessageRole::User,
+                        format!(
+                            "New member: {member_name}\nQuestion to ask word for word: {}",
+                            challenge.question
+                        ),
+                    ),
+                ],
+                Some(MAX_GREETING_TOKENS),
+            )
+            .await?;
+
+        if let Some(usage) = &completion.usage {
+            self.usage_tracker.log_chat(
+                &self.openai_model,
+                usage.prompt_tokens,
+                usage.completion_tokens,
+                usage.total_tokens,
+                VERIFICATION_USER_ID,
+                Some(guild_id),
+                Some(channel_id),
+                None,
+                CostBucket::Verification,
+            );
+        }
+
+        let greeting = completion
+            .choices
+            .first()
+            .and_then(|choice| choice.message.content.clone())
+            .map(|content| content.trim().to_string())
+            .unwrap_or_default();
+        // The member has to be able to see the exact question they're answering
+        if !greeting.contains(&challenge.question) {
+            anyhow::bail!("Greeting left out the question");
+        }
+        Ok(greeting)
+    }
+}
+
+const GREETING_PROMPT: &str = "A new member just joined this Discord server and has to answer a \
+    simple question before they get access. In your characteristic style, welcome them in one or \
+    two sentences and ask the question you're given exactly as written, then tell them to reply \
+    in this channel with the answer. Don't give the answer away. No preamble.";
@@ -0,0 +1,335 @@
+//! # Feature: Verification Gate
+//!
+//! New members are greeted in the gated channel picked with
+//! `/verification setup` and must prove they're human before they get the
+//! member role: either by clicking a button or, in question mode, by replying
+//! with the answer to a simple question the channel's persona asks. Members
+//! who haven't verified when the configured timeout runs out are kicked.
+//!
+//! - **Version**: 1.0.1
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.1: Answers are only looked up in the gated channel of guilds with verification on
+//! - 1.0.0: Initial release with button and persona question modes and timeout kicks
+
+pub mod buttons;
+pub mod gate;
+pub mod scheduler;
+
+pub use buttons::VerificationButtons;
+pub use gate::VerificationGate;
+pub use scheduler::VerificationScheduler;
+
+use anyhow::Result;
+use log::{info, warn};
+use rand::Rng;
+use serenity::builder::CreateComponents;
+use serenity::http::Http;
+use serenity::model::application::component::ButtonStyle;
+use serenity::model::channel::Message;
+
+use crate::commands::components::ComponentId;
+use crate::database::{Database, PendingVerification};
+
+/// Feature id for toggles and the component namespace
+pub const VERIFICATION_FEATURE: &str = "verification";
+
+/// Guild setting holding the gated channel prompts are posted in
+pub const CHANNEL_SETTING: &str = "verification_channel";
+
+/// Guild setting holding the role granted once a member verifies
+pub const ROLE_SETTING: &str = "verification_role";
+
+/// Guild setting holding the [`VerificationMode`]
+pub const MODE_SETTING: &str = "verification_mode";
+
+/// Guild setting holding minutes before unverified members are kicked (0 = never)
+pub const TIMEOUT_SETTING: &str = "verification_timeout_minutes";
+
+/// Longest timeout an admin can pick, one day
+pub const MAX_TIMEOUT_MINUTES: i64 = 1440;
+
+/// How a new member proves they're human
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerificationMode {
+    Button,
+    Question,
+}
+
+impl VerificationMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            VerificationMode::Button => "button",
+            VerificationMode::Question => "question",
+        }
+    }
+
+    /// Parse a stored setting, defaulting to the button
+    pub fn from_setting(value: Option<&str>) -> Self {
+        match value {
+            Some("question") => VerificationMode::Question,
+            _ => VerificationMode::Button,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            VerificationMode::Button => "click a button",
+            VerificationMode::Question => "answer a question from the persona",
+        }
+    }
+}
+
+/// A simple question with a single numeric answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Challenge {
+    pub question: String,
+    pub answer: i64,
+}
+
+impl Challenge {
+    pub fn new(a: i64, b: i64) -> Self {
+        Self {
+            question: format!("What is {a} + {b}?"),
+            answer: a + b,
+        }
+    }
+
+    pub fn random() -> Self {
+        let mut rng = rand::rng();
+        Self::new(rng.random_range(2..=12), rng.random_range(2..=12))
+    }
+}
+
+/// Whether a reply contains the expected number as its first number
+pub fn check_answer(expected: &str, reply: &str) -> bool {
+    reply
+        .split(|c: char| !c.is_ascii_digit())
+        .find(|token| !token.is_empty())
+        == Some(expected)
+}
+
+/// Prompt text for the plain challenge when the persona is unavailable
+pub fn fallback_question(user_id: &str, challenge: &Challenge) -> String {
+    format!(
+        "👋 Welcome <@{user_id}>! To get access, reply here with the answer: **{}**",
+        challenge.question
+    )
+}
+
+/// Greeting with the button members click to verify
+pub fn button_prompt(user_id: &str) -> String {
+    format!(
+        "👋 Welcome <@{user_id}>! Click the button below to confirm you're human and get access."
+    )
+}
+
+/// Line about the kick deadline appended to prompts
+pub fn deadline_note(deadline: Option<i64>) -> String {
+    match deadline {
+        Some(deadline) => {
+            format!("\n⏳ Please verify <t:{deadline}:R> or you'll be removed from the server.")
+        }
+        None => String::new(),
+    }
+}
+
+/// The verify button, only usable by the member it was posted for
+pub fn verify_button(user_id: &str) -> CreateComponents {
+    let id = ComponentId::new(VERIFICATION_FEATURE, "verify", user_id);
+    let mut components = CreateComponents::default();
+    components.create_action_row(|row| {
+        row.create_button(|btn| {
+            btn.custom_id(id.to_string())
+                .label("I'm human")
+                .emoji('✅')
+                .style(ButtonStyle::Success)
+        })
+    });
+    components
+}
+
+/// Grant the member role, clear the pending row and remove the prompt
+///
+/// Returns false when another attempt already finished the verification.
+pub async fn complete_verification(
+    http: &Http,
+    database: &Database,
+    pending: &PendingVerification,
+) -> Result<bool> {
+    let Some(role_id) = database
+        .get_guild_setting(&pending.guild_id, ROLE_SETTING)
+        .await?
+    else {
+        anyhow::bail!("No verification role set in guild {}", pending.guild_id);
+    };
+    http.add_member_role(
+        pending.guild_id.parse()?,
+        pending.user_id.parse()?,
+        role_id.parse()?,
+        Some("Passed verification"),
+    )
+    .await?;
+    if !database
+        .delete_pending_verification(&pending.guild_id, &pending.user_id)
+        .await?
+    {
+        return Ok(false);
+    }
+    info!(
+        "✅ {} passed verification in guild {}",
+        pending.user_id, pending.guild_id
+    );
+    delete_prompt(http, pending).await;
+    Ok(true)
+}
+
+/// Remove a verification prompt, ignoring prompts that are already gone
+pub async fn delete_prompt(http: &Http, pending: &PendingVerification) {
+    let Some(message_id) = &pending.message_id else {
+        return;
+    };
+    let (Ok(channel_id), Ok(message_id)) = (pending.channel_id.parse(), message_id.parse()) else {
+        return;
+    };
+    if let Err(e) = http.delete_message(channel_id, message_id).await {
+        warn!("⚠️ Failed to delete verification prompt {message_id}: {e}");
+    }
+}
+
+/// Whether verification is on in the guild and the channel is its gated channel
+///
+/// Both lookups go through the settings cache, so ordinary chat never reaches
+/// the pending verification table.
+pub async fn is_gated_channel(
+    database: &Database,
+    guild_id: &str,
+    channel_id: &str,
+) -> Result<bool> {
+    if !database
+        .is_feature_enabled(VERIFICATION_FEATURE, None, Some(guild_id))
+        .await?
+    {
+        return Ok(false);
+    }
+    Ok(database
+        .get_guild_setting(guild_id, CHANNEL_SETTING)
+        .await?
+        .is_some_and(|gated| gated == channel_id))
+}
+
+/// Check a gated-channel message against the author's pending question
+///
+/// Returns true when the message was a verification attempt, so it isn't
+/// treated as chat.
+pub async fn handle_answer(http: &Http, database: &Database, msg: &Message) -> Result<bool> {
+    let Some(guild_id) = msg.guild_id else {
+        return Ok(false);
+    };
+    if !is_gated_channel(database, &guild_id.to_string(), &msg.channel_id.to_string()).await? {
+        return Ok(false);
+    }
+    let Some(pending) = database
+        .get_pending_verification(&guild_id.to_string(), &msg.author.id.to_string())
+        .await?
+    else {
+        return Ok(false);
+    };
+    let Some(answer) = &pending.answer else {
+        return Ok(false);
+    };
+    if pending.channel_id != msg.channel_id.to_string() {
+        return Ok(false);
+    }
+
+    if check_answer(answer, &msg.content) {
+        complete_verification(http, database, &pending).await?;
+        let _ = msg.delete(http).await;
+    } else {
+        let _ = msg.react(http, '❌').await;
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mode_from_setting() {
+        assert_eq!(
+            VerificationMode::from_setting(None),
+            VerificationMode::Button
+        );
+        assert_eq!(
+            VerificationMode::from_setting(Some("question")),
+            VerificationMode::Question
+        );
+        assert_eq!(
+            VerificationMode::from_setting(Some("bogus")),
+            VerificationMode::Button
+        );
+        assert_eq!(
+            VerificationMode::from_setting(Some(VerificationMode::Question.as_str())),
+            VerificationMode::Question
+        );
+    }
+
+    #[test]
+    fn test_challenge() {
+        let challenge = Challenge::new(3, 4);
+        assert_eq!(challenge.question, "What is 3 + 4?");
+        assert_eq!(challenge.answer, 7);
+        for _ in 0..20 {
+            let random = Challenge::random();
+            assert!((4..=24).contains(&random.answer));
+        }
+    }
+
+    #[test]
+    fn test_check_answer() {
+        assert!(check_answer("7", "7"));
+        assert!(check_answer("7", "it's 7!"));
+        assert!(check_answer("7", " 7 "));
+        assert!(!check_answer("7", "8"));
+        assert!(!check_answer("7", "17"));
+        assert!(!check_answer("7", "seven"));
+        assert!(!check_answer("7", "3 + 4 = 7"));
+    }
+
+    #[test]
+    fn test_prompts() {
+        assert!(fallback_question("42", &Challenge::new(2, 2)).ends_with("**What is 2 + 2?**"));
+        assert!(button_prompt("42").starts_with("👋 Welcome <@42>!"));
+        assert_eq!(deadline_note(None), "");
+        assert!(deadline_note(Some(1_700_000_000)).contains("<t:1700000000:R>"));
+    }
+
+    #[tokio::test]
+    async fn test_is_gated_channel() {
+        let db = Database::new(":memory:").await.unwrap();
+        assert!(!is_gated_channel(&db, "g1", "c1").await.unwrap());
+
+        db.set_guild_setting("g1", CHANNEL_SETTING, "c1")
+            .await
+            .unwrap();
+        assert!(is_gated_channel(&db, "g1", "c1").await.unwrap());
+        assert!(!is_gated_channel(&db, "g1", "c2").await.unwrap());
+
+        db.set_feature_flag(VERIFICATION_FEATURE, false, None, Some("g1"))
+            .await
+            .unwrap();
+        assert!(!is_gated_channel(&db, "g1", "c1").await.unwrap());
+    }
+
+    #[test]
+    fn test_verify_button() {
+        let components = verify_button("42");
+        assert_eq!(
+            components.0[0]["components"][0]["custom_id"],
+            "verification:verify:42"
+        );
+    }
+}
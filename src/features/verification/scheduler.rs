@@ -0,0 +1,80 @@
+//! Background task kicking members who didn't verify in time
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use chrono::Utc;
+use log::{error, info, warn};
+use serenity::http::Http;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::interval;
+
+use super::{delete_prompt, VERIFICATION_FEATURE};
+use crate::database::Database;
+
+pub struct VerificationScheduler {
+    database: Database,
+}
+
+impl VerificationScheduler {
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+
+    /// Start the verification timeout loop
+    /// This should be spawned as a tokio task
+    pub async fn run(&self, http: Arc<Http>) {
+        let mut check_interval = interval(Duration::from_secs(60));
+
+        info!("🚪 Verification timeout scheduler started");
+
+        loop {
+            check_interval.tick().await;
+
+            if let Err(e) = self.process_expired(&http).await {
+                error!("❌ Error processing verification timeouts: {e}");
+            }
+        }
+    }
+
+    async fn process_expired(&self, http: &Arc<Http>) -> Result<()> {
+        let now = Utc::now().timestamp();
+        for pending in self.database.get_expired_verifications(now).await? {
+            // Turning the feature off stops the kicks but keeps members pending
+            if !self
+                .database
+                .is_feature_enabled(VERIFICATION_FEATURE, None, Some(&pending.guild_id))
+                .await?
+            {
+                continue;
+            }
+            match http
+                .kick_member_with_reason(
+                    pending.guild_id.parse()?,
+                    pending.user_id.parse()?,
+                    "Didn't complete verification in time",
+                )
+                .await
+            {
+                Ok(()) => info!(
+                    "👢 Kicked {} from guild {} after verification timed out",
+                    pending.user_id, pending.guild_id
+                ),
+                Err(e) => warn!(
+                    "⚠️ Failed to kick unverified {} from guild {}: {e}",
+                    pending.user_id, pending.guild_id
+                ),
+            }
+            self.database
+                .delete_pending_verification(&pending.guild_id, &pending.user_id)
+                .await?;
+            delete_prompt(http, &pending).await;
+        }
+        Ok(())
+    }
+}
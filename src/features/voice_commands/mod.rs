@@ -0,0 +1,437 @@
+//! # Feature: Voice Commands
+//!
+//! Voice messages are Discord's push-to-talk. When one is transcribed and
+//! starts with a wake phrase ("hey bot", "okay bot" or "hey" and the bot's
+//! name), the rest is read as a spoken command with the same semantics as
+//! the matching slash command, and the confirmation is posted as text:
+//!
+//! - "remind me in 10 minutes to check the oven" works like `/remind`
+//! - "roll two d six plus three" works like `/roll`
+//! - "convert 5 miles to kilometers" works like `/convert`
+//!
+//! Only voice message attachments are handled. Listening for commands in a
+//! voice channel is still open: it needs a voice backend the bot doesn't have
+//! yet; see `docs/plans/2026-10-15-voice-clip-playback.md`.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release with wake phrases in uploaded voice messages for spoken reminders, dice rolls and conversions; voice channel capture is not included
+
+use anyhow::Result;
+use chrono::Utc;
+use log::info;
+use regex::Regex;
+use std::sync::OnceLock;
+
+use crate::commands::handlers::remind::RemindHandler;
+use crate::database::Database;
+use crate::features::conversions::{Conversion, CONVERT_FEATURE};
+use crate::features::dice::{Advantage, DiceExpr, DICE_FEATURE};
+
+/// Feature id for toggles
+pub const VOICE_COMMANDS_FEATURE: &str = "voice_commands";
+
+/// Longest reminder a spoken command can set, so a misheard number can't
+/// schedule one decades out
+const MAX_REMINDER_SECS: i64 = 365 * 24 * 60 * 60;
+
+/// A command heard in a voice message
+#[derive(Debug, Clone, PartialEq)]
+pub enum VoiceCommand {
+    Remind {
+        seconds: i64,
+        message: String,
+    },
+    Roll {
+        notation: String,
+    },
+    Convert {
+        amount: f64,
+        from: String,
+        to: String,
+    },
+}
+
+/// Where a voice message was sent and by whom
+pub struct VoiceCommandContext<'a> {
+    pub user_id: &'a str,
+    pub channel_id: &'a str,
+    pub guild_id: Option<&'a str>,
+}
+
+fn remind_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^remind me (?:in|after) (.+?) (?:to|that|about) (.+)$|^remind me (?:to|about) (.+) (?:in|after) (.+)$")
+            .unwrap()
+    })
+}
+
+fn duration_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(\d+) (second|minute|hour|day|week)s?\b").unwrap())
+}
+
+fn convert_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^(?:convert|what is|what's|how much is|how many \S+ (?:is|are)) (-?\d+(?:\.\d+)?) (.+?) (?:to|in|into) (.+)$")
+            .unwrap()
+    })
+}
+
+/// Spoken numbers Whisper tends to write out as words
+const NUMBER_WORDS: &[(&str, &str)] = &[
+    ("a", "1"),
+    ("an", "1"),
+    ("one", "1"),
+    ("two", "2"),
+    ("three", "3"),
+    ("four", "4"),
+    ("five", "5"),
+    ("six", "6"),
+    ("seven", "7"),
+    ("eight", "8"),
+    ("nine", "9"),
+    ("ten", "10"),
+    ("eleven", "11"),
+    ("twelve", "12"),
+    ("fifteen", "15"),
+    ("twenty", "20"),
+    ("thirty", "30"),
+    ("forty", "40"),
+    ("forty-five", "45"),
+    ("fifty", "50"),
+    ("sixty", "60"),
+    ("hundred", "100"),
+];
+
+/// Lowercase and drop the punctuation Whisper adds
+fn normalise(transcript: &str) -> String {
+    transcript
+        .to_lowercase()
+        .split_whitespace()
+        .map(|word| {
+            let word = word.trim_matches(|c: char| matches!(c, ',' | '!' | '?' | ';' | ':' | '"'));
+            word.strip_suffix('.').unwrap_or(word)
+        })
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Write number words as digits, for the parts of a command that hold
+/// numbers (a reminder's own text keeps its words)
+fn with_digits(text: &str) -> String {
+    text.replace("half an hour", "30 minutes")
+        .split(' ')
+        .map(|word| {
+            NUMBER_WORDS
+                .iter()
+                .find(|(w, _)| *w == word)
+                .map_or(word, |(_, n)| n)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The transcript after its wake phrase, or None when it doesn't start with one
+fn strip_wake_phrase(transcript: &str, bot_name: &str) -> Option<String> {
+    let normalised = normalise(transcript);
+    let bot_name = normalise(bot_name);
+    ["hey", "ok", "okay"]
+        .iter()
+        .flat_map(|greeting| {
+            [
+                format!("{greeting} bot "),
+                format!("{greeting} {bot_name} "),
+            ]
+        })
+        .find_map(|phrase| normalised.strip_prefix(phrase.as_str()).map(str::to_string))
+        .map(|rest| rest.trim().to_string())
+        .filter(|rest| !rest.is_empty())
+}
+
+/// Total seconds in a spoken duration like `1 hour 30 minutes`
+fn parse_spoken_duration(text: &str) -> Option<i64> {
+    let total: i64 = duration_regex()
+        .captures_iter(text)
+        .map(|c| {
+            let value: i64 = c[1].parse().unwrap_or(0);
+            value
+                * match &c[2] {
+                    "second" => 1,
+                    "minute" => 60,
+                    "hour" => 60 * 60,
+                    "day" => 24 * 60 * 60,
+                    _ => 7 * 24 * 60 * 60,
+                }
+        })
+        .sum();
+    (total > 0).then_some(total)
+}
+
+/// Dice notation from a spoken roll like `2 d 6 plus 3 with advantage`
+fn spoken_notation(text: &str) -> String {
+    let text = text
+        .replace("with advantage", "adv")
+        .replace("with disadvantage", "dis")
+        .replace("plus", "+")
+        .replace("minus", "-");
+    match text.rsplit_once(' ') {
+        Some((dice, mode)) if mode == "adv" || mode == "dis" => {
+            format!("{} {mode}", dice.replace(' ', ""))
+        }
+        _ => text.replace(' ', ""),
+    }
+}
+
+/// Read a voice message transcript as a command, when it starts with a
+/// wake phrase and matches one
+pub fn parse_voice_command(transcript: &str, bot_name: &str) -> Option<VoiceCommand> {
+    let command = strip_wake_phrase(transcript, bot_name)?;
+
+    if let Some(captures) = remind_regex().captures(&command) {
+        let (when, message) = match (captures.get(1), captures.get(2)) {
+            (Some(when), Some(message)) => (when.as_str(), message.as_str()),
+            _ => (captures.get(4)?.as_str(), captures.get(3)?.as_str()),
+        };
+        return Some(VoiceCommand::Remind {
+            seconds: parse_spoken_duration(&with_digits(when))?,
+            message: message.trim().to_string(),
+        });
+    }
+    if let Some(dice) = command.strip_prefix("roll ") {
+        let dice = with_digits(dice);
+        let dice = dice.strip_prefix("1 ").unwrap_or(&dice);
+        return Some(VoiceCommand::Roll {
+            notation: spoken_notation(dice),
+        });
+    }
+    let command = with_digits(&command);
+    let captures = convert_regex().captures(&command)?;
+    Some(VoiceCommand::Convert {
+        amount: captures[1].parse().ok()?,
+        from: captures[2].to_string(),
+        to: captures[3].trim_end_matches('?').to_string(),
+    })
+}
+
+/// Run a transcript as a voice command, returning the confirmation to post,
+/// or None when it isn't one
+pub async fn handle_transcript(
+    database: &Database,
+    bot_name: &str,
+    transcript: &str,
+    ctx: &VoiceCommandContext<'_>,
+) -> Result<Option<String>> {
+    if !database
+        .is_feature_enabled(VOICE_COMMANDS_FEATURE, None, ctx.guild_id)
+        .await?
+    {
+        return Ok(None);
+    }
+    let Some(command) = parse_voice_command(transcript, bot_name) else {
+        return Ok(None);
+    };
+    info!(
+        "🎙️ Voice command from {} in {}: {command:?}",
+        ctx.user_id, ctx.channel_id
+    );
+
+    let reply = match command {
+        VoiceCommand::Remind { seconds, message } => {
+            if !database
+                .is_feature_enabled("reminders", None, ctx.guild_id)
+                .await?
+            {
+                "❌ Reminders are disabled on this server.".to_string()
+            } else if seconds > MAX_REMINDER_SECS {
+                "❌ Reminders can be set up to a year ahead.".to_string()
+            } else {
+                let remind_at = Utc::now() + chrono::Duration::seconds(seconds);
+                let remind_at_str = remind_at.format("%Y-%m-%d %H:%M:%S").to_string();
+                let reminder_id = database
+                    .add_reminder(ctx.user_id, ctx.channel_id, &message, &remind_at_str)
+                    .await?;
+                format!(
+                    "⏰ Got it! I'll remind you in **{}** about:\n> {message}\n\n*Reminder ID: #{reminder_id}*",
+                    RemindHandler::format_duration(seconds)
+                )
+            }
+        }
+        VoiceCommand::Roll { notation } => {
+            if !database
+                .is_feature_enabled(DICE_FEATURE, None, ctx.guild_id)
+                .await?
+            {
+                "❌ Dice are disabled on this server.".to_string()
+            } else {
+                match DiceExpr::parse(&notation, Advantage::None) {
+                    Ok(expr) => {
+                        let roll = expr.roll();
+                        format!(
+                            "🎲 Rolled `{notation}`\n{} = **{}**",
+                            roll.breakdown(),
+                            roll.total
+                        )
+                    }
+                    Err(e) => format!("❌ {e}"),
+                }
+            }
+        }
+        VoiceCommand::Convert { amount, from, to } => {
+            if !database
+                .is_feature_enabled(CONVERT_FEATURE, None, ctx.guild_id)
+                .await?
+            {
+                "❌ Conversions are disabled on this server.".to_string()
+            } else {
+                let result = match Conversion::resolve(&from, &to) {
+                    Ok(conversion) => conversion.run(amount).await,
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok((headline, Some(note))) => format!("{headline}\n{note}"),
+                    Ok((headline, None)) => headline,
+                    Err(e) => format!("❌ {e}"),
+                }
+            }
+        }
+    };
+    Ok(Some(format!("🎙️ {reply}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_wake_phrase() {
+        assert_eq!(
+            strip_wake_phrase("Hey bot, roll a d20.", "Obi").as_deref(),
+            Some("roll a d20")
+        );
+        assert_eq!(
+            strip_wake_phrase("Okay Obi! What's 5 miles in km?", "Obi").as_deref(),
+            Some("what's 5 miles in km")
+        );
+        assert_eq!(strip_wake_phrase("Roll a d20", "Obi"), None);
+        assert_eq!(strip_wake_phrase("Hey bot.", "Obi"), None);
+    }
+
+    #[test]
+    fn test_with_digits() {
+        assert_eq!(with_digits("in half an hour"), "in 30 minutes");
+        assert_eq!(with_digits("two d six"), "2 d 6");
+    }
+
+    #[test]
+    fn test_parse_remind() {
+        // Words in the reminder itself aren't turned into numbers
+        assert_eq!(
+            parse_voice_command("Hey bot, remind me in an hour to buy a cake", "Obi"),
+            Some(VoiceCommand::Remind {
+                seconds: 3600,
+                message: "buy a cake".to_string()
+            })
+        );
+        assert_eq!(
+            parse_voice_command(
+                "Hey bot, remind me in ten minutes to check the oven.",
+                "Obi"
+            ),
+            Some(VoiceCommand::Remind {
+                seconds: 600,
+                message: "check the oven".to_string()
+            })
+        );
+        assert_eq!(
+            parse_voice_command(
+                "Hey Obi, remind me to stretch in 1 hour and 30 minutes",
+                "Obi"
+            ),
+            Some(VoiceCommand::Remind {
+                seconds: 5400,
+                message: "stretch".to_string()
+            })
+        );
+        assert_eq!(
+            parse_voice_command("Hey bot, remind me in half an hour about the call", "Obi"),
+            Some(VoiceCommand::Remind {
+                seconds: 1800,
+                message: "the call".to_string()
+            })
+        );
+        assert_eq!(
+            parse_voice_command("Hey bot, remind me later to call", "Obi"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_roll() {
+        let roll = |text| match parse_voice_command(text, "Obi") {
+            Some(VoiceCommand::Roll { notation }) => notation,
+            other => panic!("not a roll: {other:?}"),
+        };
+        assert_eq!(roll("Hey bot, roll two d six plus three."), "2d6+3");
+        assert_eq!(roll("Hey bot, roll a d20 with advantage"), "d20 adv");
+        assert_eq!(roll("Hey bot, roll 4d6 minus 1"), "4d6-1");
+        assert!(
+            DiceExpr::parse(&roll("Hey bot, roll a d20 with advantage"), Advantage::None).is_ok()
+        );
+    }
+
+    #[test]
+    fn test_parse_convert() {
+        assert_eq!(
+            parse_voice_command("Hey bot, convert five miles to kilometers.", "Obi"),
+            Some(VoiceCommand::Convert {
+                amount: 5.0,
+                from: "miles".to_string(),
+                to: "kilometers".to_string()
+            })
+        );
+        assert_eq!(
+            parse_voice_command("OK bot, how much is 20 usd in eur?", "Obi"),
+            Some(VoiceCommand::Convert {
+                amount: 20.0,
+                from: "usd".to_string(),
+                to: "eur".to_string()
+            })
+        );
+        assert_eq!(parse_voice_command("Hey bot, tell me a joke", "Obi"), None);
+    }
+
+    #[tokio::test]
+    async fn test_handle_transcript() {
+        let db = Database::new(":memory:").await.unwrap();
+        let ctx = VoiceCommandContext {
+            user_id: "u1",
+            channel_id: "c1",
+            guild_id: Some("g1"),
+        };
+        let reply = handle_transcript(&db, "Obi", "Hey bot, remind me in 5 minutes to stir", &ctx)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(reply.starts_with("🎙️ ⏰ Got it! I'll remind you in **5 minutes**"));
+        assert_eq!(db.get_user_reminders("u1").await.unwrap().len(), 1);
+
+        let reply = handle_transcript(&db, "Obi", "Hey bot, convert 2 km to m", &ctx)
+            .await
+            .unwrap();
+        assert_eq!(reply.as_deref(), Some("🎙️ 📏 **2 km** = **2,000 m**"));
+
+        assert!(
+            handle_transcript(&db, "Obi", "Just a normal voice note", &ctx)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,113 @@
+//! Background task firing `budget_threshold` webhooks
+//!
+//! Guilds set a daily OpenAI budget with `/webhooks budget`. Once the guild's
+//! spend for the UTC day passes it, subscribed webhooks are notified once;
+//! the date is stored so a restart doesn't notify again.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use chrono::Utc;
+use log::{error, info};
+use std::time::Duration;
+use tokio::time::interval;
+
+use super::{notify, WebhookEvent};
+use crate::database::Database;
+
+/// Guild setting holding the daily budget in USD
+pub const BUDGET_SETTING: &str = "webhook_daily_budget";
+
+/// Guild setting holding the UTC date the budget was last reported
+pub const BUDGET_NOTIFIED_SETTING: &str = "webhook_budget_notified_on";
+
+/// Whether today's spend should be reported
+pub fn budget_crossed(spend: f64, budget: f64, notified_on: Option<&str>, today: &str) -> bool {
+    budget > 0.0 && spend >= budget && notified_on != Some(today)
+}
+
+pub struct BudgetMonitor {
+    database: Database,
+}
+
+impl BudgetMonitor {
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+
+    /// Start the budget check loop
+    /// This should be spawned as a tokio task
+    pub async fn run(&self) {
+        let mut check_interval = interval(Duration::from_secs(5 * 60));
+
+        info!("💸 Webhook budget monitor started");
+
+        loop {
+            check_interval.tick().await;
+
+            if let Err(e) = self.check_budgets().await {
+                error!("❌ Error checking webhook budgets: {e}");
+            }
+        }
+    }
+
+    async fn check_budgets(&self) -> Result<()> {
+        let today = Utc::now().format("%Y-%m-%d").to_string();
+        for guild_id in self
+            .database
+            .get_guilds_with_settings(&[BUDGET_SETTING])
+            .await?
+        {
+            let Some(budget) = self
+                .database
+                .get_guild_setting(&guild_id, BUDGET_SETTING)
+                .await?
+                .and_then(|v| v.parse::<f64>().ok())
+            else {
+                continue;
+            };
+            let notified_on = self
+                .database
+                .get_guild_setting(&guild_id, BUDGET_NOTIFIED_SETTING)
+                .await?;
+            let spend = self.database.get_guild_spend_today(&guild_id).await?;
+            if !budget_crossed(spend, budget, notified_on.as_deref(), &today) {
+                continue;
+            }
+
+            info!("💸 Guild {guild_id} passed its ${budget:.2} daily budget (${spend:.2})");
+            self.database
+                .set_guild_setting(&guild_id, BUDGET_NOTIFIED_SETTING, &today)
+                .await?;
+            notify(
+                &self.database,
+                &guild_id,
+                WebhookEvent::BudgetThreshold,
+                vec![
+                    ("spend", format!("${spend:.2}")),
+                    ("budget", format!("${budget:.2}")),
+                    ("date", today.clone()),
+                ],
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_crossed() {
+        assert!(budget_crossed(5.0, 5.0, None, "2026-10-15"));
+        assert!(budget_crossed(6.0, 5.0, Some("2026-10-14"), "2026-10-15"));
+        assert!(!budget_crossed(6.0, 5.0, Some("2026-10-15"), "2026-10-15"));
+        assert!(!budget_crossed(4.0, 5.0, None, "2026-10-15"));
+        assert!(!budget_crossed(1.0, 0.0, None, "2026-10-15"));
+    }
+}
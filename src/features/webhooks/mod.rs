@@ -0,0 +1,403 @@
+//! # Webhooks Feature
+//!
+//! Routes bot events (plugin job completed, debate concluded, conflict
+//! detected, daily budget crossed) to outbound webhooks each guild registers
+//! with `/webhooks add`. Payloads are shaped for Slack, Matrix hookshot or a
+//! generic JSON consumer, the text can be templated with `{field}`
+//! placeholders, and failed deliveries are retried with backoff.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: true
+//!
+//! ## Changelog
+//! - 1.0.0: Initial implementation
+
+pub mod budget;
+
+pub use budget::BudgetMonitor;
+
+use chrono::Utc;
+use log::{debug, info, warn};
+use serde_json::{json, Map, Value};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::database::{Database, GuildWebhook, WebhookFormat};
+use crate::features::analytics::runtime::{spawn_tracked, TaskKind};
+
+/// Feature id for toggles
+pub const WEBHOOKS_FEATURE: &str = "webhooks";
+
+/// Webhooks a guild can register
+pub const MAX_WEBHOOKS_PER_GUILD: usize = 5;
+
+/// Longest template accepted by `/webhooks add`
+pub const MAX_TEMPLATE_CHARS: usize = 500;
+
+/// Deliveries tried before giving up
+pub const MAX_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry, doubled for each one after
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(2);
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Display name Matrix hookshot posts as
+const MATRIX_USERNAME: &str = "Persona";
+
+/// Named values an event carries, available to templates as `{name}`
+pub type WebhookFields = Vec<(&'static str, String)>;
+
+/// Bot events a webhook can subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookEvent {
+    JobCompleted,
+    DebateConcluded,
+    ConflictDetected,
+    BudgetThreshold,
+}
+
+impl WebhookEvent {
+    pub const ALL: [WebhookEvent; 4] = [
+        WebhookEvent::JobCompleted,
+        WebhookEvent::DebateConcluded,
+        WebhookEvent::ConflictDetected,
+        WebhookEvent::BudgetThreshold,
+    ];
+
+    /// Name used in `/webhooks add events` and stored per webhook
+    pub fn key(&self) -> &'static str {
+        match self {
+            WebhookEvent::JobCompleted => "job_completed",
+            WebhookEvent::DebateConcluded => "debate_concluded",
+            WebhookEvent::ConflictDetected => "conflict_detected",
+            WebhookEvent::BudgetThreshold => "budget_threshold",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|e| e.key() == key)
+    }
+
+    /// Text sent when the webhook has no template of its own
+    pub fn default_template(&self) -> &'static str {
+        match self {
+            WebhookEvent::JobCompleted => "✅ {plugin} job {job_id} completed: {result}",
+            WebhookEvent::DebateConcluded => {
+                "🎭 Debate concluded: {topic} ({persona1} vs {persona2}, {rounds} rounds)"
+            }
+            WebhookEvent::ConflictDetected => {
+                "🔥 Conflict detected ({conflict_type}, {confidence} confidence) between {participants} members: {link}"
+            }
+            WebhookEvent::BudgetThreshold => {
+                "💸 Today's OpenAI spend reached {spend}, over the {budget} daily budget"
+            }
+        }
+    }
+
+    /// Sample fields for `/webhooks test`
+    pub fn sample_fields(&self) -> WebhookFields {
+        match self {
+            WebhookEvent::JobCompleted => vec![
+                ("job_id", "a1b2c3d4".to_string()),
+                ("plugin", "transcribe".to_string()),
+                ("user_id", "0".to_string()),
+                ("result", "Sample output".to_string()),
+            ],
+            WebhookEvent::DebateConcluded => vec![
+                ("topic", "Pineapple on pizza".to_string()),
+                ("persona1", "Obi-Wan".to_string()),
+                ("persona2", "Muppet".to_string()),
+                ("rounds", "4".to_string()),
+            ],
+            WebhookEvent::ConflictDetected => vec![
+                ("channel_id", "0".to_string()),
+                ("conflict_type", "heated argument".to_string()),
+                ("confidence", "80%".to_string()),
+                ("participants", "2".to_string()),
+                ("link", "https://discord.com/channels/0/0/0".to_string()),
+            ],
+            WebhookEvent::BudgetThreshold => vec![
+                ("spend", "$5.20".to_string()),
+                ("budget", "$5.00".to_string()),
+            ],
+        }
+    }
+}
+
+/// Parse `/webhooks add events`: comma-separated keys, or `all`
+pub fn parse_events(spec: &str) -> Result<Vec<WebhookEvent>, String> {
+    if spec.trim().eq_ignore_ascii_case("all") {
+        return Ok(WebhookEvent::ALL.to_vec());
+    }
+    let mut events = Vec::new();
+    for key in spec.split(',').map(|k| k.trim().to_lowercase()) {
+        if key.is_empty() {
+            continue;
+        }
+        let event = WebhookEvent::from_key(&key).ok_or_else(|| {
+            format!(
+                "Unknown event `{key}`. Use `all` or any of: {}",
+                event_keys().join(", ")
+            )
+        })?;
+        if !events.contains(&event) {
+            events.push(event);
+        }
+    }
+    if events.is_empty() {
+        return Err("Pick at least one event.".to_string());
+    }
+    Ok(events)
+}
+
+/// Every event key, for help text
+pub fn event_keys() -> Vec<&'static str> {
+    WebhookEvent::ALL.iter().map(|e| e.key()).collect()
+}
+
+/// Only public HTTPS endpoints are accepted
+pub fn validate_url(url: &str) -> Result<(), &'static str> {
+    let Ok(parsed) = reqwest::Url::parse(url) else {
+        return Err("That isn't a valid URL.");
+    };
+    if parsed.scheme() != "https" {
+        return Err("Webhook URLs must use https://.");
+    }
+    match parsed.host_str() {
+        Some(host) if host != "localhost" && !host.ends_with(".local") => Ok(()),
+        _ => Err("Webhook URLs must point at a public host."),
+    }
+}
+
+/// Replace `{name}` placeholders; unknown ones are left as written
+pub fn render_template(template: &str, fields: &[(&'static str, String)]) -> String {
+    fields
+        .iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{name}}}"), value)
+        })
+}
+
+/// Request body for a webhook's format
+pub fn build_payload(
+    format: WebhookFormat,
+    event: WebhookEvent,
+    guild_id: &str,
+    text: &str,
+    fields: &[(&'static str, String)],
+) -> Value {
+    match format {
+        WebhookFormat::Slack => json!({ "text": text }),
+        WebhookFormat::Matrix => json!({ "text": text, "username": MATRIX_USERNAME }),
+        WebhookFormat::Generic => {
+            let data: Map<String, Value> = fields
+                .iter()
+                .map(|(name, value)| (name.to_string(), Value::String(value.clone())))
+                .collect();
+            json!({
+                "event": event.key(),
+                "guild_id": guild_id,
+                "timestamp": Utc::now().to_rfc3339(),
+                "text": text,
+                "data": data,
+            })
+        }
+    }
+}
+
+/// Rate limits and server errors are worth retrying; other rejections aren't
+pub fn should_retry(status: u16) -> bool {
+    status == 429 || status >= 500
+}
+
+/// Delay before retry number `attempt` (1-based)
+pub fn retry_delay(attempt: u32) -> Duration {
+    RETRY_BASE_DELAY * 2u32.pow(attempt.saturating_sub(1))
+}
+
+fn http_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(reqwest::Client::new)
+}
+
+/// POST an event to one webhook, retrying transient failures
+///
+/// Returns the last error once every attempt has failed.
+pub async fn deliver(
+    webhook: &GuildWebhook,
+    event: WebhookEvent,
+    fields: &[(&'static str, String)],
+) -> Result<(), String> {
+    let template = webhook
+        .template
+        .as_deref()
+        .unwrap_or(event.default_template());
+    let text = render_template(template, fields);
+    let body = build_payload(webhook.format, event, &webhook.guild_id, &text, fields);
+
+    let mut attempt = 1;
+    loop {
+        let result = http_client()
+            .post(&webhook.url)
+            .timeout(REQUEST_TIMEOUT)
+            .json(&body)
+            .send()
+            .await;
+        let (error, retry) = match result {
+            Ok(response) if response.status().is_success() => {
+                debug!(
+                    "🪝 Delivered {} to webhook {} (attempt {attempt})",
+                    event.key(),
+                    webhook.id
+                );
+                return Ok(());
+            }
+            Ok(response) => {
+                let status = response.status();
+                (format!("HTTP {status}"), should_retry(status.as_u16()))
+            }
+            Err(e) => (e.to_string(), true),
+        };
+
+        if !retry || attempt >= MAX_ATTEMPTS {
+            warn!(
+                "🪝 Giving up on {} for webhook {} after {attempt} attempt(s): {error}",
+                event.key(),
+                webhook.id
+            );
+            return Err(error);
+        }
+        tokio::time::sleep(retry_delay(attempt)).await;
+        attempt += 1;
+    }
+}
+
+/// Send an event to every webhook in the guild subscribed to it
+///
+/// Runs in the background so callers never wait on outside services.
+pub fn notify(database: &Database, guild_id: &str, event: WebhookEvent, fields: WebhookFields) {
+    let database = database.clone();
+    let guild_id = guild_id.to_string();
+    spawn_tracked(TaskKind::Schedulers, async move {
+        match database
+            .is_feature_enabled(WEBHOOKS_FEATURE, None, Some(&guild_id))
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => return,
+            Err(e) => {
+                warn!("🪝 Couldn't check webhooks toggle for guild {guild_id}: {e}");
+                return;
+            }
+        }
+
+        let webhooks = match database.get_guild_webhooks(&guild_id).await {
+            Ok(webhooks) => webhooks,
+            Err(e) => {
+                warn!("🪝 Couldn't load webhooks for guild {guild_id}: {e}");
+                return;
+            }
+        };
+
+        for webhook in webhooks
+            .iter()
+            .filter(|w| w.events.iter().any(|e| e == event.key()))
+        {
+            let outcome = deliver(webhook, event, &fields).await;
+            if outcome.is_ok() {
+                info!("🪝 Sent {} to webhook {}", event.key(), webhook.id);
+            }
+            // Only touch the row when the stored error changes
+            let error = outcome.err();
+            if error != webhook.last_error {
+                if let Err(e) = database
+                    .set_webhook_last_error(webhook.id, error.as_deref())
+                    .await
+                {
+                    warn!(
+                        "🪝 Couldn't record delivery for webhook {}: {e}",
+                        webhook.id
+                    );
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_keys_roundtrip() {
+        for event in WebhookEvent::ALL {
+            assert_eq!(WebhookEvent::from_key(event.key()), Some(event));
+        }
+        assert_eq!(WebhookEvent::from_key("nope"), None);
+    }
+
+    #[test]
+    fn test_parse_events() {
+        assert_eq!(parse_events("all").unwrap(), WebhookEvent::ALL.to_vec());
+        assert_eq!(
+            parse_events(" Job_Completed, budget_threshold,job_completed").unwrap(),
+            vec![WebhookEvent::JobCompleted, WebhookEvent::BudgetThreshold]
+        );
+        assert!(parse_events("job_completed,typo").is_err());
+        assert!(parse_events(" , ").is_err());
+    }
+
+    #[test]
+    fn test_validate_url() {
+        assert!(validate_url("https://hooks.slack.com/services/T/B/x").is_ok());
+        assert!(validate_url("http://hooks.slack.com/services/T/B/x").is_err());
+        assert!(validate_url("https://localhost:8080/hook").is_err());
+        assert!(validate_url("not a url").is_err());
+    }
+
+    #[test]
+    fn test_render_template() {
+        let fields = vec![("topic", "Tabs".to_string()), ("rounds", "3".to_string())];
+        assert_eq!(
+            render_template("{topic} after {rounds} rounds {missing}", &fields),
+            "Tabs after 3 rounds {missing}"
+        );
+    }
+
+    #[test]
+    fn test_default_templates_use_sample_fields() {
+        for event in WebhookEvent::ALL {
+            let text = render_template(event.default_template(), &event.sample_fields());
+            assert!(!text.contains('{'), "{}: {text}", event.key());
+        }
+    }
+
+    #[test]
+    fn test_build_payload() {
+        let fields = vec![("spend", "$6.00".to_string())];
+        let event = WebhookEvent::BudgetThreshold;
+
+        let slack = build_payload(WebhookFormat::Slack, event, "g1", "hi", &fields);
+        assert_eq!(slack, json!({ "text": "hi" }));
+
+        let matrix = build_payload(WebhookFormat::Matrix, event, "g1", "hi", &fields);
+        assert_eq!(matrix["text"], "hi");
+        assert_eq!(matrix["username"], MATRIX_USERNAME);
+
+        let generic = build_payload(WebhookFormat::Generic, event, "g1", "hi", &fields);
+        assert_eq!(generic["event"], "budget_threshold");
+        assert_eq!(generic["guild_id"], "g1");
+        assert_eq!(generic["data"]["spend"], "$6.00");
+    }
+
+    #[test]
+    fn test_retry_policy() {
+        assert!(should_retry(429));
+        assert!(should_retry(503));
+        assert!(!should_retry(404));
+        assert_eq!(retry_delay(1), Duration::from_secs(2));
+        assert_eq!(retry_delay(2), Duration::from_secs(4));
+    }
+}
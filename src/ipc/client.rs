@@ -289,6 +289,11 @@ impl IpcClient {
             .await
     }
 
+    /// Request hourly sentiment for tracked channels
+    pub async fn request_sentiment_timeline(&self, hours: u32) -> Result<()> {
+        self.send(TuiCommand::GetSentimentTimeline { hours }).await
+    }
+
     /// Request user list with stats
     pub async fn request_user_list(&self, limit: u32) -> Result<()> {
         self.send(TuiCommand::GetUserList { limit }).await
@@ -321,6 +326,21 @@ impl IpcClient {
             .await
     }
 
+    /// Request a full-text search over stored messages
+    pub async fn request_search(
+        &self,
+        query: String,
+        guild_id: Option<u64>,
+        limit: u32,
+    ) -> Result<()> {
+        self.send(TuiCommand::SearchMessages {
+            query,
+            guild_id,
+            limit,
+        })
+        .await
+    }
+
     /// Disable auto-reconnect (for clean shutdown)
     pub async fn disable_reconnect(&self) {
         *self.should_reconnect.write().await = false;
@@ -0,0 +1,235 @@
+//! # Admin Console
+//!
+//! Line-oriented admin commands for `obi-ctl`, translated into IPC commands.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: false
+//!
+//! ## Changelog
+//! - 1.0.0: Initial parser for status, reload-plugins, feature, clear-caches, settings and cancel-job
+
+use crate::ipc::protocol::{BotEvent, TuiCommand};
+
+/// Usage text shown by `help` and on parse errors
+pub const HELP: &str = "\
+Commands:
+  status                              Connection, uptime and circuit breakers
+  reload-plugins                      Re-read plugins.yaml and re-register slash commands
+  feature <name> on|off [guild_id]    Toggle a feature globally or for one guild
+  clear-caches                        Drop cached guild, channel and feature settings
+  settings <guild_id>                 Show every stored setting for a guild
+  cancel-job <job_id>                 Cancel an active playlist job
+  help                                Show this text
+  quit                                Leave the console";
+
+/// A parsed console line
+#[derive(Debug, Clone)]
+pub enum AdminCommand {
+    /// Send this command and wait for the reply
+    Send(TuiCommand),
+    Help,
+    Quit,
+}
+
+/// Parse one console line; `request_id` tags the command so its reply can be matched
+pub fn parse_admin_command(line: &str, request_id: &str) -> Result<Option<AdminCommand>, String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let Some((&verb, args)) = words.split_first() else {
+        return Ok(None);
+    };
+    let request_id = request_id.to_string();
+
+    let command = match (verb, args) {
+        ("help" | "?", []) => AdminCommand::Help,
+        ("quit" | "exit", []) => AdminCommand::Quit,
+        ("status", []) => AdminCommand::Send(TuiCommand::GetStatus),
+        ("reload-plugins", []) => AdminCommand::Send(TuiCommand::ReloadPlugins { request_id }),
+        ("clear-caches", []) => AdminCommand::Send(TuiCommand::ClearCaches { request_id }),
+        ("settings", [guild]) => AdminCommand::Send(TuiCommand::GetGuildSettings {
+            request_id,
+            guild_id: parse_id(guild)?,
+        }),
+        ("cancel-job", [job_id]) => AdminCommand::Send(TuiCommand::CancelJob {
+            request_id,
+            job_id: job_id.to_string(),
+        }),
+        ("feature", [name, state, rest @ ..]) if rest.len() <= 1 => {
+            let enabled = match *state {
+                "on" | "enable" | "true" => true,
+                "off" | "disable" | "false" => false,
+                other => return Err(format!("Expected on or off, got '{other}'")),
+            };
+            AdminCommand::Send(TuiCommand::SetFeature {
+                request_id,
+                feature: name.to_string(),
+                enabled,
+                guild_id: rest.first().map(|g| parse_id(g)).transpose()?,
+            })
+        }
+        (
+            "help" | "?" | "quit" | "exit" | "status" | "reload-plugins" | "clear-caches"
+            | "settings" | "cancel-job" | "feature",
+            _,
+        ) => return Err(format!("Wrong arguments for '{verb}' (try 'help')")),
+        _ => return Err(format!("Unknown command '{verb}' (try 'help')")),
+    };
+    Ok(Some(command))
+}
+
+fn parse_id(raw: &str) -> Result<u64, String> {
+    raw.parse()
+        .map_err(|_| format!("'{raw}' is not a Discord ID"))
+}
+
+/// The reply to `command` among the bot's events, rendered as (success, text)
+///
+/// Returns `None` for unrelated events (heartbeats, other clients' replies).
+pub fn match_reply(
+    command: &TuiCommand,
+    request_id: &str,
+    event: &BotEvent,
+) -> Option<(bool, String)> {
+    match (command, event) {
+        (
+            TuiCommand::GetStatus,
+            BotEvent::StatusUpdate {
+                connected,
+                uptime_seconds,
+                guild_count,
+                active_sessions,
+                breakers,
+            },
+        ) => {
+            let mut text = format!(
+                "connected: {connected}\nuptime: {uptime_seconds}s\nguilds: {guild_count}\nactive sessions: {active_sessions}"
+            );
+            for breaker in breakers {
+                text.push_str(&format!(
+                    "\nbreaker {}: {}",
+                    breaker.dependency, breaker.state
+                ));
+            }
+            Some((true, text))
+        }
+        (
+            _,
+            BotEvent::CommandResponse {
+                request_id: id,
+                success,
+                message,
+                data,
+            },
+        ) if id == request_id => {
+            let mut text = message.clone().unwrap_or_default();
+            if let Some(serde_json::Value::Object(map)) = data {
+                for (key, value) in map {
+                    let value = value
+                        .as_str()
+                        .map(str::to_string)
+                        .unwrap_or_else(|| value.to_string());
+                    text.push_str(&format!("\n  {key} = {value}"));
+                }
+            } else if let Some(data) = data {
+                text.push_str(&format!("\n{data}"));
+            }
+            Some((*success, text))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Result<Option<AdminCommand>, String> {
+        parse_admin_command(line, "req-1")
+    }
+
+    #[test]
+    fn test_blank_line_is_ignored() {
+        assert!(parse("   ").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_parse_feature_toggle() {
+        match parse("feature reminders off 123").unwrap() {
+            Some(AdminCommand::Send(TuiCommand::SetFeature {
+                request_id,
+                feature,
+                enabled,
+                guild_id,
+            })) => {
+                assert_eq!(request_id, "req-1");
+                assert_eq!(feature, "reminders");
+                assert!(!enabled);
+                assert_eq!(guild_id, Some(123));
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+        assert!(matches!(
+            parse("feature reminders on").unwrap(),
+            Some(AdminCommand::Send(TuiCommand::SetFeature {
+                guild_id: None,
+                enabled: true,
+                ..
+            }))
+        ));
+        assert!(parse("feature reminders maybe").is_err());
+    }
+
+    #[test]
+    fn test_parse_admin_operations() {
+        assert!(matches!(
+            parse("reload-plugins").unwrap(),
+            Some(AdminCommand::Send(TuiCommand::ReloadPlugins { .. }))
+        ));
+        assert!(matches!(
+            parse("settings 42").unwrap(),
+            Some(AdminCommand::Send(TuiCommand::GetGuildSettings {
+                guild_id: 42,
+                ..
+            }))
+        ));
+        assert!(matches!(
+            parse("cancel-job abc").unwrap(),
+            Some(AdminCommand::Send(TuiCommand::CancelJob { job_id, .. })) if job_id == "abc"
+        ));
+        assert!(matches!(parse("help").unwrap(), Some(AdminCommand::Help)));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("settings").unwrap_err().contains("Wrong arguments"));
+        assert!(parse("settings general")
+            .unwrap_err()
+            .contains("not a Discord ID"));
+        assert!(parse("reboot").unwrap_err().contains("Unknown command"));
+    }
+
+    #[test]
+    fn test_match_reply_only_accepts_own_request() {
+        let command = TuiCommand::ClearCaches {
+            request_id: "req-1".to_string(),
+        };
+        let other = BotEvent::CommandResponse {
+            request_id: "req-2".to_string(),
+            success: true,
+            message: None,
+            data: None,
+        };
+        assert!(match_reply(&command, "req-1", &other).is_none());
+        assert!(match_reply(&command, "req-1", &BotEvent::Heartbeat { timestamp: 0 }).is_none());
+
+        let settings = BotEvent::CommandResponse {
+            request_id: "req-1".to_string(),
+            success: true,
+            message: Some("1 setting(s)".to_string()),
+            data: Some(serde_json::json!({ "persona": "obi" })),
+        };
+        let (success, text) = match_reply(&command, "req-1", &settings).unwrap();
+        assert!(success);
+        assert_eq!(text, "1 setting(s)\n  persona = obi");
+    }
+}
@@ -2,22 +2,28 @@
 //!
 //! Inter-process communication between the bot and TUI.
 //!
-//! - **Version**: 1.1.0
+//! - **Version**: 1.5.0
 //! - **Since**: 3.17.0
 //! - **Toggleable**: false
 //!
 //! ## Changelog
+//! - 1.5.0: Added GetSentimentTimeline command and ChannelSentimentInfo for the Stats mood panel
+//! - 1.4.0: Added admin commands (plugin reload, cache clear, guild settings, job cancel) and the ctl parser behind obi-ctl
+//! - 1.3.0: Added BreakerInfo to StatusUpdate for circuit breaker state
+//! - 1.2.0: Added SearchMessages command and SearchResultInfo for message search
 //! - 1.1.0: Added TopUser struct with username support for TUI display
 //! - 1.0.0: Initial IPC implementation with Unix socket protocol
 
 pub mod client;
+pub mod ctl;
 pub mod protocol;
 pub mod server;
 
 pub use client::{connect_with_retry, IpcClient};
 pub use protocol::{
-    AttachmentInfo, BotEvent, ChannelHistorySummary, ChannelInfo, ChannelType, DisplayMessage,
-    DmSessionInfo, ErrorInfo, GuildInfo, TopUser, TuiCommand, UserStats, UserSummary,
+    AttachmentInfo, BotEvent, BreakerInfo, ChannelHistorySummary, ChannelInfo, ChannelSentimentInfo, ChannelType, DisplayMessage,
+    DmSessionInfo, ErrorInfo, GuildInfo, SearchResultInfo, TopUser, TuiCommand, UserStats,
+    UserSummary,
 };
 pub use server::IpcServer;
 
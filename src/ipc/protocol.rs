@@ -50,6 +50,8 @@ pub enum BotEvent {
         uptime_seconds: u64,
         guild_count: usize,
         active_sessions: usize,
+        #[serde(default)]
+        breakers: Vec<BreakerInfo>,
     },
     /// Heartbeat to keep connection alive
     Heartbeat { timestamp: i64 },
@@ -72,6 +74,24 @@ pub enum BotEvent {
         memory_total: u64,
         db_size: u64,
         uptime_seconds: u64,
+        /// SQLite WAL + shared-memory files
+        #[serde(default)]
+        db_wal_size: u64,
+        /// Chunker working directories
+        #[serde(default)]
+        transcript_size: u64,
+        #[serde(default)]
+        net_rx_per_sec: u64,
+        #[serde(default)]
+        net_tx_per_sec: u64,
+        /// All tasks alive in the tokio runtime
+        #[serde(default)]
+        alive_tasks: usize,
+        /// (feature, tracked task count)
+        #[serde(default)]
+        tasks_by_feature: Vec<(String, usize)>,
+        #[serde(default)]
+        openai_in_flight: usize,
     },
     /// Channel information response
     ChannelInfoResponse {
@@ -114,6 +134,16 @@ pub enum BotEvent {
         /// Channels with their history summaries
         channels: Vec<ChannelHistorySummary>,
     },
+    /// Full-text message search results
+    SearchResultsResponse {
+        query: String,
+        results: Vec<SearchResultInfo>,
+    },
+    /// Hourly sentiment for every channel with sentiment tracking on
+    SentimentTimelineResponse {
+        hours: u32,
+        channels: Vec<ChannelSentimentInfo>,
+    },
 }
 
 /// Simplified message for display in TUI
@@ -240,6 +270,46 @@ pub struct ChannelHistorySummary {
     pub last_activity: Option<DateTime<Utc>>,
 }
 
+/// Circuit breaker state for one external dependency
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreakerInfo {
+    /// Dependency identifier (e.g. "openai_chat", "yt_dlp")
+    pub dependency: String,
+    pub name: String,
+    /// "closed", "open" or "half_open"
+    pub state: String,
+    pub requests: usize,
+    pub failures: usize,
+    pub retry_after_secs: Option<u64>,
+    pub last_error: Option<String>,
+}
+
+/// Stored message matching a search query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResultInfo {
+    pub channel_id: u64,
+    pub channel_name: Option<String>,
+    pub guild_id: Option<u64>,
+    pub guild_name: Option<String>,
+    pub user_id: String,
+    pub author_name: String,
+    pub is_bot: bool,
+    /// Matching excerpt with hits wrapped in `**`
+    pub snippet: String,
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+/// Hourly sentiment for one tracked channel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelSentimentInfo {
+    pub channel_id: u64,
+    pub channel_name: Option<String>,
+    pub guild_id: u64,
+    pub guild_name: Option<String>,
+    /// (hour start unix timestamp, mean score -1.0..1.0, message count), oldest first
+    pub points: Vec<(i64, f64, i64)>,
+}
+
 // ============================================================================
 // TUI -> Bot Commands
 // ============================================================================
@@ -310,6 +380,22 @@ pub enum TuiCommand {
     GetFeatureStates { guild_id: Option<u64> },
     /// Request channels with conversation history (for browse mode)
     GetChannelsWithHistory { guild_id: Option<u64> },
+    /// Full-text search over stored messages (None = all guilds and DMs)
+    SearchMessages {
+        query: String,
+        guild_id: Option<u64>,
+        limit: u32,
+    },
+    /// Request hourly sentiment for tracked channels over the last `hours`
+    GetSentimentTimeline { hours: u32 },
+    /// Re-read the plugin configuration and re-register slash commands
+    ReloadPlugins { request_id: String },
+    /// Drop cached guild, channel, feature and privacy settings
+    ClearCaches { request_id: String },
+    /// Every stored setting for a guild, returned as CommandResponse data
+    GetGuildSettings { request_id: String, guild_id: u64 },
+    /// Cancel an active playlist job
+    CancelJob { request_id: String, job_id: String },
 }
 
 // ============================================================================
@@ -381,4 +467,37 @@ mod tests {
         assert!(json.contains("SendMessage"));
         assert!(json.contains("test-123"));
     }
+
+    #[test]
+    fn test_status_update_without_breakers_decodes() {
+        // Older bots don't send breaker state
+        let json = r#"{"type":"StatusUpdate","connected":true,"uptime_seconds":5,"guild_count":1,"active_sessions":0}"#;
+        match serde_json::from_str::<BotEvent>(json).unwrap() {
+            BotEvent::StatusUpdate { breakers, .. } => assert!(breakers.is_empty()),
+            _ => panic!("Wrong event type"),
+        }
+    }
+
+    #[test]
+    fn test_search_roundtrip() {
+        let cmd = TuiCommand::SearchMessages {
+            query: "deploy".to_string(),
+            guild_id: Some(42),
+            limit: 25,
+        };
+        let encoded = encode_message(&cmd).unwrap();
+        let decoded: TuiCommand = decode_message(&mut Cursor::new(encoded)).unwrap();
+        match decoded {
+            TuiCommand::SearchMessages {
+                query,
+                guild_id,
+                limit,
+            } => {
+                assert_eq!(query, "deploy");
+                assert_eq!(guild_id, Some(42));
+                assert_eq!(limit, 25);
+            }
+            _ => panic!("Wrong command type"),
+        }
+    }
 }
@@ -2,11 +2,17 @@
 //!
 //! Unix socket server for the bot to communicate with TUI clients.
 //!
-//! - **Version**: 1.6.0
+//! - **Version**: 1.12.0
 //! - **Since**: 3.17.0
 //! - **Toggleable**: false
 //!
 //! ## Changelog
+//! - 1.12.0: Added GetSentimentTimeline handler (hourly mood of sentiment-tracked channels)
+//! - 1.11.0: Added admin handlers: ReloadPlugins, ClearCaches, GetGuildSettings and CancelJob
+//! - 1.10.0: GetHistoricalMetrics reads the downsampled system metrics tiers; the TUI no longer logs its own cpu/memory rows
+//! - 1.9.0: System metrics include storage, network, task and OpenAI in-flight counts and are pushed to clients with the heartbeat
+//! - 1.8.0: GetStatus reports circuit breaker state
+//! - 1.7.0: Added SearchMessages handler (full-text search over stored messages)
 //! - 1.6.0: Added GetChannelsWithHistory handler, fixed username lookup in GetChannelHistory
 //! - 1.5.0: Implemented SetFeature, SetGuildSetting, and SetChannelPersona handlers
 //! - 1.4.0: Added cache_user method and TopUser struct support for username resolution
@@ -15,17 +21,24 @@
 //! - 1.1.0: Added command processing support and shared state for guilds/bot info
 //! - 1.0.0: Initial IPC implementation with Unix socket protocol
 
+use crate::commands::{
+    register_global_commands_with_plugins, register_guild_commands_with_plugins,
+};
 use crate::database::Database;
+use crate::features::analytics::runtime::{spawn_tracked, TaskKind};
+use crate::features::analytics::CurrentMetrics;
+use crate::features::plugins::{PluginConfig, PluginManager};
+use crate::features::resilience::circuit_breakers;
 use crate::ipc::get_socket_path;
 use crate::ipc::protocol::{
-    encode_message, BotEvent, ChannelHistorySummary, DisplayMessage, DmSessionInfo, ErrorInfo,
-    GuildInfo, TopUser, TuiCommand, UserStats, UserSummary,
+    encode_message, BotEvent, BreakerInfo, ChannelHistorySummary, ChannelSentimentInfo, DisplayMessage, DmSessionInfo,
+    ErrorInfo, GuildInfo, SearchResultInfo, TopUser, TuiCommand, UserStats, UserSummary,
 };
 use anyhow::Result;
 use chrono::{DateTime, NaiveDateTime, Utc};
 use log::{debug, error, info, warn};
 use serenity::http::Http;
-use serenity::model::id::ChannelId;
+use serenity::model::id::{ChannelId, GuildId};
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
@@ -70,6 +83,17 @@ pub struct IpcServer {
     db_path: Option<String>,
     /// Discord HTTP client for sending messages
     http: Arc<RwLock<Option<Arc<Http>>>>,
+    /// What ReloadPlugins and CancelJob act on
+    plugins: Arc<RwLock<Option<PluginReload>>>,
+}
+
+/// The live plugin manager, where its configuration is loaded from, and
+/// where slash commands are registered (a dev guild or globally)
+#[derive(Clone)]
+struct PluginReload {
+    manager: Arc<PluginManager>,
+    path: String,
+    guild_id: Option<GuildId>,
 }
 
 impl IpcServer {
@@ -92,6 +116,7 @@ impl IpcServer {
             database: None,
             db_path: None,
             http: Arc::new(RwLock::new(None)),
+            plugins: Arc::new(RwLock::new(None)),
         }
     }
 
@@ -109,6 +134,20 @@ impl IpcServer {
         info!("IPC server HTTP client configured");
     }
 
+    /// Let admin commands reload plugins from `path` and cancel their jobs
+    pub async fn set_plugin_manager(
+        &self,
+        manager: Arc<PluginManager>,
+        path: String,
+        guild_id: Option<GuildId>,
+    ) {
+        *self.plugins.write().await = Some(PluginReload {
+            manager,
+            path,
+            guild_id,
+        });
+    }
+
     /// Start the IPC server in a background task
     pub async fn start(self: Arc<Self>) -> Result<()> {
         let socket_path = get_socket_path();
@@ -123,7 +162,7 @@ impl IpcServer {
 
         // Spawn the accept loop
         let server = self.clone();
-        tokio::spawn(async move {
+        spawn_tracked(TaskKind::Ipc, async move {
             loop {
                 match listener.accept().await {
                     Ok((stream, _addr)) => {
@@ -140,7 +179,7 @@ impl IpcServer {
 
                         let server_clone = server.clone();
                         let client_count_ref = server.client_count.clone();
-                        tokio::spawn(async move {
+                        spawn_tracked(TaskKind::Ipc, async move {
                             if let Err(e) = server_clone.handle_client(stream).await {
                                 debug!("Client handler ended: {e}");
                             }
@@ -288,6 +327,28 @@ impl IpcServer {
         self.broadcast(BotEvent::Heartbeat { timestamp });
     }
 
+    /// Sample system metrics and send them to all clients
+    pub async fn broadcast_system_metrics(&self) {
+        let db_path = self.db_path.as_deref().unwrap_or_default();
+        let metrics = CurrentMetrics::sample(db_path).await;
+        let network = metrics.network.unwrap_or_default();
+
+        self.broadcast(BotEvent::SystemMetricsUpdate {
+            cpu_percent: metrics.cpu_usage,
+            memory_bytes: metrics.memory_used,
+            memory_total: metrics.memory_total,
+            db_size: metrics.db_size,
+            uptime_seconds: self.get_uptime_seconds(),
+            db_wal_size: metrics.storage.db_wal_bytes,
+            transcript_size: metrics.storage.transcript_bytes,
+            net_rx_per_sec: network.rx_bytes_per_sec,
+            net_tx_per_sec: network.tx_bytes_per_sec,
+            alive_tasks: metrics.runtime.alive_tasks,
+            tasks_by_feature: metrics.runtime.tasks_by_feature,
+            openai_in_flight: metrics.runtime.openai_in_flight,
+        });
+    }
+
     /// Update cached guild information (call this on Ready event)
     pub async fn set_guilds(&self, guilds: Vec<GuildInfo>) {
         *self.guilds.write().await = guilds;
@@ -364,11 +425,25 @@ impl IpcServer {
             TuiCommand::GetStatus => {
                 let guild_count = self.guilds.read().await.len();
                 let active_sessions = self.get_active_sessions().await;
+                let breakers = circuit_breakers()
+                    .snapshot()
+                    .into_iter()
+                    .map(|status| BreakerInfo {
+                        dependency: status.dependency.id().to_string(),
+                        name: status.dependency.name().to_string(),
+                        state: status.state.as_str().to_string(),
+                        requests: status.requests,
+                        failures: status.failures,
+                        retry_after_secs: status.retry_after.map(|d| d.as_secs()),
+                        last_error: status.last_error,
+                    })
+                    .collect();
                 self.broadcast(BotEvent::StatusUpdate {
                     connected: true,
                     uptime_seconds: self.get_uptime_seconds(),
                     guild_count,
                     active_sessions,
+                    breakers,
                 });
                 debug!("Sent StatusUpdate response");
             }
@@ -700,43 +775,8 @@ impl IpcServer {
                 }
             }
             TuiCommand::GetSystemMetrics => {
-                use sysinfo::System;
-                let mut sys = System::new();
-                // CPU needs two refreshes for accurate reading
-                sys.refresh_cpu_usage();
-                tokio::time::sleep(std::time::Duration::from_millis(200)).await;
-                sys.refresh_cpu_usage();
-                sys.refresh_memory();
-
-                let cpu_percent = sys.global_cpu_usage();
-                let memory_bytes = sys.used_memory();
-                let memory_total = sys.total_memory();
-
-                // Get database file size
-                let db_size = if let Some(ref path) = self.db_path {
-                    std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
-                } else {
-                    0
-                };
-
-                self.broadcast(BotEvent::SystemMetricsUpdate {
-                    cpu_percent,
-                    memory_bytes,
-                    memory_total,
-                    db_size,
-                    uptime_seconds: self.get_uptime_seconds(),
-                });
+                self.broadcast_system_metrics().await;
                 debug!("Sent SystemMetricsUpdate response");
-
-                // Also log performance metrics to database for historical tracking
-                if let Some(ref db) = self.database {
-                    let _ = db
-                        .log_performance_metric("cpu", cpu_percent as f64, Some("%"), None)
-                        .await;
-                    let _ = db
-                        .log_performance_metric("memory", memory_bytes as f64, Some("bytes"), None)
-                        .await;
-                }
             }
             TuiCommand::GetChannelInfo { channel_id } => {
                 // Look up channel in cached guilds
@@ -770,7 +810,13 @@ impl IpcServer {
             }
             TuiCommand::GetHistoricalMetrics { metric_type, hours } => {
                 if let Some(ref db) = self.database {
-                    match db.get_historical_metrics(&metric_type, hours).await {
+                    // History comes from the metrics loop's tiers; the TUI asks by short name
+                    let stored_type = match metric_type.as_str() {
+                        "cpu" => "system_cpu_percent",
+                        "memory" => "system_memory_percent",
+                        other => other,
+                    };
+                    match db.get_historical_metrics(stored_type, hours).await {
                         Ok(data_points) => {
                             self.broadcast(BotEvent::HistoricalMetricsResponse {
                                 metric_type,
@@ -1136,13 +1182,230 @@ impl IpcServer {
                     self.broadcast(BotEvent::ChannelsWithHistoryResponse { channels: vec![] });
                 }
             }
+            TuiCommand::SearchMessages {
+                query,
+                guild_id,
+                limit,
+            } => {
+                let Some(ref db) = self.database else {
+                    return;
+                };
+                let guild_id_str = guild_id.map(|id| id.to_string());
+                match db
+                    .search_messages(&query, guild_id_str.as_deref(), None, limit as i64)
+                    .await
+                {
+                    Ok(entries) => {
+                        let guilds = self.get_guilds().await;
+                        let results: Vec<SearchResultInfo> = entries
+                            .into_iter()
+                            .map(|e| {
+                                let channel_id = e.channel_id.parse().unwrap_or(0);
+                                let (channel_name, guild_name) = guilds
+                                    .iter()
+                                    .find_map(|g| {
+                                        g.channels
+                                            .iter()
+                                            .find(|c| c.id == channel_id)
+                                            .map(|c| (Some(c.name.clone()), Some(g.name.clone())))
+                                    })
+                                    .unwrap_or((None, None));
+                                let timestamp =
+                                    NaiveDateTime::parse_from_str(&e.timestamp, "%Y-%m-%d %H:%M:%S")
+                                        .map(|dt| DateTime::<Utc>::from_naive_utc_and_offset(dt, Utc))
+                                        .ok();
+
+                                SearchResultInfo {
+                                    channel_id,
+                                    channel_name,
+                                    guild_id: e.guild_id.as_ref().and_then(|g| g.parse().ok()),
+                                    guild_name,
+                                    author_name: e.username.unwrap_or_else(|| e.user_id.clone()),
+                                    user_id: e.user_id,
+                                    is_bot: e.role == "assistant",
+                                    snippet: e.snippet,
+                                    timestamp,
+                                }
+                            })
+                            .collect();
+                        debug!("Sent SearchResultsResponse with {} results", results.len());
+                        self.broadcast(BotEvent::SearchResultsResponse { query, results });
+                    }
+                    Err(e) => {
+                        warn!("Failed to search messages: {e}");
+                        self.broadcast(BotEvent::SearchResultsResponse {
+                            query,
+                            results: vec![],
+                        });
+                    }
+                }
+            }
+            TuiCommand::GetSentimentTimeline { hours } => {
+                let Some(ref db) = self.database else {
+                    return;
+                };
+                let tracked = match db.get_sentiment_tracked_channels().await {
+                    Ok(tracked) => tracked,
+                    Err(e) => {
+                        warn!("Failed to get sentiment-tracked channels: {e}");
+                        return;
+                    }
+                };
+                let since = chrono::Utc::now().timestamp() - hours as i64 * 3600;
+                let guilds = self.get_guilds().await;
+                let mut channels = Vec::new();
+                for (guild_id, channel_id) in tracked {
+                    let points = match db.get_channel_sentiment(&channel_id, since).await {
+                        Ok(points) => points,
+                        Err(e) => {
+                            warn!("Failed to get sentiment for channel {channel_id}: {e}");
+                            continue;
+                        }
+                    };
+                    let guild_id: u64 = guild_id.parse().unwrap_or(0);
+                    let channel_id: u64 = channel_id.parse().unwrap_or(0);
+                    let guild = guilds.iter().find(|g| g.id == guild_id);
+                    channels.push(ChannelSentimentInfo {
+                        channel_id,
+                        channel_name: guild.and_then(|g| {
+                            g.channels
+                                .iter()
+                                .find(|c| c.id == channel_id)
+                                .map(|c| c.name.clone())
+                        }),
+                        guild_id,
+                        guild_name: guild.map(|g| g.name.clone()),
+                        points: points
+                            .into_iter()
+                            .map(|p| (p.hour, p.score, p.messages))
+                            .collect(),
+                    });
+                }
+                debug!("Sent SentimentTimelineResponse with {} channels", channels.len());
+                self.broadcast(BotEvent::SentimentTimelineResponse { hours, channels });
+            }
+            TuiCommand::ReloadPlugins { request_id } => {
+                let result = self.reload_plugins().await.map(|message| (message, None));
+                self.respond(request_id, result);
+            }
+            TuiCommand::ClearCaches { request_id } => {
+                let result = match &self.database {
+                    Some(db) => {
+                        db.clear_settings_cache();
+                        Ok(("Settings cache cleared".to_string(), None))
+                    }
+                    None => Err(anyhow::anyhow!("Database not configured")),
+                };
+                self.respond(request_id, result);
+            }
+            TuiCommand::GetGuildSettings {
+                request_id,
+                guild_id,
+            } => {
+                let result = self.guild_settings(guild_id).await;
+                self.respond(request_id, result);
+            }
+            TuiCommand::CancelJob { request_id, job_id } => {
+                let result = self
+                    .cancel_job(&job_id)
+                    .await
+                    .map(|message| (message, None));
+                self.respond(request_id, result);
+            }
         }
     }
 
+    /// Answer an admin command with its outcome
+    fn respond(&self, request_id: String, result: Result<(String, Option<serde_json::Value>)>) {
+        let event = match result {
+            Ok((message, data)) => BotEvent::CommandResponse {
+                request_id,
+                success: true,
+                message: Some(message),
+                data,
+            },
+            Err(e) => {
+                warn!("IPC admin command failed: {e}");
+                BotEvent::CommandResponse {
+                    request_id,
+                    success: false,
+                    message: Some(e.to_string()),
+                    data: None,
+                }
+            }
+        };
+        self.broadcast(event);
+    }
+
+    async fn plugin_reload(&self) -> Result<PluginReload> {
+        self.plugins.read().await.clone().ok_or_else(|| {
+            anyhow::anyhow!("Plugin system is disabled (no plugin configuration at startup)")
+        })
+    }
+
+    /// Swap in the plugin configuration on disk and re-register slash commands
+    async fn reload_plugins(&self) -> Result<String> {
+        let reload = self.plugin_reload().await?;
+        let config = PluginConfig::load_auto(&reload.path)?;
+        let count = config.plugins.len();
+        reload.manager.replace_config(config);
+        info!(
+            "🔌 Reloaded {count} plugin(s) from {} over IPC",
+            reload.path
+        );
+
+        let Some(http) = self.http.read().await.clone() else {
+            return Ok(format!(
+                "Reloaded {count} plugin(s); slash commands are registered when the bot connects"
+            ));
+        };
+        let plugins = reload.manager.plugins();
+        match reload.guild_id {
+            Some(guild_id) => {
+                register_guild_commands_with_plugins(&http, guild_id, &plugins).await?
+            }
+            None => register_global_commands_with_plugins(&http, &plugins).await?,
+        }
+        Ok(format!(
+            "Reloaded {count} plugin(s) from {} and re-registered slash commands",
+            reload.path
+        ))
+    }
+
+    async fn cancel_job(&self, job_id: &str) -> Result<String> {
+        let jobs = self.plugin_reload().await?.manager.job_manager.clone();
+        if jobs.cancel_playlist_job(job_id, "IPC").await? {
+            return Ok(format!("Playlist job {job_id} cancelled"));
+        }
+        if jobs.get_playlist_job(job_id).is_some() {
+            anyhow::bail!("Playlist job {job_id} has already finished");
+        }
+        if jobs.get_job(job_id).is_some() {
+            anyhow::bail!(
+                "Job {job_id} is a single plugin run; only playlist jobs can be cancelled"
+            );
+        }
+        anyhow::bail!("No job {job_id}")
+    }
+
+    async fn guild_settings(&self, guild_id: u64) -> Result<(String, Option<serde_json::Value>)> {
+        let db = self
+            .database
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Database not configured"))?;
+        let settings = db.get_all_guild_settings(&guild_id.to_string()).await?;
+        let message = format!("{} setting(s) for guild {guild_id}", settings.len());
+        let data: serde_json::Map<String, serde_json::Value> = settings
+            .into_iter()
+            .map(|(key, value)| (key, serde_json::Value::String(value)))
+            .collect();
+        Ok((message, Some(serde_json::Value::Object(data))))
+    }
+
     /// Start the command processing loop (call this after server start)
     pub fn start_command_processor(self: Arc<Self>) {
         let server = self.clone();
-        tokio::spawn(async move {
+        spawn_tracked(TaskKind::Ipc, async move {
             info!("📡 IPC command processor started");
             loop {
                 if let Some(cmd) = server.try_recv_command().await {
@@ -7,6 +7,9 @@ pub mod features;
 // IPC layer - communication between bot and TUI
 pub mod ipc;
 
+// Dry-run harness - mocked Discord and OpenAI for scenario tests
+pub mod testing;
+
 // TUI layer - terminal user interface (optional feature)
 #[cfg(feature = "tui")]
 pub mod tui;
@@ -16,6 +19,7 @@ pub mod message_components;
 
 // Infrastructure (to be reorganized)
 pub mod database;
+pub mod message_writer;
 
 // Application layer
 pub mod command_handler;
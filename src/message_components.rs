@@ -1,16 +1,37 @@
 use anyhow::Result;
-use log::{error, info};
+use async_trait::async_trait;
+use log::{error, info, warn};
 use serenity::builder::CreateComponents;
 use serenity::model::application::component::{ActionRowComponent, ButtonStyle};
 use serenity::model::application::interaction::message_component::MessageComponentInteraction;
 use serenity::model::application::interaction::modal::ModalSubmitInteraction;
 use serenity::model::application::interaction::InteractionResponseType;
 use serenity::prelude::Context;
+use std::time::Duration;
 
-use crate::commands::CommandHandler;
+use crate::commands::{CommandHandler, ComponentHandler, ComponentId};
+use crate::core::{chunk_for_embed, continuation_embed, persona_embed};
 use crate::database::Database;
+use crate::features::achievements::{self, Progress};
+use crate::features::analytics::runtime::track_openai;
 use crate::features::analytics::CostBucket;
-use crate::features::personas::PersonaManager;
+use crate::features::discussion::{
+    forget_discussion_state, parse_discussion_target, restore_discussion_state,
+    save_discussion_state_logged, DiscussionType,
+};
+use crate::features::guardrails::GuardrailProfile;
+use crate::features::personas::{Persona, PersonaManager};
+use crate::features::scenes::{self, channel_scene};
+
+/// How long confirm/cancel buttons stay usable
+pub const CONFIRMATION_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// First-contact notice sent instead of a reply until the user accepts
+pub const DM_CONSENT_PROMPT: &str = "👋 Before I reply: answering DMs means sending your messages to OpenAI and keeping our conversation history so I remember context. Nothing you send is read or stored until you accept, and `/privacy` lets you change what's kept later.";
+
+/// Sent to users who declined the consent prompt when they DM again
+pub const DM_CONSENT_DECLINED: &str =
+    "🔒 You declined, so I'm not reading or storing your DMs. Changed your mind? Accept below.";
 
 /// Handler for all message component interactions
 pub struct MessageComponentHandler {
@@ -32,139 +53,37 @@ impl MessageComponentHandler {
         }
     }
 
-    /// Handle all types of component interactions
-    pub async fn handle_component_interaction(
-        &self,
-        ctx: &Context,
-        interaction: &MessageComponentInteraction,
-    ) -> Result<()> {
-        let custom_id = &interaction.data.custom_id;
-        let user_id = interaction.user.id.to_string();
-
-        info!("Processing component interaction: {custom_id} from user: {user_id}");
-
-        match custom_id.as_str() {
-            "persona_muppet" | "persona_chef" | "persona_obi" | "persona_teacher"
-            | "persona_analyst" | "persona_visionary" => {
-                self.handle_persona_button(ctx, interaction).await?;
-            }
-            id if id.starts_with("confirm_") => {
-                self.handle_confirmation(ctx, interaction).await?;
-            }
-            id if id.starts_with("cancel_") => {
-                self.handle_cancellation(ctx, interaction).await?;
-            }
-            id if id.starts_with("page_") => {
-                self.handle_pagination(ctx, interaction).await?;
-            }
-            id if id.starts_with("debate_continue_") => {
-                self.handle_debate_continue(ctx, interaction).await?;
-            }
-            id if id.starts_with("debate_end_") => {
-                self.handle_debate_end(ctx, interaction).await?;
-            }
-            id if id.starts_with("hear_debate_") => {
-                self.handle_debate_hear(ctx, interaction).await?;
-            }
-            id if id.starts_with("speaker_council_") => {
-                self.handle_council_speaker(ctx, interaction).await?;
-            }
-            id if id.starts_with("continue_council_") => {
-                self.handle_council_continue(ctx, interaction).await?;
-            }
-            id if id.starts_with("dismiss_council_") => {
-                self.handle_council_dismiss(ctx, interaction).await?;
-            }
-            "show_help_modal" => {
-                self.show_help_modal(ctx, interaction).await?;
-            }
-            "show_persona_modal" => {
-                self.show_persona_creation_modal(ctx, interaction).await?;
-            }
-            _ => {
-                interaction
-                    .create_interaction_response(&ctx.http, |response| {
-                        response
-                            .kind(InteractionResponseType::ChannelMessageWithSource)
-                            .interaction_response_data(|message| {
-                                message.content("Unknown component interaction.")
-                            })
-                    })
-                    .await?;
-            }
-        }
-
-        Ok(())
-    }
-
-    /// Handle modal submit interactions
-    pub async fn handle_modal_submit(
-        &self,
-        ctx: &Context,
-        interaction: &ModalSubmitInteraction,
-    ) -> Result<()> {
-        let custom_id = &interaction.data.custom_id;
-        let user_id = interaction.user.id.to_string();
-
-        info!("Processing modal submit: {custom_id} from user: {user_id}");
-
-        match custom_id.as_str() {
-            "help_feedback_modal" => {
-                self.handle_help_feedback_modal(ctx, interaction).await?;
-            }
-            "persona_creation_modal" => {
-                self.handle_persona_creation_modal(ctx, interaction).await?;
-            }
-            "ai_prompt_modal" => {
-                self.handle_ai_prompt_modal(ctx, interaction).await?;
-            }
-            _ => {
-                interaction
-                    .create_interaction_response(&ctx.http, |response| {
-                        response
-                            .kind(InteractionResponseType::ChannelMessageWithSource)
-                            .interaction_response_data(|message| {
-                                message.content("Unknown modal submission.")
-                            })
-                    })
-                    .await?;
-            }
-        }
-
-        Ok(())
-    }
-
     /// Create persona selection components (simplified for compatibility)
     pub fn create_persona_select_menu() -> CreateComponents {
         CreateComponents::default()
             .create_action_row(|row| {
                 row.create_button(|button| {
                     button
-                        .custom_id("persona_muppet")
+                        .custom_id(ComponentId::new("persona", "select", "muppet").to_string())
                         .label("🐸 Muppet")
                         .style(ButtonStyle::Secondary)
                 })
                 .create_button(|button| {
                     button
-                        .custom_id("persona_chef")
+                        .custom_id(ComponentId::new("persona", "select", "chef").to_string())
                         .label("👨‍🍳 Chef")
                         .style(ButtonStyle::Secondary)
                 })
                 .create_button(|button| {
                     button
-                        .custom_id("persona_obi")
+                        .custom_id(ComponentId::new("persona", "select", "obi").to_string())
                         .label("⚔️ Obi-Wan")
                         .style(ButtonStyle::Secondary)
                 })
                 .create_button(|button| {
                     button
-                        .custom_id("persona_teacher")
+                        .custom_id(ComponentId::new("persona", "select", "teacher").to_string())
                         .label("📚 Teacher")
                         .style(ButtonStyle::Secondary)
                 })
                 .create_button(|button| {
                     button
-                        .custom_id("persona_analyst")
+                        .custom_id(ComponentId::new("persona", "select", "analyst").to_string())
                         .label("📊 Analyst")
                         .style(ButtonStyle::Secondary)
                 })
@@ -172,7 +91,7 @@ impl MessageComponentHandler {
             .create_action_row(|row| {
                 row.create_button(|button| {
                     button
-                        .custom_id("persona_visionary")
+                        .custom_id(ComponentId::new("persona", "select", "visionary").to_string())
                         .label("🔮 Visionary")
                         .style(ButtonStyle::Secondary)
                 })
@@ -186,13 +105,13 @@ impl MessageComponentHandler {
             .create_action_row(|row| {
                 row.create_button(|button| {
                     button
-                        .custom_id("show_help_modal")
+                        .custom_id(ComponentId::new("help", "show", "").to_string())
                         .label("❓ Get Detailed Help")
                         .style(ButtonStyle::Primary)
                 })
                 .create_button(|button| {
                     button
-                        .custom_id("show_persona_modal")
+                        .custom_id(ComponentId::new("prompt", "show", "").to_string())
                         .label("✨ Create Custom Prompt")
                         .style(ButtonStyle::Secondary)
                 })
@@ -200,64 +119,71 @@ impl MessageComponentHandler {
             .to_owned()
     }
 
-    /// Create confirmation buttons
-    pub fn create_confirmation_buttons(action_id: &str) -> CreateComponents {
+    /// Create the DM consent buttons; users who already declined only get Accept
+    pub fn create_dm_consent_buttons(declined: bool) -> CreateComponents {
         CreateComponents::default()
             .create_action_row(|row| {
                 row.create_button(|button| {
                     button
-                        .custom_id(format!("confirm_{action_id}"))
-                        .label("✅ Confirm")
+                        .custom_id(ComponentId::new("consent", "accept", "").to_string())
+                        .label("✅ Accept")
                         .style(ButtonStyle::Success)
-                })
-                .create_button(|button| {
-                    button
-                        .custom_id(format!("cancel_{action_id}"))
-                        .label("❌ Cancel")
-                        .style(ButtonStyle::Danger)
-                })
+                });
+                if !declined {
+                    row.create_button(|button| {
+                        button
+                            .custom_id(ComponentId::new("consent", "decline", "").to_string())
+                            .label("Decline")
+                            .style(ButtonStyle::Secondary)
+                    });
+                }
+                row
             })
             .to_owned()
     }
 
-    /// Create pagination buttons
-    pub fn create_pagination_buttons(current_page: u32, total_pages: u32) -> CreateComponents {
+    /// Record the user's answer to the DM consent prompt
+    async fn handle_dm_consent(
+        &self,
+        ctx: &Context,
+        interaction: &MessageComponentInteraction,
+        accepted: bool,
+    ) -> Result<()> {
+        let user_id = interaction.user.id.to_string();
+        self.database.set_dm_consent(&user_id, accepted).await?;
+
+        let content = if accepted {
+            "✅ Thanks! Send me a message and I'll reply. Anything you sent before accepting was not read, so please send it again."
+        } else {
+            "🚫 Got it. I won't read or store your DMs; message me again any time if you change your mind."
+        };
+        interaction
+            .create_interaction_response(&ctx.http, |response| {
+                response
+                    .kind(InteractionResponseType::UpdateMessage)
+                    .interaction_response_data(|message| message.content(content).components(|c| c))
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Create confirmation buttons (they expire after `CONFIRMATION_TTL`)
+    pub fn create_confirmation_buttons(action_id: &str) -> CreateComponents {
+        let confirm_id = ComponentId::new("confirm", "yes", action_id).expires_in(CONFIRMATION_TTL);
+        let cancel_id = ComponentId::new("confirm", "no", action_id).expires_in(CONFIRMATION_TTL);
         CreateComponents::default()
             .create_action_row(|row| {
                 row.create_button(|button| {
                     button
-                        .custom_id("page_first")
-                        .label("⏮️")
-                        .style(ButtonStyle::Secondary)
-                        .disabled(current_page == 1)
-                })
-                .create_button(|button| {
-                    button
-                        .custom_id("page_prev")
-                        .label("⬅️")
-                        .style(ButtonStyle::Secondary)
-                        .disabled(current_page == 1)
-                })
-                .create_button(|button| {
-                    button
-                        .custom_id("page_info")
-                        .label(format!("{current_page}/{total_pages}"))
-                        .style(ButtonStyle::Secondary)
-                        .disabled(true)
-                })
-                .create_button(|button| {
-                    button
-                        .custom_id("page_next")
-                        .label("➡️")
-                        .style(ButtonStyle::Secondary)
-                        .disabled(current_page == total_pages)
+                        .custom_id(confirm_id.to_string())
+                        .label("✅ Confirm")
+                        .style(ButtonStyle::Success)
                 })
                 .create_button(|button| {
                     button
-                        .custom_id("page_last")
-                        .label("⏭️")
-                        .style(ButtonStyle::Secondary)
-                        .disabled(current_page == total_pages)
+                        .custom_id(cancel_id.to_string())
+                        .label("❌ Cancel")
+                        .style(ButtonStyle::Danger)
                 })
             })
             .to_owned()
@@ -268,22 +194,14 @@ impl MessageComponentHandler {
         &self,
         ctx: &Context,
         interaction: &MessageComponentInteraction,
+        persona_name: &str,
     ) -> Result<()> {
-        let persona_name = match interaction.data.custom_id.as_str() {
-            "persona_muppet" => "muppet",
-            "persona_chef" => "chef",
-            "persona_obi" => "obi",
-            "persona_teacher" => "teacher",
-            "persona_analyst" => "analyst",
-            "persona_visionary" => "visionary",
-            _ => return Ok(()),
-        };
-
         let user_id = interaction.user.id.to_string();
+        let guild_id = interaction.guild_id.map(|id| id.to_string());
 
         if self.persona_manager.get_persona(persona_name).is_some() {
             self.database
-                .set_user_persona(&user_id, persona_name)
+                .set_user_persona_in_guild(&user_id, guild_id.as_deref(), persona_name)
                 .await?;
 
             interaction
@@ -319,13 +237,8 @@ impl MessageComponentHandler {
         &self,
         ctx: &Context,
         interaction: &MessageComponentInteraction,
+        action_id: &str,
     ) -> Result<()> {
-        let action_id = interaction
-            .data
-            .custom_id
-            .strip_prefix("confirm_")
-            .unwrap_or("");
-
         interaction
             .create_interaction_response(&ctx.http, |response| {
                 response
@@ -361,41 +274,6 @@ impl MessageComponentHandler {
         Ok(())
     }
 
-    /// Handle pagination button clicks
-    async fn handle_pagination(
-        &self,
-        ctx: &Context,
-        interaction: &MessageComponentInteraction,
-    ) -> Result<()> {
-        let action = interaction
-            .data
-            .custom_id
-            .strip_prefix("page_")
-            .unwrap_or("");
-
-        // This is a simple implementation - in a real app you'd track page state
-        let message = match action {
-            "first" => "📄 Showing first page",
-            "prev" => "📄 Showing previous page",
-            "next" => "📄 Showing next page",
-            "last" => "📄 Showing last page",
-            _ => "📄 Page navigation",
-        };
-
-        interaction
-            .create_interaction_response(&ctx.http, |response| {
-                response
-                    .kind(InteractionResponseType::UpdateMessage)
-                    .interaction_response_data(|msg| {
-                        msg.content(message)
-                            .set_components(Self::create_pagination_buttons(1, 3))
-                    })
-            })
-            .await?;
-
-        Ok(())
-    }
-
     /// Show help modal
     async fn show_help_modal(
         &self,
@@ -408,7 +286,7 @@ impl MessageComponentHandler {
                     .kind(InteractionResponseType::Modal)
                     .interaction_response_data(|modal| {
                         modal
-                            .custom_id("help_feedback_modal")
+                            .custom_id(ComponentId::new("help", "feedback", "").to_string())
                             .title("Help & Feedback")
                             .components(|c| {
                                 c.create_action_row(|row| {
@@ -454,7 +332,7 @@ impl MessageComponentHandler {
                     .kind(InteractionResponseType::Modal)
                     .interaction_response_data(|modal| {
                         modal
-                            .custom_id("ai_prompt_modal")
+                            .custom_id(ComponentId::new("prompt", "submit", "").to_string())
                             .title("Custom AI Prompt")
                             .components(|c| {
                                 c.create_action_row(|row| {
@@ -499,14 +377,23 @@ impl MessageComponentHandler {
         }
 
         let user_id = interaction.user.id.to_string();
-        let user_persona = self.database.get_user_persona(&user_id).await?;
+        let guild_id = interaction.guild_id.map(|id| id.to_string());
+        let user_persona = self
+            .database
+            .get_user_persona_with_guild(&user_id, guild_id.as_deref())
+            .await?;
         let system_prompt = self
             .persona_manager
             .get_system_prompt(&user_persona, Some("explain"));
 
         // Log the help request
         self.database
-            .log_usage(&user_id, "help_modal", Some(&user_persona))
+            .log_usage(
+                &user_id,
+                guild_id.as_deref(),
+                "help_modal",
+                Some(&user_persona),
+            )
             .await?;
 
         let combined_message = if help_details.is_empty() {
@@ -570,8 +457,9 @@ impl MessageComponentHandler {
         }
 
         let user_id = interaction.user.id.to_string();
+        let guild_id = interaction.guild_id.map(|id| id.to_string());
         self.database
-            .log_usage(&user_id, "custom_prompt", None)
+            .log_usage(&user_id, guild_id.as_deref(), "custom_prompt", None)
             .await?;
 
         // Immediately defer the interaction to prevent timeout
@@ -627,18 +515,11 @@ impl MessageComponentHandler {
         &self,
         ctx: &Context,
         interaction: &MessageComponentInteraction,
+        thread_id: u64,
     ) -> Result<()> {
         use crate::features::debate::{get_active_debates, DebateOrchestrator, CONTINUE_ROUNDS};
         use serenity::model::id::ChannelId;
 
-        // Extract thread ID from custom_id
-        let thread_id_str = interaction
-            .data
-            .custom_id
-            .strip_prefix("debate_continue_")
-            .unwrap_or("0");
-        let thread_id: u64 = thread_id_str.parse().unwrap_or(0);
-
         // Check if debate state exists
         if get_active_debates().get(&thread_id).is_none() {
             interaction
@@ -675,6 +556,15 @@ impl MessageComponentHandler {
         let channel_id_str = thread_id.to_string();
         let openai_model = std::env::var("OPENAI_MODEL").unwrap_or_else(|_| "gpt-4o".to_string());
         let usage_tracker = self.command_handler.get_usage_tracker();
+        let database = self.database.clone();
+        let guardrails = GuardrailProfile::for_channel(
+            &database,
+            guild_id.as_deref(),
+            Some(&thread_id.to_string()),
+        )
+        .await?;
+        let scene =
+            channel_scene(&database, guild_id.as_deref(), Some(&thread_id.to_string())).await?;
 
         // Spawn the continuation
         tokio::spawn(async move {
@@ -684,6 +574,8 @@ impl MessageComponentHandler {
             let get_response = |system_prompt: String,
                                 user_message: String,
                                 history: Vec<(String, String)>| {
+                let system_prompt =
+                    scenes::with_scene(guardrails.apply(&system_prompt), scene.as_deref());
                 let model = openai_model.clone();
                 let tracker = usage_tracker.clone();
                 let uid = user_id.clone();
@@ -725,8 +617,8 @@ impl MessageComponentHandler {
                         tool_calls: None,
                     });
 
-                    let chat_completion = openai::chat::ChatCompletion::builder(&model, messages)
-                        .create()
+                    let request = openai::chat::ChatCompletion::builder(&model, messages).create();
+                    let chat_completion = track_openai(request)
                         .await
                         .map_err(|e| anyhow::anyhow!("OpenAI API error: {}", e))?;
 
@@ -763,6 +655,7 @@ impl MessageComponentHandler {
                     })
                     .await;
             }
+            save_discussion_state_logged(&database, DiscussionType::Debate, thread_id).await;
         });
 
         Ok(())
@@ -773,19 +666,34 @@ impl MessageComponentHandler {
         &self,
         ctx: &Context,
         interaction: &MessageComponentInteraction,
+        thread_id: u64,
     ) -> Result<()> {
-        use crate::features::debate::DebateOrchestrator;
-
-        // Extract thread ID from custom_id
-        let thread_id_str = interaction
-            .data
-            .custom_id
-            .strip_prefix("debate_end_")
-            .unwrap_or("0");
-        let thread_id: u64 = thread_id_str.parse().unwrap_or(0);
+        use crate::features::debate::{notify_concluded, record_result, DebateOrchestrator};
+        use crate::features::discussion::create_debate_vote_buttons;
 
         // Clean up the debate state
-        DebateOrchestrator::end_debate(thread_id);
+        let state = DebateOrchestrator::end_debate(thread_id);
+        forget_discussion_state(&self.database, DiscussionType::Debate, thread_id).await?;
+        let guild_id = interaction.guild_id.map(|id| id.to_string());
+        let mut vote_buttons = None;
+        if let Some(state) = state {
+            record_result(&self.database, guild_id.as_deref(), thread_id, &state).await;
+            if let Some(guild_id) = &guild_id {
+                notify_concluded(
+                    &self.database,
+                    &self.persona_manager,
+                    guild_id,
+                    thread_id,
+                    &state,
+                );
+            }
+            vote_buttons = Some(create_debate_vote_buttons(
+                thread_id,
+                &state.config.persona1_id,
+                &state.config.persona2_id,
+                &self.persona_manager,
+            ));
+        }
 
         // Update the message to remove buttons
         interaction
@@ -796,10 +704,15 @@ impl MessageComponentHandler {
                         message
                             .embed(|e| {
                                 e.title("Debate Concluded")
-                                    .description("This debate has ended. Thank you for watching!")
+                                    .description(if vote_buttons.is_some() {
+                                        "This debate has ended. Thank you for watching! Who won?"
+                                    } else {
+                                        "This debate has ended. Thank you for watching!"
+                                    })
                                     .color(0x7289DA)
                             })
-                            .components(|c| c) // Clear buttons
+                            // Swap the controls for vote buttons (or clear them)
+                            .set_components(vote_buttons.unwrap_or_default())
                     })
             })
             .await?;
@@ -808,33 +721,93 @@ impl MessageComponentHandler {
         Ok(())
     }
 
+    /// Handle a vote for who won a concluded debate; voting again changes the pick
+    async fn handle_debate_vote(
+        &self,
+        ctx: &Context,
+        interaction: &MessageComponentInteraction,
+        thread_id: u64,
+        persona_id: String,
+    ) -> Result<()> {
+        let thread = thread_id.to_string();
+        let result = self.database.get_debate_result(&thread).await?;
+        let mut leader = None;
+        let content = match &result {
+            Some(result) if [&result.persona1_id, &result.persona2_id].contains(&&persona_id) => {
+                self.database
+                    .record_debate_vote(&thread, &interaction.user.id.to_string(), &persona_id)
+                    .await?;
+                let tally = self.database.get_debate_tally(&thread).await?;
+                let votes = |id: &str| {
+                    tally
+                        .iter()
+                        .find(|(p, _)| p == id)
+                        .map(|(_, n)| *n)
+                        .unwrap_or(0)
+                };
+                leader = match votes(&result.persona1_id).cmp(&votes(&result.persona2_id)) {
+                    std::cmp::Ordering::Greater => Some(result.persona1_id.clone()),
+                    std::cmp::Ordering::Less => Some(result.persona2_id.clone()),
+                    std::cmp::Ordering::Equal => None,
+                };
+                let name = |id: &str| {
+                    self.persona_manager
+                        .get_persona(id)
+                        .map(|p| p.name.clone())
+                        .unwrap_or_else(|| id.to_string())
+                };
+                format!(
+                    "🗳️ You picked **{}**. Current tally: {} {} – {} {}",
+                    name(&persona_id),
+                    name(&result.persona1_id),
+                    votes(&result.persona1_id),
+                    votes(&result.persona2_id),
+                    name(&result.persona2_id),
+                )
+            }
+            _ => "This debate isn't open for votes.".to_string(),
+        };
+
+        interaction
+            .create_interaction_response(&ctx.http, |response| {
+                response
+                    .kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|message| message.content(content).ephemeral(true))
+            })
+            .await?;
+        info!("Debate vote in thread {thread_id} for {persona_id}");
+
+        // A vote can put a side ahead, so everyone backing the leader may have a win now
+        if let (Some(leader), Some(guild_id)) = (leader, result.and_then(|result| result.guild_id))
+        {
+            for voter in self.database.get_debate_voters(&thread, &leader).await? {
+                if let Err(e) = achievements::check(
+                    &self.database,
+                    &ctx.http,
+                    &guild_id,
+                    &voter,
+                    Progress::default(),
+                )
+                .await
+                {
+                    warn!("Failed to check achievements for {voter}: {e}");
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Handle debate hear button - get a single response from a specific persona
     async fn handle_debate_hear(
         &self,
         ctx: &Context,
         interaction: &MessageComponentInteraction,
+        thread_id: u64,
+        persona_id: String,
     ) -> Result<()> {
         use crate::features::debate::{get_active_debates, DebateOrchestrator};
-        use crate::features::parse_debate_hear_id;
         use serenity::model::id::ChannelId;
 
-        // Parse the custom_id to get thread_id and persona_id
-        let (thread_id, persona_id) = match parse_debate_hear_id(&interaction.data.custom_id) {
-            Some(parsed) => parsed,
-            None => {
-                interaction
-                    .create_interaction_response(&ctx.http, |response| {
-                        response
-                            .kind(InteractionResponseType::ChannelMessageWithSource)
-                            .interaction_response_data(|message| {
-                                message.content("Invalid button data.").ephemeral(true)
-                            })
-                    })
-                    .await?;
-                return Ok(());
-            }
-        };
-
         // Check if debate state exists
         let _state = match get_active_debates().get(&thread_id) {
             Some(s) => s.clone(),
@@ -881,6 +854,15 @@ impl MessageComponentHandler {
         let openai_model = std::env::var("OPENAI_MODEL").unwrap_or_else(|_| "gpt-4o".to_string());
         let usage_tracker = self.command_handler.get_usage_tracker();
         let persona_manager_clone = self.persona_manager.clone();
+        let database = self.database.clone();
+        let guardrails = GuardrailProfile::for_channel(
+            &database,
+            guild_id.as_deref(),
+            Some(&thread_id.to_string()),
+        )
+        .await?;
+        let scene =
+            channel_scene(&database, guild_id.as_deref(), Some(&thread_id.to_string())).await?;
 
         // Spawn the single response task
         tokio::spawn(async move {
@@ -890,6 +872,8 @@ impl MessageComponentHandler {
             let get_response = |system_prompt: String,
                                 user_message: String,
                                 history: Vec<(String, String)>| {
+                let system_prompt =
+                    scenes::with_scene(guardrails.apply(&system_prompt), scene.as_deref());
                 let model = openai_model.clone();
                 let tracker = usage_tracker.clone();
                 let uid = user_id.clone();
@@ -931,8 +915,8 @@ impl MessageComponentHandler {
                         tool_calls: None,
                     });
 
-                    let chat_completion = openai::chat::ChatCompletion::builder(&model, messages)
-                        .create()
+                    let request = openai::chat::ChatCompletion::builder(&model, messages).create();
+                    let chat_completion = track_openai(request)
                         .await
                         .map_err(|e| anyhow::anyhow!("OpenAI API error: {}", e))?;
 
@@ -970,6 +954,8 @@ impl MessageComponentHandler {
                     .await;
             }
 
+            save_discussion_state_logged(&database, DiscussionType::Debate, thread_id).await;
+
             // Re-send the control buttons
             let updated_state = get_active_debates().get(&thread_id).map(|s| s.clone());
             if let Some(state) = updated_state {
@@ -1002,26 +988,10 @@ impl MessageComponentHandler {
         &self,
         ctx: &Context,
         interaction: &MessageComponentInteraction,
+        thread_id: u64,
+        persona_id: String,
     ) -> Result<()> {
         use crate::features::council::get_active_councils;
-        use crate::features::parse_council_speaker_id;
-
-        // Parse the custom_id
-        let (thread_id, persona_id) = match parse_council_speaker_id(&interaction.data.custom_id) {
-            Some(parsed) => parsed,
-            None => {
-                interaction
-                    .create_interaction_response(&ctx.http, |response| {
-                        response
-                            .kind(InteractionResponseType::ChannelMessageWithSource)
-                            .interaction_response_data(|message| {
-                                message.content("Invalid button data.").ephemeral(true)
-                            })
-                    })
-                    .await?;
-                return Ok(());
-            }
-        };
 
         // Check if council state exists
         let state = match get_active_councils().get(&thread_id) {
@@ -1068,6 +1038,15 @@ impl MessageComponentHandler {
         let openai_model = std::env::var("OPENAI_MODEL").unwrap_or_else(|_| "gpt-4o".to_string());
         let usage_tracker = self.command_handler.get_usage_tracker();
         let persona_manager = self.persona_manager.clone();
+        let database = self.database.clone();
+        let guardrails = GuardrailProfile::for_channel(
+            &database,
+            guild_id.as_deref(),
+            Some(&thread_id.to_string()),
+        )
+        .await?;
+        let scene =
+            channel_scene(&database, guild_id.as_deref(), Some(&thread_id.to_string())).await?;
         let channel_id = serenity::model::id::ChannelId(thread_id);
 
         tokio::spawn(async move {
@@ -1101,7 +1080,10 @@ impl MessageComponentHandler {
             let messages = vec![
                 openai::chat::ChatCompletionMessage {
                     role: openai::chat::ChatCompletionMessageRole::System,
-                    content: Some(council_context),
+                    content: Some(scenes::with_scene(
+                        guardrails.apply(&council_context),
+                        scene.as_deref(),
+                    )),
                     name: None,
                     function_call: None,
                     tool_call_id: None,
@@ -1117,9 +1099,10 @@ impl MessageComponentHandler {
                 },
             ];
 
-            let response = match openai::chat::ChatCompletion::builder(&openai_model, messages)
-                .create()
-                .await
+            let response = match track_openai(
+                openai::chat::ChatCompletion::builder(&openai_model, messages).create(),
+            )
+            .await
             {
                 Ok(completion) => {
                     if let Some(usage) = &completion.usage {
@@ -1151,27 +1134,10 @@ impl MessageComponentHandler {
             if let Some(mut state) = get_active_councils().get_mut(&thread_id) {
                 state.add_persona_response(&persona_id, response.clone());
             }
+            save_discussion_state_logged(&database, DiscussionType::Council, thread_id).await;
 
             // Send the response
-            let mut embed = serenity::builder::CreateEmbed::default();
-            embed.author(|a| {
-                a.name(&persona.name);
-                if let Some(url) = &persona.portrait_url {
-                    a.icon_url(url);
-                }
-                a
-            });
-            embed.color(persona.color);
-            let response_text = if response.len() > 4096 {
-                format!("{}...", &response[..4090])
-            } else {
-                response
-            };
-            embed.description(&response_text);
-
-            let _ = channel_id
-                .send_message(&ctx_clone.http, |m| m.set_embed(embed.clone()))
-                .await;
+            send_persona_response(&ctx_clone, channel_id, &persona, &response).await;
 
             // Re-send control buttons
             if let Some(state) = get_active_councils().get(&thread_id) {
@@ -1203,17 +1169,10 @@ impl MessageComponentHandler {
         &self,
         ctx: &Context,
         interaction: &MessageComponentInteraction,
+        thread_id: u64,
     ) -> Result<()> {
         use crate::features::council::get_active_councils;
 
-        // Extract thread ID
-        let thread_id_str = interaction
-            .data
-            .custom_id
-            .strip_prefix("continue_council_")
-            .unwrap_or("0");
-        let thread_id: u64 = thread_id_str.parse().unwrap_or(0);
-
         // Check if council exists
         let state = match get_active_councils().get(&thread_id) {
             Some(s) => s.clone(),
@@ -1254,6 +1213,15 @@ impl MessageComponentHandler {
         let persona_manager = self.persona_manager.clone();
         let channel_id = serenity::model::id::ChannelId(thread_id);
         let persona_ids = state.persona_ids.clone();
+        let database = self.database.clone();
+        let guardrails = GuardrailProfile::for_channel(
+            &database,
+            guild_id.as_deref(),
+            Some(&thread_id.to_string()),
+        )
+        .await?;
+        let scene =
+            channel_scene(&database, guild_id.as_deref(), Some(&thread_id.to_string())).await?;
 
         tokio::spawn(async move {
             // Add continuation marker to history
@@ -1289,7 +1257,10 @@ impl MessageComponentHandler {
                 let messages = vec![
                     openai::chat::ChatCompletionMessage {
                         role: openai::chat::ChatCompletionMessageRole::System,
-                        content: Some(council_context),
+                        content: Some(scenes::with_scene(
+                        guardrails.apply(&council_context),
+                        scene.as_deref(),
+                    )),
                         name: None,
                         function_call: None,
                         tool_call_id: None,
@@ -1307,9 +1278,10 @@ impl MessageComponentHandler {
                     },
                 ];
 
-                let response = match openai::chat::ChatCompletion::builder(&openai_model, messages)
-                    .create()
-                    .await
+                let response = match track_openai(
+                    openai::chat::ChatCompletion::builder(&openai_model, messages).create(),
+                )
+                .await
                 {
                     Ok(completion) => {
                         if let Some(usage) = &completion.usage {
@@ -1343,29 +1315,13 @@ impl MessageComponentHandler {
                 }
 
                 // Send embed
-                let mut embed = serenity::builder::CreateEmbed::default();
-                embed.author(|a| {
-                    a.name(&persona.name);
-                    if let Some(url) = &persona.portrait_url {
-                        a.icon_url(url);
-                    }
-                    a
-                });
-                embed.color(persona.color);
-                let response_text = if response.len() > 4096 {
-                    format!("{}...", &response[..4090])
-                } else {
-                    response
-                };
-                embed.description(&response_text);
-
-                let _ = channel_id
-                    .send_message(&ctx_clone.http, |m| m.set_embed(embed.clone()))
-                    .await;
+                send_persona_response(&ctx_clone, channel_id, &persona, &response).await;
 
                 tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
             }
 
+            save_discussion_state_logged(&database, DiscussionType::Council, thread_id).await;
+
             // Re-send control buttons
             if let Some(state) = get_active_councils().get(&thread_id) {
                 let buttons = crate::features::create_council_buttons(
@@ -1392,19 +1348,13 @@ impl MessageComponentHandler {
         &self,
         ctx: &Context,
         interaction: &MessageComponentInteraction,
+        thread_id: u64,
     ) -> Result<()> {
         use crate::features::council::get_active_councils;
 
-        // Extract thread ID
-        let thread_id_str = interaction
-            .data
-            .custom_id
-            .strip_prefix("dismiss_council_")
-            .unwrap_or("0");
-        let thread_id: u64 = thread_id_str.parse().unwrap_or(0);
-
         // Remove the council state
         get_active_councils().remove(&thread_id);
+        forget_discussion_state(&self.database, DiscussionType::Council, thread_id).await?;
 
         // Update the message
         interaction
@@ -1428,6 +1378,172 @@ impl MessageComponentHandler {
     }
 }
 
+#[async_trait]
+impl ComponentHandler for MessageComponentHandler {
+    fn features(&self) -> &'static [&'static str] {
+        &[
+            "persona", "confirm", "help", "prompt", "debate", "council", "consent",
+        ]
+    }
+
+    async fn handle_component(
+        &self,
+        ctx: &Context,
+        interaction: &MessageComponentInteraction,
+        id: &ComponentId,
+    ) -> Result<()> {
+        match (id.feature.as_str(), id.action.as_str()) {
+            ("persona", "select") => {
+                self.handle_persona_button(ctx, interaction, &id.payload)
+                    .await
+            }
+            ("confirm", "yes") => {
+                self.handle_confirmation(ctx, interaction, &id.payload)
+                    .await
+            }
+            ("confirm", "no") => self.handle_cancellation(ctx, interaction).await,
+            ("consent", "accept") => self.handle_dm_consent(ctx, interaction, true).await,
+            ("consent", "decline") => self.handle_dm_consent(ctx, interaction, false).await,
+            ("help", "show") => self.show_help_modal(ctx, interaction).await,
+            ("prompt", "show") => self.show_persona_creation_modal(ctx, interaction).await,
+            ("debate", _) => {
+                self.handle_discussion_button(ctx, interaction, id, DiscussionType::Debate)
+                    .await
+            }
+            ("council", _) => {
+                self.handle_discussion_button(ctx, interaction, id, DiscussionType::Council)
+                    .await
+            }
+            _ => reply_invalid_button(ctx, interaction).await,
+        }
+    }
+
+    async fn handle_modal(
+        &self,
+        ctx: &Context,
+        interaction: &ModalSubmitInteraction,
+        id: &ComponentId,
+    ) -> Result<()> {
+        match (id.feature.as_str(), id.action.as_str()) {
+            ("help", "feedback") => self.handle_help_feedback_modal(ctx, interaction).await,
+            ("persona", "create") => self.handle_persona_creation_modal(ctx, interaction).await,
+            ("prompt", "submit") => self.handle_ai_prompt_modal(ctx, interaction).await,
+            _ => {
+                interaction
+                    .create_interaction_response(&ctx.http, |response| {
+                        response
+                            .kind(InteractionResponseType::ChannelMessageWithSource)
+                            .interaction_response_data(|message| {
+                                message.content("Unknown modal submission.").ephemeral(true)
+                            })
+                    })
+                    .await?;
+                Ok(())
+            }
+        }
+    }
+}
+
+impl MessageComponentHandler {
+    /// Route a council/debate control, restoring persisted state first so
+    /// buttons posted before a restart still work
+    async fn handle_discussion_button(
+        &self,
+        ctx: &Context,
+        interaction: &MessageComponentInteraction,
+        id: &ComponentId,
+        kind: DiscussionType,
+    ) -> Result<()> {
+        let Some(thread_id) = id.thread_id() else {
+            return reply_invalid_button(ctx, interaction).await;
+        };
+        if let Err(e) = restore_discussion_state(&self.database, kind, thread_id).await {
+            error!("Failed to restore {kind} state for thread {thread_id}: {e}");
+        }
+
+        match (kind, id.action.as_str()) {
+            (DiscussionType::Debate, "continue") => {
+                self.handle_debate_continue(ctx, interaction, thread_id)
+                    .await
+            }
+            (DiscussionType::Debate, "end") => {
+                self.handle_debate_end(ctx, interaction, thread_id).await
+            }
+            (DiscussionType::Council, "continue") => {
+                self.handle_council_continue(ctx, interaction, thread_id)
+                    .await
+            }
+            (DiscussionType::Council, "dismiss") => {
+                self.handle_council_dismiss(ctx, interaction, thread_id)
+                    .await
+            }
+            (DiscussionType::Debate, "vote") => {
+                let Some((thread_id, persona_id)) = parse_discussion_target(&id.payload) else {
+                    return reply_invalid_button(ctx, interaction).await;
+                };
+                self.handle_debate_vote(ctx, interaction, thread_id, persona_id)
+                    .await
+            }
+            (kind, action @ ("hear" | "speaker")) => {
+                let Some((thread_id, persona_id)) = parse_discussion_target(&id.payload) else {
+                    return reply_invalid_button(ctx, interaction).await;
+                };
+                match (kind, action) {
+                    (DiscussionType::Debate, "hear") => {
+                        self.handle_debate_hear(ctx, interaction, thread_id, persona_id)
+                            .await
+                    }
+                    (DiscussionType::Council, "speaker") => {
+                        self.handle_council_speaker(ctx, interaction, thread_id, persona_id)
+                            .await
+                    }
+                    _ => reply_invalid_button(ctx, interaction).await,
+                }
+            }
+            _ => reply_invalid_button(ctx, interaction).await,
+        }
+    }
+}
+
+async fn reply_invalid_button(
+    ctx: &Context,
+    interaction: &MessageComponentInteraction,
+) -> Result<()> {
+    interaction
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|message| {
+                    message.content("Invalid button data.").ephemeral(true)
+                })
+        })
+        .await?;
+    Ok(())
+}
+
+/// Post a council member's response, continuing in extra embeds when it is long
+async fn send_persona_response(
+    ctx: &Context,
+    channel_id: serenity::model::id::ChannelId,
+    persona: &Persona,
+    response: &str,
+) {
+    for (i, chunk) in chunk_for_embed(response).iter().enumerate() {
+        let embed = if i == 0 {
+            persona_embed(persona, chunk)
+        } else {
+            continuation_embed(persona, chunk)
+        };
+        if let Err(e) = channel_id
+            .send_message(&ctx.http, |m| m.set_embed(embed))
+            .await
+        {
+            error!("Failed to send council response from {}: {e}", persona.name);
+            return;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1453,8 +1569,13 @@ mod tests {
     }
 
     #[test]
-    fn test_create_pagination_buttons() {
-        let components = MessageComponentHandler::create_pagination_buttons(2, 5);
-        assert!(!components.0.is_empty());
+    fn test_create_dm_consent_buttons() {
+        let buttons = |declined| {
+            let components = MessageComponentHandler::create_dm_consent_buttons(declined);
+            components.0[0]["components"].as_array().unwrap().len()
+        };
+        assert_eq!(buttons(false), 2);
+        // Declined users can only change their mind
+        assert_eq!(buttons(true), 1);
     }
 }
@@ -0,0 +1,199 @@
+//! Write-behind buffer for conversation history
+//!
+//! Guild messages are stored for every event on the message hot path. Instead of
+//! a synchronous INSERT per message, `MessageWriter` queues them and a background
+//! task writes batches every `batch_size` messages or `flush_interval`, whichever
+//! comes first. Call `flush()` when a reader needs the latest rows, and
+//! `shutdown()` before exit so nothing queued is lost.
+
+use crate::database::{Database, StoredMessage};
+use log::{debug, error, info, warn};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+/// Default number of queued messages that triggers a write
+pub const DEFAULT_BATCH_SIZE: usize = 50;
+
+/// Default maximum time a message waits in the buffer
+pub const DEFAULT_FLUSH_INTERVAL_MS: u64 = 500;
+
+enum WriterCommand {
+    Store(StoredMessage),
+    Flush(oneshot::Sender<()>),
+    Shutdown(oneshot::Sender<()>),
+}
+
+/// Buffered, batching writer for `conversation_history`
+#[derive(Clone)]
+pub struct MessageWriter {
+    sender: mpsc::UnboundedSender<WriterCommand>,
+}
+
+impl MessageWriter {
+    /// Create a writer with a background batching task
+    pub fn new(database: Database, batch_size: usize, flush_interval: Duration) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(Self::background_writer(
+            database,
+            receiver,
+            batch_size.max(1),
+            flush_interval,
+        ));
+        MessageWriter { sender }
+    }
+
+    /// Queue a message for storage (non-blocking)
+    pub fn store(
+        &self,
+        guild_id: Option<&str>,
+        user_id: &str,
+        channel_id: &str,
+        role: &str,
+        content: &str,
+        persona: Option<&str>,
+    ) {
+        let message = StoredMessage {
+            guild_id: guild_id.map(String::from),
+            user_id: user_id.to_string(),
+            channel_id: channel_id.to_string(),
+            role: role.to_string(),
+            content: content.to_string(),
+            persona: persona.map(String::from),
+        };
+
+        if let Err(e) = self.sender.send(WriterCommand::Store(message)) {
+            warn!("Failed to queue message for storage: {e}");
+        }
+    }
+
+    /// Write everything queued so far and wait until it is committed
+    pub async fn flush(&self) {
+        let (done, wait) = oneshot::channel();
+        if self.sender.send(WriterCommand::Flush(done)).is_ok() {
+            let _ = wait.await;
+        }
+    }
+
+    /// Flush remaining messages and stop the background task
+    pub async fn shutdown(&self) {
+        let (done, wait) = oneshot::channel();
+        if self.sender.send(WriterCommand::Shutdown(done)).is_ok() {
+            let _ = wait.await;
+        }
+    }
+
+    async fn background_writer(
+        database: Database,
+        mut receiver: mpsc::UnboundedReceiver<WriterCommand>,
+        batch_size: usize,
+        flush_interval: Duration,
+    ) {
+        let mut buffer: Vec<StoredMessage> = Vec::with_capacity(batch_size);
+        let mut ticker = tokio::time::interval(flush_interval);
+
+        loop {
+            tokio::select! {
+                command = receiver.recv() => match command {
+                    Some(WriterCommand::Store(message)) => {
+                        buffer.push(message);
+                        if buffer.len() >= batch_size {
+                            Self::write_batch(&database, &mut buffer).await;
+                        }
+                    }
+                    Some(WriterCommand::Flush(done)) => {
+                        Self::write_batch(&database, &mut buffer).await;
+                        let _ = done.send(());
+                    }
+                    Some(WriterCommand::Shutdown(done)) => {
+                        Self::write_batch(&database, &mut buffer).await;
+                        info!("Message writer flushed and stopped");
+                        let _ = done.send(());
+                        return;
+                    }
+                    None => {
+                        Self::write_batch(&database, &mut buffer).await;
+                        return;
+                    }
+                },
+                _ = ticker.tick() => {
+                    Self::write_batch(&database, &mut buffer).await;
+                }
+            }
+        }
+    }
+
+    async fn write_batch(database: &Database, buffer: &mut Vec<StoredMessage>) {
+        if buffer.is_empty() {
+            return;
+        }
+        let batch = std::mem::take(buffer);
+        match database.store_messages_batch(&batch).await {
+            Ok(()) => debug!("Stored batch of {} messages", batch.len()),
+            Err(e) => error!("Failed to store batch of {} messages: {e}", batch.len()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_flush_writes_partial_batch() {
+        let database = Database::new(":memory:").await.unwrap();
+        let writer = MessageWriter::new(database.clone(), 10, Duration::from_secs(60));
+
+        writer.store(Some("g1"), "u1", "c1", "user", "one", None);
+        writer.store(Some("g1"), "u1", "c1", "user", "two", None);
+        writer.flush().await;
+
+        let history = database
+            .get_conversation_history("u1", "c1", 10)
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_full_batch_writes_without_flush() {
+        let database = Database::new(":memory:").await.unwrap();
+        let writer = MessageWriter::new(database.clone(), 3, Duration::from_secs(60));
+
+        for i in 0..3 {
+            writer.store(Some("g1"), "u1", "c1", "user", &format!("msg {i}"), None);
+        }
+
+        // The batch threshold triggers the write; poll briefly for the commit
+        let mut stored = 0;
+        for _ in 0..50 {
+            stored = database
+                .get_conversation_history("u1", "c1", 10)
+                .await
+                .unwrap()
+                .len();
+            if stored == 3 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(stored, 3);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_flushes_remaining() {
+        let database = Database::new(":memory:").await.unwrap();
+        let writer = MessageWriter::new(database.clone(), 100, Duration::from_secs(60));
+
+        writer.store(Some("g1"), "u1", "c1", "user", "last words", None);
+        writer.shutdown().await;
+
+        let history = database
+            .get_conversation_history("u1", "c1", 10)
+            .await
+            .unwrap();
+        assert_eq!(
+            history,
+            vec![("user".to_string(), "last words".to_string())]
+        );
+    }
+}
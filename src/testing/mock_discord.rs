@@ -0,0 +1,402 @@
+//! # Mock Discord API
+//!
+//! Loopback HTTP server standing in for the Discord REST API. Serenity's `Http`
+//! client is pointed at it through its proxy setting, so handlers run unmodified
+//! against a real `Context` while every request is recorded for assertions.
+//!
+//! - **Version**: 1.0.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use log::debug;
+use serde_json::{json, Value};
+use serenity::cache::Cache;
+use serenity::client::bridge::gateway::ShardMessenger;
+use serenity::futures::channel::mpsc::{unbounded, UnboundedReceiver};
+use serenity::gateway::InterMessage;
+use serenity::http::{Http, HttpBuilder};
+use serenity::prelude::{Context, RwLock, TypeMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// Application ID reported for the mocked bot
+pub const MOCK_APPLICATION_ID: u64 = 900_000_000_000_000_001;
+
+/// User ID of the mocked bot account
+pub const MOCK_BOT_USER_ID: u64 = 900_000_000_000_000_002;
+
+/// Guild that scenario interactions originate from
+pub const MOCK_GUILD_ID: u64 = 900_000_000_000_000_010;
+
+/// Text channel that scenario interactions originate from
+pub const MOCK_CHANNEL_ID: u64 = 900_000_000_000_000_020;
+
+/// Human user that invokes scenario interactions
+pub const MOCK_USER_ID: u64 = 900_000_000_000_000_030;
+
+/// First snowflake handed out for created messages and threads
+const FIRST_GENERATED_ID: u64 = 910_000_000_000_000_000;
+
+/// A request received by the mock server
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// Path without the `/api/v10` prefix or query string
+    pub path: String,
+    /// JSON body (or Null for empty and non-JSON bodies)
+    pub body: Value,
+}
+
+impl RecordedRequest {
+    /// Message content, whether sent directly or inside an interaction callback
+    pub fn content(&self) -> Option<&str> {
+        self.body["content"]
+            .as_str()
+            .or_else(|| self.body["data"]["content"].as_str())
+    }
+
+    /// Embeds, whether sent directly or inside an interaction callback
+    pub fn embeds(&self) -> Vec<&Value> {
+        let embeds = if self.body["embeds"].is_array() {
+            &self.body["embeds"]
+        } else {
+            &self.body["data"]["embeds"]
+        };
+        embeds
+            .as_array()
+            .map(|e| e.iter().collect())
+            .unwrap_or_default()
+    }
+
+    /// Content plus embed titles and descriptions, for loose text assertions
+    pub fn text(&self) -> String {
+        let mut text = self.content().unwrap_or_default().to_string();
+        for embed in self.embeds() {
+            for key in ["title", "description"] {
+                if let Some(value) = embed[key].as_str() {
+                    text.push('\n');
+                    text.push_str(value);
+                }
+            }
+        }
+        text
+    }
+
+    /// Whether this request posts visible output (messages, followups, callbacks, edits)
+    pub fn is_output(&self) -> bool {
+        match self.method.as_str() {
+            "POST" => {
+                self.path.ends_with("/messages")
+                    || self.path.ends_with("/callback")
+                    || self.path.starts_with("/webhooks/")
+            }
+            "PATCH" => self.path.contains("/messages/"),
+            _ => false,
+        }
+    }
+}
+
+struct MockState {
+    requests: Mutex<Vec<RecordedRequest>>,
+    next_id: AtomicU64,
+}
+
+impl MockState {
+    fn next_id(&self) -> u64 {
+        self.next_id.fetch_add(1, Ordering::SeqCst)
+    }
+}
+
+/// Running mock Discord API
+pub struct MockDiscord {
+    state: Arc<MockState>,
+    http: Arc<Http>,
+    server: JoinHandle<()>,
+    /// Kept alive so shard messages (presence updates) don't error
+    _shard_rx: Mutex<UnboundedReceiver<InterMessage>>,
+    shard: ShardMessenger,
+}
+
+impl MockDiscord {
+    /// Bind to an ephemeral loopback port and start serving
+    pub async fn start() -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let state = Arc::new(MockState {
+            requests: Mutex::new(Vec::new()),
+            next_id: AtomicU64::new(FIRST_GENERATED_ID),
+        });
+
+        let server_state = state.clone();
+        let server = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let state = server_state.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = serve_connection(stream, state).await {
+                        debug!("Mock Discord connection closed: {e}");
+                    }
+                });
+            }
+        });
+
+        let http = HttpBuilder::new("mock-token")
+            .proxy(format!("http://{addr}"))?
+            .ratelimiter_disabled(true)
+            .application_id(MOCK_APPLICATION_ID)
+            .build();
+
+        let (tx, rx) = unbounded();
+
+        Ok(Self {
+            state,
+            http: Arc::new(http),
+            server,
+            _shard_rx: Mutex::new(rx),
+            shard: ShardMessenger::new(tx),
+        })
+    }
+
+    /// HTTP client routed to the mock
+    pub fn http(&self) -> Arc<Http> {
+        self.http.clone()
+    }
+
+    /// Serenity context whose HTTP client is routed to the mock (cache starts empty)
+    pub fn context(&self) -> Context {
+        Context {
+            data: Arc::new(RwLock::new(TypeMap::new())),
+            shard: self.shard.clone(),
+            shard_id: 0,
+            http: self.http.clone(),
+            cache: Arc::new(Cache::new()),
+        }
+    }
+
+    /// Every request received so far, in arrival order
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.requests.lock().unwrap().clone()
+    }
+
+    /// Requests that produced user-visible output
+    pub fn outputs(&self) -> Vec<RecordedRequest> {
+        self.requests()
+            .into_iter()
+            .filter(|r| r.is_output())
+            .collect()
+    }
+
+    /// Wait until a recorded request matches, for work that runs in spawned tasks
+    pub async fn wait_for<F>(&self, timeout: Duration, predicate: F) -> Option<RecordedRequest>
+    where
+        F: Fn(&RecordedRequest) -> bool,
+    {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            if let Some(found) = self.requests().into_iter().find(|r| predicate(r)) {
+                return Some(found);
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return None;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+
+    /// Forget recorded requests (e.g. between replayed interactions)
+    pub fn clear(&self) {
+        self.state.requests.lock().unwrap().clear();
+    }
+}
+
+impl Drop for MockDiscord {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+/// Serve keep-alive HTTP/1.1 requests on one connection
+async fn serve_connection(stream: TcpStream, state: Arc<MockState>) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+
+    loop {
+        let mut request_line = String::new();
+        if reader.read_line(&mut request_line).await? == 0 {
+            return Ok(());
+        }
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default().to_string();
+        let target = parts.next().unwrap_or_default().to_string();
+
+        let mut content_length = 0usize;
+        loop {
+            let mut header = String::new();
+            if reader.read_line(&mut header).await? == 0 {
+                return Ok(());
+            }
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap_or(0);
+                }
+            }
+        }
+
+        let mut body = vec![0u8; content_length];
+        reader.read_exact(&mut body).await?;
+        let body: Value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+
+        let path = target
+            .split('?')
+            .next()
+            .unwrap_or_default()
+            .trim_start_matches("/api/v10")
+            .to_string();
+
+        let (status, response) = route(&state, &method, &path, &body);
+        state
+            .requests
+            .lock()
+            .unwrap()
+            .push(RecordedRequest { method, path, body });
+
+        let payload = response.map(|v| v.to_string()).unwrap_or_default();
+        let reason = if status == 204 { "No Content" } else { "OK" };
+        let head = format!(
+            "HTTP/1.1 {status} {reason}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: keep-alive\r\n\r\n",
+            payload.len()
+        );
+        writer.write_all(head.as_bytes()).await?;
+        writer.write_all(payload.as_bytes()).await?;
+        writer.flush().await?;
+    }
+}
+
+/// Produce a plausible response for a Discord REST route
+fn route(state: &MockState, method: &str, path: &str, body: &Value) -> (u16, Option<Value>) {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    match (method, segments.as_slice()) {
+        ("GET", ["users", "@me"]) => (200, Some(current_user_json())),
+        ("GET", ["channels", channel_id]) => (200, Some(text_channel_json(parse_id(channel_id)))),
+        ("GET", ["channels", _, "messages"]) => (200, Some(json!([]))),
+        ("POST", ["channels", channel_id, "messages"]) => (
+            200,
+            Some(message_json(state.next_id(), parse_id(channel_id), body)),
+        ),
+        ("PATCH", ["channels", channel_id, "messages", message_id]) => (
+            200,
+            Some(message_json(
+                parse_id(message_id),
+                parse_id(channel_id),
+                body,
+            )),
+        ),
+        ("POST", ["channels", channel_id, "threads"])
+        | ("POST", ["channels", channel_id, "messages", _, "threads"]) => (
+            200,
+            Some(thread_json(
+                state.next_id(),
+                parse_id(channel_id),
+                body["name"].as_str().unwrap_or("thread"),
+            )),
+        ),
+        ("POST", ["webhooks", _, _]) => (
+            200,
+            Some(message_json(state.next_id(), MOCK_CHANNEL_ID, body)),
+        ),
+        ("GET" | "PATCH", ["webhooks", _, _, "messages", _]) => (
+            200,
+            Some(message_json(state.next_id(), MOCK_CHANNEL_ID, body)),
+        ),
+        ("GET", _) => (
+            404,
+            Some(json!({ "message": "Unknown route (mock)", "code": 0 })),
+        ),
+        _ => (204, None),
+    }
+}
+
+fn parse_id(segment: &str) -> u64 {
+    segment.parse().unwrap_or(MOCK_CHANNEL_ID)
+}
+
+fn bot_user_json() -> Value {
+    json!({
+        "id": MOCK_BOT_USER_ID.to_string(),
+        "username": "mock-bot",
+        "discriminator": "0001",
+        "avatar": null,
+        "bot": true,
+    })
+}
+
+fn current_user_json() -> Value {
+    let mut user = bot_user_json();
+    user["mfa_enabled"] = json!(false);
+    user["verified"] = json!(true);
+    user
+}
+
+fn message_json(id: u64, channel_id: u64, body: &Value) -> Value {
+    let content = body["content"]
+        .as_str()
+        .or_else(|| body["data"]["content"].as_str())
+        .unwrap_or_default();
+    json!({
+        "id": id.to_string(),
+        "channel_id": channel_id.to_string(),
+        "author": bot_user_json(),
+        "content": content,
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "edited_timestamp": null,
+        "tts": false,
+        "mention_everyone": false,
+        "mentions": [],
+        "mention_roles": [],
+        "attachments": [],
+        "embeds": [],
+        "pinned": false,
+        "type": 0,
+    })
+}
+
+fn text_channel_json(id: u64) -> Value {
+    json!({
+        "id": id.to_string(),
+        "type": 0,
+        "guild_id": MOCK_GUILD_ID.to_string(),
+        "name": "general",
+        "position": 0,
+        "permission_overwrites": [],
+        "nsfw": false,
+    })
+}
+
+fn thread_json(id: u64, parent_id: u64, name: &str) -> Value {
+    json!({
+        "id": id.to_string(),
+        "type": 11,
+        "guild_id": MOCK_GUILD_ID.to_string(),
+        "parent_id": parent_id.to_string(),
+        "owner_id": MOCK_BOT_USER_ID.to_string(),
+        "name": name,
+        "thread_metadata": {
+            "archived": false,
+            "auto_archive_duration": 1440,
+            "archive_timestamp": chrono::Utc::now().to_rfc3339(),
+            "locked": false,
+        },
+    })
+}
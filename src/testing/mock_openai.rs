@@ -0,0 +1,208 @@
+//! # Mock OpenAI Client
+//!
+//! Scripted `ChatClient` that records every request and never touches the network.
+//!
+//! - **Version**: 1.2.0
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.2.0: Records temperature and top_p of completions with parameter overrides
+//! - 1.1.0: Records the token cap of limited completions
+//! - 1.0.0: Initial release
+
+use anyhow::Result;
+use async_trait::async_trait;
+use openai::chat::{
+    ChatCompletion, ChatCompletionChoice, ChatCompletionMessage, ChatCompletionMessageRole,
+};
+use openai::Usage;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use crate::core::{ChatClient, ModelParams};
+
+/// Reply used when no scripted reply is queued
+pub const DEFAULT_MOCK_REPLY: &str = "This is a mock reply.";
+
+/// A chat completion request captured by the mock
+#[derive(Debug, Clone)]
+pub struct RecordedChatRequest {
+    pub model: String,
+    /// (role, content) pairs in request order
+    pub messages: Vec<(String, String)>,
+    /// Token cap passed to `create_chat_completion_limited`
+    pub max_tokens: Option<u64>,
+    /// Sampling temperature passed to `create_chat_completion_with_params`
+    pub temperature: Option<f32>,
+    /// Nucleus sampling passed to `create_chat_completion_with_params`
+    pub top_p: Option<f32>,
+}
+
+impl RecordedChatRequest {
+    /// Content of the system prompt, if any
+    pub fn system_prompt(&self) -> Option<&str> {
+        self.messages
+            .iter()
+            .find(|(role, _)| role == "system")
+            .map(|(_, content)| content.as_str())
+    }
+
+    /// Content of the final user message, if any
+    pub fn last_user_message(&self) -> Option<&str> {
+        self.messages
+            .iter()
+            .rev()
+            .find(|(role, _)| role == "user")
+            .map(|(_, content)| content.as_str())
+    }
+}
+
+/// Chat client returning queued replies (or a default) in FIFO order
+pub struct MockChatClient {
+    replies: Mutex<VecDeque<std::result::Result<String, String>>>,
+    default_reply: String,
+    requests: Mutex<Vec<RecordedChatRequest>>,
+}
+
+impl Default for MockChatClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockChatClient {
+    pub fn new() -> Self {
+        Self::with_default_reply(DEFAULT_MOCK_REPLY)
+    }
+
+    /// Create a mock that answers every unscripted request with `reply`
+    pub fn with_default_reply(reply: impl Into<String>) -> Self {
+        Self {
+            replies: Mutex::new(VecDeque::new()),
+            default_reply: reply.into(),
+            requests: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Queue a successful reply
+    pub fn push_reply(&self, reply: impl Into<String>) {
+        self.replies.lock().unwrap().push_back(Ok(reply.into()));
+    }
+
+    /// Queue an API error
+    pub fn push_error(&self, message: impl Into<String>) {
+        self.replies.lock().unwrap().push_back(Err(message.into()));
+    }
+
+    /// All requests received so far
+    pub fn requests(&self) -> Vec<RecordedChatRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    pub fn request_count(&self) -> usize {
+        self.requests.lock().unwrap().len()
+    }
+}
+
+#[async_trait]
+impl ChatClient for MockChatClient {
+    async fn create_chat_completion(
+        &self,
+        model: &str,
+        messages: Vec<ChatCompletionMessage>,
+    ) -> Result<ChatCompletion> {
+        self.create_chat_completion_limited(model, messages, None)
+            .await
+    }
+
+    async fn create_chat_completion_limited(
+        &self,
+        model: &str,
+        messages: Vec<ChatCompletionMessage>,
+        max_tokens: Option<u64>,
+    ) -> Result<ChatCompletion> {
+        let params = ModelParams {
+            max_tokens,
+            ..ModelParams::default()
+        };
+        self.create_chat_completion_with_params(model, messages, params)
+            .await
+    }
+
+    async fn create_chat_completion_with_params(
+        &self,
+        model: &str,
+        messages: Vec<ChatCompletionMessage>,
+        params: ModelParams,
+    ) -> Result<ChatCompletion> {
+        let recorded = RecordedChatRequest {
+            model: model.to_string(),
+            messages: messages
+                .iter()
+                .map(|m| {
+                    (
+                        role_name(&m.role).to_string(),
+                        m.content.clone().unwrap_or_default(),
+                    )
+                })
+                .collect(),
+            max_tokens: params.max_tokens,
+            temperature: params.temperature,
+            top_p: params.top_p,
+        };
+        let prompt_chars: usize = recorded.messages.iter().map(|(_, c)| c.len()).sum();
+        self.requests.lock().unwrap().push(recorded);
+
+        let reply = self
+            .replies
+            .lock()
+            .unwrap()
+            .pop_front()
+            .unwrap_or_else(|| Ok(self.default_reply.clone()));
+
+        match reply {
+            Ok(content) => Ok(mock_completion(model, &content, prompt_chars)),
+            Err(message) => Err(anyhow::anyhow!(message)),
+        }
+    }
+}
+
+/// Build a completion with a rough chars/4 token estimate so usage tracking has data
+pub fn mock_completion(model: &str, content: &str, prompt_chars: usize) -> ChatCompletion {
+    let prompt_tokens = (prompt_chars / 4) as u32;
+    let completion_tokens = (content.len() / 4) as u32;
+    ChatCompletion {
+        id: format!("mock-{}", uuid::Uuid::new_v4()),
+        object: "chat.completion".to_string(),
+        created: chrono::Utc::now().timestamp() as u64,
+        model: model.to_string(),
+        choices: vec![ChatCompletionChoice {
+            index: 0,
+            finish_reason: "stop".to_string(),
+            message: ChatCompletionMessage {
+                role: ChatCompletionMessageRole::Assistant,
+                content: Some(content.to_string()),
+                name: None,
+                function_call: None,
+                tool_call_id: None,
+                tool_calls: None,
+            },
+        }],
+        usage: Some(Usage {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens + completion_tokens,
+        }),
+    }
+}
+
+fn role_name(role: &ChatCompletionMessageRole) -> &'static str {
+    match role {
+        ChatCompletionMessageRole::System => "system",
+        ChatCompletionMessageRole::User => "user",
+        ChatCompletionMessageRole::Assistant => "assistant",
+        ChatCompletionMessageRole::Function => "function",
+        ChatCompletionMessageRole::Tool => "tool",
+        ChatCompletionMessageRole::Developer => "developer",
+    }
+}
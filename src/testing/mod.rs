@@ -0,0 +1,161 @@
+//! # Dry-Run Harness
+//!
+//! Runs the real command dispatcher against a mocked Discord API and a scripted
+//! OpenAI client, so slash commands, plugins, debates and conflict mediation can be
+//! exercised end-to-end without network access.
+//!
+//! - **Version**: 1.1.0
+//! - **Since**: 4.7.0
+//! - **Toggleable**: false
+//!
+//! ## Changelog
+//! - 1.1.0: Added interaction recorder and `--replay` support
+//! - 1.0.0: Initial release with MockDiscord, MockChatClient and scenario tests
+
+pub mod mock_discord;
+pub mod mock_openai;
+pub mod replay;
+
+#[cfg(test)]
+mod scenarios;
+
+pub use mock_discord::{
+    MockDiscord, RecordedRequest, MOCK_APPLICATION_ID, MOCK_BOT_USER_ID, MOCK_CHANNEL_ID,
+    MOCK_GUILD_ID, MOCK_USER_ID,
+};
+pub use mock_openai::{mock_completion, MockChatClient, RecordedChatRequest, DEFAULT_MOCK_REPLY};
+pub use replay::{replay_file, InteractionRecorder, RecordedInteraction, ReplayReport};
+
+use anyhow::Result;
+use serde_json::{json, Value};
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::prelude::Context;
+use std::sync::Arc;
+
+use crate::command_handler::CommandHandler;
+use crate::database::Database;
+use crate::features::analytics::{InteractionTracker, UsageTracker};
+use crate::features::plugins::{PluginConfig, PluginManager};
+
+/// Model name reported to the mock chat client
+pub const MOCK_MODEL: &str = "gpt-mock";
+
+/// A fully wired bot running against mocks with an in-memory database
+pub struct DryRun {
+    pub discord: MockDiscord,
+    pub openai: Arc<MockChatClient>,
+    pub database: Database,
+    pub handler: CommandHandler,
+}
+
+impl DryRun {
+    /// Start a harness with no plugins configured
+    pub async fn start() -> Result<Self> {
+        Self::start_with_plugins(None, Vec::new()).await
+    }
+
+    /// Start a harness with the given plugin configuration and command allowlist
+    pub async fn start_with_plugins(
+        plugins: Option<PluginConfig>,
+        allowed_commands: Vec<String>,
+    ) -> Result<Self> {
+        let discord = MockDiscord::start().await?;
+        let openai = Arc::new(MockChatClient::new());
+        let database = Database::new(":memory:").await?;
+
+        let plugin_manager = plugins.map(|config| {
+            let mut manager = PluginManager::new(
+                config,
+                database.clone(),
+                MOCK_MODEL.to_string(),
+                allowed_commands,
+            );
+            manager.output_handler = manager
+                .output_handler
+                .clone()
+                .with_chat_client(openai.clone());
+            Arc::new(manager)
+        });
+
+        let handler = CommandHandler::new(
+            database.clone(),
+            "mock-openai-key".to_string(),
+            MOCK_MODEL.to_string(),
+            true,
+            "medium",
+            5,
+            UsageTracker::new(database.clone()),
+            InteractionTracker::new(database.clone()),
+            plugin_manager,
+        )
+        .with_chat_client(openai.clone());
+
+        Ok(Self {
+            discord,
+            openai,
+            database,
+            handler,
+        })
+    }
+
+    /// Serenity context routed to the mock Discord API
+    pub fn context(&self) -> Context {
+        self.discord.context()
+    }
+
+    /// Dispatch a slash command through the registry exactly as the gateway handler does
+    pub async fn run_slash(&self, command: &ApplicationCommandInteraction) -> Result<()> {
+        self.handler
+            .handle_slash_command(&self.context(), command)
+            .await
+    }
+}
+
+/// Build a guild slash command interaction from the mock user in the mock channel
+pub fn slash_command(name: &str, options: Vec<Value>) -> Result<ApplicationCommandInteraction> {
+    let interaction = json!({
+        "id": uuid_snowflake().to_string(),
+        "application_id": MOCK_APPLICATION_ID.to_string(),
+        "type": 2,
+        "data": {
+            "id": uuid_snowflake().to_string(),
+            "name": name,
+            "type": 1,
+            "options": options,
+        },
+        "guild_id": MOCK_GUILD_ID.to_string(),
+        "channel_id": MOCK_CHANNEL_ID.to_string(),
+        "user": {
+            "id": MOCK_USER_ID.to_string(),
+            "username": "dry-run-user",
+            "discriminator": "0001",
+            "avatar": null,
+        },
+        "token": format!("mock-interaction-token-{}", uuid::Uuid::new_v4()),
+        "version": 1,
+        "locale": "en-US",
+    });
+    Ok(serde_json::from_value(interaction)?)
+}
+
+/// Build a command option, inferring the Discord option type from the JSON value
+pub fn option(name: &str, value: impl Into<Value>) -> Value {
+    let value = value.into();
+    let kind = match &value {
+        Value::Bool(_) => 5,
+        Value::Number(n) if n.is_i64() || n.is_u64() => 4,
+        Value::Number(_) => 10,
+        _ => 3,
+    };
+    json!({ "name": name, "type": kind, "value": value })
+}
+
+/// Build a subcommand option wrapping nested options
+pub fn subcommand(name: &str, options: Vec<Value>) -> Value {
+    json!({ "name": name, "type": 1, "options": options })
+}
+
+/// Random snowflake-sized ID for interactions
+fn uuid_snowflake() -> u64 {
+    (uuid::Uuid::new_v4().as_u128() as u64) >> 1
+}
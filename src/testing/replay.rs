@@ -0,0 +1,414 @@
+//! # Interaction Recorder and Replay
+//!
+//! Captures anonymized slash command payloads to a JSON Lines file in production and
+//! feeds them back through the dispatcher against the dry-run harness, so command
+//! handling bugs can be reproduced locally without Discord or OpenAI access.
+//!
+//! - **Version**: 1.0.1
+//! - **Since**: 4.7.0
+//!
+//! ## Changelog
+//! - 1.0.1: Values of secret options such as the `/ticket config` token are redacted
+//! - 1.0.0: Initial release
+
+use anyhow::{Context as _, Result};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::{DryRun, RecordedRequest};
+use crate::features::issues::TOKEN_OPTION;
+
+/// Kind tag for recorded slash commands
+pub const KIND_APPLICATION_COMMAND: &str = "application_command";
+
+/// How long replay waits for work spawned by a handler before collecting output
+const REPLAY_SETTLE_TIME: Duration = Duration::from_millis(500);
+
+/// Replacement for interaction tokens and other secrets
+const REDACTED: &str = "redacted";
+
+/// Keys whose values are snowflake IDs that get pseudonymized
+const ID_KEYS: &[&str] = &[
+    "id",
+    "guild_id",
+    "channel_id",
+    "application_id",
+    "target_id",
+];
+
+/// Options whose value is a secret, redacted like interaction tokens
+const SECRET_OPTIONS: &[&str] = &[TOKEN_OPTION];
+
+/// Option types whose value is a snowflake (user, channel, role, mentionable)
+const ID_OPTION_TYPES: &[u64] = &[6, 7, 8, 9];
+
+/// Keys that are dropped entirely (personal data not needed to reproduce a bug)
+const DROPPED_KEYS: &[&str] = &[
+    "avatar",
+    "banner",
+    "nick",
+    "email",
+    "global_name",
+    "avatar_decoration",
+    "accent_color",
+    "public_flags",
+];
+
+/// One line of a recording file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedInteraction {
+    pub recorded_at: String,
+    pub kind: String,
+    pub payload: Value,
+}
+
+/// Appends anonymized interaction payloads to a JSON Lines file
+pub struct InteractionRecorder {
+    path: PathBuf,
+    file: Mutex<std::fs::File>,
+    salt: u64,
+}
+
+impl InteractionRecorder {
+    /// Open (or create) the recording file in append mode
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open recording file {}", path.display()))?;
+
+        // Per-process salt: IDs stay consistent within a session but can't be reversed
+        let salt = uuid::Uuid::new_v4().as_u128() as u64;
+
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+            salt,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Record a slash command (failures are logged, never propagated to the handler)
+    pub fn record_command(&self, command: &ApplicationCommandInteraction) {
+        if let Err(e) = self.try_record_command(command) {
+            warn!("Failed to record interaction: {e}");
+        }
+    }
+
+    fn try_record_command(&self, command: &ApplicationCommandInteraction) -> Result<()> {
+        let mut payload = serde_json::to_value(command)?;
+        anonymize(&mut payload, self.salt);
+
+        let entry = RecordedInteraction {
+            recorded_at: chrono::Utc::now().to_rfc3339(),
+            kind: KIND_APPLICATION_COMMAND.to_string(),
+            payload,
+        };
+        let line = serde_json::to_string(&entry)?;
+
+        let mut file = self
+            .file
+            .lock()
+            .map_err(|_| anyhow::anyhow!("recorder lock poisoned"))?;
+        writeln!(file, "{line}")?;
+        Ok(())
+    }
+}
+
+/// Pseudonymize IDs, redact tokens and drop personal fields in place
+pub fn anonymize(value: &mut Value, salt: u64) {
+    match value {
+        Value::Object(map) => {
+            // Serenity serializes absent fields as null but rejects some of them on the way
+            // back in (e.g. `app_permissions`), so drop them like Discord's own payloads do
+            map.retain(|key, child| !child.is_null() && !DROPPED_KEYS.contains(&key.as_str()));
+
+            // Resolved users/channels/roles are keyed by their snowflake
+            let snowflake_keys: Vec<String> = map
+                .keys()
+                .filter(|key| is_snowflake(key))
+                .cloned()
+                .collect();
+            for key in snowflake_keys {
+                if let (Some(child), Ok(id)) = (map.remove(&key), key.parse::<u64>()) {
+                    map.insert(pseudonymous_id(id, salt).to_string(), child);
+                }
+            }
+
+            // User, channel, role and mentionable options carry IDs in their value
+            let is_id_option = map
+                .get("type")
+                .and_then(Value::as_u64)
+                .is_some_and(|kind| ID_OPTION_TYPES.contains(&kind))
+                && map.contains_key("name");
+            let is_secret_option = map
+                .get("name")
+                .and_then(Value::as_str)
+                .is_some_and(|name| SECRET_OPTIONS.contains(&name))
+                && map.contains_key("value");
+            if is_secret_option {
+                // Serenity repeats the option's value as `resolved`
+                map.remove("resolved");
+            }
+
+            for (key, child) in map.iter_mut() {
+                match key.as_str() {
+                    "token" => *child = Value::String(REDACTED.to_string()),
+                    "value" if is_secret_option => *child = Value::String(REDACTED.to_string()),
+                    "value" if is_id_option => pseudonymize_id(child, salt),
+                    _ if ID_KEYS.contains(&key.as_str()) => pseudonymize_id(child, salt),
+                    _ => anonymize(child, salt),
+                }
+            }
+            // User objects: replace the display name with one derived from the pseudonymous ID
+            if let (Some(id), Some(Value::String(_))) = (
+                map.get("id").and_then(Value::as_str).map(str::to_string),
+                map.get("username"),
+            ) {
+                map.insert("username".to_string(), Value::String(format!("user-{id}")));
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                anonymize(item, salt);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_snowflake(value: &str) -> bool {
+    value.len() >= 15 && value.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn pseudonymize_id(value: &mut Value, salt: u64) {
+    if let Some(id) = value.as_str().and_then(|s| s.parse::<u64>().ok()) {
+        *value = Value::String(pseudonymous_id(id, salt).to_string());
+    }
+}
+
+/// Stable, snowflake-sized replacement for an ID under a given salt
+pub fn pseudonymous_id(id: u64, salt: u64) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    salt.hash(&mut hasher);
+    id.hash(&mut hasher);
+    // Keep it positive as i64 and non-zero so serenity parses it like a real snowflake
+    (hasher.finish() >> 1).max(1)
+}
+
+/// Outcome of replaying one recorded interaction
+#[derive(Debug, Clone)]
+pub struct ReplayOutcome {
+    pub line: usize,
+    pub command: String,
+    pub error: Option<String>,
+    pub discord_outputs: Vec<RecordedRequest>,
+    pub openai_requests: usize,
+}
+
+/// Summary of a replay run
+#[derive(Debug, Clone, Default)]
+pub struct ReplayReport {
+    pub outcomes: Vec<ReplayOutcome>,
+    pub skipped: usize,
+}
+
+impl ReplayReport {
+    pub fn failures(&self) -> usize {
+        self.outcomes.iter().filter(|o| o.error.is_some()).count()
+    }
+
+    /// Human-readable report for the `--replay` CLI
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for outcome in &self.outcomes {
+            let status = match &outcome.error {
+                Some(e) => format!("ERROR: {e}"),
+                None => "ok".to_string(),
+            };
+            out.push_str(&format!(
+                "#{} /{} -> {} ({} OpenAI request(s))\n",
+                outcome.line, outcome.command, status, outcome.openai_requests
+            ));
+            for request in &outcome.discord_outputs {
+                let text = request.text().replace('\n', " ");
+                let text: String = text.chars().take(160).collect();
+                out.push_str(&format!(
+                    "    {} {} {}\n",
+                    request.method, request.path, text
+                ));
+            }
+        }
+        out.push_str(&format!(
+            "Replayed {} interaction(s): {} failed, {} skipped\n",
+            self.outcomes.len(),
+            self.failures(),
+            self.skipped
+        ));
+        out
+    }
+}
+
+/// Read a recording file and replay every interaction against a fresh dry-run harness
+pub async fn replay_file(path: impl AsRef<Path>) -> Result<ReplayReport> {
+    let path = path.as_ref();
+    let file = std::fs::File::open(path)
+        .with_context(|| format!("Failed to open recording {}", path.display()))?;
+
+    let harness = DryRun::start().await?;
+    let mut report = ReplayReport::default();
+
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line_no = index + 1;
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let entry: RecordedInteraction = match serde_json::from_str(&line) {
+            Ok(entry) => entry,
+            Err(e) => {
+                warn!("Skipping line {line_no}: not a recorded interaction ({e})");
+                report.skipped += 1;
+                continue;
+            }
+        };
+        if entry.kind != KIND_APPLICATION_COMMAND {
+            warn!("Skipping line {line_no}: unsupported kind '{}'", entry.kind);
+            report.skipped += 1;
+            continue;
+        }
+        let command: ApplicationCommandInteraction = match serde_json::from_value(entry.payload) {
+            Ok(command) => command,
+            Err(e) => {
+                warn!("Skipping line {line_no}: payload did not parse ({e})");
+                report.skipped += 1;
+                continue;
+            }
+        };
+
+        info!("Replaying line {line_no}: /{}", command.data.name);
+        harness.discord.clear();
+        let openai_before = harness.openai.request_count();
+
+        let result = harness.run_slash(&command).await;
+        tokio::time::sleep(REPLAY_SETTLE_TIME).await;
+
+        report.outcomes.push(ReplayOutcome {
+            line: line_no,
+            command: command.data.name.clone(),
+            error: result.err().map(|e| e.to_string()),
+            discord_outputs: harness.discord.outputs(),
+            openai_requests: harness.openai.request_count() - openai_before,
+        });
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{option, slash_command, subcommand, MOCK_USER_ID};
+
+    fn temp_recording() -> PathBuf {
+        std::env::temp_dir().join(format!("persona-replay-{}.jsonl", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn test_anonymize_redacts_token_and_pseudonymizes_ids() {
+        let command = slash_command("ask", vec![option("prompt", "hi")]).unwrap();
+        let mut payload = serde_json::to_value(&command).unwrap();
+        anonymize(&mut payload, 42);
+
+        assert_eq!(payload["token"], REDACTED);
+        let user_id = payload["user"]["id"].as_str().unwrap();
+        assert_ne!(user_id, MOCK_USER_ID.to_string());
+        assert_eq!(user_id, pseudonymous_id(MOCK_USER_ID, 42).to_string());
+        assert_eq!(payload["user"]["username"], format!("user-{user_id}"));
+        assert!(payload["user"].get("avatar").is_none());
+        // Option values are kept so the command can be reproduced
+        assert_eq!(payload["data"]["options"][0]["value"], "hi");
+        assert_eq!(payload["data"]["name"], "ask");
+    }
+
+    #[test]
+    fn test_anonymize_redacts_secret_options() {
+        let command = slash_command(
+            "ticket",
+            vec![subcommand(
+                "config",
+                vec![
+                    option("project", "OPS"),
+                    option(TOKEN_OPTION, "jira-secret"),
+                ],
+            )],
+        )
+        .unwrap();
+        let mut payload = serde_json::to_value(&command).unwrap();
+        anonymize(&mut payload, 42);
+
+        let options = &payload["data"]["options"][0]["options"];
+        assert_eq!(options[0]["value"], "OPS");
+        assert_eq!(options[1]["value"], REDACTED);
+        assert!(!payload.to_string().contains("jira-secret"));
+    }
+
+    #[test]
+    fn test_pseudonymous_id_is_stable_per_salt() {
+        assert_eq!(pseudonymous_id(123, 7), pseudonymous_id(123, 7));
+        assert_ne!(pseudonymous_id(123, 7), pseudonymous_id(123, 8));
+        assert_ne!(pseudonymous_id(123, 7), pseudonymous_id(124, 7));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_record_then_replay_roundtrip() {
+        let path = temp_recording();
+        let recorder = InteractionRecorder::open(&path).unwrap();
+        recorder.record_command(&slash_command("does_not_exist", vec![]).unwrap());
+        recorder.record_command(
+            &slash_command(
+                "ask",
+                vec![option("persona", "obi"), option("prompt", "hi")],
+            )
+            .unwrap(),
+        );
+        drop(recorder);
+
+        // A corrupt line is skipped rather than aborting the replay
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"not json\n")
+            .unwrap();
+
+        let report = replay_file(&path).await.unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(report.outcomes.len(), 2);
+        assert_eq!(report.skipped, 1);
+        assert_eq!(report.failures(), 0);
+        assert_eq!(report.outcomes[0].command, "does_not_exist");
+        assert!(report.outcomes[0].discord_outputs[0]
+            .text()
+            .starts_with("Unknown command."));
+        assert_eq!(report.outcomes[1].openai_requests, 1);
+        assert!(report
+            .render()
+            .contains("Replayed 2 interaction(s): 0 failed, 1 skipped"));
+    }
+}
@@ -0,0 +1,281 @@
+//! End-to-end scenarios run against the dry-run harness
+
+use super::*;
+use crate::core::ChatClient;
+use crate::features::debate::orchestrator::DebateConfig;
+use crate::features::debate::{get_active_debates, DebateOrchestrator};
+use openai::chat::{ChatCompletionMessage, ChatCompletionMessageRole};
+use serenity::model::id::{ChannelId, MessageId};
+use std::time::Duration;
+
+const SPAWNED_WORK_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn is_original_edit(request: &RecordedRequest) -> bool {
+    request.method == "PATCH" && request.path.ends_with("/messages/@original")
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_ask_dispatches_through_registry_with_mocked_openai() {
+    let harness = DryRun::start().await.unwrap();
+    harness.openai.push_reply("Patience, young one.");
+
+    let command = slash_command(
+        "ask",
+        vec![
+            option("persona", "obi"),
+            option("prompt", "How do I learn patience?"),
+        ],
+    )
+    .unwrap();
+    harness.run_slash(&command).await.unwrap();
+
+    let requests = harness.openai.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].model, MOCK_MODEL);
+    assert_eq!(
+        requests[0].last_user_message(),
+        Some("How do I learn patience?")
+    );
+    assert!(requests[0].system_prompt().is_some_and(|p| !p.is_empty()));
+
+    let outputs = harness.discord.outputs();
+    assert_eq!(outputs[0].body["type"], 5, "interaction is deferred first");
+    let edit = outputs.iter().find(|r| is_original_edit(r)).unwrap();
+    assert!(edit.text().contains("Patience, young one."));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_ask_reports_openai_errors_to_the_user() {
+    let harness = DryRun::start().await.unwrap();
+    harness.openai.push_error("simulated outage");
+
+    let command = slash_command(
+        "ask",
+        vec![option("persona", "obi"), option("prompt", "Hello?")],
+    )
+    .unwrap();
+    harness.run_slash(&command).await.unwrap();
+
+    let outputs = harness.discord.outputs();
+    let edit = outputs.iter().find(|r| is_original_edit(r)).unwrap();
+    assert!(edit
+        .content()
+        .unwrap()
+        .starts_with("Sorry, I couldn't get a response"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_unknown_slash_command_gets_help_hint() {
+    let harness = DryRun::start().await.unwrap();
+
+    let command = slash_command("does_not_exist", vec![]).unwrap();
+    harness.run_slash(&command).await.unwrap();
+
+    let outputs = harness.discord.outputs();
+    assert_eq!(outputs.len(), 1);
+    assert!(outputs[0]
+        .content()
+        .unwrap()
+        .starts_with("Unknown command."));
+    assert_eq!(harness.openai.request_count(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_status_reports_registry_command_metrics() {
+    let harness = DryRun::start().await.unwrap();
+
+    for _ in 0..2 {
+        harness
+            .run_slash(&slash_command("ping", vec![]).unwrap())
+            .await
+            .unwrap();
+    }
+    harness.discord.clear();
+    harness
+        .run_slash(&slash_command("status", vec![]).unwrap())
+        .await
+        .unwrap();
+
+    let status = harness.discord.outputs()[0].content().unwrap().to_string();
+    assert!(status.contains("**Top Commands**"));
+    assert!(status.contains("`/ping` 2×"), "{status}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_plugin_execution_posts_output_to_channel() {
+    let config: PluginConfig = serde_yaml::from_str(
+        r#"
+plugins:
+  - name: echo
+    description: Echo text back
+    version: "1.0.0"
+    command:
+      name: echo
+      description: Echo text back
+      options:
+        - name: text
+          description: Text to echo
+          type: string
+          required: true
+    execution:
+      command: echo
+      args: ["${text}"]
+      timeout_seconds: 5
+"#,
+    )
+    .unwrap();
+    let harness = DryRun::start_with_plugins(Some(config), vec!["echo".to_string()])
+        .await
+        .unwrap();
+
+    let command = slash_command(
+        "plugins",
+        vec![subcommand("echo", vec![option("text", "dry-run-output")])],
+    )
+    .unwrap();
+    harness.run_slash(&command).await.unwrap();
+
+    let channel_path = format!("/channels/{MOCK_CHANNEL_ID}/messages");
+    let posted = harness
+        .discord
+        .wait_for(SPAWNED_WORK_TIMEOUT, |r| {
+            r.path == channel_path && r.text().contains("dry-run-output")
+        })
+        .await;
+    assert!(posted.is_some(), "plugin output was not posted");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_debate_rounds_alternate_and_save_state() {
+    let harness = DryRun::start().await.unwrap();
+    harness.openai.push_reply("Opening from the first debater.");
+    harness
+        .openai
+        .push_reply("Rebuttal from the second debater.");
+
+    let thread_id = ChannelId(MOCK_CHANNEL_ID + 1);
+    let config = DebateConfig {
+        persona1_id: "obi".to_string(),
+        persona2_id: "muppet".to_string(),
+        topic: "Is sand coarse?".to_string(),
+        rounds: 2,
+        initiator_id: MOCK_USER_ID.to_string(),
+        guild_id: Some(MOCK_GUILD_ID.to_string()),
+        initial_history: None,
+        previous_debaters: None,
+        rules: None,
+        opening_only: true,
+        prior_context: None,
+    };
+
+    let openai = harness.openai.clone();
+    let get_response = |system_prompt: String, user_message: String, _history| {
+        let openai = openai.clone();
+        async move {
+            let messages = [
+                (ChatCompletionMessageRole::System, system_prompt),
+                (ChatCompletionMessageRole::User, user_message),
+            ]
+            .into_iter()
+            .map(|(role, content)| ChatCompletionMessage {
+                role,
+                content: Some(content),
+                name: None,
+                function_call: None,
+                tool_call_id: None,
+                tool_calls: None,
+            })
+            .collect();
+            let completion = openai.create_chat_completion(MOCK_MODEL, messages).await?;
+            Ok(completion.choices[0]
+                .message
+                .content
+                .clone()
+                .unwrap_or_default())
+        }
+    };
+
+    DebateOrchestrator::new()
+        .run_debate(&harness.context(), thread_id, config, get_response)
+        .await
+        .unwrap();
+
+    let posts: Vec<_> = harness
+        .discord
+        .outputs()
+        .into_iter()
+        .filter(|r| r.path == format!("/channels/{}/messages", thread_id.0))
+        .collect();
+    assert_eq!(posts.len(), 3, "two rounds plus the closing message");
+    assert!(posts[0].text().contains("Opening from the first debater."));
+    assert!(posts[1]
+        .text()
+        .contains("Rebuttal from the second debater."));
+    assert!(posts[2].body["components"].is_array());
+
+    let state = get_active_debates().get(&thread_id.0).unwrap().clone();
+    assert_eq!(state.history.len(), 2);
+    assert!(state.awaiting_user_input);
+    DebateOrchestrator::end_debate(thread_id.0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_conflict_mediation_posts_ai_response() {
+    let harness = DryRun::start().await.unwrap();
+    harness
+        .openai
+        .push_reply("Calm yourselves. Anger leads nowhere good.");
+
+    let channel = ChannelId(MOCK_CHANNEL_ID + 2);
+    let guild_id = MOCK_GUILD_ID.to_string();
+    for (user, content) in [
+        ("111", "you're stupid and wrong"),
+        ("222", "NO YOU ARE COMPLETELY WRONG!!!"),
+        ("111", "shut up, nobody asked you"),
+        ("222", "you're an idiot, this is bullshit"),
+    ] {
+        harness
+            .database
+            .store_guild_message(
+                Some(&guild_id),
+                user,
+                &channel.to_string(),
+                "user",
+                content,
+                None,
+            )
+            .await
+            .unwrap();
+    }
+
+    harness
+        .handler
+        .check_and_mediate_conflicts(
+            &harness.discord.http(),
+            channel,
+            MessageId(1),
+            Some(&guild_id),
+        )
+        .await
+        .unwrap();
+
+    let prompt = harness.openai.requests()[0]
+        .system_prompt()
+        .unwrap()
+        .to_string();
+    assert!(prompt.contains("Obi-Wan"));
+    assert!(prompt.contains("you're stupid and wrong"));
+
+    let posts = harness.discord.outputs();
+    assert_eq!(posts.len(), 1);
+    assert_eq!(
+        posts[0].content(),
+        Some("Calm yourselves. Anger leads nowhere good.")
+    );
+    assert!(harness
+        .database
+        .get_last_mediation_timestamp(&channel.to_string())
+        .await
+        .unwrap()
+        .is_some());
+}
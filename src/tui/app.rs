@@ -2,16 +2,19 @@
 //!
 //! Main application state and screen navigation.
 //!
-//! - **Version**: 1.1.0
+//! - **Version**: 1.4.0
 //! - **Since**: 3.18.0
 //! - **Toggleable**: false
 //!
 //! ## Changelog
+//! - 1.4.0: Track channel sentiment timelines for the Stats mood panel
+//! - 1.3.0: Track circuit breaker state from StatusUpdate
+//! - 1.2.0: Add message search screen
 //! - 1.1.0: Add collapsible guild sections for channel watcher
 //! - 1.0.0: Initial release
 
-use crate::ipc::{BotEvent, ChannelHistorySummary, GuildInfo};
-use crate::tui::state::{ChannelState, ErrorsState, StatsCache, UsersState};
+use crate::ipc::{BotEvent, BreakerInfo, ChannelHistorySummary, GuildInfo};
+use crate::tui::state::{ChannelState, ErrorsState, SearchState, StatsCache, UsersState};
 use crate::tui::ui::SettingsTab;
 use std::collections::{HashMap, HashSet};
 
@@ -24,6 +27,7 @@ pub enum Screen {
     Users,
     Settings,
     Errors,
+    Search,
     Help,
 }
 
@@ -36,6 +40,7 @@ impl Screen {
             Screen::Users => "User Analytics",
             Screen::Settings => "Settings",
             Screen::Errors => "Error Logs",
+            Screen::Search => "Message Search",
             Screen::Help => "Help",
         }
     }
@@ -48,6 +53,7 @@ impl Screen {
             Screen::Users => '4',
             Screen::Settings => '5',
             Screen::Errors => '6',
+            Screen::Search => '7',
             Screen::Help => '?',
         }
     }
@@ -60,6 +66,7 @@ impl Screen {
             Screen::Users,
             Screen::Settings,
             Screen::Errors,
+            Screen::Search,
             Screen::Help,
         ]
     }
@@ -80,6 +87,8 @@ pub enum InputPurpose {
     AddChannel,
     /// Sending a message to a channel
     SendMessage,
+    /// Searching stored messages
+    Search,
 }
 
 /// Represents what the current selection points to in the channel list
@@ -113,6 +122,8 @@ pub struct App {
     pub uptime_seconds: u64,
     /// Active session count
     pub active_sessions: usize,
+    /// Circuit breaker state per external dependency
+    pub breakers: Vec<BreakerInfo>,
     /// Channel state (watched channels, messages)
     pub channel_state: ChannelState,
     /// Stats cache
@@ -121,6 +132,8 @@ pub struct App {
     pub users_state: UsersState,
     /// Errors state (error logs)
     pub errors_state: ErrorsState,
+    /// Message search state
+    pub search_state: SearchState,
     /// Current input mode
     pub input_mode: InputMode,
     /// Purpose of the current input
@@ -177,10 +190,12 @@ impl App {
             bot_user_id: None,
             uptime_seconds: 0,
             active_sessions: 0,
+            breakers: Vec::new(),
             channel_state: ChannelState::new(),
             stats_cache: StatsCache::new(),
             users_state: UsersState::new(),
             errors_state: ErrorsState::new(),
+            search_state: SearchState::new(),
             input_mode: InputMode::Normal,
             input_purpose: InputPurpose::default(),
             input_buffer: String::new(),
@@ -256,10 +271,12 @@ impl App {
                 uptime_seconds,
                 guild_count: _,
                 active_sessions,
+                breakers,
             } => {
                 self.bot_connected = connected;
                 self.uptime_seconds = uptime_seconds;
                 self.active_sessions = active_sessions;
+                self.breakers = breakers;
             }
             BotEvent::CommandResponse {
                 success, message, ..
@@ -303,12 +320,26 @@ impl App {
                 memory_total,
                 db_size,
                 uptime_seconds,
+                db_wal_size,
+                transcript_size,
+                net_rx_per_sec,
+                net_tx_per_sec,
+                alive_tasks,
+                tasks_by_feature,
+                openai_in_flight,
             } => {
                 self.stats_cache.system.cpu_percent = cpu_percent;
                 self.stats_cache.system.memory_bytes = memory_bytes;
                 self.stats_cache.system.memory_total = memory_total;
                 self.stats_cache.system.db_size = db_size;
                 self.stats_cache.system.uptime_seconds = uptime_seconds;
+                self.stats_cache.system.db_wal_size = db_wal_size;
+                self.stats_cache.system.transcript_size = transcript_size;
+                self.stats_cache.system.net_rx_per_sec = net_rx_per_sec;
+                self.stats_cache.system.net_tx_per_sec = net_tx_per_sec;
+                self.stats_cache.system.alive_tasks = alive_tasks;
+                self.stats_cache.system.tasks_by_feature = tasks_by_feature;
+                self.stats_cache.system.openai_in_flight = openai_in_flight;
             }
             BotEvent::ChannelInfoResponse {
                 channel_id,
@@ -341,6 +372,9 @@ impl App {
                 self.stats_cache
                     .set_historical_data(&metric_type, data_points);
             }
+            BotEvent::SentimentTimelineResponse { channels, .. } => {
+                self.stats_cache.sentiment = channels;
+            }
             BotEvent::UserListResponse { users } => {
                 self.users_state.set_users(users);
             }
@@ -365,6 +399,9 @@ impl App {
                     self.feature_states.len()
                 ));
             }
+            BotEvent::SearchResultsResponse { query, results } => {
+                self.search_state.set_results(&query, results);
+            }
             BotEvent::ChannelsWithHistoryResponse { channels } => {
                 let count = channels.len();
                 self.db_channel_history.clear();
@@ -445,6 +482,12 @@ impl App {
         self.input_purpose = InputPurpose::SendMessage;
     }
 
+    /// Enter editing mode for a search query
+    pub fn start_search_input(&mut self) {
+        self.input_mode = InputMode::Editing;
+        self.input_purpose = InputPurpose::Search;
+    }
+
     /// Exit editing mode
     pub fn stop_editing(&mut self) {
         self.input_mode = InputMode::Normal;
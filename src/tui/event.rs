@@ -157,6 +157,9 @@ pub fn map_key_event(key: KeyEvent, in_edit_mode: bool) -> KeyAction {
             (KeyCode::Char('6'), KeyModifiers::NONE) => {
                 KeyAction::SwitchScreen(crate::tui::Screen::Errors)
             }
+            (KeyCode::Char('7'), KeyModifiers::NONE) => {
+                KeyAction::SwitchScreen(crate::tui::Screen::Search)
+            }
             (KeyCode::Char('?'), KeyModifiers::NONE) => {
                 KeyAction::SwitchScreen(crate::tui::Screen::Help)
             }
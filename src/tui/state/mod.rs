@@ -4,10 +4,12 @@
 
 mod channels;
 mod errors;
+mod search;
 mod stats_cache;
 mod users;
 
 pub use channels::ChannelState;
 pub use errors::ErrorsState;
+pub use search::SearchState;
 pub use stats_cache::StatsCache;
 pub use users::UsersState;
@@ -0,0 +1,71 @@
+//! # Search State
+//!
+//! State management for the message search screen.
+
+use crate::ipc::SearchResultInfo;
+
+/// Message search state
+pub struct SearchState {
+    /// Last submitted query
+    pub query: String,
+    /// Results for the last query (newest first)
+    pub results: Vec<SearchResultInfo>,
+    /// Currently selected result index
+    pub selected_index: usize,
+    /// Whether a search is in flight
+    pub searching: bool,
+}
+
+impl SearchState {
+    pub fn new() -> Self {
+        SearchState {
+            query: String::new(),
+            results: Vec::new(),
+            selected_index: 0,
+            searching: false,
+        }
+    }
+
+    /// Record a submitted query and clear stale results
+    pub fn start_search(&mut self, query: String) {
+        self.query = query;
+        self.results.clear();
+        self.selected_index = 0;
+        self.searching = true;
+    }
+
+    /// Store results, ignoring responses for queries that were superseded
+    pub fn set_results(&mut self, query: &str, results: Vec<SearchResultInfo>) {
+        if query != self.query {
+            return;
+        }
+        self.results = results;
+        self.selected_index = 0;
+        self.searching = false;
+    }
+
+    /// Get currently selected result
+    pub fn selected_result(&self) -> Option<&SearchResultInfo> {
+        self.results.get(self.selected_index)
+    }
+
+    /// Move selection up
+    pub fn select_previous(&mut self) {
+        if self.selected_index > 0 {
+            self.selected_index -= 1;
+        }
+    }
+
+    /// Move selection down
+    pub fn select_next(&mut self) {
+        if self.selected_index < self.results.len().saturating_sub(1) {
+            self.selected_index += 1;
+        }
+    }
+}
+
+impl Default for SearchState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! Cached statistics from the database.
 
-use crate::ipc::TopUser;
+use crate::ipc::{ChannelSentimentInfo, TopUser};
 use std::time::Instant;
 
 /// Cached usage statistics
@@ -39,6 +39,20 @@ pub struct SystemMetrics {
     pub db_size: u64,
     /// Bot uptime in seconds
     pub uptime_seconds: u64,
+    /// SQLite WAL + shared-memory size in bytes
+    pub db_wal_size: u64,
+    /// Transcript working directories size in bytes
+    pub transcript_size: u64,
+    /// Network receive rate in bytes/sec
+    pub net_rx_per_sec: u64,
+    /// Network transmit rate in bytes/sec
+    pub net_tx_per_sec: u64,
+    /// All tasks alive in the tokio runtime
+    pub alive_tasks: usize,
+    /// Tracked tasks per feature
+    pub tasks_by_feature: Vec<(String, usize)>,
+    /// OpenAI requests in flight
+    pub openai_in_flight: usize,
 }
 
 /// Historical metrics for sparklines
@@ -60,6 +74,8 @@ pub struct StatsCache {
     pub system: SystemMetrics,
     /// Historical metrics for charts
     pub historical: HistoricalMetrics,
+    /// Hourly mood of sentiment-tracked channels
+    pub sentiment: Vec<ChannelSentimentInfo>,
     /// Last refresh time
     pub last_refresh: Option<Instant>,
     /// Refresh interval in seconds
@@ -106,6 +122,25 @@ impl TimePeriod {
             TimePeriod::AllTime => None,
         }
     }
+
+    /// Span of the CPU/memory history graphs (All Time shows the last month)
+    pub fn history_hours(&self) -> u32 {
+        self.days().unwrap_or(30) * 24
+    }
+
+    /// Span of the channel mood panel, capped at a week
+    pub fn mood_hours(&self) -> u32 {
+        self.history_hours().min(crate::features::sentiment::MAX_MOOD_HOURS as u32)
+    }
+
+    /// Short label for the history graphs
+    pub fn history_label(&self) -> &'static str {
+        match self {
+            TimePeriod::Today => "24h",
+            TimePeriod::Week => "7d",
+            TimePeriod::Month | TimePeriod::AllTime => "30d",
+        }
+    }
 }
 
 impl StatsCache {
@@ -114,6 +149,7 @@ impl StatsCache {
             usage: UsageStats::default(),
             system: SystemMetrics::default(),
             historical: HistoricalMetrics::default(),
+            sentiment: Vec::new(),
             last_refresh: None,
             refresh_interval: 30, // Default 30 seconds
             refreshing: false,
@@ -190,31 +226,14 @@ impl StatsCache {
         }
     }
 
-    /// Get CPU history as sparkline data (values only)
-    pub fn cpu_sparkline_data(&self) -> Vec<u64> {
-        self.historical
-            .cpu_history
-            .iter()
-            .map(|(_, v)| *v as u64)
-            .collect()
+    /// Get CPU history as sparkline data, averaged down to `max_points`
+    pub fn cpu_sparkline_data(&self, max_points: usize) -> Vec<u64> {
+        fit_points(&self.historical.cpu_history, max_points)
     }
 
-    /// Get memory history as sparkline data (percentage)
-    pub fn memory_sparkline_data(&self) -> Vec<u64> {
-        let total = self.system.memory_total as f64;
-        if total > 0.0 {
-            self.historical
-                .memory_history
-                .iter()
-                .map(|(_, v)| ((v / total) * 100.0) as u64)
-                .collect()
-        } else {
-            self.historical
-                .memory_history
-                .iter()
-                .map(|(_, v)| *v as u64)
-                .collect()
-        }
+    /// Get system memory history (percentage) as sparkline data, averaged down to `max_points`
+    pub fn memory_sparkline_data(&self, max_points: usize) -> Vec<u64> {
+        fit_points(&self.historical.memory_history, max_points)
     }
 
     /// Get cost history as sparkline data
@@ -233,3 +252,15 @@ impl Default for StatsCache {
         Self::new()
     }
 }
+
+/// Average consecutive points so a series fits in `max_points` columns
+fn fit_points(points: &[(i64, f64)], max_points: usize) -> Vec<u64> {
+    if points.is_empty() || max_points == 0 {
+        return Vec::new();
+    }
+    let per_column = points.len().div_ceil(max_points);
+    points
+        .chunks(per_column)
+        .map(|chunk| (chunk.iter().map(|(_, v)| v).sum::<f64>() / chunk.len() as f64) as u64)
+        .collect()
+}
@@ -422,6 +422,10 @@ fn render_input(frame: &mut Frame, app: &App, area: Rect) {
             " Channel ID (Enter to watch, Esc to cancel) ",
             Style::default().fg(Color::Yellow),
         ),
+        InputPurpose::Search => (
+            " Search (Enter to search, Esc to cancel) ",
+            Style::default().fg(Color::Cyan),
+        ),
     };
 
     let input = Paragraph::new(app.input_buffer.as_str())
@@ -18,7 +18,7 @@ pub fn render_dashboard(frame: &mut Frame, app: &App, area: Rect) {
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(8),  // Connection status
-            Constraint::Length(10), // System info
+            Constraint::Length(12), // System info
             Constraint::Min(0),     // Guild list
         ])
         .split(chunks[0]);
@@ -92,6 +92,25 @@ fn render_connection_status(frame: &mut Frame, app: &App, area: Rect) {
         ),
     ]));
 
+    // Circuit breakers (only list the ones that aren't closed)
+    let tripped: Vec<_> = app
+        .breakers
+        .iter()
+        .filter(|b| b.state != "closed")
+        .collect();
+    let breaker_status = if app.breakers.is_empty() {
+        Span::styled("--", Style::default().fg(Color::DarkGray))
+    } else if tripped.is_empty() {
+        Span::styled("All closed", Style::default().fg(Color::Green))
+    } else {
+        let names: Vec<_> = tripped
+            .iter()
+            .map(|b| format!("{} ({})", b.name, b.state.replace('_', "-")))
+            .collect();
+        Span::styled(names.join(", "), Style::default().fg(Color::Red))
+    };
+    lines.push(Line::from(vec![Span::raw("Breakers:  "), breaker_status]));
+
     let block = titled_block("Connection Status");
     let paragraph = Paragraph::new(lines).block(block);
     frame.render_widget(paragraph, area);
@@ -142,11 +161,66 @@ fn render_system_info(frame: &mut Frame, app: &App, area: Rect) {
     lines.push(Line::from(vec![
         Span::raw("DB Size:   "),
         Span::styled(
-            format_bytes(stats.system.db_size),
+            format!(
+                "{} (+{} WAL)",
+                format_bytes(stats.system.db_size),
+                format_bytes(stats.system.db_wal_size)
+            ),
             Style::default().fg(Color::Cyan),
         ),
     ]));
 
+    // Transcript storage
+    lines.push(Line::from(vec![
+        Span::raw("Transcr:   "),
+        Span::styled(
+            format_bytes(stats.system.transcript_size),
+            Style::default().fg(Color::Cyan),
+        ),
+    ]));
+
+    // Network throughput
+    lines.push(Line::from(vec![
+        Span::raw("Network:   "),
+        Span::styled(
+            format!(
+                "↓ {}/s ↑ {}/s",
+                format_bytes(stats.system.net_rx_per_sec),
+                format_bytes(stats.system.net_tx_per_sec)
+            ),
+            Style::default().fg(Color::Cyan),
+        ),
+    ]));
+
+    // Tasks per feature
+    let per_feature: Vec<String> = stats
+        .system
+        .tasks_by_feature
+        .iter()
+        .map(|(feature, count)| format!("{} {}", feature, count))
+        .collect();
+    lines.push(Line::from(vec![
+        Span::raw("Tasks:     "),
+        Span::styled(
+            format!("{} ({})", stats.system.alive_tasks, per_feature.join(" · ")),
+            Style::default().fg(Color::Yellow),
+        ),
+    ]));
+
+    // OpenAI requests in flight
+    let in_flight_color = if stats.system.openai_in_flight > 0 {
+        Color::Yellow
+    } else {
+        Color::DarkGray
+    };
+    lines.push(Line::from(vec![
+        Span::raw("OpenAI:    "),
+        Span::styled(
+            format!("{} in flight", stats.system.openai_in_flight),
+            Style::default().fg(in_flight_color),
+        ),
+    ]));
+
     // Last heartbeat
     if let Some(ts) = app.last_heartbeat {
         let ago = chrono::Utc::now().timestamp() - ts;
@@ -29,7 +29,7 @@ fn render_navigation_help(frame: &mut Frame, area: Rect) {
                 ("q", "Quit application"),
                 ("Ctrl+c", "Force quit"),
                 ("?", "Show this help"),
-                ("1-6", "Switch screens"),
+                ("1-7", "Switch screens"),
                 ("Esc", "Go back / Cancel"),
             ],
         ),
@@ -161,6 +161,15 @@ fn render_screen_help(frame: &mut Frame, area: Rect) {
                 "Press 'r' to refresh",
             ],
         ),
+        (
+            "Search [7]",
+            vec![
+                "Full-text search of stored messages",
+                "",
+                "/ or i: Enter a query",
+                "Enter: Open result's channel",
+            ],
+        ),
     ];
 
     let mut lines = vec![];
@@ -6,6 +6,7 @@ mod channel_watcher;
 mod dashboard;
 mod errors;
 mod help;
+mod search;
 mod settings;
 mod stats;
 mod users;
@@ -14,6 +15,7 @@ pub use channel_watcher::render_channels;
 pub use dashboard::render_dashboard;
 pub use errors::render_errors;
 pub use help::render_help;
+pub use search::render_search;
 pub use settings::{render_settings, SettingsTab};
 pub use stats::render_stats;
 pub use users::render_users;
@@ -44,6 +46,7 @@ pub fn render(frame: &mut Frame, app: &App) {
         Screen::Users => render_users(frame, app, chunks[1]),
         Screen::Settings => render_settings(frame, app, chunks[1]),
         Screen::Errors => render_errors(frame, app, chunks[1]),
+        Screen::Search => render_search(frame, app, chunks[1]),
         Screen::Help => render_help(frame, app, chunks[1]),
     }
 
@@ -157,11 +160,12 @@ pub fn format_currency(amount: f64) -> String {
     format!("${:.4}", amount)
 }
 
-/// Helper to truncate text
+/// Helper to truncate text (counts characters, safe for non-ASCII message content)
 pub fn truncate_text(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
+    if s.chars().count() <= max_len {
         s.to_string()
     } else {
-        format!("{}...", &s[..max_len.saturating_sub(3)])
+        let kept: String = s.chars().take(max_len.saturating_sub(3)).collect();
+        format!("{}...", kept)
     }
 }
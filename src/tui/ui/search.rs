@@ -0,0 +1,143 @@
+//! # Search UI
+//!
+//! Full-text search over stored messages.
+
+use crate::tui::app::{InputMode, InputPurpose};
+use crate::tui::ui::{titled_block, truncate_text};
+use crate::tui::App;
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph};
+
+/// Render the search screen
+pub fn render_search(frame: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Query input
+            Constraint::Min(0),    // Results
+        ])
+        .split(area);
+
+    render_query(frame, app, chunks[0]);
+    render_results(frame, app, chunks[1]);
+}
+
+fn render_query(frame: &mut Frame, app: &App, area: Rect) {
+    let editing = app.input_mode == InputMode::Editing && app.input_purpose == InputPurpose::Search;
+
+    let (text, title, style) = if editing {
+        (
+            app.input_buffer.as_str(),
+            " Search (Enter to search, Esc to cancel) ",
+            Style::default().fg(Color::Cyan),
+        )
+    } else if app.search_state.query.is_empty() {
+        (
+            "Press / or i to search stored messages",
+            " Search ",
+            Style::default().fg(Color::DarkGray),
+        )
+    } else {
+        (
+            app.search_state.query.as_str(),
+            " Search (/ for new query, Enter to open channel) ",
+            Style::default().fg(Color::White),
+        )
+    };
+
+    let input = Paragraph::new(text)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .style(style);
+    frame.render_widget(input, area);
+
+    if editing {
+        frame.set_cursor_position(Position::new(
+            area.x + app.input_buffer.len() as u16 + 1,
+            area.y + 1,
+        ));
+    }
+}
+
+fn render_results(frame: &mut Frame, app: &App, area: Rect) {
+    let state = &app.search_state;
+
+    if state.results.is_empty() {
+        let msg = if state.searching {
+            "Searching..."
+        } else if state.query.is_empty() {
+            "No search yet"
+        } else {
+            "No matching messages"
+        };
+        let paragraph = Paragraph::new(msg)
+            .block(titled_block("Results"))
+            .style(Style::default().fg(Color::DarkGray))
+            .alignment(Alignment::Center);
+        frame.render_widget(paragraph, area);
+        return;
+    }
+
+    let snippet_width = (area.width as usize).saturating_sub(60).max(20);
+
+    let items: Vec<ListItem> = state
+        .results
+        .iter()
+        .enumerate()
+        .map(|(i, result)| {
+            let is_selected = i == state.selected_index;
+            let style = if is_selected {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+
+            let prefix = if is_selected { "> " } else { "  " };
+            let timestamp = result
+                .timestamp
+                .map(|t| t.format("%m-%d %H:%M").to_string())
+                .unwrap_or_else(|| "--".to_string());
+            let location = match (&result.guild_name, &result.channel_name) {
+                (Some(guild), Some(channel)) => format!("{}/#{}", guild, channel),
+                (None, Some(channel)) => format!("#{}", channel),
+                _ if result.guild_id.is_none() => "DM".to_string(),
+                _ => format!("#{}", result.channel_id),
+            };
+            let author_style = if result.is_bot {
+                Style::default().fg(Color::Magenta)
+            } else {
+                Style::default().fg(Color::Cyan)
+            };
+
+            let line = Line::from(vec![
+                Span::raw(prefix),
+                Span::styled(
+                    format!("[{}] ", timestamp),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(
+                    format!("{:<24} ", truncate_text(&location, 24)),
+                    Style::default().fg(Color::Green),
+                ),
+                Span::styled(
+                    format!("{:<16} ", truncate_text(&result.author_name, 16)),
+                    author_style,
+                ),
+                Span::raw(truncate_text(
+                    &result.snippet.replace('\n', " "),
+                    snippet_width,
+                )),
+            ]);
+
+            ListItem::new(line).style(style)
+        })
+        .collect();
+
+    let title = format!("Results ({})", state.results.len());
+    let list = List::new(items)
+        .block(titled_block(&title))
+        .style(Style::default().fg(Color::White));
+
+    frame.render_widget(list, area);
+}
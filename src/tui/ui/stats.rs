@@ -1,7 +1,10 @@
 //! # Stats UI
 //!
-//! Usage statistics and cost breakdown display.
+//! Usage statistics, cost breakdown and channel mood display.
 
+use crate::database::SentimentPoint;
+use crate::features::sentiment::{hour_start, hourly_series, mood_label, sparkline, weighted_average};
+use crate::ipc::ChannelSentimentInfo;
 use crate::tui::ui::{format_bytes, format_currency, titled_block};
 use crate::tui::App;
 use ratatui::prelude::*;
@@ -42,6 +45,14 @@ pub fn render_stats(frame: &mut Frame, app: &App, area: Rect) {
         ])
         .split(main_chunks[0]);
 
+    let center_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),                         // Cost by feature
+            Constraint::Length(mood_panel_height(app)), // Channel mood
+        ])
+        .split(main_chunks[1]);
+
     let right_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -52,7 +63,8 @@ pub fn render_stats(frame: &mut Frame, app: &App, area: Rect) {
 
     render_cost_summary(frame, app, left_chunks[0]);
     render_cost_by_service(frame, app, left_chunks[1]);
-    render_cost_by_bucket(frame, app, main_chunks[1]);
+    render_cost_by_bucket(frame, app, center_chunks[0]);
+    render_channel_mood(frame, app, center_chunks[1]);
     render_daily_chart(frame, app, right_chunks[0]);
     render_top_users(frame, app, right_chunks[1]);
 }
@@ -84,14 +96,18 @@ fn render_system_sparklines(frame: &mut Frame, app: &App, area: Rect) {
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
         .split(area);
 
-    // CPU sparkline
-    let cpu_data = app.stats_cache.cpu_sparkline_data();
+    let history = app.stats_cache.time_period.history_label();
+
+    // CPU sparkline (inside the block borders)
+    let cpu_data = app
+        .stats_cache
+        .cpu_sparkline_data(chunks[0].width.saturating_sub(2) as usize);
     let cpu_title = if cpu_data.is_empty() {
         format!("CPU (current: {:.1}%)", app.stats_cache.system.cpu_percent)
     } else {
         format!(
-            "CPU 24h (current: {:.1}%)",
-            app.stats_cache.system.cpu_percent
+            "CPU {} (current: {:.1}%)",
+            history, app.stats_cache.system.cpu_percent
         )
     };
     let cpu_sparkline = Sparkline::default()
@@ -101,7 +117,9 @@ fn render_system_sparklines(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(cpu_sparkline, chunks[0]);
 
     // Memory sparkline
-    let memory_data = app.stats_cache.memory_sparkline_data();
+    let memory_data = app
+        .stats_cache
+        .memory_sparkline_data(chunks[1].width.saturating_sub(2) as usize);
     let mem_percent = app.stats_cache.memory_percent();
     let mem_used = format_bytes(app.stats_cache.system.memory_bytes);
     let mem_total = format_bytes(app.stats_cache.system.memory_total);
@@ -110,8 +128,8 @@ fn render_system_sparklines(frame: &mut Frame, app: &App, area: Rect) {
         format!("Memory ({:.1}% - {}/{})", mem_percent, mem_used, mem_total)
     } else {
         format!(
-            "Memory 24h ({:.1}% - {}/{})",
-            mem_percent, mem_used, mem_total
+            "Memory {} ({:.1}% - {}/{})",
+            history, mem_percent, mem_used, mem_total
         )
     };
     let memory_sparkline = Sparkline::default()
@@ -252,6 +270,23 @@ fn render_cost_by_bucket(frame: &mut Frame, app: &App, area: Rect) {
                 "transcription" => Color::LightGreen,
                 "imagine" => Color::LightMagenta,
                 "fetch" => Color::LightCyan,
+                "trivia" => Color::LightYellow,
+                "story" => Color::LightRed,
+                "ticket" => Color::White,
+                "report" => Color::Gray,
+                "topics" => Color::Rgb(180, 140, 255),
+                "language" => Color::Rgb(255, 170, 100),
+                "channel_topics" => Color::Rgb(120, 200, 160),
+                "qotd" => Color::Rgb(240, 200, 90),
+                "support" => Color::Rgb(120, 190, 230),
+                "anon_questions" => Color::Rgb(200, 160, 200),
+                "verification" => Color::Rgb(150, 220, 120),
+                "autoresponse" => Color::Rgb(240, 140, 90),
+                "send_later" => Color::Rgb(130, 170, 250),
+                "convert" => Color::Rgb(240, 200, 120),
+                "dice" => Color::Rgb(200, 90, 90),
+                "dm_history" => Color::Rgb(150, 200, 220),
+                "birthdays" => Color::Rgb(250, 150, 200),
                 _ => Color::DarkGray,
             };
 
@@ -276,6 +311,93 @@ fn render_cost_by_bucket(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(list, area);
 }
 
+/// Two lines per tracked channel (at most four) plus borders, or one line when none are tracked
+fn mood_panel_height(app: &App) -> u16 {
+    match app.stats_cache.sentiment.len() {
+        0 => 3,
+        n => n.min(4) as u16 * 2 + 2,
+    }
+}
+
+fn render_channel_mood(frame: &mut Frame, app: &App, area: Rect) {
+    let hours = app.stats_cache.time_period.mood_hours();
+    let title = format!("Channel Mood ({hours}h)");
+
+    if app.stats_cache.sentiment.is_empty() {
+        let paragraph = Paragraph::new("No channels with sentiment tracking")
+            .block(titled_block(&title))
+            .style(Style::default().fg(Color::DarkGray))
+            .alignment(Alignment::Center);
+        frame.render_widget(paragraph, area);
+        return;
+    }
+
+    let width = area.width.saturating_sub(2) as usize;
+    let now = chrono::Utc::now().timestamp();
+    let since = hour_start(now) - (hours as i64 - 1) * 3600;
+    let mut lines = Vec::new();
+    for channel in app.stats_cache.sentiment.iter().take(4) {
+        let (header, series) = channel_mood_line(channel, since, now);
+        let color = mood_color(weighted_average(&sentiment_points(channel)));
+        lines.push(Line::from(Span::styled(
+            header,
+            Style::default().fg(Color::White),
+        )));
+        // Newest hours win when the window is wider than the panel
+        let visible = &series[series.len().saturating_sub(width)..];
+        lines.push(Line::from(Span::styled(
+            sparkline(visible),
+            Style::default().fg(color),
+        )));
+    }
+
+    let paragraph = Paragraph::new(lines).block(titled_block(&title));
+    frame.render_widget(paragraph, area);
+}
+
+fn sentiment_points(channel: &ChannelSentimentInfo) -> Vec<SentimentPoint> {
+    channel
+        .points
+        .iter()
+        .map(|&(hour, score, messages)| SentimentPoint {
+            hour,
+            messages,
+            score,
+        })
+        .collect()
+}
+
+/// Header (`#name  emoji label score`) and hourly series for one channel
+fn channel_mood_line(
+    channel: &ChannelSentimentInfo,
+    since: i64,
+    now: i64,
+) -> (String, Vec<Option<f64>>) {
+    let points = sentiment_points(channel);
+    let name = channel
+        .channel_name
+        .clone()
+        .unwrap_or_else(|| channel.channel_id.to_string());
+    let header = match weighted_average(&points) {
+        Some(score) => {
+            let (emoji, label) = mood_label(score);
+            let messages: i64 = points.iter().map(|p| p.messages).sum();
+            format!("#{name}  {emoji} {label} {score:+.2} ({messages} msgs)")
+        }
+        None => format!("#{name}  no messages"),
+    };
+    (header, hourly_series(&points, since, now))
+}
+
+fn mood_color(score: Option<f64>) -> Color {
+    match score {
+        Some(score) if score >= 0.1 => Color::Green,
+        Some(score) if score <= -0.1 => Color::Red,
+        Some(_) => Color::Yellow,
+        None => Color::DarkGray,
+    }
+}
+
 fn render_daily_chart(frame: &mut Frame, app: &App, area: Rect) {
     let stats = &app.stats_cache.usage;
 